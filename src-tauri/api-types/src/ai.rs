@@ -0,0 +1,28 @@
+//! `/ai/*` request bodies and queries.
+
+use serde::{Deserialize, Serialize};
+
+/// `POST /ai/config`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveConfigRequest {
+    pub base_url: String,
+    pub model: String,
+    pub api_key: Option<String>,
+    pub disable_thinking: Option<bool>,
+    pub max_context_tokens: Option<u32>,
+}
+
+/// `POST /ai/test`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestConnectionRequest {
+    pub base_url: Option<String>,
+    pub model: Option<String>,
+    pub api_key: Option<String>,
+}
+
+/// `GET /ai/models`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListModelsQuery {
+    pub base_url: String,
+    pub api_key: Option<String>,
+}
@@ -0,0 +1,16 @@
+//! `/calendar` queries.
+
+use serde::{Deserialize, Serialize};
+
+/// `GET /calendar`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalendarQuery {
+    /// Unix seconds; defaults to now
+    pub from: Option<i64>,
+    /// Unix seconds; defaults to 7 days after `from`
+    pub to: Option<i64>,
+    /// Country code, e.g. `US`
+    pub country: Option<String>,
+    /// Minimum impact: `low` / `medium` / `high` (default `low`)
+    pub impact: Option<String>,
+}
@@ -0,0 +1,77 @@
+//! Request / response models for the StockenBoard HTTP API (`/api/*`).
+//!
+//! The server deserializes request bodies and query strings into these types and serializes
+//! the row models below, so external Rust tools and bots get compile-time-checked payloads:
+//!
+//! ```no_run
+//! use stockenboard_api_types::{models::Subscription, ApiResponse};
+//!
+//! # fn example(body: &str) -> serde_json::Result<()> {
+//! // GET /api/subscriptions
+//! let subs: ApiResponse<Vec<Subscription>> = serde_json::from_str(body)?;
+//! for sub in subs.data {
+//!     println!("{} via {}", sub.symbol, sub.selected_provider_id);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Every success response is wrapped in [`ApiResponse`] (`{ "data": ... }`) and every failure in
+//! [`ApiError`] (`{ "error": { "code", "message", "key"? } }`). Payloads that are not modelled here
+//! (live quotes, notification rules, system settings) can be read as `serde_json::Value`.
+
+use serde::{Deserialize, Serialize};
+
+pub mod ai;
+pub mod calendar;
+pub mod models;
+pub mod prices;
+pub mod providers;
+pub mod subscriptions;
+pub mod views;
+
+/// Success response envelope: `{ "data": T }`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiResponse<T> {
+    pub data: T,
+}
+
+/// Error response envelope: `{ "error": { "code": string, "message": string } }`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiError {
+    pub error: ApiErrorBody,
+}
+
+/// Inner body of an API error response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiErrorBody {
+    /// `not_found`, `bad_request`, `unauthorized`, `forbidden`, `db_busy` or `internal_error`
+    pub code: String,
+    /// Human-readable message in the server's UI language
+    pub message: String,
+    /// Message catalog key (snake_case, e.g. `subscription_not_found`) for localized messages
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Subscription;
+
+    #[test]
+    fn parses_envelopes() {
+        let ok: ApiResponse<Vec<Subscription>> = serde_json::from_str(
+            r#"{"data":[{"id":1,"sub_type":"asset","symbol":"BTC","display_name":null,
+                "selected_provider_id":"binance","asset_type":"crypto","pool_address":null,
+                "token_from_address":null,"token_to_address":null,"sort_order":1,"record_enabled":0,
+                "record_from_hour":null,"record_to_hour":null,"provider_params":null}]}"#,
+        )
+        .unwrap();
+        assert_eq!(ok.data[0].symbol, "BTC");
+
+        let err: ApiError =
+            serde_json::from_str(r#"{"error":{"code":"not_found","message":"Subscription 9 not found"}}"#).unwrap();
+        assert_eq!((err.error.code.as_str(), err.error.key), ("not_found", None));
+    }
+}
@@ -0,0 +1,82 @@
+//! Row models returned by the API (also used by the server's database layer).
+
+use serde::{Deserialize, Serialize};
+
+/// A watched asset or DEX pool (`GET /subscriptions`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Subscription {
+    pub id: i64,
+    /// `asset` or `dex`
+    pub sub_type: String,
+    pub symbol: String,
+    pub display_name: Option<String>,
+    pub selected_provider_id: String,
+    pub asset_type: String,
+    pub pool_address: Option<String>,
+    pub token_from_address: Option<String>,
+    pub token_to_address: Option<String>,
+    pub sort_order: i64,
+    pub record_enabled: i64,
+    pub record_from_hour: Option<i64>,
+    pub record_to_hour: Option<i64>,
+    /// Provider-specific parameters as a JSON object string, e.g. Polygon `{"adjusted": false}`
+    pub provider_params: Option<String>,
+    /// Minimum seconds between two recorded prices; `None` uses the default (5), `0` keeps every poll
+    pub record_dedup_secs: Option<i64>,
+}
+
+/// A page of subscriptions (`GET /views`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ViewRow {
+    pub id: i64,
+    pub name: String,
+    pub view_type: String,
+    pub is_default: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ViewSubCount {
+    pub view_id: i64,
+    pub count: i64,
+}
+
+/// One recorded price (`GET /history/:sub_id`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceHistoryRow {
+    pub id: i64,
+    pub subscription_id: i64,
+    pub provider_id: String,
+    pub price: f64,
+    pub change_pct: Option<f64>,
+    pub volume: Option<f64>,
+    pub pre_price: Option<f64>,
+    pub post_price: Option<f64>,
+    /// Unix seconds
+    pub recorded_at: i64,
+    /// DEX pool liquidity (USD); DEX subscriptions only, returned with `fields=liquidity`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub liquidity_usd: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fdv: Option<f64>,
+    /// Derivatives open interest (in coins); returned with `fields=derivatives`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub open_interest: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub long_short_ratio: Option<f64>,
+    /// Quote currency reported by the provider (e.g. `USDT`, `KRW`); `None` for older records
+    #[serde(default)]
+    pub currency: Option<String>,
+}
+
+/// A provider switch (`provider_change`, values are provider IDs) or `symbol_rename`
+/// (`GET /history/:sub_id/events`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubscriptionEvent {
+    pub id: i64,
+    pub subscription_id: i64,
+    pub event_type: String,
+    pub old_value: String,
+    pub new_value: String,
+    /// Unix seconds
+    pub occurred_at: i64,
+}
@@ -0,0 +1,135 @@
+//! `/prices/*`, `/history/*`, `/sparkline`, `/twap` and `/candles` request bodies, queries and responses.
+
+use serde::{Deserialize, Serialize};
+
+/// One provider and its symbols in `POST /prices/fetch-multi`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceRequest {
+    pub provider_id: String,
+    pub symbols: Vec<String>,
+}
+
+/// `POST /prices/fetch-multiple`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FetchMultipleRequest {
+    pub provider_id: String,
+    pub symbols: Vec<String>,
+}
+
+/// `POST /prices/fetch-multi`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FetchMultiRequest {
+    pub requests: Vec<PriceRequest>,
+}
+
+/// `GET /prices/changes`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangesQuery {
+    /// Unix timestamp in milliseconds; only entries with `last_updated` after it are returned
+    pub since: i64,
+}
+
+/// `GET /prices/snapshot`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotQuery {
+    /// Comma-separated subscription IDs, in display order
+    pub subscription_ids: String,
+    /// `png` returns the raw image instead of a data URL
+    pub format: Option<String>,
+}
+
+/// `GET /history/:sub_id`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryQuery {
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    pub limit: Option<i64>,
+    /// Comma-separated optional column groups: `liquidity` (liquidity_usd, fdv),
+    /// `derivatives` (open_interest, long_short_ratio)
+    pub fields: Option<String>,
+}
+
+/// `GET /history/:sub_id/events`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventsQuery {
+    /// Unix seconds, inclusive
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+}
+
+/// `GET /history/stats`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatsQuery {
+    /// Comma-separated subscription IDs
+    pub subscription_ids: String,
+}
+
+/// `GET /history/:sub_id/vwap`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VwapQuery {
+    /// Unix seconds; when set, an anchored VWAP from this time is returned as well
+    pub anchor: Option<i64>,
+}
+
+/// `GET /sparkline`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SparklineQuery {
+    pub subscription_id: i64,
+    /// Maximum number of points (2–500, default 50)
+    pub points: Option<usize>,
+    /// Look-back window such as `30m`, `24h` or `7d` (default `24h`)
+    pub window: Option<String>,
+}
+
+/// `GET /twap`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TwapQuery {
+    pub subscription_id: i64,
+    /// Averaging window such as `15m`, `1h` or `1d` (default `1h`)
+    pub window: Option<String>,
+}
+
+/// `GET /candles`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CandlesQuery {
+    pub provider_id: String,
+    pub symbol: String,
+    /// Earliest candle start (Unix seconds, inclusive)
+    pub from: Option<i64>,
+    /// Latest candle start (Unix seconds, inclusive)
+    pub to: Option<i64>,
+    /// Most recent candles to return (default 1440, max 10000)
+    pub limit: Option<usize>,
+}
+
+/// `POST /history/:sub_id/import-csv`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportHistoryCsvRequest {
+    pub content: String,
+    /// Validate and preview only; nothing is written
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// `POST /history/cleanup`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleanupRequest {
+    pub retention_days: Option<i64>,
+}
+
+/// One entry of `GET /history/stats`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryStatsResult {
+    pub subscription_id: i64,
+    pub total_records: i64,
+    pub earliest: Option<i64>,
+    pub latest: Option<i64>,
+    /// Failed history writes since the server started
+    #[serde(default)]
+    pub record_failures: i64,
+    #[serde(default)]
+    pub last_record_error: Option<String>,
+    /// Unix seconds
+    #[serde(default)]
+    pub last_record_error_at: Option<i64>,
+}
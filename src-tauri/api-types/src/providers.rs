@@ -0,0 +1,45 @@
+//! `/providers/*` and `/provider-settings/*` request bodies and queries.
+
+use serde::{Deserialize, Serialize};
+
+/// Body for `POST /providers/:id/enable`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnableProviderBody {
+    pub api_key: Option<String>,
+    pub api_secret: Option<String>,
+}
+
+/// Body for `PUT /provider-settings/:id`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpsertProviderSettingsBody {
+    pub api_key: Option<String>,
+    pub api_secret: Option<String>,
+    pub api_url: Option<String>,
+    pub refresh_interval: Option<i64>,
+    pub connection_type: Option<String>,
+    pub record_from_hour: Option<i64>,
+    pub record_to_hour: Option<i64>,
+    pub proxy_url: Option<String>,
+    pub timeout_ms: Option<i64>,
+    /// JSON object string of extra request headers
+    pub extra_headers: Option<String>,
+}
+
+/// Body for `PUT /providers/:id/debug`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetProviderDebugBody {
+    pub enabled: bool,
+}
+
+/// Query for `GET /providers/resolve`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolveProviderQuery {
+    pub symbol: String,
+}
+
+/// Body for `PUT /provider-settings/:id/record-hours`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetProviderRecordHoursBody {
+    pub from_hour: Option<i64>,
+    pub to_hour: Option<i64>,
+}
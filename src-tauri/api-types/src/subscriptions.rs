@@ -0,0 +1,108 @@
+//! `/subscriptions/*` request bodies and queries.
+
+use serde::{Deserialize, Serialize};
+
+/// `GET /subscriptions`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListQuery {
+    /// `asset` or `dex`
+    #[serde(rename = "type")]
+    pub sub_type: Option<String>,
+}
+
+/// `POST /subscriptions`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddSubscriptionRequest {
+    pub sub_type: String,
+    pub symbol: String,
+    pub display_name: Option<String>,
+    pub provider_id: String,
+    pub asset_type: String,
+    pub pool_address: Option<String>,
+    pub token_from: Option<String>,
+    pub token_to: Option<String>,
+}
+
+/// One asset subscription in `POST /subscriptions/batch`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchAddItem {
+    pub symbol: String,
+    pub display_name: Option<String>,
+    pub provider_id: String,
+    pub asset_type: String,
+}
+
+/// `DELETE /subscriptions/batch`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchRemoveRequest {
+    pub ids: Vec<i64>,
+}
+
+/// `POST /subscriptions/import-csv`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportCsvRequest {
+    pub content: String,
+    /// Check each row against its provider before importing (default `true`)
+    #[serde(default = "default_validate")]
+    pub validate: bool,
+}
+
+fn default_validate() -> bool {
+    true
+}
+
+/// A basket component and its relative weight (weights need not sum to 100)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BasketWeight {
+    pub subscription_id: i64,
+    pub weight: f64,
+}
+
+/// `POST /subscriptions/basket`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateBasketRequest {
+    pub symbol: String,
+    pub display_name: Option<String>,
+    pub components: Vec<BasketWeight>,
+}
+
+/// `POST /subscriptions/merge`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeSubscriptionsRequest {
+    pub keep_id: i64,
+    pub merge_ids: Vec<i64>,
+}
+
+/// `PUT /subscriptions/:id`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateSubscriptionRequest {
+    pub symbol: String,
+    pub display_name: Option<String>,
+    pub provider_id: String,
+    pub asset_type: String,
+}
+
+/// `PUT /subscriptions/:id/provider-params`; `null` clears the parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetProviderParamsRequest {
+    pub params: Option<serde_json::Value>,
+}
+
+/// `POST /subscriptions/:id/toggle-record`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToggleRecordRequest {
+    pub enabled: bool,
+}
+
+/// `PUT /subscriptions/:id/record-hours`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetRecordHoursRequest {
+    pub from_hour: Option<i64>,
+    pub to_hour: Option<i64>,
+}
+
+/// `PUT /subscriptions/:id/record-dedup`; `null` restores the default window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetRecordDedupRequest {
+    pub dedup_secs: Option<i64>,
+}
@@ -0,0 +1,30 @@
+//! `/views/*` request bodies and queries.
+
+use serde::{Deserialize, Serialize};
+
+/// `GET /views`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListViewsQuery {
+    #[serde(rename = "type", default)]
+    pub view_type: String,
+}
+
+/// `POST /views`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateViewBody {
+    pub name: String,
+    #[serde(rename = "type", default)]
+    pub view_type: String,
+}
+
+/// `PUT /views/:id`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenameViewBody {
+    pub name: String,
+}
+
+/// `POST /views/:id/subscriptions`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddSubBody {
+    pub subscription_id: i64,
+}
@@ -14,34 +14,13 @@ use axum::{
     routing::{get, post},
     Router,
 };
-use serde::Deserialize;
 
 use crate::api::{ApiError, ApiResponse};
 use crate::core_state::CoreState;
 
 // ─── Request / Query Types ──────────────────────────────────────────────────────
 
-#[derive(Debug, Deserialize)]
-pub struct SaveConfigRequest {
-    pub base_url: String,
-    pub model: String,
-    pub api_key: Option<String>,
-    pub disable_thinking: Option<bool>,
-    pub max_context_tokens: Option<u32>,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct TestConnectionRequest {
-    pub base_url: Option<String>,
-    pub model: Option<String>,
-    pub api_key: Option<String>,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct ListModelsQuery {
-    pub base_url: String,
-    pub api_key: Option<String>,
-}
+pub use stockenboard_api_types::ai::{ListModelsQuery, SaveConfigRequest, TestConnectionRequest};
 
 // ─── Router ─────────────────────────────────────────────────────────────────────
 
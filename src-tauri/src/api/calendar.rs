@@ -0,0 +1,65 @@
+//! Economic calendar API endpoints.
+//!
+//! Provides:
+//! - `GET /calendar?from=&to=&country=&impact=` — stored economic events (Unix seconds; defaults to the next 7 days)
+//! - `POST /calendar/refresh` — fetch the calendar from FMP / Finnhub now
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    routing::{get, post},
+    Router,
+};
+
+use crate::api::{ApiError, ApiResponse};
+use crate::calendar::Impact;
+use crate::core_state::CoreState;
+
+// ─── Query / Request Types ──────────────────────────────────────────────────────
+
+pub use stockenboard_api_types::calendar::CalendarQuery;
+
+// ─── Router ─────────────────────────────────────────────────────────────────────
+
+pub fn router() -> Router<Arc<CoreState>> {
+    Router::new()
+        .route("/calendar", get(list_events))
+        .route("/calendar/refresh", post(refresh))
+}
+
+// ─── Handlers ───────────────────────────────────────────────────────────────────
+
+/// GET /calendar?from=&to=&country=&impact=
+/// List stored economic events ordered by time.
+async fn list_events(
+    State(state): State<Arc<CoreState>>,
+    Query(query): Query<CalendarQuery>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    let min_impact = match query.impact.as_deref().map(str::parse::<Impact>) {
+        None => Impact::Low,
+        Some(Ok(impact)) => impact,
+        Some(Err(())) => {
+            let impact = query.impact.unwrap_or_default();
+            return Err(ApiError::bad_request(format!("Unknown impact: {}", impact)));
+        }
+    };
+    let from = query.from.unwrap_or_else(|| chrono::Utc::now().timestamp());
+    let to = query.to.unwrap_or(from + 7 * 86_400);
+    let country = query.country.as_deref().map(str::trim).filter(|c| !c.is_empty());
+    state
+        .db
+        .list_economic_events(from, to, country, min_impact)
+        .map(ApiResponse::ok)
+        .map_err(ApiError::internal)
+}
+
+/// POST /calendar/refresh
+/// Fetch the economic calendar now; returns the number of stored events.
+async fn refresh(State(state): State<Arc<CoreState>>) -> Result<impl IntoResponse, impl IntoResponse> {
+    crate::calendar::refresh(&state.db, &state.event_bus)
+        .await
+        .map(|count| ApiResponse::ok(serde_json::json!({ "count": count })))
+        .map_err(ApiError::bad_request)
+}
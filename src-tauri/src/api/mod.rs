@@ -2,17 +2,30 @@
 //!
 //! Provides:
 //! - `build_router(state)` — constructs the full Axum router with CORS and 404 fallback
+//! - `build_lan_router(state)` — the same routes behind `lan_guard` for the LAN listener
+//! - `restart_lan_listener(state)` — (re)binds the LAN listener from the current settings
+//! - `kiosk_guard` — rejects writes (403 `kiosk_locked`) while kiosk mode is on or the request carries the restricted kiosk token
+//! - `lan_guard` — requires the LAN listener token (401 `unauthorized`) and enforces its scope (403 `forbidden`)
 //! - `ApiResponse<T>` — success envelope `{ "data": T }`
-//! - `ApiError` / `ApiErrorBody` — error envelope `{ "error": { "code", "message" } }`
+//! - `ApiError` / `ApiErrorBody` — error envelope `{ "error": { "code", "message" } }` (plus `key` for catalog messages)
 
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::Duration;
 
-use axum::{http::StatusCode, response::IntoResponse, Json, Router};
+use axum::{
+    extract::Request,
+    http::{HeaderMap, StatusCode},
+    middleware::Next,
+    response::IntoResponse,
+    Json, Router,
+};
 use serde::Serialize;
 use tower_http::cors::CorsLayer;
 
 use crate::core_state::CoreState;
+use crate::db::DbError;
+use crate::i18n::{LocalizedError, Msg};
 
 // Submodules for each resource group (will be created in tasks 4.2–4.8)
 pub mod subscriptions;
@@ -20,6 +33,7 @@ pub mod views;
 pub mod providers;
 pub mod notifications;
 pub mod ai;
+pub mod calendar;
 pub mod prices;
 pub mod system;
 pub mod ws;
@@ -60,6 +74,9 @@ pub struct ApiError {
 pub struct ApiErrorBody {
     pub code: String,
     pub message: String,
+    /// Message key from `crate::i18n` (only for localized messages)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<Msg>,
 }
 
 impl ApiError {
@@ -68,6 +85,7 @@ impl ApiError {
             error: ApiErrorBody {
                 code: code.into(),
                 message: message.into(),
+                key: None,
             },
         }
     }
@@ -86,12 +104,48 @@ impl ApiError {
         )
     }
 
-    pub fn internal(message: impl Into<String>) -> (StatusCode, Json<Self>) {
+    pub fn unauthorized(message: impl Into<String>) -> (StatusCode, Json<Self>) {
         (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(Self::new("internal_error", message)),
+            StatusCode::UNAUTHORIZED,
+            Json(Self::new("unauthorized", message)),
         )
     }
+
+    pub fn forbidden(message: impl Into<String>) -> (StatusCode, Json<Self>) {
+        (
+            StatusCode::FORBIDDEN,
+            Json(Self::new("forbidden", message)),
+        )
+    }
+
+    /// Error carrying a localized catalog message; `code` follows the status like the helpers above.
+    pub fn localized(status: StatusCode, err: LocalizedError) -> (StatusCode, Json<Self>) {
+        let code = match status {
+            StatusCode::NOT_FOUND => "not_found",
+            StatusCode::FORBIDDEN => "forbidden",
+            StatusCode::BAD_REQUEST => "bad_request",
+            _ => "internal_error",
+        };
+        let mut error = Self::new(code, err.message);
+        error.error.key = Some(err.key);
+        (status, Json(error))
+    }
+
+    /// Internal error; [`DbError::Busy`] (the database stayed busy after retries) maps to
+    /// 503 `db_busy`. Plain messages convert into `DbError::Other`.
+    pub fn internal(err: impl Into<DbError>) -> (StatusCode, Json<Self>) {
+        match err.into() {
+            DbError::Busy(e) => {
+                let mut error = Self::new("db_busy", e.message);
+                error.error.key = Some(e.key);
+                (StatusCode::SERVICE_UNAVAILABLE, Json(error))
+            }
+            DbError::Other(message) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(Self::new("internal_error", message)),
+            ),
+        }
+    }
 }
 
 // ─── Router Builder ─────────────────────────────────────────────────────────────
@@ -101,22 +155,34 @@ impl ApiError {
 /// The router is nested under `/api` so that static file serving can occupy `/` later.
 /// Use [`build_router_with_static`] to include SPA static file serving.
 pub fn build_router(state: Arc<CoreState>) -> Router {
-    let api_routes = Router::new()
-        // Sub-routers will be merged here in tasks 4.2–4.8
+    Router::new()
+        .nest("/api", api_routes(state))
+        .layer(CorsLayer::permissive())
+}
+
+/// Build the router served by the LAN listener: the same `/api/*` routes, each request checked
+/// by [`lan_guard`] against the LAN listener token and scope. Static files are not served here.
+pub fn build_lan_router(state: Arc<CoreState>) -> Router {
+    Router::new()
+        .nest("/api", api_routes(state).layer(axum::middleware::from_fn(lan_guard)))
+        .layer(CorsLayer::permissive())
+}
+
+/// All `/api` sub-routers with the 404 fallback and kiosk guard.
+fn api_routes(state: Arc<CoreState>) -> Router {
+    Router::new()
         .merge(subscriptions::router())
         .merge(views::router())
         .merge(providers::router())
         .merge(notifications::router())
         .merge(ai::router())
+        .merge(calendar::router())
         .merge(prices::router())
         .merge(system::router())
         .merge(ws::router())
         .fallback(api_fallback)
-        .with_state(state);
-
-    Router::new()
-        .nest("/api", api_routes)
-        .layer(CorsLayer::permissive())
+        .layer(axum::middleware::from_fn(kiosk_guard))
+        .with_state(state)
 }
 
 /// Build the full application router with API routes AND static file serving.
@@ -131,18 +197,6 @@ pub fn build_router(state: Arc<CoreState>) -> Router {
 /// - `Cache-Control: no-cache, no-store, must-revalidate` for `index.html`
 /// - SPA fallback: paths not matching API routes or static files serve `index.html`
 pub fn build_router_with_static(state: Arc<CoreState>, static_dir: &Path) -> Router {
-    let api_routes = Router::new()
-        .merge(subscriptions::router())
-        .merge(views::router())
-        .merge(providers::router())
-        .merge(notifications::router())
-        .merge(ai::router())
-        .merge(prices::router())
-        .merge(system::router())
-        .merge(ws::router())
-        .fallback(api_fallback)
-        .with_state(state);
-
     // Static file layer serves files from static_dir and falls back to index.html.
     // Uses static_file_service which applies cache header middleware:
     // - Cache-Control: public, max-age=31536000, immutable for hashed assets
@@ -150,7 +204,7 @@ pub fn build_router_with_static(state: Arc<CoreState>, static_dir: &Path) -> Rou
     let static_service = static_files::static_file_service(static_dir);
 
     Router::new()
-        .nest("/api", api_routes)
+        .nest("/api", api_routes(state))
         .fallback_service(static_service)
         .layer(CorsLayer::permissive())
 }
@@ -161,3 +215,110 @@ pub fn build_router_with_static(state: Arc<CoreState>, static_dir: &Path) -> Rou
 async fn api_fallback() -> impl IntoResponse {
     ApiError::not_found("The requested endpoint does not exist")
 }
+
+// ─── Kiosk Guard ────────────────────────────────────────────────────────────────
+
+/// Restricted kiosk token from `Authorization: Bearer <token>` or `X-Kiosk-Token`.
+pub fn kiosk_token(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .or_else(|| headers.get("x-kiosk-token").and_then(|v| v.to_str().ok()))
+        .map(|v| v.trim().to_string())
+}
+
+/// Request extension for clients that must not see provider secrets (kiosk read-only clients and
+/// every request over the LAN listener); handlers returning provider settings redact them.
+#[derive(Debug, Clone, Copy)]
+pub struct RedactSecrets;
+
+/// Middleware that keeps kiosk clients read-only while live-data calls keep working.
+async fn kiosk_guard(mut req: Request, next: Next) -> axum::response::Response {
+    let token = kiosk_token(req.headers());
+    if crate::kiosk::is_read_only(&crate::kiosk::kiosk_state(), token.as_deref()) {
+        if !crate::kiosk::allows_request(req.method().as_str(), req.uri().path()) {
+            return ApiError::localized(StatusCode::FORBIDDEN, LocalizedError::new(Msg::KioskLocked, &[]))
+                .into_response();
+        }
+        req.extensions_mut().insert(RedactSecrets);
+    }
+    next.run(req).await
+}
+
+// ─── LAN Listener ───────────────────────────────────────────────────────────────
+
+/// LAN listener token from `Authorization: Bearer <token>` only — a query-string token would end
+/// up in proxy logs and browser history.
+pub fn api_token(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(|v| v.trim().to_string())
+}
+
+/// Middleware for the LAN listener: a valid token is always required and the configured scope
+/// decides which requests are allowed. Settings are read per request, so changes apply at once.
+async fn lan_guard(mut req: Request, next: Next) -> axum::response::Response {
+    let config = crate::lan_listener::lan_listener_config();
+    if !crate::lan_listener::authorize(&config, api_token(req.headers()).as_deref()) {
+        return ApiError::unauthorized("A valid API token is required").into_response();
+    }
+    if !crate::lan_listener::allows_request(config.scope, req.method().as_str(), req.uri().path()) {
+        return ApiError::forbidden("This request is not allowed over the LAN listener").into_response();
+    }
+    req.extensions_mut().insert(RedactSecrets);
+    next.run(req).await
+}
+
+/// Accept loop of the running LAN listener.
+static LAN_SERVER: LazyLock<Mutex<Option<tokio::task::JoinHandle<()>>>> = LazyLock::new(|| Mutex::new(None));
+
+/// Attempts to bind the LAN listener; the previous listener may take a moment to release the port.
+const LAN_BIND_ATTEMPTS: u32 = 5;
+
+/// Stop the LAN listener (if running) and start it again from the current settings.
+/// Does nothing else when the LAN listener is disabled. Must be called inside a Tokio runtime.
+pub fn restart_lan_listener(state: Arc<CoreState>) {
+    use crate::lan_listener::{lan_listener_config, set_lan_listener_status, LanListenerStatus};
+
+    let mut server = LAN_SERVER.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(handle) = server.take() {
+        handle.abort();
+    }
+    set_lan_listener_status(LanListenerStatus::default());
+    let config = lan_listener_config();
+    if !config.enabled {
+        return;
+    }
+
+    *server = Some(tokio::spawn(async move {
+        let addr = config.address();
+        let mut attempt = 1;
+        let listener = loop {
+            match tokio::net::TcpListener::bind(&addr).await {
+                Ok(listener) => break listener,
+                Err(e) if attempt < LAN_BIND_ATTEMPTS => {
+                    tracing::debug!("[API] LAN listener bind to {} failed ({}), retrying", addr, e);
+                    attempt += 1;
+                    tokio::time::sleep(Duration::from_millis(200)).await;
+                }
+                Err(e) => {
+                    tracing::error!("[API] Failed to bind LAN listener to {}: {}", addr, e);
+                    set_lan_listener_status(LanListenerStatus {
+                        listening: None,
+                        error: Some(format!("Failed to bind to {}: {}", addr, e)),
+                    });
+                    return;
+                }
+            }
+        };
+        tracing::info!("[API] LAN listener on http://{} ({} scope)", addr, config.scope.as_str());
+        set_lan_listener_status(LanListenerStatus { listening: Some(addr), error: None });
+        if let Err(e) = axum::serve(listener, build_lan_router(state)).await {
+            tracing::error!("[API] LAN listener error: {}", e);
+            set_lan_listener_status(LanListenerStatus { listening: None, error: Some(e.to_string()) });
+        }
+    }));
+}
@@ -13,6 +13,7 @@ use serde::Deserialize;
 
 use crate::core_state::CoreState;
 use crate::db::{NotificationChannelRow, NotificationHistoryRow, NotificationRuleRow};
+use crate::i18n::{LocalizedError, Msg};
 use crate::notifications::models::{
     CreateRuleRequest, SaveChannelRequest, TelegramConfig, UpdateRuleRequest, WebhookConfig,
 };
@@ -402,7 +403,9 @@ async fn test_channel(
     let channel = channels
         .iter()
         .find(|c| c.id == id)
-        .ok_or_else(|| ApiError::not_found(format!("Channel {} not found", id)))?;
+        .ok_or_else(|| {
+            ApiError::localized(axum::http::StatusCode::NOT_FOUND, LocalizedError::new(Msg::ChannelNotFound, &[&id]))
+        })?;
 
     let client = reqwest::Client::new();
 
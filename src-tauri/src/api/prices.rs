@@ -3,10 +3,28 @@
 //! Provides:
 //! - `GET /prices/fetch/:provider/:symbol` — fetch a single price from a provider
 //! - `POST /prices/fetch-multiple` — fetch multiple prices from a provider
-//! - `GET /prices/cached` — get all cached prices from polling
+//! - `POST /prices/fetch-multi` — fetch from several providers concurrently, grouped per provider
+//! - `GET /prices/cached` — get all cached prices from polling (with freshness metadata and
+//!   1h / 7d / 30d / YTD `changes` derived from recorded history)
+//! - `GET /prices/changes?since=<ms>` — cached prices updated after a timestamp (incremental sync)
 //! - `GET /prices/poll-ticks` — get current poll ticks per provider
-//! - `GET /history/stats` — get history stats for subscription IDs
-//! - `GET /history/:sub_id` — get price history for a subscription
+//! - `GET /prices/snapshot?subscription_ids=1,2&format=png` — current prices and 24h sparklines rendered as a PNG table
+//!   (a `data:` URL by default, raw `image/png` with `format=png`)
+//! - `GET /sparkline?subscription_id=&points=50&window=24h` — recorded prices downsampled to a fixed-size array for tiles
+//! - `GET /twap?subscription_id=&window=1h` — time-weighted average price from recorded history, each price
+//!   weighted by how long it held (irregular sampling does not skew it toward dense stretches)
+//! - `GET /candles?provider_id=&symbol=&from=&to=&limit=1440` — saved 1-minute candles aggregated from WebSocket
+//!   streams (only written while candle saving is enabled, see `/system/candles`)
+//! - `GET /prices/gas` — latest gas prices from the gas tracker (Ethereum gwei, Solana priority fee)
+//! - `GET /depeg` — stablecoin depeg status (USDT / USDC / DAI / FDUSD deviation from $1.00 across exchanges)
+//! - `GET /history/stats` — get history stats for subscription IDs, with write failures since the server started
+//! - `GET /history/:sub_id` — get price history for a subscription, each row tagged with its quote `currency`
+//!   (`?fields=liquidity` adds DEX liquidity_usd / fdv,
+//!   `?fields=derivatives` adds open_interest / long_short_ratio)
+//! - `GET /history/:sub_id/events?from=&to=` — provider switches and symbol renames, for annotating charts
+//! - `GET /history/:sub_id/streaks` — up/down streaks, max drawdown and recovery time
+//! - `GET /history/:sub_id/vwap?anchor=` — session VWAP (since 00:00 UTC) and optional anchored VWAP
+//! - `POST /history/:sub_id/import-csv` — import external CSV history (timestamp, price, volume), with `dry_run` preview
 //! - `POST /history/cleanup` — cleanup old history records
 //! - `DELETE /history` — purge all history
 //! - `DELETE /history/:sub_id` — delete history for a subscription
@@ -19,45 +37,26 @@ use axum::{
     routing::{delete, get, post},
     Router,
 };
-use serde::Deserialize;
 
 use crate::api::{ApiError, ApiResponse};
 use crate::core_state::CoreState;
+use crate::i18n::{LocalizedError, Msg};
 
 // ─── Query / Request Types ──────────────────────────────────────────────────────
 
-#[derive(Debug, Deserialize)]
-pub struct FetchMultipleRequest {
-    pub provider_id: String,
-    pub symbols: Vec<String>,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct HistoryQuery {
-    pub from: Option<i64>,
-    pub to: Option<i64>,
-    pub limit: Option<i64>,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct StatsQuery {
-    /// Comma-separated subscription IDs
-    pub subscription_ids: String,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct CleanupRequest {
-    pub retention_days: Option<i64>,
-}
+pub use stockenboard_api_types::prices::{
+    CandlesQuery, ChangesQuery, CleanupRequest, EventsQuery, FetchMultiRequest, FetchMultipleRequest,
+    HistoryQuery, HistoryStatsResult, ImportHistoryCsvRequest, SnapshotQuery, SparklineQuery, StatsQuery,
+    TwapQuery, VwapQuery,
+};
 
 // ─── Response Types ─────────────────────────────────────────────────────────────
 
 #[derive(Debug, serde::Serialize)]
-pub struct HistoryStatsResult {
-    pub subscription_id: i64,
-    pub total_records: i64,
-    pub earliest: Option<i64>,
-    pub latest: Option<i64>,
+pub struct PriceChanges {
+    /// Server time (ms) when the diff was taken; pass it as `since` on the next request
+    pub as_of: i64,
+    pub prices: Vec<crate::polling::CachedPrice>,
 }
 
 // ─── Router ─────────────────────────────────────────────────────────────────────
@@ -66,12 +65,24 @@ pub fn router() -> Router<Arc<CoreState>> {
     Router::new()
         .route("/prices/fetch/:provider/:symbol", get(fetch_single))
         .route("/prices/fetch-multiple", post(fetch_multiple))
+        .route("/prices/fetch-multi", post(fetch_multi))
         .route("/prices/cached", get(get_cached))
+        .route("/prices/changes", get(get_changes))
         .route("/prices/poll-ticks", get(get_poll_ticks))
+        .route("/prices/snapshot", get(get_snapshot))
+        .route("/sparkline", get(get_sparkline))
+        .route("/twap", get(get_twap))
+        .route("/candles", get(get_candles))
+        .route("/prices/gas", get(get_gas_prices))
+        .route("/depeg", get(get_depeg_status))
         .route("/history/stats", get(get_stats))
         .route("/history/cleanup", post(cleanup))
         .route("/history", delete(purge_all))
         .route("/history/:sub_id", get(get_history).delete(delete_history))
+        .route("/history/:sub_id/events", get(get_events))
+        .route("/history/:sub_id/streaks", get(get_streaks))
+        .route("/history/:sub_id/vwap", get(get_vwap))
+        .route("/history/:sub_id/import-csv", post(import_history_csv))
 }
 
 // ─── Handlers ───────────────────────────────────────────────────────────────────
@@ -86,7 +97,9 @@ async fn fetch_single(
         .registry
         .get_or_create(&provider, &state.db)
         .await
-        .ok_or_else(|| ApiError::not_found(format!("Provider not found: {}", provider)))?;
+        .ok_or_else(|| {
+            ApiError::localized(axum::http::StatusCode::NOT_FOUND, LocalizedError::new(Msg::ProviderNotFound, &[&provider]))
+        })?;
 
     match provider_instance.fetch_price(&symbol).await {
         Ok(data) => Ok(ApiResponse::ok(data)),
@@ -114,14 +127,108 @@ async fn fetch_multiple(
     }
 }
 
+/// POST /prices/fetch-multi
+/// Fetch prices from several providers concurrently. Per-provider failures are
+/// reported in that group's `error` instead of failing the whole request.
+async fn fetch_multi(
+    State(state): State<Arc<CoreState>>,
+    Json(body): Json<FetchMultiRequest>,
+) -> impl IntoResponse {
+    ApiResponse::ok(state.registry.fetch_multi(&body.requests, &state.db).await)
+}
+
 /// GET /prices/cached
-/// Return all currently cached prices from polling.
+/// Return all currently cached prices from polling, each with `fetched_at`,
+/// `interval_ms` and a `stale` flag derived from the provider's poll ticks.
 async fn get_cached(
     State(state): State<Arc<CoreState>>,
 ) -> impl IntoResponse {
-    let cache = state.polling.cache.read().await;
-    let data: Vec<_> = cache.values().cloned().collect();
-    ApiResponse::ok(data)
+    ApiResponse::ok(state.polling.cached_prices().await)
+}
+
+/// GET /prices/snapshot?subscription_ids=1,2,3&format=png
+/// Render the given subscriptions as a shareable PNG table.
+async fn get_snapshot(
+    State(state): State<Arc<CoreState>>,
+    Query(query): Query<SnapshotQuery>,
+) -> axum::response::Response {
+    let ids: Vec<i64> = query
+        .subscription_ids
+        .split(',')
+        .filter_map(|s| s.trim().parse::<i64>().ok())
+        .collect();
+    if ids.is_empty() {
+        return ApiError::bad_request("subscription_ids must contain at least one valid ID").into_response();
+    }
+    match state.render_snapshot(&ids).await {
+        Ok(png) if query.format.as_deref() == Some("png") => {
+            ([(axum::http::header::CONTENT_TYPE, "image/png")], png).into_response()
+        }
+        Ok(png) => ApiResponse::ok(crate::snapshot::data_url(&png)).into_response(),
+        Err(e) => ApiError::bad_request(e).into_response(),
+    }
+}
+
+/// GET /sparkline?subscription_id=1&points=50&window=24h
+/// Recorded prices in the window, averaged down to at most `points` values (oldest first).
+async fn get_sparkline(
+    State(state): State<Arc<CoreState>>,
+    Query(query): Query<SparklineQuery>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    let window = query.window.as_deref().unwrap_or(crate::sparkline::DEFAULT_WINDOW);
+    let window_secs = crate::sparkline::parse_window(window).map_err(ApiError::bad_request)?;
+    crate::sparkline::load(
+        &state.db,
+        query.subscription_id,
+        query.points,
+        window_secs,
+        chrono::Utc::now().timestamp(),
+    )
+    .map(ApiResponse::ok)
+    .map_err(ApiError::internal)
+}
+
+/// GET /twap?subscription_id=1&window=1h
+/// Time-weighted average of the recorded prices over the window; `twap` is null when nothing was recorded.
+async fn get_twap(
+    State(state): State<Arc<CoreState>>,
+    Query(query): Query<TwapQuery>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    let window = query.window.as_deref().unwrap_or(crate::indicators::DEFAULT_TWAP_WINDOW);
+    let window_secs = crate::sparkline::parse_window(window).map_err(ApiError::bad_request)?;
+    crate::indicators::load_twap(&state.db, query.subscription_id, window_secs, chrono::Utc::now().timestamp())
+        .map(ApiResponse::ok)
+        .map_err(ApiError::internal)
+}
+
+/// GET /candles?provider_id=binance&symbol=BTCUSDT&from=&to=&limit=1440
+/// Saved 1-minute candles in the range, oldest first.
+async fn get_candles(
+    State(state): State<Arc<CoreState>>,
+    Query(query): Query<CandlesQuery>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    let limit = query
+        .limit
+        .unwrap_or(crate::candles::DEFAULT_QUERY_LIMIT)
+        .min(crate::candles::MAX_QUERY_LIMIT);
+    state
+        .db
+        .get_candles(&query.provider_id, &query.symbol, query.from, query.to, limit)
+        .map(ApiResponse::ok)
+        .map_err(ApiError::internal)
+}
+
+/// GET /prices/changes?since=<ms>
+/// Return only the cached prices updated after `since`, so external pollers can
+/// sync incrementally instead of re-downloading the full list.
+async fn get_changes(
+    State(state): State<Arc<CoreState>>,
+    Query(query): Query<ChangesQuery>,
+) -> impl IntoResponse {
+    // 先取時間再讀快取：期間寫入的資料在下一次請求中仍會被涵蓋
+    let as_of = chrono::Utc::now().timestamp_millis();
+    let prices = state.polling.cached_prices_since(Some(query.since)).await;
+    ApiResponse::ok(PriceChanges { as_of, prices })
 }
 
 /// GET /prices/poll-ticks
@@ -134,6 +241,22 @@ async fn get_poll_ticks(
     ApiResponse::ok(data)
 }
 
+/// GET /prices/gas
+/// Get the latest cached gas price per chain.
+async fn get_gas_prices() -> impl IntoResponse {
+    ApiResponse::ok(crate::gas::latest())
+}
+
+/// GET /depeg
+/// Get the latest stablecoin depeg status; polls once when the monitor has not run yet.
+async fn get_depeg_status(State(state): State<Arc<CoreState>>) -> impl IntoResponse {
+    let mut statuses = crate::depeg::latest();
+    if statuses.is_empty() {
+        statuses = crate::depeg::poll_once(&state.registry, &state.db, &state.event_bus).await;
+    }
+    ApiResponse::ok(statuses)
+}
+
 /// GET /history/stats?subscription_ids=1,2,3
 /// Get history statistics for specified subscription IDs.
 async fn get_stats(
@@ -152,20 +275,11 @@ async fn get_stats(
         ));
     }
 
-    let mut results = Vec::new();
-    for sid in ids {
-        match state.db.get_history_stats(sid) {
-            Ok(stats) => results.push(HistoryStatsResult {
-                subscription_id: sid,
-                total_records: stats.total,
-                earliest: stats.oldest,
-                latest: stats.newest,
-            }),
-            Err(e) => return Err(ApiError::internal(e)),
-        }
-    }
-
-    Ok(ApiResponse::ok(results))
+    ids.into_iter()
+        .map(|sid| crate::record_health::history_stats(&state.db, sid))
+        .collect::<Result<Vec<_>, _>>()
+        .map(ApiResponse::ok)
+        .map_err(ApiError::internal)
 }
 
 /// GET /history/:sub_id?from=&to=&limit=
@@ -176,13 +290,82 @@ async fn get_history(
     Query(query): Query<HistoryQuery>,
 ) -> Result<impl IntoResponse, impl IntoResponse> {
     let limit = query.limit.unwrap_or(500);
+    let (mut liquidity, mut derivatives) = (false, false);
+    for field in query.fields.iter().flat_map(|f| f.split(',')).map(str::trim).filter(|f| !f.is_empty()) {
+        match field {
+            "liquidity" => liquidity = true,
+            "derivatives" => derivatives = true,
+            other => return Err(ApiError::bad_request(format!("Unknown history field: {}", other))),
+        }
+    }
 
     match state.db.get_price_history(sub_id, query.from, query.to, limit) {
-        Ok(rows) => Ok(ApiResponse::ok(rows)),
+        Ok(mut rows) => {
+            for row in &mut rows {
+                if !liquidity {
+                    row.liquidity_usd = None;
+                    row.fdv = None;
+                }
+                if !derivatives {
+                    row.open_interest = None;
+                    row.long_short_ratio = None;
+                }
+            }
+            Ok(ApiResponse::ok(rows))
+        }
         Err(e) => Err(ApiError::internal(e)),
     }
 }
 
+/// GET /history/:sub_id/events?from=&to=
+/// Provider switches (`provider_change`) and symbol renames (`symbol_rename`), oldest first.
+async fn get_events(
+    State(state): State<Arc<CoreState>>,
+    Path(sub_id): Path<i64>,
+    Query(query): Query<EventsQuery>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    state
+        .db
+        .list_subscription_events(sub_id, query.from, query.to)
+        .map(ApiResponse::ok)
+        .map_err(ApiError::internal)
+}
+
+/// GET /history/:sub_id/streaks
+/// Up/down day streaks, max drawdown and recovery time computed from daily closes.
+async fn get_streaks(
+    State(state): State<Arc<CoreState>>,
+    Path(sub_id): Path<i64>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    crate::streaks::load(&state.db, sub_id)
+        .map(ApiResponse::ok)
+        .map_err(ApiError::internal)
+}
+
+/// GET /history/:sub_id/vwap?anchor=
+/// Session VWAP from recorded prices and volumes, plus anchored VWAP when `anchor` is given.
+async fn get_vwap(
+    State(state): State<Arc<CoreState>>,
+    Path(sub_id): Path<i64>,
+    Query(query): Query<VwapQuery>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    crate::indicators::load(&state.db, sub_id, query.anchor, chrono::Utc::now().timestamp())
+        .map(ApiResponse::ok)
+        .map_err(ApiError::internal)
+}
+
+/// POST /history/:sub_id/import-csv
+/// Validate external CSV rows, skip timestamps already recorded and insert the rest in one transaction.
+async fn import_history_csv(
+    State(state): State<Arc<CoreState>>,
+    Path(sub_id): Path<i64>,
+    Json(body): Json<ImportHistoryCsvRequest>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    crate::history_csv::import(&state.db, sub_id, &body.content, body.dry_run, chrono::Utc::now().timestamp())
+        .map(ApiResponse::ok)
+        .map_err(ApiError::bad_request)
+}
+
 /// POST /history/cleanup
 /// Delete history records older than retention_days (default 90).
 async fn cleanup(
@@ -3,51 +3,38 @@
 //! Routes:
 //! - `GET  /providers`                — list all available providers
 //! - `POST /providers/:id/enable`     — enable a provider (register with registry)
+//! - `DELETE /providers/cache`        — drop cached provider instances and settings
+//! - `GET  /providers/resolve?symbol=` — classify a symbol and rank the configured providers that can serve it
+//! - `GET  /providers/anomalies`      — per-provider counts of prices rejected by the sanity filter
+//! - `GET  /providers/latency`        — per-provider fetch latency (p50 / p95) and the effective polling interval
+//! - `GET  /providers/:id/debug`      — captured raw HTTP responses for a provider
+//! - `PUT  /providers/:id/debug`      — enable/disable raw response capture
 //! - `GET  /provider-settings`        — list all provider settings from DB
 //! - `PUT  /provider-settings/:id`    — upsert provider settings
 //! - `GET  /provider-settings/:id/has-key` — check if provider has an API key configured
+//! - `GET  /provider-settings/:id/record-hours` — the provider's recording window and the effective window
+//! - `PUT  /provider-settings/:id/record-hours` — set the provider's recording window
+//! - `DELETE /provider-settings/:id/record-hours` — clear it (record all day unless a subscription overrides)
 
 use std::sync::Arc;
 
 use axum::{
-    extract::{Path, State},
-    routing::{get, post, put},
+    extract::{Path, Query, State},
+    routing::{delete, get, post, put},
     Json, Router,
 };
-use serde::Deserialize;
 
 use crate::core_state::CoreState;
-use crate::providers::get_all_provider_info;
+use crate::providers::{get_all_provider_info, HttpOptions};
 
 use super::{ApiError, ApiResponse};
 
 // ─── Request Bodies ─────────────────────────────────────────────────────────────
 
-/// Body for `POST /providers/:id/enable`
-#[derive(Debug, Deserialize)]
-pub struct EnableProviderBody {
-    pub api_key: Option<String>,
-    pub api_secret: Option<String>,
-}
-
-/// Body for `PUT /provider-settings/:id`
-#[derive(Debug, Deserialize)]
-pub struct UpsertProviderSettingsBody {
-    pub api_key: Option<String>,
-    pub api_secret: Option<String>,
-    pub api_url: Option<String>,
-    pub refresh_interval: Option<i64>,
-    pub connection_type: Option<String>,
-    pub record_from_hour: Option<i64>,
-    pub record_to_hour: Option<i64>,
-}
-
-/// Body for `PUT /provider-settings/:id/record-hours`
-#[derive(Debug, Deserialize)]
-pub struct SetProviderRecordHoursBody {
-    pub from_hour: Option<i64>,
-    pub to_hour: Option<i64>,
-}
+pub use stockenboard_api_types::providers::{
+    EnableProviderBody, ResolveProviderQuery, SetProviderDebugBody, SetProviderRecordHoursBody,
+    UpsertProviderSettingsBody,
+};
 
 // ─── Router ─────────────────────────────────────────────────────────────────────
 
@@ -55,10 +42,18 @@ pub fn router() -> Router<Arc<CoreState>> {
     Router::new()
         .route("/providers", get(list_providers))
         .route("/providers/:id/enable", post(enable_provider))
+        .route("/providers/cache", delete(clear_provider_cache))
+        .route("/providers/resolve", get(resolve_provider))
+        .route("/providers/anomalies", get(get_price_anomalies))
+        .route("/providers/latency", get(get_provider_latency))
+        .route("/providers/:id/debug", get(get_provider_debug).put(set_provider_debug))
         .route("/provider-settings", get(list_settings))
         .route("/provider-settings/:id", put(upsert_settings))
         .route("/provider-settings/:id/has-key", get(has_key))
-        .route("/provider-settings/:id/record-hours", put(set_provider_record_hours))
+        .route(
+            "/provider-settings/:id/record-hours",
+            get(get_provider_record_hours).put(set_provider_record_hours).delete(clear_provider_record_hours),
+        )
 }
 
 // ─── Handlers ───────────────────────────────────────────────────────────────────
@@ -69,38 +64,67 @@ async fn list_providers() -> impl axum::response::IntoResponse {
     ApiResponse::ok(providers)
 }
 
+/// `GET /providers/resolve?symbol=` — suggest providers for a plain symbol, best first.
+///
+/// Providers missing a required key, in failure backoff or out of quota are listed last
+/// with `available: false` and a `reason`.
+async fn resolve_provider(
+    State(state): State<Arc<CoreState>>,
+    Query(query): Query<ResolveProviderQuery>,
+) -> impl axum::response::IntoResponse {
+    crate::provider_resolver::resolve(&state, &query.symbol)
+        .await
+        .map(ApiResponse::ok)
+        .map_err(ApiError::bad_request)
+}
+
 /// `POST /providers/:id/enable` — enable a provider in the registry.
 ///
-/// Reads stored api_url from DB settings, then calls `registry.update_provider(...)`.
-/// Finally triggers a polling reload.
+/// Without credentials in the body the cached instance is dropped and rebuilt
+/// from DB settings on next use; otherwise reads stored api_url from DB settings
+/// and calls `registry.update_provider(...)`. Finally triggers a polling reload.
 async fn enable_provider(
     State(state): State<Arc<CoreState>>,
     Path(id): Path<String>,
     Json(body): Json<EnableProviderBody>,
 ) -> impl axum::response::IntoResponse {
-    // Look up existing api_url from DB settings
-    let api_url = state
-        .db
-        .get_provider_settings(&id)
-        .ok()
-        .flatten()
-        .and_then(|s| s.api_url.filter(|u| !u.is_empty()));
-
-    state
-        .registry
-        .update_provider(&id, body.api_key, body.api_secret, api_url)
-        .await;
+    if body.api_key.is_none() && body.api_secret.is_none() {
+        state.registry.invalidate(&id).await;
+    } else {
+        // Look up existing api_url / proxy from DB settings
+        let settings = state.db.get_provider_settings(&id).ok().flatten();
+        let http = settings.as_ref().map(HttpOptions::from).unwrap_or_default();
+        let api_url = settings.and_then(|s| s.api_url.filter(|u| !u.is_empty()));
+
+        state
+            .registry
+            .update_provider(&id, body.api_key, body.api_secret, api_url, http)
+            .await;
+    }
 
     state.polling.reload();
 
     ApiResponse::ok(serde_json::json!({ "enabled": true }))
 }
 
+/// `DELETE /providers/cache` — drop all cached provider instances and settings
+/// so the next fetch rebuilds them from the current DB settings.
+async fn clear_provider_cache(
+    State(state): State<Arc<CoreState>>,
+) -> impl axum::response::IntoResponse {
+    state.db.invalidate_provider_settings_cache();
+    state.registry.clear().await;
+    state.polling.reload();
+    ApiResponse::ok(serde_json::json!({ "cleared": true }))
+}
+
 /// `GET /provider-settings` — list all provider settings rows from the database.
 async fn list_settings(
     State(state): State<Arc<CoreState>>,
+    redact: Option<axum::Extension<super::RedactSecrets>>,
 ) -> impl axum::response::IntoResponse {
     match state.db.list_provider_settings() {
+        Ok(settings) if redact.is_some() => Ok(ApiResponse::ok(settings.into_iter().map(|s| s.redacted()).collect::<Vec<_>>())),
         Ok(settings) => Ok(ApiResponse::ok(settings)),
         Err(e) => Err(ApiError::internal(e)),
     }
@@ -123,20 +147,15 @@ async fn upsert_settings(
         connection_type,
         body.record_from_hour,
         body.record_to_hour,
+        body.proxy_url.as_deref(),
+        body.timeout_ms,
+        body.extra_headers.as_deref(),
     );
 
     match result {
         Ok(()) => {
-            // Also update the registry so the provider is available for fetching
-            state
-                .registry
-                .update_provider(
-                    &id,
-                    body.api_key,
-                    body.api_secret,
-                    body.api_url,
-                )
-                .await;
+            // Drop the cached instance so the new settings take effect on next fetch
+            state.registry.invalidate(&id).await;
 
             state.polling.reload();
 
@@ -155,6 +174,32 @@ async fn has_key(
     ApiResponse::ok(exists)
 }
 
+/// `GET /provider-settings/:id/record-hours` — recording hours for a provider; all day when unset.
+async fn get_provider_record_hours(
+    State(state): State<Arc<CoreState>>,
+    Path(id): Path<String>,
+) -> impl axum::response::IntoResponse {
+    state
+        .db
+        .get_provider_record_hours(&id)
+        .map(ApiResponse::ok)
+        .map_err(ApiError::internal)
+}
+
+/// `DELETE /provider-settings/:id/record-hours` — clear recording hours for a provider.
+async fn clear_provider_record_hours(
+    State(state): State<Arc<CoreState>>,
+    Path(id): Path<String>,
+) -> impl axum::response::IntoResponse {
+    match state.db.clear_provider_record_hours(&id) {
+        Ok(()) => {
+            state.polling.reload();
+            Ok(ApiResponse::ok(serde_json::json!({ "success": true })))
+        }
+        Err(e) => Err(ApiError::internal(e)),
+    }
+}
+
 /// `PUT /provider-settings/:id/record-hours` — set recording hours for a provider.
 async fn set_provider_record_hours(
     State(state): State<Arc<CoreState>>,
@@ -162,7 +207,37 @@ async fn set_provider_record_hours(
     Json(body): Json<SetProviderRecordHoursBody>,
 ) -> impl axum::response::IntoResponse {
     match state.db.set_provider_record_hours(&id, body.from_hour, body.to_hour) {
-        Ok(()) => Ok(ApiResponse::ok(serde_json::json!({ "success": true }))),
+        Ok(()) => {
+            // 紀錄時段在 polling 重新載入時解析
+            state.polling.reload();
+            Ok(ApiResponse::ok(serde_json::json!({ "success": true })))
+        }
         Err(e) => Err(ApiError::internal(e)),
     }
 }
+
+/// `GET /providers/anomalies` — rejected-price counts and the latest anomaly per provider.
+async fn get_price_anomalies() -> impl axum::response::IntoResponse {
+    ApiResponse::ok(crate::sanity::metrics())
+}
+
+/// `GET /providers/latency` — recent fetch latency and the effective interval per provider.
+async fn get_provider_latency() -> impl axum::response::IntoResponse {
+    ApiResponse::ok(crate::latency_tune::latency_metrics())
+}
+
+/// `GET /providers/:id/debug` — captured raw HTTP responses (newest first).
+async fn get_provider_debug(Path(id): Path<String>) -> impl axum::response::IntoResponse {
+    ApiResponse::ok(crate::providers::debug::debug_info(&id))
+}
+
+/// `PUT /providers/:id/debug` — toggle raw response capture for a provider.
+async fn set_provider_debug(
+    Path(id): Path<String>,
+    Json(body): Json<SetProviderDebugBody>,
+) -> impl axum::response::IntoResponse {
+    match crate::providers::debug::set_capture(&id, body.enabled) {
+        Ok(()) => Ok(ApiResponse::ok(serde_json::json!({ "enabled": body.enabled }))),
+        Err(e) => Err(ApiError::bad_request(e)),
+    }
+}
@@ -4,9 +4,18 @@
 //! - `GET /subscriptions` — list all (with optional `?type=` filter)
 //! - `POST /subscriptions` — add a single subscription
 //! - `POST /subscriptions/batch` — add multiple subscriptions
+//! - `POST /subscriptions/import-csv` — bulk import from CSV with per-row errors
+//! - `POST /subscriptions/basket` — create a basket subscription from weighted component subscriptions
+//! - `GET /subscriptions/duplicates` — group subscriptions tracking the same asset under different providers/formats
+//! - `POST /subscriptions/merge` — merge duplicates into one subscription, keeping their history, views and rules
 //! - `PUT /subscriptions/:id` — update a subscription
+//! - `PUT /subscriptions/:id/provider-params` — set provider-specific parameters (JSON object, `null` clears)
 //! - `DELETE /subscriptions/:id` — remove a subscription
 //! - `DELETE /subscriptions/batch` — remove multiple subscriptions
+//! - `GET /subscriptions/:id/record-hours` — own and provider recording hours plus the effective window
+//! - `PUT /subscriptions/:id/record-hours` — set the subscription's recording hours
+//! - `DELETE /subscriptions/:id/record-hours` — clear them so the provider's hours apply
+//! - `PUT /subscriptions/:id/record-dedup` — set the minimum seconds between recorded prices (`null` = default)
 
 use std::sync::Arc;
 
@@ -15,7 +24,6 @@ use axum::{
     routing::{get, post, put},
     Router,
 };
-use serde::Deserialize;
 
 use crate::api::{ApiError, ApiResponse};
 use crate::core_state::CoreState;
@@ -24,53 +32,12 @@ use crate::providers::normalize_symbol;
 
 // ─── Query / Request Types ──────────────────────────────────────────────────────
 
-#[derive(Debug, Deserialize)]
-pub struct ListQuery {
-    #[serde(rename = "type")]
-    pub sub_type: Option<String>,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct AddSubscriptionRequest {
-    pub sub_type: String,
-    pub symbol: String,
-    pub display_name: Option<String>,
-    pub provider_id: String,
-    pub asset_type: String,
-    pub pool_address: Option<String>,
-    pub token_from: Option<String>,
-    pub token_to: Option<String>,
-}
-
 use crate::db::BatchAddItem;
-
-#[derive(Debug, Deserialize)]
-pub struct UpdateSubscriptionRequest {
-    pub symbol: String,
-    pub display_name: Option<String>,
-    pub provider_id: String,
-    pub asset_type: String,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct BatchRemoveRequest {
-    pub ids: Vec<i64>,
-}
-
-// ─── Router ─────────────────────────────────────────────────────────────────────
-
-// ─── Toggle Record / Record Hours Types ─────────────────────────────────────────
-
-#[derive(Debug, Deserialize)]
-pub struct ToggleRecordRequest {
-    pub enabled: bool,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct SetRecordHoursRequest {
-    pub from_hour: Option<i64>,
-    pub to_hour: Option<i64>,
-}
+pub use stockenboard_api_types::subscriptions::{
+    AddSubscriptionRequest, BatchRemoveRequest, CreateBasketRequest, ImportCsvRequest, ListQuery,
+    MergeSubscriptionsRequest, SetProviderParamsRequest, SetRecordDedupRequest, SetRecordHoursRequest, ToggleRecordRequest,
+    UpdateSubscriptionRequest,
+};
 
 // ─── Router ─────────────────────────────────────────────────────────────────────
 
@@ -78,9 +45,18 @@ pub fn router() -> Router<Arc<CoreState>> {
     Router::new()
         .route("/subscriptions", get(list_subscriptions).post(add_subscription))
         .route("/subscriptions/batch", post(add_batch).delete(remove_batch))
+        .route("/subscriptions/import-csv", post(import_csv))
+        .route("/subscriptions/basket", post(create_basket))
+        .route("/subscriptions/duplicates", get(find_duplicates))
+        .route("/subscriptions/merge", post(merge_subscriptions))
         .route("/subscriptions/:id", put(update_subscription).delete(remove_subscription))
+        .route("/subscriptions/:id/provider-params", put(set_provider_params))
         .route("/subscriptions/:id/toggle-record", post(toggle_record))
-        .route("/subscriptions/:id/record-hours", axum::routing::put(set_record_hours))
+        .route(
+            "/subscriptions/:id/record-hours",
+            get(get_record_hours).put(set_record_hours).delete(clear_record_hours),
+        )
+        .route("/subscriptions/:id/record-dedup", put(set_record_dedup))
 }
 
 // ─── Handlers ───────────────────────────────────────────────────────────────────
@@ -178,6 +154,59 @@ async fn add_batch(
     .into_response())
 }
 
+/// POST /subscriptions/import-csv
+/// Validates each CSV row against its provider and imports the valid ones in one transaction.
+async fn import_csv(
+    State(state): State<Arc<CoreState>>,
+    Json(body): Json<ImportCsvRequest>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    let report = crate::subscription_csv::import(&state, &body.content, body.validate)
+        .await
+        .map_err(|e| ApiError::bad_request(e).into_response())?;
+    Ok(ApiResponse::ok(report).into_response())
+}
+
+/// POST /subscriptions/basket
+/// Create a basket subscription; component prices at creation become the index base (100).
+async fn create_basket(
+    State(state): State<Arc<CoreState>>,
+    Json(body): Json<CreateBasketRequest>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    let id = crate::basket::create(&state, &body.symbol, body.display_name.as_deref(), &body.components)
+        .await
+        .map_err(|e| ApiError::bad_request(e).into_response())?;
+    Ok(ApiResponse::created(serde_json::json!({ "id": id })).into_response())
+}
+
+/// GET /subscriptions/duplicates
+/// List groups of subscriptions that track the same asset, with a suggested one to keep.
+async fn find_duplicates(
+    State(state): State<Arc<CoreState>>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    let groups = crate::subscription_dedup::find_duplicates(&state.db)
+        .map_err(|e| ApiError::internal(e).into_response())?;
+    Ok(ApiResponse::ok(groups).into_response())
+}
+
+/// POST /subscriptions/merge
+/// Merge `merge_ids` into `keep_id` and delete them; history at identical timestamps is dropped.
+async fn merge_subscriptions(
+    State(state): State<Arc<CoreState>>,
+    Json(body): Json<MergeSubscriptionsRequest>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    let result = crate::subscription_dedup::merge(&state, body.keep_id, &body.merge_ids)
+        .map_err(|e| ApiError::bad_request(e).into_response())?;
+    Ok(ApiResponse::ok(result).into_response())
+}
+
 /// PUT /subscriptions/:id
 /// Update an existing subscription.
 async fn update_subscription(
@@ -204,6 +233,22 @@ async fn update_subscription(
     }
 }
 
+/// PUT /subscriptions/:id/provider-params
+/// Set provider-specific parameters; takes effect on the next fetch.
+async fn set_provider_params(
+    State(state): State<Arc<CoreState>>,
+    Path(id): Path<i64>,
+    Json(body): Json<SetProviderParamsRequest>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    let json = body.params.map(|p| p.to_string());
+    match state.db.set_provider_params(id, json.as_deref()) {
+        Ok(()) => Ok(ApiResponse::ok(serde_json::json!({ "success": true })).into_response()),
+        Err(e) => Err(ApiError::bad_request(e).into_response()),
+    }
+}
+
 /// DELETE /subscriptions/:id
 /// Remove a single subscription.
 async fn remove_subscription(
@@ -256,6 +301,38 @@ async fn toggle_record(
     }
 }
 
+/// GET /subscriptions/:id/record-hours
+/// Recording hours for a subscription, its provider's hours and the effective window
+/// (subscription > provider > all day).
+async fn get_record_hours(
+    State(state): State<Arc<CoreState>>,
+    Path(id): Path<i64>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    match state.db.get_record_hours(id) {
+        Ok(hours) => Ok(ApiResponse::ok(hours).into_response()),
+        Err(e) => Err(ApiError::not_found(e).into_response()),
+    }
+}
+
+/// DELETE /subscriptions/:id/record-hours
+/// Clear recording hours for a subscription so it inherits its provider's hours.
+async fn clear_record_hours(
+    State(state): State<Arc<CoreState>>,
+    Path(id): Path<i64>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    match state.db.set_record_hours(id, None, None) {
+        Ok(()) => {
+            state.polling.reload();
+            Ok(ApiResponse::ok(serde_json::json!({ "success": true })).into_response())
+        }
+        Err(e) => Err(ApiError::internal(e).into_response()),
+    }
+}
+
 /// PUT /subscriptions/:id/record-hours
 /// Set recording hours for a subscription.
 async fn set_record_hours(
@@ -266,7 +343,29 @@ async fn set_record_hours(
     use axum::response::IntoResponse;
 
     match state.db.set_record_hours(id, body.from_hour, body.to_hour) {
-        Ok(()) => Ok(ApiResponse::ok(serde_json::json!({ "success": true })).into_response()),
+        Ok(()) => {
+            state.polling.reload();
+            Ok(ApiResponse::ok(serde_json::json!({ "success": true })).into_response())
+        }
         Err(e) => Err(ApiError::internal(e).into_response()),
     }
 }
+
+/// PUT /subscriptions/:id/record-dedup
+/// Set the dedup window for a subscription's price history: polls within `dedup_secs` of the
+/// last recorded price are skipped. `0` records every poll, `null` restores the 5-second default.
+async fn set_record_dedup(
+    State(state): State<Arc<CoreState>>,
+    Path(id): Path<i64>,
+    Json(body): Json<SetRecordDedupRequest>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    match state.db.set_record_dedup(id, body.dedup_secs) {
+        Ok(()) => {
+            state.polling.reload();
+            Ok(ApiResponse::ok(serde_json::json!({ "success": true })).into_response())
+        }
+        Err(e) => Err(ApiError::bad_request(e).into_response()),
+    }
+}
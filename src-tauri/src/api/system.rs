@@ -1,18 +1,55 @@
 //! System, icon, data, and DEX endpoints.
 //!
 //! Provides:
-//! - `GET /system/config` — get system config (api_port, unattended_polling)
+//! - `GET /system/config` — get system config (api_port, unattended_polling, recording_paused, http_proxy, log_level, language, yahoo_quote_summary, jupiter_token_list, alphavantage_refresh_hours, alphavantage_quota_remaining, eodhd_default_exchange, provider_metadata, provider_metadata_status)
 //! - `PUT /system/config` — set system config
 //! - `POST /system/reload-polling` — reload polling
 //! - `POST /system/reset` — reset all data
 //! - `GET /system/data-dir` — get data directory path
+//! - `GET /system/logs` — recent log entries from the in-memory ring buffer
+//! - `GET /system/demo-mode` / `PUT /system/demo-mode` — mock provider (demo mode) settings
+//! - `GET /system/replay` / `PUT /system/replay` — history replay provider settings
+//! - `GET /system/subgraph-protocols` / `PUT /system/subgraph-protocols` — subgraph protocol registry (PUT stores the custom entries)
+//! - `GET /system/recording-schedule` / `PUT /system/recording-schedule` — scheduled unattended recording
+//! - `GET /system/power-mode` / `PUT /system/power-mode` — battery-aware low-power polling
+//! - `GET /system/gas-tracker` / `PUT /system/gas-tracker` — gas price tracker settings (Etherscan key, RPC URLs, interval)
+//! - `GET /system/depeg-monitor` / `PUT /system/depeg-monitor` — stablecoin depeg monitor settings (interval, threshold)
+//! - `GET /system/economic-calendar` / `PUT /system/economic-calendar` — economic calendar settings (source, countries, alert keywords)
+//! - `GET /system/price-sanity` / `PUT /system/price-sanity` — outlier price rejection settings (multiple, window)
+//! - `GET /system/cache-limit` / `PUT /system/cache-limit` — price cache size limit (`max_entries`, LRU eviction)
+//! - `GET /system/emit-throttle` / `PUT /system/emit-throttle` — desktop event coalescing (`max_per_sec`, 0 disables)
+//! - `GET /system/record-stall` / `PUT /system/record-stall` — alert when recording writes nothing for `stall_minutes` (0 disables)
+//! - `GET /system/latency-tune` / `PUT /system/latency-tune` — nudge provider intervals by p95 latency (enabled, min / max interval)
+//! - `GET /system/candles` / `PUT /system/candles` — whether closed 1-minute WS candles are saved, and for how many days
+//! - `GET /system/telemetry` / `PUT /system/telemetry` — OTLP trace export settings (enabled, endpoint, service name)
+//! - `GET /system/cloud-backup` / `PUT /system/cloud-backup` — S3-compatible backup target (secret key and passphrase are write-only; empty keeps the stored value)
+//! - `POST /system/cloud-backup/run` — upload an encrypted DB snapshot now
+//! - `GET /system/cloud-backups` — list backups in the bucket, newest first
+//! - `POST /system/cloud-backups/restore` — replace the database with a backup (`{key}`)
+//! - `GET /system/settings-sync` / `PUT /system/settings-sync` — WebDAV / folder settings sync (password is write-only)
+//! - `POST /system/settings-sync/run` — sync now; returns the action (`pushed` / `pulled` / `up_to_date`) and the last status
+//! - `GET /system/kiosk` / `PUT /system/kiosk` — kiosk (read-only) mode: `{enabled, pin, token, current_pin}`; `current_pin` is required to change it while locked
+//! - `GET /system/lan-listener` / `PUT /system/lan-listener` — second API listener for the LAN: `{enabled, bind, port, token, scope}`
+//!   (`scope` is `read_only` or `full`; every request needs the token); GET also returns the listener `status`
+//! - `GET /status` — version, polling mode, and price cache usage (entries, evictions, approximate bytes)
+//! - `GET/PUT/DELETE /system/theme-bg/:theme_id` — theme background (PUT takes `{path}` / `{data}`)
+//! - `GET /system/read-file?path=` — read an icon / theme background as a data URL (data dirs only)
+//! - `POST /system/deep-link` — apply a `stockenboard://` link (add subscription / resolve view)
+//! - `GET /system/polling-rates` — requested and effective polling interval per window/scope (`PUT /system/visible-subscriptions` takes an optional `interval_ms`)
 //! - `POST /icons/:symbol` — set icon (raw bytes upload)
+//! - `POST /icons/:symbol/from-path` — set icon from a server-side file path or base64 data (`{path}` / `{data}`)
 //! - `DELETE /icons/:symbol` — remove icon
 //! - `GET /icons` — list available icons
+//! - `GET /icons/mappings` — symbol → icon filename table
 //! - `POST /icons/download-logos` — download logos for all subscriptions
+//! - `POST /icons/:symbol/fetch` — auto-fetch a logo for one symbol (`{provider_id}`), saved as PNG
 //! - `GET /data/export` — export data
 //! - `POST /data/import` — import data
+//! - `GET /data/config` — export the full app config (`?include_secrets=true` adds API keys)
+//! - `POST /data/config` — import an app config (`?mode=merge|replace`, default merge)
+//! - `POST /data/export-file` — render subscriptions or price history as a JSON / CSV / XLSX download
 //! - `GET /dex/pool/:provider/:address` — lookup DEX pool
+//! - `POST /dex/quotes` — compare a swap quote across DEX aggregators (`{chain, token_in, token_out, amount}`)
 
 use std::sync::Arc;
 
@@ -26,8 +63,12 @@ use serde::{Deserialize, Serialize};
 
 use crate::api::{ApiError, ApiResponse};
 use crate::core_state::CoreState;
-use crate::db::ExportData;
-use crate::providers::create_dex_lookup;
+use crate::file_access::FileAccessError;
+use crate::db::{AppConfig, ConfigImportMode, DbError, ExportData};
+use crate::file_export::{self, ExportDataset, ExportFormat};
+use crate::i18n::{LocalizedError, Msg};
+use crate::providers::metadata::{MetadataSource, MetadataStatus};
+use crate::providers::{create_dex_lookup, HttpOptions};
 
 // ─── Request / Response Types ───────────────────────────────────────────────────
 
@@ -35,20 +76,60 @@ use crate::providers::create_dex_lookup;
 struct SystemConfig {
     api_port: u16,
     unattended_polling: bool,
+    recording_paused: bool,
     api_enabled: bool,
+    http_proxy: Option<String>,
+    log_level: String,
+    language: &'static str,
+    yahoo_quote_summary: bool,
+    jupiter_token_list: bool,
+    alphavantage_refresh_hours: u32,
+    alphavantage_quota_remaining: u32,
+    eodhd_default_exchange: String,
+    provider_metadata: MetadataSource,
+    provider_metadata_status: MetadataStatus,
 }
 
 #[derive(Debug, Deserialize)]
 struct SetSystemConfig {
     api_port: Option<u16>,
     unattended_polling: Option<bool>,
+    recording_paused: Option<bool>,
     api_enabled: Option<bool>,
+    /// Empty string clears the global proxy
+    http_proxy: Option<String>,
+    log_level: Option<String>,
+    /// Backend message language (`zh_TW`, `en`, `ja`; other ids fall back)
+    language: Option<String>,
+    yahoo_quote_summary: Option<bool>,
+    jupiter_token_list: Option<bool>,
+    alphavantage_refresh_hours: Option<u32>,
+    eodhd_default_exchange: Option<String>,
+    /// Saving reloads the signed provider metadata immediately
+    provider_metadata: Option<MetadataSource>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SetKioskConfig {
+    #[serde(flatten)]
+    config: crate::kiosk::KioskConfig,
+    /// PIN required to unlock or change the settings while kiosk mode is on
+    #[serde(default)]
+    current_pin: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RecentLogsQuery {
+    level: Option<String>,
+    limit: Option<usize>,
 }
 
 #[derive(Debug, Deserialize)]
 struct SetVisibleSubscriptionsRequest {
     ids: Vec<i64>,
     scope: Option<String>,
+    /// Requested update cadence for this scope; omitted uses the provider interval
+    interval_ms: Option<u64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -70,21 +151,64 @@ pub fn router() -> Router<Arc<CoreState>> {
         .route("/system/reload-polling", post(reload_polling))
         .route("/system/reset", post(reset_all))
         .route("/system/data-dir", get(get_data_dir))
+        .route("/system/logs", get(get_recent_logs))
+        .route("/system/demo-mode", get(get_demo_mode).put(set_demo_mode))
+        .route("/system/replay", get(get_replay_config).put(set_replay_config))
+        .route(
+            "/system/subgraph-protocols",
+            get(get_subgraph_protocols).put(set_subgraph_protocols),
+        )
+        .route(
+            "/system/recording-schedule",
+            get(get_recording_schedule).put(set_recording_schedule),
+        )
+        .route("/system/power-mode", get(get_power_mode).put(set_power_mode))
+        .route("/system/gas-tracker", get(get_gas_tracker).put(set_gas_tracker))
+        .route("/system/depeg-monitor", get(get_depeg_monitor).put(set_depeg_monitor))
+        .route("/system/economic-calendar", get(get_economic_calendar).put(set_economic_calendar))
+        .route("/system/price-sanity", get(get_price_sanity).put(set_price_sanity))
+        .route("/system/cache-limit", get(get_cache_limit).put(set_cache_limit))
+        .route("/system/emit-throttle", get(get_emit_throttle).put(set_emit_throttle))
+        .route("/system/record-stall", get(get_record_stall).put(set_record_stall))
+        .route("/system/latency-tune", get(get_latency_tune).put(set_latency_tune))
+        .route("/system/ws-first", get(get_ws_first).put(set_ws_first))
+        .route("/system/candles", get(get_candles_config).put(set_candles_config))
+        .route("/system/telemetry", get(get_telemetry).put(set_telemetry))
+        .route("/system/cloud-backup", get(get_cloud_backup).put(set_cloud_backup))
+        .route("/system/cloud-backup/run", post(run_cloud_backup))
+        .route("/system/cloud-backups", get(list_cloud_backups))
+        .route("/system/cloud-backups/restore", post(restore_from_cloud))
+        .route("/system/settings-sync", get(get_settings_sync).put(set_settings_sync))
+        .route("/system/settings-sync/run", post(run_settings_sync))
+        .route("/system/kiosk", get(get_kiosk).put(set_kiosk))
+        .route("/system/lan-listener", get(get_lan_listener).put(set_lan_listener))
+        .route("/status", get(get_status))
+        .route("/system/deep-link", post(open_deep_link))
         .route("/system/visible-subscriptions", axum::routing::put(set_visible_subscriptions))
-        .route("/system/theme-bg/:theme_id", get(get_theme_bg).delete(remove_theme_bg))
+        .route("/system/polling-rates", get(get_polling_rates))
+        .route(
+            "/system/theme-bg/:theme_id",
+            get(get_theme_bg).put(save_theme_bg).delete(remove_theme_bg),
+        )
         .route("/system/read-file", get(read_file_base64))
         .route("/system/desktop-only", post(desktop_only_noop))
         .route("/icons", get(list_icons))
         .route("/icons/dir", get(get_icons_dir_path))
+        .route("/icons/mappings", get(list_icon_mappings))
         .route("/icons/download-logos", post(download_logos))
         .route("/icons/clear-all", delete(clear_all_icons_handler))
         .route("/icons/:symbol/download", post(download_single_icon_handler))
+        .route("/icons/:symbol/fetch", post(fetch_icon_handler))
+        .route("/icons/:symbol/from-path", post(set_icon_from_path))
         .route("/icons/search", get(search_icons_handler))
         .route("/icons/:symbol/save", post(save_icon_from_data_handler))
         .route("/icons/:symbol", post(set_icon).delete(remove_icon))
         .route("/data/export", get(export_data))
         .route("/data/import", post(import_data))
+        .route("/data/config", get(export_app_config).post(import_app_config))
+        .route("/data/export-file", post(export_file))
         .route("/dex/pool/:provider/:address", get(lookup_dex_pool))
+        .route("/dex/quotes", post(compare_dex_quotes))
 }
 
 // ─── System Handlers ────────────────────────────────────────────────────────────
@@ -113,10 +237,23 @@ async fn get_config(
         .map(|v| v == "1")
         .unwrap_or(false);
 
+    let http_proxy = crate::providers::global_proxy();
+
     Ok(ApiResponse::ok(SystemConfig {
         api_port,
         unattended_polling,
+        recording_paused: state.polling.is_recording_paused(),
         api_enabled,
+        http_proxy,
+        log_level: crate::logging::current_level(),
+        language: crate::i18n::locale().id(),
+        yahoo_quote_summary: crate::providers::yahoo::quote_summary_enabled(),
+        jupiter_token_list: crate::providers::jupiter::token_list_enabled(),
+        alphavantage_refresh_hours: crate::providers::alphavantage::refresh_hours(),
+        alphavantage_quota_remaining: crate::providers::alphavantage::quota_remaining().await,
+        eodhd_default_exchange: crate::providers::eodhd::default_exchange(),
+        provider_metadata: crate::core_state::load_provider_metadata_source(&state.db),
+        provider_metadata_status: crate::providers::metadata::status(),
     })
     .into_response())
 }
@@ -142,6 +279,12 @@ async fn set_config(
         state.polling.set_unattended(enabled).await;
     }
 
+    if let Some(paused) = body.recording_paused {
+        state
+            .set_recording_paused(paused)
+            .map_err(|e| ApiError::internal(e).into_response())?;
+    }
+
     if let Some(enabled) = body.api_enabled {
         state
             .db
@@ -149,6 +292,56 @@ async fn set_config(
             .map_err(|e| ApiError::internal(e).into_response())?;
     }
 
+    if let Some(proxy) = body.http_proxy {
+        state
+            .set_http_proxy(Some(proxy))
+            .await
+            .map_err(|e| ApiError::bad_request(e).into_response())?;
+    }
+
+    if let Some(level) = body.log_level {
+        state
+            .set_log_level(&level)
+            .map_err(|e| ApiError::bad_request(e).into_response())?;
+    }
+
+    if let Some(language) = body.language {
+        state
+            .set_language(&language)
+            .map_err(|e| ApiError::internal(e).into_response())?;
+    }
+
+    if let Some(enabled) = body.yahoo_quote_summary {
+        state
+            .set_yahoo_quote_summary(enabled)
+            .map_err(|e| ApiError::internal(e).into_response())?;
+    }
+
+    if let Some(enabled) = body.jupiter_token_list {
+        state
+            .set_jupiter_token_list(enabled)
+            .map_err(|e| ApiError::internal(e).into_response())?;
+    }
+
+    if let Some(hours) = body.alphavantage_refresh_hours {
+        state
+            .set_alphavantage_refresh_hours(hours)
+            .map_err(|e| ApiError::internal(e).into_response())?;
+    }
+
+    if let Some(exchange) = body.eodhd_default_exchange {
+        state
+            .set_eodhd_default_exchange(&exchange)
+            .map_err(|e| ApiError::bad_request(e).into_response())?;
+    }
+
+    if let Some(source) = body.provider_metadata {
+        state
+            .set_provider_metadata_source(source)
+            .await
+            .map_err(|e| ApiError::bad_request(e).into_response())?;
+    }
+
     Ok(ApiResponse::ok(serde_json::json!({ "success": true })).into_response())
 }
 
@@ -158,6 +351,7 @@ async fn reload_polling(
 ) -> Result<axum::response::Response, axum::response::Response> {
     use axum::response::IntoResponse;
 
+    state.db.invalidate_provider_settings_cache();
     state.polling.reload();
     Ok(ApiResponse::ok(serde_json::json!({ "success": true })).into_response())
 }
@@ -173,6 +367,29 @@ async fn reset_all(
         .reset_all_data()
         .map_err(|e| ApiError::internal(e).into_response())?;
 
+    state.registry.clear().await;
+    crate::providers::set_global_proxy(None);
+    crate::providers::mock::set_mock_config(Default::default());
+    crate::providers::replay::set_replay_config(Default::default());
+    state.polling.set_recording_paused(false);
+    crate::power::set_power_config(Default::default());
+    crate::gas::set_gas_config(Default::default());
+    crate::depeg::set_depeg_config(Default::default());
+    crate::calendar::set_calendar_config(Default::default());
+    crate::sanity::set_sanity_config(Default::default());
+    crate::cache_limit::set_cache_config(Default::default());
+    crate::emit_throttle::set_emit_config(Default::default());
+    crate::record_health::set_record_stall_config(Default::default());
+    crate::latency_tune::set_latency_tune_config(Default::default());
+    crate::ws_first::set_ws_first_config(Default::default());
+    crate::candles::set_candle_config(Default::default());
+    crate::telemetry::set_otlp_config(Default::default());
+    crate::cloud_backup::set_cloud_backup_config(Default::default());
+    crate::settings_sync::set_sync_config(Default::default());
+    crate::i18n::set_locale(Default::default());
+    crate::kiosk::set_kiosk_state(Default::default());
+    crate::lan_listener::set_lan_listener_config(Default::default());
+    crate::api::restart_lan_listener(state.clone());
     state.notification_engine.reload_rules().await;
     state.polling.reload();
     Ok(ApiResponse::ok(serde_json::json!({ "success": true })).into_response())
@@ -188,9 +405,526 @@ async fn get_data_dir(
     Ok(ApiResponse::ok(serde_json::json!({ "data_dir": dir })).into_response())
 }
 
+/// GET /system/logs?level=warn&limit=200
+async fn get_recent_logs(
+    Query(query): Query<RecentLogsQuery>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    let logs = crate::logging::recent_logs(query.level.as_deref(), query.limit.unwrap_or(200))
+        .map_err(|e| ApiError::bad_request(e).into_response())?;
+    Ok(ApiResponse::ok(logs).into_response())
+}
+
+/// GET /system/demo-mode
+async fn get_demo_mode() -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    ApiResponse::ok(crate::providers::mock::mock_config()).into_response()
+}
+
+/// PUT /system/demo-mode
+async fn set_demo_mode(
+    State(state): State<Arc<CoreState>>,
+    Json(body): Json<crate::providers::mock::MockConfig>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    state
+        .set_demo_mode(body)
+        .await
+        .map_err(|e| ApiError::bad_request(e).into_response())?;
+    Ok(ApiResponse::ok(serde_json::json!({ "success": true })).into_response())
+}
+
+/// GET /system/replay
+async fn get_replay_config() -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    ApiResponse::ok(crate::providers::replay::replay_config()).into_response()
+}
+
+/// PUT /system/replay
+async fn set_replay_config(
+    State(state): State<Arc<CoreState>>,
+    Json(body): Json<crate::providers::replay::ReplayConfig>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    state
+        .set_replay(body)
+        .await
+        .map_err(|e| ApiError::bad_request(e).into_response())?;
+    Ok(ApiResponse::ok(serde_json::json!({ "success": true })).into_response())
+}
+
+/// GET /system/subgraph-protocols
+async fn get_subgraph_protocols() -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    ApiResponse::ok(crate::providers::subgraph::protocol_registry()).into_response()
+}
+
+/// PUT /system/subgraph-protocols
+async fn set_subgraph_protocols(
+    State(state): State<Arc<CoreState>>,
+    Json(body): Json<Vec<crate::providers::subgraph::SubgraphProtocol>>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    state
+        .set_subgraph_protocols(body)
+        .map_err(|e| ApiError::bad_request(e).into_response())?;
+    Ok(ApiResponse::ok(serde_json::json!({ "success": true })).into_response())
+}
+
+/// GET /system/recording-schedule
+async fn get_recording_schedule(State(state): State<Arc<CoreState>>) -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    ApiResponse::ok(crate::schedule::load_schedule(&state.db)).into_response()
+}
+
+/// PUT /system/recording-schedule
+async fn set_recording_schedule(
+    State(state): State<Arc<CoreState>>,
+    Json(body): Json<crate::schedule::RecordingSchedule>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    state
+        .recording_scheduler
+        .save(&body)
+        .map_err(|e| ApiError::bad_request(e).into_response())?;
+    Ok(ApiResponse::ok(serde_json::json!({ "success": true })).into_response())
+}
+
+/// GET /system/power-mode
+async fn get_power_mode() -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    let config = crate::power::power_config();
+    ApiResponse::ok(serde_json::json!({
+        "enabled": config.enabled,
+        "factor": config.factor,
+        "low_power": crate::power::is_low_power(),
+    }))
+    .into_response()
+}
+
+/// PUT /system/power-mode
+async fn set_power_mode(
+    State(state): State<Arc<CoreState>>,
+    Json(body): Json<crate::power::PowerConfig>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    state
+        .set_power_mode(body)
+        .await
+        .map_err(|e| ApiError::bad_request(e).into_response())?;
+    Ok(ApiResponse::ok(serde_json::json!({ "success": true })).into_response())
+}
+
+/// GET /system/gas-tracker
+async fn get_gas_tracker() -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    ApiResponse::ok(crate::gas::gas_config()).into_response()
+}
+
+/// PUT /system/gas-tracker
+async fn set_gas_tracker(
+    State(state): State<Arc<CoreState>>,
+    Json(body): Json<crate::gas::GasConfig>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    state
+        .set_gas_config(body)
+        .await
+        .map_err(|e| ApiError::bad_request(e).into_response())?;
+    Ok(ApiResponse::ok(serde_json::json!({ "success": true })).into_response())
+}
+
+/// GET /system/depeg-monitor
+async fn get_depeg_monitor() -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    ApiResponse::ok(crate::depeg::depeg_config()).into_response()
+}
+
+/// PUT /system/depeg-monitor
+async fn set_depeg_monitor(
+    State(state): State<Arc<CoreState>>,
+    Json(body): Json<crate::depeg::DepegConfig>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    state
+        .set_depeg_config(body)
+        .await
+        .map_err(|e| ApiError::bad_request(e).into_response())?;
+    Ok(ApiResponse::ok(serde_json::json!({ "success": true })).into_response())
+}
+
+/// GET /system/price-sanity
+async fn get_price_sanity() -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    ApiResponse::ok(crate::sanity::sanity_config()).into_response()
+}
+
+/// PUT /system/price-sanity
+async fn set_price_sanity(
+    State(state): State<Arc<CoreState>>,
+    Json(body): Json<crate::sanity::SanityConfig>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    state
+        .set_sanity_config(body)
+        .map_err(|e| ApiError::bad_request(e).into_response())?;
+    Ok(ApiResponse::ok(serde_json::json!({ "success": true })).into_response())
+}
+
+/// GET /system/cache-limit
+async fn get_cache_limit() -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    ApiResponse::ok(crate::cache_limit::cache_config()).into_response()
+}
+
+/// PUT /system/cache-limit
+async fn set_cache_limit(
+    State(state): State<Arc<CoreState>>,
+    Json(body): Json<crate::cache_limit::CacheConfig>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    state
+        .set_cache_config(body)
+        .map_err(|e| ApiError::bad_request(e).into_response())?;
+    Ok(ApiResponse::ok(serde_json::json!({ "success": true })).into_response())
+}
+
+/// GET /system/emit-throttle
+async fn get_emit_throttle() -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    ApiResponse::ok(crate::emit_throttle::emit_config()).into_response()
+}
+
+/// PUT /system/emit-throttle
+async fn set_emit_throttle(
+    State(state): State<Arc<CoreState>>,
+    Json(body): Json<crate::emit_throttle::EmitConfig>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    state
+        .set_emit_config(body)
+        .map_err(|e| ApiError::bad_request(e).into_response())?;
+    Ok(ApiResponse::ok(serde_json::json!({ "success": true })).into_response())
+}
+
+/// GET /system/record-stall
+async fn get_record_stall() -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    ApiResponse::ok(crate::record_health::record_stall_config()).into_response()
+}
+
+/// PUT /system/record-stall
+async fn set_record_stall(
+    State(state): State<Arc<CoreState>>,
+    Json(body): Json<crate::record_health::RecordStallConfig>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    state
+        .set_record_stall_config(body)
+        .map_err(|e| ApiError::bad_request(e).into_response())?;
+    Ok(ApiResponse::ok(serde_json::json!({ "success": true })).into_response())
+}
+
+/// GET /system/latency-tune
+async fn get_latency_tune() -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    ApiResponse::ok(crate::latency_tune::latency_tune_config()).into_response()
+}
+
+/// PUT /system/latency-tune
+async fn set_latency_tune(
+    State(state): State<Arc<CoreState>>,
+    Json(body): Json<crate::latency_tune::LatencyTuneConfig>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    state
+        .set_latency_tune_config(body)
+        .map_err(|e| ApiError::bad_request(e).into_response())?;
+    Ok(ApiResponse::ok(serde_json::json!({ "success": true })).into_response())
+}
+
+/// GET /system/ws-first
+async fn get_ws_first() -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    ApiResponse::ok(crate::ws_first::ws_first_config()).into_response()
+}
+
+/// PUT /system/ws-first
+async fn set_ws_first(
+    State(state): State<Arc<CoreState>>,
+    Json(body): Json<crate::ws_first::WsFirstConfig>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    state
+        .set_ws_first_config(body)
+        .map_err(|e| ApiError::bad_request(e).into_response())?;
+    Ok(ApiResponse::ok(serde_json::json!({ "success": true })).into_response())
+}
+
+/// GET /system/candles
+async fn get_candles_config() -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    ApiResponse::ok(crate::candles::candle_config()).into_response()
+}
+
+/// PUT /system/candles
+async fn set_candles_config(
+    State(state): State<Arc<CoreState>>,
+    Json(body): Json<crate::candles::CandleConfig>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    state
+        .set_candle_config(body)
+        .map_err(|e| ApiError::bad_request(e).into_response())?;
+    Ok(ApiResponse::ok(serde_json::json!({ "success": true })).into_response())
+}
+
+/// GET /system/telemetry
+async fn get_telemetry() -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    ApiResponse::ok(crate::telemetry::otlp_config()).into_response()
+}
+
+/// PUT /system/telemetry
+async fn set_telemetry(
+    State(state): State<Arc<CoreState>>,
+    Json(body): Json<crate::telemetry::OtlpConfig>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    state
+        .set_otlp_config(body)
+        .map_err(|e| ApiError::bad_request(e).into_response())?;
+    Ok(ApiResponse::ok(serde_json::json!({ "success": true })).into_response())
+}
+
+/// GET /system/cloud-backup
+async fn get_cloud_backup() -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    ApiResponse::ok(crate::cloud_backup::cloud_backup_config().redacted()).into_response()
+}
+
+/// PUT /system/cloud-backup
+async fn set_cloud_backup(
+    State(state): State<Arc<CoreState>>,
+    Json(body): Json<crate::cloud_backup::CloudBackupConfig>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    state
+        .set_cloud_backup_config(body)
+        .map_err(|e| ApiError::bad_request(e).into_response())?;
+    Ok(ApiResponse::ok(serde_json::json!({ "success": true })).into_response())
+}
+
+/// POST /system/cloud-backup/run
+async fn run_cloud_backup(State(state): State<Arc<CoreState>>) -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    match state.run_cloud_backup().await {
+        Ok(backup) => ApiResponse::ok(backup).into_response(),
+        Err(e) => ApiError::bad_request(e).into_response(),
+    }
+}
+
+/// GET /system/cloud-backups
+async fn list_cloud_backups(State(state): State<Arc<CoreState>>) -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    match state.list_cloud_backups().await {
+        Ok(backups) => ApiResponse::ok(backups).into_response(),
+        Err(e) => ApiError::bad_request(e).into_response(),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RestoreBackupBody {
+    key: String,
+}
+
+/// POST /system/cloud-backups/restore
+async fn restore_from_cloud(
+    State(state): State<Arc<CoreState>>,
+    Json(body): Json<RestoreBackupBody>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    state
+        .restore_from_cloud(&body.key)
+        .await
+        .map_err(|e| ApiError::bad_request(e).into_response())?;
+    Ok(ApiResponse::ok(serde_json::json!({ "success": true })).into_response())
+}
+
+/// GET /system/settings-sync
+async fn get_settings_sync() -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    ApiResponse::ok(serde_json::json!({
+        "config": crate::settings_sync::sync_config().redacted(),
+        "status": crate::settings_sync::sync_status(),
+    }))
+    .into_response()
+}
+
+/// PUT /system/settings-sync
+async fn set_settings_sync(
+    State(state): State<Arc<CoreState>>,
+    Json(body): Json<crate::settings_sync::SyncConfig>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    state
+        .set_sync_config(body)
+        .map_err(|e| ApiError::bad_request(e).into_response())?;
+    Ok(ApiResponse::ok(serde_json::json!({ "success": true })).into_response())
+}
+
+/// POST /system/settings-sync/run
+async fn run_settings_sync(State(state): State<Arc<CoreState>>) -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    match state.sync_settings_now().await {
+        Ok(action) => ApiResponse::ok(serde_json::json!({
+            "action": action,
+            "status": crate::settings_sync::sync_status(),
+        }))
+        .into_response(),
+        Err(e) => ApiError::bad_request(e).into_response(),
+    }
+}
+
+/// GET /system/kiosk
+async fn get_kiosk() -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    ApiResponse::ok(crate::kiosk::kiosk_state().info()).into_response()
+}
+
+/// PUT /system/kiosk — clients using the restricted token can never change it
+async fn set_kiosk(
+    State(state): State<Arc<CoreState>>,
+    headers: axum::http::HeaderMap,
+    Json(body): Json<SetKioskConfig>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    let current = crate::kiosk::kiosk_state();
+    let token = crate::api::kiosk_token(&headers);
+    if !current.token.is_empty() && token.as_deref() == Some(current.token.as_str()) {
+        return Err(ApiError::localized(
+            axum::http::StatusCode::FORBIDDEN,
+            LocalizedError::new(Msg::KioskLocked, &[]),
+        )
+        .into_response());
+    }
+    state
+        .set_kiosk_config(body.config, &body.current_pin)
+        .map_err(|e| ApiError::bad_request(e).into_response())?;
+    Ok(ApiResponse::ok(serde_json::json!({ "success": true })).into_response())
+}
+
+/// GET /system/lan-listener
+async fn get_lan_listener() -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    ApiResponse::ok(crate::lan_listener::lan_listener_info()).into_response()
+}
+
+/// PUT /system/lan-listener — rebinds the LAN listener; a request arriving over it may not get a response
+async fn set_lan_listener(
+    State(state): State<Arc<CoreState>>,
+    Json(body): Json<crate::lan_listener::LanListenerConfig>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    state
+        .set_lan_listener_config(body)
+        .map_err(|e| ApiError::bad_request(e).into_response())?;
+    crate::api::restart_lan_listener(state.clone());
+    Ok(ApiResponse::ok(serde_json::json!({ "success": true })).into_response())
+}
+
+/// GET /status
+async fn get_status(State(state): State<Arc<CoreState>>) -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    ApiResponse::ok(state.status().await).into_response()
+}
+
+/// GET /system/economic-calendar
+async fn get_economic_calendar() -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    ApiResponse::ok(crate::calendar::calendar_config()).into_response()
+}
+
+/// PUT /system/economic-calendar
+async fn set_economic_calendar(
+    State(state): State<Arc<CoreState>>,
+    Json(body): Json<crate::calendar::CalendarConfig>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    state
+        .set_calendar_config(body)
+        .await
+        .map_err(|e| ApiError::bad_request(e).into_response())?;
+    Ok(ApiResponse::ok(serde_json::json!({ "success": true })).into_response())
+}
+
+#[derive(Debug, Deserialize)]
+struct DeepLinkBody {
+    url: String,
+}
+
+/// POST /system/deep-link
+async fn open_deep_link(
+    State(state): State<Arc<CoreState>>,
+    Json(body): Json<DeepLinkBody>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    let outcome = crate::deep_link::parse(&body.url)
+        .and_then(|action| crate::deep_link::apply(&state, action))
+        .map_err(|e| ApiError::bad_request(e).into_response())?;
+    Ok(ApiResponse::ok(outcome).into_response())
+}
+
 // ─── Icon Handlers ──────────────────────────────────────────────────────────────
 
-/// POST /icons/:symbol — accepts raw bytes body, saves as icons/{symbol}.png
+/// POST /icons/:symbol — accepts raw bytes body, saves as icons/{symbol}.png (or .svg)
 async fn set_icon(
     State(state): State<Arc<CoreState>>,
     Path(symbol): Path<String>,
@@ -202,21 +936,38 @@ async fn set_icon(
         return Err(ApiError::bad_request("Request body is empty").into_response());
     }
 
-    let icon_name = symbol.to_lowercase();
-    let icons_dir = state.data_dir.join("icons");
-
-    tokio::fs::create_dir_all(&icons_dir)
-        .await
-        .map_err(|e| ApiError::internal(format!("Failed to create icons directory: {}", e)).into_response())?;
-
-    let dest = icons_dir.join(format!("{}.png", icon_name));
-    tokio::fs::write(&dest, &body)
+    let dest = crate::icons::save_icon_bytes(&state.db, &state.data_dir.join("icons"), &symbol, &body)
         .await
-        .map_err(|e| ApiError::internal(format!("Failed to write icon: {}", e)).into_response())?;
+        .map_err(|e| ApiError::bad_request(e).into_response())?;
 
     Ok(ApiResponse::ok(serde_json::json!({ "path": dest.to_string_lossy() })).into_response())
 }
 
+#[derive(Debug, Deserialize)]
+struct ImageInputBody {
+    #[serde(default)]
+    path: Option<String>,
+    #[serde(default)]
+    data: Option<String>,
+}
+
+/// POST /icons/:symbol/from-path — non-interactive icon setter (file path or base64 / data URL)
+async fn set_icon_from_path(
+    State(state): State<Arc<CoreState>>,
+    Path(symbol): Path<String>,
+    Json(body): Json<ImageInputBody>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    let (bytes, _) = crate::icons::read_image_input(body.path.as_deref(), body.data.as_deref())
+        .await
+        .map_err(|e| ApiError::bad_request(e).into_response())?;
+    let dest = crate::icons::save_icon_bytes(&state.db, &state.data_dir.join("icons"), &symbol, &bytes)
+        .await
+        .map_err(|e| ApiError::bad_request(e).into_response())?;
+    Ok(ApiResponse::ok(dest.to_string_lossy()).into_response())
+}
+
 /// DELETE /icons/:symbol — removes icons/{symbol}.png
 async fn remove_icon(
     State(state): State<Arc<CoreState>>,
@@ -224,14 +975,9 @@ async fn remove_icon(
 ) -> Result<axum::response::Response, axum::response::Response> {
     use axum::response::IntoResponse;
 
-    let icon_name = symbol.to_lowercase();
-    let dest = state.data_dir.join("icons").join(format!("{}.png", icon_name));
-
-    if dest.exists() {
-        tokio::fs::remove_file(&dest)
-            .await
-            .map_err(|e| ApiError::internal(format!("Failed to delete icon: {}", e)).into_response())?;
-    }
+    crate::icons::remove_icon_files(&state.db, &state.data_dir.join("icons"), &symbol)
+        .await
+        .map_err(|e| ApiError::internal(e).into_response())?;
 
     Ok(ApiResponse::ok(serde_json::json!({ "success": true })).into_response())
 }
@@ -252,12 +998,19 @@ async fn clear_all_icons_handler(
         .map_err(|e| ApiError::internal(format!("Failed to read icons directory: {}", e)).into_response())?;
     while let Ok(Some(entry)) = entries.next_entry().await {
         let path = entry.path();
-        if path.extension().map(|e| e == "png").unwrap_or(false)
-            && tokio::fs::remove_file(&path).await.is_ok()
+        let is_icon = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| crate::icons::ICON_EXTS.contains(&e));
+        if is_icon && tokio::fs::remove_file(&path).await.is_ok()
         {
             count += 1;
         }
     }
+    state
+        .db
+        .clear_icon_mappings()
+        .map_err(|e| ApiError::internal(e).into_response())?;
     Ok(ApiResponse::ok(serde_json::json!({ "deleted": count })).into_response())
 }
 
@@ -270,25 +1023,41 @@ async fn download_single_icon_handler(
     use axum::response::IntoResponse;
 
     let symbol = body.get("symbol").and_then(|v| v.as_str()).unwrap_or(&save_as);
-    let icons_dir = state.data_dir.join("icons");
-
-    tokio::fs::create_dir_all(&icons_dir)
-        .await
-        .map_err(|e| ApiError::internal(format!("Failed to create icons directory: {}", e)).into_response())?;
 
     let client = reqwest::Client::new();
     let bytes = crate::icons::try_download_png(&client, symbol, false)
         .await
-        .ok_or_else(|| ApiError::not_found(format!("Logo not found for symbol: {}", symbol)).into_response())?;
+        .ok_or_else(|| {
+            ApiError::localized(axum::http::StatusCode::NOT_FOUND, LocalizedError::new(Msg::LogoNotFound, &[&symbol])).into_response()
+        })?;
 
-    let dest = icons_dir.join(format!("{}.png", save_as.to_lowercase()));
-    tokio::fs::write(&dest, &bytes)
+    crate::icons::save_icon_bytes(&state.db, &state.data_dir.join("icons"), &save_as, &bytes)
         .await
-        .map_err(|e| ApiError::internal(format!("Failed to save icon: {}", e)).into_response())?;
+        .map_err(|e| ApiError::internal(e).into_response())?;
 
     Ok(ApiResponse::ok(serde_json::json!({ "success": true })).into_response())
 }
 
+#[derive(Debug, Deserialize)]
+struct FetchIconBody {
+    provider_id: String,
+}
+
+/// POST /icons/:symbol/fetch — pick a logo source for the symbol's asset type and save it
+async fn fetch_icon_handler(
+    State(state): State<Arc<CoreState>>,
+    Path(symbol): Path<String>,
+    Json(body): Json<FetchIconBody>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    let icons_dir = state.data_dir.join("icons");
+    let source = crate::icons::fetch_icon(&state.db, &icons_dir, &symbol, &body.provider_id)
+        .await
+        .map_err(|e| ApiError::not_found(e).into_response())?;
+    Ok(ApiResponse::ok(source).into_response())
+}
+
 /// GET /icons/search?symbol=BTC — search multiple CDN sources for icon
 async fn search_icons_handler(
     Query(params): Query<std::collections::HashMap<String, String>>,
@@ -324,20 +1093,14 @@ async fn save_icon_from_data_handler(
         return Err(ApiError::bad_request("Missing 'data_url' field").into_response());
     }
 
-    let icons_dir = state.data_dir.join("icons");
-    tokio::fs::create_dir_all(&icons_dir)
-        .await
-        .map_err(|e| ApiError::internal(format!("Failed to create icons directory: {}", e)).into_response())?;
-
     let b64_part = data_url.split(',').nth(1).unwrap_or("");
     let bytes = base64::engine::general_purpose::STANDARD
         .decode(b64_part)
         .map_err(|e| ApiError::bad_request(format!("Invalid base64: {}", e)).into_response())?;
 
-    let dest = icons_dir.join(format!("{}.png", save_as.to_lowercase()));
-    tokio::fs::write(&dest, &bytes)
+    crate::icons::save_icon_bytes(&state.db, &state.data_dir.join("icons"), &save_as, &bytes)
         .await
-        .map_err(|e| ApiError::internal(format!("Failed to save icon: {}", e)).into_response())?;
+        .map_err(|e| ApiError::bad_request(e).into_response())?;
 
     Ok(ApiResponse::ok(serde_json::json!({ "success": true })).into_response())
 }
@@ -351,6 +1114,18 @@ async fn get_icons_dir_path(
     ApiResponse::ok(dir.to_string_lossy().to_string()).into_response()
 }
 
+/// GET /icons/mappings — symbol → icon filename table
+async fn list_icon_mappings(
+    State(state): State<Arc<CoreState>>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    match state.db.list_icon_mappings() {
+        Ok(mappings) => Ok(ApiResponse::ok(mappings).into_response()),
+        Err(e) => Err(ApiError::internal(e).into_response()),
+    }
+}
+
 /// GET /icons — list all available icon filenames
 async fn list_icons(
     State(state): State<Arc<CoreState>>,
@@ -416,10 +1191,18 @@ async fn set_visible_subscriptions(
 
     let window_id = body.scope.unwrap_or_else(|| "web".to_string());
     let id_set: std::collections::HashSet<i64> = body.ids.into_iter().collect();
-    state.polling.set_visible(window_id, id_set).await;
+    state.set_visible_subscriptions(window_id, id_set, body.interval_ms).await;
     Ok(ApiResponse::ok(serde_json::json!({ "success": true })).into_response())
 }
 
+/// GET /system/polling-rates
+/// Requested and effective polling intervals per window/scope.
+async fn get_polling_rates(State(state): State<Arc<CoreState>>) -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    ApiResponse::ok(state.polling.window_rates().await).into_response()
+}
+
 /// GET /system/theme-bg/:theme_id
 /// Get the theme background file as a base64 data URL (or null if not set).
 async fn get_theme_bg(
@@ -452,6 +1235,24 @@ async fn get_theme_bg(
     Ok(ApiResponse::ok(serde_json::Value::Null).into_response())
 }
 
+/// PUT /system/theme-bg/:theme_id
+/// Set the theme background from a server-side file path or base64 data (`{path}` / `{data}`).
+async fn save_theme_bg(
+    State(state): State<Arc<CoreState>>,
+    Path(theme_id): Path<String>,
+    Json(body): Json<ImageInputBody>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    let (bytes, _) = crate::icons::read_image_input(body.path.as_deref(), body.data.as_deref())
+        .await
+        .map_err(|e| ApiError::bad_request(e).into_response())?;
+    let dest = crate::icons::save_theme_bg_bytes(&state.data_dir.join("theme_bg"), &theme_id, &bytes)
+        .await
+        .map_err(|e| ApiError::bad_request(e).into_response())?;
+    Ok(ApiResponse::ok(dest.to_string_lossy()).into_response())
+}
+
 /// DELETE /system/theme-bg/:theme_id
 /// Remove the theme background file.
 async fn remove_theme_bg(
@@ -470,32 +1271,17 @@ async fn remove_theme_bg(
 
 /// GET /system/read-file?path=...
 /// Read a local file and return its content as a base64 data URL.
+/// Only files under the icons / theme_bg data directories are readable.
 async fn read_file_base64(
+    State(state): State<Arc<CoreState>>,
     Query(query): Query<ReadFileQuery>,
 ) -> Result<axum::response::Response, axum::response::Response> {
     use axum::response::IntoResponse;
 
-    let path = &query.path;
-    let bytes = tokio::fs::read(path)
-        .await
-        .map_err(|e| ApiError::not_found(format!("Failed to read file: {}", e)).into_response())?;
-
-    use base64::Engine;
-    let b64 = base64::engine::general_purpose::STANDARD.encode(&bytes);
-    let mime = if path.ends_with(".png") {
-        "image/png"
-    } else if path.ends_with(".jpg") || path.ends_with(".jpeg") {
-        "image/jpeg"
-    } else if path.ends_with(".webp") {
-        "image/webp"
-    } else if path.ends_with(".svg") {
-        "image/svg+xml"
-    } else if path.ends_with(".gif") {
-        "image/gif"
-    } else {
-        "application/octet-stream"
-    };
-    let data_url = format!("data:{};base64,{}", mime, b64);
+    let data_url = state.file_access.read_data_url(&query.path).await.map_err(|e| match e {
+        FileAccessError::Denied(_) => ApiError::forbidden(e.to_string()).into_response(),
+        FileAccessError::Io(_) => ApiError::not_found(e.to_string()).into_response(),
+    })?;
     Ok(ApiResponse::ok(data_url).into_response())
 }
 
@@ -547,6 +1333,88 @@ async fn import_data(
     }
 }
 
+#[derive(Debug, Deserialize)]
+struct ExportConfigQuery {
+    #[serde(default)]
+    include_secrets: bool,
+}
+
+/// GET /data/config
+async fn export_app_config(
+    State(state): State<Arc<CoreState>>,
+    Query(query): Query<ExportConfigQuery>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    match state.db.export_app_config(query.include_secrets) {
+        Ok(config) => Ok(ApiResponse::ok(config).into_response()),
+        Err(e) => Err(ApiError::internal(e).into_response()),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ImportConfigQuery {
+    #[serde(default)]
+    mode: ConfigImportMode,
+}
+
+/// POST /data/config
+async fn import_app_config(
+    State(state): State<Arc<CoreState>>,
+    Query(query): Query<ImportConfigQuery>,
+    Json(config): Json<AppConfig>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    let summary = state
+        .import_app_config(&config, query.mode)
+        .await
+        .map_err(|e| match e {
+            DbError::Busy(_) => ApiError::internal(e).into_response(),
+            DbError::Other(message) => ApiError::bad_request(message).into_response(),
+        })?;
+    Ok(ApiResponse::ok(summary).into_response())
+}
+
+#[derive(Debug, Deserialize)]
+struct ExportFileBody {
+    dataset: ExportDataset,
+    #[serde(default)]
+    format: ExportFormat,
+    #[serde(default)]
+    filename: Option<String>,
+}
+
+/// POST /data/export-file — returns the rendered file as an attachment
+async fn export_file(
+    State(state): State<Arc<CoreState>>,
+    Json(body): Json<ExportFileBody>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::http::header;
+    use axum::response::IntoResponse;
+
+    let bytes = file_export::render(&state.db, &body.dataset, body.format)
+        .map_err(|e| ApiError::internal(e).into_response())?;
+    let filename = body
+        .filename
+        .filter(|f| !f.is_empty())
+        .unwrap_or_else(|| format!("stockenboard-export.{}", body.format.extension()))
+        .chars()
+        .map(|c| if c.is_ascii_graphic() && c != '"' && c != '\\' { c } else { '_' })
+        .collect::<String>();
+    Ok((
+        [
+            (header::CONTENT_TYPE, body.format.mime_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        bytes,
+    )
+        .into_response())
+}
+
 // ─── DEX Handler ────────────────────────────────────────────────────────────────
 
 /// GET /dex/pool/:provider/:address — lookup a DEX pool
@@ -560,8 +1428,9 @@ async fn lookup_dex_pool(
     let settings = state.db.get_provider_settings(&provider_id).ok().flatten();
     let api_key = settings.as_ref().and_then(|s| s.api_key.clone());
     let api_url = settings.as_ref().and_then(|s| s.api_url.clone());
+    let http = settings.as_ref().map(HttpOptions::from).unwrap_or_default();
 
-    let lookup = create_dex_lookup(&provider_id, api_key, api_url)
+    let lookup = create_dex_lookup(&provider_id, api_key, api_url, &http)
         .ok_or_else(|| {
             ApiError::bad_request(format!("Provider '{}' does not support pool lookup", provider_id))
                 .into_response()
@@ -572,3 +1441,30 @@ async fn lookup_dex_pool(
         Err(e) => Err(ApiError::internal(e).into_response()),
     }
 }
+
+#[derive(Debug, Deserialize)]
+struct CompareDexQuotesBody {
+    chain: String,
+    token_in: String,
+    token_out: String,
+    amount: f64,
+}
+
+/// POST /dex/quotes — compare a swap quote across DEX aggregators
+async fn compare_dex_quotes(
+    State(state): State<Arc<CoreState>>,
+    Json(body): Json<CompareDexQuotesBody>,
+) -> Result<axum::response::Response, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    crate::providers::dex_quotes::compare_dex_quotes(
+        &state.db,
+        &body.chain,
+        &body.token_in,
+        &body.token_out,
+        body.amount,
+    )
+    .await
+    .map(|result| ApiResponse::ok(result).into_response())
+    .map_err(|e| ApiError::bad_request(e).into_response())
+}
@@ -16,7 +16,6 @@ use axum::{
     routing::{delete, get},
     Json, Router,
 };
-use serde::Deserialize;
 
 use crate::core_state::CoreState;
 use crate::db::ViewSubCount;
@@ -25,28 +24,7 @@ use super::{ApiError, ApiResponse};
 
 // ─── Query / Body types ─────────────────────────────────────────────────────────
 
-#[derive(Debug, Deserialize)]
-pub struct ListViewsQuery {
-    #[serde(rename = "type", default)]
-    pub view_type: String,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct CreateViewBody {
-    pub name: String,
-    #[serde(rename = "type", default)]
-    pub view_type: String,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct RenameViewBody {
-    pub name: String,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct AddSubBody {
-    pub subscription_id: i64,
-}
+pub use stockenboard_api_types::views::{AddSubBody, CreateViewBody, ListViewsQuery, RenameViewBody};
 
 // ─── Router ─────────────────────────────────────────────────────────────────────
 
@@ -2,10 +2,15 @@
 //!
 //! Upgrades HTTP connections to WebSocket and:
 //! - Subscribes to `CoreState.event_bus` and forwards all `AppEvent` variants as JSON
-//! - Handles incoming `start_ws_stream` / `stop_ws_stream` commands for provider WS streams
-//! - Cleans up resources (subscriptions, WS tasks) on client disconnect
+//! - Forwards every ticker from the shared `WsManager` as `ws-ticker-update`
+//! - Handles incoming `start_ws_stream` / `stop_ws_stream` commands; each client is a separate
+//!   `WsManager` owner, so streams requested by other clients or the desktop app are unaffected
+//! - Releases the client's stream requests on disconnect
+//!
+//! REST stream control (`/ws/stream/start`, `/ws/stream/stop`) uses the shared `api` owner,
+//! and `GET /ws/streams` lists the connection status of every provider stream.
 
-use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
 use axum::{
@@ -24,7 +29,13 @@ use tokio::sync::broadcast;
 
 use crate::core_state::CoreState;
 use crate::events::AppEvent;
-use crate::providers::{create_ws_provider, WsTickerUpdate};
+use crate::providers::WsTickerUpdate;
+
+/// `WsManager` owner for streams started through the REST endpoints.
+const REST_OWNER: &str = "api";
+
+/// Sequence for per-connection `WsManager` owners (`ws-client-{n}`).
+static NEXT_CLIENT: AtomicU64 = AtomicU64::new(1);
 
 // ─── WsMessage Envelope ─────────────────────────────────────────────────────────
 
@@ -51,7 +62,7 @@ impl WsMessage {
             AppEvent::PriceUpdate {
                 provider_id: _,
                 data,
-                record_symbols: _,
+                record_targets: _,
             } => WsMessage::new(
                 "price-update",
                 serde_json::to_value(data).unwrap_or_default(),
@@ -95,6 +106,46 @@ impl WsMessage {
                 "logo-download-progress",
                 serde_json::to_value(progress).unwrap_or_default(),
             ),
+            AppEvent::PowerMode(payload) => WsMessage::new(
+                "power-mode",
+                serde_json::to_value(payload).unwrap_or_default(),
+            ),
+            AppEvent::SystemResumed { slept_secs } => WsMessage::new(
+                "system-resumed",
+                serde_json::json!({ "slept_secs": slept_secs }),
+            ),
+            AppEvent::GasUpdate(price) => WsMessage::new(
+                "gas-update",
+                serde_json::to_value(price).unwrap_or_default(),
+            ),
+            AppEvent::DepegUpdate(statuses) => WsMessage::new(
+                "depeg-update",
+                serde_json::to_value(statuses).unwrap_or_default(),
+            ),
+            AppEvent::CalendarUpdate(events) => WsMessage::new(
+                "calendar-update",
+                serde_json::to_value(events).unwrap_or_default(),
+            ),
+            AppEvent::PriceAnomaly(anomaly) => WsMessage::new(
+                "price-anomaly",
+                serde_json::to_value(anomaly).unwrap_or_default(),
+            ),
+            AppEvent::ProviderRecovered(recovered) => WsMessage::new(
+                "provider-recovered",
+                serde_json::to_value(recovered).unwrap_or_default(),
+            ),
+            AppEvent::RecordError(error) => WsMessage::new(
+                "record-error",
+                serde_json::to_value(error).unwrap_or_default(),
+            ),
+            AppEvent::CandleClose(candle) => WsMessage::new(
+                "candle-close",
+                serde_json::to_value(candle).unwrap_or_default(),
+            ),
+            AppEvent::WsStatus(status) => WsMessage::new(
+                "ws-status",
+                serde_json::to_value(status).unwrap_or_default(),
+            ),
         }
     }
 
@@ -125,6 +176,7 @@ pub fn router() -> Router<Arc<CoreState>> {
         .route("/ws", get(ws_handler))
         .route("/ws/stream/start", axum::routing::post(start_ws_stream))
         .route("/ws/stream/stop", axum::routing::post(stop_ws_stream))
+        .route("/ws/streams", get(list_ws_streams))
 }
 
 // ─── Handler ────────────────────────────────────────────────────────────────────
@@ -143,66 +195,43 @@ async fn ws_handler(
 /// 1. **send_task** — forwards `AppEvent` and `WsTickerUpdate` messages to the client
 /// 2. **recv_task** — processes incoming commands (`start_ws_stream`, `stop_ws_stream`)
 ///
-/// On disconnect, both tasks are aborted and WS provider streams are cleaned up.
+/// On disconnect, both tasks are aborted and the client's stream requests are removed.
 async fn handle_ws_connection(socket: WebSocket, state: Arc<CoreState>) {
     let (mut sender, mut receiver) = socket.split();
+    let owner = format!("ws-client-{}", NEXT_CLIENT.fetch_add(1, Ordering::Relaxed));
 
-    // Subscribe to the shared event bus
+    // Subscribe to the shared event bus and ticker stream
     let mut event_rx = state.event_bus.subscribe();
-
-    // Channel for WS ticker updates from provider streams
-    let (ws_ticker_tx, ws_ticker_rx) =
-        broadcast::channel::<WsTickerUpdate>(256);
-
-    // Track active WS stream tasks for cleanup
-    let ws_tasks: Arc<tokio::sync::Mutex<HashMap<String, tokio::task::JoinHandle<()>>>> =
-        Arc::new(tokio::sync::Mutex::new(HashMap::new()));
+    let mut ws_ticker_rx = state.ws.subscribe();
 
     // ─── Send task: forward event bus + WS ticker events to client ───────────────
     let send_task = tokio::spawn(async move {
-        let mut ws_ticker_sub = ws_ticker_rx.resubscribe();
         loop {
-            tokio::select! {
-                result = event_rx.recv() => {
-                    match result {
-                        Ok(event) => {
-                            let msg = WsMessage::from_app_event(&event);
-                            let text = match serde_json::to_string(&msg) {
-                                Ok(t) => t,
-                                Err(_) => continue,
-                            };
-                            if sender.send(Message::Text(text)).await.is_err() {
-                                break;
-                            }
-                        }
-                        Err(broadcast::error::RecvError::Lagged(_)) => continue,
-                        Err(broadcast::error::RecvError::Closed) => break,
-                    }
-                }
-                result = ws_ticker_sub.recv() => {
-                    match result {
-                        Ok(update) => {
-                            let msg = WsMessage::from_ws_ticker(&update);
-                            let text = match serde_json::to_string(&msg) {
-                                Ok(t) => t,
-                                Err(_) => continue,
-                            };
-                            if sender.send(Message::Text(text)).await.is_err() {
-                                break;
-                            }
-                        }
-                        Err(broadcast::error::RecvError::Lagged(_)) => continue,
-                        Err(broadcast::error::RecvError::Closed) => break,
-                    }
-                }
+            let msg = tokio::select! {
+                result = event_rx.recv() => match result {
+                    Ok(event) => WsMessage::from_app_event(&event),
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                result = ws_ticker_rx.recv() => match result {
+                    Ok(update) => WsMessage::from_ws_ticker(&update),
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+            };
+            let text = match serde_json::to_string(&msg) {
+                Ok(t) => t,
+                Err(_) => continue,
+            };
+            if sender.send(Message::Text(text)).await.is_err() {
+                break;
             }
         }
     });
 
     // ─── Receive task: handle incoming commands ─────────────────────────────────
-    let ws_tasks_clone = ws_tasks.clone();
-    let ws_ticker_tx_clone = ws_ticker_tx.clone();
-
+    let state_for_recv = state.clone();
+    let owner_for_recv = owner.clone();
     let recv_task = tokio::spawn(async move {
         while let Some(Ok(msg)) = receiver.next().await {
             match msg {
@@ -211,7 +240,7 @@ async fn handle_ws_connection(socket: WebSocket, state: Arc<CoreState>) {
                         Ok(c) => c,
                         Err(_) => continue,
                     };
-                    handle_command(cmd, &ws_tasks_clone, &ws_ticker_tx_clone).await;
+                    handle_command(cmd, &state_for_recv, &owner_for_recv);
                 }
                 Message::Close(_) => break,
                 _ => {}
@@ -225,61 +254,29 @@ async fn handle_ws_connection(socket: WebSocket, state: Arc<CoreState>) {
         _ = recv_task => {}
     }
 
-    // ─── Cleanup: abort all active WS provider streams ──────────────────────────
-    let mut tasks = ws_tasks.lock().await;
-    for (_, handle) in tasks.drain() {
-        handle.abort();
-    }
+    // ─── Cleanup: release this client's stream requests ─────────────────────────
+    state.ws.remove_owner(&owner);
 }
 
 /// Handle an incoming WebSocket command from the client.
-async fn handle_command(
-    cmd: WsCommand,
-    ws_tasks: &Arc<tokio::sync::Mutex<HashMap<String, tokio::task::JoinHandle<()>>>>,
-    ws_ticker_tx: &broadcast::Sender<WsTickerUpdate>,
-) {
-    match cmd.command.as_str() {
+fn handle_command(cmd: WsCommand, state: &CoreState, owner: &str) {
+    let provider_id = match cmd.provider_id {
+        Some(id) => id,
+        None => return,
+    };
+    let result = match cmd.command.as_str() {
         "start_ws_stream" => {
-            let provider_id = match cmd.provider_id {
-                Some(id) => id,
-                None => return,
-            };
             let symbols = cmd.symbols.unwrap_or_default();
             if symbols.is_empty() {
                 return;
             }
-
-            // Stop existing stream for this provider if any
-            {
-                let mut tasks = ws_tasks.lock().await;
-                if let Some(handle) = tasks.remove(&provider_id) {
-                    handle.abort();
-                }
-            }
-
-            // Create WS provider and start streaming
-            let ws_provider = match create_ws_provider(&provider_id) {
-                Some(p) => p,
-                None => return,
-            };
-
-            let sender = Arc::new(ws_ticker_tx.clone());
-            if let Ok(handle) = ws_provider.subscribe(symbols, sender).await {
-                let mut tasks = ws_tasks.lock().await;
-                tasks.insert(provider_id, handle);
-            }
-        }
-        "stop_ws_stream" => {
-            let provider_id = match cmd.provider_id {
-                Some(id) => id,
-                None => return,
-            };
-            let mut tasks = ws_tasks.lock().await;
-            if let Some(handle) = tasks.remove(&provider_id) {
-                handle.abort();
-            }
+            state.ws.set_symbols(owner, &provider_id, symbols)
         }
-        _ => {}
+        "stop_ws_stream" => state.ws.set_symbols(owner, &provider_id, Vec::new()),
+        _ => return,
+    };
+    if let Err(e) = result {
+        tracing::debug!("[API WS] {} command rejected: {}", cmd.command, e);
     }
 }
 
@@ -293,13 +290,11 @@ struct WsStreamRequest {
     symbols: Option<Vec<String>>,
 }
 
-/// POST /ws/stream/start — start a WebSocket stream for a provider.
+/// POST /ws/stream/start — start (or replace the symbols of) a WebSocket stream for a provider.
 ///
-/// In server mode, WebSocket streams are managed per-connection via the `/api/ws` endpoint.
-/// This REST endpoint is provided for API compatibility. It returns success to acknowledge
-/// the request — the actual streaming happens through the WebSocket connection.
+/// Ticks are delivered to every `/api/ws` client as `ws-ticker-update` messages.
 async fn start_ws_stream(
-    State(_state): State<Arc<CoreState>>,
+    State(state): State<Arc<CoreState>>,
     axum::extract::Json(body): axum::extract::Json<WsStreamRequest>,
 ) -> axum::response::Response {
     use axum::response::IntoResponse;
@@ -310,36 +305,29 @@ async fn start_ws_stream(
         return ApiError::bad_request("symbols must not be empty").into_response();
     }
 
-    // Validate provider supports WS
-    if create_ws_provider(&body.provider_id).is_none() {
-        return ApiError::bad_request(format!(
-            "Provider '{}' does not support WebSocket streaming",
-            body.provider_id
-        ))
-        .into_response();
+    match state.ws.set_symbols(REST_OWNER, &body.provider_id, symbols) {
+        Ok(()) => ApiResponse::ok(serde_json::json!({ "success": true })).into_response(),
+        // unsupported provider, or more streams than one connection allows
+        Err(e) => ApiError::bad_request(e).into_response(),
     }
-
-    // In server mode, WS streams are managed per WebSocket connection.
-    // Return success — the client should send start_ws_stream via the WS connection.
-    ApiResponse::ok(serde_json::json!({
-        "success": true,
-        "message": "Send start_ws_stream command via WebSocket connection for real-time data"
-    })).into_response()
 }
 
-/// POST /ws/stream/stop — stop a WebSocket stream for a provider.
+/// POST /ws/stream/stop — stop a WebSocket stream started through `/ws/stream/start`.
 async fn stop_ws_stream(
-    State(_state): State<Arc<CoreState>>,
+    State(state): State<Arc<CoreState>>,
     axum::extract::Json(body): axum::extract::Json<WsStreamRequest>,
 ) -> axum::response::Response {
     use axum::response::IntoResponse;
     use crate::api::ApiResponse;
 
-    // In server mode, WS streams are managed per WebSocket connection.
-    // Return success — the client should send stop_ws_stream via the WS connection.
-    let _ = &body.provider_id;
-    ApiResponse::ok(serde_json::json!({
-        "success": true,
-        "message": "Send stop_ws_stream command via WebSocket connection"
-    })).into_response()
+    let _ = state.ws.set_symbols(REST_OWNER, &body.provider_id, Vec::new());
+    ApiResponse::ok(serde_json::json!({ "success": true })).into_response()
+}
+
+/// GET /ws/streams — connection status of every provider WebSocket stream.
+async fn list_ws_streams(State(state): State<Arc<CoreState>>) -> axum::response::Response {
+    use axum::response::IntoResponse;
+    use crate::api::ApiResponse;
+
+    ApiResponse::ok(state.ws.statuses()).into_response()
 }
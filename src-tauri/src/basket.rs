@@ -0,0 +1,307 @@
+//! 指數 / 籃子訂閱 — 以其他訂閱加權組成的合成訂閱（例如 50% BTC、30% ETH、20% SOL）。
+//!
+//! 籃子是 `selected_provider_id = "basket"` 的 asset 訂閱，成分存於該訂閱的 `provider_params`：
+//! `{"components": [{"subscription_id": 1, "weight": 50, "base_price": 65000}, ...]}`。
+//! 籃子價格是以建立時成分價格為基準（100）的加權指數 `100 × Σ wᵢ × pᵢ / baseᵢ`（權重正規化為總和 1），
+//! 因此權重即建立當下的資金配置比例。
+//!
+//! 籃子不經 provider 取價：polling 會一併輪詢可見籃子的成分，成分更新時重新計算並寫入快取、
+//! 送出 `provider_id = "basket"` 的 PriceUpdate，因此籃子可像一般 symbol 一樣紀錄歷史與設定通知。
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core_state::CoreState;
+use crate::db::RecordTarget;
+use crate::i18n::{tr, Msg};
+use crate::providers::registry::PriceRequest;
+use crate::providers::types::{AssetData, AssetDataBuilder};
+
+pub const PROVIDER_ID: &str = "basket";
+/// 建立時的指數值
+const BASE_VALUE: f64 = 100.0;
+
+/// 建立籃子時指定的成分與權重（權重為相對值，不需加總為 100）
+pub use stockenboard_api_types::subscriptions::BasketWeight;
+
+/// 存於 `provider_params` 的成分定義；`base_price` 為建立時的成分價格
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BasketComponent {
+    pub subscription_id: i64,
+    pub weight: f64,
+    pub base_price: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BasketParams {
+    components: Vec<BasketComponent>,
+}
+
+/// 籃子 `provider_params` 中引用的成分訂閱 ID；格式錯誤時為空
+pub fn component_ids(provider_params: Option<&str>) -> Vec<i64> {
+    serde_json::from_str::<BasketParams>(provider_params.unwrap_or_default())
+        .map(|p| p.components.iter().map(|c| c.subscription_id).collect())
+        .unwrap_or_default()
+}
+
+/// 成分已對應到 polling 快取 key（`provider:symbol`）的籃子
+#[derive(Debug, Clone, PartialEq)]
+pub struct Basket {
+    pub symbol: String,
+    /// 紀錄目標（訂閱開啟紀錄且未暫停時才有）
+    pub record: Option<RecordTarget>,
+    pub components: Vec<ResolvedComponent>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedComponent {
+    pub provider_id: String,
+    pub symbol: String,
+    pub weight: f64,
+    pub base_price: f64,
+}
+
+impl ResolvedComponent {
+    pub fn cache_key(&self) -> String {
+        format!("{}:{}", self.provider_id, self.symbol)
+    }
+}
+
+impl Basket {
+    /// 由籃子訂閱的 `provider_params` 與 polling 訂閱清單（id, symbol, provider, record）解析成分
+    pub fn resolve(
+        symbol: &str,
+        record: Option<RecordTarget>,
+        provider_params: Option<&str>,
+        subs: &[(i64, String, String, bool)],
+    ) -> Result<Self, String> {
+        let params: BasketParams = serde_json::from_str(provider_params.unwrap_or_default())
+            .map_err(|e| format!("Invalid basket components: {}", e))?;
+        if params.components.is_empty() {
+            return Err("Basket has no components".to_string());
+        }
+        let components = params
+            .components
+            .iter()
+            .map(|c| {
+                let (_, sub_symbol, provider_id, _) = subs
+                    .iter()
+                    .find(|(id, _, _, _)| *id == c.subscription_id)
+                    .ok_or_else(|| format!("Component subscription {} not found", c.subscription_id))?;
+                if provider_id == PROVIDER_ID {
+                    return Err("A basket cannot contain another basket".to_string());
+                }
+                Ok(ResolvedComponent {
+                    provider_id: provider_id.clone(),
+                    symbol: sub_symbol.clone(),
+                    weight: c.weight,
+                    base_price: c.base_price,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Self {
+            symbol: symbol.to_string(),
+            record,
+            components,
+        })
+    }
+
+    pub fn cache_key(&self) -> String {
+        format!("{}:{}", PROVIDER_ID, self.symbol)
+    }
+
+    /// 是否有成分由此 provider 取價
+    pub fn depends_on(&self, provider_id: &str) -> bool {
+        self.components.iter().any(|c| c.provider_id == provider_id)
+    }
+
+    /// 以快取中的成分價格計算指數；任一成分尚無有效價格時回傳 None
+    ///
+    /// 所有成分都有 24h 漲跌幅時，以各成分 24h 前的價格推算籃子的 24h 變化。
+    pub fn compute(&self, cache: &HashMap<String, AssetData>) -> Option<AssetData> {
+        let total_weight: f64 = self.components.iter().map(|c| c.weight).sum();
+        let mut value = 0.0;
+        let mut previous = Some(0.0);
+        let mut last_updated = 0;
+        for component in &self.components {
+            let data = cache.get(&component.cache_key())?;
+            if !data.price.is_finite() || data.price <= 0.0 {
+                return None;
+            }
+            let share = BASE_VALUE * component.weight / total_weight / component.base_price;
+            value += share * data.price;
+            previous = previous
+                .zip(data.change_percent_24h)
+                .map(|(p, pct)| p + share * data.price / (1.0 + pct / 100.0));
+            last_updated = last_updated.max(data.last_updated);
+        }
+        let previous = previous.filter(|p| *p > 0.0);
+        let mut data = AssetDataBuilder::new(&self.symbol, PROVIDER_ID)
+            .price(value)
+            .change_24h(previous.map(|p| value - p))
+            .change_percent_24h(previous.map(|p| (value / p - 1.0) * 100.0))
+            .build();
+        data.last_updated = last_updated;
+        Some(data)
+    }
+}
+
+/// 建立籃子訂閱，回傳新 ID；以目前快取（沒有時即時查詢）的成分價格作為指數基準
+pub async fn create(
+    state: &CoreState,
+    symbol: &str,
+    display_name: Option<&str>,
+    weights: &[BasketWeight],
+) -> Result<i64, String> {
+    let symbol = symbol.trim().to_uppercase();
+    if symbol.is_empty() {
+        return Err("Basket symbol is required".to_string());
+    }
+    if weights.is_empty() {
+        return Err("A basket needs at least one component".to_string());
+    }
+    for (i, w) in weights.iter().enumerate() {
+        if !w.weight.is_finite() || w.weight <= 0.0 {
+            return Err("Component weights must be positive numbers".to_string());
+        }
+        if weights[..i].iter().any(|o| o.subscription_id == w.subscription_id) {
+            return Err(format!("Duplicate basket component: subscription {}", w.subscription_id));
+        }
+    }
+
+    let subs = state.db.read_polling_subscriptions(None)?;
+    let mut components = Vec::with_capacity(weights.len());
+    for w in weights {
+        let (_, sub_symbol, provider_id, _) = subs
+            .iter()
+            .find(|(id, _, _, _)| *id == w.subscription_id)
+            .ok_or_else(|| tr(Msg::SubscriptionNotFound, &[&w.subscription_id]))?;
+        if provider_id == PROVIDER_ID {
+            return Err("A basket cannot contain another basket".to_string());
+        }
+        components.push((w, provider_id.clone(), sub_symbol.clone()));
+    }
+
+    // 基準價格：優先使用 polling 快取，缺少的成分依 provider 分組即時查詢
+    let valid = |d: &AssetData| d.price.is_finite() && d.price > 0.0;
+    let mut prices: HashMap<String, f64> = {
+        let cache = state.polling.cache.read().await;
+        components
+            .iter()
+            .filter_map(|(_, pid, sym)| {
+                let key = format!("{}:{}", pid, sym);
+                cache.get(&key).filter(|d| valid(d)).map(|d| (key, d.price))
+            })
+            .collect()
+    };
+    let mut requests: Vec<PriceRequest> = Vec::new();
+    for (_, pid, sym) in &components {
+        if prices.contains_key(&format!("{}:{}", pid, sym)) {
+            continue;
+        }
+        match requests.iter_mut().find(|r| &r.provider_id == pid) {
+            Some(req) => req.symbols.push(sym.clone()),
+            None => requests.push(PriceRequest {
+                provider_id: pid.clone(),
+                symbols: vec![sym.clone()],
+            }),
+        }
+    }
+    for result in state.registry.fetch_multi(&requests, &state.db).await {
+        for d in result.data.iter().filter(|d| valid(d)) {
+            prices.insert(format!("{}:{}", result.provider_id, d.symbol), d.price);
+        }
+    }
+
+    let components = components
+        .iter()
+        .map(|(w, pid, sym)| {
+            let base_price = *prices
+                .get(&format!("{}:{}", pid, sym))
+                .ok_or_else(|| format!("No price available for {} ({})", sym, pid))?;
+            Ok(BasketComponent {
+                subscription_id: w.subscription_id,
+                weight: w.weight,
+                base_price,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let all_stock = state
+        .db
+        .list_all_subscriptions()?
+        .iter()
+        .filter(|s| weights.iter().any(|w| w.subscription_id == s.id))
+        .all(|s| s.asset_type == "stock");
+    let params = serde_json::to_string(&BasketParams { components }).map_err(|e| e.to_string())?;
+    let id = state.db.add_subscription(
+        "asset",
+        &symbol,
+        display_name,
+        PROVIDER_ID,
+        if all_stock { "stock" } else { "crypto" },
+        None,
+        None,
+        None,
+    )?;
+    state.db.set_provider_params(id, Some(&params))?;
+    state.polling.reload();
+    Ok(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(provider_id: &str, symbol: &str, price: f64, change_pct: Option<f64>) -> (String, AssetData) {
+        let data = AssetDataBuilder::new(symbol, provider_id)
+            .price(price)
+            .change_percent_24h(change_pct)
+            .build();
+        (format!("{}:{}", provider_id, symbol), data)
+    }
+
+    fn subs() -> Vec<(i64, String, String, bool)> {
+        vec![
+            (1, "BTCUSDT".to_string(), "binance".to_string(), false),
+            (2, "ETHUSDT".to_string(), "binance".to_string(), false),
+            (3, "MYIDX".to_string(), PROVIDER_ID.to_string(), true),
+        ]
+    }
+
+    #[test]
+    fn index_tracks_weighted_component_returns() {
+        let params = r#"{"components": [
+            {"subscription_id": 1, "weight": 60, "base_price": 50000},
+            {"subscription_id": 2, "weight": 40, "base_price": 2000}
+        ]}"#;
+        let basket = Basket::resolve("MYIDX", None, Some(params), &subs()).unwrap();
+        assert_eq!(basket.cache_key(), "basket:MYIDX");
+        assert!(basket.depends_on("binance"));
+
+        // BTC +10%、ETH -5%（自建立時起）→ 100 × (0.6 × 1.1 + 0.4 × 0.95) = 104
+        let mut cache: HashMap<String, AssetData> =
+            [asset("binance", "BTCUSDT", 55000.0, Some(10.0)), asset("binance", "ETHUSDT", 1900.0, Some(-5.0))]
+                .into_iter()
+                .collect();
+        let data = basket.compute(&cache).unwrap();
+        assert!((data.price - 104.0).abs() < 1e-9);
+        assert_eq!(data.provider_id, PROVIDER_ID);
+        // 24h 前兩者皆為基準價 → 100
+        assert!((data.change_24h.unwrap() - 4.0).abs() < 1e-9);
+        assert!((data.change_percent_24h.unwrap() - 4.0).abs() < 1e-9);
+
+        cache.remove("binance:ETHUSDT");
+        assert!(basket.compute(&cache).is_none());
+    }
+
+    #[test]
+    fn resolve_rejects_missing_and_nested_components() {
+        let params = |id: i64| format!(r#"{{"components": [{{"subscription_id": {}, "weight": 1, "base_price": 1}}]}}"#, id);
+        assert!(Basket::resolve("X", None, Some(&params(9)), &subs()).is_err());
+        assert!(Basket::resolve("X", None, Some(&params(3)), &subs()).is_err());
+        assert!(Basket::resolve("X", None, Some(r#"{"components": []}"#), &subs()).is_err());
+        assert!(Basket::resolve("X", None, None, &subs()).is_err());
+    }
+}
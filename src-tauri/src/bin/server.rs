@@ -5,12 +5,14 @@
 //! - `SB_PORT`       — HTTP server port (default: `8080`)
 //! - `SB_DATA_DIR`   — Path to persistent data directory (default: `./data`)
 //! - `SB_STATIC_DIR` — Path to built SPA static files (default: `./static`)
+//! - `SB_SECRETS_PASSPHRASE` — Passphrase for encrypting provider API keys when no OS keychain is available
 
 use std::sync::Arc;
 
 use stockenboard_lib::config::ServerConfig;
 use stockenboard_lib::core_state::CoreState;
 use stockenboard_lib::api;
+use stockenboard_lib::logging;
 
 #[tokio::main]
 async fn main() {
@@ -24,6 +26,7 @@ async fn main() {
     let data_dir = config.data_dir;
 
     // ─── Create data directory if it does not exist ─────────────────────────────
+    // (logging is not initialized yet, so startup failures go straight to stderr)
     if let Err(e) = std::fs::create_dir_all(&data_dir) {
         eprintln!(
             "[Server] Failed to create data directory '{}': {}",
@@ -33,9 +36,11 @@ async fn main() {
         std::process::exit(1);
     }
 
+    logging::init(&data_dir.join("logs"));
+
     // ─── Initialize shared core state ───────────────────────────────────────────
     let state = CoreState::new(&data_dir).unwrap_or_else(|e| {
-        eprintln!("[Server] Failed to initialize core state: {}", e);
+        tracing::error!("[Server] Failed to initialize core state: {}", e);
         std::process::exit(1);
     });
 
@@ -43,8 +48,7 @@ async fn main() {
     state.start_background_tasks().await;
 
     // Auto-set unattended based on active recordings at startup
-    let active_count = state.db.count_active_recordings().unwrap_or(0);
-    if active_count > 0 {
+    if state.recordings_need_unattended() {
         state.polling.set_unattended(true).await;
     }
 
@@ -59,21 +63,28 @@ async fn main() {
     let static_dir = std::env::var("SB_STATIC_DIR")
         .map(std::path::PathBuf::from)
         .unwrap_or_else(|_| std::path::PathBuf::from("./static"));
-    let app = api::build_router_with_static(Arc::new(state), &static_dir);
+    let state = Arc::new(state);
+    stockenboard_lib::settings_sync::start(state.clone());
+    let app = api::build_router_with_static(state.clone(), &static_dir);
 
     // ─── Bind TCP listener and start serving ────────────────────────────────────
     let addr = format!("{}:{}", bind, port);
     let listener = tokio::net::TcpListener::bind(&addr).await.unwrap_or_else(|e| {
-        eprintln!("[Server] Failed to bind to {}: {}", addr, e);
+        tracing::error!("[Server] Failed to bind to {}: {}", addr, e);
         std::process::exit(1);
     });
 
-    println!("[Server] Listening on http://{}:{}", bind, port);
+    tracing::info!("[Server] Listening on http://{}:{}", bind, port);
+
+    // Optional token-protected LAN listener (settings `lan_api_*`)
+    api::restart_lan_listener(state.clone());
 
     axum::serve(listener, app.into_make_service())
         .with_graceful_shutdown(shutdown_signal())
         .await
         .expect("[Server] Unexpected server error");
+
+    state.save_price_snapshot().await;
 }
 
 /// Waits for a shutdown signal (Ctrl+C / SIGTERM).
@@ -97,5 +108,5 @@ async fn shutdown_signal() {
         let _ = ctrl_c.await;
     }
 
-    println!("[Server] Shutdown signal received, stopping gracefully...");
+    tracing::info!("[Server] Shutdown signal received, stopping gracefully...");
 }
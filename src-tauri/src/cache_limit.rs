@@ -0,0 +1,220 @@
+//! 價格快取的容量上限 — 長時間無人值守時，`PollingManager.cache` 只在 reload 時依訂閱清理，
+//! 訂閱大量 symbol（或籃子、還原的快照）時會持續佔用記憶體。
+//!
+//! 快取超過 `max_entries` 時淘汰最久未寫入的項目（LRU，以寫入順序計）；被淘汰的 symbol 若仍在輪詢，
+//! 下一次取價會重新寫入。`ticks` 以相同上限依 `fetched_at` 淘汰最舊的 provider。
+//! `/api/status` 與 `get_cache_status` 回報目前項目數與估算的記憶體用量。
+//!
+//! 設定存於 app settings（`polling_cache_max_entries`）。
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{LazyLock, RwLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::providers::AssetData;
+
+/// 可設定的最小 / 最大上限
+pub const MIN_ENTRIES: usize = 100;
+pub const MAX_ENTRIES: usize = 1_000_000;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CacheConfig {
+    /// 價格快取最多保留的 `provider:symbol` 項目數
+    pub max_entries: usize,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self { max_entries: 5_000 }
+    }
+}
+
+impl CacheConfig {
+    /// 檢查設定
+    pub fn normalized(self) -> Result<Self, String> {
+        if !(MIN_ENTRIES..=MAX_ENTRIES).contains(&self.max_entries) {
+            return Err(format!(
+                "Cache max entries must be between {} and {}",
+                MIN_ENTRIES, MAX_ENTRIES
+            ));
+        }
+        Ok(self)
+    }
+}
+
+/// 快取用量（`/api/status` 的 `cache`）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CacheStatus {
+    pub entries: usize,
+    pub max_entries: usize,
+    /// 仍是啟動時還原快照的項目數
+    pub restored: usize,
+    pub ticks: usize,
+    /// 啟動以來因超過上限被淘汰的項目數
+    pub evicted: u64,
+    /// 快取內容的估算記憶體用量（bytes）
+    pub approx_bytes: usize,
+}
+
+static CACHE_CONFIG: LazyLock<RwLock<CacheConfig>> = LazyLock::new(|| RwLock::new(CacheConfig::default()));
+
+pub fn set_cache_config(config: CacheConfig) {
+    *CACHE_CONFIG.write().unwrap_or_else(|e| e.into_inner()) = config;
+}
+
+pub fn cache_config() -> CacheConfig {
+    CACHE_CONFIG.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// 快取 key 的寫入順序（由 PollingManager 持有）
+#[derive(Debug, Default)]
+pub struct LruOrder {
+    seq: u64,
+    last_write: HashMap<String, u64>,
+    evicted: u64,
+}
+
+impl LruOrder {
+    /// 記錄 key 剛被寫入
+    pub fn touch(&mut self, key: &str) {
+        self.seq += 1;
+        match self.last_write.get_mut(key) {
+            Some(seq) => *seq = self.seq,
+            None => {
+                self.last_write.insert(key.to_string(), self.seq);
+            }
+        }
+    }
+
+    pub fn retain(&mut self, keys: &HashSet<String>) {
+        self.last_write.retain(|k, _| keys.contains(k));
+    }
+
+    pub fn clear(&mut self) {
+        self.last_write.clear();
+    }
+
+    pub fn evicted(&self) -> u64 {
+        self.evicted
+    }
+
+    /// 快取超過 `max_entries` 時淘汰最久未寫入的項目（一併移除 restored 標記），回傳淘汰數
+    pub fn evict(
+        &mut self,
+        cache: &mut HashMap<String, AssetData>,
+        restored: &mut HashSet<String>,
+        max_entries: usize,
+    ) -> usize {
+        let excess = cache.len().saturating_sub(max_entries);
+        if excess == 0 {
+            return 0;
+        }
+        // 沒有寫入紀錄的 key 視為最舊
+        let mut keys: Vec<(u64, String)> = cache
+            .keys()
+            .map(|k| (self.last_write.get(k).copied().unwrap_or(0), k.clone()))
+            .collect();
+        keys.select_nth_unstable(excess - 1);
+        for (_, key) in keys.into_iter().take(excess) {
+            cache.remove(&key);
+            restored.remove(&key);
+            self.last_write.remove(&key);
+        }
+        self.evicted += excess as u64;
+        tracing::warn!(
+            "[Cache] evicted {} least recently updated price(s) (limit {})",
+            excess,
+            max_entries
+        );
+        excess
+    }
+}
+
+/// 超過上限時移除 `fetched_at` 最舊的項目
+pub fn evict_oldest<T>(map: &mut HashMap<String, T>, max_entries: usize, fetched_at: impl Fn(&T) -> i64) {
+    while map.len() > max_entries {
+        let Some(oldest) = map.iter().min_by_key(|(_, v)| fetched_at(v)).map(|(k, _)| k.clone()) else {
+            break;
+        };
+        map.remove(&oldest);
+    }
+}
+
+/// 估算快取內容佔用的記憶體（結構大小加上字串與 extra 的長度，不含 HashMap 本身的額外配置）
+pub fn estimate_bytes(cache: &HashMap<String, AssetData>) -> usize {
+    cache
+        .iter()
+        .map(|(key, d)| {
+            std::mem::size_of::<String>()
+                + key.len()
+                + std::mem::size_of::<AssetData>()
+                + d.symbol.len()
+                + d.currency.len()
+                + d.provider_id.len()
+                + d.extra.as_ref().map_or(0, |extra| {
+                    extra
+                        .iter()
+                        .map(|(k, v)| k.len() + v.to_string().len() + std::mem::size_of::<serde_json::Value>())
+                        .sum()
+                })
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::types::AssetDataBuilder;
+
+    fn insert(
+        lru: &mut LruOrder,
+        cache: &mut HashMap<String, AssetData>,
+        symbol: &str,
+    ) -> String {
+        let key = format!("binance:{}", symbol);
+        cache.insert(key.clone(), AssetDataBuilder::new(symbol, "binance").price(1.0).build());
+        lru.touch(&key);
+        key
+    }
+
+    #[test]
+    fn evicts_least_recently_written_entries() {
+        let mut lru = LruOrder::default();
+        let mut cache = HashMap::new();
+        let mut restored = HashSet::from(["old:SNAP".to_string()]);
+        cache.insert("old:SNAP".to_string(), AssetDataBuilder::new("SNAP", "old").build());
+        for symbol in ["BTC", "ETH", "SOL"] {
+            insert(&mut lru, &mut cache, symbol);
+        }
+        // BTC 重新寫入後變成最新
+        insert(&mut lru, &mut cache, "BTC");
+
+        assert_eq!(lru.evict(&mut cache, &mut restored, 2), 2);
+        let mut keys: Vec<_> = cache.keys().cloned().collect();
+        keys.sort();
+        assert_eq!(keys, ["binance:BTC", "binance:SOL"]);
+        assert!(restored.is_empty());
+        assert_eq!(lru.evicted(), 2);
+        assert_eq!(lru.evict(&mut cache, &mut restored, 2), 0);
+    }
+
+    #[test]
+    fn oldest_ticks_are_dropped_and_size_is_estimated() {
+        let mut ticks: HashMap<String, i64> =
+            HashMap::from([("a".to_string(), 3), ("b".to_string(), 1), ("c".to_string(), 2)]);
+        evict_oldest(&mut ticks, 2, |t| *t);
+        assert!(!ticks.contains_key("b") && ticks.len() == 2);
+
+        let mut cache = HashMap::new();
+        assert_eq!(estimate_bytes(&cache), 0);
+        insert(&mut LruOrder::default(), &mut cache, "BTC");
+        assert!(estimate_bytes(&cache) > std::mem::size_of::<AssetData>());
+    }
+
+    #[test]
+    fn config_validation() {
+        assert!(CacheConfig { max_entries: 10 }.normalized().is_err());
+        assert!(CacheConfig::default().normalized().is_ok());
+    }
+}
@@ -0,0 +1,408 @@
+//! 經濟日曆 — 定期從 FMP 或 Finnhub 的 economic calendar 抓取未來數天的總經事件
+//! （時間、國家、影響程度、前值 / 預測值 / 公布值），存入 `economic_events` 表，
+//! 供前端顯示與「事件開始前 N 分鐘」通知規則使用（例如 FOMC 前 15 分鐘提醒）。
+//!
+//! API key 沿用 FMP / Finnhub 的 provider 設定；`source = "auto"` 時依序使用已設定 key 的來源。
+//! 設定存於 app settings（`calendar_*`）。背景 task 在啟用日曆或存在已啟用的事件通知規則時才會抓取，
+//! 每次抓取後及事件規則啟用期間每 [`CHECK_INTERVAL_SECS`] 秒送出 `AppEvent::CalendarUpdate`（即將到來的事件）。
+
+use std::collections::HashSet;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::broadcast;
+
+use crate::db::DbPool;
+use crate::events::AppEvent;
+use crate::providers::types::{provider_client, resolve_base_url, HttpOptions};
+
+const FMP_BASE_URL: &str = "https://financialmodelingprep.com";
+const FINNHUB_BASE_URL: &str = "https://finnhub.io";
+/// 支援的來源（`auto` 依此順序挑選已設定 API key 者）
+pub const SOURCES: [&str; 2] = ["fmp", "finnhub"];
+/// 最短抓取間隔（秒），日曆資料變動不頻繁
+pub const MIN_INTERVAL_SECS: u64 = 600;
+/// 抓取今天起幾天內的事件
+const LOOKAHEAD_DAYS: i64 = 7;
+/// 已過去的事件保留天數
+const RETENTION_DAYS: i64 = 30;
+/// 事件通知規則的檢查間隔（秒）
+pub const CHECK_INTERVAL_SECS: u64 = 30;
+
+/// 事件影響程度（由低到高排序）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Impact {
+    Low,
+    Medium,
+    High,
+}
+
+impl Impact {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        }
+    }
+}
+
+impl FromStr for Impact {
+    type Err = ();
+
+    /// 不分大小寫；FMP 的 `None` 視為 low
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "low" | "none" | "" => Ok(Self::Low),
+            "medium" => Ok(Self::Medium),
+            "high" => Ok(Self::High),
+            _ => Err(()),
+        }
+    }
+}
+
+/// 一筆總經事件；`id` 為 DB 主鍵（尚未寫入時為 0）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EconomicEvent {
+    pub id: i64,
+    pub source: String,
+    /// 事件時間（Unix 秒，UTC）
+    pub event_time: i64,
+    /// 國家代碼（如 `US`）
+    pub country: String,
+    pub event: String,
+    pub impact: Impact,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    pub previous: Option<f64>,
+    pub forecast: Option<f64>,
+    pub actual: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CalendarConfig {
+    /// 即使沒有事件通知規則也持續抓取
+    pub enabled: bool,
+    /// `auto` / `fmp` / `finnhub`
+    pub source: String,
+    pub interval_secs: u64,
+    /// 只保留這些國家的事件（空 = 全部）
+    #[serde(default)]
+    pub countries: Vec<String>,
+    /// 儲存的最低影響程度
+    pub min_impact: Impact,
+    /// 事件通知規則比對的事件名稱關鍵字（不分大小寫；空 = 所有高影響事件）
+    #[serde(default)]
+    pub alert_keywords: Vec<String>,
+}
+
+impl Default for CalendarConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            source: "auto".to_string(),
+            interval_secs: 3600,
+            countries: vec!["US".to_string()],
+            min_impact: Impact::High,
+            alert_keywords: ["FOMC", "Fed Interest Rate", "CPI", "Nonfarm Payrolls"]
+                .map(String::from)
+                .to_vec(),
+        }
+    }
+}
+
+impl CalendarConfig {
+    /// 檢查並正規化設定（國家代碼轉大寫，去除空白項目）
+    pub fn normalized(self) -> Result<Self, String> {
+        let source = self.source.trim().to_lowercase();
+        if source != "auto" && !SOURCES.contains(&source.as_str()) {
+            return Err(format!("Unknown calendar source: {}", self.source));
+        }
+        if self.interval_secs < MIN_INTERVAL_SECS {
+            return Err(format!("Calendar interval must be at least {} seconds", MIN_INTERVAL_SECS));
+        }
+        let clean = |values: Vec<String>, upper: bool| -> Vec<String> {
+            let mut out: Vec<String> = Vec::new();
+            for v in values.iter().map(|v| v.trim()).filter(|v| !v.is_empty()) {
+                let v = if upper { v.to_uppercase() } else { v.to_string() };
+                if !out.contains(&v) {
+                    out.push(v);
+                }
+            }
+            out
+        };
+        Ok(Self {
+            enabled: self.enabled,
+            source,
+            interval_secs: self.interval_secs,
+            countries: clean(self.countries, true),
+            min_impact: self.min_impact,
+            alert_keywords: clean(self.alert_keywords, false),
+        })
+    }
+}
+
+static CALENDAR_CONFIG: LazyLock<RwLock<CalendarConfig>> =
+    LazyLock::new(|| RwLock::new(CalendarConfig::default()));
+/// 是否存在已啟用的事件通知規則（由 NotificationEngine 重新載入規則時更新）
+static RULES_ACTIVE: AtomicBool = AtomicBool::new(false);
+/// 已通知過的 (rule_id, event_id)，避免同一事件在提醒時間窗內重複觸發
+static NOTIFIED: LazyLock<Mutex<HashSet<(i64, i64)>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+pub fn set_calendar_config(config: CalendarConfig) {
+    *CALENDAR_CONFIG.write().unwrap_or_else(|e| e.into_inner()) = config;
+}
+
+pub fn calendar_config() -> CalendarConfig {
+    CALENDAR_CONFIG.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+pub fn set_rules_active(active: bool) {
+    RULES_ACTIVE.store(active, Ordering::Relaxed);
+}
+
+/// 標記規則已針對事件通知過；回傳 false 表示先前已通知
+pub fn mark_notified(rule_id: i64, event_id: i64) -> bool {
+    NOTIFIED.lock().unwrap_or_else(|e| e.into_inner()).insert((rule_id, event_id))
+}
+
+/// 事件是否為通知規則關注的事件：名稱包含任一關鍵字，未設定關鍵字時為所有高影響事件
+pub fn is_alert_event(event: &EconomicEvent, keywords: &[String]) -> bool {
+    if keywords.is_empty() {
+        return event.impact == Impact::High;
+    }
+    let name = event.event.to_lowercase();
+    keywords.iter().any(|k| name.contains(&k.to_lowercase()))
+}
+
+fn num(v: &Value) -> Option<f64> {
+    v.as_f64().or_else(|| v.as_str().and_then(|s| s.trim().parse().ok()))
+}
+
+/// 事件時間字串（UTC，`2024-03-20 18:00:00` 或 ISO 格式）轉 Unix 秒
+fn parse_time(s: &str) -> Option<i64> {
+    ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|fmt| chrono::NaiveDateTime::parse_from_str(s.trim().trim_end_matches('Z'), fmt).ok())
+        .map(|t| t.and_utc().timestamp())
+}
+
+/// 解析一筆事件；`time_key` / `previous_key` / `forecast_key` 依來源不同
+fn parse_event(source: &str, item: &Value, time_key: &str, previous_key: &str, forecast_key: &str) -> Option<EconomicEvent> {
+    let event = item["event"].as_str()?.trim();
+    if event.is_empty() {
+        return None;
+    }
+    Some(EconomicEvent {
+        id: 0,
+        source: source.to_string(),
+        event_time: parse_time(item[time_key].as_str()?)?,
+        country: item["country"].as_str().unwrap_or_default().to_uppercase(),
+        event: event.to_string(),
+        impact: Impact::from_str(item["impact"].as_str().unwrap_or_default()).unwrap_or(Impact::Low),
+        unit: item["unit"].as_str().filter(|u| !u.is_empty()).map(String::from),
+        previous: num(&item[previous_key]),
+        forecast: num(&item[forecast_key]),
+        actual: num(&item["actual"]),
+    })
+}
+
+/// FMP `/api/v3/economic_calendar` 回應為事件陣列
+fn parse_fmp(body: &Value) -> Result<Vec<EconomicEvent>, String> {
+    let items = body.as_array().ok_or_else(|| {
+        let message = body["Error Message"].as_str().unwrap_or("unexpected response");
+        format!("FMP economic calendar error: {}", message)
+    })?;
+    Ok(items
+        .iter()
+        .filter_map(|item| parse_event("fmp", item, "date", "previous", "estimate"))
+        .collect())
+}
+
+/// Finnhub `/api/v1/calendar/economic` 回應為 `{economicCalendar: [...]}`
+fn parse_finnhub(body: &Value) -> Result<Vec<EconomicEvent>, String> {
+    let items = body["economicCalendar"].as_array().ok_or_else(|| {
+        let message = body["error"].as_str().unwrap_or("unexpected response");
+        format!("Finnhub economic calendar error: {}", message)
+    })?;
+    Ok(items
+        .iter()
+        .filter_map(|item| parse_event("finnhub", item, "time", "prev", "estimate"))
+        .collect())
+}
+
+/// 依設定挑選來源：回傳 (source, api_key, base_url, 連線設定)
+fn resolve_source(db: &DbPool, source: &str) -> Result<(&'static str, String, String, HttpOptions), String> {
+    for id in SOURCES.iter().filter(|id| source == "auto" || source == **id) {
+        let settings = db.get_provider_settings(id).ok().flatten();
+        let Some(key) = settings.as_ref().and_then(|s| s.api_key.clone()).filter(|k| !k.is_empty()) else {
+            continue;
+        };
+        let default = if *id == "fmp" { FMP_BASE_URL } else { FINNHUB_BASE_URL };
+        let http = settings.as_ref().map(HttpOptions::from).unwrap_or_default();
+        let base_url = resolve_base_url(settings.and_then(|s| s.api_url), default);
+        return Ok((id, key, base_url, http));
+    }
+    Err("Economic calendar requires an FMP or Finnhub API key".to_string())
+}
+
+/// 檢查來源是否有可用的 API key
+pub fn check_source(db: &DbPool, source: &str) -> Result<(), String> {
+    resolve_source(db, source).map(|_| ())
+}
+
+async fn fetch(db: &DbPool, config: &CalendarConfig) -> Result<Vec<EconomicEvent>, String> {
+    let (source, key, base_url, http) = resolve_source(db, &config.source)?;
+    let today = chrono::Utc::now().date_naive();
+    let to = today + chrono::Duration::days(LOOKAHEAD_DAYS);
+    let url = match source {
+        "fmp" => format!("{}/api/v3/economic_calendar?from={}&to={}&apikey={}", base_url, today, to, key),
+        _ => format!("{}/api/v1/calendar/economic?from={}&to={}&token={}", base_url, today, to, key),
+    };
+    let body: Value = provider_client(&http)
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Economic calendar request failed: {}", e))?
+        .error_for_status()
+        .map_err(|e| format!("Economic calendar request failed: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Economic calendar response parse failed: {}", e))?;
+    let events = if source == "fmp" { parse_fmp(&body)? } else { parse_finnhub(&body)? };
+    Ok(filter_events(events, config))
+}
+
+/// 套用國家與最低影響程度篩選
+fn filter_events(events: Vec<EconomicEvent>, config: &CalendarConfig) -> Vec<EconomicEvent> {
+    events
+        .into_iter()
+        .filter(|e| e.impact >= config.min_impact)
+        .filter(|e| config.countries.is_empty() || config.countries.contains(&e.country))
+        .collect()
+}
+
+/// 即將到來的事件（現在起 [`LOOKAHEAD_DAYS`] 天內）
+pub fn upcoming(db: &DbPool) -> Result<Vec<EconomicEvent>, String> {
+    let now = chrono::Utc::now().timestamp();
+    db.list_economic_events(now, now + LOOKAHEAD_DAYS * 86_400, None, Impact::Low)
+}
+
+/// 立即抓取並寫入 DB，清除過期事件；回傳寫入的事件數並送出 `AppEvent::CalendarUpdate`
+pub async fn refresh(db: &DbPool, event_bus: &broadcast::Sender<AppEvent>) -> Result<usize, String> {
+    let events = fetch(db, &calendar_config()).await?;
+    let count = db.upsert_economic_events(&events)?;
+    db.delete_economic_events_before(chrono::Utc::now().timestamp() - RETENTION_DAYS * 86_400)?;
+    let _ = event_bus.send(AppEvent::CalendarUpdate(upcoming(db)?));
+    Ok(count)
+}
+
+/// 啟動背景日曆 task：依設定間隔抓取（省電模式下乘上倍率），事件規則啟用時定期送出即將到來的事件
+pub fn start(db: Arc<DbPool>, event_bus: broadcast::Sender<AppEvent>) {
+    tokio::spawn(async move {
+        let mut last_fetch: Option<Instant> = None;
+        loop {
+            let config = calendar_config();
+            let rules_active = RULES_ACTIVE.load(Ordering::Relaxed);
+            if config.enabled || rules_active {
+                let interval = Duration::from_secs_f64(
+                    config.interval_secs.max(MIN_INTERVAL_SECS) as f64 * crate::power::interval_multiplier(),
+                );
+                let mut sent = false;
+                if last_fetch.is_none_or(|t| t.elapsed() >= interval) {
+                    // 失敗（例如未設定 API key）也等到下個間隔再試
+                    last_fetch = Some(Instant::now());
+                    match refresh(&db, &event_bus).await {
+                        Ok(count) => {
+                            sent = true;
+                            tracing::debug!("[Calendar] Stored {} events", count);
+                        }
+                        Err(e) => tracing::warn!("[Calendar] Refresh failed: {}", e),
+                    }
+                }
+                if rules_active && !sent {
+                    match upcoming(&db) {
+                        Ok(events) => {
+                            let _ = event_bus.send(AppEvent::CalendarUpdate(events));
+                        }
+                        Err(e) => tracing::warn!("[Calendar] Failed to read events: {}", e),
+                    }
+                }
+            }
+            tokio::time::sleep(Duration::from_secs(CHECK_INTERVAL_SECS)).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_fmp_and_finnhub_calendars() {
+        let fmp = json!([
+            {"event": "Fed Interest Rate Decision", "date": "2024-03-20 18:00:00", "country": "US",
+             "previous": 5.5, "estimate": 5.5, "actual": null, "impact": "High", "unit": "%"},
+            {"event": "", "date": "2024-03-20 18:00:00", "country": "US", "impact": "High"}
+        ]);
+        let events = parse_fmp(&fmp).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_time, 1_710_957_600);
+        assert_eq!((events[0].impact, events[0].forecast, events[0].actual), (Impact::High, Some(5.5), None));
+        assert_eq!(events[0].unit.as_deref(), Some("%"));
+
+        let finnhub = json!({"economicCalendar": [
+            {"event": "CPI YoY", "time": "2024-04-10 12:30:00", "country": "us", "prev": 3.2,
+             "estimate": 3.4, "actual": 3.5, "impact": "medium", "unit": "%"}
+        ]});
+        let events = parse_finnhub(&finnhub).unwrap();
+        assert_eq!((events[0].country.as_str(), events[0].previous, events[0].actual), ("US", Some(3.2), Some(3.5)));
+        assert_eq!(events[0].impact, Impact::Medium);
+
+        assert!(parse_fmp(&json!({"Error Message": "Invalid API KEY."})).unwrap_err().contains("Invalid API KEY"));
+    }
+
+    #[test]
+    fn filters_by_country_impact_and_keywords() {
+        let event = |name: &str, country: &str, impact: Impact| EconomicEvent {
+            id: 0,
+            source: "fmp".to_string(),
+            event_time: 0,
+            country: country.to_string(),
+            event: name.to_string(),
+            impact,
+            unit: None,
+            previous: None,
+            forecast: None,
+            actual: None,
+        };
+        let config = CalendarConfig::default();
+        let kept = filter_events(
+            vec![
+                event("FOMC Minutes", "US", Impact::High),
+                event("ECB Interest Rate Decision", "EU", Impact::High),
+                event("Building Permits", "US", Impact::Medium),
+            ],
+            &config,
+        );
+        assert_eq!(kept.len(), 1);
+        assert!(is_alert_event(&kept[0], &config.alert_keywords));
+        assert!(!is_alert_event(&event("GDP Growth Rate", "US", Impact::High), &config.alert_keywords));
+        assert!(is_alert_event(&event("GDP Growth Rate", "US", Impact::High), &[]));
+
+        let normalized = CalendarConfig {
+            countries: vec![" us ".to_string(), "US".to_string(), "".to_string()],
+            ..Default::default()
+        }
+        .normalized()
+        .unwrap();
+        assert_eq!(normalized.countries, ["US"]);
+        assert!(CalendarConfig { source: "yahoo".to_string(), ..Default::default() }.normalized().is_err());
+    }
+}
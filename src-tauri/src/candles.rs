@@ -0,0 +1,280 @@
+//! WS 串流 1 分鐘 K 線 — 將 WebSocket ticker 即時彙整為記憶體中的 OHLCV K 線，
+//! 沒有 K 線 API 的 provider 也能取得圖表等級的資料。
+//!
+//! `WsManager` 每收到一筆 `WsTickerUpdate` 都呼叫 [`observe`]（每筆 tick 只彙整一次）。
+//! 背景任務在每分鐘開始後 [`CLOSE_DELAY_MS`] 收盤上一分鐘的 K 線，逐根送出
+//! `AppEvent::CandleClose`（前端事件 `candle-close`）；該分鐘沒有任何 tick 的 symbol 不產生 K 線。
+//!
+//! 成交量以 ticker 的 24h 滾動成交量在該分鐘內的增加推算（滾動視窗移出的量大於新成交時為 null）。
+//! 選擇保存時（`persist`）收盤的 K 線寫入 `price_candles`，`get_candles` / `/api/candles` 查詢，
+//! 超過 `retention_days` 的 K 線每小時清除一次。設定存於 app settings（`candle_persist`、`candle_retention_days`）。
+
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex, RwLock};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::db::DbPool;
+use crate::events::AppEvent;
+use crate::providers::WsTickerUpdate;
+
+/// K 線週期（秒）
+pub const CANDLE_SECS: i64 = 60;
+/// 每分鐘開始後等待跨分鐘邊界的 tick 的時間
+pub const CLOSE_DELAY_MS: i64 = 1_000;
+/// 保存天數上限
+pub const MAX_RETENTION_DAYS: u32 = 365;
+/// `get_candles` 預設 / 最多回傳的 K 線數
+pub const DEFAULT_QUERY_LIMIT: usize = 1_440;
+pub const MAX_QUERY_LIMIT: usize = 10_000;
+/// 清除過期 K 線的間隔
+const PRUNE_INTERVAL_SECS: i64 = 3_600;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CandleConfig {
+    /// 收盤的 K 線是否寫入資料庫
+    pub persist: bool,
+    /// 保存天數
+    pub retention_days: u32,
+}
+
+impl Default for CandleConfig {
+    fn default() -> Self {
+        Self {
+            persist: false,
+            retention_days: 7,
+        }
+    }
+}
+
+impl CandleConfig {
+    /// 檢查設定
+    pub fn normalized(self) -> Result<Self, String> {
+        if !(1..=MAX_RETENTION_DAYS).contains(&self.retention_days) {
+            return Err(format!("Candle retention must be between 1 and {} days", MAX_RETENTION_DAYS));
+        }
+        Ok(self)
+    }
+}
+
+/// 一根 1 分鐘 K 線（`candle-close` 事件）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Candle {
+    pub provider_id: String,
+    pub symbol: String,
+    /// 分鐘起點（Unix 秒）
+    pub start: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    /// 由 24h 滾動成交量推算的該分鐘成交量
+    pub volume: Option<f64>,
+    /// 彙整的 tick 數
+    pub ticks: u32,
+}
+
+/// 彙整中的 K 線
+#[derive(Debug)]
+struct Building {
+    candle: Candle,
+    first_volume: Option<f64>,
+    last_volume: Option<f64>,
+}
+
+impl Building {
+    fn finish(self) -> Candle {
+        let volume = self
+            .first_volume
+            .zip(self.last_volume)
+            .map(|(first, last)| last - first)
+            .filter(|v| *v >= 0.0);
+        Candle { volume, ..self.candle }
+    }
+}
+
+/// 依 (provider, symbol) 彙整 tick 為 1 分鐘 K 線
+#[derive(Debug, Default)]
+pub struct CandleAggregator {
+    building: HashMap<(String, String), Building>,
+    /// 下一分鐘開始前就收到新分鐘 tick 而提前結束的 K 線
+    closed: Vec<Candle>,
+    /// 已收盤到此分鐘（不含）；更早的 tick 直接略過
+    closed_until: i64,
+}
+
+impl CandleAggregator {
+    /// 加入一筆 tick（`at` 為 Unix 秒）；價格無效或屬於已收盤分鐘的 tick 略過
+    pub fn push(&mut self, provider_id: &str, symbol: &str, price: f64, volume_24h: Option<f64>, at: i64) {
+        if !price.is_finite() || price <= 0.0 {
+            return;
+        }
+        let start = at.div_euclid(CANDLE_SECS) * CANDLE_SECS;
+        if start < self.closed_until {
+            return;
+        }
+        let key = (provider_id.to_string(), symbol.to_string());
+        if let Some(building) = self.building.get_mut(&key) {
+            let candle = &mut building.candle;
+            if start < candle.start {
+                return;
+            }
+            if start == candle.start {
+                candle.high = candle.high.max(price);
+                candle.low = candle.low.min(price);
+                candle.close = price;
+                candle.ticks += 1;
+                building.first_volume = building.first_volume.or(volume_24h);
+                building.last_volume = volume_24h.or(building.last_volume);
+                return;
+            }
+        }
+        let next = Building {
+            candle: Candle {
+                provider_id: provider_id.to_string(),
+                symbol: symbol.to_string(),
+                start,
+                open: price,
+                high: price,
+                low: price,
+                close: price,
+                volume: None,
+                ticks: 1,
+            },
+            first_volume: volume_24h,
+            last_volume: volume_24h,
+        };
+        if let Some(previous) = self.building.insert(key, next) {
+            self.closed.push(previous.finish());
+        }
+    }
+
+    /// 收盤 `now`（Unix 秒）所在分鐘之前的所有 K 線，依時間、provider、symbol 排序
+    pub fn close_due(&mut self, now: i64) -> Vec<Candle> {
+        let current = now.div_euclid(CANDLE_SECS) * CANDLE_SECS;
+        let due: Vec<(String, String)> = self
+            .building
+            .iter()
+            .filter(|(_, b)| b.candle.start < current)
+            .map(|(key, _)| key.clone())
+            .collect();
+        let mut candles = std::mem::take(&mut self.closed);
+        candles.extend(due.iter().filter_map(|key| self.building.remove(key)).map(Building::finish));
+        candles.sort_by(|a, b| {
+            (a.start, &a.provider_id, &a.symbol).cmp(&(b.start, &b.provider_id, &b.symbol))
+        });
+        self.closed_until = self.closed_until.max(current);
+        candles
+    }
+}
+
+static CANDLE_CONFIG: LazyLock<RwLock<CandleConfig>> = LazyLock::new(|| RwLock::new(CandleConfig::default()));
+static AGGREGATOR: LazyLock<Mutex<CandleAggregator>> = LazyLock::new(|| Mutex::new(CandleAggregator::default()));
+
+pub fn set_candle_config(config: CandleConfig) {
+    *CANDLE_CONFIG.write().unwrap_or_else(|e| e.into_inner()) = config;
+}
+
+pub fn candle_config() -> CandleConfig {
+    CANDLE_CONFIG.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// 將一筆 WS ticker 加入彙整
+pub fn observe(update: &WsTickerUpdate) {
+    AGGREGATOR.lock().unwrap_or_else(|e| e.into_inner()).push(
+        &update.provider_id,
+        &update.symbol,
+        update.data.price,
+        update.data.volume,
+        update.data.last_updated.div_euclid(1000),
+    );
+}
+
+/// 啟動每分鐘的收盤任務（沒有 WS 串流時只會閒置）
+pub fn start(db: Arc<DbPool>, event_bus: broadcast::Sender<AppEvent>) {
+    tokio::spawn(async move {
+        let mut last_prune = 0;
+        loop {
+            let now_ms = chrono::Utc::now().timestamp_millis();
+            let wait_ms = CANDLE_SECS * 1000 - now_ms.rem_euclid(CANDLE_SECS * 1000) + CLOSE_DELAY_MS;
+            tokio::time::sleep(Duration::from_millis(wait_ms as u64)).await;
+
+            let now = chrono::Utc::now().timestamp();
+            let candles = AGGREGATOR.lock().unwrap_or_else(|e| e.into_inner()).close_due(now);
+            let config = candle_config();
+            if config.persist && !candles.is_empty() {
+                if let Err(e) = db.insert_candles(&candles) {
+                    tracing::warn!("[Candles] Failed to save {} candle(s): {}", candles.len(), e);
+                }
+            }
+            if now - last_prune >= PRUNE_INTERVAL_SECS {
+                last_prune = now;
+                match db.cleanup_candles(now - config.retention_days as i64 * 86_400) {
+                    Ok(0) => {}
+                    Ok(n) => tracing::info!("[Candles] Removed {} expired candle(s)", n),
+                    Err(e) => tracing::warn!("[Candles] Failed to remove expired candles: {}", e),
+                }
+            }
+            for candle in candles {
+                let _ = event_bus.send(AppEvent::CandleClose(candle));
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ticks_aggregate_into_minute_candles() {
+        let mut agg = CandleAggregator::default();
+        agg.push("binance", "BTCUSDT", 100.0, Some(1_000.0), 120);
+        agg.push("binance", "BTCUSDT", 103.0, Some(1_004.0), 130);
+        agg.push("binance", "BTCUSDT", 99.0, None, 150);
+        agg.push("binance", "BTCUSDT", 101.0, Some(1_006.5), 179);
+        agg.push("binance", "BTCUSDT", f64::NAN, Some(2_000.0), 179);
+        agg.push("bybit", "ETH", 10.0, Some(500.0), 125);
+        agg.push("bybit", "ETH", 11.0, Some(480.0), 170);
+
+        // 尚在同一分鐘時不收盤
+        assert!(agg.close_due(179).is_empty());
+        let candles = agg.close_due(181);
+        assert_eq!(candles.len(), 2);
+        let btc = &candles[0];
+        assert_eq!((btc.provider_id.as_str(), btc.start), ("binance", 120));
+        assert_eq!((btc.open, btc.high, btc.low, btc.close, btc.ticks), (100.0, 103.0, 99.0, 101.0, 4));
+        assert_eq!(btc.volume, Some(6.5));
+        // 24h 成交量減少時無法推算
+        assert_eq!((candles[1].symbol.as_str(), candles[1].volume), ("ETH", None));
+
+        // 已收盤分鐘的延遲 tick 不會產生新的 K 線
+        agg.push("binance", "BTCUSDT", 120.0, None, 170);
+        assert!(agg.close_due(240).is_empty());
+    }
+
+    #[test]
+    fn new_minute_tick_closes_previous_candle() {
+        let mut agg = CandleAggregator::default();
+        agg.push("coinbase", "BTC", 100.0, None, 60);
+        agg.push("coinbase", "BTC", 105.0, None, 125);
+        agg.push("coinbase", "BTC", 104.0, None, 130);
+        // 比目前 K 線更早的 tick 略過
+        agg.push("coinbase", "BTC", 1.0, None, 119);
+
+        let candles = agg.close_due(125);
+        assert_eq!(candles.len(), 1);
+        assert_eq!((candles[0].start, candles[0].close, candles[0].ticks), (60, 100.0, 1));
+        let candles = agg.close_due(180);
+        assert_eq!((candles[0].start, candles[0].open, candles[0].close, candles[0].ticks), (120, 105.0, 104.0, 2));
+    }
+
+    #[test]
+    fn validates_retention() {
+        assert!(CandleConfig::default().normalized().is_ok());
+        assert!(CandleConfig { persist: true, retention_days: 0 }.normalized().is_err());
+        assert!(CandleConfig { persist: true, retention_days: MAX_RETENTION_DAYS + 1 }.normalized().is_err());
+    }
+}
@@ -0,0 +1,100 @@
+//! 剪貼簿文字 — 將報價或一段價格歷史整理成可直接貼進聊天室 / 試算表的文字。
+//!
+//! 這裡只負責產生文字；實際寫入系統剪貼簿由 desktop 的
+//! `copy_price_to_clipboard` / `copy_history_csv_to_clipboard` command 處理。
+
+use crate::db::DbPool;
+use crate::file_export::{history_table, to_csv_string};
+use crate::providers::AssetData;
+
+/// 單次複製到剪貼簿的歷史筆數上限（更大的資料請用檔案匯出）
+pub const MAX_CLIPBOARD_ROWS: i64 = 10_000;
+
+/// 以指定小數位數顯示價格（見 [`AssetData::display_scale`]）
+pub fn format_price(price: f64, scale: u32) -> String {
+    format!("{:.*}", scale as usize, price)
+}
+
+/// 單行報價，例如 `BTCUSDT 65000.00 USDT (+1.50%) · binance · 2026-01-02 03:04 UTC`
+pub fn quote_text(data: &AssetData) -> String {
+    let mut text = format!("{} {}", data.symbol, format_price(data.price, data.display_scale()));
+    if !data.currency.is_empty() {
+        text.push(' ');
+        text.push_str(&data.currency);
+    }
+    if let Some(pct) = data.change_percent_24h {
+        text.push_str(&format!(" ({:+.2}%)", pct));
+    }
+    text.push_str(&format!(" · {}", data.provider_id));
+    // last_updated 為毫秒
+    if let Some(time) = chrono::DateTime::from_timestamp_millis(data.last_updated) {
+        text.push_str(&format!(" · {}", time.format("%Y-%m-%d %H:%M UTC")));
+    }
+    text
+}
+
+/// 單一 subscription 在時間範圍內的價格歷史 CSV（含標題列）
+pub fn history_csv(
+    db: &DbPool,
+    subscription_id: i64,
+    from_ts: Option<i64>,
+    to_ts: Option<i64>,
+) -> Result<String, String> {
+    let table = history_table(db, &[subscription_id], from_ts, to_ts, MAX_CLIPBOARD_ROWS)?;
+    if table.rows.is_empty() {
+        return Err("No price history in this range".to_string());
+    }
+    to_csv_string(&table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::AssetDataBuilder;
+
+    #[test]
+    fn quote_text_includes_change_provider_and_time() {
+        let data = AssetDataBuilder::new("BTCUSDT", "binance")
+            .price(65000.0)
+            .currency("USDT")
+            .change_percent_24h(Some(1.5))
+            .build();
+        let data = AssetData {
+            last_updated: 1_767_323_040_000,
+            ..data
+        };
+        assert_eq!(
+            quote_text(&data),
+            "BTCUSDT 65000.00 USDT (+1.50%) · binance · 2026-01-02 03:04 UTC"
+        );
+    }
+
+    #[test]
+    fn tiny_prices_keep_significant_digits() {
+        let bonk = AssetDataBuilder::new("BONKUSDT", "binance").price(0.00002345).build();
+        assert!(quote_text(&bonk).starts_with("BONKUSDT 0.00002345 "));
+        // provider 提供的 tick size 優先
+        let scaled = AssetDataBuilder::new("BTCUSDT", "binance")
+            .price(65000.123)
+            .price_scale(Some(1))
+            .build();
+        assert!(quote_text(&scaled).starts_with("BTCUSDT 65000.1 "));
+    }
+
+    #[test]
+    fn history_csv_has_header_and_rejects_empty_range() {
+        let db = DbPool::open(&std::path::PathBuf::from(":memory:")).unwrap();
+        let id = db
+            .add_subscription("asset", "ETH", None, "binance", "crypto", None, None, None)
+            .unwrap();
+        assert!(history_csv(&db, id, None, None).is_err());
+
+        db.toggle_record(id, true).unwrap();
+        let targets = db.read_record_targets().unwrap().remove("binance").unwrap();
+        db.write_price_history("binance", &[("ETH".to_string(), 3000.5, None, None, None, None, None, None, None, None, "USDT".to_string())], &targets);
+        let csv = history_csv(&db, id, None, None).unwrap();
+        let mut lines = csv.lines();
+        assert!(lines.next().unwrap().starts_with("symbol,provider,time_utc"));
+        assert!(lines.next().unwrap().starts_with("ETH,binance,"));
+    }
+}
@@ -0,0 +1,685 @@
+//! S3 相容雲端備份（選用）— 定期將 DB 快照加密後上傳到 S3 相容的物件儲存
+//! （AWS S3、Cloudflare R2、MinIO、Backblaze B2 等），長時間運作的錄製主機故障時可由雲端還原。
+//!
+//! - 快照以 `VACUUM INTO` 產生，包含設定、訂閱與所有價格紀錄；provider API key 以明文附在快照前，
+//!   只存在於加密後的備份內，還原時以本機主金鑰重新儲存，換裝置還原也不會遺失
+//! - 以使用者設定的 passphrase 經 PBKDF2 衍生金鑰、AES-256-GCM 加密；儲存端只看得到密文
+//! - 物件 key 為 `{prefix}stockenboard-{YYYYMMDD-HHMMSS}.sbbak`，上傳後只保留最新的 `retain` 份
+//! - 每次備份另外上傳上次封存後新增的價格紀錄（`.sbhist`，不受保留份數限制）；還原時一併匯入，
+//!   `cleanup_history` 清掉的舊紀錄仍可由雲端找回
+//! - 請求以 AWS Signature V4 簽章，使用 path-style URL（`{endpoint}/{bucket}/{key}`）
+//!
+//! 設定存於 app settings（`cloud_backup_*`），secret key 與 passphrase 以 [`crate::secrets`] 加密保存。
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, RwLock};
+use std::time::Duration;
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use ring::hmac;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::db::{DbPool, ExportedSecret};
+
+/// 排程檢查間隔
+pub const CHECK_INTERVAL_SECS: u64 = 600;
+/// 上次成功備份時間（Unix 秒）的 setting key
+pub const LAST_BACKUP_SETTING: &str = "cloud_backup_last_at";
+
+/// 已上傳到價格紀錄封存檔的時間（Unix 秒）的 setting key
+pub const HISTORY_ARCHIVED_SETTING: &str = "cloud_backup_history_archived_to";
+
+/// 備份檔格式標頭與副檔名
+const MAGIC: &[u8] = b"SBBK1";
+const EXTENSION: &str = ".sbbak";
+/// 價格紀錄封存檔副檔名（不列入備份清單與保留份數）
+const HISTORY_EXTENSION: &str = ".sbhist";
+/// 未附 API key 的舊版備份直接以 SQLite 檔開頭
+const SQLITE_HEADER: &[u8] = b"SQLite format 3\0";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const PBKDF2_ROUNDS: u32 = 100_000;
+const MIN_PASSPHRASE_LEN: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CloudBackupConfig {
+    pub enabled: bool,
+    /// S3 API endpoint，例如 `https://s3.us-east-1.amazonaws.com`、`https://<account>.r2.cloudflarestorage.com`
+    pub endpoint: String,
+    /// 簽章用的 region（R2 為 `auto`）
+    pub region: String,
+    pub bucket: String,
+    /// 物件 key 前綴（例如 `stockenboard/`）
+    pub prefix: String,
+    pub access_key_id: String,
+    /// 讀取時不回傳；更新時留空表示沿用已儲存的值
+    pub secret_access_key: String,
+    /// 備份加密 passphrase，規則同 `secret_access_key`
+    pub passphrase: String,
+    pub interval_hours: u32,
+    /// 保留的備份份數
+    pub retain: u32,
+}
+
+impl Default for CloudBackupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: String::new(),
+            region: "us-east-1".to_string(),
+            bucket: String::new(),
+            prefix: "stockenboard/".to_string(),
+            access_key_id: String::new(),
+            secret_access_key: String::new(),
+            passphrase: String::new(),
+            interval_hours: 24,
+            retain: 7,
+        }
+    }
+}
+
+impl CloudBackupConfig {
+    /// 檢查設定並正規化 endpoint / prefix
+    pub fn normalized(mut self) -> Result<Self, String> {
+        self.endpoint = self.endpoint.trim().trim_end_matches('/').to_string();
+        self.region = self.region.trim().to_string();
+        self.bucket = self.bucket.trim().to_string();
+        self.prefix = self.prefix.trim().trim_start_matches('/').to_string();
+        self.access_key_id = self.access_key_id.trim().to_string();
+        if self.region.is_empty() {
+            self.region = CloudBackupConfig::default().region;
+        }
+        if !self.prefix.is_empty() && !self.prefix.ends_with('/') {
+            self.prefix.push('/');
+        }
+        if !(1..=168).contains(&self.interval_hours) {
+            return Err("Backup interval must be between 1 and 168 hours".to_string());
+        }
+        if !(1..=365).contains(&self.retain) {
+            return Err("Backup retention must be between 1 and 365".to_string());
+        }
+        if self.enabled {
+            self.check_target()?;
+        }
+        Ok(self)
+    }
+
+    /// 連線與加密所需的欄位是否齊全（列出 / 還原在未啟用排程時也可使用）
+    pub fn check_target(&self) -> Result<(), String> {
+        if !(self.endpoint.starts_with("http://") || self.endpoint.starts_with("https://")) {
+            return Err("Backup endpoint must start with http:// or https://".to_string());
+        }
+        if self.bucket.is_empty() {
+            return Err("Backup bucket is required".to_string());
+        }
+        if self.access_key_id.is_empty() || self.secret_access_key.is_empty() {
+            return Err("Backup access key and secret key are required".to_string());
+        }
+        if self.passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+            return Err(format!("Backup passphrase must be at least {} characters", MIN_PASSPHRASE_LEN));
+        }
+        Ok(())
+    }
+
+    /// 回傳給前端的版本（不含 secret key 與 passphrase）
+    pub fn redacted(mut self) -> Self {
+        self.secret_access_key.clear();
+        self.passphrase.clear();
+        self
+    }
+
+    fn host(&self) -> &str {
+        let rest = self.endpoint.split_once("://").map_or(self.endpoint.as_str(), |(_, r)| r);
+        rest.split('/').next().unwrap_or(rest)
+    }
+}
+
+static CLOUD_BACKUP_CONFIG: LazyLock<RwLock<CloudBackupConfig>> =
+    LazyLock::new(|| RwLock::new(CloudBackupConfig::default()));
+
+pub fn set_cloud_backup_config(config: CloudBackupConfig) {
+    *CLOUD_BACKUP_CONFIG.write().unwrap_or_else(|e| e.into_inner()) = config;
+}
+
+pub fn cloud_backup_config() -> CloudBackupConfig {
+    CLOUD_BACKUP_CONFIG.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// 雲端上的一份備份
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CloudBackup {
+    pub key: String,
+    pub size: u64,
+    /// S3 回傳的 ISO 8601 時間
+    pub last_modified: String,
+}
+
+// ── 加密 ────────────────────────────────────────────────────────
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, PBKDF2_ROUNDS, &mut key);
+    key
+}
+
+/// 加密備份內容：`MAGIC || salt || nonce || ciphertext`
+pub fn encrypt_backup(plaintext: &[u8], passphrase: &str) -> Result<Vec<u8>, String> {
+    let mut salt = [0u8; SALT_LEN];
+    SystemRandom::new()
+        .fill(&mut salt)
+        .map_err(|_| "Failed to generate salt".to_string())?;
+    let key = derive_key(passphrase, &salt);
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|e| format!("Encryption failed: {}", e))?;
+    let mut out = Vec::with_capacity(MAGIC.len() + SALT_LEN + NONCE_LEN + ciphertext.len());
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&salt);
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+pub fn decrypt_backup(payload: &[u8], passphrase: &str) -> Result<Vec<u8>, String> {
+    let body = payload
+        .strip_prefix(MAGIC)
+        .ok_or_else(|| "Not a StockenBoard backup".to_string())?;
+    if body.len() <= SALT_LEN + NONCE_LEN {
+        return Err("Backup is truncated".to_string());
+    }
+    let (salt, rest) = body.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+    let key = derive_key(passphrase, salt);
+    Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key))
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "Decryption failed (wrong passphrase?)".to_string())
+}
+
+/// 備份明文：`u32 BE JSON 長度 || provider API key JSON || SQLite 快照`
+fn pack_snapshot(snapshot: &[u8], secrets: &[ExportedSecret]) -> Result<Vec<u8>, String> {
+    let json = serde_json::to_vec(secrets).map_err(|e| e.to_string())?;
+    let len = u32::try_from(json.len()).map_err(|_| "Too many API keys to back up".to_string())?;
+    let mut out = Vec::with_capacity(4 + json.len() + snapshot.len());
+    out.extend_from_slice(&len.to_be_bytes());
+    out.extend_from_slice(&json);
+    out.extend_from_slice(snapshot);
+    Ok(out)
+}
+
+/// 拆出快照與 API key；舊版備份（直接是 SQLite 檔）回傳 None
+fn unpack_snapshot(plaintext: Vec<u8>) -> Result<(Vec<u8>, Option<Vec<ExportedSecret>>), String> {
+    if plaintext.starts_with(SQLITE_HEADER) {
+        return Ok((plaintext, None));
+    }
+    let len = plaintext
+        .get(..4)
+        .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]) as usize)
+        .ok_or_else(|| "Backup is truncated".to_string())?;
+    let json = plaintext
+        .get(4..4 + len)
+        .ok_or_else(|| "Backup is truncated".to_string())?;
+    let secrets = serde_json::from_slice(json).map_err(|e| format!("Invalid API keys in backup: {}", e))?;
+    Ok((plaintext[4 + len..].to_vec(), Some(secrets)))
+}
+
+// ── S3（Signature V4）───────────────────────────────────────────
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    hex(&Sha256::digest(data))
+}
+
+/// SigV4 URI encoding（`encode_slash` 為 false 時保留路徑分隔符）
+fn uri_encode(s: &str, encode_slash: bool) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            b'/' if !encode_slash => "/".to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn signing_key(secret: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let sign = |key: &[u8], data: &str| hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, key), data.as_bytes());
+    let k_date = sign(format!("AWS4{}", secret).as_bytes(), date);
+    let k_region = sign(k_date.as_ref(), region);
+    let k_service = sign(k_region.as_ref(), service);
+    sign(k_service.as_ref(), "aws4_request").as_ref().to_vec()
+}
+
+/// 產生 SigV4 簽章後的 headers（`x-amz-date`、`x-amz-content-sha256`、`authorization`）
+fn sign_request(
+    config: &CloudBackupConfig,
+    method: &str,
+    canonical_uri: &str,
+    canonical_query: &str,
+    payload_hash: &str,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Vec<(&'static str, String)> {
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+    let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+    let canonical_request = format!(
+        "{}\n{}\n{}\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+        method,
+        canonical_uri,
+        canonical_query,
+        config.host(),
+        payload_hash,
+        amz_date,
+        signed_headers,
+        payload_hash
+    );
+    let scope = format!("{}/{}/s3/aws4_request", date, config.region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        sha256_hex(canonical_request.as_bytes())
+    );
+    let key = signing_key(&config.secret_access_key, &date, &config.region, "s3");
+    let signature = hex(hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, &key), string_to_sign.as_bytes()).as_ref());
+    vec![
+        ("x-amz-date", amz_date),
+        ("x-amz-content-sha256", payload_hash.to_string()),
+        (
+            "authorization",
+            format!(
+                "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+                config.access_key_id, scope, signed_headers, signature
+            ),
+        ),
+    ]
+}
+
+/// 送出簽章後的 S3 請求，回傳 body
+async fn s3_request(
+    config: &CloudBackupConfig,
+    method: reqwest::Method,
+    key: Option<&str>,
+    query: &[(&str, &str)],
+    body: Vec<u8>,
+) -> Result<Vec<u8>, String> {
+    let mut canonical_uri = format!("/{}", uri_encode(&config.bucket, true));
+    if let Some(key) = key {
+        canonical_uri.push('/');
+        canonical_uri.push_str(&uri_encode(key, false));
+    }
+    let mut query: Vec<(String, String)> = query
+        .iter()
+        .map(|(k, v)| (uri_encode(k, true), uri_encode(v, true)))
+        .collect();
+    query.sort();
+    let canonical_query = query.iter().map(|(k, v)| format!("{}={}", k, v)).collect::<Vec<_>>().join("&");
+
+    let payload_hash = sha256_hex(&body);
+    let headers = sign_request(
+        config,
+        method.as_str(),
+        &canonical_uri,
+        &canonical_query,
+        &payload_hash,
+        chrono::Utc::now(),
+    );
+    let mut url = format!("{}{}", config.endpoint, canonical_uri);
+    if !canonical_query.is_empty() {
+        url.push('?');
+        url.push_str(&canonical_query);
+    }
+
+    let mut request = crate::providers::shared_client()
+        .request(method, url)
+        .timeout(Duration::from_secs(300));
+    for (name, value) in headers {
+        request = request.header(name, value);
+    }
+    let resp = request
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| format!("Backup storage request failed: {}", e))?;
+    let status = resp.status();
+    let bytes = resp
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to read backup storage response: {}", e))?;
+    if !status.is_success() {
+        let text = String::from_utf8_lossy(&bytes);
+        let code = xml_values(&text, "Code").into_iter().next().unwrap_or_default();
+        return Err(format!("Backup storage returned HTTP {} {}", status.as_u16(), code).trim_end().to_string());
+    }
+    Ok(bytes.to_vec())
+}
+
+/// 取出 XML 中所有 `<tag>value</tag>` 的值（S3 回應結構單純，不需要完整的 XML parser）
+fn xml_values(xml: &str, tag: &str) -> Vec<String> {
+    let (open, close) = (format!("<{}>", tag), format!("</{}>", tag));
+    let mut values = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find(&open) {
+        rest = &rest[start + open.len()..];
+        let Some(end) = rest.find(&close) else {
+            break;
+        };
+        values.push(
+            rest[..end]
+                .replace("&lt;", "<")
+                .replace("&gt;", ">")
+                .replace("&quot;", "\"")
+                .replace("&apos;", "'")
+                .replace("&amp;", "&"),
+        );
+        rest = &rest[end + close.len()..];
+    }
+    values
+}
+
+/// 解析 `ListObjectsV2` 回應，只保留副檔名為 `extension` 的物件，新到舊排序
+fn parse_list(xml: &str, extension: &str) -> Vec<CloudBackup> {
+    let mut backups: Vec<CloudBackup> = xml_values(xml, "Contents")
+        .iter()
+        .filter_map(|entry| {
+            let key = xml_values(entry, "Key").into_iter().next()?;
+            if !key.ends_with(extension) {
+                return None;
+            }
+            Some(CloudBackup {
+                key,
+                size: xml_values(entry, "Size").first().and_then(|s| s.parse().ok()).unwrap_or(0),
+                last_modified: xml_values(entry, "LastModified").into_iter().next().unwrap_or_default(),
+            })
+        })
+        .collect();
+    // key 內含時間戳，字典序即時間序
+    backups.sort_by(|a, b| b.key.cmp(&a.key));
+    backups
+}
+
+/// 列出雲端上的備份（新到舊；單次最多 1000 筆）
+pub async fn list_backups(config: &CloudBackupConfig) -> Result<Vec<CloudBackup>, String> {
+    list_objects(config, EXTENSION).await
+}
+
+async fn list_objects(config: &CloudBackupConfig, extension: &str) -> Result<Vec<CloudBackup>, String> {
+    config.check_target()?;
+    let body = s3_request(
+        config,
+        reqwest::Method::GET,
+        None,
+        &[("list-type", "2"), ("prefix", &config.prefix)],
+        Vec::new(),
+    )
+    .await?;
+    Ok(parse_list(&String::from_utf8_lossy(&body), extension))
+}
+
+// ── 備份 / 還原 ─────────────────────────────────────────────────
+
+/// 每次呼叫都產生不同的暫存檔名，排程備份與手動備份 / 還原同時執行時不會互相覆寫
+fn temp_path(data_dir: &Path, name: &str) -> PathBuf {
+    static SEQ: AtomicU64 = AtomicU64::new(0);
+    let path = data_dir.join(format!(
+        "{}-{}-{}.tmp.db",
+        name,
+        std::process::id(),
+        SEQ.fetch_add(1, Ordering::Relaxed)
+    ));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn object_key(config: &CloudBackupConfig, name: &str, extension: &str) -> String {
+    format!(
+        "{}{}-{}{}",
+        config.prefix,
+        name,
+        chrono::Utc::now().format("%Y%m%d-%H%M%S"),
+        extension
+    )
+}
+
+/// 立即備份：產生快照、加密上傳並刪除超過保留份數的舊備份
+pub async fn backup_now(db: &DbPool, data_dir: &Path, config: &CloudBackupConfig) -> Result<CloudBackup, String> {
+    config.check_target()?;
+    let path = temp_path(data_dir, "cloud-backup");
+    let snapshot = db.write_snapshot(&path).and_then(|_| {
+        std::fs::read(&path).map_err(|e| format!("Failed to read DB snapshot: {}", e))
+    });
+    let _ = std::fs::remove_file(&path);
+    let plaintext = pack_snapshot(&snapshot?, &db.export_secrets()?)?;
+    let payload = encrypt_backup(&plaintext, &config.passphrase)?;
+
+    let key = object_key(config, "stockenboard", EXTENSION);
+    let size = payload.len() as u64;
+    s3_request(config, reqwest::Method::PUT, Some(&key), &[], payload).await?;
+    db.set_setting(LAST_BACKUP_SETTING, &chrono::Utc::now().timestamp().to_string())?;
+    tracing::info!("[CloudBackup] Uploaded {} ({} bytes)", key, size);
+
+    match list_backups(config).await {
+        Ok(backups) => {
+            for old in backups.iter().skip(config.retain as usize) {
+                if let Err(e) = s3_request(config, reqwest::Method::DELETE, Some(&old.key), &[], Vec::new()).await {
+                    tracing::warn!("[CloudBackup] Failed to delete old backup {}: {}", old.key, e);
+                }
+            }
+        }
+        Err(e) => tracing::warn!("[CloudBackup] Failed to list backups for retention: {}", e),
+    }
+    if let Err(e) = archive_history(db, data_dir, config).await {
+        tracing::warn!("[CloudBackup] Failed to upload history archive: {}", e);
+    }
+
+    Ok(CloudBackup {
+        key,
+        size,
+        last_modified: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+    })
+}
+
+/// 下載並解密備份，以其內容取代目前的 DB
+pub async fn restore(db: &DbPool, data_dir: &Path, config: &CloudBackupConfig, key: &str) -> Result<(), String> {
+    config.check_target()?;
+    if !key.starts_with(&config.prefix) || !key.ends_with(EXTENSION) {
+        return Err(format!("'{}' is not a backup under the configured prefix", key));
+    }
+    let payload = s3_request(config, reqwest::Method::GET, Some(key), &[], Vec::new()).await?;
+    let (snapshot, secrets) = unpack_snapshot(decrypt_backup(&payload, &config.passphrase)?)?;
+
+    let path = temp_path(data_dir, "cloud-restore");
+    let result = std::fs::write(&path, snapshot)
+        .map_err(|e| format!("Failed to write DB snapshot: {}", e))
+        .and_then(|_| db.restore_snapshot(&path));
+    let _ = std::fs::remove_file(&path);
+    result?;
+    match secrets {
+        Some(secrets) => {
+            let restored = db.replace_provider_secrets(&secrets)?;
+            tracing::info!("[CloudBackup] Restored API keys for {} provider(s)", restored);
+        }
+        None => tracing::warn!(
+            "[CloudBackup] {} was made before API keys were included in backups; keys encrypted on another device must be re-entered",
+            key
+        ),
+    }
+    let imported = import_history_archives(db, data_dir, config).await;
+    tracing::info!("[CloudBackup] Restored {} ({} archived price record(s) re-imported)", key, imported);
+    Ok(())
+}
+
+/// 上傳上次封存後新增的價格紀錄（沒有新紀錄時不上傳）
+async fn archive_history(db: &DbPool, data_dir: &Path, config: &CloudBackupConfig) -> Result<(), String> {
+    let after = db
+        .get_setting(HISTORY_ARCHIVED_SETTING)?
+        .and_then(|v| v.parse::<i64>().ok())
+        .unwrap_or(0);
+    let until = chrono::Utc::now().timestamp();
+    let path = temp_path(data_dir, "cloud-history");
+    let archive = db.write_history_archive(&path, after, until).and_then(|rows| match rows {
+        0 => Ok(None),
+        _ => std::fs::read(&path)
+            .map(Some)
+            .map_err(|e| format!("Failed to read history archive: {}", e)),
+    });
+    let _ = std::fs::remove_file(&path);
+    let Some(archive) = archive? else {
+        return Ok(());
+    };
+
+    let key = object_key(config, "stockenboard-history", HISTORY_EXTENSION);
+    let payload = encrypt_backup(&archive, &config.passphrase)?;
+    let size = payload.len();
+    s3_request(config, reqwest::Method::PUT, Some(&key), &[], payload).await?;
+    db.set_setting(HISTORY_ARCHIVED_SETTING, &until.to_string())?;
+    tracing::info!("[CloudBackup] Uploaded history archive {} ({} bytes)", key, size);
+    Ok(())
+}
+
+/// 匯入雲端上所有價格紀錄封存檔，回傳新增筆數；單一封存檔失敗只記錄警告
+async fn import_history_archives(db: &DbPool, data_dir: &Path, config: &CloudBackupConfig) -> usize {
+    let archives = match list_objects(config, HISTORY_EXTENSION).await {
+        Ok(archives) => archives,
+        Err(e) => {
+            tracing::warn!("[CloudBackup] Failed to list history archives: {}", e);
+            return 0;
+        }
+    };
+    let mut imported = 0;
+    for archive in archives {
+        let result = async {
+            let payload = s3_request(config, reqwest::Method::GET, Some(&archive.key), &[], Vec::new()).await?;
+            let plaintext = decrypt_backup(&payload, &config.passphrase)?;
+            let path = temp_path(data_dir, "cloud-history-restore");
+            let result = std::fs::write(&path, plaintext)
+                .map_err(|e| format!("Failed to write history archive: {}", e))
+                .and_then(|_| db.import_history_archive(&path));
+            let _ = std::fs::remove_file(&path);
+            result
+        }
+        .await;
+        match result {
+            Ok(n) => imported += n,
+            Err(e) => tracing::warn!("[CloudBackup] Failed to import {}: {}", archive.key, e),
+        }
+    }
+    imported
+}
+
+/// 啟動排程備份（未啟用時只會閒置）
+pub fn start(db: Arc<DbPool>, data_dir: PathBuf) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(Duration::from_secs(CHECK_INTERVAL_SECS)).await;
+            let config = cloud_backup_config();
+            if !config.enabled {
+                continue;
+            }
+            let last = db
+                .get_setting(LAST_BACKUP_SETTING)
+                .ok()
+                .flatten()
+                .and_then(|v| v.parse::<i64>().ok())
+                .unwrap_or(0);
+            if chrono::Utc::now().timestamp() - last < config.interval_hours as i64 * 3600 {
+                continue;
+            }
+            if let Err(e) = backup_now(&db, &data_dir, &config).await {
+                tracing::warn!("[CloudBackup] Scheduled backup failed: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target() -> CloudBackupConfig {
+        CloudBackupConfig {
+            enabled: true,
+            endpoint: " https://minio.example.com:9000/ ".to_string(),
+            region: String::new(),
+            bucket: "backups".to_string(),
+            prefix: "/recorder".to_string(),
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_string(),
+            passphrase: "correct horse".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn config_validation() {
+        let config = target().normalized().unwrap();
+        assert_eq!(config.endpoint, "https://minio.example.com:9000");
+        assert_eq!(config.host(), "minio.example.com:9000");
+        assert_eq!((config.region.as_str(), config.prefix.as_str()), ("us-east-1", "recorder/"));
+        assert!(config.clone().redacted().passphrase.is_empty());
+
+        assert!(CloudBackupConfig { passphrase: "short".to_string(), ..target() }.normalized().is_err());
+        assert!(CloudBackupConfig { retain: 0, ..target() }.normalized().is_err());
+        // 未啟用時允許不完整的設定
+        assert!(CloudBackupConfig { enabled: false, bucket: String::new(), ..target() }.normalized().is_ok());
+    }
+
+    #[test]
+    fn backup_encryption_roundtrip() {
+        let payload = encrypt_backup(b"SQLite format 3", "correct horse").unwrap();
+        assert!(payload.starts_with(MAGIC));
+        assert_eq!(decrypt_backup(&payload, "correct horse").unwrap(), b"SQLite format 3");
+        assert!(decrypt_backup(&payload, "wrong horse").is_err());
+        assert!(decrypt_backup(b"SQLite format 3", "correct horse").is_err());
+    }
+
+    #[test]
+    fn snapshot_bundles_api_keys() {
+        let secrets = vec![ExportedSecret {
+            provider_id: "alpaca".to_string(),
+            api_key: Some("key-1".to_string()),
+            api_secret: None,
+        }];
+        let (snapshot, restored) = unpack_snapshot(pack_snapshot(b"SQLite format 3\0db", &secrets).unwrap()).unwrap();
+        assert_eq!(snapshot, b"SQLite format 3\0db");
+        assert_eq!(restored.unwrap()[0].api_key.as_deref(), Some("key-1"));
+
+        // 舊版備份只有 SQLite 快照
+        let (snapshot, restored) = unpack_snapshot(b"SQLite format 3\0db".to_vec()).unwrap();
+        assert_eq!(snapshot, b"SQLite format 3\0db");
+        assert!(restored.is_none());
+        assert!(unpack_snapshot(vec![0, 0, 0, 9, b'[']).is_err());
+    }
+
+    #[test]
+    fn sigv4_signing_key_matches_aws_example() {
+        // AWS 文件的 signing key 範例
+        let key = signing_key("wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY", "20120215", "us-east-1", "iam");
+        assert_eq!(hex(&key), "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d");
+        assert_eq!(uri_encode("a b/c+d.sbbak", false), "a%20b/c%2Bd.sbbak");
+    }
+
+    #[test]
+    fn list_response_keeps_backups_newest_first() {
+        let xml = r#"<ListBucketResult>
+            <Contents><Key>recorder/stockenboard-20261001-000000.sbbak</Key><Size>100</Size><LastModified>2026-10-01T00:00:00.000Z</LastModified></Contents>
+            <Contents><Key>recorder/notes.txt</Key><Size>5</Size></Contents>
+            <Contents><Key>recorder/stockenboard-history-20261002-000000.sbhist</Key><Size>50</Size></Contents>
+            <Contents><Key>recorder/stockenboard-20261002-000000.sbbak</Key><Size>120</Size><LastModified>2026-10-02T00:00:00.000Z</LastModified></Contents>
+        </ListBucketResult>"#;
+        let backups = parse_list(xml, EXTENSION);
+        assert_eq!(backups.len(), 2);
+        assert_eq!(backups[0].key, "recorder/stockenboard-20261002-000000.sbbak");
+        assert_eq!(backups[0].size, 120);
+        assert_eq!(backups[1].last_modified, "2026-10-01T00:00:00.000Z");
+        assert_eq!(parse_list(xml, HISTORY_EXTENSION).len(), 1);
+    }
+}
@@ -0,0 +1,60 @@
+use super::{CommandError, CommandResult, ErrorCode};
+use crate::core_state::CoreState;
+use crate::i18n::{LocalizedError, Msg};
+use std::sync::Arc;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+/// 複製單行報價到剪貼簿；優先使用 polling 快取，沒有時即時向 provider 查詢。回傳複製的文字。
+#[tauri::command]
+pub async fn copy_price_to_clipboard(
+    state: tauri::State<'_, Arc<CoreState>>,
+    app: tauri::AppHandle,
+    provider_id: String,
+    symbol: String,
+) -> CommandResult<String> {
+    let cached = state
+        .polling
+        .cache
+        .read()
+        .await
+        .get(&format!("{}:{}", provider_id, symbol))
+        .cloned();
+    let data = match cached {
+        Some(data) => data,
+        None => {
+            let p = state
+                .registry
+                .get_or_create(&provider_id, &state.db)
+                .await
+                .ok_or_else(|| {
+                    CommandError::localized(
+                        ErrorCode::NotFound,
+                        LocalizedError::new(Msg::ProviderNotFound, &[&provider_id]),
+                    )
+                })?;
+            p.fetch_price(&symbol).await?
+        }
+    };
+    let text = crate::clipboard::quote_text(&data);
+    app.clipboard()
+        .write_text(text.clone())
+        .map_err(|e| format!("Failed to write clipboard: {}", e))?;
+    Ok(text)
+}
+
+/// 複製一段價格歷史（CSV，含標題列）到剪貼簿。`from` / `to` 為 unix 秒。回傳複製的筆數。
+#[tauri::command]
+pub async fn copy_history_csv_to_clipboard(
+    state: tauri::State<'_, Arc<CoreState>>,
+    app: tauri::AppHandle,
+    subscription_id: i64,
+    from: Option<i64>,
+    to: Option<i64>,
+) -> CommandResult<usize> {
+    let csv = crate::clipboard::history_csv(&state.db, subscription_id, from, to)?;
+    let rows = csv.lines().count().saturating_sub(1);
+    app.clipboard()
+        .write_text(csv)
+        .map_err(|e| format!("Failed to write clipboard: {}", e))?;
+    Ok(rows)
+}
@@ -1,179 +1,108 @@
+use super::{CommandError, CommandResult};
 use crate::core_state::CoreState;
-use crate::db::ExportData;
+use crate::db::{AppConfig, ConfigImportMode, ConfigImportSummary, ExportData};
+use crate::file_export::{self, ExportDataset, ExportFormat};
 use std::sync::Arc;
 
+/// 存檔對話框匯出：給 `content` 時直接寫入（JSON），給 `dataset` 時由後端依 `format` 產生 JSON／CSV／XLSX
 #[tauri::command]
-pub async fn export_file(filename: String, content: String) -> Result<(), String> {
+pub async fn export_file(
+    state: tauri::State<'_, Arc<CoreState>>,
+    filename: String,
+    content: Option<String>,
+    format: Option<ExportFormat>,
+    dataset: Option<ExportDataset>,
+) -> CommandResult<()> {
+    let format = format.unwrap_or_default();
+    let bytes = match (dataset, content) {
+        (Some(dataset), _) => file_export::render(&state.db, &dataset, format)?,
+        (None, Some(content)) => content.into_bytes(),
+        (None, None) => return Err(CommandError::bad_request("Nothing to export")),
+    };
     let path = rfd::AsyncFileDialog::new()
         .set_file_name(&filename)
-        .add_filter("JSON", &["json"])
+        .add_filter(format.filter_name(), &[format.extension()])
         .save_file()
         .await
-        .ok_or_else(|| "Cancelled".to_string())?;
-    tokio::fs::write(path.path(), content.as_bytes())
+        .ok_or_else(CommandError::cancelled)?;
+    tokio::fs::write(path.path(), bytes)
         .await
-        .map_err(|e| format!("Write failed: {}", e))
+        .map_err(|e| CommandError::from(format!("Write failed: {}", e)))
 }
 
 #[tauri::command]
-pub async fn import_file() -> Result<String, String> {
+pub async fn import_file(state: tauri::State<'_, Arc<CoreState>>) -> CommandResult<String> {
     let file = rfd::AsyncFileDialog::new()
         .add_filter("JSON", &["json"])
         .pick_file()
         .await
-        .ok_or_else(|| "Cancelled".to_string())?;
-    String::from_utf8(file.read().await).map_err(|e| format!("Read failed: {}", e))
+        .ok_or_else(CommandError::cancelled)?;
+    state.file_access.record_pick(file.path());
+    String::from_utf8(file.read().await).map_err(|e| CommandError::from(format!("Read failed: {}", e)))
 }
 
 #[tauri::command]
-pub async fn export_data(state: tauri::State<'_, Arc<CoreState>>) -> Result<ExportData, String> {
-    state.db.export_data()
+pub async fn export_data(state: tauri::State<'_, Arc<CoreState>>) -> CommandResult<ExportData> {
+    Ok(state.db.export_data()?)
 }
 
 #[tauri::command]
 pub async fn import_data(
     state: tauri::State<'_, Arc<CoreState>>,
     data: ExportData,
-) -> Result<(usize, usize), String> {
+) -> CommandResult<(usize, usize)> {
     let result = state.db.import_data(&data)?;
     state.polling.reload();
     Ok(result)
 }
 
+/// 匯出完整 app 設定（版本化 JSON）；預設不含 API key / secret
 #[tauri::command]
-pub async fn reset_all_data(state: tauri::State<'_, Arc<CoreState>>) -> Result<(), String> {
-    state.db.reset_all_data()?;
-    state.notification_engine.reload_rules().await;
-    state.polling.reload();
-    Ok(())
-}
-
-// ── Price History ────────────────────────────────────────────────
-
-#[derive(serde::Serialize)]
-pub struct ToggleRecordResponse {
-    pub success: bool,
-    pub needs_confirm: bool,
-}
-
-#[tauri::command]
-pub async fn toggle_record(
-    state: tauri::State<'_, Arc<CoreState>>,
-    subscription_id: i64,
-    enabled: bool,
-    confirmed: Option<bool>,
-) -> Result<ToggleRecordResponse, String> {
-    if enabled {
-        let active_count = state.db.count_active_recordings()?;
-        if active_count == 0 && confirmed != Some(true) {
-            // First recording: require confirmation
-            return Ok(ToggleRecordResponse { success: false, needs_confirm: true });
-        }
-        // Enable recording
-        state.db.toggle_record(subscription_id, true)?;
-        // Enable unattended if transitioning from 0
-        if active_count == 0 {
-            state.polling.set_unattended(true).await;
-        }
-    } else {
-        // Disable recording
-        state.db.toggle_record(subscription_id, false)?;
-        // Check if this was the last active recording
-        let remaining = state.db.count_active_recordings()?;
-        if remaining == 0 {
-            state.polling.set_unattended(false).await;
-        }
-    }
-    state.polling.reload();
-    Ok(ToggleRecordResponse { success: true, needs_confirm: false })
-}
-
-#[tauri::command]
-pub async fn set_record_hours(
+pub async fn export_app_config(
     state: tauri::State<'_, Arc<CoreState>>,
-    subscription_id: i64,
-    from_hour: Option<i64>,
-    to_hour: Option<i64>,
-) -> Result<(), String> {
-    state
-        .db
-        .set_record_hours(subscription_id, from_hour, to_hour)
+    include_secrets: Option<bool>,
+) -> CommandResult<AppConfig> {
+    Ok(state.db.export_app_config(include_secrets.unwrap_or(false))?)
 }
 
-/// Retained for external HTTP API consumers — not invoked by frontend UI
 #[tauri::command]
-pub async fn set_provider_record_hours(
+pub async fn import_app_config(
     state: tauri::State<'_, Arc<CoreState>>,
-    provider_id: String,
-    from_hour: Option<i64>,
-    to_hour: Option<i64>,
-) -> Result<(), String> {
-    state
-        .db
-        .set_provider_record_hours(&provider_id, from_hour, to_hour)
+    config: AppConfig,
+    mode: Option<ConfigImportMode>,
+) -> CommandResult<ConfigImportSummary> {
+    Ok(state
+        .import_app_config(&config, mode.unwrap_or_default())
+        .await?)
 }
 
 #[tauri::command]
-pub async fn get_price_history(
-    state: tauri::State<'_, Arc<CoreState>>,
-    subscription_id: i64,
-    from_ts: i64,
-    to_ts: i64,
-    limit: Option<i64>,
-) -> Result<Vec<crate::db::PriceHistoryRow>, String> {
-    state.db.get_price_history(
-        subscription_id,
-        Some(from_ts),
-        Some(to_ts),
-        limit.unwrap_or(10000),
-    )
-}
-
-#[tauri::command]
-pub async fn get_history_stats(
-    state: tauri::State<'_, Arc<CoreState>>,
-    subscription_ids: Vec<i64>,
-) -> Result<Vec<HistoryStatsResult>, String> {
-    let mut results = Vec::new();
-    for sid in subscription_ids {
-        let stats = state.db.get_history_stats(sid)?;
-        results.push(HistoryStatsResult {
-            subscription_id: sid,
-            total_records: stats.total,
-            earliest: stats.oldest,
-            latest: stats.newest,
-        });
-    }
-    Ok(results)
-}
-
-#[derive(serde::Serialize)]
-pub struct HistoryStatsResult {
-    pub subscription_id: i64,
-    pub total_records: i64,
-    pub earliest: Option<i64>,
-    pub latest: Option<i64>,
-}
-
-#[tauri::command]
-pub async fn cleanup_history(
-    state: tauri::State<'_, Arc<CoreState>>,
-    retention_days: Option<i64>,
-) -> Result<i64, String> {
-    let days = retention_days.unwrap_or(90);
-    let cutoff = chrono::Utc::now().timestamp() - (days * 86400);
-    state.db.cleanup_history(cutoff)
-}
-
-#[tauri::command]
-pub async fn purge_all_history(state: tauri::State<'_, Arc<CoreState>>) -> Result<i64, String> {
-    state.db.purge_all_history()
-}
-
-#[tauri::command]
-pub async fn delete_subscription_history(
-    state: tauri::State<'_, Arc<CoreState>>,
-    subscription_id: i64,
-) -> Result<i64, String> {
-    state.db.delete_history_for_subscription(subscription_id)
+pub async fn reset_all_data(state: tauri::State<'_, Arc<CoreState>>) -> CommandResult<()> {
+    state.db.reset_all_data()?;
+    state.registry.clear().await;
+    crate::providers::set_global_proxy(None);
+    crate::providers::mock::set_mock_config(Default::default());
+    crate::providers::replay::set_replay_config(Default::default());
+    state.polling.set_recording_paused(false);
+    crate::power::set_power_config(Default::default());
+    crate::gas::set_gas_config(Default::default());
+    crate::depeg::set_depeg_config(Default::default());
+    crate::calendar::set_calendar_config(Default::default());
+    crate::sanity::set_sanity_config(Default::default());
+    crate::cache_limit::set_cache_config(Default::default());
+    crate::emit_throttle::set_emit_config(Default::default());
+    crate::record_health::set_record_stall_config(Default::default());
+    crate::latency_tune::set_latency_tune_config(Default::default());
+    crate::ws_first::set_ws_first_config(Default::default());
+    crate::candles::set_candle_config(Default::default());
+    crate::telemetry::set_otlp_config(Default::default());
+    crate::cloud_backup::set_cloud_backup_config(Default::default());
+    crate::settings_sync::set_sync_config(Default::default());
+    crate::i18n::set_locale(Default::default());
+    crate::kiosk::set_kiosk_state(Default::default());
+    crate::lan_listener::set_lan_listener_config(Default::default());
+    crate::api::restart_lan_listener(state.inner().clone());
+    state.notification_engine.reload_rules().await;
+    state.polling.reload();
+    Ok(())
 }
@@ -0,0 +1,88 @@
+//! 指令共用的錯誤型別。
+//!
+//! 前端收到 `{ code, message, key? }`：`code` 與 REST API 錯誤的 `error.code` 相同，
+//! 可依 code 分支而不必比對訊息字串；`key` 為 [`crate::i18n`] 的訊息 key（僅已翻譯的訊息）。
+//! 核心模組沿用 `Result<_, String>`，`?` 時轉成 `internal_error`；DB 寫入路徑回傳的
+//! [`DbError::Busy`] 轉成 `db_busy`（依型別判斷，不比對訊息文字）。
+
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::db::DbError;
+use crate::file_access::FileAccessError;
+use crate::i18n::{LocalizedError, Msg};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    BadRequest,
+    NotFound,
+    Forbidden,
+    /// 使用者關閉檔案對話框
+    Cancelled,
+    /// 資料庫重試後仍忙碌
+    DbBusy,
+    #[serde(rename = "internal_error")]
+    Internal,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CommandError {
+    pub code: ErrorCode,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<Msg>,
+}
+
+pub type CommandResult<T> = Result<T, CommandError>;
+
+impl CommandError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self { code, message: message.into(), key: None }
+    }
+
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::BadRequest, message)
+    }
+
+    pub fn cancelled() -> Self {
+        Self::new(ErrorCode::Cancelled, "Cancelled")
+    }
+
+    /// 帶翻譯訊息與 key 的錯誤
+    pub fn localized(code: ErrorCode, err: LocalizedError) -> Self {
+        Self { code, message: err.message, key: Some(err.key) }
+    }
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        Self::new(ErrorCode::Internal, message)
+    }
+}
+
+impl From<DbError> for CommandError {
+    fn from(e: DbError) -> Self {
+        match e {
+            DbError::Busy(e) => Self::localized(ErrorCode::DbBusy, e),
+            DbError::Other(message) => Self::new(ErrorCode::Internal, message),
+        }
+    }
+}
+
+impl From<FileAccessError> for CommandError {
+    fn from(e: FileAccessError) -> Self {
+        let code = match e {
+            FileAccessError::Denied(_) => ErrorCode::Forbidden,
+            FileAccessError::Io(_) => ErrorCode::NotFound,
+        };
+        Self::new(code, e.to_string())
+    }
+}
@@ -0,0 +1,255 @@
+use super::{CommandError, CommandResult};
+use crate::core_state::CoreState;
+use stockenboard_api_types::prices::HistoryStatsResult;
+use std::sync::Arc;
+
+#[derive(serde::Serialize)]
+pub struct ToggleRecordResponse {
+    pub success: bool,
+    pub needs_confirm: bool,
+}
+
+#[tauri::command]
+pub async fn toggle_record(
+    state: tauri::State<'_, Arc<CoreState>>,
+    subscription_id: i64,
+    enabled: bool,
+    confirmed: Option<bool>,
+) -> CommandResult<ToggleRecordResponse> {
+    if enabled {
+        let active_count = state.db.count_active_recordings()?;
+        if active_count == 0 && confirmed != Some(true) {
+            // First recording: require confirmation
+            return Ok(ToggleRecordResponse { success: false, needs_confirm: true });
+        }
+        // Enable recording
+        state.db.toggle_record(subscription_id, true)?;
+        // Enable unattended if transitioning from 0
+        if active_count == 0 {
+            state.polling.set_unattended(true).await;
+        }
+    } else {
+        // Disable recording
+        state.db.toggle_record(subscription_id, false)?;
+        // Check if this was the last active recording
+        let remaining = state.db.count_active_recordings()?;
+        if remaining == 0 {
+            state.polling.set_unattended(false).await;
+        }
+    }
+    state.polling.reload();
+    Ok(ToggleRecordResponse { success: true, needs_confirm: false })
+}
+
+#[tauri::command]
+pub async fn set_record_hours(
+    state: tauri::State<'_, Arc<CoreState>>,
+    subscription_id: i64,
+    from_hour: Option<i64>,
+    to_hour: Option<i64>,
+) -> CommandResult<()> {
+    state
+        .db
+        .set_record_hours(subscription_id, from_hour, to_hour)?;
+    // 紀錄時段在 polling 重新載入時解析
+    state.polling.reload();
+    Ok(())
+}
+
+/// 設定訂閱的紀錄去重間隔（秒）；None 為預設 5 秒，0 為每次輪詢都紀錄
+#[tauri::command]
+pub async fn set_record_dedup(
+    state: tauri::State<'_, Arc<CoreState>>,
+    subscription_id: i64,
+    dedup_secs: Option<i64>,
+) -> CommandResult<()> {
+    state.db.set_record_dedup(subscription_id, dedup_secs)?;
+    // 去重間隔隨紀錄目標在 polling 重新載入時解析
+    state.polling.reload();
+    Ok(())
+}
+
+/// Retained for external HTTP API consumers — not invoked by frontend UI
+#[tauri::command]
+pub async fn set_provider_record_hours(
+    state: tauri::State<'_, Arc<CoreState>>,
+    provider_id: String,
+    from_hour: Option<i64>,
+    to_hour: Option<i64>,
+) -> CommandResult<()> {
+    state
+        .db
+        .set_provider_record_hours(&provider_id, from_hour, to_hour)?;
+    state.polling.reload();
+    Ok(())
+}
+
+/// 查詢紀錄時段：給 `subscription_id` 時含訂閱自己的設定，否則查 `provider_id` 的設定；
+/// 回傳依「訂閱 > provider > 全天」解析後的生效時段
+#[tauri::command]
+pub async fn get_record_hours(
+    state: tauri::State<'_, Arc<CoreState>>,
+    provider_id: Option<String>,
+    subscription_id: Option<i64>,
+) -> CommandResult<crate::db::RecordHours> {
+    match (subscription_id, provider_id) {
+        (Some(id), _) => Ok(state.db.get_record_hours(id)?),
+        (None, Some(provider_id)) => Ok(state.db.get_provider_record_hours(&provider_id)?),
+        (None, None) => Err(CommandError::bad_request("Either provider_id or subscription_id is required")),
+    }
+}
+
+/// 清除訂閱或 provider 的紀錄時段（改為繼承 provider 設定 / 全天紀錄）
+#[tauri::command]
+pub async fn clear_record_hours(
+    state: tauri::State<'_, Arc<CoreState>>,
+    provider_id: Option<String>,
+    subscription_id: Option<i64>,
+) -> CommandResult<()> {
+    match (subscription_id, provider_id) {
+        (Some(id), _) => state.db.set_record_hours(id, None, None)?,
+        (None, Some(provider_id)) => state.db.clear_provider_record_hours(&provider_id)?,
+        (None, None) => return Err(CommandError::bad_request("Either provider_id or subscription_id is required")),
+    }
+    state.polling.reload();
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_price_history(
+    state: tauri::State<'_, Arc<CoreState>>,
+    subscription_id: i64,
+    from_ts: i64,
+    to_ts: i64,
+    limit: Option<i64>,
+) -> CommandResult<Vec<crate::db::PriceHistoryRow>> {
+    Ok(state.db.get_price_history(
+        subscription_id,
+        Some(from_ts),
+        Some(to_ts),
+        limit.unwrap_or(10000),
+    )?)
+}
+
+/// 訂閱的 provider 切換 / symbol 變更紀錄，供圖表標註資料來源切換點
+#[tauri::command]
+pub async fn get_subscription_events(
+    state: tauri::State<'_, Arc<CoreState>>,
+    subscription_id: i64,
+    from_ts: Option<i64>,
+    to_ts: Option<i64>,
+) -> CommandResult<Vec<crate::db::SubscriptionEvent>> {
+    Ok(state.db.list_subscription_events(subscription_id, from_ts, to_ts)?)
+}
+
+#[tauri::command]
+pub async fn get_history_stats(
+    state: tauri::State<'_, Arc<CoreState>>,
+    subscription_ids: Vec<i64>,
+) -> CommandResult<Vec<HistoryStatsResult>> {
+    Ok(subscription_ids
+        .into_iter()
+        .map(|sid| crate::record_health::history_stats(&state.db, sid))
+        .collect::<Result<Vec<_>, String>>()?)
+}
+
+/// 連漲 / 連跌天數、最大回撤與回復時間
+#[tauri::command]
+pub async fn get_streaks(
+    state: tauri::State<'_, Arc<CoreState>>,
+    subscription_id: i64,
+) -> CommandResult<crate::streaks::StreakStats> {
+    Ok(crate::streaks::load(&state.db, subscription_id)?)
+}
+
+/// 當日 Session VWAP；給 `anchor`（Unix 秒）時另計算從該時間起的 Anchored VWAP
+#[tauri::command]
+pub async fn get_vwap(
+    state: tauri::State<'_, Arc<CoreState>>,
+    subscription_id: i64,
+    anchor: Option<i64>,
+) -> CommandResult<crate::indicators::VwapReport> {
+    Ok(crate::indicators::load(&state.db, subscription_id, anchor, chrono::Utc::now().timestamp())?)
+}
+
+/// 卡片走勢線：`window`（如 `24h`、`7d`）內的紀錄縮成最多 `points` 個點
+#[tauri::command]
+pub async fn get_sparkline(
+    state: tauri::State<'_, Arc<CoreState>>,
+    subscription_id: i64,
+    points: Option<usize>,
+    window: Option<String>,
+) -> CommandResult<crate::sparkline::Sparkline> {
+    let window_secs = crate::sparkline::parse_window(window.as_deref().unwrap_or(crate::sparkline::DEFAULT_WINDOW))
+        .map_err(CommandError::bad_request)?;
+    Ok(crate::sparkline::load(&state.db, subscription_id, points, window_secs, chrono::Utc::now().timestamp())?)
+}
+
+/// 最近 `window`（如 `15m`、`1h`）內紀錄的 TWAP，每筆價格以持續時間加權
+#[tauri::command]
+pub async fn get_twap(
+    state: tauri::State<'_, Arc<CoreState>>,
+    subscription_id: i64,
+    window: Option<String>,
+) -> CommandResult<crate::indicators::TwapReport> {
+    let window_secs =
+        crate::sparkline::parse_window(window.as_deref().unwrap_or(crate::indicators::DEFAULT_TWAP_WINDOW))
+            .map_err(CommandError::bad_request)?;
+    Ok(crate::indicators::load_twap(&state.db, subscription_id, window_secs, chrono::Utc::now().timestamp())?)
+}
+
+/// 已保存的 WS 1 分鐘 K 線（`from` / `to` 為分鐘起點 Unix 秒，含），依時間排序
+#[tauri::command]
+pub async fn get_candles(
+    state: tauri::State<'_, Arc<CoreState>>,
+    provider_id: String,
+    symbol: String,
+    from: Option<i64>,
+    to: Option<i64>,
+    limit: Option<usize>,
+) -> CommandResult<Vec<crate::candles::Candle>> {
+    let limit = limit
+        .unwrap_or(crate::candles::DEFAULT_QUERY_LIMIT)
+        .min(crate::candles::MAX_QUERY_LIMIT);
+    Ok(state.db.get_candles(&provider_id, &symbol, from, to, limit)?)
+}
+
+/// 從外部 CSV（timestamp, price, volume）匯入訂閱的價格紀錄；`dry_run` 時只回傳預覽與統計
+#[tauri::command]
+pub async fn import_history_csv(
+    state: tauri::State<'_, Arc<CoreState>>,
+    subscription_id: i64,
+    content: String,
+    dry_run: Option<bool>,
+) -> CommandResult<crate::history_csv::HistoryImportReport> {
+    Ok(crate::history_csv::import(
+        &state.db,
+        subscription_id,
+        &content,
+        dry_run.unwrap_or(false),
+        chrono::Utc::now().timestamp(),
+    )?)
+}
+
+#[tauri::command]
+pub async fn cleanup_history(
+    state: tauri::State<'_, Arc<CoreState>>,
+    retention_days: Option<i64>,
+) -> CommandResult<i64> {
+    let days = retention_days.unwrap_or(90);
+    let cutoff = chrono::Utc::now().timestamp() - (days * 86400);
+    Ok(state.db.cleanup_history(cutoff)?)
+}
+
+#[tauri::command]
+pub async fn purge_all_history(state: tauri::State<'_, Arc<CoreState>>) -> CommandResult<i64> {
+    Ok(state.db.purge_all_history()?)
+}
+
+#[tauri::command]
+pub async fn delete_subscription_history(
+    state: tauri::State<'_, Arc<CoreState>>,
+    subscription_id: i64,
+) -> CommandResult<i64> {
+    Ok(state.db.delete_history_for_subscription(subscription_id)?)
+}
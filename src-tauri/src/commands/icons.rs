@@ -1,6 +1,8 @@
+use super::{CommandError, CommandResult, ErrorCode};
 use crate::core_state::CoreState;
+use crate::db::IconMapping;
+use crate::i18n::{LocalizedError, Msg};
 use std::sync::Arc;
-use tauri::Manager;
 
 /// Opens the icons directory in the native file explorer.
 /// Creates the directory if it does not exist.
@@ -8,7 +10,7 @@ use tauri::Manager;
 pub async fn open_icons_folder(
     state: tauri::State<'_, Arc<CoreState>>,
     app: tauri::AppHandle,
-) -> Result<(), String> {
+) -> CommandResult<()> {
     let icons_dir = state.data_dir.join("icons");
 
     // Ensure directory exists
@@ -29,22 +31,29 @@ pub async fn open_icons_folder(
 pub async fn set_icon(
     state: tauri::State<'_, Arc<CoreState>>,
     symbol: String,
-) -> Result<String, String> {
+) -> CommandResult<String> {
     let file = rfd::AsyncFileDialog::new()
         .add_filter("Images", &["png", "jpg", "jpeg", "webp", "svg"])
         .set_title("Select Icon")
         .pick_file()
         .await
-        .ok_or_else(|| "Cancelled".to_string())?;
-    let icon_name = symbol.to_lowercase();
-    let icons_dir = state.data_dir.join("icons");
-    tokio::fs::create_dir_all(&icons_dir)
-        .await
-        .map_err(|e| format!("Failed to create icons directory: {}", e))?;
-    let dest = icons_dir.join(format!("{}.png", icon_name));
-    tokio::fs::write(&dest, file.read().await)
-        .await
-        .map_err(|e| format!("Failed to write icon: {}", e))?;
+        .ok_or_else(CommandError::cancelled)?;
+    state.file_access.record_pick(file.path());
+    let dest =
+        crate::icons::save_icon_bytes(&state.db, &state.data_dir.join("icons"), &symbol, &file.read().await).await?;
+    Ok(dest.to_string_lossy().to_string())
+}
+
+/// 不開對話框的 `set_icon`：由 `path`（本機檔案）或 `data`（base64 / data URL）擇一提供圖片，供腳本使用。
+#[tauri::command]
+pub async fn set_icon_from_path(
+    state: tauri::State<'_, Arc<CoreState>>,
+    symbol: String,
+    path: Option<String>,
+    data: Option<String>,
+) -> CommandResult<String> {
+    let (bytes, _) = crate::icons::read_image_input(path.as_deref(), data.as_deref()).await?;
+    let dest = crate::icons::save_icon_bytes(&state.db, &state.data_dir.join("icons"), &symbol, &bytes).await?;
     Ok(dest.to_string_lossy().to_string())
 }
 
@@ -52,21 +61,15 @@ pub async fn set_icon(
 pub async fn remove_icon(
     state: tauri::State<'_, Arc<CoreState>>,
     symbol: String,
-) -> Result<(), String> {
-    let icon_name = symbol.to_lowercase();
-    let dest = state.data_dir.join("icons").join(format!("{}.png", icon_name));
-    if dest.exists() {
-        tokio::fs::remove_file(&dest)
-            .await
-            .map_err(|e| format!("Failed to delete icon: {}", e))?;
-    }
+) -> CommandResult<()> {
+    crate::icons::remove_icon_files(&state.db, &state.data_dir.join("icons"), &symbol).await?;
     Ok(())
 }
 
 #[tauri::command]
 pub async fn get_icons_dir(
     state: tauri::State<'_, Arc<CoreState>>,
-) -> Result<String, String> {
+) -> CommandResult<String> {
     let dir = state.data_dir.join("icons");
     Ok(dir.to_string_lossy().to_string())
 }
@@ -80,7 +83,7 @@ pub use crate::icons::LogoDownloadResult;
 pub async fn download_logos(
     state: tauri::State<'_, Arc<CoreState>>,
     app: tauri::AppHandle,
-) -> Result<LogoDownloadResult, String> {
+) -> CommandResult<LogoDownloadResult> {
     use tauri::Emitter;
 
     let icons_dir = state.data_dir.join("icons");
@@ -101,13 +104,13 @@ pub async fn download_logos(
         }
     });
 
-    crate::icons::download_all_logos(&state.db, &icons_dir, Some(progress_tx)).await
+    Ok(crate::icons::download_all_logos(&state.db, &icons_dir, Some(progress_tx)).await?)
 }
 
 #[tauri::command]
 pub async fn clear_all_icons(
     state: tauri::State<'_, Arc<CoreState>>,
-) -> Result<i64, String> {
+) -> CommandResult<i64> {
     let icons_dir = state.data_dir.join("icons");
     if !icons_dir.exists() {
         return Ok(0);
@@ -118,43 +121,62 @@ pub async fn clear_all_icons(
         .map_err(|e| format!("Failed to read icons directory: {}", e))?;
     while let Ok(Some(entry)) = entries.next_entry().await {
         let path = entry.path();
-        if path.extension().map(|e| e == "png").unwrap_or(false) {
+        let is_icon = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| crate::icons::ICON_EXTS.contains(&e));
+        if is_icon {
             if tokio::fs::remove_file(&path).await.is_ok() {
                 count += 1;
             }
         }
     }
+    state.db.clear_icon_mappings()?;
     Ok(count)
 }
 
+/// symbol → icon 檔名對照表
+#[tauri::command]
+pub async fn list_icon_mappings(
+    state: tauri::State<'_, Arc<CoreState>>,
+) -> CommandResult<Vec<IconMapping>> {
+    Ok(state.db.list_icon_mappings()?)
+}
+
 #[tauri::command]
 pub async fn download_single_icon(
     state: tauri::State<'_, Arc<CoreState>>,
     symbol: String,
     save_as: String,
-) -> Result<(), String> {
-    let icons_dir = state.data_dir.join("icons");
-    tokio::fs::create_dir_all(&icons_dir)
-        .await
-        .map_err(|e| format!("Failed to create icons directory: {}", e))?;
-
+) -> CommandResult<()> {
     let bytes = crate::icons::try_download_png(
         &reqwest::Client::new(),
         &symbol,
         false,
-    ).await.ok_or_else(|| format!("Logo not found for symbol: {}", symbol))?;
+    ).await.ok_or_else(|| {
+        CommandError::localized(ErrorCode::NotFound, LocalizedError::new(Msg::LogoNotFound, &[&symbol]))
+    })?;
 
-    let dest = icons_dir.join(format!("{}.png", save_as.to_lowercase()));
-    tokio::fs::write(&dest, &bytes)
-        .await
-        .map_err(|e| format!("Failed to save icon: {}", e))?;
+    crate::icons::save_icon_bytes(&state.db, &state.data_dir.join("icons"), &save_as, &bytes).await?;
     Ok(())
 }
 
+/// 依資產類型自動下載 logo（CoinGecko / FMP / Jupiter，Parqet 備援），轉成 PNG 存入 icons 目錄。
+/// 回傳提供圖片的來源名稱。
+#[tauri::command]
+pub async fn fetch_icon(
+    state: tauri::State<'_, Arc<CoreState>>,
+    symbol: String,
+    provider_id: String,
+) -> CommandResult<String> {
+    let icons_dir = state.data_dir.join("icons");
+    Ok(crate::icons::fetch_icon(&state.db, &icons_dir, &symbol, &provider_id).await?)
+}
+
 #[tauri::command]
 pub async fn search_icons(
     symbol: String,
-) -> Result<Vec<crate::icons::IconSearchResult>, String> {
+) -> CommandResult<Vec<crate::icons::IconSearchResult>> {
     let client = reqwest::Client::builder()
         .timeout(std::time::Duration::from_secs(8))
         .user_agent("StockenBoard/1.0")
@@ -168,118 +190,27 @@ pub async fn save_icon_from_data(
     state: tauri::State<'_, Arc<CoreState>>,
     save_as: String,
     data_url: String,
-) -> Result<(), String> {
+) -> CommandResult<()> {
     use base64::Engine;
-    let icons_dir = state.data_dir.join("icons");
-    tokio::fs::create_dir_all(&icons_dir)
-        .await
-        .map_err(|e| format!("Failed to create icons directory: {}", e))?;
 
     // Parse data URL: "data:image/png;base64,{base64}"
     let b64_part = data_url
         .split(",")
         .nth(1)
-        .ok_or_else(|| "Invalid data URL format".to_string())?;
+        .ok_or_else(|| CommandError::bad_request("Invalid data URL format"))?;
     let bytes = base64::engine::general_purpose::STANDARD
         .decode(b64_part)
-        .map_err(|e| format!("Failed to decode base64: {}", e))?;
+        .map_err(|e| CommandError::bad_request(format!("Failed to decode base64: {}", e)))?;
 
-    let dest = icons_dir.join(format!("{}.png", save_as.to_lowercase()));
-    tokio::fs::write(&dest, &bytes)
-        .await
-        .map_err(|e| format!("Failed to save icon: {}", e))?;
+    crate::icons::save_icon_bytes(&state.db, &state.data_dir.join("icons"), &save_as, &bytes).await?;
     Ok(())
 }
 
-/// 讀取本地檔案並回傳 base64 data URL
+/// 讀取本地檔案並回傳 base64 data URL；僅限 icons / theme_bg 目錄與使用者在對話框選取過的檔案
 #[tauri::command]
-pub async fn read_local_file_base64(path: String) -> Result<String, String> {
-    let bytes = tokio::fs::read(&path)
-        .await
-        .map_err(|e| format!("Failed to read file: {}", e))?;
-    use base64::Engine;
-    let b64 = base64::engine::general_purpose::STANDARD.encode(&bytes);
-    let mime = if path.ends_with(".png") {
-        "image/png"
-    } else if path.ends_with(".jpg") || path.ends_with(".jpeg") {
-        "image/jpeg"
-    } else if path.ends_with(".webp") {
-        "image/webp"
-    } else if path.ends_with(".svg") {
-        "image/svg+xml"
-    } else if path.ends_with(".gif") {
-        "image/gif"
-    } else {
-        "application/octet-stream"
-    };
-    Ok(format!("data:{};base64,{}", mime, b64))
-}
-
-// ── Theme Background ────────────────────────────────────────────
-
-#[tauri::command]
-pub async fn save_theme_bg(app: tauri::AppHandle, theme_id: String) -> Result<String, String> {
-    let file = rfd::AsyncFileDialog::new()
-        .add_filter("Images", &["png", "jpg", "jpeg", "webp"])
-        .set_title("Select Background Image")
-        .pick_file()
-        .await
-        .ok_or_else(|| "Cancelled".to_string())?;
-    let dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app directory: {}", e))?
-        .join("theme_bg");
-    tokio::fs::create_dir_all(&dir)
-        .await
-        .map_err(|e| format!("Failed to create directory: {}", e))?;
-    let ext = file
-        .file_name()
-        .rsplit('.')
-        .next()
-        .map(|e| e.to_lowercase())
-        .filter(|e| matches!(e.as_str(), "png" | "jpg" | "jpeg" | "webp"))
-        .unwrap_or_else(|| "png".to_string());
-    for old_ext in &["png", "jpg", "jpeg", "webp", "img"] {
-        let old = dir.join(format!("{}.{}", theme_id, old_ext));
-        let _ = tokio::fs::remove_file(&old).await;
-    }
-    let dest = dir.join(format!("{}.{}", theme_id, ext));
-    tokio::fs::write(&dest, file.read().await)
-        .await
-        .map_err(|e| format!("Failed to write file: {}", e))?;
-    Ok(dest.to_string_lossy().to_string())
-}
-
-#[tauri::command]
-pub async fn remove_theme_bg(app: tauri::AppHandle, theme_id: String) -> Result<(), String> {
-    let dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app directory: {}", e))?
-        .join("theme_bg");
-    for ext in &["png", "jpg", "jpeg", "webp", "img"] {
-        let path = dir.join(format!("{}.{}", theme_id, ext));
-        let _ = tokio::fs::remove_file(&path).await;
-    }
-    Ok(())
-}
-
-#[tauri::command]
-pub async fn get_theme_bg_path(
-    app: tauri::AppHandle,
-    theme_id: String,
-) -> Result<Option<String>, String> {
-    let dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app directory: {}", e))?
-        .join("theme_bg");
-    for ext in &["png", "jpg", "jpeg", "webp", "img"] {
-        let path = dir.join(format!("{}.{}", theme_id, ext));
-        if path.exists() {
-            return Ok(Some(path.to_string_lossy().to_string()));
-        }
-    }
-    Ok(None)
+pub async fn read_local_file_base64(
+    state: tauri::State<'_, Arc<CoreState>>,
+    path: String,
+) -> CommandResult<String> {
+    Ok(state.file_access.read_data_url(&path).await?)
 }
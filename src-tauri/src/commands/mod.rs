@@ -1,17 +1,31 @@
+mod error;
+
+pub mod clipboard;
 pub mod data;
+pub mod history;
 pub mod icons;
 pub mod notifications;
 pub mod prices;
 pub mod providers;
+pub mod settings;
 pub mod subscriptions;
 pub mod system;
+pub mod theme;
 pub mod views;
+pub mod ws;
+
+pub use error::{CommandError, CommandResult, ErrorCode};
 
+pub use clipboard::*;
 pub use data::*;
+pub use history::*;
 pub use icons::*;
 pub use notifications::*;
 pub use prices::*;
 pub use providers::*;
+pub use settings::*;
 pub use subscriptions::*;
 pub use system::*;
+pub use theme::*;
 pub use views::*;
+pub use ws::*;
@@ -1,4 +1,6 @@
+use super::{CommandError, CommandResult, ErrorCode};
 use crate::core_state::CoreState;
+use crate::i18n::{LocalizedError, Msg};
 use std::sync::Arc;
 
 // ── Global Cooldown Commands ────────────────────────────────────
@@ -6,20 +8,21 @@ use std::sync::Arc;
 #[tauri::command]
 pub async fn get_notification_global_cooldown(
     state: tauri::State<'_, Arc<CoreState>>,
-) -> Result<u64, String> {
+) -> CommandResult<u64> {
     let val = state
         .db
         .get_setting("notification_global_cooldown")?
         .unwrap_or_else(|| "30".into());
-    val.parse::<u64>()
-        .map_err(|e| format!("Invalid cooldown value: {}", e))
+    Ok(val
+        .parse::<u64>()
+        .map_err(|e| format!("Invalid cooldown value: {}", e))?)
 }
 
 #[tauri::command]
 pub async fn set_notification_global_cooldown(
     state: tauri::State<'_, Arc<CoreState>>,
     secs: u64,
-) -> Result<(), String> {
+) -> CommandResult<()> {
     state
         .db
         .set_setting("notification_global_cooldown", &secs.to_string())?;
@@ -33,16 +36,18 @@ pub async fn set_notification_global_cooldown(
 pub async fn create_notification_rule(
     state: tauri::State<'_, Arc<CoreState>>,
     rule: crate::notifications::models::CreateRuleRequest,
-) -> Result<i64, String> {
+) -> CommandResult<i64> {
     // Validate AI config when condition_type is "ai"
     let threshold = if rule.condition_type == "ai" {
         // ai_config is required for AI rules
         let ai_config = rule
             .ai_config
             .as_ref()
-            .ok_or_else(|| "ai_config is required when condition_type is \"ai\"".to_string())?;
+            .ok_or_else(|| {
+                CommandError::bad_request("ai_config is required when condition_type is \"ai\"")
+            })?;
         // Validate ai_config fields
-        ai_config.validate()?;
+        ai_config.validate().map_err(CommandError::bad_request)?;
         // AI rules use threshold 0.0
         0.0
     } else {
@@ -104,8 +109,8 @@ pub async fn create_notification_rule(
 #[tauri::command]
 pub async fn list_notification_rules(
     state: tauri::State<'_, Arc<CoreState>>,
-) -> Result<Vec<crate::db::NotificationRuleRow>, String> {
-    state.db.list_notification_rules()
+) -> CommandResult<Vec<crate::db::NotificationRuleRow>> {
+    Ok(state.db.list_notification_rules()?)
 }
 
 #[tauri::command]
@@ -113,10 +118,10 @@ pub async fn update_notification_rule(
     state: tauri::State<'_, Arc<CoreState>>,
     id: i64,
     rule: crate::notifications::models::UpdateRuleRequest,
-) -> Result<(), String> {
+) -> CommandResult<()> {
     // Validate AI config if provided
     if let Some(Some(ref ai_config)) = rule.ai_config {
-        ai_config.validate()?;
+        ai_config.validate().map_err(CommandError::bad_request)?;
     }
 
     // If switching to AI type, ensure ai_config is provided
@@ -124,7 +129,11 @@ pub async fn update_notification_rule(
         if ct == "ai" {
             match &rule.ai_config {
                 Some(Some(_)) => {} // ai_config provided, OK
-                _ => return Err("ai_config is required when condition_type is \"ai\"".to_string()),
+                _ => {
+                    return Err(CommandError::bad_request(
+                        "ai_config is required when condition_type is \"ai\"",
+                    ))
+                }
             }
         }
     }
@@ -228,7 +237,7 @@ pub async fn update_notification_rule(
 pub async fn delete_notification_rule(
     state: tauri::State<'_, Arc<CoreState>>,
     id: i64,
-) -> Result<(), String> {
+) -> CommandResult<()> {
     state.db.delete_notification_rule(id)?;
     state.notification_engine.reload_rules().await;
     state.sync_polling_for_rules().await;
@@ -242,7 +251,7 @@ pub async fn toggle_notification_rule(
     state: tauri::State<'_, Arc<CoreState>>,
     id: i64,
     enabled: bool,
-) -> Result<(), String> {
+) -> CommandResult<()> {
     state.db.toggle_notification_rule(id, enabled)?;
     state.notification_engine.reload_rules().await;
     state.sync_polling_for_rules().await;
@@ -263,15 +272,17 @@ pub async fn toggle_notification_rule(
 pub async fn save_notification_channel(
     state: tauri::State<'_, Arc<CoreState>>,
     channel: crate::notifications::models::SaveChannelRequest,
-) -> Result<i64, String> {
+) -> CommandResult<i64> {
     // Validate config based on channel_type
-    match channel.channel_type.as_str() {
+    let id = match channel.channel_type.as_str() {
         "telegram" => {
             let config: crate::notifications::models::TelegramConfig =
                 serde_json::from_str(&channel.config)
-                    .map_err(|e| format!("Invalid Telegram config format: {}", e))?;
+                    .map_err(|e| {
+                        CommandError::bad_request(format!("Invalid Telegram config format: {}", e))
+                    })?;
             if config.bot_token.is_empty() || config.chat_id.is_empty() {
-                return Err("Bot Token and Chat ID must not be empty".to_string());
+                return Err(CommandError::bad_request("Bot Token and Chat ID must not be empty"));
             }
             // Encrypt bot_token before storing
             let encrypted_token = crate::notifications::crypto::encrypt_token(&config.bot_token)?;
@@ -283,45 +294,56 @@ pub async fn save_notification_channel(
                 &channel.channel_type,
                 &channel.name,
                 &stored_config.to_string(),
-            )
+            )?
         }
         "webhook" => {
             let config: crate::notifications::models::WebhookConfig =
                 serde_json::from_str(&channel.config)
-                    .map_err(|e| format!("Invalid Webhook config format: {}", e))?;
+                    .map_err(|e| {
+                        CommandError::bad_request(format!("Invalid Webhook config format: {}", e))
+                    })?;
             if config.url.is_empty() {
-                return Err("Webhook URL must not be empty".to_string());
+                return Err(CommandError::bad_request("Webhook URL must not be empty"));
             }
             state.db.create_notification_channel(
                 &channel.channel_type,
                 &channel.name,
                 &channel.config,
-            )
+            )?
         }
-        _ => Err(format!("Unsupported channel type: {}", channel.channel_type)),
-    }
+        _ => {
+            return Err(CommandError::bad_request(format!(
+                "Unsupported channel type: {}",
+                channel.channel_type
+            )));
+        }
+    };
+    Ok(id)
 }
 
 #[tauri::command]
 pub async fn list_notification_channels(
     state: tauri::State<'_, Arc<CoreState>>,
-) -> Result<Vec<crate::db::NotificationChannelRow>, String> {
-    state.db.list_notification_channels()
+) -> CommandResult<Vec<crate::db::NotificationChannelRow>> {
+    Ok(state.db.list_notification_channels()?)
 }
 
 #[tauri::command]
 pub async fn delete_notification_channel(
     state: tauri::State<'_, Arc<CoreState>>,
     id: i64,
-) -> Result<(), String> {
+) -> CommandResult<()> {
     // Prevent deletion of the built-in local and system channels
     let channels = state.db.list_notification_channels()?;
     if let Some(ch) = channels.iter().find(|c| c.id == id) {
         if ch.channel_type == "local" || ch.channel_type == "system" {
-            return Err("Cannot delete the built-in notification channel".to_string());
+            return Err(CommandError::new(
+                ErrorCode::Forbidden,
+                "Cannot delete the built-in notification channel",
+            ));
         }
     }
-    state.db.delete_notification_channel(id)
+    Ok(state.db.delete_notification_channel(id)?)
 }
 
 #[tauri::command]
@@ -329,12 +351,17 @@ pub async fn test_notification_channel(
     state: tauri::State<'_, Arc<CoreState>>,
     app: tauri::AppHandle,
     id: i64,
-) -> Result<(), String> {
+) -> CommandResult<()> {
     let channels = state.db.list_notification_channels()?;
     let channel = channels
         .iter()
         .find(|c| c.id == id)
-        .ok_or_else(|| format!("Channel {} not found", id))?;
+        .ok_or_else(|| {
+            CommandError::localized(
+                ErrorCode::NotFound,
+                LocalizedError::new(Msg::ChannelNotFound, &[&id]),
+            )
+        })?;
 
     let client = reqwest::Client::new();
 
@@ -344,8 +371,10 @@ pub async fn test_notification_channel(
                 .map_err(|e| format!("Failed to parse config: {}", e))?;
             let encrypted_token = stored_config["bot_token"]
                 .as_str()
-                .ok_or("Missing bot_token")?;
-            let chat_id = stored_config["chat_id"].as_str().ok_or("Missing chat_id")?;
+                .ok_or_else(|| "Missing bot_token".to_string())?;
+            let chat_id = stored_config["chat_id"]
+                .as_str()
+                .ok_or_else(|| "Missing chat_id".to_string())?;
             let bot_token = crate::notifications::crypto::decrypt_token(encrypted_token)?;
             let config = crate::notifications::models::TelegramConfig {
                 bot_token,
@@ -353,7 +382,8 @@ pub async fn test_notification_channel(
             };
             let test_message =
                 "🔔 StockenBoard Test Notification\n\nThis is a test message to confirm the Telegram channel is configured correctly.";
-            crate::notifications::telegram::send_telegram(&client, &config, test_message).await
+            crate::notifications::telegram::send_telegram(&client, &config, test_message).await?;
+            Ok(())
         }
         "webhook" => {
             let config: crate::notifications::models::WebhookConfig =
@@ -368,7 +398,8 @@ pub async fn test_notification_channel(
                 rule_name: "Test rule".to_string(),
                 triggered_at: chrono::Utc::now(),
             };
-            crate::notifications::webhook::send_webhook(&client, &config, &test_data).await
+            crate::notifications::webhook::send_webhook(&client, &config, &test_data).await?;
+            Ok(())
         }
         "local" => {
             // Emit a test notification event to the frontend
@@ -399,7 +430,10 @@ pub async fn test_notification_channel(
                 .map_err(|e| format!("Failed to send system notification: {}", e))?;
             Ok(())
         }
-        _ => Err(format!("Unsupported channel type: {}", channel.channel_type)),
+        _ => Err(CommandError::bad_request(format!(
+            "Unsupported channel type: {}",
+            channel.channel_type
+        ))),
     }
 }
 
@@ -412,10 +446,10 @@ pub async fn get_notification_history(
     from: Option<i64>,
     to: Option<i64>,
     limit: Option<i64>,
-) -> Result<Vec<crate::db::NotificationHistoryRow>, String> {
-    state
+) -> CommandResult<Vec<crate::db::NotificationHistoryRow>> {
+    Ok(state
         .db
-        .query_notification_history(rule_id, from, to, limit)
+        .query_notification_history(rule_id, from, to, limit)?)
 }
 
 // ── AI Provider Config Commands ─────────────────────────────────
@@ -428,7 +462,7 @@ pub async fn save_ai_provider_config(
     api_key: Option<String>,
     disable_thinking: Option<bool>,
     max_context_tokens: Option<u32>,
-) -> Result<(), String> {
+) -> CommandResult<()> {
     state
         .db
         .save_ai_provider_config(&base_url, &model, api_key.as_deref(), disable_thinking.unwrap_or(true), max_context_tokens)?;
@@ -439,7 +473,7 @@ pub async fn save_ai_provider_config(
 #[tauri::command]
 pub async fn get_ai_provider_config(
     state: tauri::State<'_, Arc<CoreState>>,
-) -> Result<Option<crate::notifications::models::AiProviderConfigResponse>, String> {
+) -> CommandResult<Option<crate::notifications::models::AiProviderConfigResponse>> {
     let config = state.db.load_ai_provider_config()?;
     Ok(
         config.map(|c| crate::notifications::models::AiProviderConfigResponse {
@@ -458,19 +492,19 @@ pub async fn test_ai_connection(
     base_url: Option<String>,
     model: Option<String>,
     api_key: Option<String>,
-) -> Result<String, String> {
+) -> CommandResult<String> {
     // 1. Determine config: use provided values or fall back to DB
     let db_config = state.db.load_ai_provider_config()?;
 
     let effective_base_url = base_url
         .filter(|u| !u.is_empty())
         .or_else(|| db_config.as_ref().map(|c| c.base_url.clone()))
-        .ok_or_else(|| "No base_url provided and none saved".to_string())?;
+        .ok_or_else(|| CommandError::bad_request("No base_url provided and none saved"))?;
 
     let effective_model = model
         .filter(|m| !m.is_empty())
         .or_else(|| db_config.as_ref().map(|c| c.model.clone()))
-        .ok_or_else(|| "No model provided and none saved".to_string())?;
+        .ok_or_else(|| CommandError::bad_request("No model provided and none saved"))?;
 
     let effective_api_key = api_key
         .filter(|k| !k.is_empty())
@@ -523,7 +557,8 @@ pub async fn test_ai_connection(
             "AI API error (HTTP {}): {}",
             status.as_u16(),
             error_body
-        ));
+        )
+        .into());
     }
 
     // 6. Parse response and check JSON output capability
@@ -579,7 +614,7 @@ fn extract_json_block(text: &str) -> Option<String> {
 }
 
 #[tauri::command]
-pub async fn list_ai_models(base_url: String, api_key: Option<String>) -> Result<Vec<String>, String> {
+pub async fn list_ai_models(base_url: String, api_key: Option<String>) -> CommandResult<Vec<String>> {
     // Try Ollama-style /api/tags endpoint first, then OpenAI-style /models
     let client = reqwest::Client::builder()
         .timeout(std::time::Duration::from_secs(10))
@@ -633,5 +668,5 @@ pub async fn list_ai_models(base_url: String, api_key: Option<String>) -> Result
         }
     }
 
-    Err("Failed to list models, please verify URL is correct".to_string())
+    Err("Failed to list models, please verify URL is correct".to_string().into())
 }
@@ -1,24 +1,32 @@
+use super::{CommandError, CommandResult, ErrorCode};
+use crate::calendar::{EconomicEvent, Impact};
 use crate::core_state::CoreState;
-use crate::polling::PollTick;
+use crate::depeg::DepegStatus;
+use crate::gas::GasPrice;
+use crate::i18n::{LocalizedError, Msg};
+use crate::providers::dex_quotes::DexQuoteComparison;
+use crate::polling::{CachedPrice, PollTick};
+use crate::providers::registry::{PriceRequest, ProviderPrices};
 use crate::providers::{
-    create_dex_lookup, create_ws_provider, get_all_provider_info, AssetData, DexPoolInfo,
-    ProviderInfo,
+    create_dex_lookup, get_all_provider_info, AssetData, DexPoolInfo,
+    HttpOptions, ProviderInfo,
 };
 use std::sync::Arc;
-use tauri::Emitter;
 
 #[tauri::command]
 pub async fn fetch_asset_price(
     state: tauri::State<'_, Arc<CoreState>>,
     provider_id: String,
     symbol: String,
-) -> Result<AssetData, String> {
+) -> CommandResult<AssetData> {
     let p = state
         .registry
         .get_or_create(&provider_id, &state.db)
         .await
-        .ok_or_else(|| format!("Provider not found: {}", provider_id))?;
-    p.fetch_price(&symbol).await
+        .ok_or_else(|| {
+            CommandError::localized(ErrorCode::NotFound, LocalizedError::new(Msg::ProviderNotFound, &[&provider_id]))
+        })?;
+    Ok(p.fetch_price(&symbol).await?)
 }
 
 #[tauri::command]
@@ -26,11 +34,20 @@ pub async fn fetch_multiple_prices(
     state: tauri::State<'_, Arc<CoreState>>,
     provider_id: String,
     symbols: Vec<String>,
-) -> Result<Vec<AssetData>, String> {
-    state
+) -> CommandResult<Vec<AssetData>> {
+    Ok(state
         .registry
         .fetch_with_limit(&provider_id, &symbols, &state.db)
-        .await
+        .await?)
+}
+
+/// 一次向多個 provider 取價（並行），結果依請求順序分組回傳
+#[tauri::command]
+pub async fn fetch_prices_multi(
+    state: tauri::State<'_, Arc<CoreState>>,
+    requests: Vec<PriceRequest>,
+) -> CommandResult<Vec<ProviderPrices>> {
+    Ok(state.registry.fetch_multi(&requests, &state.db).await)
 }
 
 #[tauri::command]
@@ -45,17 +62,19 @@ pub async fn enable_provider(
     provider_id: String,
     api_key: Option<String>,
     api_secret: Option<String>,
-) -> Result<(), String> {
-    let api_url = state
-        .db
-        .get_provider_settings(&provider_id)
-        .ok()
-        .flatten()
-        .and_then(|s| s.api_url.filter(|u| !u.is_empty()));
-    state
-        .registry
-        .update_provider(&provider_id, api_key, api_secret, api_url)
-        .await;
+) -> CommandResult<()> {
+    if api_key.is_none() && api_secret.is_none() {
+        // 未指定 key：丟棄舊 instance，下次使用時依 DB 設定重建
+        state.registry.invalidate(&provider_id).await;
+    } else {
+        let settings = state.db.get_provider_settings(&provider_id).ok().flatten();
+        let http = settings.as_ref().map(HttpOptions::from).unwrap_or_default();
+        let api_url = settings.and_then(|s| s.api_url.filter(|u| !u.is_empty()));
+        state
+            .registry
+            .update_provider(&provider_id, api_key, api_secret, api_url, http)
+            .await;
+    }
     state.polling.reload();
     Ok(())
 }
@@ -63,88 +82,113 @@ pub async fn enable_provider(
 #[tauri::command]
 pub async fn get_cached_prices(
     state: tauri::State<'_, Arc<CoreState>>,
-) -> Result<Vec<AssetData>, String> {
-    Ok(state.polling.cache.read().await.values().cloned().collect())
+) -> CommandResult<Vec<CachedPrice>> {
+    Ok(state.polling.cached_prices().await)
+}
+
+/// 將訂閱的目前價格與 24h 走勢畫成 PNG 快照，回傳 `data:` URL
+#[tauri::command]
+pub async fn render_snapshot(
+    state: tauri::State<'_, Arc<CoreState>>,
+    subscription_ids: Vec<i64>,
+) -> CommandResult<String> {
+    let png = state.render_snapshot(&subscription_ids).await?;
+    Ok(crate::snapshot::data_url(&png))
 }
 
 #[tauri::command]
-pub async fn get_poll_ticks(state: tauri::State<'_, Arc<CoreState>>) -> Result<Vec<PollTick>, String> {
+pub async fn get_poll_ticks(state: tauri::State<'_, Arc<CoreState>>) -> CommandResult<Vec<PollTick>> {
     Ok(state.polling.ticks.read().await.values().cloned().collect())
 }
 
+#[tauri::command]
+pub async fn get_gas_prices() -> CommandResult<Vec<GasPrice>> {
+    Ok(crate::gas::latest())
+}
+
+/// 取得穩定幣脫鉤狀態；監控尚未執行過時立即查詢一次
+#[tauri::command]
+pub async fn get_depeg_status(state: tauri::State<'_, Arc<CoreState>>) -> CommandResult<Vec<DepegStatus>> {
+    let statuses = crate::depeg::latest();
+    if !statuses.is_empty() {
+        return Ok(statuses);
+    }
+    Ok(crate::depeg::poll_once(&state.registry, &state.db, &state.event_bus).await)
+}
+
+/// 查詢經濟日曆事件（Unix 秒；預設為現在起 7 天內），`min_impact` 為 low / medium / high
+#[tauri::command]
+pub async fn get_economic_calendar(
+    state: tauri::State<'_, Arc<CoreState>>,
+    from: Option<i64>,
+    to: Option<i64>,
+    country: Option<String>,
+    min_impact: Option<String>,
+) -> CommandResult<Vec<EconomicEvent>> {
+    let min_impact = match min_impact.as_deref() {
+        None => Impact::Low,
+        Some(v) => v.parse().map_err(|_| CommandError::bad_request(format!("Unknown impact: {}", v)))?,
+    };
+    let from = from.unwrap_or_else(|| chrono::Utc::now().timestamp());
+    let to = to.unwrap_or(from + 7 * 86_400);
+    let country = country.as_deref().map(str::trim).filter(|c| !c.is_empty());
+    Ok(state.db.list_economic_events(from, to, country, min_impact)?)
+}
+
+/// 立即抓取經濟日曆，回傳寫入的事件數
+#[tauri::command]
+pub async fn refresh_economic_calendar(state: tauri::State<'_, Arc<CoreState>>) -> CommandResult<usize> {
+    Ok(crate::calendar::refresh(&state.db, &state.event_bus).await?)
+}
+
 #[tauri::command]
 pub async fn set_visible_subscriptions(
     state: tauri::State<'_, Arc<CoreState>>,
     window: tauri::Window,
     ids: Vec<i64>,
     scope: Option<String>,
-) -> Result<(), String> {
+    interval_ms: Option<u64>,
+) -> CommandResult<()> {
     let window_id = match scope {
         Some(s) => format!("{}_{}", window.label(), s),
         None => window.label().to_string(),
     };
     let id_set: std::collections::HashSet<i64> = ids.into_iter().collect();
-    state.polling.set_visible(window_id, id_set).await;
+    state.set_visible_subscriptions(window_id, id_set, interval_ms).await;
     Ok(())
 }
 
+/// 各視窗要求的與實際的輪詢間隔
+#[tauri::command]
+pub async fn get_polling_rates(
+    state: tauri::State<'_, Arc<CoreState>>,
+) -> CommandResult<Vec<crate::polling::WindowRates>> {
+    Ok(state.polling.window_rates().await)
+}
+
 #[tauri::command]
 pub async fn lookup_dex_pool(
     state: tauri::State<'_, Arc<CoreState>>,
     provider_id: String,
     pool_address: String,
-) -> Result<DexPoolInfo, String> {
+) -> CommandResult<DexPoolInfo> {
     let settings = state.db.get_provider_settings(&provider_id).ok().flatten();
     let api_key = settings.as_ref().and_then(|s| s.api_key.clone());
     let api_url = settings.as_ref().and_then(|s| s.api_url.clone());
-    let lookup = create_dex_lookup(&provider_id, api_key, api_url)
-        .ok_or_else(|| format!("{} does not support pool lookup", provider_id))?;
-    lookup.lookup_pool(&pool_address).await
+    let http = settings.as_ref().map(HttpOptions::from).unwrap_or_default();
+    let lookup = create_dex_lookup(&provider_id, api_key, api_url, &http)
+        .ok_or_else(|| CommandError::bad_request(format!("{} does not support pool lookup", provider_id)))?;
+    Ok(lookup.lookup_pool(&pool_address).await?)
 }
 
-// ── WebSocket ───────────────────────────────────────────────────
-
+/// 同時向所有支援該鏈的 DEX 聚合器詢價（`amount` 為完整 token 數量），依換得數量排序
 #[tauri::command]
-pub async fn start_ws_stream(
+pub async fn compare_dex_quotes(
     state: tauri::State<'_, Arc<CoreState>>,
-    app: tauri::AppHandle,
-    provider_id: String,
-    symbols: Vec<String>,
-) -> Result<(), String> {
-    {
-        let mut tasks = state.ws_tasks.write().await;
-        if let Some((fwd, ws)) = tasks.remove(&provider_id) {
-            fwd.abort();
-            ws.abort();
-        }
-    }
-    let ws_provider = create_ws_provider(&provider_id)
-        .ok_or_else(|| format!("{} does not support WebSocket", provider_id))?;
-    let sender = Arc::new(state.ws_sender.clone());
-    let mut receiver = state.ws_sender.subscribe();
-    let ws_handle = ws_provider.subscribe(symbols, sender).await?;
-    let app_handle = app.clone();
-    let forwarder = tokio::spawn(async move {
-        while let Ok(update) = receiver.recv().await {
-            let _ = app_handle.emit("ws-ticker-update", &update);
-        }
-    });
-    state
-        .ws_tasks
-        .write()
-        .await
-        .insert(provider_id, (forwarder, ws_handle));
-    Ok(())
-}
-
-#[tauri::command]
-pub async fn stop_ws_stream(
-    state: tauri::State<'_, Arc<CoreState>>,
-    provider_id: String,
-) -> Result<(), String> {
-    if let Some((fwd, ws)) = state.ws_tasks.write().await.remove(&provider_id) {
-        fwd.abort();
-        ws.abort();
-    }
-    Ok(())
+    chain: String,
+    token_in: String,
+    token_out: String,
+    amount: f64,
+) -> CommandResult<DexQuoteComparison> {
+    Ok(crate::providers::dex_quotes::compare_dex_quotes(&state.db, &chain, &token_in, &token_out, amount).await?)
 }
@@ -1,12 +1,22 @@
+use super::{CommandError, CommandResult};
 use crate::core_state::CoreState;
-use crate::db::ProviderSettingsRow;
+use crate::db::{ExportedSecret, ProviderSettingsRow};
+use crate::provider_resolver::ProviderResolution;
+use crate::providers::debug::ProviderDebugInfo;
+use crate::latency_tune::ProviderLatency;
+use crate::sanity::AnomalyMetrics;
 use std::sync::Arc;
 
+/// kiosk 鎖定中不回傳 key / secret
 #[tauri::command]
 pub async fn list_provider_settings(
     state: tauri::State<'_, Arc<CoreState>>,
-) -> Result<Vec<ProviderSettingsRow>, String> {
-    state.db.list_provider_settings()
+) -> CommandResult<Vec<ProviderSettingsRow>> {
+    let settings = state.db.list_provider_settings()?;
+    if crate::kiosk::kiosk_state().enabled {
+        return Ok(settings.into_iter().map(ProviderSettingsRow::redacted).collect());
+    }
+    Ok(settings)
 }
 
 #[tauri::command]
@@ -22,7 +32,10 @@ pub async fn upsert_provider_settings(
     connection_type: String,
     record_from_hour: Option<i64>,
     record_to_hour: Option<i64>,
-) -> Result<(), String> {
+    proxy_url: Option<String>,
+    timeout_ms: Option<i64>,
+    extra_headers: Option<String>,
+) -> CommandResult<()> {
     state.db.upsert_provider_settings(
         &provider_id,
         api_key.as_deref(),
@@ -32,17 +45,81 @@ pub async fn upsert_provider_settings(
         &connection_type,
         record_from_hour,
         record_to_hour,
+        proxy_url.as_deref(),
+        timeout_ms,
+        extra_headers.as_deref(),
     )?;
-    // 同步 Rust 端 provider instance + 觸發 polling reload
-    state
-        .registry
-        .update_provider(
-            &provider_id,
-            api_key.filter(|k| !k.is_empty()),
-            api_secret.filter(|s| !s.is_empty()),
-            api_url.filter(|u| !u.is_empty()),
-        )
-        .await;
+    // 丟棄舊的 provider instance（下次使用時以新設定重建）+ 觸發 polling reload
+    state.registry.invalidate(&provider_id).await;
     state.polling.reload();
     Ok(())
 }
+
+/// 清除所有快取的 provider instance 與設定，強制以 DB 目前設定重建
+#[tauri::command]
+pub async fn clear_provider_cache(state: tauri::State<'_, Arc<CoreState>>) -> CommandResult<()> {
+    state.db.invalidate_provider_settings_cache();
+    state.registry.clear().await;
+    state.polling.reload();
+    Ok(())
+}
+
+// ── Debug capture ───────────────────────────────────────────────
+
+/// 開啟／關閉 provider 原始回應擷取（僅存在記憶體）
+/// 判斷 symbol 類型並排序可服務的 provider（新增訂閱時的建議）
+#[tauri::command]
+pub async fn resolve_best_provider(
+    state: tauri::State<'_, Arc<CoreState>>,
+    symbol: String,
+) -> CommandResult<ProviderResolution> {
+    crate::provider_resolver::resolve(&state, &symbol)
+        .await
+        .map_err(CommandError::bad_request)
+}
+
+#[tauri::command]
+pub async fn set_provider_debug(provider_id: String, enabled: bool) -> CommandResult<()> {
+    crate::providers::debug::set_capture(&provider_id, enabled).map_err(CommandError::bad_request)
+}
+
+/// 取得 provider 最近擷取的原始回應
+#[tauri::command]
+pub async fn get_provider_debug(provider_id: String) -> CommandResult<ProviderDebugInfo> {
+    Ok(crate::providers::debug::debug_info(&provider_id))
+}
+
+// ── Price sanity ────────────────────────────────────────────────
+
+/// 各 provider 被合理性檢查拒絕的報價統計
+#[tauri::command]
+pub async fn get_price_anomaly_metrics() -> CommandResult<Vec<AnomalyMetrics>> {
+    Ok(crate::sanity::metrics())
+}
+
+/// 各 provider 的取價延遲（p50 / p95）與實際輪詢間隔
+#[tauri::command]
+pub async fn get_provider_latency() -> CommandResult<Vec<ProviderLatency>> {
+    Ok(crate::latency_tune::latency_metrics())
+}
+
+// ── Secrets ─────────────────────────────────────────────────────
+
+/// 將 API key / secret 移至 OS keychain，DB 不再保存其值
+#[tauri::command]
+pub async fn migrate_secrets_to_keyring(
+    state: tauri::State<'_, Arc<CoreState>>,
+) -> CommandResult<usize> {
+    let db = state.db.clone();
+    Ok(tokio::task::spawn_blocking(move || db.migrate_secrets_to_keyring())
+        .await
+        .map_err(|e| format!("Migration task failed: {}", e))??)
+}
+
+/// 匯出明文 API key / secret（跨裝置搬移用）
+#[tauri::command]
+pub async fn export_secrets(
+    state: tauri::State<'_, Arc<CoreState>>,
+) -> CommandResult<Vec<ExportedSecret>> {
+    Ok(state.db.export_secrets()?)
+}
@@ -0,0 +1,409 @@
+use super::{CommandError, CommandResult};
+use crate::calendar::CalendarConfig;
+use crate::candles::CandleConfig;
+use crate::cache_limit::CacheConfig;
+use crate::core_state::CoreState;
+use crate::depeg::DepegConfig;
+use crate::emit_throttle::EmitConfig;
+use crate::gas::GasConfig;
+use crate::latency_tune::LatencyTuneConfig;
+use crate::power::PowerConfig;
+use crate::providers::metadata::{MetadataSource, MetadataStatus};
+use crate::providers::mock::MockConfig;
+use crate::providers::replay::ReplayConfig;
+use crate::providers::subgraph::SubgraphProtocol;
+use crate::record_health::RecordStallConfig;
+use crate::sanity::SanityConfig;
+use crate::schedule::RecordingSchedule;
+use crate::telemetry::OtlpConfig;
+use crate::ws_first::WsFirstConfig;
+use std::sync::Arc;
+
+// ── API Settings ────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_api_port(state: tauri::State<'_, Arc<CoreState>>) -> CommandResult<u16> {
+    let val = state.db.get_setting("api_port")?.unwrap_or("8080".into());
+    Ok(val.parse::<u16>()
+        .map_err(|e| format!("Invalid port: {}", e))?)
+}
+
+#[tauri::command]
+pub async fn set_api_port(state: tauri::State<'_, Arc<CoreState>>, port: u16) -> CommandResult<()> {
+    if port < 1024 {
+        return Err(CommandError::bad_request("Port must be between 1024 and 65535"));
+    }
+    Ok(state.db.set_setting("api_port", &port.to_string())?)
+}
+
+#[tauri::command]
+pub async fn get_api_enabled(state: tauri::State<'_, Arc<CoreState>>) -> CommandResult<bool> {
+    let val = state.db.get_setting("api_enabled")?.unwrap_or("0".into());
+    Ok(val == "1")
+}
+
+#[tauri::command]
+pub async fn set_api_enabled(
+    state: tauri::State<'_, Arc<CoreState>>,
+    enabled: bool,
+) -> CommandResult<()> {
+    Ok(state
+        .db
+        .set_setting("api_enabled", if enabled { "1" } else { "0" })?)
+}
+
+// ── HTTP Proxy ──────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_http_proxy() -> CommandResult<Option<String>> {
+    Ok(crate::providers::global_proxy())
+}
+
+#[tauri::command]
+pub async fn set_http_proxy(
+    state: tauri::State<'_, Arc<CoreState>>,
+    proxy: Option<String>,
+) -> CommandResult<()> {
+    state.set_http_proxy(proxy).await.map_err(CommandError::bad_request)
+}
+
+// ── Yahoo quoteSummary ──────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_yahoo_quote_summary() -> CommandResult<bool> {
+    Ok(crate::providers::yahoo::quote_summary_enabled())
+}
+
+#[tauri::command]
+pub async fn set_yahoo_quote_summary(
+    state: tauri::State<'_, Arc<CoreState>>,
+    enabled: bool,
+) -> CommandResult<()> {
+    Ok(state.set_yahoo_quote_summary(enabled)?)
+}
+
+// ── Jupiter token list ──────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_jupiter_token_list() -> CommandResult<bool> {
+    Ok(crate::providers::jupiter::token_list_enabled())
+}
+
+#[tauri::command]
+pub async fn set_jupiter_token_list(
+    state: tauri::State<'_, Arc<CoreState>>,
+    enabled: bool,
+) -> CommandResult<()> {
+    Ok(state.set_jupiter_token_list(enabled)?)
+}
+
+// ── Alpha Vantage quota ─────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_alphavantage_refresh_hours() -> CommandResult<u32> {
+    Ok(crate::providers::alphavantage::refresh_hours())
+}
+
+#[tauri::command]
+pub async fn set_alphavantage_refresh_hours(
+    state: tauri::State<'_, Arc<CoreState>>,
+    hours: u32,
+) -> CommandResult<()> {
+    Ok(state.set_alphavantage_refresh_hours(hours)?)
+}
+
+#[tauri::command]
+pub async fn get_alphavantage_quota() -> CommandResult<u32> {
+    Ok(crate::providers::alphavantage::quota_remaining().await)
+}
+
+// ── EODHD default exchange ──────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_eodhd_default_exchange() -> CommandResult<String> {
+    Ok(crate::providers::eodhd::default_exchange())
+}
+
+#[tauri::command]
+pub async fn set_eodhd_default_exchange(
+    state: tauri::State<'_, Arc<CoreState>>,
+    exchange: String,
+) -> CommandResult<()> {
+    state.set_eodhd_default_exchange(&exchange).map_err(CommandError::bad_request)
+}
+
+// ── Provider metadata ───────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_provider_metadata_source(
+    state: tauri::State<'_, Arc<CoreState>>,
+) -> CommandResult<MetadataSource> {
+    Ok(crate::core_state::load_provider_metadata_source(&state.db))
+}
+
+#[tauri::command]
+pub async fn get_provider_metadata_status() -> CommandResult<MetadataStatus> {
+    Ok(crate::providers::metadata::status())
+}
+
+/// 儲存來源設定並立即重新載入覆寫，回傳套用後的狀態
+#[tauri::command]
+pub async fn set_provider_metadata_source(
+    state: tauri::State<'_, Arc<CoreState>>,
+    source: MetadataSource,
+) -> CommandResult<MetadataStatus> {
+    state.set_provider_metadata_source(source).await.map_err(CommandError::bad_request)
+}
+
+// ── Subgraph protocols ──────────────────────────────────────────
+
+/// 完整的 subgraph protocol registry（內建 + 自訂）
+#[tauri::command]
+pub async fn get_subgraph_protocols() -> CommandResult<Vec<SubgraphProtocol>> {
+    Ok(crate::providers::subgraph::protocol_registry())
+}
+
+#[tauri::command]
+pub async fn set_subgraph_protocols(
+    state: tauri::State<'_, Arc<CoreState>>,
+    protocols: Vec<SubgraphProtocol>,
+) -> CommandResult<()> {
+    state.set_subgraph_protocols(protocols).map_err(CommandError::bad_request)
+}
+
+// ── Demo Mode ───────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_demo_mode() -> CommandResult<MockConfig> {
+    Ok(crate::providers::mock::mock_config())
+}
+
+#[tauri::command]
+pub async fn set_demo_mode(
+    state: tauri::State<'_, Arc<CoreState>>,
+    config: MockConfig,
+) -> CommandResult<()> {
+    state.set_demo_mode(config).await.map_err(CommandError::bad_request)
+}
+
+// ── History Replay ──────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_replay_config() -> CommandResult<ReplayConfig> {
+    Ok(crate::providers::replay::replay_config())
+}
+
+#[tauri::command]
+pub async fn set_replay_config(
+    state: tauri::State<'_, Arc<CoreState>>,
+    config: ReplayConfig,
+) -> CommandResult<()> {
+    state.set_replay(config).await.map_err(CommandError::bad_request)
+}
+
+// ── Recording Schedule ──────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_recording_schedule(
+    state: tauri::State<'_, Arc<CoreState>>,
+) -> CommandResult<RecordingSchedule> {
+    Ok(crate::schedule::load_schedule(&state.db))
+}
+
+#[tauri::command]
+pub async fn set_recording_schedule(
+    state: tauri::State<'_, Arc<CoreState>>,
+    schedule: RecordingSchedule,
+) -> CommandResult<()> {
+    state.recording_scheduler.save(&schedule).map_err(CommandError::bad_request)
+}
+
+// ── Low-Power Mode ──────────────────────────────────────────────
+
+#[derive(serde::Serialize)]
+pub struct PowerModeStatus {
+    #[serde(flatten)]
+    pub config: PowerConfig,
+    pub low_power: bool,
+}
+
+#[tauri::command]
+pub async fn get_power_mode() -> CommandResult<PowerModeStatus> {
+    Ok(PowerModeStatus {
+        config: crate::power::power_config(),
+        low_power: crate::power::is_low_power(),
+    })
+}
+
+#[tauri::command]
+pub async fn set_power_mode(
+    state: tauri::State<'_, Arc<CoreState>>,
+    config: PowerConfig,
+) -> CommandResult<()> {
+    state.set_power_mode(config).await.map_err(CommandError::bad_request)
+}
+
+// ── Gas Tracker ─────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_gas_config() -> CommandResult<GasConfig> {
+    Ok(crate::gas::gas_config())
+}
+
+#[tauri::command]
+pub async fn set_gas_config(
+    state: tauri::State<'_, Arc<CoreState>>,
+    config: GasConfig,
+) -> CommandResult<()> {
+    state.set_gas_config(config).await.map_err(CommandError::bad_request)
+}
+
+// ── Depeg Monitor ───────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_depeg_config() -> CommandResult<DepegConfig> {
+    Ok(crate::depeg::depeg_config())
+}
+
+#[tauri::command]
+pub async fn set_depeg_config(
+    state: tauri::State<'_, Arc<CoreState>>,
+    config: DepegConfig,
+) -> CommandResult<()> {
+    state.set_depeg_config(config).await.map_err(CommandError::bad_request)
+}
+
+// ── Economic Calendar ───────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_calendar_config() -> CommandResult<CalendarConfig> {
+    Ok(crate::calendar::calendar_config())
+}
+
+#[tauri::command]
+pub async fn set_calendar_config(
+    state: tauri::State<'_, Arc<CoreState>>,
+    config: CalendarConfig,
+) -> CommandResult<()> {
+    state.set_calendar_config(config).await.map_err(CommandError::bad_request)
+}
+
+// ── Price Sanity ────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_price_sanity_config() -> CommandResult<SanityConfig> {
+    Ok(crate::sanity::sanity_config())
+}
+
+#[tauri::command]
+pub async fn set_price_sanity_config(
+    state: tauri::State<'_, Arc<CoreState>>,
+    config: SanityConfig,
+) -> CommandResult<()> {
+    state.set_sanity_config(config).map_err(CommandError::bad_request)
+}
+
+// ── Cache Limit / Status ────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_cache_limit() -> CommandResult<CacheConfig> {
+    Ok(crate::cache_limit::cache_config())
+}
+
+#[tauri::command]
+pub async fn set_cache_limit(
+    state: tauri::State<'_, Arc<CoreState>>,
+    config: CacheConfig,
+) -> CommandResult<()> {
+    state.set_cache_config(config).map_err(CommandError::bad_request)
+}
+
+// ── Event Throttle ──────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_emit_config() -> CommandResult<EmitConfig> {
+    Ok(crate::emit_throttle::emit_config())
+}
+
+#[tauri::command]
+pub async fn set_emit_config(
+    state: tauri::State<'_, Arc<CoreState>>,
+    config: EmitConfig,
+) -> CommandResult<()> {
+    state.set_emit_config(config).map_err(CommandError::bad_request)
+}
+
+// ── Record Health ───────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_record_stall_config() -> CommandResult<RecordStallConfig> {
+    Ok(crate::record_health::record_stall_config())
+}
+
+#[tauri::command]
+pub async fn set_record_stall_config(
+    state: tauri::State<'_, Arc<CoreState>>,
+    config: RecordStallConfig,
+) -> CommandResult<()> {
+    state.set_record_stall_config(config).map_err(CommandError::bad_request)
+}
+
+// ── Latency Tune ────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_latency_tune_config() -> CommandResult<LatencyTuneConfig> {
+    Ok(crate::latency_tune::latency_tune_config())
+}
+
+#[tauri::command]
+pub async fn set_latency_tune_config(
+    state: tauri::State<'_, Arc<CoreState>>,
+    config: LatencyTuneConfig,
+) -> CommandResult<()> {
+    state.set_latency_tune_config(config).map_err(CommandError::bad_request)
+}
+
+// ── WS-first Polling ────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_ws_first_config() -> CommandResult<WsFirstConfig> {
+    Ok(crate::ws_first::ws_first_config())
+}
+
+#[tauri::command]
+pub async fn set_ws_first_config(
+    state: tauri::State<'_, Arc<CoreState>>,
+    config: WsFirstConfig,
+) -> CommandResult<()> {
+    state.set_ws_first_config(config).map_err(CommandError::bad_request)
+}
+
+// ── Candles ─────────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_candle_config() -> CommandResult<CandleConfig> {
+    Ok(crate::candles::candle_config())
+}
+
+#[tauri::command]
+pub async fn set_candle_config(
+    state: tauri::State<'_, Arc<CoreState>>,
+    config: CandleConfig,
+) -> CommandResult<()> {
+    state.set_candle_config(config).map_err(CommandError::bad_request)
+}
+
+// ── Telemetry ───────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_otlp_config() -> CommandResult<OtlpConfig> {
+    Ok(crate::telemetry::otlp_config())
+}
+
+#[tauri::command]
+pub async fn set_otlp_config(
+    state: tauri::State<'_, Arc<CoreState>>,
+    config: OtlpConfig,
+) -> CommandResult<()> {
+    state.set_otlp_config(config).map_err(CommandError::bad_request)
+}
@@ -1,20 +1,24 @@
+use super::{CommandError, CommandResult};
+use crate::basket::BasketWeight;
 use crate::core_state::CoreState;
 use crate::db::{BatchAddItem, BatchAddResult, Subscription};
+use crate::subscription_csv::CsvImportReport;
+use crate::subscription_dedup::{DuplicateGroup, MergeResult};
 use std::sync::Arc;
 
 #[tauri::command]
 pub async fn list_subscriptions(
     state: tauri::State<'_, Arc<CoreState>>,
     sub_type: String,
-) -> Result<Vec<Subscription>, String> {
-    state.db.list_subscriptions(&sub_type)
+) -> CommandResult<Vec<Subscription>> {
+    Ok(state.db.list_subscriptions(&sub_type)?)
 }
 
 #[tauri::command]
 pub async fn list_all_subscriptions(
     state: tauri::State<'_, Arc<CoreState>>,
-) -> Result<Vec<Subscription>, String> {
-    state.db.list_all_subscriptions()
+) -> CommandResult<Vec<Subscription>> {
+    Ok(state.db.list_all_subscriptions()?)
 }
 
 #[tauri::command]
@@ -30,23 +34,26 @@ pub async fn add_subscription(
     pool_address: Option<String>,
     token_from: Option<String>,
     token_to: Option<String>,
-) -> Result<i64, String> {
+) -> CommandResult<i64> {
     use crate::providers::normalize_symbol;
     let normalized = if sub_type == "dex" {
         symbol.clone()
     } else {
         normalize_symbol(&symbol, &asset_type)
     };
-    let id = state.db.add_subscription(
-        &sub_type,
-        &normalized,
-        display_name.as_deref(),
-        &provider_id,
-        &asset_type,
-        pool_address.as_deref(),
-        token_from.as_deref(),
-        token_to.as_deref(),
-    )?;
+    let id = state
+        .db
+        .add_subscription(
+            &sub_type,
+            &normalized,
+            display_name.as_deref(),
+            &provider_id,
+            &asset_type,
+            pool_address.as_deref(),
+            token_from.as_deref(),
+            token_to.as_deref(),
+        )
+        .map_err(CommandError::bad_request)?;
     state.polling.reload();
     Ok(id)
 }
@@ -55,7 +62,7 @@ pub async fn add_subscription(
 pub async fn add_subscriptions_batch(
     state: tauri::State<'_, Arc<CoreState>>,
     items: Vec<BatchAddItem>,
-) -> Result<BatchAddResult, String> {
+) -> CommandResult<BatchAddResult> {
     let mut succeeded = Vec::new();
     let mut failed = Vec::new();
     let mut duplicates = Vec::new();
@@ -90,6 +97,49 @@ pub async fn add_subscriptions_batch(
     })
 }
 
+/// 建立以其他訂閱加權組成的籃子訂閱，回傳新 ID
+#[tauri::command]
+pub async fn create_basket(
+    state: tauri::State<'_, Arc<CoreState>>,
+    symbol: String,
+    display_name: Option<String>,
+    components: Vec<BasketWeight>,
+) -> CommandResult<i64> {
+    crate::basket::create(&state, &symbol, display_name.as_deref(), &components)
+        .await
+        .map_err(CommandError::bad_request)
+}
+
+/// 從 CSV 內容批次匯入 asset 訂閱（`validate` 預設 true：逐列向 provider 驗證 symbol）
+#[tauri::command]
+pub async fn import_subscriptions_csv(
+    state: tauri::State<'_, Arc<CoreState>>,
+    content: String,
+    validate: Option<bool>,
+) -> CommandResult<CsvImportReport> {
+    crate::subscription_csv::import(&state, &content, validate.unwrap_or(true))
+        .await
+        .map_err(CommandError::bad_request)
+}
+
+/// 找出以不同 provider / 格式追蹤同一資產的重複訂閱
+#[tauri::command]
+pub async fn find_duplicate_subscriptions(
+    state: tauri::State<'_, Arc<CoreState>>,
+) -> CommandResult<Vec<DuplicateGroup>> {
+    Ok(crate::subscription_dedup::find_duplicates(&state.db)?)
+}
+
+/// 把 `merge_ids` 合併進 `keep_id`（價格紀錄、view、通知規則一併移過去）並刪除
+#[tauri::command]
+pub async fn merge_subscriptions(
+    state: tauri::State<'_, Arc<CoreState>>,
+    keep_id: i64,
+    merge_ids: Vec<i64>,
+) -> CommandResult<MergeResult> {
+    crate::subscription_dedup::merge(&state, keep_id, &merge_ids).map_err(CommandError::bad_request)
+}
+
 #[tauri::command]
 pub async fn update_subscription(
     state: tauri::State<'_, Arc<CoreState>>,
@@ -98,7 +148,7 @@ pub async fn update_subscription(
     display_name: Option<String>,
     provider_id: String,
     asset_type: String,
-) -> Result<(), String> {
+) -> CommandResult<()> {
     use crate::providers::normalize_symbol;
     let normalized = normalize_symbol(&symbol, &asset_type);
     state.db.update_subscription(
@@ -112,8 +162,19 @@ pub async fn update_subscription(
     Ok(())
 }
 
+/// 設定訂閱的 provider 專屬參數（JSON 物件；null 清除），下一次查詢即生效
+#[tauri::command]
+pub async fn set_provider_params(
+    state: tauri::State<'_, Arc<CoreState>>,
+    id: i64,
+    params: Option<serde_json::Value>,
+) -> CommandResult<()> {
+    let json = params.map(|p| p.to_string());
+    state.db.set_provider_params(id, json.as_deref()).map_err(CommandError::bad_request)
+}
+
 #[tauri::command]
-pub async fn remove_subscription(state: tauri::State<'_, Arc<CoreState>>, id: i64) -> Result<(), String> {
+pub async fn remove_subscription(state: tauri::State<'_, Arc<CoreState>>, id: i64) -> CommandResult<()> {
     state.db.remove_subscription(id)?;
     state.polling.reload();
     Ok(())
@@ -123,7 +184,7 @@ pub async fn remove_subscription(state: tauri::State<'_, Arc<CoreState>>, id: i6
 pub async fn remove_subscriptions(
     state: tauri::State<'_, Arc<CoreState>>,
     ids: Vec<i64>,
-) -> Result<(), String> {
+) -> CommandResult<()> {
     state.db.remove_subscriptions(&ids)?;
     state.polling.reload();
     Ok(())
@@ -133,6 +194,6 @@ pub async fn remove_subscriptions(
 pub async fn has_api_key(
     state: tauri::State<'_, Arc<CoreState>>,
     provider_id: String,
-) -> Result<bool, String> {
+) -> CommandResult<bool> {
     Ok(state.db.has_api_key(&provider_id))
 }
@@ -1,9 +1,15 @@
+use super::{CommandError, CommandResult};
 use crate::core_state::CoreState;
+use crate::deep_link::DeepLinkOutcome;
+use crate::cloud_backup::{CloudBackup, CloudBackupConfig};
+use crate::settings_sync::SyncConfig;
+use crate::core_state::AppStatus;
+use crate::tray::TrayConfig;
 use std::sync::Arc;
 use tauri_plugin_shell::ShellExt;
 
 #[tauri::command]
-pub async fn get_data_dir(app: tauri::AppHandle) -> Result<String, String> {
+pub async fn get_data_dir(app: tauri::AppHandle) -> CommandResult<String> {
     use tauri::Manager;
     let dir = app
         .path()
@@ -43,58 +49,277 @@ pub async fn get_data_dir(app: tauri::AppHandle) -> Result<String, String> {
     Ok(dir.to_string_lossy().to_string())
 }
 
-// ── API Settings ────────────────────────────────────────────────
+// ── Logging ─────────────────────────────────────────────────────
 
 #[tauri::command]
-pub async fn get_api_port(state: tauri::State<'_, Arc<CoreState>>) -> Result<u16, String> {
-    let val = state.db.get_setting("api_port")?.unwrap_or("8080".into());
-    val.parse::<u16>()
-        .map_err(|e| format!("Invalid port: {}", e))
+pub async fn get_recent_logs(
+    level: Option<String>,
+    limit: Option<usize>,
+) -> CommandResult<Vec<crate::logging::LogEntry>> {
+    crate::logging::recent_logs(level.as_deref(), limit.unwrap_or(200))
+        .map_err(CommandError::bad_request)
 }
 
 #[tauri::command]
-pub async fn set_api_port(state: tauri::State<'_, Arc<CoreState>>, port: u16) -> Result<(), String> {
-    if port < 1024 {
-        return Err("Port must be between 1024 and 65535".to_string());
-    }
-    state.db.set_setting("api_port", &port.to_string())
+pub async fn get_log_level() -> CommandResult<String> {
+    Ok(crate::logging::current_level())
 }
 
 #[tauri::command]
-pub async fn get_api_enabled(state: tauri::State<'_, Arc<CoreState>>) -> Result<bool, String> {
-    let val = state.db.get_setting("api_enabled")?.unwrap_or("0".into());
-    Ok(val == "1")
+pub async fn set_log_level(
+    state: tauri::State<'_, Arc<CoreState>>,
+    level: String,
+) -> CommandResult<()> {
+    state.set_log_level(&level).map_err(CommandError::bad_request)
 }
 
 #[tauri::command]
-pub async fn set_api_enabled(
+pub async fn get_language() -> CommandResult<String> {
+    Ok(crate::i18n::locale().id().to_string())
+}
+
+#[tauri::command]
+pub async fn set_language(
     state: tauri::State<'_, Arc<CoreState>>,
-    enabled: bool,
-) -> Result<(), String> {
-    state
-        .db
-        .set_setting("api_enabled", if enabled { "1" } else { "0" })
+    language: String,
+) -> CommandResult<()> {
+    Ok(state.set_language(&language)?)
+}
+
+#[tauri::command]
+pub async fn open_log_dir(app: tauri::AppHandle) -> CommandResult<String> {
+    let dir = crate::logging::log_dir().ok_or_else(|| "Logging is not initialized".to_string())?;
+    let path_str = dir.to_string_lossy().to_string();
+
+    #[cfg(target_os = "windows")]
+    let opener = "explorer";
+    #[cfg(target_os = "macos")]
+    let opener = "open";
+    #[cfg(target_os = "linux")]
+    let opener = "xdg-open";
+
+    app.shell()
+        .command(opener)
+        .arg(&path_str)
+        .spawn()
+        .map_err(|e| format!("Failed to open folder: {}", e))?;
+
+    Ok(path_str)
 }
 
 // ── Polling ─────────────────────────────────────────────────────
 
 #[tauri::command]
-pub async fn reload_polling(state: tauri::State<'_, Arc<CoreState>>) -> Result<(), String> {
+pub async fn reload_polling(state: tauri::State<'_, Arc<CoreState>>) -> CommandResult<()> {
+    state.db.invalidate_provider_settings_cache();
     state.polling.reload();
     Ok(())
 }
 
 #[tauri::command]
 pub async fn set_unattended_polling(
+    app: tauri::AppHandle,
     state: tauri::State<'_, Arc<CoreState>>,
     enabled: bool,
-) -> Result<(), String> {
+) -> CommandResult<()> {
     state.polling.set_unattended(enabled).await;
+    crate::tray::refresh(&app).await;
     Ok(())
 }
 
 /// Retained for external HTTP API consumers — not invoked by frontend UI
 #[tauri::command]
-pub async fn get_unattended_polling(state: tauri::State<'_, Arc<CoreState>>) -> Result<bool, String> {
+pub async fn get_unattended_polling(state: tauri::State<'_, Arc<CoreState>>) -> CommandResult<bool> {
     Ok(state.polling.is_unattended().await)
 }
+
+#[tauri::command]
+pub async fn set_recording_paused(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Arc<CoreState>>,
+    paused: bool,
+) -> CommandResult<()> {
+    state.set_recording_paused(paused)?;
+    crate::tray::refresh(&app).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_recording_paused(state: tauri::State<'_, Arc<CoreState>>) -> CommandResult<bool> {
+    Ok(state.polling.is_recording_paused())
+}
+
+#[tauri::command]
+pub async fn get_app_status(state: tauri::State<'_, Arc<CoreState>>) -> CommandResult<AppStatus> {
+    Ok(state.status().await)
+}
+
+// ── Cloud Backup ────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_cloud_backup_config() -> CommandResult<CloudBackupConfig> {
+    Ok(crate::cloud_backup::cloud_backup_config().redacted())
+}
+
+#[tauri::command]
+pub async fn set_cloud_backup_config(
+    state: tauri::State<'_, Arc<CoreState>>,
+    config: CloudBackupConfig,
+) -> CommandResult<()> {
+    state
+        .set_cloud_backup_config(config)
+        .map_err(CommandError::bad_request)
+}
+
+#[tauri::command]
+pub async fn run_cloud_backup(state: tauri::State<'_, Arc<CoreState>>) -> CommandResult<CloudBackup> {
+    state.run_cloud_backup().await.map_err(CommandError::bad_request)
+}
+
+#[tauri::command]
+pub async fn list_cloud_backups(state: tauri::State<'_, Arc<CoreState>>) -> CommandResult<Vec<CloudBackup>> {
+    state.list_cloud_backups().await.map_err(CommandError::bad_request)
+}
+
+#[tauri::command]
+pub async fn restore_from_cloud(
+    state: tauri::State<'_, Arc<CoreState>>,
+    key: String,
+) -> CommandResult<()> {
+    state
+        .restore_from_cloud(&key)
+        .await
+        .map_err(CommandError::bad_request)
+}
+
+// ── Settings Sync ───────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_sync_config() -> CommandResult<serde_json::Value> {
+    Ok(serde_json::json!({
+        "config": crate::settings_sync::sync_config().redacted(),
+        "status": crate::settings_sync::sync_status(),
+    }))
+}
+
+#[tauri::command]
+pub async fn set_sync_config(
+    state: tauri::State<'_, Arc<CoreState>>,
+    config: SyncConfig,
+) -> CommandResult<()> {
+    state.set_sync_config(config).map_err(CommandError::bad_request)
+}
+
+#[tauri::command]
+pub async fn sync_settings_now(state: tauri::State<'_, Arc<CoreState>>) -> CommandResult<serde_json::Value> {
+    let action = state.sync_settings_now().await?;
+    Ok(serde_json::json!({
+        "action": action,
+        "status": crate::settings_sync::sync_status(),
+    }))
+}
+
+// ── Kiosk ───────────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_kiosk_config() -> CommandResult<crate::kiosk::KioskInfo> {
+    Ok(crate::kiosk::kiosk_state().info())
+}
+
+#[tauri::command]
+pub async fn set_kiosk_config(
+    state: tauri::State<'_, Arc<CoreState>>,
+    config: crate::kiosk::KioskConfig,
+    current_pin: Option<String>,
+) -> CommandResult<()> {
+    state
+        .set_kiosk_config(config, current_pin.as_deref().unwrap_or(""))
+        .map_err(CommandError::bad_request)
+}
+
+// ── LAN Listener ────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_lan_listener_config() -> CommandResult<crate::lan_listener::LanListenerInfo> {
+    Ok(crate::lan_listener::lan_listener_info())
+}
+
+/// 儲存區網 API listener 設定並依新的 bind / port 重新啟動
+#[tauri::command]
+pub async fn set_lan_listener_config(
+    state: tauri::State<'_, Arc<CoreState>>,
+    config: crate::lan_listener::LanListenerConfig,
+) -> CommandResult<()> {
+    state
+        .set_lan_listener_config(config)
+        .map_err(CommandError::bad_request)?;
+    crate::api::restart_lan_listener(state.inner().clone());
+    Ok(())
+}
+
+// ── Deep Links ──────────────────────────────────────────────────
+
+/// 由前端套用 `stockenboard://` 連結（例如貼上的連結），回傳結果供前端切換頁面或重新載入
+#[tauri::command]
+pub async fn open_deep_link(
+    state: tauri::State<'_, Arc<CoreState>>,
+    url: String,
+) -> CommandResult<DeepLinkOutcome> {
+    crate::deep_link::parse(&url)
+        .and_then(|action| crate::deep_link::apply(&state, action))
+        .map_err(CommandError::bad_request)
+}
+
+// ── System Tray ─────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_tray_config(state: tauri::State<'_, Arc<CoreState>>) -> CommandResult<TrayConfig> {
+    Ok(crate::tray::load_config(&state.db))
+}
+
+#[tauri::command]
+pub async fn set_tray_config(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Arc<CoreState>>,
+    config: TrayConfig,
+) -> CommandResult<()> {
+    crate::tray::save_config(&state.db, &config)?;
+    crate::tray::refresh(&app).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_close_to_tray(state: tauri::State<'_, Arc<CoreState>>) -> CommandResult<bool> {
+    Ok(crate::tray::close_to_tray_enabled(&state.db))
+}
+
+#[tauri::command]
+pub async fn set_close_to_tray(
+    state: tauri::State<'_, Arc<CoreState>>,
+    enabled: bool,
+) -> CommandResult<()> {
+    Ok(crate::tray::set_close_to_tray(&state.db, enabled)?)
+}
+
+// ── Autostart ───────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_autostart(app: tauri::AppHandle) -> CommandResult<bool> {
+    use tauri_plugin_autostart::ManagerExt;
+    Ok(app
+        .autolaunch()
+        .is_enabled()
+        .map_err(|e| format!("Failed to read autostart state: {}", e))?)
+}
+
+#[tauri::command]
+pub async fn set_autostart(app: tauri::AppHandle, enabled: bool) -> CommandResult<()> {
+    use tauri_plugin_autostart::ManagerExt;
+    let autolaunch = app.autolaunch();
+    let result = if enabled {
+        autolaunch.enable()
+    } else {
+        autolaunch.disable()
+    };
+    Ok(result.map_err(|e| format!("Failed to update autostart: {}", e))?)
+}
@@ -0,0 +1,71 @@
+use super::{CommandError, CommandResult};
+use crate::core_state::CoreState;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::Manager;
+
+/// 主題背景圖存放目錄（app data 下的 `theme_bg`）
+fn theme_bg_dir(app: &tauri::AppHandle) -> CommandResult<PathBuf> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app directory: {}", e))?;
+    Ok(dir.join("theme_bg"))
+}
+
+#[tauri::command]
+pub async fn save_theme_bg(
+    state: tauri::State<'_, Arc<CoreState>>,
+    app: tauri::AppHandle,
+    theme_id: String,
+) -> CommandResult<String> {
+    let file = rfd::AsyncFileDialog::new()
+        .add_filter("Images", &["png", "jpg", "jpeg", "webp"])
+        .set_title("Select Background Image")
+        .pick_file()
+        .await
+        .ok_or_else(CommandError::cancelled)?;
+    state.file_access.record_pick(file.path());
+    let dir = theme_bg_dir(&app)?;
+    let dest = crate::icons::save_theme_bg_bytes(&dir, &theme_id, &file.read().await).await?;
+    Ok(dest.to_string_lossy().to_string())
+}
+
+/// 不開對話框的 `save_theme_bg`：由 `path` 或 `data`（base64 / data URL）擇一提供 PNG / JPEG / WebP。
+#[tauri::command]
+pub async fn save_theme_bg_from_path(
+    app: tauri::AppHandle,
+    theme_id: String,
+    path: Option<String>,
+    data: Option<String>,
+) -> CommandResult<String> {
+    let (bytes, _) = crate::icons::read_image_input(path.as_deref(), data.as_deref()).await?;
+    let dir = theme_bg_dir(&app)?;
+    let dest = crate::icons::save_theme_bg_bytes(&dir, &theme_id, &bytes).await?;
+    Ok(dest.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+pub async fn remove_theme_bg(app: tauri::AppHandle, theme_id: String) -> CommandResult<()> {
+    let dir = theme_bg_dir(&app)?;
+    for ext in &["png", "jpg", "jpeg", "webp", "img"] {
+        let path = dir.join(format!("{}.{}", theme_id, ext));
+        let _ = tokio::fs::remove_file(&path).await;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_theme_bg_path(
+    app: tauri::AppHandle,
+    theme_id: String,
+) -> CommandResult<Option<String>> {
+    let dir = theme_bg_dir(&app)?;
+    for ext in &["png", "jpg", "jpeg", "webp", "img"] {
+        let path = dir.join(format!("{}.{}", theme_id, ext));
+        if path.exists() {
+            return Ok(Some(path.to_string_lossy().to_string()));
+        }
+    }
+    Ok(None)
+}
@@ -1,3 +1,4 @@
+use super::CommandResult;
 use crate::core_state::CoreState;
 use crate::db::{ViewRow, ViewSubCount};
 use std::sync::Arc;
@@ -6,8 +7,8 @@ use std::sync::Arc;
 pub async fn list_views(
     state: tauri::State<'_, Arc<CoreState>>,
     view_type: String,
-) -> Result<Vec<ViewRow>, String> {
-    state.db.list_views(&view_type)
+) -> CommandResult<Vec<ViewRow>> {
+    Ok(state.db.list_views(&view_type)?)
 }
 
 #[tauri::command]
@@ -15,8 +16,8 @@ pub async fn create_view(
     state: tauri::State<'_, Arc<CoreState>>,
     name: String,
     view_type: String,
-) -> Result<i64, String> {
-    state.db.create_view(&name, &view_type)
+) -> CommandResult<i64> {
+    Ok(state.db.create_view(&name, &view_type)?)
 }
 
 #[tauri::command]
@@ -24,28 +25,28 @@ pub async fn rename_view(
     state: tauri::State<'_, Arc<CoreState>>,
     id: i64,
     name: String,
-) -> Result<(), String> {
-    state.db.rename_view(id, &name)
+) -> CommandResult<()> {
+    Ok(state.db.rename_view(id, &name)?)
 }
 
 #[tauri::command]
-pub async fn delete_view(state: tauri::State<'_, Arc<CoreState>>, id: i64) -> Result<(), String> {
-    state.db.delete_view(id)
+pub async fn delete_view(state: tauri::State<'_, Arc<CoreState>>, id: i64) -> CommandResult<()> {
+    Ok(state.db.delete_view(id)?)
 }
 
 #[tauri::command]
 pub async fn get_view_sub_counts(
     state: tauri::State<'_, Arc<CoreState>>,
-) -> Result<Vec<ViewSubCount>, String> {
-    state.db.get_view_sub_counts()
+) -> CommandResult<Vec<ViewSubCount>> {
+    Ok(state.db.get_view_sub_counts()?)
 }
 
 #[tauri::command]
 pub async fn get_view_subscription_ids(
     state: tauri::State<'_, Arc<CoreState>>,
     view_id: i64,
-) -> Result<Vec<i64>, String> {
-    state.db.get_view_subscription_ids(view_id)
+) -> CommandResult<Vec<i64>> {
+    Ok(state.db.get_view_subscription_ids(view_id)?)
 }
 
 #[tauri::command]
@@ -53,8 +54,8 @@ pub async fn add_sub_to_view(
     state: tauri::State<'_, Arc<CoreState>>,
     view_id: i64,
     subscription_id: i64,
-) -> Result<(), String> {
-    state.db.add_sub_to_view(view_id, subscription_id)
+) -> CommandResult<()> {
+    Ok(state.db.add_sub_to_view(view_id, subscription_id)?)
 }
 
 #[tauri::command]
@@ -62,6 +63,6 @@ pub async fn remove_sub_from_view(
     state: tauri::State<'_, Arc<CoreState>>,
     view_id: i64,
     subscription_id: i64,
-) -> Result<(), String> {
-    state.db.remove_sub_from_view(view_id, subscription_id)
+) -> CommandResult<()> {
+    Ok(state.db.remove_sub_from_view(view_id, subscription_id)?)
 }
@@ -0,0 +1,31 @@
+use super::{CommandError, CommandResult};
+use crate::core_state::CoreState;
+use crate::ws_manager::WsStatus;
+use std::sync::Arc;
+
+/// 桌面前端在 WsManager 中的 owner
+const WS_OWNER: &str = "app";
+
+/// 設定 provider 的串流 symbol；已連線時只增減訂閱，不重建 socket
+#[tauri::command]
+pub async fn start_ws_stream(
+    state: tauri::State<'_, Arc<CoreState>>,
+    provider_id: String,
+    symbols: Vec<String>,
+) -> CommandResult<()> {
+    state.ws.set_symbols(WS_OWNER, &provider_id, symbols).map_err(CommandError::bad_request)
+}
+
+#[tauri::command]
+pub async fn stop_ws_stream(
+    state: tauri::State<'_, Arc<CoreState>>,
+    provider_id: String,
+) -> CommandResult<()> {
+    state.ws.set_symbols(WS_OWNER, &provider_id, Vec::new()).map_err(CommandError::bad_request)
+}
+
+/// 所有 WS 連線的狀態（含 `/api/ws` client 要求的串流）
+#[tauri::command]
+pub async fn get_ws_streams(state: tauri::State<'_, Arc<CoreState>>) -> CommandResult<Vec<WsStatus>> {
+    Ok(state.ws.statuses())
+}
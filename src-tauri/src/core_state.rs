@@ -3,26 +3,42 @@
 //! 包含資料庫、Provider Registry、Event Bus、通知引擎、AI 排程器、全局冷卻期、輪詢管理器。
 //! 不含任何 Tauri 相關依賴。
 
+use serde::Serialize;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::broadcast;
 
-#[cfg(feature = "desktop")]
-use std::collections::HashMap;
-#[cfg(feature = "desktop")]
-use tokio::sync::RwLock;
-#[cfg(feature = "desktop")]
-use tokio::task::JoinHandle;
+use std::collections::{HashMap, HashSet};
 
-use crate::db::DbPool;
+use crate::db::{AppConfig, ConfigImportMode, ConfigImportSummary, DbPool, DbResult};
+use crate::cache_limit::{CacheConfig, CacheStatus};
+use crate::emit_throttle::EmitConfig;
+use crate::record_health::RecordStallConfig;
+use crate::latency_tune::LatencyTuneConfig;
+use crate::ws_first::WsFirstConfig;
+use crate::candles::CandleConfig;
+use crate::calendar::CalendarConfig;
+use crate::depeg::DepegConfig;
 use crate::events::AppEvent;
+use crate::file_access::FileAccess;
+use crate::gas::GasConfig;
 use crate::notifications::engine::NotificationEngine;
 use crate::notifications::ai_scheduler::AiScheduler;
 use crate::notifications::global_cooldown::GlobalCooldown;
 use crate::polling::PollingManager;
+use crate::power::PowerConfig;
+use crate::providers::metadata::{MetadataSource, MetadataStatus};
+use crate::providers::mock::MockConfig;
+use crate::providers::replay::ReplayConfig;
+use crate::providers::subgraph::SubgraphProtocol;
+use crate::cloud_backup::{CloudBackup, CloudBackupConfig};
+use crate::sanity::SanityConfig;
+use crate::kiosk::KioskConfig;
+use crate::settings_sync::{SyncAction, SyncConfig};
+use crate::telemetry::OtlpConfig;
 use crate::providers::registry::ProviderRegistry;
-#[cfg(feature = "desktop")]
-use crate::providers::WsTickerUpdate;
+use crate::schedule::RecordingScheduler;
+use crate::ws_manager::WsManager;
 
 /// 確保 DB schema 一致 — 版本不同就刪除重建。
 ///
@@ -33,7 +49,7 @@ pub fn ensure_clean_db(app_dir: &Path) {
     const SCHEMA_VER: &str = "8"; // Bumped for push notifications tables
     let current = std::fs::read_to_string(&marker).unwrap_or_default();
     if current.trim() != SCHEMA_VER {
-        eprintln!(
+        tracing::warn!(
             "[DB] Schema version mismatch (current={:?}, expected={}), deleting and recreating database",
             current.trim(),
             SCHEMA_VER
@@ -46,6 +62,380 @@ pub fn ensure_clean_db(app_dir: &Path) {
     }
 }
 
+/// 從 app settings 讀取 demo 模式設定（缺少或無效的值使用預設）
+pub fn load_mock_config(db: &DbPool) -> MockConfig {
+    let setting = |key: &str| db.get_setting(key).ok().flatten();
+    let defaults = MockConfig::default();
+    MockConfig {
+        enabled: setting("demo_mode").is_some_and(|v| v == "1"),
+        volatility_pct: setting("mock_volatility_pct")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(defaults.volatility_pct),
+        latency_ms: setting("mock_latency_ms")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(defaults.latency_ms),
+    }
+}
+
+/// 從 app settings 讀取 history replay 設定
+pub fn load_replay_config(db: &DbPool) -> ReplayConfig {
+    let setting = |key: &str| db.get_setting(key).ok().flatten();
+    let defaults = ReplayConfig::default();
+    ReplayConfig {
+        enabled: setting("replay_enabled").is_some_and(|v| v == "1"),
+        from: setting("replay_from")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(defaults.from),
+        to: setting("replay_to").and_then(|v| v.parse().ok()),
+        speed: setting("replay_speed")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(defaults.speed),
+    }
+}
+
+/// 從 app settings 讀取省電模式設定
+pub fn load_power_config(db: &DbPool) -> PowerConfig {
+    let setting = |key: &str| db.get_setting(key).ok().flatten();
+    PowerConfig {
+        enabled: setting("low_power_mode").is_some_and(|v| v == "1"),
+        factor: setting("low_power_factor")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(PowerConfig::default().factor),
+    }
+}
+
+/// 從 app settings 讀取是否為 Yahoo 股票額外查詢 quoteSummary
+pub fn load_yahoo_quote_summary(db: &DbPool) -> bool {
+    db.get_setting("yahoo_quote_summary").ok().flatten().as_deref() == Some("1")
+}
+
+/// 從 app settings 讀取是否載入 Jupiter verified token list
+pub fn load_jupiter_token_list(db: &DbPool) -> bool {
+    db.get_setting("jupiter_token_list").ok().flatten().as_deref() == Some("1")
+}
+
+/// 從 app settings 讀取 Alpha Vantage 單一 symbol 的最短更新間隔（小時）
+pub fn load_alphavantage_refresh_hours(db: &DbPool) -> u32 {
+    db.get_setting("alphavantage_refresh_hours")
+        .ok()
+        .flatten()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(crate::providers::alphavantage::DEFAULT_REFRESH_HOURS)
+}
+
+/// 從 app settings 讀取 EODHD 未帶後綴 symbol 的預設交易所
+pub fn load_eodhd_default_exchange(db: &DbPool) -> String {
+    db.get_setting("eodhd_default_exchange")
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| crate::providers::eodhd::DEFAULT_EXCHANGE.to_string())
+}
+
+/// 從 app settings 讀取 gas tracker 設定（Etherscan key 解密後回傳）
+pub fn load_gas_config(db: &DbPool) -> GasConfig {
+    let setting = |key: &str| db.get_setting(key).ok().flatten().filter(|v| !v.is_empty());
+    let default = GasConfig::default();
+    GasConfig {
+        enabled: setting("gas_tracker_enabled").is_some_and(|v| v == "1"),
+        interval_secs: setting("gas_tracker_interval")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(default.interval_secs),
+        etherscan_api_key: setting("gas_tracker_etherscan_key")
+            .and_then(|v| crate::notifications::crypto::decrypt_token(&v).ok())
+            .unwrap_or_default(),
+        eth_rpc_url: setting("gas_tracker_eth_rpc").unwrap_or(default.eth_rpc_url),
+        solana_rpc_url: setting("gas_tracker_solana_rpc").unwrap_or(default.solana_rpc_url),
+    }
+}
+
+/// 從 app settings 讀取穩定幣脫鉤監控設定
+pub fn load_depeg_config(db: &DbPool) -> DepegConfig {
+    let setting = |key: &str| db.get_setting(key).ok().flatten().filter(|v| !v.is_empty());
+    let default = DepegConfig::default();
+    DepegConfig {
+        enabled: setting("depeg_monitor_enabled").is_some_and(|v| v == "1"),
+        interval_secs: setting("depeg_monitor_interval")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(default.interval_secs),
+        threshold_pct: setting("depeg_monitor_threshold")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(default.threshold_pct),
+    }
+}
+
+/// 從 app settings 讀取經濟日曆設定（國家與關鍵字以逗號分隔儲存）
+pub fn load_calendar_config(db: &DbPool) -> CalendarConfig {
+    let setting = |key: &str| db.get_setting(key).ok().flatten();
+    let list = |value: String| -> Vec<String> {
+        value.split(',').map(str::trim).filter(|v| !v.is_empty()).map(String::from).collect()
+    };
+    let default = CalendarConfig::default();
+    CalendarConfig {
+        enabled: setting("calendar_enabled").is_some_and(|v| v == "1"),
+        source: setting("calendar_source").filter(|v| !v.is_empty()).unwrap_or(default.source),
+        interval_secs: setting("calendar_interval")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(default.interval_secs),
+        countries: setting("calendar_countries").map(list).unwrap_or(default.countries),
+        min_impact: setting("calendar_min_impact")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(default.min_impact),
+        alert_keywords: setting("calendar_alert_keywords").map(list).unwrap_or(default.alert_keywords),
+    }
+}
+
+/// 從 app settings 讀取價格合理性檢查設定（未設定時預設啟用）
+pub fn load_sanity_config(db: &DbPool) -> SanityConfig {
+    let setting = |key: &str| db.get_setting(key).ok().flatten().filter(|v| !v.is_empty());
+    let default = SanityConfig::default();
+    SanityConfig {
+        enabled: setting("price_sanity_enabled").map_or(default.enabled, |v| v == "1"),
+        max_multiple: setting("price_sanity_multiple")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(default.max_multiple),
+        window: setting("price_sanity_window")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(default.window),
+    }
+}
+
+/// 從 app settings 讀取價格快取上限
+pub fn load_cache_config(db: &DbPool) -> CacheConfig {
+    db.get_setting("polling_cache_max_entries")
+        .ok()
+        .flatten()
+        .and_then(|v| v.parse().ok())
+        .map(|max_entries| CacheConfig { max_entries })
+        .and_then(|c| c.normalized().ok())
+        .unwrap_or_default()
+}
+
+/// 從 app settings 讀取前端事件節流設定
+pub fn load_emit_config(db: &DbPool) -> EmitConfig {
+    db.get_setting("emit_max_per_sec")
+        .ok()
+        .flatten()
+        .and_then(|v| v.parse().ok())
+        .map(|max_per_sec| EmitConfig { max_per_sec })
+        .and_then(|c| c.normalized().ok())
+        .unwrap_or_default()
+}
+
+/// 從 app settings 讀取紀錄停滯提醒設定
+pub fn load_record_stall_config(db: &DbPool) -> RecordStallConfig {
+    db.get_setting("record_stall_minutes")
+        .ok()
+        .flatten()
+        .and_then(|v| v.parse().ok())
+        .map(|stall_minutes| RecordStallConfig { stall_minutes })
+        .and_then(|c| c.normalized().ok())
+        .unwrap_or_default()
+}
+
+/// 從 app settings 讀取 WS 優先輪詢設定
+pub fn load_ws_first_config(db: &DbPool) -> WsFirstConfig {
+    let setting = |key: &str| db.get_setting(key).ok().flatten().filter(|v| !v.is_empty());
+    WsFirstConfig {
+        enabled: setting("ws_first_enabled").is_some_and(|v| v == "1"),
+        fallback_secs: setting("ws_first_fallback_secs")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(WsFirstConfig::default().fallback_secs),
+    }
+    .normalized()
+    .unwrap_or_default()
+}
+
+/// 從 app settings 讀取 provider 延遲自動調整設定
+pub fn load_latency_tune_config(db: &DbPool) -> LatencyTuneConfig {
+    let setting = |key: &str| db.get_setting(key).ok().flatten().filter(|v| !v.is_empty());
+    let default = LatencyTuneConfig::default();
+    LatencyTuneConfig {
+        enabled: setting("latency_tune_enabled").is_some_and(|v| v == "1"),
+        min_interval_ms: setting("latency_tune_min_ms")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(default.min_interval_ms),
+        max_interval_ms: setting("latency_tune_max_ms")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(default.max_interval_ms),
+    }
+    .normalized()
+    .unwrap_or_default()
+}
+
+/// 從 app settings 讀取 WS K 線保存設定
+pub fn load_candle_config(db: &DbPool) -> CandleConfig {
+    let setting = |key: &str| db.get_setting(key).ok().flatten().filter(|v| !v.is_empty());
+    CandleConfig {
+        persist: setting("candle_persist").is_some_and(|v| v == "1"),
+        retention_days: setting("candle_retention_days")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(CandleConfig::default().retention_days),
+    }
+    .normalized()
+    .unwrap_or_default()
+}
+
+/// 從 app settings 讀取 OTLP trace 匯出設定
+pub fn load_otlp_config(db: &DbPool) -> OtlpConfig {
+    let setting = |key: &str| db.get_setting(key).ok().flatten().filter(|v| !v.is_empty());
+    let default = OtlpConfig::default();
+    OtlpConfig {
+        enabled: setting("otlp_enabled").is_some_and(|v| v == "1"),
+        endpoint: setting("otlp_endpoint").unwrap_or(default.endpoint),
+        service_name: setting("otlp_service_name").unwrap_or(default.service_name),
+    }
+    .normalized()
+    .unwrap_or_default()
+}
+
+/// 解密加密存放的 setting；失敗時記錄錯誤並視為未設定
+fn decrypt_setting(key: &str, value: &str) -> Option<String> {
+    crate::secrets::decrypt_secret(value)
+        .map_err(|e| tracing::error!("[Settings] Failed to decrypt {}: {}", key, e))
+        .ok()
+}
+
+/// 從 app settings 讀取雲端備份設定（secret key 與 passphrase 解密後放入記憶體）
+pub fn load_cloud_backup_config(db: &DbPool) -> CloudBackupConfig {
+    let setting = |key: &str| db.get_setting(key).ok().flatten().filter(|v| !v.is_empty());
+    let secret = |key: &str| {
+        setting(key)
+            .and_then(|v| decrypt_setting(key, &v))
+            .unwrap_or_default()
+    };
+    let default = CloudBackupConfig::default();
+    CloudBackupConfig {
+        enabled: setting("cloud_backup_enabled").is_some_and(|v| v == "1"),
+        endpoint: setting("cloud_backup_endpoint").unwrap_or_default(),
+        region: setting("cloud_backup_region").unwrap_or(default.region),
+        bucket: setting("cloud_backup_bucket").unwrap_or_default(),
+        prefix: setting("cloud_backup_prefix").unwrap_or(default.prefix),
+        access_key_id: setting("cloud_backup_access_key_id").unwrap_or_default(),
+        secret_access_key: secret("cloud_backup_secret_access_key"),
+        passphrase: secret("cloud_backup_passphrase"),
+        interval_hours: setting("cloud_backup_interval_hours")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(default.interval_hours),
+        retain: setting("cloud_backup_retain")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(default.retain),
+    }
+    .normalized()
+    .unwrap_or_default()
+}
+
+/// 從 app settings 讀取設定同步設定（WebDAV 密碼解密後放入記憶體）
+pub fn load_sync_config(db: &DbPool) -> SyncConfig {
+    let setting = |key: &str| db.get_setting(key).ok().flatten().filter(|v| !v.is_empty());
+    SyncConfig {
+        enabled: setting("sync_enabled").is_some_and(|v| v == "1"),
+        location: setting("sync_location").unwrap_or_default(),
+        username: setting("sync_username").unwrap_or_default(),
+        password: setting("sync_password")
+            .and_then(|v| decrypt_setting("sync_password", &v))
+            .unwrap_or_default(),
+        interval_minutes: setting("sync_interval_minutes")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(SyncConfig::default().interval_minutes),
+    }
+    .normalized()
+    .unwrap_or_default()
+}
+
+/// 讀取 kiosk 狀態（`kiosk_enabled`、`kiosk_pin_hash`、`kiosk_token`）
+pub fn load_kiosk_state(db: &DbPool) -> crate::kiosk::KioskState {
+    let setting = |key: &str| db.get_setting(key).ok().flatten().filter(|v| !v.is_empty());
+    crate::kiosk::KioskState {
+        enabled: setting("kiosk_enabled").is_some_and(|v| v == "1"),
+        pin_hash: setting("kiosk_pin_hash"),
+        token: setting("kiosk_token").unwrap_or_default(),
+    }
+}
+
+/// 讀取區網 API listener 設定（`lan_api_*`）
+pub fn load_lan_listener_config(db: &DbPool) -> crate::lan_listener::LanListenerConfig {
+    let setting = |key: &str| db.get_setting(key).ok().flatten().filter(|v| !v.is_empty());
+    let default = crate::lan_listener::LanListenerConfig::default();
+    crate::lan_listener::LanListenerConfig {
+        enabled: setting("lan_api_enabled").is_some_and(|v| v == "1"),
+        bind: setting("lan_api_bind").unwrap_or(default.bind),
+        port: setting("lan_api_port").and_then(|v| v.parse().ok()).unwrap_or(default.port),
+        token: setting("lan_api_token").unwrap_or_default(),
+        scope: setting("lan_api_scope")
+            .and_then(|v| crate::lan_listener::ListenerScope::from_id(&v))
+            .unwrap_or_default(),
+    }
+    .normalized()
+    .unwrap_or_default()
+}
+
+/// 讀取後端訊息語言（settings `language`，未設定為英文）
+pub fn load_locale(db: &DbPool) -> crate::i18n::Locale {
+    db.get_setting("language")
+        .ok()
+        .flatten()
+        .map(|v| crate::i18n::Locale::from_id(&v))
+        .unwrap_or_default()
+}
+
+/// 執行狀態（`/api/status`、`get_app_status`）
+#[derive(Debug, Clone, Serialize)]
+pub struct AppStatus {
+    pub version: &'static str,
+    pub unattended: bool,
+    pub recording_paused: bool,
+    pub cache: CacheStatus,
+}
+
+/// 從 app settings 讀取 provider metadata 覆寫檔的來源（URL 與驗證公鑰）
+pub fn load_provider_metadata_source(db: &DbPool) -> MetadataSource {
+    let get = |key: &str| db.get_setting(key).ok().flatten().unwrap_or_default();
+    MetadataSource {
+        url: get("provider_metadata_url"),
+        public_key: get("provider_metadata_key"),
+    }
+}
+
+/// 從 app settings 讀取使用者自訂的 subgraph protocols（JSON 陣列，無效時視為空）
+pub fn load_subgraph_protocols(db: &DbPool) -> Vec<SubgraphProtocol> {
+    db.get_setting("subgraph_protocols")
+        .ok()
+        .flatten()
+        .and_then(|v| serde_json::from_str(&v).ok())
+        .unwrap_or_default()
+}
+
+/// 價格快照的定期保存間隔
+const PRICE_SNAPSHOT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(300);
+
+async fn save_snapshot(db: &DbPool, polling: &PollingManager) {
+    let entries = polling.cache_snapshot().await;
+    if entries.is_empty() {
+        return;
+    }
+    if let Err(e) = db.save_price_snapshot(&entries) {
+        tracing::warn!("[CoreState] Failed to save price snapshot: {}", e);
+    }
+}
+
+/// 從 app settings 讀取上次保存的 polling 可見範圍（window/scope → subscription ids）
+pub fn load_visible_scopes(db: &DbPool) -> HashMap<String, HashSet<i64>> {
+    db.get_setting("polling_visible_scopes")
+        .ok()
+        .flatten()
+        .and_then(|v| serde_json::from_str(&v).ok())
+        .unwrap_or_default()
+}
+
+/// 從 app settings 讀取各 window/scope 要求的更新間隔
+pub fn load_window_intervals(db: &DbPool) -> HashMap<String, u64> {
+    db.get_setting("polling_window_intervals")
+        .ok()
+        .flatten()
+        .and_then(|v| serde_json::from_str(&v).ok())
+        .unwrap_or_default()
+}
+
 /// 共享核心狀態結構，包含所有與平台無關的元件。
 pub struct CoreState {
     /// 統一 DB 存取層
@@ -62,35 +452,38 @@ pub struct CoreState {
     pub global_cooldown: Arc<GlobalCooldown>,
     /// 輪詢管理器
     pub polling: PollingManager,
+    /// 錄製排程器（依時段切換 unattended polling）
+    pub recording_scheduler: Arc<RecordingScheduler>,
     /// 資料目錄路徑（icons、theme_bg 等存放位置）
     pub data_dir: PathBuf,
-
-    // ── Desktop-only fields (Tauri WebSocket relay) ──────────────
-    /// WebSocket ticker update broadcast sender (desktop only)
-    #[cfg(feature = "desktop")]
-    pub ws_sender: broadcast::Sender<WsTickerUpdate>,
-    /// Active WebSocket task handles keyed by provider ID (desktop only)
-    #[cfg(feature = "desktop")]
-    #[allow(clippy::type_complexity)]
-    pub ws_tasks: RwLock<HashMap<String, (JoinHandle<()>, JoinHandle<()>)>>,
+    /// `read_local_file_base64` 的讀取白名單
+    pub file_access: FileAccess,
+    /// 所有 WebSocket 連線（桌面前端與 `/api/ws` client 共用）
+    pub ws: Arc<WsManager>,
 }
 
 impl CoreState {
     /// 初始化所有共享元件。
     ///
     /// 1. 確保 DB schema 一致（`ensure_clean_db`）
-    /// 2. 開啟資料庫
+    /// 2. 開啟資料庫，並將舊命名的 icon 檔遷移到新命名（`icons::migrate_icon_names`）
     /// 3. 建立 Provider Registry
     /// 4. 建立 Event Bus
     /// 5. 從 DB 讀取 global cooldown 設定
     /// 6. 建立 NotificationEngine
     /// 7. 建立 AiScheduler
-    /// 8. 建立 PollingManager
+    /// 8. 建立 PollingManager（沿用上次保存的可見範圍）
+    /// 9. 建立 RecordingScheduler
     pub fn new(data_dir: &Path) -> Result<Self, Box<dyn std::error::Error>> {
         ensure_clean_db(data_dir);
 
         let db_path = data_dir.join("stockenboard.db");
         let db = Arc::new(DbPool::open(&db_path)?);
+        match crate::icons::migrate_icon_names(&db, &data_dir.join("icons")) {
+            Ok(0) => {}
+            Ok(n) => tracing::info!("[Icons] Renamed {} icon file(s) to the new naming scheme", n),
+            Err(e) => tracing::warn!("[Icons] Icon name migration failed: {}", e),
+        }
 
         // Ensure the built-in local notification channel exists
         db.ensure_local_channel()
@@ -101,6 +494,34 @@ impl CoreState {
             .map_err(|e| format!("Failed to ensure system channel: {}", e))?;
 
         let registry = Arc::new(ProviderRegistry::new());
+        crate::providers::set_global_proxy(db.get_setting("http_proxy").ok().flatten());
+        crate::providers::mock::set_mock_config(load_mock_config(&db));
+        crate::providers::replay::set_replay_config(load_replay_config(&db));
+        crate::providers::yahoo::set_quote_summary_enabled(load_yahoo_quote_summary(&db));
+        crate::providers::jupiter::set_token_list_enabled(load_jupiter_token_list(&db));
+        crate::providers::alphavantage::set_refresh_hours(load_alphavantage_refresh_hours(&db));
+        crate::providers::eodhd::set_default_exchange(&load_eodhd_default_exchange(&db));
+        crate::providers::subgraph::set_custom_protocols(load_subgraph_protocols(&db));
+        crate::power::set_power_config(load_power_config(&db));
+        crate::gas::set_gas_config(load_gas_config(&db));
+        crate::depeg::set_depeg_config(load_depeg_config(&db));
+        crate::calendar::set_calendar_config(load_calendar_config(&db));
+        crate::sanity::set_sanity_config(load_sanity_config(&db));
+        crate::cache_limit::set_cache_config(load_cache_config(&db));
+        crate::emit_throttle::set_emit_config(load_emit_config(&db));
+        crate::record_health::set_record_stall_config(load_record_stall_config(&db));
+        crate::latency_tune::set_latency_tune_config(load_latency_tune_config(&db));
+        crate::ws_first::set_ws_first_config(load_ws_first_config(&db));
+        crate::candles::set_candle_config(load_candle_config(&db));
+        crate::telemetry::set_otlp_config(load_otlp_config(&db));
+        crate::cloud_backup::set_cloud_backup_config(load_cloud_backup_config(&db));
+        crate::settings_sync::set_sync_config(load_sync_config(&db));
+        if let Some(level) = db.get_setting("log_level").ok().flatten() {
+            let _ = crate::logging::set_level(&level);
+        }
+        crate::i18n::set_locale(load_locale(&db));
+        crate::kiosk::set_kiosk_state(load_kiosk_state(&db));
+        crate::lan_listener::set_lan_listener_config(load_lan_listener_config(&db));
 
         let (event_bus, _) = broadcast::channel::<AppEvent>(512);
 
@@ -124,7 +545,13 @@ impl CoreState {
                 .with_global_cooldown(global_cooldown.clone()),
         );
 
-        let polling = PollingManager::new();
+        let ws = Arc::new(WsManager::new(event_bus.clone()).with_db(db.clone()));
+        let polling = PollingManager::with_window_profiles(load_visible_scopes(&db), load_window_intervals(&db))
+            .with_ws(ws.clone());
+        polling.set_recording_paused(
+            db.get_setting("recording_paused").ok().flatten().is_some_and(|v| v == "1"),
+        );
+        let recording_scheduler = Arc::new(RecordingScheduler::new(db.clone(), polling.clone()));
 
         Ok(Self {
             db,
@@ -134,15 +561,14 @@ impl CoreState {
             ai_scheduler,
             global_cooldown,
             polling,
+            recording_scheduler,
             data_dir: data_dir.to_path_buf(),
-            #[cfg(feature = "desktop")]
-            ws_sender: broadcast::channel(256).0,
-            #[cfg(feature = "desktop")]
-            ws_tasks: RwLock::new(HashMap::new()),
+            file_access: FileAccess::new([data_dir.join("icons"), data_dir.join("theme_bg")]),
+            ws,
         })
     }
 
-    /// 啟動背景任務：Notification Engine 與 AI Scheduler。
+    /// 啟動背景任務：Notification Engine、AI Scheduler、錄製排程與電源探測。
     ///
     /// 注意：Polling 不在此啟動，因為在 desktop 模式下需要 `tauri::AppHandle`，
     /// 而在 server 模式下將以不同方式啟動。Polling 啟動由呼叫方自行處理。
@@ -161,6 +587,42 @@ impl CoreState {
             scheduler.start().await;
         });
 
+        // 啟動錄製排程（未啟用時不會變更 unattended 狀態）
+        self.recording_scheduler.start();
+
+        // 啟動電源探測（省電模式）
+        self.start_power_monitor();
+
+        // 還原上次的價格快照並定期保存
+        self.start_price_snapshots();
+
+        // 載入並定期更新 provider metadata 覆寫
+        self.start_provider_metadata_updates();
+
+        // 啟動 gas 價格追蹤（未啟用且沒有 gas 規則時只會閒置）
+        self.start_gas_tracker();
+
+        // 啟動穩定幣脫鉤監控（未啟用且沒有脫鉤規則時只會閒置）
+        self.start_depeg_monitor();
+
+        // 啟動經濟日曆抓取（未啟用且沒有事件規則時只會閒置）
+        self.start_economic_calendar();
+
+        // 定期由紀錄更新多時間框漲跌的基準價
+        self.start_timeframe_changes();
+
+        // 紀錄停滯檢查（未設定門檻時只會閒置）
+        self.start_record_health();
+
+        // WS 串流 1 分鐘 K 線收盤（沒有 WS 串流時只會閒置）
+        self.start_candles();
+
+        // OTLP trace 匯出（未啟用時只會閒置）
+        self.start_telemetry_export();
+
+        // 排程雲端備份（未啟用時只會閒置）
+        self.start_cloud_backup();
+
         // 自動開啟後台 polling（如果有啟用的通知規則）
         self.sync_polling_for_rules().await;
 
@@ -168,71 +630,659 @@ impl CoreState {
         // 在 desktop 模式下，此工作由 lib.rs 中的 event forwarder 負責。
         // 在 server 模式下，由此處負責。
         #[cfg(not(feature = "desktop"))]
-        {
-            let db = self.db.clone();
-            let mut history_rx = self.event_bus.subscribe();
-            tokio::spawn(async move {
-                use std::collections::HashSet;
-                loop {
-                    match history_rx.recv().await {
-                        Ok(AppEvent::PriceUpdate {
-                            provider_id,
-                            data,
-                            record_symbols,
-                        }) => {
-                            if !record_symbols.is_empty() {
-                                let record_set: HashSet<String> =
-                                    record_symbols.into_iter().collect();
-                                let records: Vec<crate::db::PriceRecord> = data
-                                    .iter()
-                                    .filter(|d| record_set.contains(&d.symbol))
-                                    .map(|d| {
-                                        let pre = d
-                                            .extra
-                                            .as_ref()
-                                            .and_then(|e| e.get("pre_market_price"))
-                                            .and_then(|v| v.as_f64());
-                                        let post = d
-                                            .extra
-                                            .as_ref()
-                                            .and_then(|e| e.get("post_market_price"))
-                                            .and_then(|v| v.as_f64());
-                                        (
-                                            d.symbol.clone(),
-                                            d.price,
-                                            d.change_percent_24h,
-                                            d.volume,
-                                            pre,
-                                            post,
-                                        )
-                                    })
-                                    .collect();
-                                db.write_price_history(&provider_id, &records);
+        self.start_price_recorder();
+    }
+
+    /// 啟動 Price History Recorder：監聽 PriceUpdate 事件，將需紀錄的 symbol 寫入 price_history。
+    ///
+    /// server 模式由 `start_background_tasks` 呼叫；desktop headless 模式沒有 event forwarder，
+    /// 因此直接呼叫此方法。
+    pub fn start_price_recorder(&self) {
+        let db = self.db.clone();
+        let event_bus = self.event_bus.clone();
+        let mut history_rx = self.event_bus.subscribe();
+        tokio::spawn(async move {
+            loop {
+                match history_rx.recv().await {
+                    Ok(AppEvent::PriceUpdate {
+                        provider_id,
+                        data,
+                        record_targets,
+                    }) => {
+                        if !record_targets.is_empty() {
+                            let records: Vec<crate::db::PriceRecord> = data
+                                .iter()
+                                .filter(|d| record_targets.contains_key(&d.symbol))
+                                .map(|d| d.price_record())
+                                .collect();
+                            for error in db.write_price_history(&provider_id, &records, &record_targets) {
+                                let _ = event_bus.send(AppEvent::RecordError(error));
                             }
                         }
-                        Ok(_) => {}
-                        Err(broadcast::error::RecvError::Lagged(n)) => {
-                            eprintln!("[PriceRecorder] Lagged behind by {} events", n);
-                        }
-                        Err(broadcast::error::RecvError::Closed) => break,
                     }
+                    Ok(_) => {}
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        tracing::warn!("[PriceRecorder] Lagged behind by {} events", n);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+
+    /// 儲存並套用全域 HTTP proxy（None 或空字串 = 不使用 proxy）。
+    ///
+    /// 已建立的 provider 持有舊 client，因此一併清除 registry 並重新載入 polling。
+    pub async fn set_http_proxy(&self, proxy: Option<String>) -> Result<(), String> {
+        let proxy = proxy.map(|p| p.trim().to_string()).filter(|p| !p.is_empty());
+        if let Some(p) = proxy.as_deref() {
+            reqwest::Proxy::all(p).map_err(|e| format!("Invalid proxy URL: {}", e))?;
+        }
+        self.db
+            .set_setting("http_proxy", proxy.as_deref().unwrap_or(""))?;
+        crate::providers::set_global_proxy(proxy);
+        self.registry.clear().await;
+        self.polling.reload();
+        Ok(())
+    }
+
+    /// 儲存並套用 demo 模式設定；重建 `mock` instance 並重新載入 polling
+    pub async fn set_demo_mode(&self, config: MockConfig) -> Result<(), String> {
+        if !config.volatility_pct.is_finite() || !(0.0..=50.0).contains(&config.volatility_pct) {
+            return Err("Volatility must be between 0 and 50 (%)".to_string());
+        }
+        self.db.set_setting("demo_mode", if config.enabled { "1" } else { "0" })?;
+        self.db.set_setting("mock_volatility_pct", &config.volatility_pct.to_string())?;
+        self.db.set_setting("mock_latency_ms", &config.latency_ms.to_string())?;
+        crate::providers::mock::set_mock_config(config);
+        self.registry.invalidate("mock").await;
+        self.polling.reload();
+        Ok(())
+    }
+
+    /// 儲存並套用 Yahoo quoteSummary 設定（下一次 poll 生效）
+    pub fn set_yahoo_quote_summary(&self, enabled: bool) -> Result<(), String> {
+        self.db
+            .set_setting("yahoo_quote_summary", if enabled { "1" } else { "0" })?;
+        crate::providers::yahoo::set_quote_summary_enabled(enabled);
+        Ok(())
+    }
+
+    /// 儲存並套用 Jupiter token list 設定（下一次 Jupiter 查詢時載入）
+    pub fn set_jupiter_token_list(&self, enabled: bool) -> Result<(), String> {
+        self.db
+            .set_setting("jupiter_token_list", if enabled { "1" } else { "0" })?;
+        crate::providers::jupiter::set_token_list_enabled(enabled);
+        Ok(())
+    }
+
+    /// 儲存並套用 Alpha Vantage 更新間隔（小時，至少 1）
+    pub fn set_alphavantage_refresh_hours(&self, hours: u32) -> Result<(), String> {
+        let hours = hours.max(1);
+        self.db
+            .set_setting("alphavantage_refresh_hours", &hours.to_string())?;
+        crate::providers::alphavantage::set_refresh_hours(hours);
+        Ok(())
+    }
+
+    /// 驗證、儲存並套用 EODHD 預設交易所（下一次 EODHD 查詢生效）
+    pub fn set_eodhd_default_exchange(&self, exchange: &str) -> Result<(), String> {
+        let exchange = crate::providers::eodhd::normalize_exchange(exchange)?;
+        self.db.set_setting("eodhd_default_exchange", &exchange)?;
+        crate::providers::eodhd::set_default_exchange(&exchange);
+        Ok(())
+    }
+
+    /// 儲存並套用自訂 subgraph protocols（內建項目不存入設定）
+    pub fn set_subgraph_protocols(&self, protocols: Vec<SubgraphProtocol>) -> Result<(), String> {
+        let custom: Vec<SubgraphProtocol> = protocols.into_iter().filter(|p| !p.builtin).collect();
+        crate::providers::subgraph::validate_protocols(&custom)?;
+        let json = serde_json::to_string(&custom).map_err(|e| e.to_string())?;
+        self.db.set_setting("subgraph_protocols", &json)?;
+        crate::providers::subgraph::set_custom_protocols(custom);
+        Ok(())
+    }
+
+    /// 儲存並套用 history replay 設定；重建 `replay` instance（從頭播放）並重新載入 polling
+    pub async fn set_replay(&self, config: ReplayConfig) -> Result<(), String> {
+        config.validate(chrono::Utc::now().timestamp())?;
+        self.db.set_setting("replay_enabled", if config.enabled { "1" } else { "0" })?;
+        self.db.set_setting("replay_from", &config.from.to_string())?;
+        self.db.set_setting(
+            "replay_to",
+            &config.to.map(|t| t.to_string()).unwrap_or_default(),
+        )?;
+        self.db.set_setting("replay_speed", &config.speed.to_string())?;
+        crate::providers::replay::set_replay_config(config);
+        self.registry.invalidate("replay").await;
+        self.polling.reload();
+        Ok(())
+    }
+
+    /// 設定某個 window/scope 的可見 subscription 與要求的更新間隔；有變更時持久化整份範圍，供下次啟動時沿用
+    pub async fn set_visible_subscriptions(&self, window_id: String, ids: HashSet<i64>, interval_ms: Option<u64>) {
+        let interval_ms = interval_ms.filter(|ms| *ms > 0);
+        if !self.polling.set_visible(window_id, ids, interval_ms).await {
+            return;
+        }
+        let scopes = self.polling.visible_scopes().await;
+        let intervals = self.polling.window_intervals().await;
+        let saved = serde_json::to_string(&scopes)
+            .map_err(|e| e.to_string())
+            .and_then(|json| self.db.set_setting("polling_visible_scopes", &json))
+            .and_then(|_| serde_json::to_string(&intervals).map_err(|e| e.to_string()))
+            .and_then(|json| self.db.set_setting("polling_window_intervals", &json));
+        if let Err(e) = saved {
+            tracing::warn!("[Polling] Failed to persist visible scopes: {}", e);
+        }
+    }
+
+    /// 暫停／恢復全域價格紀錄並持久化（各訂閱的 record 設定保持不變）
+    pub fn set_recording_paused(&self, paused: bool) -> Result<(), String> {
+        self.db.set_setting("recording_paused", if paused { "1" } else { "0" })?;
+        self.polling.set_recording_paused(paused);
+        Ok(())
+    }
+
+    /// 啟動背景電源探測 task（省電模式未啟用時不會改變 polling）與休眠喚醒偵測
+    pub fn start_power_monitor(&self) {
+        crate::power::start_monitor(self.polling.clone(), self.event_bus.clone());
+        crate::power::start_wake_detector(self.polling.clone(), self.event_bus.clone());
+    }
+
+    /// 還原上次保存的價格快照（標記為 stale，tile 在首次 poll 前即可顯示），
+    /// 並每 `PRICE_SNAPSHOT_INTERVAL` 保存一次，避免非正常結束時遺失
+    pub fn start_price_snapshots(&self) {
+        let db = self.db.clone();
+        let polling = self.polling.clone();
+        tokio::spawn(async move {
+            match db.load_price_snapshot() {
+                Ok(entries) if !entries.is_empty() => {
+                    tracing::info!("[CoreState] Restored {} cached prices from snapshot", entries.len());
+                    polling.restore_cache(entries).await;
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("[CoreState] Failed to load price snapshot: {}", e),
+            }
+            let mut interval = tokio::time::interval(PRICE_SNAPSHOT_INTERVAL);
+            // 第一次 tick 立即觸發，跳過
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                save_snapshot(&db, &polling).await;
+            }
+        });
+    }
+
+    /// 載入 app-data 下已簽章的 provider metadata 覆寫檔，並每 `metadata::REFRESH_INTERVAL`
+    /// 從設定的 URL 更新一次
+    pub fn start_provider_metadata_updates(&self) {
+        let db = self.db.clone();
+        let polling = self.polling.clone();
+        let data_dir = self.data_dir.clone();
+        tokio::spawn(async move {
+            let source = load_provider_metadata_source(&db);
+            if !source.public_key.is_empty() {
+                tracing::warn!("[CoreState] Verifying provider metadata with the developer key override");
+            }
+            match crate::providers::metadata::load_file(&data_dir, source.verifying_key()) {
+                Ok(0) => {}
+                Ok(count) => {
+                    tracing::info!("[CoreState] Applied provider metadata for {} providers", count);
+                    polling.reload();
+                }
+                Err(e) => tracing::warn!("[CoreState] {}", e),
+            }
+            let mut interval = tokio::time::interval(crate::providers::metadata::REFRESH_INTERVAL);
+            loop {
+                interval.tick().await;
+                // 每次重新讀取設定，設定變更後不必重啟
+                let source = load_provider_metadata_source(&db);
+                if source.url.is_empty() {
+                    continue;
+                }
+                match crate::providers::metadata::refresh(&data_dir, &source.url, source.verifying_key()).await {
+                    Ok(count) => {
+                        tracing::info!("[CoreState] Updated provider metadata for {} providers", count);
+                        polling.reload();
+                    }
+                    Err(e) => tracing::warn!("[CoreState] {}", e),
                 }
-            });
+            }
+        });
+    }
+
+    /// 啟動背景 gas 價格追蹤
+    pub fn start_gas_tracker(&self) {
+        crate::gas::start(self.event_bus.clone());
+    }
+
+    /// 儲存並套用 gas tracker 設定（Etherscan key 加密儲存），啟用時立即查詢一次
+    pub async fn set_gas_config(&self, config: GasConfig) -> Result<(), String> {
+        let config = config.normalized()?;
+        self.db.set_setting("gas_tracker_enabled", if config.enabled { "1" } else { "0" })?;
+        self.db.set_setting("gas_tracker_interval", &config.interval_secs.to_string())?;
+        let encrypted_key = if config.etherscan_api_key.is_empty() {
+            String::new()
+        } else {
+            crate::notifications::crypto::encrypt_token(&config.etherscan_api_key)?
+        };
+        self.db.set_setting("gas_tracker_etherscan_key", &encrypted_key)?;
+        self.db.set_setting("gas_tracker_eth_rpc", &config.eth_rpc_url)?;
+        self.db.set_setting("gas_tracker_solana_rpc", &config.solana_rpc_url)?;
+        let enabled = config.enabled;
+        crate::gas::set_gas_config(config);
+        if enabled {
+            crate::gas::poll_once(&self.event_bus).await;
+        }
+        Ok(())
+    }
+
+    /// 啟動背景穩定幣脫鉤監控
+    pub fn start_depeg_monitor(&self) {
+        crate::depeg::start(self.registry.clone(), self.db.clone(), self.event_bus.clone());
+    }
+
+    /// 儲存並套用脫鉤監控設定，啟用時立即查詢一次
+    pub async fn set_depeg_config(&self, config: DepegConfig) -> Result<(), String> {
+        let config = config.normalized()?;
+        self.db.set_setting("depeg_monitor_enabled", if config.enabled { "1" } else { "0" })?;
+        self.db.set_setting("depeg_monitor_interval", &config.interval_secs.to_string())?;
+        self.db.set_setting("depeg_monitor_threshold", &config.threshold_pct.to_string())?;
+        let enabled = config.enabled;
+        crate::depeg::set_depeg_config(config);
+        if enabled {
+            crate::depeg::poll_once(&self.registry, &self.db, &self.event_bus).await;
+        }
+        Ok(())
+    }
+
+    /// 儲存並套用價格合理性檢查設定（下一次取價起生效）
+    pub fn set_sanity_config(&self, config: SanityConfig) -> Result<(), String> {
+        let config = config.normalized()?;
+        self.db.set_setting("price_sanity_enabled", if config.enabled { "1" } else { "0" })?;
+        self.db.set_setting("price_sanity_multiple", &config.max_multiple.to_string())?;
+        self.db.set_setting("price_sanity_window", &config.window.to_string())?;
+        crate::sanity::set_sanity_config(config);
+        Ok(())
+    }
+
+    /// 儲存並套用價格快取上限（下一次寫入快取時淘汰超出的項目）
+    pub fn set_cache_config(&self, config: CacheConfig) -> Result<(), String> {
+        let config = config.normalized()?;
+        self.db.set_setting("polling_cache_max_entries", &config.max_entries.to_string())?;
+        crate::cache_limit::set_cache_config(config);
+        Ok(())
+    }
+
+    /// 儲存並套用前端事件節流設定（轉送迴圈下一個事件起生效）
+    pub fn set_emit_config(&self, config: EmitConfig) -> Result<(), String> {
+        let config = config.normalized()?;
+        self.db.set_setting("emit_max_per_sec", &config.max_per_sec.to_string())?;
+        crate::emit_throttle::set_emit_config(config);
+        Ok(())
+    }
+
+    /// 儲存並套用紀錄停滯提醒設定（下一輪檢查起生效）
+    pub fn set_record_stall_config(&self, config: RecordStallConfig) -> Result<(), String> {
+        let config = config.normalized()?;
+        self.db.set_setting("record_stall_minutes", &config.stall_minutes.to_string())?;
+        crate::record_health::set_record_stall_config(config);
+        Ok(())
+    }
+
+    /// 儲存並套用 provider 延遲自動調整設定（各 provider 下一次取價起生效）
+    pub fn set_latency_tune_config(&self, config: LatencyTuneConfig) -> Result<(), String> {
+        let config = config.normalized()?;
+        self.db.set_setting("latency_tune_enabled", if config.enabled { "1" } else { "0" })?;
+        self.db.set_setting("latency_tune_min_ms", &config.min_interval_ms.to_string())?;
+        self.db.set_setting("latency_tune_max_ms", &config.max_interval_ms.to_string())?;
+        crate::latency_tune::set_latency_tune_config(config);
+        Ok(())
+    }
+
+    /// 儲存並套用 WS 優先輪詢設定（重新載入 polling 後生效）
+    pub fn set_ws_first_config(&self, config: WsFirstConfig) -> Result<(), String> {
+        let config = config.normalized()?;
+        self.db.set_setting("ws_first_enabled", if config.enabled { "1" } else { "0" })?;
+        self.db.set_setting("ws_first_fallback_secs", &config.fallback_secs.to_string())?;
+        crate::ws_first::set_ws_first_config(config);
+        self.polling.reload();
+        Ok(())
+    }
+
+    /// 儲存並套用 WS K 線保存設定（下一次收盤起生效）
+    pub fn set_candle_config(&self, config: CandleConfig) -> Result<(), String> {
+        let config = config.normalized()?;
+        self.db.set_setting("candle_persist", if config.persist { "1" } else { "0" })?;
+        self.db.set_setting("candle_retention_days", &config.retention_days.to_string())?;
+        crate::candles::set_candle_config(config);
+        Ok(())
+    }
+
+    /// 儲存並套用 OTLP trace 匯出設定（停用時丟棄尚未送出的 span）
+    pub fn set_otlp_config(&self, config: OtlpConfig) -> Result<(), String> {
+        let config = config.normalized()?;
+        self.db.set_setting("otlp_enabled", if config.enabled { "1" } else { "0" })?;
+        self.db.set_setting("otlp_endpoint", &config.endpoint)?;
+        self.db.set_setting("otlp_service_name", &config.service_name)?;
+        crate::telemetry::set_otlp_config(config);
+        Ok(())
+    }
+
+    /// 儲存並套用雲端備份設定；secret key / passphrase 留空時沿用已儲存的值
+    pub fn set_cloud_backup_config(&self, mut config: CloudBackupConfig) -> Result<(), String> {
+        let current = crate::cloud_backup::cloud_backup_config();
+        if config.secret_access_key.is_empty() {
+            config.secret_access_key = current.secret_access_key;
+        }
+        if config.passphrase.is_empty() {
+            config.passphrase = current.passphrase;
         }
+        let config = config.normalized()?;
+        self.db.set_setting("cloud_backup_enabled", if config.enabled { "1" } else { "0" })?;
+        self.db.set_setting("cloud_backup_endpoint", &config.endpoint)?;
+        self.db.set_setting("cloud_backup_region", &config.region)?;
+        self.db.set_setting("cloud_backup_bucket", &config.bucket)?;
+        self.db.set_setting("cloud_backup_prefix", &config.prefix)?;
+        self.db.set_setting("cloud_backup_access_key_id", &config.access_key_id)?;
+        self.db.set_setting(
+            "cloud_backup_secret_access_key",
+            &crate::secrets::encrypt_secret(&config.secret_access_key)?,
+        )?;
+        self.db.set_setting(
+            "cloud_backup_passphrase",
+            &crate::secrets::encrypt_secret(&config.passphrase)?,
+        )?;
+        self.db.set_setting("cloud_backup_interval_hours", &config.interval_hours.to_string())?;
+        self.db.set_setting("cloud_backup_retain", &config.retain.to_string())?;
+        crate::cloud_backup::set_cloud_backup_config(config);
+        Ok(())
+    }
+
+    /// 立即執行一次雲端備份
+    pub async fn run_cloud_backup(&self) -> Result<CloudBackup, String> {
+        let config = crate::cloud_backup::cloud_backup_config();
+        crate::cloud_backup::backup_now(&self.db, &self.data_dir, &config).await
+    }
+
+    /// 列出雲端上的備份（新到舊）
+    pub async fn list_cloud_backups(&self) -> Result<Vec<CloudBackup>, String> {
+        crate::cloud_backup::list_backups(&crate::cloud_backup::cloud_backup_config()).await
+    }
+
+    /// 由雲端備份還原整個 DB 與價格紀錄封存檔，並重新套用由 settings 載入的全域狀態。
+    ///
+    /// 本機專屬的 settings 不被快照覆寫；目前的雲端備份設定（endpoint、bucket 等）也會寫回還原後的 DB。
+    pub async fn restore_from_cloud(&self, key: &str) -> Result<(), String> {
+        let config = crate::cloud_backup::cloud_backup_config();
+        crate::cloud_backup::restore(&self.db, &self.data_dir, &config, key).await?;
+        self.set_cloud_backup_config(config)?;
+        self.reapply_settings().await;
+        Ok(())
+    }
+
+    /// 儲存並套用設定同步設定；密碼留空時沿用已儲存的值
+    pub fn set_sync_config(&self, mut config: SyncConfig) -> Result<(), String> {
+        if config.password.is_empty() {
+            config.password = crate::settings_sync::sync_config().password;
+        }
+        let config = config.normalized()?;
+        self.db.set_setting("sync_enabled", if config.enabled { "1" } else { "0" })?;
+        self.db.set_setting("sync_location", &config.location)?;
+        self.db.set_setting("sync_username", &config.username)?;
+        self.db.set_setting("sync_password", &crate::secrets::encrypt_secret(&config.password)?)?;
+        self.db.set_setting("sync_interval_minutes", &config.interval_minutes.to_string())?;
+        crate::settings_sync::set_sync_config(config);
+        Ok(())
+    }
+
+    /// 立即同步一次設定
+    pub async fn sync_settings_now(&self) -> Result<SyncAction, String> {
+        crate::settings_sync::sync_now(self, &crate::settings_sync::sync_config(), None).await
+    }
+
+    /// 將指定 subscription（依順序）的目前價格與 24h 走勢畫成 PNG 快照
+    pub async fn render_snapshot(&self, subscription_ids: &[i64]) -> Result<Vec<u8>, String> {
+        let now = chrono::Utc::now().timestamp();
+        let rows = {
+            let cache = self.polling.cache.read().await;
+            crate::snapshot::collect_rows(&self.db, &cache, subscription_ids, now)?
+        };
+        crate::snapshot::render_png("StockenBoard", &rows, now)
+    }
+
+    /// 目前的執行狀態與快取用量
+    pub async fn status(&self) -> AppStatus {
+        AppStatus {
+            version: env!("CARGO_PKG_VERSION"),
+            unattended: self.polling.is_unattended().await,
+            recording_paused: self.polling.is_recording_paused(),
+            cache: self.polling.cache_status().await,
+        }
+    }
+
+    /// 啟動多時間框漲跌基準價的背景更新
+    pub fn start_timeframe_changes(&self) {
+        crate::timeframes::start(self.db.clone());
+    }
+
+    /// 啟動歷史紀錄停滯檢查
+    pub fn start_record_health(&self) {
+        crate::record_health::start(self.db.clone(), self.event_bus.clone(), self.polling.recording_paused_flag());
+    }
+
+    /// 啟動 WS 串流 K 線的每分鐘收盤
+    pub fn start_candles(&self) {
+        crate::candles::start(self.db.clone(), self.event_bus.clone());
+    }
+
+    /// 啟動 OTLP trace 背景匯出
+    pub fn start_telemetry_export(&self) {
+        crate::telemetry::start();
+    }
+
+    /// 啟動排程雲端備份
+    pub fn start_cloud_backup(&self) {
+        crate::cloud_backup::start(self.db.clone(), self.data_dir.clone());
+    }
+
+    /// 啟動背景經濟日曆抓取
+    pub fn start_economic_calendar(&self) {
+        crate::calendar::start(self.db.clone(), self.event_bus.clone());
+    }
+
+    /// 儲存並套用經濟日曆設定；啟用時須有可用的 API key，並立即抓取一次
+    pub async fn set_calendar_config(&self, config: CalendarConfig) -> Result<(), String> {
+        let config = config.normalized()?;
+        if config.enabled {
+            crate::calendar::check_source(&self.db, &config.source)?;
+        }
+        self.db.set_setting("calendar_enabled", if config.enabled { "1" } else { "0" })?;
+        self.db.set_setting("calendar_source", &config.source)?;
+        self.db.set_setting("calendar_interval", &config.interval_secs.to_string())?;
+        self.db.set_setting("calendar_countries", &config.countries.join(","))?;
+        self.db.set_setting("calendar_min_impact", config.min_impact.as_str())?;
+        self.db.set_setting("calendar_alert_keywords", &config.alert_keywords.join(","))?;
+        let enabled = config.enabled;
+        crate::calendar::set_calendar_config(config);
+        if enabled {
+            if let Err(e) = crate::calendar::refresh(&self.db, &self.event_bus).await {
+                tracing::warn!("[Calendar] Refresh failed: {}", e);
+            }
+        }
+        Ok(())
+    }
+
+    /// 儲存 provider metadata 來源並立即重新載入覆寫（更換公鑰時清除舊覆寫）
+    pub async fn set_provider_metadata_source(
+        &self,
+        source: MetadataSource,
+    ) -> Result<MetadataStatus, String> {
+        let source = source.normalized()?;
+        self.db.set_setting("provider_metadata_url", &source.url)?;
+        self.db.set_setting("provider_metadata_key", &source.public_key)?;
+        crate::providers::metadata::clear();
+        let result = if source.url.is_empty() {
+            crate::providers::metadata::load_file(&self.data_dir, source.verifying_key())
+        } else {
+            crate::providers::metadata::refresh(&self.data_dir, &source.url, source.verifying_key()).await
+        };
+        self.polling.reload();
+        result.map(|_| crate::providers::metadata::status())
+    }
+
+    /// 保存目前的價格快取（程式結束時呼叫）
+    pub async fn save_price_snapshot(&self) {
+        save_snapshot(&self.db, &self.polling).await;
+    }
+
+    /// 儲存並套用省電模式設定，立即重新探測電源
+    pub async fn set_power_mode(&self, config: PowerConfig) -> Result<(), String> {
+        if !config.factor.is_finite() || !(1.0..=crate::power::MAX_FACTOR).contains(&config.factor) {
+            return Err(format!(
+                "Low-power factor must be between 1 and {}",
+                crate::power::MAX_FACTOR
+            ));
+        }
+        self.db.set_setting("low_power_mode", if config.enabled { "1" } else { "0" })?;
+        self.db.set_setting("low_power_factor", &config.factor.to_string())?;
+        crate::power::set_power_config(config);
+        crate::power::evaluate(&self.polling, &self.event_bus).await;
+        // 倍率改變但省電狀態未變時，evaluate 不會重新載入
+        self.polling.reload();
+        Ok(())
+    }
+
+    /// 調整日誌等級並持久化（重啟後沿用）
+    pub fn set_log_level(&self, level: &str) -> Result<(), String> {
+        crate::logging::set_level(level)?;
+        self.db.set_setting("log_level", &level.trim().to_lowercase())
+    }
+
+    /// 調整後端訊息語言並持久化（與前端 locale id 相同）
+    pub fn set_language(&self, language: &str) -> Result<(), String> {
+        let locale = crate::i18n::Locale::from_id(language);
+        self.db.set_setting("language", language.trim())?;
+        crate::i18n::set_locale(locale);
+        Ok(())
+    }
+
+    /// 儲存並套用 kiosk 設定；鎖定中需提供正確的 PIN，新 PIN 留空時沿用已儲存的 PIN
+    pub fn set_kiosk_config(&self, config: KioskConfig, current_pin: &str) -> Result<(), String> {
+        let current = crate::kiosk::kiosk_state();
+        if current.enabled && !crate::kiosk::verify_pin(&current, current_pin) {
+            return Err("Incorrect kiosk PIN".to_string());
+        }
+        let config = config.normalized()?;
+        let pin_hash = if config.pin.is_empty() {
+            current.pin_hash
+        } else {
+            Some(crate::kiosk::hash_pin(&config.pin))
+        };
+        self.db.set_setting("kiosk_enabled", if config.enabled { "1" } else { "0" })?;
+        self.db.set_setting("kiosk_pin_hash", pin_hash.as_deref().unwrap_or(""))?;
+        self.db.set_setting("kiosk_token", &config.token)?;
+        crate::kiosk::set_kiosk_state(crate::kiosk::KioskState {
+            enabled: config.enabled,
+            pin_hash,
+            token: config.token,
+        });
+        Ok(())
+    }
+
+    /// 儲存並套用區網 API listener 設定；token 與 scope 立即生效，bind / port 需由呼叫端重新啟動 listener
+    pub fn set_lan_listener_config(&self, config: crate::lan_listener::LanListenerConfig) -> Result<(), String> {
+        let config = config.normalized()?;
+        self.db.set_setting("lan_api_enabled", if config.enabled { "1" } else { "0" })?;
+        self.db.set_setting("lan_api_bind", &config.bind)?;
+        self.db.set_setting("lan_api_port", &config.port.to_string())?;
+        self.db.set_setting("lan_api_token", &config.token)?;
+        self.db.set_setting("lan_api_scope", config.scope.as_str())?;
+        crate::lan_listener::set_lan_listener_config(config);
+        Ok(())
+    }
+
+    /// 匯入 app 設定，並重新套用由 settings 載入的全域狀態（proxy、demo、replay、省電、日誌等級等）
+    pub async fn import_app_config(
+        &self,
+        config: &AppConfig,
+        mode: ConfigImportMode,
+    ) -> DbResult<ConfigImportSummary> {
+        let summary = self.db.import_app_config(config, mode)?;
+        self.reapply_settings().await;
+        Ok(summary)
+    }
+
+    /// 重新套用由 settings 載入的全域狀態，並重新載入規則與 polling（匯入設定、雲端還原後使用）
+    async fn reapply_settings(&self) {
+        crate::providers::set_global_proxy(self.db.get_setting("http_proxy").ok().flatten());
+        crate::providers::mock::set_mock_config(load_mock_config(&self.db));
+        crate::providers::replay::set_replay_config(load_replay_config(&self.db));
+        crate::providers::yahoo::set_quote_summary_enabled(load_yahoo_quote_summary(&self.db));
+        crate::providers::jupiter::set_token_list_enabled(load_jupiter_token_list(&self.db));
+        crate::providers::alphavantage::set_refresh_hours(load_alphavantage_refresh_hours(&self.db));
+        crate::providers::eodhd::set_default_exchange(&load_eodhd_default_exchange(&self.db));
+        crate::providers::subgraph::set_custom_protocols(load_subgraph_protocols(&self.db));
+        crate::power::set_power_config(load_power_config(&self.db));
+        crate::gas::set_gas_config(load_gas_config(&self.db));
+        crate::depeg::set_depeg_config(load_depeg_config(&self.db));
+        crate::calendar::set_calendar_config(load_calendar_config(&self.db));
+        crate::sanity::set_sanity_config(load_sanity_config(&self.db));
+        crate::cache_limit::set_cache_config(load_cache_config(&self.db));
+        crate::emit_throttle::set_emit_config(load_emit_config(&self.db));
+        crate::record_health::set_record_stall_config(load_record_stall_config(&self.db));
+        crate::latency_tune::set_latency_tune_config(load_latency_tune_config(&self.db));
+        crate::ws_first::set_ws_first_config(load_ws_first_config(&self.db));
+        crate::candles::set_candle_config(load_candle_config(&self.db));
+        crate::telemetry::set_otlp_config(load_otlp_config(&self.db));
+        crate::cloud_backup::set_cloud_backup_config(load_cloud_backup_config(&self.db));
+        crate::settings_sync::set_sync_config(load_sync_config(&self.db));
+        if let Some(level) = self.db.get_setting("log_level").ok().flatten() {
+            let _ = crate::logging::set_level(&level);
+        }
+        crate::i18n::set_locale(load_locale(&self.db));
+        crate::kiosk::set_kiosk_state(load_kiosk_state(&self.db));
+        crate::lan_listener::set_lan_listener_config(load_lan_listener_config(&self.db));
+        if let Some(secs) = self
+            .db
+            .get_setting("notification_global_cooldown")
+            .ok()
+            .flatten()
+            .and_then(|s| s.parse().ok())
+        {
+            self.global_cooldown.set_cooldown(secs);
+        }
+        self.polling.set_recording_paused(
+            self.db.get_setting("recording_paused").ok().flatten().is_some_and(|v| v == "1"),
+        );
+
+        self.registry.clear().await;
+        self.notification_engine.reload_rules().await;
+        self.ai_scheduler.reload().await;
+        self.polling.reload();
+        self.sync_polling_for_rules().await;
+    }
+
+    /// 啟動時是否因為有開啟紀錄的訂閱而需要背景 polling（錄製排程啟用時交由排程決定）
+    pub fn recordings_need_unattended(&self) -> bool {
+        !crate::schedule::load_schedule(&self.db).enabled
+            && self.db.count_active_recordings().unwrap_or(0) > 0
     }
 
     /// 當有啟用的通知規則時，自動啟動後台 polling。
     /// 當沒有啟用的規則時，恢復為前端驅動模式（除非有手動開啟的紀錄）。
+    /// 啟用錄製排程時，紀錄的背景 polling 由排程決定。
     pub async fn sync_polling_for_rules(&self) {
         let has_enabled_rules = self.db.list_notification_rules()
             .map(|rules| rules.iter().any(|r| r.enabled))
             .unwrap_or(false);
-        let has_active_recordings = self.db.count_active_recordings().unwrap_or(0) > 0;
+        let has_active_recordings = self.recordings_need_unattended();
 
         if (has_enabled_rules || has_active_recordings) && !self.polling.is_unattended().await {
             self.polling.set_unattended(true).await;
             self.polling.reload();
-            eprintln!("[CoreState] Auto-enabled background polling (active rules/recordings exist)");
+            tracing::info!("[CoreState] Auto-enabled background polling (active rules/recordings exist)");
         }
     }
 }
@@ -0,0 +1,742 @@
+//! 完整 app 設定的版本化匯出／匯入（`export_app_config` / `import_app_config`）。
+//!
+//! 與 `export_data` 不同，涵蓋 provider 設定、通知 channel／規則與 app settings；
+//! 匯入前先驗證整份檔案，並在單一 transaction 中寫入，失敗時不留下部分資料。
+
+use std::collections::{HashMap, HashSet};
+
+use rusqlite::{params, Connection, OptionalExtension};
+
+use super::busy::{db_error, DbResult};
+use super::schema::{
+    AppConfig, AppConfigChannel, AppConfigRule, AppConfigView, ConfigImportMode,
+    ConfigImportSummary, ExportSubscription, SubscriptionRef, APP_CONFIG_VERSION,
+};
+use super::DbPool;
+use crate::i18n::{tr, Msg};
+use crate::notifications::crypto::{decrypt_token, encrypt_token};
+
+/// 綁定單一機器、不隨設定搬移的 settings（雲端還原時也保留本機的值）
+pub(super) const MACHINE_SETTINGS: &[&str] = &[
+    "secrets_backend",
+    "secrets_key_source",
+    "secrets_key_check",
+    "coingecko_coins_refreshed_at",
+    "coinpaprika_coins_refreshed_at",
+    "jupiter_token_list_refreshed_at",
+    "alphavantage_quota",
+    "cloud_backup_secret_access_key",
+    "cloud_backup_passphrase",
+    "cloud_backup_last_at",
+    "cloud_backup_history_archived_to",
+    "sync_enabled",
+    "sync_location",
+    "sync_username",
+    "sync_password",
+    "sync_interval_minutes",
+    "sync_device_id",
+    "sync_last_hash",
+    "sync_last_remote_at",
+    "kiosk_enabled",
+    "kiosk_pin_hash",
+    "kiosk_token",
+    "lan_api_enabled",
+    "lan_api_bind",
+    "lan_api_port",
+    "lan_api_token",
+    "lan_api_scope",
+];
+/// 以機器金鑰加密的 settings，只在包含 secret 時以明文匯出
+const SECRET_SETTINGS: &[&str] = &["ai_api_key", "gas_tracker_etherscan_key"];
+/// 每台機器啟動時都會建立的內建 channel
+const BUILTIN_CHANNELS: &[&str] = &["local", "system"];
+
+impl AppConfig {
+    /// 檢查版本、provider 與所有交互參照（view / rule → 訂閱，rule → channel）
+    pub fn validate(&self) -> Result<(), String> {
+        if self.version == 0 || self.version > APP_CONFIG_VERSION {
+            return Err(format!(
+                "Unsupported config version {} (this app supports up to {})",
+                self.version, APP_CONFIG_VERSION
+            ));
+        }
+
+        let mut subs = HashSet::new();
+        for sub in &self.subscriptions {
+            if sub.symbol.trim().is_empty() {
+                return Err("Subscription symbol must not be empty".to_string());
+            }
+            if sub.sub_type != "asset" && sub.sub_type != "dex" {
+                return Err(format!("Invalid subscription type '{}'", sub.sub_type));
+            }
+            if crate::providers::get_provider_info(&sub.selected_provider_id).is_none() {
+                return Err(tr(Msg::ProviderNotFound, &[&sub.selected_provider_id]));
+            }
+            if !subs.insert(sub_ref(sub)) {
+                return Err(format!(
+                    "Duplicate subscription {} ({})",
+                    sub.symbol, sub.selected_provider_id
+                ));
+            }
+        }
+        let check_ref = |r: &SubscriptionRef, owner: &str| {
+            if subs.contains(r) {
+                Ok(())
+            } else {
+                Err(format!(
+                    "{} references unknown subscription {} ({})",
+                    owner, r.symbol, r.provider_id
+                ))
+            }
+        };
+
+        for view in &self.views {
+            if view.name.trim().is_empty() {
+                return Err("View name must not be empty".to_string());
+            }
+            if view.view_type != "asset" && view.view_type != "dex" {
+                return Err(format!("Invalid view type '{}'", view.view_type));
+            }
+            for r in &view.subscriptions {
+                check_ref(r, &format!("View '{}'", view.name))?;
+            }
+        }
+
+        for row in &self.provider_settings {
+            if crate::providers::get_provider_info(&row.provider_id).is_none() {
+                return Err(tr(Msg::ProviderNotFound, &[&row.provider_id]));
+            }
+            if let Some(headers) = row.extra_headers.as_deref() {
+                crate::providers::parse_extra_headers(headers)?;
+            }
+        }
+
+        let mut channel_keys = HashSet::new();
+        for channel in &self.notification_channels {
+            if !channel_keys.insert(channel.key) {
+                return Err(format!("Duplicate notification channel key {}", channel.key));
+            }
+            serde_json::from_str::<serde_json::Value>(&channel.config)
+                .map_err(|e| format!("Invalid config for channel '{}': {}", channel.name, e))?;
+        }
+
+        for rule in &self.notification_rules {
+            let owner = format!("Rule '{}'", rule.name);
+            check_ref(&rule.subscription, &owner)?;
+            for r in rule.subscriptions.iter().flatten() {
+                check_ref(r, &owner)?;
+            }
+            if let Some(key) = rule.channels.iter().find(|k| !channel_keys.contains(k)) {
+                return Err(format!("{} references unknown channel {}", owner, key));
+            }
+            match rule.ai_config.as_deref() {
+                Some(json) => {
+                    serde_json::from_str::<crate::notifications::models::AiConfig>(json)
+                        .map_err(|e| format!("Invalid ai_config for {}: {}", owner, e))?
+                        .validate()?;
+                }
+                None if rule.condition_type == "ai" => {
+                    return Err(format!("{} is an AI rule without ai_config", owner));
+                }
+                None => {}
+            }
+        }
+
+        if self.settings.keys().any(|k| k.trim().is_empty()) {
+            return Err("Setting keys must not be empty".to_string());
+        }
+        Ok(())
+    }
+}
+
+fn sub_ref(sub: &ExportSubscription) -> SubscriptionRef {
+    SubscriptionRef {
+        symbol: sub.symbol.clone(),
+        provider_id: sub.selected_provider_id.clone(),
+    }
+}
+
+/// Telegram channel 的 Bot Token 以機器金鑰加密：匯出時解密或移除，匯入時重新加密
+fn export_channel_config(channel_type: &str, config: &str, include_secrets: bool) -> String {
+    if channel_type != "telegram" {
+        return config.to_string();
+    }
+    let Ok(mut value) = serde_json::from_str::<serde_json::Value>(config) else {
+        return config.to_string();
+    };
+    let token = value
+        .get("bot_token")
+        .and_then(|t| t.as_str())
+        .and_then(|t| decrypt_token(t).ok())
+        .filter(|_| include_secrets);
+    match token {
+        Some(token) => value["bot_token"] = token.into(),
+        None => {
+            if let Some(obj) = value.as_object_mut() {
+                obj.remove("bot_token");
+            }
+        }
+    }
+    value.to_string()
+}
+
+/// 回傳要寫入 DB 的 channel config；None = Telegram channel 缺少 Bot Token
+fn import_channel_config(channel: &AppConfigChannel) -> Result<Option<String>, String> {
+    if channel.channel_type != "telegram" {
+        return Ok(Some(channel.config.clone()));
+    }
+    let mut value: serde_json::Value = serde_json::from_str(&channel.config)
+        .map_err(|e| format!("Invalid config for channel '{}': {}", channel.name, e))?;
+    let Some(token) = value
+        .get("bot_token")
+        .and_then(|t| t.as_str())
+        .filter(|t| !t.is_empty())
+    else {
+        return Ok(None);
+    };
+    value["bot_token"] = encrypt_token(token)?.into();
+    Ok(Some(value.to_string()))
+}
+
+impl DbPool {
+    /// 匯出完整 app 設定；`include_secrets` 為 false 時不含 API key / secret 與 Bot Token
+    pub fn export_app_config(&self, include_secrets: bool) -> Result<AppConfig, String> {
+        let export = self.export_data()?;
+        let subs = self.list_all_subscriptions()?;
+        let ref_by_id: HashMap<i64, SubscriptionRef> = subs
+            .iter()
+            .map(|s| {
+                (
+                    s.id,
+                    SubscriptionRef {
+                        symbol: s.symbol.clone(),
+                        provider_id: s.selected_provider_id.clone(),
+                    },
+                )
+            })
+            .collect();
+
+        let mut views = Vec::new();
+        for view_type in ["asset", "dex"] {
+            for view in self.list_views(view_type)?.into_iter().filter(|v| !v.is_default) {
+                let subscriptions = self
+                    .get_view_subscription_ids(view.id)?
+                    .iter()
+                    .filter_map(|id| ref_by_id.get(id).cloned())
+                    .collect();
+                views.push(AppConfigView {
+                    name: view.name,
+                    view_type: view.view_type,
+                    subscriptions,
+                });
+            }
+        }
+
+        let provider_settings = self
+            .list_provider_settings()?
+            .into_iter()
+            .map(|mut row| {
+                if !include_secrets {
+                    row.api_key = None;
+                    row.api_secret = None;
+                }
+                row
+            })
+            .collect();
+
+        let notification_channels = self
+            .list_notification_channels()?
+            .into_iter()
+            .map(|c| AppConfigChannel {
+                key: c.id,
+                config: export_channel_config(&c.channel_type, &c.config, include_secrets),
+                channel_type: c.channel_type,
+                name: c.name,
+            })
+            .collect();
+
+        let mut notification_rules = Vec::new();
+        for rule in self.list_notification_rules()? {
+            let Some(subscription) = ref_by_id.get(&rule.subscription_id).cloned() else {
+                continue;
+            };
+            let subscriptions = rule
+                .subscription_ids
+                .as_deref()
+                .and_then(|json| serde_json::from_str::<Vec<i64>>(json).ok())
+                .map(|ids| ids.iter().filter_map(|id| ref_by_id.get(id).cloned()).collect());
+            notification_rules.push(AppConfigRule {
+                name: rule.name,
+                subscription,
+                subscriptions,
+                condition_type: rule.condition_type,
+                threshold: rule.threshold,
+                channels: serde_json::from_str(&rule.channel_ids).unwrap_or_default(),
+                cooldown_secs: rule.cooldown_secs,
+                enabled: rule.enabled,
+                ai_config: rule.ai_config,
+            });
+        }
+
+        let mut settings = std::collections::BTreeMap::new();
+        {
+            let conn = self.conn.lock().unwrap();
+            let mut stmt = conn
+                .prepare("SELECT key, value FROM app_settings ORDER BY key")
+                .map_err(|e| e.to_string())?;
+            let rows = stmt
+                .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+                .map_err(|e| e.to_string())?;
+            for (key, value) in rows.filter_map(|r| r.ok()) {
+                if MACHINE_SETTINGS.contains(&key.as_str()) {
+                    continue;
+                }
+                if SECRET_SETTINGS.contains(&key.as_str()) {
+                    match decrypt_token(&value) {
+                        Ok(plain) if include_secrets && !plain.is_empty() => {
+                            settings.insert(key, plain);
+                        }
+                        _ => {}
+                    }
+                    continue;
+                }
+                settings.insert(key, value);
+            }
+        }
+
+        Ok(AppConfig {
+            version: APP_CONFIG_VERSION,
+            exported_at: chrono::Utc::now().timestamp(),
+            includes_secrets: include_secrets,
+            subscriptions: export.subscriptions,
+            views,
+            provider_settings,
+            notification_channels,
+            notification_rules,
+            settings,
+        })
+    }
+
+    /// 驗證並匯入 app 設定。
+    ///
+    /// - `Merge`：新增匯入檔中的項目、更新同 key 的現有項目，其餘保持不變；
+    ///   匯入檔未含 secret 時保留現有的 API key / secret。
+    /// - `Replace`：另外刪除匯入檔中沒有的訂閱、view、provider 設定、channel、規則與 settings。
+    ///   同一 (symbol, provider) 的訂閱只會更新，因此其價格歷史會保留。
+    pub fn import_app_config(
+        &self,
+        config: &AppConfig,
+        mode: ConfigImportMode,
+    ) -> DbResult<ConfigImportSummary> {
+        config.validate()?;
+
+        // secret 欄位需在取得連線鎖之前處理（keychain 後端會查詢 settings）
+        let mut provider_secrets = Vec::new();
+        for row in &config.provider_settings {
+            let api_key = row
+                .api_key
+                .as_deref()
+                .map(|v| self.store_secret_column(&row.provider_id, "api_key", Some(v)))
+                .transpose()?
+                .flatten();
+            let api_secret = row
+                .api_secret
+                .as_deref()
+                .map(|v| self.store_secret_column(&row.provider_id, "api_secret", Some(v)))
+                .transpose()?
+                .flatten();
+            provider_secrets.push((api_key, api_secret));
+        }
+        let mut settings = config.settings.clone();
+        for key in MACHINE_SETTINGS {
+            settings.remove(*key);
+        }
+        for key in SECRET_SETTINGS {
+            if let Some(value) = settings.get_mut(*key) {
+                *value = encrypt_token(value)?;
+            }
+        }
+
+        let tx = self.begin_write().map_err(|e| db_error("Failed to start import", e))?;
+        let mut summary = ConfigImportSummary::default();
+
+        if mode == ConfigImportMode::Replace {
+            remove_absent(&tx, config, &settings)?;
+        }
+
+        // 訂閱
+        let mut sub_ids: HashMap<SubscriptionRef, i64> = HashMap::new();
+        for sub in &config.subscriptions {
+            tx.execute(
+                "INSERT INTO subscriptions (sub_type, symbol, display_name, selected_provider_id, asset_type, pool_address, token_from_address, token_to_address, record_enabled, record_from_hour, record_to_hour, sort_order, provider_params, record_dedup_secs)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
+                 ON CONFLICT(symbol, selected_provider_id) DO UPDATE SET
+                   sub_type = ?1, display_name = ?3, asset_type = ?5, pool_address = ?6, token_from_address = ?7, token_to_address = ?8,
+                   record_enabled = ?9, record_from_hour = ?10, record_to_hour = ?11, sort_order = ?12, provider_params = ?13, record_dedup_secs = ?14",
+                params![
+                    sub.sub_type, sub.symbol, sub.display_name, sub.selected_provider_id,
+                    sub.asset_type, sub.pool_address, sub.token_from_address, sub.token_to_address,
+                    sub.record_enabled.unwrap_or(false), sub.record_from_hour, sub.record_to_hour, sub.sort_order.unwrap_or(0),
+                    sub.provider_params, sub.record_dedup_secs
+                ],
+            )
+            .map_err(|e| format!("Failed to import subscription {}: {}", sub.symbol, e))?;
+            let id: i64 = tx
+                .query_row(
+                    "SELECT id FROM subscriptions WHERE symbol = ?1 AND selected_provider_id = ?2",
+                    params![sub.symbol, sub.selected_provider_id],
+                    |row| row.get(0),
+                )
+                .map_err(|e| e.to_string())?;
+            sub_ids.insert(sub_ref(sub), id);
+            summary.subscriptions += 1;
+        }
+
+        // View（現有同名 view 會加入匯入的訂閱）
+        for view in &config.views {
+            tx.execute(
+                "INSERT OR IGNORE INTO views (name, view_type, is_default) VALUES (?1, ?2, 0)",
+                params![view.name, view.view_type],
+            )
+            .map_err(|e| e.to_string())?;
+            let view_id: i64 = tx
+                .query_row(
+                    "SELECT id FROM views WHERE name = ?1 AND view_type = ?2",
+                    params![view.name, view.view_type],
+                    |row| row.get(0),
+                )
+                .map_err(|e| e.to_string())?;
+            for r in &view.subscriptions {
+                tx.execute(
+                    "INSERT OR IGNORE INTO view_subscriptions (view_id, subscription_id) VALUES (?1, ?2)",
+                    params![view_id, sub_ids[r]],
+                )
+                .map_err(|e| e.to_string())?;
+            }
+            summary.views += 1;
+        }
+
+        // Provider 設定（未含 secret 時保留現有值）
+        for (row, (api_key, api_secret)) in config.provider_settings.iter().zip(provider_secrets) {
+            tx.execute(
+                "INSERT INTO provider_settings (provider_id, api_key, api_secret, api_url, refresh_interval, connection_type, record_from_hour, record_to_hour, proxy_url, timeout_ms, extra_headers)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+                 ON CONFLICT(provider_id) DO UPDATE SET
+                   api_key = COALESCE(?2, api_key), api_secret = COALESCE(?3, api_secret), api_url = ?4, refresh_interval = ?5,
+                   connection_type = ?6, record_from_hour = ?7, record_to_hour = ?8, proxy_url = ?9, timeout_ms = ?10, extra_headers = ?11",
+                params![
+                    row.provider_id, api_key, api_secret, row.api_url, row.refresh_interval, row.connection_type,
+                    row.record_from_hour, row.record_to_hour, row.proxy_url, row.timeout_ms, row.extra_headers
+                ],
+            )
+            .map_err(|e| format!("Failed to import settings for {}: {}", row.provider_id, e))?;
+            summary.provider_settings += 1;
+        }
+
+        // 通知 channel：內建 channel 與同類型同名的現有 channel 直接沿用
+        let mut channel_ids: HashMap<i64, i64> = HashMap::new();
+        for channel in &config.notification_channels {
+            let existing: Option<i64> = if BUILTIN_CHANNELS.contains(&channel.channel_type.as_str()) {
+                tx.query_row(
+                    "SELECT id FROM notification_channels WHERE channel_type = ?1 ORDER BY id LIMIT 1",
+                    [&channel.channel_type],
+                    |row| row.get(0),
+                )
+            } else {
+                tx.query_row(
+                    "SELECT id FROM notification_channels WHERE channel_type = ?1 AND name = ?2 ORDER BY id LIMIT 1",
+                    params![channel.channel_type, channel.name],
+                    |row| row.get(0),
+                )
+            }
+            .optional()
+            .map_err(|e| e.to_string())?;
+            let stored = import_channel_config(channel)?;
+
+            let id = match (existing, stored) {
+                (Some(id), Some(stored)) if !BUILTIN_CHANNELS.contains(&channel.channel_type.as_str()) => {
+                    tx.execute(
+                        "UPDATE notification_channels SET config = ?1 WHERE id = ?2",
+                        params![stored, id],
+                    )
+                    .map_err(|e| e.to_string())?;
+                    id
+                }
+                (Some(id), _) => id,
+                (None, Some(stored)) => {
+                    tx.execute(
+                        "INSERT INTO notification_channels (channel_type, name, config, created_at) VALUES (?1, ?2, ?3, ?4)",
+                        params![channel.channel_type, channel.name, stored, chrono::Utc::now().timestamp()],
+                    )
+                    .map_err(|e| e.to_string())?;
+                    tx.last_insert_rowid()
+                }
+                (None, None) => {
+                    summary.skipped.push(format!(
+                        "Channel '{}': Telegram Bot Token not included in the export",
+                        channel.name
+                    ));
+                    continue;
+                }
+            };
+            channel_ids.insert(channel.key, id);
+            summary.notification_channels += 1;
+        }
+
+        // 通知規則：同名且同訂閱的規則視為已存在
+        let now = chrono::Utc::now().timestamp();
+        for rule in &config.notification_rules {
+            let subscription_id = sub_ids[&rule.subscription];
+            let channels: Vec<i64> = rule
+                .channels
+                .iter()
+                .filter_map(|key| channel_ids.get(key).copied())
+                .collect();
+            if channels.is_empty() {
+                summary.skipped.push(format!("Rule '{}': none of its channels were imported", rule.name));
+                continue;
+            }
+            let exists: bool = tx
+                .query_row(
+                    "SELECT EXISTS(SELECT 1 FROM notification_rules WHERE name = ?1 AND subscription_id = ?2)",
+                    params![rule.name, subscription_id],
+                    |row| row.get(0),
+                )
+                .map_err(|e| e.to_string())?;
+            if exists {
+                summary.skipped.push(format!("Rule '{}': already exists", rule.name));
+                continue;
+            }
+            let subscription_ids = rule
+                .subscriptions
+                .as_ref()
+                .map(|refs| refs.iter().map(|r| sub_ids[r]).collect::<Vec<_>>())
+                .map(|ids| serde_json::to_string(&ids))
+                .transpose()
+                .map_err(|e| e.to_string())?;
+            let channel_ids_json = serde_json::to_string(&channels).map_err(|e| e.to_string())?;
+            tx.execute(
+                "INSERT INTO notification_rules (name, subscription_id, condition_type, threshold, channel_ids, cooldown_secs, enabled, ai_config, subscription_ids, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?10)",
+                params![
+                    rule.name, subscription_id, rule.condition_type, rule.threshold, channel_ids_json,
+                    rule.cooldown_secs, rule.enabled, rule.ai_config, subscription_ids, now
+                ],
+            )
+            .map_err(|e| format!("Failed to import rule '{}': {}", rule.name, e))?;
+            summary.notification_rules += 1;
+        }
+
+        // App settings
+        for (key, value) in &settings {
+            tx.execute(
+                "INSERT INTO app_settings (key, value) VALUES (?1, ?2) ON CONFLICT(key) DO UPDATE SET value = ?2",
+                params![key, value],
+            )
+            .map_err(|e| e.to_string())?;
+            summary.settings += 1;
+        }
+
+        tx.commit().map_err(|e| format!("Failed to commit import: {}", e))?;
+        self.invalidate_provider_settings_cache();
+        Ok(summary)
+    }
+}
+
+/// Replace 模式：刪除匯入檔中沒有的項目（規則一律重建）
+fn remove_absent(
+    tx: &Connection,
+    config: &AppConfig,
+    settings: &std::collections::BTreeMap<String, String>,
+) -> Result<(), String> {
+    let exec = |sql: &str, values: &[String]| -> Result<(), String> {
+        tx.execute(sql, rusqlite::params_from_iter(values))
+            .map(|_| ())
+            .map_err(|e| format!("Failed to clear existing data: {}", e))
+    };
+    // 以 JSON 陣列傳入保留清單，避免組合 SQL
+    let keep = |values: Vec<String>| vec![serde_json::to_string(&values).unwrap_or_default()];
+
+    exec("DELETE FROM notification_rules", &[])?;
+    exec(
+        "DELETE FROM subscriptions WHERE symbol || char(31) || selected_provider_id NOT IN (SELECT value FROM json_each(?1))",
+        &keep(
+            config
+                .subscriptions
+                .iter()
+                .map(|s| format!("{}\u{1f}{}", s.symbol, s.selected_provider_id))
+                .collect(),
+        ),
+    )?;
+    exec(
+        "DELETE FROM views WHERE is_default = 0 AND view_type || char(31) || name NOT IN (SELECT value FROM json_each(?1))",
+        &keep(
+            config
+                .views
+                .iter()
+                .map(|v| format!("{}\u{1f}{}", v.view_type, v.name))
+                .collect(),
+        ),
+    )?;
+    // 匯入的 view 內容以匯入檔為準
+    exec(
+        "DELETE FROM view_subscriptions WHERE view_id IN (SELECT id FROM views WHERE is_default = 0)",
+        &[],
+    )?;
+    exec(
+        "DELETE FROM provider_settings WHERE provider_id NOT IN (SELECT value FROM json_each(?1))",
+        &keep(config.provider_settings.iter().map(|p| p.provider_id.clone()).collect()),
+    )?;
+    exec(
+        "DELETE FROM notification_channels WHERE channel_type NOT IN ('local', 'system')
+           AND channel_type || char(31) || name NOT IN (SELECT value FROM json_each(?1))",
+        &keep(
+            config
+                .notification_channels
+                .iter()
+                .map(|c| format!("{}\u{1f}{}", c.channel_type, c.name))
+                .collect(),
+        ),
+    )?;
+    let mut kept_settings: Vec<String> = settings.keys().cloned().collect();
+    kept_settings.extend(MACHINE_SETTINGS.iter().map(|k| k.to_string()));
+    kept_settings.extend(SECRET_SETTINGS.iter().map(|k| k.to_string()));
+    exec(
+        "DELETE FROM app_settings WHERE key NOT IN (SELECT value FROM json_each(?1))",
+        &keep(kept_settings),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn open_test_db() -> DbPool {
+        DbPool::open(&PathBuf::from(":memory:")).unwrap()
+    }
+
+    /// 建立含訂閱、view、provider 設定、通知規則與 settings 的 DB
+    fn populated_db() -> DbPool {
+        let db = open_test_db();
+        db.ensure_local_channel().unwrap();
+        let btc = db
+            .add_subscription("asset", "BTCUSDT", Some("Bitcoin"), "binance", "crypto", None, None, None)
+            .unwrap();
+        db.add_subscription("asset", "AAPL", None, "yahoo", "stock", None, None, None)
+            .unwrap();
+        let view = db.create_view("Crypto", "asset").unwrap();
+        db.add_sub_to_view(view, btc).unwrap();
+        db.upsert_provider_settings(
+            "binance", Some("key-123"), None, None, Some(5000), "rest", None, None, None, None, None,
+        )
+        .unwrap();
+        let local = db.list_notification_channels().unwrap()[0].id;
+        db.create_notification_rule(
+            "BTC above 100k", btc, "price_above", 100_000.0, &format!("[{}]", local), 300, None, None,
+        )
+        .unwrap();
+        db.set_setting("http_proxy", "http://127.0.0.1:8888").unwrap();
+        db
+    }
+
+    #[test]
+    fn export_round_trips_into_empty_db() {
+        let source = populated_db();
+        let config = source.export_app_config(false).unwrap();
+        assert_eq!(config.version, APP_CONFIG_VERSION);
+        assert!(config.provider_settings.iter().all(|p| p.api_key.is_none()));
+
+        // JSON 往返後匯入新 DB
+        let json = serde_json::to_string(&config).unwrap();
+        let parsed: AppConfig = serde_json::from_str(&json).unwrap();
+        let target = open_test_db();
+        target.ensure_local_channel().unwrap();
+        let summary = target.import_app_config(&parsed, ConfigImportMode::Merge).unwrap();
+        assert_eq!(summary.subscriptions, 2);
+        assert_eq!(summary.views, 1);
+        assert_eq!(summary.notification_rules, 1);
+        assert!(summary.skipped.is_empty(), "{:?}", summary.skipped);
+
+        let subs = target.list_all_subscriptions().unwrap();
+        assert_eq!(subs.len(), 2);
+        let views = target.list_views("asset").unwrap();
+        let crypto = views.iter().find(|v| v.name == "Crypto").unwrap();
+        assert_eq!(target.get_view_subscription_ids(crypto.id).unwrap().len(), 1);
+        let rules = target.list_notification_rules().unwrap();
+        assert_eq!(rules[0].name, "BTC above 100k");
+        assert_eq!(
+            target.get_setting("http_proxy").unwrap().as_deref(),
+            Some("http://127.0.0.1:8888")
+        );
+        assert_eq!(
+            target.get_provider_settings("binance").unwrap().unwrap().refresh_interval,
+            Some(5000)
+        );
+    }
+
+    #[test]
+    fn merge_keeps_existing_secrets_and_replace_removes_absent_items() {
+        let db = populated_db();
+        let mut config = db.export_app_config(false).unwrap();
+        config.subscriptions.retain(|s| s.symbol == "BTCUSDT");
+        config.settings.remove("http_proxy");
+
+        db.import_app_config(&config, ConfigImportMode::Merge).unwrap();
+        assert_eq!(db.list_all_subscriptions().unwrap().len(), 2);
+        assert_eq!(
+            db.get_provider_settings("binance").unwrap().unwrap().api_key.as_deref(),
+            Some("key-123")
+        );
+
+        let btc_id = db.list_all_subscriptions().unwrap().iter().find(|s| s.symbol == "BTCUSDT").unwrap().id;
+        db.import_app_config(&config, ConfigImportMode::Replace).unwrap();
+        let subs = db.list_all_subscriptions().unwrap();
+        assert_eq!(subs.len(), 1);
+        // 同一訂閱只更新，id（與價格歷史）保留
+        assert_eq!(subs[0].id, btc_id);
+        assert_eq!(db.get_setting("http_proxy").unwrap(), None);
+        assert_eq!(db.list_notification_rules().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn invalid_config_is_rejected_without_writing() {
+        let db = populated_db();
+        let mut config = db.export_app_config(false).unwrap();
+        config.views[0].subscriptions.push(SubscriptionRef {
+            symbol: "MISSING".to_string(),
+            provider_id: "binance".to_string(),
+        });
+        assert!(db.import_app_config(&config, ConfigImportMode::Replace).is_err());
+        assert_eq!(db.list_all_subscriptions().unwrap().len(), 2);
+
+        let mut future = db.export_app_config(false).unwrap();
+        future.version = APP_CONFIG_VERSION + 1;
+        assert!(future.validate().is_err());
+    }
+
+    #[test]
+    fn telegram_token_only_exported_with_secrets() {
+        let db = open_test_db();
+        let stored = serde_json::json!({
+            "bot_token": encrypt_token("123:abc").unwrap(),
+            "chat_id": "42",
+        });
+        db.create_notification_channel("telegram", "Phone", &stored.to_string())
+            .unwrap();
+
+        let without = db.export_app_config(false).unwrap();
+        assert!(!without.notification_channels[0].config.contains("bot_token"));
+        let target = open_test_db();
+        let summary = target.import_app_config(&without, ConfigImportMode::Merge).unwrap();
+        assert_eq!(summary.notification_channels, 0);
+        assert_eq!(summary.skipped.len(), 1);
+
+        let with = db.export_app_config(true).unwrap();
+        assert!(with.notification_channels[0].config.contains("123:abc"));
+        target.import_app_config(&with, ConfigImportMode::Merge).unwrap();
+        let channel = &target.list_notification_channels().unwrap()[0];
+        assert!(!channel.config.contains("123:abc"));
+        assert!(channel.config.contains("42"));
+    }
+}
@@ -0,0 +1,214 @@
+use std::path::Path;
+
+use super::app_config::MACHINE_SETTINGS;
+use super::DbPool;
+
+impl DbPool {
+    // ── Snapshot / Restore ──────────────────────────────────────
+
+    /// 以 `VACUUM INTO` 將整個 DB（含價格紀錄）寫成一致的快照檔；目標檔案不可已存在。
+    pub fn write_snapshot(&self, path: &Path) -> Result<(), String> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("VACUUM INTO ?1", [path.to_string_lossy()])
+            .map_err(|e| format!("Failed to write DB snapshot: {}", e))?;
+        Ok(())
+    }
+
+    /// 以快照檔內容取代目前所有資料表（單一 transaction）。
+    ///
+    /// 只複製兩邊都存在的欄位，較舊版本的快照缺少的欄位保留預設值；快照中沒有的資料表會被清空。
+    /// 本機專屬的 settings（主金鑰檢查值、kiosk、LAN、同步等）保留目前的值。
+    pub fn restore_snapshot(&self, path: &Path) -> Result<(), String> {
+        let machine_keys = serde_json::to_string(MACHINE_SETTINGS).map_err(|e| e.to_string())?;
+        let conn = self.conn.lock().unwrap();
+        conn.execute("ATTACH DATABASE ?1 AS snapshot", [path.to_string_lossy()])
+            .map_err(|e| format!("Failed to open DB snapshot: {}", e))?;
+
+        let result = (|| -> rusqlite::Result<()> {
+            let table_names = |schema: &str| -> rusqlite::Result<Vec<String>> {
+                let mut stmt = conn.prepare(&format!(
+                    "SELECT name FROM {}.sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'",
+                    schema
+                ))?;
+                let rows = stmt.query_map([], |row| row.get(0))?;
+                rows.collect()
+            };
+            let columns = |schema: &str, table: &str| -> rusqlite::Result<Vec<String>> {
+                let mut stmt = conn.prepare("SELECT name FROM pragma_table_info(?1, ?2)")?;
+                let rows = stmt.query_map([table, schema], |row| row.get(0))?;
+                rows.collect()
+            };
+
+            let main_tables = table_names("main")?;
+            let snapshot_tables = table_names("snapshot")?;
+
+            let machine_settings: Vec<(String, String)> = {
+                let mut stmt = conn.prepare(
+                    "SELECT key, value FROM main.app_settings WHERE key IN (SELECT value FROM json_each(?1))",
+                )?;
+                let rows = stmt.query_map([&machine_keys], |row| Ok((row.get(0)?, row.get(1)?)))?;
+                rows.collect::<rusqlite::Result<_>>()?
+            };
+
+            let tx = conn.unchecked_transaction()?;
+            tx.execute_batch("PRAGMA defer_foreign_keys = ON;")?;
+            for table in &main_tables {
+                tx.execute(&format!("DELETE FROM main.\"{}\"", table), [])?;
+            }
+            for table in snapshot_tables.iter().filter(|t| main_tables.contains(t)) {
+                let main_columns = columns("main", table)?;
+                let shared: Vec<String> = columns("snapshot", table)?
+                    .into_iter()
+                    .filter(|c| main_columns.contains(c))
+                    .map(|c| format!("\"{}\"", c))
+                    .collect();
+                if shared.is_empty() {
+                    continue;
+                }
+                let list = shared.join(", ");
+                tx.execute(
+                    &format!(
+                        "INSERT INTO main.\"{table}\" ({list}) SELECT {list} FROM snapshot.\"{table}\"",
+                        table = table,
+                        list = list
+                    ),
+                    [],
+                )?;
+            }
+            tx.execute(
+                "DELETE FROM main.app_settings WHERE key IN (SELECT value FROM json_each(?1))",
+                [&machine_keys],
+            )?;
+            for (key, value) in &machine_settings {
+                tx.execute(
+                    "INSERT INTO main.app_settings (key, value) VALUES (?1, ?2)",
+                    [key, value],
+                )?;
+            }
+            tx.commit()
+        })();
+
+        let _ = conn.execute_batch("DETACH DATABASE snapshot;");
+        drop(conn);
+        self.invalidate_provider_settings_cache();
+        self.forget_last_written(None);
+        result.map_err(|e| format!("Failed to restore DB snapshot: {}", e))
+    }
+
+    /// 將 `recorded_at` 介於 (`after_ts`, `until_ts`] 的價格紀錄寫成只含 `price_history` 的封存檔，回傳筆數。
+    pub fn write_history_archive(&self, path: &Path, after_ts: i64, until_ts: i64) -> Result<usize, String> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("ATTACH DATABASE ?1 AS archive", [path.to_string_lossy()])
+            .map_err(|e| format!("Failed to create history archive: {}", e))?;
+        let result = conn
+            .execute(
+                "CREATE TABLE archive.price_history AS
+                 SELECT * FROM main.price_history WHERE recorded_at > ?1 AND recorded_at <= ?2",
+                [after_ts, until_ts],
+            )
+            .and_then(|_| conn.query_row("SELECT COUNT(*) FROM archive.price_history", [], |row| row.get(0)));
+        let _ = conn.execute_batch("DETACH DATABASE archive;");
+        result.map_err(|e| format!("Failed to write history archive: {}", e))
+    }
+
+    /// 匯入封存檔中目前沒有的價格紀錄（同一訂閱、同一時間視為重複），略過已不存在的訂閱。
+    pub fn import_history_archive(&self, path: &Path) -> Result<usize, String> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("ATTACH DATABASE ?1 AS archive", [path.to_string_lossy()])
+            .map_err(|e| format!("Failed to open history archive: {}", e))?;
+
+        let result = (|| -> rusqlite::Result<usize> {
+            let columns = |schema: &str| -> rusqlite::Result<Vec<String>> {
+                let mut stmt = conn.prepare("SELECT name FROM pragma_table_info('price_history', ?1)")?;
+                let rows = stmt.query_map([schema], |row| row.get(0))?;
+                rows.collect()
+            };
+            let main_columns = columns("main")?;
+            let shared: Vec<String> = columns("archive")?
+                .into_iter()
+                .filter(|c| c != "id" && main_columns.contains(c))
+                .map(|c| format!("\"{}\"", c))
+                .collect();
+            let list = shared.join(", ");
+            conn.execute(
+                &format!(
+                    "INSERT INTO main.price_history ({list})
+                     SELECT {list} FROM archive.price_history a
+                     WHERE a.subscription_id IN (SELECT id FROM main.subscriptions)
+                       AND NOT EXISTS (
+                           SELECT 1 FROM main.price_history p
+                           WHERE p.subscription_id = a.subscription_id AND p.recorded_at = a.recorded_at
+                       )",
+                    list = list
+                ),
+                [],
+            )
+        })();
+
+        let _ = conn.execute_batch("DETACH DATABASE archive;");
+        drop(conn);
+        self.forget_last_written(None);
+        result.map_err(|e| format!("Failed to import history archive: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    #[test]
+    fn snapshot_roundtrip_replaces_current_data() {
+        let db = DbPool::open(&PathBuf::from(":memory:")).unwrap();
+        db.add_subscription("asset", "BTC/USDT", None, "binance", "crypto", None, None, None)
+            .unwrap();
+        db.set_setting("theme", "dark").unwrap();
+        db.set_setting("kiosk_token", "old-token").unwrap();
+
+        let path = std::env::temp_dir().join(format!("sb-snapshot-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        db.write_snapshot(&path).unwrap();
+
+        db.add_subscription("asset", "ETH/USDT", None, "binance", "crypto", None, None, None)
+            .unwrap();
+        db.set_setting("theme", "light").unwrap();
+        db.set_setting("kiosk_token", "new-token").unwrap();
+
+        db.restore_snapshot(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        let symbols: Vec<String> = db.list_all_subscriptions().unwrap().into_iter().map(|s| s.symbol).collect();
+        assert_eq!(symbols, ["BTC/USDT"]);
+        assert_eq!(db.get_setting("theme").unwrap().as_deref(), Some("dark"));
+        // 本機專屬的 settings 不被快照覆寫
+        assert_eq!(db.get_setting("kiosk_token").unwrap().as_deref(), Some("new-token"));
+    }
+
+    #[test]
+    fn history_archive_restores_cleaned_up_records() {
+        let db = DbPool::open(&PathBuf::from(":memory:")).unwrap();
+        let id = db
+            .add_subscription("asset", "BTC/USDT", None, "binance", "crypto", None, None, None)
+            .unwrap();
+        db.insert_price_history_for_test(id, "binance", &[(1.0, None, None, 100), (2.0, None, None, 200), (3.0, None, None, 300)])
+            .unwrap();
+
+        let path = std::env::temp_dir().join(format!("sb-history-archive-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(db.write_history_archive(&path, 100, 300).unwrap(), 2);
+
+        db.cleanup_history(250).unwrap();
+        assert_eq!(db.import_history_archive(&path).unwrap(), 1);
+        assert_eq!(db.import_history_archive(&path).unwrap(), 0);
+        let _ = std::fs::remove_file(&path);
+
+        let count: i64 = db
+            .conn
+            .lock()
+            .unwrap()
+            .query_row("SELECT COUNT(*) FROM price_history WHERE subscription_id = ?1", [id], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 2);
+    }
+}
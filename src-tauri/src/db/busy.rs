@@ -0,0 +1,208 @@
+//! SQLITE_BUSY / SQLITE_LOCKED 處理 — 其他連線（headless server、外部工具、備份還原）持有寫入鎖時，
+//! `busy_timeout` 會先在 SQLite 內等待；但 WAL 下 deferred transaction 升級為寫入、或讀取快照過舊時
+//! SQLite 會立即回傳 busy 以避免死結，這類錯誤由寫入路徑以 [`DbPool::begin_write`] /
+//! [`DbPool::write_retry`] 退避重試；每次重試前先釋放 `DbPool.conn` 的鎖，等待期間其他 DB 使用者照常進行。
+//!
+//! 重試後仍 busy 時回傳 [`DbError::Busy`]（帶在地化的 `Msg::DbBusy`），API 以 503 `db_busy`、
+//! command 以 `db_busy` code 回應，讓使用者看到「資料庫忙碌」而不是原始的 `database is locked`。
+//! 呼叫端依型別判斷 busy，不比對訊息文字（訊息隨目前語言而變）。
+
+use std::fmt;
+use std::ops::Deref;
+use std::sync::MutexGuard;
+use std::time::Duration;
+
+use rusqlite::{Connection, ErrorCode};
+
+use super::DbPool;
+use crate::i18n::{LocalizedError, Msg};
+
+/// 每次重試前的等待時間
+const RETRY_DELAYS_MS: [u64; 3] = [50, 200, 500];
+
+pub fn is_busy(e: &rusqlite::Error) -> bool {
+    matches!(
+        e.sqlite_error_code(),
+        Some(ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked)
+    )
+}
+
+/// 執行 `op`；回傳 busy 時依 `RETRY_DELAYS_MS` 退避重試（`op` 內取得的鎖在等待前已釋放）
+pub fn retry_busy<T>(mut op: impl FnMut() -> rusqlite::Result<T>) -> rusqlite::Result<T> {
+    for delay in RETRY_DELAYS_MS {
+        match op() {
+            Err(e) if is_busy(&e) => {
+                tracing::debug!("[DB] Database busy, retrying in {}ms", delay);
+                std::thread::sleep(Duration::from_millis(delay));
+            }
+            result => return result,
+        }
+    }
+    op()
+}
+
+/// 持有連線鎖的寫入 transaction；未 `commit` 就 drop 時 rollback
+pub struct WriteTx<'a> {
+    conn: MutexGuard<'a, Connection>,
+    committed: bool,
+}
+
+impl WriteTx<'_> {
+    pub fn commit(mut self) -> rusqlite::Result<()> {
+        self.conn.execute_batch("COMMIT")?;
+        self.committed = true;
+        Ok(())
+    }
+}
+
+impl Deref for WriteTx<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        &self.conn
+    }
+}
+
+impl Drop for WriteTx<'_> {
+    fn drop(&mut self) {
+        if !self.committed {
+            let _ = self.conn.execute_batch("ROLLBACK");
+        }
+    }
+}
+
+impl DbPool {
+    /// 以 IMMEDIATE 開始寫入 transaction：一開始就取得寫入鎖（由 `busy_timeout` 等待），
+    /// 不會在 transaction 中途升級寫入時被立即回傳 busy
+    pub(super) fn begin_write(&self) -> rusqlite::Result<WriteTx<'_>> {
+        retry_busy(|| {
+            let conn = self.conn.lock().unwrap();
+            conn.execute_batch("BEGIN IMMEDIATE")?;
+            Ok(WriteTx { conn, committed: false })
+        })
+    }
+
+    /// 執行單一寫入（自動 commit）；busy 時釋放連線鎖後退避重試
+    pub(super) fn write_retry<T>(&self, mut op: impl FnMut(&Connection) -> rusqlite::Result<T>) -> rusqlite::Result<T> {
+        retry_busy(|| op(&self.conn.lock().unwrap()))
+    }
+}
+
+/// 會重試 busy 的寫入路徑的錯誤
+#[derive(Debug, Clone, PartialEq)]
+pub enum DbError {
+    /// 重試後仍 busy
+    Busy(LocalizedError),
+    Other(String),
+}
+
+pub type DbResult<T> = Result<T, DbError>;
+
+impl fmt::Display for DbError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DbError::Busy(e) => e.fmt(f),
+            DbError::Other(message) => f.write_str(message),
+        }
+    }
+}
+
+impl From<String> for DbError {
+    fn from(message: String) -> Self {
+        DbError::Other(message)
+    }
+}
+
+impl From<&str> for DbError {
+    fn from(message: &str) -> Self {
+        DbError::Other(message.to_string())
+    }
+}
+
+/// 沿用 `Result<_, String>` 的核心模組以 `?` 轉回訊息
+impl From<DbError> for String {
+    fn from(e: DbError) -> Self {
+        e.to_string()
+    }
+}
+
+/// 統一的 DB 錯誤：busy 轉成 [`DbError::Busy`]，其他錯誤加上 `context` 前綴
+pub fn db_error(context: &str, e: rusqlite::Error) -> DbError {
+    if is_busy(&e) {
+        tracing::warn!("[DB] {} failed after retries: {}", context, e);
+        DbError::Busy(LocalizedError::new(Msg::DbBusy, &[&RETRY_DELAYS_MS.len()]))
+    } else {
+        DbError::Other(format!("{}: {}", context, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::ffi;
+
+    fn sqlite_error(code: i32) -> rusqlite::Error {
+        rusqlite::Error::SqliteFailure(ffi::Error::new(code), None)
+    }
+
+    #[test]
+    fn retries_busy_until_success_or_limit() {
+        let mut calls = 0;
+        let result = retry_busy(|| {
+            calls += 1;
+            if calls < 3 { Err(sqlite_error(ffi::SQLITE_BUSY)) } else { Ok(calls) }
+        });
+        assert_eq!(result.unwrap(), 3);
+
+        let mut calls = 0;
+        let err = retry_busy::<()>(|| {
+            calls += 1;
+            Err(sqlite_error(ffi::SQLITE_LOCKED))
+        })
+        .unwrap_err();
+        assert_eq!(calls, RETRY_DELAYS_MS.len() + 1);
+        assert!(matches!(db_error("Failed to write", err), DbError::Busy(e) if e.key == Msg::DbBusy));
+
+        // 非 busy 錯誤不重試
+        let mut calls = 0;
+        let err = retry_busy::<()>(|| {
+            calls += 1;
+            Err(sqlite_error(ffi::SQLITE_CONSTRAINT))
+        })
+        .unwrap_err();
+        assert_eq!(calls, 1);
+        assert!(matches!(db_error("Failed to write", err), DbError::Other(m) if m.starts_with("Failed to write: ")));
+    }
+
+    #[test]
+    fn write_tx_rolls_back_unless_committed_and_releases_the_connection() {
+        let db = DbPool::open(&std::path::PathBuf::from(":memory:")).unwrap();
+        let count = |db: &DbPool| -> i64 {
+            db.conn
+                .lock()
+                .unwrap()
+                .query_row("SELECT COUNT(*) FROM app_settings WHERE key = 'busy_test'", [], |r| r.get(0))
+                .unwrap()
+        };
+
+        let tx = db.begin_write().unwrap();
+        tx.execute("INSERT INTO app_settings (key, value) VALUES ('busy_test', '1')", []).unwrap();
+        assert!(db.conn.try_lock().is_err());
+        drop(tx);
+        assert_eq!(count(&db), 0);
+
+        let tx = db.begin_write().unwrap();
+        tx.execute("INSERT INTO app_settings (key, value) VALUES ('busy_test', '1')", []).unwrap();
+        tx.commit().unwrap();
+        assert_eq!(count(&db), 1);
+
+        // 每次嘗試各自取得連線鎖，嘗試結束（退避等待前）即釋放
+        let mut calls = 0;
+        let result = db.write_retry(|_| {
+            calls += 1;
+            if calls == 1 { Err(sqlite_error(ffi::SQLITE_BUSY)) } else { Ok(db.conn.try_lock().is_err()) }
+        });
+        assert_eq!((result.unwrap(), calls), (true, 2));
+        assert!(db.conn.try_lock().is_ok());
+    }
+}
@@ -0,0 +1,135 @@
+use std::str::FromStr;
+
+use rusqlite::params;
+
+use super::busy::{db_error, DbResult};
+use super::DbPool;
+use crate::calendar::{EconomicEvent, Impact};
+
+impl DbPool {
+    // ── Economic Calendar ───────────────────────────────────────
+
+    /// 新增或更新經濟事件（以 source + country + event + event_time 為 key），回傳寫入筆數
+    pub fn upsert_economic_events(&self, events: &[EconomicEvent]) -> DbResult<usize> {
+        let now = chrono::Utc::now().timestamp();
+        let tx = self.begin_write().map_err(|e| db_error("Failed to save economic events", e))?;
+        {
+            let mut stmt = tx
+                .prepare(
+                    "INSERT INTO economic_events
+                        (source, event_time, country, event, impact, unit, previous, forecast, actual, updated_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+                     ON CONFLICT (source, country, event, event_time) DO UPDATE SET
+                        impact = excluded.impact, unit = excluded.unit, previous = excluded.previous,
+                        forecast = excluded.forecast, actual = excluded.actual, updated_at = excluded.updated_at",
+                )
+                .map_err(|e| format!("Failed to save economic events: {}", e))?;
+            for e in events {
+                stmt.execute(params![
+                    e.source,
+                    e.event_time,
+                    e.country,
+                    e.event,
+                    e.impact.as_str(),
+                    e.unit,
+                    e.previous,
+                    e.forecast,
+                    e.actual,
+                    now
+                ])
+                .map_err(|e| format!("Failed to save economic events: {}", e))?;
+            }
+        }
+        tx.commit()
+            .map_err(|e| format!("Failed to save economic events: {}", e))?;
+        Ok(events.len())
+    }
+
+    /// 查詢時間範圍內（Unix 秒，含端點）的經濟事件，依時間排序
+    pub fn list_economic_events(
+        &self,
+        from: i64,
+        to: i64,
+        country: Option<&str>,
+        min_impact: Impact,
+    ) -> Result<Vec<EconomicEvent>, String> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, source, event_time, country, event, impact, unit, previous, forecast, actual
+                 FROM economic_events
+                 WHERE event_time >= ?1 AND event_time <= ?2 AND (?3 IS NULL OR country = ?3)
+                 ORDER BY event_time, id",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(params![from, to, country.map(str::to_uppercase)], |row| {
+                Ok(EconomicEvent {
+                    id: row.get(0)?,
+                    source: row.get(1)?,
+                    event_time: row.get(2)?,
+                    country: row.get(3)?,
+                    event: row.get(4)?,
+                    impact: Impact::from_str(&row.get::<_, String>(5)?).unwrap_or(Impact::Low),
+                    unit: row.get(6)?,
+                    previous: row.get(7)?,
+                    forecast: row.get(8)?,
+                    actual: row.get(9)?,
+                })
+            })
+            .map_err(|e| e.to_string())?;
+        let events = rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())?;
+        Ok(events.into_iter().filter(|e| e.impact >= min_impact).collect())
+    }
+
+    /// 刪除指定時間（Unix 秒）之前的經濟事件
+    pub fn delete_economic_events_before(&self, before: i64) -> Result<usize, String> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM economic_events WHERE event_time < ?1", [before])
+            .map_err(|e| format!("Failed to clean up economic events: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn event(name: &str, event_time: i64, impact: Impact, actual: Option<f64>) -> EconomicEvent {
+        EconomicEvent {
+            id: 0,
+            source: "fmp".to_string(),
+            event_time,
+            country: "US".to_string(),
+            event: name.to_string(),
+            impact,
+            unit: Some("%".to_string()),
+            previous: Some(3.2),
+            forecast: Some(3.4),
+            actual,
+        }
+    }
+
+    #[test]
+    fn events_upsert_and_filter() {
+        let db = DbPool::open(&PathBuf::from(":memory:")).unwrap();
+        db.upsert_economic_events(&[
+            event("CPI YoY", 2_000, Impact::High, None),
+            event("Building Permits", 1_000, Impact::Medium, None),
+        ])
+        .unwrap();
+        // 公布後重新抓取只更新數值
+        db.upsert_economic_events(&[event("CPI YoY", 2_000, Impact::High, Some(3.5))]).unwrap();
+
+        let all = db.list_economic_events(0, 10_000, None, Impact::Low).unwrap();
+        assert_eq!(all.iter().map(|e| e.event.as_str()).collect::<Vec<_>>(), ["Building Permits", "CPI YoY"]);
+        assert_eq!(all[1].actual, Some(3.5));
+
+        let high = db.list_economic_events(0, 10_000, Some("us"), Impact::High).unwrap();
+        assert_eq!(high.len(), 1);
+        assert!(db.list_economic_events(0, 10_000, Some("EU"), Impact::Low).unwrap().is_empty());
+
+        assert_eq!(db.delete_economic_events_before(1_500).unwrap(), 1);
+        assert_eq!(db.list_economic_events(0, 10_000, None, Impact::Low).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,127 @@
+use rusqlite::params;
+
+use super::busy::{db_error, DbResult};
+use super::DbPool;
+use crate::candles::Candle;
+
+impl DbPool {
+    // ── Price Candles ───────────────────────────────────────────
+
+    /// 寫入收盤的 K 線（同一 provider + symbol + 分鐘已存在時覆寫）
+    pub fn insert_candles(&self, candles: &[Candle]) -> DbResult<()> {
+        let tx = self.begin_write().map_err(|e| db_error("Failed to save candles", e))?;
+        {
+            let mut stmt = tx
+                .prepare(
+                    "INSERT OR REPLACE INTO price_candles
+                     (provider_id, symbol, start_at, open, high, low, close, volume, ticks)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                )
+                .map_err(|e| format!("Failed to save candles: {}", e))?;
+            for c in candles {
+                stmt.execute(params![
+                    c.provider_id,
+                    c.symbol,
+                    c.start,
+                    c.open,
+                    c.high,
+                    c.low,
+                    c.close,
+                    c.volume,
+                    c.ticks
+                ])
+                .map_err(|e| format!("Failed to save candles: {}", e))?;
+            }
+        }
+        tx.commit().map_err(|e| db_error("Failed to save candles", e))
+    }
+
+    /// 讀取 `[from, to]`（Unix 秒，含）內最近的 `limit` 根 K 線，依時間排序
+    pub fn get_candles(
+        &self,
+        provider_id: &str,
+        symbol: &str,
+        from: Option<i64>,
+        to: Option<i64>,
+        limit: usize,
+    ) -> Result<Vec<Candle>, String> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(
+                "SELECT start_at, open, high, low, close, volume, ticks FROM price_candles
+                 WHERE provider_id = ?1 AND symbol = ?2 AND start_at >= ?3 AND start_at <= ?4
+                 ORDER BY start_at DESC LIMIT ?5",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(
+                params![
+                    provider_id,
+                    symbol,
+                    from.unwrap_or(i64::MIN),
+                    to.unwrap_or(i64::MAX),
+                    limit as i64
+                ],
+                |row| {
+                    Ok(Candle {
+                        provider_id: provider_id.to_string(),
+                        symbol: symbol.to_string(),
+                        start: row.get(0)?,
+                        open: row.get(1)?,
+                        high: row.get(2)?,
+                        low: row.get(3)?,
+                        close: row.get(4)?,
+                        volume: row.get(5)?,
+                        ticks: row.get(6)?,
+                    })
+                },
+            )
+            .map_err(|e| e.to_string())?;
+        let mut candles = rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())?;
+        candles.reverse();
+        Ok(candles)
+    }
+
+    /// 刪除起點早於 `before_ts` 的 K 線，回傳刪除筆數
+    pub fn cleanup_candles(&self, before_ts: i64) -> DbResult<i64> {
+        let deleted = self
+            .write_retry(|conn| conn.execute("DELETE FROM price_candles WHERE start_at < ?1", [before_ts]))
+            .map_err(|e| db_error("Failed to clean up candles", e))?;
+        Ok(deleted as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn candles_upsert_query_and_cleanup() {
+        let db = DbPool::open(&PathBuf::from(":memory:")).unwrap();
+        let candle = |start: i64, close: f64| Candle {
+            provider_id: "binance".to_string(),
+            symbol: "BTCUSDT".to_string(),
+            start,
+            open: 100.0,
+            high: 110.0,
+            low: 90.0,
+            close,
+            volume: Some(1.5),
+            ticks: 3,
+        };
+        db.insert_candles(&[candle(60, 101.0), candle(120, 102.0), candle(180, 103.0)]).unwrap();
+        db.insert_candles(&[candle(180, 104.0)]).unwrap();
+
+        let all = db.get_candles("binance", "BTCUSDT", None, None, 10).unwrap();
+        assert_eq!(all.iter().map(|c| (c.start, c.close)).collect::<Vec<_>>(), vec![(60, 101.0), (120, 102.0), (180, 104.0)]);
+        assert_eq!(all[0], candle(60, 101.0));
+        // limit 取最近的 K 線
+        let latest = db.get_candles("binance", "BTCUSDT", Some(60), Some(180), 2).unwrap();
+        assert_eq!(latest.iter().map(|c| c.start).collect::<Vec<_>>(), vec![120, 180]);
+        assert!(db.get_candles("bybit", "BTCUSDT", None, None, 10).unwrap().is_empty());
+
+        assert_eq!(db.cleanup_candles(180).unwrap(), 2);
+        assert_eq!(db.get_candles("binance", "BTCUSDT", None, None, 10).unwrap().len(), 1);
+    }
+}
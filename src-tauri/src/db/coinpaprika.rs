@@ -0,0 +1,85 @@
+use rusqlite::params;
+
+use super::busy::{db_error, DbResult};
+use super::{CoinPaprikaCoin, DbPool};
+
+/// 對照表最後更新時間（unix 秒）的 app setting key
+const REFRESHED_AT_KEY: &str = "coinpaprika_coins_refreshed_at";
+
+impl DbPool {
+    // ── CoinPaprika Coin List ───────────────────────────────────
+
+    /// 整批取代 CoinPaprika coin 清單並記錄更新時間
+    pub fn save_coinpaprika_coins(&self, coins: &[CoinPaprikaCoin], refreshed_at: i64) -> DbResult<()> {
+        {
+            let tx = self.begin_write().map_err(|e| db_error("Failed to save CoinPaprika coins", e))?;
+            tx.execute("DELETE FROM coinpaprika_coins", [])
+                .map_err(|e| format!("Failed to save CoinPaprika coins: {}", e))?;
+            {
+                let mut stmt = tx
+                    .prepare(
+                        "INSERT OR REPLACE INTO coinpaprika_coins (id, symbol, name, rank)
+                         VALUES (?1, ?2, ?3, ?4)",
+                    )
+                    .map_err(|e| format!("Failed to save CoinPaprika coins: {}", e))?;
+                for coin in coins {
+                    stmt.execute(params![coin.id, coin.symbol, coin.name, coin.rank])
+                        .map_err(|e| format!("Failed to save CoinPaprika coins: {}", e))?;
+                }
+            }
+            tx.commit()
+                .map_err(|e| db_error("Failed to save CoinPaprika coins", e))?;
+        }
+        Ok(self.set_setting(REFRESHED_AT_KEY, &refreshed_at.to_string())?)
+    }
+
+    /// 讀取 CoinPaprika coin 清單與其更新時間（從未更新過時為 None）
+    pub fn load_coinpaprika_coins(&self) -> Result<(Vec<CoinPaprikaCoin>, Option<i64>), String> {
+        let refreshed_at = self
+            .get_setting(REFRESHED_AT_KEY)?
+            .and_then(|v| v.parse().ok());
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT id, symbol, name, rank FROM coinpaprika_coins")
+            .map_err(|e| e.to_string())?;
+        let coins = stmt
+            .query_map([], |row| {
+                Ok(CoinPaprikaCoin {
+                    id: row.get(0)?,
+                    symbol: row.get(1)?,
+                    name: row.get(2)?,
+                    rank: row.get(3)?,
+                })
+            })
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        Ok((coins, refreshed_at))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn coin_list_round_trips_with_refresh_time() {
+        let db = DbPool::open(&PathBuf::from(":memory:")).unwrap();
+        assert_eq!(db.load_coinpaprika_coins().unwrap(), (vec![], None));
+
+        let coin = |id: &str, rank: Option<i64>| CoinPaprikaCoin {
+            id: id.to_string(),
+            symbol: "BTC".to_string(),
+            name: id.to_string(),
+            rank,
+        };
+        db.save_coinpaprika_coins(&[coin("btc-bitcoin", Some(1)), coin("btc-fake", None)], 100)
+            .unwrap();
+        db.save_coinpaprika_coins(&[coin("btc-bitcoin", Some(1))], 200).unwrap();
+
+        let (coins, at) = db.load_coinpaprika_coins().unwrap();
+        assert_eq!(coins, vec![coin("btc-bitcoin", Some(1))]);
+        assert_eq!(at, Some(200));
+    }
+}
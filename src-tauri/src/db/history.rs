@@ -1,95 +1,118 @@
+use std::collections::{HashMap, HashSet};
+
 use chrono::Timelike;
-use rusqlite::params;
+use rusqlite::{params, OptionalExtension};
 
-use super::schema::{PriceHistoryRow, PriceRecord, HistoryStats};
+use super::busy::{db_error, DbResult};
+use super::schema::{PriceHistoryRow, PriceRecord, RecordTargets, HistoryStats, ReplayFrame};
 use super::DbPool;
+use crate::record_health::{self, RecordError, RecordErrorKind};
+use crate::telemetry::{Span, SpanKind};
 
 impl DbPool {
     // ── Price History ───────────────────────────────────────────
 
+    /// 寫入一批價格紀錄；只寫入 `targets` 中（已由 polling 解析、開啟紀錄）且在紀錄時段內的 symbol。
+    /// 回傳寫入失敗的紀錄（已累計到 `record_health`），由呼叫端送出 `record-error` 事件
     pub fn write_price_history(
         &self,
         provider_id: &str,
         data: &[PriceRecord],
-    ) {
-        let conn = self.conn.lock().unwrap();
+        targets: &RecordTargets,
+    ) -> Vec<RecordError> {
+        let mut span = Span::root("db.write_price_history", SpanKind::Internal);
+        span.set_str("provider.id", provider_id);
+        span.set_int("records", data.len() as i64);
         let now = chrono::Utc::now().timestamp();
         let local_hour = chrono::Local::now().hour();
+        let mut written = 0;
+        let mut failures = Vec::new();
 
-        for (symbol, price, change_pct, volume, pre_price, post_price) in data {
-            // 找到訂閱 ID 和紀錄設定
-            let sub_row: Option<(i64, Option<i64>, Option<i64>)> = conn
-                .prepare_cached("SELECT id, record_from_hour, record_to_hour FROM subscriptions WHERE symbol = ?1 AND selected_provider_id = ?2")
-                .ok()
-                .and_then(|mut stmt| {
-                    stmt.query_row(params![symbol, provider_id], |row| {
-                        Ok((row.get(0)?, row.get(1)?, row.get(2)?))
-                    })
-                    .ok()
-                });
-            let (sub_id, sub_from, sub_to) = match sub_row {
-                Some(r) => r,
-                None => continue,
+        for (symbol, price, change_pct, volume, pre_price, post_price, liquidity_usd, fdv, open_interest, long_short_ratio, currency) in data {
+            let Some(target) = targets.get(symbol) else {
+                continue;
             };
-
-            // 檢查是否啟用紀錄
-            let record_enabled: bool = conn
-                .query_row(
-                    "SELECT record_enabled FROM subscriptions WHERE id = ?1",
-                    [sub_id],
-                    |row| row.get::<_, i64>(0),
-                )
-                .map(|v| v != 0)
-                .unwrap_or(false);
-            if !record_enabled {
+            if !target.in_window(local_hour) {
                 continue;
             }
+            let sub_id = target.subscription_id;
 
-            // 紀錄時段檢查
-            let (from_h, to_h) = if let (Some(from), Some(to)) = (sub_from, sub_to) {
-                (from as u32, to as u32)
+            // 流動性 / FDV 只對 DEX 訂閱有意義
+            let (liquidity_usd, fdv) = if target.is_dex {
+                (*liquidity_usd, *fdv)
             } else {
-                let prov_hours: Option<(Option<i64>, Option<i64>)> = conn
-                    .prepare_cached("SELECT record_from_hour, record_to_hour FROM provider_settings WHERE provider_id = ?1")
-                    .ok()
-                    .and_then(|mut stmt| {
-                        stmt.query_row([provider_id], |row| Ok((row.get(0)?, row.get(1)?)))
-                            .ok()
-                    });
-                match prov_hours {
-                    Some((Some(pf), Some(pt))) => (pf as u32, pt as u32),
-                    _ => (0, 24),
-                }
+                (None, None)
             };
-
-            if from_h != 0 || to_h != 24 {
-                let in_window = if from_h <= to_h {
-                    local_hour >= from_h && local_hour < to_h
-                } else {
-                    local_hour >= from_h || local_hour < to_h
-                };
-                if !in_window {
-                    continue;
+            let currency = Some(currency.trim()).filter(|c| !c.is_empty());
+            // 連線鎖逐筆取得（順序與其他路徑相同：conn → last_written），busy 退避時不卡住其他 DB 使用者
+            let result = self.write_retry(|conn| {
+                let mut last_written = self.last_written.lock().unwrap();
+                // 去重：距上次寫入未滿 dedup_secs 秒則略過；上次寫入時間只在首次遇到此訂閱時查詢
+                if target.dedup_secs > 0 {
+                    let last = match last_written.get(&sub_id) {
+                        Some(last) => Some(*last),
+                        None => {
+                            let last: Option<i64> = conn
+                                .prepare_cached("SELECT MAX(recorded_at) FROM price_history WHERE subscription_id = ?1")
+                                .and_then(|mut stmt| stmt.query_row([sub_id], |row| row.get(0)))
+                                .ok()
+                                .flatten();
+                            if let Some(last) = last {
+                                last_written.insert(sub_id, last);
+                            }
+                            last
+                        }
+                    };
+                    if last.is_some_and(|last| now - last < target.dedup_secs) {
+                        return Ok(false);
+                    }
+                }
+                conn.execute(
+                    "INSERT INTO price_history (subscription_id, provider_id, price, change_pct, volume, pre_price, post_price, recorded_at, liquidity_usd, fdv, open_interest, long_short_ratio, currency) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+                    params![sub_id, provider_id, price, change_pct, volume, pre_price, post_price, now, liquidity_usd, fdv, open_interest, long_short_ratio, currency],
+                )?;
+                last_written.insert(sub_id, now);
+                Ok(true)
+            });
+            match result {
+                Ok(false) => {}
+                Ok(true) => written += 1,
+                Err(e) => {
+                    let error = db_error("Failed to record price", e).to_string();
+                    span.set_error(&error);
+                    tracing::warn!("[History] {}:{} {}", provider_id, symbol, error);
+                    let failure = RecordError {
+                        subscription_id: sub_id,
+                        provider_id: provider_id.to_string(),
+                        symbol: symbol.clone(),
+                        kind: RecordErrorKind::WriteFailed,
+                        error,
+                        occurred_at: now,
+                    };
+                    record_health::record_failure(&failure);
+                    failures.push(failure);
                 }
             }
-
-            // 5 秒去重
-            let recent: bool = conn
-                .prepare_cached("SELECT 1 FROM price_history WHERE subscription_id = ?1 AND recorded_at > ?2 LIMIT 1")
-                .ok()
-                .and_then(|mut stmt| {
-                    stmt.query_row(params![sub_id, now - 5], |_| Ok(true)).ok()
-                })
-                .unwrap_or(false);
-            if recent {
-                continue;
-            }
-
-            let _ = conn.execute(
-                "INSERT INTO price_history (subscription_id, provider_id, price, change_pct, volume, pre_price, post_price, recorded_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-                params![sub_id, provider_id, price, change_pct, volume, pre_price, post_price, now],
-            );
         }
+        span.set_int("written", written);
+        failures
+    }
+
+    /// 開啟紀錄的訂閱最後一筆紀錄時間（沒有紀錄的訂閱不在結果中）
+    pub fn last_recorded_at(&self) -> Result<HashMap<i64, i64>, String> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(
+                "SELECT s.id, MAX(h.recorded_at) FROM subscriptions s \
+                 JOIN price_history h ON h.subscription_id = s.id \
+                 WHERE s.record_enabled = 1 GROUP BY s.id",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| e.to_string())?;
+        rows.collect::<Result<HashMap<_, _>, _>>()
+            .map_err(|e| e.to_string())
     }
 
     pub fn get_price_history(
@@ -100,7 +123,7 @@ impl DbPool {
         limit: i64,
     ) -> Result<Vec<PriceHistoryRow>, String> {
         let conn = self.conn.lock().unwrap();
-        let mut sql = "SELECT id, subscription_id, provider_id, price, change_pct, volume, pre_price, post_price, recorded_at FROM price_history WHERE subscription_id = ?1".to_string();
+        let mut sql = "SELECT id, subscription_id, provider_id, price, change_pct, volume, pre_price, post_price, recorded_at, liquidity_usd, fdv, open_interest, long_short_ratio, currency FROM price_history WHERE subscription_id = ?1".to_string();
         let mut p: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(subscription_id)];
         if let Some(f) = from {
             p.push(Box::new(f));
@@ -128,6 +151,42 @@ impl DbPool {
                     pre_price: row.get(6)?,
                     post_price: row.get(7)?,
                     recorded_at: row.get(8)?,
+                    liquidity_usd: row.get(9)?,
+                    fdv: row.get(10)?,
+                    open_interest: row.get(11)?,
+                    long_short_ratio: row.get(12)?,
+                    currency: row.get(13)?,
+                })
+            })
+            .map_err(|e| e.to_string())?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())
+    }
+
+    /// 讀取 `[from, to]` 區間內所有紀錄（依時間排序），供 replay provider 使用。
+    /// 排除 replay 自己寫回的紀錄，避免回放內容自我循環。
+    /// 區間長度由呼叫端限制（見 `ReplayConfig::validate`）。
+    pub fn load_replay_frames(&self, from: i64, to: i64) -> Result<Vec<ReplayFrame>, String> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(
+                "SELECT h.provider_id, s.symbol, h.price, h.change_pct, h.volume, h.pre_price, h.post_price, h.recorded_at
+                 FROM price_history h JOIN subscriptions s ON s.id = h.subscription_id
+                 WHERE h.recorded_at >= ?1 AND h.recorded_at <= ?2 AND h.provider_id != 'replay'
+                 ORDER BY h.recorded_at",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(params![from, to], |row| {
+                Ok(ReplayFrame {
+                    provider_id: row.get(0)?,
+                    symbol: row.get(1)?,
+                    price: row.get(2)?,
+                    change_pct: row.get(3)?,
+                    volume: row.get(4)?,
+                    pre_price: row.get(5)?,
+                    post_price: row.get(6)?,
+                    recorded_at: row.get(7)?,
                 })
             })
             .map_err(|e| e.to_string())?;
@@ -151,35 +210,171 @@ impl DbPool {
         .map_err(|e| e.to_string())
     }
 
-    pub fn cleanup_history(&self, before_ts: i64) -> Result<i64, String> {
+    /// 每個 UTC 日最後一筆紀錄的 (recorded_at, price)，依時間排序，供 streaks 統計使用
+    pub fn get_daily_closes(&self, subscription_id: i64) -> Result<Vec<(i64, f64)>, String> {
         let conn = self.conn.lock().unwrap();
-        let deleted = conn
-            .execute(
-                "DELETE FROM price_history WHERE recorded_at < ?1",
-                [before_ts],
+        // SQLite 對 MAX() 彙總時，裸欄位 price 取自 recorded_at 最大的那一列
+        let mut stmt = conn
+            .prepare(
+                "SELECT MAX(recorded_at), price FROM price_history
+                 WHERE subscription_id = ?1
+                 GROUP BY recorded_at / 86400
+                 ORDER BY 1",
             )
             .map_err(|e| e.to_string())?;
-        Ok(deleted as i64)
+        let rows = stmt
+            .query_map([subscription_id], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| e.to_string())?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())
     }
 
-    pub fn purge_all_history(&self) -> Result<i64, String> {
+    /// `from`（Unix 秒）之後的 (recorded_at, price, volume)，依時間排序，供 VWAP 計算使用
+    pub fn get_price_volume_since(&self, subscription_id: i64, from: i64) -> Result<Vec<(i64, f64, Option<f64>)>, String> {
         let conn = self.conn.lock().unwrap();
-        conn.execute("DELETE FROM price_history", [])
+        let mut stmt = conn
+            .prepare_cached(
+                "SELECT recorded_at, price, volume FROM price_history
+                 WHERE subscription_id = ?1 AND recorded_at >= ?2
+                 ORDER BY recorded_at",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(params![subscription_id, from], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
             .map_err(|e| e.to_string())?;
-        Ok(conn.changes() as i64)
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())
+    }
+
+    /// `before`（Unix 秒）之前、不早於 `not_before` 的最後一筆 (recorded_at, price)，供 TWAP 取得窗格起點的價格
+    pub fn get_last_price_before(
+        &self,
+        subscription_id: i64,
+        before: i64,
+        not_before: i64,
+    ) -> Result<Option<(i64, f64)>, String> {
+        let conn = self.conn.lock().unwrap();
+        conn.prepare_cached(
+            "SELECT recorded_at, price FROM price_history
+             WHERE subscription_id = ?1 AND recorded_at < ?2 AND recorded_at >= ?3
+             ORDER BY recorded_at DESC LIMIT 1",
+        )
+        .and_then(|mut stmt| {
+            stmt.query_row(params![subscription_id, before, not_before], |row| Ok((row.get(0)?, row.get(1)?)))
+                .optional()
+        })
+        .map_err(|e| e.to_string())
+    }
+
+    /// 最接近 `target`（Unix 秒）、相差不超過 `tolerance` 秒的紀錄價格，供多時間框漲跌使用
+    pub fn get_price_near(&self, subscription_id: i64, target: i64, tolerance: i64) -> Result<Option<f64>, String> {
+        let conn = self.conn.lock().unwrap();
+        // 前後各取一筆（走 subscription_id + recorded_at 索引），再取較接近者
+        let nearest = |sql: &str, bound: i64| -> Result<Option<(i64, f64)>, String> {
+            conn.prepare_cached(sql)
+                .and_then(|mut stmt| {
+                    stmt.query_row(params![subscription_id, target, bound], |row| Ok((row.get(0)?, row.get(1)?)))
+                        .optional()
+                })
+                .map_err(|e| e.to_string())
+        };
+        let before = nearest(
+            "SELECT recorded_at, price FROM price_history
+             WHERE subscription_id = ?1 AND recorded_at <= ?2 AND recorded_at >= ?3
+             ORDER BY recorded_at DESC LIMIT 1",
+            target - tolerance,
+        )?;
+        let after = nearest(
+            "SELECT recorded_at, price FROM price_history
+             WHERE subscription_id = ?1 AND recorded_at > ?2 AND recorded_at <= ?3
+             ORDER BY recorded_at LIMIT 1",
+            target + tolerance,
+        )?;
+        Ok(before
+            .into_iter()
+            .chain(after)
+            .min_by_key(|(ts, _)| (ts - target).abs())
+            .map(|(_, price)| price))
     }
 
-    pub fn delete_history_for_subscription(&self, subscription_id: i64) -> Result<i64, String> {
+    /// `[from, to]` 區間內已有紀錄的時間點，供 CSV 匯入去重
+    pub fn get_history_timestamps(&self, subscription_id: i64, from: i64, to: i64) -> Result<HashSet<i64>, String> {
         let conn = self.conn.lock().unwrap();
-        let deleted = conn
-            .execute(
-                "DELETE FROM price_history WHERE subscription_id = ?1",
-                [subscription_id],
+        let mut stmt = conn
+            .prepare(
+                "SELECT recorded_at FROM price_history
+                 WHERE subscription_id = ?1 AND recorded_at >= ?2 AND recorded_at <= ?3",
             )
             .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(params![subscription_id, from, to], |row| row.get(0))
+            .map_err(|e| e.to_string())?;
+        rows.collect::<Result<HashSet<_>, _>>()
+            .map_err(|e| e.to_string())
+    }
+
+    /// 在單一 transaction 中寫入匯入的 (recorded_at, price, volume)；已有相同時間紀錄者略過，回傳寫入筆數
+    pub fn insert_imported_history(
+        &self,
+        subscription_id: i64,
+        provider_id: &str,
+        records: &[(i64, f64, Option<f64>)],
+    ) -> DbResult<usize> {
+        let tx = self.begin_write().map_err(|e| db_error("Failed to import price history", e))?;
+        let mut inserted = 0;
+        {
+            let mut stmt = tx
+                .prepare(
+                    "INSERT INTO price_history (subscription_id, provider_id, price, volume, recorded_at)
+                     SELECT ?1, ?2, ?3, ?4, ?5
+                     WHERE NOT EXISTS (SELECT 1 FROM price_history WHERE subscription_id = ?1 AND recorded_at = ?5)",
+                )
+                .map_err(|e| format!("Failed to import price history: {}", e))?;
+            for (recorded_at, price, volume) in records {
+                inserted += stmt
+                    .execute(params![subscription_id, provider_id, price, volume, recorded_at])
+                    .map_err(|e| format!("Failed to import price history: {}", e))?;
+            }
+        }
+        tx.commit()
+            .map_err(|e| format!("Failed to import price history: {}", e))?;
+        Ok(inserted)
+    }
+
+    pub fn cleanup_history(&self, before_ts: i64) -> DbResult<i64> {
+        let deleted = self
+            .write_retry(|conn| conn.execute("DELETE FROM price_history WHERE recorded_at < ?1", [before_ts]))
+            .map_err(|e| db_error("Failed to clean up history", e))?;
+        Ok(deleted as i64)
+    }
+
+    pub fn purge_all_history(&self) -> DbResult<i64> {
+        let deleted = self
+            .write_retry(|conn| conn.execute("DELETE FROM price_history", []))
+            .map_err(|e| db_error("Failed to purge history", e))?;
+        self.forget_last_written(None);
         Ok(deleted as i64)
     }
 
+    pub fn delete_history_for_subscription(&self, subscription_id: i64) -> DbResult<i64> {
+        let deleted = self
+            .write_retry(|conn| conn.execute("DELETE FROM price_history WHERE subscription_id = ?1", [subscription_id]))
+            .map_err(|e| db_error("Failed to delete history", e))?;
+        self.forget_last_written(Some(subscription_id));
+        Ok(deleted as i64)
+    }
+
+    /// 清除去重用的最後寫入時間（None 為全部）；紀錄被刪除或搬移後呼叫，下次寫入時重新由 DB 載入
+    pub(crate) fn forget_last_written(&self, subscription_id: Option<i64>) {
+        let mut last_written = self.last_written.lock().unwrap();
+        match subscription_id {
+            Some(id) => {
+                last_written.remove(&id);
+            }
+            None => last_written.clear(),
+        }
+    }
+
     /// Test helper: insert price history records directly (bypasses record_enabled and dedup checks)
     #[cfg(test)]
     pub fn insert_price_history_for_test(
@@ -199,3 +394,115 @@ impl DbPool {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{DEFAULT_RECORD_DEDUP_SECS, MAX_RECORD_DEDUP_SECS};
+    use std::path::PathBuf;
+
+    #[test]
+    fn liquidity_is_recorded_only_for_dex_subscriptions() {
+        let db = DbPool::open(&PathBuf::from(":memory:")).unwrap();
+        let pool = db
+            .add_subscription("dex", "0xpool:0xa:0xb", None, "raydium", "crypto", Some("0xpool"), Some("0xa"), Some("0xb"))
+            .unwrap();
+        let coin = db
+            .add_subscription("asset", "BTC", None, "raydium", "crypto", None, None, None)
+            .unwrap();
+        db.toggle_record(pool, true).unwrap();
+        db.toggle_record(coin, true).unwrap();
+
+        db.write_price_history(
+            "raydium",
+            &[
+                ("0xpool:0xa:0xb".to_string(), 1.5, None, None, None, None, Some(2.0e6), Some(9.0e7), None, None, String::new()),
+                ("BTC".to_string(), 65000.0, None, None, None, None, Some(1.0), Some(1.0), Some(5.2e4), Some(1.8), "KRW".to_string()),
+            ],
+            &db.read_record_targets().unwrap()["raydium"],
+        );
+
+        let dex = db.get_price_history(pool, None, None, 10).unwrap();
+        assert_eq!((dex[0].liquidity_usd, dex[0].fdv), (Some(2.0e6), Some(9.0e7)));
+        let asset = db.get_price_history(coin, None, None, 10).unwrap();
+        assert_eq!((asset[0].liquidity_usd, asset[0].fdv), (None, None));
+        assert_eq!((asset[0].open_interest, asset[0].long_short_ratio), (Some(5.2e4), Some(1.8)));
+        assert_eq!(dex[0].open_interest, None);
+        assert_eq!((dex[0].currency.as_deref(), asset[0].currency.as_deref()), (None, Some("KRW")));
+    }
+
+    #[test]
+    fn failed_writes_are_reported_and_counted() {
+        let db = DbPool::open(&PathBuf::from(":memory:")).unwrap();
+        let kept = db
+            .add_subscription("asset", "BTC", None, "binance", "crypto", None, None, None)
+            .unwrap();
+        let removed = db
+            .add_subscription("asset", "ETH", None, "binance", "crypto", None, None, None)
+            .unwrap();
+        db.toggle_record(kept, true).unwrap();
+        db.toggle_record(removed, true).unwrap();
+        // polling 尚未重新載入時，紀錄目標仍指向已刪除的訂閱
+        let targets = db.read_record_targets().unwrap().remove("binance").unwrap();
+        db.remove_subscription(removed).unwrap();
+
+        let record = |symbol: &str| (symbol.to_string(), 1.0, None, None, None, None, None, None, None, None, String::new());
+        let failures = db.write_price_history("binance", &[record("BTC"), record("ETH")], &targets);
+        assert_eq!(failures.len(), 1);
+        assert_eq!((failures[0].subscription_id, failures[0].kind), (removed, RecordErrorKind::WriteFailed));
+        assert_eq!(record_health::health(removed).failures, 1);
+        assert_eq!(db.last_recorded_at().unwrap().keys().copied().collect::<Vec<_>>(), vec![kept]);
+    }
+
+    #[test]
+    fn dedup_window_is_per_subscription() {
+        let db = DbPool::open(&PathBuf::from(":memory:")).unwrap();
+        let add = |symbol: &str| {
+            let id = db.add_subscription("asset", symbol, None, "binance", "crypto", None, None, None).unwrap();
+            db.toggle_record(id, true).unwrap();
+            id
+        };
+        let (btc, eth, sol) = (add("BTC"), add("ETH"), add("SOL"));
+        db.set_record_dedup(eth, Some(0)).unwrap();
+        assert!(db.set_record_dedup(eth, Some(-1)).is_err());
+        assert!(db.set_record_dedup(eth, Some(MAX_RECORD_DEDUP_SECS + 1)).is_err());
+        assert!(db.set_record_dedup(999, None).is_err());
+        // 未經本程序寫入的既有紀錄也納入去重
+        let now = chrono::Utc::now().timestamp();
+        db.insert_price_history_for_test(sol, "binance", &[(1.0, None, None, now - 2)]).unwrap();
+
+        let targets = db.read_record_targets().unwrap().remove("binance").unwrap();
+        assert_eq!((targets["BTC"].dedup_secs, targets["ETH"].dedup_secs), (DEFAULT_RECORD_DEDUP_SECS, 0));
+        let record = |symbol: &str| (symbol.to_string(), 1.0, None, None, None, None, None, None, None, None, String::new());
+        let batch = [record("BTC"), record("ETH"), record("SOL")];
+        db.write_price_history("binance", &batch, &targets);
+        db.write_price_history("binance", &batch, &targets);
+        let count = |id| db.get_price_history(id, None, None, 10).unwrap().len();
+        assert_eq!((count(btc), count(eth), count(sol)), (1, 2, 1));
+
+        // 刪除紀錄後不再沿用快取的寫入時間
+        db.delete_history_for_subscription(btc).unwrap();
+        db.write_price_history("binance", &batch, &targets);
+        assert_eq!((count(btc), count(eth), count(sol)), (1, 3, 1));
+    }
+
+    #[test]
+    fn daily_closes_take_last_record_per_day() {
+        let db = DbPool::open(&PathBuf::from(":memory:")).unwrap();
+        let sub = db
+            .add_subscription("asset", "BTC", None, "binance", "crypto", None, None, None)
+            .unwrap();
+        db.insert_price_history_for_test(
+            sub,
+            "binance",
+            &[
+                (100.0, None, None, 3_600),
+                (105.0, None, None, 80_000),
+                (99.0, None, None, 86_400 + 10),
+                (101.0, None, None, 86_400 + 20),
+            ],
+        )
+        .unwrap();
+        assert_eq!(db.get_daily_closes(sub).unwrap(), vec![(80_000, 105.0), (86_420, 101.0)]);
+    }
+}
@@ -0,0 +1,104 @@
+use rusqlite::params;
+
+use super::schema::IconMapping;
+use super::DbPool;
+
+impl DbPool {
+    // ── Icon Mappings ───────────────────────────────────────────
+
+    /// 記錄 symbol 對應的 icon 檔名（symbol 不分大小寫）
+    pub fn upsert_icon_mapping(&self, symbol: &str, file_name: &str) -> Result<(), String> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO icons (symbol, file_name, updated_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT(symbol) DO UPDATE SET file_name = ?2, updated_at = ?3",
+            params![
+                symbol.trim().to_lowercase(),
+                file_name,
+                chrono::Utc::now().timestamp()
+            ],
+        )
+        .map_err(|e| format!("Failed to save icon mapping: {}", e))?;
+        Ok(())
+    }
+
+    /// 只在此檔名尚未有任何對照時新增（遷移既有檔案用，不覆蓋已知的 symbol）
+    pub fn insert_icon_mapping_if_unknown(&self, symbol: &str, file_name: &str) -> Result<bool, String> {
+        let conn = self.conn.lock().unwrap();
+        let inserted = conn
+            .execute(
+                "INSERT OR IGNORE INTO icons (symbol, file_name, updated_at)
+                 SELECT ?1, ?2, ?3 WHERE NOT EXISTS (SELECT 1 FROM icons WHERE file_name = ?2)",
+                params![
+                    symbol.trim().to_lowercase(),
+                    file_name,
+                    chrono::Utc::now().timestamp()
+                ],
+            )
+            .map_err(|e| format!("Failed to save icon mapping: {}", e))?;
+        Ok(inserted > 0)
+    }
+
+    pub fn delete_icon_mapping(&self, symbol: &str) -> Result<(), String> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM icons WHERE symbol = ?1",
+            [symbol.trim().to_lowercase()],
+        )
+        .map_err(|e| format!("Failed to delete icon mapping: {}", e))?;
+        Ok(())
+    }
+
+    pub fn clear_icon_mappings(&self) -> Result<(), String> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM icons", [])
+            .map_err(|e| format!("Failed to clear icon mappings: {}", e))?;
+        Ok(())
+    }
+
+    pub fn list_icon_mappings(&self) -> Result<Vec<IconMapping>, String> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT symbol, file_name, updated_at FROM icons ORDER BY symbol")
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(IconMapping {
+                    symbol: row.get(0)?,
+                    file_name: row.get(1)?,
+                    updated_at: row.get(2)?,
+                })
+            })
+            .map_err(|e| e.to_string())?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn mappings_are_case_insensitive_and_migration_does_not_overwrite() {
+        let db = DbPool::open(&PathBuf::from(":memory:")).unwrap();
+        db.upsert_icon_mapping("Pool:ABC", "pool_abc-1a2b3c4d.png").unwrap();
+        db.upsert_icon_mapping("pool:abc", "pool_abc-1a2b3c4d.svg").unwrap();
+
+        // 已知檔名 → 不再以檔名當 symbol 新增
+        assert!(!db
+            .insert_icon_mapping_if_unknown("pool_abc-1a2b3c4d", "pool_abc-1a2b3c4d.svg")
+            .unwrap());
+        assert!(db.insert_icon_mapping_if_unknown("btc", "btc.png").unwrap());
+
+        let mappings = db.list_icon_mappings().unwrap();
+        assert_eq!(mappings.len(), 2);
+        assert_eq!(mappings[1].symbol, "pool:abc");
+        assert_eq!(mappings[1].file_name, "pool_abc-1a2b3c4d.svg");
+
+        db.delete_icon_mapping("POOL:abc").unwrap();
+        assert_eq!(db.list_icon_mappings().unwrap().len(), 1);
+        db.clear_icon_mappings().unwrap();
+        assert!(db.list_icon_mappings().unwrap().is_empty());
+    }
+}
@@ -2,19 +2,38 @@
 ///
 /// 所有 SQLite 操作集中在此模組，前端不再直接操作 SQL。
 /// 使用 `Mutex<Connection>` 確保寫入操作序列化，搭配 WAL mode 允許並行讀取。
+/// `provider_settings` 讀取頻繁（polling reload、registry、DEX lookup），
+/// 因此在記憶體中快取，寫入時自動失效。
+mod app_config;
+mod backup;
+mod busy;
+mod calendar;
+mod candles;
+mod coingecko;
+mod coinpaprika;
 mod history;
+mod icons;
 mod notifications;
+mod price_cache;
 mod providers;
+mod quote_cache;
 mod schema;
 mod settings;
 mod subscriptions;
+mod token_metadata;
 mod views;
 
+pub use busy::{DbError, DbResult};
 pub use schema::*;
 
 use rusqlite::Connection;
+use std::collections::HashMap;
 use std::path::PathBuf;
-use std::sync::Mutex;
+use std::sync::atomic::AtomicU64;
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::i18n::{tr, Msg};
+use crate::secrets::{KeyringStore, SecretStore};
 
 // ── Schema ──────────────────────────────────────────────────────
 
@@ -32,7 +51,10 @@ CREATE TABLE IF NOT EXISTS provider_settings (
     refresh_interval INTEGER,
     connection_type  TEXT NOT NULL DEFAULT 'rest',
     record_from_hour INTEGER,
-    record_to_hour   INTEGER
+    record_to_hour   INTEGER,
+    proxy_url        TEXT,
+    timeout_ms       INTEGER,
+    extra_headers    TEXT
 );
 
 CREATE TABLE IF NOT EXISTS subscriptions (
@@ -49,6 +71,8 @@ CREATE TABLE IF NOT EXISTS subscriptions (
     record_enabled       INTEGER NOT NULL DEFAULT 0,
     record_from_hour     INTEGER,
     record_to_hour       INTEGER,
+    provider_params      TEXT,
+    record_dedup_secs    INTEGER,
     UNIQUE(symbol, selected_provider_id)
 );
 
@@ -78,12 +102,31 @@ CREATE TABLE IF NOT EXISTS price_history (
     pre_price       REAL,
     post_price      REAL,
     recorded_at     INTEGER NOT NULL,
+    liquidity_usd   REAL,
+    fdv             REAL,
+    open_interest   REAL,
+    long_short_ratio REAL,
+    currency        TEXT,
     FOREIGN KEY (subscription_id) REFERENCES subscriptions(id) ON DELETE CASCADE
 );
 
 CREATE INDEX IF NOT EXISTS idx_price_history_sub_time
     ON price_history (subscription_id, recorded_at);
 
+-- 訂閱變更紀錄（切換 provider / 改 symbol），圖表用來標註資料來源的切換點
+CREATE TABLE IF NOT EXISTS subscription_events (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    subscription_id INTEGER NOT NULL,
+    event_type      TEXT NOT NULL,
+    old_value       TEXT NOT NULL,
+    new_value       TEXT NOT NULL,
+    occurred_at     INTEGER NOT NULL,
+    FOREIGN KEY (subscription_id) REFERENCES subscriptions(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_subscription_events_sub_time
+    ON subscription_events (subscription_id, occurred_at);
+
 INSERT OR IGNORE INTO views (id, name, view_type, is_default) VALUES (1, 'All', 'asset', 1);
 INSERT OR IGNORE INTO views (id, name, view_type, is_default) VALUES (2, 'All', 'dex', 1);
 
@@ -141,18 +184,108 @@ CREATE INDEX IF NOT EXISTS idx_notification_history_rule_time
 
 CREATE INDEX IF NOT EXISTS idx_notification_rules_sub
     ON notification_rules (subscription_id);
+
+-- icon 檔名對照（symbol 小寫 → icons/ 下的實際檔名）
+CREATE TABLE IF NOT EXISTS icons (
+    symbol      TEXT PRIMARY KEY,
+    file_name   TEXT NOT NULL,
+    updated_at  INTEGER NOT NULL
+);
+
+-- CoinGecko symbol → ID 對照表來源（每週從 /coins/list 更新）
+CREATE TABLE IF NOT EXISTS coingecko_coins (
+    id              TEXT PRIMARY KEY,
+    symbol          TEXT NOT NULL,
+    name            TEXT NOT NULL,
+    market_cap_rank INTEGER
+);
+
+-- CoinPaprika symbol → ID 對照表來源（每週從 /v1/coins 更新）
+CREATE TABLE IF NOT EXISTS coinpaprika_coins (
+    id      TEXT PRIMARY KEY,
+    symbol  TEXT NOT NULL,
+    name    TEXT NOT NULL,
+    rank    INTEGER
+);
+
+-- 鏈上 token metadata（DEX 報價的 decimals / symbol 快取）
+CREATE TABLE IF NOT EXISTS token_metadata (
+    chain       TEXT NOT NULL,
+    address     TEXT NOT NULL,
+    symbol      TEXT,
+    decimals    INTEGER NOT NULL,
+    updated_at  INTEGER NOT NULL,
+    PRIMARY KEY (chain, address)
+);
+
+-- polling 快取快照（啟動時先顯示上次的價格，標記為 stale）
+CREATE TABLE IF NOT EXISTS price_cache (
+    cache_key   TEXT PRIMARY KEY,
+    data        TEXT NOT NULL,
+    saved_at    INTEGER NOT NULL
+);
+
+-- 呼叫額度極少的 provider 的逐 symbol 報價快取（重啟後沿用，不重複耗用額度）
+CREATE TABLE IF NOT EXISTS provider_quote_cache (
+    provider_id TEXT NOT NULL,
+    symbol      TEXT NOT NULL,
+    data        TEXT NOT NULL,
+    fetched_at  INTEGER NOT NULL,
+    PRIMARY KEY (provider_id, symbol)
+);
+
+-- 經濟日曆事件（FMP / Finnhub economic calendar，event_time 為 Unix 秒 UTC）
+CREATE TABLE IF NOT EXISTS economic_events (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    source      TEXT NOT NULL,
+    event_time  INTEGER NOT NULL,
+    country     TEXT NOT NULL,
+    event       TEXT NOT NULL,
+    impact      TEXT NOT NULL,
+    unit        TEXT,
+    previous    REAL,
+    forecast    REAL,
+    actual      REAL,
+    updated_at  INTEGER NOT NULL,
+    UNIQUE (source, country, event, event_time)
+);
+
+CREATE INDEX IF NOT EXISTS idx_economic_events_time
+    ON economic_events (event_time);
+
+-- WS 串流彙整的 1 分鐘 K 線（start_at 為分鐘起點 Unix 秒，只在開啟保存時寫入）
+CREATE TABLE IF NOT EXISTS price_candles (
+    provider_id TEXT NOT NULL,
+    symbol      TEXT NOT NULL,
+    start_at    INTEGER NOT NULL,
+    open        REAL NOT NULL,
+    high        REAL NOT NULL,
+    low         REAL NOT NULL,
+    close       REAL NOT NULL,
+    volume      REAL,
+    ticks       INTEGER NOT NULL,
+    PRIMARY KEY (provider_id, symbol, start_at)
+);
 "#;
 
 // ── DbPool ──────────────────────────────────────────────────────
 
 pub struct DbPool {
     pub(crate) conn: Mutex<Connection>,
+    /// provider_settings 的記憶體快取（None = 尚未載入或已失效）
+    pub(crate) provider_settings_cache: RwLock<Option<HashMap<String, ProviderSettingsRow>>>,
+    /// 每次快取失效時遞增；載入期間若有失效，載入結果不寫入快取
+    pub(crate) provider_settings_generation: AtomicU64,
+    /// keychain 後端啟用時 API key / secret 的實際存放處
+    pub(crate) secret_store: RwLock<Arc<dyn SecretStore>>,
+    /// 各訂閱最後寫入 price_history 的時間（Unix 秒），供去重判斷；首次寫入時由 DB 載入
+    pub(crate) last_written: Mutex<HashMap<i64, i64>>,
 }
 
 impl DbPool {
     pub fn open(path: &PathBuf) -> Result<Self, String> {
-        let conn = Connection::open(path).map_err(|e| format!("Failed to open DB: {}", e))?;
-        // 啟用 WAL mode + busy timeout，應對高併發
+        let conn = Connection::open(path).map_err(|e| tr(Msg::DbOpenFailed, &[&e]))?;
+        // 啟用 WAL mode + busy timeout，應對高併發（busy timeout 後仍 busy 的寫入見 `busy` 模組）
         conn.execute_batch(
             "PRAGMA journal_mode=WAL;
              PRAGMA busy_timeout=5000;
@@ -169,9 +302,31 @@ impl DbPool {
         let _ = conn.execute_batch(
             "ALTER TABLE notification_rules ADD COLUMN subscription_ids TEXT;",
         );
+        let _ = conn.execute_batch("ALTER TABLE provider_settings ADD COLUMN proxy_url TEXT;");
+        let _ = conn.execute_batch("ALTER TABLE provider_settings ADD COLUMN timeout_ms INTEGER;");
+        let _ = conn.execute_batch("ALTER TABLE provider_settings ADD COLUMN extra_headers TEXT;");
+        let _ = conn.execute_batch("ALTER TABLE subscriptions ADD COLUMN provider_params TEXT;");
+        let _ = conn.execute_batch("ALTER TABLE price_history ADD COLUMN liquidity_usd REAL;");
+        let _ = conn.execute_batch("ALTER TABLE price_history ADD COLUMN fdv REAL;");
+        let _ = conn.execute_batch("ALTER TABLE price_history ADD COLUMN open_interest REAL;");
+        let _ = conn.execute_batch("ALTER TABLE price_history ADD COLUMN long_short_ratio REAL;");
+        let _ = conn.execute_batch("ALTER TABLE price_history ADD COLUMN currency TEXT;");
+        let _ = conn.execute_batch("ALTER TABLE subscriptions ADD COLUMN record_dedup_secs INTEGER;");
+        // 既有明文 API key / secret 加密
+        match providers::encrypt_plaintext_secrets(&conn) {
+            Ok(0) => {}
+            Ok(n) => tracing::info!("[DB] Encrypted API keys for {} provider(s)", n),
+            Err(e) => tracing::warn!("[DB] Failed to encrypt existing API keys: {}", e),
+        }
+        // 主金鑰與既有密文不符時直接失敗，避免 API key 無聲消失
+        providers::check_secrets_key(&conn)?;
 
         Ok(Self {
             conn: Mutex::new(conn),
+            provider_settings_cache: RwLock::new(None),
+            provider_settings_generation: AtomicU64::new(0),
+            secret_store: RwLock::new(Arc::new(KeyringStore)),
+            last_written: Mutex::new(HashMap::new()),
         })
     }
 }
@@ -312,4 +467,227 @@ mod tests {
         assert!(db.list_notification_rules().unwrap().is_empty(), "notification_rules should be empty after reset");
         assert!(db.query_notification_history(None, None, None, None).unwrap().is_empty(), "notification_history should be empty after reset");
     }
+
+    /// Provider settings are served from the in-memory cache and refreshed
+    /// after writes invalidate it.
+    #[test]
+    fn provider_settings_cache_invalidated_on_upsert() {
+        let db = open_test_db();
+        assert!(db.get_provider_settings("binance").unwrap().is_none());
+
+        db.upsert_provider_settings("binance", Some("k1"), None, None, Some(5000), "rest", None, None, Some("socks5://127.0.0.1:1080"), Some(3000), Some(r#"{"X-Gateway-Key":"abc"}"#))
+            .unwrap();
+        let row = db.get_provider_settings("binance").unwrap().unwrap();
+        assert_eq!(row.api_key.as_deref(), Some("k1"));
+        assert_eq!(row.proxy_url.as_deref(), Some("socks5://127.0.0.1:1080"));
+        let http = crate::providers::HttpOptions::from(&row);
+        assert_eq!(http.timeout_ms, Some(3000));
+        assert_eq!(http.headers.get("X-Gateway-Key").map(String::as_str), Some("abc"));
+        assert!(db
+            .upsert_provider_settings("binance", None, None, None, None, "rest", None, None, None, None, Some("[1,2]"))
+            .is_err());
+        assert!(db.has_api_key("binance"));
+
+        db.upsert_provider_settings("binance", Some(""), None, None, Some(8000), "rest", None, None, None, None, None)
+            .unwrap();
+        assert!(!db.has_api_key("binance"));
+        let polling = db.read_polling_provider_settings().unwrap();
+        assert_eq!(polling.get("binance").and_then(|s| s.3), Some(8000));
+
+        db.reset_all_data().unwrap();
+        assert!(db.list_provider_settings().unwrap().is_empty());
+    }
+
+    /// Rows loaded before a concurrent write invalidates the cache are not
+    /// cached, so readers never see the pre-write settings afterwards.
+    #[test]
+    fn stale_provider_settings_not_cached_after_invalidation() {
+        let db = open_test_db();
+        db.upsert_provider_settings("binance", Some("old"), None, None, None, "rest", None, None, None, None, None)
+            .unwrap();
+        let generation = db.provider_settings_generation.load(std::sync::atomic::Ordering::SeqCst);
+        let stale: HashMap<_, _> = db
+            .load_provider_settings_from_db()
+            .unwrap()
+            .into_iter()
+            .map(|row| (row.provider_id.clone(), row))
+            .collect();
+
+        db.upsert_provider_settings("binance", Some("new"), None, None, None, "rest", None, None, None, None, None)
+            .unwrap();
+        db.fill_provider_settings_cache(generation, &stale);
+        assert_eq!(
+            db.get_provider_settings("binance").unwrap().unwrap().api_key.as_deref(),
+            Some("new")
+        );
+    }
+
+    /// API keys are encrypted in the table but returned decrypted.
+    #[test]
+    fn provider_api_keys_encrypted_at_rest() {
+        let db = open_test_db();
+        db.upsert_provider_settings("alpaca", Some("key-1"), Some("secret-1"), None, None, "rest", None, None, None, None, None)
+            .unwrap();
+
+        let (raw_key, raw_secret): (String, String) = db
+            .conn
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT api_key, api_secret FROM provider_settings WHERE provider_id = 'alpaca'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert!(crate::secrets::is_encrypted(&raw_key));
+        assert!(crate::secrets::is_encrypted(&raw_secret));
+
+        let row = db.get_provider_settings("alpaca").unwrap().unwrap();
+        assert_eq!(row.api_key.as_deref(), Some("key-1"));
+        assert_eq!(row.api_secret.as_deref(), Some("secret-1"));
+    }
+
+    /// Plaintext rows written by older versions are encrypted by the migration.
+    #[test]
+    fn plaintext_api_keys_migrated() {
+        let db = open_test_db();
+        {
+            let conn = db.conn.lock().unwrap();
+            conn.execute(
+                "INSERT INTO provider_settings (provider_id, api_key, connection_type) VALUES ('finnhub', 'legacy', 'rest')",
+                [],
+            )
+            .unwrap();
+            assert_eq!(providers::encrypt_plaintext_secrets(&conn).unwrap(), 1);
+            assert_eq!(providers::encrypt_plaintext_secrets(&conn).unwrap(), 0);
+        }
+        db.invalidate_provider_settings_cache();
+        let row = db.get_provider_settings("finnhub").unwrap().unwrap();
+        assert_eq!(row.api_key.as_deref(), Some("legacy"));
+    }
+
+    /// Opening a DB whose secrets were encrypted with another master key fails
+    /// instead of silently dropping the keys.
+    #[test]
+    fn mismatched_master_key_fails_open() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("secrets.db");
+        let db = DbPool::open(&path).unwrap();
+        assert_eq!(
+            db.get_setting(providers::KEY_SOURCE_SETTING).unwrap().as_deref(),
+            Some("passphrase")
+        );
+        db.set_setting(providers::KEY_SOURCE_SETTING, "keychain").unwrap();
+        db.set_setting(providers::KEY_CHECK_SETTING, "enc:v1:AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA")
+            .unwrap();
+        drop(db);
+
+        let err = DbPool::open(&path).err().unwrap();
+        assert!(err.contains("different keychain master key"), "{err}");
+    }
+
+    /// Restoring secrets re-stores bundled keys under the local master key and
+    /// clears keys that were not bundled instead of keeping foreign ciphertext.
+    #[test]
+    fn replace_provider_secrets_reencrypts_and_clears() {
+        let db = open_test_db();
+        for pid in ["alpaca", "finnhub"] {
+            db.upsert_provider_settings(pid, Some("old"), None, None, None, "rest", None, None, None, None, None)
+                .unwrap();
+        }
+        let bundled = [ExportedSecret {
+            provider_id: "alpaca".to_string(),
+            api_key: Some("key-2".to_string()),
+            api_secret: Some("secret-2".to_string()),
+        }];
+        assert_eq!(db.replace_provider_secrets(&bundled).unwrap(), 1);
+
+        let row = db.get_provider_settings("alpaca").unwrap().unwrap();
+        assert_eq!((row.api_key.as_deref(), row.api_secret.as_deref()), (Some("key-2"), Some("secret-2")));
+        assert_eq!(db.get_provider_settings("finnhub").unwrap().unwrap().api_key, None);
+    }
+
+    /// After migrating to the secret store, no secret value remains in the DB
+    /// file; reads and new writes go through the store.
+    #[test]
+    fn secrets_migrated_to_secret_store() {
+        let db = open_test_db();
+        let store = Arc::new(crate::secrets::MemoryStore::default());
+        db.set_secret_store(store.clone());
+        db.upsert_provider_settings("alpaca", Some("key-1"), Some("secret-1"), None, None, "rest", None, None, None, None, None)
+            .unwrap();
+
+        assert_eq!(db.migrate_secrets_to_keyring().unwrap(), 2);
+        assert!(db.secrets_in_keyring());
+
+        let raw_secret: String = db
+            .conn
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT api_secret FROM provider_settings WHERE provider_id = 'alpaca'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(crate::secrets::is_keyring_ref(&raw_secret));
+        assert_eq!(
+            store.get(&crate::secrets::secret_account("alpaca", "api_secret")).unwrap().as_deref(),
+            Some("secret-1")
+        );
+
+        db.upsert_provider_settings("alpaca", Some("key-2"), Some("secret-2"), None, None, "rest", None, None, None, None, None)
+            .unwrap();
+        let exported = db.export_secrets().unwrap();
+        assert_eq!(exported.len(), 1);
+        assert_eq!(exported[0].api_key.as_deref(), Some("key-2"));
+        assert_eq!(exported[0].api_secret.as_deref(), Some("secret-2"));
+
+        db.reset_all_data().unwrap();
+        assert_eq!(store.get(&crate::secrets::secret_account("alpaca", "api_key")).unwrap(), None);
+    }
+
+    #[test]
+    fn replay_frames_exclude_replay_rows_and_respect_window() {
+        let db = DbPool::open(&PathBuf::from(":memory:")).unwrap();
+        let btc = db
+            .add_subscription("asset", "BTCUSDT", None, "binance", "crypto", None, None, None)
+            .unwrap();
+        let replayed = db
+            .add_subscription("asset", "ETHUSDT", None, "replay", "crypto", None, None, None)
+            .unwrap();
+        db.insert_price_history_for_test(btc, "binance", &[(100.0, None, None, 1_000), (110.0, None, None, 2_000), (120.0, None, None, 3_000)])
+            .unwrap();
+        db.insert_price_history_for_test(replayed, "replay", &[(5.0, None, None, 1_500)])
+            .unwrap();
+
+        let frames = db.load_replay_frames(1_000, 2_000).unwrap();
+        assert_eq!(frames.len(), 2);
+        assert!(frames.iter().all(|f| f.symbol == "BTCUSDT" && f.provider_id == "binance"));
+        assert_eq!(frames[1].price, 110.0);
+    }
+
+    #[test]
+    fn update_subscription_records_provider_and_symbol_events() {
+        let db = open_test_db();
+        let id = add_asset(&db, "BTCUSDT", "binance");
+        db.update_subscription(id, "BTCUSDT", Some("Bitcoin"), "binance", "crypto").unwrap();
+        assert!(db.list_subscription_events(id, None, None).unwrap().is_empty());
+
+        db.update_subscription(id, "BTC-USD", None, "coinbase", "crypto").unwrap();
+        let events = db.list_subscription_events(id, None, None).unwrap();
+        let changes: Vec<_> = events
+            .iter()
+            .map(|e| (e.event_type.as_str(), e.old_value.as_str(), e.new_value.as_str()))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![("provider_change", "binance", "coinbase"), ("symbol_rename", "BTCUSDT", "BTC-USD")]
+        );
+        let at = events[0].occurred_at;
+        assert_eq!(db.list_subscription_events(id, Some(at + 1), None).unwrap().len(), 0);
+        assert_eq!(db.list_subscription_events(id, Some(at), Some(at)).unwrap().len(), 2);
+
+        assert!(db.update_subscription(id + 100, "ETH", None, "binance", "crypto").is_err());
+    }
 }
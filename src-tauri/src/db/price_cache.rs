@@ -0,0 +1,68 @@
+use rusqlite::params;
+
+use super::busy::{db_error, DbResult};
+use super::DbPool;
+use crate::providers::AssetData;
+
+impl DbPool {
+    // ── Price Cache Snapshot ────────────────────────────────────
+
+    /// 以目前的 polling 快取（`provider:symbol` → AssetData）整批取代快照
+    pub fn save_price_snapshot(&self, entries: &[(String, AssetData)]) -> DbResult<()> {
+        let tx = self.begin_write().map_err(|e| db_error("Failed to save price snapshot", e))?;
+        tx.execute("DELETE FROM price_cache", [])
+            .map_err(|e| format!("Failed to save price snapshot: {}", e))?;
+        let now = chrono::Utc::now().timestamp();
+        for (key, data) in entries {
+            let json = serde_json::to_string(data).map_err(|e| e.to_string())?;
+            tx.execute(
+                "INSERT INTO price_cache (cache_key, data, saved_at) VALUES (?1, ?2, ?3)",
+                params![key, json, now],
+            )
+            .map_err(|e| format!("Failed to save price snapshot: {}", e))?;
+        }
+        tx.commit()
+            .map_err(|e| db_error("Failed to save price snapshot", e))
+    }
+
+    /// 讀取上次保存的快照（無法解析的列略過）
+    pub fn load_price_snapshot(&self) -> Result<Vec<(String, AssetData)>, String> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT cache_key, data FROM price_cache")
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+            .map_err(|e| e.to_string())?;
+        Ok(rows
+            .filter_map(|r| r.ok())
+            .filter_map(|(key, json)| serde_json::from_str(&json).ok().map(|data| (key, data)))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::AssetDataBuilder;
+    use std::path::PathBuf;
+
+    #[test]
+    fn snapshot_round_trips_and_replaces_previous() {
+        let db = DbPool::open(&PathBuf::from(":memory:")).unwrap();
+        let btc = AssetDataBuilder::new("BTCUSDT", "binance").price(65000.0).build();
+        let eth = AssetDataBuilder::new("ETHUSDT", "binance").price(3000.0).build();
+        db.save_price_snapshot(&[
+            ("binance:BTCUSDT".to_string(), btc),
+            ("binance:ETHUSDT".to_string(), eth.clone()),
+        ])
+        .unwrap();
+        assert_eq!(db.load_price_snapshot().unwrap().len(), 2);
+
+        db.save_price_snapshot(&[("binance:ETHUSDT".to_string(), eth)]).unwrap();
+        let loaded = db.load_price_snapshot().unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].0, "binance:ETHUSDT");
+        assert_eq!(loaded[0].1.price, 3000.0);
+    }
+}
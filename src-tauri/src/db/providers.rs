@@ -1,60 +1,114 @@
-use rusqlite::params;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
 
-use super::schema::{PollingProviderSetting, ProviderSettingsRow};
+use super::schema::{ExportedSecret, PollingProviderSetting, ProviderSettingsRow, RecordHours};
 use super::DbPool;
+use crate::secrets::{is_keyring_ref, secret_account, SecretStore, KEYRING_MARKER};
+
+/// app_settings 中記錄 secret 後端的 key（"keyring" 或未設定 = 加密寫入 DB）
+const SECRETS_BACKEND_KEY: &str = "secrets_backend";
+/// app_settings 中記錄主金鑰來源與金鑰檢查值的 key
+pub(super) const KEY_SOURCE_SETTING: &str = "secrets_key_source";
+pub(super) const KEY_CHECK_SETTING: &str = "secrets_key_check";
 
 impl DbPool {
     // ── Provider Settings ───────────────────────────────────────
 
     pub fn list_provider_settings(&self) -> Result<Vec<ProviderSettingsRow>, String> {
-        let conn = self.conn.lock().unwrap();
-        let mut stmt = conn
-            .prepare("SELECT provider_id, api_key, api_secret, api_url, refresh_interval, connection_type, record_from_hour, record_to_hour FROM provider_settings")
-            .map_err(|e| e.to_string())?;
-        let rows = stmt
-            .query_map([], |row| {
-                Ok(ProviderSettingsRow {
-                    provider_id: row.get(0)?,
-                    api_key: row.get(1)?,
-                    api_secret: row.get(2)?,
-                    api_url: row.get(3)?,
-                    refresh_interval: row.get(4)?,
-                    connection_type: row.get(5)?,
-                    record_from_hour: row.get(6)?,
-                    record_to_hour: row.get(7)?,
-                })
-            })
-            .map_err(|e| e.to_string())?;
-        rows.collect::<Result<Vec<_>, _>>()
-            .map_err(|e| e.to_string())
+        let mut rows: Vec<ProviderSettingsRow> =
+            self.cached_provider_settings()?.into_values().collect();
+        rows.sort_by(|a, b| a.provider_id.cmp(&b.provider_id));
+        Ok(rows)
     }
 
     pub fn get_provider_settings(
         &self,
         provider_id: &str,
     ) -> Result<Option<ProviderSettingsRow>, String> {
-        let conn = self.conn.lock().unwrap();
-        let result = conn.query_row(
-            "SELECT provider_id, api_key, api_secret, api_url, refresh_interval, connection_type, record_from_hour, record_to_hour FROM provider_settings WHERE provider_id = ?1",
-            [provider_id],
-            |row| {
-                Ok(ProviderSettingsRow {
-                    provider_id: row.get(0)?,
-                    api_key: row.get(1)?,
-                    api_secret: row.get(2)?,
-                    api_url: row.get(3)?,
-                    refresh_interval: row.get(4)?,
-                    connection_type: row.get(5)?,
-                    record_from_hour: row.get(6)?,
-                    record_to_hour: row.get(7)?,
-                })
-            },
-        );
-        match result {
-            Ok(row) => Ok(Some(row)),
-            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(e.to_string()),
+        {
+            let cache = self.provider_settings_cache.read().unwrap();
+            if let Some(map) = cache.as_ref() {
+                return Ok(map.get(provider_id).cloned());
+            }
         }
+        Ok(self.cached_provider_settings()?.remove(provider_id))
+    }
+
+    /// 使 provider_settings 記憶體快取失效，下次讀取時重新從 DB 載入。
+    pub fn invalidate_provider_settings_cache(&self) {
+        let mut cache = self.provider_settings_cache.write().unwrap();
+        self.provider_settings_generation.fetch_add(1, Ordering::SeqCst);
+        *cache = None;
+    }
+
+    /// 取得 provider_settings 快取的複本；快取失效時從 DB 載入。
+    fn cached_provider_settings(&self) -> Result<HashMap<String, ProviderSettingsRow>, String> {
+        {
+            let cache = self.provider_settings_cache.read().unwrap();
+            if let Some(map) = cache.as_ref() {
+                return Ok(map.clone());
+            }
+        }
+        let generation = self.provider_settings_generation.load(Ordering::SeqCst);
+        let map: HashMap<String, ProviderSettingsRow> = self
+            .load_provider_settings_from_db()?
+            .into_iter()
+            .map(|row| (row.provider_id.clone(), row))
+            .collect();
+        self.fill_provider_settings_cache(generation, &map);
+        Ok(map)
+    }
+
+    /// 載入開始後沒有任何失效才寫入快取，避免把寫入前讀到的舊資料放回快取。
+    pub(super) fn fill_provider_settings_cache(
+        &self,
+        generation: u64,
+        map: &HashMap<String, ProviderSettingsRow>,
+    ) {
+        let mut cache = self.provider_settings_cache.write().unwrap();
+        if self.provider_settings_generation.load(Ordering::SeqCst) == generation {
+            *cache = Some(map.clone());
+        }
+    }
+
+    pub(super) fn load_provider_settings_from_db(&self) -> Result<Vec<ProviderSettingsRow>, String> {
+        let rows = {
+            let conn = self.conn.lock().unwrap();
+            let mut stmt = conn
+                .prepare("SELECT provider_id, api_key, api_secret, api_url, refresh_interval, connection_type, record_from_hour, record_to_hour, proxy_url, timeout_ms, extra_headers FROM provider_settings")
+                .map_err(|e| e.to_string())?;
+            let rows = stmt
+                .query_map([], |row| {
+                    Ok(ProviderSettingsRow {
+                        provider_id: row.get(0)?,
+                        api_key: row.get(1)?,
+                        api_secret: row.get(2)?,
+                        api_url: row.get(3)?,
+                        refresh_interval: row.get(4)?,
+                        connection_type: row.get(5)?,
+                        record_from_hour: row.get(6)?,
+                        record_to_hour: row.get(7)?,
+                        proxy_url: row.get(8)?,
+                        timeout_ms: row.get(9)?,
+                        extra_headers: row.get(10)?,
+                    })
+                })
+                .map_err(|e| e.to_string())?;
+            rows.collect::<Result<Vec<_>, _>>()
+                .map_err(|e| e.to_string())?
+        };
+        // 在釋放連線鎖之後才解密 / 查詢 secret store（keychain 可能較慢）
+        Ok(rows
+            .into_iter()
+            .map(|mut row| {
+                row.api_key = self.resolve_secret_column(&row.provider_id, "api_key", row.api_key.take());
+                row.api_secret =
+                    self.resolve_secret_column(&row.provider_id, "api_secret", row.api_secret.take());
+                row
+            })
+            .collect())
     }
 
     // 引數對應 provider_settings 資料表欄位，刻意保持平面簽章
@@ -69,16 +123,26 @@ impl DbPool {
         connection_type: &str,
         record_from_hour: Option<i64>,
         record_to_hour: Option<i64>,
+        proxy_url: Option<&str>,
+        timeout_ms: Option<i64>,
+        extra_headers: Option<&str>,
     ) -> Result<(), String> {
+        if let Some(headers) = extra_headers {
+            crate::providers::parse_extra_headers(headers)?;
+        }
+        let api_key = self.store_secret_column(provider_id, "api_key", api_key)?;
+        let api_secret = self.store_secret_column(provider_id, "api_secret", api_secret)?;
         let conn = self.conn.lock().unwrap();
         conn.execute(
-            "INSERT INTO provider_settings (provider_id, api_key, api_secret, api_url, refresh_interval, connection_type, record_from_hour, record_to_hour)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            "INSERT INTO provider_settings (provider_id, api_key, api_secret, api_url, refresh_interval, connection_type, record_from_hour, record_to_hour, proxy_url, timeout_ms, extra_headers)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
              ON CONFLICT(provider_id) DO UPDATE SET
-               api_key = ?2, api_secret = ?3, api_url = ?4, refresh_interval = ?5, connection_type = ?6, record_from_hour = ?7, record_to_hour = ?8",
-            params![provider_id, api_key, api_secret, api_url, refresh_interval, connection_type, record_from_hour, record_to_hour],
+               api_key = ?2, api_secret = ?3, api_url = ?4, refresh_interval = ?5, connection_type = ?6, record_from_hour = ?7, record_to_hour = ?8, proxy_url = ?9, timeout_ms = ?10, extra_headers = ?11",
+            params![provider_id, api_key, api_secret, api_url, refresh_interval, connection_type, record_from_hour, record_to_hour, proxy_url, timeout_ms, extra_headers],
         )
         .map_err(|e| format!("Failed to update provider settings: {}", e))?;
+        drop(conn);
+        self.invalidate_provider_settings_cache();
         Ok(())
     }
 
@@ -96,43 +160,332 @@ impl DbPool {
             params![provider_id, from, to],
         )
         .map_err(|e| e.to_string())?;
+        drop(conn);
+        self.invalidate_provider_settings_cache();
         Ok(())
     }
 
-    pub fn has_api_key(&self, provider_id: &str) -> bool {
+    /// provider 的紀錄時段設定與生效時段；未設定時為全天
+    pub fn get_provider_record_hours(&self, provider_id: &str) -> Result<RecordHours, String> {
         let conn = self.conn.lock().unwrap();
-        conn.query_row(
-            "SELECT api_key FROM provider_settings WHERE provider_id = ?1",
+        let (from, to) = conn
+            .query_row(
+                "SELECT record_from_hour, record_to_hour FROM provider_settings WHERE provider_id = ?1",
+                [provider_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()
+            .map_err(|e| e.to_string())?
+            .unwrap_or((None, None));
+        Ok(RecordHours::new(provider_id.to_string(), None, from, to))
+    }
+
+    /// 清除 provider 的紀錄時段（不建立 provider_settings 列）
+    pub fn clear_provider_record_hours(&self, provider_id: &str) -> Result<(), String> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE provider_settings SET record_from_hour = NULL, record_to_hour = NULL WHERE provider_id = ?1",
             [provider_id],
-            |row| row.get::<_, Option<String>>(0),
         )
-        .ok()
-        .flatten()
-        .map(|k| !k.is_empty())
-        .unwrap_or(false)
+        .map_err(|e| e.to_string())?;
+        drop(conn);
+        self.invalidate_provider_settings_cache();
+        Ok(())
+    }
+
+    // ── Secrets ─────────────────────────────────────────────────
+
+    /// 替換 secret store 後端（預設為 OS keychain）
+    pub fn set_secret_store(&self, store: Arc<dyn SecretStore>) {
+        *self.secret_store.write().unwrap() = store;
+        self.invalidate_provider_settings_cache();
+    }
+
+    fn secret_store(&self) -> Arc<dyn SecretStore> {
+        self.secret_store.read().unwrap().clone()
+    }
+
+    /// API key / secret 是否存放於 secret store（而非加密後寫入 DB）
+    pub fn secrets_in_keyring(&self) -> bool {
+        self.get_setting(SECRETS_BACKEND_KEY)
+            .ok()
+            .flatten()
+            .is_some_and(|v| v == "keyring")
+    }
+
+    /// 將所有 API key / secret 移至 secret store，DB 欄位只保留標記。
+    /// 回傳搬移的值數量。
+    pub fn migrate_secrets_to_keyring(&self) -> Result<usize, String> {
+        let store = self.secret_store();
+        let rows = self.load_provider_settings_from_db()?;
+        let mut migrated = 0;
+        for row in &rows {
+            for (field, value) in [("api_key", &row.api_key), ("api_secret", &row.api_secret)] {
+                let Some(value) = value.as_deref().filter(|v| !v.is_empty()) else {
+                    continue;
+                };
+                store.set(&secret_account(&row.provider_id, field), value)?;
+                let conn = self.conn.lock().unwrap();
+                conn.execute(secret_column_update_sql(field), params![KEYRING_MARKER, row.provider_id])
+                    .map_err(|e| e.to_string())?;
+                migrated += 1;
+            }
+        }
+        self.set_setting(SECRETS_BACKEND_KEY, "keyring")?;
+        self.invalidate_provider_settings_cache();
+        tracing::info!(
+            "[DB] Migrated {} provider secret(s) to {}",
+            migrated,
+            store.name()
+        );
+        Ok(migrated)
+    }
+
+    /// 匯出所有已設定的明文 API key / secret（供跨裝置搬移）
+    pub fn export_secrets(&self) -> Result<Vec<ExportedSecret>, String> {
+        Ok(self
+            .list_provider_settings()?
+            .into_iter()
+            .filter(|row| {
+                row.api_key.as_deref().is_some_and(|v| !v.is_empty())
+                    || row.api_secret.as_deref().is_some_and(|v| !v.is_empty())
+            })
+            .map(|row| ExportedSecret {
+                provider_id: row.provider_id,
+                api_key: row.api_key,
+                api_secret: row.api_secret,
+            })
+            .collect())
+    }
+
+    /// 以明文 key / secret 重寫所有 provider 的 secret 欄位（雲端還原後以本機金鑰重新儲存），回傳還原的 provider 數；
+    /// 清單中沒有的 provider 清除 key，不留下其他裝置的密文。
+    pub fn replace_provider_secrets(&self, secrets: &[ExportedSecret]) -> Result<usize, String> {
+        let provider_ids: Vec<String> = {
+            let conn = self.conn.lock().unwrap();
+            let mut stmt = conn
+                .prepare("SELECT provider_id FROM provider_settings")
+                .map_err(|e| e.to_string())?;
+            let rows = stmt.query_map([], |row| row.get(0)).map_err(|e| e.to_string())?;
+            rows.collect::<Result<_, _>>().map_err(|e| e.to_string())?
+        };
+        let mut restored = 0;
+        for provider_id in provider_ids {
+            let secret = secrets.iter().find(|s| s.provider_id == provider_id);
+            let api_key =
+                self.store_secret_column(&provider_id, "api_key", secret.and_then(|s| s.api_key.as_deref()))?;
+            let api_secret =
+                self.store_secret_column(&provider_id, "api_secret", secret.and_then(|s| s.api_secret.as_deref()))?;
+            self.conn
+                .lock()
+                .unwrap()
+                .execute(
+                    "UPDATE provider_settings SET api_key = ?1, api_secret = ?2 WHERE provider_id = ?3",
+                    params![api_key, api_secret, provider_id],
+                )
+                .map_err(|e| e.to_string())?;
+            restored += usize::from(secret.is_some());
+        }
+        self.invalidate_provider_settings_cache();
+        Ok(restored)
+    }
+
+    /// 刪除 secret store 中屬於任一 provider 的值（reset 時使用）
+    pub(super) fn purge_secret_store(&self) {
+        let provider_ids: Vec<String> = {
+            let conn = self.conn.lock().unwrap();
+            let Ok(mut stmt) = conn.prepare(
+                "SELECT provider_id FROM provider_settings WHERE api_key = ?1 OR api_secret = ?1",
+            ) else {
+                return;
+            };
+            stmt.query_map([KEYRING_MARKER], |row| row.get(0))
+                .map(|rows| rows.filter_map(|r| r.ok()).collect())
+                .unwrap_or_default()
+        };
+        let store = self.secret_store();
+        for pid in provider_ids {
+            for field in ["api_key", "api_secret"] {
+                if let Err(e) = store.delete(&secret_account(&pid, field)) {
+                    tracing::warn!("[DB] Failed to delete {} secret for {}: {}", field, pid, e);
+                }
+            }
+        }
+    }
+
+    /// 寫入前處理 secret 欄位：keychain 後端存入 store 並回傳標記，否則加密。
+    pub(super) fn store_secret_column(
+        &self,
+        provider_id: &str,
+        field: &str,
+        value: Option<&str>,
+    ) -> Result<Option<String>, String> {
+        if !self.secrets_in_keyring() {
+            return value.map(crate::secrets::encrypt_secret).transpose();
+        }
+        let store = self.secret_store();
+        let account = secret_account(provider_id, field);
+        match value.filter(|v| !v.is_empty()) {
+            Some(v) => {
+                store.set(&account, v)?;
+                Ok(Some(KEYRING_MARKER.to_string()))
+            }
+            None => {
+                store.delete(&account)?;
+                Ok(value.map(str::to_string))
+            }
+        }
+    }
+
+    /// 讀取 secret 欄位：標記則查詢 secret store，否則解密；失敗時視為未設定。
+    fn resolve_secret_column(
+        &self,
+        provider_id: &str,
+        field: &str,
+        value: Option<String>,
+    ) -> Option<String> {
+        let value = value?;
+        let resolved = if is_keyring_ref(&value) {
+            self.secret_store().get(&secret_account(provider_id, field))
+        } else {
+            crate::secrets::decrypt_secret(&value).map(Some)
+        };
+        match resolved {
+            Ok(v) => v,
+            Err(e) => {
+                tracing::warn!("[DB] Failed to read {} for {}: {}", field, provider_id, e);
+                None
+            }
+        }
     }
 
-    /// 為 Polling 讀取所有 provider 設定
+    pub fn has_api_key(&self, provider_id: &str) -> bool {
+        self.get_provider_settings(provider_id)
+            .ok()
+            .flatten()
+            .and_then(|s| s.api_key)
+            .map(|k| !k.is_empty())
+            .unwrap_or(false)
+    }
+
+    /// 為 Polling 讀取所有 provider 設定（來自記憶體快取）
     pub fn read_polling_provider_settings(
         &self,
-    ) -> Result<std::collections::HashMap<String, PollingProviderSetting>, String> {
-        let conn = self.conn.lock().unwrap();
+    ) -> Result<HashMap<String, PollingProviderSetting>, String> {
+        Ok(self
+            .cached_provider_settings()?
+            .into_iter()
+            .map(|(pid, row)| {
+                (
+                    pid,
+                    (row.api_key, row.api_secret, row.api_url, row.refresh_interval),
+                )
+            })
+            .collect())
+    }
+}
+
+/// Migration：將既有明文 api_key / api_secret 加密。
+pub(super) fn encrypt_plaintext_secrets(conn: &Connection) -> Result<usize, String> {
+    let rows: Vec<(String, Option<String>, Option<String>)> = {
         let mut stmt = conn
-            .prepare("SELECT provider_id, api_key, api_secret, api_url, refresh_interval FROM provider_settings")
+            .prepare("SELECT provider_id, api_key, api_secret FROM provider_settings")
             .map_err(|e| e.to_string())?;
         let rows = stmt
-            .query_map([], |row| {
-                Ok((
-                    row.get::<_, String>(0)?,
-                    (
-                        row.get(1)?,
-                        row.get(2)?,
-                        row.get(3).ok().flatten(),
-                        row.get(4)?,
-                    ),
-                ))
-            })
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
             .map_err(|e| e.to_string())?;
-        Ok(rows.filter_map(|r| r.ok()).collect())
+        rows.filter_map(|r| r.ok()).collect()
+    };
+
+    let needs_encryption = |v: &Option<String>| {
+        v.as_deref().is_some_and(|s| {
+            !s.is_empty() && !crate::secrets::is_encrypted(s) && !is_keyring_ref(s)
+        })
+    };
+
+    let mut migrated = 0;
+    for (provider_id, key, secret) in rows {
+        if !needs_encryption(&key) && !needs_encryption(&secret) {
+            continue;
+        }
+        let encrypt = |v: Option<String>| -> Result<Option<String>, String> {
+            match v {
+                Some(s) if is_keyring_ref(&s) => Ok(Some(s)),
+                other => other.as_deref().map(crate::secrets::encrypt_secret).transpose(),
+            }
+        };
+        let key = encrypt(key)?;
+        let secret = encrypt(secret)?;
+        conn.execute(
+            "UPDATE provider_settings SET api_key = ?1, api_secret = ?2 WHERE provider_id = ?3",
+            params![key, secret, provider_id],
+        )
+        .map_err(|e| e.to_string())?;
+        migrated += 1;
+    }
+    Ok(migrated)
+}
+
+/// 確認目前主金鑰與加密既有 secret 的金鑰相同；尚未記錄時寫入金鑰來源與檢查值。
+///
+/// 金鑰不符（例如以 passphrase 加密的資料庫在未設定 `SB_SECRETS_PASSPHRASE` 時開啟）回傳錯誤；
+/// 暫時取不到金鑰只記錄錯誤，之後的加解密會重試。
+pub(super) fn check_secrets_key(conn: &Connection) -> Result<(), String> {
+    let setting = |key: &str| -> Result<Option<String>, String> {
+        conn.query_row("SELECT value FROM app_settings WHERE key = ?1", [key], |row| row.get(0))
+            .optional()
+            .map_err(|e| e.to_string())
+    };
+    let encrypted: Vec<String> = {
+        let mut stmt = conn
+            .prepare(
+                "SELECT api_key FROM provider_settings WHERE api_key LIKE 'enc:v1:%'
+                 UNION ALL SELECT api_secret FROM provider_settings WHERE api_secret LIKE 'enc:v1:%'
+                 UNION ALL SELECT value FROM app_settings WHERE value LIKE 'enc:v1:%' AND key != ?1",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([KEY_CHECK_SETTING], |row| row.get(0))
+            .map_err(|e| e.to_string())?;
+        rows.collect::<Result<_, _>>().map_err(|e| e.to_string())?
+    };
+
+    if let Err(e) = crate::secrets::key_source() {
+        if !encrypted.is_empty() {
+            tracing::error!("[DB] Stored API keys cannot be decrypted: {}", e);
+        }
+        return Ok(());
+    }
+    if let (Some(source), Some(check)) = (setting(KEY_SOURCE_SETTING)?, setting(KEY_CHECK_SETTING)?) {
+        return crate::secrets::verify_key_check(&source, &check);
+    }
+
+    // 舊版資料庫沒有檢查值：以目前金鑰為準，解不開的值需重新輸入
+    let unreadable = encrypted
+        .iter()
+        .filter(|v| crate::secrets::decrypt_secret(v).is_err())
+        .count();
+    if unreadable > 0 {
+        tracing::error!(
+            "[DB] {} stored secret(s) were encrypted with another master key and must be re-entered",
+            unreadable
+        );
+    }
+    let source = crate::secrets::key_source()?;
+    let check = crate::secrets::key_check()?;
+    for (key, value) in [(KEY_SOURCE_SETTING, source.as_str()), (KEY_CHECK_SETTING, check.as_str())] {
+        conn.execute(
+            "INSERT INTO app_settings (key, value) VALUES (?1, ?2) ON CONFLICT(key) DO UPDATE SET value = ?2",
+            params![key, value],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+fn secret_column_update_sql(field: &str) -> &'static str {
+    match field {
+        "api_key" => "UPDATE provider_settings SET api_key = ?1 WHERE provider_id = ?2",
+        _ => "UPDATE provider_settings SET api_secret = ?1 WHERE provider_id = ?2",
     }
 }
@@ -0,0 +1,66 @@
+use rusqlite::params;
+
+use super::busy::{db_error, DbResult};
+use super::DbPool;
+use crate::providers::AssetData;
+
+impl DbPool {
+    // ── Provider Quote Cache ────────────────────────────────────
+
+    /// 新增或更新 provider 的報價快取（以 provider + symbol 為 key，fetched_at 為 unix 秒）
+    pub fn upsert_provider_quotes(&self, provider_id: &str, quotes: &[(AssetData, i64)]) -> DbResult<()> {
+        let tx = self.begin_write().map_err(|e| db_error("Failed to save provider quotes", e))?;
+        {
+            let mut stmt = tx
+                .prepare(
+                    "INSERT OR REPLACE INTO provider_quote_cache (provider_id, symbol, data, fetched_at)
+                     VALUES (?1, ?2, ?3, ?4)",
+                )
+                .map_err(|e| format!("Failed to save provider quotes: {}", e))?;
+            for (data, fetched_at) in quotes {
+                let json = serde_json::to_string(data).map_err(|e| e.to_string())?;
+                stmt.execute(params![provider_id, data.symbol, json, fetched_at])
+                    .map_err(|e| format!("Failed to save provider quotes: {}", e))?;
+            }
+        }
+        tx.commit()
+            .map_err(|e| db_error("Failed to save provider quotes", e))
+    }
+
+    /// 讀取 provider 的所有報價快取（無法解析的列略過）
+    pub fn load_provider_quotes(&self, provider_id: &str) -> Result<Vec<(AssetData, i64)>, String> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT data, fetched_at FROM provider_quote_cache WHERE provider_id = ?1")
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(params![provider_id], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+            })
+            .map_err(|e| e.to_string())?;
+        Ok(rows
+            .filter_map(|r| r.ok())
+            .filter_map(|(json, at)| serde_json::from_str(&json).ok().map(|data| (data, at)))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::AssetDataBuilder;
+    use std::path::PathBuf;
+
+    #[test]
+    fn quotes_upsert_per_provider_and_symbol() {
+        let db = DbPool::open(&PathBuf::from(":memory:")).unwrap();
+        let quote = |price: f64| AssetDataBuilder::new("IBM", "alphavantage").price(price).build();
+        db.upsert_provider_quotes("alphavantage", &[(quote(180.0), 100)]).unwrap();
+        db.upsert_provider_quotes("alphavantage", &[(quote(181.5), 200)]).unwrap();
+
+        let loaded = db.load_provider_quotes("alphavantage").unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!((loaded[0].0.price, loaded[0].1), (181.5, 200));
+        assert!(db.load_provider_quotes("finnhub").unwrap().is_empty());
+    }
+}
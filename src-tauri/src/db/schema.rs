@@ -2,7 +2,8 @@ use serde::{Deserialize, Serialize};
 
 // ── Shared complex-type aliases ─────────────────────────────────
 
-/// 一筆待寫入的價格紀錄：(symbol, price, change_pct, volume, pre_price, post_price)
+/// 一筆待寫入的價格紀錄：
+/// (symbol, price, change_pct, volume, pre_price, post_price, liquidity_usd, fdv, open_interest, long_short_ratio, currency)
 pub type PriceRecord = (
     String,
     f64,
@@ -10,29 +11,125 @@ pub type PriceRecord = (
     Option<f64>,
     Option<f64>,
     Option<f64>,
+    Option<f64>,
+    Option<f64>,
+    Option<f64>,
+    Option<f64>,
+    String,
 );
 
+/// 未設定時的紀錄去重間隔（秒）
+pub const DEFAULT_RECORD_DEDUP_SECS: i64 = 5;
+/// 紀錄去重間隔上限（一天）
+pub const MAX_RECORD_DEDUP_SECS: i64 = 86_400;
+
+/// 預先解析的紀錄目標 — polling 每次重新載入時由訂閱與 provider 設定建立一次，
+/// 寫入歷史時不必逐筆查詢 subscriptions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecordTarget {
+    pub subscription_id: i64,
+    pub is_dex: bool,
+    /// 紀錄時段（本地時間，訂閱設定優先，其次 provider 設定；0..24 為全天）
+    pub from_hour: u32,
+    pub to_hour: u32,
+    /// 兩筆紀錄的最短間隔（秒）；0 為不去重
+    pub dedup_secs: i64,
+}
+
+impl RecordTarget {
+    /// 本地時間 `hour` 是否在紀錄時段內
+    pub fn in_window(&self, hour: u32) -> bool {
+        if self.from_hour == 0 && self.to_hour == 24 {
+            true
+        } else if self.from_hour <= self.to_hour {
+            hour >= self.from_hour && hour < self.to_hour
+        } else {
+            hour >= self.from_hour || hour < self.to_hour
+        }
+    }
+}
+
+/// 紀錄時段的來源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordHoursSource {
+    Subscription,
+    Provider,
+    /// 都未設定，全天紀錄
+    AllDay,
+}
+
+/// 依優先順序解析紀錄時段：訂閱設定 > provider 設定 > 全天；起訖都設定才算有設定
+pub fn resolve_record_hours(
+    subscription: (Option<i64>, Option<i64>),
+    provider: (Option<i64>, Option<i64>),
+) -> (u32, u32, RecordHoursSource) {
+    let hours = |(from, to): (Option<i64>, Option<i64>)| from.zip(to).map(|(f, t)| (f as u32, t as u32));
+    match (hours(subscription), hours(provider)) {
+        (Some((f, t)), _) => (f, t, RecordHoursSource::Subscription),
+        (None, Some((f, t))) => (f, t, RecordHoursSource::Provider),
+        (None, None) => (0, 24, RecordHoursSource::AllDay),
+    }
+}
+
+/// 紀錄時段查詢結果：訂閱與 provider 各自的設定，以及實際生效的時段（本地時間）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordHours {
+    pub provider_id: String,
+    /// 查詢 provider 時為 None
+    pub subscription_id: Option<i64>,
+    /// 訂閱是否開啟紀錄；查詢 provider 時為 None
+    pub record_enabled: Option<bool>,
+    pub subscription_from_hour: Option<i64>,
+    pub subscription_to_hour: Option<i64>,
+    pub provider_from_hour: Option<i64>,
+    pub provider_to_hour: Option<i64>,
+    /// 生效時段；0..24 為全天，起點大於終點表示跨午夜
+    pub effective_from_hour: u32,
+    pub effective_to_hour: u32,
+    pub source: RecordHoursSource,
+}
+
+impl RecordHours {
+    pub fn new(
+        provider_id: String,
+        subscription: Option<(i64, bool, Option<i64>, Option<i64>)>,
+        provider_from_hour: Option<i64>,
+        provider_to_hour: Option<i64>,
+    ) -> Self {
+        let (subscription_id, record_enabled, subscription_from_hour, subscription_to_hour) = match subscription {
+            Some((id, enabled, from, to)) => (Some(id), Some(enabled), from, to),
+            None => (None, None, None, None),
+        };
+        let (effective_from_hour, effective_to_hour, source) = resolve_record_hours(
+            (subscription_from_hour, subscription_to_hour),
+            (provider_from_hour, provider_to_hour),
+        );
+        Self {
+            provider_id,
+            subscription_id,
+            record_enabled,
+            subscription_from_hour,
+            subscription_to_hour,
+            provider_from_hour,
+            provider_to_hour,
+            effective_from_hour,
+            effective_to_hour,
+            source,
+        }
+    }
+}
+
+/// polling symbol → 紀錄目標（單一 provider）
+pub type RecordTargets = std::collections::HashMap<String, RecordTarget>;
+
 /// Polling 用的 provider 設定值：(api_key, api_secret, api_url, refresh_interval)
 pub type PollingProviderSetting = (Option<String>, Option<String>, Option<String>, Option<i64>);
 
 // ── Data types ──────────────────────────────────────────────────
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Subscription {
-    pub id: i64,
-    pub sub_type: String,
-    pub symbol: String,
-    pub display_name: Option<String>,
-    pub selected_provider_id: String,
-    pub asset_type: String,
-    pub pool_address: Option<String>,
-    pub token_from_address: Option<String>,
-    pub token_to_address: Option<String>,
-    pub sort_order: i64,
-    pub record_enabled: i64,
-    pub record_from_hour: Option<i64>,
-    pub record_to_hour: Option<i64>,
-}
+/// API 也會回傳的資料列，定義於 `stockenboard-api-types` 讓外部工具共用
+pub use stockenboard_api_types::models::{PriceHistoryRow, Subscription, SubscriptionEvent, ViewRow, ViewSubCount};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProviderSettingsRow {
@@ -44,27 +141,46 @@ pub struct ProviderSettingsRow {
     pub connection_type: String,
     pub record_from_hour: Option<i64>,
     pub record_to_hour: Option<i64>,
+    /// Provider 專用 HTTP proxy（覆寫全域 `http_proxy`）
+    pub proxy_url: Option<String>,
+    /// 請求逾時（毫秒）；None = 預設 15 秒
+    pub timeout_ms: Option<i64>,
+    pub extra_headers: Option<String>, // JSON object string: {"Header-Name": "value"}
 }
 
+impl ProviderSettingsRow {
+    /// 給唯讀 / 區網用戶端的版本：不含 key、secret，以及可能帶帳密的 proxy 與自訂 header
+    pub fn redacted(mut self) -> Self {
+        self.api_key = None;
+        self.api_secret = None;
+        self.proxy_url = None;
+        self.extra_headers = None;
+        self
+    }
+}
+
+/// `export_secrets` 的輸出：單一 provider 的明文 API key / secret
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ViewRow {
-    pub id: i64,
-    pub name: String,
-    pub view_type: String,
-    pub is_default: bool,
+pub struct ExportedSecret {
+    pub provider_id: String,
+    pub api_key: Option<String>,
+    pub api_secret: Option<String>,
 }
 
+/// symbol → icons 目錄中的檔名
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ViewSubCount {
-    pub view_id: i64,
-    pub count: i64,
+pub struct IconMapping {
+    pub symbol: String,
+    pub file_name: String,
+    pub updated_at: i64,
 }
 
+/// 一筆回放用的歷史價格（`price_history` JOIN `subscriptions`）
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PriceHistoryRow {
-    pub id: i64,
-    pub subscription_id: i64,
+pub struct ReplayFrame {
+    /// 原始紀錄的 provider；同一 symbol 可能由多個 provider 錄製
     pub provider_id: String,
+    pub symbol: String,
     pub price: f64,
     pub change_pct: Option<f64>,
     pub volume: Option<f64>,
@@ -141,6 +257,10 @@ pub struct ExportSubscription {
     pub record_from_hour: Option<i64>,
     pub record_to_hour: Option<i64>,
     pub sort_order: Option<i64>,
+    #[serde(default)]
+    pub provider_params: Option<String>,
+    #[serde(default)]
+    pub record_dedup_secs: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -150,14 +270,108 @@ pub struct ExportView {
     pub symbols: Vec<String>,
 }
 
+// ── App config export/import types ──────────────────────────────
+
+/// 目前的 app config schema 版本；格式有不相容變更時遞增
+pub const APP_CONFIG_VERSION: u32 = 1;
+
+/// 完整 app 設定的版本化快照（訂閱、view、provider 設定、通知與 app settings）。
+/// 不含價格歷史與通知紀錄。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppConfig {
+    pub version: u32,
+    pub exported_at: i64,
+    /// 是否包含明文 API key / secret / Bot Token
+    #[serde(default)]
+    pub includes_secrets: bool,
+    #[serde(default)]
+    pub subscriptions: Vec<ExportSubscription>,
+    #[serde(default)]
+    pub views: Vec<AppConfigView>,
+    #[serde(default)]
+    pub provider_settings: Vec<ProviderSettingsRow>,
+    #[serde(default)]
+    pub notification_channels: Vec<AppConfigChannel>,
+    #[serde(default)]
+    pub notification_rules: Vec<AppConfigRule>,
+    #[serde(default)]
+    pub settings: std::collections::BTreeMap<String, String>,
+}
+
+/// 以 (symbol, provider) 指向匯出檔中的訂閱（對應 subscriptions 的 UNIQUE 條件）
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SubscriptionRef {
+    pub symbol: String,
+    pub provider_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppConfigView {
+    pub name: String,
+    pub view_type: String,
+    pub subscriptions: Vec<SubscriptionRef>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppConfigChannel {
+    /// 匯出時的 channel id，僅供 rule 的 `channels` 參照
+    pub key: i64,
+    pub channel_type: String,
+    pub name: String,
+    pub config: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppConfigRule {
+    pub name: String,
+    pub subscription: SubscriptionRef,
+    /// AI 規則的多訂閱清單
+    #[serde(default)]
+    pub subscriptions: Option<Vec<SubscriptionRef>>,
+    pub condition_type: String,
+    pub threshold: f64,
+    /// 參照 `AppConfigChannel::key`
+    pub channels: Vec<i64>,
+    pub cooldown_secs: i64,
+    pub enabled: bool,
+    #[serde(default)]
+    pub ai_config: Option<String>,
+}
+
+/// `merge` 保留匯入檔以外的現有資料；`replace` 讓設定與匯入檔一致（同一訂閱的價格歷史保留）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigImportMode {
+    #[default]
+    Merge,
+    Replace,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConfigImportSummary {
+    pub subscriptions: usize,
+    pub views: usize,
+    pub provider_settings: usize,
+    pub notification_channels: usize,
+    pub notification_rules: usize,
+    pub settings: usize,
+    /// 略過的項目與原因
+    pub skipped: Vec<String>,
+}
+
 // ── Batch subscription types ────────────────────────────────────
 
+pub use stockenboard_api_types::subscriptions::BatchAddItem;
+
+/// 批次匯入的單筆 asset 訂閱（CSV 匯入使用），`views` 為要加入的 asset view 名稱
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct BatchAddItem {
+pub struct SubscriptionImportRow {
     pub symbol: String,
     pub display_name: Option<String>,
     pub provider_id: String,
     pub asset_type: String,
+    pub record_enabled: bool,
+    pub views: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -166,3 +380,30 @@ pub struct BatchAddResult {
     pub failed: Vec<String>,
     pub duplicates: Vec<String>,
 }
+
+/// CoinGecko `/coins/list` 的一筆（`market_cap_rank` 來自 `/coins/markets`，僅前段排名有值）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoinGeckoCoin {
+    pub id: String,
+    pub symbol: String,
+    pub name: String,
+    pub market_cap_rank: Option<i64>,
+}
+
+/// CoinPaprika `/v1/coins` 的一筆（`rank` 為 0 的未排名幣存成 None）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoinPaprikaCoin {
+    pub id: String,
+    pub symbol: String,
+    pub name: String,
+    pub rank: Option<i64>,
+}
+
+/// 鏈上 token 的 symbol / decimals（DEX 報價換算用，依 chain + address 快取）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenMetadata {
+    pub chain: String,
+    pub address: String,
+    pub symbol: Option<String>,
+    pub decimals: u32,
+}
@@ -218,6 +218,7 @@ impl DbPool {
     }
 
     pub fn reset_all_data(&self) -> Result<(), String> {
+        self.purge_secret_store();
         let conn = self.conn.lock().unwrap();
         // 刪除所有資料（notification_history first due to FK constraints）
         conn.execute_batch(
@@ -225,6 +226,7 @@ impl DbPool {
              DELETE FROM notification_rules;
              DELETE FROM notification_channels;
              DELETE FROM price_history;
+             DELETE FROM price_candles;
              DELETE FROM view_subscriptions;
              DELETE FROM subscriptions;
              DELETE FROM views;
@@ -240,6 +242,9 @@ impl DbPool {
              INSERT OR IGNORE INTO app_settings (key, value) VALUES ('api_port', '8080');
              INSERT OR IGNORE INTO app_settings (key, value) VALUES ('api_enabled', '0');"
         ).map_err(|e| format!("Failed to restore default data: {}", e))?;
+        drop(conn);
+        self.forget_last_written(None);
+        self.invalidate_provider_settings_cache();
 
         Ok(())
     }
@@ -287,7 +292,7 @@ impl DbPool {
         let subs_out: Vec<ExportSubscription>;
         {
             let mut stmt = conn
-                .prepare("SELECT symbol, display_name, selected_provider_id, asset_type, sub_type, pool_address, token_from_address, token_to_address, record_enabled, record_from_hour, record_to_hour, sort_order FROM subscriptions ORDER BY sort_order, id")
+                .prepare("SELECT symbol, display_name, selected_provider_id, asset_type, sub_type, pool_address, token_from_address, token_to_address, record_enabled, record_from_hour, record_to_hour, sort_order, provider_params, record_dedup_secs FROM subscriptions ORDER BY sort_order, id")
                 .map_err(|e| e.to_string())?;
             let rows = stmt
                 .query_map([], |row| {
@@ -304,6 +309,8 @@ impl DbPool {
                         record_from_hour: row.get(9)?,
                         record_to_hour: row.get(10)?,
                         sort_order: row.get(11)?,
+                        provider_params: row.get(12)?,
+                        record_dedup_secs: row.get(13)?,
                     })
                 })
                 .map_err(|e| e.to_string())?;
@@ -325,12 +332,13 @@ impl DbPool {
         for sub in &data.subscriptions {
             let changed = conn
                 .execute(
-                    "INSERT OR IGNORE INTO subscriptions (sub_type, symbol, display_name, selected_provider_id, asset_type, pool_address, token_from_address, token_to_address, record_enabled, record_from_hour, record_to_hour, sort_order)
-                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                    "INSERT OR IGNORE INTO subscriptions (sub_type, symbol, display_name, selected_provider_id, asset_type, pool_address, token_from_address, token_to_address, record_enabled, record_from_hour, record_to_hour, sort_order, provider_params, record_dedup_secs)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
                     params![
                         sub.sub_type, sub.symbol, sub.display_name, sub.selected_provider_id,
                         sub.asset_type, sub.pool_address, sub.token_from_address, sub.token_to_address,
-                        sub.record_enabled.unwrap_or(false), sub.record_from_hour, sub.record_to_hour, sub.sort_order.unwrap_or(0)
+                        sub.record_enabled.unwrap_or(false), sub.record_from_hour, sub.record_to_hour, sub.sort_order.unwrap_or(0),
+                        sub.provider_params, sub.record_dedup_secs
                     ],
                 )
                 .unwrap_or(0);
@@ -1,7 +1,14 @@
-use rusqlite::params;
-use std::collections::HashSet;
+use rusqlite::{params, OptionalExtension};
+use std::collections::{HashMap, HashSet};
 
-use super::schema::Subscription;
+use crate::i18n::{tr, Msg};
+use crate::providers::ProviderParams;
+
+use super::busy::{db_error, DbResult};
+use super::schema::{
+    resolve_record_hours, RecordHours, RecordTarget, RecordTargets, Subscription, SubscriptionEvent,
+    SubscriptionImportRow, DEFAULT_RECORD_DEDUP_SECS, MAX_RECORD_DEDUP_SECS,
+};
 use super::DbPool;
 
 impl DbPool {
@@ -13,7 +20,7 @@ impl DbPool {
             .prepare(
                 "SELECT id, sub_type, symbol, display_name, selected_provider_id, asset_type,
                     pool_address, token_from_address, token_to_address, sort_order,
-                    record_enabled, record_from_hour, record_to_hour
+                    record_enabled, record_from_hour, record_to_hour, provider_params, record_dedup_secs
                  FROM subscriptions WHERE sub_type = ?1 ORDER BY sort_order, id",
             )
             .map_err(|e| e.to_string())?;
@@ -33,6 +40,8 @@ impl DbPool {
                     record_enabled: row.get(10)?,
                     record_from_hour: row.get(11)?,
                     record_to_hour: row.get(12)?,
+                    provider_params: row.get(13)?,
+                    record_dedup_secs: row.get(14)?,
                 })
             })
             .map_err(|e| e.to_string())?;
@@ -46,7 +55,7 @@ impl DbPool {
             .prepare(
                 "SELECT id, sub_type, symbol, display_name, selected_provider_id, asset_type,
                     pool_address, token_from_address, token_to_address, sort_order,
-                    record_enabled, record_from_hour, record_to_hour
+                    record_enabled, record_from_hour, record_to_hour, provider_params, record_dedup_secs
                  FROM subscriptions ORDER BY sort_order, id",
             )
             .map_err(|e| e.to_string())?;
@@ -66,6 +75,8 @@ impl DbPool {
                     record_enabled: row.get(10)?,
                     record_from_hour: row.get(11)?,
                     record_to_hour: row.get(12)?,
+                    provider_params: row.get(13)?,
+                    record_dedup_secs: row.get(14)?,
                 })
             })
             .map_err(|e| e.to_string())?;
@@ -101,6 +112,48 @@ impl DbPool {
         Ok(conn.last_insert_rowid())
     }
 
+    /// 在單一 transaction 中新增多筆 asset 訂閱，並套用紀錄開關與 view（不存在時建立）。
+    /// 個別列失敗（例如已存在）不影響其他列；回傳與輸入同序的結果。
+    pub fn import_subscription_rows(
+        &self,
+        rows: &[SubscriptionImportRow],
+    ) -> DbResult<Vec<Result<i64, String>>> {
+        let tx = self.begin_write().map_err(|e| db_error("Failed to start import", e))?;
+        let mut results = Vec::with_capacity(rows.len());
+        for row in rows {
+            let changed = tx
+                .execute(
+                    "INSERT OR IGNORE INTO subscriptions (sub_type, symbol, display_name, selected_provider_id, asset_type, record_enabled)
+                     VALUES ('asset', ?1, ?2, ?3, ?4, ?5)",
+                    params![row.symbol, row.display_name, row.provider_id, row.asset_type, row.record_enabled],
+                )
+                .map_err(|e| format!("Failed to add subscription: {}", e))?;
+            if changed == 0 {
+                results.push(Err("Subscription already exists".to_string()));
+                continue;
+            }
+            let id = tx.last_insert_rowid();
+            for view in &row.views {
+                tx.execute(
+                    "INSERT OR IGNORE INTO views (name, view_type, is_default) VALUES (?1, 'asset', 0)",
+                    [view],
+                )
+                .map_err(|e| format!("Failed to create view: {}", e))?;
+                tx.execute(
+                    "INSERT OR IGNORE INTO view_subscriptions (view_id, subscription_id)
+                     SELECT id, ?2 FROM views WHERE name = ?1 AND view_type = 'asset'",
+                    params![view, id],
+                )
+                .map_err(|e| format!("Failed to add subscription to view: {}", e))?;
+            }
+            results.push(Ok(id));
+        }
+        tx.commit()
+            .map_err(|e| format!("Failed to commit import: {}", e))?;
+        Ok(results)
+    }
+
+    /// 更新訂閱；provider 或 symbol 有變動時同時寫入 `subscription_events`
     pub fn update_subscription(
         &self,
         id: i64,
@@ -108,13 +161,90 @@ impl DbPool {
         display_name: Option<&str>,
         provider_id: &str,
         asset_type: &str,
-    ) -> Result<(), String> {
-        let conn = self.conn.lock().unwrap();
-        conn.execute(
+    ) -> DbResult<()> {
+        let tx = self.begin_write().map_err(|e| db_error("Failed to update subscription", e))?;
+        let err = |e| db_error("Failed to update subscription", e);
+        let (old_symbol, old_provider): (String, String) = tx
+            .query_row(
+                "SELECT symbol, selected_provider_id FROM subscriptions WHERE id = ?1",
+                [id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()
+            .map_err(err)?
+            .ok_or_else(|| tr(Msg::SubscriptionNotFound, &[&id]))?;
+        tx.execute(
             "UPDATE subscriptions SET symbol = ?1, display_name = ?2, selected_provider_id = ?3, asset_type = ?4 WHERE id = ?5",
             params![symbol, display_name, provider_id, asset_type, id],
         )
-        .map_err(|e| format!("Failed to update subscription: {}", e))?;
+        .map_err(err)?;
+
+        let now = chrono::Utc::now().timestamp();
+        let changes = [
+            ("provider_change", old_provider.as_str(), provider_id),
+            ("symbol_rename", old_symbol.as_str(), symbol),
+        ];
+        for (event_type, old_value, new_value) in changes {
+            if old_value != new_value {
+                tx.execute(
+                    "INSERT INTO subscription_events (subscription_id, event_type, old_value, new_value, occurred_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![id, event_type, old_value, new_value, now],
+                )
+                .map_err(err)?;
+            }
+        }
+        tx.commit().map_err(err)?;
+        Ok(())
+    }
+
+    /// 訂閱的變更紀錄（舊到新）；`from` / `to` 為 Unix 秒，含端點
+    pub fn list_subscription_events(
+        &self,
+        subscription_id: i64,
+        from: Option<i64>,
+        to: Option<i64>,
+    ) -> Result<Vec<SubscriptionEvent>, String> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, subscription_id, event_type, old_value, new_value, occurred_at
+                 FROM subscription_events
+                 WHERE subscription_id = ?1 AND occurred_at >= ?2 AND occurred_at <= ?3
+                 ORDER BY occurred_at, id",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(
+                params![subscription_id, from.unwrap_or(i64::MIN), to.unwrap_or(i64::MAX)],
+                |row| {
+                    Ok(SubscriptionEvent {
+                        id: row.get(0)?,
+                        subscription_id: row.get(1)?,
+                        event_type: row.get(2)?,
+                        old_value: row.get(3)?,
+                        new_value: row.get(4)?,
+                        occurred_at: row.get(5)?,
+                    })
+                },
+            )
+            .map_err(|e| e.to_string())?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+    }
+
+    /// 設定訂閱的 provider 專屬參數；None 或空物件清除參數
+    pub fn set_provider_params(&self, id: i64, provider_params: Option<&str>) -> Result<(), String> {
+        let params = provider_params
+            .map(crate::providers::parse_provider_params)
+            .transpose()?
+            .filter(|p| !p.is_empty())
+            .map(|p| serde_json::Value::Object(p).to_string());
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE subscriptions SET provider_params = ?1 WHERE id = ?2",
+            params![params, id],
+        )
+        .map_err(|e| format!("Failed to update provider params: {}", e))?;
         Ok(())
     }
 
@@ -147,6 +277,87 @@ impl DbPool {
         Ok(())
     }
 
+    /// 在單一 transaction 中把 `merge_ids` 合併進 `keep_id`：價格紀錄（與保留訂閱時間相同者捨棄）、
+    /// view 成員、通知規則與紀錄開關移到保留的訂閱後，刪除被合併的訂閱。回傳 (移動, 捨棄) 的紀錄筆數
+    pub fn merge_subscriptions(&self, keep_id: i64, merge_ids: &[i64]) -> DbResult<(i64, i64)> {
+        let tx = self.begin_write().map_err(|e| db_error("Failed to start merge", e))?;
+        let err = |e| db_error("Failed to merge subscriptions", e);
+        let (mut moved, mut dropped) = (0, 0);
+        for id in merge_ids {
+            dropped += tx
+                .execute(
+                    "DELETE FROM price_history WHERE subscription_id = ?1 AND recorded_at IN
+                        (SELECT recorded_at FROM price_history WHERE subscription_id = ?2)",
+                    params![id, keep_id],
+                )
+                .map_err(err)? as i64;
+            moved += tx
+                .execute(
+                    "UPDATE price_history SET subscription_id = ?2 WHERE subscription_id = ?1",
+                    params![id, keep_id],
+                )
+                .map_err(err)? as i64;
+            tx.execute(
+                "INSERT OR IGNORE INTO view_subscriptions (view_id, subscription_id)
+                 SELECT view_id, ?2 FROM view_subscriptions WHERE subscription_id = ?1",
+                params![id, keep_id],
+            )
+            .map_err(err)?;
+            tx.execute(
+                "UPDATE notification_rules SET subscription_id = ?2 WHERE subscription_id = ?1",
+                params![id, keep_id],
+            )
+            .map_err(err)?;
+            tx.execute(
+                "UPDATE subscriptions SET record_enabled = MAX(record_enabled,
+                    (SELECT record_enabled FROM subscriptions WHERE id = ?1)) WHERE id = ?2",
+                params![id, keep_id],
+            )
+            .map_err(err)?;
+        }
+
+        // 多訂閱規則的 subscription_ids（JSON 陣列）
+        let merged: HashSet<i64> = merge_ids.iter().copied().collect();
+        let rules: Vec<(i64, String)> = {
+            let mut stmt = tx
+                .prepare("SELECT id, subscription_ids FROM notification_rules WHERE subscription_ids IS NOT NULL")
+                .map_err(err)?;
+            let rows = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+                .map_err(err)?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(err)?
+        };
+        for (rule_id, json) in rules {
+            let Ok(ids) = serde_json::from_str::<Vec<i64>>(&json) else {
+                continue;
+            };
+            if !ids.iter().any(|id| merged.contains(id)) {
+                continue;
+            }
+            let mut rewritten: Vec<i64> = Vec::with_capacity(ids.len());
+            for id in ids {
+                let id = if merged.contains(&id) { keep_id } else { id };
+                if !rewritten.contains(&id) {
+                    rewritten.push(id);
+                }
+            }
+            tx.execute(
+                "UPDATE notification_rules SET subscription_ids = ?1 WHERE id = ?2",
+                params![serde_json::to_string(&rewritten).unwrap_or_default(), rule_id],
+            )
+            .map_err(err)?;
+        }
+
+        for id in merge_ids {
+            tx.execute("DELETE FROM subscriptions WHERE id = ?1", [id])
+                .map_err(err)?;
+        }
+        tx.commit()
+            .map_err(|e| format!("Failed to commit merge: {}", e))?;
+        self.forget_last_written(Some(keep_id));
+        Ok((moved, dropped))
+    }
+
     pub fn toggle_record(&self, id: i64, enabled: bool) -> Result<(), String> {
         let conn = self.conn.lock().unwrap();
         conn.execute(
@@ -182,8 +393,87 @@ impl DbPool {
         Ok(())
     }
 
+    /// 設定紀錄去重間隔（秒）；None 為預設 [`DEFAULT_RECORD_DEDUP_SECS`]，0 為每次輪詢都紀錄
+    pub fn set_record_dedup(&self, id: i64, dedup_secs: Option<i64>) -> Result<(), String> {
+        if let Some(secs) = dedup_secs {
+            if !(0..=MAX_RECORD_DEDUP_SECS).contains(&secs) {
+                return Err(format!("Dedup window must be between 0 and {} seconds", MAX_RECORD_DEDUP_SECS));
+            }
+        }
+        let conn = self.conn.lock().unwrap();
+        let updated = conn
+            .execute(
+                "UPDATE subscriptions SET record_dedup_secs = ?1 WHERE id = ?2",
+                params![dedup_secs, id],
+            )
+            .map_err(|e| e.to_string())?;
+        if updated == 0 {
+            return Err(tr(Msg::SubscriptionNotFound, &[&id]));
+        }
+        Ok(())
+    }
+
+    /// 訂閱的紀錄時段設定與生效時段（含 provider 設定）
+    pub fn get_record_hours(&self, id: i64) -> Result<RecordHours, String> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT s.selected_provider_id, s.record_enabled, s.record_from_hour, s.record_to_hour, \
+             p.record_from_hour, p.record_to_hour \
+             FROM subscriptions s LEFT JOIN provider_settings p ON p.provider_id = s.selected_provider_id \
+             WHERE s.id = ?1",
+            [id],
+            |row| {
+                Ok(RecordHours::new(
+                    row.get(0)?,
+                    Some((id, row.get::<_, i64>(1)? != 0, row.get(2)?, row.get(3)?)),
+                    row.get(4)?,
+                    row.get(5)?,
+                ))
+            },
+        )
+        .optional()
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| tr(Msg::SubscriptionNotFound, &[&id]))
+    }
+
     // ── Polling 專用 ────────────────────────────────────────────
 
+    /// 為 Polling 讀取所有開啟紀錄的訂閱並解析紀錄時段（provider → polling symbol → 目標），
+    /// 同一 provider 與 symbol 有多筆訂閱時取 id 最小者
+    pub fn read_record_targets(&self) -> Result<HashMap<String, RecordTargets>, String> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(
+                "SELECT s.id, s.sub_type, s.symbol, s.selected_provider_id, s.pool_address, s.token_from_address, s.token_to_address, \
+                 s.record_from_hour, s.record_to_hour, p.record_from_hour, p.record_to_hour, s.record_dedup_secs \
+                 FROM subscriptions s LEFT JOIN provider_settings p ON p.provider_id = s.selected_provider_id \
+                 WHERE s.record_enabled = 1 ORDER BY s.id",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([], |row| {
+                let sub_type: String = row.get(1)?;
+                let (from_hour, to_hour, _) =
+                    resolve_record_hours((row.get(7)?, row.get(8)?), (row.get(9)?, row.get(10)?));
+                let target = RecordTarget {
+                    subscription_id: row.get(0)?,
+                    is_dex: sub_type == "dex",
+                    from_hour,
+                    to_hour,
+                    dedup_secs: row.get::<_, Option<i64>>(11)?.unwrap_or(DEFAULT_RECORD_DEDUP_SECS),
+                };
+                let symbol = polling_symbol(&sub_type, row.get(2)?, row.get(4)?, row.get(5)?, row.get(6)?);
+                Ok((row.get::<_, String>(3)?, symbol, target))
+            })
+            .map_err(|e| e.to_string())?;
+
+        let mut targets: HashMap<String, RecordTargets> = HashMap::new();
+        for (provider_id, symbol, target) in rows.filter_map(|r| r.ok()) {
+            targets.entry(provider_id).or_default().entry(symbol).or_insert(target);
+        }
+        Ok(targets)
+    }
+
     /// 為 Polling 讀取所有訂閱（可選 visible_ids 過濾）
     pub fn read_polling_subscriptions(
         &self,
@@ -204,16 +494,7 @@ impl DbPool {
                 let token_to: Option<String> = row.get(6)?;
                 let record_enabled: i64 = row.get(7)?;
 
-                let final_symbol = if sub_type == "dex" {
-                    format!(
-                        "{}:{}:{}",
-                        pool_address.unwrap_or_default(),
-                        token_from.unwrap_or_default(),
-                        token_to.unwrap_or_default()
-                    )
-                } else {
-                    symbol
-                };
+                let final_symbol = polling_symbol(&sub_type, symbol, pool_address, token_from, token_to);
 
                 Ok((id, final_symbol, provider_id, record_enabled != 0))
             })
@@ -227,4 +508,165 @@ impl DbPool {
             None => all,
         })
     }
+
+    /// 為 Polling 讀取籃子訂閱的成分定義（subscription id → provider_params）
+    pub fn read_basket_params(&self) -> Result<HashMap<i64, String>, String> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, provider_params FROM subscriptions
+                 WHERE selected_provider_id = ?1 AND provider_params IS NOT NULL",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([crate::basket::PROVIDER_ID], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| e.to_string())?;
+        rows.collect::<Result<HashMap<_, _>, _>>()
+            .map_err(|e| e.to_string())
+    }
+
+    /// 讀取某 provider 所有訂閱的參數，key 為 polling 使用的 symbol（DEX 為組合後的 symbol）
+    pub fn read_provider_params(&self, provider_id: &str) -> Result<HashMap<String, ProviderParams>, String> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(
+                "SELECT sub_type, symbol, pool_address, token_from_address, token_to_address, provider_params
+                 FROM subscriptions WHERE selected_provider_id = ?1 AND provider_params IS NOT NULL",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([provider_id], |row| {
+                let symbol = polling_symbol(
+                    &row.get::<_, String>(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                );
+                Ok((symbol, row.get::<_, String>(5)?))
+            })
+            .map_err(|e| e.to_string())?;
+        let mut out = HashMap::new();
+        for row in rows {
+            let (symbol, json) = row.map_err(|e| e.to_string())?;
+            match crate::providers::parse_provider_params(&json) {
+                Ok(params) => {
+                    out.insert(symbol, params);
+                }
+                Err(e) => tracing::warn!("[DB] Ignoring provider_params for {}: {}", symbol, e),
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// 訂閱送給 provider 查詢的 symbol：DEX 訂閱為 `pool:token_from:token_to`
+fn polling_symbol(
+    sub_type: &str,
+    symbol: String,
+    pool_address: Option<String>,
+    token_from: Option<String>,
+    token_to: Option<String>,
+) -> String {
+    if sub_type == "dex" {
+        format!(
+            "{}:{}:{}",
+            pool_address.unwrap_or_default(),
+            token_from.unwrap_or_default(),
+            token_to.unwrap_or_default()
+        )
+    } else {
+        symbol
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::RecordHoursSource;
+    use std::path::PathBuf;
+
+    #[test]
+    fn record_targets_resolve_hours_once() {
+        let db = DbPool::open(&PathBuf::from(":memory:")).unwrap();
+        let own = db
+            .add_subscription("asset", "AAPL", None, "yahoo", "stock", None, None, None)
+            .unwrap();
+        let inherited = db
+            .add_subscription("asset", "MSFT", None, "yahoo", "stock", None, None, None)
+            .unwrap();
+        let off = db
+            .add_subscription("asset", "TSLA", None, "yahoo", "stock", None, None, None)
+            .unwrap();
+        let dex = db
+            .add_subscription("dex", "ETH/USDC", None, "subgraph", "crypto", Some("uniswap_v3:0xpool"), Some("0xa"), Some("0xb"))
+            .unwrap();
+        for id in [own, inherited, dex] {
+            db.toggle_record(id, true).unwrap();
+        }
+        db.set_record_hours(own, Some(22), Some(6)).unwrap();
+        db.set_provider_record_hours("yahoo", Some(9), Some(17)).unwrap();
+
+        let targets = db.read_record_targets().unwrap();
+        let yahoo = &targets["yahoo"];
+        assert_eq!(yahoo.len(), 2);
+        assert!(!yahoo.contains_key("TSLA"), "subscription {} does not record", off);
+        let aapl = yahoo["AAPL"];
+        assert_eq!((aapl.subscription_id, aapl.from_hour, aapl.to_hour), (own, 22, 6));
+        assert!(aapl.in_window(23) && aapl.in_window(5) && !aapl.in_window(12));
+        assert_eq!((yahoo["MSFT"].from_hour, yahoo["MSFT"].to_hour), (9, 17));
+        let pool = targets["subgraph"]["uniswap_v3:0xpool:0xa:0xb"];
+        assert!(pool.is_dex && pool.in_window(0) && pool.in_window(23));
+    }
+
+    #[test]
+    fn record_hours_report_effective_window_and_clear() {
+        let db = DbPool::open(&PathBuf::from(":memory:")).unwrap();
+        let id = db
+            .add_subscription("asset", "AAPL", None, "yahoo", "stock", None, None, None)
+            .unwrap();
+        let hours = db.get_record_hours(id).unwrap();
+        assert_eq!((hours.effective_from_hour, hours.effective_to_hour, hours.source), (0, 24, RecordHoursSource::AllDay));
+        assert_eq!(hours.record_enabled, Some(false));
+
+        db.set_provider_record_hours("yahoo", Some(9), Some(17)).unwrap();
+        db.set_record_hours(id, Some(22), Some(6)).unwrap();
+        let hours = db.get_record_hours(id).unwrap();
+        assert_eq!((hours.provider_from_hour, hours.provider_to_hour), (Some(9), Some(17)));
+        assert_eq!((hours.effective_from_hour, hours.effective_to_hour, hours.source), (22, 6, RecordHoursSource::Subscription));
+
+        // 只設定一端的訂閱時段不生效，退回 provider 設定
+        db.set_record_hours(id, Some(22), None).unwrap();
+        assert_eq!(db.get_record_hours(id).unwrap().source, RecordHoursSource::Provider);
+
+        db.clear_provider_record_hours("yahoo").unwrap();
+        let provider = db.get_provider_record_hours("yahoo").unwrap();
+        assert_eq!((provider.subscription_id, provider.source), (None, RecordHoursSource::AllDay));
+        db.clear_provider_record_hours("binance").unwrap();
+        assert!(db.get_provider_record_hours("binance").unwrap().provider_from_hour.is_none());
+        assert!(db.get_record_hours(id + 100).is_err());
+    }
+
+    #[test]
+    fn provider_params_are_keyed_by_polling_symbol() {
+        let db = DbPool::open(&PathBuf::from(":memory:")).unwrap();
+        let stock = db
+            .add_subscription("asset", "AAPL", None, "polygon", "stock", None, None, None)
+            .unwrap();
+        let dex = db
+            .add_subscription("dex", "ETH/USDC", None, "subgraph", "crypto", Some("uniswap_v3:0xpool"), Some("0xa"), Some("0xb"))
+            .unwrap();
+        db.set_provider_params(stock, Some(r#"{"adjusted": false}"#)).unwrap();
+        db.set_provider_params(dex, Some(r#"{"fee_tier": 500}"#)).unwrap();
+        assert!(db.set_provider_params(stock, Some("[1, 2]")).is_err());
+
+        let polygon = db.read_provider_params("polygon").unwrap();
+        assert_eq!(polygon["AAPL"]["adjusted"], false);
+        let subgraph = db.read_provider_params("subgraph").unwrap();
+        assert_eq!(subgraph["uniswap_v3:0xpool:0xa:0xb"]["fee_tier"], 500);
+
+        db.set_provider_params(stock, Some("{}")).unwrap();
+        assert!(db.read_provider_params("polygon").unwrap().is_empty());
+        assert_eq!(db.list_all_subscriptions().unwrap()[0].provider_params, None);
+    }
 }
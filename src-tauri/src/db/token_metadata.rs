@@ -0,0 +1,77 @@
+use rusqlite::params;
+
+use super::busy::{db_error, DbResult};
+use super::{DbPool, TokenMetadata};
+
+impl DbPool {
+    // ── Token Metadata ──────────────────────────────────────────
+
+    /// 新增或更新 token metadata（以 chain + address 為 key）
+    pub fn upsert_token_metadata(&self, tokens: &[TokenMetadata]) -> DbResult<()> {
+        let now = chrono::Utc::now().timestamp();
+        let tx = self.begin_write().map_err(|e| db_error("Failed to save token metadata", e))?;
+        {
+            let mut stmt = tx
+                .prepare(
+                    "INSERT INTO token_metadata (chain, address, symbol, decimals, updated_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5)
+                     ON CONFLICT(chain, address) DO UPDATE SET
+                        symbol = COALESCE(excluded.symbol, token_metadata.symbol),
+                        decimals = excluded.decimals,
+                        updated_at = excluded.updated_at",
+                )
+                .map_err(|e| format!("Failed to save token metadata: {}", e))?;
+            for token in tokens {
+                stmt.execute(params![token.chain, token.address, token.symbol, token.decimals, now])
+                    .map_err(|e| format!("Failed to save token metadata: {}", e))?;
+            }
+        }
+        tx.commit()
+            .map_err(|e| db_error("Failed to save token metadata", e))
+    }
+
+    /// 讀取指定鏈的所有 token metadata
+    pub fn load_token_metadata(&self, chain: &str) -> Result<Vec<TokenMetadata>, String> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT chain, address, symbol, decimals FROM token_metadata WHERE chain = ?1")
+            .map_err(|e| e.to_string())?;
+        let tokens = stmt
+            .query_map(params![chain], |row| {
+                Ok(TokenMetadata {
+                    chain: row.get(0)?,
+                    address: row.get(1)?,
+                    symbol: row.get(2)?,
+                    decimals: row.get(3)?,
+                })
+            })
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        Ok(tokens)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn upsert_keeps_known_symbol_and_filters_by_chain() {
+        let db = DbPool::open(&PathBuf::from(":memory:")).unwrap();
+        let token = |chain: &str, symbol: Option<&str>, decimals: u32| TokenMetadata {
+            chain: chain.to_string(),
+            address: "mint1".to_string(),
+            symbol: symbol.map(str::to_string),
+            decimals,
+        };
+        db.upsert_token_metadata(&[token("solana", Some("BONK"), 5), token("ethereum", None, 18)])
+            .unwrap();
+        // 只查到 decimals 時不覆蓋已知的 symbol
+        db.upsert_token_metadata(&[token("solana", None, 6)]).unwrap();
+
+        assert_eq!(db.load_token_metadata("solana").unwrap(), vec![token("solana", Some("BONK"), 6)]);
+        assert_eq!(db.load_token_metadata("ethereum").unwrap(), vec![token("ethereum", None, 18)]);
+    }
+}
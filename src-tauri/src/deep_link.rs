@@ -0,0 +1,259 @@
+//! `stockenboard://` deep link — 讓瀏覽器 bookmarklet 或外部工具新增訂閱、開啟指定頁面。
+//!
+//! - `stockenboard://add?provider=binance&symbol=BTCUSDT[&name=…][&type=crypto|stock]`
+//! - `stockenboard://view?id=3` 或 `stockenboard://view?name=Crypto[&type=asset|dex]`
+//!
+//! 解析與套用與平台無關（HTTP API 的 `POST /system/deep-link` 也使用）；
+//! desktop 由 deep-link plugin 收到 URL 後呼叫 [`dispatch`]。OS 層級的連結不經過 command 的
+//! kiosk 檢查，鎖定中由 [`check_kiosk`] 拒絕新增訂閱（開啟頁面不修改資料，照常允許）。
+
+use serde::Serialize;
+
+use crate::core_state::CoreState;
+use crate::i18n::{tr, Msg};
+use crate::providers::{get_provider_info, normalize_symbol};
+
+pub const SCHEME: &str = "stockenboard";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeepLinkAction {
+    AddSubscription {
+        provider_id: String,
+        symbol: String,
+        display_name: Option<String>,
+        asset_type: String,
+    },
+    OpenView {
+        id: Option<i64>,
+        name: Option<String>,
+        view_type: String,
+    },
+}
+
+/// 套用後的結果 — desktop 轉為前端事件，HTTP API 直接回傳
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum DeepLinkOutcome {
+    SubscriptionAdded {
+        id: i64,
+        sub_type: String,
+        symbol: String,
+        provider_id: String,
+    },
+    OpenView {
+        view_id: i64,
+        view_type: String,
+    },
+}
+
+pub fn parse(url: &str) -> Result<DeepLinkAction, String> {
+    let parsed = reqwest::Url::parse(url.trim()).map_err(|e| format!("Invalid deep link: {}", e))?;
+    if parsed.scheme() != SCHEME {
+        return Err(format!("Unsupported URL scheme: {}", parsed.scheme()));
+    }
+    let param = |key: &str| {
+        parsed
+            .query_pairs()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+
+    match parsed.host_str().unwrap_or_default() {
+        "add" => {
+            let provider_id = param("provider").ok_or("Deep link is missing 'provider'")?;
+            let symbol = param("symbol").ok_or("Deep link is missing 'symbol'")?;
+            let info = get_provider_info(&provider_id)
+                .ok_or_else(|| tr(Msg::ProviderNotFound, &[&provider_id]))?;
+            let asset_type = match param("type") {
+                Some(t) if t == "crypto" || t == "stock" => t,
+                Some(t) => {
+                    return Err(format!("Invalid asset type '{}' (expected crypto or stock)", t));
+                }
+                None => match info.provider_type.as_str() {
+                    "crypto" | "stock" => info.provider_type.clone(),
+                    "both" => {
+                        return Err(format!(
+                            "Provider {} supports crypto and stock, add '&type=crypto' or '&type=stock'",
+                            provider_id
+                        ));
+                    }
+                    _ => return Err(format!("Provider {} cannot be added via deep link", provider_id)),
+                },
+            };
+            if info.provider_type != asset_type && info.provider_type != "both" {
+                return Err(format!(
+                    "Provider {} does not support {} symbols",
+                    provider_id, asset_type
+                ));
+            }
+            Ok(DeepLinkAction::AddSubscription {
+                provider_id,
+                symbol,
+                display_name: param("name"),
+                asset_type,
+            })
+        }
+        "view" => {
+            let id = param("id")
+                .map(|v| v.parse::<i64>().map_err(|_| format!("Invalid view id: {}", v)))
+                .transpose()?;
+            let name = param("name");
+            if id.is_none() && name.is_none() {
+                return Err("Deep link needs a view 'id' or 'name'".to_string());
+            }
+            let view_type = param("type").unwrap_or_else(|| "asset".to_string());
+            if view_type != "asset" && view_type != "dex" {
+                return Err(format!("Invalid view type '{}' (expected asset or dex)", view_type));
+            }
+            Ok(DeepLinkAction::OpenView { id, name, view_type })
+        }
+        other => Err(format!("Unknown deep link action: {}", other)),
+    }
+}
+
+/// kiosk 鎖定中拒絕會修改資料的 deep link
+pub fn check_kiosk(action: DeepLinkAction, locked: bool) -> Result<DeepLinkAction, String> {
+    match action {
+        DeepLinkAction::AddSubscription { .. } if locked => Err(tr(Msg::KioskLocked, &[])),
+        action => Ok(action),
+    }
+}
+
+/// 套用 deep link：新增訂閱（並重新載入 polling）或解析要開啟的頁面
+pub fn apply(state: &CoreState, action: DeepLinkAction) -> Result<DeepLinkOutcome, String> {
+    match action {
+        DeepLinkAction::AddSubscription {
+            provider_id,
+            symbol,
+            display_name,
+            asset_type,
+        } => {
+            let symbol = normalize_symbol(&symbol, &asset_type);
+            let id = state.db.add_subscription(
+                "asset",
+                &symbol,
+                display_name.as_deref(),
+                &provider_id,
+                &asset_type,
+                None,
+                None,
+                None,
+            )?;
+            state.polling.reload();
+            Ok(DeepLinkOutcome::SubscriptionAdded {
+                id,
+                sub_type: "asset".to_string(),
+                symbol,
+                provider_id,
+            })
+        }
+        DeepLinkAction::OpenView { id, name, view_type } => {
+            let views = state.db.list_views(&view_type)?;
+            let view = views
+                .iter()
+                .find(|v| match (id, name.as_deref()) {
+                    (Some(id), _) => v.id == id,
+                    (None, Some(name)) => v.name.trim().eq_ignore_ascii_case(name),
+                    (None, None) => false,
+                })
+                .ok_or_else(|| tr(Msg::ViewNotFound, &[]))?;
+            Ok(DeepLinkOutcome::OpenView {
+                view_id: view.id,
+                view_type: view.view_type.clone(),
+            })
+        }
+    }
+}
+
+/// Desktop：處理 deep-link plugin 收到的 URL，結果以前端事件送出，錯誤以系統通知提示
+#[cfg(feature = "desktop")]
+pub fn dispatch(app: &tauri::AppHandle, url: &str) {
+    use std::sync::Arc;
+    use tauri::{Emitter, Manager};
+
+    let Some(state) = app.try_state::<Arc<CoreState>>() else {
+        return;
+    };
+    tracing::info!("[DeepLink] {}", url);
+    let locked = crate::kiosk::kiosk_state().enabled;
+    match parse(url)
+        .and_then(|action| check_kiosk(action, locked))
+        .and_then(|action| apply(&state, action))
+    {
+        Ok(DeepLinkOutcome::SubscriptionAdded { sub_type, symbol, provider_id, .. }) => {
+            let _ = app.emit(
+                "subscriptions-changed",
+                &serde_json::json!({ "sub_type": sub_type }),
+            );
+            let _ = state.event_bus.send(crate::events::AppEvent::SystemNotification {
+                title: "StockenBoard".to_string(),
+                body: tr(Msg::SubscriptionAdded, &[&symbol, &provider_id]),
+            });
+        }
+        Ok(DeepLinkOutcome::OpenView { view_id, view_type }) => {
+            crate::tray::show_main_window(app);
+            let _ = app.emit(
+                "open-view",
+                &serde_json::json!({ "view_id": view_id, "view_type": view_type }),
+            );
+        }
+        Err(e) => {
+            tracing::warn!("[DeepLink] {}: {}", url, e);
+            let _ = state.event_bus.send(crate::events::AppEvent::SystemNotification {
+                title: "StockenBoard".to_string(),
+                body: e,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_add_and_view_links() {
+        assert_eq!(
+            parse("stockenboard://add?provider=binance&symbol=BTCUSDT&name=Bitcoin").unwrap(),
+            DeepLinkAction::AddSubscription {
+                provider_id: "binance".to_string(),
+                symbol: "BTCUSDT".to_string(),
+                display_name: Some("Bitcoin".to_string()),
+                asset_type: "crypto".to_string(),
+            }
+        );
+        assert_eq!(
+            parse("stockenboard://view?name=My%20Crypto").unwrap(),
+            DeepLinkAction::OpenView {
+                id: None,
+                name: Some("My Crypto".to_string()),
+                view_type: "asset".to_string(),
+            }
+        );
+        assert!(matches!(
+            parse("stockenboard://view?id=3&type=dex").unwrap(),
+            DeepLinkAction::OpenView { id: Some(3), .. }
+        ));
+    }
+
+    #[test]
+    fn parse_rejects_invalid_links() {
+        assert!(parse("https://add?provider=binance&symbol=BTC").is_err());
+        assert!(parse("stockenboard://add?provider=binance").is_err());
+        assert!(parse("stockenboard://add?provider=nope&symbol=BTC").is_err());
+        assert!(parse("stockenboard://add?provider=binance&symbol=AAPL&type=stock").is_err());
+        assert!(parse("stockenboard://view").is_err());
+        assert!(parse("stockenboard://view?id=abc").is_err());
+        assert!(parse("stockenboard://delete?id=1").is_err());
+    }
+
+    #[test]
+    fn kiosk_lock_rejects_adding_subscriptions() {
+        let add = parse("stockenboard://add?provider=binance&symbol=BTCUSDT").unwrap();
+        let view = parse("stockenboard://view?id=3").unwrap();
+        assert!(check_kiosk(add.clone(), true).is_err());
+        assert_eq!(check_kiosk(add.clone(), false).unwrap(), add);
+        assert_eq!(check_kiosk(view.clone(), true).unwrap(), view);
+    }
+}
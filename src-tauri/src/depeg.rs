@@ -0,0 +1,289 @@
+//! 穩定幣脫鉤監控 — 內建的監控預設，定期從多個交易所查詢主流穩定幣（USDT / USDC / DAI / FDUSD）
+//! 的美元報價，計算與 $1.00 的偏離，供前端顯示與脫鉤通知規則使用。
+//!
+//! 每個穩定幣取各來源報價的中位數作為代表價格，避免單一交易所的異常報價造成誤判；
+//! 代表價格偏離超過 `threshold_pct` 時標記為 `depegged`。
+//!
+//! 設定存於 app settings（`depeg_monitor_*`）。背景 task 在啟用監控或存在已啟用的脫鉤通知規則時
+//! 才會查詢，最新狀態存於記憶體快取並送出 `AppEvent::DepegUpdate`。
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, RwLock};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::db::DbPool;
+use crate::events::AppEvent;
+use crate::providers::registry::{PriceRequest, ProviderPrices, ProviderRegistry};
+use crate::providers::types::parse_crypto_symbol;
+
+/// 監控的穩定幣（依此順序輸出）
+pub const COINS: [&str; 4] = ["USDT", "USDC", "DAI", "FDUSD"];
+
+/// 報價來源：(穩定幣, provider, symbol)；只使用對美元（而非對 USDT）報價的交易對
+const SOURCES: &[(&str, &str, &str)] = &[
+    ("USDT", "coingecko", "tether"),
+    ("USDT", "kraken", "USDT-USD"),
+    ("USDT", "coinbase", "USDT-USD"),
+    ("USDC", "coingecko", "usd-coin"),
+    ("USDC", "kraken", "USDC-USD"),
+    ("USDC", "coinbase", "USDC-USD"),
+    ("DAI", "coingecko", "dai"),
+    ("DAI", "kraken", "DAI-USD"),
+    ("DAI", "coinbase", "DAI-USD"),
+    ("FDUSD", "coingecko", "first-digital-usd"),
+];
+
+/// 最短查詢間隔（秒），多個來源共用 provider rate limit
+pub const MIN_INTERVAL_SECS: u64 = 15;
+
+/// 單一來源的報價
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DepegQuote {
+    pub provider_id: String,
+    pub symbol: String,
+    pub price: f64,
+    /// 與 $1.00 的偏離（%），正值 = 溢價
+    pub deviation_pct: f64,
+}
+
+/// 單一穩定幣的脫鉤狀態；`deviation_pct` 為脫鉤規則比較的值
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DepegStatus {
+    pub coin: String,
+    /// 各來源報價的中位數
+    pub price: f64,
+    pub deviation_pct: f64,
+    /// 偏離絕對值是否超過設定的閾值
+    pub depegged: bool,
+    pub quotes: Vec<DepegQuote>,
+    /// 查詢時間（Unix ms）
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DepegConfig {
+    /// 即使沒有脫鉤通知規則也持續監控
+    pub enabled: bool,
+    pub interval_secs: u64,
+    /// 標記為脫鉤的偏離閾值（%，絕對值）
+    pub threshold_pct: f64,
+}
+
+impl Default for DepegConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: 60,
+            threshold_pct: 0.5,
+        }
+    }
+}
+
+impl DepegConfig {
+    /// 檢查設定
+    pub fn normalized(self) -> Result<Self, String> {
+        if self.interval_secs < MIN_INTERVAL_SECS {
+            return Err(format!("Depeg monitor interval must be at least {} seconds", MIN_INTERVAL_SECS));
+        }
+        if !self.threshold_pct.is_finite() || self.threshold_pct <= 0.0 {
+            return Err("Depeg threshold must be a positive percentage".to_string());
+        }
+        Ok(self)
+    }
+}
+
+static DEPEG_CONFIG: LazyLock<RwLock<DepegConfig>> = LazyLock::new(|| RwLock::new(DepegConfig::default()));
+static LATEST: LazyLock<RwLock<Vec<DepegStatus>>> = LazyLock::new(|| RwLock::new(Vec::new()));
+/// 是否存在已啟用的脫鉤通知規則（由 NotificationEngine 重新載入規則時更新）
+static RULES_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+pub fn set_depeg_config(config: DepegConfig) {
+    *DEPEG_CONFIG.write().unwrap_or_else(|e| e.into_inner()) = config;
+}
+
+pub fn depeg_config() -> DepegConfig {
+    DEPEG_CONFIG.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+pub fn set_rules_active(active: bool) {
+    RULES_ACTIVE.store(active, Ordering::Relaxed);
+}
+
+/// 快取中各穩定幣的最新狀態（依 [`COINS`] 排序）
+pub fn latest() -> Vec<DepegStatus> {
+    LATEST.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// 推斷訂閱對應的穩定幣，決定脫鉤規則比較哪個穩定幣的狀態
+///
+/// 先比對內建來源（如 coingecko `tether`），再比對 symbol 本身或其 base asset（如 `USDC-USD`）。
+pub fn coin_for(provider_id: &str, symbol: &str) -> Option<&'static str> {
+    if let Some((coin, _, _)) = SOURCES
+        .iter()
+        .find(|(_, p, s)| *p == provider_id && s.eq_ignore_ascii_case(symbol))
+    {
+        return Some(coin);
+    }
+    let upper = symbol.trim().to_uppercase();
+    // 先比對完整 symbol，避免 "FDUSD" 被拆成 FD + USD
+    if let Some(coin) = COINS.iter().find(|c| **c == upper) {
+        return Some(coin);
+    }
+    let (base, _) = parse_crypto_symbol(&upper);
+    COINS.iter().find(|c| **c == base).copied()
+}
+
+fn deviation_pct(price: f64) -> f64 {
+    (price - 1.0) * 100.0
+}
+
+fn median(prices: &mut [f64]) -> f64 {
+    prices.sort_by(|a, b| a.total_cmp(b));
+    let mid = prices.len() / 2;
+    if prices.len().is_multiple_of(2) {
+        (prices[mid - 1] + prices[mid]) / 2.0
+    } else {
+        prices[mid]
+    }
+}
+
+/// 依來源表把各 provider 的回應整理成每個穩定幣的狀態；沒有任何有效報價的穩定幣略過
+fn build_statuses(results: &[ProviderPrices], threshold_pct: f64, updated_at: i64) -> Vec<DepegStatus> {
+    COINS
+        .iter()
+        .filter_map(|coin| {
+            let quotes: Vec<DepegQuote> = SOURCES
+                .iter()
+                .filter(|(c, _, _)| c == coin)
+                .filter_map(|(_, provider_id, symbol)| {
+                    let asset = results
+                        .iter()
+                        .filter(|r| r.provider_id == *provider_id)
+                        .flat_map(|r| &r.data)
+                        .find(|a| a.symbol.eq_ignore_ascii_case(symbol))?;
+                    (asset.price.is_finite() && asset.price > 0.0).then(|| DepegQuote {
+                        provider_id: provider_id.to_string(),
+                        symbol: symbol.to_string(),
+                        price: asset.price,
+                        deviation_pct: deviation_pct(asset.price),
+                    })
+                })
+                .collect();
+            if quotes.is_empty() {
+                return None;
+            }
+            let price = median(&mut quotes.iter().map(|q| q.price).collect::<Vec<_>>());
+            let deviation_pct = deviation_pct(price);
+            Some(DepegStatus {
+                coin: coin.to_string(),
+                price,
+                deviation_pct,
+                depegged: deviation_pct.abs() > threshold_pct,
+                quotes,
+                updated_at,
+            })
+        })
+        .collect()
+}
+
+/// 查詢所有來源並更新快取，送出 `AppEvent::DepegUpdate`
+pub async fn poll_once(
+    registry: &ProviderRegistry,
+    db: &DbPool,
+    event_bus: &broadcast::Sender<AppEvent>,
+) -> Vec<DepegStatus> {
+    let mut requests: Vec<PriceRequest> = Vec::new();
+    for (_, provider_id, symbol) in SOURCES {
+        match requests.iter_mut().find(|r| r.provider_id == *provider_id) {
+            Some(req) => req.symbols.push(symbol.to_string()),
+            None => requests.push(PriceRequest {
+                provider_id: provider_id.to_string(),
+                symbols: vec![symbol.to_string()],
+            }),
+        }
+    }
+    let results = registry.fetch_multi(&requests, db).await;
+    for result in &results {
+        if let Some(e) = &result.error {
+            tracing::warn!("[Depeg] {} fetch failed: {}", result.provider_id, e);
+        }
+    }
+
+    let statuses = build_statuses(
+        &results,
+        depeg_config().threshold_pct,
+        chrono::Utc::now().timestamp_millis(),
+    );
+    if !statuses.is_empty() {
+        *LATEST.write().unwrap_or_else(|e| e.into_inner()) = statuses.clone();
+        let _ = event_bus.send(AppEvent::DepegUpdate(statuses.clone()));
+    }
+    statuses
+}
+
+/// 啟動背景脫鉤監控 task；每輪重新讀取設定，省電模式下間隔乘上倍率
+pub fn start(registry: Arc<ProviderRegistry>, db: Arc<DbPool>, event_bus: broadcast::Sender<AppEvent>) {
+    tokio::spawn(async move {
+        loop {
+            let config = depeg_config();
+            if config.enabled || RULES_ACTIVE.load(Ordering::Relaxed) {
+                poll_once(&registry, &db, &event_bus).await;
+            }
+            let secs = config.interval_secs.max(MIN_INTERVAL_SECS) as f64
+                * crate::power::interval_multiplier();
+            tokio::time::sleep(Duration::from_secs_f64(secs)).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::types::AssetDataBuilder;
+
+    fn prices(provider_id: &str, quotes: &[(&str, f64)]) -> ProviderPrices {
+        ProviderPrices {
+            provider_id: provider_id.to_string(),
+            data: quotes
+                .iter()
+                .map(|(symbol, price)| AssetDataBuilder::new(symbol, provider_id).price(*price).build())
+                .collect(),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn statuses_use_median_price_across_sources() {
+        let results = vec![
+            prices("coingecko", &[("tether", 0.9990), ("usd-coin", 0.9700)]),
+            prices("kraken", &[("USDT-USD", 1.0002), ("USDC-USD", 0.9750)]),
+            prices("coinbase", &[("USDT-USD", 0.9996), ("USDC-USD", 0.0)]),
+        ];
+        let statuses = build_statuses(&results, 0.5, 0);
+        assert_eq!(statuses.len(), 2);
+
+        let usdt = &statuses[0];
+        assert_eq!((usdt.coin.as_str(), usdt.price, usdt.quotes.len()), ("USDT", 0.9996, 3));
+        assert!(!usdt.depegged);
+
+        // 無效報價（0）不列入，偶數筆取平均
+        let usdc = &statuses[1];
+        assert_eq!(usdc.quotes.len(), 2);
+        assert!((usdc.price - 0.9725).abs() < 1e-9);
+        assert!((usdc.deviation_pct + 2.75).abs() < 1e-9);
+        assert!(usdc.depegged);
+    }
+
+    #[test]
+    fn resolves_subscription_coin() {
+        assert_eq!(coin_for("coingecko", "tether"), Some("USDT"));
+        assert_eq!(coin_for("coinbase", "USDC-USD"), Some("USDC"));
+        assert_eq!(coin_for("binance", "FDUSD"), Some("FDUSD"));
+        assert_eq!(coin_for("binance", "DAIUSDT"), Some("DAI"));
+        assert_eq!(coin_for("binance", "BTCUSDT"), None);
+        assert!(DepegConfig { threshold_pct: 0.0, ..Default::default() }.normalized().is_err());
+    }
+}
@@ -0,0 +1,156 @@
+//! 前端事件節流 — 每個 provider group 每輪都會送出完整的 `price-update` 與 `poll-tick`，
+//! provider 一多（例如 20 個、5 秒間隔）webview 會被大量事件塞滿。
+//!
+//! 桌面版的事件轉送迴圈把兩者先收進 `EventCoalescer`，每秒最多送出 `max_per_sec` 次：
+//! `price-update` 合併成一個陣列（同一 `provider:symbol` 只保留最新一筆），
+//! `poll-tick` 合併成陣列（每個 provider 只保留最新一筆）。第一個事件立即送出，
+//! 之後同一時間窗內的事件延到窗口結束時一起送出。`max_per_sec = 0` 停用節流，逐筆送出。
+//!
+//! 只影響 Tauri 事件；歷史紀錄、通知與 `/ws` 仍收到每一筆更新。
+//! 設定存於 app settings（`emit_max_per_sec`）。
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, RwLock};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::events::PollTickPayload;
+use crate::providers::AssetData;
+
+/// 每秒最多送出次數的上限
+pub const MAX_PER_SEC: u32 = 60;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmitConfig {
+    /// 每秒最多送出幾次合併後的事件；0 = 不節流
+    pub max_per_sec: u32,
+}
+
+impl Default for EmitConfig {
+    fn default() -> Self {
+        Self { max_per_sec: 4 }
+    }
+}
+
+impl EmitConfig {
+    /// 檢查設定
+    pub fn normalized(self) -> Result<Self, String> {
+        if self.max_per_sec > MAX_PER_SEC {
+            return Err(format!("Emit rate must be between 0 and {} per second", MAX_PER_SEC));
+        }
+        Ok(self)
+    }
+
+    /// 兩次送出的最小間隔；不節流時為 None
+    pub fn interval(&self) -> Option<Duration> {
+        (self.max_per_sec > 0).then(|| Duration::from_secs(1) / self.max_per_sec)
+    }
+}
+
+static EMIT_CONFIG: LazyLock<RwLock<EmitConfig>> = LazyLock::new(|| RwLock::new(EmitConfig::default()));
+
+pub fn set_emit_config(config: EmitConfig) {
+    *EMIT_CONFIG.write().unwrap_or_else(|e| e.into_inner()) = config;
+}
+
+pub fn emit_config() -> EmitConfig {
+    EMIT_CONFIG.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// 待送出的合併事件
+#[derive(Debug, Default)]
+pub struct EventCoalescer {
+    prices: Vec<AssetData>,
+    /// `provider:symbol` → `prices` 中的位置
+    price_index: HashMap<String, usize>,
+    ticks: Vec<PollTickPayload>,
+    last_flush: Option<Instant>,
+}
+
+impl EventCoalescer {
+    pub fn push_prices(&mut self, data: &[AssetData]) {
+        for asset in data {
+            let key = format!("{}:{}", asset.provider_id, asset.symbol);
+            match self.price_index.get(&key) {
+                Some(&i) => self.prices[i] = asset.clone(),
+                None => {
+                    self.price_index.insert(key, self.prices.len());
+                    self.prices.push(asset.clone());
+                }
+            }
+        }
+    }
+
+    pub fn push_tick(&mut self, tick: PollTickPayload) {
+        match self.ticks.iter_mut().find(|t| t.provider_id == tick.provider_id) {
+            Some(existing) => *existing = tick,
+            None => self.ticks.push(tick),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.prices.is_empty() && self.ticks.is_empty()
+    }
+
+    /// 下一次可送出的時間：距上次送出已滿 `interval` 時為 `now`
+    pub fn due(&self, now: Instant, interval: Duration) -> Instant {
+        self.last_flush.map_or(now, |last| (last + interval).max(now))
+    }
+
+    /// 取出待送出的價格與 tick，並記錄送出時間
+    pub fn take(&mut self, now: Instant) -> (Vec<AssetData>, Vec<PollTickPayload>) {
+        self.last_flush = Some(now);
+        self.price_index.clear();
+        (std::mem::take(&mut self.prices), std::mem::take(&mut self.ticks))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::AssetDataBuilder;
+
+    fn asset(provider: &str, symbol: &str, price: f64) -> AssetData {
+        AssetDataBuilder::new(symbol, provider).price(price).build()
+    }
+
+    fn tick(provider: &str, fetched_at: i64) -> PollTickPayload {
+        PollTickPayload { provider_id: provider.to_string(), fetched_at, interval_ms: 5_000 }
+    }
+
+    #[test]
+    fn merges_updates_keeping_latest_per_key() {
+        let mut coalescer = EventCoalescer::default();
+        coalescer.push_prices(&[asset("binance", "BTC", 1.0), asset("binance", "ETH", 2.0)]);
+        coalescer.push_prices(&[asset("binance", "BTC", 3.0), asset("okx", "BTC", 4.0)]);
+        coalescer.push_tick(tick("binance", 1));
+        coalescer.push_tick(tick("okx", 2));
+        coalescer.push_tick(tick("binance", 3));
+
+        let (prices, ticks) = coalescer.take(Instant::now());
+        let prices: Vec<_> = prices.iter().map(|a| (a.provider_id.as_str(), a.symbol.as_str(), a.price)).collect();
+        assert_eq!(prices, vec![("binance", "BTC", 3.0), ("binance", "ETH", 2.0), ("okx", "BTC", 4.0)]);
+        let ticks: Vec<_> = ticks.iter().map(|t| (t.provider_id.as_str(), t.fetched_at)).collect();
+        assert_eq!(ticks, vec![("binance", 3), ("okx", 2)]);
+        assert!(coalescer.is_empty());
+    }
+
+    #[test]
+    fn first_update_is_due_immediately_then_rate_limited() {
+        let config = EmitConfig { max_per_sec: 4 };
+        let interval = config.interval().unwrap();
+        assert_eq!(interval, Duration::from_millis(250));
+        assert_eq!(EmitConfig { max_per_sec: 0 }.interval(), None);
+        assert!(EmitConfig { max_per_sec: MAX_PER_SEC + 1 }.normalized().is_err());
+
+        let mut coalescer = EventCoalescer::default();
+        let start = Instant::now();
+        assert_eq!(coalescer.due(start, interval), start);
+        coalescer.take(start);
+        let soon = start + Duration::from_millis(100);
+        assert_eq!(coalescer.due(soon, interval), start + interval);
+        let later = start + Duration::from_secs(1);
+        assert_eq!(coalescer.due(later, interval), later);
+    }
+}
@@ -1,8 +1,17 @@
 /// AppEvent — 統一的應用程式事件類型
 /// 用於 Event Bus 解耦 Polling、DB 寫入、前端通知
+use crate::calendar::EconomicEvent;
+use crate::candles::Candle;
+use crate::db::RecordTargets;
+use crate::depeg::DepegStatus;
+use crate::gas::GasPrice;
 use crate::icons::DownloadProgress;
 use crate::providers::AssetData;
+use crate::record_health::RecordError;
+use crate::sanity::PriceAnomaly;
+use crate::watchdog::ProviderRecovered;
 use serde::Serialize;
+use std::sync::Arc;
 
 #[derive(Clone, Debug)]
 pub enum AppEvent {
@@ -10,8 +19,8 @@ pub enum AppEvent {
     PriceUpdate {
         provider_id: String,
         data: Vec<AssetData>,
-        /// 需要寫入歷史的 symbol 集合
-        record_symbols: Vec<String>,
+        /// 需要寫入歷史的 symbol 與其紀錄目標（polling 每次重新載入時解析）
+        record_targets: Arc<RecordTargets>,
     },
     /// 價格錯誤
     PriceError {
@@ -34,6 +43,26 @@ pub enum AppEvent {
     },
     /// Logo download progress — forwarded from download_all_logos broadcast channel
     LogoDownloadProgress(DownloadProgress),
+    /// 省電模式切換（電池供電 ↔ 接上電源）
+    PowerMode(PowerModePayload),
+    /// 系統從休眠恢復（推估休眠秒數）— WS 連線需重建
+    SystemResumed { slept_secs: i64 },
+    /// Gas 價格更新（gas tracker 每條鏈一筆）
+    GasUpdate(GasPrice),
+    /// 穩定幣脫鉤狀態更新（depeg monitor 每輪一次，含所有穩定幣）
+    DepegUpdate(Vec<DepegStatus>),
+    /// 經濟日曆即將到來的事件（每次抓取後，及事件通知規則啟用期間定期送出）
+    CalendarUpdate(Vec<EconomicEvent>),
+    /// Polling 合理性檢查拒絕的異常報價
+    PriceAnomaly(PriceAnomaly),
+    /// Watchdog 探測成功、已恢復輪詢的 provider
+    ProviderRecovered(ProviderRecovered),
+    /// 歷史紀錄寫入失敗或停滯
+    RecordError(RecordError),
+    /// WS 串流彙整的 1 分鐘 K 線收盤
+    CandleClose(Candle),
+    /// WS 連線狀態變化
+    WsStatus(crate::ws_manager::WsStatus),
 }
 
 /// 前端事件用的通知觸發 payload（規則觸發即時推送到 UI）
@@ -59,3 +88,12 @@ pub struct PollTickPayload {
     pub fetched_at: i64,
     pub interval_ms: u64,
 }
+
+/// 前端事件用的省電模式 payload
+#[derive(Debug, Clone, Serialize)]
+pub struct PowerModePayload {
+    pub on_battery: bool,
+    pub low_power: bool,
+    /// 目前套用的 polling 間隔倍率
+    pub interval_multiplier: f64,
+}
@@ -0,0 +1,149 @@
+//! 本機檔案讀取的白名單 — `read_local_file_base64`（與 HTTP API 的 `GET /system/read-file`）
+//! 只能讀取 app 資料目錄下的 icons / theme_bg，或使用者在檔案對話框中明確選取過的檔案。
+//!
+//! 路徑一律先 canonicalize 再比對，因此 `..` 與 symlink 無法跳出允許的目錄。
+
+use std::collections::HashSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
+
+/// 讀取失敗的原因；HTTP API 依此回 403 / 404，指令回 `forbidden` / `not_found`
+#[derive(Debug)]
+pub enum FileAccessError {
+    /// 不在允許的目錄內，也不是使用者選取過的檔案
+    Denied(PathBuf),
+    /// 檔案不存在或無法讀取
+    Io(std::io::Error),
+}
+
+impl fmt::Display for FileAccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Denied(path) => write!(f, "Access denied: {}", path.display()),
+            Self::Io(e) => write!(f, "Failed to read file: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for FileAccessError {}
+
+#[derive(Debug, Default)]
+pub struct FileAccess {
+    /// 允許讀取的目錄（含子目錄）
+    roots: RwLock<Vec<PathBuf>>,
+    /// 使用者透過對話框選取過的檔案（canonical path）
+    picked: Mutex<HashSet<PathBuf>>,
+}
+
+impl FileAccess {
+    pub fn new(roots: impl IntoIterator<Item = PathBuf>) -> Self {
+        Self {
+            roots: RwLock::new(roots.into_iter().collect()),
+            picked: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// 新增允許讀取的目錄（desktop 的 theme_bg 位於 app_data_dir，於 setup 時加入）
+    pub fn allow_dir(&self, dir: PathBuf) {
+        let mut roots = self.roots.write().unwrap_or_else(|e| e.into_inner());
+        if !roots.contains(&dir) {
+            roots.push(dir);
+        }
+    }
+
+    /// 記錄使用者在對話框中選取的檔案
+    pub fn record_pick(&self, path: &Path) {
+        if let Ok(canonical) = path.canonicalize() {
+            self.picked
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(canonical);
+        }
+    }
+
+    /// 檢查 `path` 是否允許讀取，成功時回傳 canonical path
+    pub fn check(&self, path: &Path) -> Result<PathBuf, FileAccessError> {
+        let canonical = path.canonicalize().map_err(FileAccessError::Io)?;
+        let in_root = self
+            .roots
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            // 目錄不存在時 canonicalize 失敗，裡面自然也沒有可讀的檔案
+            .filter_map(|root| root.canonicalize().ok())
+            .any(|root| canonical.starts_with(root));
+        if in_root
+            || self
+                .picked
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .contains(&canonical)
+        {
+            Ok(canonical)
+        } else {
+            Err(FileAccessError::Denied(path.to_path_buf()))
+        }
+    }
+
+    /// 通過白名單檢查後讀取檔案，並回傳 base64 data URL
+    pub async fn read_data_url(&self, path: &str) -> Result<String, FileAccessError> {
+        let canonical = self.check(Path::new(path))?;
+        let bytes = tokio::fs::read(&canonical).await.map_err(FileAccessError::Io)?;
+        use base64::Engine;
+        let b64 = base64::engine::general_purpose::STANDARD.encode(&bytes);
+        let ext = canonical
+            .extension()
+            .map(|e| e.to_string_lossy().to_ascii_lowercase())
+            .unwrap_or_default();
+        let mime = match ext.as_str() {
+            "png" => "image/png",
+            "jpg" | "jpeg" => "image/jpeg",
+            "webp" => "image/webp",
+            "svg" => "image/svg+xml",
+            "gif" => "image/gif",
+            _ => "application/octet-stream",
+        };
+        Ok(format!("data:{};base64,{}", mime, b64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_allowed_dirs_and_picked_files_are_readable() {
+        let dir = tempfile::tempdir().unwrap();
+        let icons = dir.path().join("icons");
+        std::fs::create_dir_all(&icons).unwrap();
+        std::fs::write(icons.join("btc.png"), b"png").unwrap();
+        let outside = dir.path().join("secret.txt");
+        std::fs::write(&outside, b"secret").unwrap();
+
+        let access = FileAccess::new([icons.clone(), dir.path().join("theme_bg")]);
+        assert!(access.check(&icons.join("btc.png")).is_ok());
+        assert!(matches!(access.check(&outside), Err(FileAccessError::Denied(_))));
+        // `..` 會被 canonicalize 解析，無法跳出 icons
+        assert!(matches!(
+            access.check(&icons.join("..").join("secret.txt")),
+            Err(FileAccessError::Denied(_))
+        ));
+        assert!(matches!(access.check(&icons.join("missing.png")), Err(FileAccessError::Io(_))));
+
+        access.record_pick(&outside);
+        assert!(access.check(&outside).is_ok());
+    }
+
+    #[tokio::test]
+    async fn read_data_url_uses_extension_mime() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.SVG"), b"<svg/>").unwrap();
+        let access = FileAccess::new([dir.path().to_path_buf()]);
+        let url = access
+            .read_data_url(&dir.path().join("a.SVG").to_string_lossy())
+            .await
+            .unwrap();
+        assert!(url.starts_with("data:image/svg+xml;base64,"));
+    }
+}
@@ -0,0 +1,351 @@
+//! 訂閱與價格歷史的檔案匯出 — 依 `format` 輸出 JSON、CSV（正確跳脫）或真正的 .xlsx。
+//!
+//! 資料先整理成 [`Table`]（標題列 + 儲存格），再交由各格式的 writer 輸出，
+//! 因此 desktop 的 `export_file` 與 HTTP API 的 `POST /data/export-file` 共用同一份邏輯。
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::db::DbPool;
+
+/// 單次匯出的歷史筆數上限（.xlsx 每個工作表最多 1,048,576 列）
+const MAX_HISTORY_ROWS: i64 = 1_000_000;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Json,
+    Csv,
+    Xlsx,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Csv => "csv",
+            Self::Xlsx => "xlsx",
+        }
+    }
+
+    pub fn filter_name(self) -> &'static str {
+        match self {
+            Self::Json => "JSON",
+            Self::Csv => "CSV",
+            Self::Xlsx => "Excel Workbook",
+        }
+    }
+
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::Csv => "text/csv; charset=utf-8",
+            Self::Xlsx => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        }
+    }
+}
+
+/// 要匯出的資料集
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ExportDataset {
+    Subscriptions,
+    History {
+        subscription_ids: Vec<i64>,
+        #[serde(default)]
+        from_ts: Option<i64>,
+        #[serde(default)]
+        to_ts: Option<i64>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cell {
+    Text(String),
+    Number(f64),
+    Empty,
+}
+
+impl From<Option<String>> for Cell {
+    fn from(value: Option<String>) -> Self {
+        value.map_or(Cell::Empty, Cell::Text)
+    }
+}
+
+impl From<Option<f64>> for Cell {
+    fn from(value: Option<f64>) -> Self {
+        value.map_or(Cell::Empty, Cell::Number)
+    }
+}
+
+impl From<Option<i64>> for Cell {
+    fn from(value: Option<i64>) -> Self {
+        value.map_or(Cell::Empty, |v| Cell::Number(v as f64))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Table {
+    /// xlsx 工作表名稱
+    pub name: String,
+    pub headers: Vec<&'static str>,
+    pub rows: Vec<Vec<Cell>>,
+}
+
+pub fn subscriptions_table(db: &DbPool) -> Result<Table, String> {
+    let rows = db
+        .list_all_subscriptions()?
+        .into_iter()
+        .map(|s| {
+            vec![
+                Cell::Text(s.sub_type),
+                Cell::Text(s.symbol),
+                s.display_name.into(),
+                Cell::Text(s.selected_provider_id),
+                Cell::Text(s.asset_type),
+                Cell::Number(s.record_enabled as f64),
+                s.record_from_hour.into(),
+                s.record_to_hour.into(),
+                s.pool_address.into(),
+                s.token_from_address.into(),
+                s.token_to_address.into(),
+            ]
+        })
+        .collect();
+    Ok(Table {
+        name: "Subscriptions".to_string(),
+        headers: vec![
+            "sub_type",
+            "symbol",
+            "display_name",
+            "provider",
+            "asset_type",
+            "record",
+            "record_from_hour",
+            "record_to_hour",
+            "pool_address",
+            "token_from_address",
+            "token_to_address",
+        ],
+        rows,
+    })
+}
+
+/// `limit`：所有 subscription 合計的最大筆數
+pub fn history_table(
+    db: &DbPool,
+    subscription_ids: &[i64],
+    from_ts: Option<i64>,
+    to_ts: Option<i64>,
+    limit: i64,
+) -> Result<Table, String> {
+    let symbols: HashMap<i64, String> = db
+        .list_all_subscriptions()?
+        .into_iter()
+        .map(|s| (s.id, s.symbol))
+        .collect();
+    let mut rows = Vec::new();
+    for sid in subscription_ids {
+        let remaining = limit - rows.len() as i64;
+        if remaining <= 0 {
+            break;
+        }
+        for r in db.get_price_history(*sid, from_ts, to_ts, remaining)? {
+            let time = chrono::DateTime::from_timestamp(r.recorded_at, 0)
+                .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string());
+            rows.push(vec![
+                symbols.get(sid).cloned().into(),
+                Cell::Text(r.provider_id),
+                time.into(),
+                Cell::Number(r.recorded_at as f64),
+                Cell::Number(r.price),
+                r.change_pct.into(),
+                r.volume.into(),
+                r.pre_price.into(),
+                r.post_price.into(),
+            ]);
+        }
+    }
+    Ok(Table {
+        name: "History".to_string(),
+        headers: vec![
+            "symbol",
+            "provider",
+            "time_utc",
+            "timestamp",
+            "price",
+            "change_pct",
+            "volume",
+            "pre_price",
+            "post_price",
+        ],
+        rows,
+    })
+}
+
+/// JSON：每列輸出為以標題為 key 的物件
+pub fn to_json(table: &Table) -> Result<Vec<u8>, String> {
+    let rows: Vec<serde_json::Map<String, serde_json::Value>> = table
+        .rows
+        .iter()
+        .map(|row| {
+            table
+                .headers
+                .iter()
+                .zip(row)
+                .map(|(header, cell)| {
+                    let value = match cell {
+                        Cell::Text(s) => serde_json::Value::from(s.as_str()),
+                        Cell::Number(n) => serde_json::Value::from(*n),
+                        Cell::Empty => serde_json::Value::Null,
+                    };
+                    (header.to_string(), value)
+                })
+                .collect()
+        })
+        .collect();
+    serde_json::to_vec_pretty(&rows).map_err(|e| e.to_string())
+}
+
+/// CSV：加上 BOM 讓 Excel 以 UTF-8 開啟
+pub fn to_csv(table: &Table) -> Result<Vec<u8>, String> {
+    let mut bytes = b"\xEF\xBB\xBF".to_vec();
+    bytes.extend(to_csv_string(table)?.into_bytes());
+    Ok(bytes)
+}
+
+/// 不含 BOM 的 CSV 文字（供剪貼簿使用）；由 csv writer 處理引號、逗號與換行的跳脫
+pub fn to_csv_string(table: &Table) -> Result<String, String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer
+        .write_record(&table.headers)
+        .map_err(|e| e.to_string())?;
+    for row in &table.rows {
+        writer
+            .write_record(row.iter().map(|cell| match cell {
+                Cell::Text(s) => s.clone(),
+                Cell::Number(n) => n.to_string(),
+                Cell::Empty => String::new(),
+            }))
+            .map_err(|e| e.to_string())?;
+    }
+    let bytes = writer.into_inner().map_err(|e| e.to_string())?;
+    String::from_utf8(bytes).map_err(|e| e.to_string())
+}
+
+pub fn to_xlsx(table: &Table) -> Result<Vec<u8>, String> {
+    use rust_xlsxwriter::{Format, Workbook};
+
+    let xlsx_err = |e: rust_xlsxwriter::XlsxError| format!("Failed to write xlsx: {}", e);
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    sheet.set_name(&table.name).map_err(xlsx_err)?;
+    let bold = Format::new().set_bold();
+    for (col, header) in table.headers.iter().enumerate() {
+        sheet
+            .write_string_with_format(0, col as u16, *header, &bold)
+            .map_err(xlsx_err)?;
+    }
+    for (i, row) in table.rows.iter().enumerate() {
+        let r = i as u32 + 1;
+        for (col, cell) in row.iter().enumerate() {
+            match cell {
+                Cell::Text(s) => {
+                    sheet.write_string(r, col as u16, s).map_err(xlsx_err)?;
+                }
+                Cell::Number(n) => {
+                    sheet.write_number(r, col as u16, *n).map_err(xlsx_err)?;
+                }
+                Cell::Empty => {}
+            }
+        }
+    }
+    sheet.set_freeze_panes(1, 0).map_err(xlsx_err)?;
+    sheet.autofit();
+    workbook.save_to_buffer().map_err(xlsx_err)
+}
+
+/// 依資料集與格式產生檔案內容
+pub fn render(db: &DbPool, dataset: &ExportDataset, format: ExportFormat) -> Result<Vec<u8>, String> {
+    let table = match dataset {
+        ExportDataset::Subscriptions => subscriptions_table(db)?,
+        ExportDataset::History {
+            subscription_ids,
+            from_ts,
+            to_ts,
+        } => history_table(db, subscription_ids, *from_ts, *to_ts, MAX_HISTORY_ROWS)?,
+    };
+    match format {
+        ExportFormat::Json => to_json(&table),
+        ExportFormat::Csv => to_csv(&table),
+        ExportFormat::Xlsx => to_xlsx(&table),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Table {
+        Table {
+            name: "Sample".to_string(),
+            headers: vec!["symbol", "display_name", "price"],
+            rows: vec![
+                vec![
+                    Cell::Text("BRK-B".to_string()),
+                    Cell::Text("Berkshire, \"B\"\nshares".to_string()),
+                    Cell::Number(412.5),
+                ],
+                vec![Cell::Text("BTC".to_string()), Cell::Empty, Cell::Number(1.0)],
+            ],
+        }
+    }
+
+    #[test]
+    fn csv_escapes_quotes_commas_and_newlines() {
+        let bytes = to_csv(&sample()).unwrap();
+        let text = String::from_utf8(bytes).unwrap();
+        let text = text.trim_start_matches('\u{feff}');
+        assert_eq!(
+            text,
+            "symbol,display_name,price\nBRK-B,\"Berkshire, \"\"B\"\"\nshares\",412.5\nBTC,,1\n"
+        );
+
+        // 讀回後與原值一致
+        let mut reader = csv::Reader::from_reader(text.as_bytes());
+        let first = reader.records().next().unwrap().unwrap();
+        assert_eq!(&first[1], "Berkshire, \"B\"\nshares");
+    }
+
+    #[test]
+    fn xlsx_is_a_zip_workbook() {
+        let bytes = to_xlsx(&sample()).unwrap();
+        assert_eq!(&bytes[..2], b"PK");
+    }
+
+    #[test]
+    fn json_rows_keyed_by_header() {
+        let value: serde_json::Value = serde_json::from_slice(&to_json(&sample()).unwrap()).unwrap();
+        assert_eq!(value[0]["price"], 412.5);
+        assert!(value[1]["display_name"].is_null());
+    }
+
+    #[test]
+    fn history_table_includes_symbol_and_time() {
+        let db = DbPool::open(&std::path::PathBuf::from(":memory:")).unwrap();
+        let id = db
+            .add_subscription("asset", "BTC", None, "binance", "crypto", None, None, None)
+            .unwrap();
+        db.toggle_record(id, true).unwrap();
+        let targets = db.read_record_targets().unwrap().remove("binance").unwrap();
+        db.write_price_history("binance", &[("BTC".to_string(), 65000.0, Some(1.5), None, None, None, None, None, None, None, "USDT".to_string())], &targets);
+
+        let table = history_table(&db, &[id], None, None, 10).unwrap();
+        assert_eq!(table.rows.len(), 1);
+        assert_eq!(table.rows[0][0], Cell::Text("BTC".to_string()));
+        assert_eq!(table.rows[0][4], Cell::Number(65000.0));
+        assert!(matches!(table.rows[0][2], Cell::Text(_)));
+    }
+}
@@ -0,0 +1,369 @@
+//! Gas 價格追蹤 — 定期查詢 Ethereum gas price 與 Solana priority fee，供前端顯示與 gas 通知規則使用。
+//!
+//! - Ethereum：設定 Etherscan API key 時使用 gas oracle（safe / propose / fast + base fee），
+//!   否則以 RPC `eth_gasPrice` 取得單一報價；單位 gwei
+//! - Solana：RPC `getRecentPrioritizationFees`，取最近區塊 priority fee 的 25 / 50 / 75 百分位；
+//!   單位 micro-lamports / CU
+//!
+//! 設定存於 app settings（`gas_tracker_*`，Etherscan key 加密儲存）。背景 task 在啟用追蹤或存在
+//! 已啟用的 gas 通知規則時才會查詢，最新報價存於記憶體快取並送出 `AppEvent::GasUpdate`。
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, RwLock};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::broadcast;
+
+use crate::events::AppEvent;
+
+pub const DEFAULT_ETH_RPC_URL: &str = "https://ethereum-rpc.publicnode.com";
+pub const DEFAULT_SOLANA_RPC_URL: &str = "https://api.mainnet-beta.solana.com";
+const ETHERSCAN_GAS_ORACLE_URL: &str =
+    "https://api.etherscan.io/v2/api?chainid=1&module=gastracker&action=gasoracle";
+/// 最短查詢間隔（秒），避免對公共 RPC 過度請求
+pub const MIN_INTERVAL_SECS: u64 = 10;
+const WEI_PER_GWEI: f64 = 1e9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GasChain {
+    Ethereum,
+    Solana,
+}
+
+impl GasChain {
+    pub const ALL: [GasChain; 2] = [GasChain::Ethereum, GasChain::Solana];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ethereum => "ethereum",
+            Self::Solana => "solana",
+        }
+    }
+
+    /// 報價單位
+    pub fn unit(&self) -> &'static str {
+        match self {
+            Self::Ethereum => "gwei",
+            Self::Solana => "micro-lamports/CU",
+        }
+    }
+}
+
+impl FromStr for GasChain {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ethereum" => Ok(Self::Ethereum),
+            "solana" => Ok(Self::Solana),
+            _ => Err(()),
+        }
+    }
+}
+
+/// 單一鏈的 gas 報價；`standard` 為通知規則比較的值
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GasPrice {
+    pub chain: String,
+    pub unit: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub safe: Option<f64>,
+    pub standard: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fast: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_fee: Option<f64>,
+    /// 資料來源：`etherscan` / `rpc`
+    pub source: String,
+    /// 查詢時間（Unix ms）
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GasConfig {
+    /// 即使沒有 gas 通知規則也持續追蹤
+    pub enabled: bool,
+    pub interval_secs: u64,
+    /// Etherscan API key；空字串 = 改用 `eth_rpc_url` 的 `eth_gasPrice`
+    #[serde(default)]
+    pub etherscan_api_key: String,
+    #[serde(default)]
+    pub eth_rpc_url: String,
+    #[serde(default)]
+    pub solana_rpc_url: String,
+}
+
+impl Default for GasConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: 30,
+            etherscan_api_key: String::new(),
+            eth_rpc_url: DEFAULT_ETH_RPC_URL.to_string(),
+            solana_rpc_url: DEFAULT_SOLANA_RPC_URL.to_string(),
+        }
+    }
+}
+
+impl GasConfig {
+    /// 檢查並正規化設定（空白 RPC URL 還原為預設值）
+    pub fn normalized(self) -> Result<Self, String> {
+        if self.interval_secs < MIN_INTERVAL_SECS {
+            return Err(format!("Gas tracker interval must be at least {} seconds", MIN_INTERVAL_SECS));
+        }
+        let url = |value: String, default: &str| -> Result<String, String> {
+            let value = value.trim();
+            if value.is_empty() {
+                return Ok(default.to_string());
+            }
+            if !value.starts_with("https://") && !value.starts_with("http://") {
+                return Err(format!("RPC URL must start with http:// or https:// ({})", value));
+            }
+            Ok(value.to_string())
+        };
+        Ok(Self {
+            enabled: self.enabled,
+            interval_secs: self.interval_secs,
+            etherscan_api_key: self.etherscan_api_key.trim().to_string(),
+            eth_rpc_url: url(self.eth_rpc_url, DEFAULT_ETH_RPC_URL)?,
+            solana_rpc_url: url(self.solana_rpc_url, DEFAULT_SOLANA_RPC_URL)?,
+        })
+    }
+}
+
+static GAS_CONFIG: LazyLock<RwLock<GasConfig>> = LazyLock::new(|| RwLock::new(GasConfig::default()));
+static LATEST: LazyLock<RwLock<HashMap<GasChain, GasPrice>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+/// 是否存在已啟用的 gas 通知規則（由 NotificationEngine 重新載入規則時更新）
+static RULES_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+pub fn set_gas_config(config: GasConfig) {
+    *GAS_CONFIG.write().unwrap_or_else(|e| e.into_inner()) = config;
+}
+
+pub fn gas_config() -> GasConfig {
+    GAS_CONFIG.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+pub fn set_rules_active(active: bool) {
+    RULES_ACTIVE.store(active, Ordering::Relaxed);
+}
+
+/// 快取中各鏈的最新報價（依鏈固定排序）
+pub fn latest() -> Vec<GasPrice> {
+    let cache = LATEST.read().unwrap_or_else(|e| e.into_inner());
+    GasChain::ALL.iter().filter_map(|c| cache.get(c).cloned()).collect()
+}
+
+/// 推斷訂閱所在的鏈，決定 gas 規則比較哪條鏈的報價
+///
+/// Solana DEX provider（jupiter / raydium）→ solana；subgraph 依 symbol 的 chain 段（預設 ethereum）；
+/// 其他訂閱依 base asset：ETH → ethereum、SOL → solana。
+pub fn chain_for(provider_id: &str, symbol: &str) -> Option<GasChain> {
+    match provider_id {
+        "jupiter" | "raydium" => return Some(GasChain::Solana),
+        "subgraph" => {
+            // "protocol[:chain]:pool:tokenFrom:tokenTo"
+            let parts: Vec<&str> = symbol.split(':').collect();
+            let chain = if parts.len() == 5 { parts[1] } else { "ethereum" };
+            return GasChain::from_str(chain).ok();
+        }
+        _ => {}
+    }
+    let upper = symbol.to_uppercase();
+    let base = upper
+        .split(['-', '/', '_', ':', '.'])
+        .next()
+        .unwrap_or_default();
+    let base = ["USDT", "USDC", "BUSD", "USD", "EUR", "BTC"]
+        .iter()
+        .find_map(|quote| base.strip_suffix(quote).filter(|b| !b.is_empty()))
+        .unwrap_or(base);
+    match base {
+        "ETH" => Some(GasChain::Ethereum),
+        "SOL" => Some(GasChain::Solana),
+        _ => None,
+    }
+}
+
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+/// Etherscan gas oracle 的數值為 gwei 字串
+fn parse_etherscan_oracle(body: &Value) -> Result<GasPrice, String> {
+    if body["status"].as_str() != Some("1") {
+        let message = body["result"].as_str().or(body["message"].as_str()).unwrap_or("unknown error");
+        return Err(format!("Etherscan gas oracle error: {}", message));
+    }
+    let result = &body["result"];
+    let field = |key: &str| result[key].as_str().and_then(|v| v.parse::<f64>().ok());
+    Ok(GasPrice {
+        chain: GasChain::Ethereum.as_str().to_string(),
+        unit: GasChain::Ethereum.unit().to_string(),
+        safe: field("SafeGasPrice"),
+        standard: field("ProposeGasPrice").ok_or("Etherscan gas oracle response missing ProposeGasPrice")?,
+        fast: field("FastGasPrice"),
+        base_fee: field("suggestBaseFee"),
+        source: "etherscan".to_string(),
+        updated_at: now_ms(),
+    })
+}
+
+/// `eth_gasPrice` 回傳 hex wei
+fn parse_eth_gas_price(body: &Value) -> Result<GasPrice, String> {
+    let hex = body["result"]
+        .as_str()
+        .ok_or_else(|| format!("eth_gasPrice error: {}", body["error"]))?;
+    let wei = u128::from_str_radix(hex.trim_start_matches("0x"), 16)
+        .map_err(|e| format!("Invalid eth_gasPrice result '{}': {}", hex, e))?;
+    Ok(GasPrice {
+        chain: GasChain::Ethereum.as_str().to_string(),
+        unit: GasChain::Ethereum.unit().to_string(),
+        safe: None,
+        standard: wei as f64 / WEI_PER_GWEI,
+        fast: None,
+        base_fee: None,
+        source: "rpc".to_string(),
+        updated_at: now_ms(),
+    })
+}
+
+/// `getRecentPrioritizationFees` 回傳最近區塊的 priority fee，取 25 / 50 / 75 百分位
+fn parse_solana_priority_fees(body: &Value) -> Result<GasPrice, String> {
+    let entries = body["result"]
+        .as_array()
+        .ok_or_else(|| format!("getRecentPrioritizationFees error: {}", body["error"]))?;
+    let mut fees: Vec<f64> = entries
+        .iter()
+        .filter_map(|e| e["prioritizationFee"].as_f64())
+        .collect();
+    if fees.is_empty() {
+        return Err("getRecentPrioritizationFees returned no fees".to_string());
+    }
+    fees.sort_by(|a, b| a.total_cmp(b));
+    let percentile = |p: usize| fees[(fees.len() - 1) * p / 100];
+    Ok(GasPrice {
+        chain: GasChain::Solana.as_str().to_string(),
+        unit: GasChain::Solana.unit().to_string(),
+        safe: Some(percentile(25)),
+        standard: percentile(50),
+        fast: Some(percentile(75)),
+        base_fee: None,
+        source: "rpc".to_string(),
+        updated_at: now_ms(),
+    })
+}
+
+async fn get_json(request: reqwest::RequestBuilder, what: &str) -> Result<Value, String> {
+    request
+        .send()
+        .await
+        .map_err(|e| format!("{} request failed: {}", what, e))?
+        .error_for_status()
+        .map_err(|e| format!("{} request failed: {}", what, e))?
+        .json()
+        .await
+        .map_err(|e| format!("{} response parse failed: {}", what, e))
+}
+
+async fn rpc_call(url: &str, method: &str) -> Result<Value, String> {
+    let body = serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": []});
+    get_json(crate::providers::shared_client().post(url).json(&body), method).await
+}
+
+pub async fn fetch(chain: GasChain, config: &GasConfig) -> Result<GasPrice, String> {
+    match chain {
+        GasChain::Ethereum if !config.etherscan_api_key.is_empty() => {
+            let url = format!("{}&apikey={}", ETHERSCAN_GAS_ORACLE_URL, config.etherscan_api_key);
+            parse_etherscan_oracle(&get_json(crate::providers::shared_client().get(url), "Etherscan gas oracle").await?)
+        }
+        GasChain::Ethereum => parse_eth_gas_price(&rpc_call(&config.eth_rpc_url, "eth_gasPrice").await?),
+        GasChain::Solana => parse_solana_priority_fees(
+            &rpc_call(&config.solana_rpc_url, "getRecentPrioritizationFees").await?,
+        ),
+    }
+}
+
+/// 查詢所有鏈並更新快取，每筆成功的報價送出 `AppEvent::GasUpdate`
+pub async fn poll_once(event_bus: &broadcast::Sender<AppEvent>) {
+    let config = gas_config();
+    let (eth, sol) = tokio::join!(
+        fetch(GasChain::Ethereum, &config),
+        fetch(GasChain::Solana, &config)
+    );
+    for (chain, result) in [(GasChain::Ethereum, eth), (GasChain::Solana, sol)] {
+        match result {
+            Ok(price) => {
+                LATEST
+                    .write()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .insert(chain, price.clone());
+                let _ = event_bus.send(AppEvent::GasUpdate(price));
+            }
+            Err(e) => tracing::warn!("[Gas] {} gas price fetch failed: {}", chain.as_str(), e),
+        }
+    }
+}
+
+/// 啟動背景 gas 追蹤 task；每輪重新讀取設定，省電模式下間隔乘上倍率
+pub fn start(event_bus: broadcast::Sender<AppEvent>) {
+    tokio::spawn(async move {
+        loop {
+            let config = gas_config();
+            if config.enabled || RULES_ACTIVE.load(Ordering::Relaxed) {
+                poll_once(&event_bus).await;
+            }
+            let secs = config.interval_secs.max(MIN_INTERVAL_SECS) as f64
+                * crate::power::interval_multiplier();
+            tokio::time::sleep(Duration::from_secs_f64(secs)).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_gas_sources_into_common_units() {
+        let oracle = parse_etherscan_oracle(&serde_json::json!({
+            "status": "1",
+            "message": "OK",
+            "result": {"SafeGasPrice": "0.8", "ProposeGasPrice": "1.2", "FastGasPrice": "2", "suggestBaseFee": "0.75"}
+        }))
+        .unwrap();
+        assert_eq!((oracle.safe, oracle.standard, oracle.fast), (Some(0.8), 1.2, Some(2.0)));
+        assert_eq!(oracle.base_fee, Some(0.75));
+        assert!(parse_etherscan_oracle(&serde_json::json!({"status": "0", "result": "Invalid API Key"})).is_err());
+
+        // 12.5 gwei
+        let rpc = parse_eth_gas_price(&serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": "0x2e90edd00"})).unwrap();
+        assert_eq!(rpc.standard, 12.5);
+        assert_eq!(rpc.unit, "gwei");
+
+        let fees: Vec<Value> = [0, 100, 5000, 200, 1000]
+            .iter()
+            .enumerate()
+            .map(|(slot, fee)| serde_json::json!({"slot": slot, "prioritizationFee": fee}))
+            .collect();
+        let sol = parse_solana_priority_fees(&serde_json::json!({"result": fees})).unwrap();
+        assert_eq!((sol.safe, sol.standard, sol.fast), (Some(100.0), 200.0, Some(1000.0)));
+        assert!(parse_solana_priority_fees(&serde_json::json!({"result": []})).is_err());
+    }
+
+    #[test]
+    fn resolves_subscription_chain() {
+        assert_eq!(chain_for("binance", "ETHUSDT"), Some(GasChain::Ethereum));
+        assert_eq!(chain_for("coinbase", "ETH-USD"), Some(GasChain::Ethereum));
+        assert_eq!(chain_for("coingecko", "sol"), Some(GasChain::Solana));
+        assert_eq!(chain_for("binance", "ETHFIUSDT"), None);
+        assert_eq!(chain_for("raydium", "pool:a:b"), Some(GasChain::Solana));
+        assert_eq!(chain_for("subgraph", "uniswap_v3:0xpool:0xa:0xb"), Some(GasChain::Ethereum));
+        assert_eq!(chain_for("subgraph", "uniswap_v3:arbitrum:0xpool:0xa:0xb"), None);
+    }
+}
@@ -0,0 +1,117 @@
+//! Headless 模式（`--headless`）— 不建立視窗，只執行 polling、價格紀錄與 HTTP API。
+//!
+//! 適合在家用伺服器上當作資料收集器：polling 強制為 unattended（若啟用錄製排程，
+//! 則依排程時段切換），HTTP API 一律啟動（不看 `api_enabled` 設定）。
+//!
+//! 環境變數：
+//! - `SB_DATA_DIR` — 資料目錄（預設 `./data`，與 desktop 相同）
+//! - `SB_BIND`     — API 綁定位址（預設 `127.0.0.1`；要讓區網存取請設 `0.0.0.0`，或改用需要 token 的區網 listener，
+//!   見 [`crate::lan_listener`]）
+//! - `SB_PORT`     — API port（預設沿用 `api_port` 設定，再預設 8080）
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::api;
+use crate::core_state::CoreState;
+
+pub const HEADLESS_FLAG: &str = "--headless";
+
+/// 命令列是否帶有 `--headless`
+pub fn requested() -> bool {
+    std::env::args().skip(1).any(|a| a == HEADLESS_FLAG)
+}
+
+/// 執行 headless 模式直到收到 Ctrl+C / SIGTERM
+pub fn run() {
+    let runtime = tokio::runtime::Runtime::new().expect("Failed to start tokio runtime");
+    if let Err(e) = runtime.block_on(serve()) {
+        tracing::error!("[Headless] {}", e);
+        std::process::exit(1);
+    }
+}
+
+async fn serve() -> Result<(), String> {
+    let data_dir = std::env::var("SB_DATA_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from("./data"));
+    std::fs::create_dir_all(&data_dir).map_err(|e| {
+        format!("Failed to create data directory '{}': {}", data_dir.display(), e)
+    })?;
+
+    crate::logging::init(&data_dir.join("logs"));
+
+    let state = Arc::new(
+        CoreState::new(&data_dir).map_err(|e| format!("Failed to initialize core state: {}", e))?,
+    );
+
+    state.start_background_tasks().await;
+    crate::settings_sync::start(state.clone());
+    // desktop build 的紀錄由 event forwarder 負責；headless 沒有 forwarder，需自行啟動
+    //（非 desktop build 已在 start_background_tasks 中啟動）
+    #[cfg(feature = "desktop")]
+    state.start_price_recorder();
+
+    // 錄製排程啟用時由排程決定是否背景 polling
+    if !crate::schedule::load_schedule(&state.db).enabled {
+        state.polling.set_unattended(true).await;
+    }
+    state.polling.start(
+        state.db.clone(),
+        state.registry.clone(),
+        state.event_bus.clone(),
+    );
+
+    let bind = std::env::var("SB_BIND").unwrap_or_else(|_| "127.0.0.1".to_string());
+    let port = match std::env::var("SB_PORT") {
+        Ok(p) => p
+            .parse::<u16>()
+            .map_err(|_| format!("SB_PORT must be a valid port number, got: {}", p))?,
+        Err(_) => state
+            .db
+            .get_setting("api_port")
+            .ok()
+            .flatten()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(8080),
+    };
+
+    let addr = format!("{}:{}", bind, port);
+    let listener = tokio::net::TcpListener::bind(&addr)
+        .await
+        .map_err(|e| format!("Failed to bind to {}: {}", addr, e))?;
+    tracing::info!("[Headless] Polling + recording running, API on http://{}", addr);
+
+    api::restart_lan_listener(state.clone());
+
+    axum::serve(listener, api::build_router(state.clone()))
+        .with_graceful_shutdown(shutdown_signal())
+        .await
+        .map_err(|e| format!("Server error: {}", e))?;
+
+    state.save_price_snapshot().await;
+    Ok(())
+}
+
+/// Waits for a shutdown signal (Ctrl+C / SIGTERM).
+async fn shutdown_signal() {
+    let ctrl_c = tokio::signal::ctrl_c();
+
+    #[cfg(unix)]
+    {
+        let mut sigterm =
+            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+                .expect("failed to register SIGTERM handler");
+        tokio::select! {
+            _ = ctrl_c => {},
+            _ = sigterm.recv() => {},
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = ctrl_c.await;
+    }
+
+    tracing::info!("[Headless] Shutdown signal received, stopping...");
+}
@@ -0,0 +1,344 @@
+//! 從外部 CSV 匯入價格紀錄 — 把其他工具或券商匯出的歷史價格補進 `price_history`。
+//!
+//! 欄位依序為 `timestamp, price, volume`（volume 可省略）；第一列若為標題則依欄名對應
+//! （`date` / `time`、`close` / `last` 等常見名稱皆可，其餘欄位略過）。分隔符號自動判斷 `,` `;` 或 tab，
+//! 以 `;` 分隔時數值可用逗號作小數點。
+//!
+//! 時間可為 Unix 秒、Unix 毫秒、RFC 3339，或 `YYYY-MM-DD[ HH:MM[:SS]]`（未帶時區者視為 UTC）。
+//! 與既有紀錄時間完全相同的列視為重複並略過；`dry_run` 只回傳預覽與統計，不寫入。
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use serde::Serialize;
+
+use crate::db::DbPool;
+use crate::i18n::{tr, Msg};
+
+/// 單次匯入最多列數
+const MAX_ROWS: usize = 200_000;
+/// 預覽回傳的列數
+const PREVIEW_ROWS: usize = 20;
+/// 容許的時鐘誤差：晚於現在超過此秒數的紀錄視為無效
+const FUTURE_TOLERANCE_SECS: i64 = 300;
+/// 超過此值的整數時間戳視為毫秒
+const MILLIS_THRESHOLD: i64 = 100_000_000_000;
+
+const DATETIME_FORMATS: [&str; 6] = [
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M",
+    "%Y/%m/%d %H:%M:%S",
+    "%Y/%m/%d %H:%M",
+];
+const DATE_FORMATS: [&str; 2] = ["%Y-%m-%d", "%Y/%m/%d"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Column {
+    Timestamp,
+    Price,
+    Volume,
+    Ignored,
+}
+
+impl Column {
+    fn from_header(name: &str) -> Self {
+        match name.trim().to_lowercase().replace([' ', '-'], "_").as_str() {
+            "timestamp" | "time" | "date" | "datetime" | "recorded_at" | "ts" => Self::Timestamp,
+            "price" | "close" | "last" | "value" => Self::Price,
+            "volume" | "vol" => Self::Volume,
+            _ => Self::Ignored,
+        }
+    }
+}
+
+/// 通過驗證的一筆紀錄
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HistoryCsvRow {
+    /// CSV 中的行號（從 1 起算，含標題列）
+    pub line: usize,
+    pub recorded_at: i64,
+    pub price: f64,
+    pub volume: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HistoryCsvError {
+    pub line: usize,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HistoryImportReport {
+    pub subscription_id: i64,
+    pub dry_run: bool,
+    pub total_rows: usize,
+    /// 通過驗證、且與既有紀錄不重複的列數
+    pub new_rows: usize,
+    /// 與既有紀錄時間相同而略過的列數
+    pub duplicates: usize,
+    /// 實際寫入筆數（dry run 為 0）
+    pub inserted: usize,
+    /// 新紀錄的時間範圍（Unix 秒）
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    /// 依時間排序的前幾筆新紀錄
+    pub preview: Vec<HistoryCsvRow>,
+    pub errors: Vec<HistoryCsvError>,
+}
+
+/// 依第一個非空白列判斷分隔符號
+fn detect_delimiter(content: &str) -> u8 {
+    let first = content.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+    [b',', b';', b'\t']
+        .into_iter()
+        .max_by_key(|d| first.bytes().filter(|b| b == d).count())
+        .filter(|d| first.as_bytes().contains(d))
+        .unwrap_or(b',')
+}
+
+/// 解析時間欄位，回傳 Unix 秒
+fn parse_timestamp(value: &str) -> Result<i64, String> {
+    let value = value.trim();
+    if let Ok(n) = value.parse::<i64>() {
+        return Ok(if n.abs() >= MILLIS_THRESHOLD { n / 1000 } else { n });
+    }
+    if let Ok(n) = value.parse::<f64>() {
+        if n.is_finite() {
+            let n = if n.abs() >= MILLIS_THRESHOLD as f64 { n / 1000.0 } else { n };
+            return Ok(n.floor() as i64);
+        }
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Ok(dt.timestamp());
+    }
+    for format in DATETIME_FORMATS {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(value, format) {
+            return Ok(dt.and_utc().timestamp());
+        }
+    }
+    for format in DATE_FORMATS {
+        if let Ok(date) = NaiveDate::parse_from_str(value, format) {
+            return Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc().timestamp());
+        }
+    }
+    Err(format!("Invalid timestamp '{}'", value))
+}
+
+/// 解析數值；略過貨幣符號與千分位，`decimal_comma` 時逗號為小數點
+fn parse_number(value: &str, decimal_comma: bool) -> Option<f64> {
+    let cleaned: String = value.chars().filter(|c| !matches!(c, ' ' | '_' | '$')).collect();
+    let cleaned = if decimal_comma && !cleaned.contains('.') {
+        cleaned.replace(',', ".")
+    } else {
+        cleaned.replace(',', "")
+    };
+    cleaned.parse::<f64>().ok().filter(|n| n.is_finite())
+}
+
+/// 解析並驗證一列
+fn parse_row(
+    columns: &[Column],
+    record: &csv::StringRecord,
+    line: usize,
+    decimal_comma: bool,
+    now: i64,
+) -> Result<HistoryCsvRow, String> {
+    let field = |wanted: Column| {
+        columns
+            .iter()
+            .zip(record.iter())
+            .find(|(c, _)| **c == wanted)
+            .map_or("", |(_, v)| v)
+    };
+    let recorded_at = parse_timestamp(field(Column::Timestamp))?;
+    if recorded_at < 0 || recorded_at > now + FUTURE_TOLERANCE_SECS {
+        return Err(format!("Timestamp {} is out of range", recorded_at));
+    }
+    let price_field = field(Column::Price);
+    let price = parse_number(price_field, decimal_comma).ok_or_else(|| format!("Invalid price '{}'", price_field))?;
+    if price <= 0.0 {
+        return Err(format!("Price must be positive (got {})", price));
+    }
+    let volume = match field(Column::Volume) {
+        "" => None,
+        v => Some(
+            parse_number(v, decimal_comma)
+                .filter(|n| *n >= 0.0)
+                .ok_or_else(|| format!("Invalid volume '{}'", v))?,
+        ),
+    };
+    Ok(HistoryCsvRow { line, recorded_at, price, volume })
+}
+
+/// 解析並驗證 CSV 內容；格式錯誤或數值不合理的列放入錯誤清單，空白列略過
+pub fn parse(content: &str, now: i64) -> Result<(Vec<HistoryCsvRow>, Vec<HistoryCsvError>), String> {
+    let content = content.trim_start_matches('\u{feff}');
+    let delimiter = detect_delimiter(content);
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .delimiter(delimiter)
+        .from_reader(content.as_bytes());
+
+    let mut columns = vec![Column::Timestamp, Column::Price, Column::Volume];
+    let mut rows = Vec::new();
+    let mut errors = Vec::new();
+    let mut first_line: HashMap<i64, usize> = HashMap::new();
+    for (index, record) in reader.records().enumerate() {
+        let record = record.map_err(|e| format!("Invalid CSV: {}", e))?;
+        let line = crate::subscription_csv::record_line(content, &record, index);
+        if record.iter().all(|f| f.is_empty()) {
+            continue;
+        }
+        if index == 0
+            && record
+                .iter()
+                .any(|f| matches!(Column::from_header(f), Column::Timestamp | Column::Price))
+        {
+            columns = record.iter().map(Column::from_header).collect();
+            if !columns.contains(&Column::Timestamp) || !columns.contains(&Column::Price) {
+                return Err("CSV header needs a timestamp/date column and a price/close column".to_string());
+            }
+            continue;
+        }
+        if rows.len() + errors.len() >= MAX_ROWS {
+            return Err(format!("CSV has more than {} rows", MAX_ROWS));
+        }
+
+        let row = parse_row(&columns, &record, line, delimiter == b';', now);
+        match row {
+            Ok(row) => match first_line.get(&row.recorded_at) {
+                Some(first) => errors.push(HistoryCsvError {
+                    line,
+                    error: format!("Duplicate timestamp of line {}", first),
+                }),
+                None => {
+                    first_line.insert(row.recorded_at, line);
+                    rows.push(row);
+                }
+            },
+            Err(error) => errors.push(HistoryCsvError { line, error }),
+        }
+    }
+    Ok((rows, errors))
+}
+
+/// 解析 CSV 並寫入訂閱的價格紀錄（以訂閱目前的 provider 標記來源）；`dry_run` 時只產生預覽
+pub fn import(
+    db: &DbPool,
+    subscription_id: i64,
+    content: &str,
+    dry_run: bool,
+    now: i64,
+) -> Result<HistoryImportReport, String> {
+    let sub = db
+        .list_all_subscriptions()?
+        .into_iter()
+        .find(|s| s.id == subscription_id)
+        .ok_or_else(|| tr(Msg::SubscriptionNotFound, &[&subscription_id]))?;
+    let (mut rows, errors) = parse(content, now)?;
+    let total_rows = rows.len() + errors.len();
+
+    let existing: HashSet<i64> = match (
+        rows.iter().map(|r| r.recorded_at).min(),
+        rows.iter().map(|r| r.recorded_at).max(),
+    ) {
+        (Some(from), Some(to)) => db.get_history_timestamps(subscription_id, from, to)?,
+        _ => HashSet::new(),
+    };
+    let before = rows.len();
+    rows.retain(|r| !existing.contains(&r.recorded_at));
+    let duplicates = before - rows.len();
+    rows.sort_by_key(|r| r.recorded_at);
+
+    let inserted = if dry_run || rows.is_empty() {
+        0
+    } else {
+        let records: Vec<(i64, f64, Option<f64>)> = rows.iter().map(|r| (r.recorded_at, r.price, r.volume)).collect();
+        db.insert_imported_history(subscription_id, &sub.selected_provider_id, &records)?
+    };
+
+    Ok(HistoryImportReport {
+        subscription_id,
+        dry_run,
+        total_rows,
+        new_rows: rows.len(),
+        duplicates,
+        inserted,
+        from: rows.first().map(|r| r.recorded_at),
+        to: rows.last().map(|r| r.recorded_at),
+        preview: rows.iter().take(PREVIEW_ROWS).cloned().collect(),
+        errors,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    const NOW: i64 = 1_767_225_600; // 2026-01-01 00:00 UTC
+
+    #[test]
+    fn parses_timestamp_formats() {
+        assert_eq!(parse_timestamp("1700000000"), Ok(1_700_000_000));
+        assert_eq!(parse_timestamp("1700000000123"), Ok(1_700_000_000));
+        assert_eq!(parse_timestamp("1700000000.9"), Ok(1_700_000_000));
+        assert_eq!(parse_timestamp("2024-01-02T03:04:05+08:00"), Ok(1_704_135_845));
+        assert_eq!(parse_timestamp("2024-01-01 19:04:05"), Ok(1_704_135_845));
+        assert_eq!(parse_timestamp("2024/01/02"), Ok(1_704_153_600));
+        assert!(parse_timestamp("yesterday").is_err());
+    }
+
+    #[test]
+    fn parse_header_delimiters_and_validation() {
+        let csv = "\u{feff}Date;Open;Close;Volume\n2024-01-02;1,0;101,5;1200\n\n2024-01-03;1;-3;\n2024-01-02;1;102;\n2099-01-01;1;1;\n";
+        let (rows, errors) = parse(csv, NOW).unwrap();
+        assert_eq!(
+            rows,
+            vec![HistoryCsvRow { line: 2, recorded_at: 1_704_153_600, price: 101.5, volume: Some(1200.0) }]
+        );
+        let errors: Vec<(usize, &str)> = errors.iter().map(|e| (e.line, e.error.as_str())).collect();
+        assert_eq!(errors[0], (4, "Price must be positive (got -3)"));
+        assert_eq!(errors[1], (5, "Duplicate timestamp of line 2"));
+        assert_eq!(errors[2].0, 6);
+        assert!(errors[2].1.contains("out of range"));
+
+        // 無標題：timestamp, price, volume；千分位逗號需加引號
+        let (rows, errors) = parse("1700000000,\"65,000.50\"\n1700000060,65010,abc\n", NOW).unwrap();
+        assert_eq!(rows[0].price, 65_000.5);
+        assert_eq!(rows[0].volume, None);
+        assert_eq!(errors[0].error, "Invalid volume 'abc'");
+
+        assert!(parse("date,amount\nx,1\n", NOW).is_err());
+    }
+
+    #[test]
+    fn import_dedups_against_existing_rows() {
+        let db = DbPool::open(&PathBuf::from(":memory:")).unwrap();
+        let sub = db
+            .add_subscription("asset", "AAPL", None, "yahoo", "stock", None, None, None)
+            .unwrap();
+        db.insert_price_history_for_test(sub, "yahoo", &[(190.0, None, None, 1_700_000_060)]).unwrap();
+        let csv = "timestamp,close,volume\n1700000120,192,10\n1700000000,189,\n1700000060,191,\n";
+
+        let preview = import(&db, sub, csv, true, NOW).unwrap();
+        assert_eq!((preview.total_rows, preview.new_rows, preview.duplicates, preview.inserted), (3, 2, 1, 0));
+        assert_eq!((preview.from, preview.to), (Some(1_700_000_000), Some(1_700_000_120)));
+        assert_eq!(preview.preview[0].price, 189.0);
+        assert_eq!(db.get_history_stats(sub).unwrap().total, 1);
+
+        let report = import(&db, sub, csv, false, NOW).unwrap();
+        assert_eq!(report.inserted, 2);
+        let rows = db.get_price_volume_since(sub, 0).unwrap();
+        assert_eq!(rows, vec![(1_700_000_000, 189.0, None), (1_700_000_060, 190.0, None), (1_700_000_120, 192.0, Some(10.0))]);
+
+        // 再匯入一次全部視為重複
+        let again = import(&db, sub, csv, false, NOW).unwrap();
+        assert_eq!((again.new_rows, again.duplicates, again.inserted), (0, 3, 0));
+        assert!(import(&db, sub + 100, csv, true, NOW).is_err());
+    }
+}
@@ -0,0 +1,193 @@
+//! 後端錯誤與狀態訊息的多語言表（zh-TW、en、ja）。
+//!
+//! 訊息以 [`Msg`] key 表示，依 app settings 的 `language`（與前端相同的 locale id，
+//! 例如 `zh_TW`、`en`、`ja`）選擇語言；未設定時為英文。`zh_CN` 退回繁體中文，其他語言退回英文。
+//!
+//! 需要讓前端以 key 判斷錯誤時回傳 [`LocalizedError`]（`{ key, message }`）；
+//! 沿用 `Result<_, String>` 的呼叫端可直接 `?` 轉成已翻譯的字串。
+
+use std::fmt;
+use std::sync::{LazyLock, RwLock};
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub enum Locale {
+    #[serde(rename = "zh_TW")]
+    ZhTw,
+    #[default]
+    #[serde(rename = "en")]
+    En,
+    #[serde(rename = "ja")]
+    Ja,
+}
+
+impl Locale {
+    /// 由前端 locale id 解析（接受 `zh_TW` / `zh-TW` 等寫法）
+    pub fn from_id(id: &str) -> Self {
+        let id = id.trim().replace('-', "_").to_lowercase();
+        match id.as_str() {
+            "zh_tw" | "zh_hant" | "zh_cn" | "zh_hans" | "zh" => Locale::ZhTw,
+            "ja" | "ja_jp" => Locale::Ja,
+            _ => Locale::En,
+        }
+    }
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Locale::ZhTw => "zh_TW",
+            Locale::En => "en",
+            Locale::Ja => "ja",
+        }
+    }
+}
+
+static LOCALE: LazyLock<RwLock<Locale>> = LazyLock::new(|| RwLock::new(Locale::default()));
+
+pub fn set_locale(locale: Locale) {
+    *LOCALE.write().unwrap_or_else(|e| e.into_inner()) = locale;
+}
+
+pub fn locale() -> Locale {
+    *LOCALE.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// 訊息 key；模板中的 `{0}`、`{1}` 依序代入參數
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Msg {
+    ProviderNotFound,
+    SubscriptionNotFound,
+    ViewNotFound,
+    ChannelNotFound,
+    LogoNotFound,
+    DbOpenFailed,
+    DbBusy,
+    SubscriptionAdded,
+    KioskLocked,
+    RecordStalled,
+}
+
+impl Msg {
+    pub const ALL: [Msg; 10] = [
+        Msg::ProviderNotFound,
+        Msg::SubscriptionNotFound,
+        Msg::ViewNotFound,
+        Msg::ChannelNotFound,
+        Msg::LogoNotFound,
+        Msg::DbOpenFailed,
+        Msg::DbBusy,
+        Msg::SubscriptionAdded,
+        Msg::KioskLocked,
+        Msg::RecordStalled,
+    ];
+
+    pub fn template(self, locale: Locale) -> &'static str {
+        use Locale::*;
+        match (self, locale) {
+            (Msg::ProviderNotFound, En) => "Provider not found: {0}",
+            (Msg::ProviderNotFound, ZhTw) => "找不到數據源：{0}",
+            (Msg::ProviderNotFound, Ja) => "データソースが見つかりません: {0}",
+            (Msg::SubscriptionNotFound, En) => "Subscription {0} not found",
+            (Msg::SubscriptionNotFound, ZhTw) => "找不到訂閱 {0}",
+            (Msg::SubscriptionNotFound, Ja) => "サブスクリプション {0} が見つかりません",
+            (Msg::ViewNotFound, En) => "View not found",
+            (Msg::ViewNotFound, ZhTw) => "找不到頁面",
+            (Msg::ViewNotFound, Ja) => "ビューが見つかりません",
+            (Msg::ChannelNotFound, En) => "Channel {0} not found",
+            (Msg::ChannelNotFound, ZhTw) => "找不到通知通道 {0}",
+            (Msg::ChannelNotFound, Ja) => "通知チャネル {0} が見つかりません",
+            (Msg::LogoNotFound, En) => "Logo not found for symbol: {0}",
+            (Msg::LogoNotFound, ZhTw) => "找不到 {0} 的 Logo",
+            (Msg::LogoNotFound, Ja) => "{0} のロゴが見つかりません",
+            (Msg::DbOpenFailed, En) => "Failed to open DB: {0}",
+            (Msg::DbOpenFailed, ZhTw) => "開啟 DB 失敗：{0}",
+            (Msg::DbOpenFailed, Ja) => "DB を開けませんでした: {0}",
+            (Msg::DbBusy, En) => "Database is busy (retried {0} times), please try again",
+            (Msg::DbBusy, ZhTw) => "資料庫忙碌中（已重試 {0} 次），請稍後再試",
+            (Msg::DbBusy, Ja) => "データベースが使用中です（{0} 回再試行しました）。しばらくしてから再度お試しください",
+            (Msg::SubscriptionAdded, En) => "Added {0} ({1})",
+            (Msg::SubscriptionAdded, ZhTw) => "已新增 {0}（{1}）",
+            (Msg::SubscriptionAdded, Ja) => "{0}（{1}）を追加しました",
+            (Msg::KioskLocked, En) => "Kiosk mode is on: changes are disabled",
+            (Msg::KioskLocked, ZhTw) => "Kiosk 模式已開啟，無法修改",
+            (Msg::KioskLocked, Ja) => "キオスクモード中のため変更できません",
+            (Msg::RecordStalled, En) => "{0} has recorded no prices for over {1} minutes",
+            (Msg::RecordStalled, ZhTw) => "{0} 已超過 {1} 分鐘沒有寫入價格紀錄",
+            (Msg::RecordStalled, Ja) => "{0} の価格記録が {1} 分以上ありません",
+        }
+    }
+}
+
+fn render(template: &str, args: &[String]) -> String {
+    args.iter()
+        .enumerate()
+        .fold(template.to_string(), |text, (i, arg)| text.replace(&format!("{{{}}}", i), arg))
+}
+
+/// 以目前語言翻譯訊息
+pub fn tr(msg: Msg, args: &[&dyn fmt::Display]) -> String {
+    let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+    render(msg.template(locale()), &args)
+}
+
+/// 帶 key 的錯誤：`message` 為建立時的語言
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LocalizedError {
+    pub key: Msg,
+    pub message: String,
+}
+
+impl LocalizedError {
+    pub fn new(key: Msg, args: &[&dyn fmt::Display]) -> Self {
+        Self { key, message: tr(key, args) }
+    }
+}
+
+impl fmt::Display for LocalizedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<LocalizedError> for String {
+    fn from(e: LocalizedError) -> Self {
+        e.message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_locale_uses_the_same_placeholders() {
+        let placeholders = |s: &str| (0..4).filter(|i| s.contains(&format!("{{{}}}", i))).count();
+        for msg in Msg::ALL {
+            let en = placeholders(msg.template(Locale::En));
+            for locale in [Locale::ZhTw, Locale::Ja] {
+                assert_eq!(placeholders(msg.template(locale)), en, "{:?} {:?}", msg, locale);
+            }
+        }
+    }
+
+    #[test]
+    fn locale_ids_and_rendering() {
+        assert_eq!(Locale::from_id("zh-TW"), Locale::ZhTw);
+        assert_eq!(Locale::from_id("zh_CN"), Locale::ZhTw);
+        assert_eq!(Locale::from_id("ja"), Locale::Ja);
+        assert_eq!(Locale::from_id("ko"), Locale::En);
+        assert_eq!(Locale::from_id(Locale::Ja.id()), Locale::Ja);
+
+        let args = ["BTC".to_string(), "binance".to_string()];
+        assert_eq!(render(Msg::SubscriptionAdded.template(Locale::ZhTw), &args), "已新增 BTC（binance）");
+        let err = LocalizedError {
+            key: Msg::ProviderNotFound,
+            message: render(Msg::ProviderNotFound.template(Locale::En), &args[1..]),
+        };
+        assert_eq!(
+            serde_json::to_value(&err).unwrap(),
+            serde_json::json!({ "key": "provider_not_found", "message": "Provider not found: binance" })
+        );
+    }
+}
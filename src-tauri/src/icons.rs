@@ -1,6 +1,14 @@
 //! Shared logo download logic used by both Tauri commands and the HTTP API.
+//!
+//! [`fetch_icon`] picks sources by asset type — CoinGecko for crypto, FMP for stocks,
+//! the Jupiter token list for Solana mints — with Parqet as the fallback, and
+//! normalizes whatever it downloads to a PNG of at most [`ICON_SIZE`] px.
+//!
+//! Icons and theme backgrounds imported by the user go through the same kind of
+//! pipeline ([`process_icon`], [`process_theme_bg`]): decode, downscale, re-encode;
+//! SVG icons are stored untouched as `.svg`.
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use serde::Serialize;
@@ -8,6 +16,9 @@ use tokio::sync::Semaphore;
 
 use crate::db::DbPool;
 
+/// Longest edge (px) of stored icons; larger downloads are scaled down.
+pub const ICON_SIZE: u32 = 128;
+
 /// Result of a bulk logo download operation.
 #[derive(Debug, Clone, Serialize)]
 pub struct LogoDownloadResult {
@@ -55,11 +66,8 @@ pub async fn download_all_logos(
     let mut processed = 0u32;
 
     for sub in &subs {
-        let icon_name = to_icon_name(&sub.symbol);
-        let dest = icons_dir.join(format!("{}.png", icon_name));
-
         // Already exists → skip (don't overwrite manually set icons)
-        if dest.exists() {
+        if find_icon(icons_dir, &sub.symbol).is_some() {
             skipped += 1;
             processed += 1;
             if let Some(ref tx) = progress_tx {
@@ -79,8 +87,8 @@ pub async fn download_all_logos(
 
         match bytes {
             Some(data) => {
-                if let Err(e) = tokio::fs::write(&dest, &data).await {
-                    eprintln!("[LogoDownload] Failed to write {}: {}", icon_name, e);
+                if let Err(e) = save_icon_bytes(db, icons_dir, &sub.symbol, &data).await {
+                    tracing::warn!("[LogoDownload] Failed to save {}: {}", sub.symbol, e);
                     failed_list.push(sub.symbol.clone());
                 } else {
                     succeeded += 1;
@@ -115,9 +123,43 @@ pub async fn download_all_logos(
     })
 }
 
+/// Longest sanitized prefix kept in a hashed icon filename.
+const ICON_NAME_MAX_LEN: usize = 48;
+
+/// Device names Windows refuses as file stems.
+const WINDOWS_RESERVED_NAMES: &[&str] = &[
+    "con", "prn", "aux", "nul", "com1", "com2", "com3", "com4", "com5", "com6", "com7", "com8",
+    "com9", "lpt1", "lpt2", "lpt3", "lpt4", "lpt5", "lpt6", "lpt7", "lpt8", "lpt9",
+];
+
+/// 32-bit FNV-1a — tiny and deterministic, mirrored by the frontend's `getIconName`.
+fn fnv1a(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c_9dc5, |hash, b| (hash ^ u32::from(*b)).wrapping_mul(0x0100_0193))
+}
+
 /// Convert a symbol to its canonical icon filename (without extension).
+///
+/// Plain symbols keep their lowercase name (`BTC` → `btc`). Anything that is not a safe
+/// file stem on every platform — `:` / `/` in DEX symbols, non-ASCII, Windows device names,
+/// overly long names — becomes `{sanitized}-{fnv1a hash}` so distinct symbols never collide.
 pub fn to_icon_name(symbol: &str) -> String {
-    symbol.to_lowercase()
+    let lower = symbol.trim().to_lowercase();
+    let sanitized: String = lower
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '_' })
+        .collect();
+    let device_name = lower.split('.').next().unwrap_or_default();
+    let plain = !lower.is_empty()
+        && sanitized == lower
+        && lower.len() <= ICON_NAME_MAX_LEN
+        && !lower.starts_with('.')
+        && !lower.ends_with('.')
+        && !WINDOWS_RESERVED_NAMES.contains(&device_name);
+    if plain {
+        return lower;
+    }
+    let prefix: String = sanitized.trim_matches('.').chars().take(ICON_NAME_MAX_LEN).collect();
+    format!("{}-{:08x}", prefix, fnv1a(lower.as_bytes()))
 }
 
 /// Convert a symbol to the query format expected by logo APIs.
@@ -145,6 +187,152 @@ pub async fn try_download_png(
     fetch_if_png(client, &url).await
 }
 
+/// A place to look up a symbol's logo, in the order [`icon_sources`] returns them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IconSource {
+    /// CoinGecko search API → `large` image of the first exact symbol match
+    CoinGecko(String),
+    /// FMP static stock logo
+    Fmp(String),
+    /// Jupiter token search by mint → `icon` URL
+    Jupiter(String),
+    /// Parqet logo CDN (stocks and major coins)
+    Parqet(String),
+}
+
+impl IconSource {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::CoinGecko(_) => "CoinGecko",
+            Self::Fmp(_) => "FMP",
+            Self::Jupiter(_) => "Jupiter",
+            Self::Parqet(_) => "Parqet",
+        }
+    }
+}
+
+/// Solana mint addresses are 32–44 base58 characters.
+pub fn is_solana_mint(symbol: &str) -> bool {
+    (32..=44).contains(&symbol.len())
+        && symbol
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() && !matches!(c, '0' | 'O' | 'I' | 'l'))
+}
+
+/// Candidate logo sources for a symbol, most specific first.
+pub fn icon_sources(symbol: &str, provider_id: &str, asset_type: &str) -> Vec<IconSource> {
+    let symbol = symbol.trim();
+    if provider_id == "jupiter" && is_solana_mint(symbol) {
+        return vec![IconSource::Jupiter(symbol.to_string())];
+    }
+    let query = to_query_symbol(symbol, asset_type);
+    match asset_type {
+        "crypto" => vec![IconSource::CoinGecko(query.clone()), IconSource::Parqet(query)],
+        _ => vec![IconSource::Fmp(query.clone()), IconSource::Parqet(query)],
+    }
+}
+
+/// Download a logo for `symbol` (as subscribed on `provider_id`), normalize it to PNG
+/// and save it as `icons/{symbol}.png`, replacing any existing icon.
+/// Returns the name of the source that provided the image.
+pub async fn fetch_icon(
+    db: &DbPool,
+    icons_dir: &Path,
+    symbol: &str,
+    provider_id: &str,
+) -> Result<String, String> {
+    let asset_type = db
+        .list_all_subscriptions()?
+        .into_iter()
+        .find(|s| s.symbol == symbol && s.selected_provider_id == provider_id)
+        .map(|s| s.asset_type)
+        .or_else(|| {
+            crate::providers::get_provider_info(provider_id)
+                .map(|info| info.provider_type)
+                .filter(|t| t == "crypto" || t == "stock")
+        })
+        .unwrap_or_else(|| "crypto".to_string());
+
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .user_agent("StockenBoard/1.0")
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    for source in icon_sources(symbol, provider_id, &asset_type) {
+        let Some(bytes) = download_from(&client, &source).await else {
+            continue;
+        };
+        match save_icon_bytes(db, icons_dir, symbol, &bytes).await {
+            Ok(_) => return Ok(source.name().to_string()),
+            Err(e) => {
+                tracing::debug!("[FetchIcon] {} image for {} rejected: {}", source.name(), symbol, e);
+            }
+        }
+    }
+    Err(format!("Logo not found for symbol: {}", symbol))
+}
+
+async fn download_from(client: &reqwest::Client, source: &IconSource) -> Option<Vec<u8>> {
+    let image_url = match source {
+        IconSource::CoinGecko(query) => {
+            let url = reqwest::Url::parse_with_params("https://api.coingecko.com/api/v3/search", &[("query", query)]).ok()?;
+            let resp: serde_json::Value = client
+                .get(url)
+                .send()
+                .await
+                .ok()?
+                .json()
+                .await
+                .ok()?;
+            resp["coins"]
+                .as_array()?
+                .iter()
+                .find(|c| c["symbol"].as_str().is_some_and(|s| s.eq_ignore_ascii_case(query)))?
+                ["large"]
+                .as_str()?
+                .to_string()
+        }
+        IconSource::Jupiter(mint) => {
+            let url = reqwest::Url::parse_with_params("https://lite-api.jup.ag/tokens/v2/search", &[("query", mint)]).ok()?;
+            let resp: serde_json::Value = client
+                .get(url)
+                .send()
+                .await
+                .ok()?
+                .json()
+                .await
+                .ok()?;
+            resp.as_array()?
+                .iter()
+                .find(|t| t["id"].as_str() == Some(mint.as_str()))?
+                ["icon"]
+                .as_str()?
+                .to_string()
+        }
+        IconSource::Fmp(symbol) => {
+            format!("https://financialmodelingprep.com/image-stock/{}.png", symbol)
+        }
+        IconSource::Parqet(symbol) => {
+            format!("https://assets.parqet.com/logos/symbol/{}", symbol.to_uppercase())
+        }
+    };
+    fetch_image(client, &image_url).await
+}
+
+/// Decode a downloaded image (PNG / JPEG / WebP), scale it down to fit
+/// [`ICON_SIZE`] keeping the aspect ratio, and re-encode as PNG.
+pub fn normalize_png(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let mut img = image::load_from_memory(bytes).map_err(|e| format!("Unsupported image: {}", e))?;
+    if img.width() > ICON_SIZE || img.height() > ICON_SIZE {
+        img = img.resize(ICON_SIZE, ICON_SIZE, image::imageops::FilterType::Lanczos3);
+    }
+    let mut out = std::io::Cursor::new(Vec::new());
+    img.write_to(&mut out, image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode PNG: {}", e))?;
+    Ok(out.into_inner())
+}
+
 /// Search multiple icon sources and return all found results with their source names.
 #[derive(serde::Serialize, Clone)]
 pub struct IconSearchResult {
@@ -207,3 +395,420 @@ async fn fetch_if_png(client: &reqwest::Client, url: &str) -> Option<Vec<u8>> {
     }
     Some(bytes.to_vec())
 }
+
+/// Fetch a raster image of any type (SVG is skipped; decoding validates the rest).
+async fn fetch_image(client: &reqwest::Client, url: &str) -> Option<Vec<u8>> {
+    let resp = client.get(url).send().await.ok()?;
+    if !resp.status().is_success() {
+        return None;
+    }
+    let content_type = resp
+        .headers()
+        .get("content-type")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    if content_type.contains("svg") || content_type.starts_with("text/") {
+        return None;
+    }
+    let bytes = resp.bytes().await.ok()?;
+    if bytes.len() < 100 {
+        return None;
+    }
+    Some(bytes.to_vec())
+}
+
+// ── Non-interactive image input ─────────────────────────────────
+
+/// Theme background file extensions, in lookup order.
+pub const THEME_BG_EXTS: &[&str] = &["png", "jpg", "jpeg", "webp", "img"];
+
+/// Detect an image type from its magic bytes (SVG is recognised by its markup).
+pub fn sniff_image_ext(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("png")
+    } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("jpg")
+    } else if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some("webp")
+    } else {
+        let head = String::from_utf8_lossy(&bytes[..bytes.len().min(512)]).to_lowercase();
+        let head = head.trim_start_matches('\u{feff}').trim_start();
+        (head.starts_with("<svg") || (head.starts_with("<?xml") && head.contains("<svg")))
+            .then_some("svg")
+    }
+}
+
+/// Load image bytes from exactly one of a local file `path` or base64 `data`
+/// (plain base64 or a `data:image/...;base64,` URL), returning the bytes and their type.
+pub async fn read_image_input(
+    path: Option<&str>,
+    data: Option<&str>,
+) -> Result<(Vec<u8>, &'static str), String> {
+    use base64::Engine;
+
+    let bytes = match (path.filter(|p| !p.is_empty()), data.filter(|d| !d.is_empty())) {
+        (Some(path), None) => tokio::fs::read(path)
+            .await
+            .map_err(|e| format!("Failed to read file: {}", e))?,
+        (None, Some(data)) => {
+            let b64 = match data.split_once(',') {
+                Some((prefix, rest)) if prefix.starts_with("data:") => rest,
+                _ => data,
+            };
+            base64::engine::general_purpose::STANDARD
+                .decode(b64.trim())
+                .map_err(|e| format!("Failed to decode base64: {}", e))?
+        }
+        (Some(_), Some(_)) => return Err("Provide either 'path' or 'data', not both".to_string()),
+        (None, None) => return Err("Missing 'path' or 'data'".to_string()),
+    };
+    let ext = sniff_image_ext(&bytes).ok_or("Unsupported image format (expected PNG, JPEG, WebP or SVG)")?;
+    Ok((bytes, ext))
+}
+
+/// File names are derived from symbols / theme ids, so reject anything that could escape the directory.
+fn check_file_stem(stem: &str) -> Result<(), String> {
+    if stem.is_empty() || stem.contains(['/', '\\']) || stem.contains("..") {
+        return Err(format!("Invalid name: {}", stem));
+    }
+    Ok(())
+}
+
+// ── Image pipeline ──────────────────────────────────────────────
+
+/// Extensions an icon may be stored with: raster images become PNG, SVG is kept as-is.
+pub const ICON_EXTS: &[&str] = &["png", "svg"];
+
+/// Longest edge (px) of stored theme backgrounds.
+pub const THEME_BG_MAX_SIZE: u32 = 2560;
+const THEME_BG_JPEG_QUALITY: u8 = 85;
+
+/// Icon pipeline: SVG passes through untouched, everything else is normalized to PNG.
+pub fn process_icon(bytes: &[u8]) -> Result<(Vec<u8>, &'static str), String> {
+    match sniff_image_ext(bytes) {
+        Some("svg") => Ok((bytes.to_vec(), "svg")),
+        _ => Ok((normalize_png(bytes)?, "png")),
+    }
+}
+
+/// Theme background pipeline: downscale to [`THEME_BG_MAX_SIZE`] and re-encode —
+/// JPEG for opaque images, PNG when transparency has to be kept.
+pub fn process_theme_bg(bytes: &[u8]) -> Result<(Vec<u8>, &'static str), String> {
+    let mut img = image::load_from_memory(bytes).map_err(|e| format!("Unsupported image: {}", e))?;
+    if img.width() > THEME_BG_MAX_SIZE || img.height() > THEME_BG_MAX_SIZE {
+        img = img.resize(THEME_BG_MAX_SIZE, THEME_BG_MAX_SIZE, image::imageops::FilterType::Lanczos3);
+    }
+    let transparent = img.color().has_alpha() && img.to_rgba8().pixels().any(|p| p[3] < u8::MAX);
+    let mut out = std::io::Cursor::new(Vec::new());
+    if transparent {
+        img.write_to(&mut out, image::ImageFormat::Png)
+            .map_err(|e| format!("Failed to encode PNG: {}", e))?;
+        return Ok((out.into_inner(), "png"));
+    }
+    image::codecs::jpeg::JpegEncoder::new_with_quality(&mut out, THEME_BG_JPEG_QUALITY)
+        .encode_image(&img.to_rgb8())
+        .map_err(|e| format!("Failed to encode JPEG: {}", e))?;
+    Ok((out.into_inner(), "jpg"))
+}
+
+/// Path of the stored icon for a symbol, whichever extension it was saved with.
+pub fn find_icon(icons_dir: &Path, symbol: &str) -> Option<PathBuf> {
+    let icon_name = to_icon_name(symbol);
+    ICON_EXTS
+        .iter()
+        .map(|ext| icons_dir.join(format!("{}.{}", icon_name, ext)))
+        .find(|p| p.exists())
+}
+
+/// Delete a symbol's icon in every stored format (and its mapping). Returns whether anything was removed.
+pub async fn remove_icon_files(db: &DbPool, icons_dir: &Path, symbol: &str) -> Result<bool, String> {
+    let icon_name = to_icon_name(symbol);
+    let mut removed = false;
+    for ext in ICON_EXTS {
+        let path = icons_dir.join(format!("{}.{}", icon_name, ext));
+        if path.exists() {
+            tokio::fs::remove_file(&path)
+                .await
+                .map_err(|e| format!("Failed to delete icon: {}", e))?;
+            removed = true;
+        }
+    }
+    db.delete_icon_mapping(symbol)?;
+    Ok(removed)
+}
+
+/// Run `bytes` through the icon pipeline and save as `icons/{icon_name}.png` (or `.svg`),
+/// replacing the icon previously stored in the other format and recording the mapping.
+pub async fn save_icon_bytes(
+    db: &DbPool,
+    icons_dir: &Path,
+    symbol: &str,
+    bytes: &[u8],
+) -> Result<PathBuf, String> {
+    let icon_name = to_icon_name(symbol);
+    let input = bytes.to_vec();
+    let (data, ext) = tokio::task::spawn_blocking(move || process_icon(&input))
+        .await
+        .map_err(|e| e.to_string())??;
+    tokio::fs::create_dir_all(icons_dir)
+        .await
+        .map_err(|e| format!("Failed to create icons directory: {}", e))?;
+    remove_icon_files(db, icons_dir, symbol).await?;
+    let file_name = format!("{}.{}", icon_name, ext);
+    let dest = icons_dir.join(&file_name);
+    tokio::fs::write(&dest, data)
+        .await
+        .map_err(|e| format!("Failed to write icon: {}", e))?;
+    db.upsert_icon_mapping(symbol, &file_name)?;
+    Ok(dest)
+}
+
+/// One-off migration of icons saved under the old `symbol.to_lowercase()` naming:
+/// files whose stem is not a valid [`to_icon_name`] result are renamed, and every
+/// icon without a mapping gets one. Safe to run on every startup.
+pub fn migrate_icon_names(db: &DbPool, icons_dir: &Path) -> Result<usize, String> {
+    let Ok(entries) = std::fs::read_dir(icons_dir) else {
+        return Ok(0);
+    };
+    let mut renamed = 0;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let (Some(stem), Some(ext)) = (
+            path.file_stem().and_then(|s| s.to_str()),
+            path.extension().and_then(|e| e.to_str()),
+        ) else {
+            continue;
+        };
+        if !ICON_EXTS.contains(&ext) {
+            continue;
+        }
+        let icon_name = to_icon_name(stem);
+        let file_name = format!("{}.{}", icon_name, ext);
+        if icon_name != stem {
+            let target = icons_dir.join(&file_name);
+            if target.exists() {
+                tracing::warn!("[Icons] Not migrating {:?}: {} already exists", path, file_name);
+                continue;
+            }
+            std::fs::rename(&path, &target)
+                .map_err(|e| format!("Failed to rename icon {:?}: {}", path, e))?;
+            renamed += 1;
+            db.upsert_icon_mapping(stem, &file_name)?;
+        } else {
+            db.insert_icon_mapping_if_unknown(stem, &file_name)?;
+        }
+    }
+    Ok(renamed)
+}
+
+/// Run `bytes` through the background pipeline and save as `theme_bg/{theme_id}.{jpg|png}`,
+/// replacing any previous background for that theme.
+pub async fn save_theme_bg_bytes(dir: &Path, theme_id: &str, bytes: &[u8]) -> Result<PathBuf, String> {
+    check_file_stem(theme_id)?;
+    let input = bytes.to_vec();
+    let (data, ext) = tokio::task::spawn_blocking(move || process_theme_bg(&input))
+        .await
+        .map_err(|e| e.to_string())??;
+    tokio::fs::create_dir_all(dir)
+        .await
+        .map_err(|e| format!("Failed to create directory: {}", e))?;
+    for old_ext in THEME_BG_EXTS {
+        let _ = tokio::fs::remove_file(dir.join(format!("{}.{}", theme_id, old_ext))).await;
+    }
+    let dest = dir.join(format!("{}.{}", theme_id, ext));
+    tokio::fs::write(&dest, data)
+        .await
+        .map_err(|e| format!("Failed to write file: {}", e))?;
+    Ok(dest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sources_follow_asset_type() {
+        assert_eq!(
+            icon_sources("BTCUSDT", "binance", "crypto"),
+            vec![
+                IconSource::CoinGecko("BTC".to_string()),
+                IconSource::Parqet("BTC".to_string()),
+            ]
+        );
+        assert_eq!(
+            icon_sources("aapl", "fmp", "stock"),
+            vec![
+                IconSource::Fmp("AAPL".to_string()),
+                IconSource::Parqet("AAPL".to_string()),
+            ]
+        );
+        let mint = "So11111111111111111111111111111111111111112";
+        assert_eq!(
+            icon_sources(mint, "jupiter", "crypto"),
+            vec![IconSource::Jupiter(mint.to_string())]
+        );
+    }
+
+    #[test]
+    fn detects_solana_mints() {
+        assert!(is_solana_mint("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"));
+        assert!(!is_solana_mint("SOL"));
+        assert!(!is_solana_mint("0x0000000000000000000000000000000000000000"));
+    }
+
+    #[test]
+    fn normalize_scales_down_and_outputs_png() {
+        let img = image::RgbaImage::from_pixel(300, 150, image::Rgba([255, 0, 0, 255]));
+        let mut jpeg = std::io::Cursor::new(Vec::new());
+        image::DynamicImage::ImageRgba8(img)
+            .to_rgb8()
+            .write_to(&mut jpeg, image::ImageFormat::Jpeg)
+            .unwrap();
+
+        let png = normalize_png(&jpeg.into_inner()).unwrap();
+        assert_eq!(&png[..4], b"\x89PNG");
+        let decoded = image::load_from_memory(&png).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (ICON_SIZE, ICON_SIZE / 2));
+
+        assert!(normalize_png(b"<html>not found</html>").is_err());
+    }
+
+    #[test]
+    fn sniffs_image_types() {
+        assert_eq!(sniff_image_ext(b"\x89PNG\r\n\x1a\n...."), Some("png"));
+        assert_eq!(sniff_image_ext(&[0xFF, 0xD8, 0xFF, 0xE0]), Some("jpg"));
+        assert_eq!(sniff_image_ext(b"RIFF\0\0\0\0WEBPVP8 "), Some("webp"));
+        assert_eq!(sniff_image_ext(b"<?xml version=\"1.0\"?><svg></svg>"), Some("svg"));
+        assert_eq!(sniff_image_ext(b"GIF89a"), None);
+    }
+
+    #[tokio::test]
+    async fn image_input_from_path_or_data_url() {
+        use base64::Engine;
+
+        let dir = tempfile::tempdir().unwrap();
+        let png = b"\x89PNG\r\n\x1a\nrest-of-file".to_vec();
+        let file = dir.path().join("logo.png");
+        std::fs::write(&file, &png).unwrap();
+
+        let (bytes, ext) = read_image_input(file.to_str(), None).await.unwrap();
+        assert_eq!((bytes, ext), (png.clone(), "png"));
+
+        let data_url = format!(
+            "data:image/png;base64,{}",
+            base64::engine::general_purpose::STANDARD.encode(&png)
+        );
+        let (bytes, _) = read_image_input(None, Some(&data_url)).await.unwrap();
+        assert_eq!(bytes, png);
+
+        assert!(read_image_input(None, None).await.is_err());
+        assert!(read_image_input(file.to_str(), Some(&data_url)).await.is_err());
+    }
+
+    fn encode(img: image::DynamicImage, format: image::ImageFormat) -> Vec<u8> {
+        let mut out = std::io::Cursor::new(Vec::new());
+        img.write_to(&mut out, format).unwrap();
+        out.into_inner()
+    }
+
+    #[test]
+    fn icon_pipeline_converts_raster_and_keeps_svg() {
+        let jpeg = encode(
+            image::DynamicImage::ImageRgb8(image::RgbImage::new(512, 512)),
+            image::ImageFormat::Jpeg,
+        );
+        let (png, ext) = process_icon(&jpeg).unwrap();
+        assert_eq!(ext, "png");
+        assert_eq!(image::load_from_memory(&png).unwrap().width(), ICON_SIZE);
+
+        let svg = b"<svg xmlns=\"http://www.w3.org/2000/svg\"></svg>";
+        assert_eq!(process_icon(svg).unwrap(), (svg.to_vec(), "svg"));
+    }
+
+    #[test]
+    fn theme_bg_pipeline_downscales_and_picks_format() {
+        let opaque = encode(
+            image::DynamicImage::ImageRgba8(image::RgbaImage::from_pixel(4000, 2000, image::Rgba([10, 20, 30, 255]))),
+            image::ImageFormat::Png,
+        );
+        let (jpg, ext) = process_theme_bg(&opaque).unwrap();
+        assert_eq!(ext, "jpg");
+        let decoded = image::load_from_memory(&jpg).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (THEME_BG_MAX_SIZE, THEME_BG_MAX_SIZE / 2));
+
+        let translucent = encode(
+            image::DynamicImage::ImageRgba8(image::RgbaImage::from_pixel(64, 64, image::Rgba([0, 0, 0, 128]))),
+            image::ImageFormat::Png,
+        );
+        assert_eq!(process_theme_bg(&translucent).unwrap().1, "png");
+    }
+
+    #[test]
+    fn icon_names_are_safe_and_distinct() {
+        assert_eq!(to_icon_name("BTC"), "btc");
+        assert_eq!(to_icon_name(" brk.b "), "brk.b");
+        // 與前端 getIconName 共用的測試向量
+        assert_eq!(to_icon_name("pool:So11:EPjF"), "pool_so11_epjf-bbefba9a");
+        assert_eq!(to_icon_name("CON"), "con-f08dd14b");
+
+        let names = ["BTC/USDT", "btc_usdt", "btc:usdt", "BTC_USDT"].map(to_icon_name);
+        assert_eq!(names[1], names[3]);
+        assert_ne!(names[0], names[1]);
+        assert_ne!(names[0], names[2]);
+        for name in names.iter().chain([to_icon_name("../x"), to_icon_name("🚀")].iter()) {
+            assert!(name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')));
+            assert!(!name.starts_with('.'));
+        }
+        assert!(to_icon_name(&"a".repeat(100)).len() <= ICON_NAME_MAX_LEN + 9);
+    }
+
+    #[test]
+    fn migration_renames_legacy_files_and_records_mappings() {
+        let db = DbPool::open(&PathBuf::from(":memory:")).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("btc.png"), b"btc").unwrap();
+        std::fs::write(dir.path().join("pool:abc.png"), b"pool").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), b"x").unwrap();
+
+        assert_eq!(migrate_icon_names(&db, dir.path()).unwrap(), 1);
+        let migrated = format!("{}.png", to_icon_name("pool:abc"));
+        assert_eq!(std::fs::read(dir.path().join(&migrated)).unwrap(), b"pool");
+        assert!(!dir.path().join("pool:abc.png").exists());
+
+        let mappings = db.list_icon_mappings().unwrap();
+        assert!(mappings.iter().any(|m| m.symbol == "pool:abc" && m.file_name == migrated));
+        assert!(mappings.iter().any(|m| m.symbol == "btc" && m.file_name == "btc.png"));
+
+        // 再跑一次不變
+        assert_eq!(migrate_icon_names(&db, dir.path()).unwrap(), 0);
+        assert_eq!(db.list_icon_mappings().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn saving_replaces_previous_files() {
+        let db = DbPool::open(&PathBuf::from(":memory:")).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let png = encode(
+            image::DynamicImage::ImageRgb8(image::RgbImage::new(16, 16)),
+            image::ImageFormat::Png,
+        );
+
+        std::fs::write(dir.path().join("dark.png"), b"old").unwrap();
+        let dest = save_theme_bg_bytes(dir.path(), "dark", &png).await.unwrap();
+        assert_eq!(dest, dir.path().join("dark.jpg"));
+        assert!(!dir.path().join("dark.png").exists());
+
+        save_icon_bytes(&db, dir.path(), "BTC", b"<svg></svg>").await.unwrap();
+        let dest = save_icon_bytes(&db, dir.path(), "BTC", &png).await.unwrap();
+        assert_eq!(find_icon(dir.path(), "btc"), Some(dest));
+        assert!(!dir.path().join("btc.svg").exists());
+        assert_eq!(db.list_icon_mappings().unwrap()[0].file_name, "btc.png");
+
+        assert!(save_theme_bg_bytes(dir.path(), "../escape", &png).await.is_err());
+        let dest = save_icon_bytes(&db, dir.path(), "a/b", &png).await.unwrap();
+        assert_eq!(dest.parent(), Some(dir.path()));
+
+        assert!(remove_icon_files(&db, dir.path(), "A/B").await.unwrap());
+        assert_eq!(db.list_icon_mappings().unwrap().len(), 1);
+    }
+}
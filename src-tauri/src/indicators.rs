@@ -0,0 +1,300 @@
+//! 以價格紀錄計算的技術指標 — VWAP（成交量加權平均價）與 TWAP（時間加權平均價）。
+//!
+//! - Session VWAP：當日（UTC 00:00 起）的紀錄
+//! - Anchored VWAP：從使用者指定的時間點（Unix 秒）起的紀錄
+//!
+//! price_history 的 `volume` 是 provider 回報的累計成交量（股票為當日累計，加密貨幣多為滾動 24h），
+//! 以相鄰兩筆紀錄的正向增量作為該筆價格的成交量；沒有可用增量時退回等權平均（`volume_weighted = false`）。
+//! `vwap_above` / `vwap_below` 規則以 Session VWAP 評估，結果快取 60 秒。
+//!
+//! TWAP 把每筆價格視為持續到下一筆紀錄（最後一筆持續到現在），以持續時間加權，
+//! 取樣不均（例如紀錄時段、暫停或 provider 間隔不同）時不會偏向密集取樣的區段。
+//! 窗格起點之前的最後一筆紀錄（不早於一個窗格長度）作為起點價格。
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, RwLock};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::db::DbPool;
+
+const DAY_SECS: i64 = 86_400;
+/// 規則評估用 Session VWAP 的快取時間
+const SESSION_CACHE_TTL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Vwap {
+    /// 計算區間（Unix 秒，實際第一筆與最後一筆紀錄時間）
+    pub from: i64,
+    pub to: i64,
+    pub vwap: f64,
+    pub last_price: f64,
+    /// 最新價格相對 VWAP 的偏離（%）
+    pub deviation_pct: f64,
+    pub samples: usize,
+    /// false 表示紀錄沒有可用成交量，以等權平均計算
+    pub volume_weighted: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VwapReport {
+    pub subscription_id: i64,
+    pub session: Option<Vwap>,
+    /// 有指定 anchor 時才計算
+    pub anchored: Option<Vwap>,
+}
+
+/// TWAP 預設時間窗
+pub const DEFAULT_TWAP_WINDOW: &str = "1h";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Twap {
+    /// 計算區間（Unix 秒）：窗格起點（沒有起點價格時為第一筆紀錄時間）到現在
+    pub from: i64,
+    pub to: i64,
+    pub twap: f64,
+    /// 窗格內紀錄的逐筆平均，供比較取樣不均的影響
+    pub simple_average: f64,
+    pub last_price: f64,
+    /// 窗格內的紀錄筆數（不含起點價格）
+    pub samples: usize,
+    /// 相鄰取樣（含最後一筆到現在）的最大間隔（秒）
+    pub max_gap_secs: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TwapReport {
+    pub subscription_id: i64,
+    pub window_secs: i64,
+    /// 窗格內沒有任何價格時為 None
+    pub twap: Option<Twap>,
+}
+
+/// subscription_id → (計算時間, Session VWAP)
+type SessionCache = HashMap<i64, (Instant, Option<f64>)>;
+
+static SESSION_CACHE: LazyLock<RwLock<SessionCache>> = LazyLock::new(|| RwLock::new(SessionCache::new()));
+
+/// 當日 00:00 UTC
+pub fn session_start(now: i64) -> i64 {
+    now - now.rem_euclid(DAY_SECS)
+}
+
+/// 由依時間排序的紀錄 `(recorded_at, price, volume)` 計算 VWAP
+pub fn compute_vwap(rows: &[(i64, f64, Option<f64>)]) -> Option<Vwap> {
+    let rows: Vec<_> = rows.iter().filter(|(_, price, _)| *price > 0.0).collect();
+    let (first, last) = (rows.first()?, rows.last()?);
+
+    let mut weighted = 0.0;
+    let mut total_volume = 0.0;
+    for pair in rows.windows(2) {
+        if let (Some(prev), Some(cur)) = (pair[0].2, pair[1].2) {
+            let traded = cur - prev;
+            if traded > 0.0 {
+                weighted += pair[1].1 * traded;
+                total_volume += traded;
+            }
+        }
+    }
+    let volume_weighted = total_volume > 0.0;
+    let vwap = if volume_weighted {
+        weighted / total_volume
+    } else {
+        rows.iter().map(|(_, price, _)| price).sum::<f64>() / rows.len() as f64
+    };
+
+    Some(Vwap {
+        from: first.0,
+        to: last.0,
+        vwap,
+        last_price: last.1,
+        deviation_pct: (last.1 - vwap) / vwap * 100.0,
+        samples: rows.len(),
+        volume_weighted,
+    })
+}
+
+/// 由窗格起點價格 `opening`（窗格起點前的最後一筆）與依時間排序的紀錄 `(recorded_at, price)`
+/// 計算 `[start, end]` 的 TWAP
+pub fn compute_twap(opening: Option<f64>, rows: &[(i64, f64)], start: i64, end: i64) -> Option<Twap> {
+    let rows: Vec<(i64, f64)> = rows
+        .iter()
+        .copied()
+        .filter(|&(ts, price)| price > 0.0 && ts >= start && ts <= end)
+        .collect();
+    let points: Vec<(i64, f64)> = opening
+        .filter(|price| *price > 0.0)
+        .map(|price| (start, price))
+        .into_iter()
+        .chain(rows.iter().copied())
+        .collect();
+    let (first, last) = (*points.first()?, *points.last()?);
+
+    let mut weighted = 0.0;
+    let mut duration = 0;
+    let mut max_gap_secs = 0;
+    for (i, &(ts, price)) in points.iter().enumerate() {
+        let held = points.get(i + 1).map_or(end, |next| next.0) - ts;
+        weighted += price * held as f64;
+        duration += held;
+        max_gap_secs = max_gap_secs.max(held);
+    }
+    let simple_average = if rows.is_empty() {
+        first.1
+    } else {
+        rows.iter().map(|(_, price)| price).sum::<f64>() / rows.len() as f64
+    };
+
+    Some(Twap {
+        from: first.0,
+        to: end,
+        // 唯一一筆紀錄剛好在現在時沒有持續時間
+        twap: if duration > 0 { weighted / duration as f64 } else { last.1 },
+        simple_average,
+        last_price: last.1,
+        samples: rows.len(),
+        max_gap_secs,
+    })
+}
+
+/// 讀取訂閱最近 `window_secs` 秒的 TWAP
+pub fn load_twap(db: &DbPool, subscription_id: i64, window_secs: i64, now: i64) -> Result<TwapReport, String> {
+    let start = now - window_secs;
+    let opening = db
+        .get_last_price_before(subscription_id, start, start - window_secs)?
+        .map(|(_, price)| price);
+    let rows: Vec<(i64, f64)> = db
+        .get_price_volume_since(subscription_id, start)?
+        .into_iter()
+        .map(|(ts, price, _)| (ts, price))
+        .collect();
+    Ok(TwapReport {
+        subscription_id,
+        window_secs,
+        twap: compute_twap(opening, &rows, start, now),
+    })
+}
+
+/// 讀取訂閱的 Session VWAP 與（指定 anchor 時）Anchored VWAP
+pub fn load(db: &DbPool, subscription_id: i64, anchor: Option<i64>, now: i64) -> Result<VwapReport, String> {
+    let session = compute_vwap(&db.get_price_volume_since(subscription_id, session_start(now))?);
+    let anchored = match anchor {
+        Some(from) => compute_vwap(&db.get_price_volume_since(subscription_id, from)?),
+        None => None,
+    };
+    Ok(VwapReport { subscription_id, session, anchored })
+}
+
+/// 規則評估用的 Session VWAP（快取 60 秒）；當日沒有紀錄時為 None
+pub fn session_vwap(db: &DbPool, subscription_id: i64, now: i64) -> Option<f64> {
+    if let Some((at, vwap)) = SESSION_CACHE.read().unwrap().get(&subscription_id) {
+        if at.elapsed() < SESSION_CACHE_TTL {
+            return *vwap;
+        }
+    }
+    let vwap = db
+        .get_price_volume_since(subscription_id, session_start(now))
+        .ok()
+        .and_then(|rows| compute_vwap(&rows))
+        .map(|v| v.vwap);
+    SESSION_CACHE.write().unwrap().insert(subscription_id, (Instant::now(), vwap));
+    vwap
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn vwap_weights_prices_by_volume_increments() {
+        // 成交量增量 10 @ 101、30 @ 103；104 那筆累計量回落（24h 滾動）不計入
+        let rows = [
+            (0, 100.0, Some(1_000.0)),
+            (60, 101.0, Some(1_010.0)),
+            (120, 103.0, Some(1_040.0)),
+            (180, 104.0, Some(1_020.0)),
+        ];
+        let v = compute_vwap(&rows).unwrap();
+        assert!((v.vwap - 102.5).abs() < 1e-9);
+        assert!(v.volume_weighted);
+        assert_eq!((v.from, v.to, v.samples, v.last_price), (0, 180, 4, 104.0));
+        assert!((v.deviation_pct - (104.0 - 102.5) / 102.5 * 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn vwap_without_volume_falls_back_to_mean() {
+        let v = compute_vwap(&[(0, 10.0, None), (60, 20.0, None), (120, 0.0, None)]).unwrap();
+        assert_eq!((v.vwap, v.samples, v.volume_weighted), (15.0, 2, false));
+        assert!(compute_vwap(&[]).is_none());
+    }
+
+    #[test]
+    fn twap_weights_prices_by_duration() {
+        // 100 持續 60 秒、110 持續 10 秒、120 持續 30 秒（到現在）
+        let t = compute_twap(None, &[(0, 100.0), (60, 110.0), (65, 0.0), (70, 120.0)], 0, 100).unwrap();
+        assert!((t.twap - (100.0 * 60.0 + 110.0 * 10.0 + 120.0 * 30.0) / 100.0).abs() < 1e-9);
+        assert!((t.simple_average - 110.0).abs() < 1e-9);
+        assert_eq!((t.from, t.to, t.samples, t.max_gap_secs, t.last_price), (0, 100, 3, 60, 120.0));
+
+        // 起點價格涵蓋窗格起點到第一筆紀錄
+        let t = compute_twap(Some(90.0), &[(-10, 80.0), (50, 100.0)], 0, 100).unwrap();
+        assert_eq!((t.from, t.twap, t.samples, t.simple_average), (0, 95.0, 1, 100.0));
+        let t = compute_twap(Some(90.0), &[], 0, 100).unwrap();
+        assert_eq!((t.twap, t.simple_average, t.samples, t.max_gap_secs), (90.0, 90.0, 0, 100));
+        assert_eq!(compute_twap(None, &[(100, 7.0)], 0, 100).unwrap().twap, 7.0);
+        assert!(compute_twap(None, &[], 0, 100).is_none());
+    }
+
+    #[test]
+    fn twap_report_uses_recent_opening_price() {
+        let db = DbPool::open(&PathBuf::from(":memory:")).unwrap();
+        let sub = db
+            .add_subscription("asset", "BTC", None, "binance", "crypto", None, None, None)
+            .unwrap();
+        let now = 10_000;
+        db.insert_price_history_for_test(
+            sub,
+            "binance",
+            &[(50.0, None, None, 1_000), (100.0, None, None, 5_000), (200.0, None, None, 9_000)],
+        )
+        .unwrap();
+
+        // 窗格 [6000, 10000]：起點價格 100 持續 3000 秒、200 持續 1000 秒
+        let report = load_twap(&db, sub, 4_000, now).unwrap();
+        assert_eq!(report.twap.unwrap().twap, 125.0);
+        // 起點前的紀錄早於一個窗格長度時不使用
+        let report = load_twap(&db, sub, 2_000, now).unwrap();
+        assert_eq!(report.twap.map(|t| (t.from, t.twap)), Some((9_000, 200.0)));
+        assert!(load_twap(&db, sub, 400, now).unwrap().twap.is_none());
+    }
+
+    #[test]
+    fn report_splits_session_and_anchor() {
+        let db = DbPool::open(&PathBuf::from(":memory:")).unwrap();
+        let sub = db
+            .add_subscription("asset", "AAPL", None, "yahoo", "stock", None, None, None)
+            .unwrap();
+        let now = 20 * DAY_SECS + 3_600;
+        db.insert_price_history_for_test(
+            sub,
+            "yahoo",
+            &[
+                (180.0, None, Some(100.0), 19 * DAY_SECS + 100),
+                (190.0, None, Some(200.0), 19 * DAY_SECS + 200),
+                (200.0, None, Some(10.0), 20 * DAY_SECS + 100),
+                (210.0, None, Some(30.0), 20 * DAY_SECS + 200),
+            ],
+        )
+        .unwrap();
+
+        let report = load(&db, sub, Some(19 * DAY_SECS), now).unwrap();
+        let session = report.session.unwrap();
+        assert_eq!((session.from, session.vwap), (20 * DAY_SECS + 100, 210.0));
+        let anchored = report.anchored.unwrap();
+        assert_eq!((anchored.samples, anchored.vwap), (4, (190.0 * 100.0 + 210.0 * 20.0) / 120.0));
+        assert_eq!(session_vwap(&db, sub, now), Some(210.0));
+        assert!(load(&db, sub, None, now).unwrap().anchored.is_none());
+    }
+}
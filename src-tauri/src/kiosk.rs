@@ -0,0 +1,320 @@
+//! Kiosk（唯讀）模式 — 牆面看板等場景避免誤觸修改 provider key 或刪除歷史。
+//!
+//! 兩種啟用方式：
+//! - 本機設定 `kiosk_enabled`：所有會修改資料的 command 與 REST 寫入都被拒絕，
+//!   解除需輸入 PIN（未設定 PIN 時任何人都能解除）。
+//! - 受限 token：REST 請求帶 `Authorization: Bearer <token>`（或 `X-Kiosk-Token`）時一律唯讀，
+//!   適合只把 token 網址交給看板的瀏覽器，其他用戶端不受影響。
+//!
+//! 唯讀時仍允許即時資料相關的呼叫（讀取、報價查詢、可見訂閱回報、WS stream 開關），
+//! 讓看板照常更新；command 以明確清單允許，會回傳明文密鑰的讀取一律拒絕，provider 設定的 key 會遮蔽。設定存於 app settings（`kiosk_*`，不隨設定匯出或同步），PIN 只保存 SHA-256。
+
+use std::sync::{LazyLock, RwLock};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// 唯讀時仍允許的非 GET REST 路徑（相對於 `/api`）
+const READ_ONLY_WRITES: &[&str] = &[
+    "/prices/fetch-multiple",
+    "/prices/fetch-multi",
+    "/dex/quotes",
+    "/system/visible-subscriptions",
+    "/ws/stream/start",
+    "/ws/stream/stop",
+    "/system/desktop-only",
+    // 解除鎖定自行驗證 PIN
+    "/system/kiosk",
+];
+
+/// 會回傳明文密鑰的讀取路徑：唯讀時一律拒絕（區網 listener 不論 scope 也拒絕）；
+/// 含路徑參數的見 [`reads_secrets`]
+const SECRET_READS: &[&str] = &[
+    // `?include_secrets=true` 含 API key
+    "/data/config",
+    // 通道設定含 bot token / webhook URL
+    "/notifications/channels",
+    // 含區網 listener token
+    "/system/lan-listener",
+    // provider 錯誤訊息可能含帶 key 的 URL（ring buffer 已遮蔽，仍不對唯讀用戶端開放）
+    "/system/logs",
+];
+
+/// 唯讀時允許的 command（逐一列出；不含會寫檔或匯出密鑰的 command）
+const READ_ONLY_COMMANDS: &[&str] = &[
+    // 報價與即時資料
+    "fetch_asset_price",
+    "fetch_multiple_prices",
+    "fetch_prices_multi",
+    "get_cached_prices",
+    "get_poll_ticks",
+    "get_polling_rates",
+    "set_visible_subscriptions",
+    "start_ws_stream",
+    "stop_ws_stream",
+    "get_ws_streams",
+    "get_gas_prices",
+    "get_depeg_status",
+    "get_economic_calendar",
+    "compare_dex_quotes",
+    "lookup_dex_pool",
+    "resolve_best_provider",
+    "render_snapshot",
+    "copy_price_to_clipboard",
+    "copy_history_csv_to_clipboard",
+    // providers（`list_provider_settings` 於鎖定中遮蔽 key）
+    "get_all_providers",
+    "list_provider_settings",
+    "has_api_key",
+    "get_provider_latency",
+    "get_price_anomaly_metrics",
+    // 訂閱、頁面、icon
+    "list_subscriptions",
+    "list_all_subscriptions",
+    "find_duplicate_subscriptions",
+    "list_views",
+    "get_view_sub_counts",
+    "get_view_subscription_ids",
+    "get_icons_dir",
+    "list_icon_mappings",
+    "search_icons",
+    "read_local_file_base64",
+    "get_theme_bg_path",
+    "open_icons_folder",
+    // 歷史紀錄
+    "get_record_hours",
+    "get_price_history",
+    "get_subscription_events",
+    "get_history_stats",
+    "get_streaks",
+    "get_vwap",
+    "get_sparkline",
+    "get_twap",
+    "get_candles",
+    // 通知（通道設定含密鑰，不列入）
+    "list_notification_rules",
+    "get_notification_history",
+    "get_notification_global_cooldown",
+    "get_ai_provider_config",
+    "list_ai_models",
+    // 系統與設定
+    "get_app_status",
+    "get_data_dir",
+    "get_api_port",
+    "get_api_enabled",
+    "get_unattended_polling",
+    "get_recording_paused",
+    "get_recording_schedule",
+    "get_power_mode",
+    "get_http_proxy",
+    "get_yahoo_quote_summary",
+    "get_jupiter_token_list",
+    "get_alphavantage_refresh_hours",
+    "get_alphavantage_quota",
+    "get_eodhd_default_exchange",
+    "get_provider_metadata_source",
+    "get_provider_metadata_status",
+    "get_subgraph_protocols",
+    "get_demo_mode",
+    "get_replay_config",
+    "get_gas_config",
+    "get_depeg_config",
+    "get_calendar_config",
+    "get_price_sanity_config",
+    "get_cache_limit",
+    "get_emit_config",
+    "get_record_stall_config",
+    "get_latency_tune_config",
+    "get_ws_first_config",
+    "get_candle_config",
+    "get_otlp_config",
+    "get_cloud_backup_config",
+    "list_cloud_backups",
+    "get_sync_config",
+    "get_log_level",
+    "get_language",
+    "open_log_dir",
+    "get_tray_config",
+    "get_close_to_tray",
+    "get_autostart",
+    "get_kiosk_config",
+    // 解除鎖定自行驗證 PIN
+    "set_kiosk_config",
+];
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KioskConfig {
+    pub enabled: bool,
+    /// 解除鎖定的 PIN（4–12 位數字）；讀取時不回傳，更新時留空表示沿用
+    pub pin: String,
+    /// 受限 token（至少 16 字元）；空字串停用
+    pub token: String,
+}
+
+impl KioskConfig {
+    pub fn normalized(mut self) -> Result<Self, String> {
+        self.pin = self.pin.trim().to_string();
+        self.token = self.token.trim().to_string();
+        if !self.pin.is_empty()
+            && (!(4..=12).contains(&self.pin.len()) || !self.pin.chars().all(|c| c.is_ascii_digit()))
+        {
+            return Err("Kiosk PIN must be 4-12 digits".to_string());
+        }
+        if !self.token.is_empty() && self.token.len() < 16 {
+            return Err("Kiosk token must be at least 16 characters".to_string());
+        }
+        Ok(self)
+    }
+}
+
+/// 生效中的 kiosk 狀態（PIN 只保留 hash）
+#[derive(Debug, Clone, Default)]
+pub struct KioskState {
+    pub enabled: bool,
+    pub pin_hash: Option<String>,
+    pub token: String,
+}
+
+/// 對外顯示的 kiosk 設定（不含 PIN）
+#[derive(Debug, Clone, Serialize)]
+pub struct KioskInfo {
+    pub enabled: bool,
+    pub has_pin: bool,
+    pub token: String,
+}
+
+impl KioskState {
+    pub fn info(&self) -> KioskInfo {
+        KioskInfo {
+            enabled: self.enabled,
+            has_pin: self.pin_hash.is_some(),
+            token: self.token.clone(),
+        }
+    }
+}
+
+static KIOSK: LazyLock<RwLock<KioskState>> = LazyLock::new(|| RwLock::new(KioskState::default()));
+
+pub fn set_kiosk_state(state: KioskState) {
+    *KIOSK.write().unwrap_or_else(|e| e.into_inner()) = state;
+}
+
+pub fn kiosk_state() -> KioskState {
+    KIOSK.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+pub fn hash_pin(pin: &str) -> String {
+    Sha256::digest(pin.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 鎖定中是否可以用此 PIN 解除（未設定 PIN 時一律可以）
+pub fn verify_pin(state: &KioskState, pin: &str) -> bool {
+    state.pin_hash.as_ref().is_none_or(|hash| *hash == hash_pin(pin.trim()))
+}
+
+/// 此請求是否為唯讀：本機鎖定中，或帶了受限 token
+pub fn is_read_only(state: &KioskState, token: Option<&str>) -> bool {
+    state.enabled || (!state.token.is_empty() && token == Some(state.token.as_str()))
+}
+
+/// 是否為讀取明文密鑰的 REST 請求
+pub fn reads_secrets(method: &str, path: &str) -> bool {
+    if !matches!(method, "GET" | "HEAD") {
+        return false;
+    }
+    let path = path.trim_end_matches('/');
+    // `/providers/:id/debug`：擷取的請求與錯誤可能含 API key
+    let provider_debug = path
+        .strip_prefix("/providers/")
+        .and_then(|rest| rest.strip_suffix("/debug"))
+        .is_some_and(|id| !id.is_empty() && !id.contains('/'));
+    provider_debug || SECRET_READS.contains(&path)
+}
+
+/// 唯讀時是否允許此 REST 請求
+pub fn allows_request(method: &str, path: &str) -> bool {
+    if reads_secrets(method, path) {
+        return false;
+    }
+    matches!(method, "GET" | "HEAD" | "OPTIONS") || READ_ONLY_WRITES.contains(&path.trim_end_matches('/'))
+}
+
+/// 唯讀時是否允許此 command
+pub fn allows_command(command: &str) -> bool {
+    READ_ONLY_COMMANDS.contains(&command)
+}
+
+/// 包裝 Tauri invoke handler：kiosk 鎖定中拒絕會修改資料的 command
+#[cfg(feature = "desktop")]
+pub fn guard_commands<F>(handler: F) -> impl Fn(tauri::ipc::Invoke<tauri::Wry>) -> bool + Send + Sync + 'static
+where
+    F: Fn(tauri::ipc::Invoke<tauri::Wry>) -> bool + Send + Sync + 'static,
+{
+    move |invoke| {
+        if kiosk_state().enabled && !allows_command(invoke.message.command()) {
+            invoke.resolver.reject(crate::commands::CommandError::localized(
+                crate::commands::ErrorCode::Forbidden,
+                crate::i18n::LocalizedError::new(crate::i18n::Msg::KioskLocked, &[]),
+            ));
+            return true;
+        }
+        handler(invoke)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_only_scope() {
+        let state = KioskState { enabled: false, pin_hash: None, token: "wall-display-token-1".into() };
+        assert!(is_read_only(&state, Some("wall-display-token-1")));
+        assert!(!is_read_only(&state, Some("other")));
+        assert!(!is_read_only(&state, None));
+        assert!(is_read_only(&KioskState { enabled: true, ..state }, None));
+
+        assert!(allows_request("GET", "/history/3"));
+        assert!(allows_request("POST", "/prices/fetch-multi"));
+        assert!(!allows_request("DELETE", "/history"));
+        assert!(!allows_request("PUT", "/provider-settings/binance"));
+
+        assert!(allows_command("get_all_providers"));
+        assert!(allows_command("set_visible_subscriptions"));
+        assert!(!allows_command("upsert_provider_settings"));
+        assert!(!allows_command("purge_all_history"));
+        assert!(!allows_command("open_deep_link"));
+        for command in ["export_secrets", "export_app_config", "export_file", "fetch_icon", "list_notification_channels", "get_lan_listener_config", "get_provider_debug", "get_recent_logs"] {
+            assert!(!allows_command(command), "{}", command);
+        }
+    }
+
+    #[test]
+    fn secret_reads_are_rejected() {
+        assert!(!allows_request("GET", "/data/config"));
+        assert!(!allows_request("GET", "/data/config/"));
+        assert!(!allows_request("GET", "/notifications/channels"));
+        assert!(!allows_request("GET", "/system/lan-listener"));
+        assert!(!allows_request("GET", "/system/logs"));
+        assert!(!allows_request("GET", "/providers/polygon/debug"));
+        assert!(!allows_request("GET", "/providers/polygon/debug/"));
+        assert!(allows_request("GET", "/providers"));
+        assert!(allows_request("GET", "/provider-settings"));
+        assert!(reads_secrets("HEAD", "/data/config"));
+        assert!(!reads_secrets("POST", "/notifications/channels"));
+    }
+
+    #[test]
+    fn pin_validation_and_check() {
+        assert!(KioskConfig { pin: "12a4".into(), ..Default::default() }.normalized().is_err());
+        assert!(KioskConfig { pin: "123".into(), ..Default::default() }.normalized().is_err());
+        assert!(KioskConfig { token: "short".into(), ..Default::default() }.normalized().is_err());
+        assert!(KioskConfig { pin: " 2468 ".into(), ..Default::default() }.normalized().is_ok());
+
+        let state = KioskState { enabled: true, pin_hash: Some(hash_pin("2468")), token: String::new() };
+        assert!(verify_pin(&state, "2468"));
+        assert!(!verify_pin(&state, "1357"));
+        assert!(verify_pin(&KioskState::default(), ""));
+    }
+}
@@ -0,0 +1,204 @@
+//! 區網 API listener — 與本機 listener 共用同一組路由，另外綁定一個位址／port 給區網用戶端。
+//!
+//! 本機 listener（`127.0.0.1`，不需驗證）照舊給本機工具使用；區網 listener 一律需要
+//! `Authorization: Bearer <token>`（不接受 query string，避免 token 留在 log 與瀏覽紀錄），
+//! 並依 `scope` 限制：
+//! - `read_only`（預設）：只允許讀取與即時資料相關的呼叫（與 kiosk 唯讀相同，但不能變更 kiosk 設定）
+//! - `full`：與本機 listener 相同
+//!
+//! 不論 scope 都不回傳明文密鑰：拒絕 [`crate::kiosk::reads_secrets`] 的路徑（含可能帶 key 的
+//! provider 除錯擷取與最近日誌），provider 設定遮蔽 key。
+//!
+//! token 與 scope 每個請求即時讀取；bind / port 變更時重新啟動 listener（[`crate::api::restart_lan_listener`]）。
+//! 設定存於 app settings（`lan_api_*`，不隨設定匯出或同步）。
+
+use std::net::IpAddr;
+use std::sync::{LazyLock, RwLock};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// token 最短長度
+pub const MIN_TOKEN_LEN: usize = 16;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ListenerScope {
+    #[default]
+    ReadOnly,
+    Full,
+}
+
+impl ListenerScope {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::ReadOnly => "read_only",
+            Self::Full => "full",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "read_only" => Some(Self::ReadOnly),
+            "full" => Some(Self::Full),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LanListenerConfig {
+    pub enabled: bool,
+    /// 綁定的介面位址（`0.0.0.0` = 所有介面）
+    pub bind: String,
+    pub port: u16,
+    /// 區網用戶端必須帶的 token（至少 [`MIN_TOKEN_LEN`] 字元）
+    pub token: String,
+    pub scope: ListenerScope,
+}
+
+impl Default for LanListenerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind: "0.0.0.0".to_string(),
+            port: 8081,
+            token: String::new(),
+            scope: ListenerScope::ReadOnly,
+        }
+    }
+}
+
+impl LanListenerConfig {
+    /// 檢查設定
+    pub fn normalized(mut self) -> Result<Self, String> {
+        self.bind = self.bind.trim().to_string();
+        self.token = self.token.trim().to_string();
+        if self.bind.parse::<IpAddr>().is_err() {
+            return Err(format!("Invalid bind address: {}", self.bind));
+        }
+        if self.port == 0 {
+            return Err("LAN listener port must be between 1 and 65535".to_string());
+        }
+        if (self.enabled || !self.token.is_empty()) && self.token.len() < MIN_TOKEN_LEN {
+            return Err(format!("LAN listener token must be at least {} characters", MIN_TOKEN_LEN));
+        }
+        Ok(self)
+    }
+
+    pub fn address(&self) -> String {
+        match self.bind.parse::<IpAddr>() {
+            Ok(IpAddr::V6(ip)) => format!("[{}]:{}", ip, self.port),
+            _ => format!("{}:{}", self.bind, self.port),
+        }
+    }
+}
+
+/// listener 目前的狀態
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LanListenerStatus {
+    /// 正在監聽的位址
+    pub listening: Option<String>,
+    /// 最近一次啟動失敗的原因
+    pub error: Option<String>,
+}
+
+/// `get_lan_listener_config` 回傳的設定與狀態
+#[derive(Debug, Clone, Serialize)]
+pub struct LanListenerInfo {
+    #[serde(flatten)]
+    pub config: LanListenerConfig,
+    pub status: LanListenerStatus,
+}
+
+static LAN_CONFIG: LazyLock<RwLock<LanListenerConfig>> =
+    LazyLock::new(|| RwLock::new(LanListenerConfig::default()));
+static LAN_STATUS: LazyLock<RwLock<LanListenerStatus>> =
+    LazyLock::new(|| RwLock::new(LanListenerStatus::default()));
+
+pub fn set_lan_listener_config(config: LanListenerConfig) {
+    *LAN_CONFIG.write().unwrap_or_else(|e| e.into_inner()) = config;
+}
+
+pub fn lan_listener_config() -> LanListenerConfig {
+    LAN_CONFIG.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+pub fn set_lan_listener_status(status: LanListenerStatus) {
+    *LAN_STATUS.write().unwrap_or_else(|e| e.into_inner()) = status;
+}
+
+pub fn lan_listener_info() -> LanListenerInfo {
+    LanListenerInfo {
+        config: lan_listener_config(),
+        status: LAN_STATUS.read().unwrap_or_else(|e| e.into_inner()).clone(),
+    }
+}
+
+/// 請求帶的 token 是否正確（比較 SHA-256，避免逐字比較洩漏時間差）
+pub fn authorize(config: &LanListenerConfig, token: Option<&str>) -> bool {
+    !config.token.is_empty()
+        && token.is_some_and(|t| Sha256::digest(t.as_bytes()) == Sha256::digest(config.token.as_bytes()))
+}
+
+/// 此 scope 是否允許這個 REST 請求（路徑相對於 `/api`）；不論 scope 都不回傳明文密鑰
+pub fn allows_request(scope: ListenerScope, method: &str, path: &str) -> bool {
+    if crate::kiosk::reads_secrets(method, path) {
+        return false;
+    }
+    match scope {
+        ListenerScope::Full => true,
+        ListenerScope::ReadOnly => {
+            path.trim_end_matches('/') != "/system/kiosk" && crate::kiosk::allows_request(method, path)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled(token: &str) -> LanListenerConfig {
+        LanListenerConfig { enabled: true, token: token.to_string(), ..Default::default() }
+    }
+
+    #[test]
+    fn validates_config() {
+        assert!(LanListenerConfig::default().normalized().is_ok());
+        assert!(enabled("").normalized().is_err());
+        assert!(enabled("short").normalized().is_err());
+        assert!(LanListenerConfig { bind: "lan".into(), ..enabled("lan-token-0123456789") }.normalized().is_err());
+        assert!(LanListenerConfig { port: 0, ..enabled("lan-token-0123456789") }.normalized().is_err());
+
+        let config = LanListenerConfig { bind: " ::1 ".into(), ..enabled(" lan-token-0123456789 ") }
+            .normalized()
+            .unwrap();
+        assert_eq!((config.token.as_str(), config.address().as_str()), ("lan-token-0123456789", "[::1]:8081"));
+        assert_eq!(LanListenerConfig::default().address(), "0.0.0.0:8081");
+    }
+
+    #[test]
+    fn token_and_scope() {
+        let config = enabled("lan-token-0123456789");
+        assert!(authorize(&config, Some("lan-token-0123456789")));
+        assert!(!authorize(&config, Some("lan-token-012345678")));
+        assert!(!authorize(&config, None));
+        assert!(!authorize(&LanListenerConfig::default(), Some("")));
+
+        assert!(allows_request(ListenerScope::ReadOnly, "GET", "/prices/cached"));
+        assert!(allows_request(ListenerScope::ReadOnly, "POST", "/prices/fetch-multi"));
+        assert!(!allows_request(ListenerScope::ReadOnly, "PUT", "/system/kiosk"));
+        assert!(!allows_request(ListenerScope::ReadOnly, "DELETE", "/history"));
+        assert!(allows_request(ListenerScope::Full, "DELETE", "/history"));
+        for scope in [ListenerScope::ReadOnly, ListenerScope::Full] {
+            assert!(!allows_request(scope, "GET", "/data/config"));
+            assert!(!allows_request(scope, "GET", "/notifications/channels"));
+            assert!(!allows_request(scope, "GET", "/system/lan-listener"));
+            assert!(!allows_request(scope, "GET", "/providers/polygon/debug"));
+            assert!(!allows_request(scope, "GET", "/system/logs"));
+        }
+        assert!(allows_request(ListenerScope::Full, "POST", "/notifications/channels"));
+        assert_eq!(ListenerScope::from_id(ListenerScope::Full.as_str()), Some(ListenerScope::Full));
+    }
+}
@@ -0,0 +1,290 @@
+//! Provider 延遲統計與輪詢間隔自動調整（選用）。
+//!
+//! polling 每次取價都記錄該 provider 的請求耗時（含 rate limit 等待），保留最近 [`WINDOW`] 筆，
+//! `get_provider_latency` / `/api/providers/latency` 回報 p50 / p95 與目前的實際間隔。
+//!
+//! 啟用自動調整時，累積至少 [`MIN_SAMPLES`] 筆新樣本後依 p95 調整該 provider 的 tick 間隔：
+//! - p95 達到目前間隔的 [`SLOW_RATIO`]：間隔 ×1.5，避免請求互相重疊
+//! - p95 低於目前間隔的 [`FAST_RATIO`]：間隔 ×0.8，逐步回到設定的間隔
+//!
+//! 實際間隔不低於設定的間隔與 `min_interval_ms`，也不高於 `max_interval_ms`。停用時立即回到設定的間隔。
+//! 設定存於 app settings（`latency_tune_enabled`、`latency_tune_min_ms`、`latency_tune_max_ms`）。
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{LazyLock, RwLock};
+
+use serde::{Deserialize, Serialize};
+
+/// 每個 provider 保留的延遲樣本數
+pub const WINDOW: usize = 20;
+/// 兩次調整之間至少需要的新樣本數
+pub const MIN_SAMPLES: usize = 5;
+/// p95 達到間隔的此比例時放慢
+pub const SLOW_RATIO: f64 = 0.8;
+/// p95 低於間隔的此比例時加快
+pub const FAST_RATIO: f64 = 0.25;
+/// 可設定的間隔範圍（ms）
+pub const MIN_BOUND_MS: u64 = 100;
+pub const MAX_BOUND_MS: u64 = 3_600_000;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LatencyTuneConfig {
+    pub enabled: bool,
+    /// 調整後的間隔下限（ms）；設定的間隔較大時以設定的間隔為準
+    pub min_interval_ms: u64,
+    /// 調整後的間隔上限（ms）
+    pub max_interval_ms: u64,
+}
+
+impl Default for LatencyTuneConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_interval_ms: 1_000,
+            max_interval_ms: 120_000,
+        }
+    }
+}
+
+impl LatencyTuneConfig {
+    /// 檢查設定
+    pub fn normalized(self) -> Result<Self, String> {
+        for ms in [self.min_interval_ms, self.max_interval_ms] {
+            if !(MIN_BOUND_MS..=MAX_BOUND_MS).contains(&ms) {
+                return Err(format!("Interval bounds must be between {} and {} ms", MIN_BOUND_MS, MAX_BOUND_MS));
+            }
+        }
+        if self.min_interval_ms > self.max_interval_ms {
+            return Err("Minimum interval must not exceed the maximum interval".to_string());
+        }
+        Ok(self)
+    }
+}
+
+/// 單一 provider 的延遲統計（`get_provider_latency`）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProviderLatency {
+    pub provider_id: String,
+    /// 目前保留的樣本數
+    pub samples: usize,
+    pub last_ms: u64,
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+    /// 設定（訂閱／provider）決定的 tick 間隔
+    pub configured_interval_ms: u64,
+    /// 實際使用的 tick 間隔
+    pub effective_interval_ms: u64,
+    /// 程式啟動後的自動調整次數
+    pub adjustments: u64,
+}
+
+#[derive(Debug)]
+struct Tracker {
+    samples: VecDeque<u64>,
+    since_adjust: usize,
+    configured_ms: u64,
+    effective_ms: u64,
+    adjustments: u64,
+}
+
+impl Tracker {
+    fn new(configured_ms: u64) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(WINDOW),
+            since_adjust: 0,
+            configured_ms,
+            effective_ms: configured_ms,
+            adjustments: 0,
+        }
+    }
+
+    fn percentile(&self, pct: usize) -> u64 {
+        let mut sorted: Vec<u64> = self.samples.iter().copied().collect();
+        sorted.sort_unstable();
+        sorted
+            .get((sorted.len() * pct).div_ceil(100).saturating_sub(1))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    fn observe(&mut self, configured_ms: u64, latency_ms: u64, config: &LatencyTuneConfig) -> u64 {
+        self.configured_ms = configured_ms;
+        self.samples.push_back(latency_ms);
+        while self.samples.len() > WINDOW {
+            self.samples.pop_front();
+        }
+        self.since_adjust += 1;
+
+        if !config.enabled {
+            self.effective_ms = configured_ms;
+            return self.effective_ms;
+        }
+        let floor = configured_ms.max(config.min_interval_ms);
+        let ceiling = config.max_interval_ms.max(floor);
+        let current = self.effective_ms.clamp(floor, ceiling);
+        let next = if self.since_adjust >= MIN_SAMPLES {
+            tuned_interval(current, self.percentile(95)).clamp(floor, ceiling)
+        } else {
+            current
+        };
+        if next != current {
+            self.since_adjust = 0;
+            self.adjustments += 1;
+        }
+        self.effective_ms = next;
+        next
+    }
+}
+
+/// 依 p95 延遲調整間隔（未套用上下限）
+pub fn tuned_interval(current_ms: u64, p95_ms: u64) -> u64 {
+    let (current, p95) = (current_ms as f64, p95_ms as f64);
+    if p95 >= current * SLOW_RATIO {
+        current_ms.saturating_mul(3) / 2
+    } else if p95 < current * FAST_RATIO {
+        current_ms.saturating_mul(4) / 5
+    } else {
+        current_ms
+    }
+}
+
+static TUNE_CONFIG: LazyLock<RwLock<LatencyTuneConfig>> =
+    LazyLock::new(|| RwLock::new(LatencyTuneConfig::default()));
+static TRACKERS: LazyLock<RwLock<HashMap<String, Tracker>>> = LazyLock::new(|| RwLock::new(HashMap::new()));
+
+pub fn set_latency_tune_config(config: LatencyTuneConfig) {
+    *TUNE_CONFIG.write().unwrap_or_else(|e| e.into_inner()) = config;
+}
+
+pub fn latency_tune_config() -> LatencyTuneConfig {
+    TUNE_CONFIG.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// provider 目前的 tick 間隔 — polling 重新載入後沿用上一輪調整的結果
+pub fn effective_interval(provider_id: &str, configured_ms: u64) -> u64 {
+    let config = latency_tune_config();
+    if !config.enabled {
+        return configured_ms;
+    }
+    let floor = configured_ms.max(config.min_interval_ms);
+    let ceiling = config.max_interval_ms.max(floor);
+    TRACKERS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(provider_id)
+        .map_or(configured_ms, |t| t.effective_ms)
+        .clamp(floor, ceiling)
+}
+
+/// 記錄一次取價耗時，回傳下一個 tick 應使用的間隔
+pub fn observe(provider_id: &str, configured_ms: u64, latency_ms: u64) -> u64 {
+    let config = latency_tune_config();
+    let mut trackers = TRACKERS.write().unwrap_or_else(|e| e.into_inner());
+    let tracker = trackers
+        .entry(provider_id.to_string())
+        .or_insert_with(|| Tracker::new(configured_ms));
+    let before = tracker.effective_ms;
+    let next = tracker.observe(configured_ms, latency_ms, &config);
+    if config.enabled && next != before {
+        tracing::info!(
+            "[LatencyTune] {} interval {}ms -> {}ms (p95 {}ms)",
+            provider_id,
+            before,
+            next,
+            tracker.percentile(95)
+        );
+    }
+    next
+}
+
+/// 移除已不在輪詢的 provider
+pub fn retain(active: &HashSet<&str>) {
+    TRACKERS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .retain(|pid, _| active.contains(pid.as_str()));
+}
+
+/// 各 provider 的延遲統計（依 provider_id 排序）
+pub fn latency_metrics() -> Vec<ProviderLatency> {
+    let trackers = TRACKERS.read().unwrap_or_else(|e| e.into_inner());
+    let mut metrics: Vec<ProviderLatency> = trackers
+        .iter()
+        .map(|(provider_id, t)| ProviderLatency {
+            provider_id: provider_id.clone(),
+            samples: t.samples.len(),
+            last_ms: t.samples.back().copied().unwrap_or(0),
+            p50_ms: t.percentile(50),
+            p95_ms: t.percentile(95),
+            configured_interval_ms: t.configured_ms,
+            effective_interval_ms: t.effective_ms,
+            adjustments: t.adjustments,
+        })
+        .collect();
+    metrics.sort_by(|a, b| a.provider_id.cmp(&b.provider_id));
+    metrics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled(min_interval_ms: u64, max_interval_ms: u64) -> LatencyTuneConfig {
+        LatencyTuneConfig { enabled: true, min_interval_ms, max_interval_ms }
+    }
+
+    #[test]
+    fn validates_bounds() {
+        assert!(LatencyTuneConfig::default().normalized().is_ok());
+        assert!(enabled(5_000, 1_000).normalized().is_err());
+        assert!(enabled(50, 1_000).normalized().is_err());
+        assert!(enabled(1_000, MAX_BOUND_MS + 1).normalized().is_err());
+    }
+
+    #[test]
+    fn percentiles_use_nearest_rank() {
+        let mut t = Tracker::new(1_000);
+        for ms in 1..=20 {
+            t.observe(1_000, ms * 10, &LatencyTuneConfig::default());
+        }
+        assert_eq!((t.percentile(50), t.percentile(95)), (100, 190));
+        assert_eq!(Tracker::new(1_000).percentile(95), 0);
+    }
+
+    #[test]
+    fn slow_provider_backs_off_within_bounds_then_recovers() {
+        let config = enabled(1_000, 5_000);
+        let mut t = Tracker::new(2_000);
+        // 樣本不足時不調整
+        for _ in 0..MIN_SAMPLES - 1 {
+            assert_eq!(t.observe(2_000, 1_900, &config), 2_000);
+        }
+        assert_eq!(t.observe(2_000, 1_900, &config), 3_000);
+        // 需要重新累積樣本；p95 1900 < 3000 × 0.8 時維持
+        for _ in 0..MIN_SAMPLES {
+            assert_eq!(t.observe(2_000, 1_900, &config), 3_000);
+        }
+        for _ in 0..MIN_SAMPLES * 4 {
+            t.observe(2_000, 4_500, &config);
+        }
+        assert_eq!(t.effective_ms, 5_000);
+
+        // 變快後逐步回到設定的間隔，不低於設定值
+        for _ in 0..WINDOW * 4 {
+            t.observe(2_000, 50, &config);
+        }
+        assert_eq!(t.effective_ms, 2_000);
+        assert!(t.adjustments >= 4);
+
+        // 停用時立即回到設定的間隔
+        t.effective_ms = 4_000;
+        assert_eq!(t.observe(2_000, 4_500, &LatencyTuneConfig::default()), 2_000);
+    }
+
+    #[test]
+    fn min_bound_raises_fast_configured_intervals() {
+        let mut t = Tracker::new(500);
+        assert_eq!(t.observe(500, 10, &enabled(1_000, 5_000)), 1_000);
+        assert_eq!(tuned_interval(1_000, 500), 1_000);
+    }
+}
@@ -1,32 +1,93 @@
 pub mod api;
+pub mod basket;
+pub mod cache_limit;
+pub mod calendar;
+pub mod candles;
+pub mod clipboard;
+pub mod cloud_backup;
 #[cfg(feature = "desktop")]
 mod commands;
 pub mod config;
 pub mod core_state;
 pub mod db;
+pub mod deep_link;
+pub mod depeg;
+pub mod emit_throttle;
 pub mod events;
+pub mod file_access;
+pub mod file_export;
+pub mod gas;
+pub mod headless;
+pub mod history_csv;
+pub mod i18n;
 pub mod icons;
+pub mod indicators;
+pub mod kiosk;
+pub mod lan_listener;
+pub mod latency_tune;
+pub mod logging;
 pub mod notifications;
 pub mod polling;
+pub mod power;
+pub mod provider_resolver;
 pub mod providers;
+pub mod record_health;
+pub mod sanity;
+pub mod schedule;
+pub mod secrets;
+pub mod settings_sync;
+pub mod snapshot;
+pub mod sparkline;
+pub mod streaks;
+pub mod subscription_csv;
+pub mod subscription_dedup;
+pub mod telemetry;
+pub mod timeframes;
+pub mod watchdog;
+pub mod ws_first;
+pub mod ws_manager;
+pub mod ws_proxy;
+#[cfg(feature = "desktop")]
+mod tray;
 
 #[cfg(feature = "desktop")]
 use commands::{
-    add_sub_to_view, add_subscription, add_subscriptions_batch, cleanup_history,
+    add_sub_to_view, add_subscription, add_subscriptions_batch, cleanup_history, clear_provider_cache,
     create_notification_rule, create_view, delete_notification_channel, delete_notification_rule,
-    delete_subscription_history, delete_view, download_logos, clear_all_icons, download_single_icon, search_icons, save_icon_from_data, enable_provider, export_data,
-    export_file, fetch_asset_price, fetch_multiple_prices, get_ai_provider_config, get_all_providers,
-    get_api_enabled, get_api_port, get_cached_prices, get_data_dir, get_history_stats,
+    delete_subscription_history, delete_view, download_logos, clear_all_icons, download_single_icon, fetch_icon, list_icon_mappings, search_icons, save_icon_from_data, enable_provider, export_data,
+    copy_history_csv_to_clipboard, copy_price_to_clipboard,
+    export_file, export_secrets, fetch_asset_price, fetch_multiple_prices, fetch_prices_multi, get_ai_provider_config, get_all_providers,
+    get_api_enabled, get_api_port, get_cached_prices, render_snapshot, get_ws_streams, get_data_dir, get_history_stats, get_subscription_events, get_streaks, get_vwap, get_sparkline, get_twap, get_candles, import_history_csv, get_http_proxy,
+    get_demo_mode, get_log_level, get_recent_logs, get_replay_config, open_log_dir, set_demo_mode,
+    get_yahoo_quote_summary, set_yahoo_quote_summary, get_jupiter_token_list, set_jupiter_token_list,
+    get_alphavantage_refresh_hours, set_alphavantage_refresh_hours, get_alphavantage_quota,
+    get_eodhd_default_exchange, set_eodhd_default_exchange,
+    get_provider_metadata_source, get_provider_metadata_status, set_provider_metadata_source,
+    get_subgraph_protocols, set_subgraph_protocols,
+    set_log_level, get_language, set_language, set_replay_config, get_provider_debug, set_provider_debug, resolve_best_provider,
+    get_recording_paused, set_recording_paused, get_tray_config, set_tray_config,
+    get_close_to_tray, set_close_to_tray, get_autostart, set_autostart,
+    get_recording_schedule, set_recording_schedule, get_power_mode, set_power_mode, open_deep_link,
+    get_gas_config, set_gas_config, get_gas_prices,
+    get_depeg_config, set_depeg_config, get_depeg_status,
+    get_calendar_config, set_calendar_config, get_economic_calendar, refresh_economic_calendar,
+    get_price_sanity_config, set_price_sanity_config, get_price_anomaly_metrics, get_provider_latency,
+    get_cache_limit, set_cache_limit, get_emit_config, set_emit_config, get_record_stall_config, set_record_stall_config, get_latency_tune_config, set_latency_tune_config, get_ws_first_config, set_ws_first_config, get_candle_config, set_candle_config, get_app_status, get_otlp_config, set_otlp_config,
+    get_cloud_backup_config, set_cloud_backup_config, run_cloud_backup, list_cloud_backups, restore_from_cloud,
+    get_sync_config, set_sync_config, sync_settings_now, get_kiosk_config, set_kiosk_config, get_lan_listener_config, set_lan_listener_config,
+    export_app_config, import_app_config, import_subscriptions_csv, create_basket,
+    find_duplicate_subscriptions, merge_subscriptions,
     get_icons_dir, get_notification_global_cooldown, get_notification_history, get_poll_ticks, open_icons_folder,
     get_price_history, get_theme_bg_path, get_unattended_polling, get_view_sub_counts,
     get_view_subscription_ids, has_api_key, import_data, import_file, list_all_subscriptions,
     list_notification_channels, list_notification_rules,
-    list_provider_settings, list_subscriptions, list_views, lookup_dex_pool, purge_all_history,
+    list_provider_settings, list_subscriptions, list_views, lookup_dex_pool, compare_dex_quotes, migrate_secrets_to_keyring,
+    purge_all_history,
     read_local_file_base64, reload_polling, remove_icon, remove_sub_from_view, remove_subscription,
     remove_subscriptions, remove_theme_bg, rename_view, reset_all_data, save_ai_provider_config,
-    save_notification_channel, save_theme_bg, set_api_enabled, set_api_port, set_icon,
-    set_notification_global_cooldown, set_provider_record_hours, set_record_hours,
-    set_unattended_polling, set_visible_subscriptions, start_ws_stream, stop_ws_stream,
+    save_notification_channel, save_theme_bg, save_theme_bg_from_path, set_api_enabled, set_api_port, set_http_proxy, set_icon, set_icon_from_path,
+    set_notification_global_cooldown, set_provider_params, set_provider_record_hours, set_record_hours, set_record_dedup, get_record_hours, clear_record_hours,
+    set_unattended_polling, set_visible_subscriptions, get_polling_rates, start_ws_stream, stop_ws_stream,
     test_ai_connection, list_ai_models, test_notification_channel, toggle_notification_rule,
     toggle_record, update_notification_rule, update_subscription, upsert_provider_settings,
 };
@@ -36,9 +97,9 @@ use core_state::CoreState;
 #[cfg(feature = "desktop")]
 use db::PriceRecord;
 #[cfg(feature = "desktop")]
-use events::AppEvent;
+use emit_throttle::EventCoalescer;
 #[cfg(feature = "desktop")]
-use std::collections::HashSet;
+use events::AppEvent;
 #[cfg(feature = "desktop")]
 use std::sync::Arc;
 #[cfg(feature = "desktop")]
@@ -46,14 +107,54 @@ use tauri::{Emitter, Manager};
 #[cfg(feature = "desktop")]
 use tokio::sync::broadcast;
 
+/// 節流中的事件到期時間；沒有待送出的事件時永不完成
+#[cfg(feature = "desktop")]
+async fn flush_deadline(at: Option<tokio::time::Instant>) {
+    match at {
+        Some(at) => tokio::time::sleep_until(at).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// 送出合併後的 price-update（AssetData 陣列）與 poll-tick（PollTickPayload 陣列）
+#[cfg(feature = "desktop")]
+fn emit_coalesced(app: &tauri::AppHandle, coalescer: &mut EventCoalescer) {
+    if coalescer.is_empty() {
+        return;
+    }
+    let (prices, ticks) = coalescer.take(std::time::Instant::now());
+    if !prices.is_empty() {
+        let _ = app.emit("price-update", &prices);
+    }
+    if !ticks.is_empty() {
+        let _ = app.emit("poll-tick", &ticks);
+    }
+}
+
 #[cfg(feature = "desktop")]
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // `--headless`：不建立視窗，只跑 polling + 紀錄 + HTTP API
+    if headless::requested() {
+        headless::run();
+        return;
+    }
+
     tauri::Builder::default()
+        // 必須最先註冊：第二個實例會在建立 polling / DB 之前結束，改由既有實例接手
+        .plugin(tauri_plugin_single_instance::init(tray::on_second_instance))
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_notification::init())
-        .invoke_handler(tauri::generate_handler![
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_autostart::init(
+            tauri_plugin_autostart::MacosLauncher::LaunchAgent,
+            None,
+        ))
+        .on_window_event(tray::on_window_event)
+        // kiosk 鎖定中只放行讀取與即時資料相關的 command
+        .invoke_handler(kiosk::guard_commands(tauri::generate_handler![
             // 注意：部分指令目前前端尚未呼叫（如 enable_provider、get_unattended_polling、
             // remove_icon、set_provider_record_hours、get_history_stats），屬刻意保留的 IPC 介面：
             // 其底層能力已存在且部分經由其他路徑使用（例：api/ module 直接用 polling.is_unattended()，
@@ -62,27 +163,91 @@ pub fn run() {
             // Provider / Fetch
             fetch_asset_price,
             fetch_multiple_prices,
+            fetch_prices_multi,
             get_all_providers,
             enable_provider,
             // Polling
             reload_polling,
             set_unattended_polling,
             get_unattended_polling,
+            set_recording_paused,
+            get_recording_paused,
+            get_recording_schedule,
+            set_recording_schedule,
+            get_power_mode,
+            set_power_mode,
+            get_gas_config,
+            set_gas_config,
+            get_depeg_config,
+            set_depeg_config,
+            get_calendar_config,
+            set_calendar_config,
+            get_price_sanity_config,
+            set_price_sanity_config,
+            get_cache_limit,
+            set_cache_limit,
+            get_emit_config,
+            set_emit_config,
+            get_record_stall_config,
+            set_record_stall_config,
+            get_latency_tune_config,
+            set_latency_tune_config,
+            get_ws_first_config,
+            set_ws_first_config,
+            get_candle_config,
+            set_candle_config,
+            get_app_status,
+            get_otlp_config,
+            set_otlp_config,
+            get_cloud_backup_config,
+            set_cloud_backup_config,
+            run_cloud_backup,
+            list_cloud_backups,
+            restore_from_cloud,
+            get_sync_config,
+            set_sync_config,
+            sync_settings_now,
+            get_kiosk_config,
+            set_kiosk_config,
+            get_lan_listener_config,
+            set_lan_listener_config,
+            open_deep_link,
             set_visible_subscriptions,
+            get_polling_rates,
             get_cached_prices,
+            render_snapshot,
+            copy_price_to_clipboard,
+            copy_history_csv_to_clipboard,
             get_poll_ticks,
+            get_gas_prices,
+            get_depeg_status,
+            get_economic_calendar,
+            refresh_economic_calendar,
             // Subscriptions (NEW)
             list_subscriptions,
             list_all_subscriptions,
             add_subscription,
             add_subscriptions_batch,
+            import_subscriptions_csv,
+            create_basket,
+            find_duplicate_subscriptions,
+            merge_subscriptions,
             update_subscription,
+            set_provider_params,
             remove_subscription,
             remove_subscriptions,
             has_api_key,
             // Provider Settings (NEW)
             list_provider_settings,
             upsert_provider_settings,
+            clear_provider_cache,
+            get_provider_debug,
+            set_provider_debug,
+            resolve_best_provider,
+            get_price_anomaly_metrics,
+            get_provider_latency,
+            migrate_secrets_to_keyring,
+            export_secrets,
             // Views (NEW)
             list_views,
             create_view,
@@ -94,20 +259,25 @@ pub fn run() {
             remove_sub_from_view,
             // WebSocket
             start_ws_stream,
+            get_ws_streams,
             stop_ws_stream,
             // Icons
             set_icon,
+            set_icon_from_path,
             remove_icon,
             get_icons_dir,
             open_icons_folder,
             download_logos,
             clear_all_icons,
             download_single_icon,
+            fetch_icon,
+            list_icon_mappings,
             search_icons,
             save_icon_from_data,
             read_local_file_base64,
             // Theme
             save_theme_bg,
+            save_theme_bg_from_path,
             remove_theme_bg,
             get_theme_bg_path,
             // Import/Export
@@ -115,14 +285,27 @@ pub fn run() {
             import_file,
             export_data,
             import_data,
+            export_app_config,
+            import_app_config,
             // DEX
             lookup_dex_pool,
+            compare_dex_quotes,
             // History
             toggle_record,
             set_record_hours,
+            set_record_dedup,
             set_provider_record_hours,
+            get_record_hours,
+            clear_record_hours,
             get_price_history,
+            get_subscription_events,
             get_history_stats,
+            get_streaks,
+            get_vwap,
+            get_sparkline,
+            get_twap,
+            get_candles,
+            import_history_csv,
             cleanup_history,
             purge_all_history,
             delete_subscription_history,
@@ -133,6 +316,42 @@ pub fn run() {
             set_api_port,
             get_api_enabled,
             set_api_enabled,
+            get_http_proxy,
+            set_http_proxy,
+            get_yahoo_quote_summary,
+            set_yahoo_quote_summary,
+            get_jupiter_token_list,
+            set_jupiter_token_list,
+            get_alphavantage_refresh_hours,
+            set_alphavantage_refresh_hours,
+            get_alphavantage_quota,
+            get_eodhd_default_exchange,
+            set_eodhd_default_exchange,
+            get_provider_metadata_source,
+            get_provider_metadata_status,
+            set_provider_metadata_source,
+            get_subgraph_protocols,
+            set_subgraph_protocols,
+            // Demo mode
+            get_demo_mode,
+            set_demo_mode,
+            // History replay
+            get_replay_config,
+            set_replay_config,
+            // Logging
+            get_recent_logs,
+            get_log_level,
+            set_log_level,
+            get_language,
+            set_language,
+            open_log_dir,
+            // System tray / autostart
+            get_tray_config,
+            set_tray_config,
+            get_close_to_tray,
+            set_close_to_tray,
+            get_autostart,
+            set_autostart,
             // Notifications
             create_notification_rule,
             list_notification_rules,
@@ -151,7 +370,7 @@ pub fn run() {
             get_ai_provider_config,
             test_ai_connection,
             list_ai_models,
-        ])
+        ]))
         .setup(|app| {
             // Data directory — unified across desktop/server, dev/release:
             // Use SB_DATA_DIR env var if set, otherwise `./data` from CWD.
@@ -162,22 +381,27 @@ pub fn run() {
                 .map(std::path::PathBuf::from)
                 .unwrap_or_else(|_| std::path::PathBuf::from("./data"));
 
+            logging::init(&data_dir.join("logs"));
+
             {
                 // Build unified CoreState (handles DB, registry, event bus, etc.)
                 let core = CoreState::new(&data_dir)
                     .expect("Failed to initialize CoreState");
+                // Desktop theme backgrounds live under the app data dir, not `data_dir`
+                if let Ok(app_data_dir) = app.path().app_data_dir() {
+                    core.file_access.allow_dir(app_data_dir.join("theme_bg"));
+                }
                 let core = Arc::new(core);
 
                 // Start polling inside async context so tokio::spawn works
                 let polling_ref = core.polling.clone();
                 let db_for_polling = core.db.clone();
-                let db_for_unattended = core.db.clone();
+                let recordings_need_unattended = core.recordings_need_unattended();
                 let registry_for_polling = core.registry.clone();
                 let event_bus_for_polling = core.event_bus.clone();
                 tauri::async_runtime::spawn(async move {
                     // Auto-set unattended based on active recordings at startup
-                    let active_count = db_for_unattended.count_active_recordings().unwrap_or(0);
-                    if active_count > 0 {
+                    if recordings_need_unattended {
                         polling_ref.set_unattended(true).await;
                     }
 
@@ -188,48 +412,73 @@ pub fn run() {
                     );
                 });
 
+                // WsManager 的 ticker 轉發到前端（所有 provider 共用一個 forwarder）
+                let app_for_ws = app.handle().clone();
+                let mut ws_rx = core.ws.subscribe();
+                tauri::async_runtime::spawn(async move {
+                    loop {
+                        match ws_rx.recv().await {
+                            Ok(update) => {
+                                let _ = app_for_ws.emit("ws-ticker-update", &update);
+                            }
+                            Err(broadcast::error::RecvError::Lagged(n)) => {
+                                tracing::warn!("[WS] Ticker forwarder lagged {} updates", n);
+                            }
+                            Err(broadcast::error::RecvError::Closed) => break,
+                        }
+                    }
+                });
+
                 let db_for_forwarder = core.db.clone();
+                let core_for_forwarder = core.clone();
                 let app_for_forwarder = app.handle().clone();
                 let mut event_rx = core.event_bus.subscribe();
                 tauri::async_runtime::spawn(async move {
+                    // price-update / poll-tick 節流：合併後每秒最多送出 max_per_sec 次
+                    let mut coalescer = EventCoalescer::default();
+                    let mut flush_at: Option<tokio::time::Instant> = None;
                     loop {
-                        match event_rx.recv().await {
+                        let received = tokio::select! {
+                            received = event_rx.recv() => received,
+                            _ = flush_deadline(flush_at) => {
+                                flush_at = None;
+                                emit_coalesced(&app_for_forwarder, &mut coalescer);
+                                continue;
+                            }
+                        };
+                        match received {
                             Ok(event) => match event {
                                 AppEvent::PriceUpdate {
                                     provider_id,
                                     data,
-                                    record_symbols,
+                                    record_targets,
                                 } => {
-                                    let _ = app_for_forwarder.emit("price-update", &data);
-                                    if !record_symbols.is_empty() {
-                                        let record_set: HashSet<String> =
-                                            record_symbols.into_iter().collect();
+                                    match emit_throttle::emit_config().interval() {
+                                        Some(interval) => {
+                                            coalescer.push_prices(&data);
+                                            flush_at.get_or_insert_with(|| {
+                                                coalescer.due(std::time::Instant::now(), interval).into()
+                                            });
+                                        }
+                                        None => {
+                                            let _ = app_for_forwarder.emit("price-update", &data);
+                                        }
+                                    }
+                                    if !record_targets.is_empty() {
                                         let records: Vec<PriceRecord> = data
                                             .iter()
-                                            .filter(|d| record_set.contains(&d.symbol))
-                                            .map(|d| {
-                                                let pre = d
-                                                    .extra
-                                                    .as_ref()
-                                                    .and_then(|e| e.get("pre_market_price"))
-                                                    .and_then(|v| v.as_f64());
-                                                let post = d
-                                                    .extra
-                                                    .as_ref()
-                                                    .and_then(|e| e.get("post_market_price"))
-                                                    .and_then(|v| v.as_f64());
-                                                (
-                                                    d.symbol.clone(),
-                                                    d.price,
-                                                    d.change_percent_24h,
-                                                    d.volume,
-                                                    pre,
-                                                    post,
-                                                )
-                                            })
+                                            .filter(|d| record_targets.contains_key(&d.symbol))
+                                            .map(|d| d.price_record())
                                             .collect();
-                                        db_for_forwarder
-                                            .write_price_history(&provider_id, &records);
+                                        for error in db_for_forwarder.write_price_history(
+                                            &provider_id,
+                                            &records,
+                                            &record_targets,
+                                        ) {
+                                            let _ = core_for_forwarder
+                                                .event_bus
+                                                .send(AppEvent::RecordError(error));
+                                        }
                                     }
                                 }
                                 AppEvent::PriceError {
@@ -251,14 +500,22 @@ pub fn run() {
                                     fetched_at,
                                     interval_ms,
                                 } => {
-                                    let _ = app_for_forwarder.emit(
-                                        "poll-tick",
-                                        &events::PollTickPayload {
-                                            provider_id,
-                                            fetched_at,
-                                            interval_ms,
-                                        },
-                                    );
+                                    let tick = events::PollTickPayload {
+                                        provider_id,
+                                        fetched_at,
+                                        interval_ms,
+                                    };
+                                    match emit_throttle::emit_config().interval() {
+                                        Some(interval) => {
+                                            coalescer.push_tick(tick);
+                                            flush_at.get_or_insert_with(|| {
+                                                coalescer.due(std::time::Instant::now(), interval).into()
+                                            });
+                                        }
+                                        None => {
+                                            let _ = app_for_forwarder.emit("poll-tick", &tick);
+                                        }
+                                    }
                                 }
                                 AppEvent::NotificationTriggered(payload) => {
                                     let _ = app_for_forwarder
@@ -277,9 +534,51 @@ pub fn run() {
                                     let _ = app_for_forwarder
                                         .emit("logo-download-progress", &progress);
                                 }
+                                AppEvent::SystemResumed { slept_secs } => {
+                                    let _ = app_for_forwarder.emit(
+                                        "system-resumed",
+                                        &serde_json::json!({ "slept_secs": slept_secs }),
+                                    );
+                                    // 休眠期間 socket 已無聲斷線，省電模式下維持暫停
+                                    if !power::is_low_power() {
+                                        core_for_forwarder.ws.resume();
+                                    }
+                                }
+                                AppEvent::GasUpdate(price) => {
+                                    let _ = app_for_forwarder.emit("gas-update", &price);
+                                }
+                                AppEvent::DepegUpdate(statuses) => {
+                                    let _ = app_for_forwarder.emit("depeg-update", &statuses);
+                                }
+                                AppEvent::CalendarUpdate(events) => {
+                                    let _ = app_for_forwarder.emit("calendar-update", &events);
+                                }
+                                AppEvent::PriceAnomaly(anomaly) => {
+                                    let _ = app_for_forwarder.emit("price-anomaly", &anomaly);
+                                }
+                                AppEvent::ProviderRecovered(recovered) => {
+                                    let _ = app_for_forwarder.emit("provider-recovered", &recovered);
+                                }
+                                AppEvent::RecordError(error) => {
+                                    let _ = app_for_forwarder.emit("record-error", &error);
+                                }
+                                AppEvent::CandleClose(candle) => {
+                                    let _ = app_for_forwarder.emit("candle-close", &candle);
+                                }
+                                AppEvent::WsStatus(status) => {
+                                    let _ = app_for_forwarder.emit("ws-status", &status);
+                                }
+                                AppEvent::PowerMode(payload) => {
+                                    let _ = app_for_forwarder.emit("power-mode", &payload);
+                                    if payload.low_power {
+                                        core_for_forwarder.ws.suspend();
+                                    } else {
+                                        core_for_forwarder.ws.resume();
+                                    }
+                                }
                             },
                             Err(broadcast::error::RecvError::Lagged(n)) => {
-                                eprintln!("[EventBus] Forwarder lagged {} events", n);
+                                tracing::warn!("[EventBus] Forwarder lagged {} events", n);
                             }
                             Err(broadcast::error::RecvError::Closed) => break,
                         }
@@ -288,6 +587,31 @@ pub fn run() {
 
                 app.manage(core.clone());
 
+                if let Err(e) = tray::init(app.handle(), &core) {
+                    tracing::warn!("[Tray] Failed to create tray icon: {}", e);
+                }
+
+                {
+                    use tauri_plugin_deep_link::DeepLinkExt;
+                    // Windows / Linux 開發模式需在執行期註冊 URL scheme（安裝版由 bundler 註冊）
+                    #[cfg(any(windows, target_os = "linux"))]
+                    if let Err(e) = app.deep_link().register_all() {
+                        tracing::warn!("[DeepLink] Failed to register URL scheme: {}", e);
+                    }
+                    let app_for_links = app.handle().clone();
+                    app.deep_link().on_open_url(move |event| {
+                        for url in event.urls() {
+                            deep_link::dispatch(&app_for_links, url.as_str());
+                        }
+                    });
+                    // 以 deep link 啟動 app 時的 URL
+                    if let Ok(Some(urls)) = app.deep_link().get_current() {
+                        for url in urls {
+                            deep_link::dispatch(app.handle(), url.as_str());
+                        }
+                    }
+                }
+
                 let engine_for_start = core.notification_engine.clone();
                 let notification_event_rx = core.event_bus.subscribe();
                 tauri::async_runtime::spawn(async move {
@@ -300,8 +624,26 @@ pub fn run() {
                     ai_scheduler_for_start.start().await;
                 });
 
+                let core_for_background = core.clone();
+                tauri::async_runtime::spawn(async move {
+                    core_for_background.recording_scheduler.start();
+                    core_for_background.start_power_monitor();
+                    core_for_background.start_price_snapshots();
+                    core_for_background.start_provider_metadata_updates();
+                    core_for_background.start_gas_tracker();
+                    core_for_background.start_depeg_monitor();
+                    core_for_background.start_economic_calendar();
+                    core_for_background.start_timeframe_changes();
+                    core_for_background.start_telemetry_export();
+                    core_for_background.start_cloud_backup();
+                    crate::settings_sync::start(core_for_background.clone());
+                });
+
                 let core_for_api = core.clone();
                 tauri::async_runtime::spawn(async move {
+                    // 區網 listener 有自己的開關，不受 api_enabled 影響
+                    api::restart_lan_listener(core_for_api.clone());
+
                     let enabled = core_for_api
                         .db
                         .get_setting("api_enabled")
@@ -319,29 +661,37 @@ pub fn run() {
                         .unwrap_or(8080);
 
                     if !enabled {
-                        eprintln!("[API] Server disabled");
+                        tracing::info!("[API] Server disabled");
                         return;
                     }
 
                     let app = api::build_router(core_for_api);
                     let addr = format!("127.0.0.1:{}", port);
-                    eprintln!("[API] Starting HTTP server on http://{}", addr);
+                    tracing::info!("[API] Starting HTTP server on http://{}", addr);
 
                     let listener = match tokio::net::TcpListener::bind(&addr).await {
                         Ok(l) => l,
                         Err(e) => {
-                            eprintln!("[API] Failed to bind to {}: {}", addr, e);
+                            tracing::error!("[API] Failed to bind to {}: {}", addr, e);
                             return;
                         }
                     };
 
                     if let Err(e) = axum::serve(listener, app).await {
-                        eprintln!("[API] Server error: {}", e);
+                        tracing::error!("[API] Server error: {}", e);
                     }
                 });
             }
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // 結束前保存價格快照，下次啟動時可立即顯示
+            if let tauri::RunEvent::Exit = event {
+                if let Some(core) = app.try_state::<Arc<CoreState>>() {
+                    tauri::async_runtime::block_on(core.save_price_snapshot());
+                }
+            }
+        });
 }
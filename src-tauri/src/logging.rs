@@ -0,0 +1,292 @@
+//! 結構化日誌 — 以 `tracing` 取代 `eprintln!`。
+//!
+//! 三個輸出目的地：
+//! - stderr（開發時可見）
+//! - `<data_dir>/logs/stockenboard.YYYY-MM-DD.log`（每日輪替，保留 7 天；打包後仍可取得）
+//! - 記憶體 ring buffer（最近 [`RING_CAPACITY`] 筆，供 `get_recent_logs` 查詢）；
+//!   唯讀用戶端也讀得到，URL 中帶 API key 的 query 參數會遮蔽（reqwest 錯誤訊息含完整 URL）
+//!
+//! 日誌等級可在執行期透過 [`set_level`] 調整（設定值存於 app setting `log_level`）。
+
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex, OnceLock};
+
+use serde::Serialize;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::{reload, Layer, Registry};
+
+/// Ring buffer 最多保留的日誌筆數
+pub const RING_CAPACITY: usize = 2000;
+
+/// 預設日誌等級
+pub const DEFAULT_LEVEL: &str = "info";
+
+/// 保留的日誌檔數量（每日一檔）
+const MAX_LOG_FILES: usize = 7;
+
+static RING: LazyLock<Mutex<VecDeque<LogEntry>>> =
+    LazyLock::new(|| Mutex::new(VecDeque::with_capacity(RING_CAPACITY)));
+static LEVEL_HANDLE: OnceLock<reload::Handle<LevelFilter, Registry>> = OnceLock::new();
+static LOG_DIR: OnceLock<PathBuf> = OnceLock::new();
+/// non-blocking writer 的 guard — drop 時才會 flush，因此整個 process 期間持有
+static FILE_GUARD: OnceLock<WorkerGuard> = OnceLock::new();
+
+/// Ring buffer 中的一筆日誌
+#[derive(Debug, Clone, Serialize)]
+pub struct LogEntry {
+    /// Unix 毫秒
+    pub timestamp: i64,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+}
+
+/// 初始化全域 subscriber。重複呼叫（或已有其他 subscriber）時不做任何事。
+pub fn init(log_dir: &Path) {
+    if LEVEL_HANDLE.get().is_some() {
+        return;
+    }
+
+    let (filter, handle) = reload::Layer::new(LevelFilter::INFO);
+
+    let file_layer = match std::fs::create_dir_all(log_dir).map_err(|e| e.to_string()).and_then(|_| {
+        RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix("stockenboard")
+            .filename_suffix("log")
+            .max_log_files(MAX_LOG_FILES)
+            .build(log_dir)
+            .map_err(|e| e.to_string())
+    }) {
+        Ok(appender) => {
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            let _ = FILE_GUARD.set(guard);
+            Some(
+                tracing_subscriber::fmt::layer()
+                    .with_writer(writer)
+                    .with_ansi(false),
+            )
+        }
+        Err(e) => {
+            eprintln!("[Logging] Failed to open log directory {}: {}", log_dir.display(), e);
+            None
+        }
+    };
+
+    let subscriber = Registry::default()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
+        .with(file_layer)
+        .with(RingBufferLayer);
+
+    if tracing::subscriber::set_global_default(subscriber).is_ok() {
+        let _ = LEVEL_HANDLE.set(handle);
+        let _ = LOG_DIR.set(log_dir.to_path_buf());
+    }
+}
+
+/// 解析日誌等級字串（trace / debug / info / warn / error）
+pub fn parse_level(level: &str) -> Result<Level, String> {
+    level
+        .trim()
+        .parse::<Level>()
+        .map_err(|_| format!("Invalid log level: {} (expected trace/debug/info/warn/error)", level))
+}
+
+/// 執行期調整日誌等級；尚未初始化時只做驗證
+pub fn set_level(level: &str) -> Result<(), String> {
+    let level = parse_level(level)?;
+    if let Some(handle) = LEVEL_HANDLE.get() {
+        handle
+            .reload(LevelFilter::from_level(level))
+            .map_err(|e| format!("Failed to change log level: {}", e))?;
+    }
+    Ok(())
+}
+
+/// 目前生效的日誌等級
+pub fn current_level() -> String {
+    LEVEL_HANDLE
+        .get()
+        .and_then(|h| h.clone_current())
+        .and_then(|f| f.into_level())
+        .map(|l| l.as_str().to_lowercase())
+        .unwrap_or_else(|| DEFAULT_LEVEL.to_string())
+}
+
+/// 日誌檔目錄（未初始化時為 None）
+pub fn log_dir() -> Option<PathBuf> {
+    LOG_DIR.get().cloned()
+}
+
+/// 取得最近的日誌（新 → 舊），只包含等級 >= `min_level` 的項目
+pub fn recent_logs(min_level: Option<&str>, limit: usize) -> Result<Vec<LogEntry>, String> {
+    let min = min_level
+        .filter(|l| !l.is_empty())
+        .map(parse_level)
+        .transpose()?
+        .unwrap_or(Level::TRACE);
+    let ring = RING.lock().unwrap_or_else(|e| e.into_inner());
+    Ok(ring
+        .iter()
+        .rev()
+        // tracing 的 Level 排序：TRACE > DEBUG > ... > ERROR（越詳細越大）
+        .filter(|e| e.level.parse::<Level>().map(|l| l <= min).unwrap_or(true))
+        .take(limit)
+        .cloned()
+        .collect())
+}
+
+fn push_entry(entry: LogEntry) {
+    let mut ring = RING.lock().unwrap_or_else(|e| e.into_inner());
+    if ring.len() >= RING_CAPACITY {
+        ring.pop_front();
+    }
+    ring.push_back(entry);
+}
+
+/// 把每個 event 複製一份到 ring buffer
+struct RingBufferLayer;
+
+impl<S: Subscriber> Layer<S> for RingBufferLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        let meta = event.metadata();
+        push_entry(LogEntry {
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            level: meta.level().as_str().to_lowercase(),
+            target: meta.target().to_string(),
+            message: redact_query_secrets(&visitor.finish()),
+        });
+    }
+}
+
+/// 遮蔽文字中 `?apikey=…` / `&token=…` 等參數值（見 [`crate::providers::debug::SECRET_QUERY_PARAMS`]）
+fn redact_query_secrets(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = String::with_capacity(text.len());
+    let mut copied = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'?' && bytes[i] != b'&' {
+            i += 1;
+            continue;
+        }
+        let name_start = i + 1;
+        let name_end = name_start
+            + bytes[name_start..]
+                .iter()
+                .take_while(|b| b.is_ascii_alphanumeric() || **b == b'_')
+                .count();
+        if bytes.get(name_end) != Some(&b'=')
+            || !crate::providers::debug::is_secret_param(&text[name_start..name_end])
+        {
+            i = name_end.max(i + 1);
+            continue;
+        }
+        let value_start = name_end + 1;
+        let value_end = value_start
+            + bytes[value_start..]
+                .iter()
+                .take_while(|b| !b.is_ascii_whitespace() && !b"&#)\"'>".contains(b))
+                .count();
+        out.push_str(&text[copied..value_start]);
+        out.push_str("***");
+        copied = value_end;
+        i = value_end;
+    }
+    out.push_str(&text[copied..]);
+    out
+}
+
+/// 組合 `message` 與其他結構化欄位（`key=value`）
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: Vec<String>,
+}
+
+impl MessageVisitor {
+    fn finish(self) -> String {
+        if self.fields.is_empty() {
+            self.message
+        } else if self.message.is_empty() {
+            self.fields.join(" ")
+        } else {
+            format!("{} {}", self.message, self.fields.join(" "))
+        }
+    }
+}
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.fields.push(format!("{}={}", field.name(), value));
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.fields.push(format!("{}={:?}", field.name(), value));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ring_buffer_captures_and_filters_by_level() {
+        let subscriber = Registry::default().with(RingBufferLayer);
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::debug!(target: "ring_test", "debug line");
+            tracing::warn!(target: "ring_test", provider = "binance", "fetch failed");
+        });
+
+        let all: Vec<_> = recent_logs(None, RING_CAPACITY)
+            .unwrap()
+            .into_iter()
+            .filter(|e| e.target == "ring_test")
+            .collect();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].message, "fetch failed provider=binance");
+
+        let warn_only: Vec<_> = recent_logs(Some("warn"), RING_CAPACITY)
+            .unwrap()
+            .into_iter()
+            .filter(|e| e.target == "ring_test")
+            .collect();
+        assert_eq!(warn_only.len(), 1);
+        assert_eq!(warn_only[0].level, "warn");
+
+        assert!(recent_logs(Some("verbose"), 10).is_err());
+    }
+
+    #[test]
+    fn redacts_api_keys_in_urls() {
+        assert_eq!(
+            redact_query_secrets(
+                "[Polling] polygon fetch failed: error sending request for url (https://api.polygon.io/v2/aggs/ticker/AAPL/prev?adjusted=true&apiKey=pk_123)"
+            ),
+            "[Polling] polygon fetch failed: error sending request for url (https://api.polygon.io/v2/aggs/ticker/AAPL/prev?adjusted=true&apiKey=***)"
+        );
+        assert_eq!(
+            redact_query_secrets("GET /quote?symbol=AAPL&token=abc&format=json"),
+            "GET /quote?symbol=AAPL&token=***&format=json"
+        );
+        assert_eq!(redact_query_secrets("https://x/eod?api_token=k1 ok"), "https://x/eod?api_token=*** ok");
+        assert_eq!(redact_query_secrets("a&b 價格 ?tokens=1"), "a&b 價格 ?tokens=1");
+    }
+}
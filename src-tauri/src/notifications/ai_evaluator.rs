@@ -91,7 +91,7 @@ pub fn parse_ai_response(raw: &str) -> Result<AiResponse, AiEvalError> {
     }
 
     // Neither direct parse nor markdown extraction worked
-    eprintln!("[AiEvaluator] AI response is not valid JSON, raw: {}", raw);
+    tracing::warn!("[AiEvaluator] AI response is not valid JSON, raw: {}", raw);
     Err(AiEvalError::InvalidJson(raw.to_string()))
 }
 
@@ -138,7 +138,7 @@ fn validate_ai_response_json(
     let obj = match value.as_object() {
         Some(obj) => obj,
         None => {
-            eprintln!("[AiEvaluator] AI response is not a JSON object, raw: {}", raw);
+            tracing::warn!("[AiEvaluator] AI response is not a JSON object, raw: {}", raw);
             return Err(AiEvalError::MissingField(
                 "response is not a JSON object".to_string(),
             ));
@@ -150,14 +150,14 @@ fn validate_ai_response_json(
         Some(v) => match v.as_bool() {
             Some(b) => b,
             None => {
-                eprintln!("[AiEvaluator] 'trigger' field is not boolean, raw: {}", raw);
+                tracing::warn!("[AiEvaluator] 'trigger' field is not boolean, raw: {}", raw);
                 return Err(AiEvalError::MissingField(
                     "\"trigger\" field is not a boolean".to_string(),
                 ));
             }
         },
         None => {
-            eprintln!("[AiEvaluator] Missing 'trigger' field, raw: {}", raw);
+            tracing::warn!("[AiEvaluator] Missing 'trigger' field, raw: {}", raw);
             return Err(AiEvalError::MissingField(
                 "missing \"trigger\" field".to_string(),
             ));
@@ -169,14 +169,14 @@ fn validate_ai_response_json(
         Some(v) => match v.as_str() {
             Some(s) => s.to_string(),
             None => {
-                eprintln!("[AiEvaluator] 'reason' field is not a string, raw: {}", raw);
+                tracing::warn!("[AiEvaluator] 'reason' field is not a string, raw: {}", raw);
                 return Err(AiEvalError::MissingField(
                     "\"reason\" field is not a string".to_string(),
                 ));
             }
         },
         None => {
-            eprintln!("[AiEvaluator] Missing 'reason' field, raw: {}", raw);
+            tracing::warn!("[AiEvaluator] Missing 'reason' field, raw: {}", raw);
             return Err(AiEvalError::MissingField(
                 "missing \"reason\" field".to_string(),
             ));
@@ -348,12 +348,12 @@ pub async fn evaluate_ai_rule(
     let history_rows = db
         .get_price_history(subscription_id, None, None, ai_config.history_window as i64)
         .map_err(|e| {
-            eprintln!("[AiEvaluator] rule_id={} failed to get price history: {}", rule_id, e);
+            tracing::warn!("[AiEvaluator] rule_id={} failed to get price history: {}", rule_id, e);
             AiEvalError::DatabaseError(e)
         })?;
 
     if history_rows.is_empty() {
-        eprintln!(
+        tracing::warn!(
             "[AiEvaluator] rule_id={} subscription_id={} no price history available",
             rule_id, subscription_id
         );
@@ -403,7 +403,7 @@ pub async fn evaluate_ai_rule(
     }
 
     let response = request.json(&request_body).send().await.map_err(|e| {
-        eprintln!("[AiEvaluator] rule_id={} AI API request failed: {}", rule_id, e);
+        tracing::warn!("[AiEvaluator] rule_id={} AI API request failed: {}", rule_id, e);
         AiEvalError::RequestFailed(e.to_string())
     })?;
 
@@ -411,7 +411,7 @@ pub async fn evaluate_ai_rule(
     let status = response.status();
     if !status.is_success() {
         let error_body = response.text().await.unwrap_or_default();
-        eprintln!(
+        tracing::warn!(
             "[AiEvaluator] rule_id={} AI API returned HTTP {}: {}",
             rule_id, status, error_body
         );
@@ -423,7 +423,7 @@ pub async fn evaluate_ai_rule(
 
     // Step 5: Parse response body to extract AI's message content
     let response_json: serde_json::Value = response.json().await.map_err(|e| {
-        eprintln!(
+        tracing::warn!(
             "[AiEvaluator] rule_id={} failed to parse AI API response JSON: {}",
             rule_id, e
         );
@@ -450,7 +450,7 @@ pub async fn evaluate_ai_rule(
             m.get("reasoning").and_then(|v| v.as_str())
         })
         .ok_or_else(|| {
-            eprintln!(
+            tracing::warn!(
                 "[AiEvaluator] rule_id={} AI API response format invalid, cannot extract content: {}",
                 rule_id, response_json
             );
@@ -461,7 +461,7 @@ pub async fn evaluate_ai_rule(
 
     // Step 6: Parse AI response content
     let ai_response = parse_ai_response(content).map_err(|e| {
-        eprintln!(
+        tracing::warn!(
             "[AiEvaluator] rule_id={} failed to parse AI response content: {}",
             rule_id, e
         );
@@ -513,7 +513,7 @@ pub async fn evaluate_ai_rule_multi(
 
     // Step 2: Fetch price history for each subscription and resolve symbols
     let all_subscriptions = db.list_all_subscriptions().map_err(|e| {
-        eprintln!("[AiEvaluator] rule_id={} failed to list subscriptions: {}", rule_id, e);
+        tracing::warn!("[AiEvaluator] rule_id={} failed to list subscriptions: {}", rule_id, e);
         AiEvalError::DatabaseError(e)
     })?;
 
@@ -524,7 +524,7 @@ pub async fn evaluate_ai_rule_multi(
         let symbol = match all_subscriptions.iter().find(|s| s.id == sub_id) {
             Some(s) => s.symbol.clone(),
             None => {
-                eprintln!(
+                tracing::warn!(
                     "[AiEvaluator] rule_id={} subscription_id={} not found, skipping",
                     rule_id, sub_id
                 );
@@ -537,7 +537,7 @@ pub async fn evaluate_ai_rule_multi(
         let history_rows = db
             .get_price_history(sub_id, None, None, fetch_count)
             .map_err(|e| {
-                eprintln!(
+                tracing::warn!(
                     "[AiEvaluator] rule_id={} sub_id={} failed to get price history: {}",
                     rule_id, sub_id, e
                 );
@@ -545,7 +545,7 @@ pub async fn evaluate_ai_rule_multi(
             })?;
 
         if history_rows.is_empty() {
-            eprintln!(
+            tracing::warn!(
                 "[AiEvaluator] rule_id={} sub_id={} no price history, skipping",
                 rule_id, sub_id
             );
@@ -573,7 +573,7 @@ pub async fn evaluate_ai_rule_multi(
 
     // Step 3: If all subscriptions are missing, return error
     if subscriptions_data.is_empty() {
-        eprintln!(
+        tracing::warn!(
             "[AiEvaluator] rule_id={} all subscriptions missing or have no price history",
             rule_id
         );
@@ -610,14 +610,14 @@ pub async fn evaluate_ai_rule_multi(
     }
 
     let response = request.json(&request_body).send().await.map_err(|e| {
-        eprintln!("[AiEvaluator] rule_id={} AI API request failed: {}", rule_id, e);
+        tracing::warn!("[AiEvaluator] rule_id={} AI API request failed: {}", rule_id, e);
         AiEvalError::RequestFailed(e.to_string())
     })?;
 
     let status = response.status();
     if !status.is_success() {
         let error_body = response.text().await.unwrap_or_default();
-        eprintln!(
+        tracing::warn!(
             "[AiEvaluator] rule_id={} AI API returned HTTP {}: {}",
             rule_id, status, error_body
         );
@@ -629,7 +629,7 @@ pub async fn evaluate_ai_rule_multi(
 
     // Step 6: Parse response body
     let response_json: serde_json::Value = response.json().await.map_err(|e| {
-        eprintln!(
+        tracing::warn!(
             "[AiEvaluator] rule_id={} failed to parse AI API response JSON: {}",
             rule_id, e
         );
@@ -650,7 +650,7 @@ pub async fn evaluate_ai_rule_multi(
             m.get("reasoning").and_then(|v| v.as_str())
         })
         .ok_or_else(|| {
-            eprintln!(
+            tracing::warn!(
                 "[AiEvaluator] rule_id={} AI API response format invalid: {}",
                 rule_id, response_json
             );
@@ -661,7 +661,7 @@ pub async fn evaluate_ai_rule_multi(
 
     // Step 7: Parse AI response content
     let ai_response = parse_ai_response(content).map_err(|e| {
-        eprintln!(
+        tracing::warn!(
             "[AiEvaluator] rule_id={} failed to parse AI response content: {}",
             rule_id, e
         );
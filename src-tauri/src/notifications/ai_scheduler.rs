@@ -87,7 +87,7 @@ impl AiScheduler {
         let rules = match self.db.list_notification_rules() {
             Ok(rules) => rules,
             Err(e) => {
-                eprintln!("[AiScheduler] Failed to load notification rules: {}", e);
+                tracing::warn!("[AiScheduler] Failed to load notification rules: {}", e);
                 return;
             }
         };
@@ -96,11 +96,11 @@ impl AiScheduler {
         let provider_config = match self.db.load_ai_provider_config() {
             Ok(Some(config)) => config,
             Ok(None) => {
-                eprintln!("[AiScheduler] AI Provider not configured, all AI rules paused");
+                tracing::warn!("[AiScheduler] AI Provider not configured, all AI rules paused");
                 return;
             }
             Err(e) => {
-                eprintln!("[AiScheduler] Failed to load AI Provider config: {}", e);
+                tracing::warn!("[AiScheduler] Failed to load AI Provider config: {}", e);
                 return;
             }
         };
@@ -116,7 +116,7 @@ impl AiScheduler {
                 Some(config_str) => match serde_json::from_str::<AiConfig>(config_str) {
                     Ok(config) => config,
                     Err(e) => {
-                        eprintln!(
+                        tracing::warn!(
                             "[AiScheduler] rule_id={} failed to parse ai_config: {}",
                             rule.id, e
                         );
@@ -124,7 +124,7 @@ impl AiScheduler {
                     }
                 },
                 None => {
-                    eprintln!(
+                    tracing::warn!(
                         "[AiScheduler] rule_id={} is AI rule but missing ai_config",
                         rule.id
                     );
@@ -151,7 +151,7 @@ impl AiScheduler {
             tasks.insert(rule.id, TaskHandle { abort_handle });
         }
 
-        eprintln!(
+        tracing::info!(
             "[AiScheduler] Started, {} AI rules running",
             tasks.len()
         );
@@ -170,7 +170,7 @@ impl AiScheduler {
             let mut tasks = self.tasks.write().await;
             if let Some(handle) = tasks.remove(&rule_id) {
                 handle.abort_handle.abort();
-                eprintln!("[AiScheduler] rule_id={} stopped old task", rule_id);
+                tracing::info!("[AiScheduler] rule_id={} stopped old task", rule_id);
             }
         }
 
@@ -178,11 +178,11 @@ impl AiScheduler {
         let rule = match self.db.get_notification_rule(rule_id) {
             Ok(Some(rule)) => rule,
             Ok(None) => {
-                eprintln!("[AiScheduler] rule_id={} not found", rule_id);
+                tracing::warn!("[AiScheduler] rule_id={} not found", rule_id);
                 return;
             }
             Err(e) => {
-                eprintln!("[AiScheduler] rule_id={} failed to load rule: {}", rule_id, e);
+                tracing::warn!("[AiScheduler] rule_id={} failed to load rule: {}", rule_id, e);
                 return;
             }
         };
@@ -197,7 +197,7 @@ impl AiScheduler {
             Some(config_str) => match serde_json::from_str::<AiConfig>(config_str) {
                 Ok(config) => config,
                 Err(e) => {
-                    eprintln!(
+                    tracing::warn!(
                         "[AiScheduler] rule_id={} failed to parse ai_config: {}",
                         rule_id, e
                     );
@@ -205,7 +205,7 @@ impl AiScheduler {
                 }
             },
             None => {
-                eprintln!(
+                tracing::warn!(
                     "[AiScheduler] rule_id={} is AI rule but missing ai_config",
                     rule_id
                 );
@@ -217,14 +217,14 @@ impl AiScheduler {
         let provider_config = match self.db.load_ai_provider_config() {
             Ok(Some(config)) => config,
             Ok(None) => {
-                eprintln!(
+                tracing::warn!(
                     "[AiScheduler] AI Provider not configured, rule_id={} cannot start",
                     rule_id
                 );
                 return;
             }
             Err(e) => {
-                eprintln!(
+                tracing::warn!(
                     "[AiScheduler] rule_id={} Failed to load AI Provider config: {}",
                     rule_id, e
                 );
@@ -251,7 +251,7 @@ impl AiScheduler {
 
         let mut tasks = self.tasks.write().await;
         tasks.insert(rule_id, TaskHandle { abort_handle });
-        eprintln!("[AiScheduler] rule_id={} started new task", rule_id);
+        tracing::info!("[AiScheduler] rule_id={} started new task", rule_id);
     }
 
     /// 停止某條規則的 task
@@ -264,7 +264,7 @@ impl AiScheduler {
         let mut tasks = self.tasks.write().await;
         if let Some(handle) = tasks.remove(&rule_id) {
             handle.abort_handle.abort();
-            eprintln!("[AiScheduler] rule_id={} task stopped and removed", rule_id);
+            tracing::info!("[AiScheduler] rule_id={} task stopped and removed", rule_id);
         }
     }
 
@@ -284,12 +284,12 @@ impl AiScheduler {
             let mut tasks = self.tasks.write().await;
             for (rule_id, handle) in tasks.drain() {
                 handle.abort_handle.abort();
-                eprintln!("[AiScheduler] reload: rule_id={} task stopped", rule_id);
+                tracing::info!("[AiScheduler] reload: rule_id={} task stopped", rule_id);
             }
         }
 
         // Step 2: Re-run start logic
-        eprintln!("[AiScheduler] Reloading all AI rules...");
+        tracing::info!("[AiScheduler] Reloading all AI rules...");
         self.start().await;
     }
 
@@ -315,7 +315,7 @@ impl AiScheduler {
             // Resolve subscription IDs (with NULL fallback for pre-migration rules)
             let resolved_ids = resolve_subscription_ids(&subscription_ids_json, subscription_id);
 
-            eprintln!(
+            tracing::info!(
                 "[AiScheduler] rule_id={} task started, interval {}s, cooldown {}s, subscriptions={:?}",
                 rule_id, ai_config.analysis_interval_secs, cooldown_secs, resolved_ids
             );
@@ -328,7 +328,7 @@ impl AiScheduler {
                 let max_context_tokens = match db.load_max_context_tokens() {
                     Ok(v) => v,
                     Err(e) => {
-                        eprintln!(
+                        tracing::warn!(
                             "[AiScheduler] rule_id={} failed to load max_context_tokens: {}, using None",
                             rule_id, e
                         );
@@ -356,7 +356,7 @@ impl AiScheduler {
                                 should_suppress_trigger(last_trigger_time, cooldown_secs as u64);
 
                             if in_cooldown {
-                                eprintln!(
+                                tracing::warn!(
                                     "[AiScheduler] rule_id={} triggered but in cooldown, ignoring",
                                     rule_id
                                 );
@@ -366,7 +366,7 @@ impl AiScheduler {
                             // Step 3b: Check global cooldown (atomically marks as triggered if passes)
                             if let Some(ref gc) = global_cooldown {
                                 if !gc.check_and_trigger() {
-                                    eprintln!(
+                                    tracing::warn!(
                                         "[AiScheduler] rule_id={} global cooldown active, skipping",
                                         rule_id
                                     );
@@ -380,7 +380,7 @@ impl AiScheduler {
                             let symbol = match get_symbol_for_subscription(&db, primary_subscription_id) {
                                 Some(s) => s,
                                 None => {
-                                    eprintln!(
+                                    tracing::warn!(
                                         "[AiScheduler] rule_id={} cannot get symbol for subscription_id={}",
                                         rule_id, primary_subscription_id
                                     );
@@ -442,13 +442,13 @@ impl AiScheduler {
                             // Step 3c: Record trigger time for cooldown tracking
                             last_trigger_time = Some(Instant::now());
 
-                            eprintln!(
+                            tracing::info!(
                                 "[AiScheduler] rule_id={} AI notification dispatched, reason: {}",
                                 rule_id, response.reason
                             );
                         } else {
                             // Step 4: trigger = false, log and continue
-                            eprintln!(
+                            tracing::debug!(
                                 "[AiScheduler] rule_id={} AI decided not to trigger: {}",
                                 rule_id, response.reason
                             );
@@ -456,7 +456,7 @@ impl AiScheduler {
                     }
                     Err(e) => {
                         // Step 4: Error — log and continue to next iteration
-                        eprintln!("[AiScheduler] rule_id={} AI evaluation error: {}", rule_id, e);
+                        tracing::warn!("[AiScheduler] rule_id={} AI evaluation error: {}", rule_id, e);
                     }
                 }
                 // Step 5: Evaluation is complete; loop back to sleep for next interval
@@ -31,7 +31,7 @@ pub async fn dispatch_notification(
     let channels = match db.list_notification_channels() {
         Ok(ch) => ch,
         Err(e) => {
-            eprintln!("[Dispatcher] Failed to load channel list: {}", e);
+            tracing::warn!("[Dispatcher] Failed to load channel list: {}", e);
             return;
         }
     };
@@ -40,7 +40,7 @@ pub async fn dispatch_notification(
         let channel = match channels.iter().find(|c| c.id == *channel_id) {
             Some(c) => c,
             None => {
-                eprintln!("[Dispatcher] Channel {} not found, skipping", channel_id);
+                tracing::warn!("[Dispatcher] Channel {} not found, skipping", channel_id);
                 continue;
             }
         };
@@ -48,7 +48,7 @@ pub async fn dispatch_notification(
         let channel_type = match ChannelType::from_str(&channel.channel_type) {
             Ok(ct) => ct,
             Err(_) => {
-                eprintln!(
+                tracing::warn!(
                     "[Dispatcher] Channel {} type invalid: {}",
                     channel_id, channel.channel_type
                 );
@@ -130,7 +130,7 @@ pub async fn dispatch_notification(
                 );
             }
             Err(e) => {
-                eprintln!("[Dispatcher] Channel {} send failed: {}", channel_id, e);
+                tracing::warn!("[Dispatcher] Channel {} send failed: {}", channel_id, e);
                 record_history(
                     db,
                     rule.id,
@@ -178,6 +178,6 @@ fn record_history(
     if let Err(e) =
         db.insert_notification_history(rule_id, channel_id, status, price, message, error)
     {
-        eprintln!("[Dispatcher] Failed to write notification history: {}", e);
+        tracing::warn!("[Dispatcher] Failed to write notification history: {}", e);
     }
 }
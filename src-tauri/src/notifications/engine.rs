@@ -1,6 +1,6 @@
 //! NotificationEngine — 推播通知引擎主邏輯
 //!
-//! 訂閱 Event Bus 的 PriceUpdate 事件，評估觸發條件，派發通知。
+//! 訂閱 Event Bus 的 PriceUpdate / GasUpdate / DepegUpdate / CalendarUpdate 事件，評估觸發條件，派發通知。
 
 use std::collections::HashMap;
 use std::str::FromStr;
@@ -48,35 +48,18 @@ impl NotificationEngine {
         let global_cooldown = self.global_cooldown.clone();
 
         tokio::spawn(async move {
-            eprintln!("[NotificationEngine] Started, listening for events");
+            tracing::info!("[NotificationEngine] Started, listening for events");
             loop {
                 match event_rx.recv().await {
                     Ok(AppEvent::PriceUpdate { data, .. }) => {
                         let rules_guard = rules.read().await;
+                        let now = chrono::Utc::now().timestamp();
                         for asset in &data {
+                            let vwap_triggered = evaluator::evaluate_vwap_rules(&rules_guard, asset, |sub_id| {
+                                crate::indicators::session_vwap(&db, sub_id, now)
+                            });
                             let triggered = evaluator::evaluate_rules(&rules_guard, asset);
-                            for rule in triggered {
-                                // Check per-rule cooldown first (lightweight, no side effects)
-                                let mut cd_guard = cooldowns.write().await;
-                                if let Some(last_fired) = cd_guard.get(&rule.id) {
-                                    if last_fired.elapsed().as_secs() < rule.cooldown_secs {
-                                        continue;
-                                    }
-                                }
-
-                                // Check global cooldown (atomically marks as triggered if passes)
-                                if !global_cooldown.check_and_trigger() {
-                                    eprintln!(
-                                        "[NotificationEngine] rule_id={} global cooldown active, skipping trigger",
-                                        rule.id
-                                    );
-                                    continue;
-                                }
-
-                                cd_guard.insert(rule.id, Instant::now());
-                                drop(cd_guard);
-
-                                // Build notification data and dispatch
+                            for rule in triggered.into_iter().chain(vwap_triggered) {
                                 let notif_data = NotificationData {
                                     symbol: asset.symbol.clone(),
                                     provider: asset.provider_id.clone(),
@@ -86,50 +69,108 @@ impl NotificationEngine {
                                     rule_name: rule.name.clone(),
                                     triggered_at: chrono::Utc::now(),
                                 };
-                                dispatcher::dispatch_notification(
+                                fire_rule(
                                     &db,
                                     &http_client,
+                                    &event_bus,
+                                    &global_cooldown,
+                                    &cooldowns,
                                     rule,
-                                    &notif_data,
+                                    notif_data,
                                 )
                                 .await;
-
-                                // If the rule has a system channel, emit OS notification event
-                                if let Ok(channels) = db.list_notification_channels() {
-                                    let has_system = rule.channel_ids.iter().any(|cid| {
-                                        channels.iter().any(|ch| ch.id == *cid && ch.channel_type == "system")
-                                    });
-                                    if has_system {
-                                        let _ = event_bus.send(AppEvent::SystemNotification {
-                                            title: format!("StockenBoard — {}", notif_data.rule_name),
-                                            body: format!("{} @ ${}", notif_data.symbol, notif_data.price),
-                                        });
-                                    }
-                                }
-
-                                // 發布觸發事件供前端側欄即時顯示（閾值規則，非 AI）
-                                let _ = event_bus.send(AppEvent::NotificationTriggered(
-                                    NotificationTriggeredPayload {
-                                        rule_name: notif_data.rule_name.clone(),
-                                        symbol: notif_data.symbol.clone(),
-                                        provider: notif_data.provider.clone(),
-                                        price: notif_data.price,
-                                        condition_type: notif_data.condition_type.as_str().to_string(),
-                                        threshold: notif_data.threshold,
-                                        triggered_at: notif_data.triggered_at.timestamp(),
-                                        is_ai: false,
-                                        ai_reason: None,
-                                    },
-                                ));
                             }
                         }
                     }
+                    Ok(AppEvent::GasUpdate(gas)) => {
+                        let rules_guard = rules.read().await;
+                        for rule in evaluator::evaluate_gas_rules(&rules_guard, &gas) {
+                            // gas 規則以鏈名稱作為 provider，價格欄位為 gas 報價
+                            let notif_data = NotificationData {
+                                symbol: rule.symbol.clone(),
+                                provider: gas.chain.clone(),
+                                price: gas.standard,
+                                condition_type: rule.condition_type.clone(),
+                                threshold: rule.threshold,
+                                rule_name: rule.name.clone(),
+                                triggered_at: chrono::Utc::now(),
+                            };
+                            fire_rule(
+                                &db,
+                                &http_client,
+                                &event_bus,
+                                &global_cooldown,
+                                &cooldowns,
+                                rule,
+                                notif_data,
+                            )
+                            .await;
+                        }
+                    }
+                    Ok(AppEvent::DepegUpdate(statuses)) => {
+                        let rules_guard = rules.read().await;
+                        for (rule, status) in evaluator::evaluate_depeg_rules(&rules_guard, &statuses) {
+                            // 脫鉤規則以穩定幣名稱作為 provider，價格欄位為各來源中位數
+                            let notif_data = NotificationData {
+                                symbol: rule.symbol.clone(),
+                                provider: status.coin.clone(),
+                                price: status.price,
+                                condition_type: rule.condition_type.clone(),
+                                threshold: rule.threshold,
+                                rule_name: rule.name.clone(),
+                                triggered_at: chrono::Utc::now(),
+                            };
+                            fire_rule(
+                                &db,
+                                &http_client,
+                                &event_bus,
+                                &global_cooldown,
+                                &cooldowns,
+                                rule,
+                                notif_data,
+                            )
+                            .await;
+                        }
+                    }
+                    Ok(AppEvent::CalendarUpdate(events)) => {
+                        let rules_guard = rules.read().await;
+                        let keywords = crate::calendar::calendar_config().alert_keywords;
+                        let now = chrono::Utc::now().timestamp();
+                        for (rule, event) in
+                            evaluator::evaluate_calendar_rules(&rules_guard, &events, &keywords, now)
+                        {
+                            // 同一事件只提醒一次（提醒時間窗內每次檢查都會符合）
+                            if !crate::calendar::mark_notified(rule.id, event.id) {
+                                continue;
+                            }
+                            // 事件規則以事件名稱作為 symbol、國家作為 provider，價格欄位為距離開始的分鐘數
+                            let notif_data = NotificationData {
+                                symbol: event.event.clone(),
+                                provider: event.country.clone(),
+                                price: ((event.event_time - now) as f64 / 60.0).ceil(),
+                                condition_type: rule.condition_type.clone(),
+                                threshold: rule.threshold,
+                                rule_name: rule.name.clone(),
+                                triggered_at: chrono::Utc::now(),
+                            };
+                            fire_rule(
+                                &db,
+                                &http_client,
+                                &event_bus,
+                                &global_cooldown,
+                                &cooldowns,
+                                rule,
+                                notif_data,
+                            )
+                            .await;
+                        }
+                    }
                     Ok(_) => {}
                     Err(broadcast::error::RecvError::Lagged(n)) => {
-                        eprintln!("[NotificationEngine] Lagged {} events, continuing", n);
+                        tracing::warn!("[NotificationEngine] Lagged {} events, continuing", n);
                     }
                     Err(broadcast::error::RecvError::Closed) => {
-                        eprintln!("[NotificationEngine] Event Bus closed, engine stopping");
+                        tracing::error!("[NotificationEngine] Event Bus closed, engine stopping");
                         break;
                     }
                 }
@@ -141,15 +182,24 @@ impl NotificationEngine {
     pub async fn reload_rules(&self) {
         match self.load_rules_from_db() {
             Ok(new_rules) => {
+                crate::gas::set_rules_active(
+                    new_rules.iter().any(|r| r.enabled && r.condition_type.is_gas()),
+                );
+                crate::depeg::set_rules_active(
+                    new_rules.iter().any(|r| r.enabled && r.condition_type.is_depeg()),
+                );
+                crate::calendar::set_rules_active(
+                    new_rules.iter().any(|r| r.enabled && r.condition_type.is_calendar()),
+                );
                 let mut rules_guard = self.rules.write().await;
                 *rules_guard = new_rules;
-                eprintln!(
+                tracing::info!(
                     "[NotificationEngine] Rules reloaded, total: {}",
                     rules_guard.len()
                 );
             }
             Err(e) => {
-                eprintln!("[NotificationEngine] Failed to load rules: {}", e);
+                tracing::warn!("[NotificationEngine] Failed to load rules: {}", e);
             }
         }
     }
@@ -170,7 +220,7 @@ impl NotificationEngine {
                 let condition_type = match ConditionType::from_str(&row.condition_type) {
                     Ok(ct) => ct,
                     Err(_) => {
-                        eprintln!(
+                        tracing::warn!(
                             "[NotificationEngine] Rule {} has invalid condition type: {}",
                             row.id, row.condition_type
                         );
@@ -193,7 +243,7 @@ impl NotificationEngine {
                     enabled: row.enabled,
                 });
             } else {
-                eprintln!(
+                tracing::warn!(
                     "[NotificationEngine] Rule {} subscription {} not found, skipping",
                     row.id, row.subscription_id
                 );
@@ -202,3 +252,73 @@ impl NotificationEngine {
         Ok(rules)
     }
 }
+
+/// 檢查冷卻期後派發已觸發的規則（閾值、gas、脫鉤與事件規則共用）
+async fn fire_rule(
+    db: &Arc<DbPool>,
+    http_client: &reqwest::Client,
+    event_bus: &broadcast::Sender<AppEvent>,
+    global_cooldown: &GlobalCooldown,
+    cooldowns: &RwLock<HashMap<i64, Instant>>,
+    rule: &NotificationRule,
+    notif_data: NotificationData,
+) {
+    // Check per-rule cooldown first (lightweight, no side effects)
+    let mut cd_guard = cooldowns.write().await;
+    if let Some(last_fired) = cd_guard.get(&rule.id) {
+        if last_fired.elapsed().as_secs() < rule.cooldown_secs {
+            return;
+        }
+    }
+
+    // Check global cooldown (atomically marks as triggered if passes)
+    if !global_cooldown.check_and_trigger() {
+        tracing::warn!(
+            "[NotificationEngine] rule_id={} global cooldown active, skipping trigger",
+            rule.id
+        );
+        return;
+    }
+
+    cd_guard.insert(rule.id, Instant::now());
+    drop(cd_guard);
+
+    dispatcher::dispatch_notification(db, http_client, rule, &notif_data).await;
+
+    // If the rule has a system channel, emit OS notification event
+    if let Ok(channels) = db.list_notification_channels() {
+        let has_system = rule.channel_ids.iter().any(|cid| {
+            channels.iter().any(|ch| ch.id == *cid && ch.channel_type == "system")
+        });
+        if has_system {
+            let body = if notif_data.condition_type.is_gas() {
+                format!("{} gas {}", notif_data.provider, notif_data.price)
+            } else if notif_data.condition_type.is_depeg() {
+                format!("{} @ ${:.4}", notif_data.provider, notif_data.price)
+            } else if notif_data.condition_type.is_calendar() {
+                format!("{} ({}) in {} min", notif_data.symbol, notif_data.provider, notif_data.price)
+            } else {
+                format!("{} @ ${}", notif_data.symbol, notif_data.price)
+            };
+            let _ = event_bus.send(AppEvent::SystemNotification {
+                title: format!("StockenBoard — {}", notif_data.rule_name),
+                body,
+            });
+        }
+    }
+
+    // 發布觸發事件供前端側欄即時顯示（閾值規則，非 AI）
+    let _ = event_bus.send(AppEvent::NotificationTriggered(
+        NotificationTriggeredPayload {
+            rule_name: notif_data.rule_name.clone(),
+            symbol: notif_data.symbol.clone(),
+            provider: notif_data.provider.clone(),
+            price: notif_data.price,
+            condition_type: notif_data.condition_type.as_str().to_string(),
+            threshold: notif_data.threshold,
+            triggered_at: notif_data.triggered_at.timestamp(),
+            is_ai: false,
+            ai_reason: None,
+        },
+    ));
+}
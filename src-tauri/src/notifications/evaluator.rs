@@ -1,7 +1,13 @@
 //! 條件評估邏輯
 //!
-//! 根據 ConditionType 和 AssetData 判斷是否觸發通知。
+//! 根據 ConditionType 和 AssetData（gas 規則為 GasPrice、脫鉤規則為 DepegStatus、
+//! 事件規則為 EconomicEvent）判斷是否觸發通知。
 
+use std::str::FromStr;
+
+use crate::calendar::EconomicEvent;
+use crate::depeg::DepegStatus;
+use crate::gas::{GasChain, GasPrice};
 use crate::notifications::models::{ConditionType, NotificationRule};
 use crate::providers::types::AssetData;
 
@@ -18,11 +24,102 @@ pub fn evaluate_condition(
         ConditionType::PriceBelow => Some(asset.price < threshold),
         ConditionType::ChangePctAbove => asset.change_percent_24h.map(|pct| pct > threshold),
         ConditionType::ChangePctBelow => asset.change_percent_24h.map(|pct| pct < threshold),
-        // AI rules are evaluated by the AI scheduler, not the threshold evaluator
-        ConditionType::Ai => None,
+        // AI rules are evaluated by the AI scheduler, gas / depeg / calendar / VWAP rules by
+        // `evaluate_gas_rules` / `evaluate_depeg_rules` / `evaluate_calendar_rules` / `evaluate_vwap_rules`
+        ConditionType::Ai
+        | ConditionType::GasAbove
+        | ConditionType::GasBelow
+        | ConditionType::DepegAbove
+        | ConditionType::EventBefore
+        | ConditionType::VwapAbove
+        | ConditionType::VwapBelow => None,
     }
 }
 
+/// 評估 gas 規則對一筆 gas 報價，回傳所有被觸發的規則
+/// 只評估訂閱所在鏈（`gas::chain_for`）與報價相同的已啟用 gas 規則，以 `standard` 比較閾值
+pub fn evaluate_gas_rules<'a>(
+    rules: &'a [NotificationRule],
+    gas: &GasPrice,
+) -> Vec<&'a NotificationRule> {
+    let Ok(chain) = GasChain::from_str(&gas.chain) else {
+        return Vec::new();
+    };
+    rules
+        .iter()
+        .filter(|r| r.enabled && crate::gas::chain_for(&r.provider_id, &r.symbol) == Some(chain))
+        .filter(|r| match r.condition_type {
+            ConditionType::GasAbove => gas.standard > r.threshold,
+            ConditionType::GasBelow => gas.standard < r.threshold,
+            _ => false,
+        })
+        .collect()
+}
+
+/// 評估脫鉤規則對一輪 depeg monitor 狀態，回傳被觸發的規則與其對應的穩定幣狀態
+/// 只評估訂閱對應穩定幣（`depeg::coin_for`）的已啟用脫鉤規則，以偏離絕對值比較閾值
+pub fn evaluate_depeg_rules<'a, 'b>(
+    rules: &'a [NotificationRule],
+    statuses: &'b [DepegStatus],
+) -> Vec<(&'a NotificationRule, &'b DepegStatus)> {
+    rules
+        .iter()
+        .filter(|r| r.enabled && r.condition_type == ConditionType::DepegAbove)
+        .filter_map(|r| {
+            let coin = crate::depeg::coin_for(&r.provider_id, &r.symbol)?;
+            let status = statuses.iter().find(|s| s.coin == coin)?;
+            (status.deviation_pct.abs() > r.threshold).then_some((r, status))
+        })
+        .collect()
+}
+
+/// 評估事件規則對即將到來的經濟事件，回傳被觸發的規則與事件
+/// 事件須符合提醒關鍵字（`calendar::is_alert_event`），且在 `now`（Unix 秒）之後的閾值分鐘內開始
+pub fn evaluate_calendar_rules<'a, 'b>(
+    rules: &'a [NotificationRule],
+    events: &'b [EconomicEvent],
+    keywords: &[String],
+    now: i64,
+) -> Vec<(&'a NotificationRule, &'b EconomicEvent)> {
+    let due: Vec<&EconomicEvent> = events
+        .iter()
+        .filter(|e| e.event_time >= now && crate::calendar::is_alert_event(e, keywords))
+        .collect();
+    rules
+        .iter()
+        .filter(|r| r.enabled && r.condition_type == ConditionType::EventBefore)
+        .flat_map(|r| {
+            due.iter()
+                .filter(move |e| (e.event_time - now) as f64 <= r.threshold * 60.0)
+                .map(move |e| (r, *e))
+        })
+        .collect()
+}
+
+/// 評估 VWAP 規則對一筆 AssetData，回傳所有被觸發的規則
+/// `session_vwap` 以 subscription_id 取得當日 VWAP（沒有紀錄時為 None，規則不觸發）
+pub fn evaluate_vwap_rules<'a>(
+    rules: &'a [NotificationRule],
+    asset: &AssetData,
+    session_vwap: impl Fn(i64) -> Option<f64>,
+) -> Vec<&'a NotificationRule> {
+    filter_matching_rules(rules, &asset.provider_id, &asset.symbol)
+        .into_iter()
+        .filter(|r| r.condition_type.is_vwap())
+        .filter(|r| {
+            let Some(vwap) = session_vwap(r.subscription_id).filter(|v| *v > 0.0) else {
+                return false;
+            };
+            let deviation = (asset.price - vwap) / vwap * 100.0;
+            match r.condition_type {
+                ConditionType::VwapAbove => deviation > r.threshold,
+                ConditionType::VwapBelow => deviation < -r.threshold,
+                _ => false,
+            }
+        })
+        .collect()
+}
+
 /// 從一組規則中篩選出匹配指定 provider_id 和 symbol 的規則
 /// 只回傳 enabled=true 且 provider_id 和 symbol 都匹配的規則
 pub fn filter_matching_rules<'a>(
@@ -74,6 +171,11 @@ mod tests {
             last_updated: 0,
             provider_id: provider_id.to_string(),
             extra: None,
+            pre_market: None,
+            post_market: None,
+            price_scale: None,
+            liquidity_usd: None,
+            fdv: None,
         }
     }
 
@@ -335,4 +437,119 @@ mod tests {
         let triggered = evaluate_rules(&rules, &asset);
         assert_eq!(triggered.len(), 2);
     }
+
+    // === evaluate_gas_rules tests ===
+
+    #[test]
+    fn test_evaluate_gas_rules_matches_chain_and_threshold() {
+        let rules = vec![
+            make_rule(1, "binance", "ETHUSDT", ConditionType::GasBelow, 10.0, true),
+            make_rule(2, "binance", "ETHUSDT", ConditionType::GasAbove, 50.0, true),
+            make_rule(3, "raydium", "pool:a:b", ConditionType::GasBelow, 10.0, true),
+            make_rule(4, "binance", "ETHUSDT", ConditionType::PriceBelow, 10.0, true),
+            make_rule(5, "binance", "ETHUSDT", ConditionType::GasBelow, 10.0, false),
+        ];
+        let gas = GasPrice {
+            chain: "ethereum".to_string(),
+            unit: "gwei".to_string(),
+            safe: None,
+            standard: 8.5,
+            fast: None,
+            base_fee: None,
+            source: "rpc".to_string(),
+            updated_at: 0,
+        };
+        let triggered = evaluate_gas_rules(&rules, &gas);
+        assert_eq!(triggered.len(), 1);
+        assert_eq!(triggered[0].id, 1);
+
+        // 價格更新不會觸發 gas 規則
+        let asset = make_asset("ETHUSDT", "binance", 5.0, None);
+        assert_eq!(evaluate_condition(&ConditionType::GasBelow, 10.0, &asset), None);
+    }
+
+    // === evaluate_depeg_rules tests ===
+
+    #[test]
+    fn test_evaluate_depeg_rules_matches_coin_and_abs_deviation() {
+        let rules = vec![
+            make_rule(1, "coinbase", "USDC-USD", ConditionType::DepegAbove, 1.0, true),
+            make_rule(2, "coingecko", "tether", ConditionType::DepegAbove, 1.0, true),
+            make_rule(3, "binance", "BTCUSDT", ConditionType::DepegAbove, 1.0, true),
+            make_rule(4, "coinbase", "USDC-USD", ConditionType::DepegAbove, 5.0, true),
+            make_rule(5, "coinbase", "USDC-USD", ConditionType::DepegAbove, 1.0, false),
+        ];
+        let status = |coin: &str, price: f64| DepegStatus {
+            coin: coin.to_string(),
+            price,
+            deviation_pct: (price - 1.0) * 100.0,
+            depegged: false,
+            quotes: Vec::new(),
+            updated_at: 0,
+        };
+        let statuses = vec![status("USDT", 1.0005), status("USDC", 0.97)];
+        let triggered = evaluate_depeg_rules(&rules, &statuses);
+        assert_eq!(triggered.len(), 1);
+        assert_eq!((triggered[0].0.id, triggered[0].1.coin.as_str()), (1, "USDC"));
+    }
+
+    // === evaluate_calendar_rules tests ===
+
+    #[test]
+    fn test_evaluate_calendar_rules_fires_within_lead_time() {
+        use crate::calendar::Impact;
+
+        let rules = vec![
+            make_rule(1, "binance", "BTCUSDT", ConditionType::EventBefore, 15.0, true),
+            make_rule(2, "binance", "BTCUSDT", ConditionType::EventBefore, 5.0, true),
+            make_rule(3, "binance", "BTCUSDT", ConditionType::EventBefore, 15.0, false),
+        ];
+        let event = |id: i64, name: &str, event_time: i64| EconomicEvent {
+            id,
+            source: "fmp".to_string(),
+            event_time,
+            country: "US".to_string(),
+            event: name.to_string(),
+            impact: Impact::High,
+            unit: None,
+            previous: None,
+            forecast: None,
+            actual: None,
+        };
+        let now = 1_000_000;
+        let events = vec![
+            event(1, "FOMC Press Conference", now + 10 * 60),
+            event(2, "GDP Growth Rate", now + 60),
+            event(3, "FOMC Minutes", now - 60),
+        ];
+        let keywords = vec!["fomc".to_string()];
+        let triggered = evaluate_calendar_rules(&rules, &events, &keywords, now);
+        assert_eq!(triggered.len(), 1);
+        assert_eq!((triggered[0].0.id, triggered[0].1.id), (1, 1));
+
+        // 未設定關鍵字時所有高影響事件都會提醒
+        assert_eq!(evaluate_calendar_rules(&rules, &events, &[], now).len(), 3);
+    }
+
+    // === evaluate_vwap_rules tests ===
+
+    #[test]
+    fn test_evaluate_vwap_rules_compares_deviation_from_session_vwap() {
+        let rules = vec![
+            make_rule(1, "yahoo", "AAPL", ConditionType::VwapAbove, 2.0, true),
+            make_rule(2, "yahoo", "AAPL", ConditionType::VwapBelow, 2.0, true),
+            make_rule(3, "yahoo", "AAPL", ConditionType::VwapAbove, 5.0, true),
+            make_rule(4, "yahoo", "AAPL", ConditionType::PriceAbove, 1.0, true),
+        ];
+        let above = make_asset("AAPL", "yahoo", 103.0, None);
+        let ids: Vec<i64> = evaluate_vwap_rules(&rules, &above, |_| Some(100.0)).iter().map(|r| r.id).collect();
+        assert_eq!(ids, vec![1]);
+
+        let below = make_asset("AAPL", "yahoo", 97.0, None);
+        let ids: Vec<i64> = evaluate_vwap_rules(&rules, &below, |_| Some(100.0)).iter().map(|r| r.id).collect();
+        assert_eq!(ids, vec![2]);
+
+        // 當日沒有紀錄時不觸發
+        assert!(evaluate_vwap_rules(&rules, &above, |_| None).is_empty());
+    }
 }
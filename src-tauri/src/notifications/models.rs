@@ -14,6 +14,16 @@ pub enum ConditionType {
     PriceBelow,
     ChangePctAbove,
     ChangePctBelow,
+    /// 訂閱所在鏈的 gas 價格高於 / 低於閾值（單位見 `GasChain::unit`）
+    GasAbove,
+    GasBelow,
+    /// 訂閱對應穩定幣的脫鉤偏離（與 $1.00 的 %，絕對值）高於閾值
+    DepegAbove,
+    /// 經濟日曆中符合提醒關鍵字的事件將在閾值（分鐘）內開始；不看訂閱本身
+    EventBefore,
+    /// 價格高於 / 低於當日 Session VWAP 超過閾值（%）
+    VwapAbove,
+    VwapBelow,
     Ai,
 }
 
@@ -26,6 +36,12 @@ impl FromStr for ConditionType {
             "price_below" => Ok(Self::PriceBelow),
             "change_pct_above" => Ok(Self::ChangePctAbove),
             "change_pct_below" => Ok(Self::ChangePctBelow),
+            "gas_above" => Ok(Self::GasAbove),
+            "gas_below" => Ok(Self::GasBelow),
+            "depeg_above" => Ok(Self::DepegAbove),
+            "event_before" => Ok(Self::EventBefore),
+            "vwap_above" => Ok(Self::VwapAbove),
+            "vwap_below" => Ok(Self::VwapBelow),
             "ai" => Ok(Self::Ai),
             _ => Err(()),
         }
@@ -39,9 +55,35 @@ impl ConditionType {
             Self::PriceBelow => "price_below",
             Self::ChangePctAbove => "change_pct_above",
             Self::ChangePctBelow => "change_pct_below",
+            Self::GasAbove => "gas_above",
+            Self::GasBelow => "gas_below",
+            Self::DepegAbove => "depeg_above",
+            Self::EventBefore => "event_before",
+            Self::VwapAbove => "vwap_above",
+            Self::VwapBelow => "vwap_below",
             Self::Ai => "ai",
         }
     }
+
+    /// 是否為 gas 規則（由 gas tracker 的報價評估，而非價格更新）
+    pub fn is_gas(&self) -> bool {
+        matches!(self, Self::GasAbove | Self::GasBelow)
+    }
+
+    /// 是否為脫鉤規則（由 depeg monitor 的狀態評估，而非價格更新）
+    pub fn is_depeg(&self) -> bool {
+        matches!(self, Self::DepegAbove)
+    }
+
+    /// 是否為經濟日曆事件規則（由 calendar 的即將到來事件評估，而非價格更新）
+    pub fn is_calendar(&self) -> bool {
+        matches!(self, Self::EventBefore)
+    }
+
+    /// 是否為 VWAP 規則（價格更新時另以紀錄計算的 Session VWAP 評估）
+    pub fn is_vwap(&self) -> bool {
+        matches!(self, Self::VwapAbove | Self::VwapBelow)
+    }
 }
 
 // === AI 設定 ===
@@ -4,27 +4,39 @@
 //! 包含訊息格式化與重試邏輯（30 秒後重試一次）。
 
 use super::models::{ConditionType, NotificationData, TelegramConfig};
+use crate::providers::auto_price_scale;
 use serde_json::json;
+use std::str::FromStr;
 use std::time::Duration;
 use tokio::time::sleep;
 
 /// 將條件類型轉換為中文描述，並格式化閾值
 fn format_condition_description(condition_type: &ConditionType, threshold: f64) -> String {
     match condition_type {
-        ConditionType::PriceAbove => format!("Price above {}", format_price(threshold)),
-        ConditionType::PriceBelow => format!("Price below {}", format_price(threshold)),
+        ConditionType::PriceAbove => {
+            format!("Price above {}", format_price(threshold, auto_price_scale(threshold)))
+        }
+        ConditionType::PriceBelow => {
+            format!("Price below {}", format_price(threshold, auto_price_scale(threshold)))
+        }
         ConditionType::ChangePctAbove => format!("24h change above {:.2}%", threshold),
         ConditionType::ChangePctBelow => format!("24h change below {:.2}%", threshold),
+        ConditionType::GasAbove => format!("Gas above {}", threshold),
+        ConditionType::GasBelow => format!("Gas below {}", threshold),
+        ConditionType::DepegAbove => format!("Depeg above {:.2}%", threshold),
+        ConditionType::EventBefore => format!("{} minutes before event", threshold),
+        ConditionType::VwapAbove => format!("{:.2}% above session VWAP", threshold),
+        ConditionType::VwapBelow => format!("{:.2}% below session VWAP", threshold),
         ConditionType::Ai => "AI analysis triggered".to_string(),
     }
 }
 
-/// 格式化價格顯示（加入千分位逗號）
-fn format_price(price: f64) -> String {
-    let formatted = format!("{:.2}", price);
-    let parts: Vec<&str> = formatted.split('.').collect();
-    let integer_part = parts[0];
-    let decimal_part = parts[1];
+/// 格式化價格顯示（加入千分位逗號），`scale` 為小數位數
+fn format_price(price: f64, scale: u32) -> String {
+    let formatted = format!("{:.*}", scale as usize, price);
+    let (integer_part, decimal_part) = formatted
+        .split_once('.')
+        .unwrap_or((formatted.as_str(), ""));
 
     let negative = integer_part.starts_with('-');
     let digits = if negative {
@@ -49,10 +61,15 @@ fn format_price(price: f64) -> String {
         .rev()
         .collect();
 
+    let decimals = if decimal_part.is_empty() {
+        String::new()
+    } else {
+        format!(".{}", decimal_part)
+    };
     if negative {
-        format!("-${}.{}", with_commas, decimal_part)
+        format!("-${}{}", with_commas, decimals)
     } else {
-        format!("${}.{}", with_commas, decimal_part)
+        format!("${}{}", with_commas, decimals)
     }
 }
 
@@ -97,7 +114,55 @@ pub fn format_telegram_message(data: &NotificationData) -> String {
     }
 
     let condition_desc = format_condition_description(&data.condition_type, data.threshold);
-    let price_display = format_price(data.price);
+
+    // gas 規則的 provider 欄位為鏈名稱（ethereum / solana）
+    if data.condition_type.is_gas() {
+        let unit = crate::gas::GasChain::from_str(&data.provider)
+            .map(|c| c.unit())
+            .unwrap_or_default();
+        return format!(
+            "⛽ StockenBoard Gas Alert\n\n\
+             Symbol: {}\n\
+             Chain: {}\n\
+             Gas Price: {} {}\n\
+             Condition: {} {}\n\
+             Triggered At: {}",
+            data.symbol, data.provider, data.price, unit, condition_desc, unit, time_display
+        );
+    }
+
+    // 脫鉤規則的 provider 欄位為穩定幣名稱，價格為各來源的中位數
+    if data.condition_type.is_depeg() {
+        return format!(
+            "🪙 StockenBoard Depeg Alert\n\n\
+             Symbol: {}\n\
+             Stablecoin: {}\n\
+             Price: ${:.4} ({:+.2}%)\n\
+             Condition: {}\n\
+             Triggered At: {}",
+            data.symbol,
+            data.provider,
+            data.price,
+            (data.price - 1.0) * 100.0,
+            condition_desc,
+            time_display
+        );
+    }
+
+    // 事件規則的 symbol 為事件名稱、provider 為國家，價格為距離開始的分鐘數
+    if data.condition_type.is_calendar() {
+        return format!(
+            "📅 StockenBoard Economic Calendar Alert\n\n\
+             Event: {}\n\
+             Country: {}\n\
+             Starts In: {} min\n\
+             Condition: {}\n\
+             Triggered At: {}",
+            data.symbol, data.provider, data.price, condition_desc, time_display
+        );
+    }
+
+    let price_display = format_price(data.price, auto_price_scale(data.price));
 
     format!(
         "📊 StockenBoard Price Alert\n\n\
@@ -134,7 +199,7 @@ pub async fn send_telegram(
     match send_request(client, &url, &body).await {
         Ok(()) => return Ok(()),
         Err(e) => {
-            eprintln!("[Telegram] {}. Retrying in 30 seconds...", e);
+            tracing::warn!("[Telegram] {}. Retrying in 30 seconds...", e);
         }
     }
 
@@ -253,13 +318,73 @@ mod tests {
         assert!(message.contains("Condition: 24h change below -5.00%"));
     }
 
+    #[test]
+    fn test_format_telegram_message_gas_below() {
+        let data = NotificationData {
+            symbol: "ETHUSDT".to_string(),
+            provider: "ethereum".to_string(),
+            price: 8.5,
+            condition_type: ConditionType::GasBelow,
+            threshold: 10.0,
+            rule_name: "ETH gas < 10 gwei".to_string(),
+            triggered_at: chrono::Utc.with_ymd_and_hms(2024, 4, 10, 6, 45, 30).unwrap(),
+        };
+
+        let message = format_telegram_message(&data);
+
+        assert!(message.contains("Gas Alert"));
+        assert!(message.contains("Gas Price: 8.5 gwei"));
+        assert!(message.contains("Condition: Gas below 10 gwei"));
+    }
+
+    #[test]
+    fn test_format_telegram_message_depeg_above() {
+        let data = NotificationData {
+            symbol: "USDC-USD".to_string(),
+            provider: "USDC".to_string(),
+            price: 0.9725,
+            condition_type: ConditionType::DepegAbove,
+            threshold: 1.0,
+            rule_name: "USDC depeg".to_string(),
+            triggered_at: chrono::Utc.with_ymd_and_hms(2024, 4, 10, 6, 45, 30).unwrap(),
+        };
+
+        let message = format_telegram_message(&data);
+
+        assert!(message.contains("Depeg Alert"));
+        assert!(message.contains("Price: $0.9725 (-2.75%)"));
+        assert!(message.contains("Condition: Depeg above 1.00%"));
+    }
+
+    #[test]
+    fn test_format_telegram_message_event_before() {
+        let data = NotificationData {
+            symbol: "Fed Interest Rate Decision".to_string(),
+            provider: "US".to_string(),
+            price: 15.0,
+            condition_type: ConditionType::EventBefore,
+            threshold: 15.0,
+            rule_name: "FOMC heads-up".to_string(),
+            triggered_at: chrono::Utc.with_ymd_and_hms(2024, 3, 20, 17, 45, 0).unwrap(),
+        };
+
+        let message = format_telegram_message(&data);
+
+        assert!(message.contains("Economic Calendar Alert"));
+        assert!(message.contains("Event: Fed Interest Rate Decision"));
+        assert!(message.contains("Starts In: 15 min"));
+        assert!(message.contains("Condition: 15 minutes before event"));
+    }
+
     #[test]
     fn test_format_price_with_commas() {
-        assert_eq!(format_price(67500.0), "$67,500.00");
-        assert_eq!(format_price(1000000.0), "$1,000,000.00");
-        assert_eq!(format_price(100.0), "$100.00");
-        assert_eq!(format_price(0.08), "$0.08");
-        assert_eq!(format_price(1234567.89), "$1,234,567.89");
+        assert_eq!(format_price(67500.0, 2), "$67,500.00");
+        assert_eq!(format_price(1000000.0, 2), "$1,000,000.00");
+        assert_eq!(format_price(100.0, 2), "$100.00");
+        assert_eq!(format_price(0.08, 2), "$0.08");
+        assert_eq!(format_price(0.00002345, 8), "$0.00002345");
+        assert_eq!(format_price(1234.6, 0), "$1,235");
+        assert_eq!(format_price(1234567.89, 2), "$1,234,567.89");
     }
 
     #[test]
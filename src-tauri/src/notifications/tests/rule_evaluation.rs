@@ -22,6 +22,11 @@ fn make_asset(symbol: &str, provider_id: &str, price: f64, change_pct: Option<f6
         last_updated: 0,
         provider_id: provider_id.to_string(),
         extra: None,
+        pre_market: None,
+        post_market: None,
+        price_scale: None,
+        liquidity_usd: None,
+        fdv: None,
     }
 }
 
@@ -76,7 +76,7 @@ pub async fn send_webhook(
     match send_request(client, config, &payload).await {
         Ok(()) => return Ok(()),
         Err(e) => {
-            eprintln!("[Webhook] Request failed: {}. Retrying in 30 seconds...", e);
+            tracing::warn!("[Webhook] Request failed: {}. Retrying in 30 seconds...", e);
         }
     }
 
@@ -1,10 +1,17 @@
-use crate::db::DbPool;
+use crate::basket::{self, Basket};
+use crate::cache_limit::{self, CacheStatus, LruOrder};
+use crate::db::{DbPool, RecordTarget, RecordTargets};
 use crate::events::AppEvent;
 use crate::providers::registry::ProviderRegistry;
-use crate::providers::types::PROVIDER_INFO_MAP;
+use crate::providers::types::get_provider_info;
 use crate::providers::AssetData;
+use crate::sanity::PriceWindows;
+use crate::timeframes::{self, TimeframeChanges};
+use crate::ws_first::{FeedMode, WsFeed};
+use crate::ws_manager::WsManager;
 use serde::Serialize;
-use std::collections::{HashMap, HashSet};
+use std::collections::{hash_map::Entry, HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
 use tokio::sync::{broadcast, watch, RwLock};
@@ -14,6 +21,44 @@ pub struct PollTick {
     pub provider_id: String,
     pub fetched_at: i64,
     pub interval_ms: u64,
+    /// 最近一次成功取價的 tick 時間（失敗的 tick 沿用上一次的值）
+    pub last_success_at: Option<i64>,
+}
+
+/// 快取價格與其新鮮度 — 由該 provider 的 [`PollTick`] 推算，前端與 `/api/prices/cached`
+/// 可直接顯示「X 秒前更新」
+#[derive(Debug, Clone, Serialize)]
+pub struct CachedPrice {
+    #[serde(flatten)]
+    pub data: AssetData,
+    /// 產生此價格的 poll tick 時間（ms）
+    pub fetched_at: Option<i64>,
+    pub interval_ms: Option<u64>,
+    /// 超過兩個輪詢間隔未更新、該 provider 已不在輪詢，或仍是啟動時還原的快照
+    pub stale: bool,
+    /// 由紀錄推算的 1h / 7d / 30d / YTD 漲跌幅；尚無紀錄時省略
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub changes: Option<TimeframeChanges>,
+}
+
+impl CachedPrice {
+    pub fn new(data: AssetData, tick: Option<&PollTick>, restored: bool, now_ms: i64) -> Self {
+        let fetched_at = tick.and_then(|t| t.last_success_at);
+        let interval_ms = tick.map(|t| t.interval_ms);
+        let stale = restored
+            || match (fetched_at, interval_ms) {
+                (Some(at), Some(interval)) => now_ms - at > 2 * interval as i64,
+                _ => true,
+            };
+        let changes = timeframes::changes_for(&data.provider_id, &data.symbol, data.price);
+        Self {
+            data,
+            fetched_at,
+            interval_ms,
+            stale,
+            changes,
+        }
+    }
 }
 
 /// Tracks exponential backoff state for a provider that has consecutive failures.
@@ -24,6 +69,12 @@ pub struct BackoffState {
     pub next_allowed_at: Instant,
 }
 
+/// reload 訊號的合併視窗 — 匯入大量訂閱、連續切換紀錄等操作只觸發一次 polling 重建
+const RELOAD_DEBOUNCE_MS: u64 = 500;
+
+/// 持續收到 reload 時最多延後重建的時間
+const RELOAD_MAX_DELAY_MS: u64 = 5_000;
+
 /// Maximum backoff delay: 5 minutes (300,000ms)
 const MAX_BACKOFF_MS: u64 = 300_000;
 
@@ -34,8 +85,21 @@ pub struct PollingManager {
     pub cache: Arc<RwLock<HashMap<String, AssetData>>>,
     pub ticks: Arc<RwLock<HashMap<String, PollTick>>>,
     pub backoff: Arc<RwLock<HashMap<String, BackoffState>>>,
+    /// 由快照還原、尚未被新資料取代的快取 key
+    restored: Arc<RwLock<HashSet<String>>>,
+    /// 價格合理性檢查的近期價格（`provider:symbol`）
+    sanity: Arc<std::sync::Mutex<PriceWindows>>,
+    /// 快取 key 的寫入順序，超過容量上限時淘汰最舊的項目
+    lru: Arc<std::sync::Mutex<LruOrder>>,
     visible_ids: Arc<RwLock<HashMap<String, HashSet<i64>>>>,
+    /// 各 window/scope 要求的更新間隔（ms）；未列出的沿用 provider 間隔
+    window_intervals: Arc<RwLock<HashMap<String, u64>>>,
+    /// 最近一次載入設定時算出的實際輪詢間隔
+    rates: Arc<RwLock<PollRates>>,
     unattended: Arc<RwLock<bool>>,
+    recording_paused: Arc<AtomicBool>,
+    /// WS 優先輪詢使用的連線管理（未設定時一律 HTTP 輪詢）
+    ws: Option<Arc<WsManager>>,
     reload_tx: watch::Sender<u64>,
     stop_tx: watch::Sender<bool>,
 }
@@ -46,8 +110,15 @@ impl Clone for PollingManager {
             cache: self.cache.clone(),
             ticks: self.ticks.clone(),
             backoff: self.backoff.clone(),
+            restored: self.restored.clone(),
+            sanity: self.sanity.clone(),
+            lru: self.lru.clone(),
             visible_ids: self.visible_ids.clone(),
+            window_intervals: self.window_intervals.clone(),
+            rates: self.rates.clone(),
             unattended: self.unattended.clone(),
+            recording_paused: self.recording_paused.clone(),
+            ws: self.ws.clone(),
             reload_tx: self.reload_tx.clone(),
             stop_tx: self.stop_tx.clone(),
         }
@@ -63,8 +134,46 @@ struct ProviderConfig {
 #[derive(Debug)]
 struct PollingGroup {
     symbols: Vec<String>,
-    record_symbols: Vec<String>,
+    /// 需要紀錄的 symbol → 紀錄目標
+    record_targets: RecordTargets,
+    /// 群組 tick 間隔 — 所有 symbol 中最快的間隔
     interval_ms: u64,
+    /// 各 symbol 的實際間隔；慢於 `interval_ms` 的 symbol 每隔數個 tick 才取價
+    symbol_intervals: HashMap<String, u64>,
+}
+
+impl PollingGroup {
+    /// 第 `tick` 次 tick 要取價的 symbol（間隔四捨五入為 tick 的倍數）
+    fn due_symbols(&self, tick: u64) -> Vec<String> {
+        self.symbols
+            .iter()
+            .filter(|s| {
+                let every = self
+                    .symbol_intervals
+                    .get(*s)
+                    .map_or(1, |ms| ((ms + self.interval_ms / 2) / self.interval_ms.max(1)).max(1));
+                tick.is_multiple_of(every)
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+/// 實際輪詢間隔：快取 key（`provider:symbol`）與 subscription id 各一份
+#[derive(Debug, Clone, Default)]
+struct PollRates {
+    by_key: HashMap<String, u64>,
+    by_subscription: HashMap<i64, u64>,
+}
+
+/// 某個 window/scope 的輪詢設定與實際間隔（`get_polling_rates`）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WindowRates {
+    pub window_id: String,
+    /// 視窗要求的間隔；`None` 表示沿用 provider 間隔
+    pub requested_interval_ms: Option<u64>,
+    /// 可見 subscription → 實際間隔（多個視窗顯示同一 symbol 時取最快者，且不快於 provider 間隔）
+    pub intervals: HashMap<i64, u64>,
 }
 
 impl Default for PollingManager {
@@ -75,39 +184,103 @@ impl Default for PollingManager {
 
 impl PollingManager {
     pub fn new() -> Self {
+        Self::with_visible_scopes(HashMap::new())
+    }
+
+    /// 以上次保存的可見範圍（window/scope → subscription ids）建立，重啟後不必等各視窗重新註冊
+    pub fn with_visible_scopes(scopes: HashMap<String, HashSet<i64>>) -> Self {
+        Self::with_window_profiles(scopes, HashMap::new())
+    }
+
+    /// 同 [`Self::with_visible_scopes`]，並沿用各 window/scope 要求的更新間隔
+    pub fn with_window_profiles(scopes: HashMap<String, HashSet<i64>>, intervals: HashMap<String, u64>) -> Self {
         let (stop_tx, _) = watch::channel(false);
         let (reload_tx, _) = watch::channel(0u64);
         Self {
             cache: Arc::new(RwLock::new(HashMap::new())),
             ticks: Arc::new(RwLock::new(HashMap::new())),
             backoff: Arc::new(RwLock::new(HashMap::new())),
-            visible_ids: Arc::new(RwLock::new(HashMap::new())),
+            restored: Arc::new(RwLock::new(HashSet::new())),
+            sanity: Arc::new(std::sync::Mutex::new(PriceWindows::default())),
+            lru: Arc::new(std::sync::Mutex::new(LruOrder::default())),
+            visible_ids: Arc::new(RwLock::new(scopes)),
+            window_intervals: Arc::new(RwLock::new(intervals)),
+            rates: Arc::new(RwLock::new(PollRates::default())),
             unattended: Arc::new(RwLock::new(false)),
+            recording_paused: Arc::new(AtomicBool::new(false)),
+            ws: None,
             reload_tx,
             stop_tx,
         }
     }
 
+    /// 啟用 WS 優先輪詢時，由 `ws` 串流支援 WebSocket 的 provider（需在 [`Self::start`] 前設定）
+    pub fn with_ws(mut self, ws: Arc<WsManager>) -> Self {
+        self.ws = Some(ws);
+        self
+    }
+
+    /// 要求重新載入設定；polling 迴圈會合併 [`RELOAD_DEBOUNCE_MS`] 內的連續要求，只重建一次
     pub fn reload(&self) {
         self.reload_tx.send_modify(|v| *v = v.wrapping_add(1));
     }
 
-    pub async fn set_visible(&self, window_id: String, ids: HashSet<i64>) {
+    /// 設定某個 window/scope 的可見 subscription 與要求的更新間隔（`None` 沿用 provider 間隔）；
+    /// 有變更時回傳 `true` 並重新載入
+    pub async fn set_visible(&self, window_id: String, ids: HashSet<i64>, interval_ms: Option<u64>) -> bool {
         let mut map = self.visible_ids.write().await;
+        let mut intervals = self.window_intervals.write().await;
+        let interval_ms = interval_ms.filter(|_| !ids.is_empty());
+        if map.get(&window_id).is_some_and(|existing| *existing == ids)
+            && intervals.get(&window_id).copied() == interval_ms
+        {
+            return false;
+        }
         if ids.is_empty() {
             if map.remove(&window_id).is_none() {
-                return;
+                return false;
             }
         } else {
-            if let Some(existing) = map.get(&window_id) {
-                if *existing == ids {
-                    return;
-                }
-            }
-            map.insert(window_id, ids);
+            map.insert(window_id.clone(), ids);
         }
+        match interval_ms {
+            Some(ms) => intervals.insert(window_id, ms),
+            None => intervals.remove(&window_id),
+        };
         drop(map);
+        drop(intervals);
         self.reload_tx.send_modify(|v| *v = v.wrapping_add(1));
+        true
+    }
+
+    /// 目前所有 window/scope 的可見範圍
+    pub async fn visible_scopes(&self) -> HashMap<String, HashSet<i64>> {
+        self.visible_ids.read().await.clone()
+    }
+
+    /// 目前各 window/scope 要求的更新間隔
+    pub async fn window_intervals(&self) -> HashMap<String, u64> {
+        self.window_intervals.read().await.clone()
+    }
+
+    /// 各 window/scope 的要求間隔與可見 subscription 的實際輪詢間隔（依 window id 排序）
+    pub async fn window_rates(&self) -> Vec<WindowRates> {
+        let scopes = self.visible_ids.read().await;
+        let intervals = self.window_intervals.read().await;
+        let rates = self.rates.read().await;
+        let mut result: Vec<WindowRates> = scopes
+            .iter()
+            .map(|(window_id, ids)| WindowRates {
+                window_id: window_id.clone(),
+                requested_interval_ms: intervals.get(window_id).copied(),
+                intervals: ids
+                    .iter()
+                    .filter_map(|id| Some((*id, *rates.by_subscription.get(id)?)))
+                    .collect(),
+            })
+            .collect();
+        result.sort_by(|a, b| a.window_id.cmp(&b.window_id));
+        result
     }
 
     pub async fn set_unattended(&self, enabled: bool) {
@@ -124,9 +297,92 @@ impl PollingManager {
         *self.unattended.read().await
     }
 
+    /// 所有快取價格，附上新鮮度資訊
+    pub async fn cached_prices(&self) -> Vec<CachedPrice> {
+        self.cached_prices_since(None).await
+    }
+
+    /// `last_updated` 晚於 `since`（ms）的快取價格；`None` 表示全部
+    pub async fn cached_prices_since(&self, since: Option<i64>) -> Vec<CachedPrice> {
+        let cache = self.cache.read().await;
+        let ticks = self.ticks.read().await;
+        let restored = self.restored.read().await;
+        let rates = self.rates.read().await;
+        let now = chrono::Utc::now().timestamp_millis();
+        cache
+            .iter()
+            .filter(|(_, d)| since.is_none_or(|ts| d.last_updated > ts))
+            .map(|(key, d)| {
+                // 慢於 provider tick 的 symbol 以自己的間隔判斷新鮮度
+                let mut tick = ticks.get(&d.provider_id).cloned();
+                if let (Some(t), Some(ms)) = (tick.as_mut(), rates.by_key.get(key)) {
+                    t.interval_ms = *ms;
+                }
+                CachedPrice::new(d.clone(), tick.as_ref(), restored.contains(key), now)
+            })
+            .collect()
+    }
+
+    /// 目前快取內容（`provider:symbol` → AssetData），供保存快照
+    pub async fn cache_snapshot(&self) -> Vec<(String, AssetData)> {
+        self.cache
+            .read()
+            .await
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect()
+    }
+
+    /// 啟動時以上次保存的快照填入快取（標記為 stale，直到被新資料取代）；不覆蓋已有的資料
+    pub async fn restore_cache(&self, entries: Vec<(String, AssetData)>) {
+        let mut cache = self.cache.write().await;
+        let mut restored = self.restored.write().await;
+        for (key, data) in entries {
+            if let Entry::Vacant(slot) = cache.entry(key.clone()) {
+                slot.insert(data);
+                restored.insert(key);
+            }
+        }
+        let max_entries = cache_limit::cache_config().max_entries;
+        self.lru
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .evict(&mut cache, &mut restored, max_entries);
+    }
+
+    /// 快取用量與估算記憶體
+    pub async fn cache_status(&self) -> CacheStatus {
+        let cache = self.cache.read().await;
+        CacheStatus {
+            entries: cache.len(),
+            max_entries: cache_limit::cache_config().max_entries,
+            restored: self.restored.read().await.len(),
+            ticks: self.ticks.read().await.len(),
+            evicted: self.lru.lock().unwrap_or_else(|e| e.into_inner()).evicted(),
+            approx_bytes: cache_limit::estimate_bytes(&cache),
+        }
+    }
+
+    /// 暫停／恢復價格紀錄 — 暫停時照常取價，但不再送出紀錄目標
+    pub fn set_recording_paused(&self, paused: bool) {
+        if self.recording_paused.swap(paused, Ordering::Relaxed) != paused {
+            self.reload_tx.send_modify(|v| *v = v.wrapping_add(1));
+        }
+    }
+
+    pub fn is_recording_paused(&self) -> bool {
+        self.recording_paused.load(Ordering::Relaxed)
+    }
+
+    /// 暫停狀態的共用旗標，供背景工作（紀錄停滯檢查）讀取
+    pub fn recording_paused_flag(&self) -> Arc<AtomicBool> {
+        self.recording_paused.clone()
+    }
+
     /// 啟動 Polling 主迴圈
     /// Polling 只負責取得數據並發送 AppEvent 到 event_bus，
-    /// 不再直接寫 DB 或 emit 到前端（由 Forwarder 處理）
+    /// 不再直接寫 DB 或 emit 到前端（由 Forwarder 處理）；
+    /// 同時啟動探測退避中 provider 的 [`crate::watchdog`]
     pub fn start(
         &self,
         db: Arc<DbPool>,
@@ -136,30 +392,48 @@ impl PollingManager {
         let cache = self.cache.clone();
         let ticks = self.ticks.clone();
         let backoff = self.backoff.clone();
+        let restored = self.restored.clone();
+        let sanity = self.sanity.clone();
+        let lru = self.lru.clone();
         let visible_ids = self.visible_ids.clone();
+        let window_intervals = self.window_intervals.clone();
+        let rates = self.rates.clone();
         let unattended = self.unattended.clone();
+        let recording_paused = self.recording_paused.clone();
+        let ws = self.ws.clone();
         let mut reload_rx = self.reload_tx.subscribe();
         let mut stop_rx = self.stop_tx.subscribe();
+        crate::watchdog::start(
+            backoff.clone(),
+            db.clone(),
+            registry.clone(),
+            event_bus.clone(),
+            self.stop_tx.subscribe(),
+        );
 
         tokio::spawn(async move {
             loop {
                 let is_unattended = *unattended.read().await;
 
-                let (vis_snapshot, has_windows): (HashSet<i64>, bool) = if is_unattended {
-                    (HashSet::new(), false)
+                let (vis_snapshot, has_windows): (HashMap<i64, Option<u64>>, bool) = if is_unattended {
+                    (HashMap::new(), false)
                 } else {
                     let map = visible_ids.read().await;
                     if map.is_empty() {
-                        (HashSet::new(), false)
+                        (HashMap::new(), false)
                     } else {
-                        (map.values().flat_map(|s| s.iter().copied()).collect(), true)
+                        (merge_visible(&map, &*window_intervals.read().await), true)
                     }
                 };
                 if !is_unattended && has_windows && vis_snapshot.is_empty() {
                     cache.write().await.clear();
                     ticks.write().await.clear();
+                    lru.lock().unwrap_or_else(|e| e.into_inner()).clear();
                     tokio::select! {
-                        _ = reload_rx.changed() => continue,
+                        _ = reload_rx.changed() => {
+                            settle_reloads(&mut reload_rx).await;
+                            continue;
+                        }
                         _ = stop_rx.changed() => break,
                     }
                 }
@@ -169,10 +443,14 @@ impl PollingManager {
                 } else {
                     None
                 };
-                let groups = match load_config(&db, visible_ref) {
-                    Ok(g) => g,
+                let record = !recording_paused.load(Ordering::Relaxed);
+                let (groups, baskets) = match load_config(&db, visible_ref, record) {
+                    Ok((groups, baskets, poll_rates)) => {
+                        *rates.write().await = poll_rates;
+                        (groups, baskets)
+                    }
                     Err(e) => {
-                        eprintln!("[Polling] Failed to read config: {}", e);
+                        tracing::warn!("[Polling] Failed to read config: {}", e);
                         tokio::time::sleep(std::time::Duration::from_secs(5)).await;
                         continue;
                     }
@@ -184,107 +462,190 @@ impl PollingManager {
                         .flat_map(|(pid, g)| {
                             g.symbols.iter().map(move |s| format!("{}:{}", pid, s))
                         })
+                        .chain(baskets.iter().map(Basket::cache_key))
                         .collect();
                     cache.write().await.retain(|k, _| valid.contains(k));
-                    let active_pids: HashSet<&String> = groups.keys().collect();
-                    ticks.write().await.retain(|k, _| active_pids.contains(k));
+                    restored.write().await.retain(|k| valid.contains(k));
+                    sanity.lock().unwrap_or_else(|e| e.into_inner()).retain(&valid);
+                    lru.lock().unwrap_or_else(|e| e.into_inner()).retain(&valid);
+                    let mut active_pids: HashSet<&str> = groups.keys().map(String::as_str).collect();
+                    if !baskets.is_empty() {
+                        active_pids.insert(basket::PROVIDER_ID);
+                    }
+                    ticks.write().await.retain(|k, _| active_pids.contains(k.as_str()));
+                    crate::latency_tune::retain(&active_pids);
                 }
 
+                // WS 優先：支援 WebSocket 的 provider 改由串流取價（停用時取消 polling 的串流要求）
+                let ws_first = crate::ws_first::ws_first_config();
+                let feed = match &ws {
+                    Some(ws) if ws_first.enabled => Some(Arc::new(WsFeed::subscribe(
+                        ws.clone(),
+                        ws_first.fallback_secs,
+                        groups.iter().map(|(pid, g)| (pid.clone(), g.symbols.clone())).collect(),
+                    ))),
+                    Some(ws) => {
+                        ws.remove_owner(crate::ws_first::OWNER);
+                        None
+                    }
+                    None => None,
+                };
+
                 if groups.is_empty() {
                     tokio::select! {
-                        _ = reload_rx.changed() => continue,
+                        _ = reload_rx.changed() => {
+                            settle_reloads(&mut reload_rx).await;
+                            continue;
+                        }
                         _ = stop_rx.changed() => break,
                     }
                 }
 
                 let (gen_stop_tx, _) = watch::channel(false);
-                let mut handles = Vec::with_capacity(groups.len());
+                let mut handles = Vec::with_capacity(groups.len() + 1);
+                if let (Some(ws), Some(feed)) = (&ws, &feed) {
+                    let mut ticker_rx = ws.subscribe();
+                    let feed = feed.clone();
+                    handles.push(tokio::spawn(async move {
+                        loop {
+                            match ticker_rx.recv().await {
+                                Ok(update) => feed.observe(update),
+                                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                                Err(broadcast::error::RecvError::Closed) => break,
+                            }
+                        }
+                    }));
+                }
 
-                for (provider_id, group) in &groups {
-                    let symbols = group.symbols.clone();
+                for (provider_id, group) in groups {
                     let interval_ms = group.interval_ms;
+                    let provider_id = &provider_id;
                     let pid = provider_id.clone();
                     let cache = cache.clone();
                     let ticks = ticks.clone();
                     let backoff = backoff.clone();
+                    let restored = restored.clone();
+                    let sanity = sanity.clone();
+                    let lru = lru.clone();
                     let mut gen_stop = gen_stop_tx.subscribe();
-                    let record_symbols: Vec<String> = group.record_symbols.clone();
+                    let record_targets = Arc::new(group.record_targets.clone());
+                    let group = Arc::new(group);
                     let db_clone = db.clone();
                     let reg = registry.clone();
                     let bus = event_bus.clone();
+                    let baskets: Vec<Basket> =
+                        baskets.iter().filter(|b| b.depends_on(provider_id)).cloned().collect();
+                    let feed = feed.as_ref().filter(|f| f.streams(provider_id)).cloned();
 
                     handles.push(tokio::spawn(async move {
+                        // 重新載入後沿用上一輪的成功時間
+                        let mut last_success_at =
+                            ticks.read().await.get(&pid).and_then(|t| t.last_success_at);
+                        let mut tick_count: u64 = 0;
+                        // 啟用延遲自動調整時依 p95 延遲放慢／加快，否則即為設定的間隔
+                        let mut interval_ms = crate::latency_tune::effective_interval(&pid, interval_ms);
                         loop {
-                            // Check backoff: skip if provider is in backoff period
-                            {
-                                let backoff_map = backoff.read().await;
-                                if let Some(state) = backoff_map.get(&pid) {
-                                    if Instant::now() < state.next_allowed_at {
-                                        eprintln!(
-                                            "[Polling] Skipping {} — in backoff (failures: {}, level: {})",
-                                            pid, state.consecutive_failures, state.consecutive_failures
-                                        );
-                                        drop(backoff_map);
-                                        tokio::select! {
-                                            _ = tokio::time::sleep(std::time::Duration::from_millis(interval_ms)) => {},
-                                            _ = gen_stop.changed() => break,
+                            let now = chrono::Utc::now().timestamp_millis();
+                            let mode = feed.as_ref().map_or(FeedMode::Poll, |f| f.mode(&pid, now));
+                            let fetched_at = if mode == FeedMode::Poll {
+                                // Check backoff: skip if provider is in backoff period
+                                {
+                                    let backoff_map = backoff.read().await;
+                                    if let Some(state) = backoff_map.get(&pid) {
+                                        if Instant::now() < state.next_allowed_at {
+                                            tracing::warn!(
+                                                "[Polling] Skipping {} — in backoff (failures: {}, level: {})",
+                                                pid, state.consecutive_failures, state.consecutive_failures
+                                            );
+                                            drop(backoff_map);
+                                            tokio::select! {
+                                                _ = tokio::time::sleep(std::time::Duration::from_millis(interval_ms)) => {},
+                                                _ = gen_stop.changed() => break,
+                                            }
+                                            continue;
                                         }
-                                        continue;
                                     }
                                 }
-                            }
+                                // 改回 HTTP 輪詢期間，丟棄斷線前殘留的串流 ticker
+                                if let Some(f) = &feed {
+                                    f.take(&pid);
+                                }
 
-                            match reg.fetch_with_limit(&pid, &symbols, &db_clone).await {
-                                Ok(results) => {
-                                    // On success: reset backoff state for this provider
-                                    {
-                                        let mut backoff_map = backoff.write().await;
-                                        backoff_map.remove(&pid);
+                                let symbols = group.due_symbols(tick_count);
+                                tick_count = tick_count.wrapping_add(1);
+                                let started = Instant::now();
+                                let result = reg.fetch_with_limit(&pid, &symbols, &db_clone).await;
+                                let fetched_at = chrono::Utc::now().timestamp_millis();
+                                interval_ms = crate::latency_tune::observe(
+                                    &pid,
+                                    group.interval_ms,
+                                    started.elapsed().as_millis() as u64,
+                                );
+                                match result {
+                                    Ok(results) => {
+                                        last_success_at = Some(fetched_at);
+                                        // On success: reset backoff state for this provider
+                                        {
+                                            let mut backoff_map = backoff.write().await;
+                                            backoff_map.remove(&pid);
+                                        }
+                                        publish_prices(
+                                            &pid, results, &record_targets, &baskets, &sanity, &cache, &restored, &lru,
+                                            &ticks, &bus, fetched_at, interval_ms,
+                                        )
+                                        .await;
                                     }
-                                    // 更新本地快取（保持 get_cached_prices 功能）
-                                    {
-                                        let mut c = cache.write().await;
-                                        for d in &results {
-                                            c.insert(format!("{}:{}", pid, d.symbol), d.clone());
+                                    Err(e) => {
+                                        tracing::warn!("[Polling] {} fetch failed: {}", pid, e);
+                                        // On failure: increment failures, compute backoff delay
+                                        {
+                                            let mut backoff_map = backoff.write().await;
+                                            let state = backoff_map
+                                                .entry(pid.clone())
+                                                .or_insert_with(|| BackoffState {
+                                                    consecutive_failures: 0,
+                                                    next_allowed_at: Instant::now(),
+                                                });
+                                            state.consecutive_failures += 1;
+                                            let delay_ms = compute_backoff_delay(state.consecutive_failures);
+                                            state.next_allowed_at = Instant::now()
+                                                + std::time::Duration::from_millis(delay_ms);
                                         }
+                                        let _ = bus.send(AppEvent::PriceError {
+                                            provider_id: pid.clone(),
+                                            symbols: symbols.clone(),
+                                            error: e,
+                                        });
                                     }
-                                    // 發送 PriceUpdate 到 event bus
-                                    let _ = bus.send(AppEvent::PriceUpdate {
-                                        provider_id: pid.clone(),
-                                        data: results,
-                                        record_symbols: record_symbols.clone(),
-                                    });
                                 }
-                                Err(e) => {
-                                    eprintln!("[Polling] {} fetch failed: {}", pid, e);
-                                    // On failure: increment failures, compute backoff delay
-                                    {
-                                        let mut backoff_map = backoff.write().await;
-                                        let state = backoff_map
-                                            .entry(pid.clone())
-                                            .or_insert_with(|| BackoffState {
-                                                consecutive_failures: 0,
-                                                next_allowed_at: Instant::now(),
-                                            });
-                                        state.consecutive_failures += 1;
-                                        let delay_ms = compute_backoff_delay(state.consecutive_failures);
-                                        state.next_allowed_at = Instant::now()
-                                            + std::time::Duration::from_millis(delay_ms);
+                                fetched_at
+                            } else {
+                                // WS 優先：已連線時送出上一個 tick 以來的串流 ticker，斷線未超過容忍時間時等待重連
+                                if mode == FeedMode::Stream {
+                                    last_success_at = Some(now);
+                                    let results = feed.as_ref().map(|f| f.take(&pid)).unwrap_or_default();
+                                    if !results.is_empty() {
+                                        publish_prices(
+                                            &pid, results, &record_targets, &baskets, &sanity, &cache, &restored, &lru,
+                                            &ticks, &bus, now, interval_ms,
+                                        )
+                                        .await;
                                     }
-                                    let _ = bus.send(AppEvent::PriceError {
-                                        provider_id: pid.clone(),
-                                        symbols: symbols.clone(),
-                                        error: e,
-                                    });
                                 }
-                            }
+                                now
+                            };
                             // 發送 PollTick
                             let tick = PollTick {
                                 provider_id: pid.clone(),
-                                fetched_at: chrono::Utc::now().timestamp_millis(),
+                                fetched_at,
                                 interval_ms,
+                                last_success_at,
                             };
-                            ticks.write().await.insert(pid.clone(), tick.clone());
+                            {
+                                let mut t = ticks.write().await;
+                                t.insert(pid.clone(), tick.clone());
+                                cache_limit::evict_oldest(&mut t, cache_limit::cache_config().max_entries, |t| t.fetched_at);
+                            }
                             let _ = bus.send(AppEvent::PollTick {
                                 provider_id: pid.clone(),
                                 fetched_at: tick.fetched_at,
@@ -299,7 +660,8 @@ impl PollingManager {
                 }
 
                 tokio::select! {
-                    _ = reload_rx.changed() => {},
+                    // 合併期間舊的 provider task 照常取價
+                    _ = reload_rx.changed() => settle_reloads(&mut reload_rx).await,
                     _ = stop_rx.changed() => {
                         drop(gen_stop_tx);
                         for h in handles { h.abort(); }
@@ -311,10 +673,29 @@ impl PollingManager {
                     h.abort();
                 }
             }
+            if let Some(ws) = &ws {
+                ws.remove_owner(crate::ws_first::OWNER);
+            }
         });
     }
 }
 
+/// 收到 reload 後，等到 [`RELOAD_DEBOUNCE_MS`] 內沒有新的要求（最多 [`RELOAD_MAX_DELAY_MS`]）再回傳
+async fn settle_reloads(reload_rx: &mut watch::Receiver<u64>) {
+    let deadline = tokio::time::Instant::now() + std::time::Duration::from_millis(RELOAD_MAX_DELAY_MS);
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(std::time::Duration::from_millis(RELOAD_DEBOUNCE_MS)) => return,
+            _ = tokio::time::sleep_until(deadline) => return,
+            changed = reload_rx.changed() => {
+                if changed.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
 /// Computes the backoff delay in milliseconds using exponential backoff.
 /// Formula: min(BASE_BACKOFF_MS * 2^failures, MAX_BACKOFF_MS)
 pub fn compute_backoff_delay(consecutive_failures: u32) -> u64 {
@@ -323,15 +704,195 @@ pub fn compute_backoff_delay(consecutive_failures: u32) -> u64 {
     delay.min(MAX_BACKOFF_MS)
 }
 
-/// 從 DbPool 讀取配置，組合成 polling groups
+/// 取得價格後的共同處理：過濾異常報價、寫入快取、送出 PriceUpdate 並更新相依的籃子
+#[allow(clippy::too_many_arguments)]
+async fn publish_prices(
+    pid: &str,
+    results: Vec<AssetData>,
+    record_targets: &Arc<RecordTargets>,
+    baskets: &[Basket],
+    sanity: &std::sync::Mutex<PriceWindows>,
+    cache: &RwLock<HashMap<String, AssetData>>,
+    restored: &RwLock<HashSet<String>>,
+    lru: &std::sync::Mutex<LruOrder>,
+    ticks: &RwLock<HashMap<String, PollTick>>,
+    bus: &broadcast::Sender<AppEvent>,
+    fetched_at: i64,
+    interval_ms: u64,
+) {
+    // 過濾明顯錯誤的報價，不寫入快取也不送出 PriceUpdate
+    let (results, anomalies) = sanity.lock().unwrap_or_else(|e| e.into_inner()).filter(pid, results);
+    for anomaly in anomalies {
+        let _ = bus.send(AppEvent::PriceAnomaly(anomaly));
+    }
+    // 更新本地快取（保持 get_cached_prices 功能）
+    {
+        let mut c = cache.write().await;
+        let mut r = restored.write().await;
+        let mut order = lru.lock().unwrap_or_else(|e| e.into_inner());
+        for d in &results {
+            let key = format!("{}:{}", pid, d.symbol);
+            r.remove(&key);
+            order.touch(&key);
+            c.insert(key, d.clone());
+        }
+        order.evict(&mut c, &mut r, cache_limit::cache_config().max_entries);
+    }
+    // 發送 PriceUpdate 到 event bus
+    let _ = bus.send(AppEvent::PriceUpdate {
+        provider_id: pid.to_string(),
+        data: results,
+        record_targets: record_targets.clone(),
+    });
+    if !baskets.is_empty() {
+        update_baskets(baskets, cache, restored, lru, ticks, bus, fetched_at, interval_ms).await;
+    }
+}
+
+/// 成分更新後重新計算相依的籃子：寫入快取、更新 `basket` 的 PollTick 並送出 PriceUpdate
+#[allow(clippy::too_many_arguments)]
+async fn update_baskets(
+    baskets: &[Basket],
+    cache: &RwLock<HashMap<String, AssetData>>,
+    restored: &RwLock<HashSet<String>>,
+    lru: &std::sync::Mutex<LruOrder>,
+    ticks: &RwLock<HashMap<String, PollTick>>,
+    bus: &broadcast::Sender<AppEvent>,
+    fetched_at: i64,
+    interval_ms: u64,
+) {
+    let mut data = Vec::new();
+    {
+        let mut c = cache.write().await;
+        let mut r = restored.write().await;
+        let mut order = lru.lock().unwrap_or_else(|e| e.into_inner());
+        for b in baskets {
+            if let Some(d) = b.compute(&c) {
+                let key = b.cache_key();
+                r.remove(&key);
+                order.touch(&key);
+                c.insert(key, d.clone());
+                data.push(d);
+            }
+        }
+        order.evict(&mut c, &mut r, cache_limit::cache_config().max_entries);
+    }
+    if data.is_empty() {
+        return;
+    }
+    let record_targets = baskets
+        .iter()
+        .filter(|b| data.iter().any(|d| d.symbol == b.symbol))
+        .filter_map(|b| Some((b.symbol.clone(), b.record?)))
+        .collect();
+    let _ = bus.send(AppEvent::PriceUpdate {
+        provider_id: basket::PROVIDER_ID.to_string(),
+        data,
+        record_targets: Arc::new(record_targets),
+    });
+    ticks.write().await.insert(
+        basket::PROVIDER_ID.to_string(),
+        PollTick {
+            provider_id: basket::PROVIDER_ID.to_string(),
+            fetched_at,
+            interval_ms,
+            last_success_at: Some(fetched_at),
+        },
+    );
+    let _ = bus.send(AppEvent::PollTick {
+        provider_id: basket::PROVIDER_ID.to_string(),
+        fetched_at,
+        interval_ms,
+    });
+}
+
+/// provider groups、可見的籃子與實際輪詢間隔
+type LoadedConfig = (HashMap<String, PollingGroup>, Vec<Basket>, PollRates);
+
+/// subscription id、籃子的快取 key，以及其取價的 (provider, symbol)
+type RateSource = (i64, Option<String>, Vec<(String, String)>);
+
+/// 合併各 window/scope 的可見範圍：subscription → 要求的間隔（`None` 表示至少有一個視窗沿用 provider 間隔）
+fn merge_visible(
+    scopes: &HashMap<String, HashSet<i64>>,
+    intervals: &HashMap<String, u64>,
+) -> HashMap<i64, Option<u64>> {
+    let mut merged: HashMap<i64, Option<u64>> = HashMap::new();
+    for (window_id, ids) in scopes {
+        let requested = intervals.get(window_id).copied();
+        for id in ids {
+            merged
+                .entry(*id)
+                .and_modify(|current| {
+                    *current = match (*current, requested) {
+                        (Some(a), Some(b)) => Some(a.min(b)),
+                        _ => None,
+                    }
+                })
+                .or_insert(requested);
+        }
+    }
+    merged
+}
+
+/// 從 DbPool 讀取配置，組合成 polling groups、可見的籃子與實際輪詢間隔（`record = false` 時不紀錄任何 symbol）
+///
+/// 籃子不經 provider 取價；可見籃子的成分即使本身不可見也會加入輪詢（不紀錄）。
+/// 視窗要求的間隔只能放慢輪詢（不快於 provider 間隔）；同一 symbol 取所有要求中最快者。
 fn load_config(
     db: &Arc<DbPool>,
-    visible_ids: Option<&HashSet<i64>>,
-) -> Result<HashMap<String, PollingGroup>, String> {
-    let all_subs = db.read_polling_subscriptions(visible_ids)?;
+    visible_ids: Option<&HashMap<i64, Option<u64>>>,
+    record: bool,
+) -> Result<LoadedConfig, String> {
+    let subs = db.read_polling_subscriptions(None)?;
     let settings_map = db.read_polling_provider_settings()?;
+    let basket_params = db.read_basket_params()?;
+    // 每一輪只解析一次紀錄目標，寫入歷史時不必逐筆查詢 subscriptions
+    let targets = if record { db.read_record_targets()? } else { HashMap::new() };
+    let record_target = |provider_id: &str, symbol: &str, record_enabled: bool| -> Option<RecordTarget> {
+        if !record_enabled {
+            return None;
+        }
+        targets.get(provider_id)?.get(symbol).copied()
+    };
+
+    let mut baskets = Vec::new();
+    // (symbol, provider, record_enabled, 要求的間隔)
+    let mut polled: Vec<(String, String, bool, Option<u64>)> = Vec::new();
+    // 籃子對應其成分
+    let mut sources: Vec<RateSource> = Vec::new();
+    for (id, symbol, provider_id, record_enabled) in &subs {
+        let requested = match visible_ids {
+            Some(ids) => match ids.get(id) {
+                Some(requested) => *requested,
+                None => continue,
+            },
+            None => None,
+        };
+        if provider_id != basket::PROVIDER_ID {
+            polled.push((symbol.clone(), provider_id.clone(), *record_enabled, requested));
+            sources.push((*id, None, vec![(provider_id.clone(), symbol.clone())]));
+            continue;
+        }
+        let target = record_target(basket::PROVIDER_ID, symbol, *record_enabled);
+        match Basket::resolve(symbol, target, basket_params.get(id).map(String::as_str), &subs) {
+            Ok(b) => {
+                polled.extend(
+                    b.components
+                        .iter()
+                        .map(|c| (c.symbol.clone(), c.provider_id.clone(), false, requested)),
+                );
+                sources.push((
+                    *id,
+                    Some(b.cache_key()),
+                    b.components.iter().map(|c| (c.provider_id.clone(), c.symbol.clone())).collect(),
+                ));
+                baskets.push(b);
+            }
+            Err(e) => tracing::warn!("[Polling] Skipping basket {}: {}", symbol, e),
+        }
+    }
 
-    let info_map = &*PROVIDER_INFO_MAP;
     let mut groups: HashMap<String, PollingGroup> = HashMap::new();
 
     let mut configs: HashMap<String, ProviderConfig> = HashMap::new();
@@ -345,7 +906,7 @@ fn load_config(
         );
     }
 
-    for (_, symbol, provider_id, record_enabled) in &all_subs {
+    for (symbol, provider_id, record_enabled, requested) in &polled {
         let pid = provider_id;
         let config = configs.get(pid.as_str());
 
@@ -353,8 +914,8 @@ fn load_config(
             .and_then(|c| c.api_key.as_ref())
             .map(|k| !k.is_empty())
             .unwrap_or(false);
-        let default_interval = info_map
-            .get(pid.as_str())
+        // 預設間隔可能被遠端 metadata 覆寫，因此不直接讀編譯期的 info map
+        let default_interval = get_provider_info(pid)
             .map(|i| {
                 if has_key {
                     i.key_interval
@@ -366,23 +927,51 @@ fn load_config(
         let interval_ms = config
             .and_then(|c| c.refresh_interval)
             .unwrap_or(default_interval) as u64;
+        let interval_ms = requested.map_or(interval_ms, |r| r.max(interval_ms));
+        // 省電模式時放大間隔
+        let interval_ms = (interval_ms as f64 * crate::power::interval_multiplier()) as u64;
 
         let group = groups.entry(pid.clone()).or_insert_with(|| PollingGroup {
             symbols: Vec::new(),
-            record_symbols: Vec::new(),
+            record_targets: RecordTargets::new(),
             interval_ms,
+            symbol_intervals: HashMap::new(),
         });
+        group.interval_ms = group.interval_ms.min(interval_ms);
+        group
+            .symbol_intervals
+            .entry(symbol.clone())
+            .and_modify(|ms| *ms = (*ms).min(interval_ms))
+            .or_insert(interval_ms);
         if !group.symbols.contains(symbol) {
             group.symbols.push(symbol.clone());
         }
-        if *record_enabled && !group.record_symbols.contains(symbol) {
-            group.record_symbols.push(symbol.clone());
+        if let Some(target) = record_target(pid, symbol, *record_enabled) {
+            group.record_targets.entry(symbol.clone()).or_insert(target);
         }
     }
 
-    Ok(groups)
-}
+    let mut rates = PollRates::default();
+    for (pid, group) in &groups {
+        for (symbol, ms) in &group.symbol_intervals {
+            rates.by_key.insert(format!("{}:{}", pid, symbol), *ms);
+        }
+    }
+    for (id, basket_key, parts) in sources {
+        let fastest = parts
+            .iter()
+            .filter_map(|(pid, symbol)| groups.get(pid)?.symbol_intervals.get(symbol).copied())
+            .min();
+        if let Some(ms) = fastest {
+            rates.by_subscription.insert(id, ms);
+            if let Some(key) = basket_key {
+                rates.by_key.insert(key, ms);
+            }
+        }
+    }
 
+    Ok((groups, baskets, rates))
+}
 
 #[cfg(test)]
 mod tests {
@@ -572,4 +1161,262 @@ mod tests {
             );
         }
     }
+
+    #[tokio::test]
+    async fn test_restored_cache_is_stale_and_never_overwrites() {
+        let manager = PollingManager::new();
+        let live = crate::providers::AssetDataBuilder::new("ETH", "binance").price(2.0).build();
+        manager.cache.write().await.insert("binance:ETH".to_string(), live);
+
+        let old = |symbol: &str| crate::providers::AssetDataBuilder::new(symbol, "binance").price(1.0).build();
+        manager
+            .restore_cache(vec![
+                ("binance:BTC".to_string(), old("BTC")),
+                ("binance:ETH".to_string(), old("ETH")),
+            ])
+            .await;
+
+        let prices = manager.cached_prices().await;
+        let btc = prices.iter().find(|p| p.data.symbol == "BTC").unwrap();
+        assert!(btc.stale);
+        let eth = prices.iter().find(|p| p.data.symbol == "ETH").unwrap();
+        assert_eq!(eth.data.price, 2.0);
+        assert_eq!(manager.cache_snapshot().await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_reloads_are_coalesced() {
+        let manager = PollingManager::new();
+        let mut reload_rx = manager.reload_tx.subscribe();
+        let sender = manager.clone();
+        let burst = tokio::spawn(async move {
+            for _ in 0..5 {
+                sender.reload();
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        });
+
+        reload_rx.changed().await.unwrap();
+        let started = Instant::now();
+        settle_reloads(&mut reload_rx).await;
+        burst.await.unwrap();
+        // 最後一次要求後仍等待完整的合併視窗，五次要求只觸發一次重建
+        assert!(started.elapsed() >= Duration::from_millis(300 + RELOAD_DEBOUNCE_MS));
+        assert_eq!(*reload_rx.borrow_and_update(), 5);
+        assert!(!reload_rx.has_changed().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_cache_status_counts_restored_entries() {
+        let manager = PollingManager::new();
+        let old = |symbol: &str| crate::providers::AssetDataBuilder::new(symbol, "binance").price(1.0).build();
+        manager
+            .restore_cache(vec![
+                ("binance:BTC".to_string(), old("BTC")),
+                ("binance:ETH".to_string(), old("ETH")),
+            ])
+            .await;
+
+        let status = manager.cache_status().await;
+        assert_eq!((status.entries, status.restored, status.ticks, status.evicted), (2, 2, 0, 0));
+        assert!(status.approx_bytes > 0);
+    }
+
+    #[tokio::test]
+    async fn test_cached_prices_since_filters_by_last_updated() {
+        let manager = PollingManager::new();
+        let at = |symbol: &str, ts: i64| AssetData {
+            last_updated: ts,
+            ..crate::providers::AssetDataBuilder::new(symbol, "binance").price(1.0).build()
+        };
+        {
+            let mut cache = manager.cache.write().await;
+            cache.insert("binance:BTC".to_string(), at("BTC", 1_000));
+            cache.insert("binance:ETH".to_string(), at("ETH", 2_000));
+        }
+
+        assert_eq!(manager.cached_prices_since(None).await.len(), 2);
+        let changed = manager.cached_prices_since(Some(1_000)).await;
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].data.symbol, "ETH");
+        assert!(manager.cached_prices_since(Some(2_000)).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_visible_scopes_seed_and_report_changes() {
+        let seeded = HashMap::from([("main".to_string(), HashSet::from([1, 2]))]);
+        let manager = PollingManager::with_visible_scopes(seeded.clone());
+        assert_eq!(manager.visible_scopes().await, seeded);
+
+        // 與保存的範圍相同：不重新載入
+        assert!(!manager.set_visible("main".to_string(), HashSet::from([1, 2]), None).await);
+        assert!(manager.set_visible("main".to_string(), HashSet::from([3]), None).await);
+        assert!(manager.set_visible("main".to_string(), HashSet::new(), None).await);
+        assert!(!manager.set_visible("main".to_string(), HashSet::new(), None).await);
+        assert!(manager.visible_scopes().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_window_profiles_poll_each_symbol_at_its_fastest_cadence() {
+        let db = Arc::new(DbPool::open(&std::path::PathBuf::from(":memory:")).unwrap());
+        let btc = db.add_subscription("asset", "BTCUSDT", None, "binance", "crypto", None, None, None).unwrap();
+        let eth = db.add_subscription("asset", "ETHUSDT", None, "binance", "crypto", None, None, None).unwrap();
+
+        // 主看板沿用 provider 間隔；小視窗只要每 10 分鐘更新
+        let manager = PollingManager::new();
+        assert!(manager.set_visible("main".to_string(), HashSet::from([btc]), None).await);
+        assert!(manager.set_visible("overlay".to_string(), HashSet::from([btc, eth]), Some(600_000)).await);
+        assert!(!manager.set_visible("overlay".to_string(), HashSet::from([btc, eth]), Some(600_000)).await);
+        let merged = merge_visible(&manager.visible_scopes().await, &manager.window_intervals().await);
+        assert_eq!(merged, HashMap::from([(btc, None), (eth, Some(600_000))]));
+
+        let (groups, _, rates) = load_config(&db, Some(&merged), false).unwrap();
+        let group = &groups["binance"];
+        let (fast, slow) = (group.symbol_intervals["BTCUSDT"], group.symbol_intervals["ETHUSDT"]);
+        assert_eq!(group.interval_ms, fast);
+        assert!(slow > fast);
+        assert_eq!(group.due_symbols(0), ["BTCUSDT", "ETHUSDT"]);
+        assert_eq!(group.due_symbols(1), ["BTCUSDT"]);
+        assert_eq!(rates.by_key["binance:ETHUSDT"], slow);
+
+        *manager.rates.write().await = rates;
+        let reported = manager.window_rates().await;
+        assert_eq!(reported.iter().map(|w| w.window_id.as_str()).collect::<Vec<_>>(), ["main", "overlay"]);
+        assert_eq!(reported[0].requested_interval_ms, None);
+        assert_eq!(reported[1].requested_interval_ms, Some(600_000));
+        assert_eq!(reported[1].intervals, HashMap::from([(btc, fast), (eth, slow)]));
+
+        // 清空視窗時一併移除其間隔
+        assert!(manager.set_visible("overlay".to_string(), HashSet::new(), Some(600_000)).await);
+        assert!(manager.window_intervals().await.is_empty());
+    }
+
+    #[test]
+    fn test_cached_price_staleness_follows_last_successful_tick() {
+        let data = crate::providers::AssetDataBuilder::new("BTC", "binance").price(1.0).build();
+        let tick = |last_success_at| PollTick {
+            provider_id: "binance".to_string(),
+            fetched_at: 10_000,
+            interval_ms: 1_000,
+            last_success_at,
+        };
+
+        let fresh = CachedPrice::new(data.clone(), Some(&tick(Some(9_000))), false, 10_500);
+        assert_eq!(fresh.fetched_at, Some(9_000));
+        assert_eq!(fresh.interval_ms, Some(1_000));
+        assert!(!fresh.stale);
+
+        // 最近幾次 tick 失敗：超過兩個間隔即視為過期
+        assert!(CachedPrice::new(data.clone(), Some(&tick(Some(7_000))), false, 10_500).stale);
+        assert!(CachedPrice::new(data.clone(), Some(&tick(None)), false, 10_500).stale);
+        assert!(CachedPrice::new(data.clone(), None, false, 10_500).stale);
+
+        // 還原的快照在被新資料取代前一律 stale
+        assert!(CachedPrice::new(data.clone(), Some(&tick(Some(9_000))), true, 10_500).stale);
+
+        // AssetData 欄位攤平在同一層
+        let json = serde_json::to_value(&fresh).unwrap();
+        assert_eq!(json["symbol"], "BTC");
+        assert_eq!(json["stale"], false);
+    }
+
+    #[test]
+    fn test_recording_paused_triggers_reload_only_on_change() {
+        let manager = PollingManager::new();
+        let reload_rx = manager.reload_tx.subscribe();
+        assert!(!manager.is_recording_paused());
+
+        manager.set_recording_paused(true);
+        assert!(manager.is_recording_paused());
+        assert_eq!(*reload_rx.borrow(), 1);
+
+        // 狀態未變不重新載入
+        manager.set_recording_paused(true);
+        assert_eq!(*reload_rx.borrow(), 1);
+
+        // clone 共用同一個旗標
+        manager.clone().set_recording_paused(false);
+        assert!(!manager.is_recording_paused());
+        assert_eq!(*reload_rx.borrow(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_visible_basket_polls_its_components_and_updates_cache() {
+        let db = Arc::new(DbPool::open(&std::path::PathBuf::from(":memory:")).unwrap());
+        let btc = db.add_subscription("asset", "BTCUSDT", None, "binance", "crypto", None, None, None).unwrap();
+        let eth = db.add_subscription("asset", "ETHUSDT", None, "binance", "crypto", None, None, None).unwrap();
+        let idx = db.add_subscription("asset", "MYIDX", None, "basket", "crypto", None, None, None).unwrap();
+        db.toggle_record(idx, true).unwrap();
+        let params = format!(
+            r#"{{"components": [{{"subscription_id": {}, "weight": 1, "base_price": 50000}}, {{"subscription_id": {}, "weight": 1, "base_price": 2000}}]}}"#,
+            btc, eth
+        );
+        db.set_provider_params(idx, Some(&params)).unwrap();
+
+        // 只有籃子可見：成分照常輪詢但不紀錄，籃子本身不進 provider group
+        let (groups, baskets, _) = load_config(&db, Some(&HashMap::from([(idx, None)])), true).unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups["binance"].symbols, ["BTCUSDT", "ETHUSDT"]);
+        assert!(groups["binance"].record_targets.is_empty());
+        assert_eq!(baskets.len(), 1);
+        assert_eq!(baskets[0].record.map(|t| t.subscription_id), Some(idx));
+
+        let manager = PollingManager::new();
+        let (bus, mut rx) = broadcast::channel(8);
+        {
+            let mut cache = manager.cache.write().await;
+            for (symbol, price) in [("BTCUSDT", 55000.0), ("ETHUSDT", 2000.0)] {
+                let data = crate::providers::AssetDataBuilder::new(symbol, "binance").price(price).build();
+                cache.insert(format!("binance:{}", symbol), data);
+            }
+        }
+        update_baskets(&baskets, &manager.cache, &manager.restored, &manager.lru, &manager.ticks, &bus, 1_000, 5_000).await;
+
+        assert!((manager.cache.read().await["basket:MYIDX"].price - 105.0).abs() < 1e-9);
+        assert!(manager.ticks.read().await.contains_key("basket"));
+        match rx.recv().await.unwrap() {
+            AppEvent::PriceUpdate { provider_id, record_targets, .. } => {
+                assert_eq!(provider_id, "basket");
+                assert_eq!(record_targets.keys().collect::<Vec<_>>(), ["MYIDX"]);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_published_prices_fill_cache_and_clear_restored_flag() {
+        let manager = PollingManager::new();
+        manager
+            .restore_cache(vec![(
+                "binance:BTCUSDT".to_string(),
+                crate::providers::AssetDataBuilder::new("BTCUSDT", "binance").price(1.0).build(),
+            )])
+            .await;
+        let (bus, mut rx) = broadcast::channel(8);
+        let streamed = vec![crate::providers::AssetDataBuilder::new("BTCUSDT", "binance").price(50_000.0).build()];
+        publish_prices(
+            "binance",
+            streamed,
+            &Arc::new(RecordTargets::new()),
+            &[],
+            &manager.sanity,
+            &manager.cache,
+            &manager.restored,
+            &manager.lru,
+            &manager.ticks,
+            &bus,
+            1_000,
+            5_000,
+        )
+        .await;
+
+        assert_eq!(manager.cache.read().await["binance:BTCUSDT"].price, 50_000.0);
+        assert!(manager.restored.read().await.is_empty());
+        match rx.recv().await.unwrap() {
+            AppEvent::PriceUpdate { provider_id, data, .. } => {
+                assert_eq!((provider_id.as_str(), data.len()), ("binance", 1));
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
 }
@@ -0,0 +1,240 @@
+//! 省電模式 — 偵測筆電是否使用電池供電，開啟時將所有 polling 間隔乘上倍率並暫停 WS 串流。
+//!
+//! 設定存於 app settings（`low_power_mode` / `low_power_factor`）；背景 task 定期探測電源，
+//! 狀態改變時重新載入 polling 並送出 `AppEvent::PowerMode`。
+//!
+//! 另有喚醒偵測：休眠期間 socket 會無聲斷線，因此比對牆上時鐘，發現時間跳躍即視為從休眠恢復，
+//! 立即重新 polling 並送出 `AppEvent::SystemResumed`（desktop 收到後重建 WS 串流）。
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::events::{AppEvent, PowerModePayload};
+use crate::polling::PollingManager;
+
+/// 電源探測間隔
+const PROBE_INTERVAL: Duration = Duration::from_secs(60);
+/// 喚醒偵測的檢查間隔
+const WAKE_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+/// 牆上時鐘比預期多走超過此值才視為曾經休眠（避免排程延遲誤判）
+const WAKE_GAP_THRESHOLD: Duration = Duration::from_secs(30);
+pub const MAX_FACTOR: f64 = 20.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PowerConfig {
+    /// 使用電池時自動進入省電模式
+    pub enabled: bool,
+    /// 省電時 polling 間隔的倍率
+    pub factor: f64,
+}
+
+const DEFAULT_CONFIG: PowerConfig = PowerConfig {
+    enabled: false,
+    factor: 3.0,
+};
+
+impl Default for PowerConfig {
+    fn default() -> Self {
+        DEFAULT_CONFIG
+    }
+}
+
+static POWER_CONFIG: RwLock<PowerConfig> = RwLock::new(DEFAULT_CONFIG);
+static LOW_POWER: AtomicBool = AtomicBool::new(false);
+
+pub fn set_power_config(config: PowerConfig) {
+    *POWER_CONFIG.write().unwrap_or_else(|e| e.into_inner()) = config;
+}
+
+pub fn power_config() -> PowerConfig {
+    *POWER_CONFIG.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// 目前是否處於省電模式
+pub fn is_low_power() -> bool {
+    LOW_POWER.load(Ordering::Relaxed)
+}
+
+/// polling 間隔倍率（非省電時為 1）
+pub fn interval_multiplier() -> f64 {
+    if is_low_power() {
+        power_config().factor.max(1.0)
+    } else {
+        1.0
+    }
+}
+
+/// 探測電源：Some(true) = 使用電池，Some(false) = 接上電源，None = 無法判斷（例如桌機）
+pub fn on_battery() -> Option<bool> {
+    #[cfg(target_os = "linux")]
+    {
+        probe_power_supply_dir(std::path::Path::new("/sys/class/power_supply"))
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let output = std::process::Command::new("pmset")
+            .args(["-g", "batt"])
+            .output()
+            .ok()?;
+        parse_pmset(&String::from_utf8_lossy(&output.stdout))
+    }
+    #[cfg(target_os = "windows")]
+    {
+        use windows_sys::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+        let mut status: SYSTEM_POWER_STATUS = unsafe { std::mem::zeroed() };
+        // SAFETY: status 為有效的可寫入結構
+        if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+            return None;
+        }
+        match status.ACLineStatus {
+            0 => Some(true),
+            1 => Some(false),
+            _ => None,
+        }
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+    {
+        None
+    }
+}
+
+/// Linux：任一 `Mains` 供電中即視為接上電源，否則看電池是否 `Discharging`
+#[cfg(any(target_os = "linux", test))]
+fn probe_power_supply_dir(dir: &std::path::Path) -> Option<bool> {
+    let read = |path: std::path::PathBuf| {
+        std::fs::read_to_string(path)
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default()
+    };
+    let mut battery: Option<bool> = None;
+    for entry in std::fs::read_dir(dir).ok()?.flatten() {
+        let path = entry.path();
+        match read(path.join("type")).as_str() {
+            "Mains" if read(path.join("online")) == "1" => return Some(false),
+            "Battery" => {
+                let discharging = read(path.join("status")) == "Discharging";
+                battery = Some(battery.unwrap_or(false) || discharging);
+            }
+            _ => {}
+        }
+    }
+    battery
+}
+
+#[cfg(any(target_os = "macos", test))]
+fn parse_pmset(output: &str) -> Option<bool> {
+    if output.contains("'Battery Power'") {
+        Some(true)
+    } else if output.contains("'AC Power'") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// 探測電源並套用省電狀態；狀態改變時重新載入 polling 並送出事件
+pub async fn evaluate(polling: &PollingManager, event_bus: &broadcast::Sender<AppEvent>) {
+    let config = power_config();
+    let on_battery = if config.enabled {
+        tokio::task::spawn_blocking(on_battery).await.ok().flatten()
+    } else {
+        None
+    };
+    let low_power = config.enabled && on_battery == Some(true);
+    if LOW_POWER.swap(low_power, Ordering::Relaxed) == low_power {
+        return;
+    }
+
+    tracing::info!(
+        "[Power] {} low-power mode",
+        if low_power { "Entering" } else { "Leaving" }
+    );
+    polling.reload();
+    let _ = event_bus.send(AppEvent::PowerMode(PowerModePayload {
+        on_battery: on_battery.unwrap_or(false),
+        low_power,
+        interval_multiplier: interval_multiplier(),
+    }));
+}
+
+/// 啟動背景電源探測 task
+pub fn start_monitor(polling: PollingManager, event_bus: broadcast::Sender<AppEvent>) {
+    tokio::spawn(async move {
+        loop {
+            evaluate(&polling, &event_bus).await;
+            tokio::time::sleep(PROBE_INTERVAL).await;
+        }
+    });
+}
+
+/// 實際經過的牆上時間比預期多出門檻以上時，回傳推估的休眠秒數
+fn detect_resume(expected: Duration, wall_elapsed_ms: i64) -> Option<i64> {
+    let gap_ms = wall_elapsed_ms - expected.as_millis() as i64;
+    (gap_ms > WAKE_GAP_THRESHOLD.as_millis() as i64).then_some(gap_ms / 1000)
+}
+
+/// 啟動喚醒偵測 task：從休眠恢復時立即重新 polling 並送出 `AppEvent::SystemResumed`
+pub fn start_wake_detector(polling: PollingManager, event_bus: broadcast::Sender<AppEvent>) {
+    tokio::spawn(async move {
+        let mut last = chrono::Utc::now().timestamp_millis();
+        loop {
+            tokio::time::sleep(WAKE_CHECK_INTERVAL).await;
+            let now = chrono::Utc::now().timestamp_millis();
+            if let Some(slept_secs) = detect_resume(WAKE_CHECK_INTERVAL, now - last) {
+                tracing::info!("[Power] Resumed from sleep (~{}s), refreshing connections", slept_secs);
+                polling.reload();
+                let _ = event_bus.send(AppEvent::SystemResumed { slept_secs });
+            }
+            last = now;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn supply(dir: &std::path::Path, name: &str, files: &[(&str, &str)]) {
+        let path = dir.join(name);
+        std::fs::create_dir_all(&path).unwrap();
+        for (file, content) in files {
+            std::fs::write(path.join(file), format!("{}\n", content)).unwrap();
+        }
+    }
+
+    #[test]
+    fn linux_power_supply_probe() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(probe_power_supply_dir(dir.path()), None);
+
+        supply(dir.path(), "BAT0", &[("type", "Battery"), ("status", "Discharging")]);
+        supply(dir.path(), "AC", &[("type", "Mains"), ("online", "0")]);
+        assert_eq!(probe_power_supply_dir(dir.path()), Some(true));
+
+        supply(dir.path(), "AC", &[("type", "Mains"), ("online", "1")]);
+        assert_eq!(probe_power_supply_dir(dir.path()), Some(false));
+    }
+
+    #[test]
+    fn wake_detection_ignores_small_delays() {
+        assert_eq!(detect_resume(Duration::from_secs(10), 10_000), None);
+        assert_eq!(detect_resume(Duration::from_secs(10), 35_000), None);
+        assert_eq!(detect_resume(Duration::from_secs(10), 3_610_000), Some(3600));
+        // 時鐘往回調整不算喚醒
+        assert_eq!(detect_resume(Duration::from_secs(10), -60_000), None);
+    }
+
+    #[test]
+    fn pmset_output_parsing() {
+        assert_eq!(
+            parse_pmset("Now drawing from 'Battery Power'\n -InternalBattery-0 85%; discharging"),
+            Some(true)
+        );
+        assert_eq!(parse_pmset("Now drawing from 'AC Power'"), Some(false));
+        assert_eq!(parse_pmset(""), None);
+    }
+}
@@ -0,0 +1,328 @@
+//! 新增訂閱時的 provider 建議 — 使用者只輸入 symbol 時，判斷其類型並排序可服務的 provider。
+//!
+//! 分類依序為：Polymarket condition id（`0x` + 64 hex）、EVM pool 位址（`0x` + 40 hex）、
+//! Solana mint（base58 32–44 字元）、crypto 交易對（`BTC/USDT`、`ETH-USD`、`BTCUSDT`）、
+//! 美股代號（1–5 字母，可帶 `.B` 類股後綴）。1–5 字母的純代號可能是幣也可能是股票，
+//! 兩邊的 provider 都列出，依 CoinGecko 市值排名清單決定哪一邊優先。
+//!
+//! 缺少必要 API key / secret、處於失敗退避或今日額度用盡的 provider 仍會列出，但標記為不可用並附上原因。
+//! 可用者依：已有訂閱（可共用同一批次請求）、WebSocket、批次查詢、預設刷新間隔排序。
+
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+
+use serde::Serialize;
+
+use crate::core_state::CoreState;
+use crate::providers::{get_all_provider_info, normalize_symbol, parse_crypto_symbol, ProviderInfo};
+
+/// 可辨識為交易對 quote 的幣別
+const QUOTES: &[&str] = &[
+    "USDT", "USDC", "BUSD", "FDUSD", "DAI", "USD", "EUR", "GBP", "JPY", "TRY", "BTC", "ETH", "BNB",
+];
+/// 可查詢 EVM pool / token 位址的 provider
+const EVM_ADDRESS_PROVIDERS: &[&str] = &["subgraph", "okx_dex", "bitquery"];
+/// 可查詢 Solana mint / pool 位址的 provider
+const SOLANA_ADDRESS_PROVIDERS: &[&str] = &["jupiter", "raydium", "okx_dex", "bitquery"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SymbolKind {
+    CryptoPair,
+    UsEquity,
+    /// 1–5 字母的純代號，可能是幣也可能是股票
+    Ticker,
+    MintAddress,
+    PoolAddress,
+    ConditionId,
+    Unknown,
+}
+
+/// provider 目前的設定與狀態
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProviderStatus {
+    pub has_key: bool,
+    pub has_secret: bool,
+    /// 連續失敗後的退避期間
+    pub backing_off: bool,
+    /// 今日額度已用盡（Alpha Vantage）
+    pub quota_exhausted: bool,
+    /// 使用此 provider 的既有訂閱數
+    pub subscriptions: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProviderSuggestion {
+    pub provider_id: String,
+    pub provider_name: String,
+    /// 建立訂閱時使用的 asset type（crypto / stock / dex / prediction）
+    pub asset_type: String,
+    /// 依 asset type 統一化後的 symbol
+    pub symbol: String,
+    pub available: bool,
+    /// 不可用的原因
+    pub reason: Option<String>,
+    pub has_api_key: bool,
+    /// 預設刷新間隔（ms，依是否有 API key）
+    pub interval_ms: i64,
+    pub score: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProviderResolution {
+    pub symbol: String,
+    pub kind: SymbolKind,
+    pub suggestions: Vec<ProviderSuggestion>,
+}
+
+fn is_evm_address(value: &str) -> bool {
+    value.len() == 42 && value.starts_with("0x") && value[2..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// 1–5 字母，可帶 1–2 字母的類股後綴（`BRK.B`）
+fn is_equity_ticker(value: &str) -> bool {
+    let (root, class) = match value.split_once('.') {
+        Some((root, class)) => (root, Some(class)),
+        None => (value, None),
+    };
+    (1..=5).contains(&root.len())
+        && root.chars().all(|c| c.is_ascii_alphabetic())
+        && class.is_none_or(|c| (1..=2).contains(&c.len()) && c.chars().all(|c| c.is_ascii_alphabetic()))
+}
+
+/// 判斷 symbol 類型
+pub fn classify(symbol: &str) -> SymbolKind {
+    let symbol = symbol.trim();
+    let market = symbol.split_once(':').map_or(symbol, |(market, _)| market);
+    if crate::providers::polymarket::is_condition_id(market) {
+        return SymbolKind::ConditionId;
+    }
+    if is_evm_address(symbol) {
+        return SymbolKind::PoolAddress;
+    }
+    if crate::icons::is_solana_mint(symbol) {
+        return SymbolKind::MintAddress;
+    }
+    let upper = symbol.to_uppercase();
+    if upper.contains(['/', '-']) {
+        let (base, quote) = parse_crypto_symbol(&upper);
+        let alnum = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric());
+        return if alnum(&base) && QUOTES.contains(&quote.as_str()) {
+            SymbolKind::CryptoPair
+        } else {
+            SymbolKind::Unknown
+        };
+    }
+    if is_equity_ticker(&upper) {
+        return if upper.contains('.') { SymbolKind::UsEquity } else { SymbolKind::Ticker };
+    }
+    // 較長或含數字的代號（BTCUSDT、1INCH、RENDER）視為 crypto
+    if (2..=12).contains(&upper.len()) && upper.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return SymbolKind::CryptoPair;
+    }
+    SymbolKind::Unknown
+}
+
+/// provider 可服務此類型 symbol 時，回傳對應的 asset type
+fn asset_type_for(kind: SymbolKind, info: &ProviderInfo, prefer_crypto: bool) -> Option<&'static str> {
+    let classes = &info.capabilities.asset_classes;
+    let serves = |class: &str| classes.iter().any(|c| c == class) && info.provider_type != "dex";
+    match kind {
+        SymbolKind::CryptoPair => serves("crypto").then_some("crypto"),
+        SymbolKind::UsEquity => serves("stock").then_some("stock"),
+        SymbolKind::Ticker => match (serves("crypto"), serves("stock")) {
+            (true, true) => Some(if prefer_crypto { "crypto" } else { "stock" }),
+            (true, false) => Some("crypto"),
+            (false, true) => Some("stock"),
+            (false, false) => None,
+        },
+        SymbolKind::PoolAddress => EVM_ADDRESS_PROVIDERS
+            .contains(&info.id.as_str())
+            .then_some(if info.provider_type == "dex" { "dex" } else { "prediction" }),
+        SymbolKind::MintAddress => SOLANA_ADDRESS_PROVIDERS
+            .contains(&info.id.as_str())
+            .then_some(if info.provider_type == "dex" { "dex" } else { "prediction" }),
+        SymbolKind::ConditionId => info.symbol_format.contains("condition_id").then_some("prediction"),
+        SymbolKind::Unknown => None,
+    }
+}
+
+/// 依類型篩選並排序 provider；`prefer_crypto` 決定純代號優先視為幣或股票
+pub fn rank(
+    symbol: &str,
+    kind: SymbolKind,
+    providers: &[ProviderInfo],
+    status: &HashMap<String, ProviderStatus>,
+    prefer_crypto: bool,
+) -> Vec<ProviderSuggestion> {
+    let symbol = symbol.trim();
+    let default_status = ProviderStatus::default();
+    let mut suggestions: Vec<ProviderSuggestion> = providers
+        .iter()
+        .filter(|info| !matches!(info.id.as_str(), "mock" | "replay"))
+        .filter_map(|info| {
+            let asset_type = asset_type_for(kind, info, prefer_crypto)?;
+            let st = status.get(&info.id).unwrap_or(&default_status);
+            let reason = if info.requires_api_key && !st.has_key {
+                Some("API key required")
+            } else if info.requires_api_secret && !st.has_secret {
+                Some("API secret required")
+            } else if st.quota_exhausted {
+                Some("Daily quota exhausted")
+            } else if st.backing_off {
+                Some("Backing off after repeated failures")
+            } else {
+                None
+            };
+            let interval_ms = if st.has_key { info.key_interval } else { info.free_interval };
+
+            let mut score = 30 - (interval_ms / 2_000).min(30) as i32;
+            if st.subscriptions > 0 {
+                score += 40;
+            }
+            if info.supports_websocket {
+                score += 20;
+            }
+            if info.capabilities.supports_batch {
+                score += 10;
+            }
+            if info.optional_api_key && st.has_key {
+                score += 5;
+            }
+            if kind == SymbolKind::Ticker && (asset_type == "crypto") == prefer_crypto {
+                score += 50;
+            }
+            Some(ProviderSuggestion {
+                provider_id: info.id.clone(),
+                provider_name: info.name.clone(),
+                asset_type: asset_type.to_string(),
+                symbol: normalize_symbol(symbol, asset_type),
+                available: reason.is_none(),
+                reason: reason.map(str::to_string),
+                has_api_key: st.has_key,
+                interval_ms,
+                score,
+            })
+        })
+        .collect();
+    suggestions.sort_by(|a, b| {
+        b.available
+            .cmp(&a.available)
+            .then(b.score.cmp(&a.score))
+            .then_with(|| a.provider_name.cmp(&b.provider_name))
+    });
+    suggestions
+}
+
+/// 純代號是否為已知的幣（主流幣對照或 CoinGecko 有市值排名者）
+fn is_known_coin(state: &CoreState, ticker: &str) -> bool {
+    if crate::providers::coingecko::static_id(ticker).is_some() {
+        return true;
+    }
+    state.db.load_coingecko_coins().is_ok_and(|(coins, _)| {
+        coins
+            .iter()
+            .any(|c| c.market_cap_rank.is_some() && c.symbol.eq_ignore_ascii_case(ticker))
+    })
+}
+
+/// 分類 symbol 並依目前設定、退避與額度狀態排序建議的 provider
+pub async fn resolve(state: &CoreState, symbol: &str) -> Result<ProviderResolution, String> {
+    let symbol = symbol.trim();
+    if symbol.is_empty() {
+        return Err("Symbol is empty".to_string());
+    }
+    let kind = classify(symbol);
+    let prefer_crypto = kind == SymbolKind::Ticker && is_known_coin(state, &symbol.to_uppercase());
+
+    let providers = get_all_provider_info();
+    let mut subscriptions: HashMap<String, usize> = HashMap::new();
+    for sub in state.db.list_all_subscriptions()? {
+        *subscriptions.entry(sub.selected_provider_id).or_default() += 1;
+    }
+    let now = Instant::now();
+    let backing_off: HashSet<String> = state
+        .polling
+        .backoff
+        .read()
+        .await
+        .iter()
+        .filter(|(_, b)| b.next_allowed_at > now)
+        .map(|(id, _)| id.clone())
+        .collect();
+    let alphavantage_exhausted = crate::providers::alphavantage::quota_remaining().await == 0;
+
+    let status: HashMap<String, ProviderStatus> = providers
+        .iter()
+        .map(|info| {
+            let settings = state.db.get_provider_settings(&info.id).ok().flatten();
+            let filled = |v: Option<&String>| v.is_some_and(|v| !v.is_empty());
+            let status = ProviderStatus {
+                has_key: filled(settings.as_ref().and_then(|s| s.api_key.as_ref())),
+                has_secret: filled(settings.as_ref().and_then(|s| s.api_secret.as_ref())),
+                backing_off: backing_off.contains(&info.id),
+                quota_exhausted: info.id == "alphavantage" && alphavantage_exhausted,
+                subscriptions: subscriptions.get(&info.id).copied().unwrap_or(0),
+            };
+            (info.id.clone(), status)
+        })
+        .collect();
+
+    Ok(ProviderResolution {
+        symbol: symbol.to_string(),
+        kind,
+        suggestions: rank(symbol, kind, &providers, &status, prefer_crypto),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_symbols() {
+        assert_eq!(classify("BTC/USDT"), SymbolKind::CryptoPair);
+        assert_eq!(classify("eth-usd"), SymbolKind::CryptoPair);
+        assert_eq!(classify("BTCUSDT"), SymbolKind::CryptoPair);
+        assert_eq!(classify("1INCH"), SymbolKind::CryptoPair);
+        assert_eq!(classify("AAPL"), SymbolKind::Ticker);
+        assert_eq!(classify("brk.b"), SymbolKind::UsEquity);
+        assert_eq!(classify("So11111111111111111111111111111111111111112"), SymbolKind::MintAddress);
+        assert_eq!(classify("0x88e6a0c2ddd26feeb64f039a2c41296fcb3f5640"), SymbolKind::PoolAddress);
+        let condition = format!("0x{}", "ab".repeat(32));
+        assert_eq!(classify(&condition), SymbolKind::ConditionId);
+        assert_eq!(classify(&format!("{}:Yes", condition)), SymbolKind::ConditionId);
+        assert_eq!(classify("BRK-B"), SymbolKind::Unknown);
+        assert_eq!(classify("hello world"), SymbolKind::Unknown);
+    }
+
+    #[test]
+    fn ranks_available_providers_first() {
+        let providers = get_all_provider_info();
+        let mut status = HashMap::new();
+        status.insert("coinbase".to_string(), ProviderStatus { subscriptions: 3, ..Default::default() });
+        status.insert("kraken".to_string(), ProviderStatus { backing_off: true, ..Default::default() });
+
+        let ranked = rank("ETH/USDT", SymbolKind::CryptoPair, &providers, &status, false);
+        assert!(ranked.iter().all(|s| s.asset_type == "crypto" && s.symbol == "ETH"));
+        assert!(ranked.iter().all(|s| !["yahoo", "jupiter", "polymarket"].contains(&s.provider_id.as_str())));
+        assert_eq!(ranked[0].provider_id, "coinbase");
+        let first_unavailable = ranked.iter().position(|s| !s.available).unwrap();
+        assert!(ranked[first_unavailable..].iter().all(|s| !s.available));
+        let kraken = ranked.iter().find(|s| s.provider_id == "kraken").unwrap();
+        assert_eq!(kraken.reason.as_deref(), Some("Backing off after repeated failures"));
+        let cmc = ranked.iter().find(|s| s.provider_id == "coinmarketcap").unwrap();
+        assert_eq!(cmc.reason.as_deref(), Some("API key required"));
+
+        // 純代號：股票優先時 yahoo 排在 crypto 交易所前面
+        let ranked = rank("aapl", SymbolKind::Ticker, &providers, &HashMap::new(), false);
+        let pos = |id: &str| ranked.iter().position(|s| s.provider_id == id).unwrap();
+        assert!(pos("yahoo") < pos("binance"));
+        assert_eq!(ranked[pos("yahoo")].symbol, "AAPL");
+
+        let condition = format!("0x{}", "ab".repeat(32));
+        let ranked = rank(&condition, SymbolKind::ConditionId, &providers, &HashMap::new(), false);
+        assert_eq!(ranked.len(), 1);
+        assert_eq!((ranked[0].provider_id.as_str(), ranked[0].asset_type.as_str()), ("polymarket", "prediction"));
+    }
+}
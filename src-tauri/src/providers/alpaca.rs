@@ -1,17 +1,27 @@
+use super::debug::SendCaptured;
 use super::traits::*;
 use super::types::*;
 use std::collections::HashMap;
 
+const DEFAULT_BASE_URL: &str = "https://data.alpaca.markets";
+
 pub struct AlpacaProvider {
     client: reqwest::Client,
+    base_url: String,
     api_key: Option<String>,
     api_secret: Option<String>,
 }
 
 impl AlpacaProvider {
-    pub fn new(api_key: Option<String>, api_secret: Option<String>) -> Self {
+    pub fn new(
+        api_key: Option<String>,
+        api_secret: Option<String>,
+        api_url: Option<String>,
+        http: &HttpOptions,
+    ) -> Self {
         Self {
-            client: shared_client(),
+            client: provider_client(http),
+            base_url: resolve_base_url(api_url, DEFAULT_BASE_URL),
             api_key,
             api_secret,
         }
@@ -30,65 +40,67 @@ impl AlpacaProvider {
         format!("{}/{}", base, q)
     }
 
-    fn parse_bar(symbol: &str, bar: &serde_json::Value) -> AssetData {
-        let price = bar["c"].as_f64().unwrap_or(0.0);
-        let open = bar["o"].as_f64().unwrap_or(price);
-        let change = price - open;
-        let pct = if open > 0.0 {
-            (change / open) * 100.0
-        } else {
-            0.0
-        };
-
-        AssetDataBuilder::new(symbol, "alpaca")
-            .price(price)
-            .change_24h(Some(change))
-            .change_percent_24h(Some(pct))
-            .high_24h(bar["h"].as_f64())
-            .low_24h(bar["l"].as_f64())
-            .volume(bar["v"].as_f64())
-            .extra_f64("open_price", bar["o"].as_f64())
-            .extra_f64("weighted_avg_price", bar["vw"].as_f64())
-            .extra_i64("trade_count", bar["n"].as_i64())
-            .build()
-    }
-}
-
-#[async_trait::async_trait]
-impl DataProvider for AlpacaProvider {
-    fn info(&self) -> ProviderInfo {
-        provider_info_or_panic("alpaca")
+    /// 解析 snapshot — 價格取最新成交（無成交時退回當日 bar 收盤），
+    /// 漲跌以前一交易日收盤計算，bid/ask 取最新報價
+    fn parse_snapshot(symbol: &str, snap: &serde_json::Value) -> Option<AssetData> {
+        let trade = &snap["latestTrade"];
+        let quote = &snap["latestQuote"];
+        let daily = &snap["dailyBar"];
+        let price = trade["p"].as_f64().or_else(|| daily["c"].as_f64())?;
+        let prev_close = snap["prevDailyBar"]["c"].as_f64().filter(|c| *c > 0.0);
+        let change = prev_close.map(|c| price - c);
+        let pct = prev_close.map(|c| (price - c) / c * 100.0);
+
+        Some(
+            AssetDataBuilder::new(symbol, "alpaca")
+                .price(price)
+                .change_24h(change)
+                .change_percent_24h(pct)
+                .high_24h(daily["h"].as_f64())
+                .low_24h(daily["l"].as_f64())
+                .volume(daily["v"].as_f64())
+                .extra_f64("bid", quote["bp"].as_f64())
+                .extra_f64("ask", quote["ap"].as_f64())
+                .extra_f64("bid_size", quote["bs"].as_f64())
+                .extra_f64("ask_size", quote["as"].as_f64())
+                .extra_f64("prev_close", prev_close)
+                .extra_f64("open_price", daily["o"].as_f64())
+                .extra_f64("weighted_avg_price", daily["vw"].as_f64())
+                .extra_i64("trade_count", daily["n"].as_i64())
+                .extra_str("last_trade_time", trade["t"].as_str())
+                .build(),
+        )
     }
 
-    async fn fetch_price(&self, symbol: &str) -> Result<AssetData, String> {
+    /// 批量取 snapshot，回傳以大寫 symbol 為 key 的 map
+    async fn fetch_snapshots(
+        &self,
+        crypto: bool,
+        symbols: &[&str],
+    ) -> Result<HashMap<String, serde_json::Value>, String> {
         let api_key = self.api_key.as_ref().ok_or("Alpaca requires API key")?;
         let api_secret = self.api_secret.as_ref().ok_or("Alpaca requires API secret")?;
 
-        let is_crypto = Self::is_crypto(symbol);
-        let api_symbol = if is_crypto {
-            Self::to_alpaca_crypto(symbol)
-        } else {
-            symbol.to_string()
-        };
-
-        let url = if is_crypto {
+        let url = if crypto {
             format!(
-                "https://data.alpaca.markets/v1beta3/crypto/us/latest/bars?symbols={}",
-                api_symbol
+                "{}/v1beta3/crypto/us/snapshots?symbols={}",
+                self.base_url,
+                symbols.join(",")
             )
         } else {
             format!(
-                "https://data.alpaca.markets/v2/stocks/{}/bars/latest",
-                api_symbol
+                "{}/v2/stocks/snapshots?symbols={}",
+                self.base_url,
+                symbols.join(",")
             )
         };
 
-        let data: serde_json::Value = self
+        let mut data: serde_json::Value = self
             .client
             .get(&url)
             .header("APCA-API-KEY-ID", api_key)
             .header("APCA-API-SECRET-KEY", api_secret)
-            .send()
+            .send_captured("alpaca")
             .await
             .map_err(|e| format!("Alpaca connection failed: {}", e))?
             .error_for_status()
@@ -97,18 +109,46 @@ impl DataProvider for AlpacaProvider {
             .await
             .map_err(|e| format!("Alpaca parse failed: {}", e))?;
 
-        let bar = if is_crypto {
-            &data["bars"][&api_symbol]
+        // crypto 回傳 {"snapshots": {...}}，stock 直接以 symbol 為 key
+        let snapshots = if crypto {
+            data["snapshots"].take()
         } else {
-            &data["bar"]
+            data
         };
-        if bar.is_null() {
-            return Err(format!(
-                "Alpaca not found: {}. Use AAPL for stocks, BTC/USD for crypto",
-                symbol
-            ));
-        }
-        Ok(Self::parse_bar(symbol, bar))
+        Ok(match snapshots {
+            serde_json::Value::Object(obj) => obj
+                .into_iter()
+                .map(|(k, v)| (k.to_uppercase(), v))
+                .collect(),
+            _ => HashMap::new(),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl DataProvider for AlpacaProvider {
+    fn info(&self) -> ProviderInfo {
+        provider_info_or_panic("alpaca")
+    }
+
+    async fn fetch_price(&self, symbol: &str) -> Result<AssetData, String> {
+        let is_crypto = Self::is_crypto(symbol);
+        let api_symbol = if is_crypto {
+            Self::to_alpaca_crypto(symbol)
+        } else {
+            symbol.to_uppercase()
+        };
+
+        let snapshots = self.fetch_snapshots(is_crypto, &[&api_symbol]).await?;
+        snapshots
+            .get(&api_symbol)
+            .and_then(|snap| Self::parse_snapshot(symbol, snap))
+            .ok_or_else(|| {
+                format!(
+                    "Alpaca not found: {}. Use AAPL for stocks, BTC/USD for crypto",
+                    symbol
+                )
+            })
     }
 
     /// 批量查詢 — symbols=AAPL,MSFT 或 symbols=BTC/USD,ETH/USD
@@ -120,76 +160,37 @@ impl DataProvider for AlpacaProvider {
             return self.fetch_price(&symbols[0]).await.map(|d| vec![d]);
         }
 
-        let api_key = self.api_key.as_ref().ok_or("Alpaca requires API key")?;
-        let api_secret = self.api_secret.as_ref().ok_or("Alpaca requires API secret")?;
-
         // 分成 crypto 和 stock
         let mut crypto_map: Vec<(String, String)> = Vec::new(); // (original, alpaca_sym)
-        let mut stock_syms: Vec<String> = Vec::new();
+        let mut stock_map: Vec<(String, String)> = Vec::new();
 
         for s in symbols {
             if Self::is_crypto(s) {
                 crypto_map.push((s.clone(), Self::to_alpaca_crypto(s)));
             } else {
-                stock_syms.push(s.clone());
+                stock_map.push((s.clone(), s.to_uppercase()));
             }
         }
 
         let mut results = Vec::new();
-
-        // 批量查 crypto
-        if !crypto_map.is_empty() {
-            let alpaca_syms: Vec<&str> = crypto_map.iter().map(|(_, a)| a.as_str()).collect();
-            let url = format!(
-                "https://data.alpaca.markets/v1beta3/crypto/us/latest/bars?symbols={}",
-                alpaca_syms.join(",")
-            );
-            if let Ok(resp) = self
-                .client
-                .get(&url)
-                .header("APCA-API-KEY-ID", api_key)
-                .header("APCA-API-SECRET-KEY", api_secret)
-                .send()
-                .await
-            {
-                if let Ok(data) = resp.json::<serde_json::Value>().await {
-                    let bars = &data["bars"];
-                    for (original, alpaca_sym) in &crypto_map {
-                        let bar = &bars[alpaca_sym];
-                        if !bar.is_null() {
-                            results.push(Self::parse_bar(original, bar));
-                        }
-                    }
-                }
+        for (crypto, map) in [(true, &crypto_map), (false, &stock_map)] {
+            if map.is_empty() {
+                continue;
             }
-        }
-
-        // 批量查 stock
-        if !stock_syms.is_empty() {
-            let syms = stock_syms.join(",");
-            let url = format!(
-                "https://data.alpaca.markets/v2/stocks/bars/latest?symbols={}",
-                syms
-            );
-            if let Ok(resp) = self
-                .client
-                .get(&url)
-                .header("APCA-API-KEY-ID", api_key)
-                .header("APCA-API-SECRET-KEY", api_secret)
-                .send()
-                .await
-            {
-                if let Ok(data) = resp.json::<serde_json::Value>().await {
-                    let bars = &data["bars"];
-                    if let Some(obj) = bars.as_object() {
-                        let bar_map: HashMap<String, &serde_json::Value> =
-                            obj.iter().map(|(k, v)| (k.to_uppercase(), v)).collect();
-                        for sym in &stock_syms {
-                            if let Some(bar) = bar_map.get(&sym.to_uppercase()) {
-                                results.push(Self::parse_bar(sym, bar));
-                            }
-                        }
-                    }
+            let alpaca_syms: Vec<&str> = map.iter().map(|(_, a)| a.as_str()).collect();
+            let snapshots = match self.fetch_snapshots(crypto, &alpaca_syms).await {
+                Ok(s) => s,
+                Err(e) => {
+                    tracing::warn!("Alpaca snapshots skipped: {}", e);
+                    continue;
+                }
+            };
+            for (original, alpaca_sym) in map {
+                if let Some(asset) = snapshots
+                    .get(alpaca_sym)
+                    .and_then(|snap| Self::parse_snapshot(original, snap))
+                {
+                    results.push(asset);
                 }
             }
         }
@@ -197,3 +198,31 @@ impl DataProvider for AlpacaProvider {
         Ok(results)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn snapshot_uses_latest_trade_and_previous_close() {
+        let snap = json!({
+            "latestTrade": {"p": 105.0, "t": "2026-01-02T15:30:00Z"},
+            "latestQuote": {"bp": 104.9, "ap": 105.1, "bs": 3, "as": 2},
+            "dailyBar": {"o": 101.0, "h": 106.0, "l": 100.0, "c": 104.0, "v": 1000.0},
+            "prevDailyBar": {"c": 100.0}
+        });
+        let d = AlpacaProvider::parse_snapshot("AAPL", &snap).unwrap();
+        assert_eq!(d.price, 105.0);
+        assert_eq!(d.change_24h, Some(5.0));
+        assert_eq!(d.change_percent_24h, Some(5.0));
+        let extra = d.extra.unwrap();
+        assert_eq!(extra["bid"], json!(104.9));
+        assert_eq!(extra["ask"], json!(105.1));
+
+        // 無成交也無前日收盤：退回當日收盤，不計漲跌
+        let d = AlpacaProvider::parse_snapshot("X", &json!({"dailyBar": {"c": 7.0}})).unwrap();
+        assert_eq!((d.price, d.change_24h), (7.0, None));
+        assert!(AlpacaProvider::parse_snapshot("X", &json!({})).is_none());
+    }
+}
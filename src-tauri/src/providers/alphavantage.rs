@@ -1,52 +1,166 @@
+use super::debug::SendCaptured;
 use super::traits::*;
 use super::types::*;
+use crate::db::DbPool;
+use chrono::{DateTime, Timelike, Utc};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::LazyLock;
+use tokio::sync::RwLock;
+
+const DEFAULT_BASE_URL: &str = "https://www.alphavantage.co";
+const PROVIDER_ID: &str = "alphavantage";
+/// 免費方案每天 25 次呼叫（UTC 日界重置）
+const DAILY_CALLS: u32 = 25;
+/// 今日已用額度的 app setting key，格式 `YYYY-MM-DD:used`
+const QUOTA_KEY: &str = "alphavantage_quota";
+pub const DEFAULT_REFRESH_HOURS: u32 = 24;
+
+/// 同一 symbol 兩次實際查詢的最短間隔（由 app setting `alphavantage_refresh_hours` 控制）
+static REFRESH_HOURS: AtomicU32 = AtomicU32::new(DEFAULT_REFRESH_HOURS);
+
+pub fn set_refresh_hours(hours: u32) {
+    REFRESH_HOURS.store(hours.max(1), Ordering::Relaxed);
+}
+
+pub fn refresh_hours() -> u32 {
+    REFRESH_HOURS.load(Ordering::Relaxed)
+}
+
+/// 今日剩餘呼叫次數
+pub async fn quota_remaining() -> u32 {
+    STATE.read().await.quota.remaining_at(Utc::now())
+}
+
+/// symbol → (報價, 查詢時間) 的記憶體快取與今日額度，來源為 DB 的 `provider_quote_cache` 表
+static STATE: LazyLock<RwLock<AlphaVantageState>> =
+    LazyLock::new(|| RwLock::new(AlphaVantageState::default()));
+
+#[derive(Default)]
+struct AlphaVantageState {
+    quotes: HashMap<String, (AssetData, i64)>,
+    /// 是否已從 DB 載入
+    loaded: bool,
+    /// 查詢後尚未寫入 DB 的報價（下次 `prepare` 寫入）
+    pending: Vec<(AssetData, i64)>,
+    quota: DailyQuota,
+    quota_dirty: bool,
+}
+
+/// 每日額度，並把剩餘額度平均分配到今天剩下的每個小時，避免一開始就耗盡
+#[derive(Debug, Default)]
+struct DailyQuota {
+    day: String,
+    used: u32,
+    hour: Option<u32>,
+    hour_budget: u32,
+    hour_used: u32,
+}
+
+impl DailyQuota {
+    fn day_of(now: DateTime<Utc>) -> String {
+        now.format("%Y-%m-%d").to_string()
+    }
+
+    fn roll(&mut self, now: DateTime<Utc>) {
+        let day = Self::day_of(now);
+        if day != self.day {
+            self.day = day;
+            self.used = 0;
+            self.hour = None;
+        }
+        if self.hour != Some(now.hour()) {
+            self.hour = Some(now.hour());
+            self.hour_budget = DAILY_CALLS.saturating_sub(self.used).div_ceil(24 - now.hour());
+            self.hour_used = 0;
+        }
+    }
+
+    fn remaining_at(&self, now: DateTime<Utc>) -> u32 {
+        if self.day == Self::day_of(now) {
+            DAILY_CALLS.saturating_sub(self.used)
+        } else {
+            DAILY_CALLS
+        }
+    }
+
+    /// 本小時仍有配額時記錄一次呼叫並回傳 true
+    fn try_acquire(&mut self, now: DateTime<Utc>) -> bool {
+        self.roll(now);
+        if self.used >= DAILY_CALLS || self.hour_used >= self.hour_budget {
+            return false;
+        }
+        self.used += 1;
+        self.hour_used += 1;
+        true
+    }
+
+    /// API 回報額度用盡：今天不再呼叫
+    fn exhaust(&mut self, now: DateTime<Utc>) {
+        self.roll(now);
+        self.used = DAILY_CALLS;
+    }
+
+    fn to_setting(&self) -> String {
+        format!("{}:{}", self.day, self.used)
+    }
+
+    fn from_setting(value: &str) -> Self {
+        let (day, used) = value.split_once(':').unwrap_or_default();
+        Self {
+            day: day.to_string(),
+            used: used.parse().unwrap_or(0),
+            ..Self::default()
+        }
+    }
+}
+
+enum QuoteError {
+    RateLimited,
+    Other(String),
+}
 
 pub struct AlphaVantageProvider {
     client: reqwest::Client,
+    base_url: String,
     api_key: Option<String>,
 }
 
 impl AlphaVantageProvider {
-    pub fn new(api_key: Option<String>) -> Self {
+    pub fn new(api_key: Option<String>, api_url: Option<String>, http: &HttpOptions) -> Self {
         Self {
-            client: shared_client(),
+            client: provider_client(http),
+            base_url: resolve_base_url(api_url, DEFAULT_BASE_URL),
             api_key,
         }
     }
-}
-
-#[async_trait::async_trait]
-impl DataProvider for AlphaVantageProvider {
-    fn info(&self) -> ProviderInfo {
-        provider_info_or_panic("alphavantage")
-    }
-
-    async fn fetch_price(&self, symbol: &str) -> Result<AssetData, String> {
-        let api_key = self.api_key.as_ref().ok_or("Alpha Vantage requires API key")?;
 
+    /// 實際呼叫 GLOBAL_QUOTE（會耗用一次額度）
+    async fn fetch_quote(&self, symbol: &str, api_key: &str) -> Result<AssetData, QuoteError> {
         let data: serde_json::Value = self
             .client
             .get(format!(
-                "https://www.alphavantage.co/query?function=GLOBAL_QUOTE&symbol={}&apikey={}",
+                "{}/query?function=GLOBAL_QUOTE&symbol={}&apikey={}",
+                self.base_url,
                 symbol, api_key
             ))
-            .send()
+            .send_captured(PROVIDER_ID)
             .await
-            .map_err(|e| format!("AlphaVantage connection failed: {}", e))?
+            .map_err(|e| QuoteError::Other(format!("AlphaVantage connection failed: {}", e)))?
             .error_for_status()
-            .map_err(|e| format!("AlphaVantage API error: {}", e))?
+            .map_err(|e| QuoteError::Other(format!("AlphaVantage API error: {}", e)))?
             .json()
             .await
-            .map_err(|e| format!("AlphaVantage parse failed: {}", e))?;
+            .map_err(|e| QuoteError::Other(format!("AlphaVantage parse failed: {}", e)))?;
 
         // Check for rate limit message
         if data["Note"].is_string() || data["Information"].is_string() {
-            return Err("Alpha Vantage rate limit reached (25 calls/day)".to_string());
+            return Err(QuoteError::RateLimited);
         }
 
         let q = &data["Global Quote"];
         if q.is_null() || q["05. price"].is_null() {
-            return Err(format!("AlphaVantage not found: {}", symbol));
+            return Err(QuoteError::Other(format!("AlphaVantage not found: {}", symbol)));
         }
 
         let parse = |key: &str| q[key].as_str().and_then(|s| s.parse::<f64>().ok());
@@ -54,7 +168,7 @@ impl DataProvider for AlphaVantageProvider {
             .as_str()
             .and_then(|s| s.trim_end_matches('%').parse::<f64>().ok());
 
-        Ok(AssetDataBuilder::new(symbol, "alphavantage")
+        Ok(AssetDataBuilder::new(symbol, PROVIDER_ID)
             .price(parse("05. price").unwrap_or(0.0))
             .change_24h(parse("09. change"))
             .change_percent_24h(pct)
@@ -63,10 +177,111 @@ impl DataProvider for AlphaVantageProvider {
             .volume(parse("06. volume"))
             .extra_f64("open_price", parse("02. open"))
             .extra_f64("prev_close", parse("08. previous close"))
+            .extra_str("trading_day", q["07. latest trading day"].as_str())
             .build())
     }
+}
+
+/// 額度不足時：有舊報價就沿用，否則回報錯誤
+fn stale_or(symbol: &str, state: &AlphaVantageState, err: String) -> Result<AssetData, String> {
+    match state.quotes.get(symbol) {
+        Some((data, _)) => Ok(data.clone()),
+        None => Err(err),
+    }
+}
+
+#[async_trait::async_trait]
+impl DataProvider for AlphaVantageProvider {
+    fn info(&self) -> ProviderInfo {
+        provider_info_or_panic(PROVIDER_ID)
+    }
+
+    /// 首次查詢前從 DB 載入快取與今日額度；之後寫回新報價與已用額度
+    async fn prepare(&self, db: &DbPool) {
+        {
+            let state = STATE.read().await;
+            if state.loaded && state.pending.is_empty() && !state.quota_dirty {
+                return;
+            }
+        }
+
+        let mut state = STATE.write().await;
+        if !state.loaded {
+            match db.load_provider_quotes(PROVIDER_ID) {
+                Ok(quotes) => {
+                    for (data, at) in quotes {
+                        state.quotes.entry(data.symbol.clone()).or_insert((data, at));
+                    }
+                }
+                Err(e) => tracing::warn!("[AlphaVantage] Failed to load cached quotes: {}", e),
+            }
+            if let Some(value) = db.get_setting(QUOTA_KEY).ok().flatten() {
+                let stored = DailyQuota::from_setting(&value);
+                // 載入前已查詢過時，同一天取較大的用量
+                if state.quota.day.is_empty() {
+                    state.quota = stored;
+                } else if stored.day == state.quota.day {
+                    state.quota.used = state.quota.used.max(stored.used);
+                }
+            }
+            state.loaded = true;
+        }
+        if !state.pending.is_empty() {
+            let pending = std::mem::take(&mut state.pending);
+            if let Err(e) = db.upsert_provider_quotes(PROVIDER_ID, &pending) {
+                tracing::warn!("[AlphaVantage] Failed to save cached quotes: {}", e);
+            }
+        }
+        if state.quota_dirty {
+            if let Err(e) = db.set_setting(QUOTA_KEY, &state.quota.to_setting()) {
+                tracing::warn!("[AlphaVantage] Failed to save quota usage: {}", e);
+            }
+            state.quota_dirty = false;
+        }
+    }
 
-    /// 限流並行查詢 — Alpha Vantage 沒有批量 endpoint（注意免費版 25 calls/day）
+    async fn fetch_price(&self, symbol: &str) -> Result<AssetData, String> {
+        let api_key = self.api_key.as_ref().ok_or("Alpha Vantage requires API key")?;
+        let now = Utc::now();
+        let max_age = i64::from(refresh_hours()) * 3600;
+
+        {
+            let mut state = STATE.write().await;
+            if let Some((data, at)) = state.quotes.get(symbol) {
+                if now.timestamp() - at < max_age {
+                    return Ok(data.clone());
+                }
+            }
+            if !state.quota.try_acquire(now) {
+                let err = format!(
+                    "Alpha Vantage call budget reached ({} of {} calls left today)",
+                    state.quota.remaining_at(now),
+                    DAILY_CALLS
+                );
+                return stale_or(symbol, &state, err);
+            }
+            state.quota_dirty = true;
+        }
+
+        let result = self.fetch_quote(symbol, api_key).await;
+        let mut state = STATE.write().await;
+        match result {
+            Ok(data) => {
+                let at = now.timestamp();
+                state.quotes.insert(symbol.to_string(), (data.clone(), at));
+                state.pending.push((data.clone(), at));
+                Ok(data)
+            }
+            Err(QuoteError::RateLimited) => {
+                state.quota.exhaust(now);
+                let err = format!("Alpha Vantage rate limit reached ({} calls/day)", DAILY_CALLS);
+                stale_or(symbol, &state, err)
+            }
+            Err(QuoteError::Other(e)) => Err(e),
+        }
+    }
+
+    /// 限流並行查詢 — Alpha Vantage 沒有批量 endpoint；未過期的 symbol 直接取快取，不耗用額度
     async fn fetch_prices(&self, symbols: &[String]) -> Result<Vec<AssetData>, String> {
         if symbols.is_empty() {
             return Ok(vec![]);
@@ -75,65 +290,47 @@ impl DataProvider for AlphaVantageProvider {
             return self.fetch_price(&symbols[0]).await.map(|d| vec![d]);
         }
 
-        let api_key = self
-            .api_key
-            .as_ref()
-            .ok_or("Alpha Vantage requires API key")?
-            .clone();
-        let client = self.client.clone();
-
-        let mut tasks = tokio::task::JoinSet::new();
-        let mut results = Vec::new();
-        let semaphore = std::sync::Arc::new(tokio::sync::Semaphore::new(2));
-
-        for sym in symbols {
-            let sym = sym.clone();
-            let c = client.clone();
-            let key = api_key.clone();
-            let sem = semaphore.clone();
-            tasks.spawn(async move {
-                let _permit = sem.acquire().await;
-                let data_res: Result<serde_json::Value, _> = c
-                    .get(format!("https://www.alphavantage.co/query?function=GLOBAL_QUOTE&symbol={}&apikey={}", sym, key))
-                    .send().await.map_err(|e| format!("AlphaVantage: {}", e))?
-                    .error_for_status().map_err(|e| format!("AlphaVantage API error: {}", e))?
-                    .json().await.map_err(|e| format!("AlphaVantage: {}", e));
-
-                let data = match data_res {
-                    Ok(d) => d,
-                    Err(e) => return Err(e),
-                };
-
-                if data["Note"].is_string() || data["Information"].is_string() {
-                    return Err("Alpha Vantage rate limit reached".to_string());
-                }
-                let q = &data["Global Quote"];
-                if q.is_null() || q["05. price"].is_null() {
-                    return Err(format!("AlphaVantage not found: {}", sym));
-                }
-                let parse = |key: &str| q[key].as_str().and_then(|s| s.parse::<f64>().ok());
-                let pct = q["10. change percent"].as_str()
-                    .and_then(|s| s.trim_end_matches('%').parse::<f64>().ok());
-
-                Ok(AssetDataBuilder::new(&sym, "alphavantage")
-                    .price(parse("05. price").unwrap_or(0.0))
-                    .change_24h(parse("09. change"))
-                    .change_percent_24h(pct)
-                    .high_24h(parse("03. high"))
-                    .low_24h(parse("04. low"))
-                    .volume(parse("06. volume"))
-                    .extra_f64("open_price", parse("02. open"))
-                    .extra_f64("prev_close", parse("08. previous close"))
-                    .build())
-            });
-        }
-
-        while let Some(Ok(res)) = tasks.join_next().await {
-            match res {
-                Ok(data) => results.push(data),
-                Err(e) => eprintln!("AlphaVantage skipped: {}", e),
+        use futures::stream::{self, StreamExt};
+        let results: Vec<_> = stream::iter(symbols.to_vec())
+            .map(|sym| async move { self.fetch_price(&sym).await })
+            .buffer_unordered(2)
+            .collect()
+            .await;
+
+        let mut out = Vec::new();
+        for r in results {
+            match r {
+                Ok(data) => out.push(data),
+                Err(e) => tracing::warn!("AlphaVantage skipped: {}", e),
             }
         }
-        Ok(results)
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn quota_is_spread_over_remaining_hours() {
+        let mut quota = DailyQuota::default();
+        // 12:00 UTC 還剩 12 小時 → 本小時最多 ceil(25 / 12) = 3 次
+        let noon = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        assert_eq!((0..5).filter(|_| quota.try_acquire(noon)).count(), 3);
+        assert_eq!(quota.remaining_at(noon), 22);
+
+        // 最後一小時可用掉所有剩餘額度
+        let last_hour = Utc.with_ymd_and_hms(2026, 3, 1, 23, 0, 0).unwrap();
+        assert_eq!((0..30).filter(|_| quota.try_acquire(last_hour)).count(), 22);
+        assert!(!quota.try_acquire(last_hour));
+
+        // 隔天重置；設定值可還原當日用量
+        let next_day = Utc.with_ymd_and_hms(2026, 3, 2, 0, 0, 0).unwrap();
+        assert_eq!(quota.remaining_at(next_day), DAILY_CALLS);
+        assert!(quota.try_acquire(next_day));
+        let restored = DailyQuota::from_setting(&quota.to_setting());
+        assert_eq!((restored.day.as_str(), restored.used), ("2026-03-02", 1));
     }
 }
@@ -1,15 +1,96 @@
+use super::debug::SendCaptured;
+use super::derivatives::{self, Positioning};
 use super::traits::*;
 use super::types::*;
 use std::collections::HashMap;
+use tokio::sync::RwLock;
+
+const DEFAULT_BASE_URL: &str = "https://api.binance.com";
+/// USDⓈ-M 永續合約 API（未平倉量 / 多空比）
+const FUTURES_BASE_URL: &str = "https://fapi.binance.com";
 
 pub struct BinanceProvider {
     client: reqwest::Client,
+    base_url: String,
+    /// exchangeInfo 查到的價格小數位數（binance symbol → scale；None = 查無 PRICE_FILTER）
+    price_scales: RwLock<HashMap<String, Option<u32>>>,
+}
+
+/// 從 exchangeInfo 回應取出各 symbol 的 PRICE_FILTER tickSize 並換算小數位數
+fn parse_tick_scales(info: &serde_json::Value) -> HashMap<String, u32> {
+    info["symbols"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|s| {
+            let symbol = s["symbol"].as_str()?;
+            let tick = s["filters"]
+                .as_array()?
+                .iter()
+                .find(|f| f["filterType"] == "PRICE_FILTER")?["tickSize"]
+                .as_str()?;
+            Some((symbol.to_string(), scale_from_tick_size(tick)?))
+        })
+        .collect()
+}
+
+/// 由 openInterest 與 globalLongShortAccountRatio 回應組成持倉資料
+fn parse_positioning(open_interest: &serde_json::Value, ratio: Option<&serde_json::Value>) -> Positioning {
+    Positioning {
+        open_interest: derivatives::num(&open_interest["openInterest"]),
+        open_interest_usd: None,
+        long_short_ratio: ratio.and_then(|r| derivatives::num(&r[0]["longShortRatio"])),
+    }
 }
 
 impl BinanceProvider {
-    pub fn new(_api_key: Option<String>) -> Self {
+    pub fn new(_api_key: Option<String>, api_url: Option<String>, http: &HttpOptions) -> Self {
         Self {
-            client: shared_client(),
+            client: provider_client(http),
+            base_url: resolve_base_url(api_url, DEFAULT_BASE_URL),
+            price_scales: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// 以 exchangeInfo 的 tick size 補上 `price_scale`；每個 symbol 只成功查詢一次，
+    /// 查詢失敗時不記錄，下次再試（價格照常回傳）
+    async fn apply_price_scales(&self, results: &mut [AssetData]) {
+        let mut missing: Vec<String> = {
+            let scales = self.price_scales.read().await;
+            results
+                .iter()
+                .map(|d| to_binance_symbol(&d.symbol))
+                .filter(|s| !scales.contains_key(s))
+                .collect()
+        };
+        if !missing.is_empty() {
+            missing.sort();
+            missing.dedup();
+            let list: Vec<String> = missing.iter().map(|s| format!("\"{}\"", s)).collect();
+            let url = format!(
+                "{}/api/v3/exchangeInfo?symbols=[{}]",
+                self.base_url,
+                list.join(",")
+            );
+            let info = match self.client.get(&url).send_captured("binance").await {
+                Ok(resp) if resp.status().is_success() => resp.json::<serde_json::Value>().await.ok(),
+                _ => None,
+            };
+            match info {
+                Some(info) => {
+                    let fetched = parse_tick_scales(&info);
+                    let mut scales = self.price_scales.write().await;
+                    for sym in missing {
+                        let scale = fetched.get(&sym).copied();
+                        scales.insert(sym, scale);
+                    }
+                }
+                None => tracing::debug!("[Binance] exchangeInfo unavailable, using default price scale"),
+            }
+        }
+        let scales = self.price_scales.read().await;
+        for d in results.iter_mut() {
+            d.price_scale = scales.get(&to_binance_symbol(&d.symbol)).copied().flatten();
         }
     }
 
@@ -39,11 +120,11 @@ impl DataProvider for BinanceProvider {
 
     async fn fetch_price(&self, symbol: &str) -> Result<AssetData, String> {
         let sym = to_binance_symbol(symbol);
-        let url = format!("https://api.binance.com/api/v3/ticker/24hr?symbol={}", sym);
+        let url = format!("{}/api/v3/ticker/24hr?symbol={}", self.base_url, sym);
         let data: serde_json::Value = self
             .client
             .get(&url)
-            .send()
+            .send_captured("binance")
             .await
             .map_err(|e| format!("Binance connection failed: {}", e))?
             .error_for_status()
@@ -52,7 +133,9 @@ impl DataProvider for BinanceProvider {
             .await
             .map_err(|e| format!("Binance parse failed: {}", e))?;
 
-        Ok(Self::parse_ticker(symbol, &data))
+        let mut data = Self::parse_ticker(symbol, &data);
+        self.apply_price_scales(std::slice::from_mut(&mut data)).await;
+        Ok(data)
     }
 
     /// 批量查詢 — 智慧策略：
@@ -65,6 +148,49 @@ impl DataProvider for BinanceProvider {
         if symbols.len() == 1 {
             return self.fetch_price(&symbols[0]).await.map(|d| vec![d]);
         }
+        let mut results = self.fetch_tickers(symbols).await?;
+        self.apply_price_scales(&mut results).await;
+        Ok(results)
+    }
+
+    /// `provider_params` 支援 `{"derivatives": true}`：另查詢同交易對永續合約的未平倉量與多空比
+    async fn fetch_prices_with_params(
+        &self,
+        symbols: &[String],
+        params: &HashMap<String, ProviderParams>,
+    ) -> Result<Vec<AssetData>, String> {
+        let mut results = self.fetch_prices(symbols).await?;
+        derivatives::enrich(&mut results, params, |sym| self.fetch_positioning(sym)).await;
+        Ok(results)
+    }
+}
+
+impl BinanceProvider {
+    async fn get_futures_json(&self, path: &str) -> Result<serde_json::Value, String> {
+        self.client
+            .get(format!("{}{}", FUTURES_BASE_URL, path))
+            .send_captured("binance")
+            .await
+            .map_err(|e| format!("Binance futures connection failed: {}", e))?
+            .error_for_status()
+            .map_err(|e| format!("Binance futures API error: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("Binance futures parse failed: {}", e))
+    }
+
+    /// 查詢永續合約的未平倉量（必要）與最新 5 分鐘多空帳戶比（可缺）
+    async fn fetch_positioning(&self, symbol: String) -> Result<Positioning, String> {
+        let sym = to_binance_symbol(&symbol);
+        let oi_path = format!("/fapi/v1/openInterest?symbol={}", sym);
+        let ratio_path = format!("/futures/data/globalLongShortAccountRatio?symbol={}&period=5m&limit=1", sym);
+        let (open_interest, ratio) =
+            futures::join!(self.get_futures_json(&oi_path), self.get_futures_json(&ratio_path));
+        Ok(parse_positioning(&open_interest?, ratio.ok().as_ref()))
+    }
+
+    /// 批量取得 24hr ticker（不含 price_scale）
+    async fn fetch_tickers(&self, symbols: &[String]) -> Result<Vec<AssetData>, String> {
 
         // 建立 binance_symbol -> original_symbol 映射
         let mappings: Vec<(String, String)> = symbols
@@ -80,13 +206,14 @@ impl DataProvider for BinanceProvider {
                 .collect();
             let syms_param = format!("[{}]", binance_syms.join(","));
             let url = format!(
-                "https://api.binance.com/api/v3/ticker/24hr?symbols={}",
+                "{}/api/v3/ticker/24hr?symbols={}",
+                self.base_url,
                 syms_param
             );
             let resp = self
                 .client
                 .get(&url)
-                .send()
+                .send_captured("binance")
                 .await
                 .map_err(|e| format!("Binance batch connection failed: {}", e))?;
             let body = resp
@@ -111,20 +238,20 @@ impl DataProvider for BinanceProvider {
         }
 
         // 大量 symbol 或精確查詢失敗 → 取回所有 ticker，在本地過濾（免疫無效 symbol）
-        let url = "https://api.binance.com/api/v3/ticker/24hr";
-        let resp = self.client.get(url).send().await.map_err(|e| {
-            eprintln!("Binance request failed: {:?}", e);
+        let url = format!("{}/api/v3/ticker/24hr", self.base_url);
+        let resp = self.client.get(url).send_captured("binance").await.map_err(|e| {
+            tracing::warn!("Binance request failed: {:?}", e);
             format!("Binance full query connection failed: {}", e)
         })?;
 
         let status = resp.status();
         let body = resp.text().await.map_err(|e| {
-            eprintln!("Binance read body failed: {:?}", e);
+            tracing::warn!("Binance read body failed: {:?}", e);
             format!("Binance full query read failed: {}", e)
         })?;
 
         if !status.is_success() {
-            eprintln!(
+            tracing::warn!(
                 "Binance batch request rejected ({}): {}",
                 status,
                 &body[..body.len().min(200)]
@@ -136,7 +263,7 @@ impl DataProvider for BinanceProvider {
         }
 
         let arr: Vec<serde_json::Value> = serde_json::from_str(&body).map_err(|e| {
-            eprintln!("Binance parse failed: {:?}", e);
+            tracing::warn!("Binance parse failed: {:?}", e);
             format!("Binance full query parse failed: {}", e)
         })?;
 
@@ -154,3 +281,39 @@ impl DataProvider for BinanceProvider {
         Ok(results)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tick_scales_come_from_price_filter() {
+        let info = serde_json::json!({
+            "symbols": [
+                {"symbol": "BTCUSDT", "filters": [
+                    {"filterType": "PRICE_FILTER", "minPrice": "0.01", "tickSize": "0.01000000"},
+                    {"filterType": "LOT_SIZE", "stepSize": "0.00001000"}
+                ]},
+                {"symbol": "BONKUSDT", "filters": [
+                    {"filterType": "PRICE_FILTER", "tickSize": "0.00000001"}
+                ]},
+                {"symbol": "NOFILTER", "filters": []}
+            ]
+        });
+        let scales = parse_tick_scales(&info);
+        assert_eq!(scales.get("BTCUSDT"), Some(&2));
+        assert_eq!(scales.get("BONKUSDT"), Some(&8));
+        assert!(!scales.contains_key("NOFILTER"));
+    }
+
+    #[test]
+    fn positioning_parses_futures_responses() {
+        let oi = serde_json::json!({"symbol": "BTCUSDT", "openInterest": "81234.567", "time": 1700000000000i64});
+        let ratio = serde_json::json!([
+            {"symbol": "BTCUSDT", "longShortRatio": "1.8105", "longAccount": "0.6442", "shortAccount": "0.3558"}
+        ]);
+        let p = parse_positioning(&oi, Some(&ratio));
+        assert_eq!((p.open_interest, p.long_short_ratio), (Some(81234.567), Some(1.8105)));
+        assert_eq!(parse_positioning(&oi, None).long_short_ratio, None);
+    }
+}
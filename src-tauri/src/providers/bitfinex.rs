@@ -1,20 +1,25 @@
+use super::debug::SendCaptured;
 use super::traits::*;
 use super::types::*;
 
+const DEFAULT_BASE_URL: &str = "https://api-pub.bitfinex.com";
+
 pub struct BitfinexProvider {
     client: reqwest::Client,
+    base_url: String,
 }
 
 impl Default for BitfinexProvider {
     fn default() -> Self {
-        Self::new()
+        Self::new(None, &HttpOptions::default())
     }
 }
 
 impl BitfinexProvider {
-    pub fn new() -> Self {
+    pub fn new(api_url: Option<String>, http: &HttpOptions) -> Self {
         Self {
-            client: shared_client(),
+            client: provider_client(http),
+            base_url: resolve_base_url(api_url, DEFAULT_BASE_URL),
         }
     }
 }
@@ -53,11 +58,11 @@ impl DataProvider for BitfinexProvider {
 
     async fn fetch_price(&self, symbol: &str) -> Result<AssetData, String> {
         let bfx = to_bitfinex_symbol(symbol);
-        let url = format!("https://api-pub.bitfinex.com/v2/ticker/{}", bfx);
+        let url = format!("{}/v2/ticker/{}", self.base_url, bfx);
         let arr: Vec<serde_json::Value> = self
             .client
             .get(&url)
-            .send()
+            .send_captured("bitfinex")
             .await
             .map_err(|e| format!("Bitfinex connection failed: {}", e))?
             .error_for_status()
@@ -82,11 +87,11 @@ impl DataProvider for BitfinexProvider {
 
         let bfx_syms: Vec<String> = symbols.iter().map(|s| to_bitfinex_symbol(s)).collect();
         let param = bfx_syms.join(",");
-        let url = format!("https://api-pub.bitfinex.com/v2/tickers?symbols={}", param);
+        let url = format!("{}/v2/tickers?symbols={}", self.base_url, param);
         let data: Vec<Vec<serde_json::Value>> = self
             .client
             .get(&url)
-            .send()
+            .send_captured("bitfinex")
             .await
             .map_err(|e| format!("Bitfinex batch connection failed: {}", e))?
             .json()
@@ -1,58 +1,191 @@
+use super::debug::SendCaptured;
 use super::traits::*;
 use super::types::*;
 
+const DEFAULT_BASE_URL: &str = "https://streaming.bitquery.io";
+/// 單次 GraphQL 合併查詢的最大 token 數（免費額度以查詢次數計）
+const MAX_TOKENS_PER_QUERY: usize = 20;
+
 pub struct BitqueryProvider {
     client: reqwest::Client,
+    base_url: String,
     api_key: Option<String>,
 }
 
-impl BitqueryProvider {
-    pub fn new(api_key: Option<String>) -> Self {
-        Self {
-            client: shared_client(),
-            api_key,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Network {
+    Eth,
+    Bsc,
+    Base,
+    Arbitrum,
+    Solana,
+}
+
+impl Network {
+    const ALL: [Network; 5] = [
+        Network::Eth,
+        Network::Bsc,
+        Network::Base,
+        Network::Arbitrum,
+        Network::Solana,
+    ];
+
+    fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "eth" | "ethereum" => Some(Network::Eth),
+            "bsc" => Some(Network::Bsc),
+            "base" => Some(Network::Base),
+            "arbitrum" | "arb" => Some(Network::Arbitrum),
+            "solana" | "sol" => Some(Network::Solana),
+            _ => None,
+        }
+    }
+
+    /// Bitquery 的 network 名稱，同時作為 GraphQL alias
+    fn key(self) -> &'static str {
+        match self {
+            Network::Eth => "eth",
+            Network::Bsc => "bsc",
+            Network::Base => "base",
+            Network::Arbitrum => "arbitrum",
+            Network::Solana => "solana",
         }
     }
 }
 
-#[async_trait::async_trait]
-impl DataProvider for BitqueryProvider {
-    fn info(&self) -> ProviderInfo {
-        provider_info_or_panic("bitquery")
+#[derive(Debug, PartialEq, Eq)]
+struct TokenQuery {
+    network: Network,
+    token: String,
+    quote: Option<String>,
+}
+
+/// 解析 symbol：`[network:]token_address[:quote_address]`，未指定 network 時為 eth
+fn parse_symbol(symbol: &str) -> Result<TokenQuery, String> {
+    let parts: Vec<&str> = symbol.trim().split(':').map(str::trim).collect();
+    let (network, rest) = match Network::parse(parts[0]) {
+        Some(n) => (n, &parts[1..]),
+        None => (Network::Eth, &parts[..]),
+    };
+    let (token, quote) = match rest {
+        [token] => (*token, None),
+        [token, quote] => (*token, Some(*quote)),
+        _ => {
+            return Err(format!(
+                "Bitquery: invalid symbol '{}', expected [network:]address[:quote_address]",
+                symbol
+            ))
+        }
+    };
+    // 地址會直接嵌入 GraphQL 字串，只允許英數字
+    let normalize = |addr: &str| -> Result<String, String> {
+        if addr.is_empty() || !addr.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(format!("Bitquery: invalid address '{}'", addr));
+        }
+        // EVM 合約地址在 Bitquery 中為小寫；Solana base58 區分大小寫
+        Ok(if network == Network::Solana {
+            addr.to_string()
+        } else {
+            addr.to_ascii_lowercase()
+        })
+    };
+    Ok(TokenQuery {
+        network,
+        token: normalize(token)?,
+        quote: quote.map(normalize).transpose()?,
+    })
+}
+
+/// 單一 token 的 DEXTradeByTokens 查詢（以 `t{index}` 為 alias）
+fn trade_field(index: usize, q: &TokenQuery) -> String {
+    let field = if q.network == Network::Solana {
+        "MintAddress"
+    } else {
+        "SmartContract"
+    };
+    let side = q
+        .quote
+        .as_ref()
+        .map(|quote| format!(", Side: {{Currency: {{{}: {{is: \"{}\"}}}}}}", field, quote))
+        .unwrap_or_default();
+    format!(
+        "t{}: DEXTradeByTokens(limit: {{count: 1}}, orderBy: {{descending: Block_Time}}, \
+         where: {{Trade: {{Currency: {{{}: {{is: \"{}\"}}}}{}}}}}) \
+         {{ Trade {{ PriceInUSD Price AmountInUSD }} }}",
+        index, field, q.token, side
+    )
+}
+
+/// 將多個 token 合併成一個 GraphQL 查詢，依 network 分組
+fn build_query(queries: &[TokenQuery]) -> String {
+    let mut out = String::from("{\n");
+    for network in Network::ALL {
+        let fields: Vec<String> = queries
+            .iter()
+            .enumerate()
+            .filter(|(_, q)| q.network == network)
+            .map(|(i, q)| format!("    {}", trade_field(i, q)))
+            .collect();
+        if fields.is_empty() {
+            continue;
+        }
+        let root = if network == Network::Solana {
+            "Solana".to_string()
+        } else {
+            format!("EVM(dataset: combined, network: {})", network.key())
+        };
+        out.push_str(&format!(
+            "  {}: {} {{\n{}\n  }}\n",
+            network.key(),
+            root,
+            fields.join("\n")
+        ));
     }
+    out.push('}');
+    out
+}
 
-    async fn fetch_price(&self, symbol: &str) -> Result<AssetData, String> {
+/// 取出第 index 個 token 的最新成交；quote 篩選時另存以 quote 計價的價格
+fn parse_trade(symbol: &str, index: usize, q: &TokenQuery, data: &serde_json::Value) -> Option<AssetData> {
+    let trade = &data[q.network.key()][format!("t{}", index)][0]["Trade"];
+    let price = trade["PriceInUSD"].as_f64()?;
+    Some(
+        AssetDataBuilder::new(symbol, "bitquery")
+            .price(price)
+            .volume(trade["AmountInUSD"].as_f64())
+            .extra_str("network", Some(q.network.key()))
+            .extra_f64(
+                "quote_price",
+                q.quote.as_ref().and_then(|_| trade["Price"].as_f64()),
+            )
+            .build(),
+    )
+}
+
+impl BitqueryProvider {
+    pub fn new(api_key: Option<String>, api_url: Option<String>, http: &HttpOptions) -> Self {
+        Self {
+            client: provider_client(http),
+            base_url: resolve_base_url(api_url, DEFAULT_BASE_URL),
+            api_key,
+        }
+    }
+
+    /// 送出合併查詢，回傳 `data` 欄位
+    async fn query(&self, query: &str) -> Result<serde_json::Value, String> {
         let api_key = self
             .api_key
             .as_ref()
             .ok_or("Bitquery requires API key (OAuth token)")?;
 
         // Bitquery v2 uses streaming.bitquery.io/graphql with Bearer token
-        let query = format!(
-            r#"{{
-            EVM(dataset: combined, network: eth) {{
-                DEXTradeByTokens(
-                    limit: {{count: 1}}
-                    orderBy: {{descending: Block_Time}}
-                    where: {{Trade: {{Currency: {{SmartContract: {{is: "{}"}}}}}}}}
-                ) {{
-                    Trade {{
-                        PriceInUSD
-                        AmountInUSD
-                    }}
-                }}
-            }}
-        }}"#,
-            symbol
-        );
-
-        let data: serde_json::Value = self
+        let mut body: serde_json::Value = self
             .client
-            .post("https://streaming.bitquery.io/graphql")
+            .post(format!("{}/graphql", self.base_url))
             .header("Authorization", format!("Bearer {}", api_key))
             .header("Content-Type", "application/json")
             .json(&serde_json::json!({ "query": query }))
-            .send()
+            .send_captured("bitquery")
             .await
             .map_err(|e| format!("Bitquery connection failed: {}", e))?
             .error_for_status()
@@ -61,15 +194,30 @@ impl DataProvider for BitqueryProvider {
             .await
             .map_err(|e| format!("Bitquery parse failed: {}", e))?;
 
-        let trade = &data["data"]["EVM"]["DEXTradeByTokens"][0]["Trade"];
+        if body["data"].is_null() {
+            let msg = body["errors"][0]["message"]
+                .as_str()
+                .unwrap_or("empty response");
+            return Err(format!("Bitquery query error: {}", msg));
+        }
+        Ok(body["data"].take())
+    }
+}
 
-        Ok(AssetDataBuilder::new(symbol, "bitquery")
-            .price(trade["PriceInUSD"].as_f64().unwrap_or(0.0))
-            .volume(trade["AmountInUSD"].as_f64())
-            .build())
+#[async_trait::async_trait]
+impl DataProvider for BitqueryProvider {
+    fn info(&self) -> ProviderInfo {
+        provider_info_or_panic("bitquery")
     }
 
-    /// 限流並行查詢 — Bitquery GraphQL 可以合併但太複雜，限制同時 2 個
+    async fn fetch_price(&self, symbol: &str) -> Result<AssetData, String> {
+        let q = parse_symbol(symbol)?;
+        let data = self.query(&build_query(std::slice::from_ref(&q))).await?;
+        parse_trade(symbol, 0, &q, &data)
+            .ok_or_else(|| format!("Bitquery: no trades found for {}", symbol))
+    }
+
+    /// 合併查詢 — 每 MAX_TOKENS_PER_QUERY 個 token 一個 GraphQL 請求，跨 network 也合併
     async fn fetch_prices(&self, symbols: &[String]) -> Result<Vec<AssetData>, String> {
         if symbols.is_empty() {
             return Ok(vec![]);
@@ -78,64 +226,85 @@ impl DataProvider for BitqueryProvider {
             return self.fetch_price(&symbols[0]).await.map(|d| vec![d]);
         }
 
-        let api_key = self
-            .api_key
-            .as_ref()
-            .ok_or("Bitquery requires API key")?
-            .clone();
-        let client = self.client.clone();
-
-        use futures::stream::{self, StreamExt};
-        let results: Vec<_> = stream::iter(symbols.to_vec())
-            .map(|sym| {
-                let c = client.clone();
-                let key = api_key.clone();
-                async move {
-                    let query = format!(
-                        r#"{{
-                        EVM(dataset: combined, network: eth) {{
-                            DEXTradeByTokens(
-                                limit: {{count: 1}}
-                                orderBy: {{descending: Block_Time}}
-                                where: {{Trade: {{Currency: {{SmartContract: {{is: "{}"}}}}}}}}
-                            ) {{ Trade {{ PriceInUSD AmountInUSD }} }}
-                        }}
-                    }}"#,
-                        sym
-                    );
-                    let data: serde_json::Value = c
-                        .post("https://streaming.bitquery.io/graphql")
-                        .header("Authorization", format!("Bearer {}", key))
-                        .header("Content-Type", "application/json")
-                        .json(&serde_json::json!({ "query": query }))
-                        .send()
-                        .await
-                        .map_err(|e| format!("Bitquery: {}", e))?
-                        .error_for_status()
-                        .map_err(|e| format!("Bitquery API error: {}", e))?
-                        .json()
-                        .await
-                        .map_err(|e| format!("Bitquery: {}", e))?;
-                    let trade = &data["data"]["EVM"]["DEXTradeByTokens"][0]["Trade"];
-                    Ok::<AssetData, String>(
-                        AssetDataBuilder::new(&sym, "bitquery")
-                            .price(trade["PriceInUSD"].as_f64().unwrap_or(0.0))
-                            .volume(trade["AmountInUSD"].as_f64())
-                            .build(),
-                    )
+        let mut names = Vec::new();
+        let mut queries = Vec::new();
+        for sym in symbols {
+            match parse_symbol(sym) {
+                Ok(q) => {
+                    names.push(sym.as_str());
+                    queries.push(q);
                 }
-            })
-            .buffer_unordered(2)
-            .collect()
-            .await;
+                Err(e) => tracing::warn!("Bitquery skipped: {}", e),
+            }
+        }
 
         let mut out = Vec::new();
-        for r in results {
-            match r {
-                Ok(data) => out.push(data),
-                Err(e) => eprintln!("Bitquery skipped: {}", e),
+        for (names, queries) in names
+            .chunks(MAX_TOKENS_PER_QUERY)
+            .zip(queries.chunks(MAX_TOKENS_PER_QUERY))
+        {
+            let data = match self.query(&build_query(queries)).await {
+                Ok(d) => d,
+                Err(e) if symbols.len() <= MAX_TOKENS_PER_QUERY => return Err(e),
+                Err(e) => {
+                    tracing::warn!("Bitquery batch skipped: {}", e);
+                    continue;
+                }
+            };
+            for (i, (sym, q)) in names.iter().zip(queries).enumerate() {
+                match parse_trade(sym, i, q, &data) {
+                    Some(asset) => out.push(asset),
+                    None => tracing::warn!("Bitquery skipped: no trades found for {}", sym),
+                }
             }
         }
         Ok(out)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn symbol_parses_network_token_and_quote() {
+        assert_eq!(
+            parse_symbol("0xABC").unwrap(),
+            TokenQuery { network: Network::Eth, token: "0xabc".into(), quote: None }
+        );
+        assert_eq!(
+            parse_symbol("bsc:0xAbc:0xDef").unwrap(),
+            TokenQuery { network: Network::Bsc, token: "0xabc".into(), quote: Some("0xdef".into()) }
+        );
+        // Solana mint 區分大小寫
+        assert_eq!(parse_symbol("solana:So11Mint").unwrap().token, "So11Mint");
+        assert!(parse_symbol("eth:0xabc\"}").is_err());
+        assert!(parse_symbol("eth:a:b:c").is_err());
+    }
+
+    #[test]
+    fn merged_query_groups_by_network_and_maps_aliases_back() {
+        let queries = vec![
+            parse_symbol("eth:0xaaa").unwrap(),
+            parse_symbol("solana:Mint1:Mint2").unwrap(),
+            parse_symbol("eth:0xbbb").unwrap(),
+        ];
+        let query = build_query(&queries);
+        assert_eq!(query.matches("EVM(dataset: combined, network: eth)").count(), 1);
+        assert!(query.contains("t1: DEXTradeByTokens"));
+        assert!(query.contains("Side: {Currency: {MintAddress: {is: \"Mint2\"}}}"));
+
+        let data = json!({
+            "eth": {
+                "t0": [{"Trade": {"PriceInUSD": 1.5, "AmountInUSD": 10.0, "Price": 0.001}}],
+                "t2": []
+            },
+            "solana": {"t1": [{"Trade": {"PriceInUSD": 2.0, "Price": 0.5}}]}
+        });
+        assert_eq!(parse_trade("a", 0, &queries[0], &data).unwrap().price, 1.5);
+        let sol = parse_trade("b", 1, &queries[1], &data).unwrap();
+        assert_eq!(sol.extra.unwrap()["quote_price"], json!(0.5));
+        assert!(parse_trade("c", 2, &queries[2], &data).is_none());
+    }
+}
@@ -0,0 +1,244 @@
+//! symbol → coin ID 對照表的共用實作（CoinGecko / CoinPaprika）
+//!
+//! 對照表來源為 DB 中快取的 coin 清單，每週向 API 更新一次；更新失敗時沿用舊表，
+//! `RETRY_AFTER` 內不再重試。網路請求期間不持有對照表的寫鎖，查詢不會被更新卡住。
+
+use super::types::parse_crypto_symbol;
+use crate::db::{CoinGeckoCoin, CoinPaprikaCoin, DbPool, DbResult};
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, RwLock};
+
+/// coin 清單每週更新一次
+const REFRESH_SECS: i64 = 7 * 24 * 3600;
+const RETRY_AFTER: Duration = Duration::from_secs(3600);
+
+/// coin 清單中的一筆，供建立對照表使用
+pub trait CoinRow {
+    fn id(&self) -> &str;
+    fn symbol(&self) -> &str;
+    /// 排名（越小越前）；None = 未排名
+    fn rank(&self) -> Option<i64>;
+}
+
+impl CoinRow for CoinGeckoCoin {
+    fn id(&self) -> &str {
+        &self.id
+    }
+    fn symbol(&self) -> &str {
+        &self.symbol
+    }
+    fn rank(&self) -> Option<i64> {
+        self.market_cap_rank
+    }
+}
+
+impl CoinRow for CoinPaprikaCoin {
+    fn id(&self) -> &str {
+        &self.id
+    }
+    fn symbol(&self) -> &str {
+        &self.symbol
+    }
+    fn rank(&self) -> Option<i64> {
+        self.rank
+    }
+}
+
+/// 由 coin 清單建立對照表：同 symbol 多幣時取排名最前者，皆無排名時取 id 最短者；
+/// 另以 id 本身為 key，讓使用者可以直接輸入 coin ID
+pub fn build_id_index<T: CoinRow>(coins: &[T]) -> HashMap<String, String> {
+    let mut best: HashMap<String, &T> = HashMap::with_capacity(coins.len());
+    let priority = |c: &T| (c.rank().unwrap_or(i64::MAX), c.id().len());
+    for coin in coins {
+        let key = coin.symbol().to_uppercase();
+        match best.get(&key) {
+            Some(existing) if priority(existing) <= priority(coin) => {}
+            _ => {
+                best.insert(key, coin);
+            }
+        }
+    }
+    let mut map: HashMap<String, String> = best
+        .into_iter()
+        .map(|(k, c)| (k, c.id().to_string()))
+        .collect();
+    for coin in coins {
+        map.entry(coin.id().to_uppercase())
+            .or_insert_with(|| coin.id().to_string());
+    }
+    map
+}
+
+/// 內建主流幣對照：ticker → coin ID
+pub fn static_id(ids: &'static [(&'static str, &'static str)], base: &str) -> Option<&'static str> {
+    ids.iter()
+        .find(|(symbol, _)| *symbol == base)
+        .map(|(_, id)| *id)
+}
+
+#[derive(Default)]
+struct IndexState {
+    map: HashMap<String, String>,
+    /// 對照表的更新時間（unix 秒）
+    refreshed_at: i64,
+    /// 上次嘗試從網路更新的時間；失敗後 `RETRY_AFTER` 內不再重試
+    last_attempt: Option<Instant>,
+}
+
+impl IndexState {
+    fn is_fresh(&self, now: i64) -> bool {
+        !self.map.is_empty() && now - self.refreshed_at < REFRESH_SECS
+    }
+
+    fn recently_tried(&self) -> bool {
+        self.last_attempt.is_some_and(|t| t.elapsed() < RETRY_AFTER)
+    }
+}
+
+/// 單一 provider 的 symbol → coin ID 對照表
+pub struct CoinIdIndex {
+    /// log 前綴，例如 "CoinGecko"
+    name: &'static str,
+    static_ids: &'static [(&'static str, &'static str)],
+    state: RwLock<IndexState>,
+    /// 同一時間只允許一個更新流程；查詢不需要取得這把鎖
+    refresh: Mutex<()>,
+}
+
+impl CoinIdIndex {
+    pub fn new(name: &'static str, static_ids: &'static [(&'static str, &'static str)]) -> Self {
+        Self {
+            name,
+            static_ids,
+            state: RwLock::new(IndexState::default()),
+            refresh: Mutex::new(()),
+        }
+    }
+
+    /// 將 symbol 轉換成 coin ID：動態對照表（base / 原始輸入）→ 內建主流幣對照
+    pub async fn lookup(&self, symbol: &str) -> Option<String> {
+        let (base, _) = parse_crypto_symbol(symbol);
+        {
+            let state = self.state.read().await;
+            // 也嘗試原始輸入（使用者可能直接輸入 coin ID）
+            let found = state
+                .map
+                .get(&base)
+                .or_else(|| state.map.get(&symbol.to_uppercase()));
+            if let Some(id) = found {
+                return Some(id.clone());
+            }
+        }
+        static_id(self.static_ids, &base).map(str::to_string)
+    }
+
+    /// 確保對照表可用：先讀 DB，超過一週才以 `fetch` 更新並寫回 DB；更新失敗時沿用舊表。
+    /// 讀 DB 與網路請求都在寫鎖之外進行，完成後才取得寫鎖替換對照表。
+    pub async fn refresh<T, F>(
+        &self,
+        db: &DbPool,
+        load: impl FnOnce(&DbPool) -> Result<(Vec<T>, Option<i64>), String>,
+        save: impl FnOnce(&DbPool, &[T], i64) -> DbResult<()>,
+        fetch: F,
+    ) where
+        T: CoinRow,
+        F: Future<Output = Result<Vec<T>, String>>,
+    {
+        let now = chrono::Utc::now().timestamp();
+        {
+            let state = self.state.read().await;
+            if state.is_fresh(now) || state.recently_tried() {
+                return;
+            }
+        }
+
+        let _refresh = self.refresh.lock().await;
+        let needs_load = {
+            let state = self.state.read().await;
+            if state.is_fresh(now) || state.recently_tried() {
+                return;
+            }
+            state.map.is_empty()
+        };
+        if needs_load {
+            match load(db) {
+                Ok((coins, Some(at))) if !coins.is_empty() => {
+                    let map = build_id_index(&coins);
+                    let mut state = self.state.write().await;
+                    state.map = map;
+                    state.refreshed_at = at;
+                    if state.is_fresh(now) {
+                        return;
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("[{}] Failed to load coin list: {}", self.name, e),
+            }
+        }
+
+        self.state.write().await.last_attempt = Some(Instant::now());
+        match fetch.await {
+            Ok(coins) => {
+                if let Err(e) = save(db, &coins, now) {
+                    tracing::warn!("[{}] Failed to save coin list: {}", self.name, e);
+                }
+                let map = build_id_index(&coins);
+                let mut state = self.state.write().await;
+                state.map = map;
+                state.refreshed_at = now;
+                tracing::info!("[{}] Refreshed coin list ({} coins)", self.name, coins.len());
+            }
+            Err(e) => tracing::warn!(
+                "[{}] Coin list refresh failed, keeping {} cached ids: {}",
+                self.name,
+                self.state.read().await.map.len(),
+                e
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coin(id: &str, symbol: &str, rank: Option<i64>) -> CoinPaprikaCoin {
+        CoinPaprikaCoin {
+            id: id.to_string(),
+            symbol: symbol.to_string(),
+            name: id.to_string(),
+            rank,
+        }
+    }
+
+    #[tokio::test]
+    async fn refresh_swaps_in_the_fetched_list_and_skips_while_fresh() {
+        static IDS: &[(&str, &str)] = &[("BTC", "btc-static")];
+        let db = DbPool::open(&std::path::PathBuf::from(":memory:")).unwrap();
+        let index = CoinIdIndex::new("Test", IDS);
+        assert_eq!(index.lookup("BTCUSDT").await.as_deref(), Some("btc-static"));
+
+        index
+            .refresh(
+                &db,
+                DbPool::load_coinpaprika_coins,
+                DbPool::save_coinpaprika_coins,
+                async { Ok(vec![coin("btc-bitcoin", "BTC", Some(1))]) },
+            )
+            .await;
+        assert_eq!(index.lookup("BTCUSDT").await.as_deref(), Some("btc-bitcoin"));
+        assert_eq!(db.load_coinpaprika_coins().unwrap().0.len(), 1);
+
+        // 對照表仍新鮮時不會再呼叫 fetch
+        index
+            .refresh(
+                &db,
+                DbPool::load_coinpaprika_coins,
+                DbPool::save_coinpaprika_coins,
+                async { panic!("fresh index must not refetch") },
+            )
+            .await;
+    }
+}
@@ -1,15 +1,23 @@
+use super::debug::SendCaptured;
 use super::traits::*;
 use super::types::*;
+use std::collections::HashMap;
+
+const DEFAULT_BASE_URL: &str = "https://rest.coinapi.io";
+/// 超過此數量改用一次取回所有資產的 bulk 匯率（整批只扣 1 credit）
+const BULK_THRESHOLD: usize = 3;
 
 pub struct CoinApiProvider {
     client: reqwest::Client,
+    base_url: String,
     api_key: String,
 }
 
 impl CoinApiProvider {
-    pub fn new(api_key: Option<String>) -> Self {
+    pub fn new(api_key: Option<String>, api_url: Option<String>, http: &HttpOptions) -> Self {
         Self {
-            client: shared_client(),
+            client: provider_client(http),
+            base_url: resolve_base_url(api_url, DEFAULT_BASE_URL),
             api_key: api_key.unwrap_or_default(),
         }
     }
@@ -21,6 +29,23 @@ fn to_coinapi_base(symbol: &str) -> String {
     base
 }
 
+/// 解析 `/v1/exchangerate/USD?invert=true` — rates[].rate 為 1 單位資產的 USD 價格
+fn parse_bulk_rates(data: &serde_json::Value) -> HashMap<String, f64> {
+    data["rates"]
+        .as_array()
+        .map(|rates| {
+            rates
+                .iter()
+                .filter_map(|r| {
+                    let asset = r["asset_id_quote"].as_str()?.to_uppercase();
+                    let rate = r["rate"].as_f64().filter(|v| *v > 0.0)?;
+                    Some((asset, rate))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 #[async_trait::async_trait]
 impl DataProvider for CoinApiProvider {
     fn info(&self) -> ProviderInfo {
@@ -32,12 +57,12 @@ impl DataProvider for CoinApiProvider {
             return Err("CoinAPI: requires API key".into());
         }
         let base = to_coinapi_base(symbol);
-        let url = format!("https://rest.coinapi.io/v1/exchangerate/{}/USD", base);
+        let url = format!("{}/v1/exchangerate/{}/USD", self.base_url, base);
         let data: serde_json::Value = self
             .client
             .get(&url)
             .header("X-CoinAPI-Key", &self.api_key)
-            .send()
+            .send_captured("coinapi")
             .await
             .map_err(|e| format!("CoinAPI connection failed: {}", e))?
             .error_for_status()
@@ -61,35 +86,65 @@ impl DataProvider for CoinApiProvider {
             return Err("CoinAPI: requires API key".into());
         }
 
-        // CoinAPI supports batch via /v1/exchangerate/{base} but one at a time
-        // Use concurrent requests with limit
-        use futures::stream::{self, StreamExt};
-        let results: Vec<_> = stream::iter(symbols.to_vec())
-            .map(|sym| {
-                let client = self.client.clone();
-                let key = self.api_key.clone();
-                async move {
-                    let base = to_coinapi_base(&sym);
-                    let url = format!("https://rest.coinapi.io/v1/exchangerate/{}/USD", base);
-                    match client.get(&url).header("X-CoinAPI-Key", &key).send().await {
-                        Ok(resp) => match resp.json::<serde_json::Value>().await {
-                            Ok(data) => {
-                                let price = data["rate"].as_f64().unwrap_or(0.0);
-                                Ok(AssetDataBuilder::new(&sym, "coinapi")
-                                    .price(price)
-                                    .currency("USD")
-                                    .build())
-                            }
-                            Err(e) => Err(format!("CoinAPI parse failed: {}", e)),
-                        },
-                        Err(e) => Err(format!("CoinAPI connection failed: {}", e)),
-                    }
-                }
+        if symbols.len() <= BULK_THRESHOLD {
+            use futures::stream::{self, StreamExt};
+            let results: Vec<_> = stream::iter(symbols.to_vec())
+                .map(|sym| async move { self.fetch_price(&sym).await })
+                .buffer_unordered(2) // Conservative: free tier is limited
+                .collect()
+                .await;
+            return Ok(results.into_iter().filter_map(|r| r.ok()).collect());
+        }
+
+        // 一次取回所有資產對 USD 的匯率，本地比對
+        let url = format!("{}/v1/exchangerate/USD?invert=true", self.base_url);
+        let data: serde_json::Value = self
+            .client
+            .get(&url)
+            .header("X-CoinAPI-Key", &self.api_key)
+            .send_captured("coinapi")
+            .await
+            .map_err(|e| format!("CoinAPI connection failed: {}", e))?
+            .error_for_status()
+            .map_err(|e| format!("CoinAPI API error: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("CoinAPI parse failed: {}", e))?;
+        let rates = parse_bulk_rates(&data);
+
+        Ok(symbols
+            .iter()
+            .filter_map(|sym| {
+                let price = rates.get(&to_coinapi_base(sym).to_uppercase())?;
+                Some(
+                    AssetDataBuilder::new(sym, "coinapi")
+                        .price(*price)
+                        .currency("USD")
+                        .build(),
+                )
             })
-            .buffer_unordered(2) // Conservative: free tier is limited
-            .collect()
-            .await;
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
 
-        Ok(results.into_iter().filter_map(|r| r.ok()).collect())
+    #[test]
+    fn bulk_rates_map_assets_to_usd_price() {
+        let data = json!({
+            "asset_id_base": "USD",
+            "rates": [
+                {"asset_id_quote": "BTC", "rate": 65000.5},
+                {"asset_id_quote": "eth", "rate": 3200.0},
+                {"asset_id_quote": "DEAD", "rate": 0.0}
+            ]
+        });
+        let rates = parse_bulk_rates(&data);
+        assert_eq!(rates.get("BTC"), Some(&65000.5));
+        assert_eq!(rates.get("ETH"), Some(&3200.0));
+        assert!(!rates.contains_key("DEAD"));
     }
 }
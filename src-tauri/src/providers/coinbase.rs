@@ -1,20 +1,25 @@
+use super::debug::SendCaptured;
 use super::traits::*;
 use super::types::*;
 
+const DEFAULT_BASE_URL: &str = "https://api.coinbase.com";
+
 pub struct CoinbaseProvider {
     client: reqwest::Client,
+    base_url: String,
 }
 
 impl Default for CoinbaseProvider {
     fn default() -> Self {
-        Self::new()
+        Self::new(None, &HttpOptions::default())
     }
 }
 
 impl CoinbaseProvider {
-    pub fn new() -> Self {
+    pub fn new(api_url: Option<String>, http: &HttpOptions) -> Self {
         Self {
-            client: shared_client(),
+            client: provider_client(http),
+            base_url: resolve_base_url(api_url, DEFAULT_BASE_URL),
         }
     }
 }
@@ -28,11 +33,11 @@ impl DataProvider for CoinbaseProvider {
     async fn fetch_price(&self, symbol: &str) -> Result<AssetData, String> {
         // Auto-convert: BTCUSDT -> BTC-USD, BTC/USD -> BTC-USD
         let pair = to_coinbase_symbol(symbol);
-        let url = format!("https://api.coinbase.com/v2/prices/{}/spot", pair);
+        let url = format!("{}/v2/prices/{}/spot", self.base_url, pair);
         let data: serde_json::Value = self
             .client
             .get(&url)
-            .send()
+            .send_captured("coinbase")
             .await
             .map_err(|e| format!("Coinbase connection failed: {}", e))?
             .error_for_status()
@@ -68,10 +73,10 @@ impl DataProvider for CoinbaseProvider {
                 let client = self.client.clone();
                 async move {
                     let pair = to_coinbase_symbol(&sym);
-                    let url = format!("https://api.coinbase.com/v2/prices/{}/spot", pair);
+                    let url = format!("{}/v2/prices/{}/spot", self.base_url, pair);
                     match client
                         .get(&url)
-                        .send()
+                        .send_captured("coinbase")
                         .await
                         .and_then(|r| r.error_for_status())
                     {
@@ -101,7 +106,7 @@ impl DataProvider for CoinbaseProvider {
         for r in results {
             match r {
                 Ok(data) => out.push(data),
-                Err(e) => eprintln!("Coinbase skipped: {}", e),
+                Err(e) => tracing::warn!("Coinbase skipped: {}", e),
             }
         }
         Ok(out)
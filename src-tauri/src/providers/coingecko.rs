@@ -1,25 +1,76 @@
+use super::coin_index::{self, CoinIdIndex};
+use super::debug::SendCaptured;
 use super::traits::*;
 use super::types::*;
+use crate::db::{CoinGeckoCoin, DbPool};
 use std::collections::HashMap;
-use std::sync::OnceLock;
-use tokio::sync::RwLock;
+use std::sync::LazyLock;
 
-/// 動態 symbol→CoinGecko ID 快取（從 /coins/list API 載入）
-static COINGECKO_ID_CACHE: OnceLock<RwLock<HashMap<String, String>>> = OnceLock::new();
+/// symbol / ID（大寫）→ CoinGecko ID 對照表，來源為 DB 中的 `coingecko_coins`
+static ID_INDEX: LazyLock<CoinIdIndex> = LazyLock::new(|| CoinIdIndex::new("CoinGecko", STATIC_IDS));
 
-fn id_cache() -> &'static RwLock<HashMap<String, String>> {
-    COINGECKO_ID_CACHE.get_or_init(|| RwLock::new(HashMap::new()))
+/// `/coins/markets` 取前幾頁（每頁 250）的市值排名，用來解決同 symbol 多幣的歧義
+const RANKED_PAGES: u32 = 4;
+
+/// 清單尚未載入（或無法取得）時使用的主流幣對照
+const STATIC_IDS: &[(&str, &str)] = &[
+    ("BTC", "bitcoin"),
+    ("ETH", "ethereum"),
+    ("USDT", "tether"),
+    ("BNB", "binancecoin"),
+    ("SOL", "solana"),
+    ("USDC", "usd-coin"),
+    ("XRP", "ripple"),
+    ("DOGE", "dogecoin"),
+    ("TON", "the-open-network"),
+    ("ADA", "cardano"),
+    ("TRX", "tron"),
+    ("AVAX", "avalanche-2"),
+    ("SHIB", "shiba-inu"),
+    ("DOT", "polkadot"),
+    ("LINK", "chainlink"),
+    ("BCH", "bitcoin-cash"),
+    ("LTC", "litecoin"),
+    ("MATIC", "matic-network"),
+    ("POL", "polygon-ecosystem-token"),
+    ("UNI", "uniswap"),
+    ("ATOM", "cosmos"),
+    ("XLM", "stellar"),
+    ("NEAR", "near"),
+    ("APT", "aptos"),
+    ("ARB", "arbitrum"),
+    ("OP", "optimism"),
+    ("SUI", "sui"),
+    ("PEPE", "pepe"),
+    ("BONK", "bonk"),
+    ("WIF", "dogwifcoin"),
+];
+
+/// 內建主流幣對照的反查：CoinGecko ID → ticker
+pub(crate) fn static_symbol(id: &str) -> Option<&'static str> {
+    STATIC_IDS
+        .iter()
+        .find(|(_, coin_id)| *coin_id == id)
+        .map(|(symbol, _)| *symbol)
+}
+
+pub(crate) fn static_id(base: &str) -> Option<&'static str> {
+    coin_index::static_id(STATIC_IDS, base)
 }
 
+const DEFAULT_BASE_URL: &str = "https://api.coingecko.com";
+
 pub struct CoinGeckoProvider {
     client: reqwest::Client,
+    base_url: String,
     api_key: Option<String>,
 }
 
 impl CoinGeckoProvider {
-    pub fn new(api_key: Option<String>) -> Self {
+    pub fn new(api_key: Option<String>, api_url: Option<String>, http: &HttpOptions) -> Self {
         Self {
-            client: shared_client(),
+            client: provider_client(http),
+            base_url: resolve_base_url(api_url, DEFAULT_BASE_URL),
             api_key,
         }
     }
@@ -34,24 +85,24 @@ impl CoinGeckoProvider {
         req
     }
 
-    /// 載入 CoinGecko /coins/list 並建立 symbol(大寫) → id 對照表
-    async fn ensure_id_cache(&self) -> Result<(), String> {
-        {
-            let cache = id_cache().read().await;
-            if !cache.is_empty() {
-                return Ok(());
-            }
-        }
-
+    /// 從 `/coins/list` 取得完整清單，並以 `/coins/markets` 前幾頁補上市值排名
+    async fn fetch_coin_list(&self) -> Result<Vec<CoinGeckoCoin>, String> {
         #[derive(serde::Deserialize)]
         struct CoinListItem {
             id: String,
             symbol: String,
+            #[serde(default)]
+            name: String,
+        }
+        #[derive(serde::Deserialize)]
+        struct MarketItem {
+            id: String,
+            market_cap_rank: Option<i64>,
         }
 
         let items: Vec<CoinListItem> = self
-            .build_request("https://api.coingecko.com/api/v3/coins/list")
-            .send()
+            .build_request(&format!("{}/api/v3/coins/list", self.base_url))
+            .send_captured("coingecko")
             .await
             .map_err(|e| format!("CoinGecko coins/list connection failed: {}", e))?
             .error_for_status()
@@ -60,47 +111,44 @@ impl CoinGeckoProvider {
             .await
             .map_err(|e| format!("CoinGecko coins/list parse failed: {}", e))?;
 
-        let mut map = HashMap::with_capacity(items.len());
-        for item in &items {
-            let key = item.symbol.to_uppercase();
-            // 相同 symbol 取 id 最短的（通常是最主流的幣）
-            let replace = match map.get(&key) {
-                Some(existing_id) => item.id.len() < String::len(existing_id),
-                None => true,
+        // 排名只用於消歧義，取不到（例如 rate limit）就以已取得的頁數為準
+        let mut ranks: HashMap<String, i64> = HashMap::new();
+        for page in 1..=RANKED_PAGES {
+            let url = format!(
+                "{}/api/v3/coins/markets?vs_currency=usd&order=market_cap_desc&per_page=250&page={}",
+                self.base_url, page
+            );
+            let markets: Vec<MarketItem> = match self.build_request(&url).send_captured("coingecko").await {
+                Ok(resp) if resp.status().is_success() => match resp.json().await {
+                    Ok(m) => m,
+                    Err(_) => break,
+                },
+                _ => break,
             };
-            if replace {
-                map.insert(key, item.id.clone());
-            }
-        }
-        // 也用 id 本身作為 key，讓使用者可以直接輸入 CoinGecko ID
-        for item in &items {
-            map.entry(item.id.to_uppercase())
-                .or_insert_with(|| item.id.clone());
+            ranks.extend(
+                markets
+                    .into_iter()
+                    .filter_map(|m| m.market_cap_rank.map(|r| (m.id, r))),
+            );
         }
 
-        *id_cache().write().await = map;
-        Ok(())
+        Ok(items
+            .into_iter()
+            .map(|item| CoinGeckoCoin {
+                market_cap_rank: ranks.get(&item.id).copied(),
+                id: item.id,
+                symbol: item.symbol,
+                name: item.name,
+            })
+            .collect())
     }
 
-    /// 將 symbol 轉換成 CoinGecko ID（動態查表）
+    /// 將 symbol 轉換成 CoinGecko ID：動態對照表 → 內建主流幣對照 → 小寫原字串
     async fn resolve_id(&self, symbol: &str) -> String {
-        let (base, _) = parse_crypto_symbol(symbol);
-
-        // 嘗試從快取查找
-        if let Ok(()) = self.ensure_id_cache().await {
-            let cache = id_cache().read().await;
-            if let Some(id) = cache.get(&base) {
-                return id.clone();
-            }
-            // 也嘗試原始輸入（使用者可能直接輸入 CoinGecko ID 如 "bitcoin"）
-            let upper = symbol.to_uppercase();
-            if let Some(id) = cache.get(&upper) {
-                return id.clone();
-            }
-        }
-
-        // 快取 miss 或載入失敗：fallback 用 lowercase
-        symbol.to_lowercase()
+        ID_INDEX
+            .lookup(symbol)
+            .await
+            .unwrap_or_else(|| symbol.to_lowercase())
     }
 
     fn parse_coin(
@@ -129,16 +177,29 @@ impl DataProvider for CoinGeckoProvider {
         provider_info_or_panic("coingecko")
     }
 
+    /// 確保 symbol → ID 對照表可用：先讀 DB，超過一週才向 CoinGecko 更新並寫回 DB
+    async fn prepare(&self, db: &DbPool) {
+        ID_INDEX
+            .refresh(
+                db,
+                DbPool::load_coingecko_coins,
+                DbPool::save_coingecko_coins,
+                self.fetch_coin_list(),
+            )
+            .await;
+    }
+
     async fn fetch_price(&self, symbol: &str) -> Result<AssetData, String> {
         let coin_id = self.resolve_id(symbol).await;
         let url = format!(
-            "https://api.coingecko.com/api/v3/simple/price?ids={}&vs_currencies=usd&include_24hr_vol=true&include_24hr_change=true&include_market_cap=true",
+            "{}/api/v3/simple/price?ids={}&vs_currencies=usd&include_24hr_vol=true&include_24hr_change=true&include_market_cap=true",
+            self.base_url,
             coin_id
         );
 
         let data: serde_json::Value = self
             .build_request(&url)
-            .send()
+            .send_captured("coingecko")
             .await
             .map_err(|e| format!("CoinGecko connection failed: {}", e))?
             .error_for_status()
@@ -175,13 +236,14 @@ impl DataProvider for CoinGeckoProvider {
         let ids_str = ids.join(",");
 
         let url = format!(
-            "https://api.coingecko.com/api/v3/simple/price?ids={}&vs_currencies=usd&include_24hr_vol=true&include_24hr_change=true&include_market_cap=true",
+            "{}/api/v3/simple/price?ids={}&vs_currencies=usd&include_24hr_vol=true&include_24hr_change=true&include_market_cap=true",
+            self.base_url,
             ids_str
         );
 
         let data: serde_json::Value = self
             .build_request(&url)
-            .send()
+            .send_captured("coingecko")
             .await
             .map_err(|e| format!("CoinGecko batch connection failed: {}", e))?
             .error_for_status()
@@ -199,9 +261,45 @@ impl DataProvider for CoinGeckoProvider {
         for (symbol, coin_id) in &mappings {
             match Self::parse_coin(symbol, coin_id, &data[coin_id]) {
                 Ok(asset) => results.push(asset),
-                Err(e) => eprintln!("CoinGecko batch skipped {}: {}", symbol, e),
+                Err(e) => tracing::warn!("CoinGecko batch skipped {}: {}", symbol, e),
             }
         }
         Ok(results)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::coin_index::build_id_index;
+
+    fn coin(id: &str, symbol: &str, rank: Option<i64>) -> CoinGeckoCoin {
+        CoinGeckoCoin {
+            id: id.to_string(),
+            symbol: symbol.to_string(),
+            name: id.to_string(),
+            market_cap_rank: rank,
+        }
+    }
+
+    #[test]
+    fn ambiguous_symbols_prefer_market_cap_rank_then_shortest_id() {
+        let index = build_id_index(&[
+            coin("eth-fake", "eth", None),
+            coin("ethereum", "eth", Some(2)),
+            coin("ethereum-wormhole", "eth", Some(900)),
+            coin("pepe-token-x", "pepe", None),
+            coin("pepe-x", "pepe", None),
+        ]);
+        assert_eq!(index["ETH"], "ethereum");
+        assert_eq!(index["PEPE"], "pepe-x");
+        // 也可以直接以 CoinGecko ID 查詢
+        assert_eq!(index["ETH-FAKE"], "eth-fake");
+    }
+
+    #[test]
+    fn static_map_covers_majors() {
+        assert_eq!(static_id("BTC"), Some("bitcoin"));
+        assert_eq!(static_id("NOPE"), None);
+    }
+}
@@ -1,15 +1,20 @@
+use super::debug::SendCaptured;
 use super::traits::*;
 use super::types::*;
 
+const DEFAULT_BASE_URL: &str = "https://pro-api.coinmarketcap.com";
+
 pub struct CoinMarketCapProvider {
     client: reqwest::Client,
+    base_url: String,
     api_key: Option<String>,
 }
 
 impl CoinMarketCapProvider {
-    pub fn new(api_key: Option<String>) -> Self {
+    pub fn new(api_key: Option<String>, api_url: Option<String>, http: &HttpOptions) -> Self {
         Self {
-            client: shared_client(),
+            client: provider_client(http),
+            base_url: resolve_base_url(api_url, DEFAULT_BASE_URL),
             api_key,
         }
     }
@@ -47,14 +52,15 @@ impl DataProvider for CoinMarketCapProvider {
         let api_key = self.api_key.as_ref().ok_or("CoinMarketCap requires API key")?;
         let base = to_base_symbol(symbol);
         let url = format!(
-            "https://pro-api.coinmarketcap.com/v1/cryptocurrency/quotes/latest?symbol={}",
+            "{}/v1/cryptocurrency/quotes/latest?symbol={}",
+            self.base_url,
             base
         );
         let data: serde_json::Value = self
             .client
             .get(&url)
             .header("X-CMC_PRO_API_KEY", api_key)
-            .send()
+            .send_captured("coinmarketcap")
             .await
             .map_err(|e| format!("CMC connection failed: {}", e))?
             .error_for_status()
@@ -84,14 +90,15 @@ impl DataProvider for CoinMarketCapProvider {
         let syms = bases.join(",");
 
         let url = format!(
-            "https://pro-api.coinmarketcap.com/v1/cryptocurrency/quotes/latest?symbol={}",
+            "{}/v1/cryptocurrency/quotes/latest?symbol={}",
+            self.base_url,
             syms
         );
         let resp = self
             .client
             .get(&url)
             .header("X-CMC_PRO_API_KEY", api_key)
-            .send()
+            .send_captured("coinmarketcap")
             .await
             .map_err(|e| format!("CMC batch connection failed: {}", e))?
             .error_for_status()
@@ -109,7 +116,7 @@ impl DataProvider for CoinMarketCapProvider {
         for (symbol, base) in &mappings {
             match Self::parse_coin(symbol, base, &data) {
                 Ok(asset) => results.push(asset),
-                Err(e) => eprintln!("CMC batch skipping {}: {}", symbol, e),
+                Err(e) => tracing::warn!("CMC batch skipping {}: {}", symbol, e),
             }
         }
         Ok(results)
@@ -1,52 +1,83 @@
+use super::coin_index::CoinIdIndex;
+use super::debug::SendCaptured;
 use super::traits::*;
 use super::types::*;
-use std::collections::HashMap;
-use std::sync::OnceLock;
-use tokio::sync::RwLock;
-
-/// 動態 symbol(大寫) → CoinPaprika ID 快取（從 /v1/coins API 載入）
-static COINPAPRIKA_ID_CACHE: OnceLock<RwLock<HashMap<String, String>>> = OnceLock::new();
-
-fn id_cache() -> &'static RwLock<HashMap<String, String>> {
-    COINPAPRIKA_ID_CACHE.get_or_init(|| RwLock::new(HashMap::new()))
-}
+use crate::db::{CoinPaprikaCoin, DbPool};
+use futures::stream::{self, StreamExt};
+use std::sync::LazyLock;
+
+/// symbol / ID（大寫）→ CoinPaprika ID 對照表，來源為 DB 中的 `coinpaprika_coins`
+static ID_INDEX: LazyLock<CoinIdIndex> = LazyLock::new(|| CoinIdIndex::new("CoinPaprika", STATIC_IDS));
+
+/// 批量查詢時同時進行的 /v1/tickers/{id} 請求數
+const BATCH_CONCURRENCY: usize = 4;
+
+/// 清單尚未載入（或無法取得）時使用的主流幣對照
+const STATIC_IDS: &[(&str, &str)] = &[
+    ("BTC", "btc-bitcoin"),
+    ("ETH", "eth-ethereum"),
+    ("USDT", "usdt-tether"),
+    ("BNB", "bnb-binance-coin"),
+    ("SOL", "sol-solana"),
+    ("USDC", "usdc-usd-coin"),
+    ("XRP", "xrp-xrp"),
+    ("DOGE", "doge-dogecoin"),
+    ("TON", "ton-toncoin"),
+    ("ADA", "ada-cardano"),
+    ("TRX", "trx-tron"),
+    ("AVAX", "avax-avalanche"),
+    ("SHIB", "shib-shiba-inu"),
+    ("DOT", "dot-polkadot"),
+    ("LINK", "link-chainlink"),
+    ("BCH", "bch-bitcoin-cash"),
+    ("LTC", "ltc-litecoin"),
+    ("MATIC", "matic-polygon"),
+    ("UNI", "uni-uniswap"),
+    ("ATOM", "atom-cosmos"),
+    ("XLM", "xlm-stellar"),
+    ("NEAR", "near-near-protocol"),
+    ("APT", "apt-aptos"),
+    ("ARB", "arb-arbitrum"),
+    ("OP", "op-optimism"),
+];
+
+const DEFAULT_BASE_URL: &str = "https://api.coinpaprika.com";
 
 pub struct CoinPaprikaProvider {
     client: reqwest::Client,
+    base_url: String,
 }
 
 impl Default for CoinPaprikaProvider {
     fn default() -> Self {
-        Self::new()
+        Self::new(None, &HttpOptions::default())
     }
 }
 
 impl CoinPaprikaProvider {
-    pub fn new() -> Self {
+    pub fn new(api_url: Option<String>, http: &HttpOptions) -> Self {
         Self {
-            client: shared_client(),
+            client: provider_client(http),
+            base_url: resolve_base_url(api_url, DEFAULT_BASE_URL),
         }
     }
 
-    /// 載入 /v1/coins 並建立 symbol(大寫) → id 對照表
-    async fn ensure_id_cache(&self) -> Result<(), String> {
-        {
-            if !id_cache().read().await.is_empty() {
-                return Ok(());
-            }
-        }
-
+    /// 從 /v1/coins 取得完整 coin 清單
+    async fn fetch_coin_list(&self) -> Result<Vec<CoinPaprikaCoin>, String> {
         #[derive(serde::Deserialize)]
         struct CoinItem {
             id: String,
             symbol: String,
-            rank: u64,
+            #[serde(default)]
+            name: String,
+            #[serde(default)]
+            rank: i64,
         }
 
         let items: Vec<CoinItem> = self
             .client
-            .get("https://api.coinpaprika.com/v1/coins")
-            .send()
+            .get(format!("{}/v1/coins", self.base_url))
+            .send_captured("coinpaprika")
             .await
             .map_err(|e| format!("CoinPaprika coins connection failed: {}", e))?
             .error_for_status()
@@ -55,47 +86,51 @@ impl CoinPaprikaProvider {
             .await
             .map_err(|e| format!("CoinPaprika coins parse failed: {}", e))?;
 
-        let mut map: HashMap<String, (String, u64)> = HashMap::with_capacity(items.len());
-        for item in &items {
-            let key = item.symbol.to_uppercase();
-            // 相同 symbol 取 rank 最低的（rank=1 是最主流的幣）
-            let rank = if item.rank == 0 { u64::MAX } else { item.rank };
-            let replace = match map.get(&key) {
-                Some((_, existing_rank)) => rank < *existing_rank,
-                None => true,
-            };
-            if replace {
-                map.insert(key, (item.id.clone(), rank));
-            }
+        Ok(items
+            .into_iter()
+            .map(|item| CoinPaprikaCoin {
+                id: item.id,
+                symbol: item.symbol,
+                name: item.name,
+                // rank=0 表示未排名
+                rank: (item.rank > 0).then_some(item.rank),
+            })
+            .collect())
+    }
+
+    /// 將 symbol 轉換成 CoinPaprika ID：動態對照表 → 內建主流幣對照 → 看起來像 ID 的原始輸入
+    async fn resolve_id(&self, symbol: &str) -> Result<String, String> {
+        if let Some(id) = ID_INDEX.lookup(symbol).await {
+            return Ok(id);
         }
-        // 也把 id 本身作為 key，讓使用者可以直接輸入 CoinPaprika ID
-        for item in &items {
-            map.entry(item.id.to_uppercase())
-                .or_insert_with(|| (item.id.clone(), item.rank));
+        // CoinPaprika ID 皆為 "{symbol}-{name}" 格式；其他輸入無法猜出正確 ID
+        if symbol.contains('-') {
+            return Ok(symbol.to_lowercase());
         }
-
-        *id_cache().write().await = map.into_iter().map(|(k, (v, _))| (k, v)).collect();
-        Ok(())
+        Err(format!("CoinPaprika: unknown symbol {}", symbol))
     }
 
-    /// 將 symbol 轉換成 CoinPaprika ID（動態查表）
-    async fn resolve_id(&self, symbol: &str) -> String {
-        let (base, _) = parse_crypto_symbol(symbol);
-
-        if let Ok(()) = self.ensure_id_cache().await {
-            let cache = id_cache().read().await;
-            if let Some(id) = cache.get(&base) {
-                return id.clone();
-            }
-            // 也嘗試原始輸入（使用者可能直接輸入 id 如 "btc-bitcoin"）
-            let upper = symbol.to_uppercase();
-            if let Some(id) = cache.get(&upper) {
-                return id.clone();
-            }
-        }
+    async fn fetch_ticker(&self, symbol: &str) -> Result<AssetData, String> {
+        let id = self.resolve_id(symbol).await?;
+        let url = format!("{}/v1/tickers/{}", self.base_url, id);
+        let data: serde_json::Value = self
+            .client
+            .get(&url)
+            .send_captured("coinpaprika")
+            .await
+            .map_err(|e| format!("CoinPaprika connection failed: {}", e))?
+            .error_for_status()
+            .map_err(|e| {
+                format!(
+                    "CoinPaprika API error: {} (query ID: {}, please verify symbol)",
+                    e, id
+                )
+            })?
+            .json()
+            .await
+            .map_err(|e| format!("CoinPaprika parse failed: {}", e))?;
 
-        // fallback: 猜測 id 格式
-        format!("{0}-{0}", base.to_lowercase())
+        Ok(parse_paprika_ticker(symbol, &data))
     }
 }
 
@@ -125,27 +160,20 @@ impl DataProvider for CoinPaprikaProvider {
         provider_info_or_panic("coinpaprika")
     }
 
-    async fn fetch_price(&self, symbol: &str) -> Result<AssetData, String> {
-        let id = self.resolve_id(symbol).await;
-        let url = format!("https://api.coinpaprika.com/v1/tickers/{}", id);
-        let data: serde_json::Value = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| format!("CoinPaprika connection failed: {}", e))?
-            .error_for_status()
-            .map_err(|e| {
-                format!(
-                    "CoinPaprika API error: {} (query ID: {}, please verify symbol)",
-                    e, id
-                )
-            })?
-            .json()
-            .await
-            .map_err(|e| format!("CoinPaprika parse failed: {}", e))?;
+    /// 確保 symbol → ID 對照表可用：先讀 DB，超過一週才向 CoinPaprika 更新並寫回 DB
+    async fn prepare(&self, db: &DbPool) {
+        ID_INDEX
+            .refresh(
+                db,
+                DbPool::load_coinpaprika_coins,
+                DbPool::save_coinpaprika_coins,
+                self.fetch_coin_list(),
+            )
+            .await;
+    }
 
-        Ok(parse_paprika_ticker(symbol, &data))
+    async fn fetch_price(&self, symbol: &str) -> Result<AssetData, String> {
+        self.fetch_ticker(symbol).await
     }
 
     async fn fetch_prices(&self, symbols: &[String]) -> Result<Vec<AssetData>, String> {
@@ -156,53 +184,48 @@ impl DataProvider for CoinPaprikaProvider {
             return self.fetch_price(&symbols[0]).await.map(|d| vec![d]);
         }
 
-        // CoinPaprika /tickers 一次回傳所有幣的行情
-        let arr: Vec<serde_json::Value> = self
-            .client
-            .get("https://api.coinpaprika.com/v1/tickers")
-            .send()
-            .await
-            .map_err(|e| format!("CoinPaprika batch connection failed: {}", e))?
-            .error_for_status()
-            .map_err(|e| format!("CoinPaprika batch API error: {}", e))?
-            .json()
-            .await
-            .map_err(|e| format!("CoinPaprika batch parse failed: {}", e))?;
-
-        // 建立 id → ticker 索引
-        let mut id_map: HashMap<String, &serde_json::Value> = HashMap::new();
-        // 建立 symbol(大寫) → ticker 索引（同 symbol 取 rank 最低者）
-        let mut sym_map: HashMap<String, &serde_json::Value> = HashMap::new();
-        for item in &arr {
-            if let Some(id) = item["id"].as_str() {
-                id_map.insert(id.to_string(), item);
-            }
-            if let Some(sym) = item["symbol"].as_str() {
-                let key = sym.to_uppercase();
-                let rank = item["rank"].as_u64().unwrap_or(u64::MAX);
-                let replace = match sym_map.get(&key) {
-                    Some(existing) => rank < existing["rank"].as_u64().unwrap_or(u64::MAX),
-                    None => true,
-                };
-                if replace {
-                    sym_map.insert(key, item);
-                }
-            }
-        }
+        // /v1/tickers 全量回應有數 MB，改以 ID 逐一查詢並限制同時請求數
+        let results: Vec<Result<AssetData, String>> = stream::iter(symbols.to_vec())
+            .map(|sym| async move { self.fetch_ticker(&sym).await })
+            .buffer_unordered(BATCH_CONCURRENCY)
+            .collect()
+            .await;
 
         let mut out = Vec::new();
-        for sym in symbols {
-            let (base, _) = parse_crypto_symbol(sym);
-            // 先嘗試 symbol，再嘗試輸入作為 id
-            let ticker = sym_map
-                .get(&base)
-                .or_else(|| id_map.get(&sym.to_lowercase()));
-            if let Some(item) = ticker {
-                out.push(parse_paprika_ticker(sym, item));
-            } else {
-                eprintln!("CoinPaprika not found: {}", sym);
+        for r in results {
+            match r {
+                Ok(data) => out.push(data),
+                Err(e) => tracing::warn!("CoinPaprika skipped: {}", e),
             }
         }
         Ok(out)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::coin_index::build_id_index;
+
+    fn coin(id: &str, symbol: &str, rank: Option<i64>) -> CoinPaprikaCoin {
+        CoinPaprikaCoin {
+            id: id.to_string(),
+            symbol: symbol.to_string(),
+            name: id.to_string(),
+            rank,
+        }
+    }
+
+    #[test]
+    fn ambiguous_symbols_prefer_rank_then_shortest_id() {
+        let index = build_id_index(&[
+            coin("btc-bitcoin-fork", "BTC", None),
+            coin("btc-bitcoin", "BTC", Some(1)),
+            coin("uni-unicorn-token", "UNI", None),
+            coin("uni-unicorn", "UNI", None),
+        ]);
+        assert_eq!(index["BTC"], "btc-bitcoin");
+        assert_eq!(index["UNI"], "uni-unicorn");
+        assert_eq!(index["BTC-BITCOIN-FORK"], "btc-bitcoin-fork");
+    }
+}
@@ -1,15 +1,20 @@
+use super::debug::SendCaptured;
 use super::traits::*;
 use super::types::*;
 
+const DEFAULT_BASE_URL: &str = "https://min-api.cryptocompare.com";
+
 pub struct CryptoCompareProvider {
     client: reqwest::Client,
+    base_url: String,
     api_key: Option<String>,
 }
 
 impl CryptoCompareProvider {
-    pub fn new(api_key: Option<String>) -> Self {
+    pub fn new(api_key: Option<String>, api_url: Option<String>, http: &HttpOptions) -> Self {
         Self {
-            client: shared_client(),
+            client: provider_client(http),
+            base_url: resolve_base_url(api_url, DEFAULT_BASE_URL),
             api_key,
         }
     }
@@ -53,13 +58,14 @@ impl DataProvider for CryptoCompareProvider {
     async fn fetch_price(&self, symbol: &str) -> Result<AssetData, String> {
         let base = to_base_symbol(symbol);
         let url = format!(
-            "https://min-api.cryptocompare.com/data/pricemultifull?fsyms={}&tsyms=USD",
+            "{}/data/pricemultifull?fsyms={}&tsyms=USD",
+            self.base_url,
             base
         );
 
         let data: serde_json::Value = self
             .build_request(&url)
-            .send()
+            .send_captured("cryptocompare")
             .await
             .map_err(|e| format!("CryptoCompare connection failed: {}", e))?
             .error_for_status()
@@ -89,13 +95,14 @@ impl DataProvider for CryptoCompareProvider {
         let fsyms = bases.join(",");
 
         let url = format!(
-            "https://min-api.cryptocompare.com/data/pricemultifull?fsyms={}&tsyms=USD",
+            "{}/data/pricemultifull?fsyms={}&tsyms=USD",
+            self.base_url,
             fsyms
         );
 
         let resp = self
             .build_request(&url)
-            .send()
+            .send_captured("cryptocompare")
             .await
             .map_err(|e| format!("CryptoCompare batch connection failed: {}", e))?
             .error_for_status()
@@ -113,7 +120,7 @@ impl DataProvider for CryptoCompareProvider {
         for (symbol, base) in &mappings {
             match Self::parse_coin(symbol, base, &data) {
                 Ok(asset) => results.push(asset),
-                Err(e) => eprintln!("CryptoCompare batch skipping {}: {}", symbol, e),
+                Err(e) => tracing::warn!("CryptoCompare batch skipping {}: {}", symbol, e),
             }
         }
         Ok(results)
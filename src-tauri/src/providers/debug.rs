@@ -0,0 +1,263 @@
+//! Provider 原始回應擷取（除錯用）。
+//!
+//! 對某個 provider 開啟 capture 後，經由 [`SendCaptured::send_captured`] 發出的請求
+//! 會把原始回應（status、headers、body）存進該 provider 的 ring buffer，
+//! 最多保留 [`CAPTURE_CAPACITY`] 筆。開關只存在記憶體中，重啟後自動關閉。
+//!
+//! 不少 provider 把 API key 放在 query string，存入前會移除 [`SECRET_QUERY_PARAMS`]；
+//! 回應的 `Set-Cookie` 與認證相關 header 也不保留。
+
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::sync::{LazyLock, RwLock};
+use std::time::Instant;
+
+use serde::Serialize;
+
+use crate::i18n::{tr, Msg};
+
+/// 每個 provider 保留的回應筆數
+pub const CAPTURE_CAPACITY: usize = 20;
+
+/// 單筆 body 最多保留的位元組數（超過會截斷）
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+/// 帶 API key 的 query 參數名稱（不分大小寫）
+pub const SECRET_QUERY_PARAMS: &[&str] = &["apikey", "api_key", "api_token", "token", "access_key"];
+
+/// 不保留的回應 header
+const SECRET_HEADERS: &[&str] = &["set-cookie", "authorization", "proxy-authorization", "www-authenticate"];
+
+/// key 存在 = 該 provider 已開啟 capture
+static CAPTURES: LazyLock<RwLock<HashMap<String, VecDeque<CapturedResponse>>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// 一筆擷取到的 HTTP 回應
+#[derive(Debug, Clone, Serialize)]
+pub struct CapturedResponse {
+    /// Unix 毫秒
+    pub timestamp: i64,
+    pub method: String,
+    pub url: String,
+    /// 連線失敗時為 None（見 `error`）
+    pub status: Option<u16>,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+    pub truncated: bool,
+    pub elapsed_ms: u64,
+    pub error: Option<String>,
+}
+
+/// `get_provider_debug` 的回傳值
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderDebugInfo {
+    pub provider_id: String,
+    pub enabled: bool,
+    /// 新 → 舊
+    pub responses: Vec<CapturedResponse>,
+}
+
+/// 開啟／關閉某個 provider 的回應擷取；關閉時一併清空已擷取的內容
+pub fn set_capture(provider_id: &str, enabled: bool) -> Result<(), String> {
+    if !super::PROVIDER_INFO_MAP.contains_key(provider_id) {
+        return Err(tr(Msg::ProviderNotFound, &[&provider_id]));
+    }
+    let mut captures = CAPTURES.write().unwrap_or_else(|e| e.into_inner());
+    if enabled {
+        captures.entry(provider_id.to_string()).or_default();
+    } else {
+        captures.remove(provider_id);
+    }
+    Ok(())
+}
+
+pub fn is_capturing(provider_id: &str) -> bool {
+    CAPTURES
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .contains_key(provider_id)
+}
+
+pub fn debug_info(provider_id: &str) -> ProviderDebugInfo {
+    let captures = CAPTURES.read().unwrap_or_else(|e| e.into_inner());
+    let responses = captures.get(provider_id);
+    ProviderDebugInfo {
+        provider_id: provider_id.to_string(),
+        enabled: responses.is_some(),
+        responses: responses
+            .map(|r| r.iter().rev().cloned().collect())
+            .unwrap_or_default(),
+    }
+}
+
+/// 是否為帶 API key 的 query 參數
+pub fn is_secret_param(name: &str) -> bool {
+    SECRET_QUERY_PARAMS.iter().any(|p| p.eq_ignore_ascii_case(name))
+}
+
+/// 移除帶 API key 的 query 參數
+fn redacted_url(url: &reqwest::Url) -> String {
+    let mut url = url.clone();
+    let kept: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(k, _)| !is_secret_param(k))
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    if kept.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(kept);
+    }
+    url.to_string()
+}
+
+fn record(provider_id: &str, entry: CapturedResponse) {
+    let mut captures = CAPTURES.write().unwrap_or_else(|e| e.into_inner());
+    // capture 可能在請求途中被關閉 — 此時直接丟棄
+    if let Some(buf) = captures.get_mut(provider_id) {
+        if buf.len() >= CAPTURE_CAPACITY {
+            buf.pop_front();
+        }
+        buf.push_back(entry);
+    }
+}
+
+/// `RequestBuilder::send` 的替代品：provider 開啟 capture 時記錄原始回應，
+/// 未開啟時行為與 `send()` 完全相同
+pub trait SendCaptured {
+    fn send_captured(
+        self,
+        provider_id: &'static str,
+    ) -> impl Future<Output = reqwest::Result<reqwest::Response>> + Send;
+}
+
+impl SendCaptured for reqwest::RequestBuilder {
+    async fn send_captured(self, provider_id: &'static str) -> reqwest::Result<reqwest::Response> {
+        if !is_capturing(provider_id) {
+            return self.send().await;
+        }
+
+        let (method, url) = match self.try_clone().and_then(|b| b.build().ok()) {
+            Some(req) => (req.method().to_string(), redacted_url(req.url())),
+            None => (String::new(), String::new()),
+        };
+        let started = Instant::now();
+        let timestamp = chrono::Utc::now().timestamp_millis();
+
+        let resp = match self.send().await {
+            Ok(resp) => resp,
+            Err(e) => {
+                record(
+                    provider_id,
+                    CapturedResponse {
+                        timestamp,
+                        method,
+                        url,
+                        status: None,
+                        headers: Vec::new(),
+                        body: String::new(),
+                        truncated: false,
+                        elapsed_ms: started.elapsed().as_millis() as u64,
+                        // reqwest 錯誤訊息含完整 URL
+                        error: Some(match e.url() {
+                            Some(u) => e.to_string().replace(u.as_str(), &redacted_url(u)),
+                            None => e.to_string(),
+                        }),
+                    },
+                );
+                return Err(e);
+            }
+        };
+
+        let status = resp.status();
+        let version = resp.version();
+        let headers = resp.headers().clone();
+        let bytes = resp.bytes().await?;
+
+        let truncated = bytes.len() > MAX_BODY_BYTES;
+        record(
+            provider_id,
+            CapturedResponse {
+                timestamp,
+                method,
+                url,
+                status: Some(status.as_u16()),
+                headers: headers
+                    .iter()
+                    .filter(|(k, _)| !SECRET_HEADERS.contains(&k.as_str()))
+                    .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or("<binary>").to_string()))
+                    .collect(),
+                body: String::from_utf8_lossy(&bytes[..bytes.len().min(MAX_BODY_BYTES)]).into_owned(),
+                truncated,
+                elapsed_ms: started.elapsed().as_millis() as u64,
+                error: None,
+            },
+        );
+
+        // body 已被讀出，重建一個等價的 Response 交回 provider 照常解析
+        let mut rebuilt = http::Response::new(bytes);
+        *rebuilt.status_mut() = status;
+        *rebuilt.version_mut() = version;
+        *rebuilt.headers_mut() = headers;
+        Ok(reqwest::Response::from(rebuilt))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::get, Router};
+
+    #[tokio::test]
+    async fn captures_raw_response_and_preserves_body() {
+        let app = Router::new().route("/ticker", get(|| async { "{\"price\":1.5}" }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = reqwest::Client::new();
+        let url = format!("http://{}/ticker", addr);
+
+        // 未開啟時不記錄
+        client.get(&url).send_captured("kraken").await.unwrap();
+        assert!(debug_info("kraken").responses.is_empty());
+
+        set_capture("kraken", true).unwrap();
+        let resp = client.get(&url).send_captured("kraken").await.unwrap();
+        let data: serde_json::Value = resp.json().await.unwrap();
+        assert_eq!(data["price"], 1.5);
+
+        let info = debug_info("kraken");
+        assert!(info.enabled);
+        assert_eq!(info.responses.len(), 1);
+        assert_eq!(info.responses[0].status, Some(200));
+        assert_eq!(info.responses[0].body, "{\"price\":1.5}");
+        assert_eq!(info.responses[0].url, url);
+
+        set_capture("kraken", false).unwrap();
+        assert!(!debug_info("kraken").enabled);
+        assert!(set_capture("no_such_provider", true).is_err());
+    }
+
+    #[tokio::test]
+    async fn strips_api_keys_and_cookies() {
+        let app = Router::new().route(
+            "/v2/aggs/ticker/AAPL/prev",
+            get(|| async { ([("set-cookie", "session=abc"), ("x-request-id", "42")], "{}") }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        set_capture("polygon", true).unwrap();
+        let url = format!("http://{}/v2/aggs/ticker/AAPL/prev?adjusted=true&apiKey=pk_secret_123", addr);
+        reqwest::Client::new().get(&url).send_captured("polygon").await.unwrap();
+
+        let entry = debug_info("polygon").responses.remove(0);
+        set_capture("polygon", false).unwrap();
+        assert!(!serde_json::to_string(&entry).unwrap().contains("pk_secret_123"));
+        assert_eq!(entry.url, format!("http://{}/v2/aggs/ticker/AAPL/prev?adjusted=true", addr));
+        assert!(entry.headers.iter().all(|(k, _)| k != "set-cookie"));
+        assert!(entry.headers.iter().any(|(k, _)| k == "x-request-id"));
+    }
+}
@@ -0,0 +1,142 @@
+//! 衍生品持倉資料 — 訂閱的 `provider_params` 設定 `{"derivatives": true}` 時，
+//! Binance / Bybit / OKX 在現貨報價之外另查詢同交易對 USDT 本位永續合約的未平倉量與多空帳戶比，
+//! 寫入 `extra` 的 `open_interest`（幣量）、`open_interest_usd`、`long_short_ratio`。
+//!
+//! 持倉資料查詢失敗不影響價格；開啟紀錄時 `open_interest` / `long_short_ratio` 一併寫入 price_history。
+
+use std::collections::HashMap;
+use std::future::Future;
+
+use super::types::{AssetData, ProviderParams};
+
+pub const OPEN_INTEREST: &str = "open_interest";
+pub const OPEN_INTEREST_USD: &str = "open_interest_usd";
+pub const LONG_SHORT_RATIO: &str = "long_short_ratio";
+
+/// 同時查詢持倉資料的 symbol 數
+const CONCURRENCY: usize = 3;
+
+/// 單一合約的持倉資料
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Positioning {
+    /// 未平倉量（base 幣量）
+    pub open_interest: Option<f64>,
+    /// 未平倉名目價值（USD）；交易所未提供時以現價換算
+    pub open_interest_usd: Option<f64>,
+    /// 多空帳戶比（做多帳戶數 / 做空帳戶數）
+    pub long_short_ratio: Option<f64>,
+}
+
+impl Positioning {
+    fn apply(self, data: &mut AssetData) {
+        let open_interest_usd = self
+            .open_interest_usd
+            .or_else(|| self.open_interest.filter(|_| data.price > 0.0).map(|oi| oi * data.price));
+        let fields = [
+            (OPEN_INTEREST, self.open_interest),
+            (OPEN_INTEREST_USD, open_interest_usd),
+            (LONG_SHORT_RATIO, self.long_short_ratio),
+        ];
+        for (key, value) in fields {
+            if let Some(v) = value.filter(|v| v.is_finite()) {
+                data.extra
+                    .get_or_insert_with(HashMap::new)
+                    .insert(key.to_string(), serde_json::json!(v));
+            }
+        }
+    }
+}
+
+/// 訂閱是否要求衍生品持倉資料
+pub fn is_flagged(params: &HashMap<String, ProviderParams>, symbol: &str) -> bool {
+    params.get(symbol).and_then(|p| p.get("derivatives")).and_then(|v| v.as_bool()) == Some(true)
+}
+
+/// 交易所回應中的數值（字串或數字）
+pub fn num(v: &serde_json::Value) -> Option<f64> {
+    v.as_f64().or_else(|| v.as_str().and_then(|s| s.parse().ok()))
+}
+
+/// 讀取 `extra` 中要寫入 price_history 的持倉欄位：(open_interest, long_short_ratio)
+pub fn record_fields(data: &AssetData) -> (Option<f64>, Option<f64>) {
+    let field = |key: &str| data.extra.as_ref().and_then(|e| e.get(key)).and_then(|v| v.as_f64());
+    (field(OPEN_INTEREST), field(LONG_SHORT_RATIO))
+}
+
+/// 為帶 derivatives 參數的報價補上持倉資料；`fetch` 以訂閱 symbol 查詢，失敗時只記錄警告
+pub async fn enrich<F, Fut>(results: &mut [AssetData], params: &HashMap<String, ProviderParams>, fetch: F)
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<Positioning, String>>,
+{
+    use futures::stream::{self, StreamExt};
+
+    let flagged: Vec<String> = results
+        .iter()
+        .filter(|d| is_flagged(params, &d.symbol))
+        .map(|d| d.symbol.clone())
+        .collect();
+    if flagged.is_empty() {
+        return;
+    }
+
+    let fetched: Vec<(String, Result<Positioning, String>)> = stream::iter(flagged)
+        .map(|sym| {
+            let fut = fetch(sym.clone());
+            async move { (sym, fut.await) }
+        })
+        .buffer_unordered(CONCURRENCY)
+        .collect()
+        .await;
+    for (sym, result) in fetched {
+        match result {
+            Ok(positioning) => {
+                for data in results.iter_mut().filter(|d| d.symbol == sym) {
+                    positioning.apply(data);
+                }
+            }
+            Err(e) => tracing::warn!("[Derivatives] {} positioning skipped: {}", sym, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::types::AssetDataBuilder;
+
+    fn params(symbol: &str, json: &str) -> HashMap<String, ProviderParams> {
+        HashMap::from([(symbol.to_string(), serde_json::from_str(json).unwrap())])
+    }
+
+    #[tokio::test]
+    async fn only_flagged_symbols_get_positioning() {
+        let mut results = vec![
+            AssetDataBuilder::new("BTCUSDT", "binance").price(60000.0).build(),
+            AssetDataBuilder::new("ETHUSDT", "binance").price(3000.0).build(),
+        ];
+        let params = params("BTCUSDT", r#"{"derivatives": true}"#);
+        enrich(&mut results, &params, |_| async {
+            Ok(Positioning {
+                open_interest: Some(2.0),
+                open_interest_usd: None,
+                long_short_ratio: Some(1.25),
+            })
+        })
+        .await;
+
+        let extra = results[0].extra.as_ref().unwrap();
+        assert_eq!(extra[OPEN_INTEREST_USD], serde_json::json!(120000.0));
+        assert_eq!(record_fields(&results[0]), (Some(2.0), Some(1.25)));
+        assert!(results[1].extra.is_none());
+    }
+
+    #[tokio::test]
+    async fn failed_lookup_leaves_price_untouched() {
+        let mut results = vec![AssetDataBuilder::new("BTC-USDT", "okx").price(1.0).build()];
+        let params = params("BTC-USDT", r#"{"derivatives": true}"#);
+        enrich(&mut results, &params, |_| async { Err("no swap".to_string()) }).await;
+        assert_eq!(record_fields(&results[0]), (None, None));
+        assert!(!is_flagged(&params, "ETH-USDT"));
+    }
+}
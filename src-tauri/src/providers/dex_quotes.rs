@@ -0,0 +1,141 @@
+//! DEX 聚合器詢價比較 — 同一筆 swap 同時詢問所有支援該鏈的聚合器（[`DEX_QUOTER_IDS`]），
+//! 依換得數量排序，供使用者比較目前哪個聚合器的報價最好。
+//!
+//! 只詢問已設定 API key 的 provider；未設定或詢價失敗的 provider 列在 `errors`。
+
+use serde::Serialize;
+
+use super::types::{DexQuote, HttpOptions};
+use super::{create_dex_quoter, DEX_QUOTER_IDS};
+use crate::db::DbPool;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DexQuoteError {
+    pub provider_id: String,
+    pub error: String,
+}
+
+/// 比較結果；`quotes` 依換得數量由多到少排序，第一筆即最佳報價
+#[derive(Debug, Clone, Serialize)]
+pub struct DexQuoteComparison {
+    pub chain: String,
+    pub token_in: String,
+    pub token_out: String,
+    pub amount: f64,
+    pub quotes: Vec<DexQuote>,
+    pub errors: Vec<DexQuoteError>,
+}
+
+fn rank(quotes: &mut [DexQuote]) {
+    quotes.sort_by(|a, b| b.amount_out.total_cmp(&a.amount_out));
+}
+
+/// 以 `amount` 個完整 token_in 同時向各聚合器詢價
+pub async fn compare_dex_quotes(
+    db: &DbPool,
+    chain: &str,
+    token_in: &str,
+    token_out: &str,
+    amount: f64,
+) -> Result<DexQuoteComparison, String> {
+    let (chain, token_in, token_out) = (chain.trim(), token_in.trim(), token_out.trim());
+    if chain.is_empty() || token_in.is_empty() || token_out.is_empty() {
+        return Err("Chain, token_in and token_out are required".to_string());
+    }
+    if !amount.is_finite() || amount <= 0.0 {
+        return Err("Amount must be a positive number".to_string());
+    }
+
+    let mut errors = Vec::new();
+    let mut quoters = Vec::new();
+    for id in DEX_QUOTER_IDS {
+        let settings = db.get_provider_settings(id).ok().flatten();
+        let api_key = settings.as_ref().and_then(|s| s.api_key.clone()).filter(|k| !k.is_empty());
+        let api_url = settings.as_ref().and_then(|s| s.api_url.clone());
+        let http = settings.as_ref().map(HttpOptions::from).unwrap_or_default();
+        let Some(quoter) = create_dex_quoter(id, api_key.clone(), api_url, &http) else {
+            continue;
+        };
+        if !quoter.supports_chain(chain) {
+            continue;
+        }
+        if api_key.is_none() {
+            errors.push(DexQuoteError {
+                provider_id: id.to_string(),
+                error: "API key not configured".to_string(),
+            });
+            continue;
+        }
+        quoters.push((id, quoter));
+    }
+    if quoters.is_empty() && errors.is_empty() {
+        return Err(format!("No DEX aggregator supports chain '{}'", chain));
+    }
+
+    let results = futures::future::join_all(
+        quoters
+            .iter()
+            .map(|(_, quoter)| quoter.quote(chain, token_in, token_out, amount)),
+    )
+    .await;
+
+    let mut quotes = Vec::new();
+    for ((id, _), result) in quoters.iter().zip(results) {
+        match result {
+            Ok(quote) => quotes.push(quote),
+            Err(error) => errors.push(DexQuoteError {
+                provider_id: id.to_string(),
+                error,
+            }),
+        }
+    }
+    rank(&mut quotes);
+
+    Ok(DexQuoteComparison {
+        chain: chain.to_string(),
+        token_in: token_in.to_string(),
+        token_out: token_out.to_string(),
+        amount,
+        quotes,
+        errors,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn quote(provider_id: &str, amount_out: f64) -> DexQuote {
+        DexQuote {
+            provider_id: provider_id.to_string(),
+            amount_out,
+            price: amount_out,
+            price_impact: None,
+            gas_estimate: None,
+            route: None,
+        }
+    }
+
+    #[test]
+    fn best_quote_comes_first() {
+        let mut quotes = vec![quote("okx_dex", 99.5), quote("jupiter", 100.2)];
+        rank(&mut quotes);
+        assert_eq!(quotes[0].provider_id, "jupiter");
+    }
+
+    #[tokio::test]
+    async fn unconfigured_aggregators_are_reported_without_querying() {
+        let db = DbPool::open(&PathBuf::from(":memory:")).unwrap();
+        let result = compare_dex_quotes(&db, "solana", "SOL", "USDC", 1.0).await.unwrap();
+        assert!(result.quotes.is_empty());
+        let ids: Vec<&str> = result.errors.iter().map(|e| e.provider_id.as_str()).collect();
+        assert_eq!(ids, ["jupiter", "okx_dex"]);
+
+        // 只有 OKX DEX 支援 EVM 鏈
+        let result = compare_dex_quotes(&db, "eth", "ETH", "USDC", 1.0).await.unwrap();
+        assert_eq!(result.errors.len(), 1);
+        assert!(compare_dex_quotes(&db, "tron", "TRX", "USDT", 1.0).await.is_err());
+        assert!(compare_dex_quotes(&db, "solana", "SOL", "USDC", 0.0).await.is_err());
+    }
+}
@@ -1,16 +1,73 @@
+use super::debug::SendCaptured;
 use super::traits::*;
 use super::types::*;
 use std::collections::HashMap;
+use std::sync::{LazyLock, RwLock};
+
+const DEFAULT_BASE_URL: &str = "https://eodhd.com";
+pub const DEFAULT_EXCHANGE: &str = "US";
+
+/// 未帶交易所後綴的 symbol 自動補上的交易所代碼（由 app setting `eodhd_default_exchange` 控制）
+static EXCHANGE: LazyLock<RwLock<String>> =
+    LazyLock::new(|| RwLock::new(DEFAULT_EXCHANGE.to_string()));
+
+pub fn set_default_exchange(exchange: &str) {
+    let exchange = normalize_exchange(exchange).unwrap_or_else(|_| DEFAULT_EXCHANGE.to_string());
+    *EXCHANGE.write().unwrap() = exchange;
+}
+
+pub fn default_exchange() -> String {
+    EXCHANGE.read().unwrap().clone()
+}
+
+/// 驗證交易所代碼（如 US、LSE、TO、CC），回傳大寫
+pub fn normalize_exchange(exchange: &str) -> Result<String, String> {
+    let code = exchange.trim().to_uppercase();
+    if code.is_empty() || code.len() > 10 || !code.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(format!("Invalid EODHD exchange code: '{}'", exchange));
+    }
+    Ok(code)
+}
+
+/// AAPL → AAPL.US；已帶後綴（AAPL.US、BTC-USD.CC）的原樣大寫
+fn to_eodhd_symbol(symbol: &str, exchange: &str) -> String {
+    let s = symbol.trim().to_uppercase();
+    if s.contains('.') {
+        s
+    } else {
+        format!("{}.{}", s, exchange)
+    }
+}
+
+/// 依 response 的 code 比對回原始 symbol；code 可能帶或不帶交易所後綴
+fn match_quotes(symbols: &[String], exchange: &str, arr: &[serde_json::Value]) -> Vec<AssetData> {
+    let mut response_map: HashMap<String, &serde_json::Value> = HashMap::new();
+    for v in arr {
+        if let Some(code) = v["code"].as_str() {
+            response_map.insert(to_eodhd_symbol(code, exchange), v);
+        }
+    }
+
+    symbols
+        .iter()
+        .filter_map(|sym| {
+            let data = response_map.get(&to_eodhd_symbol(sym, exchange))?;
+            Some(EODHDProvider::parse_eod(sym, data))
+        })
+        .collect()
+}
 
 pub struct EODHDProvider {
     client: reqwest::Client,
+    base_url: String,
     api_key: Option<String>,
 }
 
 impl EODHDProvider {
-    pub fn new(api_key: Option<String>) -> Self {
+    pub fn new(api_key: Option<String>, api_url: Option<String>, http: &HttpOptions) -> Self {
         Self {
-            client: shared_client(),
+            client: provider_client(http),
+            base_url: resolve_base_url(api_url, DEFAULT_BASE_URL),
             api_key,
         }
     }
@@ -37,14 +94,16 @@ impl DataProvider for EODHDProvider {
 
     async fn fetch_price(&self, symbol: &str) -> Result<AssetData, String> {
         let api_key = self.api_key.as_ref().ok_or("EODHD requires API key")?;
+        let api_symbol = to_eodhd_symbol(symbol, &default_exchange());
 
         let data: serde_json::Value = self
             .client
             .get(format!(
-                "https://eodhd.com/api/real-time/{}?api_token={}&fmt=json",
-                symbol, api_key
+                "{}/api/real-time/{}?api_token={}&fmt=json",
+                self.base_url,
+                api_symbol, api_key
             ))
-            .send()
+            .send_captured("eodhd")
             .await
             .map_err(|e| format!("EODHD connection failed: {}", e))?
             .error_for_status()
@@ -66,18 +125,26 @@ impl DataProvider for EODHDProvider {
         }
 
         let api_key = self.api_key.as_ref().ok_or("EODHD requires API key")?;
-        let extra = symbols[1..].join(",");
+        let exchange = default_exchange();
+        let mut api_symbols: Vec<String> = Vec::new();
+        for sym in symbols {
+            let api_symbol = to_eodhd_symbol(sym, &exchange);
+            if !api_symbols.contains(&api_symbol) {
+                api_symbols.push(api_symbol);
+            }
+        }
 
         // EODHD batch: first symbol in path, rest in s= param
         let url = format!(
-            "https://eodhd.com/api/real-time/{}?api_token={}&fmt=json&s={}",
-            symbols[0], api_key, extra
+            "{}/api/real-time/{}?api_token={}&fmt=json&s={}",
+            self.base_url,
+            api_symbols[0], api_key, api_symbols[1..].join(",")
         );
 
         let resp = self
             .client
             .get(&url)
-            .send()
+            .send_captured("eodhd")
             .await
             .map_err(|e| format!("EODHD batch connection failed: {}", e))?
             .error_for_status()
@@ -88,28 +155,54 @@ impl DataProvider for EODHDProvider {
             .await
             .map_err(|e| format!("EODHD batch read failed: {}", e))?;
 
-        let arr: Vec<serde_json::Value> = serde_json::from_str(&body)
-            .map_err(|_| "EODHD batch parse failed (possibly invalid symbol)".to_string())?;
+        // 去重後只剩一個 symbol 時 EODHD 回傳單一物件而非陣列
+        let arr: Vec<serde_json::Value> = match serde_json::from_str(&body) {
+            Ok(serde_json::Value::Array(arr)) => arr,
+            Ok(obj @ serde_json::Value::Object(_)) => vec![obj],
+            _ => return Err("EODHD batch parse failed (possibly invalid symbol)".to_string()),
+        };
+
+        Ok(match_quotes(symbols, &exchange, &arr))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
 
-        let response_map: HashMap<String, &serde_json::Value> = arr
+    #[test]
+    fn default_exchange_suffix_is_applied_once() {
+        assert_eq!(to_eodhd_symbol("aapl", "US"), "AAPL.US");
+        assert_eq!(to_eodhd_symbol("VOD.LSE", "US"), "VOD.LSE");
+        assert_eq!(to_eodhd_symbol("BTC-USD.CC", "US"), "BTC-USD.CC");
+        assert_eq!(normalize_exchange(" lse ").unwrap(), "LSE");
+        assert!(normalize_exchange("U.S").is_err());
+    }
+
+    #[test]
+    fn mixed_format_symbols_match_response_codes() {
+        let arr = vec![
+            json!({"code": "AAPL.US", "close": 190.0}),
+            json!({"code": "VOD.LSE", "close": 70.0}),
+            json!({"code": "MSFT", "close": 410.0}),
+        ];
+        let symbols: Vec<String> = ["AAPL", "aapl.us", "VOD.LSE", "MSFT.US", "TSLA"]
             .iter()
-            .filter_map(|v| v["code"].as_str().map(|s| (s.to_uppercase(), v)))
+            .map(|s| s.to_string())
             .collect();
-
-        let mut results = Vec::new();
-        for sym in symbols {
-            // EODHD code 可能是 AAPL.US 或 AAPL
-            let key = sym.to_uppercase();
-            if let Some(data) = response_map.get(&key) {
-                results.push(Self::parse_eod(sym, data));
-            } else {
-                // 嘗試不帶交易所後綴
-                let base = key.split('.').next().unwrap_or(&key);
-                if let Some(data) = response_map.get(base) {
-                    results.push(Self::parse_eod(sym, data));
-                }
-            }
-        }
-        Ok(results)
+        let prices: Vec<(String, f64)> = match_quotes(&symbols, "US", &arr)
+            .into_iter()
+            .map(|d| (d.symbol, d.price))
+            .collect();
+        assert_eq!(
+            prices,
+            vec![
+                ("AAPL".to_string(), 190.0),
+                ("aapl.us".to_string(), 190.0),
+                ("VOD.LSE".to_string(), 70.0),
+                ("MSFT.US".to_string(), 410.0),
+            ]
+        );
     }
 }
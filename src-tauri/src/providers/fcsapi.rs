@@ -1,15 +1,20 @@
+use super::debug::SendCaptured;
 use super::traits::*;
 use super::types::*;
 
+const DEFAULT_BASE_URL: &str = "https://api-v4.fcsapi.com";
+
 pub struct FcsApiProvider {
     client: reqwest::Client,
+    base_url: String,
     api_key: String,
 }
 
 impl FcsApiProvider {
-    pub fn new(api_key: Option<String>) -> Self {
+    pub fn new(api_key: Option<String>, api_url: Option<String>, http: &HttpOptions) -> Self {
         Self {
-            client: shared_client(),
+            client: provider_client(http),
+            base_url: resolve_base_url(api_url, DEFAULT_BASE_URL),
             api_key: api_key.unwrap_or_default(),
         }
     }
@@ -29,14 +34,15 @@ impl DataProvider for FcsApiProvider {
             return Err("FCS API requires API key".into());
         }
         let url = format!(
-            "https://api-v4.fcsapi.com/stock/latest?symbol={}&access_key={}",
+            "{}/stock/latest?symbol={}&access_key={}",
+            self.base_url,
             symbol.to_uppercase(),
             self.api_key
         );
         let data: serde_json::Value = self
             .client
             .get(&url)
-            .send()
+            .send_captured("fcsapi")
             .await
             .map_err(|e| format!("FCS API connection failed: {}", e))?
             .error_for_status()
@@ -68,13 +74,14 @@ impl DataProvider for FcsApiProvider {
             .collect::<Vec<_>>()
             .join(",");
         let url = format!(
-            "https://api-v4.fcsapi.com/stock/latest?symbol={}&access_key={}",
+            "{}/stock/latest?symbol={}&access_key={}",
+            self.base_url,
             syms, self.api_key
         );
         let data: serde_json::Value = self
             .client
             .get(&url)
-            .send()
+            .send_captured("fcsapi")
             .await
             .map_err(|e| format!("FCS API batch connection failed: {}", e))?
             .error_for_status()
@@ -1,18 +1,106 @@
+use super::binance::BinanceProvider;
+use super::debug::SendCaptured;
 use super::traits::*;
 use super::types::*;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const DEFAULT_BASE_URL: &str = "https://finnhub.io";
+/// 免費方案每分鐘 60 次呼叫
+const CALLS_PER_MINUTE: usize = 60;
+const BUDGET_WINDOW: Duration = Duration::from_secs(60);
+
+/// 每分鐘呼叫額度（滑動視窗）；收到 429 時視為整個視窗用盡
+struct MinuteBudget {
+    calls: VecDeque<Instant>,
+    exhausted_until: Option<Instant>,
+}
+
+impl MinuteBudget {
+    fn new() -> Self {
+        Self {
+            calls: VecDeque::new(),
+            exhausted_until: None,
+        }
+    }
+
+    /// 還有額度時記錄一次呼叫並回傳 true
+    fn try_acquire(&mut self, now: Instant) -> bool {
+        if self.exhausted_until.is_some_and(|until| now < until) {
+            return false;
+        }
+        while self
+            .calls
+            .front()
+            .is_some_and(|t| now.duration_since(*t) >= BUDGET_WINDOW)
+        {
+            self.calls.pop_front();
+        }
+        if self.calls.len() >= CALLS_PER_MINUTE {
+            return false;
+        }
+        self.calls.push_back(now);
+        true
+    }
+
+    fn mark_exhausted(&mut self, now: Instant) {
+        self.exhausted_until = Some(now + BUDGET_WINDOW);
+    }
+}
 
 pub struct FinnhubProvider {
     client: reqwest::Client,
+    base_url: String,
     api_key: Option<String>,
+    budget: Mutex<MinuteBudget>,
+    /// 額度用盡時 crypto 改由 Binance 查詢
+    binance: BinanceProvider,
+}
+
+/// Auto-convert crypto symbols: BTCUSDT -> BINANCE:BTCUSDT, BTC-USD -> BINANCE:BTCUSDT
+fn to_api_symbol(symbol: &str) -> String {
+    if symbol.contains(':') {
+        // Already in exchange:symbol format
+        return symbol.to_string();
+    }
+    let s = symbol.to_uppercase();
+    let looks_crypto =
+        s.ends_with("USDT") || s.ends_with("USD") || s.contains('-') || s.contains('/');
+    if looks_crypto {
+        format!("BINANCE:{}", to_binance_symbol(symbol))
+    } else {
+        symbol.to_string()
+    }
 }
 
 impl FinnhubProvider {
-    pub fn new(api_key: Option<String>) -> Self {
+    pub fn new(api_key: Option<String>, api_url: Option<String>, http: &HttpOptions) -> Self {
         Self {
-            client: shared_client(),
+            client: provider_client(http),
+            base_url: resolve_base_url(api_url, DEFAULT_BASE_URL),
             api_key,
+            budget: Mutex::new(MinuteBudget::new()),
+            binance: BinanceProvider::new(None, None, &HttpOptions::default()),
         }
     }
+
+    /// 額度用盡時的退路：Binance 上的 crypto 改查 Binance，其餘回報錯誤
+    async fn fetch_fallback(&self, symbol: &str, api_symbol: &str) -> Result<AssetData, String> {
+        let Some(binance_symbol) = api_symbol.strip_prefix("BINANCE:") else {
+            return Err(format!(
+                "Finnhub: per-minute quota exhausted, skipped {}",
+                symbol
+            ));
+        };
+        let mut data = self.binance.fetch_price(binance_symbol).await?;
+        data.symbol = symbol.to_string();
+        data.provider_id = "finnhub".to_string();
+        data.extra
+            .get_or_insert_with(Default::default)
+            .insert("source".to_string(), serde_json::json!("binance"));
+        Ok(data)
+    }
 }
 
 #[async_trait::async_trait]
@@ -23,32 +111,27 @@ impl DataProvider for FinnhubProvider {
 
     async fn fetch_price(&self, symbol: &str) -> Result<AssetData, String> {
         let api_key = self.api_key.as_ref().ok_or("Finnhub requires API key")?;
+        let api_symbol = to_api_symbol(symbol);
 
-        // Auto-convert crypto symbols: BTCUSDT -> BINANCE:BTCUSDT, BTC-USD -> BINANCE:BTCUSDT
-        let api_symbol = if symbol.contains(':') {
-            // Already in exchange:symbol format
-            symbol.to_string()
-        } else {
-            let s = symbol.to_uppercase();
-            let looks_crypto =
-                s.ends_with("USDT") || s.ends_with("USD") || s.contains('-') || s.contains('/');
-            if looks_crypto {
-                let binance_sym = to_binance_symbol(symbol);
-                format!("BINANCE:{}", binance_sym)
-            } else {
-                symbol.to_string()
-            }
-        };
+        if !self.budget.lock().unwrap().try_acquire(Instant::now()) {
+            return self.fetch_fallback(symbol, &api_symbol).await;
+        }
 
-        let data: serde_json::Value = self
+        let resp = self
             .client
             .get(format!(
-                "https://finnhub.io/api/v1/quote?symbol={}&token={}",
+                "{}/api/v1/quote?symbol={}&token={}",
+                self.base_url,
                 api_symbol, api_key
             ))
-            .send()
+            .send_captured("finnhub")
             .await
-            .map_err(|e| format!("Finnhub connection failed: {}", e))?
+            .map_err(|e| format!("Finnhub connection failed: {}", e))?;
+        if resp.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            self.budget.lock().unwrap().mark_exhausted(Instant::now());
+            return self.fetch_fallback(symbol, &api_symbol).await;
+        }
+        let data: serde_json::Value = resp
             .error_for_status()
             .map_err(|e| format!("Finnhub API error: {}", e))?
             .json()
@@ -75,7 +158,8 @@ impl DataProvider for FinnhubProvider {
             .build())
     }
 
-    /// 限流並行查詢 — Finnhub 沒有批量 endpoint，限制同時 3 個 request
+    /// 限流並行查詢 — Finnhub 沒有批量 endpoint，限制同時 3 個 request，
+    /// 每次呼叫都先扣每分鐘額度
     async fn fetch_prices(&self, symbols: &[String]) -> Result<Vec<AssetData>, String> {
         if symbols.is_empty() {
             return Ok(vec![]);
@@ -83,58 +167,13 @@ impl DataProvider for FinnhubProvider {
         if symbols.len() == 1 {
             return self.fetch_price(&symbols[0]).await.map(|d| vec![d]);
         }
-
-        let api_key = self.api_key.as_ref().ok_or("Finnhub requires API key")?.clone();
-        let client = self.client.clone();
+        if self.api_key.is_none() {
+            return Err("Finnhub requires API key".into());
+        }
 
         use futures::stream::{self, StreamExt};
         let results: Vec<_> = stream::iter(symbols.to_vec())
-            .map(|sym| {
-                let c = client.clone();
-                let key = api_key.clone();
-                async move {
-                    let api_symbol = if sym.contains(':') {
-                        sym.clone()
-                    } else {
-                        let s = sym.to_uppercase();
-                        let looks_crypto = s.ends_with("USDT")
-                            || s.ends_with("USD")
-                            || s.contains('-')
-                            || s.contains('/');
-                        if looks_crypto {
-                            format!("BINANCE:{}", to_binance_symbol(&sym))
-                        } else {
-                            sym.clone()
-                        }
-                    };
-                    let data: serde_json::Value = c
-                        .get(format!(
-                            "https://finnhub.io/api/v1/quote?symbol={}&token={}",
-                            api_symbol, key
-                        ))
-                        .send()
-                        .await
-                        .map_err(|e| format!("Finnhub: {}", e))?
-                        .error_for_status()
-                        .map_err(|e| format!("Finnhub API error: {}", e))?
-                        .json()
-                        .await
-                        .map_err(|e| format!("Finnhub: {}", e))?;
-                    let price = data["c"].as_f64().unwrap_or(0.0);
-                    if price == 0.0 {
-                        return Err(format!("Finnhub not found: {}", sym));
-                    }
-                    Ok(AssetDataBuilder::new(&sym, "finnhub")
-                        .price(price)
-                        .change_24h(data["d"].as_f64())
-                        .change_percent_24h(data["dp"].as_f64())
-                        .high_24h(data["h"].as_f64())
-                        .low_24h(data["l"].as_f64())
-                        .extra_f64("open_price", data["o"].as_f64())
-                        .extra_f64("prev_close", data["pc"].as_f64())
-                        .build())
-                }
-            })
+            .map(|sym| async move { self.fetch_price(&sym).await })
             .buffer_unordered(3)
             .collect()
             .await;
@@ -143,9 +182,38 @@ impl DataProvider for FinnhubProvider {
         for r in results {
             match r {
                 Ok(data) => out.push(data),
-                Err(e) => eprintln!("Finnhub skipped: {}", e),
+                Err(e) => tracing::warn!("Finnhub skipped: {}", e),
             }
         }
         Ok(out)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn minute_budget_caps_calls_per_window() {
+        let start = Instant::now();
+        let mut budget = MinuteBudget::new();
+        for _ in 0..CALLS_PER_MINUTE {
+            assert!(budget.try_acquire(start));
+        }
+        assert!(!budget.try_acquire(start + Duration::from_secs(59)));
+        assert!(budget.try_acquire(start + BUDGET_WINDOW));
+
+        // 429 之後整個視窗不再呼叫
+        let later = start + Duration::from_secs(120);
+        budget.mark_exhausted(later);
+        assert!(!budget.try_acquire(later + Duration::from_secs(30)));
+        assert!(budget.try_acquire(later + BUDGET_WINDOW));
+    }
+
+    #[test]
+    fn crypto_symbols_route_to_binance() {
+        assert_eq!(to_api_symbol("BTC-USD"), "BINANCE:BTCUSDT");
+        assert_eq!(to_api_symbol("AAPL"), "AAPL");
+        assert_eq!(to_api_symbol("OANDA:EUR_USD"), "OANDA:EUR_USD");
+    }
+}
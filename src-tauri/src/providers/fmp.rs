@@ -1,16 +1,21 @@
+use super::debug::SendCaptured;
 use super::traits::*;
 use super::types::*;
 use std::collections::HashMap;
 
+const DEFAULT_BASE_URL: &str = "https://financialmodelingprep.com";
+
 pub struct FMPProvider {
     client: reqwest::Client,
+    base_url: String,
     api_key: Option<String>,
 }
 
 impl FMPProvider {
-    pub fn new(api_key: Option<String>) -> Self {
+    pub fn new(api_key: Option<String>, api_url: Option<String>, http: &HttpOptions) -> Self {
         Self {
-            client: shared_client(),
+            client: provider_client(http),
+            base_url: resolve_base_url(api_url, DEFAULT_BASE_URL),
             api_key,
         }
     }
@@ -63,39 +68,16 @@ impl FMPProvider {
             builder = builder.extra_str("market_session", Some("REGULAR"));
         }
 
-        // 盤前數據
-        if let Some(pp) = pre_price {
-            builder = builder.extra_f64("pre_market_price", Some(pp));
-            let pre_change = pp - price;
-            let pre_pct = if price > 0.0 {
-                (pre_change / price) * 100.0
-            } else {
-                0.0
-            };
-            builder = builder.extra_f64("pre_market_change", Some(pre_change));
-            builder = builder.extra_f64(
-                "pre_market_change_pct",
-                q["preMarketChangePercent"].as_f64().or(Some(pre_pct)),
-            );
-        }
-
-        // 盤後數據
-        if let Some(pp) = post_price {
-            builder = builder.extra_f64("post_market_price", Some(pp));
-            let post_change = pp - price;
-            let post_pct = if price > 0.0 {
-                (post_change / price) * 100.0
-            } else {
-                0.0
-            };
-            builder = builder.extra_f64("post_market_change", Some(post_change));
-            builder = builder.extra_f64(
-                "post_market_change_pct",
-                q["afterHoursChangePercent"].as_f64().or(Some(post_pct)),
-            );
-        }
-
-        builder.build()
+        builder
+            .pre_market(pre_price.map(|pp| {
+                SessionQuote::against(pp, Some(price))
+                    .with_change_percent(q["preMarketChangePercent"].as_f64())
+            }))
+            .post_market(post_price.map(|pp| {
+                SessionQuote::against(pp, Some(price))
+                    .with_change_percent(q["afterHoursChangePercent"].as_f64())
+            }))
+            .build()
     }
 }
 
@@ -112,10 +94,11 @@ impl DataProvider for FMPProvider {
         let data: serde_json::Value = self
             .client
             .get(format!(
-                "https://financialmodelingprep.com/api/v3/quote/{}?apikey={}",
+                "{}/api/v3/quote/{}?apikey={}",
+                self.base_url,
                 api_symbol, api_key
             ))
-            .send()
+            .send_captured("fmp")
             .await
             .map_err(|e| format!("FMP connection failed: {}", e))?
             .error_for_status()
@@ -151,10 +134,11 @@ impl DataProvider for FMPProvider {
         let resp = self
             .client
             .get(format!(
-                "https://financialmodelingprep.com/api/v3/quote/{}?apikey={}",
+                "{}/api/v3/quote/{}?apikey={}",
+                self.base_url,
                 syms_str, api_key
             ))
-            .send()
+            .send_captured("fmp")
             .await
             .map_err(|e| format!("FMP batch connection failed: {}", e))?
             .error_for_status()
@@ -1,6 +1,12 @@
+use super::debug::SendCaptured;
 use super::traits::*;
 use super::types::*;
+use crate::db::{DbPool, TokenMetadata};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
 
 /// Jupiter — Solana DEX 聚合器
 ///
@@ -9,15 +15,68 @@ use std::collections::HashMap;
 ///
 /// DEX symbol 格式: auto:<inputMint>:<outputMint>
 /// 需要 API Key（在 portal.jup.ag 免費申請）
+const DEFAULT_BASE_URL: &str = "https://api.jup.ag";
+
+/// token_metadata 表中 Solana token 的 chain 值
+const CHAIN: &str = "solana";
+/// verified token list 每天更新一次；失敗後一小時內不重試
+const TOKEN_LIST_REFRESH_SECS: i64 = 24 * 3600;
+const TOKEN_LIST_RETRY_AFTER: Duration = Duration::from_secs(3600);
+const TOKEN_LIST_REFRESHED_AT_KEY: &str = "jupiter_token_list_refreshed_at";
+
+/// 是否載入 Jupiter verified token list（由 app setting `jupiter_token_list` 控制）
+static TOKEN_LIST_ENABLED: AtomicBool = AtomicBool::new(false);
+
+pub fn set_token_list_enabled(enabled: bool) {
+    TOKEN_LIST_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn token_list_enabled() -> bool {
+    TOKEN_LIST_ENABLED.load(Ordering::Relaxed)
+}
+
+/// mint → token metadata 的記憶體快取，來源為 DB 的 `token_metadata` 表
+static TOKENS: LazyLock<RwLock<TokenCache>> = LazyLock::new(|| RwLock::new(TokenCache::default()));
+
+#[derive(Default)]
+struct TokenCache {
+    tokens: HashMap<String, TokenMetadata>,
+    /// 是否已從 DB 載入
+    loaded: bool,
+    /// 查詢時新發現、尚未寫入 DB 的 token（下次 `prepare` 寫入）
+    pending: Vec<TokenMetadata>,
+    /// verified token list 的更新時間（unix 秒）
+    list_refreshed_at: i64,
+    list_attempt: Option<Instant>,
+}
+
+/// 解析 Tokens API v2 回應：`[{ "id": mint, "symbol": ..., "decimals": ... }]`
+fn parse_token_list(json: &serde_json::Value) -> Vec<TokenMetadata> {
+    json.as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|t| {
+            Some(TokenMetadata {
+                chain: CHAIN.to_string(),
+                address: t["id"].as_str()?.to_string(),
+                symbol: t["symbol"].as_str().map(str::to_string),
+                decimals: u32::try_from(t["decimals"].as_u64()?).ok()?,
+            })
+        })
+        .collect()
+}
+
 pub struct JupiterProvider {
     client: reqwest::Client,
+    base_url: String,
     api_key: Option<String>,
 }
 
 impl JupiterProvider {
-    pub fn new(api_key: Option<String>) -> Self {
+    pub fn new(api_key: Option<String>, api_url: Option<String>, http: &HttpOptions) -> Self {
         Self {
-            client: shared_client(),
+            client: provider_client(http),
+            base_url: resolve_base_url(api_url, DEFAULT_BASE_URL),
             api_key,
         }
     }
@@ -42,6 +101,40 @@ impl JupiterProvider {
         Ok((input_mint, output_mint))
     }
 
+    /// 下載 Jupiter verified token list
+    async fn fetch_token_list(&self) -> Result<Vec<TokenMetadata>, String> {
+        let url = format!("{}/tokens/v2/tag?query=verified", self.base_url);
+        let mut req = self.client.get(&url);
+        if let Some(api_key) = self.api_key.as_deref() {
+            req = req.header("x-api-key", api_key);
+        }
+        let json: serde_json::Value = req
+            .send_captured("jupiter")
+            .await
+            .map_err(|e| format!("Jupiter token list connection failed: {}", e))?
+            .error_for_status()
+            .map_err(|e| format!("Jupiter token list API error: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("Jupiter token list parse failed: {}", e))?;
+        Ok(parse_token_list(&json))
+    }
+
+    /// mint 的顯示名稱：常見 token → 快取的 token metadata → mint 本身
+    async fn token_symbol(mint: &str) -> String {
+        let known = mint_to_symbol(mint);
+        if known != mint {
+            return known.to_string();
+        }
+        TOKENS
+            .read()
+            .await
+            .tokens
+            .get(mint)
+            .and_then(|t| t.symbol.clone())
+            .unwrap_or_else(|| mint.to_string())
+    }
+
     /// 用 Quote API 取得報價
     async fn fetch_quote(
         &self,
@@ -55,14 +148,15 @@ impl JupiterProvider {
             .ok_or_else(|| "Jupiter requires API key (free at portal.jup.ag)".to_string())?;
 
         let url = format!(
-            "https://api.jup.ag/swap/v1/quote?inputMint={}&outputMint={}&amount={}&slippageBps=50&restrictIntermediateTokens=true",
+            "{}/swap/v1/quote?inputMint={}&outputMint={}&amount={}&slippageBps=50&restrictIntermediateTokens=true",
+            self.base_url,
             input_mint, output_mint, amount
         );
         let resp = self
             .client
             .get(&url)
             .header("x-api-key", api_key)
-            .send()
+            .send_captured("jupiter")
             .await
             .map_err(|e| format!("Jupiter Quote connection failed: {}", e))?;
 
@@ -119,38 +213,25 @@ impl JupiterProvider {
             .and_then(|s| s.parse::<f64>().ok());
 
         // 路由路徑
-        let route_path = quote
-            .get("routePlan")
-            .and_then(|v| v.as_array())
-            .map(|plans| {
-                plans
-                    .iter()
-                    .filter_map(|p| {
-                        p.get("swapInfo")
-                            .and_then(|s| s.get("label"))
-                            .and_then(|l| l.as_str())
-                    })
-                    .collect::<Vec<_>>()
-                    .join(" → ")
-            })
-            .unwrap_or_else(|| "Jupiter".into());
+        let route_path = route_path(&quote).unwrap_or_else(|| "Jupiter".into());
 
-        let input_sym = mint_to_symbol(input_mint);
-        let output_sym = mint_to_symbol(output_mint);
+        let input_sym = Self::token_symbol(input_mint).await;
+        let output_sym = Self::token_symbol(output_mint).await;
 
         Ok(AssetDataBuilder::new(symbol, "jupiter")
             .price(price)
-            .currency(output_sym)
+            .currency(&output_sym)
             .extra_f64("amount_out", Some(amount_out))
             .extra_f64("price_impact", price_impact)
             .extra_str("route_path", Some(&route_path))
             .extra_str("gas_estimate", Some("~0.000005 SOL"))
-            .extra_str("token_from", Some(input_sym))
-            .extra_str("token_to", Some(output_sym))
+            .extra_str("token_from", Some(&input_sym))
+            .extra_str("token_to", Some(&output_sym))
             .build())
     }
 
-    /// 取得 token decimals（透過 Price API 的 extraInfo）
+    /// 取得 token decimals：常見 token → token metadata 快取 → Price API 的 extraInfo
+    /// （查到後記入快取，由下次 `prepare` 寫入 DB）
     async fn get_token_decimals(&self, mint: &str) -> Result<u8, String> {
         // 常見 token 直接返回
         match mint {
@@ -159,17 +240,21 @@ impl JupiterProvider {
             "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB" => return Ok(6), // USDT
             _ => {}
         }
+        if let Some(token) = TOKENS.read().await.tokens.get(mint) {
+            return Ok(token.decimals as u8);
+        }
         // 用 Price API 查 extraInfo
         if let Some(api_key) = self.api_key.as_deref() {
             let url = format!(
-                "https://api.jup.ag/price/v3?ids={}&showExtraInfo=true",
+                "{}/price/v3?ids={}&showExtraInfo=true",
+                self.base_url,
                 mint
             );
             if let Ok(resp) = self
                 .client
                 .get(&url)
                 .header("x-api-key", api_key)
-                .send()
+                .send_captured("jupiter")
                 .await
             {
                 if let Ok(json) = resp.json::<serde_json::Value>().await {
@@ -181,6 +266,15 @@ impl JupiterProvider {
                         .and_then(|e| e.get("buyTokenDecimals"))
                         .and_then(|v| v.as_u64())
                     {
+                        let token = TokenMetadata {
+                            chain: CHAIN.to_string(),
+                            address: mint.to_string(),
+                            symbol: None,
+                            decimals: decimals as u32,
+                        };
+                        let mut cache = TOKENS.write().await;
+                        cache.tokens.insert(mint.to_string(), token.clone());
+                        cache.pending.push(token);
                         return Ok(decimals as u8);
                     }
                 }
@@ -190,6 +284,21 @@ impl JupiterProvider {
     }
 }
 
+/// Quote 回應中的路由路徑（各段 swapInfo.label）
+fn route_path(quote: &serde_json::Value) -> Option<String> {
+    quote.get("routePlan").and_then(|v| v.as_array()).map(|plans| {
+        plans
+            .iter()
+            .filter_map(|p| {
+                p.get("swapInfo")
+                    .and_then(|s| s.get("label"))
+                    .and_then(|l| l.as_str())
+            })
+            .collect::<Vec<_>>()
+            .join(" → ")
+    })
+}
+
 /// 常見 Solana token → mint address 映射
 fn to_mint_address(symbol: &str) -> String {
     let s = symbol.trim();
@@ -266,6 +375,69 @@ impl DataProvider for JupiterProvider {
         provider_info_or_panic("jupiter")
     }
 
+    /// 首次查詢時從 DB 載入 token metadata、寫回新查到的 decimals，
+    /// 並在啟用時每天更新一次 verified token list
+    async fn prepare(&self, db: &DbPool) {
+        let now = chrono::Utc::now().timestamp();
+        let wants_list = |cache: &TokenCache| {
+            token_list_enabled()
+                && now - cache.list_refreshed_at >= TOKEN_LIST_REFRESH_SECS
+                && cache
+                    .list_attempt
+                    .is_none_or(|t| t.elapsed() >= TOKEN_LIST_RETRY_AFTER)
+        };
+        {
+            let cache = TOKENS.read().await;
+            if cache.loaded && cache.pending.is_empty() && !wants_list(&cache) {
+                return;
+            }
+        }
+
+        let mut cache = TOKENS.write().await;
+        if !cache.loaded {
+            match db.load_token_metadata(CHAIN) {
+                Ok(tokens) => {
+                    for token in tokens {
+                        cache.tokens.entry(token.address.clone()).or_insert(token);
+                    }
+                }
+                Err(e) => tracing::warn!("[Jupiter] Failed to load token metadata: {}", e),
+            }
+            cache.list_refreshed_at = db
+                .get_setting(TOKEN_LIST_REFRESHED_AT_KEY)
+                .ok()
+                .flatten()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0);
+            cache.loaded = true;
+        }
+        if !cache.pending.is_empty() {
+            let pending = std::mem::take(&mut cache.pending);
+            if let Err(e) = db.upsert_token_metadata(&pending) {
+                tracing::warn!("[Jupiter] Failed to save token metadata: {}", e);
+            }
+        }
+        if !wants_list(&cache) {
+            return;
+        }
+
+        cache.list_attempt = Some(Instant::now());
+        match self.fetch_token_list().await {
+            Ok(tokens) => {
+                if let Err(e) = db.upsert_token_metadata(&tokens) {
+                    tracing::warn!("[Jupiter] Failed to save token list: {}", e);
+                }
+                let _ = db.set_setting(TOKEN_LIST_REFRESHED_AT_KEY, &now.to_string());
+                cache.list_refreshed_at = now;
+                tracing::info!("[Jupiter] Loaded verified token list ({} tokens)", tokens.len());
+                for token in tokens {
+                    cache.tokens.insert(token.address.clone(), token);
+                }
+            }
+            Err(e) => tracing::warn!("[Jupiter] Token list refresh failed: {}", e),
+        }
+    }
+
     async fn fetch_price(&self, symbol: &str) -> Result<AssetData, String> {
         // DEX 模式: symbol 含 ':'
         if Self::is_dex_symbol(symbol) {
@@ -278,10 +450,10 @@ impl DataProvider for JupiterProvider {
             .as_deref()
             .ok_or_else(|| "Jupiter requires API key (free at portal.jup.ag)".to_string())?;
         let mint = to_mint_address(symbol);
-        let url = format!("https://api.jup.ag/price/v3?ids={}", mint);
+        let url = format!("{}/price/v3?ids={}", self.base_url, mint);
         let req = self.client.get(&url).header("x-api-key", api_key);
         let data: serde_json::Value = req
-            .send()
+            .send_captured("jupiter")
             .await
             .map_err(|e| format!("Jupiter connection failed: {}", e))?
             .error_for_status()
@@ -309,7 +481,7 @@ impl DataProvider for JupiterProvider {
         for sym in &dex_syms {
             match self.fetch_dex_price(sym).await {
                 Ok(data) => results.push(data),
-                Err(e) => eprintln!("[Jupiter DEX] Error fetching {}: {}", sym, e),
+                Err(e) => tracing::warn!("[Jupiter DEX] Error fetching {}: {}", sym, e),
             }
         }
 
@@ -328,10 +500,10 @@ impl DataProvider for JupiterProvider {
 
             for chunk in mints.chunks(50) {
                 let ids = chunk.join(",");
-                let url = format!("https://api.jup.ag/price/v3?ids={}", ids);
+                let url = format!("{}/price/v3?ids={}", self.base_url, ids);
                 let req = self.client.get(&url).header("x-api-key", api_key);
                 let data: serde_json::Value = req
-                    .send()
+                    .send_captured("jupiter")
                     .await
                     .map_err(|e| format!("Jupiter batch connection failed: {}", e))?
                     .error_for_status()
@@ -372,8 +544,8 @@ impl DexPoolLookup for JupiterProvider {
         let amount = 10u64.pow(decimals as u32); // 1 token
         let _quote = self.fetch_quote(&input_mint, &output_mint, amount).await?;
 
-        let input_sym = mint_to_symbol(&input_mint).to_string();
-        let output_sym = mint_to_symbol(&output_mint).to_string();
+        let input_sym = Self::token_symbol(&input_mint).await;
+        let output_sym = Self::token_symbol(&output_mint).await;
 
         Ok(DexPoolInfo {
             token0_address: input_mint,
@@ -383,3 +555,74 @@ impl DexPoolLookup for JupiterProvider {
         })
     }
 }
+
+#[async_trait::async_trait]
+impl DexQuoter for JupiterProvider {
+    fn supports_chain(&self, chain: &str) -> bool {
+        matches!(chain.to_lowercase().as_str(), "sol" | "solana")
+    }
+
+    async fn quote(
+        &self,
+        _chain: &str,
+        token_in: &str,
+        token_out: &str,
+        amount: f64,
+    ) -> Result<DexQuote, String> {
+        let input_mint = to_mint_address(token_in);
+        let output_mint = to_mint_address(token_out);
+        let in_decimals = self.get_token_decimals(&input_mint).await.unwrap_or(9);
+        let raw_amount = (amount * 10f64.powi(in_decimals as i32)).round();
+        if raw_amount < 1.0 || raw_amount > u64::MAX as f64 {
+            return Err(format!("Jupiter: invalid amount {}", amount));
+        }
+
+        let quote = self.fetch_quote(&input_mint, &output_mint, raw_amount as u64).await?;
+        let out_amount_raw = quote
+            .get("outAmount")
+            .and_then(|v| v.as_str())
+            .and_then(|s| s.parse::<f64>().ok())
+            .ok_or("Jupiter Quote missing outAmount")?;
+        let out_decimals = self.get_token_decimals(&output_mint).await.unwrap_or(6);
+        let amount_out = out_amount_raw / 10f64.powi(out_decimals as i32);
+
+        // priceImpactPct 為比例（0.01 = 1%）
+        let price_impact = quote
+            .get("priceImpactPct")
+            .and_then(|v| v.as_str())
+            .and_then(|s| s.parse::<f64>().ok())
+            .map(|p| p.abs() * 100.0);
+
+        Ok(DexQuote {
+            provider_id: "jupiter".to_string(),
+            amount_out,
+            price: amount_out / amount,
+            price_impact,
+            gas_estimate: Some("~0.000005 SOL".to_string()),
+            route: route_path(&quote),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_list_entries_without_decimals_are_skipped() {
+        let tokens = parse_token_list(&serde_json::json!([
+            {"id": "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263", "symbol": "Bonk", "decimals": 5},
+            {"id": "mint-without-decimals", "symbol": "X"},
+            {"symbol": "no-id", "decimals": 6}
+        ]));
+        assert_eq!(
+            tokens,
+            vec![TokenMetadata {
+                chain: CHAIN.to_string(),
+                address: "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263".to_string(),
+                symbol: Some("Bonk".to_string()),
+                decimals: 5,
+            }]
+        );
+    }
+}
@@ -1,22 +1,141 @@
+use super::debug::SendCaptured;
 use super::traits::*;
 use super::types::*;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+const DEFAULT_BASE_URL: &str = "https://api.kraken.com";
+
+/// AssetPairs 中查不到請求的 pair 時，最多每小時重新下載一次
+const PAIR_REFRESH_AFTER: Duration = Duration::from_secs(3600);
 
 pub struct KrakenProvider {
     client: reqwest::Client,
+    base_url: String,
+    /// 請求用 pair 名稱（altname / wsname / key）→ Ticker 回應中的 key
+    pair_keys: RwLock<PairKeys>,
+}
+
+#[derive(Default)]
+struct PairKeys {
+    map: HashMap<String, String>,
+    fetched_at: Option<Instant>,
+}
+
+/// 從 `/0/public/AssetPairs` 回應建立 pair 名稱 → 回應 key 的對照
+/// （例如 XBTUSD、XBT/USD → XXBTZUSD）
+fn parse_pair_keys(info: &serde_json::Value) -> HashMap<String, String> {
+    let mut map = HashMap::new();
+    let Some(result) = info["result"].as_object() else {
+        return map;
+    };
+    for (key, pair) in result {
+        map.insert(key.to_uppercase(), key.clone());
+        if let Some(alt) = pair["altname"].as_str() {
+            map.insert(alt.to_uppercase(), key.clone());
+        }
+        if let Some(ws) = pair["wsname"].as_str() {
+            map.insert(ws.replace('/', "").to_uppercase(), key.clone());
+        }
+    }
+    map
 }
 
 impl Default for KrakenProvider {
     fn default() -> Self {
-        Self::new()
+        Self::new(None, &HttpOptions::default())
     }
 }
 
 impl KrakenProvider {
-    pub fn new() -> Self {
+    pub fn new(api_url: Option<String>, http: &HttpOptions) -> Self {
         Self {
-            client: shared_client(),
+            client: provider_client(http),
+            base_url: resolve_base_url(api_url, DEFAULT_BASE_URL),
+            pair_keys: RwLock::new(PairKeys::default()),
         }
     }
+
+    /// 取得 pairs 對應的 Ticker 回應 key；有 pair 查不到且距上次下載超過一小時才重新抓 AssetPairs
+    async fn response_keys(&self, pairs: &[String]) -> HashMap<String, String> {
+        let needs_refresh = {
+            let keys = self.pair_keys.read().await;
+            pairs.iter().any(|p| !keys.map.contains_key(p))
+                && keys
+                    .fetched_at
+                    .is_none_or(|t| t.elapsed() >= PAIR_REFRESH_AFTER)
+        };
+        if needs_refresh {
+            let url = format!("{}/0/public/AssetPairs", self.base_url);
+            let info = match self.client.get(&url).send_captured("kraken").await {
+                Ok(resp) if resp.status().is_success() => resp.json::<serde_json::Value>().await.ok(),
+                _ => None,
+            };
+            let mut keys = self.pair_keys.write().await;
+            keys.fetched_at = Some(Instant::now());
+            match info.map(|i| parse_pair_keys(&i)) {
+                Some(map) if !map.is_empty() => keys.map = map,
+                _ => tracing::warn!("[Kraken] AssetPairs unavailable, matching exact response keys only"),
+            }
+        }
+        let keys = self.pair_keys.read().await;
+        pairs
+            .iter()
+            .filter_map(|p| keys.map.get(p).map(|k| (p.clone(), k.clone())))
+            .collect()
+    }
+}
+
+fn parse_kraken_ticker(symbol: &str, ticker: &serde_json::Value) -> AssetData {
+    let price = ticker["c"][0]
+        .as_str()
+        .and_then(|s| s.parse::<f64>().ok())
+        .unwrap_or(0.0);
+    let open = ticker["o"]
+        .as_str()
+        .and_then(|s| s.parse::<f64>().ok())
+        .unwrap_or(0.0);
+    let high = ticker["h"][1].as_str().and_then(|s| s.parse::<f64>().ok());
+    let low = ticker["l"][1].as_str().and_then(|s| s.parse::<f64>().ok());
+    let volume = ticker["v"][1].as_str().and_then(|s| s.parse::<f64>().ok());
+    let change = if open > 0.0 { Some(price - open) } else { None };
+    let change_pct = if open > 0.0 {
+        Some((price - open) / open * 100.0)
+    } else {
+        None
+    };
+
+    AssetDataBuilder::new(symbol, "kraken")
+        .price(price)
+        .currency("USD")
+        .change_24h(change)
+        .change_percent_24h(change_pct)
+        .high_24h(high)
+        .low_24h(low)
+        .volume(volume)
+        .build()
+}
+
+/// 依 AssetPairs 對照把 Ticker 回應對回請求的 symbol；對不上的 symbol 直接略過
+fn match_tickers(
+    symbols: &[String],
+    pairs: &[String],
+    result: &serde_json::Map<String, serde_json::Value>,
+    keys: &HashMap<String, String>,
+) -> Vec<AssetData> {
+    let mut out = Vec::new();
+    for (sym, pair) in symbols.iter().zip(pairs) {
+        let ticker = keys
+            .get(pair)
+            .and_then(|k| result.get(k))
+            .or_else(|| result.get(pair.as_str()));
+        match ticker {
+            Some(t) => out.push(parse_kraken_ticker(sym, t)),
+            None => tracing::warn!("Kraken: no ticker for {} ({})", sym, pair),
+        }
+    }
+    out
 }
 
 /// Convert symbol to Kraken format: XBTUSD, ETHUSD
@@ -41,11 +160,11 @@ impl DataProvider for KrakenProvider {
 
     async fn fetch_price(&self, symbol: &str) -> Result<AssetData, String> {
         let pair = to_kraken_symbol(symbol);
-        let url = format!("https://api.kraken.com/0/public/Ticker?pair={}", pair);
+        let url = format!("{}/0/public/Ticker?pair={}", self.base_url, pair);
         let data: serde_json::Value = self
             .client
             .get(&url)
-            .send()
+            .send_captured("kraken")
             .await
             .map_err(|e| format!("Kraken connection failed: {}", e))?
             .json()
@@ -72,33 +191,7 @@ impl DataProvider for KrakenProvider {
             .and_then(|m| m.values().next())
             .ok_or("Kraken: trading pair not found")?;
 
-        let price = ticker["c"][0]
-            .as_str()
-            .and_then(|s| s.parse::<f64>().ok())
-            .unwrap_or(0.0);
-        let open = ticker["o"]
-            .as_str()
-            .and_then(|s| s.parse::<f64>().ok())
-            .unwrap_or(0.0);
-        let high = ticker["h"][1].as_str().and_then(|s| s.parse::<f64>().ok());
-        let low = ticker["l"][1].as_str().and_then(|s| s.parse::<f64>().ok());
-        let volume = ticker["v"][1].as_str().and_then(|s| s.parse::<f64>().ok());
-        let change = if open > 0.0 { Some(price - open) } else { None };
-        let change_pct = if open > 0.0 {
-            Some((price - open) / open * 100.0)
-        } else {
-            None
-        };
-
-        Ok(AssetDataBuilder::new(symbol, "kraken")
-            .price(price)
-            .currency("USD")
-            .change_24h(change)
-            .change_percent_24h(change_pct)
-            .high_24h(high)
-            .low_24h(low)
-            .volume(volume)
-            .build())
+        Ok(parse_kraken_ticker(symbol, ticker))
     }
 
     async fn fetch_prices(&self, symbols: &[String]) -> Result<Vec<AssetData>, String> {
@@ -111,13 +204,14 @@ impl DataProvider for KrakenProvider {
         }
         let pairs: Vec<String> = symbols.iter().map(|s| to_kraken_symbol(s)).collect();
         let url = format!(
-            "https://api.kraken.com/0/public/Ticker?pair={}",
+            "{}/0/public/Ticker?pair={}",
+            self.base_url,
             pairs.join(",")
         );
         let data: serde_json::Value = self
             .client
             .get(&url)
-            .send()
+            .send_captured("kraken")
             .await
             .map_err(|e| format!("Kraken batch connection failed: {}", e))?
             .json()
@@ -125,52 +219,40 @@ impl DataProvider for KrakenProvider {
             .map_err(|e| format!("Kraken batch parse failed: {}", e))?;
 
         let result = data["result"].as_object().ok_or("Kraken: no results")?;
-        // Build lookup: Kraken returns keys like XXBTZUSD (X-prefix for crypto, Z-prefix for fiat)
-        // We need to match our requested pairs to the returned keys
-        let mut out = Vec::new();
-        for (i, sym) in symbols.iter().enumerate() {
-            let kraken_sym = &pairs[i];
-            // Try exact match first, then search for key containing our pair
-            let ticker = result
-                .get(kraken_sym.as_str())
-                .or_else(|| {
-                    result
-                        .iter()
-                        .find(|(k, _)| k.contains(kraken_sym.as_str()))
-                        .map(|(_, v)| v)
-                })
-                .or_else(|| result.values().nth(i));
-            if let Some(t) = ticker {
-                let price = t["c"][0]
-                    .as_str()
-                    .and_then(|s| s.parse::<f64>().ok())
-                    .unwrap_or(0.0);
-                let open = t["o"]
-                    .as_str()
-                    .and_then(|s| s.parse::<f64>().ok())
-                    .unwrap_or(0.0);
-                let high = t["h"][1].as_str().and_then(|s| s.parse::<f64>().ok());
-                let low = t["l"][1].as_str().and_then(|s| s.parse::<f64>().ok());
-                let volume = t["v"][1].as_str().and_then(|s| s.parse::<f64>().ok());
-                let change = if open > 0.0 { Some(price - open) } else { None };
-                let change_pct = if open > 0.0 {
-                    Some((price - open) / open * 100.0)
-                } else {
-                    None
-                };
-                out.push(
-                    AssetDataBuilder::new(sym, "kraken")
-                        .price(price)
-                        .currency("USD")
-                        .change_24h(change)
-                        .change_percent_24h(change_pct)
-                        .high_24h(high)
-                        .low_24h(low)
-                        .volume(volume)
-                        .build(),
-                );
-            }
-        }
-        Ok(out)
+        // Ticker 回應 key 與請求的 pair 不同（例如 XBTUSD -> XXBTZUSD），以 AssetPairs 對照
+        let keys = self.response_keys(&pairs).await;
+        Ok(match_tickers(symbols, &pairs, result, &keys))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn batch_tickers_match_by_asset_pair_metadata() {
+        let info = json!({"error": [], "result": {
+            "XXBTZUSD": {"altname": "XBTUSD", "wsname": "XBT/USD"},
+            "XETHZUSD": {"altname": "ETHUSD", "wsname": "ETH/USD"},
+            "ETHUSDT": {"altname": "ETHUSDT", "wsname": "ETH/USDT"}
+        }});
+        let keys = parse_pair_keys(&info);
+        assert_eq!(keys["XBTUSD"], "XXBTZUSD");
+
+        // 回應順序與請求不同，且含有請求以外的 pair
+        let ticker = |p: &str| json!({"c": [p, "1"], "o": "1"});
+        let result = json!({
+            "ETHUSDT": ticker("3"),
+            "XETHZUSD": ticker("2"),
+            "XXBTZUSD": ticker("1")
+        });
+        let symbols = vec!["BTC".to_string(), "ETH".to_string(), "SOL".to_string()];
+        let pairs: Vec<String> = symbols.iter().map(|s| to_kraken_symbol(s)).collect();
+        let out = match_tickers(&symbols, &pairs, result.as_object().unwrap(), &keys);
+
+        assert_eq!(out.len(), 2);
+        assert_eq!((out[0].symbol.as_str(), out[0].price), ("BTC", 1.0));
+        assert_eq!((out[1].symbol.as_str(), out[1].price), ("ETH", 2.0));
     }
 }
@@ -1,20 +1,25 @@
+use super::debug::SendCaptured;
 use super::traits::*;
 use super::types::*;
 
+const DEFAULT_BASE_URL: &str = "https://api.kucoin.com";
+
 pub struct KuCoinProvider {
     client: reqwest::Client,
+    base_url: String,
 }
 
 impl Default for KuCoinProvider {
     fn default() -> Self {
-        Self::new()
+        Self::new(None, &HttpOptions::default())
     }
 }
 
 impl KuCoinProvider {
-    pub fn new() -> Self {
+    pub fn new(api_url: Option<String>, http: &HttpOptions) -> Self {
         Self {
-            client: shared_client(),
+            client: provider_client(http),
+            base_url: resolve_base_url(api_url, DEFAULT_BASE_URL),
         }
     }
 }
@@ -50,11 +55,11 @@ impl DataProvider for KuCoinProvider {
 
     async fn fetch_price(&self, symbol: &str) -> Result<AssetData, String> {
         let pair = to_kucoin_symbol(symbol);
-        let url = format!("https://api.kucoin.com/api/v1/market/stats?symbol={}", pair);
+        let url = format!("{}/api/v1/market/stats?symbol={}", self.base_url, pair);
         let resp: serde_json::Value = self
             .client
             .get(&url)
-            .send()
+            .send_captured("kucoin")
             .await
             .map_err(|e| format!("KuCoin connection failed: {}", e))?
             .json()
@@ -79,11 +84,11 @@ impl DataProvider for KuCoinProvider {
         }
 
         // KuCoin allTickers endpoint returns all tickers at once
-        let url = "https://api.kucoin.com/api/v1/market/allTickers";
+        let url = format!("{}/api/v1/market/allTickers", self.base_url);
         let resp: serde_json::Value = self
             .client
             .get(url)
-            .send()
+            .send_captured("kucoin")
             .await
             .map_err(|e| format!("KuCoin batch connection failed: {}", e))?
             .json()
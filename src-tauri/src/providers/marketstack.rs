@@ -1,16 +1,21 @@
+use super::debug::SendCaptured;
 use super::traits::*;
 use super::types::*;
 use std::collections::HashMap;
 
+const DEFAULT_BASE_URL: &str = "http://api.marketstack.com";
+
 pub struct MarketstackProvider {
     client: reqwest::Client,
+    base_url: String,
     api_key: Option<String>,
 }
 
 impl MarketstackProvider {
-    pub fn new(api_key: Option<String>) -> Self {
+    pub fn new(api_key: Option<String>, api_url: Option<String>, http: &HttpOptions) -> Self {
         Self {
-            client: shared_client(),
+            client: provider_client(http),
+            base_url: resolve_base_url(api_url, DEFAULT_BASE_URL),
             api_key,
         }
     }
@@ -50,10 +55,11 @@ impl DataProvider for MarketstackProvider {
         let data: serde_json::Value = self
             .client
             .get(format!(
-                "http://api.marketstack.com/v1/eod/latest?access_key={}&symbols={}",
+                "{}/v1/eod/latest?access_key={}&symbols={}",
+                self.base_url,
                 api_key, symbol
             ))
-            .send()
+            .send_captured("marketstack")
             .await
             .map_err(|e| format!("Marketstack connection failed: {}", e))?
             .error_for_status()
@@ -92,10 +98,11 @@ impl DataProvider for MarketstackProvider {
         let resp = self
             .client
             .get(format!(
-                "http://api.marketstack.com/v1/eod/latest?access_key={}&symbols={}",
+                "{}/v1/eod/latest?access_key={}&symbols={}",
+                self.base_url,
                 api_key, syms
             ))
-            .send()
+            .send_captured("marketstack")
             .await
             .map_err(|e| format!("Marketstack batch connection failed: {}", e))?
             .error_for_status()
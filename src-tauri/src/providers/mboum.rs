@@ -1,16 +1,21 @@
+use super::debug::SendCaptured;
 use super::traits::*;
 use super::types::*;
 use std::collections::HashMap;
 
+const DEFAULT_BASE_URL: &str = "https://api.mboum.com";
+
 pub struct MboumProvider {
     client: reqwest::Client,
+    base_url: String,
     api_key: Option<String>,
 }
 
 impl MboumProvider {
-    pub fn new(api_key: Option<String>) -> Self {
+    pub fn new(api_key: Option<String>, api_url: Option<String>, http: &HttpOptions) -> Self {
         Self {
-            client: shared_client(),
+            client: provider_client(http),
+            base_url: resolve_base_url(api_url, DEFAULT_BASE_URL),
             api_key,
         }
     }
@@ -21,7 +26,7 @@ impl MboumProvider {
         let pre_price = q["preMarketPrice"].as_f64();
         let post_price = q["postMarketPrice"].as_f64();
 
-        let mut builder = AssetDataBuilder::new(symbol, "mboum")
+        let builder = AssetDataBuilder::new(symbol, "mboum")
             .price(price)
             .currency(q["currency"].as_str().unwrap_or("USD"))
             .change_24h(q["regularMarketChange"].as_f64())
@@ -37,33 +42,10 @@ impl MboumProvider {
             .extra_str("name", q["shortName"].as_str())
             .extra_str("market_session", market_state);
 
-        // 盤前數據
-        if let Some(pp) = pre_price {
-            builder = builder.extra_f64("pre_market_price", Some(pp));
-            let pre_change = pp - price;
-            let pre_pct = if price > 0.0 {
-                (pre_change / price) * 100.0
-            } else {
-                0.0
-            };
-            builder = builder.extra_f64("pre_market_change", Some(pre_change));
-            builder = builder.extra_f64("pre_market_change_pct", Some(pre_pct));
-        }
-
-        // 盤後數據
-        if let Some(pp) = post_price {
-            builder = builder.extra_f64("post_market_price", Some(pp));
-            let post_change = pp - price;
-            let post_pct = if price > 0.0 {
-                (post_change / price) * 100.0
-            } else {
-                0.0
-            };
-            builder = builder.extra_f64("post_market_change", Some(post_change));
-            builder = builder.extra_f64("post_market_change_pct", Some(post_pct));
-        }
-
-        builder.build()
+        builder
+            .pre_market(pre_price.map(|pp| SessionQuote::against(pp, Some(price))))
+            .post_market(post_price.map(|pp| SessionQuote::against(pp, Some(price))))
+            .build()
     }
 }
 
@@ -79,11 +61,12 @@ impl DataProvider for MboumProvider {
         let data: serde_json::Value = self
             .client
             .get(format!(
-                "https://api.mboum.com/v1/markets/stock/quotes?ticker={}",
+                "{}/v1/markets/stock/quotes?ticker={}",
+                self.base_url,
                 symbol
             ))
             .header("Authorization", format!("Bearer {}", api_key))
-            .send()
+            .send_captured("mboum")
             .await
             .map_err(|e| format!("Mboum connection failed: {}", e))?
             .error_for_status()
@@ -112,7 +95,8 @@ impl DataProvider for MboumProvider {
         let api_key = self.api_key.as_ref().ok_or("Mboum requires API key")?;
         let syms_csv = symbols.join(",");
         let url = format!(
-            "https://api.mboum.com/v1/markets/stock/quotes?ticker={}",
+            "{}/v1/markets/stock/quotes?ticker={}",
+            self.base_url,
             syms_csv
         );
 
@@ -120,7 +104,7 @@ impl DataProvider for MboumProvider {
             .client
             .get(&url)
             .header("Authorization", format!("Bearer {}", api_key))
-            .send()
+            .send_captured("mboum")
             .await
             .map_err(|e| format!("Mboum batch connection failed: {}", e))?;
 
@@ -150,7 +134,7 @@ impl DataProvider for MboumProvider {
         }
 
         // 批量失敗或無法獲得任何資料，降級為逐一擷取 (限制並發數 5)
-        eprintln!("Mboum batch query failed, falling back to sequential fetch");
+        tracing::warn!("Mboum batch query failed, falling back to sequential fetch");
         let client = self.client.clone();
         let api_key_clone = api_key.clone();
         let mut tasks = tokio::task::JoinSet::new();
@@ -161,17 +145,19 @@ impl DataProvider for MboumProvider {
             let sym = sym.clone();
             let c = client.clone();
             let k = api_key_clone.clone();
+            let base_url = self.base_url.clone();
             let sem = semaphore.clone();
             tasks.spawn(async move {
                 let _permit = sem.acquire().await;
                 let url = format!(
-                    "https://api.mboum.com/v1/markets/stock/quotes?ticker={}",
+                    "{}/v1/markets/stock/quotes?ticker={}",
+                    base_url,
                     sym
                 );
                 match c
                     .get(&url)
                     .header("Authorization", format!("Bearer {}", k))
-                    .send()
+                    .send_captured("mboum")
                     .await
                 {
                     Ok(resp) if resp.status().is_success() => {
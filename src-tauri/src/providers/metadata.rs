@@ -0,0 +1,332 @@
+//! 可遠端更新的 provider metadata — 以簽章 JSON 覆寫 `build_all_provider_info` 的編譯期預設值
+//!
+//! 免費額度說明與預設間隔會隨供應商方案變動而過時，覆寫檔讓這些欄位不必等新版本即可更新。
+//! 檔案格式：`{"payload": "<base64 JSON>", "signature": "<base64 Ed25519 簽章>"}`，
+//! payload 為 `{"issued_at": <unix 秒>, "providers": {"<id>": { 要覆寫的欄位 }}}`。
+//!
+//! 簽章以內建的發行者公鑰 [`PUBLISHER_KEY`] 驗證；app settings `provider_metadata_key`
+//! （base64 的 32 bytes Ed25519 公鑰）只是開發者以自己的金鑰測試覆寫檔時的明確覆寫。
+//! 覆寫檔可直接放在 app-data 的 `provider_metadata.json`，
+//! 或設定 `provider_metadata_url` 由背景工作每日下載、驗證後存入該檔。
+
+use super::types::ProviderInfo;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{LazyLock, RwLock};
+
+/// app-data 下的覆寫檔名
+pub const METADATA_FILE: &str = "provider_metadata.json";
+/// 遠端更新間隔
+pub const REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(24 * 3600);
+/// 覆寫的最小 polling 間隔（ms），避免錯誤的 metadata 造成過度請求
+const MIN_INTERVAL_MS: i64 = 1000;
+/// 遠端檔案大小上限
+const MAX_FILE_BYTES: usize = 1024 * 1024;
+/// 發行者的 Ed25519 公鑰（base64）；官方覆寫檔以對應的私鑰簽章
+pub const PUBLISHER_KEY: &str = "XZYd7VTCQoZ9Kl1bvXUc9jIgSA6xAiVGrkRAG2Rmu6s=";
+
+/// 單一 provider 可覆寫的欄位；未提供的欄位沿用編譯期預設值
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProviderInfoOverride {
+    pub name: Option<String>,
+    pub free_tier_info: Option<String>,
+    pub symbol_format: Option<String>,
+    pub free_interval: Option<i64>,
+    pub key_interval: Option<i64>,
+    pub max_batch_size: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MetadataPayload {
+    /// 簽發時間（unix 秒）；較舊的 payload 不會覆蓋已套用的版本
+    pub issued_at: i64,
+    #[serde(default)]
+    pub providers: HashMap<String, ProviderInfoOverride>,
+}
+
+#[derive(Deserialize)]
+struct SignedMetadata {
+    payload: String,
+    signature: String,
+}
+
+/// 覆寫檔來源設定（app settings `provider_metadata_url` / `provider_metadata_key`）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MetadataSource {
+    /// 遠端覆寫檔 URL；空字串 = 只使用 app-data 下的檔案
+    #[serde(default)]
+    pub url: String,
+    /// 開發者覆寫用的 Ed25519 公鑰（base64）；空字串 = 使用內建的 [`PUBLISHER_KEY`]
+    #[serde(default)]
+    pub public_key: String,
+}
+
+impl MetadataSource {
+    /// 檢查並正規化設定
+    pub fn normalized(self) -> Result<Self, String> {
+        let url = self.url.trim().to_string();
+        let public_key = self.public_key.trim().to_string();
+        if !url.is_empty() && !url.starts_with("https://") && !url.starts_with("http://") {
+            return Err("Provider metadata URL must start with http:// or https://".into());
+        }
+        if !public_key.is_empty() {
+            decode_public_key(&public_key)?;
+        }
+        Ok(Self { url, public_key })
+    }
+
+    /// 驗證簽章用的公鑰：有開發者覆寫時使用覆寫，否則為發行者公鑰
+    pub fn verifying_key(&self) -> &str {
+        if self.public_key.is_empty() {
+            PUBLISHER_KEY
+        } else {
+            &self.public_key
+        }
+    }
+}
+
+/// 目前套用中的覆寫
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MetadataStatus {
+    /// 已套用 payload 的簽發時間；None = 未套用任何覆寫
+    pub issued_at: Option<i64>,
+    pub providers: usize,
+}
+
+#[derive(Default)]
+struct Installed {
+    issued_at: Option<i64>,
+    providers: HashMap<String, ProviderInfoOverride>,
+}
+
+impl Installed {
+    /// 接受較新的 payload；回傳套用的 provider 數
+    fn accept(&mut self, payload: MetadataPayload) -> Result<usize, String> {
+        if self.issued_at.is_some_and(|t| payload.issued_at < t) {
+            return Err("Provider metadata is older than the installed version".into());
+        }
+        let providers: HashMap<_, _> = payload
+            .providers
+            .into_iter()
+            .filter(|(id, ov)| match validate(ov) {
+                Ok(()) => super::PROVIDER_INFO_MAP.contains_key(id),
+                Err(e) => {
+                    tracing::warn!("[Metadata] Ignoring override for {}: {}", id, e);
+                    false
+                }
+            })
+            .collect();
+        let count = providers.len();
+        self.issued_at = Some(payload.issued_at);
+        self.providers = providers;
+        Ok(count)
+    }
+}
+
+static INSTALLED: LazyLock<RwLock<Installed>> = LazyLock::new(|| RwLock::new(Installed::default()));
+
+fn validate(ov: &ProviderInfoOverride) -> Result<(), String> {
+    for interval in [ov.free_interval, ov.key_interval].into_iter().flatten() {
+        if interval < MIN_INTERVAL_MS {
+            return Err(format!("interval {}ms below {}ms", interval, MIN_INTERVAL_MS));
+        }
+    }
+    if ov.max_batch_size == Some(0) {
+        return Err("max_batch_size must be at least 1".into());
+    }
+    Ok(())
+}
+
+fn merge(info: &mut ProviderInfo, ov: &ProviderInfoOverride) {
+    if let Some(name) = &ov.name {
+        info.name = name.clone();
+    }
+    if let Some(text) = &ov.free_tier_info {
+        info.free_tier_info = text.clone();
+    }
+    if let Some(format) = &ov.symbol_format {
+        info.symbol_format = format.clone();
+    }
+    if let Some(ms) = ov.free_interval {
+        info.free_interval = ms;
+    }
+    if let Some(ms) = ov.key_interval {
+        info.key_interval = ms;
+    }
+    if let Some(max) = ov.max_batch_size {
+        info.capabilities.max_batch_size = Some(max);
+    }
+}
+
+/// 將已套用的覆寫合併到 provider info（由 `get_provider_info` / `get_all_provider_info` 呼叫）
+pub fn apply(info: &mut ProviderInfo) {
+    if let Some(ov) = INSTALLED.read().unwrap_or_else(|e| e.into_inner()).providers.get(&info.id) {
+        merge(info, ov);
+    }
+}
+
+pub fn status() -> MetadataStatus {
+    let installed = INSTALLED.read().unwrap_or_else(|e| e.into_inner());
+    MetadataStatus {
+        issued_at: installed.issued_at,
+        providers: installed.providers.len(),
+    }
+}
+
+/// 清除所有覆寫（公鑰變更時，舊公鑰驗證過的內容不再可信）
+pub fn clear() {
+    *INSTALLED.write().unwrap_or_else(|e| e.into_inner()) = Installed::default();
+}
+
+/// 檢查公鑰格式（base64 的 32 bytes）
+pub fn decode_public_key(public_key: &str) -> Result<Vec<u8>, String> {
+    let key = STANDARD
+        .decode(public_key.trim())
+        .map_err(|e| format!("Invalid metadata public key: {}", e))?;
+    if key.len() != 32 {
+        return Err("Metadata public key must be a 32-byte Ed25519 key".into());
+    }
+    Ok(key)
+}
+
+/// 驗證簽章並解析 payload
+pub fn verify(raw: &[u8], public_key: &str) -> Result<MetadataPayload, String> {
+    let key = decode_public_key(public_key)?;
+    let signed: SignedMetadata =
+        serde_json::from_slice(raw).map_err(|e| format!("Invalid provider metadata file: {}", e))?;
+    let payload = STANDARD
+        .decode(signed.payload.trim())
+        .map_err(|e| format!("Invalid provider metadata payload: {}", e))?;
+    let signature = STANDARD
+        .decode(signed.signature.trim())
+        .map_err(|e| format!("Invalid provider metadata signature: {}", e))?;
+    ring::signature::UnparsedPublicKey::new(&ring::signature::ED25519, &key)
+        .verify(&payload, &signature)
+        .map_err(|_| "Provider metadata signature verification failed".to_string())?;
+    serde_json::from_slice(&payload).map_err(|e| format!("Invalid provider metadata payload: {}", e))
+}
+
+fn install(payload: MetadataPayload) -> Result<usize, String> {
+    INSTALLED.write().unwrap_or_else(|e| e.into_inner()).accept(payload)
+}
+
+/// 載入 app-data 下的覆寫檔；檔案不存在時回傳 Ok(0)
+pub fn load_file(data_dir: &Path, public_key: &str) -> Result<usize, String> {
+    let raw = match std::fs::read(data_dir.join(METADATA_FILE)) {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(format!("Failed to read provider metadata: {}", e)),
+    };
+    install(verify(&raw, public_key)?)
+}
+
+/// 下載遠端覆寫檔，驗證通過後套用並存入 app-data
+pub async fn refresh(data_dir: &Path, url: &str, public_key: &str) -> Result<usize, String> {
+    let raw = super::shared_client()
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Provider metadata download failed: {}", e))?
+        .error_for_status()
+        .map_err(|e| format!("Provider metadata download failed: {}", e))?
+        .bytes()
+        .await
+        .map_err(|e| format!("Provider metadata download failed: {}", e))?;
+    if raw.len() > MAX_FILE_BYTES {
+        return Err("Provider metadata file is too large".into());
+    }
+    let count = install(verify(&raw, public_key)?)?;
+    tokio::fs::write(data_dir.join(METADATA_FILE), &raw)
+        .await
+        .map_err(|e| format!("Failed to save provider metadata: {}", e))?;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    fn sign(pair: &Ed25519KeyPair, payload: &serde_json::Value) -> Vec<u8> {
+        let bytes = payload.to_string().into_bytes();
+        serde_json::json!({
+            "payload": STANDARD.encode(&bytes),
+            "signature": STANDARD.encode(pair.sign(&bytes)),
+        })
+        .to_string()
+        .into_bytes()
+    }
+
+    fn key_pair() -> Ed25519KeyPair {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&ring::rand::SystemRandom::new()).unwrap();
+        Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap()
+    }
+
+    #[test]
+    fn only_correctly_signed_metadata_is_accepted() {
+        let pair = key_pair();
+        let public_key = STANDARD.encode(pair.public_key().as_ref());
+        let payload = serde_json::json!({
+            "issued_at": 100,
+            "providers": {"finnhub": {"free_interval": 30000}}
+        });
+        let raw = sign(&pair, &payload);
+        let parsed = verify(&raw, &public_key).unwrap();
+        assert_eq!(parsed.providers["finnhub"].free_interval, Some(30000));
+
+        let other = STANDARD.encode(key_pair().public_key().as_ref());
+        assert!(verify(&raw, &other).is_err());
+
+        let mut tampered: serde_json::Value = serde_json::from_slice(&raw).unwrap();
+        tampered["payload"] = STANDARD.encode(b"{\"issued_at\":100,\"providers\":{}}").into();
+        assert!(verify(tampered.to_string().as_bytes(), &public_key).is_err());
+        assert!(decode_public_key("c2hvcnQ=").is_err());
+    }
+
+    #[test]
+    fn publisher_key_is_used_unless_a_developer_key_is_set() {
+        assert_eq!(decode_public_key(PUBLISHER_KEY).unwrap().len(), 32);
+        let source = MetadataSource {
+            url: "https://example.com/provider_metadata.json".into(),
+            public_key: String::new(),
+        }
+        .normalized()
+        .unwrap();
+        assert_eq!(source.verifying_key(), PUBLISHER_KEY);
+
+        let developer = STANDARD.encode(key_pair().public_key().as_ref());
+        let source = MetadataSource { public_key: developer.clone(), ..source };
+        assert_eq!(source.verifying_key(), developer);
+    }
+
+    #[test]
+    fn overrides_merge_over_defaults_and_reject_rollback() {
+        let parse = |v: serde_json::Value| serde_json::from_value::<MetadataPayload>(v).unwrap();
+        let mut installed = Installed::default();
+        let count = installed
+            .accept(parse(serde_json::json!({
+                "issued_at": 200,
+                "providers": {
+                    "eodhd": {"free_tier_info": "new plan", "max_batch_size": 10},
+                    "finnhub": {"key_interval": 10},
+                    "no_such_provider": {"name": "x"}
+                }
+            })))
+            .unwrap();
+        assert_eq!(count, 1);
+
+        let mut info = crate::providers::provider_info_or_panic("eodhd");
+        let default_interval = info.free_interval;
+        merge(&mut info, &installed.providers["eodhd"]);
+        assert_eq!(info.free_tier_info, "new plan");
+        assert_eq!(info.capabilities.max_batch_size, Some(10));
+        assert_eq!(info.free_interval, default_interval);
+
+        assert!(installed
+            .accept(parse(serde_json::json!({"issued_at": 150})))
+            .is_err());
+        assert_eq!(installed.issued_at, Some(200));
+    }
+}
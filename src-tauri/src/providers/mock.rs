@@ -0,0 +1,176 @@
+use super::traits::*;
+use super::types::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex, RwLock};
+
+/// Demo 模式設定（app settings: `demo_mode` / `mock_volatility_pct` / `mock_latency_ms`）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MockConfig {
+    /// 開啟後 `mock` provider 才會出現在列表並可被建立
+    pub enabled: bool,
+    /// 每次取價的最大漲跌幅（%）
+    pub volatility_pct: f64,
+    /// 模擬的網路延遲（毫秒）
+    pub latency_ms: u64,
+}
+
+const DEFAULT_CONFIG: MockConfig = MockConfig {
+    enabled: false,
+    volatility_pct: 0.5,
+    latency_ms: 0,
+};
+
+impl Default for MockConfig {
+    fn default() -> Self {
+        DEFAULT_CONFIG
+    }
+}
+
+static MOCK_CONFIG: RwLock<MockConfig> = RwLock::new(DEFAULT_CONFIG);
+
+/// 每個 symbol 的隨機漫步狀態 — 放在 provider 外面，重建 instance（改設定）時價格不會跳回起點
+static WALKS: LazyLock<Mutex<HashMap<String, Walk>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+pub fn set_mock_config(config: MockConfig) {
+    *MOCK_CONFIG.write().unwrap_or_else(|e| e.into_inner()) = config;
+}
+
+pub fn mock_config() -> MockConfig {
+    *MOCK_CONFIG.read().unwrap_or_else(|e| e.into_inner())
+}
+
+struct Walk {
+    open: f64,
+    price: f64,
+    high: f64,
+    low: f64,
+    volume: f64,
+    rng: u64,
+}
+
+impl Walk {
+    fn new(symbol: &str) -> Self {
+        let seed = symbol_seed(symbol);
+        // 起始價依 symbol 固定（1 ~ 1000），同一 symbol 每次啟動看起來一致
+        let open = 1.0 + (seed % 99_900) as f64 / 100.0;
+        Self {
+            open,
+            price: open,
+            high: open,
+            low: open,
+            volume: 0.0,
+            rng: seed | 1,
+        }
+    }
+
+    /// xorshift64 → [-1, 1)
+    fn next_unit(&mut self) -> f64 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        (self.rng >> 11) as f64 / (1u64 << 52) as f64 - 1.0
+    }
+
+    fn step(&mut self, volatility_pct: f64) {
+        let change = self.next_unit() * volatility_pct / 100.0;
+        self.price = (self.price * (1.0 + change)).max(0.0001);
+        self.high = self.high.max(self.price);
+        self.low = self.low.min(self.price);
+        self.volume += self.price * (1.0 + self.next_unit().abs()) * 100.0;
+    }
+}
+
+fn symbol_seed(symbol: &str) -> u64 {
+    // FNV-1a
+    symbol.bytes().fold(0xcbf2_9ce4_8422_2325, |h, b| {
+        (h ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Mock provider — 為任何 symbol 產生隨機漫步價格，不需 API key 或網路
+pub struct MockProvider {
+    config: MockConfig,
+}
+
+impl Default for MockProvider {
+    fn default() -> Self {
+        Self::new(mock_config())
+    }
+}
+
+impl MockProvider {
+    pub fn new(config: MockConfig) -> Self {
+        Self { config }
+    }
+
+    fn tick(&self, symbol: &str) -> AssetData {
+        let key = symbol.trim().to_uppercase();
+        let mut walks = WALKS.lock().unwrap_or_else(|e| e.into_inner());
+        let walk = walks.entry(key).or_insert_with_key(|k| Walk::new(k));
+        walk.step(self.config.volatility_pct);
+
+        let change = walk.price - walk.open;
+        AssetDataBuilder::new(symbol, "mock")
+            .price(walk.price)
+            .currency("USD")
+            .change_24h(Some(change))
+            .change_percent_24h(Some(change / walk.open * 100.0))
+            .high_24h(Some(walk.high))
+            .low_24h(Some(walk.low))
+            .volume(Some(walk.volume))
+            .build()
+    }
+
+    async fn simulate_latency(&self) {
+        if self.config.latency_ms > 0 {
+            tokio::time::sleep(std::time::Duration::from_millis(self.config.latency_ms)).await;
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl DataProvider for MockProvider {
+    fn info(&self) -> ProviderInfo {
+        provider_info_or_panic("mock")
+    }
+
+    async fn fetch_price(&self, symbol: &str) -> Result<AssetData, String> {
+        self.simulate_latency().await;
+        Ok(self.tick(symbol))
+    }
+
+    async fn fetch_prices(&self, symbols: &[String]) -> Result<Vec<AssetData>, String> {
+        self.simulate_latency().await;
+        Ok(symbols.iter().map(|s| self.tick(s)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn random_walk_stays_within_volatility() {
+        let provider = MockProvider::new(MockConfig {
+            enabled: true,
+            volatility_pct: 1.0,
+            latency_ms: 0,
+        });
+        let symbols = vec!["MOCKTEST-A".to_string(), "MOCKTEST-B".to_string()];
+
+        let mut last = provider.fetch_prices(&symbols).await.unwrap();
+        assert_eq!(last.len(), 2);
+        for _ in 0..50 {
+            let next = provider.fetch_prices(&symbols).await.unwrap();
+            for (prev, cur) in last.iter().zip(&next) {
+                assert_eq!(prev.symbol, cur.symbol);
+                assert!(cur.price > 0.0);
+                assert!((cur.price / prev.price - 1.0).abs() <= 0.01 + 1e-9);
+                assert!(cur.high_24h.unwrap() >= cur.price && cur.low_24h.unwrap() <= cur.price);
+            }
+            last = next;
+        }
+        assert_eq!(last[0].provider_id, "mock");
+    }
+}
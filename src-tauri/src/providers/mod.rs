@@ -1,3 +1,8 @@
+pub mod coin_index;
+pub mod debug;
+pub mod derivatives;
+pub mod dex_quotes;
+pub mod metadata;
 pub mod registry;
 pub mod traits;
 pub mod types;
@@ -5,14 +10,11 @@ pub mod types;
 // Crypto exchanges
 pub mod binance;
 pub mod bitfinex;
-pub mod bybit;
 pub mod coinbase;
-pub mod gateio;
-pub mod htx;
 pub mod kraken;
 pub mod kucoin;
-pub mod mexc;
 pub mod okx;
+pub mod simple_exchange;
 
 // Crypto aggregators
 pub mod coingecko;
@@ -47,10 +49,18 @@ pub mod subgraph;
 pub mod bitquery;
 pub mod polymarket;
 
+// Demo
+pub mod mock;
+pub mod replay;
+
 // WebSocket
 pub mod ws_binance;
+pub mod ws_bybit;
+pub mod ws_coinbase;
+pub mod ws_kraken;
+pub mod ws_okx;
 
-pub use traits::{DataProvider, DexPoolLookup, WebSocketProvider};
+pub use traits::{DataProvider, DexPoolLookup, DexQuoter, WebSocketProvider, WsStreamSymbols};
 pub use types::*;
 
 use std::sync::Arc;
@@ -60,47 +70,52 @@ pub fn create_provider_with_url(
     api_key: Option<String>,
     api_secret: Option<String>,
     api_url: Option<String>,
+    http: &HttpOptions,
 ) -> Option<Arc<dyn DataProvider>> {
+    // Bybit / Gate.io / HTX / MEXC：共用 ticker 實作，依設定表建立
+    if let Some(spec) = simple_exchange::exchange_spec(id) {
+        return Some(Arc::new(simple_exchange::SimpleExchangeProvider::new(spec, api_url, http)));
+    }
     match id {
         // Crypto exchanges
-        "binance" => Some(Arc::new(binance::BinanceProvider::new(api_key))),
-        "coinbase" => Some(Arc::new(coinbase::CoinbaseProvider::new())),
-        "kraken" => Some(Arc::new(kraken::KrakenProvider::new())),
-        "bybit" => Some(Arc::new(bybit::BybitProvider::new())),
-        "kucoin" => Some(Arc::new(kucoin::KuCoinProvider::new())),
-        "okx" => Some(Arc::new(okx::OkxProvider::new())),
-        "gateio" => Some(Arc::new(gateio::GateioProvider::new())),
-        "bitfinex" => Some(Arc::new(bitfinex::BitfinexProvider::new())),
-        "htx" => Some(Arc::new(htx::HtxProvider::new())),
-        "mexc" => Some(Arc::new(mexc::MexcProvider::new())),
+        "binance" => Some(Arc::new(binance::BinanceProvider::new(api_key, api_url, http))),
+        "coinbase" => Some(Arc::new(coinbase::CoinbaseProvider::new(api_url, http))),
+        "kraken" => Some(Arc::new(kraken::KrakenProvider::new(api_url, http))),
+        "kucoin" => Some(Arc::new(kucoin::KuCoinProvider::new(api_url, http))),
+        "okx" => Some(Arc::new(okx::OkxProvider::new(api_url, http))),
+        "bitfinex" => Some(Arc::new(bitfinex::BitfinexProvider::new(api_url, http))),
         // Crypto aggregators
-        "coingecko" => Some(Arc::new(coingecko::CoinGeckoProvider::new(api_key))),
-        "coinmarketcap" => Some(Arc::new(coinmarketcap::CoinMarketCapProvider::new(api_key))),
-        "coinpaprika" => Some(Arc::new(coinpaprika::CoinPaprikaProvider::new())),
-        "cryptocompare" => Some(Arc::new(cryptocompare::CryptoCompareProvider::new(api_key))),
+        "coingecko" => Some(Arc::new(coingecko::CoinGeckoProvider::new(api_key, api_url, http))),
+        "coinmarketcap" => Some(Arc::new(coinmarketcap::CoinMarketCapProvider::new(api_key, api_url, http))),
+        "coinpaprika" => Some(Arc::new(coinpaprika::CoinPaprikaProvider::new(api_url, http))),
+        "cryptocompare" => Some(Arc::new(cryptocompare::CryptoCompareProvider::new(api_key, api_url, http))),
         // Stock / multi-asset
-        "yahoo" => Some(Arc::new(yahoo::YahooProvider::new())),
-        "finnhub" => Some(Arc::new(finnhub::FinnhubProvider::new(api_key))),
-        "alphavantage" => Some(Arc::new(alphavantage::AlphaVantageProvider::new(api_key))),
-        "polygon" => Some(Arc::new(polygon::PolygonProvider::new(api_key))),
-        "twelvedata" => Some(Arc::new(twelvedata::TwelveDataProvider::new(api_key))),
-        "alpaca" => Some(Arc::new(alpaca::AlpacaProvider::new(api_key, api_secret))),
-        "tiingo" => Some(Arc::new(tiingo::TiingoProvider::new(api_key))),
-        "fmp" => Some(Arc::new(fmp::FMPProvider::new(api_key))),
-        "marketstack" => Some(Arc::new(marketstack::MarketstackProvider::new(api_key))),
-        "eodhd" => Some(Arc::new(eodhd::EODHDProvider::new(api_key))),
-        "mboum" => Some(Arc::new(mboum::MboumProvider::new(api_key))),
-        "fcsapi" => Some(Arc::new(fcsapi::FcsApiProvider::new(api_key))),
+        "yahoo" => Some(Arc::new(yahoo::YahooProvider::new(api_url, http))),
+        "finnhub" => Some(Arc::new(finnhub::FinnhubProvider::new(api_key, api_url, http))),
+        "alphavantage" => Some(Arc::new(alphavantage::AlphaVantageProvider::new(api_key, api_url, http))),
+        "polygon" => Some(Arc::new(polygon::PolygonProvider::new(api_key, api_url, http))),
+        "twelvedata" => Some(Arc::new(twelvedata::TwelveDataProvider::new(api_key, api_url, http))),
+        "alpaca" => Some(Arc::new(alpaca::AlpacaProvider::new(api_key, api_secret, api_url, http))),
+        "tiingo" => Some(Arc::new(tiingo::TiingoProvider::new(api_key, api_url, http))),
+        "fmp" => Some(Arc::new(fmp::FMPProvider::new(api_key, api_url, http))),
+        "marketstack" => Some(Arc::new(marketstack::MarketstackProvider::new(api_key, api_url, http))),
+        "eodhd" => Some(Arc::new(eodhd::EODHDProvider::new(api_key, api_url, http))),
+        "mboum" => Some(Arc::new(mboum::MboumProvider::new(api_key, api_url, http))),
+        "fcsapi" => Some(Arc::new(fcsapi::FcsApiProvider::new(api_key, api_url, http))),
         // Multi-asset aggregators
-        "coinapi" => Some(Arc::new(coinapi::CoinApiProvider::new(api_key))),
+        "coinapi" => Some(Arc::new(coinapi::CoinApiProvider::new(api_key, api_url, http))),
         // DEX aggregators
-        "jupiter" => Some(Arc::new(jupiter::JupiterProvider::new(api_key))),
-        "okx_dex" => Some(Arc::new(okx_dex::OkxDexProvider::new(api_key))),
-        "raydium" => Some(Arc::new(raydium::RaydiumProvider::new(api_key, api_url))),
-        "subgraph" => Some(Arc::new(subgraph::SubgraphProvider::new(api_key, api_url))),
+        "jupiter" => Some(Arc::new(jupiter::JupiterProvider::new(api_key, api_url, http))),
+        "okx_dex" => Some(Arc::new(okx_dex::OkxDexProvider::new(api_key, api_url, http))),
+        "raydium" => Some(Arc::new(raydium::RaydiumProvider::new(api_key, api_url, http))),
+        "subgraph" => Some(Arc::new(subgraph::SubgraphProvider::new(api_key, api_url, http))),
         // Prediction markets
-        "polymarket" => Some(Arc::new(polymarket::PolymarketProvider::new())),
-        "bitquery" => Some(Arc::new(bitquery::BitqueryProvider::new(api_key))),
+        "polymarket" => Some(Arc::new(polymarket::PolymarketProvider::new(api_url, http))),
+        "bitquery" => Some(Arc::new(bitquery::BitqueryProvider::new(api_key, api_url, http))),
+        // Demo mode
+        "mock" if mock::mock_config().enabled => {
+            Some(Arc::new(mock::MockProvider::new(mock::mock_config())))
+        }
         _ => None,
     }
 }
@@ -108,6 +123,10 @@ pub fn create_provider_with_url(
 pub fn create_ws_provider(id: &str) -> Option<Arc<dyn WebSocketProvider>> {
     match id {
         "binance" => Some(Arc::new(ws_binance::BinanceWsProvider::new())),
+        "bybit" => Some(Arc::new(ws_bybit::BybitWsProvider::new())),
+        "coinbase" => Some(Arc::new(ws_coinbase::CoinbaseWsProvider::new())),
+        "kraken" => Some(Arc::new(ws_kraken::KrakenWsProvider::new())),
+        "okx" => Some(Arc::new(ws_okx::OkxWsProvider::new())),
         _ => None,
     }
 }
@@ -116,11 +135,28 @@ pub fn create_dex_lookup(
     id: &str,
     api_key: Option<String>,
     api_url: Option<String>,
+    http: &HttpOptions,
 ) -> Option<Arc<dyn DexPoolLookup>> {
     match id {
-        "raydium" => Some(Arc::new(raydium::RaydiumProvider::new(api_key, api_url))),
-        "subgraph" => Some(Arc::new(subgraph::SubgraphProvider::new(api_key, api_url))),
-        "jupiter" => Some(Arc::new(jupiter::JupiterProvider::new(api_key))),
+        "raydium" => Some(Arc::new(raydium::RaydiumProvider::new(api_key, api_url, http))),
+        "subgraph" => Some(Arc::new(subgraph::SubgraphProvider::new(api_key, api_url, http))),
+        "jupiter" => Some(Arc::new(jupiter::JupiterProvider::new(api_key, api_url, http))),
+        _ => None,
+    }
+}
+
+/// 支援任意 swap 詢價的 DEX 聚合器（`compare_dex_quotes` 依序比較）
+pub const DEX_QUOTER_IDS: &[&str] = &["jupiter", "okx_dex"];
+
+pub fn create_dex_quoter(
+    id: &str,
+    api_key: Option<String>,
+    api_url: Option<String>,
+    http: &HttpOptions,
+) -> Option<Arc<dyn DexQuoter>> {
+    match id {
+        "jupiter" => Some(Arc::new(jupiter::JupiterProvider::new(api_key, api_url, http))),
+        "okx_dex" => Some(Arc::new(okx_dex::OkxDexProvider::new(api_key, api_url, http))),
         _ => None,
     }
 }
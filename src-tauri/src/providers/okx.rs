@@ -1,32 +1,62 @@
+use super::debug::SendCaptured;
+use super::derivatives::{self, Positioning};
 use super::traits::*;
 use super::types::*;
+use std::collections::HashMap;
+
+const DEFAULT_BASE_URL: &str = "https://www.okx.com";
 
 pub struct OkxProvider {
     client: reqwest::Client,
+    base_url: String,
 }
 
 impl Default for OkxProvider {
     fn default() -> Self {
-        Self::new()
+        Self::new(None, &HttpOptions::default())
     }
 }
 
 impl OkxProvider {
-    pub fn new() -> Self {
+    pub fn new(api_url: Option<String>, http: &HttpOptions) -> Self {
         Self {
-            client: shared_client(),
+            client: provider_client(http),
+            base_url: resolve_base_url(api_url, DEFAULT_BASE_URL),
         }
     }
+
+    async fn get_json(&self, path: &str) -> Result<serde_json::Value, String> {
+        self.client
+            .get(format!("{}{}", self.base_url, path))
+            .send_captured("okx")
+            .await
+            .map_err(|e| format!("OKX connection failed: {}", e))?
+            .error_for_status()
+            .map_err(|e| format!("OKX API error: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("OKX parse failed: {}", e))
+    }
+
+    /// 查詢 `{base}-{quote}-SWAP` 永續合約的未平倉量（必要）與幣種多空帳戶比（可缺）
+    async fn fetch_positioning(&self, symbol: String) -> Result<Positioning, String> {
+        let inst = format!("{}-SWAP", to_okx_symbol(&symbol));
+        let (base, _) = parse_crypto_symbol(&symbol);
+        let oi_path = format!("/api/v5/public/open-interest?instType=SWAP&instId={}", inst);
+        let ratio_path = format!("/api/v5/rubik/stat/contracts/long-short-account-ratio?ccy={}&period=5m", base);
+        let (open_interest, ratio) = futures::join!(self.get_json(&oi_path), self.get_json(&ratio_path));
+        parse_positioning(&open_interest?, ratio.ok().as_ref())
+    }
 }
 
 /// Convert to OKX format: BTC-USDT
-fn to_okx_symbol(symbol: &str) -> String {
+pub(super) fn to_okx_symbol(symbol: &str) -> String {
     let (base, quote) = parse_crypto_symbol(symbol);
     let q = if quote == "USD" { "USDT" } else { &quote };
     format!("{}-{}", base, q)
 }
 
-fn parse_okx_ticker(symbol: &str, item: &serde_json::Value) -> AssetData {
+pub(super) fn parse_okx_ticker(symbol: &str, item: &serde_json::Value) -> AssetData {
     let pf = |k: &str| item[k].as_str().and_then(|s| s.parse::<f64>().ok());
     let last = pf("last").unwrap_or(0.0);
     let open = pf("open24h").unwrap_or(0.0);
@@ -49,6 +79,19 @@ fn parse_okx_ticker(symbol: &str, item: &serde_json::Value) -> AssetData {
         .build()
 }
 
+/// 由 open-interest 與 long-short-account-ratio 回應組成持倉資料（ratio 資料最新一筆在前）
+fn parse_positioning(open_interest: &serde_json::Value, ratio: Option<&serde_json::Value>) -> Result<Positioning, String> {
+    let item = open_interest["data"]
+        .as_array()
+        .and_then(|a| a.first())
+        .ok_or("OKX: perpetual swap not found")?;
+    Ok(Positioning {
+        open_interest: derivatives::num(&item["oiCcy"]),
+        open_interest_usd: derivatives::num(&item["oiUsd"]),
+        long_short_ratio: ratio.and_then(|r| derivatives::num(&r["data"][0][1])),
+    })
+}
+
 #[async_trait::async_trait]
 impl DataProvider for OkxProvider {
     fn info(&self) -> ProviderInfo {
@@ -57,11 +100,11 @@ impl DataProvider for OkxProvider {
 
     async fn fetch_price(&self, symbol: &str) -> Result<AssetData, String> {
         let inst = to_okx_symbol(symbol);
-        let url = format!("https://www.okx.com/api/v5/market/ticker?instId={}", inst);
+        let url = format!("{}/api/v5/market/ticker?instId={}", self.base_url, inst);
         let data: serde_json::Value = self
             .client
             .get(&url)
-            .send()
+            .send_captured("okx")
             .await
             .map_err(|e| format!("OKX connection failed: {}", e))?
             .json()
@@ -85,11 +128,11 @@ impl DataProvider for OkxProvider {
         }
 
         // OKX tickers endpoint returns all SPOT tickers
-        let url = "https://www.okx.com/api/v5/market/tickers?instType=SPOT";
+        let url = format!("{}/api/v5/market/tickers?instType=SPOT", self.base_url);
         let data: serde_json::Value = self
             .client
             .get(url)
-            .send()
+            .send_captured("okx")
             .await
             .map_err(|e| format!("OKX batch connection failed: {}", e))?
             .json()
@@ -113,4 +156,34 @@ impl DataProvider for OkxProvider {
         }
         Ok(out)
     }
+
+    /// `provider_params` 支援 `{"derivatives": true}`：另查詢同交易對永續合約的未平倉量與多空比
+    async fn fetch_prices_with_params(
+        &self,
+        symbols: &[String],
+        params: &HashMap<String, ProviderParams>,
+    ) -> Result<Vec<AssetData>, String> {
+        let mut results = self.fetch_prices(symbols).await?;
+        derivatives::enrich(&mut results, params, |sym| self.fetch_positioning(sym)).await;
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn positioning_parses_swap_responses() {
+        let oi = json!({"code": "0", "data": [
+            {"instId": "BTC-USDT-SWAP", "oi": "2875123", "oiCcy": "28751.23", "oiUsd": "1955083640.1", "ts": "1700000000000"}
+        ]});
+        let ratio = json!({"code": "0", "data": [["1700000000000", "1.42"], ["1699999700000", "1.40"]]});
+        let p = parse_positioning(&oi, Some(&ratio)).unwrap();
+        assert_eq!(p.open_interest, Some(28751.23));
+        assert_eq!(p.open_interest_usd, Some(1955083640.1));
+        assert_eq!(p.long_short_ratio, Some(1.42));
+        assert!(parse_positioning(&json!({"code": "51001", "data": []}), None).is_err());
+    }
 }
@@ -1,52 +1,195 @@
+use super::debug::SendCaptured;
 use super::traits::*;
 use super::types::*;
 
 /// OKX DEX 聚合器 — 多鏈 DEX 聚合器 Spot Price
 /// 使用 swap quote API 推導即時價格：
 /// GET https://web3.okx.com/api/v5/dex/aggregator/quote
-///   ?chainId=1&fromTokenAddress=<token>&toTokenAddress=<USDC>&amount=<1_token>
+///   ?chainId=1&fromTokenAddress=<token>&toTokenAddress=<USDC|USDT>&amount=<N_tokens>
 ///
 /// 需要 API key（OKX Web3 Developer Portal 免費申請）
 /// Header: OK-ACCESS-KEY
+const DEFAULT_BASE_URL: &str = "https://web3.okx.com";
+
 pub struct OkxDexProvider {
     client: reqwest::Client,
+    base_url: String,
     api_key: Option<String>,
 }
 
 impl OkxDexProvider {
-    pub fn new(api_key: Option<String>) -> Self {
+    pub fn new(api_key: Option<String>, api_url: Option<String>, http: &HttpOptions) -> Self {
         Self {
-            client: shared_client(),
+            client: provider_client(http),
+            base_url: resolve_base_url(api_url, DEFAULT_BASE_URL),
             api_key,
         }
     }
 }
 
+/// 查詢單一 symbol 的報價（`fetch_price` 與批量並行查詢共用）
+async fn fetch_quote(
+    client: &reqwest::Client,
+    base_url: &str,
+    api_key: Option<&str>,
+    symbol: &str,
+) -> Result<AssetData, String> {
+    let api_key = api_key.ok_or_else(|| {
+        "OKX DEX requires API key (free at OKX Web3 Developer Portal)".to_string()
+    })?;
+
+    let query = parse_okx_dex_query(symbol)?;
+    // 以 amount 個完整 token 的最小單位數量查詢報價
+    let amount = 10u128
+        .checked_pow(query.decimals)
+        .and_then(|unit| unit.checked_mul(query.amount))
+        .ok_or_else(|| format!("OKX DEX: amount too large for {}", symbol))?;
+
+    let url = format!(
+        "{}/api/v5/dex/aggregator/quote?chainId={}&fromTokenAddress={}&toTokenAddress={}&amount={}",
+        base_url, query.chain_id, query.token_address, query.quote.address, amount
+    );
+
+    let resp: serde_json::Value = client
+        .get(&url)
+        .header("OK-ACCESS-KEY", api_key)
+        .send_captured("okx_dex")
+        .await
+        .map_err(|e| format!("OKX DEX connection failed: {}", e))?
+        .error_for_status()
+        .map_err(|e| format!("OKX DEX API error: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("OKX DEX parse failed: {}", e))?;
+
+    let code = resp["code"].as_str().unwrap_or("");
+    if code != "0" {
+        let msg = resp["msg"].as_str().unwrap_or("unknown error");
+        return Err(format!("OKX DEX error ({}): {}", code, msg));
+    }
+
+    let data = &resp["data"][0];
+    let to_amount: f64 = data["toTokenAmount"]
+        .as_str()
+        .unwrap_or("0")
+        .parse()
+        .unwrap_or(0.0);
+    // toTokenAmount 是穩定幣的最小單位，換算後再除以詢價的 token 數量得到單價
+    let price = to_amount / 10f64.powi(query.quote.decimals as i32) / query.amount as f64;
+
+    let estimate_gas = data["estimateGasFee"]
+        .as_str()
+        .and_then(|s| s.parse::<f64>().ok());
+
+    Ok(AssetDataBuilder::new(symbol, "okx_dex")
+        .price(price)
+        .currency("USD")
+        .extra_str("chain", Some(chain_name(&query.chain_id)))
+        .extra_str("token", Some(&query.token_address))
+        .extra_str("quote", Some(query.quote.symbol))
+        .extra_f64("est_gas", estimate_gas)
+        .build())
+}
+
 /// 鏈 ID 常量
 const CHAIN_ETH: &str = "1";
+const CHAIN_OPTIMISM: &str = "10";
 const CHAIN_BSC: &str = "56";
 const CHAIN_POLYGON: &str = "137";
+const CHAIN_BASE: &str = "8453";
 const CHAIN_ARBITRUM: &str = "42161";
+const CHAIN_AVALANCHE: &str = "43114";
 const CHAIN_SOLANA: &str = "501";
 
-/// USDC 地址（各鏈）
-fn usdc_address(chain_id: &str) -> &'static str {
-    match chain_id {
-        "1" => "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48", // ETH USDC
-        "56" => "0x8ac76a51cc950d9822d68b83fe1ad97b32cd580d", // BSC USDC
-        "137" => "0x3c499c542cef5e3811e1192ce70d8cc03d5c3359", // Polygon USDC
-        "42161" => "0xaf88d065e77c8cc2239327c5edb3a432268e5831", // Arbitrum USDC
-        "501" => "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v", // Solana USDC
-        _ => "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48",   // 默認 ETH
-    }
+/// 報價用的穩定幣
+#[derive(Debug, Clone, PartialEq)]
+struct QuoteToken {
+    symbol: &'static str,
+    address: &'static str,
+    decimals: u32,
 }
 
-/// USDC decimals
-fn usdc_decimals(chain_id: &str) -> u32 {
-    match chain_id {
-        "501" => 6, // Solana USDC
-        _ => 6,     // EVM USDC 都是 6
+/// 各鏈的穩定幣地址與 decimals（BSC 上的 USDC / USDT 為 18 位）
+fn quote_token(chain_id: &str, symbol: &str) -> Option<QuoteToken> {
+    let (address, decimals) = match (chain_id, symbol) {
+        ("1", "USDC") => ("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48", 6),
+        ("1", "USDT") => ("0xdac17f958d2ee523a2206206994597c13d831ec7", 6),
+        ("10", "USDC") => ("0x0b2c639c533813f4aa9d7837caf62653d097ff85", 6),
+        ("10", "USDT") => ("0x94b008aa00579c1307b0ef2c499ad98a8ce58e58", 6),
+        ("56", "USDC") => ("0x8ac76a51cc950d9822d68b83fe1ad97b32cd580d", 18),
+        ("56", "USDT") => ("0x55d398326f99059ff775485246999027b3197955", 18),
+        ("137", "USDC") => ("0x3c499c542cef5e3811e1192ce70d8cc03d5c3359", 6),
+        ("137", "USDT") => ("0xc2132d05d31c914a87c6611c10748aeb04b58e8f", 6),
+        ("8453", "USDC") => ("0x833589fcd6edb6e08f4c7c32d4f71b54bda02913", 6),
+        ("42161", "USDC") => ("0xaf88d065e77c8cc2239327c5edb3a432268e5831", 6),
+        ("42161", "USDT") => ("0xfd086bc7cd5c481dcc9c85ebe478a1c0b69fcbb9", 6),
+        ("43114", "USDC") => ("0xb97ef9ef8734c71904d8002f8b6bc66dd9c48a6e", 6),
+        ("43114", "USDT") => ("0x9702230a8ea53601f5cd2dc00fdbc13d4df4a8c7", 6),
+        ("501", "USDC") => ("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v", 6),
+        ("501", "USDT") => ("Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB", 6),
+        _ => return None,
+    };
+    let symbol = if symbol == "USDT" { "USDT" } else { "USDC" };
+    Some(QuoteToken {
+        symbol,
+        address,
+        decimals,
+    })
+}
+
+/// 一筆 OKX DEX 報價查詢
+#[derive(Debug, Clone, PartialEq)]
+struct OkxDexQuery {
+    chain_id: String,
+    token_address: String,
+    decimals: u32,
+    quote: QuoteToken,
+    /// 以幾個完整 token 詢價（低價幣用較大的數量可減少路由誤差）
+    amount: u128,
+}
+
+/// 解析 symbol 並套用 `key=value` 選項：
+///   - `quote=USDT` / `quote=USDC`（預設 USDC）
+///   - `amount=1000` 以 1000 個 token 詢價，價格再除回單價（預設 1）
+///
+/// 例：`eth:0x...:quote=USDT`、`sol:mint:6:amount=1000`、`PEPE:amount=1000000`
+fn parse_okx_dex_query(symbol: &str) -> Result<OkxDexQuery, String> {
+    let mut quote = "USDC".to_string();
+    let mut amount: u128 = 1;
+    let mut parts = Vec::new();
+    for part in symbol.trim().split(':') {
+        match part.split_once('=') {
+            Some((key, value)) => match key.trim().to_lowercase().as_str() {
+                "quote" => quote = value.trim().to_uppercase(),
+                "amount" => {
+                    amount = value
+                        .trim()
+                        .parse()
+                        .ok()
+                        .filter(|a| *a > 0)
+                        .ok_or_else(|| format!("OKX DEX: invalid amount '{}'", value))?
+                }
+                other => return Err(format!("OKX DEX: unknown option '{}'", other)),
+            },
+            None => parts.push(part),
+        }
     }
+
+    let (chain_id, token_address, decimals) = parse_okx_dex_symbol(&parts.join(":"));
+    let quote = quote_token(&chain_id, &quote).ok_or_else(|| {
+        format!(
+            "OKX DEX: {} is not available as quote token on {}",
+            quote,
+            chain_name(&chain_id)
+        )
+    })?;
+    Ok(OkxDexQuery {
+        chain_id,
+        token_address,
+        decimals,
+        quote,
+        amount,
+    })
 }
 
 /// 解析用戶輸入的 symbol → (chain_id, token_address, decimals)
@@ -57,19 +200,13 @@ fn usdc_decimals(chain_id: &str) -> u32 {
 ///   - "eth:0x..." → 指定鏈 + 合約地址
 ///   - "sol:mint_address" → Solana mint address
 ///   - "arb:0x..." → Arbitrum 合約地址
+///   - "base:0x..." / "avax:0x..." / "op:0x..." → Base / Avalanche / Optimism 合約地址
 fn parse_okx_dex_symbol(symbol: &str) -> (String, String, u32) {
     let s = symbol.trim();
 
     // 格式: "chain:address" 或 "chain:address:decimals"
     if let Some((chain_prefix, rest)) = s.split_once(':') {
-        let chain_id = match chain_prefix.to_lowercase().as_str() {
-            "eth" | "ethereum" => CHAIN_ETH,
-            "bsc" | "bnb" => CHAIN_BSC,
-            "polygon" | "matic" => CHAIN_POLYGON,
-            "arb" | "arbitrum" => CHAIN_ARBITRUM,
-            "sol" | "solana" => CHAIN_SOLANA,
-            _ => CHAIN_ETH,
-        };
+        let chain_id = chain_id(chain_prefix).unwrap_or(CHAIN_ETH);
         // 可能有 :decimals 後綴
         if let Some((addr, dec_str)) = rest.split_once(':') {
             let decimals = dec_str.parse().unwrap_or(18);
@@ -167,12 +304,89 @@ fn parse_okx_dex_symbol(symbol: &str) -> (String, String, u32) {
     }
 }
 
+/// 鏈前綴 → OKX chain ID
+fn chain_id(chain_prefix: &str) -> Option<&'static str> {
+    Some(match chain_prefix.trim().to_lowercase().as_str() {
+        "eth" | "ethereum" => CHAIN_ETH,
+        "bsc" | "bnb" => CHAIN_BSC,
+        "polygon" | "matic" => CHAIN_POLYGON,
+        "arb" | "arbitrum" => CHAIN_ARBITRUM,
+        "base" => CHAIN_BASE,
+        "avax" | "avalanche" => CHAIN_AVALANCHE,
+        "op" | "optimism" => CHAIN_OPTIMISM,
+        "sol" | "solana" => CHAIN_SOLANA,
+        _ => return None,
+    })
+}
+
+/// 詢價用的 token → (address, decimals)：穩定幣 → 同鏈的常見代號 → `chain:address[:decimals]`
+fn resolve_token(chain_id: &str, chain: &str, token: &str) -> (String, u32) {
+    let token = token.trim();
+    if let Some(stable) = quote_token(chain_id, &token.to_uppercase()) {
+        return (stable.address.to_string(), stable.decimals);
+    }
+    if !token.contains(':') {
+        let (id, address, decimals) = parse_okx_dex_symbol(token);
+        if id == chain_id && address != token {
+            return (address, decimals);
+        }
+    }
+    let (_, address, decimals) = parse_okx_dex_symbol(&format!("{}:{}", chain, token));
+    (address, decimals)
+}
+
+/// 解析以 `amount` 個 token_in 詢價的 swap quote 回應；`out_decimals` 為回應未帶 decimals 時的備用值
+fn parse_swap_quote(resp: &serde_json::Value, amount: f64, out_decimals: u32) -> Result<DexQuote, String> {
+    let code = resp["code"].as_str().unwrap_or("");
+    if code != "0" {
+        let msg = resp["msg"].as_str().unwrap_or("unknown error");
+        return Err(format!("OKX DEX error ({}): {}", code, msg));
+    }
+    let data = &resp["data"][0];
+    let to_amount: f64 = data["toTokenAmount"]
+        .as_str()
+        .and_then(|s| s.parse().ok())
+        .ok_or("OKX DEX quote missing toTokenAmount")?;
+    let decimals = data["toToken"]["decimal"]
+        .as_str()
+        .and_then(|s| s.parse::<i32>().ok())
+        .unwrap_or(out_decimals as i32);
+    let price_impact = data["priceImpactPercentage"]
+        .as_str()
+        .and_then(|s| s.parse::<f64>().ok())
+        .map(f64::abs);
+    let gas = data["estimateGasFee"]
+        .as_str()
+        .map(|g| format!("{} gas", g));
+    let route = data["dexRouterList"].as_array().map(|routers| {
+        routers
+            .iter()
+            .flat_map(|r| r["subRouterList"].as_array().into_iter().flatten())
+            .flat_map(|sub| sub["dexProtocol"].as_array().into_iter().flatten())
+            .filter_map(|p| p["dexName"].as_str())
+            .collect::<Vec<_>>()
+            .join(" → ")
+    });
+    let amount_out = to_amount / 10f64.powi(decimals);
+    Ok(DexQuote {
+        provider_id: "okx_dex".to_string(),
+        amount_out,
+        price: amount_out / amount,
+        price_impact,
+        gas_estimate: gas,
+        route: route.filter(|r| !r.is_empty()),
+    })
+}
+
 fn chain_name(chain_id: &str) -> &'static str {
     match chain_id {
         "1" => "Ethereum",
         "56" => "BSC",
         "137" => "Polygon",
         "42161" => "Arbitrum",
+        "8453" => "Base",
+        "43114" => "Avalanche",
+        "10" => "Optimism",
         "501" => "Solana",
         _ => "Unknown",
     }
@@ -185,58 +399,7 @@ impl DataProvider for OkxDexProvider {
     }
 
     async fn fetch_price(&self, symbol: &str) -> Result<AssetData, String> {
-        let api_key = self.api_key.as_deref().ok_or_else(|| {
-            "OKX DEX requires API key (free at OKX Web3 Developer Portal)".to_string()
-        })?;
-
-        let (chain_id, token_address, decimals) = parse_okx_dex_symbol(symbol);
-        let usdc_addr = usdc_address(&chain_id);
-        let usdc_dec = usdc_decimals(&chain_id);
-
-        // 用 1 個完整 token 的最小單位數量來查詢報價
-        let amount = 10u128.pow(decimals);
-
-        let url = format!(
-            "https://web3.okx.com/api/v5/dex/aggregator/quote?chainId={}&fromTokenAddress={}&toTokenAddress={}&amount={}",
-            chain_id, token_address, usdc_addr, amount
-        );
-
-        let resp: serde_json::Value = self
-            .client
-            .get(&url)
-            .header("OK-ACCESS-KEY", api_key)
-            .send()
-            .await
-            .map_err(|e| format!("OKX DEX connection failed: {}", e))?
-            .error_for_status()
-            .map_err(|e| format!("OKX DEX API error: {}", e))?
-            .json()
-            .await
-            .map_err(|e| format!("OKX DEX parse failed: {}", e))?;
-
-        let code = resp["code"].as_str().unwrap_or("");
-        if code != "0" {
-            let msg = resp["msg"].as_str().unwrap_or("unknown error");
-            return Err(format!("OKX DEX error ({}): {}", code, msg));
-        }
-
-        let data = &resp["data"][0];
-        let to_amount_str = data["toTokenAmount"].as_str().unwrap_or("0");
-        let to_amount: f64 = to_amount_str.parse().unwrap_or(0.0);
-        // toTokenAmount 是 USDC 的最小單位，需要除以 10^usdc_decimals 得到 USD 價格
-        let price = to_amount / 10f64.powi(usdc_dec as i32);
-
-        let estimate_gas = data["estimateGasFee"]
-            .as_str()
-            .and_then(|s| s.parse::<f64>().ok());
-
-        Ok(AssetDataBuilder::new(symbol, "okx_dex")
-            .price(price)
-            .currency("USD")
-            .extra_str("chain", Some(chain_name(&chain_id)))
-            .extra_str("token", Some(&token_address))
-            .extra_f64("est_gas", estimate_gas)
-            .build())
+        fetch_quote(&self.client, &self.base_url, self.api_key.as_deref(), symbol).await
     }
 
     async fn fetch_prices(&self, symbols: &[String]) -> Result<Vec<AssetData>, String> {
@@ -252,62 +415,129 @@ impl DataProvider for OkxDexProvider {
             let sym = sym.clone();
             let client = self.client.clone();
             let api_key = self.api_key.clone();
+            let base_url = self.base_url.clone();
             let sem = semaphore.clone();
             tasks.spawn(async move {
                 let _permit = sem.acquire().await;
-                let api_key = match api_key.as_deref() {
-                    Some(k) => k,
-                    None => return None,
-                };
-                let (chain_id, token_address, decimals) = parse_okx_dex_symbol(&sym);
-                let usdc_addr = usdc_address(&chain_id);
-                let usdc_dec = usdc_decimals(&chain_id);
-                let amount = 10u128.pow(decimals);
-                let url = format!(
-                    "https://web3.okx.com/api/v5/dex/aggregator/quote?chainId={}&fromTokenAddress={}&toTokenAddress={}&amount={}",
-                    chain_id, token_address, usdc_addr, amount
-                );
-                let resp: serde_json::Value = match client
-                    .get(&url)
-                    .header("OK-ACCESS-KEY", api_key)
-                    .send()
-                    .await
-                {
-                    Ok(r) => match r.json().await {
-                        Ok(j) => j,
-                        Err(_) => return None,
-                    },
-                    Err(_) => return None,
-                };
-                if resp["code"].as_str().unwrap_or("") != "0" {
-                    return None;
-                }
-                let data = &resp["data"][0];
-                let to_amount: f64 = data["toTokenAmount"]
-                    .as_str()
-                    .unwrap_or("0")
-                    .parse()
-                    .unwrap_or(0.0);
-                let price = to_amount / 10f64.powi(usdc_dec as i32);
-                let estimate_gas = data["estimateGasFee"]
-                    .as_str()
-                    .and_then(|s| s.parse::<f64>().ok());
-                Some(
-                    AssetDataBuilder::new(&sym, "okx_dex")
-                        .price(price)
-                        .currency("USD")
-                        .extra_str("chain", Some(chain_name(&chain_id)))
-                        .extra_str("token", Some(&token_address))
-                        .extra_f64("est_gas", estimate_gas)
-                        .build(),
-                )
+                fetch_quote(&client, &base_url, api_key.as_deref(), &sym).await
             });
         }
-        while let Some(Ok(maybe)) = tasks.join_next().await {
-            if let Some(data) = maybe {
-                results.push(data);
+        while let Some(Ok(result)) = tasks.join_next().await {
+            match result {
+                Ok(data) => results.push(data),
+                Err(e) => tracing::warn!("OKX DEX skipped: {}", e),
             }
         }
         Ok(results)
     }
 }
+
+#[async_trait::async_trait]
+impl DexQuoter for OkxDexProvider {
+    fn supports_chain(&self, chain: &str) -> bool {
+        chain_id(chain).is_some()
+    }
+
+    async fn quote(
+        &self,
+        chain: &str,
+        token_in: &str,
+        token_out: &str,
+        amount: f64,
+    ) -> Result<DexQuote, String> {
+        let api_key = self.api_key.as_deref().ok_or_else(|| {
+            "OKX DEX requires API key (free at OKX Web3 Developer Portal)".to_string()
+        })?;
+        let chain_id = chain_id(chain).ok_or_else(|| format!("OKX DEX: unsupported chain '{}'", chain))?;
+        let (from, in_decimals) = resolve_token(chain_id, chain, token_in);
+        let (to, out_decimals) = resolve_token(chain_id, chain, token_out);
+        let raw_amount = (amount * 10f64.powi(in_decimals as i32)).round();
+        if raw_amount < 1.0 || raw_amount > u128::MAX as f64 {
+            return Err(format!("OKX DEX: invalid amount {}", amount));
+        }
+
+        let url = format!(
+            "{}/api/v5/dex/aggregator/quote?chainId={}&fromTokenAddress={}&toTokenAddress={}&amount={}",
+            self.base_url, chain_id, from, to, raw_amount as u128
+        );
+        let resp: serde_json::Value = self
+            .client
+            .get(&url)
+            .header("OK-ACCESS-KEY", api_key)
+            .send_captured("okx_dex")
+            .await
+            .map_err(|e| format!("OKX DEX connection failed: {}", e))?
+            .error_for_status()
+            .map_err(|e| format!("OKX DEX API error: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("OKX DEX parse failed: {}", e))?;
+
+        parse_swap_quote(&resp, amount, out_decimals)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quote_and_amount_options_are_parsed_from_symbol() {
+        let q = parse_okx_dex_query("base:0xabc:quote=usdt").unwrap_err();
+        assert!(q.contains("USDT"), "{}", q);
+
+        let q = parse_okx_dex_query("bsc:0xabc:9:quote=USDT:amount=1000").unwrap();
+        assert_eq!(q.chain_id, CHAIN_BSC);
+        assert_eq!(q.token_address, "0xabc");
+        assert_eq!(q.decimals, 9);
+        assert_eq!(q.quote.symbol, "USDT");
+        assert_eq!(q.quote.decimals, 18);
+        assert_eq!(q.amount, 1000);
+
+        let q = parse_okx_dex_query("PEPE:amount=1000000").unwrap();
+        assert_eq!(q.chain_id, CHAIN_ETH);
+        assert_eq!(q.quote.symbol, "USDC");
+        assert_eq!(q.amount, 1_000_000);
+
+        assert!(parse_okx_dex_query("eth:0xabc:amount=0").is_err());
+        assert!(parse_okx_dex_query("eth:0xabc:slippage=1").is_err());
+    }
+
+    #[test]
+    fn swap_quote_tokens_and_response_are_normalized() {
+        assert_eq!(
+            resolve_token(CHAIN_ETH, "eth", "usdc"),
+            ("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string(), 6)
+        );
+        assert_eq!(
+            resolve_token(CHAIN_ETH, "eth", "ETH"),
+            ("0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee".to_string(), 18)
+        );
+        assert_eq!(resolve_token(CHAIN_BASE, "base", "0xabc:8"), ("0xabc".to_string(), 8));
+        assert!(chain_id("tron").is_none());
+
+        let resp = serde_json::json!({"code": "0", "data": [{
+            "toTokenAmount": "2500000000",
+            "toToken": {"decimal": "6"},
+            "priceImpactPercentage": "-0.12",
+            "estimateGasFee": "135000",
+            "dexRouterList": [{"subRouterList": [{"dexProtocol": [{"dexName": "Uniswap V3"}]}]}]
+        }]});
+        let quote = parse_swap_quote(&resp, 2.0, 18).unwrap();
+        assert_eq!(quote.amount_out, 2500.0);
+        assert_eq!(quote.price, 1250.0);
+        assert_eq!(quote.price_impact, Some(0.12));
+        assert_eq!(quote.gas_estimate.as_deref(), Some("135000 gas"));
+        assert_eq!(quote.route.as_deref(), Some("Uniswap V3"));
+        assert!(parse_swap_quote(&serde_json::json!({"code": "50011", "msg": "rate limited"}), 1.0, 6).is_err());
+    }
+
+    #[test]
+    fn new_chains_resolve_with_native_usdc() {
+        for (prefix, chain) in [("base", CHAIN_BASE), ("avax", CHAIN_AVALANCHE), ("op", CHAIN_OPTIMISM)] {
+            let q = parse_okx_dex_query(&format!("{}:0xabc", prefix)).unwrap();
+            assert_eq!(q.chain_id, chain);
+            assert_eq!(q.quote.symbol, "USDC");
+        }
+    }
+}
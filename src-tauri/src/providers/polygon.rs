@@ -1,16 +1,21 @@
+use super::debug::SendCaptured;
 use super::traits::*;
 use super::types::*;
 use std::collections::HashMap;
 
+const DEFAULT_BASE_URL: &str = "https://api.polygon.io";
+
 pub struct PolygonProvider {
     client: reqwest::Client,
+    base_url: String,
     api_key: Option<String>,
 }
 
 impl PolygonProvider {
-    pub fn new(api_key: Option<String>) -> Self {
+    pub fn new(api_key: Option<String>, api_url: Option<String>, http: &HttpOptions) -> Self {
         Self {
-            client: shared_client(),
+            client: provider_client(http),
+            base_url: resolve_base_url(api_url, DEFAULT_BASE_URL),
             api_key,
         }
     }
@@ -91,37 +96,52 @@ impl PolygonProvider {
 
         // 市場狀態
         if has_pre {
-            builder = builder.extra_str("market_session", Some("PRE"));
             let pre_price = pre_mkt["close"].as_f64().unwrap_or(0.0);
-            builder = builder.extra_f64("pre_market_price", Some(pre_price));
-            if let Some(pc) = prev_close {
-                let pre_change = pre_price - pc;
-                let pre_pct = if pc > 0.0 {
-                    (pre_change / pc) * 100.0
-                } else {
-                    0.0
-                };
-                builder = builder.extra_f64("pre_market_change", Some(pre_change));
-                builder = builder.extra_f64("pre_market_change_pct", Some(pre_pct));
-            }
+            builder = builder
+                .extra_str("market_session", Some("PRE"))
+                .pre_market(Some(SessionQuote::against(pre_price, prev_close)));
         } else if has_post {
-            builder = builder.extra_str("market_session", Some("POST"));
             let post_price = post_mkt["close"].as_f64().unwrap_or(0.0);
-            builder = builder.extra_f64("post_market_price", Some(post_price));
-            let post_change = post_price - price;
-            let post_pct = if price > 0.0 {
-                (post_change / price) * 100.0
-            } else {
-                0.0
-            };
-            builder = builder.extra_f64("post_market_change", Some(post_change));
-            builder = builder.extra_f64("post_market_change_pct", Some(post_pct));
+            builder = builder
+                .extra_str("market_session", Some("POST"))
+                .post_market(Some(SessionQuote::against(post_price, Some(price))));
         } else {
             builder = builder.extra_str("market_session", Some("REGULAR"));
         }
 
         builder.build()
     }
+
+    /// 前一交易日 aggregates；`adjusted = false` 時回傳未經分割調整的價格
+    async fn fetch_prev(&self, symbol: &str, adjusted: bool) -> Result<AssetData, String> {
+        let api_key = self.api_key.as_ref().ok_or("Polygon.io requires API key")?;
+        let api_symbol = Self::to_polygon_symbol(symbol);
+        let unadjusted = if adjusted { "" } else { "&adjusted=false" };
+        let data: serde_json::Value = self
+            .client
+            .get(format!(
+                "{}/v2/aggs/ticker/{}/prev?apiKey={}{}",
+                self.base_url,
+                api_symbol, api_key, unadjusted
+            ))
+            .send_captured("polygon")
+            .await
+            .map_err(|e| format!("Polygon connection failed: {}", e))?
+            .error_for_status()
+            .map_err(|e| format!("Polygon API error: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("Polygon parse failed: {}", e))?;
+
+        let r = &data["results"][0];
+        if r.is_null() {
+            return Err(format!(
+                "Polygon not found: {}. Use AAPL for stocks, X:BTCUSD for crypto",
+                symbol
+            ));
+        }
+        Ok(Self::parse_agg(symbol, r))
+    }
 }
 
 #[async_trait::async_trait]
@@ -137,10 +157,11 @@ impl DataProvider for PolygonProvider {
         // 股票類: 先嘗試 snapshot（含盤前盤後），失敗再 fallback 到 aggs/prev
         if !api_symbol.starts_with("X:") {
             let snap_url = format!(
-                "https://api.polygon.io/v2/snapshot/locale/us/markets/stocks/tickers/{}?apiKey={}",
+                "{}/v2/snapshot/locale/us/markets/stocks/tickers/{}?apiKey={}",
+                self.base_url,
                 api_symbol, api_key
             );
-            if let Ok(resp) = self.client.get(&snap_url).send().await {
+            if let Ok(resp) = self.client.get(&snap_url).send_captured("polygon").await {
                 if let Ok(data) = resp.json::<serde_json::Value>().await {
                     let snap = &data["ticker"];
                     if !snap.is_null() && !snap["day"].is_null() {
@@ -151,29 +172,7 @@ impl DataProvider for PolygonProvider {
         }
 
         // Crypto 或 snapshot 失敗: 用 aggs/prev
-        let data: serde_json::Value = self
-            .client
-            .get(format!(
-                "https://api.polygon.io/v2/aggs/ticker/{}/prev?apiKey={}",
-                api_symbol, api_key
-            ))
-            .send()
-            .await
-            .map_err(|e| format!("Polygon connection failed: {}", e))?
-            .error_for_status()
-            .map_err(|e| format!("Polygon API error: {}", e))?
-            .json()
-            .await
-            .map_err(|e| format!("Polygon parse failed: {}", e))?;
-
-        let r = &data["results"][0];
-        if r.is_null() {
-            return Err(format!(
-                "Polygon not found: {}. Use AAPL for stocks, X:BTCUSD for crypto",
-                symbol
-            ));
-        }
-        Ok(Self::parse_agg(symbol, r))
+        self.fetch_prev(symbol, true).await
     }
 
     /// 批量查詢 — 用並行 request 避免逐一串行被 rate limit
@@ -206,13 +205,14 @@ impl DataProvider for PolygonProvider {
             let tickers: Vec<&str> = stock_syms.iter().map(|(_, p)| p.as_str()).collect();
             let tickers_param = tickers.join(",");
             let url = format!(
-                "https://api.polygon.io/v2/snapshot/locale/us/markets/stocks/tickers?tickers={}&apiKey={}",
+                "{}/v2/snapshot/locale/us/markets/stocks/tickers?tickers={}&apiKey={}",
+                self.base_url,
                 tickers_param, api_key
             );
             match self
                 .client
                 .get(&url)
-                .send()
+                .send_captured("polygon")
                 .await
                 .and_then(|r| r.error_for_status())
             {
@@ -233,7 +233,7 @@ impl DataProvider for PolygonProvider {
                         }
                     }
                 }
-                Err(e) => eprintln!("Polygon stock snapshot failed: {}", e),
+                Err(e) => tracing::warn!("Polygon stock snapshot failed: {}", e),
             }
         }
 
@@ -245,12 +245,13 @@ impl DataProvider for PolygonProvider {
             let crypto_results: Vec<_> = stream::iter(crypto_syms)
                 .map(|(original, ps)| {
                     let url = format!(
-                        "https://api.polygon.io/v2/aggs/ticker/{}/prev?apiKey={}",
+                        "{}/v2/aggs/ticker/{}/prev?apiKey={}",
+                        self.base_url,
                         ps, api_key_owned
                     );
                     let c = client.clone();
                     async move {
-                        match c.get(&url).send().await.and_then(|r| r.error_for_status()) {
+                        match c.get(&url).send_captured("polygon").await.and_then(|r| r.error_for_status()) {
                             Ok(resp) => match resp.json::<serde_json::Value>().await {
                                 Ok(data) => {
                                     let r = &data["results"][0];
@@ -274,4 +275,37 @@ impl DataProvider for PolygonProvider {
 
         Ok(results)
     }
+
+    /// `provider_params` 支援 `{"adjusted": false}`：改查未經分割調整的前一日 aggregates
+    /// （snapshot 沒有 adjusted 選項）；其餘 symbol 走一般批量查詢
+    async fn fetch_prices_with_params(
+        &self,
+        symbols: &[String],
+        params: &HashMap<String, ProviderParams>,
+    ) -> Result<Vec<AssetData>, String> {
+        let (unadjusted, adjusted): (Vec<String>, Vec<String>) = symbols.iter().cloned().partition(|s| {
+            params.get(s).and_then(|p| p.get("adjusted")).and_then(|v| v.as_bool()) == Some(false)
+        });
+        if unadjusted.is_empty() {
+            return self.fetch_prices(symbols).await;
+        }
+
+        let mut results = self.fetch_prices(&adjusted).await?;
+        use futures::stream::{self, StreamExt};
+        let prev: Vec<_> = stream::iter(unadjusted)
+            .map(|sym| async move {
+                let result = self.fetch_prev(&sym, false).await;
+                (sym, result)
+            })
+            .buffer_unordered(3)
+            .collect()
+            .await;
+        for (sym, result) in prev {
+            match result {
+                Ok(data) => results.push(data),
+                Err(e) => tracing::warn!("Polygon unadjusted skipped {}: {}", sym, e),
+            }
+        }
+        Ok(results)
+    }
 }
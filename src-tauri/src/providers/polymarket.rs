@@ -1,22 +1,161 @@
+use super::debug::SendCaptured;
 use super::traits::*;
 use super::types::*;
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+
+const DEFAULT_BASE_URL: &str = "https://clob.polymarket.com";
+/// Gamma API — 以 market slug 查 condition_id
+const GAMMA_URL: &str = "https://gamma-api.polymarket.com";
 
 pub struct PolymarketProvider {
     client: reqwest::Client,
+    base_url: String,
+    /// slug → condition_id（market 建立後不會變，整個 instance 生命週期內快取）
+    condition_ids: RwLock<HashMap<String, String>>,
 }
 
 impl Default for PolymarketProvider {
     fn default() -> Self {
-        Self::new()
+        Self::new(None, &HttpOptions::default())
+    }
+}
+
+/// 解析 symbol：`<slug 或 condition_id>[:<outcome>]`，例如 `will-btc-hit-100k:Yes`
+fn parse_symbol(symbol: &str) -> (&str, Option<&str>) {
+    match symbol.trim().split_once(':') {
+        Some((market, outcome)) if !outcome.trim().is_empty() => (market.trim(), Some(outcome.trim())),
+        Some((market, _)) => (market.trim(), None),
+        None => (symbol.trim(), None),
     }
 }
 
+/// condition_id 為 0x 開頭的 32 bytes hex
+pub(crate) fn is_condition_id(market: &str) -> bool {
+    market.len() == 66
+        && market.starts_with("0x")
+        && market[2..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// 陣列欄位可能是 JSON 陣列，也可能是 JSON 編碼後的字串（Gamma API）
+fn json_list(v: &serde_json::Value) -> Vec<serde_json::Value> {
+    match v {
+        serde_json::Value::Array(arr) => arr.clone(),
+        serde_json::Value::String(s) => serde_json::from_str(s).unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+fn as_price(v: &serde_json::Value) -> Option<f64> {
+    v.as_f64().or_else(|| v.as_str().and_then(|s| s.parse().ok()))
+}
+
+/// 取出所有 outcome 與價格：CLOB 的 `tokens[{outcome, price}]`，或 `outcomes` + `outcome_prices`
+fn parse_outcomes(data: &serde_json::Value) -> Vec<(String, f64)> {
+    let tokens: Vec<(String, f64)> = json_list(&data["tokens"])
+        .iter()
+        .filter_map(|t| Some((t["outcome"].as_str()?.to_string(), as_price(&t["price"])?)))
+        .collect();
+    if !tokens.is_empty() {
+        return tokens;
+    }
+    let prices = if data["outcome_prices"].is_null() {
+        &data["outcomePrices"]
+    } else {
+        &data["outcome_prices"]
+    };
+    json_list(&data["outcomes"])
+        .iter()
+        .zip(json_list(prices).iter())
+        .filter_map(|(o, p)| Some((o.as_str()?.to_string(), as_price(p)?)))
+        .collect()
+}
+
+/// 以選定的 outcome（預設第一個）為價格，所有 outcome 的價格存入 extra
+fn parse_market(symbol: &str, outcome: Option<&str>, data: &serde_json::Value) -> Result<AssetData, String> {
+    let outcomes = parse_outcomes(data);
+    let (selected, price) = match outcome {
+        Some(name) => outcomes
+            .iter()
+            .find(|(o, _)| o.eq_ignore_ascii_case(name))
+            .ok_or_else(|| {
+                let names: Vec<&str> = outcomes.iter().map(|(o, _)| o.as_str()).collect();
+                format!("Polymarket: outcome '{}' not found (available: {})", name, names.join(", "))
+            })?,
+        None => outcomes
+            .first()
+            .ok_or_else(|| format!("Polymarket: no outcome prices for {}", symbol))?,
+    };
+
+    let volume = as_price(&data["volume"]);
+    let names = outcomes
+        .iter()
+        .map(|(o, _)| o.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let mut builder = AssetDataBuilder::new(symbol, "polymarket")
+        .price(*price)
+        .currency("PROB")
+        .volume(volume)
+        .extra_str("question", data["question"].as_str())
+        .extra_str("end_date", data["end_date_iso"].as_str())
+        .extra_str("outcome", Some(selected))
+        .extra_str("outcomes", Some(&names));
+    for (name, p) in &outcomes {
+        builder = builder.extra_f64(name, Some(*p));
+    }
+    Ok(builder.build())
+}
+
 impl PolymarketProvider {
-    pub fn new() -> Self {
+    pub fn new(api_url: Option<String>, http: &HttpOptions) -> Self {
         Self {
-            client: shared_client(),
+            client: provider_client(http),
+            base_url: resolve_base_url(api_url, DEFAULT_BASE_URL),
+            condition_ids: RwLock::new(HashMap::new()),
         }
     }
+
+    /// 將 slug 轉成 condition_id（Gamma API，結果快取）；本身就是 condition_id 時直接回傳
+    async fn resolve_condition_id(&self, market: &str) -> Result<String, String> {
+        if is_condition_id(market) {
+            return Ok(market.to_string());
+        }
+        if let Some(id) = self.condition_ids.read().await.get(market) {
+            return Ok(id.clone());
+        }
+        if !market
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(format!("Polymarket: invalid market slug '{}'", market));
+        }
+
+        let markets: serde_json::Value = self
+            .client
+            .get(format!("{}/markets?slug={}", GAMMA_URL, market))
+            .send_captured("polymarket")
+            .await
+            .map_err(|e| format!("Polymarket Gamma connection failed: {}", e))?
+            .error_for_status()
+            .map_err(|e| format!("Polymarket Gamma API error: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("Polymarket Gamma parse failed: {}", e))?;
+        let id = markets
+            .as_array()
+            .and_then(|arr| arr.first())
+            .and_then(|m| m["conditionId"].as_str())
+            .ok_or_else(|| format!("Polymarket: market '{}' not found", market))?
+            .to_string();
+
+        self.condition_ids
+            .write()
+            .await
+            .insert(market.to_string(), id.clone());
+        Ok(id)
+    }
 }
 
 #[async_trait::async_trait]
@@ -26,11 +165,12 @@ impl DataProvider for PolymarketProvider {
     }
 
     async fn fetch_price(&self, symbol: &str) -> Result<AssetData, String> {
-        // symbol = condition_id for the market
+        let (market, outcome) = parse_symbol(symbol);
+        let condition_id = self.resolve_condition_id(market).await?;
         let data: serde_json::Value = self
             .client
-            .get(format!("https://clob.polymarket.com/markets/{}", symbol))
-            .send()
+            .get(format!("{}/markets/{}", self.base_url, condition_id))
+            .send_captured("polymarket")
             .await
             .map_err(|e| format!("Polymarket connection failed: {}", e))?
             .error_for_status()
@@ -39,30 +179,7 @@ impl DataProvider for PolymarketProvider {
             .await
             .map_err(|e| format!("Polymarket parse failed: {}", e))?;
 
-        let price = data["outcome_prices"]
-            .as_array()
-            .and_then(|arr| arr.first())
-            .and_then(|v| v.as_str())
-            .and_then(|s| s.parse::<f64>().ok())
-            .unwrap_or(0.0);
-
-        let volume = data["volume"].as_str().and_then(|s| s.parse::<f64>().ok());
-
-        let outcomes = data["outcomes"].as_array().map(|arr| {
-            arr.iter()
-                .filter_map(|o| o.as_str())
-                .collect::<Vec<_>>()
-                .join(", ")
-        });
-
-        Ok(AssetDataBuilder::new(symbol, "polymarket")
-            .price(price)
-            .currency("PROB")
-            .volume(volume)
-            .extra_str("question", data["question"].as_str())
-            .extra_str("end_date", data["end_date_iso"].as_str())
-            .extra_str("outcomes", outcomes.as_deref())
-            .build())
+        parse_market(symbol, outcome, &data)
     }
 
     /// 限流並行查詢 — Polymarket 每個 market 是獨立 condition_id，限制同時 3 個
@@ -74,48 +191,9 @@ impl DataProvider for PolymarketProvider {
             return self.fetch_price(&symbols[0]).await.map(|d| vec![d]);
         }
 
-        let client = self.client.clone();
-
         use futures::stream::{self, StreamExt};
         let results: Vec<_> = stream::iter(symbols.to_vec())
-            .map(|sym| {
-                let c = client.clone();
-                async move {
-                    let data: serde_json::Value = c
-                        .get(format!("https://clob.polymarket.com/markets/{}", sym))
-                        .send()
-                        .await
-                        .map_err(|e| format!("Polymarket: {}", e))?
-                        .error_for_status()
-                        .map_err(|e| format!("Polymarket API error: {}", e))?
-                        .json()
-                        .await
-                        .map_err(|e| format!("Polymarket: {}", e))?;
-                    let price = data["outcome_prices"]
-                        .as_array()
-                        .and_then(|arr| arr.first())
-                        .and_then(|v| v.as_str())
-                        .and_then(|s| s.parse::<f64>().ok())
-                        .unwrap_or(0.0);
-                    let volume = data["volume"].as_str().and_then(|s| s.parse::<f64>().ok());
-                    let outcomes = data["outcomes"].as_array().map(|arr| {
-                        arr.iter()
-                            .filter_map(|o| o.as_str())
-                            .collect::<Vec<_>>()
-                            .join(", ")
-                    });
-                    Ok::<AssetData, String>(
-                        AssetDataBuilder::new(&sym, "polymarket")
-                            .price(price)
-                            .currency("PROB")
-                            .volume(volume)
-                            .extra_str("question", data["question"].as_str())
-                            .extra_str("end_date", data["end_date_iso"].as_str())
-                            .extra_str("outcomes", outcomes.as_deref())
-                            .build(),
-                    )
-                }
-            })
+            .map(|sym| async move { self.fetch_price(&sym).await })
             .buffer_unordered(3)
             .collect()
             .await;
@@ -124,9 +202,47 @@ impl DataProvider for PolymarketProvider {
         for r in results {
             match r {
                 Ok(data) => out.push(data),
-                Err(e) => eprintln!("Polymarket skipped: {}", e),
+                Err(e) => tracing::warn!("Polymarket skipped: {}", e),
             }
         }
         Ok(out)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn symbol_selects_market_and_outcome() {
+        assert_eq!(parse_symbol("btc-100k:Yes"), ("btc-100k", Some("Yes")));
+        assert_eq!(parse_symbol("btc-100k"), ("btc-100k", None));
+        assert!(is_condition_id(&format!("0x{}", "ab".repeat(32))));
+        assert!(!is_condition_id("btc-100k"));
+    }
+
+    #[test]
+    fn selected_outcome_is_price_and_all_outcomes_go_to_extra() {
+        let clob = json!({
+            "question": "Who wins?",
+            "tokens": [
+                {"outcome": "Alice", "price": 0.25},
+                {"outcome": "Bob", "price": 0.7},
+                {"outcome": "Carol", "price": 0.05}
+            ]
+        });
+        let d = parse_market("who-wins:bob", Some("bob"), &clob).unwrap();
+        assert_eq!(d.price, 0.7);
+        let extra = d.extra.unwrap();
+        assert_eq!(extra["outcome"], json!("Bob"));
+        assert_eq!(extra["Alice"], json!(0.25));
+        assert_eq!(extra["Carol"], json!(0.05));
+
+        assert!(parse_market("who-wins:dave", Some("dave"), &clob).is_err());
+
+        // Gamma 格式：outcomes / outcomePrices 為 JSON 字串
+        let gamma = json!({"outcomes": "[\"Yes\", \"No\"]", "outcomePrices": "[\"0.62\", \"0.38\"]"});
+        assert_eq!(parse_market("m", None, &gamma).unwrap().price, 0.62);
+    }
+}
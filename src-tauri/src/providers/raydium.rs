@@ -1,8 +1,10 @@
+use crate::providers::debug::SendCaptured;
 use crate::providers::traits::{DataProvider, DexPoolLookup};
 use crate::providers::types::{
-    provider_info_or_panic, shared_client, AssetData, AssetDataBuilder, DexPoolInfo, ProviderInfo,
+    provider_info_or_panic, provider_client, AssetData, AssetDataBuilder, DexPoolInfo, HttpOptions, ProviderInfo,
 };
 use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
 
 pub struct RaydiumProvider {
     client: reqwest::Client,
@@ -11,9 +13,9 @@ pub struct RaydiumProvider {
 }
 
 impl RaydiumProvider {
-    pub fn new(api_key: Option<String>, api_url: Option<String>) -> Self {
+    pub fn new(api_key: Option<String>, api_url: Option<String>, http: &HttpOptions) -> Self {
         Self {
-            client: shared_client(),
+            client: provider_client(http),
             api_key,
             api_url,
         }
@@ -47,6 +49,7 @@ struct RaydiumPoolResponse {
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct RaydiumPool {
+    id: Option<String>,
     #[serde(rename = "type")]
     _pool_type: Option<String>,
     price: Option<f64>,
@@ -68,6 +71,68 @@ struct RaydiumDayStats {
     volume: Option<f64>,
 }
 
+/// 將 pool 資料轉成指定交易方向的報價
+fn pool_to_asset(symbol: &str, token_from: &str, token_to: &str, pool: &RaydiumPool) -> AssetData {
+    // pool.price = token_b per token_a ratio
+    // Determine direction: if token_from == mintA → price = pool.price (how many B per A)
+    // if token_from == mintB → price = 1/pool.price
+    let mint_b_addr = pool
+        .mint_b
+        .as_ref()
+        .and_then(|m| m.address.as_deref())
+        .unwrap_or("");
+    let amount_out = if token_from.eq_ignore_ascii_case(mint_b_addr) {
+        let p = pool.price.unwrap_or(1.0);
+        if p > 0.0 {
+            1.0 / p
+        } else {
+            0.0
+        }
+    } else {
+        // token_from 為 mintA，或兩者皆不符時預設 mintA 方向
+        pool.price.unwrap_or(0.0)
+    };
+
+    // For USD price, we use pool.price as a proxy (Raydium pools are often quoted in USD stables)
+    let usd_price = pool.price.unwrap_or(0.0);
+
+    AssetDataBuilder::new(symbol, "raydium")
+        .price(usd_price)
+        .volume(pool.day.as_ref().and_then(|d| d.volume))
+        .liquidity_usd(pool.tvl)
+        .extra_f64("pool_tvl", pool.tvl)
+        .extra_f64("amount_out", Some(amount_out))
+        .extra_str("token_from", Some(token_from))
+        .extra_str("token_to", Some(token_to))
+        .extra_str("route_path", Some("Raydium AMM"))
+        .extra_str("gas_estimate", Some("~0.000005 SOL"))
+        .build()
+}
+
+/// 以回應中每個 pool 自帶的 id 對應請求的 symbols（API 會略過查無的 pool，不能依 index 對應）；
+/// 重複的 symbol 只回傳一次
+fn match_pools(symbols: &[String], pools: &[RaydiumPool]) -> Vec<AssetData> {
+    let by_id: HashMap<&str, &RaydiumPool> = pools
+        .iter()
+        .filter_map(|p| p.id.as_deref().map(|id| (id, p)))
+        .collect();
+    let mut seen = HashSet::new();
+    let mut results = Vec::new();
+    for sym in symbols {
+        if !seen.insert(sym.as_str()) {
+            continue;
+        }
+        let Ok((pool_addr, token_from, token_to)) = RaydiumProvider::parse_symbol(sym) else {
+            continue;
+        };
+        match by_id.get(pool_addr) {
+            Some(pool) => results.push(pool_to_asset(sym, token_from, token_to, pool)),
+            None => tracing::warn!("[Raydium] pool {} not found", pool_addr),
+        }
+    }
+    results
+}
+
 #[async_trait::async_trait]
 impl DataProvider for RaydiumProvider {
     fn info(&self) -> ProviderInfo {
@@ -84,7 +149,7 @@ impl DataProvider for RaydiumProvider {
         }
 
         let resp = req
-            .send()
+            .send_captured("raydium")
             .await
             .map_err(|e| format!("Raydium request failed: {}", e))?;
         if !resp.status().is_success() {
@@ -108,93 +173,23 @@ impl DataProvider for RaydiumProvider {
             .next()
             .ok_or_else(|| format!("Raydium: pool {} not found or returned null", pool_addr))?;
 
-        // pool.price = token_b per token_a ratio
-        // Determine direction: if token_from == mintA → price = pool.price (how many B per A)
-        // if token_from == mintB → price = 1/pool.price
-        let mint_a_addr = pool
-            .mint_a
-            .as_ref()
-            .and_then(|m| m.address.as_deref())
-            .unwrap_or("");
-        let mint_b_addr = pool
-            .mint_b
-            .as_ref()
-            .and_then(|m| m.address.as_deref())
-            .unwrap_or("");
-
-        let (amount_out, _from_symbol, _to_symbol) = if token_from.eq_ignore_ascii_case(mint_a_addr)
-        {
-            let out = pool.price.unwrap_or(0.0);
-            let fs = pool
-                .mint_a
-                .as_ref()
-                .and_then(|m| m.symbol.as_deref())
-                .unwrap_or("?");
-            let ts = pool
-                .mint_b
-                .as_ref()
-                .and_then(|m| m.symbol.as_deref())
-                .unwrap_or("?");
-            (out, fs, ts)
-        } else if token_from.eq_ignore_ascii_case(mint_b_addr) {
-            let p = pool.price.unwrap_or(1.0);
-            let out = if p > 0.0 { 1.0 / p } else { 0.0 };
-            let fs = pool
-                .mint_b
-                .as_ref()
-                .and_then(|m| m.symbol.as_deref())
-                .unwrap_or("?");
-            let ts = pool
-                .mint_a
-                .as_ref()
-                .and_then(|m| m.symbol.as_deref())
-                .unwrap_or("?");
-            (out, fs, ts)
-        } else {
-            // Fallback: assume token_from is mintA direction
-            let out = pool.price.unwrap_or(0.0);
-            let fs = pool
-                .mint_a
-                .as_ref()
-                .and_then(|m| m.symbol.as_deref())
-                .unwrap_or("?");
-            let ts = pool
-                .mint_b
-                .as_ref()
-                .and_then(|m| m.symbol.as_deref())
-                .unwrap_or("?");
-            (out, fs, ts)
-        };
-
-        // For USD price, we use pool.price as a proxy (Raydium pools are often quoted in USD stables)
-        let usd_price = pool.price.unwrap_or(0.0);
-
-        Ok(AssetDataBuilder::new(symbol, "raydium")
-            .price(usd_price)
-            .volume(pool.day.as_ref().and_then(|d| d.volume))
-            .extra_f64("pool_tvl", pool.tvl)
-            .extra_f64("amount_out", Some(amount_out))
-            .extra_str("token_from", Some(token_from))
-            .extra_str("token_to", Some(token_to))
-            .extra_str("route_path", Some("Raydium AMM"))
-            .extra_str("gas_estimate", Some("~0.000005 SOL"))
-            .build())
+        Ok(pool_to_asset(symbol, token_from, token_to, &pool))
     }
 
     async fn fetch_prices(&self, symbols: &[String]) -> Result<Vec<AssetData>, String> {
         // Batch: collect unique pool addresses, fetch in one call
-        let mut pool_map: std::collections::HashMap<String, Vec<(String, String, String)>> =
-            std::collections::HashMap::new();
+        let mut pool_ids: Vec<&str> = Vec::new();
         for sym in symbols {
-            let (pool, tf, tt) = Self::parse_symbol(sym)?;
-            pool_map.entry(pool.to_string()).or_default().push((
-                sym.clone(),
-                tf.to_string(),
-                tt.to_string(),
-            ));
+            match Self::parse_symbol(sym) {
+                Ok((pool, _, _)) if !pool_ids.contains(&pool) => pool_ids.push(pool),
+                Ok(_) => {}
+                Err(e) => tracing::warn!("[Raydium] {}", e),
+            }
+        }
+        if pool_ids.is_empty() {
+            return Ok(vec![]);
         }
 
-        let pool_ids: Vec<&str> = pool_map.keys().map(|s| s.as_str()).collect();
         let url = format!(
             "{}/pools/info/ids?ids={}",
             self.base_url(),
@@ -206,7 +201,7 @@ impl DataProvider for RaydiumProvider {
         }
 
         let resp = req
-            .send()
+            .send_captured("raydium")
             .await
             .map_err(|e| format!("Raydium batch request failed: {}", e))?;
         if !resp.status().is_success() {
@@ -224,65 +219,7 @@ impl DataProvider for RaydiumProvider {
             .into_iter()
             .flatten()
             .collect();
-        let mut results = Vec::new();
-
-        // Raydium API 按請求順序返回 pools，用 index 對應
-        for (i, pool) in pools.iter().enumerate() {
-            let pool_addr = match pool_ids.get(i) {
-                Some(addr) => *addr,
-                None => continue,
-            };
-            let requests = match pool_map.get(pool_addr) {
-                Some(r) => r,
-                None => continue,
-            };
-            let mint_a_addr = pool
-                .mint_a
-                .as_ref()
-                .and_then(|m| m.address.as_deref())
-                .unwrap_or("");
-            for (sym, token_from, token_to) in requests {
-                let amount_out = if token_from.eq_ignore_ascii_case(mint_a_addr) {
-                    pool.price.unwrap_or(0.0)
-                } else {
-                    let p = pool.price.unwrap_or(1.0);
-                    if p > 0.0 {
-                        1.0 / p
-                    } else {
-                        0.0
-                    }
-                };
-
-                let usd_price = pool.price.unwrap_or(0.0);
-
-                results.push(
-                    AssetDataBuilder::new(sym, "raydium")
-                        .price(usd_price)
-                        .volume(pool.day.as_ref().and_then(|d| d.volume))
-                        .extra_f64("pool_tvl", pool.tvl)
-                        .extra_f64("amount_out", Some(amount_out))
-                        .extra_str("token_from", Some(token_from))
-                        .extra_str("token_to", Some(token_to))
-                        .extra_str("route_path", Some("Raydium AMM"))
-                        .extra_str("gas_estimate", Some("~0.000005 SOL"))
-                        .build(),
-                );
-            }
-        }
-
-        // Fallback: for any symbols not matched, try individual fetch
-        let matched: std::collections::HashSet<String> =
-            results.iter().map(|r| r.symbol.clone()).collect();
-        for sym in symbols {
-            if !matched.contains(sym.as_str()) {
-                match self.fetch_price(sym).await {
-                    Ok(d) => results.push(d),
-                    Err(e) => eprintln!("[Raydium] fetch_price fallback for {} failed: {}", sym, e),
-                }
-            }
-        }
-
-        Ok(results)
+        Ok(match_pools(symbols, &pools))
     }
 }
 
@@ -295,7 +232,7 @@ impl DexPoolLookup for RaydiumProvider {
             req = req.header("Authorization", format!("Bearer {}", key));
         }
         let resp = req
-            .send()
+            .send_captured("raydium")
             .await
             .map_err(|e| format!("Raydium request failed: {}", e))?;
         if !resp.status().is_success() {
@@ -333,3 +270,30 @@ impl DexPoolLookup for RaydiumProvider {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_matches_pools_by_id_and_skips_duplicates() {
+        // 回應略過了查無的 pool "missing"，順序也與請求不同
+        let pools: Vec<RaydiumPool> = serde_json::from_value(serde_json::json!([
+            {"id": "poolB", "price": 4.0, "mintA": {"address": "mintX"}, "mintB": {"address": "mintY"}},
+            {"id": "poolA", "price": 2.0, "mintA": {"address": "mintS"}, "mintB": {"address": "mintU"}}
+        ]))
+        .unwrap();
+        let symbols: Vec<String> = ["missing:m1:m2", "poolA:mintU:mintS", "poolB:mintX:mintY", "poolA:mintU:mintS"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        let out = match_pools(&symbols, &pools);
+        assert_eq!(out.len(), 2);
+        assert_eq!(out[0].symbol, "poolA:mintU:mintS");
+        assert_eq!(out[0].price, 2.0);
+        assert_eq!(out[0].extra.as_ref().unwrap()["amount_out"], serde_json::json!(0.5));
+        assert_eq!(out[1].symbol, "poolB:mintX:mintY");
+        assert_eq!(out[1].price, 4.0);
+    }
+}
@@ -4,7 +4,12 @@
 /// 2. 共用實例：Polling 和 IPC commands 共用同一組 provider
 /// 3. Rate limiting：每個 provider 一個 Semaphore，防止 API 過載
 use crate::db::DbPool;
-use crate::providers::{create_provider_with_url, AssetData, DataProvider};
+use crate::i18n::{tr, Msg};
+use crate::providers::{
+    create_provider_with_url, replay, AssetData, DataProvider, HttpOptions, ProviderParams,
+};
+use crate::telemetry::{Span, SpanKind};
+use serde::Serialize;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::{RwLock, Semaphore};
@@ -14,6 +19,17 @@ const DEFAULT_CONCURRENT_REQUESTS: usize = 3;
 /// 有 API key 的 provider 並發上限
 const KEYED_CONCURRENT_REQUESTS: usize = 5;
 
+/// `fetch_prices_multi` 的單一請求：一個 provider 與其 symbols
+pub use stockenboard_api_types::prices::PriceRequest;
+
+/// 依 provider 分組的結果；失敗時 `data` 為空並附上 `error`
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderPrices {
+    pub provider_id: String,
+    pub data: Vec<AssetData>,
+    pub error: Option<String>,
+}
+
 pub struct ProviderRegistry {
     /// 共享的 provider instances（lazy init）
     providers: RwLock<HashMap<String, Arc<dyn DataProvider>>>,
@@ -46,17 +62,35 @@ impl ProviderRegistry {
         }
 
         // 從 DB 讀取設定
-        let (key, secret, url) = match db.get_provider_settings(id) {
-            Ok(Some(settings)) => (
-                settings.api_key.filter(|k| !k.is_empty()),
-                settings.api_secret.filter(|s| !s.is_empty()),
-                settings.api_url.filter(|u| !u.is_empty()),
-            ),
-            _ => (None, None, None),
+        let (key, secret, url, http) = match db.get_provider_settings(id) {
+            Ok(Some(settings)) => {
+                let http = HttpOptions::from(&settings);
+                (
+                    settings.api_key.filter(|k| !k.is_empty()),
+                    settings.api_secret.filter(|s| !s.is_empty()),
+                    settings.api_url.filter(|u| !u.is_empty()),
+                    http,
+                )
+            }
+            _ => (None, None, None, HttpOptions::default()),
         };
 
-        // 建立 provider
-        let provider = create_provider_with_url(id, key.clone(), secret, url)?;
+        // 建立 provider（replay 需要讀 DB 歷史，無法經由 create_provider_with_url 建立）
+        let provider: Arc<dyn DataProvider> = if id == "replay" {
+            let config = replay::replay_config();
+            if !config.enabled {
+                return None;
+            }
+            match replay::ReplayProvider::load(db, config) {
+                Ok(p) => Arc::new(p),
+                Err(e) => {
+                    tracing::warn!("[Registry] Failed to load replay history: {}", e);
+                    return None;
+                }
+            }
+        } else {
+            create_provider_with_url(id, key.clone(), secret, url, &http)?
+        };
         self.providers
             .write()
             .await
@@ -75,16 +109,84 @@ impl ProviderRegistry {
         symbols: &[String],
         db: &DbPool,
     ) -> Result<Vec<AssetData>, String> {
+        let mut span = Span::root("provider.fetch", SpanKind::Client);
+        span.set_str("provider.id", id);
+        span.set_int("symbols", symbols.len() as i64);
+        let result = self.fetch_traced(id, symbols, db, &span).await;
+        match &result {
+            Ok(data) => span.set_int("results", data.len() as i64),
+            Err(e) => span.set_error(e),
+        }
+        result
+    }
+
+    async fn fetch_traced(
+        &self,
+        id: &str,
+        symbols: &[String],
+        db: &DbPool,
+        span: &Span,
+    ) -> Result<Vec<AssetData>, String> {
+        let provider = self
+            .get_or_create(id, db)
+            .await
+            .ok_or_else(|| tr(Msg::ProviderNotFound, &[&id]))?;
+        let limiter = self.get_limiter(id).await;
+        let wait = span.child("rate_limiter.acquire", SpanKind::Internal);
+        let _permit = limiter
+            .acquire()
+            .await
+            .map_err(|e| format!("Rate limiter: {}", e))?;
+        drop(wait);
+        provider.prepare(db).await;
+        let params = db.read_provider_params(id).unwrap_or_else(|e| {
+            tracing::warn!("[Registry] Failed to read provider params for {}: {}", id, e);
+            HashMap::new()
+        });
+        let _request = span.child("provider.request", SpanKind::Client);
+        match provider.info().capabilities.max_batch_size {
+            Some(max) if symbols.len() > max => {
+                fetch_in_batches(provider.as_ref(), symbols, &params, max).await
+            }
+            _ => provider.fetch_prices_with_params(symbols, &params).await,
+        }
+    }
+
+    /// 同時向多個 provider 取價（各自仍受 rate limiting），結果依請求順序回傳，
+    /// 單一 provider 失敗不影響其他 provider
+    pub async fn fetch_multi(&self, requests: &[PriceRequest], db: &DbPool) -> Vec<ProviderPrices> {
+        futures::future::join_all(requests.iter().map(|req| async move {
+            let result = if req.symbols.is_empty() {
+                Ok(Vec::new())
+            } else {
+                self.fetch_with_limit(&req.provider_id, &req.symbols, db).await
+            };
+            let (data, error) = match result {
+                Ok(data) => (data, None),
+                Err(e) => (Vec::new(), Some(e)),
+            };
+            ProviderPrices {
+                provider_id: req.provider_id.clone(),
+                data,
+                error,
+            }
+        }))
+        .await
+    }
+
+    /// 帶 rate limiting 的 validate_symbol
+    pub async fn validate_symbol(&self, id: &str, symbol: &str, db: &DbPool) -> Result<(), String> {
         let provider = self
             .get_or_create(id, db)
             .await
-            .ok_or_else(|| format!("Provider not found: {}", id))?;
+            .ok_or_else(|| tr(Msg::ProviderNotFound, &[&id]))?;
         let limiter = self.get_limiter(id).await;
         let _permit = limiter
             .acquire()
             .await
             .map_err(|e| format!("Rate limiter: {}", e))?;
-        provider.fetch_prices(symbols).await
+        provider.prepare(db).await;
+        provider.validate_symbol(symbol).await
     }
 
     /// 更新已有的 provider instance（例如 API key 變更後）
@@ -94,9 +196,10 @@ impl ProviderRegistry {
         api_key: Option<String>,
         api_secret: Option<String>,
         api_url: Option<String>,
+        http: HttpOptions,
     ) {
         let has_key = api_key.is_some();
-        if let Some(provider) = create_provider_with_url(id, api_key, api_secret, api_url) {
+        if let Some(provider) = create_provider_with_url(id, api_key, api_secret, api_url, &http) {
             self.providers
                 .write()
                 .await
@@ -114,6 +217,18 @@ impl ProviderRegistry {
         }
     }
 
+    /// 移除單一 provider 的快取實例與 limiter，下次使用時依 DB 設定重建
+    pub async fn invalidate(&self, id: &str) {
+        self.providers.write().await.remove(id);
+        self.limiters.write().await.remove(id);
+    }
+
+    /// 清除所有快取的 provider 實例與 limiter
+    pub async fn clear(&self) {
+        self.providers.write().await.clear();
+        self.limiters.write().await.clear();
+    }
+
     /// 取得 rate limiter（如果不存在則建立默認的）
     async fn get_limiter(&self, id: &str) -> Arc<Semaphore> {
         {
@@ -144,3 +259,112 @@ impl ProviderRegistry {
         }
     }
 }
+
+/// 超過 provider 單次批量上限時分批查詢；部分批次失敗時回傳其餘結果，全部失敗才回報錯誤
+async fn fetch_in_batches(
+    provider: &dyn DataProvider,
+    symbols: &[String],
+    params: &HashMap<String, ProviderParams>,
+    max: usize,
+) -> Result<Vec<AssetData>, String> {
+    let mut out = Vec::new();
+    let mut last_err = None;
+    for chunk in symbols.chunks(max.max(1)) {
+        match provider.fetch_prices_with_params(chunk, params).await {
+            Ok(mut data) => out.append(&mut data),
+            Err(e) => {
+                tracing::warn!("[Registry] {} batch failed: {}", provider.info().id, e);
+                last_err = Some(e);
+            }
+        }
+    }
+    match last_err {
+        Some(e) if out.is_empty() => Err(e),
+        _ => Ok(out),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::{provider_info_or_panic, AssetDataBuilder, ProviderInfo};
+    use std::path::PathBuf;
+
+    /// 記錄每次 fetch_prices 收到的 symbol 數；含 "FAIL" 的批次回傳錯誤
+    struct CountingProvider(std::sync::Mutex<Vec<usize>>);
+
+    #[async_trait::async_trait]
+    impl DataProvider for CountingProvider {
+        fn info(&self) -> ProviderInfo {
+            provider_info_or_panic("eodhd")
+        }
+        async fn fetch_price(&self, symbol: &str) -> Result<AssetData, String> {
+            Ok(AssetDataBuilder::new(symbol, "eodhd").price(1.0).build())
+        }
+        async fn fetch_prices(&self, symbols: &[String]) -> Result<Vec<AssetData>, String> {
+            self.0.lock().unwrap().push(symbols.len());
+            if symbols.iter().any(|s| s == "FAIL") {
+                return Err("boom".into());
+            }
+            Ok(symbols
+                .iter()
+                .map(|s| AssetDataBuilder::new(s, "eodhd").price(1.0).build())
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn batches_respect_max_size_and_keep_partial_results() {
+        let provider = CountingProvider(std::sync::Mutex::new(Vec::new()));
+        let mut symbols: Vec<String> = (0..5).map(|i| format!("S{}", i)).collect();
+        let params = HashMap::new();
+        let out = fetch_in_batches(&provider, &symbols, &params, 2).await.unwrap();
+        assert_eq!(out.len(), 5);
+        assert_eq!(*provider.0.lock().unwrap(), vec![2, 2, 1]);
+
+        symbols.push("FAIL".to_string());
+        assert_eq!(fetch_in_batches(&provider, &symbols, &params, 2).await.unwrap().len(), 4);
+        assert!(fetch_in_batches(&provider, &["FAIL".to_string()], &params, 2).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn invalidate_rebuilds_provider_from_current_settings() {
+        let db = DbPool::open(&PathBuf::from(":memory:")).unwrap();
+        let registry = ProviderRegistry::new();
+
+        let first = registry.get_or_create("coingecko", &db).await.unwrap();
+        let cached = registry.get_or_create("coingecko", &db).await.unwrap();
+        assert!(Arc::ptr_eq(&first, &cached));
+
+        registry.invalidate("coingecko").await;
+        let rebuilt = registry.get_or_create("coingecko", &db).await.unwrap();
+        assert!(!Arc::ptr_eq(&first, &rebuilt));
+
+        registry.clear().await;
+        let after_clear = registry.get_or_create("coingecko", &db).await.unwrap();
+        assert!(!Arc::ptr_eq(&rebuilt, &after_clear));
+    }
+
+    #[tokio::test]
+    async fn fetch_multi_groups_results_and_errors_per_request() {
+        let db = DbPool::open(&PathBuf::from(":memory:")).unwrap();
+        let registry = ProviderRegistry::new();
+        let requests = vec![
+            PriceRequest {
+                provider_id: "no-such-provider".to_string(),
+                symbols: vec!["BTC".to_string()],
+            },
+            PriceRequest {
+                provider_id: "coingecko".to_string(),
+                symbols: Vec::new(),
+            },
+        ];
+
+        let results = registry.fetch_multi(&requests, &db).await;
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].provider_id, "no-such-provider");
+        assert!(results[0].error.as_deref().unwrap().contains("Provider not found"));
+        assert_eq!(results[1].provider_id, "coingecko");
+        assert!(results[1].error.is_none() && results[1].data.is_empty());
+    }
+}
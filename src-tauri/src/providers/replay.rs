@@ -0,0 +1,254 @@
+use super::traits::*;
+use super::types::*;
+use crate::db::{DbPool, ReplayFrame};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::Instant;
+
+/// Replay 設定（app settings: `replay_enabled` / `replay_from` / `replay_to` / `replay_speed`）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ReplayConfig {
+    /// 開啟後 `replay` provider 才會出現在列表並可被建立
+    pub enabled: bool,
+    /// 回放起點（Unix 秒）
+    pub from: i64,
+    /// 回放終點（Unix 秒）；None = 開始回放當下
+    pub to: Option<i64>,
+    /// 速度倍率：1.0 = 實際速度，60.0 = 一分鐘播放一小時
+    pub speed: f64,
+}
+
+const DEFAULT_CONFIG: ReplayConfig = ReplayConfig {
+    enabled: false,
+    from: 0,
+    to: None,
+    speed: 1.0,
+};
+
+impl Default for ReplayConfig {
+    fn default() -> Self {
+        DEFAULT_CONFIG
+    }
+}
+
+/// 單次回放區間上限（秒）；回放會把整段區間載入記憶體，不能不設上限
+pub const MAX_REPLAY_SPAN_SECS: i64 = 31 * 24 * 3600;
+
+impl ReplayConfig {
+    /// 驗證設定；開啟時必須指定起點，且區間不可超過 `MAX_REPLAY_SPAN_SECS`
+    pub fn validate(&self, now: i64) -> Result<(), String> {
+        if !self.speed.is_finite() || self.speed <= 0.0 {
+            return Err("Replay speed must be greater than 0".to_string());
+        }
+        if self.to.is_some_and(|to| to < self.from) {
+            return Err("Replay end must be after start".to_string());
+        }
+        if !self.enabled {
+            return Ok(());
+        }
+        if self.from <= 0 {
+            return Err("Replay start time is required".to_string());
+        }
+        if self.to.unwrap_or(now) - self.from > MAX_REPLAY_SPAN_SECS {
+            return Err(format!(
+                "Replay range must not exceed {} days",
+                MAX_REPLAY_SPAN_SECS / 86_400
+            ));
+        }
+        Ok(())
+    }
+}
+
+static REPLAY_CONFIG: RwLock<ReplayConfig> = RwLock::new(DEFAULT_CONFIG);
+
+pub fn set_replay_config(config: ReplayConfig) {
+    *REPLAY_CONFIG.write().unwrap_or_else(|e| e.into_inner()) = config;
+}
+
+pub fn replay_config() -> ReplayConfig {
+    *REPLAY_CONFIG.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// History replay provider — 把 `price_history` 的紀錄當作即時價格播放。
+///
+/// 建立時一次載入整段區間（長度受 `MAX_REPLAY_SPAN_SECS` 限制）；虛擬時鐘從 instance 建立時開始，
+/// 因此修改設定（registry invalidate）會從頭播放。播到終點後停在最後一筆。
+///
+/// 紀錄依 `(provider_id, symbol)` 分組，不同 provider 的同名 symbol 不會交錯。
+/// 訂閱 symbol 可寫成 `binance:BTCUSDT` 指定來源；只寫 `BTCUSDT` 時使用紀錄最多的來源。
+pub struct ReplayProvider {
+    frames: HashMap<(String, String), Vec<ReplayFrame>>,
+    /// symbol → 預設來源 provider
+    default_sources: HashMap<String, String>,
+    from: i64,
+    speed: f64,
+    started: Instant,
+}
+
+impl ReplayProvider {
+    pub fn load(db: &DbPool, config: ReplayConfig) -> Result<Self, String> {
+        let now = chrono::Utc::now().timestamp();
+        config.validate(now)?;
+        let to = config.to.unwrap_or(now);
+        Ok(Self::new(db.load_replay_frames(config.from, to)?, config.from, config.speed))
+    }
+
+    fn new(rows: Vec<ReplayFrame>, from: i64, speed: f64) -> Self {
+        let mut frames: HashMap<(String, String), Vec<ReplayFrame>> = HashMap::new();
+        for frame in rows {
+            frames
+                .entry((frame.provider_id.clone(), frame.symbol.trim().to_uppercase()))
+                .or_default()
+                .push(frame);
+        }
+        // 紀錄最多的來源優先；數量相同時取 provider id 較小者，結果才穩定
+        let mut default_sources: HashMap<String, (String, usize)> = HashMap::new();
+        for ((provider_id, symbol), series) in &frames {
+            let best = default_sources
+                .entry(symbol.clone())
+                .or_insert_with(|| (provider_id.clone(), series.len()));
+            if (series.len(), std::cmp::Reverse(provider_id)) > (best.1, std::cmp::Reverse(&best.0)) {
+                *best = (provider_id.clone(), series.len());
+            }
+        }
+        Self {
+            frames,
+            default_sources: default_sources
+                .into_iter()
+                .map(|(symbol, (provider_id, _))| (symbol, provider_id))
+                .collect(),
+            from,
+            speed: speed.max(0.0),
+            started: Instant::now(),
+        }
+    }
+
+    /// 目前回放到的時間點（Unix 秒）
+    fn virtual_now(&self) -> i64 {
+        self.from + (self.started.elapsed().as_secs_f64() * self.speed) as i64
+    }
+
+    /// 找出 symbol 對應的紀錄序列；`source:SYMBOL` 指定來源，否則用預設來源
+    fn series(&self, symbol: &str) -> Option<&Vec<ReplayFrame>> {
+        let symbol = symbol.trim();
+        if let Some((source, rest)) = symbol.split_once(':') {
+            let key = (source.trim().to_lowercase(), rest.trim().to_uppercase());
+            if let Some(series) = self.frames.get(&key) {
+                return Some(series);
+            }
+        }
+        let symbol = symbol.to_uppercase();
+        let source = self.default_sources.get(&symbol)?;
+        self.frames.get(&(source.clone(), symbol))
+    }
+
+    fn frame_at(&self, symbol: &str, at: i64) -> Option<&ReplayFrame> {
+        let frames = self.series(symbol)?;
+        // 最後一筆 recorded_at <= at；還沒播到第一筆時先顯示第一筆
+        let idx = frames.partition_point(|f| f.recorded_at <= at);
+        frames.get(idx.saturating_sub(1))
+    }
+
+    fn to_asset(symbol: &str, frame: &ReplayFrame) -> AssetData {
+        AssetDataBuilder::new(symbol, "replay")
+            .price(frame.price)
+            .change_percent_24h(frame.change_pct)
+            .volume(frame.volume)
+            .pre_market(frame.pre_price.map(|p| SessionQuote::against(p, Some(frame.price))))
+            .post_market(frame.post_price.map(|p| SessionQuote::against(p, Some(frame.price))))
+            .extra_i64("replay_time", Some(frame.recorded_at))
+            .build()
+    }
+}
+
+#[async_trait::async_trait]
+impl DataProvider for ReplayProvider {
+    fn info(&self) -> ProviderInfo {
+        provider_info_or_panic("replay")
+    }
+
+    async fn fetch_price(&self, symbol: &str) -> Result<AssetData, String> {
+        self.frame_at(symbol, self.virtual_now())
+            .map(|f| Self::to_asset(symbol, f))
+            .ok_or_else(|| format!("Replay: no recorded history for {}", symbol))
+    }
+
+    async fn fetch_prices(&self, symbols: &[String]) -> Result<Vec<AssetData>, String> {
+        let now = self.virtual_now();
+        Ok(symbols
+            .iter()
+            .filter_map(|s| self.frame_at(s, now).map(|f| Self::to_asset(s, f)))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(symbol: &str, price: f64, recorded_at: i64) -> ReplayFrame {
+        sourced("binance", symbol, price, recorded_at)
+    }
+
+    fn sourced(provider_id: &str, symbol: &str, price: f64, recorded_at: i64) -> ReplayFrame {
+        ReplayFrame {
+            provider_id: provider_id.to_string(),
+            symbol: symbol.to_string(),
+            price,
+            change_pct: None,
+            volume: None,
+            pre_price: None,
+            post_price: None,
+            recorded_at,
+        }
+    }
+
+    #[test]
+    fn frame_at_follows_virtual_clock() {
+        let frames = vec![frame("BTCUSDT", 100.0, 1_000), frame("BTCUSDT", 110.0, 1_060), frame("BTCUSDT", 90.0, 1_120)];
+        let provider = ReplayProvider::new(frames, 1_000, 60.0);
+
+        assert_eq!(provider.frame_at("btcusdt", 999).unwrap().price, 100.0);
+        assert_eq!(provider.frame_at("BTCUSDT", 1_059).unwrap().price, 100.0);
+        assert_eq!(provider.frame_at("BTCUSDT", 1_060).unwrap().price, 110.0);
+        // 播到終點後停在最後一筆
+        assert_eq!(provider.frame_at("BTCUSDT", 9_999).unwrap().price, 90.0);
+        assert!(provider.frame_at("ETHUSDT", 1_060).is_none());
+        assert!(provider.virtual_now() >= 1_000);
+    }
+
+    #[test]
+    fn providers_with_the_same_symbol_do_not_interleave() {
+        let frames = vec![
+            sourced("binance", "BTCUSDT", 100.0, 1_000),
+            sourced("bybit", "BTCUSDT", 200.0, 1_010),
+            sourced("binance", "BTCUSDT", 101.0, 1_020),
+            sourced("bybit", "BTCUSDT", 201.0, 1_030),
+            sourced("binance", "BTCUSDT", 102.0, 1_040),
+        ];
+        let provider = ReplayProvider::new(frames, 1_000, 1.0);
+
+        // 預設使用紀錄較多的 binance
+        assert_eq!(provider.frame_at("BTCUSDT", 1_035).unwrap().price, 101.0);
+        assert_eq!(provider.frame_at("bybit:BTCUSDT", 1_035).unwrap().price, 201.0);
+        assert_eq!(provider.frame_at("Binance:btcusdt", 1_045).unwrap().price, 102.0);
+        assert!(provider.frame_at("okx:BTCUSDT", 1_035).is_none());
+    }
+
+    #[test]
+    fn validate_requires_a_bounded_range_when_enabled() {
+        let now = 10_000_000;
+        let enabled = ReplayConfig { enabled: true, ..ReplayConfig::default() };
+        assert!(ReplayConfig::default().validate(now).is_ok());
+        assert!(enabled.validate(now).is_err());
+
+        let recent = ReplayConfig { from: now - 3_600, ..enabled };
+        assert!(recent.validate(now).is_ok());
+        let too_long = ReplayConfig { from: now - MAX_REPLAY_SPAN_SECS - 1, ..enabled };
+        assert!(too_long.validate(now).is_err());
+        let bounded = ReplayConfig { to: Some(too_long.from + 3_600), ..too_long };
+        assert!(bounded.validate(now).is_ok());
+        assert!(ReplayConfig { speed: 0.0, ..recent }.validate(now).is_err());
+    }
+}
@@ -0,0 +1,383 @@
+//! 簡單交易所 ticker provider — 「symbol 轉換 → GET ticker → 解析」的共用實作
+//!
+//! 每個交易所只需在 `EXCHANGES` 新增一筆 `ExchangeSpec`：交易對格式、單一 / 全部 ticker 的路徑、
+//! 回應中 ticker 的 JSON pointer，以及欄位對照。批量查詢一律抓全部 tickers 後本地比對。
+
+use super::debug::SendCaptured;
+use super::derivatives::{self, Positioning};
+use super::traits::*;
+use super::types::*;
+use std::collections::HashMap;
+
+/// 交易對格式：`{base}{separator}{quote}`，USD 一律換成 USDT
+pub struct PairFormat {
+    pub separator: &'static str,
+    pub lowercase: bool,
+}
+
+/// 24h 漲跌的來源
+pub enum ChangeSource {
+    /// 由 24h 開盤價（或前價）欄位計算漲跌與漲跌幅
+    FromOpen(&'static str),
+    /// 直接提供漲跌與漲跌幅（%）
+    Direct {
+        change: &'static str,
+        percent: &'static str,
+    },
+    /// 只提供漲跌幅（%），漲跌由現價反推
+    Percent(&'static str),
+}
+
+/// ticker 物件的欄位對照（數值可為字串或數字）
+pub struct TickerFields {
+    /// 交易對欄位（批量比對用）
+    pub symbol: &'static str,
+    pub last: &'static str,
+    pub high: &'static str,
+    pub low: &'static str,
+    pub volume: &'static str,
+    pub quote_volume: &'static str,
+    pub change: ChangeSource,
+}
+
+pub struct ExchangeSpec {
+    pub id: &'static str,
+    /// 錯誤訊息中的顯示名稱
+    pub label: &'static str,
+    pub default_base_url: &'static str,
+    pub pair: PairFormat,
+    /// 單一 ticker 路徑，`{pair}` 會替換為交易對
+    pub ticker_path: &'static str,
+    /// 全部 tickers 路徑
+    pub all_tickers_path: &'static str,
+    /// 單一 ticker 回應中 ticker 物件的 JSON pointer（"" = 整個回應）
+    pub ticker_pointer: &'static str,
+    /// 全部 tickers 回應中陣列的 JSON pointer
+    pub list_pointer: &'static str,
+    pub fields: TickerFields,
+}
+
+pub const EXCHANGES: &[ExchangeSpec] = &[
+    ExchangeSpec {
+        id: "bybit",
+        label: "Bybit",
+        default_base_url: "https://api.bybit.com",
+        pair: PairFormat { separator: "", lowercase: false },
+        ticker_path: "/v5/market/tickers?category=spot&symbol={pair}",
+        all_tickers_path: "/v5/market/tickers?category=spot",
+        ticker_pointer: "/result/list/0",
+        list_pointer: "/result/list",
+        fields: TickerFields {
+            symbol: "symbol",
+            last: "lastPrice",
+            high: "highPrice24h",
+            low: "lowPrice24h",
+            volume: "volume24h",
+            quote_volume: "turnover24h",
+            change: ChangeSource::FromOpen("prevPrice24h"),
+        },
+    },
+    ExchangeSpec {
+        id: "gateio",
+        label: "Gate.io",
+        default_base_url: "https://api.gateio.ws",
+        pair: PairFormat { separator: "_", lowercase: false },
+        ticker_path: "/api/v4/spot/tickers?currency_pair={pair}",
+        all_tickers_path: "/api/v4/spot/tickers",
+        ticker_pointer: "/0",
+        list_pointer: "",
+        fields: TickerFields {
+            symbol: "currency_pair",
+            last: "last",
+            high: "high_24h",
+            low: "low_24h",
+            volume: "base_volume",
+            quote_volume: "quote_volume",
+            // Gate.io change_percentage is already in percent (e.g. -4.47)
+            change: ChangeSource::Percent("change_percentage"),
+        },
+    },
+    ExchangeSpec {
+        id: "htx",
+        label: "HTX",
+        default_base_url: "https://api.huobi.pro",
+        pair: PairFormat { separator: "", lowercase: true },
+        ticker_path: "/market/detail/merged?symbol={pair}",
+        all_tickers_path: "/market/tickers",
+        ticker_pointer: "/tick",
+        list_pointer: "/data",
+        fields: TickerFields {
+            symbol: "symbol",
+            last: "close",
+            high: "high",
+            low: "low",
+            volume: "amount",
+            quote_volume: "vol",
+            change: ChangeSource::FromOpen("open"),
+        },
+    },
+    ExchangeSpec {
+        id: "mexc",
+        label: "MEXC",
+        default_base_url: "https://api.mexc.com",
+        pair: PairFormat { separator: "", lowercase: false },
+        ticker_path: "/api/v3/ticker/24hr?symbol={pair}",
+        all_tickers_path: "/api/v3/ticker/24hr",
+        ticker_pointer: "",
+        list_pointer: "",
+        fields: TickerFields {
+            symbol: "symbol",
+            last: "lastPrice",
+            high: "highPrice",
+            low: "lowPrice",
+            volume: "volume",
+            quote_volume: "quoteVolume",
+            change: ChangeSource::Direct {
+                change: "priceChange",
+                percent: "priceChangePercent",
+            },
+        },
+    },
+];
+
+/// 由 Bybit linear ticker 與 account-ratio 回應組成持倉資料
+fn parse_bybit_positioning(ticker: &serde_json::Value, ratio: Option<&serde_json::Value>) -> Result<Positioning, String> {
+    let item = ticker
+        .pointer("/result/list/0")
+        .ok_or_else(|| format!("Bybit: {}", api_error(ticker).unwrap_or("perpetual contract not found")))?;
+    let long_short_ratio = ratio.and_then(|r| r.pointer("/result/list/0")).and_then(|r| {
+        let (buy, sell) = (num(&r["buyRatio"])?, num(&r["sellRatio"])?);
+        (sell > 0.0).then_some(buy / sell)
+    });
+    Ok(Positioning {
+        open_interest: num(&item["openInterest"]),
+        open_interest_usd: num(&item["openInterestValue"]),
+        long_short_ratio,
+    })
+}
+
+pub fn exchange_spec(id: &str) -> Option<&'static ExchangeSpec> {
+    EXCHANGES.iter().find(|spec| spec.id == id)
+}
+
+fn num(v: &serde_json::Value) -> Option<f64> {
+    v.as_f64().or_else(|| v.as_str().and_then(|s| s.parse().ok()))
+}
+
+/// 交易所常見的錯誤訊息欄位
+fn api_error(data: &serde_json::Value) -> Option<&str> {
+    ["err-msg", "retMsg", "msg", "message"]
+        .iter()
+        .find_map(|k| data[*k].as_str())
+        .filter(|m| !m.is_empty() && *m != "OK")
+}
+
+impl ExchangeSpec {
+    /// 回傳（交易所格式交易對, 報價幣別）
+    pub(crate) fn to_pair(&self, symbol: &str) -> (String, String) {
+        let (base, quote) = parse_crypto_symbol(symbol);
+        let quote = if quote == "USD" { "USDT".to_string() } else { quote };
+        let pair = format!("{}{}{}", base, self.pair.separator, quote);
+        let pair = if self.pair.lowercase {
+            pair.to_lowercase()
+        } else {
+            pair
+        };
+        (pair, quote)
+    }
+
+    pub(crate) fn parse_ticker(&self, symbol: &str, currency: &str, item: &serde_json::Value) -> AssetData {
+        let f = &self.fields;
+        let field = |k: &str| num(&item[k]);
+        let last = field(f.last).unwrap_or(0.0);
+        let (change, pct) = match f.change {
+            ChangeSource::FromOpen(open) => match field(open).filter(|o| *o > 0.0) {
+                Some(o) => (Some(last - o), Some((last - o) / o * 100.0)),
+                None => (None, None),
+            },
+            ChangeSource::Direct { change, percent } => (field(change), field(percent)),
+            ChangeSource::Percent(percent) => {
+                let pct = field(percent);
+                (pct.map(|p| last * p / (100.0 + p)), pct)
+            }
+        };
+
+        AssetDataBuilder::new(symbol, self.id)
+            .price(last)
+            .currency(currency)
+            .change_24h(change)
+            .change_percent_24h(pct)
+            .high_24h(field(f.high))
+            .low_24h(field(f.low))
+            .volume(field(f.volume))
+            .extra_f64("quote_volume", field(f.quote_volume))
+            .build()
+    }
+
+    /// 由全部 tickers 回應比對出請求的 symbols
+    fn match_tickers(&self, symbols: &[String], data: &serde_json::Value) -> Result<Vec<AssetData>, String> {
+        let list = data
+            .pointer(self.list_pointer)
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| format!("{}: {}", self.label, api_error(data).unwrap_or("no results")))?;
+        let map: HashMap<&str, &serde_json::Value> = list
+            .iter()
+            .filter_map(|t| Some((t[self.fields.symbol].as_str()?, t)))
+            .collect();
+
+        Ok(symbols
+            .iter()
+            .filter_map(|sym| {
+                let (pair, quote) = self.to_pair(sym);
+                map.get(pair.as_str())
+                    .map(|item| self.parse_ticker(sym, &quote, item))
+            })
+            .collect())
+    }
+}
+
+pub struct SimpleExchangeProvider {
+    spec: &'static ExchangeSpec,
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl SimpleExchangeProvider {
+    pub fn new(spec: &'static ExchangeSpec, api_url: Option<String>, http: &HttpOptions) -> Self {
+        Self {
+            spec,
+            client: provider_client(http),
+            base_url: resolve_base_url(api_url, spec.default_base_url),
+        }
+    }
+
+    async fn get_json(&self, path: &str, what: &str) -> Result<serde_json::Value, String> {
+        let label = self.spec.label;
+        self.client
+            .get(format!("{}{}", self.base_url, path))
+            .send_captured(self.spec.id)
+            .await
+            .map_err(|e| format!("{} {}connection failed: {}", label, what, e))?
+            .error_for_status()
+            .map_err(|e| format!("{} {}API error: {}", label, what, e))?
+            .json()
+            .await
+            .map_err(|e| format!("{} {}parse failed: {}", label, what, e))
+    }
+
+    /// Bybit 同交易對 USDT 永續合約的未平倉量（必要）與最新 5 分鐘多空帳戶比（可缺）
+    async fn fetch_bybit_positioning(&self, symbol: String) -> Result<Positioning, String> {
+        let (pair, _) = self.spec.to_pair(&symbol);
+        let ticker_path = format!("/v5/market/tickers?category=linear&symbol={}", pair);
+        let ratio_path = format!("/v5/market/account-ratio?category=linear&symbol={}&period=5min&limit=1", pair);
+        let (ticker, ratio) =
+            futures::join!(self.get_json(&ticker_path, "futures "), self.get_json(&ratio_path, "futures "));
+        parse_bybit_positioning(&ticker?, ratio.ok().as_ref())
+    }
+}
+
+#[async_trait::async_trait]
+impl DataProvider for SimpleExchangeProvider {
+    fn info(&self) -> ProviderInfo {
+        provider_info_or_panic(self.spec.id)
+    }
+
+    async fn fetch_price(&self, symbol: &str) -> Result<AssetData, String> {
+        let spec = self.spec;
+        let (pair, quote) = spec.to_pair(symbol);
+        let data = self
+            .get_json(&spec.ticker_path.replace("{pair}", &pair), "")
+            .await?;
+
+        let item = data
+            .pointer(spec.ticker_pointer)
+            .filter(|v| v.is_object())
+            .ok_or_else(|| match api_error(&data) {
+                Some(msg) => format!("{}: {}", spec.label, msg),
+                None => format!("{}: trading pair not found", spec.label),
+            })?;
+        Ok(spec.parse_ticker(symbol, &quote, item))
+    }
+
+    /// 批量查詢 — 抓全部 tickers 後本地比對
+    async fn fetch_prices(&self, symbols: &[String]) -> Result<Vec<AssetData>, String> {
+        if symbols.is_empty() {
+            return Ok(vec![]);
+        }
+        if symbols.len() == 1 {
+            return self.fetch_price(&symbols[0]).await.map(|d| vec![d]);
+        }
+
+        let data = self.get_json(self.spec.all_tickers_path, "batch ").await?;
+        self.spec.match_tickers(symbols, &data)
+    }
+
+    /// Bybit 的 `provider_params` 支援 `{"derivatives": true}`：另查詢永續合約的未平倉量與多空比
+    async fn fetch_prices_with_params(
+        &self,
+        symbols: &[String],
+        params: &HashMap<String, ProviderParams>,
+    ) -> Result<Vec<AssetData>, String> {
+        let mut results = self.fetch_prices(symbols).await?;
+        if self.spec.id == "bybit" {
+            derivatives::enrich(&mut results, params, |sym| self.fetch_bybit_positioning(sym)).await;
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn pair_format_follows_spec() {
+        let pair = |id: &str, sym: &str| exchange_spec(id).unwrap().to_pair(sym).0;
+        assert_eq!(pair("htx", "BTC-USD"), "btcusdt");
+        assert_eq!(pair("gateio", "ETH/BTC"), "ETH_BTC");
+        assert_eq!(pair("mexc", "SOLUSDT"), "SOLUSDT");
+        assert!(exchange_spec("binance").is_none());
+    }
+
+    #[test]
+    fn tickers_parse_with_each_change_source() {
+        let htx = exchange_spec("htx").unwrap();
+        let data = json!({"status": "ok", "data": [
+            {"symbol": "btcusdt", "close": 110.0, "open": 100.0, "amount": 5.0, "vol": 550.0},
+            {"symbol": "ethusdt", "close": 10.0, "open": 0.0}
+        ]});
+        let symbols = vec!["BTCUSDT".to_string(), "ETH-USD".to_string(), "XRPUSDT".to_string()];
+        let out = htx.match_tickers(&symbols, &data).unwrap();
+        assert_eq!(out.len(), 2);
+        assert_eq!((out[0].change_24h, out[0].change_percent_24h), (Some(10.0), Some(10.0)));
+        assert_eq!(out[1].change_24h, None);
+
+        let gate = exchange_spec("gateio").unwrap();
+        let item = json!({"currency_pair": "ETH_BTC", "last": "0.05", "change_percentage": "25"});
+        let d = gate.parse_ticker("ETH/BTC", "BTC", &item);
+        assert_eq!(d.currency, "BTC");
+        assert!((d.change_24h.unwrap() - 0.01).abs() < 1e-12);
+
+        let mexc = exchange_spec("mexc").unwrap();
+        let item = json!({"lastPrice": "2.5", "priceChange": "0.5", "priceChangePercent": "25"});
+        let d = mexc.parse_ticker("SOLUSDT", "USDT", &item);
+        assert_eq!((d.change_24h, d.change_percent_24h), (Some(0.5), Some(25.0)));
+    }
+
+    #[test]
+    fn bybit_positioning_uses_linear_contract() {
+        let ticker = json!({"retCode": 0, "retMsg": "OK", "result": {"category": "linear", "list": [
+            {"symbol": "BTCUSDT", "openInterest": "52000.5", "openInterestValue": "3500000000"}
+        ]}});
+        let ratio = json!({"retCode": 0, "result": {"list": [
+            {"symbol": "BTCUSDT", "buyRatio": "0.6", "sellRatio": "0.4", "timestamp": "1700000000000"}
+        ]}});
+        let p = parse_bybit_positioning(&ticker, Some(&ratio)).unwrap();
+        assert_eq!((p.open_interest, p.open_interest_usd), (Some(52000.5), Some(3.5e9)));
+        assert!((p.long_short_ratio.unwrap() - 1.5).abs() < 1e-12);
+
+        let missing = json!({"retCode": 10001, "retMsg": "params error", "result": {"list": []}});
+        assert_eq!(parse_bybit_positioning(&missing, None).unwrap_err(), "Bybit: params error");
+    }
+}
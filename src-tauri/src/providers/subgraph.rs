@@ -1,8 +1,121 @@
+use crate::providers::debug::SendCaptured;
 use crate::providers::traits::{DataProvider, DexPoolLookup};
 use crate::providers::types::{
-    provider_info_or_panic, shared_client, AssetData, AssetDataBuilder, DexPoolInfo, ProviderInfo,
+    provider_info_or_panic, provider_client, AssetData, AssetDataBuilder, DexPoolInfo, HttpOptions, ProviderInfo,
+    ProviderParams,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// 每次 GraphQL 查詢最多帶幾個 pool id
+const POOLS_PER_QUERY: usize = 100;
+/// fee tier 的單位：百萬分之一（Uniswap v3 的 3000 = 0.3%）
+const FEE_TIER_DENOMINATOR: f64 = 1_000_000.0;
+
+/// 一個 DEX 協議在某條鏈上的 subgraph
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubgraphProtocol {
+    /// symbol 中使用的協議代號，例如 `uniswap_v3`、`aerodrome`
+    pub protocol: String,
+    pub chain: String,
+    /// 顯示名稱（route_path 使用）
+    pub name: String,
+    /// The Graph 上的 subgraph ID
+    pub subgraph_id: String,
+    /// 內建項目（不可刪除，使用者自訂同 protocol + chain 時會被覆蓋）
+    #[serde(default)]
+    pub builtin: bool,
+}
+
+/// 內建的 subgraph：(protocol, chain, name, subgraph_id)
+const BUILTIN_PROTOCOLS: &[(&str, &str, &str, &str)] = &[
+    ("uniswap_v3", "ethereum", "Uniswap V3", "5zvR82QoaXYFyDEKLZ9t6v9adgnptxYpKpSbxtgVENFV"),
+    ("uniswap_v3", "polygon", "Uniswap V3", "3hCPRGf4z88aYsZgKKE5VacLe2xvFam81D8K1pqa67xt"),
+    ("uniswap_v3", "arbitrum", "Uniswap V3", "FbCGRftH4a3yZugY7TnbYgPJVEv2LvMT6oF1fxPe9aJM"),
+    ("uniswap_v3", "base", "Uniswap V3", "GqzP4Xaehti8KSfQmv3ZctFSjnSUYz4fUR9GtFb2LHnC"),
+    ("uniswap_v3", "bsc", "Uniswap V3", "F85MNzUGYqgSHSHRGgeVMNsdnW1KtZSVgFULumXRZTw2"),
+    ("uniswap_v3", "optimism", "Uniswap V3", "Cghf4LfVqPiFw6fp6Y5X5Ubc8UpmUhSfJL82zwiBFLaj"),
+    ("uniswap_v3", "celo", "Uniswap V3", "ESdrTJ3twMwWVoQ1hUE2u7PugEHX3QkenudD6aXCkDQ4"),
+    ("sushiswap", "ethereum", "SushiSwap", "6NUtT5mGjZ1tSPHceYRnFnJFYBGMvEPLszerMRmCw4C3"),
+    ("pancakeswap", "bsc", "PancakeSwap", "A1fvJWQLBeUAggX2WtXq31Dqkn2gHP3Jnj2bh8JqBnQo"),
+];
+
+/// 使用者自訂的 subgraph（由 app setting `subgraph_protocols` 載入）
+static CUSTOM_PROTOCOLS: RwLock<Vec<SubgraphProtocol>> = RwLock::new(Vec::new());
+
+pub fn set_custom_protocols(protocols: Vec<SubgraphProtocol>) {
+    *CUSTOM_PROTOCOLS.write().unwrap_or_else(|e| e.into_inner()) = protocols;
+}
+
+pub fn custom_protocols() -> Vec<SubgraphProtocol> {
+    CUSTOM_PROTOCOLS.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+fn builtin_protocols() -> impl Iterator<Item = SubgraphProtocol> {
+    BUILTIN_PROTOCOLS
+        .iter()
+        .map(|(protocol, chain, name, subgraph_id)| SubgraphProtocol {
+            protocol: protocol.to_string(),
+            chain: chain.to_string(),
+            name: name.to_string(),
+            subgraph_id: subgraph_id.to_string(),
+            builtin: true,
+        })
+}
+
+/// 完整的 protocol registry：自訂項目優先，再補上未被覆蓋的內建項目
+pub fn protocol_registry() -> Vec<SubgraphProtocol> {
+    registry_with(custom_protocols())
+}
+
+fn registry_with(custom: Vec<SubgraphProtocol>) -> Vec<SubgraphProtocol> {
+    let mut all = custom;
+    for p in builtin_protocols() {
+        if !all.iter().any(|c| c.protocol == p.protocol && c.chain == p.chain) {
+            all.push(p);
+        }
+    }
+    all
+}
+
+/// 檢查自訂 protocol：欄位不可為空、protocol / chain 不可含 ':'，且不可重複
+pub fn validate_protocols(protocols: &[SubgraphProtocol]) -> Result<(), String> {
+    let mut seen = std::collections::HashSet::new();
+    for p in protocols {
+        if p.protocol.trim().is_empty() || p.chain.trim().is_empty() || p.subgraph_id.trim().is_empty() {
+            return Err("Subgraph protocol, chain and subgraph ID are required".to_string());
+        }
+        if p.protocol.contains(':') || p.chain.contains(':') {
+            return Err(format!("Subgraph protocol/chain must not contain ':' ({}:{})", p.protocol, p.chain));
+        }
+        if !seen.insert((p.protocol.as_str(), p.chain.as_str())) {
+            return Err(format!("Duplicate subgraph protocol: {}:{}", p.protocol, p.chain));
+        }
+    }
+    Ok(())
+}
+
+/// 依 "protocol" 或 "protocol:chain" 查 registry；只給 protocol 時優先 ethereum，否則取第一條鏈
+fn find_protocol(key: &str) -> Option<SubgraphProtocol> {
+    find_in(protocol_registry(), key)
+}
+
+fn find_in(registry: Vec<SubgraphProtocol>, key: &str) -> Option<SubgraphProtocol> {
+    match key.split_once(':') {
+        Some((protocol, chain)) => registry
+            .into_iter()
+            .find(|p| p.protocol == protocol && p.chain == chain),
+        None => {
+            let mut matches = registry.into_iter().filter(|p| p.protocol == key);
+            let first = matches.next()?;
+            if first.chain == "ethereum" {
+                return Some(first);
+            }
+            Some(matches.find(|p| p.chain == "ethereum").unwrap_or(first))
+        }
+    }
+}
 
 pub struct SubgraphProvider {
     client: reqwest::Client,
@@ -11,71 +124,98 @@ pub struct SubgraphProvider {
 }
 
 impl SubgraphProvider {
-    pub fn new(api_key: Option<String>, api_url: Option<String>) -> Self {
+    pub fn new(api_key: Option<String>, api_url: Option<String>, http: &HttpOptions) -> Self {
         Self {
-            client: shared_client(),
+            client: provider_client(http),
             api_key,
             api_url,
         }
     }
 
-    /// Parse symbol: "protocol:pool_address:token_from:token_to"
+    /// Parse symbol: "protocol[:chain]:pool_address:token_from:token_to"
     fn parse_symbol(symbol: &str) -> Result<(&str, &str, &str, &str), String> {
-        let parts: Vec<&str> = symbol.splitn(4, ':').collect();
-        if parts.len() != 4 {
+        let parts: Vec<&str> = symbol.rsplitn(4, ':').collect();
+        if parts.len() != 4 || parts.iter().any(|p| p.is_empty()) {
             return Err(format!(
-                "Invalid Subgraph symbol format '{}', expected 'protocol:pool:tokenFrom:tokenTo'",
+                "Invalid Subgraph symbol format '{}', expected 'protocol[:chain]:pool:tokenFrom:tokenTo'",
                 symbol
             ));
         }
-        Ok((parts[0], parts[1], parts[2], parts[3]))
+        Ok((parts[3], parts[2], parts[1], parts[0]))
     }
 
-    fn get_subgraph_url(&self, protocol: &str) -> Result<String, String> {
+    /// 取得 protocol（可帶 ":chain"）對應的 subgraph URL 與顯示名稱
+    fn get_subgraph_url(&self, protocol: &str) -> Result<(String, String), String> {
+        let entry = find_protocol(protocol);
+        let name = entry
+            .as_ref()
+            .map(|p| p.name.clone())
+            .unwrap_or_else(|| protocol.to_string());
+
         // If user provided a custom api_url, use it directly
         if let Some(ref url) = self.api_url {
-            return Ok(url.clone());
+            return Ok((url.clone(), name));
         }
 
         let api_key = self.api_key.as_deref().ok_or_else(|| {
             "Subgraph requires an API key from The Graph (thegraph.com)".to_string()
         })?;
-
-        // protocol format: "uniswap_v3" or "uniswap_v3:base" (with chain suffix)
-        let (base_protocol, chain) = if let Some((p, c)) = protocol.split_once(':') {
-            (p, c)
-        } else {
-            (protocol, "ethereum")
-        };
-
-        let subgraph_id = match (base_protocol, chain) {
-            // Uniswap V3 by chain
-            ("uniswap_v3", "ethereum") => "5zvR82QoaXYFyDEKLZ9t6v9adgnptxYpKpSbxtgVENFV",
-            ("uniswap_v3", "polygon") => "3hCPRGf4z88aYsZgKKE5VacLe2xvFam81D8K1pqa67xt",
-            ("uniswap_v3", "arbitrum") => "FbCGRftH4a3yZugY7TnbYgPJVEv2LvMT6oF1fxPe9aJM",
-            ("uniswap_v3", "base") => "GqzP4Xaehti8KSfQmv3ZctFSjnSUYz4fUR9GtFb2LHnC",
-            ("uniswap_v3", "bsc") => "F85MNzUGYqgSHSHRGgeVMNsdnW1KtZSVgFULumXRZTw2",
-            ("uniswap_v3", "optimism") => "Cghf4LfVqPiFw6fp6Y5X5Ubc8UpmUhSfJL82zwiBFLaj",
-            ("uniswap_v3", "celo") => "ESdrTJ3twMwWVoQ1hUE2u7PugEHX3QkenudD6aXCkDQ4",
-            // SushiSwap (Ethereum only for now)
-            ("sushiswap", _) => "6NUtT5mGjZ1tSPHceYRnFnJFYBGMvEPLszerMRmCw4C3",
-            // PancakeSwap (BSC)
-            ("pancakeswap", _) => "A1fvJWQLBeUAggX2WtXq31Dqkn2gHP3Jnj2bh8JqBnQo",
-            _ => return Err(format!("Unsupported DEX protocol/chain: {}:{}", base_protocol, chain)),
-        };
-
-        Ok(format!(
-            "https://gateway.thegraph.com/api/{}/subgraphs/id/{}",
-            api_key, subgraph_id
+        let entry = entry.ok_or_else(|| format!("Unsupported DEX protocol/chain: {}", protocol))?;
+
+        Ok((
+            format!(
+                "https://gateway.thegraph.com/api/{}/subgraphs/id/{}",
+                api_key, entry.subgraph_id
+            ),
+            name,
         ))
     }
 
-    fn build_query(pool_address: &str) -> String {
+    fn build_query(pool_addresses: &[&str]) -> String {
+        let ids: Vec<String> = pool_addresses
+            .iter()
+            .map(|a| format!("\"{}\"", a.to_lowercase()))
+            .collect();
         format!(
-            r#"{{ pool(id: "{}") {{ token0 {{ id symbol decimals }} token1 {{ id symbol decimals }} token0Price token1Price totalValueLockedUSD volumeUSD }} }}"#,
-            pool_address.to_lowercase()
+            r#"{{ pools(first: {}, where: {{ id_in: [{}] }}) {{ id token0 {{ id symbol decimals }} token1 {{ id symbol decimals }} token0Price token1Price totalValueLockedUSD volumeUSD }} }}"#,
+            pool_addresses.len(),
+            ids.join(", ")
         )
     }
+
+    /// 以單一 GraphQL 請求查詢多個 pool
+    async fn query_pools(
+        client: &reqwest::Client,
+        url: &str,
+        pool_addresses: &[&str],
+    ) -> Result<Vec<PoolData>, String> {
+        let body = serde_json::json!({ "query": Self::build_query(pool_addresses) });
+        let resp = client
+            .post(url)
+            .json(&body)
+            .send_captured("subgraph")
+            .await
+            .map_err(|e| format!("Subgraph request failed: {}", e))?;
+
+        if !resp.status().is_success() {
+            return Err(format!("Subgraph API error: HTTP {}", resp.status()));
+        }
+
+        let graph_resp: GraphResponse = resp
+            .json()
+            .await
+            .map_err(|e| format!("Subgraph JSON parse failed: {}", e))?;
+
+        if let Some(errors) = &graph_resp.errors {
+            let msg = errors
+                .first()
+                .and_then(|e| e.message.as_deref())
+                .unwrap_or("Unknown error");
+            return Err(format!("Subgraph query error: {}", msg));
+        }
+
+        Ok(graph_resp.data.and_then(|d| d.pools).unwrap_or_default())
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -86,7 +226,7 @@ struct GraphResponse {
 
 #[derive(Debug, Deserialize)]
 struct GraphData {
-    pool: Option<PoolData>,
+    pools: Option<Vec<PoolData>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -97,6 +237,7 @@ struct GraphError {
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct PoolData {
+    id: Option<String>,
     token0: Option<TokenData>,
     token1: Option<TokenData>,
     token0_price: Option<String>,
@@ -113,6 +254,66 @@ struct TokenData {
     _decimals: Option<String>,
 }
 
+/// 將 pool 資料轉成 token_from → token_to 方向的報價；token_from 不屬於此 pool 時回傳錯誤
+fn pool_to_asset(
+    symbol: &str,
+    protocol_name: &str,
+    token_from: &str,
+    token_to: &str,
+    pool: &PoolData,
+) -> Result<AssetData, String> {
+    let parse = |v: &Option<String>| v.as_deref().unwrap_or("0").parse::<f64>().unwrap_or(0.0);
+    let token_id = |t: &Option<TokenData>| t.as_ref().and_then(|t| t.id.clone()).unwrap_or_default();
+    let token_symbol = |t: &Option<TokenData>| {
+        t.as_ref()
+            .and_then(|t| t.symbol.clone())
+            .unwrap_or_else(|| "?".into())
+    };
+
+    // token0Price = how many token0 per 1 token1
+    // token1Price = how many token1 per 1 token0
+    let (price, currency_symbol, reverse_price) = if token_from.eq_ignore_ascii_case(&token_id(&pool.token0)) {
+        (parse(&pool.token1_price), token_symbol(&pool.token1), parse(&pool.token0_price))
+    } else if token_from.eq_ignore_ascii_case(&token_id(&pool.token1)) {
+        (parse(&pool.token0_price), token_symbol(&pool.token0), parse(&pool.token1_price))
+    } else {
+        return Err(format!(
+            "Subgraph: token_from {} not matching token0 or token1 for symbol {}",
+            token_from, symbol
+        ));
+    };
+
+    let volume = parse(&pool.volume_usd);
+    let tvl = parse(&pool.total_value_locked_usd);
+
+    Ok(AssetDataBuilder::new(symbol, "subgraph")
+        .price(price)
+        .currency(&currency_symbol)
+        .volume(Some(volume))
+        .liquidity_usd(Some(tvl))
+        .extra_f64("pool_tvl", Some(tvl))
+        .extra_f64("volume_24h", Some(volume))
+        .extra_f64("reverse_price", Some(reverse_price))
+        .extra_str("token_from", Some(token_from))
+        .extra_str("token_to", Some(token_to))
+        .extra_str("route_path", Some(&format!("{} Direct", protocol_name)))
+        .extra_str("gas_estimate", Some("~0.005 ETH"))
+        .build())
+}
+
+/// 以 pool 手續費（`provider_params.fee_tier`，百萬分之一）將報價換算為扣費後可換得的數量
+fn apply_fee_tier(data: &mut AssetData, fee_tier: u64) -> Result<(), String> {
+    if fee_tier as f64 >= FEE_TIER_DENOMINATOR {
+        return Err(format!("Subgraph: invalid fee_tier {} for {}", fee_tier, data.symbol));
+    }
+    let gross = data.price;
+    data.price = gross * (1.0 - fee_tier as f64 / FEE_TIER_DENOMINATOR);
+    let extra = data.extra.get_or_insert_with(HashMap::new);
+    extra.insert("fee_tier".into(), fee_tier.into());
+    extra.insert("price_before_fee".into(), gross.into());
+    Ok(())
+}
+
 #[async_trait::async_trait]
 impl DataProvider for SubgraphProvider {
     fn info(&self) -> ProviderInfo {
@@ -121,271 +322,100 @@ impl DataProvider for SubgraphProvider {
 
     async fn fetch_price(&self, symbol: &str) -> Result<AssetData, String> {
         let (protocol, pool_addr, token_from, token_to) = Self::parse_symbol(symbol)?;
-        let url = self.get_subgraph_url(protocol)?;
-        let query = Self::build_query(pool_addr);
-
-        let body = serde_json::json!({ "query": query });
-        let resp = self
-            .client
-            .post(&url)
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| format!("Subgraph request failed: {}", e))?;
-
-        if !resp.status().is_success() {
-            return Err(format!("Subgraph API error: HTTP {}", resp.status()));
-        }
-
-        let graph_resp: GraphResponse = resp
-            .json()
-            .await
-            .map_err(|e| format!("Subgraph JSON parse failed: {}", e))?;
-
-        if let Some(errors) = &graph_resp.errors {
-            let msg = errors
-                .first()
-                .and_then(|e| e.message.as_deref())
-                .unwrap_or("Unknown error");
-            return Err(format!("Subgraph query error: {}", msg));
-        }
-
-        let pool = graph_resp
-            .data
-            .and_then(|d| d.pool)
+        let (url, protocol_name) = self.get_subgraph_url(protocol)?;
+        let pools = Self::query_pools(&self.client, &url, &[pool_addr]).await?;
+        let pool = pools
+            .first()
             .ok_or_else(|| format!("Subgraph: pool {} not found", pool_addr))?;
-
-        let token0_id = pool
-            .token0
-            .as_ref()
-            .and_then(|t| t.id.as_deref())
-            .unwrap_or("");
-        let token1_id = pool
-            .token1
-            .as_ref()
-            .and_then(|t| t.id.as_deref())
-            .unwrap_or("");
-
-        // Determine price direction
-        // token0Price = how many token0 per 1 token1
-        // token1Price = how many token1 per 1 token0
-        let price: f64 = if token_from.eq_ignore_ascii_case(token0_id) {
-            // token_from is token0, we want: 1 token0 → X token1 = token1Price
-            pool.token1_price
-                .as_deref()
-                .unwrap_or("0")
-                .parse()
-                .unwrap_or(0.0)
-        } else if token_from.eq_ignore_ascii_case(token1_id) {
-            // token_from is token1, we want: 1 token1 → X token0 = token0Price
-            pool.token0_price
-                .as_deref()
-                .unwrap_or("0")
-                .parse()
-                .unwrap_or(0.0)
-        } else {
-            // Fallback
-            pool.token1_price
-                .as_deref()
-                .unwrap_or("0")
-                .parse()
-                .unwrap_or(0.0)
-        };
-
-        let tvl: f64 = pool
-            .total_value_locked_usd
-            .as_deref()
-            .unwrap_or("0")
-            .parse()
-            .unwrap_or(0.0);
-        let volume: f64 = pool
-            .volume_usd
-            .as_deref()
-            .unwrap_or("0")
-            .parse()
-            .unwrap_or(0.0);
-
-        let protocol_name = match protocol {
-            "uniswap_v3" => "Uniswap V3",
-            "sushiswap" => "SushiSwap",
-            "pancakeswap" => "PancakeSwap",
-            _ => protocol,
-        };
-
-        Ok(AssetDataBuilder::new(symbol, "subgraph")
-            .price(price)
-            .volume(Some(volume))
-            .extra_f64("pool_tvl", Some(tvl))
-            .extra_f64("volume_24h", Some(volume))
-            .extra_str("token_from", Some(token_from))
-            .extra_str("token_to", Some(token_to))
-            .extra_str("route_path", Some(&format!("{} Direct", protocol_name)))
-            .extra_str("gas_estimate", Some("~0.005 ETH"))
-            .build())
+        pool_to_asset(symbol, &protocol_name, token_from, token_to, pool)
     }
 
+    /// 同一個 subgraph 的 pool 合併成 `pools(where: { id_in })` 查詢，不同 subgraph 限流並行
     async fn fetch_prices(&self, symbols: &[String]) -> Result<Vec<AssetData>, String> {
-        let mut tasks = tokio::task::JoinSet::new();
-        let mut results = Vec::new();
-        let semaphore = std::sync::Arc::new(tokio::sync::Semaphore::new(5));
-
+        // subgraph URL → (顯示名稱, 該 subgraph 的 symbols)
+        let mut groups: HashMap<String, (String, Vec<String>)> = HashMap::new();
         for symbol in symbols {
-            let symbol = symbol.clone();
-            let sem = semaphore.clone();
-
-            // pre-compute information before spawning the task to avoid lifetime/borrow issues
-            let (protocol, pool_address, token_from, token_to) = match Self::parse_symbol(&symbol) {
-                Ok(t) => (
-                    t.0.to_string(),
-                    t.1.to_string(),
-                    t.2.to_string(),
-                    t.3.to_string(),
-                ),
-                Err(_) => continue,
-            };
-
-            let api_url = match self.get_subgraph_url(&protocol) {
-                Ok(u) => u,
-                Err(_) => continue,
+            let (protocol, ..) = match Self::parse_symbol(symbol) {
+                Ok(t) => t,
+                Err(e) => {
+                    tracing::warn!("{}", e);
+                    continue;
+                }
             };
+            match self.get_subgraph_url(protocol) {
+                Ok((url, name)) => groups.entry(url).or_insert_with(|| (name, Vec::new())).1.push(symbol.clone()),
+                Err(e) => tracing::warn!("Subgraph skipped {}: {}", symbol, e),
+            }
+        }
 
-            let query = Self::build_query(&pool_address);
-            let client = self.client.clone();
-
-            tasks.spawn(async move {
-                let _permit = sem.acquire().await;
-
-                let json_req = serde_json::json!({ "query": query });
-
-                let resp = match client
-                    .post(&api_url)
-                    .json(&json_req)
-                    .send()
-                    .await
-                    .and_then(|r| r.error_for_status())
-                {
-                    Ok(r) => r,
-                    Err(e) => {
-                        eprintln!("Subgraph connection failed: {}", e);
-                        return None;
-                    }
-                };
-
-                let graph_resp: GraphResponse = match resp.json().await {
-                    Ok(b) => b,
-                    Err(_) => return None,
-                };
-
-                if let Some(errors) = &graph_resp.errors {
-                    eprintln!("Subgraph query error for {}: {:?}", symbol, errors);
-                    return None;
-                }
+        let mut tasks = tokio::task::JoinSet::new();
+        let mut results = Vec::new();
+        let semaphore = std::sync::Arc::new(tokio::sync::Semaphore::new(5));
 
-                let pool = match graph_resp.data.and_then(|d| d.pool) {
-                    Some(p) => p,
-                    None => {
-                        eprintln!(
-                            "Subgraph: pool {} not found for symbol {}",
-                            pool_address, symbol
-                        );
-                        return None;
-                    }
-                };
-
-                let token0_id = pool
-                    .token0
-                    .as_ref()
-                    .and_then(|t| t.id.as_deref())
-                    .unwrap_or("");
-                let token1_id = pool
-                    .token1
-                    .as_ref()
-                    .and_then(|t| t.id.as_deref())
-                    .unwrap_or("");
-
-                let (price, currency_symbol, reverse_price) =
-                    if token_from.eq_ignore_ascii_case(token0_id) {
-                        (
-                            pool.token1_price
-                                .as_deref()
-                                .unwrap_or("0")
-                                .parse::<f64>()
-                                .unwrap_or(0.0),
-                            pool.token1
-                                .as_ref()
-                                .and_then(|t| t.symbol.as_deref())
-                                .unwrap_or("?"),
-                            pool.token0_price
-                                .as_deref()
-                                .unwrap_or("0")
-                                .parse::<f64>()
-                                .unwrap_or(0.0),
-                        )
-                    } else if token_from.eq_ignore_ascii_case(token1_id) {
-                        (
-                            pool.token0_price
-                                .as_deref()
-                                .unwrap_or("0")
-                                .parse::<f64>()
-                                .unwrap_or(0.0),
-                            pool.token0
-                                .as_ref()
-                                .and_then(|t| t.symbol.as_deref())
-                                .unwrap_or("?"),
-                            pool.token1_price
-                                .as_deref()
-                                .unwrap_or("0")
-                                .parse::<f64>()
-                                .unwrap_or(0.0),
-                        )
-                    } else {
-                        eprintln!(
-                            "Subgraph: token_from {} not matching token0 or token1 for symbol {}",
-                            token_from, symbol
-                        );
-                        return None;
+        for (url, (protocol_name, group)) in groups {
+            for chunk in group.chunks(POOLS_PER_QUERY) {
+                let chunk = chunk.to_vec();
+                let url = url.clone();
+                let protocol_name = protocol_name.clone();
+                let client = self.client.clone();
+                let sem = semaphore.clone();
+                tasks.spawn(async move {
+                    let _permit = sem.acquire().await;
+                    let parsed: Vec<_> = chunk.iter().filter_map(|s| Self::parse_symbol(s).ok()).collect();
+                    let mut addresses: Vec<&str> = parsed.iter().map(|p| p.1).collect();
+                    addresses.sort_unstable();
+                    addresses.dedup();
+
+                    let pools = match Self::query_pools(&client, &url, &addresses).await {
+                        Ok(p) => p,
+                        Err(e) => {
+                            tracing::warn!("{}", e);
+                            return Vec::new();
+                        }
                     };
+                    let by_id: HashMap<String, &PoolData> = pools
+                        .iter()
+                        .filter_map(|p| p.id.as_ref().map(|id| (id.to_lowercase(), p)))
+                        .collect();
+
+                    let mut out = Vec::new();
+                    for (symbol, (_, pool_addr, token_from, token_to)) in chunk.iter().zip(&parsed) {
+                        let Some(pool) = by_id.get(&pool_addr.to_lowercase()) else {
+                            tracing::warn!("Subgraph: pool {} not found for symbol {}", pool_addr, symbol);
+                            continue;
+                        };
+                        match pool_to_asset(symbol, &protocol_name, token_from, token_to, pool) {
+                            Ok(data) => out.push(data),
+                            Err(e) => tracing::warn!("{}", e),
+                        }
+                    }
+                    out
+                });
+            }
+        }
 
-                let volume: f64 = pool
-                    .volume_usd
-                    .as_deref()
-                    .unwrap_or("0")
-                    .parse()
-                    .unwrap_or(0.0);
-                let tvl: f64 = pool
-                    .total_value_locked_usd
-                    .as_deref()
-                    .unwrap_or("0")
-                    .parse()
-                    .unwrap_or(0.0);
-
-                let protocol_name = match protocol.as_str() {
-                    "uniswap_v3" => "Uniswap V3",
-                    "sushiswap" => "SushiSwap",
-                    "pancakeswap" => "PancakeSwap",
-                    _ => &protocol,
-                };
-
-                Some(
-                    AssetDataBuilder::new(&symbol, "subgraph")
-                        .price(price)
-                        .currency(currency_symbol)
-                        .volume(Some(volume))
-                        .extra_f64("pool_tvl", Some(tvl))
-                        .extra_f64("volume_24h", Some(volume))
-                        .extra_f64("reverse_price", Some(reverse_price))
-                        .extra_str("token_from", Some(&token_from))
-                        .extra_str("token_to", Some(&token_to))
-                        .extra_str("route_path", Some(&format!("{} Direct", protocol_name)))
-                        .extra_str("gas_estimate", Some("~0.005 ETH"))
-                        .build(),
-                )
-            });
+        while let Some(Ok(data)) = tasks.join_next().await {
+            results.extend(data);
         }
+        Ok(results)
+    }
 
-        while let Some(Ok(maybe_data)) = tasks.join_next().await {
-            if let Some(data) = maybe_data {
-                results.push(data);
+    /// `provider_params` 支援 `{"fee_tier": 3000}`：報價扣除該 pool 的交易手續費
+    async fn fetch_prices_with_params(
+        &self,
+        symbols: &[String],
+        params: &HashMap<String, ProviderParams>,
+    ) -> Result<Vec<AssetData>, String> {
+        let mut results = self.fetch_prices(symbols).await?;
+        for data in &mut results {
+            let fee_tier = params
+                .get(&data.symbol)
+                .and_then(|p| p.get("fee_tier"))
+                .and_then(|v| v.as_u64());
+            if let Some(fee_tier) = fee_tier {
+                if let Err(e) = apply_fee_tier(data, fee_tier) {
+                    tracing::warn!("{}", e);
+                }
             }
         }
         Ok(results)
@@ -407,33 +437,11 @@ impl DexPoolLookup for SubgraphProvider {
                 pool_address
             )),
         };
-        let url = self.get_subgraph_url(&protocol_with_chain)?;
-        let query = Self::build_query(addr);
-        let body = serde_json::json!({ "query": query });
-        let resp = self
-            .client
-            .post(&url)
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| format!("Subgraph request failed: {}", e))?;
-        if !resp.status().is_success() {
-            return Err(format!("Subgraph API error: HTTP {}", resp.status()));
-        }
-        let graph_resp: GraphResponse = resp
-            .json()
-            .await
-            .map_err(|e| format!("Subgraph JSON parse failed: {}", e))?;
-        if let Some(errors) = &graph_resp.errors {
-            let msg = errors
-                .first()
-                .and_then(|e| e.message.as_deref())
-                .unwrap_or("Unknown error");
-            return Err(format!("Subgraph query error: {}", msg));
-        }
-        let pool = graph_resp
-            .data
-            .and_then(|d| d.pool)
+        let (url, _) = self.get_subgraph_url(&protocol_with_chain)?;
+        let pools = Self::query_pools(&self.client, &url, &[addr]).await?;
+        let pool = pools
+            .into_iter()
+            .next()
             .ok_or_else(|| format!("Subgraph: pool {} not found", addr))?;
         Ok(DexPoolInfo {
             token0_address: pool
@@ -459,3 +467,76 @@ impl DexPoolLookup for SubgraphProvider {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fee_tier_discounts_quoted_price() {
+        let mut data = AssetDataBuilder::new("uniswap_v3:0xpool:0xa:0xb", "subgraph").price(2000.0).build();
+        apply_fee_tier(&mut data, 3000).unwrap();
+        assert!((data.price - 1994.0).abs() < 1e-9);
+        assert_eq!(data.extra.as_ref().unwrap()["price_before_fee"], 2000.0);
+        assert!(apply_fee_tier(&mut data, 1_000_000).is_err());
+    }
+
+    #[test]
+    fn symbols_with_and_without_chain_parse() {
+        assert_eq!(
+            SubgraphProvider::parse_symbol("uniswap_v3:base:0xpool:0xa:0xb").unwrap(),
+            ("uniswap_v3:base", "0xpool", "0xa", "0xb")
+        );
+        assert_eq!(
+            SubgraphProvider::parse_symbol("sushiswap:0xpool:0xa:0xb").unwrap(),
+            ("sushiswap", "0xpool", "0xa", "0xb")
+        );
+        assert!(SubgraphProvider::parse_symbol("0xpool:0xa:0xb").is_err());
+    }
+
+    #[test]
+    fn custom_protocols_extend_and_override_builtins() {
+        let registry = registry_with(vec![
+            SubgraphProtocol {
+                protocol: "aerodrome".into(),
+                chain: "base".into(),
+                name: "Aerodrome".into(),
+                subgraph_id: "AERO".into(),
+                builtin: false,
+            },
+            SubgraphProtocol {
+                protocol: "uniswap_v3".into(),
+                chain: "base".into(),
+                name: "Uniswap V3 (mirror)".into(),
+                subgraph_id: "MIRROR".into(),
+                builtin: false,
+            },
+        ]);
+        let find = |key: &str| find_in(registry.clone(), key).unwrap();
+        assert_eq!(find("aerodrome").subgraph_id, "AERO");
+        assert_eq!(find("uniswap_v3:base").subgraph_id, "MIRROR");
+        assert_eq!(find("uniswap_v3").chain, "ethereum");
+        assert_eq!(find("pancakeswap").chain, "bsc");
+        assert_eq!(registry.len(), BUILTIN_PROTOCOLS.len() + 1);
+
+        let dup = vec![
+            SubgraphProtocol {
+                protocol: "camelot".into(),
+                chain: "arbitrum".into(),
+                name: "Camelot".into(),
+                subgraph_id: "X".into(),
+                builtin: false,
+            };
+            2
+        ];
+        assert!(validate_protocols(&dup).is_err());
+        assert!(validate_protocols(&dup[..1]).is_ok());
+    }
+
+    #[test]
+    fn batch_query_lists_lowercased_pool_ids() {
+        let q = SubgraphProvider::build_query(&["0xAB", "0xcd"]);
+        assert!(q.contains(r#"id_in: ["0xab", "0xcd"]"#), "{}", q);
+        assert!(q.contains("pools(first: 2"), "{}", q);
+    }
+}
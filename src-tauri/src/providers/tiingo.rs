@@ -1,15 +1,23 @@
+use super::debug::SendCaptured;
 use super::traits::*;
 use super::types::*;
+use std::collections::HashMap;
+
+const DEFAULT_BASE_URL: &str = "https://api.tiingo.com";
+/// EOD 查詢往回抓的天數（涵蓋週末與連假，確保至少有兩根日線）
+const EOD_LOOKBACK_DAYS: i64 = 10;
 
 pub struct TiingoProvider {
     client: reqwest::Client,
+    base_url: String,
     api_key: Option<String>,
 }
 
 impl TiingoProvider {
-    pub fn new(api_key: Option<String>) -> Self {
+    pub fn new(api_key: Option<String>, api_url: Option<String>, http: &HttpOptions) -> Self {
         Self {
-            client: shared_client(),
+            client: provider_client(http),
+            base_url: resolve_base_url(api_url, DEFAULT_BASE_URL),
             api_key,
         }
     }
@@ -53,6 +61,56 @@ impl TiingoProvider {
             .extra_f64("prev_close", item["prevClose"].as_f64())
             .build())
     }
+
+    /// 解析 `/tiingo/daily/{ticker}/prices` 的日線陣列：最新一根為現價，前一根為前收
+    fn parse_eod(symbol: &str, bars: &serde_json::Value) -> Result<AssetData, String> {
+        let bars = bars
+            .as_array()
+            .filter(|b| !b.is_empty())
+            .ok_or_else(|| format!("Tiingo EOD not found: {}", symbol))?;
+        let last = &bars[bars.len() - 1];
+        let price = last["close"].as_f64().unwrap_or(0.0);
+        let prev = bars
+            .len()
+            .checked_sub(2)
+            .and_then(|i| bars[i]["close"].as_f64());
+        let change = prev.map(|p| price - p);
+        let pct = prev.filter(|p| *p > 0.0).map(|p| (price - p) / p * 100.0);
+
+        Ok(AssetDataBuilder::new(symbol, "tiingo")
+            .price(price)
+            .change_24h(change)
+            .change_percent_24h(pct)
+            .high_24h(last["high"].as_f64())
+            .low_24h(last["low"].as_f64())
+            .volume(last["volume"].as_f64())
+            .extra_f64("open_price", last["open"].as_f64())
+            .extra_f64("prev_close", prev)
+            .extra_str("source", Some("eod"))
+            .extra_str("last_trade_time", last["date"].as_str())
+            .build())
+    }
+
+    async fn fetch_eod(&self, symbol: &str) -> Result<AssetData, String> {
+        let api_key = self.api_key.as_ref().ok_or("Tiingo requires API key")?;
+        let start = (chrono::Utc::now() - chrono::Duration::days(EOD_LOOKBACK_DAYS)).format("%Y-%m-%d");
+        let url = format!(
+            "{}/tiingo/daily/{}/prices?startDate={}&token={}",
+            self.base_url, symbol, start, api_key
+        );
+        let data: serde_json::Value = self
+            .client
+            .get(&url)
+            .send_captured("tiingo")
+            .await
+            .map_err(|e| format!("Tiingo connection failed: {}", e))?
+            .error_for_status()
+            .map_err(|e| format!("Tiingo API error: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("Tiingo parse failed: {}", e))?;
+        Self::parse_eod(symbol, &data)
+    }
 }
 
 #[async_trait::async_trait]
@@ -67,13 +125,14 @@ impl DataProvider for TiingoProvider {
         if Self::is_crypto(symbol) {
             let tiingo_sym = Self::to_tiingo_crypto(symbol);
             let url = format!(
-                "https://api.tiingo.com/tiingo/crypto/top?tickers={}&token={}",
+                "{}/tiingo/crypto/top?tickers={}&token={}",
+                self.base_url,
                 tiingo_sym, api_key
             );
             let data: serde_json::Value = self
                 .client
                 .get(&url)
-                .send()
+                .send_captured("tiingo")
                 .await
                 .map_err(|e| format!("Tiingo connection failed: {}", e))?
                 .error_for_status()
@@ -90,11 +149,11 @@ impl DataProvider for TiingoProvider {
                 .price(top["lastPrice"].as_f64().unwrap_or(0.0))
                 .build())
         } else {
-            let url = format!("https://api.tiingo.com/iex/{}?token={}", symbol, api_key);
+            let url = format!("{}/iex/{}?token={}", self.base_url, symbol, api_key);
             let data: serde_json::Value = self
                 .client
                 .get(&url)
-                .send()
+                .send_captured("tiingo")
                 .await
                 .map_err(|e| format!("Tiingo connection failed: {}", e))?
                 .error_for_status()
@@ -143,10 +202,11 @@ impl DataProvider for TiingoProvider {
                     let key = api_key_owned.clone();
                     async move {
                         let url = format!(
-                            "https://api.tiingo.com/tiingo/crypto/top?tickers={}&token={}",
+                            "{}/tiingo/crypto/top?tickers={}&token={}",
+                            self.base_url,
                             tiingo_sym, key
                         );
-                        match c.get(&url).send().await.and_then(|r| r.error_for_status()) {
+                        match c.get(&url).send_captured("tiingo").await.and_then(|r| r.error_for_status()) {
                             Ok(resp) => match resp.json::<serde_json::Value>().await {
                                 Ok(data) => {
                                     let top = &data[0]["topOfBookData"][0];
@@ -160,12 +220,12 @@ impl DataProvider for TiingoProvider {
                                     )
                                 }
                                 Err(e) => {
-                                    eprintln!("Tiingo crypto skipped {}: {}", original, e);
+                                    tracing::warn!("Tiingo crypto skipped {}: {}", original, e);
                                     None
                                 }
                             },
                             Err(e) => {
-                                eprintln!("Tiingo crypto skipped {}: {}", original, e);
+                                tracing::warn!("Tiingo crypto skipped {}: {}", original, e);
                                 None
                             }
                         }
@@ -181,13 +241,14 @@ impl DataProvider for TiingoProvider {
         if !stock_syms.is_empty() {
             let tickers = stock_syms.join(",");
             let url = format!(
-                "https://api.tiingo.com/iex/?tickers={}&token={}",
+                "{}/iex/?tickers={}&token={}",
+                self.base_url,
                 tickers, api_key
             );
             match self
                 .client
                 .get(&url)
-                .send()
+                .send_captured("tiingo")
                 .await
                 .map_err(|e| format!("Tiingo stock batch failed: {}", e))
             {
@@ -208,16 +269,71 @@ impl DataProvider for TiingoProvider {
                             if let Some(item) = ticker_map.get(&sym.to_uppercase()) {
                                 match Self::parse_stock(sym, item) {
                                     Ok(asset) => results.push(asset),
-                                    Err(e) => eprintln!("Tiingo stock skipped {}: {}", sym, e),
+                                    Err(e) => tracing::warn!("Tiingo stock skipped {}: {}", sym, e),
                                 }
                             }
                         }
                     }
                 }
-                Err(e) => eprintln!("Tiingo stock batch failed: {}", e),
+                Err(e) => tracing::warn!("Tiingo stock batch failed: {}", e),
             }
         }
 
         Ok(results)
     }
+
+    /// `provider_params` 支援 `{"source": "eod"}`：股票改用日線收盤（IEX 未涵蓋的標的或要官方收盤價時），
+    /// 預設 `"iex"` 即時報價
+    async fn fetch_prices_with_params(
+        &self,
+        symbols: &[String],
+        params: &HashMap<String, ProviderParams>,
+    ) -> Result<Vec<AssetData>, String> {
+        let (eod, iex): (Vec<String>, Vec<String>) = symbols.iter().cloned().partition(|s| {
+            !Self::is_crypto(s)
+                && params.get(s).and_then(|p| p.get("source")).and_then(|v| v.as_str()) == Some("eod")
+        });
+        if eod.is_empty() {
+            return self.fetch_prices(symbols).await;
+        }
+
+        let mut results = self.fetch_prices(&iex).await?;
+        use futures::stream::{self, StreamExt};
+        let eod_results: Vec<_> = stream::iter(eod)
+            .map(|sym| async move {
+                let result = self.fetch_eod(&sym).await;
+                (sym, result)
+            })
+            .buffer_unordered(2)
+            .collect()
+            .await;
+        for (sym, result) in eod_results {
+            match result {
+                Ok(data) => results.push(data),
+                Err(e) => tracing::warn!("Tiingo EOD skipped {}: {}", sym, e),
+            }
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn eod_uses_previous_bar_as_prev_close() {
+        let bars = json!([
+            {"date": "2026-10-15T00:00:00.000Z", "close": 100.0},
+            {"date": "2026-10-16T00:00:00.000Z", "close": 110.0, "high": 111.0, "low": 99.0, "volume": 5.0}
+        ]);
+        let d = TiingoProvider::parse_eod("AAPL", &bars).unwrap();
+        assert_eq!(d.price, 110.0);
+        assert_eq!((d.change_24h, d.change_percent_24h), (Some(10.0), Some(10.0)));
+
+        let single = TiingoProvider::parse_eod("AAPL", &json!([{"close": 5.0}])).unwrap();
+        assert_eq!(single.change_24h, None);
+        assert!(TiingoProvider::parse_eod("AAPL", &json!([])).is_err());
+    }
 }
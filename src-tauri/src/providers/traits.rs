@@ -1,6 +1,7 @@
-use std::sync::Arc;
+use std::collections::HashMap;
 
-use super::types::{AssetData, DexPoolInfo, ProviderInfo, WsTickerUpdate};
+use super::types::{AssetData, DexPoolInfo, DexQuote, ProviderInfo, ProviderParams, WsTickerUpdate};
+use crate::db::DbPool;
 
 #[async_trait::async_trait]
 pub trait DataProvider: Send + Sync {
@@ -12,11 +13,29 @@ pub trait DataProvider: Send + Sync {
         for symbol in symbols {
             match self.fetch_price(symbol).await {
                 Ok(data) => results.push(data),
-                Err(e) => eprintln!("Error fetching {}: {}", symbol, e),
+                Err(e) => tracing::warn!("Error fetching {}: {}", symbol, e),
             }
         }
         Ok(results)
     }
+
+    /// 帶訂閱參數的批量查詢（`params` 以 symbol 為 key，只含有設定參數的 symbol）；
+    /// 預設忽略參數。需要逐 symbol 選項的 provider 覆寫此方法，未帶參數的 symbol 應與 `fetch_prices` 相同
+    async fn fetch_prices_with_params(
+        &self,
+        symbols: &[String],
+        _params: &HashMap<String, ProviderParams>,
+    ) -> Result<Vec<AssetData>, String> {
+        self.fetch_prices(symbols).await
+    }
+
+    /// 查詢前以 DB 中的資料準備內部狀態（例如 CoinGecko 的 symbol → ID 對照表）；預設不做事
+    async fn prepare(&self, _db: &DbPool) {}
+
+    /// 檢查 symbol 是否可由此 provider 取得報價（預設實際查詢一次價格）
+    async fn validate_symbol(&self, symbol: &str) -> Result<(), String> {
+        self.fetch_price(symbol).await.map(|_| ())
+    }
 }
 
 /// Trait for DEX providers that can look up pool token info
@@ -25,14 +44,58 @@ pub trait DexPoolLookup: Send + Sync {
     async fn lookup_pool(&self, pool_address: &str) -> Result<DexPoolInfo, String>;
 }
 
-/// Trait for providers that support WebSocket streaming
+/// Trait for DEX aggregators that can quote an arbitrary swap
 #[async_trait::async_trait]
-pub trait WebSocketProvider: Send + Sync {
-    /// Subscribe to real-time updates for given symbols.
-    /// Returns a JoinHandle for the WS connection task so it can be aborted on cleanup.
-    async fn subscribe(
+pub trait DexQuoter: Send + Sync {
+    /// 是否支援此鏈（`solana` / `ethereum` / `bsc` ... 與 OKX DEX symbol 的鏈前綴相同）
+    fn supports_chain(&self, chain: &str) -> bool;
+    /// 以 `amount` 個完整 token_in 詢價；token 可為常見代號或合約 / mint 地址
+    async fn quote(
         &self,
-        symbols: Vec<String>,
-        sender: Arc<tokio::sync::broadcast::Sender<WsTickerUpdate>>,
-    ) -> Result<tokio::task::JoinHandle<()>, String>;
+        chain: &str,
+        token_in: &str,
+        token_out: &str,
+        amount: f64,
+    ) -> Result<DexQuote, String>;
+}
+
+/// stream id（provider 訂閱用的交易對代號）→ 對應的訂閱 symbol（可能多個 symbol 對應同一交易對）
+pub type WsStreamSymbols = HashMap<String, Vec<String>>;
+
+/// Trait for providers that support WebSocket streaming.
+///
+/// 只描述連線 URL、訂閱訊息與推送格式；連線、心跳與重連由 `ws_manager::WsManager` 處理。
+pub trait WebSocketProvider: Send + Sync {
+    fn provider_id(&self) -> &'static str;
+    fn url(&self) -> String;
+    /// symbol 對應的 stream id
+    fn stream_id(&self, symbol: &str) -> String;
+    /// 連線建立後、訂閱前送出的訊息
+    fn connect_messages(&self) -> Vec<String> {
+        Vec::new()
+    }
+    fn subscribe_messages(&self, stream_ids: &[String]) -> Vec<String>;
+    fn unsubscribe_messages(&self, stream_ids: &[String]) -> Vec<String>;
+    /// 解析推送訊息；非 ticker 訊息回傳空陣列
+    fn parse_message(&self, streams: &WsStreamSymbols, msg: &serde_json::Value) -> Vec<WsTickerUpdate>;
+    /// 伺服器回報的錯誤（訂閱失敗等），僅記錄於 log
+    fn error_message(&self, _msg: &serde_json::Value) -> Option<String> {
+        None
+    }
+    /// 應用層 ping 訊息與間隔秒數
+    fn ping(&self) -> Option<(&'static str, u64)> {
+        None
+    }
+    /// 單一連線可訂閱的 stream 上限；None = 交易所未限制
+    fn max_streams(&self) -> Option<usize> {
+        None
+    }
+    /// 依 stream id 分組 symbol
+    fn stream_symbols(&self, symbols: &[String]) -> WsStreamSymbols {
+        let mut streams = WsStreamSymbols::new();
+        for symbol in symbols {
+            streams.entry(self.stream_id(symbol)).or_default().push(symbol.clone());
+        }
+        streams
+    }
 }
@@ -1,15 +1,20 @@
+use super::debug::SendCaptured;
 use super::traits::*;
 use super::types::*;
 
+const DEFAULT_BASE_URL: &str = "https://api.twelvedata.com";
+
 pub struct TwelveDataProvider {
     client: reqwest::Client,
+    base_url: String,
     api_key: Option<String>,
 }
 
 impl TwelveDataProvider {
-    pub fn new(api_key: Option<String>) -> Self {
+    pub fn new(api_key: Option<String>, api_url: Option<String>, http: &HttpOptions) -> Self {
         Self {
-            client: shared_client(),
+            client: provider_client(http),
+            base_url: resolve_base_url(api_url, DEFAULT_BASE_URL),
             api_key,
         }
     }
@@ -84,10 +89,11 @@ impl DataProvider for TwelveDataProvider {
         let data: serde_json::Value = self
             .client
             .get(format!(
-                "https://api.twelvedata.com/quote?symbol={}&prepost=true&apikey={}",
+                "{}/quote?symbol={}&prepost=true&apikey={}",
+                self.base_url,
                 api_symbol, api_key
             ))
-            .send()
+            .send_captured("twelvedata")
             .await
             .map_err(|e| format!("TwelveData connection failed: {}", e))?
             .error_for_status()
@@ -119,10 +125,11 @@ impl DataProvider for TwelveDataProvider {
         let resp = self
             .client
             .get(format!(
-                "https://api.twelvedata.com/quote?symbol={}&prepost=true&apikey={}",
+                "{}/quote?symbol={}&prepost=true&apikey={}",
+                self.base_url,
                 syms_str, api_key
             ))
-            .send()
+            .send_captured("twelvedata")
             .await
             .map_err(|e| format!("TwelveData batch connection failed: {}", e))?
             .error_for_status()
@@ -144,14 +151,14 @@ impl DataProvider for TwelveDataProvider {
                 if !item.is_null() {
                     match Self::parse_quote(original, item) {
                         Ok(asset) => results.push(asset),
-                        Err(e) => eprintln!("TwelveData batch skipped {}: {}", original, e),
+                        Err(e) => tracing::warn!("TwelveData batch skipped {}: {}", original, e),
                     }
                 }
             }
         } else {
             match Self::parse_quote(&mappings[0].0, &data) {
                 Ok(asset) => results.push(asset),
-                Err(e) => eprintln!("TwelveData skipped {}: {}", mappings[0].0, e),
+                Err(e) => tracing::warn!("TwelveData skipped {}: {}", mappings[0].0, e),
             }
         }
         Ok(results)
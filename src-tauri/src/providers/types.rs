@@ -1,9 +1,15 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::sync::{LazyLock, OnceLock};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{LazyLock, Mutex, RwLock};
 
-/// Global shared reqwest::Client — 所有 provider 共用同一個連接池
-static SHARED_CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+use crate::db::ProviderSettingsRow;
+
+/// 共用 reqwest::Client 池 — 相同連線設定（proxy）的 provider 共用同一個連接池
+static CLIENT_POOL: LazyLock<Mutex<HashMap<HttpOptions, reqwest::Client>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 全域 HTTP proxy（app_settings `http_proxy`）；provider 未設定 proxy 時使用
+static GLOBAL_PROXY: RwLock<Option<String>> = RwLock::new(None);
 
 /// Cached provider info list — 避免每次 info() 都重新分配
 static PROVIDER_INFO_CACHE: LazyLock<Vec<ProviderInfo>> = LazyLock::new(build_all_provider_info);
@@ -30,6 +36,102 @@ pub struct AssetData {
     pub last_updated: i64,
     pub provider_id: String,
     pub extra: Option<HashMap<String, serde_json::Value>>,
+    /// 盤前報價（僅美股等有延長交易時段的 provider 提供）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_market: Option<SessionQuote>,
+    /// 盤後報價
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_market: Option<SessionQuote>,
+    /// 價格的顯示小數位數（provider 已知 tick size 時提供，例如 Binance exchangeInfo）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price_scale: Option<u32>,
+    /// DEX pool 的流動性（TVL，USD）；DEX 訂閱開啟紀錄時寫入 price_history
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub liquidity_usd: Option<f64>,
+    /// 完全稀釋市值（USD）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fdv: Option<f64>,
+}
+
+impl AssetData {
+    /// 顯示價格時使用的小數位數：provider 提供的 `price_scale`，否則依價格量級推算
+    pub fn display_scale(&self) -> u32 {
+        self.price_scale.unwrap_or_else(|| auto_price_scale(self.price))
+    }
+
+    /// 寫入 price_history 的一筆紀錄（盤前 / 盤後價寫入各自的欄位）
+    pub fn price_record(&self) -> crate::db::PriceRecord {
+        let (open_interest, long_short_ratio) = super::derivatives::record_fields(self);
+        (
+            self.symbol.clone(),
+            self.price,
+            self.change_percent_24h,
+            self.volume,
+            self.pre_market.map(|q| q.price),
+            self.post_market.map(|q| q.price),
+            self.liquidity_usd,
+            self.fdv,
+            open_interest,
+            long_short_ratio,
+            self.currency.clone(),
+        )
+    }
+}
+
+/// 顯示小數位數的上限
+pub const MAX_PRICE_SCALE: u32 = 10;
+
+/// 依價格量級推算顯示小數位數：≥ 1 取兩位，否則保留四位有效數字（例如 0.00002345 → 8）
+pub fn auto_price_scale(price: f64) -> u32 {
+    let abs = price.abs();
+    if !abs.is_finite() || abs == 0.0 || abs >= 1.0 {
+        return 2;
+    }
+    // 小數點後的前導零個數
+    let leading_zeros = ((-abs.log10()).ceil() as u32).saturating_sub(1);
+    (leading_zeros + 4).min(MAX_PRICE_SCALE)
+}
+
+/// 由 tick size 字串（例如 Binance 的 `"0.00001000"`）換算小數位數
+pub fn scale_from_tick_size(tick: &str) -> Option<u32> {
+    let value: f64 = tick.parse().ok()?;
+    if value <= 0.0 {
+        return None;
+    }
+    let decimals = match tick.split_once('.') {
+        Some((_, frac)) => frac.trim_end_matches('0').len() as u32,
+        None => 0,
+    };
+    Some(decimals.min(MAX_PRICE_SCALE))
+}
+
+/// 盤前 / 盤後時段的報價
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SessionQuote {
+    pub price: f64,
+    pub change: Option<f64>,
+    pub change_percent: Option<f64>,
+}
+
+impl SessionQuote {
+    /// 以參考價（正常盤價或前收）計算漲跌；參考價未知時只有價格
+    pub fn against(price: f64, reference: Option<f64>) -> Self {
+        Self {
+            price,
+            change: reference.map(|r| price - r),
+            change_percent: reference
+                .filter(|r| *r > 0.0)
+                .map(|r| (price - r) / r * 100.0),
+        }
+    }
+
+    /// 以 provider 直接提供的漲跌幅取代計算值（`None` 時保留計算值）
+    pub fn with_change_percent(mut self, pct: Option<f64>) -> Self {
+        if pct.is_some() {
+            self.change_percent = pct;
+        }
+        self
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -48,22 +150,172 @@ pub struct ProviderInfo {
     pub free_interval: i64,
     /// Default refresh interval (ms) when using API key mode
     pub key_interval: i64,
+    pub capabilities: ProviderCapabilities,
 }
 
-/// Shared HTTP client — 全局單例，所有 provider 共用同一個連接池和 TCP 連接
-pub fn shared_client() -> reqwest::Client {
-    SHARED_CLIENT
-        .get_or_init(|| {
-            reqwest::Client::builder()
-                .timeout(std::time::Duration::from_secs(15))
-                .user_agent("StockenBoard/1.0")
-                .pool_max_idle_per_host(10)
-                .build()
-                .unwrap_or_default()
+/// Provider 的結構化能力旗標 — 前端與 polling 排程依此判斷，不必寫死 provider id
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProviderCapabilities {
+    /// 單一請求可查詢多個 symbol（否則 `fetch_prices` 為逐一 / 並行查詢）
+    pub supports_batch: bool,
+    /// 單一批量請求的 symbol 上限；None = 無上限。polling 超過上限時自動分批
+    pub max_batch_size: Option<usize>,
+    /// 單一 WebSocket 連線可訂閱的 stream 上限（`WebSocketProvider::max_streams`，`WsManager` 依此拒絕超量訂閱）；
+    /// None = 無上限或不支援 WebSocket
+    pub websocket_symbol_limit: Option<usize>,
+    /// 可查詢的資產類別：crypto / stock / dex / prediction
+    pub asset_classes: Vec<String>,
+}
+
+/// 預設請求逾時
+const DEFAULT_TIMEOUT_MS: u64 = 15_000;
+
+/// Provider 的 HTTP 連線設定（來自 provider_settings）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct HttpOptions {
+    /// 代理伺服器 URL（`http://`、`https://`、`socks5://`）；None = 使用全域 proxy
+    pub proxy: Option<String>,
+    /// 請求逾時（毫秒）；None = 15 秒
+    pub timeout_ms: Option<u64>,
+    /// 每個請求附加的自訂 header（自架 gateway、額外認證 header 等）
+    pub headers: BTreeMap<String, String>,
+}
+
+impl From<&ProviderSettingsRow> for HttpOptions {
+    fn from(row: &ProviderSettingsRow) -> Self {
+        Self {
+            proxy: row.proxy_url.clone().filter(|p| !p.is_empty()),
+            timeout_ms: row.timeout_ms.filter(|t| *t > 0).map(|t| t as u64),
+            headers: row
+                .extra_headers
+                .as_deref()
+                .and_then(|h| parse_extra_headers(h).ok())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// 解析 provider_settings.extra_headers（JSON 物件，值必須為字串）並驗證 header 名稱與值
+pub fn parse_extra_headers(json: &str) -> Result<BTreeMap<String, String>, String> {
+    if json.trim().is_empty() {
+        return Ok(BTreeMap::new());
+    }
+    let headers: BTreeMap<String, String> = serde_json::from_str(json)
+        .map_err(|e| format!("extra_headers must be a JSON object of strings: {}", e))?;
+    for (name, value) in &headers {
+        reqwest::header::HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| format!("Invalid header name: {}", name))?;
+        reqwest::header::HeaderValue::from_str(value)
+            .map_err(|_| format!("Invalid value for header {}", name))?;
+    }
+    Ok(headers)
+}
+
+/// 單一訂閱的 provider 專屬參數（subscriptions.provider_params），例如 `{"adjusted": false}`
+pub type ProviderParams = serde_json::Map<String, serde_json::Value>;
+
+/// 解析 subscriptions.provider_params（JSON 物件；空字串視為無參數）
+pub fn parse_provider_params(json: &str) -> Result<ProviderParams, String> {
+    if json.trim().is_empty() {
+        return Ok(ProviderParams::new());
+    }
+    serde_json::from_str(json).map_err(|e| format!("provider_params must be a JSON object: {}", e))
+}
+
+/// 設定全域 HTTP proxy，並丟棄已建立的 client（下次取得時以新設定重建）
+pub fn set_global_proxy(proxy: Option<String>) {
+    *GLOBAL_PROXY.write().unwrap() = proxy.filter(|p| !p.is_empty());
+    CLIENT_POOL.lock().unwrap().clear();
+}
+
+/// 目前的全域 HTTP proxy
+pub fn global_proxy() -> Option<String> {
+    GLOBAL_PROXY.read().unwrap().clone()
+}
+
+impl HttpOptions {
+    /// 實際使用的 proxy：provider 設定優先，否則為全域 proxy
+    pub fn effective_proxy(&self) -> Option<String> {
+        self.proxy.clone().or_else(global_proxy)
+    }
+
+    /// 與全域設定合併為實際生效的連線設定
+    fn effective(&self) -> HttpOptions {
+        HttpOptions {
+            proxy: self.effective_proxy(),
+            ..self.clone()
+        }
+    }
+}
+
+/// 套用連線設定（proxy、逾時、自訂 header）到 client builder；
+/// 供需要自訂 client 的 provider（如 Yahoo）使用
+pub fn configure_client(builder: reqwest::ClientBuilder, options: &HttpOptions) -> reqwest::ClientBuilder {
+    apply_options(builder, &options.effective())
+}
+
+fn apply_options(builder: reqwest::ClientBuilder, options: &HttpOptions) -> reqwest::ClientBuilder {
+    let mut builder = builder.timeout(std::time::Duration::from_millis(
+        options.timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS),
+    ));
+    if !options.headers.is_empty() {
+        let mut headers = reqwest::header::HeaderMap::new();
+        for (name, value) in &options.headers {
+            if let (Ok(name), Ok(value)) = (
+                reqwest::header::HeaderName::from_bytes(name.as_bytes()),
+                reqwest::header::HeaderValue::from_str(value),
+            ) {
+                headers.insert(name, value);
+            }
+        }
+        builder = builder.default_headers(headers);
+    }
+    match options.proxy.as_deref().map(reqwest::Proxy::all) {
+        Some(Ok(proxy)) => builder.proxy(proxy),
+        Some(Err(e)) => {
+            tracing::warn!("[HTTP] Ignoring invalid proxy URL: {}", e);
+            builder
+        }
+        None => builder,
+    }
+}
+
+fn pooled_client(options: HttpOptions) -> reqwest::Client {
+    let mut pool = CLIENT_POOL.lock().unwrap();
+    pool.entry(options)
+        .or_insert_with_key(|options| {
+            apply_options(
+                reqwest::Client::builder()
+                    .user_agent("StockenBoard/1.0")
+                    .pool_max_idle_per_host(10),
+                options,
+            )
+            .build()
+            .unwrap_or_default()
         })
         .clone()
 }
 
+/// 解析 provider 的 base URL：有設定 `api_url`（鏡像站、反向代理、自架相容 API）則使用，
+/// 否則回傳官方端點；結尾的 `/` 會被去除以便直接接上路徑
+pub fn resolve_base_url(api_url: Option<String>, default: &str) -> String {
+    api_url
+        .map(|u| u.trim().trim_end_matches('/').to_string())
+        .filter(|u| !u.is_empty())
+        .unwrap_or_else(|| default.to_string())
+}
+
+/// Shared HTTP client — 所有未指定 provider 的請求共用（套用全域 proxy）
+pub fn shared_client() -> reqwest::Client {
+    pooled_client(HttpOptions::default().effective())
+}
+
+/// Provider 專用 HTTP client — 套用該 provider 的 proxy（未設定則用全域 proxy）、
+/// 逾時與自訂 header，相同設定的 provider 共用連接池
+pub fn provider_client(options: &HttpOptions) -> reqwest::Client {
+    pooled_client(options.effective())
+}
+
 /// Helper to build AssetData with defaults
 pub struct AssetDataBuilder {
     data: AssetData,
@@ -86,6 +338,11 @@ impl AssetDataBuilder {
                 last_updated: chrono::Utc::now().timestamp_millis(),
                 provider_id: provider_id.to_string(),
                 extra: None,
+                pre_market: None,
+                post_market: None,
+                price_scale: None,
+                liquidity_usd: None,
+                fdv: None,
             },
             extra: HashMap::new(),
         }
@@ -123,6 +380,18 @@ impl AssetDataBuilder {
         self.data.market_cap = v;
         self
     }
+    pub fn liquidity_usd(mut self, v: Option<f64>) -> Self {
+        self.data.liquidity_usd = v;
+        self
+    }
+    pub fn fdv(mut self, v: Option<f64>) -> Self {
+        self.data.fdv = v;
+        self
+    }
+    pub fn price_scale(mut self, v: Option<u32>) -> Self {
+        self.data.price_scale = v;
+        self
+    }
 
     pub fn extra_f64(mut self, key: &str, val: Option<f64>) -> Self {
         if let Some(v) = val {
@@ -143,6 +412,28 @@ impl AssetDataBuilder {
         self
     }
 
+    /// 盤前報價；同時寫入 extra 的 `pre_market_*` 鍵以相容既有的顯示欄位
+    pub fn pre_market(mut self, quote: Option<SessionQuote>) -> Self {
+        if let Some(q) = quote {
+            self.data.pre_market = Some(q);
+            self = self.session_extra("pre_market", q);
+        }
+        self
+    }
+    /// 盤後報價；同時寫入 extra 的 `post_market_*` 鍵
+    pub fn post_market(mut self, quote: Option<SessionQuote>) -> Self {
+        if let Some(q) = quote {
+            self.data.post_market = Some(q);
+            self = self.session_extra("post_market", q);
+        }
+        self
+    }
+    fn session_extra(self, prefix: &str, q: SessionQuote) -> Self {
+        self.extra_f64(&format!("{}_price", prefix), Some(q.price))
+            .extra_f64(&format!("{}_change", prefix), q.change)
+            .extra_f64(&format!("{}_change_pct", prefix), q.change_percent)
+    }
+
     pub fn build(mut self) -> AssetData {
         self.data.extra = if self.extra.is_empty() {
             None
@@ -162,6 +453,22 @@ pub struct DexPoolInfo {
     pub token1_symbol: String,
 }
 
+/// 單一 DEX 聚合器對一筆 swap 的詢價結果（`compare_dex_quotes` 使用）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DexQuote {
+    pub provider_id: String,
+    /// 可換得的 token_out 數量（已依 decimals 換算）
+    pub amount_out: f64,
+    /// 成交均價（token_out / token_in）
+    pub price: f64,
+    /// 價格影響（%），provider 未回報時為 None
+    pub price_impact: Option<f64>,
+    /// 預估 gas（provider 回報的原始描述）
+    pub gas_estimate: Option<String>,
+    /// 路由路徑（經過的 DEX）
+    pub route: Option<String>,
+}
+
 /// WebSocket message types for real-time data
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WsTickerUpdate {
@@ -172,13 +479,32 @@ pub struct WsTickerUpdate {
 
 // All Provider static info
 // free_interval = 免費版默認刷新間隔(ms), key_interval = 有API Key時默認刷新間隔(ms)
+// `mock` / `replay` 只在對應模式開啟時列出；簽章 metadata 的覆寫會合併在預設值之上
 pub fn get_all_provider_info() -> Vec<ProviderInfo> {
-    PROVIDER_INFO_CACHE.clone()
+    let mock = super::mock::mock_config().enabled;
+    let replay = super::replay::replay_config().enabled;
+    PROVIDER_INFO_CACHE
+        .iter()
+        .filter(|p| match p.id.as_str() {
+            "mock" => mock,
+            "replay" => replay,
+            _ => true,
+        })
+        .map(|p| {
+            let mut info = p.clone();
+            super::metadata::apply(&mut info);
+            info
+        })
+        .collect()
 }
 
 /// O(1) 查找單個 provider info — 各 provider module 透過 `use super::types::*` 使用
 pub fn get_provider_info(id: &str) -> Option<ProviderInfo> {
-    PROVIDER_INFO_MAP.get(id).cloned()
+    PROVIDER_INFO_MAP.get(id).map(|p| {
+        let mut info = p.clone();
+        super::metadata::apply(&mut info);
+        info
+    })
 }
 
 /// 取得已註冊 provider 的靜態 info；供 `DataProvider::info()` 使用。
@@ -302,7 +628,7 @@ fn build_all_provider_info() -> Vec<ProviderInfo> {
             false,
             false,
             "Free 20 calls/day; paid unlimited",
-            "AAPL.US, TSLA.US",
+            "AAPL, AAPL.US, VOD.LSE",
             &["price", "change_24h", "high_24h", "low_24h", "volume"],
             300000,
             30000,
@@ -428,7 +754,7 @@ fn build_all_provider_info() -> Vec<ProviderInfo> {
             false,
             true,
             "Free unlimited reads",
-            "condition_id",
+            "slug|condition_id[:outcome]",
             &["price", "volume"],
             5000,
             5000,
@@ -441,11 +767,38 @@ fn build_all_provider_info() -> Vec<ProviderInfo> {
             false,
             false,
             "Free tier (OAuth token)",
-            "contract_address",
+            "[network:]address[:quote]",
             &["price", "volume"],
             30000,
             15000,
         ),
+        // Demo
+        pi(
+            "mock",
+            "Demo (Mock)",
+            "both",
+            false,
+            false,
+            false,
+            "Offline random-walk prices, demo mode only",
+            "Any symbol",
+            &["price", "change_24h", "high_24h", "low_24h", "volume"],
+            2000,
+            2000,
+        ),
+        pi(
+            "replay",
+            "History Replay",
+            "both",
+            false,
+            false,
+            false,
+            "Replays recorded price history",
+            "Any recorded symbol",
+            &["price", "change_24h", "volume"],
+            2000,
+            2000,
+        ),
         // New Crypto Exchanges
         pi(
             "kraken",
@@ -453,7 +806,7 @@ fn build_all_provider_info() -> Vec<ProviderInfo> {
             "crypto",
             false,
             false,
-            false,
+            true,
             "Free unlimited (public API)",
             "XBTUSD, ETHUSD",
             &["price", "change_24h", "high_24h", "low_24h", "volume"],
@@ -466,7 +819,7 @@ fn build_all_provider_info() -> Vec<ProviderInfo> {
             "crypto",
             false,
             false,
-            false,
+            true,
             "Free 120 req/s (public API)",
             "BTCUSDT, ETHUSDT",
             &["price", "change_24h", "high_24h", "low_24h", "volume"],
@@ -492,7 +845,7 @@ fn build_all_provider_info() -> Vec<ProviderInfo> {
             "crypto",
             false,
             false,
-            false,
+            true,
             "Free 20 req/2s (public API)",
             "BTC-USDT, ETH-USDT",
             &["price", "change_24h", "high_24h", "low_24h", "volume"],
@@ -757,5 +1110,31 @@ fn pi(
         supported_fields: fields.iter().map(|s| s.to_string()).collect(),
         free_interval: free_iv,
         key_interval: key_iv,
+        capabilities: capabilities(id, ptype),
+    }
+}
+
+fn capabilities(id: &str, ptype: &str) -> ProviderCapabilities {
+    let (supports_batch, max_batch_size) = match id {
+        // 沒有批量 endpoint，逐一 / 並行查詢
+        "coinbase" | "coinpaprika" | "finnhub" | "alphavantage" | "okx_dex" | "polymarket" => {
+            (false, None)
+        }
+        "eodhd" => (true, Some(15)),
+        "marketstack" => (true, Some(100)),
+        "twelvedata" => (true, Some(120)),
+        _ => (true, None),
+    };
+    let asset_classes = match ptype {
+        "both" => vec!["crypto", "stock"],
+        // DEX 報價也可作為 crypto 的價格來源
+        "dex" => vec!["crypto", "dex"],
+        other => vec![other],
+    };
+    ProviderCapabilities {
+        supports_batch,
+        max_batch_size,
+        websocket_symbol_limit: super::create_ws_provider(id).and_then(|p| p.max_streams()),
+        asset_classes: asset_classes.iter().map(|s| s.to_string()).collect(),
     }
 }
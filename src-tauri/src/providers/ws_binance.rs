@@ -1,14 +1,12 @@
 use super::traits::*;
 use super::types::*;
-use futures::{SinkExt, StreamExt};
-use std::sync::Arc;
-use tokio_tungstenite::{connect_async, tungstenite::Message};
 
 /// Binance WebSocket streaming for real-time ticker data
 pub struct BinanceWsProvider;
 
-const MAX_RECONNECT_ATTEMPTS: u32 = 10;
-const INITIAL_RECONNECT_DELAY_MS: u64 = 1000;
+const WS_URL: &str = "wss://stream.binance.com:9443/stream";
+/// combined stream 單一連線最多可訂閱的 stream 數
+pub const MAX_STREAMS: usize = 1024;
 
 impl Default for BinanceWsProvider {
     fn default() -> Self {
@@ -21,138 +19,132 @@ impl BinanceWsProvider {
         Self
     }
 
-    /// 解析 miniTicker WS 訊息為 WsTickerUpdate
-    fn parse_mini_ticker(d: &serde_json::Value) -> Option<WsTickerUpdate> {
+    fn method_message(method: &str, stream_ids: &[String]) -> String {
+        let params: Vec<String> = stream_ids.iter().map(|id| format!("{}@miniTicker", id)).collect();
+        serde_json::json!({ "method": method, "params": params, "id": 1 }).to_string()
+    }
+
+    /// 解析 miniTicker WS 訊息為 AssetData
+    fn parse_mini_ticker(symbol: &str, d: &serde_json::Value) -> Option<AssetData> {
         if d.is_null() {
             return None;
         }
-        let symbol = d["s"].as_str()?.to_string();
+        let stream_symbol = d["s"].as_str()?;
         let parse_f64 = |key: &str| d[key].as_str().and_then(|s| s.parse::<f64>().ok());
-
-        let asset = AssetDataBuilder::new(&symbol, "binance")
-            .price(parse_f64("c").unwrap_or(0.0))
-            .currency("USDT")
-            .high_24h(parse_f64("h"))
-            .low_24h(parse_f64("l"))
-            .volume(parse_f64("v"))
-            .extra_f64("open_price", parse_f64("o"))
-            .build();
-
-        Some(WsTickerUpdate {
-            symbol,
-            provider_id: "binance".to_string(),
-            data: asset,
-        })
+        let price = parse_f64("c").unwrap_or(0.0);
+        let open = parse_f64("o");
+        // miniTicker 沒有漲跌欄位，以 24h 開盤價推算，與 REST ticker 的 priceChange 一致
+        let change = open.filter(|o| *o > 0.0).map(|o| price - o);
+        let change_pct = open
+            .filter(|o| *o > 0.0)
+            .map(|o| (price - o) / o * 100.0);
+        // 報價幣別取自 stream symbol（BTCEUR → EUR、ETHBTC → BTC）
+        let (_, quote) = parse_crypto_symbol(stream_symbol);
+
+        Some(
+            AssetDataBuilder::new(symbol, "binance")
+                .price(price)
+                .currency(&quote)
+                .change_24h(change)
+                .change_percent_24h(change_pct)
+                .high_24h(parse_f64("h"))
+                .low_24h(parse_f64("l"))
+                .volume(parse_f64("v"))
+                .extra_f64("open_price", open)
+                .build(),
+        )
     }
 }
 
-#[async_trait::async_trait]
 impl WebSocketProvider for BinanceWsProvider {
-    async fn subscribe(
-        &self,
-        symbols: Vec<String>,
-        sender: Arc<tokio::sync::broadcast::Sender<WsTickerUpdate>>,
-    ) -> Result<tokio::task::JoinHandle<()>, String> {
-        if symbols.is_empty() {
-            // 返回一個立即完成的 task
-            return Ok(tokio::spawn(async {}));
-        }
+    fn provider_id(&self) -> &'static str {
+        "binance"
+    }
 
-        let streams: Vec<String> = symbols
-            .iter()
-            .map(|s| format!("{}@miniTicker", s.to_lowercase()))
-            .collect();
-        let url = format!(
-            "wss://stream.binance.com:9443/stream?streams={}",
-            streams.join("/")
-        );
+    fn url(&self) -> String {
+        WS_URL.to_string()
+    }
 
-        let (ws_stream, _) = connect_async(&url)
-            .await
-            .map_err(|e| format!("Binance WS connection failed: {}", e))?;
+    fn stream_id(&self, symbol: &str) -> String {
+        symbol.to_lowercase()
+    }
 
-        let (write, read) = ws_stream.split();
+    fn max_streams(&self) -> Option<usize> {
+        Some(MAX_STREAMS)
+    }
 
-        let handle = tokio::spawn(Self::run_ws_loop(url, symbols, sender, write, read));
+    fn subscribe_messages(&self, stream_ids: &[String]) -> Vec<String> {
+        vec![Self::method_message("SUBSCRIBE", stream_ids)]
+    }
 
-        Ok(handle)
+    fn unsubscribe_messages(&self, stream_ids: &[String]) -> Vec<String> {
+        vec![Self::method_message("UNSUBSCRIBE", stream_ids)]
+    }
+
+    /// combined stream 推送為 `{"stream": ..., "data": miniTicker}`
+    fn parse_message(&self, streams: &WsStreamSymbols, msg: &serde_json::Value) -> Vec<WsTickerUpdate> {
+        let data = &msg["data"];
+        let Some(symbols) = data["s"].as_str().and_then(|s| streams.get(&s.to_lowercase())) else {
+            return Vec::new();
+        };
+        symbols
+            .iter()
+            .filter_map(|symbol| {
+                Some(WsTickerUpdate {
+                    symbol: symbol.clone(),
+                    provider_id: "binance".to_string(),
+                    data: Self::parse_mini_ticker(symbol, data)?,
+                })
+            })
+            .collect()
+    }
+
+    fn error_message(&self, msg: &serde_json::Value) -> Option<String> {
+        msg.get("error").map(|e| e.to_string())
     }
 }
 
-impl BinanceWsProvider {
-    async fn run_ws_loop(
-        url: String,
-        symbols: Vec<String>,
-        sender: Arc<tokio::sync::broadcast::Sender<WsTickerUpdate>>,
-        mut write: futures::stream::SplitSink<
-            tokio_tungstenite::WebSocketStream<
-                tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
-            >,
-            Message,
-        >,
-        mut read: futures::stream::SplitStream<
-            tokio_tungstenite::WebSocketStream<
-                tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
-            >,
-        >,
-    ) {
-        loop {
-            match read.next().await {
-                Some(Ok(Message::Text(text))) => {
-                    if let Ok(data) = serde_json::from_str::<serde_json::Value>(text.as_ref()) {
-                        if let Some(update) = Self::parse_mini_ticker(&data["data"]) {
-                            let _ = sender.send(update);
-                        }
-                    }
-                }
-                Some(Ok(Message::Ping(payload))) => {
-                    if let Err(e) = write.send(Message::Pong(payload)).await {
-                        eprintln!("Binance WS pong send failed: {}", e);
-                        break;
-                    }
-                }
-                Some(Ok(Message::Close(_))) => {
-                    eprintln!("Binance WS connection closed, reconnecting...");
-                    break;
-                }
-                Some(Err(e)) => {
-                    eprintln!("Binance WS error: {}, reconnecting...", e);
-                    break;
-                }
-                None => {
-                    eprintln!("Binance WS stream ended, reconnecting...");
-                    break;
-                }
-                _ => {}
-            }
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn mini_ticker_uses_stream_quote_and_derives_change() {
+        let d = BinanceWsProvider::parse_mini_ticker(
+            "ETHBTC",
+            &json!({
+                "s": "ETHBTC", "c": "0.055", "o": "0.050", "h": "0.056", "l": "0.049", "v": "1000"
+            }),
+        )
+        .unwrap();
+        assert_eq!(d.currency, "BTC");
+        assert!((d.change_24h.unwrap() - 0.005).abs() < 1e-12);
+        assert!((d.change_percent_24h.unwrap() - 10.0).abs() < 1e-9);
+
+        let eur = BinanceWsProvider::parse_mini_ticker("BTCEUR", &json!({"s": "BTCEUR", "c": "1", "o": "0"})).unwrap();
+        assert_eq!(eur.currency, "EUR");
+        assert_eq!(eur.change_24h, None);
+    }
 
-        // 自動重連（指數退避）
-        let mut attempt = 0u32;
-        loop {
-            if attempt >= MAX_RECONNECT_ATTEMPTS {
-                eprintln!(
-                    "Binance WS reconnect attempts exhausted ({})",
-                    MAX_RECONNECT_ATTEMPTS
-                );
-                break;
-            }
-            let delay = INITIAL_RECONNECT_DELAY_MS * 2u64.pow(attempt.min(6));
-            eprintln!("Binance WS reconnect attempt {}, waiting {}ms...", attempt + 1, delay);
-            tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
-
-            match connect_async(&url).await {
-                Ok((new_ws, _)) => {
-                    eprintln!("Binance WS reconnected successfully");
-                    let (new_write, new_read) = new_ws.split();
-                    Box::pin(Self::run_ws_loop(url, symbols, sender, new_write, new_read)).await;
-                    return;
-                }
-                Err(e) => {
-                    eprintln!("Binance WS reconnect failed: {}", e);
-                    attempt += 1;
-                }
-            }
-        }
+    #[test]
+    fn combined_stream_updates_use_subscribed_symbols() {
+        let provider = BinanceWsProvider::new();
+        let msg: serde_json::Value =
+            serde_json::from_str(&provider.subscribe_messages(&["btcusdt".to_string()])[0]).unwrap();
+        assert_eq!((msg["method"].as_str(), &msg["params"]), (Some("SUBSCRIBE"), &json!(["btcusdt@miniTicker"])));
+
+        let streams = WsStreamSymbols::from([("btcusdt".to_string(), vec!["BTCUSDT".to_string(), "btcusdt".to_string()])]);
+        let updates = provider.parse_message(
+            &streams,
+            &json!({ "stream": "btcusdt@miniTicker", "data": { "s": "BTCUSDT", "c": "110", "o": "100" } }),
+        );
+        assert_eq!(updates.iter().map(|u| u.symbol.as_str()).collect::<Vec<_>>(), vec!["BTCUSDT", "btcusdt"]);
+        assert_eq!((updates[1].data.symbol.as_str(), updates[1].data.price), ("btcusdt", 110.0));
+        // 訂閱回應與未訂閱的交易對都忽略
+        assert!(provider.parse_message(&streams, &json!({"result": null, "id": 1})).is_empty());
+        assert!(provider
+            .parse_message(&streams, &json!({"data": {"s": "ETHUSDT", "c": "1"}}))
+            .is_empty());
     }
 }
@@ -0,0 +1,147 @@
+use super::simple_exchange::{ExchangeSpec, EXCHANGES};
+use super::traits::*;
+use super::types::*;
+
+/// Bybit WebSocket streaming for real-time spot ticker data
+pub struct BybitWsProvider;
+
+const WS_URL: &str = "wss://stream.bybit.com/v5/public/spot";
+/// Bybit 要求每 20 秒送一次 ping，否則會關閉連線
+const PING_INTERVAL_SECS: u64 = 20;
+/// 現貨 subscribe 每次最多 10 個 topic
+const MAX_ARGS_PER_REQUEST: usize = 10;
+
+impl Default for BybitWsProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BybitWsProvider {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// 與 REST provider 共用交易對格式與 ticker 欄位對照
+    fn spec() -> &'static ExchangeSpec {
+        EXCHANGES
+            .iter()
+            .find(|s| s.id == "bybit")
+            .expect("bybit exchange spec")
+    }
+
+    /// `tickers.{pair}` 訂閱 / 取消訂閱訊息，每則最多 [`MAX_ARGS_PER_REQUEST`] 個 topic
+    fn op_messages(op: &str, pairs: &[String]) -> Vec<String> {
+        let topics: Vec<String> = pairs.iter().map(|p| format!("tickers.{}", p)).collect();
+        topics
+            .chunks(MAX_ARGS_PER_REQUEST)
+            .map(|args| serde_json::json!({ "op": op, "args": args }).to_string())
+            .collect()
+    }
+}
+
+impl WebSocketProvider for BybitWsProvider {
+    fn provider_id(&self) -> &'static str {
+        "bybit"
+    }
+
+    fn url(&self) -> String {
+        WS_URL.to_string()
+    }
+
+    fn stream_id(&self, symbol: &str) -> String {
+        Self::spec().to_pair(symbol).0
+    }
+
+    fn subscribe_messages(&self, stream_ids: &[String]) -> Vec<String> {
+        Self::op_messages("subscribe", stream_ids)
+    }
+
+    fn unsubscribe_messages(&self, stream_ids: &[String]) -> Vec<String> {
+        Self::op_messages("unsubscribe", stream_ids)
+    }
+
+    /// 解析 tickers 推送為 WsTickerUpdate（同一交易對可能對應多個訂閱 symbol）；其他訊息回傳空陣列
+    fn parse_message(&self, streams: &WsStreamSymbols, msg: &serde_json::Value) -> Vec<WsTickerUpdate> {
+        if !msg["topic"].as_str().is_some_and(|t| t.starts_with("tickers.")) {
+            return Vec::new();
+        }
+        let data = &msg["data"];
+        let Some(symbols) = data["symbol"].as_str().and_then(|pair| streams.get(pair)) else {
+            return Vec::new();
+        };
+        let spec = Self::spec();
+        symbols
+            .iter()
+            .map(|symbol| {
+                let (_, quote) = spec.to_pair(symbol);
+                WsTickerUpdate {
+                    symbol: symbol.clone(),
+                    provider_id: "bybit".to_string(),
+                    data: spec.parse_ticker(symbol, &quote, data),
+                }
+            })
+            .collect()
+    }
+
+    fn error_message(&self, msg: &serde_json::Value) -> Option<String> {
+        (msg["op"] != "ping" && msg["success"] == false).then(|| format!("{} rejected: {}", msg["op"], msg["ret_msg"]))
+    }
+
+    fn ping(&self) -> Option<(&'static str, u64)> {
+        Some((r#"{"op":"ping"}"#, PING_INTERVAL_SECS))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn ticker_updates_use_subscribed_symbols() {
+        let provider = BybitWsProvider::new();
+        let pairs = provider.stream_symbols(&["BTC", "BTCUSDT", "ETHBTC"].map(String::from));
+        let updates = provider.parse_message(
+            &pairs,
+            &json!({
+                "topic": "tickers.BTCUSDT", "type": "snapshot", "ts": 1,
+                "data": {
+                    "symbol": "BTCUSDT", "lastPrice": "110", "highPrice24h": "120", "lowPrice24h": "90",
+                    "prevPrice24h": "100", "volume24h": "5", "turnover24h": "550"
+                }
+            }),
+        );
+        let mut symbols: Vec<&str> = updates.iter().map(|u| u.symbol.as_str()).collect();
+        symbols.sort();
+        assert_eq!(symbols, vec!["BTC", "BTCUSDT"]);
+        let d = &updates[0].data;
+        assert_eq!((d.provider_id.as_str(), d.currency.as_str(), d.price), ("bybit", "USDT", 110.0));
+        assert!((d.change_percent_24h.unwrap() - 10.0).abs() < 1e-9);
+
+        // 訂閱回應、pong 與未訂閱的交易對都忽略
+        let ignored = [
+            json!({"success": true, "ret_msg": "", "op": "subscribe"}),
+            json!({"success": true, "ret_msg": "pong", "op": "ping"}),
+            json!({"topic": "tickers.SOLUSDT", "data": {"symbol": "SOLUSDT", "lastPrice": "1"}}),
+        ];
+        assert!(ignored.iter().all(|m| provider.parse_message(&pairs, m).is_empty()));
+        assert!(provider
+            .error_message(&json!({"success": false, "ret_msg": "invalid topic", "op": "subscribe"}))
+            .is_some());
+    }
+
+    #[test]
+    fn subscriptions_are_chunked() {
+        let provider = BybitWsProvider::new();
+        let pairs: Vec<String> = (0..23).map(|i| provider.stream_id(&format!("T{}USDT", i))).collect();
+        let messages = provider.subscribe_messages(&pairs);
+        assert_eq!(messages.len(), 3);
+        let first: serde_json::Value = serde_json::from_str(&messages[0]).unwrap();
+        assert_eq!(first["op"], "subscribe");
+        assert_eq!(first["args"].as_array().unwrap().len(), MAX_ARGS_PER_REQUEST);
+        assert!(first["args"][0].as_str().unwrap().starts_with("tickers.T"));
+        let unsubscribe: serde_json::Value = serde_json::from_str(&provider.unsubscribe_messages(&pairs[..1])[0]).unwrap();
+        assert_eq!(unsubscribe, json!({ "op": "unsubscribe", "args": ["tickers.T0USDT"] }));
+    }
+}
@@ -0,0 +1,165 @@
+use super::traits::*;
+use super::types::*;
+
+/// Coinbase Advanced Trade WebSocket streaming for real-time ticker data
+pub struct CoinbaseWsProvider;
+
+const WS_URL: &str = "wss://advanced-trade-ws.coinbase.com";
+
+impl Default for CoinbaseWsProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CoinbaseWsProvider {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn ticker_message(op: &str, product_ids: &[String]) -> String {
+        serde_json::json!({ "type": op, "channel": "ticker", "product_ids": product_ids }).to_string()
+    }
+}
+
+impl WebSocketProvider for CoinbaseWsProvider {
+    fn provider_id(&self) -> &'static str {
+        "coinbase"
+    }
+
+    fn url(&self) -> String {
+        WS_URL.to_string()
+    }
+
+    /// Coinbase product id（BTC-USD）
+    fn stream_id(&self, symbol: &str) -> String {
+        to_coinbase_symbol(symbol)
+    }
+
+    /// 沒有成交的交易對可能很久沒有推送，訂閱 heartbeats 避免閒置連線被伺服器關閉
+    fn connect_messages(&self) -> Vec<String> {
+        vec![serde_json::json!({ "type": "subscribe", "channel": "heartbeats" }).to_string()]
+    }
+
+    fn subscribe_messages(&self, stream_ids: &[String]) -> Vec<String> {
+        vec![Self::ticker_message("subscribe", stream_ids)]
+    }
+
+    fn unsubscribe_messages(&self, stream_ids: &[String]) -> Vec<String> {
+        vec![Self::ticker_message("unsubscribe", stream_ids)]
+    }
+
+    /// 解析 ticker channel 訊息為 WsTickerUpdate（snapshot 與 update 都可能含多筆）；其他訊息回傳空陣列
+    fn parse_message(&self, streams: &WsStreamSymbols, msg: &serde_json::Value) -> Vec<WsTickerUpdate> {
+        if msg["channel"] != "ticker" {
+            return Vec::new();
+        }
+        let Some(events) = msg["events"].as_array() else {
+            return Vec::new();
+        };
+        let mut updates = Vec::new();
+        for ticker in events
+            .iter()
+            .filter_map(|e| e["tickers"].as_array())
+            .flatten()
+        {
+            let Some(product_id) = ticker["product_id"].as_str() else {
+                continue;
+            };
+            let Some(symbols) = streams.get(product_id) else {
+                continue;
+            };
+            let parse_f64 = |key: &str| ticker[key].as_str().and_then(|s| s.parse::<f64>().ok());
+            let Some(price) = parse_f64("price") else {
+                continue;
+            };
+            let change_pct = parse_f64("price_percent_chg_24_h");
+            // 只有漲跌幅，以此推回 24h 前價格計算漲跌
+            let change = change_pct
+                .filter(|pct| *pct > -100.0)
+                .map(|pct| price - price / (1.0 + pct / 100.0));
+            let quote = product_id.rsplit('-').next().unwrap_or("USD");
+            for symbol in symbols {
+                let asset = AssetDataBuilder::new(symbol, "coinbase")
+                    .price(price)
+                    .currency(quote)
+                    .change_24h(change)
+                    .change_percent_24h(change_pct)
+                    .high_24h(parse_f64("high_24_h"))
+                    .low_24h(parse_f64("low_24_h"))
+                    .volume(parse_f64("volume_24_h"))
+                    .build();
+                updates.push(WsTickerUpdate {
+                    symbol: symbol.clone(),
+                    provider_id: "coinbase".to_string(),
+                    data: asset,
+                });
+            }
+        }
+        updates
+    }
+
+    fn error_message(&self, msg: &serde_json::Value) -> Option<String> {
+        (msg["type"] == "error").then(|| msg["message"].to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn ticker_updates_use_subscribed_symbols() {
+        let provider = CoinbaseWsProvider::new();
+        let products = provider.stream_symbols(&["BTC", "BTCUSDT", "ETH-EUR"].map(String::from));
+        let updates = provider.parse_message(
+            &products,
+            &json!({
+                "channel": "ticker", "timestamp": "2026-01-01T00:00:00Z", "sequence_num": 0,
+                "events": [{
+                    "type": "snapshot",
+                    "tickers": [
+                        {
+                            "type": "ticker", "product_id": "BTC-USD", "price": "110",
+                            "volume_24_h": "5", "low_24_h": "90", "high_24_h": "120",
+                            "price_percent_chg_24_h": "10"
+                        },
+                        { "type": "ticker", "product_id": "ETH-EUR", "price": "2000" }
+                    ]
+                }]
+            }),
+        );
+        let mut symbols: Vec<&str> = updates.iter().map(|u| u.symbol.as_str()).collect();
+        symbols.sort();
+        assert_eq!(symbols, vec!["BTC", "BTCUSDT", "ETH-EUR"]);
+        let btc = &updates.iter().find(|u| u.symbol == "BTC").unwrap().data;
+        assert_eq!((btc.provider_id.as_str(), btc.currency.as_str(), btc.price), ("coinbase", "USD", 110.0));
+        assert_eq!((btc.high_24h, btc.low_24h, btc.volume), (Some(120.0), Some(90.0), Some(5.0)));
+        assert!((btc.change_24h.unwrap() - 10.0).abs() < 1e-9);
+        let eth = &updates.iter().find(|u| u.symbol == "ETH-EUR").unwrap().data;
+        assert_eq!((eth.currency.as_str(), eth.change_24h), ("EUR", None));
+
+        // 訂閱回應、heartbeat 與未訂閱的交易對都忽略
+        let ignored = [
+            json!({"channel": "subscriptions", "events": [{"subscriptions": {"ticker": ["BTC-USD"]}}]}),
+            json!({"channel": "heartbeats", "events": [{"heartbeat_counter": 1}]}),
+            json!({"channel": "ticker", "events": [{"tickers": [{"product_id": "SOL-USD", "price": "1"}]}]}),
+        ];
+        assert!(ignored.iter().all(|m| provider.parse_message(&products, m).is_empty()));
+    }
+
+    #[test]
+    fn subscribes_ticker_and_heartbeats() {
+        let provider = CoinbaseWsProvider::new();
+        let product_ids: Vec<String> = ["BTC-USD", "ETHUSDT"].iter().map(|s| provider.stream_id(s)).collect();
+        let heartbeats: serde_json::Value = serde_json::from_str(&provider.connect_messages()[0]).unwrap();
+        assert_eq!(heartbeats["channel"], "heartbeats");
+        let ticker: serde_json::Value = serde_json::from_str(&provider.subscribe_messages(&product_ids)[0]).unwrap();
+        assert_eq!(ticker["channel"], "ticker");
+        assert_eq!(ticker["product_ids"], json!(["BTC-USD", "ETH-USD"]));
+        let unsubscribe: serde_json::Value =
+            serde_json::from_str(&provider.unsubscribe_messages(&product_ids[..1])[0]).unwrap();
+        assert_eq!((unsubscribe["type"].as_str(), &unsubscribe["product_ids"]), (Some("unsubscribe"), &json!(["BTC-USD"])));
+    }
+}
@@ -0,0 +1,170 @@
+use super::traits::*;
+use super::types::*;
+
+/// Kraken WebSocket v2 streaming for real-time ticker data
+pub struct KrakenWsProvider;
+
+const WS_URL: &str = "wss://ws.kraken.com/v2";
+/// 應用層 ping 間隔（伺服器的 heartbeat 只在有訂閱時送出）
+const PING_INTERVAL_SECS: u64 = 30;
+
+impl Default for KrakenWsProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KrakenWsProvider {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// 轉為 v2 交易對格式：v2 使用 BTC 而非 REST 的 XBT（XBTUSD → BTC/USD），USDT 與 REST 一樣對應 USD
+    fn to_ws_pair(symbol: &str) -> String {
+        let (base, quote) = parse_crypto_symbol(symbol);
+        let b = match base.as_str() {
+            "XBT" => "BTC",
+            _ => &base,
+        };
+        let q = match quote.as_str() {
+            "USDT" => "USD",
+            _ => &quote,
+        };
+        format!("{}/{}", b, q)
+    }
+
+    fn method_message(method: &str, pairs: &[String]) -> String {
+        serde_json::json!({
+            "method": method,
+            "params": { "channel": "ticker", "symbol": pairs }
+        })
+        .to_string()
+    }
+}
+
+impl WebSocketProvider for KrakenWsProvider {
+    fn provider_id(&self) -> &'static str {
+        "kraken"
+    }
+
+    fn url(&self) -> String {
+        WS_URL.to_string()
+    }
+
+    fn stream_id(&self, symbol: &str) -> String {
+        Self::to_ws_pair(symbol)
+    }
+
+    fn subscribe_messages(&self, stream_ids: &[String]) -> Vec<String> {
+        vec![Self::method_message("subscribe", stream_ids)]
+    }
+
+    fn unsubscribe_messages(&self, stream_ids: &[String]) -> Vec<String> {
+        vec![Self::method_message("unsubscribe", stream_ids)]
+    }
+
+    /// 解析 ticker channel 的 snapshot / update 為 WsTickerUpdate；其他訊息回傳空陣列
+    fn parse_message(&self, streams: &WsStreamSymbols, msg: &serde_json::Value) -> Vec<WsTickerUpdate> {
+        if msg["channel"] != "ticker" {
+            return Vec::new();
+        }
+        let Some(data) = msg["data"].as_array() else {
+            return Vec::new();
+        };
+        let mut updates = Vec::new();
+        for ticker in data {
+            let Some(pair) = ticker["symbol"].as_str() else {
+                continue;
+            };
+            let Some(symbols) = streams.get(pair) else {
+                continue;
+            };
+            let Some(price) = ticker["last"].as_f64() else {
+                continue;
+            };
+            let quote = pair.rsplit('/').next().unwrap_or("USD");
+            for symbol in symbols {
+                let asset = AssetDataBuilder::new(symbol, "kraken")
+                    .price(price)
+                    .currency(quote)
+                    .change_24h(ticker["change"].as_f64())
+                    .change_percent_24h(ticker["change_pct"].as_f64())
+                    .high_24h(ticker["high"].as_f64())
+                    .low_24h(ticker["low"].as_f64())
+                    .volume(ticker["volume"].as_f64())
+                    .build();
+                updates.push(WsTickerUpdate {
+                    symbol: symbol.clone(),
+                    provider_id: "kraken".to_string(),
+                    data: asset,
+                });
+            }
+        }
+        updates
+    }
+
+    fn error_message(&self, msg: &serde_json::Value) -> Option<String> {
+        (msg["success"] == false).then(|| format!("{} rejected: {}", msg["method"], msg["error"]))
+    }
+
+    fn ping(&self) -> Option<(&'static str, u64)> {
+        Some((r#"{"method":"ping"}"#, PING_INTERVAL_SECS))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn pairs_translate_xbt_to_v2_symbols() {
+        assert_eq!(KrakenWsProvider::to_ws_pair("XBTUSD"), "BTC/USD");
+        assert_eq!(KrakenWsProvider::to_ws_pair("BTCUSDT"), "BTC/USD");
+        assert_eq!(KrakenWsProvider::to_ws_pair("ETH/EUR"), "ETH/EUR");
+        assert_eq!(KrakenWsProvider::to_ws_pair("SOL"), "SOL/USD");
+
+        let provider = KrakenWsProvider::new();
+        let pairs: Vec<String> = ["XBTUSD", "ETHUSD"].iter().map(|s| provider.stream_id(s)).collect();
+        let msg: serde_json::Value = serde_json::from_str(&provider.subscribe_messages(&pairs)[0]).unwrap();
+        assert_eq!(msg["params"], json!({ "channel": "ticker", "symbol": ["BTC/USD", "ETH/USD"] }));
+        let msg: serde_json::Value = serde_json::from_str(&provider.unsubscribe_messages(&pairs[1..])[0]).unwrap();
+        assert_eq!((msg["method"].as_str(), &msg["params"]["symbol"]), (Some("unsubscribe"), &json!(["ETH/USD"])));
+    }
+
+    #[test]
+    fn ticker_updates_use_subscribed_symbols() {
+        let provider = KrakenWsProvider::new();
+        let pairs = provider.stream_symbols(&["XBTUSD", "BTC", "ETH-EUR"].map(String::from));
+        let updates = provider.parse_message(
+            &pairs,
+            &json!({
+                "channel": "ticker", "type": "update",
+                "data": [
+                    {
+                        "symbol": "BTC/USD", "bid": 109.9, "ask": 110.1, "last": 110.0, "volume": 5.0,
+                        "vwap": 105.0, "low": 90.0, "high": 120.0, "change": 10.0, "change_pct": 10.0
+                    },
+                    { "symbol": "ETH/EUR", "last": 2000.0 }
+                ]
+            }),
+        );
+        let mut symbols: Vec<&str> = updates.iter().map(|u| u.symbol.as_str()).collect();
+        symbols.sort();
+        assert_eq!(symbols, vec!["BTC", "ETH-EUR", "XBTUSD"]);
+        let btc = &updates.iter().find(|u| u.symbol == "XBTUSD").unwrap().data;
+        assert_eq!((btc.provider_id.as_str(), btc.currency.as_str(), btc.price), ("kraken", "USD", 110.0));
+        assert_eq!((btc.change_24h, btc.change_percent_24h, btc.volume), (Some(10.0), Some(10.0), Some(5.0)));
+        let eth = &updates.iter().find(|u| u.symbol == "ETH-EUR").unwrap().data;
+        assert_eq!((eth.currency.as_str(), eth.high_24h), ("EUR", None));
+
+        // 訂閱回應、heartbeat、status 與未訂閱的交易對都忽略
+        let ignored = [
+            json!({"method": "subscribe", "result": {"channel": "ticker", "symbol": "BTC/USD"}, "success": true}),
+            json!({"channel": "heartbeat"}),
+            json!({"channel": "status", "type": "update", "data": [{"system": "online"}]}),
+            json!({"channel": "ticker", "type": "update", "data": [{"symbol": "SOL/USD", "last": 1.0}]}),
+        ];
+        assert!(ignored.iter().all(|m| provider.parse_message(&pairs, m).is_empty()));
+    }
+}
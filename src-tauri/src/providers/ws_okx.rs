@@ -0,0 +1,139 @@
+use super::okx::{parse_okx_ticker, to_okx_symbol};
+use super::traits::*;
+use super::types::*;
+
+/// OKX WebSocket v5 streaming for real-time ticker data
+pub struct OkxWsProvider;
+
+const WS_URL: &str = "wss://ws.okx.com:8443/ws/v5/public";
+/// OKX 在 30 秒內沒有任何訊息時會關閉連線，以文字 `ping` 保持連線（伺服器回 `pong`）
+const PING_INTERVAL_SECS: u64 = 25;
+
+impl Default for OkxWsProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OkxWsProvider {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn op_message(op: &str, inst_ids: &[String]) -> String {
+        let args: Vec<serde_json::Value> = inst_ids
+            .iter()
+            .map(|inst| serde_json::json!({ "channel": "tickers", "instId": inst }))
+            .collect();
+        serde_json::json!({ "op": op, "args": args }).to_string()
+    }
+}
+
+impl WebSocketProvider for OkxWsProvider {
+    fn provider_id(&self) -> &'static str {
+        "okx"
+    }
+
+    fn url(&self) -> String {
+        WS_URL.to_string()
+    }
+
+    /// 與 REST provider 共用 instId 格式（BTC-USDT）
+    fn stream_id(&self, symbol: &str) -> String {
+        to_okx_symbol(symbol)
+    }
+
+    fn subscribe_messages(&self, stream_ids: &[String]) -> Vec<String> {
+        vec![Self::op_message("subscribe", stream_ids)]
+    }
+
+    fn unsubscribe_messages(&self, stream_ids: &[String]) -> Vec<String> {
+        vec![Self::op_message("unsubscribe", stream_ids)]
+    }
+
+    /// 解析 tickers channel 推送為 WsTickerUpdate（欄位與 REST ticker 相同）；其他訊息回傳空陣列
+    fn parse_message(&self, streams: &WsStreamSymbols, msg: &serde_json::Value) -> Vec<WsTickerUpdate> {
+        if msg["arg"]["channel"] != "tickers" {
+            return Vec::new();
+        }
+        let Some(data) = msg["data"].as_array() else {
+            return Vec::new();
+        };
+        let mut updates = Vec::new();
+        for ticker in data {
+            let Some(symbols) = ticker["instId"].as_str().and_then(|inst| streams.get(inst)) else {
+                continue;
+            };
+            if ticker["last"].as_str().and_then(|s| s.parse::<f64>().ok()).is_none() {
+                continue;
+            }
+            for symbol in symbols {
+                updates.push(WsTickerUpdate {
+                    symbol: symbol.clone(),
+                    provider_id: "okx".to_string(),
+                    data: parse_okx_ticker(symbol, ticker),
+                });
+            }
+        }
+        updates
+    }
+
+    fn error_message(&self, msg: &serde_json::Value) -> Option<String> {
+        (msg["event"] == "error").then(|| format!("{}: {}", msg["code"], msg["msg"]))
+    }
+
+    fn ping(&self) -> Option<(&'static str, u64)> {
+        Some(("ping", PING_INTERVAL_SECS))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn subscribes_tickers_by_inst_id() {
+        let provider = OkxWsProvider::new();
+        let inst_ids: Vec<String> = ["BTC", "ETHUSDT"].iter().map(|s| provider.stream_id(s)).collect();
+        let msg: serde_json::Value = serde_json::from_str(&provider.subscribe_messages(&inst_ids)[0]).unwrap();
+        assert_eq!(msg["op"], "subscribe");
+        assert_eq!(
+            msg["args"],
+            json!([{ "channel": "tickers", "instId": "BTC-USDT" }, { "channel": "tickers", "instId": "ETH-USDT" }])
+        );
+        let msg: serde_json::Value = serde_json::from_str(&provider.unsubscribe_messages(&inst_ids[1..])[0]).unwrap();
+        assert_eq!(msg, json!({ "op": "unsubscribe", "args": [{ "channel": "tickers", "instId": "ETH-USDT" }] }));
+    }
+
+    #[test]
+    fn ticker_updates_use_subscribed_symbols() {
+        let provider = OkxWsProvider::new();
+        let insts = provider.stream_symbols(&["BTC", "BTCUSDT", "ETH-BTC"].map(String::from));
+        let updates = provider.parse_message(
+            &insts,
+            &json!({
+                "arg": { "channel": "tickers", "instId": "BTC-USDT" },
+                "data": [{
+                    "instType": "SPOT", "instId": "BTC-USDT", "last": "110", "open24h": "100",
+                    "high24h": "120", "low24h": "90", "vol24h": "5", "volCcy24h": "550", "ts": "1"
+                }]
+            }),
+        );
+        let mut symbols: Vec<&str> = updates.iter().map(|u| u.symbol.as_str()).collect();
+        symbols.sort();
+        assert_eq!(symbols, vec!["BTC", "BTCUSDT"]);
+        let d = &updates[0].data;
+        assert_eq!((d.provider_id.as_str(), d.price, d.change_24h), ("okx", 110.0, Some(10.0)));
+        assert_eq!((d.high_24h, d.low_24h, d.volume), (Some(120.0), Some(90.0), Some(5.0)));
+
+        // 訂閱回應、錯誤與未訂閱的交易對都忽略
+        let ignored = [
+            json!({"event": "subscribe", "arg": {"channel": "tickers", "instId": "BTC-USDT"}, "connId": "a"}),
+            json!({"event": "error", "code": "60012", "msg": "Invalid request", "connId": "a"}),
+            json!({"arg": {"channel": "tickers", "instId": "SOL-USDT"}, "data": [{"instId": "SOL-USDT", "last": "1"}]}),
+        ];
+        assert!(ignored.iter().all(|m| provider.parse_message(&insts, m).is_empty()));
+        assert_eq!(provider.error_message(&ignored[1]).as_deref(), Some(r#""60012": "Invalid request""#));
+    }
+}
@@ -1,67 +1,159 @@
+use super::debug::SendCaptured;
 use super::traits::*;
 use super::types::*;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
 /// Yahoo Finance — 使用 cookie + crumb 認證
 /// 主要端點: v7/finance/quote (支援批量 + 盤前盤後數據)
 pub struct YahooProvider {
     client: reqwest::Client,
-    auth: Arc<RwLock<Option<YahooAuth>>>,
+    base_url: String,
+    session: Arc<YahooSession>,
 }
 
 #[derive(Clone)]
 struct YahooAuth {
     crumb: String,
+    fetched_at: Instant,
 }
 
+/// 同一 base URL 的所有 YahooProvider instance 共用的認證狀態與 quoteSummary 快取。
+///
+/// crumb 綁定 cookie，因此 cookie jar 也一併共用；重新載入 polling 建立新 instance 時
+/// 不必再跑一次 crumb 流程。
+#[derive(Default)]
+struct YahooSession {
+    jar: Arc<reqwest::cookie::Jar>,
+    auth: RwLock<Option<YahooAuth>>,
+    /// symbol → (取得時間, 要併入 extra 的欄位)
+    summaries: RwLock<HashMap<String, (Instant, SummaryFields)>>,
+}
+
+/// quoteSummary 併入 extra 的欄位
+#[derive(Debug, Clone, Default, PartialEq)]
+struct SummaryFields {
+    market_cap: Option<f64>,
+    pe_ratio: Option<f64>,
+    forward_pe: Option<f64>,
+    /// 百分比（Yahoo 回傳小數，例如 0.0044 → 0.44）
+    dividend_yield: Option<f64>,
+}
+
+static SESSIONS: LazyLock<Mutex<HashMap<String, Arc<YahooSession>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 是否為股票類 quote 額外查詢 quoteSummary（app setting `yahoo_quote_summary`）
+static QUOTE_SUMMARY_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// crumb 超過此時間即主動更新（Yahoo 的 cookie 約一天過期）
+const AUTH_REFRESH_AFTER: Duration = Duration::from_secs(6 * 3600);
+/// quoteSummary 基本面資料變動慢，每個 symbol 快取一小時
+const SUMMARY_TTL: Duration = Duration::from_secs(3600);
+
+const DEFAULT_BASE_URL: &str = "https://query2.finance.yahoo.com";
+
 /// v7/quote 需要的欄位列表
 const QUOTE_FIELDS: &str = "regularMarketPrice,regularMarketChange,regularMarketChangePercent,\
 regularMarketDayHigh,regularMarketDayLow,regularMarketVolume,regularMarketPreviousClose,\
-regularMarketOpen,marketCap,currency,exchangeName,marketState,shortName,\
+regularMarketOpen,marketCap,currency,exchangeName,marketState,shortName,quoteType,\
 fiftyTwoWeekHigh,fiftyTwoWeekLow,\
 preMarketPrice,preMarketChange,preMarketChangePercent,\
 postMarketPrice,postMarketChange,postMarketChangePercent";
 
+pub fn set_quote_summary_enabled(enabled: bool) {
+    QUOTE_SUMMARY_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn quote_summary_enabled() -> bool {
+    QUOTE_SUMMARY_ENABLED.load(Ordering::Relaxed)
+}
+
+fn session_for(base_url: &str) -> Arc<YahooSession> {
+    SESSIONS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entry(base_url.to_string())
+        .or_default()
+        .clone()
+}
+
 impl Default for YahooProvider {
     fn default() -> Self {
-        Self::new()
+        Self::new(None, &HttpOptions::default())
     }
 }
 
 impl YahooProvider {
-    pub fn new() -> Self {
-        let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(15))
+    pub fn new(api_url: Option<String>, http: &HttpOptions) -> Self {
+        let base_url = resolve_base_url(api_url, DEFAULT_BASE_URL);
+        let session = session_for(&base_url);
+        let builder = reqwest::Client::builder()
             .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
-            .cookie_store(true)
-            .build()
-            .unwrap_or_default();
+            .cookie_provider(session.jar.clone());
+        let client = configure_client(builder, http).build().unwrap_or_default();
         Self {
             client,
-            auth: Arc::new(RwLock::new(None)),
+            base_url,
+            session,
         }
     }
 
     async fn get_auth(&self) -> Result<YahooAuth, String> {
         {
-            let cached = self.auth.read().await;
+            let cached = self.session.auth.read().await;
             if let Some(auth) = cached.as_ref() {
+                if auth.fetched_at.elapsed() < AUTH_REFRESH_AFTER {
+                    return Ok(auth.clone());
+                }
+            }
+        }
+
+        // 持有寫入鎖再取 crumb，避免多個 instance 同時跑 crumb 流程
+        let mut cached = self.session.auth.write().await;
+        if let Some(auth) = cached.as_ref() {
+            if auth.fetched_at.elapsed() < AUTH_REFRESH_AFTER {
                 return Ok(auth.clone());
             }
         }
+        match self.fetch_crumb().await {
+            Ok(crumb) => {
+                let auth = YahooAuth {
+                    crumb,
+                    fetched_at: Instant::now(),
+                };
+                *cached = Some(auth.clone());
+                Ok(auth)
+            }
+            // 主動更新失敗時沿用舊 crumb；真的失效時 401/403 會再觸發重新取得
+            Err(e) => match cached.as_ref() {
+                Some(old) => {
+                    tracing::warn!("[Yahoo] Crumb refresh failed, keeping previous crumb: {}", e);
+                    Ok(old.clone())
+                }
+                None => Err(e),
+            },
+        }
+    }
 
-        let _ = self
-            .client
-            .get("https://fc.yahoo.com")
-            .send()
-            .await
-            .map_err(|e| format!("Yahoo cookie fetch failed: {}", e))?;
+    async fn fetch_crumb(&self) -> Result<String, String> {
+        // 自訂 base URL（鏡像／本地代理）由對方處理 cookie，只有官方端點需要先取 cookie
+        if self.base_url == DEFAULT_BASE_URL {
+            let _ = self
+                .client
+                .get("https://fc.yahoo.com")
+                .send_captured("yahoo")
+                .await
+                .map_err(|e| format!("Yahoo cookie fetch failed: {}", e))?;
+        }
 
         let crumb = self
             .client
-            .get("https://query2.finance.yahoo.com/v1/test/getcrumb")
-            .send()
+            .get(format!("{}/v1/test/getcrumb", self.base_url))
+            .send_captured("yahoo")
             .await
             .map_err(|e| format!("Yahoo crumb fetch failed: {}", e))?
             .text()
@@ -71,32 +163,22 @@ impl YahooProvider {
         if crumb.is_empty() || crumb.contains("<!DOCTYPE") {
             return Err("Yahoo crumb fetch failed, please try again later".to_string());
         }
-
-        let auth = YahooAuth {
-            crumb,
-        };
-        let mut cached = self.auth.write().await;
-        *cached = Some(auth.clone());
-        Ok(auth)
+        Ok(crumb)
     }
 
     async fn invalidate_auth(&self) {
-        let mut cached = self.auth.write().await;
+        let mut cached = self.session.auth.write().await;
         *cached = None;
     }
 
-    /// 呼叫 v7/finance/quote 端點，支援多個 symbol
-    async fn fetch_v7_quote(&self, symbols_csv: &str) -> Result<serde_json::Value, String> {
+    /// 以已取得 crumb 的 GET 請求；401/403 時重新取得 crumb 並重試一次
+    async fn get_json(&self, url_without_crumb: &str) -> Result<serde_json::Value, String> {
         let auth = self.get_auth().await?;
-        let url = format!(
-            "https://query2.finance.yahoo.com/v7/finance/quote?symbols={}&fields={}&crumb={}",
-            symbols_csv, QUOTE_FIELDS, auth.crumb
-        );
-
+        let sep = if url_without_crumb.contains('?') { '&' } else { '?' };
         let resp = self
             .client
-            .get(&url)
-            .send()
+            .get(format!("{}{}crumb={}", url_without_crumb, sep, auth.crumb))
+            .send_captured("yahoo")
             .await
             .map_err(|e| format!("Yahoo connection failed: {}", e))?;
 
@@ -105,14 +187,10 @@ impl YahooProvider {
         {
             self.invalidate_auth().await;
             let auth2 = self.get_auth().await?;
-            let url2 = format!(
-                "https://query2.finance.yahoo.com/v7/finance/quote?symbols={}&fields={}&crumb={}",
-                symbols_csv, QUOTE_FIELDS, auth2.crumb
-            );
             let resp2 = self
                 .client
-                .get(&url2)
-                .send()
+                .get(format!("{}{}crumb={}", url_without_crumb, sep, auth2.crumb))
+                .send_captured("yahoo")
                 .await
                 .map_err(|e| format!("Yahoo retry connection failed: {}", e))?;
             return resp2
@@ -129,6 +207,93 @@ impl YahooProvider {
             .await
             .map_err(|e| format!("Yahoo parse failed: {}", e))
     }
+
+    /// 呼叫 v7/finance/quote 端點，支援多個 symbol
+    async fn fetch_v7_quote(&self, symbols_csv: &str) -> Result<serde_json::Value, String> {
+        self.get_json(&format!(
+            "{}/v7/finance/quote?symbols={}&fields={}",
+            self.base_url, symbols_csv, QUOTE_FIELDS
+        ))
+        .await
+    }
+
+    /// 單一 symbol 的 quoteSummary（快取 `SUMMARY_TTL`）；失敗時回傳 None，不影響報價
+    async fn summary_fields(&self, yahoo_symbol: &str) -> Option<SummaryFields> {
+        if let Some((at, fields)) = self.session.summaries.read().await.get(yahoo_symbol) {
+            if at.elapsed() < SUMMARY_TTL {
+                return Some(fields.clone());
+            }
+        }
+        let url = format!(
+            "{}/v10/finance/quoteSummary/{}?modules=summaryDetail",
+            self.base_url, yahoo_symbol
+        );
+        match self.get_json(&url).await {
+            Ok(data) => {
+                let fields = parse_summary_detail(&data);
+                self.session
+                    .summaries
+                    .write()
+                    .await
+                    .insert(yahoo_symbol.to_string(), (Instant::now(), fields.clone()));
+                Some(fields)
+            }
+            Err(e) => {
+                tracing::debug!("[Yahoo] quoteSummary for {} failed: {}", yahoo_symbol, e);
+                None
+            }
+        }
+    }
+
+    /// 啟用 quoteSummary 時，為股票類 quote 併入基本面欄位
+    async fn merge_summaries(&self, quotes: &[&serde_json::Value], results: &mut [AssetData]) {
+        if !quote_summary_enabled() {
+            return;
+        }
+        let targets: Vec<(usize, String)> = quotes
+            .iter()
+            .enumerate()
+            .filter(|(_, q)| q["quoteType"].as_str() == Some("EQUITY"))
+            .filter_map(|(i, q)| q["symbol"].as_str().map(|s| (i, s.to_string())))
+            .collect();
+        let fetched = futures::future::join_all(
+            targets.iter().map(|(_, sym)| self.summary_fields(sym)),
+        )
+        .await;
+        for ((i, _), fields) in targets.into_iter().zip(fetched) {
+            if let (Some(fields), Some(data)) = (fields, results.get_mut(i)) {
+                apply_summary(data, &fields);
+            }
+        }
+    }
+}
+
+/// 解析 quoteSummary `summaryDetail` 模組（數值位於 `{ "raw": ... }`）
+fn parse_summary_detail(data: &serde_json::Value) -> SummaryFields {
+    let detail = &data["quoteSummary"]["result"][0]["summaryDetail"];
+    let raw = |key: &str| detail[key]["raw"].as_f64();
+    SummaryFields {
+        market_cap: raw("marketCap"),
+        pe_ratio: raw("trailingPE"),
+        forward_pe: raw("forwardPE"),
+        dividend_yield: raw("dividendYield").map(|v| v * 100.0),
+    }
+}
+
+fn apply_summary(data: &mut AssetData, fields: &SummaryFields) {
+    if data.market_cap.is_none() {
+        data.market_cap = fields.market_cap;
+    }
+    let extra = data.extra.get_or_insert_with(HashMap::new);
+    for (key, value) in [
+        ("pe_ratio", fields.pe_ratio),
+        ("forward_pe", fields.forward_pe),
+        ("dividend_yield", fields.dividend_yield),
+    ] {
+        if let Some(v) = value {
+            extra.insert(key.to_string(), serde_json::json!(v));
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -147,7 +312,9 @@ impl DataProvider for YahooProvider {
                 symbol
             ));
         }
-        Ok(parse_v7_quote(symbol, q))
+        let mut data = parse_v7_quote(symbol, q);
+        self.merge_summaries(&[q], std::slice::from_mut(&mut data)).await;
+        Ok(data)
     }
 
     /// 批量查詢 — v7/quote 原生支援多 symbol
@@ -172,7 +339,7 @@ impl DataProvider for YahooProvider {
         }
 
         let mut results = Vec::with_capacity(arr.len());
-        let sym_map: std::collections::HashMap<String, &str> = symbols
+        let sym_map: HashMap<String, &str> = symbols
             .iter()
             .map(|s| (s.replace('.', "-").to_uppercase(), s.as_str()))
             .collect();
@@ -182,6 +349,8 @@ impl DataProvider for YahooProvider {
             let original_sym = sym_map.get(&yahoo_sym).copied().unwrap_or(&yahoo_sym);
             results.push(parse_v7_quote(original_sym, q));
         }
+        let quotes: Vec<&serde_json::Value> = arr.iter().collect();
+        self.merge_summaries(&quotes, &mut results).await;
         Ok(results)
     }
 }
@@ -196,7 +365,7 @@ fn parse_v7_quote(symbol: &str, q: &serde_json::Value) -> AssetData {
     let post_price = q["postMarketPrice"].as_f64();
     let post_pct = q["postMarketChangePercent"].as_f64();
 
-    let mut builder = AssetDataBuilder::new(symbol, "yahoo")
+    let builder = AssetDataBuilder::new(symbol, "yahoo")
         .price(price)
         .currency(currency)
         .change_24h(q["regularMarketChange"].as_f64())
@@ -213,21 +382,74 @@ fn parse_v7_quote(symbol: &str, q: &serde_json::Value) -> AssetData {
         .extra_str("name", q["shortName"].as_str())
         .extra_str("market_session", market_state);
 
-    // 盤前數據
-    if let Some(pp) = pre_price {
-        builder = builder.extra_f64("pre_market_price", Some(pp));
-        let pre_change = pp - price;
-        builder = builder.extra_f64("pre_market_change", Some(pre_change));
-        builder = builder.extra_f64("pre_market_change_pct", pre_pct);
+    builder
+        .pre_market(pre_price.map(|pp| {
+            SessionQuote::against(pp, Some(price)).with_change_percent(pre_pct)
+        }))
+        .post_market(post_price.map(|pp| {
+            SessionQuote::against(pp, Some(price)).with_change_percent(post_pct)
+        }))
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn v7_quote_fills_session_quotes_and_history_columns() {
+        let q = serde_json::json!({
+            "regularMarketPrice": 200.0,
+            "preMarketPrice": 202.0,
+            "preMarketChangePercent": 1.0,
+            "postMarketPrice": 198.0,
+        });
+        let data = parse_v7_quote("AAPL", &q);
+
+        let pre = data.pre_market.unwrap();
+        assert_eq!(pre.price, 202.0);
+        assert_eq!(pre.change, Some(2.0));
+        assert_eq!(pre.change_percent, Some(1.0));
+        // provider 未給盤後漲跌幅時由正常盤價計算
+        assert_eq!(data.post_market.unwrap().change_percent, Some(-1.0));
+        // 既有顯示欄位仍可從 extra 讀到
+        assert_eq!(data.extra.as_ref().unwrap()["pre_market_price"], 202.0);
+
+        let (_, _, _, _, pre_col, post_col, _, _, _, _, _) = data.price_record();
+        assert_eq!((pre_col, post_col), (Some(202.0), Some(198.0)));
     }
 
-    // 盤後數據
-    if let Some(pp) = post_price {
-        builder = builder.extra_f64("post_market_price", Some(pp));
-        let post_change = pp - price;
-        builder = builder.extra_f64("post_market_change", Some(post_change));
-        builder = builder.extra_f64("post_market_change_pct", post_pct);
+    #[test]
+    fn summary_detail_merges_into_extra_without_overriding_market_cap() {
+        let summary = serde_json::json!({
+            "quoteSummary": {"result": [{"summaryDetail": {
+                "marketCap": {"raw": 3.0e12},
+                "trailingPE": {"raw": 31.5},
+                "dividendYield": {"raw": 0.0044}
+            }}]}
+        });
+        let fields = parse_summary_detail(&summary);
+        assert_eq!(fields.forward_pe, None);
+
+        let mut data = parse_v7_quote("AAPL", &serde_json::json!({"regularMarketPrice": 200.0}));
+        apply_summary(&mut data, &fields);
+        assert_eq!(data.market_cap, Some(3.0e12));
+        let extra = data.extra.as_ref().unwrap();
+        assert_eq!(extra["pe_ratio"], 31.5);
+        assert!((extra["dividend_yield"].as_f64().unwrap() - 0.44).abs() < 1e-9);
+        assert!(!extra.contains_key("forward_pe"));
+
+        // v7 已有市值時保留原值
+        let mut data = parse_v7_quote("AAPL", &serde_json::json!({"marketCap": 1.0}));
+        apply_summary(&mut data, &fields);
+        assert_eq!(data.market_cap, Some(1.0));
     }
 
-    builder.build()
+    #[test]
+    fn instances_with_same_base_url_share_a_session() {
+        let a = YahooProvider::new(Some("http://127.0.0.1:9/yahoo-test".to_string()), &HttpOptions::default());
+        let b = YahooProvider::new(Some("http://127.0.0.1:9/yahoo-test".to_string()), &HttpOptions::default());
+        assert!(Arc::ptr_eq(&a.session, &b.session));
+        assert!(!Arc::ptr_eq(&a.session, &YahooProvider::new(None, &HttpOptions::default()).session));
+    }
 }
@@ -0,0 +1,265 @@
+//! 歷史紀錄健康狀態 — 寫入失敗（DB 鎖定、訂閱已刪除等）原本只寫進 log，使用者往往幾天後才發現缺口。
+//!
+//! `write_price_history` 每筆失敗都會累計到該訂閱的失敗統計（程式啟動後，`get_history_stats` 一併回傳），
+//! 並由呼叫端送出 `AppEvent::RecordError`（前端事件 `record-error`）。
+//!
+//! 停滯提醒（`stall_minutes > 0` 時啟用）：每分鐘檢查開啟紀錄、且目前在紀錄時段內的訂閱，
+//! 超過 `stall_minutes` 分鐘沒有寫入任何紀錄時送出一次 `record-error`（`kind = stalled`）與系統通知，
+//! 恢復寫入後才會再次提醒。暫停紀錄期間不檢查。設定存於 app settings（`record_stall_minutes`）。
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, RwLock};
+use std::time::Duration;
+
+use chrono::Timelike;
+use serde::{Deserialize, Serialize};
+use stockenboard_api_types::prices::HistoryStatsResult;
+use tokio::sync::broadcast;
+
+use crate::db::{DbPool, RecordTarget};
+use crate::events::AppEvent;
+use crate::i18n::{tr, Msg};
+
+/// 停滯檢查間隔
+pub const CHECK_INTERVAL_SECS: u64 = 60;
+/// 停滯提醒門檻上限（一天）
+pub const MAX_STALL_MINUTES: u32 = 1440;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecordStallConfig {
+    /// 開啟紀錄的訂閱超過幾分鐘沒有寫入就提醒；0 = 不提醒
+    pub stall_minutes: u32,
+}
+
+impl RecordStallConfig {
+    /// 檢查設定
+    pub fn normalized(self) -> Result<Self, String> {
+        if self.stall_minutes > MAX_STALL_MINUTES {
+            return Err(format!("Stall alert must be between 0 and {} minutes", MAX_STALL_MINUTES));
+        }
+        Ok(self)
+    }
+}
+
+static STALL_CONFIG: LazyLock<RwLock<RecordStallConfig>> =
+    LazyLock::new(|| RwLock::new(RecordStallConfig::default()));
+static HEALTH: LazyLock<RwLock<HashMap<i64, RecordHealth>>> = LazyLock::new(|| RwLock::new(HashMap::new()));
+
+pub fn set_record_stall_config(config: RecordStallConfig) {
+    *STALL_CONFIG.write().unwrap_or_else(|e| e.into_inner()) = config;
+}
+
+pub fn record_stall_config() -> RecordStallConfig {
+    STALL_CONFIG.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordErrorKind {
+    /// 寫入 price_history 失敗
+    WriteFailed,
+    /// 開啟紀錄但超過 `stall_minutes` 沒有寫入
+    Stalled,
+}
+
+/// `record-error` 事件
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RecordError {
+    pub subscription_id: i64,
+    pub provider_id: String,
+    /// polling symbol
+    pub symbol: String,
+    pub kind: RecordErrorKind,
+    pub error: String,
+    /// Unix 秒
+    pub occurred_at: i64,
+}
+
+/// 單一訂閱自程式啟動後的寫入失敗統計
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RecordHealth {
+    pub failures: i64,
+    pub last_error: Option<String>,
+    pub last_failure_at: Option<i64>,
+}
+
+/// 累計一筆寫入失敗
+pub fn record_failure(error: &RecordError) {
+    let mut health = HEALTH.write().unwrap_or_else(|e| e.into_inner());
+    let entry = health.entry(error.subscription_id).or_default();
+    entry.failures += 1;
+    entry.last_error = Some(error.error.clone());
+    entry.last_failure_at = Some(error.occurred_at);
+}
+
+pub fn health(subscription_id: i64) -> RecordHealth {
+    HEALTH
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&subscription_id)
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// 訂閱的紀錄統計與寫入失敗統計
+pub fn history_stats(db: &DbPool, subscription_id: i64) -> Result<HistoryStatsResult, String> {
+    let stats = db.get_history_stats(subscription_id)?;
+    let health = health(subscription_id);
+    Ok(HistoryStatsResult {
+        subscription_id,
+        total_records: stats.total,
+        earliest: stats.oldest,
+        latest: stats.newest,
+        record_failures: health.failures,
+        last_record_error: health.last_error,
+        last_record_error_at: health.last_failure_at,
+    })
+}
+
+/// 停滯偵測狀態
+#[derive(Debug, Default)]
+pub struct StallTracker {
+    /// 訂閱開始被檢查（或回到紀錄時段）的時間；沒有紀錄的訂閱以此為起點
+    since: HashMap<i64, i64>,
+    /// 已提醒、尚未恢復寫入的訂閱
+    alerted: HashSet<i64>,
+}
+
+impl StallTracker {
+    /// 一輪檢查；回傳這一輪新進入停滯的訂閱
+    pub fn check(
+        &mut self,
+        targets: &[(String, String, RecordTarget)],
+        last_recorded: &HashMap<i64, i64>,
+        now: i64,
+        local_hour: u32,
+        stall_secs: i64,
+    ) -> Vec<RecordError> {
+        let ids: HashSet<i64> = targets.iter().map(|(_, _, t)| t.subscription_id).collect();
+        self.since.retain(|id, _| ids.contains(id));
+        self.alerted.retain(|id| ids.contains(id));
+
+        let mut stalled = Vec::new();
+        for (provider_id, symbol, target) in targets {
+            let id = target.subscription_id;
+            if !target.in_window(local_hour) {
+                self.since.insert(id, now);
+                self.alerted.remove(&id);
+                continue;
+            }
+            let since = *self.since.entry(id).or_insert(now);
+            let quiet_since = last_recorded.get(&id).map_or(since, |&last| last.max(since));
+            if now - quiet_since < stall_secs {
+                self.alerted.remove(&id);
+            } else if self.alerted.insert(id) {
+                stalled.push(RecordError {
+                    subscription_id: id,
+                    provider_id: provider_id.clone(),
+                    symbol: symbol.clone(),
+                    kind: RecordErrorKind::Stalled,
+                    error: format!("No price history written for {} minutes", (now - quiet_since) / 60),
+                    occurred_at: now,
+                });
+            }
+        }
+        stalled
+    }
+}
+
+/// 啟動停滯檢查（未設定 `stall_minutes` 時只會閒置）
+pub fn start(db: Arc<DbPool>, event_bus: broadcast::Sender<AppEvent>, recording_paused: Arc<AtomicBool>) {
+    tokio::spawn(async move {
+        let mut tracker = StallTracker::default();
+        loop {
+            tokio::time::sleep(Duration::from_secs(CHECK_INTERVAL_SECS)).await;
+            let stall_minutes = record_stall_config().stall_minutes;
+            if stall_minutes == 0 || recording_paused.load(Ordering::Relaxed) {
+                tracker = StallTracker::default();
+                continue;
+            }
+            let (targets, last_recorded) = match db.read_record_targets().and_then(|t| Ok((t, db.last_recorded_at()?))) {
+                Ok(result) => result,
+                Err(e) => {
+                    tracing::warn!("[RecordHealth] Failed to read recording state: {}", e);
+                    continue;
+                }
+            };
+            let targets: Vec<(String, String, RecordTarget)> = targets
+                .into_iter()
+                .flat_map(|(provider_id, symbols)| {
+                    symbols
+                        .into_iter()
+                        .map(move |(symbol, target)| (provider_id.clone(), symbol, target))
+                })
+                .collect();
+            let now = chrono::Utc::now().timestamp();
+            let local_hour = chrono::Local::now().hour();
+            for error in tracker.check(&targets, &last_recorded, now, local_hour, stall_minutes as i64 * 60) {
+                tracing::warn!("[RecordHealth] {}:{} stalled: {}", error.provider_id, error.symbol, error.error);
+                let _ = event_bus.send(AppEvent::SystemNotification {
+                    title: "StockenBoard".to_string(),
+                    body: tr(Msg::RecordStalled, &[&error.symbol, &stall_minutes]),
+                });
+                let _ = event_bus.send(AppEvent::RecordError(error));
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(id: i64, from_hour: u32, to_hour: u32) -> (String, String, RecordTarget) {
+        let target = RecordTarget { subscription_id: id, is_dex: false, from_hour, to_hour, dedup_secs: 0 };
+        ("binance".to_string(), format!("SYM{}", id), target)
+    }
+
+    #[test]
+    fn failures_accumulate_per_subscription() {
+        let error = |id: i64, at: i64| RecordError {
+            subscription_id: id,
+            provider_id: "binance".to_string(),
+            symbol: "BTC".to_string(),
+            kind: RecordErrorKind::WriteFailed,
+            error: format!("locked at {}", at),
+            occurred_at: at,
+        };
+        record_failure(&error(-71, 1));
+        record_failure(&error(-71, 2));
+        assert_eq!(
+            health(-71),
+            RecordHealth { failures: 2, last_error: Some("locked at 2".to_string()), last_failure_at: Some(2) }
+        );
+        assert_eq!(health(-72), RecordHealth::default());
+    }
+
+    #[test]
+    fn stall_alerts_once_until_recording_resumes() {
+        let mut tracker = StallTracker::default();
+        let targets = vec![target(1, 0, 24), target(2, 9, 17)];
+        let stall = 600;
+        let mut last = HashMap::from([(1, 1_000)]);
+
+        // 第一次看到的訂閱從現在起算，不會因舊紀錄立即提醒
+        assert!(tracker.check(&targets, &last, 10_000, 20, stall).is_empty());
+        let stalled = tracker.check(&targets, &last, 10_600, 20, stall);
+        assert_eq!(stalled.iter().map(|e| e.subscription_id).collect::<Vec<_>>(), vec![1]);
+        assert_eq!(stalled[0].kind, RecordErrorKind::Stalled);
+        assert!(tracker.check(&targets, &last, 11_000, 20, stall).is_empty());
+
+        // 恢復寫入後重新計時
+        last.insert(1, 11_100);
+        assert!(tracker.check(&targets, &last, 11_200, 20, stall).is_empty());
+        assert_eq!(tracker.check(&targets, &last, 11_700, 20, stall).len(), 1);
+
+        // 紀錄時段外不提醒，回到時段後重新計時
+        assert!(tracker.check(&targets, &last, 12_000, 10, stall).iter().all(|e| e.subscription_id != 2));
+        assert!(tracker.check(&targets, &last, 12_200, 10, stall).iter().all(|e| e.subscription_id != 2));
+        assert_eq!(
+            tracker.check(&targets, &last, 12_400, 10, stall).iter().map(|e| e.subscription_id).collect::<Vec<_>>(),
+            vec![2]
+        );
+    }
+}
@@ -0,0 +1,252 @@
+//! 價格合理性檢查 — 過濾 provider 偶爾回傳的明顯錯誤報價（0.0、1000 倍的尖峰），
+//! 避免污染快取、歷史紀錄並誤觸通知規則。
+//!
+//! Polling 每次取價成功後，每個 `provider:symbol` 與最近 `window` 筆已接受價格的中位數比較，
+//! 高於中位數 × `max_multiple` 或低於中位數 ÷ `max_multiple` 即拒絕（樣本不足 [`MIN_SAMPLES`] 筆時不比較）；
+//! 已有接受過的價格後，0、負值與非有限值一律拒絕。被拒絕的報價不寫入快取與 PriceUpdate，
+//! 計入該 provider 的異常統計並送出 `AppEvent::PriceAnomaly`（前端事件 `price-anomaly`）。
+//! 連續被拒絕 [`ACCEPT_AFTER`] 次的正常數值視為真實的價格跳動（例如拆股、預測市場結算），
+//! 接受並以新價格重新累積。
+//!
+//! 設定存於 app settings（`price_sanity_*`），預設啟用。
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{LazyLock, RwLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::providers::AssetData;
+
+/// 開始檢查前需要的已接受樣本數
+pub const MIN_SAMPLES: usize = 3;
+/// 連續被拒絕幾次後改為接受
+pub const ACCEPT_AFTER: u32 = 3;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SanityConfig {
+    pub enabled: bool,
+    /// 允許偏離中位數的倍數（> 1）
+    pub max_multiple: f64,
+    /// 計算中位數的最近樣本數
+    pub window: usize,
+}
+
+impl Default for SanityConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_multiple: 10.0,
+            window: 20,
+        }
+    }
+}
+
+impl SanityConfig {
+    /// 檢查設定
+    pub fn normalized(self) -> Result<Self, String> {
+        if !self.max_multiple.is_finite() || self.max_multiple <= 1.0 {
+            return Err("Price sanity multiple must be greater than 1".to_string());
+        }
+        if !(MIN_SAMPLES..=200).contains(&self.window) {
+            return Err(format!("Price sanity window must be between {} and 200", MIN_SAMPLES));
+        }
+        Ok(self)
+    }
+}
+
+/// 一筆被拒絕的報價
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PriceAnomaly {
+    pub provider_id: String,
+    pub symbol: String,
+    pub price: f64,
+    /// 比較用的近期中位數；樣本不足時為 None
+    pub median: Option<f64>,
+    /// Unix ms
+    pub detected_at: i64,
+}
+
+/// 單一 provider 的異常統計
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AnomalyMetrics {
+    pub provider_id: String,
+    pub rejected: u64,
+    pub last: Option<PriceAnomaly>,
+}
+
+#[derive(Debug, Default)]
+struct Window {
+    prices: VecDeque<f64>,
+    rejected_streak: u32,
+}
+
+impl Window {
+    fn accept(&mut self, price: f64, size: usize) {
+        self.rejected_streak = 0;
+        self.prices.push_back(price);
+        while self.prices.len() > size {
+            self.prices.pop_front();
+        }
+    }
+
+    fn median(&self) -> Option<f64> {
+        if self.prices.len() < MIN_SAMPLES {
+            return None;
+        }
+        let mut sorted: Vec<f64> = self.prices.iter().copied().collect();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let mid = sorted.len() / 2;
+        Some(if sorted.len().is_multiple_of(2) {
+            (sorted[mid - 1] + sorted[mid]) / 2.0
+        } else {
+            sorted[mid]
+        })
+    }
+}
+
+/// 各 `provider:symbol` 最近已接受的價格（由 PollingManager 持有）
+#[derive(Debug, Default)]
+pub struct PriceWindows(HashMap<String, Window>);
+
+static SANITY_CONFIG: LazyLock<RwLock<SanityConfig>> = LazyLock::new(|| RwLock::new(SanityConfig::default()));
+static METRICS: LazyLock<RwLock<HashMap<String, AnomalyMetrics>>> = LazyLock::new(|| RwLock::new(HashMap::new()));
+
+pub fn set_sanity_config(config: SanityConfig) {
+    *SANITY_CONFIG.write().unwrap_or_else(|e| e.into_inner()) = config;
+}
+
+pub fn sanity_config() -> SanityConfig {
+    SANITY_CONFIG.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// 各 provider 的異常統計（依 provider_id 排序）
+pub fn metrics() -> Vec<AnomalyMetrics> {
+    let mut metrics: Vec<AnomalyMetrics> =
+        METRICS.read().unwrap_or_else(|e| e.into_inner()).values().cloned().collect();
+    metrics.sort_by(|a, b| a.provider_id.cmp(&b.provider_id));
+    metrics
+}
+
+impl PriceWindows {
+    /// 只保留仍在輪詢的 `provider:symbol` 樣本
+    pub fn retain(&mut self, keys: &HashSet<String>) {
+        self.0.retain(|k, _| keys.contains(k));
+    }
+
+    /// 過濾一批報價，回傳通過檢查的報價與被拒絕的異常（並計入 provider 異常統計）
+    pub fn filter(&mut self, provider_id: &str, results: Vec<AssetData>) -> (Vec<AssetData>, Vec<PriceAnomaly>) {
+        let config = sanity_config();
+        if !config.enabled {
+            return (results, Vec::new());
+        }
+
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut accepted = Vec::with_capacity(results.len());
+        let mut anomalies = Vec::new();
+        for data in results {
+            let window = self.0.entry(format!("{}:{}", provider_id, data.symbol)).or_default();
+            let median = window.median();
+            let invalid = !data.price.is_finite() || (data.price <= 0.0 && !window.prices.is_empty());
+            let outlier = median
+                .is_some_and(|m| data.price <= m / config.max_multiple || data.price >= m * config.max_multiple);
+            if !invalid && outlier && window.rejected_streak + 1 >= ACCEPT_AFTER {
+                // 持續出現的價格視為真實跳動，以新價格重新累積
+                window.prices.clear();
+            } else if invalid || outlier {
+                window.rejected_streak += 1;
+                anomalies.push(PriceAnomaly {
+                    provider_id: provider_id.to_string(),
+                    symbol: data.symbol.clone(),
+                    price: data.price,
+                    median,
+                    detected_at: now,
+                });
+                continue;
+            }
+            window.accept(data.price, config.window);
+            accepted.push(data);
+        }
+        record(provider_id, &anomalies);
+        (accepted, anomalies)
+    }
+}
+
+fn record(provider_id: &str, anomalies: &[PriceAnomaly]) {
+    if let Some(last) = anomalies.last() {
+        tracing::warn!(
+            "[Sanity] {} rejected {} price(s), e.g. {} = {} (median {})",
+            provider_id,
+            anomalies.len(),
+            last.symbol,
+            last.price,
+            last.median.map_or_else(|| "n/a".to_string(), |m| m.to_string())
+        );
+        let mut metrics = METRICS.write().unwrap_or_else(|e| e.into_inner());
+        let entry = metrics.entry(provider_id.to_string()).or_insert_with(|| AnomalyMetrics {
+            provider_id: provider_id.to_string(),
+            rejected: 0,
+            last: None,
+        });
+        entry.rejected += anomalies.len() as u64;
+        entry.last = Some(last.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::types::AssetDataBuilder;
+
+    fn quote(symbol: &str, price: f64) -> Vec<AssetData> {
+        vec![AssetDataBuilder::new(symbol, "sanity-test").price(price).build()]
+    }
+
+    fn passes(windows: &mut PriceWindows, provider_id: &str, symbol: &str, price: f64) -> bool {
+        let (accepted, _) = windows.filter(provider_id, quote(symbol, price));
+        !accepted.is_empty()
+    }
+
+    #[test]
+    fn spikes_and_zero_prints_are_rejected_after_warmup() {
+        let mut w = PriceWindows::default();
+        let pid = "sanity-spike";
+        // 樣本不足時一律接受
+        for price in [100.0, 101.0, 99.0] {
+            assert!(passes(&mut w, pid, "BTC", price));
+        }
+        assert!(!passes(&mut w, pid, "BTC", 100_000.0));
+        assert!(!passes(&mut w, pid, "BTC", 0.0));
+        assert!(passes(&mut w, pid, "BTC", 102.0));
+
+        let m = metrics().into_iter().find(|m| m.provider_id == pid).unwrap();
+        assert_eq!(m.rejected, 2);
+        assert_eq!(m.last.unwrap().price, 0.0);
+    }
+
+    #[test]
+    fn persistent_jump_is_accepted_as_real() {
+        let mut w = PriceWindows::default();
+        let pid = "sanity-split";
+        for price in [500.0, 501.0, 499.0] {
+            assert!(passes(&mut w, pid, "NVDA", price));
+        }
+        // 1:10 拆股後價格持續在 50 附近
+        assert!(!passes(&mut w, pid, "NVDA", 49.0));
+        assert!(!passes(&mut w, pid, "NVDA", 50.0));
+        assert!(passes(&mut w, pid, "NVDA", 50.5));
+        assert!(passes(&mut w, pid, "NVDA", 51.0));
+        // 零價永遠不會被視為真實跳動
+        for _ in 0..ACCEPT_AFTER + 1 {
+            assert!(!passes(&mut w, pid, "NVDA", 0.0));
+        }
+        w.retain(&HashSet::new());
+        assert!(passes(&mut w, pid, "NVDA", 1.0));
+    }
+
+    #[test]
+    fn config_validation() {
+        assert!(SanityConfig { max_multiple: 1.0, ..Default::default() }.normalized().is_err());
+        assert!(SanityConfig { window: 2, ..Default::default() }.normalized().is_err());
+        assert!(SanityConfig::default().normalized().is_ok());
+    }
+}
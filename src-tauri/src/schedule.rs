@@ -0,0 +1,227 @@
+//! 錄製排程 — 在設定的星期與時段內自動開啟 unattended polling，時段外關閉。
+//!
+//! 設定以 JSON 存於 app settings `recording_schedule`。排程只在「進入／離開」時段時切換，
+//! 因此時段內使用者的手動調整會被保留到下一次切換。
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, FixedOffset, Local, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+
+use crate::db::DbPool;
+use crate::polling::PollingManager;
+
+/// 背景 task 檢查排程的間隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 單一時段：`days` 為星期（0 = 週日 … 6 = 週六），`start`/`end` 為 `HH:MM`。
+/// `end` 早於 `start` 表示跨夜，星期以開始當天為準。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduleWindow {
+    pub days: Vec<u8>,
+    pub start: String,
+    pub end: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecordingSchedule {
+    pub enabled: bool,
+    pub windows: Vec<ScheduleWindow>,
+    /// 時段所用的 UTC 偏移（分鐘，例如美東夏令 -240）；None = 系統本地時間
+    #[serde(default)]
+    pub utc_offset_minutes: Option<i32>,
+}
+
+/// 解析 `HH:MM` 為當日分鐘數
+fn parse_hhmm(value: &str) -> Result<u32, String> {
+    let invalid = || format!("Invalid time '{}', expected HH:MM", value);
+    let (h, m) = value.trim().split_once(':').ok_or_else(invalid)?;
+    let h: u32 = h.parse().map_err(|_| invalid())?;
+    let m: u32 = m.parse().map_err(|_| invalid())?;
+    if h > 23 || m > 59 {
+        return Err(invalid());
+    }
+    Ok(h * 60 + m)
+}
+
+impl RecordingSchedule {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(offset) = self.utc_offset_minutes {
+            if !(-14 * 60..=14 * 60).contains(&offset) {
+                return Err("UTC offset must be between -840 and 840 minutes".to_string());
+            }
+        }
+        for window in &self.windows {
+            if window.days.is_empty() {
+                return Err("Each schedule window needs at least one weekday".to_string());
+            }
+            if let Some(day) = window.days.iter().find(|d| **d > 6) {
+                return Err(format!("Invalid weekday {} (0 = Sunday … 6 = Saturday)", day));
+            }
+            let start = parse_hhmm(&window.start)?;
+            let end = parse_hhmm(&window.end)?;
+            if start == end {
+                return Err("Schedule window start and end must differ".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// 排程時區下的目前時間
+    pub fn now(&self) -> DateTime<FixedOffset> {
+        match self.utc_offset_minutes.and_then(|m| FixedOffset::east_opt(m * 60)) {
+            Some(offset) => Utc::now().with_timezone(&offset),
+            None => Local::now().fixed_offset(),
+        }
+    }
+
+    /// `at` 是否落在任一時段內（無效的時段直接略過）
+    pub fn is_active_at(&self, at: DateTime<FixedOffset>) -> bool {
+        let weekday = at.weekday().num_days_from_sunday() as u8;
+        let yesterday = (weekday + 6) % 7;
+        let minute = at.hour() * 60 + at.minute();
+
+        self.windows.iter().any(|w| {
+            let (Ok(start), Ok(end)) = (parse_hhmm(&w.start), parse_hhmm(&w.end)) else {
+                return false;
+            };
+            if start < end {
+                w.days.contains(&weekday) && (start..end).contains(&minute)
+            } else {
+                // 跨夜：開始當天的 start 之後，或隔天的 end 之前
+                (w.days.contains(&weekday) && minute >= start)
+                    || (w.days.contains(&yesterday) && minute < end)
+            }
+        })
+    }
+}
+
+pub fn load_schedule(db: &DbPool) -> RecordingSchedule {
+    db.get_setting("recording_schedule")
+        .ok()
+        .flatten()
+        .and_then(|v| serde_json::from_str(&v).ok())
+        .unwrap_or_default()
+}
+
+/// 錄製排程器 — 背景 task 定期比對排程並切換 `PollingManager::set_unattended`
+pub struct RecordingScheduler {
+    db: Arc<DbPool>,
+    polling: PollingManager,
+    /// 設定變更時喚醒 task 立即重新評估
+    wake: Notify,
+}
+
+impl RecordingScheduler {
+    pub fn new(db: Arc<DbPool>, polling: PollingManager) -> Self {
+        Self {
+            db,
+            polling,
+            wake: Notify::new(),
+        }
+    }
+
+    /// 驗證並儲存排程，立即重新評估
+    pub fn save(&self, schedule: &RecordingSchedule) -> Result<(), String> {
+        schedule.validate()?;
+        let json = serde_json::to_string(schedule).map_err(|e| e.to_string())?;
+        self.db.set_setting("recording_schedule", &json)?;
+        self.wake.notify_one();
+        Ok(())
+    }
+
+    pub fn start(self: &Arc<Self>) {
+        let scheduler = self.clone();
+        tokio::spawn(async move {
+            let mut last_active: Option<bool> = None;
+            loop {
+                let schedule = load_schedule(&scheduler.db);
+                let active = schedule
+                    .enabled
+                    .then(|| schedule.is_active_at(schedule.now()));
+                if active.is_some() && active != last_active {
+                    scheduler.apply(active == Some(true)).await;
+                }
+                last_active = active;
+
+                tokio::select! {
+                    _ = tokio::time::sleep(CHECK_INTERVAL) => {}
+                    _ = scheduler.wake.notified() => {}
+                }
+            }
+        });
+    }
+
+    async fn apply(&self, active: bool) {
+        if active {
+            tracing::info!("[Schedule] Entering recording window, enabling background polling");
+            self.polling.set_unattended(true).await;
+            return;
+        }
+        // 已啟用的通知規則仍需背景 polling
+        let has_enabled_rules = self
+            .db
+            .list_notification_rules()
+            .map(|rules| rules.iter().any(|r| r.enabled))
+            .unwrap_or(false);
+        if !has_enabled_rules {
+            tracing::info!("[Schedule] Leaving recording window, disabling background polling");
+            self.polling.set_unattended(false).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn window(days: &[u8], start: &str, end: &str) -> ScheduleWindow {
+        ScheduleWindow {
+            days: days.to_vec(),
+            start: start.to_string(),
+            end: end.to_string(),
+        }
+    }
+
+    #[test]
+    fn market_hours_and_overnight_windows() {
+        let est = FixedOffset::west_opt(5 * 3600).unwrap();
+        let schedule = RecordingSchedule {
+            enabled: true,
+            windows: vec![
+                window(&[1, 2, 3, 4, 5], "09:30", "16:00"),
+                window(&[5], "22:00", "02:00"),
+            ],
+            utc_offset_minutes: Some(-300),
+        };
+        assert!(schedule.validate().is_ok());
+
+        // 2024-01-08 為週一
+        let at = |d: u32, h: u32, m: u32| est.with_ymd_and_hms(2024, 1, d, h, m, 0).unwrap();
+        assert!(schedule.is_active_at(at(8, 9, 30)));
+        assert!(!schedule.is_active_at(at(8, 16, 0)));
+        assert!(!schedule.is_active_at(at(8, 9, 29)));
+        // 週六白天不在時段內，但週五晚間的跨夜時段延續到週六 02:00
+        assert!(!schedule.is_active_at(at(13, 10, 0)));
+        assert!(schedule.is_active_at(at(12, 23, 0)));
+        assert!(schedule.is_active_at(at(13, 1, 59)));
+        assert!(!schedule.is_active_at(at(13, 2, 0)));
+    }
+
+    #[test]
+    fn validate_rejects_bad_windows() {
+        let bad = |w: ScheduleWindow| RecordingSchedule {
+            enabled: true,
+            windows: vec![w],
+            utc_offset_minutes: None,
+        };
+        assert!(bad(window(&[7], "09:00", "10:00")).validate().is_err());
+        assert!(bad(window(&[], "09:00", "10:00")).validate().is_err());
+        assert!(bad(window(&[1], "24:00", "10:00")).validate().is_err());
+        assert!(bad(window(&[1], "9am", "10:00")).validate().is_err());
+        assert!(bad(window(&[1], "10:00", "10:00")).validate().is_err());
+    }
+}
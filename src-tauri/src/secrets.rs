@@ -0,0 +1,387 @@
+//! Provider API key 加密儲存模組
+//!
+//! `provider_settings.api_key` / `api_secret` 以 AES-256-GCM 加密後寫入 SQLite，
+//! 讀取時透明解密。主金鑰來源依序為：
+//!
+//! 1. `SB_SECRETS_PASSPHRASE` 環境變數（明確指定的 passphrase 模式）
+//! 2. OS keychain（macOS Keychain / Windows Credential Manager / Secret Service），
+//!    首次使用時隨機產生並寫入
+//!
+//! 無 keychain 的平台（例如 Docker 容器）必須設定 `SB_SECRETS_PASSPHRASE`，否則加解密回傳錯誤；
+//! 取得金鑰失敗不會快取，下次呼叫重試，不會改用其他金鑰。`DbPool::open` 以
+//! [`key_check`] / [`verify_key_check`] 記錄並比對金鑰來源，金鑰不符時直接失敗。
+//!
+//! 密文格式：`enc:v1:` + base64(nonce || ciphertext)。不帶前綴的值視為舊版明文，
+//! 解密時原樣回傳，由 `DbPool::open` 的 migration 重新加密。
+//!
+//! 另提供 [`SecretStore`] 抽象：啟用 keychain 後端（`migrate_secrets_to_keyring`）時，
+//! secret 值完全不寫入 DB，欄位只保留 [`KEYRING_MARKER`]。
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+
+/// 密文前綴（含格式版本）
+pub const ENCRYPTED_PREFIX: &str = "enc:v1:";
+
+/// keychain 中的 service / entry 名稱
+const KEYRING_SERVICE: &str = "stockenboard";
+#[cfg(not(test))]
+const KEYRING_MASTER_KEY: &str = "provider-secrets-master-key";
+
+/// 明確指定 passphrase 的環境變數
+const PASSPHRASE_ENV: &str = "SB_SECRETS_PASSPHRASE";
+
+/// passphrase 衍生金鑰用的固定 salt 與迭代次數
+const PASSPHRASE_SALT: &[u8] = b"stockenboard-provider-secrets-v1";
+const PBKDF2_ROUNDS: u32 = 100_000;
+
+/// AES-GCM nonce 長度（96 bits）
+const NONCE_LEN: usize = 12;
+
+/// 金鑰檢查值的明文，用來確認目前的主金鑰與加密既有資料的金鑰相同
+const KEY_CHECK_PLAINTEXT: &str = "stockenboard-key-check";
+
+/// 主金鑰來源
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeySource {
+    Keychain,
+    Passphrase,
+}
+
+impl KeySource {
+    /// 寫入 app_settings 的名稱
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Keychain => "keychain",
+            Self::Passphrase => "passphrase",
+        }
+    }
+
+    /// 金鑰不符時提示使用者如何找回原本的金鑰
+    fn recovery_hint(source: &str) -> String {
+        match source {
+            "passphrase" => format!("set {} to the original passphrase", PASSPHRASE_ENV),
+            "keychain" => "run with the OS keychain that holds the original key".to_string(),
+            _ => format!("restore the original master key or set {}", PASSPHRASE_ENV),
+        }
+    }
+}
+
+struct SecretCipher {
+    cipher: Aes256Gcm,
+    source: KeySource,
+}
+
+/// 已取得的主金鑰；取得失敗時維持 None，下次呼叫重試
+static CIPHER: RwLock<Option<Arc<SecretCipher>>> = RwLock::new(None);
+
+fn cipher() -> Result<Arc<SecretCipher>, String> {
+    if let Some(cipher) = CIPHER.read().unwrap().as_ref() {
+        return Ok(cipher.clone());
+    }
+    let mut slot = CIPHER.write().unwrap();
+    if let Some(cipher) = slot.as_ref() {
+        return Ok(cipher.clone());
+    }
+    let (key, source) = resolve_master_key()?;
+    let cipher = Arc::new(SecretCipher {
+        cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)),
+        source,
+    });
+    *slot = Some(cipher.clone());
+    Ok(cipher)
+}
+
+/// 目前使用中的主金鑰來源
+pub fn key_source() -> Result<KeySource, String> {
+    Ok(cipher()?.source)
+}
+
+#[cfg(not(test))]
+fn resolve_master_key() -> Result<([u8; 32], KeySource), String> {
+    select_master_key(std::env::var(PASSPHRASE_ENV).ok(), load_or_create_keychain_key)
+}
+
+/// 單元測試使用固定金鑰，不碰 OS keychain
+#[cfg(test)]
+fn resolve_master_key() -> Result<([u8; 32], KeySource), String> {
+    Ok(([7u8; 32], KeySource::Passphrase))
+}
+
+/// 依序選擇 passphrase、keychain；兩者皆無時回傳錯誤，不退回其他金鑰。
+fn select_master_key(
+    passphrase: Option<String>,
+    keychain: impl FnOnce() -> Result<[u8; 32], String>,
+) -> Result<([u8; 32], KeySource), String> {
+    if let Some(passphrase) = passphrase.filter(|p| !p.is_empty()) {
+        return Ok((derive_from_passphrase(&passphrase), KeySource::Passphrase));
+    }
+    keychain().map(|key| (key, KeySource::Keychain)).map_err(|e| {
+        format!(
+            "OS keychain unavailable ({}); set {} to encrypt API keys with a passphrase",
+            e, PASSPHRASE_ENV
+        )
+    })
+}
+
+fn derive_from_passphrase(passphrase: &str) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<sha2::Sha256>(
+        passphrase.as_bytes(),
+        PASSPHRASE_SALT,
+        PBKDF2_ROUNDS,
+        &mut key,
+    );
+    key
+}
+
+#[cfg(not(test))]
+fn load_or_create_keychain_key() -> Result<[u8; 32], String> {
+    let entry = keyring::Entry::new(KEYRING_SERVICE, KEYRING_MASTER_KEY)
+        .map_err(|e| e.to_string())?;
+    match entry.get_password() {
+        Ok(encoded) => {
+            let bytes = BASE64
+                .decode(encoded.trim())
+                .map_err(|e| format!("Invalid master key in keychain: {}", e))?;
+            bytes
+                .try_into()
+                .map_err(|_| "Invalid master key length in keychain".to_string())
+        }
+        Err(keyring::Error::NoEntry) => {
+            let key = Aes256Gcm::generate_key(OsRng);
+            entry
+                .set_password(&BASE64.encode(key))
+                .map_err(|e| e.to_string())?;
+            Ok(key.into())
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+// ── Secret Store ────────────────────────────────────────────────
+
+/// DB 欄位中代表「值存放於 secret store」的標記
+pub const KEYRING_MARKER: &str = "keyring:";
+
+/// 值是否存放於 secret store
+pub fn is_keyring_ref(value: &str) -> bool {
+    value == KEYRING_MARKER
+}
+
+/// provider secret 在 secret store 中的帳號名稱
+pub fn secret_account(provider_id: &str, field: &str) -> String {
+    format!("provider:{}:{}", provider_id, field)
+}
+
+/// Secret 儲存後端
+pub trait SecretStore: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn get(&self, account: &str) -> Result<Option<String>, String>;
+    fn set(&self, account: &str, value: &str) -> Result<(), String>;
+    fn delete(&self, account: &str) -> Result<(), String>;
+}
+
+/// OS keychain 後端（keyring crate）
+pub struct KeyringStore;
+
+impl SecretStore for KeyringStore {
+    fn name(&self) -> &'static str {
+        "keyring"
+    }
+
+    fn get(&self, account: &str) -> Result<Option<String>, String> {
+        let entry = keyring::Entry::new(KEYRING_SERVICE, account).map_err(|e| e.to_string())?;
+        match entry.get_password() {
+            Ok(v) => Ok(Some(v)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    fn set(&self, account: &str, value: &str) -> Result<(), String> {
+        keyring::Entry::new(KEYRING_SERVICE, account)
+            .and_then(|entry| entry.set_password(value))
+            .map_err(|e| e.to_string())
+    }
+
+    fn delete(&self, account: &str) -> Result<(), String> {
+        let entry = keyring::Entry::new(KEYRING_SERVICE, account).map_err(|e| e.to_string())?;
+        match entry.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
+
+/// 記憶體後端（測試與無 keychain 環境用）
+#[derive(Default)]
+pub struct MemoryStore {
+    values: Mutex<HashMap<String, String>>,
+}
+
+impl SecretStore for MemoryStore {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    fn get(&self, account: &str) -> Result<Option<String>, String> {
+        Ok(self.values.lock().unwrap().get(account).cloned())
+    }
+
+    fn set(&self, account: &str, value: &str) -> Result<(), String> {
+        self.values
+            .lock()
+            .unwrap()
+            .insert(account.to_string(), value.to_string());
+        Ok(())
+    }
+
+    fn delete(&self, account: &str) -> Result<(), String> {
+        self.values.lock().unwrap().remove(account);
+        Ok(())
+    }
+}
+
+/// 值是否已為加密格式
+pub fn is_encrypted(value: &str) -> bool {
+    value.starts_with(ENCRYPTED_PREFIX)
+}
+
+/// 加密 secret，回傳帶前綴的密文；空字串原樣回傳。
+pub fn encrypt_secret(plaintext: &str) -> Result<String, String> {
+    if plaintext.is_empty() || is_encrypted(plaintext) {
+        return Ok(plaintext.to_string());
+    }
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher()?
+        .cipher
+        .encrypt(&nonce, plaintext.as_bytes())
+        .map_err(|e| format!("Encryption failed: {}", e))?;
+    let mut payload = nonce.to_vec();
+    payload.extend_from_slice(&ciphertext);
+    Ok(format!("{}{}", ENCRYPTED_PREFIX, BASE64.encode(payload)))
+}
+
+/// 解密 secret；不帶前綴的舊版明文原樣回傳。
+pub fn decrypt_secret(stored: &str) -> Result<String, String> {
+    let Some(encoded) = stored.strip_prefix(ENCRYPTED_PREFIX) else {
+        return Ok(stored.to_string());
+    };
+    let payload = BASE64
+        .decode(encoded)
+        .map_err(|e| format!("Base64 decode failed: {}", e))?;
+    if payload.len() <= NONCE_LEN {
+        return Err("Ciphertext too short".to_string());
+    }
+    let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+    let plaintext = cipher()?
+        .cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "Decryption failed (master key changed?)".to_string())?;
+    String::from_utf8(plaintext).map_err(|e| format!("UTF-8 decode failed: {}", e))
+}
+
+/// 以目前主金鑰產生金鑰檢查值，與 [`KeySource::as_str`] 一起記錄於 DB
+pub fn key_check() -> Result<String, String> {
+    encrypt_secret(KEY_CHECK_PLAINTEXT)
+}
+
+/// 確認目前主金鑰能解開 `stored_source` 金鑰產生的檢查值，否則回傳說明如何修復的錯誤。
+pub fn verify_key_check(stored_source: &str, check: &str) -> Result<(), String> {
+    let current = key_source()?;
+    if decrypt_secret(check).is_ok_and(|v| v == KEY_CHECK_PLAINTEXT) {
+        return Ok(());
+    }
+    Err(format!(
+        "Stored API keys were encrypted with a different {} master key than the current {} key; {}",
+        stored_source,
+        current.as_str(),
+        KeySource::recovery_hint(stored_source)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_decrypt_roundtrip() {
+        let encrypted = encrypt_secret("sk-live-1234567890").unwrap();
+        assert!(is_encrypted(&encrypted));
+        assert_eq!(decrypt_secret(&encrypted).unwrap(), "sk-live-1234567890");
+    }
+
+    #[test]
+    fn test_encryption_uses_random_nonce() {
+        let a = encrypt_secret("same-key").unwrap();
+        let b = encrypt_secret("same-key").unwrap();
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_legacy_plaintext_passes_through() {
+        assert_eq!(decrypt_secret("plain-api-key").unwrap(), "plain-api-key");
+        assert_eq!(encrypt_secret("").unwrap(), "");
+    }
+
+    #[test]
+    fn test_already_encrypted_not_double_encrypted() {
+        let encrypted = encrypt_secret("abc").unwrap();
+        assert_eq!(encrypt_secret(&encrypted).unwrap(), encrypted);
+    }
+
+    #[test]
+    fn test_tampered_ciphertext_returns_error() {
+        let encrypted = encrypt_secret("abc").unwrap();
+        let mut tampered = encrypted.clone();
+        tampered.pop();
+        tampered.push(if encrypted.ends_with('A') { 'B' } else { 'A' });
+        assert!(decrypt_secret(&tampered).is_err());
+        assert!(decrypt_secret("enc:v1:AAAA").is_err());
+    }
+
+    #[test]
+    fn test_select_master_key_never_falls_back() {
+        let keychain_key = [1u8; 32];
+        let (key, source) =
+            select_master_key(Some("pass".into()), || panic!("keychain not used")).unwrap();
+        assert_eq!(source, KeySource::Passphrase);
+        assert_eq!(key, derive_from_passphrase("pass"));
+
+        let (key, source) = select_master_key(Some(String::new()), || Ok(keychain_key)).unwrap();
+        assert_eq!((key, source), (keychain_key, KeySource::Keychain));
+
+        let err = select_master_key(None, || Err("no secret service".into())).unwrap_err();
+        assert!(err.contains("no secret service") && err.contains(PASSPHRASE_ENV), "{err}");
+    }
+
+    #[test]
+    fn test_key_check_detects_other_key() {
+        let check = key_check().unwrap();
+        assert!(verify_key_check("passphrase", &check).is_ok());
+
+        let other = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&[9u8; 32]));
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let mut payload = nonce.to_vec();
+        payload.extend(other.encrypt(&nonce, KEY_CHECK_PLAINTEXT.as_bytes()).unwrap());
+        let foreign = format!("{}{}", ENCRYPTED_PREFIX, BASE64.encode(payload));
+        let err = verify_key_check("keychain", &foreign).unwrap_err();
+        assert!(err.contains("OS keychain"), "{err}");
+    }
+
+    #[test]
+    fn test_memory_store_roundtrip() {
+        let store = MemoryStore::default();
+        let account = secret_account("alpaca", "api_secret");
+        assert_eq!(store.get(&account).unwrap(), None);
+        store.set(&account, "s3cret").unwrap();
+        assert_eq!(store.get(&account).unwrap().as_deref(), Some("s3cret"));
+        store.delete(&account).unwrap();
+        assert_eq!(store.get(&account).unwrap(), None);
+    }
+}
@@ -0,0 +1,414 @@
+//! 裝置間設定同步（選用）— 讓桌機與筆電共用同一份 watchlist。
+//!
+//! 同步內容為不含 secret 的 app 設定（[`AppConfig`]：訂閱、view（tag）、通知規則與 channel、
+//! provider 設定與 app settings），包成帶版本的 [`SyncFile`] 寫到 WebDAV URL 或本機同步資料夾
+//! （Dropbox、Syncthing、iCloud Drive 等）中的 [`SYNC_FILE_NAME`]。
+//!
+//! 衝突處理為 last-writer-wins：本機設定的變更時間以每 [`CHECK_INTERVAL_SECS`] 秒比對一次
+//! 設定內容的 hash 取得；遠端檔案自上次同步後有更新且比本機變更新時拉取（以 `Replace` 模式匯入，
+//! 同一訂閱的價格紀錄會保留），否則推送本機設定。首次同步時若遠端已有檔案則以遠端為準。
+//!
+//! 設定存於 app settings（`sync_*`，不隨設定匯出），WebDAV 密碼以 [`crate::secrets`] 加密保存。
+
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock, RwLock};
+use std::time::Duration;
+
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::core_state::CoreState;
+use crate::db::{AppConfig, ConfigImportMode};
+
+/// 同步檔格式版本
+pub const SYNC_FORMAT_VERSION: u32 = 1;
+/// 同步檔名（location 為資料夾或 WebDAV collection 時附加）
+pub const SYNC_FILE_NAME: &str = "stockenboard-sync.json";
+/// 本機變更檢查間隔
+pub const CHECK_INTERVAL_SECS: u64 = 60;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SyncConfig {
+    pub enabled: bool,
+    /// WebDAV URL（`https://…`）或本機資料夾路徑
+    pub location: String,
+    /// WebDAV 帳號（資料夾模式不使用）
+    pub username: String,
+    /// 讀取時不回傳；更新時留空表示沿用已儲存的值
+    pub password: String,
+    pub interval_minutes: u32,
+}
+
+impl Default for SyncConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            location: String::new(),
+            username: String::new(),
+            password: String::new(),
+            interval_minutes: 5,
+        }
+    }
+}
+
+impl SyncConfig {
+    pub fn normalized(mut self) -> Result<Self, String> {
+        self.location = self.location.trim().to_string();
+        self.username = self.username.trim().to_string();
+        if !(1..=1440).contains(&self.interval_minutes) {
+            return Err("Sync interval must be between 1 and 1440 minutes".to_string());
+        }
+        if self.enabled && self.location.is_empty() {
+            return Err("Sync location is required".to_string());
+        }
+        Ok(self)
+    }
+
+    /// 回傳給前端的版本（不含密碼）
+    pub fn redacted(mut self) -> Self {
+        self.password.clear();
+        self
+    }
+
+    fn is_webdav(&self) -> bool {
+        self.location.starts_with("http://") || self.location.starts_with("https://")
+    }
+
+    /// 同步檔的完整位置（location 已指向 `.json` 檔時直接使用）
+    fn file_location(&self) -> String {
+        if self.location.ends_with(".json") {
+            return self.location.clone();
+        }
+        let sep = if self.is_webdav() { "/" } else { std::path::MAIN_SEPARATOR_STR };
+        format!("{}{}{}", self.location.trim_end_matches(['/', '\\']), sep, SYNC_FILE_NAME)
+    }
+}
+
+static SYNC_CONFIG: LazyLock<RwLock<SyncConfig>> = LazyLock::new(|| RwLock::new(SyncConfig::default()));
+static SYNC_STATUS: LazyLock<RwLock<SyncStatus>> = LazyLock::new(|| RwLock::new(SyncStatus::default()));
+
+pub fn set_sync_config(config: SyncConfig) {
+    *SYNC_CONFIG.write().unwrap_or_else(|e| e.into_inner()) = config;
+}
+
+pub fn sync_config() -> SyncConfig {
+    SYNC_CONFIG.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+pub fn sync_status() -> SyncStatus {
+    SYNC_STATUS.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// 寫到同步位置的檔案
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncFile {
+    pub format: u32,
+    /// 寫入此檔的裝置
+    pub device_id: String,
+    /// 寫入時間（Unix ms）
+    pub updated_at: i64,
+    pub config: AppConfig,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncAction {
+    Pushed,
+    Pulled,
+    UpToDate,
+}
+
+/// 最近一次同步的結果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SyncStatus {
+    /// Unix ms
+    pub last_synced_at: Option<i64>,
+    pub last_action: Option<SyncAction>,
+    pub last_error: Option<String>,
+    /// 遠端檔案最後由哪台裝置寫入
+    pub remote_device_id: Option<String>,
+}
+
+/// last-writer-wins 判斷。
+///
+/// `local_changed_at`：本機設定自上次同步後的變更時間（未變更為 None）；
+/// `remote_updated_at`：遠端檔案的寫入時間（檔案不存在為 None）；`last_remote_at`：上次同步時的遠端時間。
+pub fn decide(local_changed_at: Option<i64>, remote_updated_at: Option<i64>, last_remote_at: i64) -> SyncAction {
+    let Some(remote_at) = remote_updated_at else {
+        return SyncAction::Pushed;
+    };
+    match (local_changed_at, remote_at > last_remote_at) {
+        (None, false) => SyncAction::UpToDate,
+        (Some(_), false) => SyncAction::Pushed,
+        (None, true) => SyncAction::Pulled,
+        (Some(local_at), true) if remote_at > local_at => SyncAction::Pulled,
+        (Some(_), true) => SyncAction::Pushed,
+    }
+}
+
+/// 設定內容的 hash（不含匯出時間）
+fn config_hash(config: &AppConfig) -> String {
+    let mut config = config.clone();
+    config.exported_at = 0;
+    let json = serde_json::to_vec(&config).unwrap_or_default();
+    Sha256::digest(&json).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 本裝置的識別碼（首次使用時產生並保存）
+fn device_id(state: &CoreState) -> Result<String, String> {
+    if let Some(id) = state.db.get_setting("sync_device_id")?.filter(|v| !v.is_empty()) {
+        return Ok(id);
+    }
+    let mut bytes = [0u8; 8];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| "Failed to generate device id".to_string())?;
+    let id: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    state.db.set_setting("sync_device_id", &id)?;
+    Ok(id)
+}
+
+// ── 讀寫同步檔 ──────────────────────────────────────────────────
+
+async fn read_remote(config: &SyncConfig) -> Result<Option<SyncFile>, String> {
+    let location = config.file_location();
+    let bytes = if config.is_webdav() {
+        let resp = crate::providers::shared_client()
+            .get(&location)
+            .basic_auth(&config.username, Some(&config.password))
+            .timeout(Duration::from_secs(30))
+            .send()
+            .await
+            .map_err(|e| format!("WebDAV request failed: {}", e))?;
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !resp.status().is_success() {
+            return Err(format!("WebDAV GET returned HTTP {}", resp.status()));
+        }
+        resp.bytes()
+            .await
+            .map_err(|e| format!("Failed to read WebDAV response: {}", e))?
+            .to_vec()
+    } else {
+        match tokio::fs::read(&location).await {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("Failed to read {}: {}", location, e)),
+        }
+    };
+    let file: SyncFile = serde_json::from_slice(&bytes).map_err(|e| format!("Invalid sync file: {}", e))?;
+    if file.format > SYNC_FORMAT_VERSION {
+        return Err(format!(
+            "Sync file format {} is newer than this app supports ({})",
+            file.format, SYNC_FORMAT_VERSION
+        ));
+    }
+    Ok(Some(file))
+}
+
+async fn write_remote(config: &SyncConfig, file: &SyncFile) -> Result<(), String> {
+    let location = config.file_location();
+    let bytes = serde_json::to_vec_pretty(file).map_err(|e| e.to_string())?;
+    if config.is_webdav() {
+        let resp = crate::providers::shared_client()
+            .put(&location)
+            .basic_auth(&config.username, Some(&config.password))
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(bytes)
+            .timeout(Duration::from_secs(30))
+            .send()
+            .await
+            .map_err(|e| format!("WebDAV request failed: {}", e))?;
+        if !resp.status().is_success() {
+            return Err(format!("WebDAV PUT returned HTTP {}", resp.status()));
+        }
+        return Ok(());
+    }
+    // 先寫暫存檔再 rename，同步工具不會讀到寫到一半的檔案
+    let path = PathBuf::from(&location);
+    let tmp = path.with_extension("json.tmp");
+    tokio::fs::write(&tmp, bytes)
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+    tokio::fs::rename(&tmp, &path)
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", location, e))
+}
+
+// ── 同步 ────────────────────────────────────────────────────────
+
+/// 本機設定的變更時間；與上次同步時的 hash 相同時為 None。
+///
+/// 從未同步過的裝置視為未變更，加入既有的同步位置時以遠端為準。
+fn local_change(state: &CoreState, local: &AppConfig, detected_at: Option<i64>) -> Option<i64> {
+    let last_hash = state.db.get_setting("sync_last_hash").ok().flatten()?;
+    (last_hash != config_hash(local))
+        .then(|| detected_at.unwrap_or_else(|| chrono::Utc::now().timestamp_millis()))
+}
+
+/// 執行一次同步。`local_changed_at` 為背景檢查偵測到本機變更的時間（手動同步時為 None，以現在時間計）。
+pub async fn sync_now(
+    state: &CoreState,
+    config: &SyncConfig,
+    local_changed_at: Option<i64>,
+) -> Result<SyncAction, String> {
+    if config.location.is_empty() {
+        return Err("Sync location is not configured".to_string());
+    }
+    let result = sync_inner(state, config, local_changed_at).await;
+    let mut status = SYNC_STATUS.write().unwrap_or_else(|e| e.into_inner());
+    match &result {
+        Ok((action, remote_device)) => {
+            status.last_synced_at = Some(chrono::Utc::now().timestamp_millis());
+            status.last_action = Some(*action);
+            status.last_error = None;
+            status.remote_device_id = remote_device.clone();
+        }
+        Err(e) => status.last_error = Some(e.clone()),
+    }
+    result.map(|(action, _)| action)
+}
+
+async fn sync_inner(
+    state: &CoreState,
+    config: &SyncConfig,
+    local_changed_at: Option<i64>,
+) -> Result<(SyncAction, Option<String>), String> {
+    let device = device_id(state)?;
+    let local = state.db.export_app_config(false)?;
+    let local_changed_at = local_change(state, &local, local_changed_at);
+    let remote = read_remote(config).await?;
+    let last_remote_at = state
+        .db
+        .get_setting("sync_last_remote_at")?
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+
+    let action = decide(local_changed_at, remote.as_ref().map(|f| f.updated_at), last_remote_at);
+    let remote_device = match action {
+        SyncAction::UpToDate => remote.map(|f| f.device_id),
+        SyncAction::Pulled => {
+            let file = remote.expect("pull requires a remote file");
+            state.import_app_config(&file.config, ConfigImportMode::Replace).await?;
+            let imported = state.db.export_app_config(false)?;
+            state.db.set_setting("sync_last_hash", &config_hash(&imported))?;
+            state.db.set_setting("sync_last_remote_at", &file.updated_at.to_string())?;
+            tracing::info!("[Sync] Pulled settings written by {}", file.device_id);
+            Some(file.device_id)
+        }
+        SyncAction::Pushed => {
+            // 寫入時間至少比遠端新，避免裝置時鐘誤差讓其他裝置忽略這次更新
+            let updated_at = chrono::Utc::now()
+                .timestamp_millis()
+                .max(remote.as_ref().map_or(0, |f| f.updated_at + 1));
+            let file = SyncFile {
+                format: SYNC_FORMAT_VERSION,
+                device_id: device.clone(),
+                updated_at,
+                config: local,
+            };
+            write_remote(config, &file).await?;
+            state.db.set_setting("sync_last_hash", &config_hash(&file.config))?;
+            state.db.set_setting("sync_last_remote_at", &updated_at.to_string())?;
+            tracing::info!("[Sync] Pushed settings to {}", config.file_location());
+            Some(device)
+        }
+    };
+    Ok((action, remote_device))
+}
+
+/// 啟動背景同步（未啟用時只會閒置）
+pub fn start(state: Arc<CoreState>) {
+    tokio::spawn(async move {
+        // 偵測到本機變更的時間與當時的 hash
+        let mut pending: Option<(String, i64)> = None;
+        let mut last_sync = 0i64;
+        loop {
+            tokio::time::sleep(Duration::from_secs(CHECK_INTERVAL_SECS)).await;
+            let config = sync_config();
+            if !config.enabled {
+                continue;
+            }
+            let now = chrono::Utc::now().timestamp_millis();
+            if let Ok(local) = state.db.export_app_config(false) {
+                let hash = config_hash(&local);
+                if pending.as_ref().is_none_or(|(h, _)| *h != hash) {
+                    pending = Some((hash, now));
+                }
+            }
+            if now - last_sync < config.interval_minutes as i64 * 60_000 {
+                continue;
+            }
+            last_sync = now;
+            match sync_now(&state, &config, pending.as_ref().map(|(_, at)| *at)).await {
+                Ok(_) => pending = None,
+                Err(e) => tracing::warn!("[Sync] Settings sync failed: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn last_writer_wins() {
+        assert_eq!(decide(None, None, 0), SyncAction::Pushed);
+        assert_eq!(decide(None, Some(100), 100), SyncAction::UpToDate);
+        assert_eq!(decide(Some(150), Some(100), 100), SyncAction::Pushed);
+        assert_eq!(decide(None, Some(200), 100), SyncAction::Pulled);
+        // 兩邊都變更：較晚的一方勝出
+        assert_eq!(decide(Some(150), Some(200), 100), SyncAction::Pulled);
+        assert_eq!(decide(Some(250), Some(200), 100), SyncAction::Pushed);
+    }
+
+    #[test]
+    fn config_validation() {
+        let config = SyncConfig {
+            enabled: true,
+            location: " https://dav.example.com/sb/ ".to_string(),
+            ..Default::default()
+        }
+        .normalized()
+        .unwrap();
+        assert_eq!(config.file_location(), "https://dav.example.com/sb/stockenboard-sync.json");
+        assert!(SyncConfig { enabled: true, ..Default::default() }.normalized().is_err());
+        assert!(SyncConfig { interval_minutes: 0, ..Default::default() }.normalized().is_err());
+    }
+
+    #[tokio::test]
+    async fn folder_sync_shares_watchlist_between_devices() {
+        let folder = tempfile::tempdir().unwrap();
+        let config = SyncConfig {
+            enabled: true,
+            location: folder.path().to_string_lossy().to_string(),
+            ..Default::default()
+        };
+        let (dir_a, dir_b) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let desktop = CoreState::new(dir_a.path()).unwrap();
+        let laptop = CoreState::new(dir_b.path()).unwrap();
+
+        desktop
+            .db
+            .add_subscription("asset", "BTC", None, "binance", "crypto", None, None, None)
+            .unwrap();
+        assert_eq!(sync_now(&desktop, &config, None).await.unwrap(), SyncAction::Pushed);
+        assert_eq!(sync_now(&laptop, &config, None).await.unwrap(), SyncAction::Pulled);
+        let symbols = |state: &CoreState| -> Vec<String> {
+            state.db.list_all_subscriptions().unwrap().into_iter().map(|s| s.symbol).collect()
+        };
+        assert_eq!(symbols(&laptop), ["BTC"]);
+        assert_eq!(sync_now(&laptop, &config, None).await.unwrap(), SyncAction::UpToDate);
+
+        // 筆電刪除訂閱後推送，桌機拉取時一併刪除
+        let id = laptop.db.list_all_subscriptions().unwrap()[0].id;
+        laptop.db.remove_subscription(id).unwrap();
+        assert_eq!(sync_now(&laptop, &config, None).await.unwrap(), SyncAction::Pushed);
+        assert_eq!(sync_now(&desktop, &config, None).await.unwrap(), SyncAction::Pulled);
+        assert!(symbols(&desktop).is_empty());
+    }
+}
@@ -0,0 +1,275 @@
+//! Watchlist 快照圖 — 把目前價格畫成 PNG 表格（含 24h 走勢線），方便分享到聊天室或附在通知中。
+//!
+//! 在後端以 `image` 直接繪製，不依賴 webview；文字使用內建的 5×7 點陣字型，
+//! 只支援 ASCII 大寫字母、數字與常用符號（顯示名稱含其他字元時改用 symbol）。
+
+use std::collections::HashMap;
+use std::io::Cursor;
+
+use image::{Rgb, RgbImage};
+
+use crate::db::DbPool;
+use crate::providers::AssetData;
+
+/// 單張快照最多列數
+pub const MAX_ROWS: usize = 30;
+/// 走勢線取樣點數
+const SPARKLINE_POINTS: usize = 48;
+/// 走勢線回溯時間（秒）
+const SPARKLINE_WINDOW_SECS: i64 = 24 * 3600;
+
+const WIDTH: u32 = 720;
+const PADDING: i32 = 16;
+const HEADER_HEIGHT: i32 = 44;
+const ROW_HEIGHT: i32 = 36;
+/// 點陣字放大倍數（字高 14px）
+const SCALE: i32 = 2;
+const GLYPH_ADVANCE: i32 = 6 * SCALE;
+const LABEL_CHARS: usize = 16;
+
+const BACKGROUND: Rgb<u8> = Rgb([17, 24, 39]);
+const SEPARATOR: Rgb<u8> = Rgb([31, 41, 55]);
+const TEXT: Rgb<u8> = Rgb([229, 231, 235]);
+const MUTED: Rgb<u8> = Rgb([156, 163, 175]);
+const UP: Rgb<u8> = Rgb([34, 197, 94]);
+const DOWN: Rgb<u8> = Rgb([239, 68, 68]);
+
+/// 快照中的一列
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotRow {
+    pub label: String,
+    pub price: Option<String>,
+    pub change_pct: Option<f64>,
+    /// 24h 價格（舊到新）
+    pub sparkline: Vec<f64>,
+}
+
+/// 依 subscription 順序整理快照資料：價格取自 polling 快取，走勢線取自紀錄
+pub fn collect_rows(
+    db: &DbPool,
+    cache: &HashMap<String, AssetData>,
+    subscription_ids: &[i64],
+    now_secs: i64,
+) -> Result<Vec<SnapshotRow>, String> {
+    let subs = db.list_all_subscriptions()?;
+    let mut rows = Vec::new();
+    for id in subscription_ids.iter().take(MAX_ROWS) {
+        let Some(sub) = subs.iter().find(|s| s.id == *id) else {
+            continue;
+        };
+        let label = sub
+            .display_name
+            .as_deref()
+            .filter(|name| !name.is_empty() && name.is_ascii())
+            .unwrap_or(&sub.symbol)
+            .to_string();
+        let data = cache.get(&format!("{}:{}", sub.selected_provider_id, sub.symbol));
+        let sparkline = crate::sparkline::load(db, sub.id, Some(SPARKLINE_POINTS), SPARKLINE_WINDOW_SECS, now_secs)?;
+        rows.push(SnapshotRow {
+            label,
+            price: data.map(|d| crate::clipboard::format_price(d.price, d.display_scale())),
+            change_pct: data.and_then(|d| d.change_percent_24h),
+            sparkline: sparkline.points,
+        });
+    }
+    if rows.is_empty() {
+        return Err("No subscriptions to render".to_string());
+    }
+    Ok(rows)
+}
+
+/// PNG 轉成 `data:` URL（與 `read_local_file_base64` 相同格式）
+pub fn data_url(png: &[u8]) -> String {
+    use base64::Engine;
+    format!("data:image/png;base64,{}", base64::engine::general_purpose::STANDARD.encode(png))
+}
+
+/// 繪製快照 PNG；`title` 顯示在左上角，時間（UTC）顯示在右上角
+pub fn render_png(title: &str, rows: &[SnapshotRow], now_secs: i64) -> Result<Vec<u8>, String> {
+    let height = (HEADER_HEIGHT + ROW_HEIGHT * rows.len() as i32 + PADDING) as u32;
+    let mut img = RgbImage::from_pixel(WIDTH, height, BACKGROUND);
+
+    let text_y = (HEADER_HEIGHT - 7 * SCALE) / 2;
+    draw_text(&mut img, PADDING, text_y, title, TEXT);
+    if let Some(time) = chrono::DateTime::from_timestamp(now_secs, 0) {
+        let stamp = time.format("%Y-%m-%d %H:%M UTC").to_string();
+        draw_text_right(&mut img, WIDTH as i32 - PADDING, text_y, &stamp, MUTED);
+    }
+
+    let spark_left = WIDTH as i32 - PADDING - 160;
+    for (i, row) in rows.iter().enumerate() {
+        let top = HEADER_HEIGHT + ROW_HEIGHT * i as i32;
+        fill_rect(&mut img, PADDING, top, WIDTH as i32 - 2 * PADDING, 1, SEPARATOR);
+        let y = top + (ROW_HEIGHT - 7 * SCALE) / 2;
+
+        let label: String = row.label.chars().take(LABEL_CHARS).collect();
+        draw_text(&mut img, PADDING, y, &label, TEXT);
+        draw_text_right(&mut img, 420, y, row.price.as_deref().unwrap_or("-"), TEXT);
+        match row.change_pct {
+            Some(pct) => {
+                let color = if pct >= 0.0 { UP } else { DOWN };
+                draw_text_right(&mut img, spark_left - PADDING, y, &format!("{:+.2}%", pct), color);
+            }
+            None => draw_text_right(&mut img, spark_left - PADDING, y, "-", MUTED),
+        }
+        draw_sparkline(&mut img, spark_left, top + 6, 160, ROW_HEIGHT - 12, &row.sparkline);
+    }
+
+    let mut out = Cursor::new(Vec::new());
+    img.write_to(&mut out, image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode PNG: {}", e))?;
+    Ok(out.into_inner())
+}
+
+fn fill_rect(img: &mut RgbImage, x: i32, y: i32, w: i32, h: i32, color: Rgb<u8>) {
+    for py in y.max(0)..(y + h).min(img.height() as i32) {
+        for px in x.max(0)..(x + w).min(img.width() as i32) {
+            img.put_pixel(px as u32, py as u32, color);
+        }
+    }
+}
+
+fn draw_text(img: &mut RgbImage, x: i32, y: i32, text: &str, color: Rgb<u8>) {
+    for (i, c) in text.chars().enumerate() {
+        let rows = glyph(c);
+        let gx = x + i as i32 * GLYPH_ADVANCE;
+        for (row, bits) in rows.iter().enumerate() {
+            for col in 0..5 {
+                if bits & (0b10000 >> col) != 0 {
+                    fill_rect(img, gx + col * SCALE, y + row as i32 * SCALE, SCALE, SCALE, color);
+                }
+            }
+        }
+    }
+}
+
+fn draw_text_right(img: &mut RgbImage, right: i32, y: i32, text: &str, color: Rgb<u8>) {
+    let width = text.chars().count() as i32 * GLYPH_ADVANCE - SCALE;
+    draw_text(img, right - width, y, text, color);
+}
+
+/// 在 (x, y, w, h) 範圍內畫折線；漲跌依首尾價格著色
+fn draw_sparkline(img: &mut RgbImage, x: i32, y: i32, w: i32, h: i32, values: &[f64]) {
+    if values.len() < 2 {
+        return;
+    }
+    let (min, max) = values
+        .iter()
+        .fold((f64::MAX, f64::MIN), |(lo, hi), v| (lo.min(*v), hi.max(*v)));
+    let range = if max > min { max - min } else { 1.0 };
+    let color = if values[values.len() - 1] >= values[0] { UP } else { DOWN };
+    let point = |i: usize| {
+        let px = x + (i as f64 * (w - 1) as f64 / (values.len() - 1) as f64).round() as i32;
+        let py = y + h - 1 - ((values[i] - min) / range * (h - 1) as f64).round() as i32;
+        (px, py)
+    };
+    for i in 1..values.len() {
+        let (x0, y0) = point(i - 1);
+        let (x1, y1) = point(i);
+        draw_line(img, x0, y0, x1, y1, color);
+    }
+}
+
+/// Bresenham 直線
+fn draw_line(img: &mut RgbImage, mut x0: i32, mut y0: i32, x1: i32, y1: i32, color: Rgb<u8>) {
+    let dx = (x1 - x0).abs();
+    let dy = -(y1 - y0).abs();
+    let sx = if x0 < x1 { 1 } else { -1 };
+    let sy = if y0 < y1 { 1 } else { -1 };
+    let mut err = dx + dy;
+    loop {
+        fill_rect(img, x0, y0, 1, 2, color);
+        if x0 == x1 && y0 == y1 {
+            break;
+        }
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x0 += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y0 += sy;
+        }
+    }
+}
+
+/// 5×7 點陣字（每列 5 bit，高位在左）；小寫轉大寫，不支援的字元顯示為 `?`
+fn glyph(c: char) -> [u8; 7] {
+    match c.to_ascii_uppercase() {
+        ' ' => [0; 7],
+        '0' => [0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110],
+        '1' => [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+        '2' => [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111],
+        '3' => [0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110],
+        '4' => [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010],
+        '5' => [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110],
+        '6' => [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110],
+        '7' => [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000],
+        '8' => [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110],
+        '9' => [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100],
+        'A' => [0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+        'B' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10001, 0b10001, 0b11110],
+        'C' => [0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110],
+        'D' => [0b11100, 0b10010, 0b10001, 0b10001, 0b10001, 0b10010, 0b11100],
+        'E' => [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111],
+        'F' => [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000],
+        'G' => [0b01110, 0b10001, 0b10000, 0b10111, 0b10001, 0b10001, 0b01111],
+        'H' => [0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+        'I' => [0b01110, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+        'J' => [0b00111, 0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100],
+        'K' => [0b10001, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010, 0b10001],
+        'L' => [0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b11111],
+        'M' => [0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001],
+        'N' => [0b10001, 0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001],
+        'O' => [0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110],
+        'P' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000],
+        'Q' => [0b01110, 0b10001, 0b10001, 0b10001, 0b10101, 0b10010, 0b01101],
+        'R' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001],
+        'S' => [0b01111, 0b10000, 0b10000, 0b01110, 0b00001, 0b00001, 0b11110],
+        'T' => [0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100],
+        'U' => [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110],
+        'V' => [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100],
+        'W' => [0b10001, 0b10001, 0b10001, 0b10101, 0b10101, 0b10101, 0b01010],
+        'X' => [0b10001, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b10001],
+        'Y' => [0b10001, 0b10001, 0b10001, 0b01010, 0b00100, 0b00100, 0b00100],
+        'Z' => [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b11111],
+        '.' => [0, 0, 0, 0, 0, 0b01100, 0b01100],
+        ',' => [0, 0, 0, 0, 0b01100, 0b00100, 0b01000],
+        '-' => [0, 0, 0, 0b11111, 0, 0, 0],
+        '+' => [0, 0b00100, 0b00100, 0b11111, 0b00100, 0b00100, 0],
+        '%' => [0b11000, 0b11001, 0b00010, 0b00100, 0b01000, 0b10011, 0b00011],
+        ':' => [0, 0b01100, 0b01100, 0, 0b01100, 0b01100, 0],
+        '/' => [0, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0],
+        '$' => [0b00100, 0b01111, 0b10100, 0b01110, 0b00101, 0b11110, 0b00100],
+        '(' => [0b00010, 0b00100, 0b01000, 0b01000, 0b01000, 0b00100, 0b00010],
+        ')' => [0b01000, 0b00100, 0b00010, 0b00010, 0b00010, 0b00100, 0b01000],
+        '_' => [0, 0, 0, 0, 0, 0, 0b11111],
+        '=' => [0, 0, 0b11111, 0, 0b11111, 0, 0],
+        _ => [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0, 0b00100],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_a_png_sized_to_the_rows() {
+        let rows = vec![
+            SnapshotRow {
+                label: "BTCUSDT".to_string(),
+                price: Some("65000.00".to_string()),
+                change_pct: Some(1.5),
+                sparkline: vec![1.0, 3.0, 2.0, 4.0],
+            },
+            SnapshotRow { label: "ETH".to_string(), price: None, change_pct: Some(-2.0), sparkline: vec![] },
+        ];
+        let png = render_png("StockenBoard", &rows, 1_767_225_600).unwrap();
+        let img = image::load_from_memory(&png).unwrap().to_rgb8();
+        assert_eq!(img.dimensions(), (WIDTH, (HEADER_HEIGHT + 2 * ROW_HEIGHT + PADDING) as u32));
+        // 上漲的走勢線與漲幅以綠色繪製
+        assert!(img.pixels().any(|p| *p == UP));
+        assert!(img.pixels().any(|p| *p == DOWN));
+    }
+}
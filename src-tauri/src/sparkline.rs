@@ -0,0 +1,140 @@
+//! 卡片走勢線資料 — 把一段時間內的價格紀錄縮成固定點數的小陣列，
+//! 讓前端只需一次極小的請求就能畫出 sparkline，不必取回完整歷史。
+//!
+//! 時間窗格式為數字加單位：`30m`、`24h`、`7d`（最長 365 天）。
+//! 紀錄筆數多於點數時以等寬區間平均縮減；少於點數時原樣回傳。
+
+use serde::Serialize;
+
+use crate::db::DbPool;
+
+pub const DEFAULT_POINTS: usize = 50;
+pub const MAX_POINTS: usize = 500;
+pub const DEFAULT_WINDOW: &str = "24h";
+const MAX_WINDOW_SECS: i64 = 365 * 86_400;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Sparkline {
+    pub subscription_id: i64,
+    pub window_secs: i64,
+    /// 實際第一筆與最後一筆紀錄時間（Unix 秒）；沒有紀錄時為 None
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    /// 價格（舊到新）
+    pub points: Vec<f64>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    /// 區間內第一筆到最後一筆紀錄的漲跌幅（%）
+    pub change_pct: Option<f64>,
+}
+
+/// 解析時間窗（`30m` / `24h` / `7d`），回傳秒數
+pub fn parse_window(window: &str) -> Result<i64, String> {
+    let window = window.trim();
+    let invalid = || format!("Invalid window: {} (expected e.g. 30m, 24h, 7d)", window);
+    let unit_secs = match window.chars().last() {
+        Some('m') => 60,
+        Some('h') => 3_600,
+        Some('d') => 86_400,
+        _ => return Err(invalid()),
+    };
+    let amount: i64 = window[..window.len() - 1].parse().map_err(|_| invalid())?;
+    if amount <= 0 {
+        return Err(invalid());
+    }
+    Ok(amount.saturating_mul(unit_secs).min(MAX_WINDOW_SECS))
+}
+
+/// 以等寬區間平均把序列縮成最多 `points` 個點
+pub fn downsample(values: &[f64], points: usize) -> Vec<f64> {
+    if values.len() <= points || points == 0 {
+        return values.to_vec();
+    }
+    (0..points)
+        .map(|i| {
+            let start = i * values.len() / points;
+            let end = ((i + 1) * values.len() / points).max(start + 1);
+            let bucket = &values[start..end];
+            bucket.iter().sum::<f64>() / bucket.len() as f64
+        })
+        .collect()
+}
+
+/// 讀取訂閱最近 `window_secs` 秒的紀錄並縮成最多 `points` 個點（2–500，預設 50）
+pub fn load(
+    db: &DbPool,
+    subscription_id: i64,
+    points: Option<usize>,
+    window_secs: i64,
+    now: i64,
+) -> Result<Sparkline, String> {
+    let points = points.unwrap_or(DEFAULT_POINTS).clamp(2, MAX_POINTS);
+    let rows = db.get_price_volume_since(subscription_id, now - window_secs)?;
+    let prices: Vec<f64> = rows.iter().map(|(_, price, _)| *price).collect();
+    let change_pct = match (prices.first(), prices.last()) {
+        (Some(first), Some(last)) if *first != 0.0 => Some((last - first) / first * 100.0),
+        _ => None,
+    };
+    Ok(Sparkline {
+        subscription_id,
+        window_secs,
+        from: rows.first().map(|(ts, _, _)| *ts),
+        to: rows.last().map(|(ts, _, _)| *ts),
+        min: prices.iter().copied().reduce(f64::min),
+        max: prices.iter().copied().reduce(f64::max),
+        change_pct,
+        points: downsample(&prices, points),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn downsample_averages_buckets() {
+        let values: Vec<f64> = (0..100).map(f64::from).collect();
+        let out = downsample(&values, 10);
+        assert_eq!(out.len(), 10);
+        assert_eq!(out[0], 4.5);
+        assert_eq!(out[9], 94.5);
+        assert_eq!(downsample(&[1.0, 2.0], 10), [1.0, 2.0]);
+    }
+
+    #[test]
+    fn parses_windows() {
+        assert_eq!(parse_window("24h"), Ok(86_400));
+        assert_eq!(parse_window("30m"), Ok(1_800));
+        assert_eq!(parse_window(" 7d "), Ok(7 * 86_400));
+        assert_eq!(parse_window("5000d"), Ok(MAX_WINDOW_SECS));
+        assert_eq!(parse_window("9223372036854775807m"), Ok(MAX_WINDOW_SECS));
+        assert!(parse_window("0h").is_err());
+        assert!(parse_window("24").is_err());
+        assert!(parse_window("h").is_err());
+        assert!(parse_window("-1d").is_err());
+    }
+
+    #[test]
+    fn load_limits_window_and_points() {
+        let db = DbPool::open(&PathBuf::from(":memory:")).unwrap();
+        let sub = db
+            .add_subscription("asset", "BTCUSDT", None, "binance", "crypto", None, None, None)
+            .unwrap();
+        let now = 10 * 86_400;
+        let mut records = vec![(1.0, None, None, now - 2 * 86_400)];
+        records.extend((0..100).map(|i| (100.0 + i as f64, None, None, now - 3_600 + i * 30)));
+        db.insert_price_history_for_test(sub, "binance", &records).unwrap();
+
+        let line = load(&db, sub, Some(4), 86_400, now).unwrap();
+        assert_eq!(line.points, vec![112.0, 137.0, 162.0, 187.0]);
+        assert_eq!((line.from, line.to), (Some(now - 3_600), Some(now - 3_600 + 99 * 30)));
+        assert_eq!((line.min, line.max), (Some(100.0), Some(199.0)));
+        assert!((line.change_pct.unwrap() - 99.0).abs() < 1e-9);
+
+        // 點數下限為 2；沒有紀錄時回傳空陣列
+        assert_eq!(load(&db, sub, Some(0), 86_400, now).unwrap().points.len(), 2);
+        let empty = load(&db, sub, None, 60, now + 86_400).unwrap();
+        assert!(empty.points.is_empty() && empty.change_pct.is_none());
+    }
+}
@@ -0,0 +1,171 @@
+//! 歷史紀錄的連漲 / 連跌與回撤統計（get_streaks）。
+//!
+//! 以每個 UTC 日最後一筆紀錄作為日收盤價：相鄰兩日收盤上漲 / 下跌延續連續天數，持平則中斷；
+//! 最大回撤為收盤價相對先前最高收盤的最大跌幅，回復時間為谷底到收盤價重回前高的天數。
+
+use serde::Serialize;
+
+use crate::db::DbPool;
+
+const DAY_SECS: i64 = 86_400;
+
+/// 連續漲跌區段（`start` / `end` 為區段首尾日收盤的紀錄時間，Unix 秒）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Streak {
+    pub days: u32,
+    pub start: i64,
+    pub end: i64,
+}
+
+/// 最大回撤區段
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Drawdown {
+    /// 跌幅百分比（正值）
+    pub percent: f64,
+    pub peak_price: f64,
+    pub peak_at: i64,
+    pub trough_price: f64,
+    pub trough_at: i64,
+    /// 收盤價重回前高的時間；尚未回復為 None
+    pub recovered_at: Option<i64>,
+    /// 谷底到回復的天數；尚未回復為 None
+    pub recovery_days: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StreakStats {
+    pub subscription_id: i64,
+    /// 有紀錄的天數
+    pub days: usize,
+    /// 目前連續天數：正值為連漲、負值為連跌、0 為持平或資料不足
+    pub current_streak: i64,
+    pub longest_up: Option<Streak>,
+    pub longest_down: Option<Streak>,
+    /// 沒有任何下跌時為 None
+    pub max_drawdown: Option<Drawdown>,
+}
+
+/// 讀取訂閱的日收盤價並計算統計
+pub fn load(db: &DbPool, subscription_id: i64) -> Result<StreakStats, String> {
+    let closes = db.get_daily_closes(subscription_id)?;
+    Ok(compute(subscription_id, &closes))
+}
+
+/// 由依時間排序的日收盤價 `(recorded_at, price)` 計算統計
+pub fn compute(subscription_id: i64, closes: &[(i64, f64)]) -> StreakStats {
+    let mut current: i64 = 0;
+    let mut run_start = 0;
+    let mut longest_up: Option<Streak> = None;
+    let mut longest_down: Option<Streak> = None;
+
+    for (i, pair) in closes.windows(2).enumerate() {
+        let ((_, prev), (ts, price)) = (pair[0], pair[1]);
+        let step = if price > prev {
+            1
+        } else if price < prev {
+            -1
+        } else {
+            0
+        };
+        if step == 0 || current.signum() != step {
+            current = 0;
+            run_start = closes[i].0;
+        }
+        current += step;
+
+        let streak = Streak { days: current.unsigned_abs() as u32, start: run_start, end: ts };
+        let longest = if step > 0 { &mut longest_up } else { &mut longest_down };
+        if step != 0 && longest.is_none_or(|s| streak.days > s.days) {
+            *longest = Some(streak);
+        }
+    }
+
+    StreakStats {
+        subscription_id,
+        days: closes.len(),
+        current_streak: current,
+        longest_up,
+        longest_down,
+        max_drawdown: max_drawdown(closes),
+    }
+}
+
+fn max_drawdown(closes: &[(i64, f64)]) -> Option<Drawdown> {
+    let mut peak: Option<(i64, f64)> = None;
+    let mut worst: Option<Drawdown> = None;
+    for &(ts, price) in closes {
+        let (peak_at, peak_price) = match peak {
+            Some(p) if p.1 >= price => p,
+            _ => {
+                peak = Some((ts, price));
+                continue;
+            }
+        };
+        if peak_price <= 0.0 {
+            continue;
+        }
+        let percent = (peak_price - price) / peak_price * 100.0;
+        if percent > 0.0 && worst.is_none_or(|d| percent > d.percent) {
+            worst = Some(Drawdown {
+                percent,
+                peak_price,
+                peak_at,
+                trough_price: price,
+                trough_at: ts,
+                recovered_at: None,
+                recovery_days: None,
+            });
+        }
+    }
+
+    let mut drawdown = worst?;
+    drawdown.recovered_at = closes
+        .iter()
+        .find(|&&(ts, price)| ts > drawdown.trough_at && price >= drawdown.peak_price)
+        .map(|&(ts, _)| ts);
+    drawdown.recovery_days = drawdown
+        .recovered_at
+        .map(|ts| ts.div_euclid(DAY_SECS) - drawdown.trough_at.div_euclid(DAY_SECS));
+    Some(drawdown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn daily(prices: &[f64]) -> Vec<(i64, f64)> {
+        prices.iter().enumerate().map(|(i, &p)| (i as i64 * DAY_SECS, p)).collect()
+    }
+
+    #[test]
+    fn streaks_reset_on_direction_change_and_flat_days() {
+        let stats = compute(1, &daily(&[10.0, 11.0, 12.0, 13.0, 12.0, 12.0, 11.0, 10.0]));
+        assert_eq!(stats.days, 8);
+        assert_eq!(stats.longest_up, Some(Streak { days: 3, start: 0, end: 3 * DAY_SECS }));
+        // 12 → 12 持平中斷連跌，之後重新起算
+        assert_eq!(stats.longest_down, Some(Streak { days: 2, start: 5 * DAY_SECS, end: 7 * DAY_SECS }));
+        assert_eq!(stats.current_streak, -2);
+    }
+
+    #[test]
+    fn drawdown_reports_recovery_days() {
+        let stats = compute(1, &daily(&[100.0, 120.0, 90.0, 96.0, 110.0, 125.0, 118.0]));
+        let dd = stats.max_drawdown.unwrap();
+        assert!((dd.percent - 25.0).abs() < 1e-9);
+        assert_eq!((dd.peak_at, dd.trough_at), (DAY_SECS, 2 * DAY_SECS));
+        assert_eq!(dd.recovered_at, Some(5 * DAY_SECS));
+        assert_eq!(dd.recovery_days, Some(3));
+        assert_eq!(stats.current_streak, -1);
+    }
+
+    #[test]
+    fn unrecovered_and_empty_history() {
+        let dd = compute(1, &daily(&[50.0, 40.0, 45.0])).max_drawdown.unwrap();
+        assert_eq!((dd.recovered_at, dd.recovery_days), (None, None));
+
+        let empty = compute(2, &[]);
+        assert_eq!((empty.days, empty.current_streak), (0, 0));
+        assert!(empty.longest_up.is_none() && empty.max_drawdown.is_none());
+        assert!(compute(3, &daily(&[1.0, 2.0, 3.0])).max_drawdown.is_none());
+    }
+}
@@ -0,0 +1,394 @@
+//! CSV 批次匯入 asset 訂閱 — 讓使用者從試算表一次搬移大量 ticker。
+//!
+//! 欄位依序為 `symbol, provider, display_name, record, tags`；第一列若為標題則依欄名對應
+//! （順序不拘，另可加上選用的 `asset_type` 欄位）。`tags` 以 `;` 或 `|` 分隔，
+//! 對應到同名的 asset view，不存在時自動建立。
+//!
+//! 每列先經 provider 的 `validate_symbol` 檢查，再於單一 transaction 中寫入；
+//! 錯誤以列為單位回報，不影響其他列。
+
+use std::collections::HashMap;
+
+use futures::StreamExt;
+use serde::Serialize;
+
+use crate::core_state::CoreState;
+use crate::db::SubscriptionImportRow;
+use crate::i18n::{tr, Msg};
+use crate::providers::{get_provider_info, normalize_symbol};
+
+/// 同時進行的 symbol 驗證數（各 provider 另有 registry 的 rate limiter）
+const VALIDATE_CONCURRENCY: usize = 8;
+const DEFAULT_COLUMNS: [Column; 5] = [
+    Column::Symbol,
+    Column::Provider,
+    Column::DisplayName,
+    Column::Record,
+    Column::Tags,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Column {
+    Symbol,
+    Provider,
+    DisplayName,
+    Record,
+    Tags,
+    AssetType,
+    Ignored,
+}
+
+impl Column {
+    fn from_header(name: &str) -> Self {
+        match name.trim().to_lowercase().replace([' ', '-'], "_").as_str() {
+            "symbol" | "ticker" => Self::Symbol,
+            "provider" | "provider_id" => Self::Provider,
+            "display_name" | "name" => Self::DisplayName,
+            "record" | "record_enabled" => Self::Record,
+            "tags" | "tag" | "views" => Self::Tags,
+            "asset_type" | "type" => Self::AssetType,
+            _ => Self::Ignored,
+        }
+    }
+}
+
+/// 解析後的一列（尚未驗證 provider 與 symbol）
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvRow {
+    /// CSV 中的行號（從 1 起算，含標題列）
+    pub line: usize,
+    pub symbol: String,
+    pub provider_id: String,
+    pub display_name: Option<String>,
+    pub asset_type: Option<String>,
+    pub record: bool,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CsvRowError {
+    pub line: usize,
+    pub symbol: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CsvImportReport {
+    pub total_rows: usize,
+    pub imported: Vec<String>,
+    pub errors: Vec<CsvRowError>,
+}
+
+fn parse_record_flag(value: &str) -> Result<bool, String> {
+    match value.trim().to_lowercase().as_str() {
+        "" | "0" | "false" | "no" | "n" => Ok(false),
+        "1" | "true" | "yes" | "y" | "x" => Ok(true),
+        other => Err(format!("Invalid record flag '{}'", other)),
+    }
+}
+
+/// record 在 CSV 中的行號（從 1 起算）。
+/// csv 會略過空行（record 位置落在空行開頭），行號改以第一個非換行字元計算
+pub(crate) fn record_line(content: &str, record: &csv::StringRecord, index: usize) -> usize {
+    record.position().map_or(index + 1, |p| {
+        let bytes = content.as_bytes();
+        let start = (p.byte() as usize..bytes.len())
+            .find(|&i| bytes[i] != b'\n' && bytes[i] != b'\r')
+            .unwrap_or(bytes.len());
+        bytes[..start].iter().filter(|b| **b == b'\n').count() + 1
+    })
+}
+
+/// 解析 CSV 內容；格式錯誤的列放入錯誤清單，空白列略過
+pub fn parse(content: &str) -> Result<(Vec<CsvRow>, Vec<CsvRowError>), String> {
+    let content = content.trim_start_matches('\u{feff}');
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(content.as_bytes());
+
+    let mut columns = DEFAULT_COLUMNS.to_vec();
+    let mut rows = Vec::new();
+    let mut errors = Vec::new();
+    for (index, record) in reader.records().enumerate() {
+        let record = record.map_err(|e| format!("Invalid CSV: {}", e))?;
+        let line = record_line(content, &record, index);
+        if record.iter().all(|f| f.is_empty()) {
+            continue;
+        }
+        if index == 0 && record.iter().any(|f| Column::from_header(f) == Column::Symbol) {
+            columns = record.iter().map(Column::from_header).collect();
+            if !columns.contains(&Column::Provider) {
+                return Err("CSV header is missing a 'provider' column".to_string());
+            }
+            continue;
+        }
+
+        let mut row = CsvRow {
+            line,
+            symbol: String::new(),
+            provider_id: String::new(),
+            display_name: None,
+            asset_type: None,
+            record: false,
+            tags: Vec::new(),
+        };
+        let mut error = None;
+        for (column, value) in columns.iter().zip(record.iter()) {
+            match column {
+                Column::Symbol => row.symbol = value.to_string(),
+                Column::Provider => row.provider_id = value.to_lowercase(),
+                Column::DisplayName => {
+                    row.display_name = Some(value.to_string()).filter(|v| !v.is_empty())
+                }
+                Column::Record => match parse_record_flag(value) {
+                    Ok(flag) => row.record = flag,
+                    Err(e) => error = Some(e),
+                },
+                Column::Tags => {
+                    row.tags = value
+                        .split([';', '|'])
+                        .map(str::trim)
+                        .filter(|t| !t.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                }
+                Column::AssetType => {
+                    row.asset_type = Some(value.to_lowercase()).filter(|v| !v.is_empty())
+                }
+                Column::Ignored => {}
+            }
+        }
+        if row.symbol.is_empty() {
+            error = Some("Missing symbol".to_string());
+        } else if row.provider_id.is_empty() {
+            error = Some("Missing provider".to_string());
+        }
+        match error {
+            Some(error) => errors.push(CsvRowError {
+                line,
+                symbol: row.symbol,
+                error,
+            }),
+            None => rows.push(row),
+        }
+    }
+    Ok((rows, errors))
+}
+
+/// 決定 asset type：明確指定者優先，否則依 provider 類型；
+/// 同時支援兩者的 provider 以穩定幣報價或 `/` 分隔判斷為 crypto。
+fn resolve_asset_type(row: &CsvRow) -> Result<String, String> {
+    let info = get_provider_info(&row.provider_id)
+        .ok_or_else(|| tr(Msg::ProviderNotFound, &[&row.provider_id]))?;
+    if info.provider_type == "dex" {
+        return Err("DEX pools cannot be imported from CSV".to_string());
+    }
+    let asset_type = match row.asset_type.as_deref() {
+        Some(t) => t.to_string(),
+        None if info.provider_type != "both" => info.provider_type.clone(),
+        None => {
+            let upper = row.symbol.to_uppercase();
+            let looks_crypto = upper.contains('/')
+                || ["USDT", "USDC", "BUSD", "-USD"].iter().any(|q| upper.ends_with(q));
+            if looks_crypto { "crypto" } else { "stock" }.to_string()
+        }
+    };
+    if asset_type != "crypto" && asset_type != "stock" {
+        return Err(format!("Invalid asset type '{}'", asset_type));
+    }
+    if info.provider_type != asset_type && info.provider_type != "both" {
+        return Err(format!(
+            "Provider {} does not support {} symbols",
+            row.provider_id, asset_type
+        ));
+    }
+    Ok(asset_type)
+}
+
+/// 解析、驗證並匯入 CSV；`validate` 為 false 時略過線上 symbol 檢查
+pub async fn import(
+    state: &CoreState,
+    content: &str,
+    validate: bool,
+) -> Result<CsvImportReport, String> {
+    let (rows, mut errors) = parse(content)?;
+    let total_rows = rows.len() + errors.len();
+    let row_error = |row: &CsvRow, error: String| CsvRowError {
+        line: row.line,
+        symbol: row.symbol.clone(),
+        error,
+    };
+
+    // provider / asset type 與檔案內重複
+    let mut seen: HashMap<(String, String), usize> = HashMap::new();
+    let mut prepared = Vec::new();
+    for row in rows {
+        let asset_type = match resolve_asset_type(&row) {
+            Ok(t) => t,
+            Err(e) => {
+                errors.push(row_error(&row, e));
+                continue;
+            }
+        };
+        let normalized = normalize_symbol(&row.symbol, &asset_type);
+        if let Some(first) = seen.insert((normalized.clone(), row.provider_id.clone()), row.line) {
+            errors.push(row_error(&row, format!("Duplicate of line {}", first)));
+            continue;
+        }
+        prepared.push((row, asset_type, normalized));
+    }
+
+    // 以 provider 實際查詢驗證 symbol（與前端新增訂閱相同，使用大寫原始 symbol）
+    if validate {
+        let requests: Vec<(String, String)> = prepared
+            .iter()
+            .map(|(row, _, _)| (row.provider_id.clone(), row.symbol.to_uppercase()))
+            .collect();
+        let checks: Vec<Result<(), String>> = futures::stream::iter(requests)
+            .map(|(provider_id, symbol)| async move {
+                state
+                    .registry
+                    .validate_symbol(&provider_id, &symbol, &state.db)
+                    .await
+            })
+            .buffered(VALIDATE_CONCURRENCY)
+            .collect()
+            .await;
+        let mut valid = Vec::with_capacity(prepared.len());
+        for (item, check) in prepared.into_iter().zip(checks) {
+            match check {
+                Ok(()) => valid.push(item),
+                Err(e) => errors.push(row_error(&item.0, e)),
+            }
+        }
+        prepared = valid;
+    }
+
+    let import_rows: Vec<SubscriptionImportRow> = prepared
+        .iter()
+        .map(|(row, asset_type, normalized)| SubscriptionImportRow {
+            symbol: normalized.clone(),
+            display_name: row.display_name.clone(),
+            provider_id: row.provider_id.clone(),
+            asset_type: asset_type.clone(),
+            record_enabled: row.record,
+            views: row.tags.clone(),
+        })
+        .collect();
+    let results = state.db.import_subscription_rows(&import_rows)?;
+
+    let mut imported = Vec::new();
+    for ((row, _, normalized), result) in prepared.iter().zip(results) {
+        match result {
+            Ok(_) => imported.push(normalized.clone()),
+            Err(e) => errors.push(row_error(row, e)),
+        }
+    }
+    errors.sort_by_key(|e| e.line);
+
+    if !imported.is_empty() {
+        state.polling.reload();
+        // 匯入開啟紀錄的訂閱時，需要背景 polling
+        state.sync_polling_for_rules().await;
+    }
+    Ok(CsvImportReport {
+        total_rows,
+        imported,
+        errors,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_positional_rows_and_flags() {
+        let csv = "BTCUSDT,binance,Bitcoin,yes,Crypto;Watch\n\nAAPL,yahoo,,0,\nETHUSDT,binance,,maybe,\n,binance\n";
+        let (rows, errors) = parse(csv).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(
+            rows[0],
+            CsvRow {
+                line: 1,
+                symbol: "BTCUSDT".to_string(),
+                provider_id: "binance".to_string(),
+                display_name: Some("Bitcoin".to_string()),
+                asset_type: None,
+                record: true,
+                tags: vec!["Crypto".to_string(), "Watch".to_string()],
+            }
+        );
+        assert_eq!(rows[1].line, 3);
+        assert!(!rows[1].record);
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].line, 4);
+        assert!(errors[0].error.contains("record flag"));
+        assert_eq!(errors[1].error, "Missing symbol");
+    }
+
+    #[test]
+    fn parse_header_maps_columns_by_name() {
+        let csv = "\u{feff}Provider,Ticker,Tags,Type\nYahoo,msft,\"Tech | Core\",stock\n";
+        let (rows, errors) = parse(csv).unwrap();
+        assert!(errors.is_empty());
+        assert_eq!(rows[0].line, 2);
+        assert_eq!(rows[0].provider_id, "yahoo");
+        assert_eq!(rows[0].symbol, "msft");
+        assert_eq!(rows[0].asset_type.as_deref(), Some("stock"));
+        assert_eq!(rows[0].tags, vec!["Tech".to_string(), "Core".to_string()]);
+
+        assert!(parse("symbol,name\nBTC,Bitcoin\n").is_err());
+    }
+
+    #[test]
+    fn resolve_asset_type_from_provider() {
+        let row = |symbol: &str, provider: &str, asset_type: Option<&str>| CsvRow {
+            line: 1,
+            symbol: symbol.to_string(),
+            provider_id: provider.to_string(),
+            display_name: None,
+            asset_type: asset_type.map(str::to_string),
+            record: false,
+            tags: Vec::new(),
+        };
+        assert_eq!(resolve_asset_type(&row("BTCUSDT", "binance", None)).unwrap(), "crypto");
+        assert_eq!(resolve_asset_type(&row("AAPL", "yahoo", None)).unwrap(), "stock");
+        assert!(resolve_asset_type(&row("AAPL", "binance", Some("stock"))).is_err());
+        assert!(resolve_asset_type(&row("AAPL", "nope", None)).is_err());
+        assert!(resolve_asset_type(&row("0xabc", "raydium", None)).is_err());
+    }
+
+    #[tokio::test]
+    async fn import_without_validation_reports_per_row_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = CoreState::new(dir.path()).unwrap();
+        state
+            .db
+            .add_subscription("asset", "ETH", None, "binance", "crypto", None, None, None)
+            .unwrap();
+
+        let csv = "symbol,provider,record,tags\nBTCUSDT,binance,1,Majors\nETHUSDT,binance,,\nBTC/USDT,binance,,\nSOL,nope,,\n";
+        let report = import(&state, csv, false).await.unwrap();
+        assert_eq!(report.total_rows, 4);
+        assert_eq!(report.imported, vec!["BTC".to_string()]);
+        let lines: Vec<usize> = report.errors.iter().map(|e| e.line).collect();
+        assert_eq!(lines, vec![3, 4, 5]);
+        assert!(report.errors[0].error.contains("already exists"));
+        assert!(report.errors[1].error.contains("Duplicate of line 2"));
+
+        let subs = state.db.list_subscriptions("asset").unwrap();
+        let btc = subs.iter().find(|s| s.symbol == "BTC").unwrap();
+        assert_eq!(btc.record_enabled, 1);
+        let majors = state
+            .db
+            .list_views("asset")
+            .unwrap()
+            .into_iter()
+            .find(|v| v.name == "Majors")
+            .unwrap();
+        assert_eq!(state.db.get_view_subscription_ids(majors.id).unwrap(), vec![btc.id]);
+    }
+}
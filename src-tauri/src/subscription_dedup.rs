@@ -0,0 +1,339 @@
+//! 重複訂閱偵測與合併 — 同一資產以不同 provider / 格式訂閱（BTCUSDT、BTC-USD、bitcoin）時找出並合併。
+//!
+//! crypto / 股票代號以 `normalize_symbol` 統一；CoinGecko 與 CoinPaprika 的幣 ID（`bitcoin`、`btc-bitcoin`）
+//! 以已下載的幣清單（或內建主流幣對照）換回 ticker。DEX 訂閱以 pool 位址比對，籃子不參與。
+//!
+//! 合併在單一 transaction 中把被合併訂閱的價格紀錄（與保留訂閱時間相同者捨棄）、view 成員與通知規則
+//! 移到保留的訂閱，再刪除被合併的訂閱。被籃子引用為成分的訂閱不能被合併掉。
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+
+use crate::core_state::CoreState;
+use crate::db::{DbPool, Subscription};
+use crate::i18n::{tr, Msg};
+use crate::providers::normalize_symbol;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DuplicateMember {
+    pub id: i64,
+    pub symbol: String,
+    pub provider_id: String,
+    pub display_name: Option<String>,
+    pub history_rows: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DuplicateGroup {
+    /// 統一後的識別（如 `crypto:BTC`、`stock:BRK.B`、`dex:0xabc…`）
+    pub key: String,
+    pub asset_type: String,
+    pub members: Vec<DuplicateMember>,
+    /// 建議保留的訂閱：紀錄最多者，同數量時取最早建立者
+    pub suggested_keep: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MergeResult {
+    pub kept: i64,
+    pub removed: Vec<i64>,
+    /// 移到保留訂閱的價格紀錄筆數
+    pub moved_history: i64,
+    /// 與保留訂閱時間相同而捨棄的紀錄筆數
+    pub dropped_history: i64,
+}
+
+/// 幣 ID（小寫）→ ticker（大寫）
+type CoinIds = HashMap<String, String>;
+
+/// 訂閱的統一識別；籃子回傳 None
+fn canonical_key(sub: &Subscription, coingecko: &CoinIds, coinpaprika: &CoinIds) -> Option<String> {
+    if sub.selected_provider_id == crate::basket::PROVIDER_ID {
+        return None;
+    }
+    let symbol = sub.symbol.trim();
+    if sub.sub_type == "dex" {
+        let pool = sub
+            .pool_address
+            .as_deref()
+            .filter(|p| !p.is_empty())
+            .unwrap_or(symbol);
+        return Some(format!("dex:{}", pool.to_lowercase()));
+    }
+    let key = match sub.asset_type.as_str() {
+        "crypto" => {
+            let id = symbol.to_lowercase();
+            let from_id = match sub.selected_provider_id.as_str() {
+                "coingecko" => coingecko.get(&id).cloned().or_else(|| {
+                    crate::providers::coingecko::static_symbol(&id).map(str::to_string)
+                }),
+                // CoinPaprika ID 為 "{symbol}-{name}"
+                "coinpaprika" => coinpaprika.get(&id).cloned().or_else(|| {
+                    id.split_once('-')
+                        .filter(|(_, name)| name.len() > 5)
+                        .map(|(ticker, _)| ticker.to_uppercase())
+                }),
+                _ => None,
+            };
+            from_id.unwrap_or_else(|| normalize_symbol(symbol, "crypto"))
+        }
+        // Yahoo 以 `BRK-B` 表示類股，其他 provider 多為 `BRK.B`
+        "stock" => normalize_symbol(symbol, "stock").replace('-', "."),
+        other => normalize_symbol(symbol, other).to_lowercase(),
+    };
+    Some(format!("{}:{}", sub.asset_type, key))
+}
+
+fn coin_ids<I: IntoIterator<Item = (String, String)>>(coins: I) -> CoinIds {
+    coins
+        .into_iter()
+        .map(|(id, symbol)| (id.to_lowercase(), symbol.to_uppercase()))
+        .collect()
+}
+
+/// 依統一識別分組；只回傳有兩筆以上訂閱的組
+pub fn group_duplicates(
+    subs: &[Subscription],
+    coingecko: &CoinIds,
+    coinpaprika: &CoinIds,
+) -> BTreeMap<String, Vec<Subscription>> {
+    let mut groups: BTreeMap<String, Vec<Subscription>> = BTreeMap::new();
+    for sub in subs {
+        if let Some(key) = canonical_key(sub, coingecko, coinpaprika) {
+            groups.entry(key).or_default().push(sub.clone());
+        }
+    }
+    groups.retain(|_, members| members.len() > 1);
+    groups
+}
+
+/// 找出追蹤同一資產的重複訂閱
+pub fn find_duplicates(db: &DbPool) -> Result<Vec<DuplicateGroup>, String> {
+    let subs = db.list_all_subscriptions()?;
+    let uses = |provider: &str| subs.iter().any(|s| s.selected_provider_id == provider);
+    let coingecko = if uses("coingecko") {
+        coin_ids(
+            db.load_coingecko_coins()?
+                .0
+                .into_iter()
+                .map(|c| (c.id, c.symbol)),
+        )
+    } else {
+        CoinIds::new()
+    };
+    let coinpaprika = if uses("coinpaprika") {
+        coin_ids(
+            db.load_coinpaprika_coins()?
+                .0
+                .into_iter()
+                .map(|c| (c.id, c.symbol)),
+        )
+    } else {
+        CoinIds::new()
+    };
+
+    let mut result = Vec::new();
+    for (key, members) in group_duplicates(&subs, &coingecko, &coinpaprika) {
+        let members = members
+            .into_iter()
+            .map(|sub| {
+                Ok(DuplicateMember {
+                    history_rows: db.get_history_stats(sub.id)?.total,
+                    id: sub.id,
+                    symbol: sub.symbol,
+                    provider_id: sub.selected_provider_id,
+                    display_name: sub.display_name,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        let suggested_keep = members
+            .iter()
+            .max_by_key(|m| (m.history_rows, -m.id))
+            .map_or(0, |m| m.id);
+        let asset_type = key.split_once(':').map_or("", |(t, _)| t).to_string();
+        result.push(DuplicateGroup {
+            key,
+            asset_type,
+            members,
+            suggested_keep,
+        });
+    }
+    Ok(result)
+}
+
+/// 把 `merge_ids` 合併進 `keep_id` 並刪除；被籃子引用的訂閱與籃子本身不能被合併
+pub fn merge(state: &CoreState, keep_id: i64, merge_ids: &[i64]) -> Result<MergeResult, String> {
+    let mut removed: Vec<i64> = merge_ids
+        .iter()
+        .copied()
+        .filter(|id| *id != keep_id)
+        .collect();
+    removed.sort_unstable();
+    removed.dedup();
+    if removed.is_empty() {
+        return Err("Nothing to merge".to_string());
+    }
+    let subs = state.db.list_all_subscriptions()?;
+    let find = |id: i64| {
+        subs.iter()
+            .find(|s| s.id == id)
+            .ok_or_else(|| tr(Msg::SubscriptionNotFound, &[&id]))
+    };
+    find(keep_id)?;
+    for id in &removed {
+        if find(*id)?.selected_provider_id == crate::basket::PROVIDER_ID {
+            return Err(format!(
+                "Subscription {} is a basket and cannot be merged",
+                id
+            ));
+        }
+    }
+    for basket in subs
+        .iter()
+        .filter(|s| s.selected_provider_id == crate::basket::PROVIDER_ID)
+    {
+        if let Some(id) = crate::basket::component_ids(basket.provider_params.as_deref())
+            .into_iter()
+            .find(|id| removed.contains(id))
+        {
+            return Err(format!(
+                "Subscription {} is a component of basket {}; remove it from the basket first",
+                id, basket.symbol
+            ));
+        }
+    }
+
+    let (moved_history, dropped_history) = state.db.merge_subscriptions(keep_id, &removed)?;
+    state.polling.reload();
+    Ok(MergeResult {
+        kept: keep_id,
+        removed,
+        moved_history,
+        dropped_history,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sub(id: i64, symbol: &str, provider: &str, asset_type: &str) -> Subscription {
+        Subscription {
+            id,
+            sub_type: "asset".to_string(),
+            symbol: symbol.to_string(),
+            display_name: None,
+            selected_provider_id: provider.to_string(),
+            asset_type: asset_type.to_string(),
+            pool_address: None,
+            token_from_address: None,
+            token_to_address: None,
+            sort_order: id,
+            record_enabled: 0,
+            record_from_hour: None,
+            record_to_hour: None,
+            provider_params: None,
+            record_dedup_secs: None,
+        }
+    }
+
+    #[test]
+    fn groups_same_asset_across_formats() {
+        let subs = vec![
+            sub(1, "BTCUSDT", "binance", "crypto"),
+            sub(2, "BTC-USD", "coinbase", "crypto"),
+            sub(3, "bitcoin", "coingecko", "crypto"),
+            sub(4, "btc-bitcoin", "coinpaprika", "crypto"),
+            sub(5, "ETH-BTC", "kraken", "crypto"),
+            sub(6, "BRK-B", "yahoo", "stock"),
+            sub(7, "BRK.B", "finnhub", "stock"),
+            sub(8, "AAPL", "yahoo", "stock"),
+            sub(9, "BTC", "basket", "crypto"),
+        ];
+        let groups = group_duplicates(&subs, &CoinIds::new(), &CoinIds::new());
+        let ids = |key: &str| groups[key].iter().map(|s| s.id).collect::<Vec<_>>();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(ids("crypto:BTC"), vec![1, 2, 3, 4]);
+        assert_eq!(ids("stock:BRK.B"), vec![6, 7]);
+
+        // 下載的幣清單優先於內建對照
+        let gecko = coin_ids([("wrapped-bitcoin".to_string(), "wbtc".to_string())]);
+        let subs = vec![
+            sub(1, "wrapped-bitcoin", "coingecko", "crypto"),
+            sub(2, "WBTCUSDT", "binance", "crypto"),
+        ];
+        assert_eq!(
+            group_duplicates(&subs, &gecko, &CoinIds::new())["crypto:WBTC"].len(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn merge_moves_history_views_and_rules() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = CoreState::new(dir.path()).unwrap();
+        let db = &state.db;
+        let keep = db
+            .add_subscription("asset", "BTC", None, "binance", "crypto", None, None, None)
+            .unwrap();
+        let dup = db
+            .add_subscription(
+                "asset",
+                "bitcoin",
+                None,
+                "coingecko",
+                "crypto",
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+        db.toggle_record(dup, true).unwrap();
+        db.insert_price_history_for_test(keep, "binance", &[(100.0, None, None, 1_000)])
+            .unwrap();
+        db.insert_price_history_for_test(
+            dup,
+            "coingecko",
+            &[(99.0, None, None, 1_000), (101.0, None, None, 2_000)],
+        )
+        .unwrap();
+        let view = db.create_view("Majors", "asset").unwrap();
+        db.add_sub_to_view(view, dup).unwrap();
+        let rule = db
+            .create_notification_rule(
+                "Dip",
+                dup,
+                "price_below",
+                90.0,
+                "[]",
+                300,
+                None,
+                Some(&format!("[{}, {}]", keep, dup)),
+            )
+            .unwrap();
+
+        let groups = find_duplicates(db).unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].suggested_keep, dup);
+
+        let result = merge(&state, keep, &[dup, keep]).unwrap();
+        assert_eq!(
+            (result.removed, result.moved_history, result.dropped_history),
+            (vec![dup], 1, 1)
+        );
+        assert_eq!(
+            db.get_price_volume_since(keep, 0).unwrap(),
+            vec![(1_000, 100.0, None), (2_000, 101.0, None)]
+        );
+        assert_eq!(db.get_view_subscription_ids(view).unwrap(), vec![keep]);
+        let rule = db.get_notification_rule(rule).unwrap().unwrap();
+        assert_eq!(rule.subscription_id, keep);
+        assert_eq!(rule.subscription_ids, Some(format!("[{}]", keep)));
+        let subs = db.list_all_subscriptions().unwrap();
+        assert_eq!(subs.len(), 1);
+        assert_eq!(subs[0].record_enabled, 1);
+        assert!(find_duplicates(db).unwrap().is_empty());
+        assert!(merge(&state, keep, &[dup]).is_err());
+    }
+}
@@ -0,0 +1,329 @@
+//! OpenTelemetry trace 匯出（選用）— 以 OTLP/HTTP JSON 將 provider 取價與 DB 寫入的 span
+//! 送到使用者既有的 collector（Jaeger、Tempo、Honeycomb 等），方便追查變慢的輪詢。
+//!
+//! - `provider.fetch`：一次 registry 取價（polling、watchdog 與 API 共用），子 span 為
+//!   `rate_limiter.acquire`（等待 rate limit）與 `provider.request`（實際請求）
+//! - `db.write_price_history`：一批價格紀錄寫入
+//!
+//! 未啟用時建立 span 不做任何事。啟用時 span 結束後放入緩衝區（最多 [`MAX_BUFFERED`] 筆，超過丟棄最舊的），
+//! 背景每 [`EXPORT_INTERVAL_SECS`] 秒 POST 到 `{endpoint}/v1/traces`；collector 無法連線時只記錄警告。
+//!
+//! 設定存於 app settings（`otlp_enabled`、`otlp_endpoint`、`otlp_service_name`）。
+
+use std::collections::VecDeque;
+use std::sync::{LazyLock, Mutex, RwLock};
+use std::time::Duration;
+
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// 緩衝區上限
+pub const MAX_BUFFERED: usize = 2_048;
+/// 匯出間隔
+pub const EXPORT_INTERVAL_SECS: u64 = 5;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OtlpConfig {
+    pub enabled: bool,
+    /// OTLP/HTTP collector 的 base URL（不含 `/v1/traces`）
+    pub endpoint: String,
+    pub service_name: String,
+}
+
+impl Default for OtlpConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: "http://localhost:4318".to_string(),
+            service_name: "stockenboard".to_string(),
+        }
+    }
+}
+
+impl OtlpConfig {
+    /// 檢查設定並去除 endpoint 結尾的 `/`
+    pub fn normalized(mut self) -> Result<Self, String> {
+        self.endpoint = self.endpoint.trim().trim_end_matches('/').to_string();
+        self.service_name = self.service_name.trim().to_string();
+        if self.enabled && !(self.endpoint.starts_with("http://") || self.endpoint.starts_with("https://")) {
+            return Err("OTLP endpoint must start with http:// or https://".to_string());
+        }
+        if self.service_name.is_empty() {
+            self.service_name = OtlpConfig::default().service_name;
+        }
+        Ok(self)
+    }
+
+    fn traces_url(&self) -> String {
+        format!("{}/v1/traces", self.endpoint)
+    }
+}
+
+/// 結束的 span 等待匯出的緩衝區
+type SpanBuffer = Mutex<VecDeque<Value>>;
+
+static OTLP_CONFIG: LazyLock<RwLock<OtlpConfig>> = LazyLock::new(|| RwLock::new(OtlpConfig::default()));
+static BUFFER: LazyLock<SpanBuffer> = LazyLock::new(SpanBuffer::default);
+static RNG: LazyLock<SystemRandom> = LazyLock::new(SystemRandom::new);
+
+pub fn set_otlp_config(config: OtlpConfig) {
+    if !config.enabled {
+        BUFFER.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+    *OTLP_CONFIG.write().unwrap_or_else(|e| e.into_inner()) = config;
+}
+
+pub fn otlp_config() -> OtlpConfig {
+    OTLP_CONFIG.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+fn enabled() -> bool {
+    OTLP_CONFIG.read().unwrap_or_else(|e| e.into_inner()).enabled
+}
+
+fn random_hex<const N: usize>() -> String {
+    let mut bytes = [0u8; N];
+    if RNG.fill(&mut bytes).is_err() {
+        // 極少發生；以時間戳避免產生全 0（OTLP 視為無效）的 ID
+        let nanos = chrono::Utc::now().timestamp_nanos_opt().unwrap_or(1) as u64;
+        for (i, b) in bytes.iter_mut().enumerate() {
+            *b = (nanos >> ((i % 8) * 8)) as u8 | 1;
+        }
+    }
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn now_nanos() -> i64 {
+    chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+}
+
+/// OTLP span kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpanKind {
+    Internal = 1,
+    Client = 3,
+}
+
+#[derive(Debug)]
+struct SpanData {
+    trace_id: String,
+    span_id: String,
+    parent_span_id: Option<String>,
+    name: &'static str,
+    kind: SpanKind,
+    start: i64,
+    attributes: Vec<Value>,
+    error: Option<String>,
+    /// 結束時放入的緩衝區（正式環境為 [`BUFFER`]，測試使用各自的緩衝區）
+    buffer: &'static SpanBuffer,
+}
+
+/// 進行中的 span，drop 時結束並放入匯出緩衝區；未啟用匯出時為空操作
+#[derive(Debug)]
+pub struct Span(Option<SpanData>);
+
+impl Span {
+    /// 開始一個新 trace 的根 span
+    pub fn root(name: &'static str, kind: SpanKind) -> Self {
+        Self::start(&BUFFER, enabled(), name, kind, None)
+    }
+
+    /// 在同一 trace 下開始子 span
+    pub fn child(&self, name: &'static str, kind: SpanKind) -> Self {
+        match &self.0 {
+            Some(parent) => Self::start(
+                parent.buffer,
+                true,
+                name,
+                kind,
+                Some((parent.trace_id.clone(), parent.span_id.clone())),
+            ),
+            None => Self(None),
+        }
+    }
+
+    fn start(
+        buffer: &'static SpanBuffer,
+        enabled: bool,
+        name: &'static str,
+        kind: SpanKind,
+        parent: Option<(String, String)>,
+    ) -> Self {
+        if !enabled {
+            return Self(None);
+        }
+        let (trace_id, parent_span_id) = match parent {
+            Some((trace_id, span_id)) => (trace_id, Some(span_id)),
+            None => (random_hex::<16>(), None),
+        };
+        Self(Some(SpanData {
+            trace_id,
+            span_id: random_hex::<8>(),
+            parent_span_id,
+            name,
+            kind,
+            start: now_nanos(),
+            attributes: Vec::new(),
+            error: None,
+            buffer,
+        }))
+    }
+
+    pub fn set_str(&mut self, key: &'static str, value: &str) {
+        if let Some(data) = &mut self.0 {
+            data.attributes.push(json!({ "key": key, "value": { "stringValue": value } }));
+        }
+    }
+
+    pub fn set_int(&mut self, key: &'static str, value: i64) {
+        if let Some(data) = &mut self.0 {
+            // OTLP JSON 的 int64 以字串表示
+            data.attributes.push(json!({ "key": key, "value": { "intValue": value.to_string() } }));
+        }
+    }
+
+    /// 標記 span 失敗
+    pub fn set_error(&mut self, message: &str) {
+        if let Some(data) = &mut self.0 {
+            data.error = Some(message.to_string());
+        }
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        let Some(data) = self.0.take() else {
+            return;
+        };
+        let mut span = json!({
+            "traceId": data.trace_id,
+            "spanId": data.span_id,
+            "name": data.name,
+            "kind": data.kind as i32,
+            "startTimeUnixNano": data.start.to_string(),
+            "endTimeUnixNano": now_nanos().to_string(),
+            "attributes": data.attributes,
+            "status": match &data.error {
+                Some(message) => json!({ "code": 2, "message": message }),
+                None => json!({ "code": 1 }),
+            },
+        });
+        if let Some(parent) = data.parent_span_id {
+            span["parentSpanId"] = json!(parent);
+        }
+        let mut buffer = data.buffer.lock().unwrap_or_else(|e| e.into_inner());
+        if buffer.len() >= MAX_BUFFERED {
+            buffer.pop_front();
+        }
+        buffer.push_back(span);
+    }
+}
+
+/// 取出緩衝區的 span，組成 OTLP `ExportTraceServiceRequest`；沒有 span 時為 None
+fn take_batch(buffer: &SpanBuffer, service_name: &str) -> Option<Value> {
+    let spans: Vec<Value> = buffer.lock().unwrap_or_else(|e| e.into_inner()).drain(..).collect();
+    if spans.is_empty() {
+        return None;
+    }
+    Some(json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [
+                    { "key": "service.name", "value": { "stringValue": service_name } },
+                    { "key": "service.version", "value": { "stringValue": env!("CARGO_PKG_VERSION") } },
+                ]
+            },
+            "scopeSpans": [{
+                "scope": { "name": "stockenboard" },
+                "spans": spans,
+            }]
+        }]
+    }))
+}
+
+/// 啟動背景匯出
+pub fn start() {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(Duration::from_secs(EXPORT_INTERVAL_SECS)).await;
+            let config = otlp_config();
+            if !config.enabled {
+                continue;
+            }
+            let Some(batch) = take_batch(&BUFFER, &config.service_name) else {
+                continue;
+            };
+            let result = crate::providers::shared_client()
+                .post(config.traces_url())
+                .json(&batch)
+                .timeout(Duration::from_secs(10))
+                .send()
+                .await;
+            match result {
+                Ok(resp) if !resp.status().is_success() => {
+                    tracing::warn!("[Telemetry] OTLP export rejected: HTTP {}", resp.status());
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("[Telemetry] OTLP export failed: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spans_export_as_otlp_json_only_when_enabled() {
+        // 不修改全域設定，改用測試自己的緩衝區
+        let buffer: &'static SpanBuffer = Box::leak(Box::default());
+
+        // 停用時不產生任何 span
+        drop(Span::start(buffer, false, "disabled", SpanKind::Internal, None));
+        assert!(buffer.lock().unwrap().is_empty());
+
+        {
+            let mut root = Span::start(buffer, true, "test.fetch", SpanKind::Client, None);
+            root.set_str("provider.id", "binance");
+            root.set_int("symbols", 3);
+            let mut child = root.child("test.acquire", SpanKind::Internal);
+            child.set_error("timeout");
+        }
+        let batch = take_batch(buffer, "test-service").unwrap();
+        assert!(take_batch(buffer, "test-service").is_none());
+
+        let spans = batch["resourceSpans"][0]["scopeSpans"][0]["spans"].as_array().unwrap();
+        assert_eq!(spans.len(), 2);
+        let find = |name: &str| spans.iter().find(|s| s["name"] == name).unwrap();
+        let (root, child) = (find("test.fetch"), find("test.acquire"));
+        assert_eq!(root["kind"], 3);
+        assert_eq!(root["traceId"].as_str().unwrap().len(), 32);
+        assert_eq!(root["attributes"][1]["value"]["intValue"], "3");
+        assert!(root.get("parentSpanId").is_none());
+        assert_eq!(child["traceId"], root["traceId"]);
+        assert_eq!(child["parentSpanId"], root["spanId"]);
+        assert_eq!(child["status"]["code"], 2);
+        assert_eq!(
+            batch["resourceSpans"][0]["resource"]["attributes"][0]["value"]["stringValue"],
+            "test-service"
+        );
+    }
+
+    #[test]
+    fn config_validation() {
+        let config = OtlpConfig {
+            enabled: true,
+            endpoint: " https://otel.example.com/ ".to_string(),
+            service_name: "  ".to_string(),
+        }
+        .normalized()
+        .unwrap();
+        assert_eq!(config.traces_url(), "https://otel.example.com/v1/traces");
+        assert_eq!(config.service_name, "stockenboard");
+        assert!(OtlpConfig { enabled: true, endpoint: "localhost:4318".to_string(), ..Default::default() }
+            .normalized()
+            .is_err());
+    }
+}
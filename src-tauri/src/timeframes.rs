@@ -0,0 +1,158 @@
+//! 多時間框漲跌幅 — 多數 provider 只提供 24h 漲跌，這裡以已紀錄的 price_history 推算
+//! 1h / 7d / 30d / 今年以來（YTD，UTC）的漲跌幅。
+//!
+//! 背景每 5 分鐘為每個訂閱查詢各時間框的基準價（最接近該時間點、且在容許範圍內的一筆紀錄）並快取；
+//! `get_cached_prices`、`/prices/cached` 與 `/prices/changes` 的每筆快取價格以目前價格對基準價計算 `changes`。
+//! 紀錄不足以涵蓋的時間框為 null。
+
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, RwLock};
+use std::time::Duration;
+
+use chrono::{Datelike, TimeZone, Utc};
+use serde::Serialize;
+
+use crate::db::DbPool;
+
+const DAY_SECS: i64 = 86_400;
+/// 基準價重新查詢間隔
+const REFRESH_SECS: u64 = 300;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Timeframe {
+    Hour,
+    Week,
+    Month,
+    Ytd,
+}
+
+const TIMEFRAMES: [Timeframe; 4] = [Timeframe::Hour, Timeframe::Week, Timeframe::Month, Timeframe::Ytd];
+
+impl Timeframe {
+    /// 基準時間點（Unix 秒）
+    fn target(self, now: i64) -> i64 {
+        match self {
+            Timeframe::Hour => now - 3_600,
+            Timeframe::Week => now - 7 * DAY_SECS,
+            Timeframe::Month => now - 30 * DAY_SECS,
+            Timeframe::Ytd => year_start(now),
+        }
+    }
+
+    /// 基準價紀錄與時間點可相差的秒數
+    fn tolerance(self) -> i64 {
+        match self {
+            Timeframe::Hour => 900,
+            Timeframe::Week => DAY_SECS,
+            Timeframe::Month => 3 * DAY_SECS,
+            Timeframe::Ytd => 7 * DAY_SECS,
+        }
+    }
+}
+
+/// 各時間框漲跌幅（%）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct TimeframeChanges {
+    pub change_1h: Option<f64>,
+    pub change_7d: Option<f64>,
+    pub change_30d: Option<f64>,
+    pub change_ytd: Option<f64>,
+}
+
+/// `provider:symbol` → 依 [`TIMEFRAMES`] 順序的基準價
+static BASELINES: LazyLock<RwLock<HashMap<String, [Option<f64>; 4]>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// 當年 1 月 1 日 00:00 UTC
+fn year_start(now: i64) -> i64 {
+    let year = Utc.timestamp_opt(now, 0).single().map_or(1970, |d| d.year());
+    Utc.with_ymd_and_hms(year, 1, 1, 0, 0, 0)
+        .single()
+        .map_or(0, |d| d.timestamp())
+}
+
+fn percent(price: f64, base: Option<f64>) -> Option<f64> {
+    base.filter(|b| *b > 0.0 && price > 0.0).map(|b| (price - b) / b * 100.0)
+}
+
+/// 以目前價格計算快取的多時間框漲跌；尚無任何基準價時為 None
+pub fn changes_for(provider_id: &str, symbol: &str, price: f64) -> Option<TimeframeChanges> {
+    let baselines = BASELINES.read().unwrap();
+    let [h1, d7, d30, ytd] = *baselines.get(&format!("{}:{}", provider_id, symbol))?;
+    Some(TimeframeChanges {
+        change_1h: percent(price, h1),
+        change_7d: percent(price, d7),
+        change_30d: percent(price, d30),
+        change_ytd: percent(price, ytd),
+    })
+}
+
+/// 重新查詢所有訂閱的基準價，回傳有基準價的訂閱數
+pub fn refresh(db: &DbPool, now: i64) -> Result<usize, String> {
+    let mut baselines = HashMap::new();
+    for sub in db.list_all_subscriptions()? {
+        let mut prices = [None; 4];
+        for (slot, tf) in prices.iter_mut().zip(TIMEFRAMES) {
+            *slot = db.get_price_near(sub.id, tf.target(now), tf.tolerance())?;
+        }
+        if prices.iter().any(Option::is_some) {
+            baselines.insert(format!("{}:{}", sub.selected_provider_id, sub.symbol), prices);
+        }
+    }
+    let count = baselines.len();
+    *BASELINES.write().unwrap() = baselines;
+    Ok(count)
+}
+
+/// 啟動背景基準價更新
+pub fn start(db: Arc<DbPool>) {
+    tokio::spawn(async move {
+        loop {
+            if let Err(e) = refresh(&db, Utc::now().timestamp()) {
+                tracing::warn!("[Timeframes] baseline refresh failed: {}", e);
+            }
+            tokio::time::sleep(Duration::from_secs(REFRESH_SECS)).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn year_start_is_january_first_utc() {
+        // 2026-10-18 12:00 UTC
+        assert_eq!(year_start(1_792_324_800), 1_767_225_600);
+    }
+
+    #[test]
+    fn changes_use_nearest_record_within_tolerance() {
+        let db = DbPool::open(&PathBuf::from(":memory:")).unwrap();
+        let sub = db
+            .add_subscription("asset", "TFTEST", None, "binance", "crypto", None, None, None)
+            .unwrap();
+        let now = year_start(1_792_324_800) + 200 * DAY_SECS;
+        db.insert_price_history_for_test(
+            sub,
+            "binance",
+            &[
+                (80.0, None, None, now - 31 * DAY_SECS),
+                (90.0, None, None, now - 7 * DAY_SECS + 600),
+                (99.0, None, None, now - 3_600 - 60),
+                (100.0, None, None, now - 60),
+            ],
+        )
+        .unwrap();
+        refresh(&db, now).unwrap();
+
+        let changes = changes_for("binance", "TFTEST", 110.0).unwrap();
+        assert!((changes.change_1h.unwrap() - 11.111).abs() < 1e-3);
+        assert!((changes.change_7d.unwrap() - 22.222).abs() < 1e-3);
+        assert!((changes.change_30d.unwrap() - 37.5).abs() < 1e-9);
+        // 紀錄沒有涵蓋年初
+        assert_eq!(changes.change_ytd, None);
+        assert!(changes_for("binance", "OTHER", 110.0).is_none());
+    }
+}
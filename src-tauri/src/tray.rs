@@ -0,0 +1,321 @@
+//! System tray — 顯示置頂 symbol 的即時價格（取自 polling 快取），
+//! 並提供背景輪詢／價格紀錄開關，左鍵點擊 icon 切換主視窗顯示。
+//!
+//! 置頂清單存於 app settings：`tray_pinned`（subscription id 的 JSON 陣列）與 `tray_max_items`。
+//! `close_to_tray` 開啟時，關閉主視窗只會隱藏到 tray，並切換為 unattended polling 繼續紀錄。
+//! 重複啟動 app 時（single-instance）也由此聚焦既有視窗。
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Manager, Window, WindowEvent, Wry};
+use tokio::sync::broadcast;
+
+use crate::clipboard::format_price;
+use crate::core_state::CoreState;
+use crate::db::{DbPool, Subscription};
+use crate::events::AppEvent;
+
+const TRAY_ID: &str = "main";
+const MAIN_WINDOW: &str = "main";
+/// 價格更新時重建選單的最短間隔
+const REFRESH_THROTTLE: Duration = Duration::from_secs(2);
+pub const MAX_ITEMS_LIMIT: usize = 20;
+
+const MENU_PRICE_PREFIX: &str = "tray-price-";
+const MENU_UNATTENDED: &str = "tray-unattended";
+const MENU_RECORDING: &str = "tray-recording";
+const MENU_TOGGLE_WINDOW: &str = "tray-toggle-window";
+const MENU_QUIT: &str = "tray-quit";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrayConfig {
+    /// 置頂的 subscription id（依序顯示）
+    pub pinned: Vec<i64>,
+    /// 最多顯示幾筆
+    pub max_items: usize,
+}
+
+impl Default for TrayConfig {
+    fn default() -> Self {
+        Self {
+            pinned: Vec::new(),
+            max_items: 5,
+        }
+    }
+}
+
+pub fn load_config(db: &DbPool) -> TrayConfig {
+    let setting = |key: &str| db.get_setting(key).ok().flatten();
+    let defaults = TrayConfig::default();
+    TrayConfig {
+        pinned: setting("tray_pinned")
+            .and_then(|v| serde_json::from_str(&v).ok())
+            .unwrap_or(defaults.pinned),
+        max_items: setting("tray_max_items")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(defaults.max_items),
+    }
+}
+
+pub fn save_config(db: &DbPool, config: &TrayConfig) -> Result<(), String> {
+    if !(1..=MAX_ITEMS_LIMIT).contains(&config.max_items) {
+        return Err(format!("Tray item count must be between 1 and {}", MAX_ITEMS_LIMIT));
+    }
+    let pinned = serde_json::to_string(&config.pinned).map_err(|e| e.to_string())?;
+    db.set_setting("tray_pinned", &pinned)?;
+    db.set_setting("tray_max_items", &config.max_items.to_string())
+}
+
+pub fn close_to_tray_enabled(db: &DbPool) -> bool {
+    db.get_setting("close_to_tray").ok().flatten().is_some_and(|v| v == "1")
+}
+
+pub fn set_close_to_tray(db: &DbPool, enabled: bool) -> Result<(), String> {
+    db.set_setting("close_to_tray", if enabled { "1" } else { "0" })
+}
+
+/// 主視窗關閉請求：`close_to_tray` 開啟時改為隱藏，並確保 polling 在背景持續執行
+pub fn on_window_event(window: &Window, event: &WindowEvent) {
+    let WindowEvent::CloseRequested { api, .. } = event else {
+        return;
+    };
+    if window.label() != MAIN_WINDOW {
+        return;
+    }
+    let Some(core) = window.try_state::<Arc<CoreState>>() else {
+        return;
+    };
+    if !close_to_tray_enabled(&core.db) || window.app_handle().tray_by_id(TRAY_ID).is_none() {
+        return;
+    }
+
+    api.prevent_close();
+    let _ = window.hide();
+    let core = core.inner().clone();
+    let app = window.app_handle().clone();
+    tauri::async_runtime::spawn(async move {
+        core.polling.set_unattended(true).await;
+        refresh(&app).await;
+    });
+}
+
+/// 已有執行中的實例時，第二次啟動只會把參數交給這裡並聚焦既有視窗。
+/// `stockenboard://` 參數由 single-instance 的 deep-link 整合轉交給 deep-link plugin 處理。
+pub fn on_second_instance(app: &AppHandle, _argv: Vec<String>, _cwd: String) {
+    tracing::info!("[App] Second instance launched, focusing existing window");
+    show_main_window(app);
+}
+
+/// 建立 tray icon，並在價格更新時（節流）刷新選單。須在 `app.manage(core)` 之後呼叫。
+pub fn init(app: &AppHandle, core: &CoreState) -> tauri::Result<()> {
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("StockenBoard")
+        .show_menu_on_left_click(false)
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                toggle_main_window(tray.app_handle());
+            }
+        })
+        .on_menu_event(|app, event| handle_menu_event(app, event.id().as_ref()));
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+
+    let app = app.clone();
+    let mut event_rx = core.event_bus.subscribe();
+    tauri::async_runtime::spawn(async move {
+        refresh(&app).await;
+        let mut last_refresh = Instant::now();
+        loop {
+            match event_rx.recv().await {
+                Ok(AppEvent::PriceUpdate { .. }) => {
+                    if last_refresh.elapsed() >= REFRESH_THROTTLE {
+                        refresh(&app).await;
+                        last_refresh = Instant::now();
+                    }
+                }
+                Ok(_) => {}
+                Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+    Ok(())
+}
+
+/// 依目前快取價格與開關狀態重建 tray 選單（設定變更後也應呼叫）
+pub async fn refresh(app: &AppHandle) {
+    let (Some(core), Some(tray)) = (app.try_state::<Arc<CoreState>>(), app.tray_by_id(TRAY_ID))
+    else {
+        return;
+    };
+    let core = core.inner().clone();
+
+    let lines = pinned_price_lines(&core).await;
+    let unattended = core.polling.is_unattended().await;
+    let recording = !core.polling.is_recording_paused();
+
+    match build_menu(app, &lines, unattended, recording) {
+        Ok(menu) => {
+            let _ = tray.set_menu(Some(menu));
+        }
+        Err(e) => tracing::warn!("[Tray] Failed to build menu: {}", e),
+    }
+    let tooltip = match lines.first() {
+        Some(line) => format!("StockenBoard — {}", line),
+        None => "StockenBoard".to_string(),
+    };
+    let _ = tray.set_tooltip(Some(tooltip));
+}
+
+async fn pinned_price_lines(core: &CoreState) -> Vec<String> {
+    let config = load_config(&core.db);
+    if config.pinned.is_empty() {
+        return Vec::new();
+    }
+    let subs = core.db.list_all_subscriptions().unwrap_or_default();
+    let cache = core.polling.cache.read().await;
+    config
+        .pinned
+        .iter()
+        .filter_map(|id| subs.iter().find(|s| s.id == *id))
+        .take(config.max_items)
+        .map(|sub| {
+            let label = sub
+                .display_name
+                .as_deref()
+                .filter(|n| !n.is_empty())
+                .unwrap_or(&sub.symbol);
+            match cache.get(&cache_key(sub)) {
+                Some(data) => {
+                    let change = data
+                        .change_percent_24h
+                        .map(|p| format!(" ({:+.2}%)", p))
+                        .unwrap_or_default();
+                    format!("{}  {}{}", label, format_price(data.price, data.display_scale()), change)
+                }
+                None => format!("{}  —", label),
+            }
+        })
+        .collect()
+}
+
+/// 與 polling 快取相同的 key：`provider:symbol`（DEX 為 `provider:pool:from:to`）
+fn cache_key(sub: &Subscription) -> String {
+    if sub.sub_type == "dex" {
+        format!(
+            "{}:{}:{}:{}",
+            sub.selected_provider_id,
+            sub.pool_address.as_deref().unwrap_or_default(),
+            sub.token_from_address.as_deref().unwrap_or_default(),
+            sub.token_to_address.as_deref().unwrap_or_default()
+        )
+    } else {
+        format!("{}:{}", sub.selected_provider_id, sub.symbol)
+    }
+}
+
+fn build_menu(
+    app: &AppHandle,
+    lines: &[String],
+    unattended: bool,
+    recording: bool,
+) -> tauri::Result<Menu<Wry>> {
+    let menu = Menu::new(app)?;
+    if lines.is_empty() {
+        menu.append(&MenuItem::with_id(
+            app,
+            format!("{}none", MENU_PRICE_PREFIX),
+            "No pinned symbols",
+            false,
+            None::<&str>,
+        )?)?;
+    }
+    for (i, line) in lines.iter().enumerate() {
+        menu.append(&MenuItem::with_id(
+            app,
+            format!("{}{}", MENU_PRICE_PREFIX, i),
+            line,
+            true,
+            None::<&str>,
+        )?)?;
+    }
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+    menu.append(&CheckMenuItem::with_id(
+        app,
+        MENU_UNATTENDED,
+        "Background polling",
+        true,
+        unattended,
+        None::<&str>,
+    )?)?;
+    menu.append(&CheckMenuItem::with_id(
+        app,
+        MENU_RECORDING,
+        "Record price history",
+        true,
+        recording,
+        None::<&str>,
+    )?)?;
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+    menu.append(&MenuItem::with_id(app, MENU_TOGGLE_WINDOW, "Show / Hide", true, None::<&str>)?)?;
+    menu.append(&MenuItem::with_id(app, MENU_QUIT, "Quit", true, None::<&str>)?)?;
+    Ok(menu)
+}
+
+fn handle_menu_event(app: &AppHandle, id: &str) {
+    match id {
+        MENU_TOGGLE_WINDOW => toggle_main_window(app),
+        MENU_QUIT => app.exit(0),
+        MENU_UNATTENDED | MENU_RECORDING => {
+            let Some(core) = app.try_state::<Arc<CoreState>>() else {
+                return;
+            };
+            let core = core.inner().clone();
+            let app = app.clone();
+            let toggle_unattended = id == MENU_UNATTENDED;
+            tauri::async_runtime::spawn(async move {
+                if toggle_unattended {
+                    let enabled = !core.polling.is_unattended().await;
+                    core.polling.set_unattended(enabled).await;
+                } else if let Err(e) =
+                    core.set_recording_paused(!core.polling.is_recording_paused())
+                {
+                    tracing::warn!("[Tray] Failed to toggle recording: {}", e);
+                }
+                refresh(&app).await;
+            });
+        }
+        _ if id.starts_with(MENU_PRICE_PREFIX) => show_main_window(app),
+        _ => {}
+    }
+}
+
+fn toggle_main_window(app: &AppHandle) {
+    let Some(window) = app.get_webview_window(MAIN_WINDOW) else {
+        return;
+    };
+    if window.is_visible().unwrap_or(false) {
+        let _ = window.hide();
+    } else {
+        show_main_window(app);
+    }
+}
+
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
@@ -0,0 +1,145 @@
+//! Provider 健康監控 — 連續失敗的 provider 會進入指數退避（最長 5 分鐘），期間完全不取價；
+//! provider 恢復後仍要等退避結束才回到輪詢。
+//!
+//! Watchdog 每 [`PROBE_INTERVAL_SECS`] 秒檢查一次，對剩餘退避時間超過檢查間隔的 provider
+//! 以單一 symbol 發出一次輕量請求（經過 registry 的 rate limit）；成功即清除退避狀態，
+//! 讓 polling 在下一個間隔恢復取價，並送出 `AppEvent::ProviderRecovered`（前端事件 `provider-recovered`）。
+//! 探測失敗不增加失敗次數。
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tokio::sync::{broadcast, watch, RwLock};
+
+use crate::basket;
+use crate::db::DbPool;
+use crate::events::AppEvent;
+use crate::polling::BackoffState;
+use crate::providers::registry::ProviderRegistry;
+
+/// 檢查間隔
+pub const PROBE_INTERVAL_SECS: u64 = 30;
+
+/// 被 watchdog 恢復的 provider
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProviderRecovered {
+    pub provider_id: String,
+    /// 恢復前的連續失敗次數
+    pub consecutive_failures: u32,
+    /// Unix ms
+    pub recovered_at: i64,
+}
+
+/// 剩餘退避時間超過檢查間隔、需要探測的 provider
+pub fn suspended(backoff: &HashMap<String, BackoffState>, now: Instant) -> Vec<String> {
+    let horizon = now + Duration::from_secs(PROBE_INTERVAL_SECS);
+    let mut ids: Vec<String> = backoff
+        .iter()
+        .filter(|(id, state)| id.as_str() != basket::PROVIDER_ID && state.next_allowed_at > horizon)
+        .map(|(id, _)| id.clone())
+        .collect();
+    ids.sort();
+    ids
+}
+
+/// 探測成功：清除退避狀態（polling 已自行恢復時回傳 None）
+pub fn mark_recovered(backoff: &mut HashMap<String, BackoffState>, provider_id: &str) -> Option<ProviderRecovered> {
+    let state = backoff.remove(provider_id)?;
+    Some(ProviderRecovered {
+        provider_id: provider_id.to_string(),
+        consecutive_failures: state.consecutive_failures,
+        recovered_at: chrono::Utc::now().timestamp_millis(),
+    })
+}
+
+/// 探測一輪：每個被暫停的 provider 以第一個訂閱的 symbol 取價一次
+async fn probe(
+    backoff: &RwLock<HashMap<String, BackoffState>>,
+    db: &Arc<DbPool>,
+    registry: &ProviderRegistry,
+    event_bus: &broadcast::Sender<AppEvent>,
+) {
+    let ids = suspended(&*backoff.read().await, Instant::now());
+    if ids.is_empty() {
+        return;
+    }
+    let subs = match db.read_polling_subscriptions(None) {
+        Ok(subs) => subs,
+        Err(e) => {
+            tracing::warn!("[Watchdog] Failed to read subscriptions: {}", e);
+            return;
+        }
+    };
+    for pid in ids {
+        let Some((_, symbol, _, _)) = subs.iter().find(|(_, _, provider_id, _)| *provider_id == pid) else {
+            continue;
+        };
+        match registry.fetch_with_limit(&pid, std::slice::from_ref(symbol), db).await {
+            Ok(results) if !results.is_empty() => {
+                if let Some(recovered) = mark_recovered(&mut *backoff.write().await, &pid) {
+                    tracing::info!(
+                        "[Watchdog] {} recovered after {} consecutive failures",
+                        pid,
+                        recovered.consecutive_failures
+                    );
+                    let _ = event_bus.send(AppEvent::ProviderRecovered(recovered));
+                }
+            }
+            Ok(_) => tracing::debug!("[Watchdog] {} probe returned no data", pid),
+            Err(e) => tracing::debug!("[Watchdog] {} still failing: {}", pid, e),
+        }
+    }
+}
+
+/// 啟動 watchdog（隨 polling 停止）
+pub fn start(
+    backoff: Arc<RwLock<HashMap<String, BackoffState>>>,
+    db: Arc<DbPool>,
+    registry: Arc<ProviderRegistry>,
+    event_bus: broadcast::Sender<AppEvent>,
+    mut stop_rx: watch::Receiver<bool>,
+) {
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep(Duration::from_secs(PROBE_INTERVAL_SECS)) => {}
+                _ = stop_rx.changed() => break,
+            }
+            probe(&backoff, &db, &registry, &event_bus).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(failures: u32, wait: Duration) -> BackoffState {
+        BackoffState {
+            consecutive_failures: failures,
+            next_allowed_at: Instant::now() + wait,
+        }
+    }
+
+    #[test]
+    fn only_long_backoffs_are_probed() {
+        let backoff = HashMap::from([
+            ("yahoo".to_string(), state(8, Duration::from_secs(256))),
+            ("binance".to_string(), state(2, Duration::from_secs(4))),
+            (basket::PROVIDER_ID.to_string(), state(9, Duration::from_secs(300))),
+            ("coingecko".to_string(), state(6, Duration::from_secs(64))),
+        ]);
+        assert_eq!(suspended(&backoff, Instant::now()), ["coingecko", "yahoo"]);
+    }
+
+    #[test]
+    fn recovery_clears_backoff_once() {
+        let mut backoff = HashMap::from([("yahoo".to_string(), state(7, Duration::from_secs(128)))]);
+        let recovered = mark_recovered(&mut backoff, "yahoo").unwrap();
+        assert_eq!((recovered.provider_id.as_str(), recovered.consecutive_failures), ("yahoo", 7));
+        assert!(backoff.is_empty());
+        assert!(mark_recovered(&mut backoff, "yahoo").is_none());
+    }
+}
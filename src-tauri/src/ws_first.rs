@@ -0,0 +1,232 @@
+//! WS 優先輪詢（選用）— 支援 WebSocket 的 provider（`ProviderInfo.supports_websocket`）改由
+//! [`WsManager`] 串流取價，不再以 HTTP 輪詢，大幅減少 REST rate limit 用量。
+//!
+//! polling 以 [`OWNER`] 向 `WsManager` 要求各 provider 的訂閱 symbol，每個 tick 依連線狀態決定來源：
+//! - 已連線：送出上一個 tick 以來收到的最新 ticker（沿用原本的 tick 間隔、`price-update` 與紀錄）
+//! - 未連線未超過 `fallback_secs`：等待重連，不取價
+//! - 未連線超過 `fallback_secs`、重連次數用盡或省電模式暫停：改回 HTTP 輪詢，重新連上後自動切回串流
+//!
+//! 設定存於 app settings（`ws_first_enabled`、`ws_first_fallback_secs`）。
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, LazyLock, Mutex, RwLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::providers::{AssetData, WsTickerUpdate};
+use crate::ws_manager::{WsConnectionState, WsManager, WsStatus};
+
+/// polling 在 WsManager 中的 owner
+pub const OWNER: &str = "polling";
+/// 可設定的斷線容忍秒數範圍
+pub const MIN_FALLBACK_SECS: u64 = 1;
+pub const MAX_FALLBACK_SECS: u64 = 3_600;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WsFirstConfig {
+    pub enabled: bool,
+    /// 斷線超過此秒數後改回 HTTP 輪詢
+    pub fallback_secs: u64,
+}
+
+impl Default for WsFirstConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            fallback_secs: 15,
+        }
+    }
+}
+
+impl WsFirstConfig {
+    /// 檢查設定
+    pub fn normalized(self) -> Result<Self, String> {
+        if !(MIN_FALLBACK_SECS..=MAX_FALLBACK_SECS).contains(&self.fallback_secs) {
+            return Err(format!(
+                "Fallback delay must be between {} and {} seconds",
+                MIN_FALLBACK_SECS, MAX_FALLBACK_SECS
+            ));
+        }
+        Ok(self)
+    }
+}
+
+static CONFIG: LazyLock<RwLock<WsFirstConfig>> = LazyLock::new(|| RwLock::new(WsFirstConfig::default()));
+
+pub fn set_ws_first_config(config: WsFirstConfig) {
+    *CONFIG.write().unwrap_or_else(|e| e.into_inner()) = config;
+}
+
+pub fn ws_first_config() -> WsFirstConfig {
+    CONFIG.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// 單一 provider 在某個 tick 的取價來源
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedMode {
+    /// 使用串流收到的 ticker
+    Stream,
+    /// 斷線中，等待重連
+    Wait,
+    /// HTTP 輪詢
+    Poll,
+}
+
+/// 依連線狀態決定取價來源
+pub fn feed_mode(status: Option<&WsStatus>, fallback_secs: u64, now_ms: i64) -> FeedMode {
+    let Some(status) = status else {
+        return FeedMode::Poll;
+    };
+    match status.state {
+        WsConnectionState::Connected => FeedMode::Stream,
+        WsConnectionState::Failed | WsConnectionState::Stopped => FeedMode::Poll,
+        WsConnectionState::Connecting | WsConnectionState::Reconnecting | WsConnectionState::Suspended => {
+            let down_ms = now_ms - status.disconnected_at.unwrap_or(now_ms);
+            if down_ms > fallback_secs.saturating_mul(1000) as i64 {
+                FeedMode::Poll
+            } else {
+                FeedMode::Wait
+            }
+        }
+    }
+}
+
+/// 一輪 polling 設定的串流來源：訂閱的 provider / symbol 與上一個 tick 以來收到的最新 ticker
+pub struct WsFeed {
+    ws: Arc<WsManager>,
+    fallback_secs: u64,
+    streamed: HashMap<String, HashSet<String>>,
+    latest: Mutex<HashMap<String, HashMap<String, AssetData>>>,
+}
+
+impl WsFeed {
+    /// 向 WsManager 要求各 provider 的 symbol（不支援 WebSocket 的 provider 略過），
+    /// 並取消上一輪要求、這一輪已不需要的 provider
+    pub fn subscribe(ws: Arc<WsManager>, fallback_secs: u64, groups: Vec<(String, Vec<String>)>) -> Self {
+        let mut streamed = HashMap::new();
+        for (provider_id, symbols) in groups {
+            let supported = crate::providers::types::get_provider_info(&provider_id)
+                .is_some_and(|info| info.supports_websocket);
+            if supported && ws.set_symbols(OWNER, &provider_id, symbols.clone()).is_ok() {
+                streamed.insert(provider_id, symbols.into_iter().collect());
+            }
+        }
+        for provider_id in ws.owned_symbols(OWNER).into_keys() {
+            if !streamed.contains_key(&provider_id) {
+                let _ = ws.set_symbols(OWNER, &provider_id, Vec::new());
+            }
+        }
+        Self {
+            ws,
+            fallback_secs,
+            streamed,
+            latest: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn streams(&self, provider_id: &str) -> bool {
+        self.streamed.contains_key(provider_id)
+    }
+
+    /// 記錄串流收到的 ticker（只保留 polling 訂閱的 symbol 的最新一筆）
+    pub fn observe(&self, update: WsTickerUpdate) {
+        if !self
+            .streamed
+            .get(&update.provider_id)
+            .is_some_and(|symbols| symbols.contains(&update.symbol))
+        {
+            return;
+        }
+        self.latest
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(update.provider_id)
+            .or_default()
+            .insert(update.symbol, update.data);
+    }
+
+    pub fn mode(&self, provider_id: &str, now_ms: i64) -> FeedMode {
+        feed_mode(self.ws.status(provider_id).as_ref(), self.fallback_secs, now_ms)
+    }
+
+    /// 取出 provider 上一個 tick 以來收到的最新 ticker
+    pub fn take(&self, provider_id: &str) -> Vec<AssetData> {
+        self.latest
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(provider_id)
+            .map(|latest| latest.into_values().collect())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::AssetDataBuilder;
+
+    fn status(state: WsConnectionState, disconnected_at: Option<i64>) -> WsStatus {
+        WsStatus {
+            provider_id: "binance".to_string(),
+            state,
+            symbols: vec!["BTC".to_string()],
+            attempt: 0,
+            error: None,
+            since: 0,
+            connected_at: None,
+            disconnected_at,
+        }
+    }
+
+    #[test]
+    fn falls_back_after_socket_stays_down() {
+        let now = 100_000;
+        assert_eq!(feed_mode(None, 15, now), FeedMode::Poll);
+        assert_eq!(feed_mode(Some(&status(WsConnectionState::Connected, None)), 15, now), FeedMode::Stream);
+        for state in [WsConnectionState::Connecting, WsConnectionState::Reconnecting, WsConnectionState::Suspended] {
+            assert_eq!(feed_mode(Some(&status(state, Some(now - 15_000))), 15, now), FeedMode::Wait);
+            assert_eq!(feed_mode(Some(&status(state, Some(now - 15_001))), 15, now), FeedMode::Poll);
+        }
+        for state in [WsConnectionState::Failed, WsConnectionState::Stopped] {
+            assert_eq!(feed_mode(Some(&status(state, Some(now))), 15, now), FeedMode::Poll);
+        }
+        assert!(WsFirstConfig { fallback_secs: 0, ..Default::default() }.normalized().is_err());
+        assert!(WsFirstConfig::default().normalized().is_ok());
+    }
+
+    #[tokio::test]
+    async fn feed_streams_supported_providers_and_keeps_latest_tick() {
+        let (event_bus, _) = tokio::sync::broadcast::channel(64);
+        let ws = Arc::new(WsManager::new(event_bus));
+        // 暫停中只記錄訂閱集合，不實際連線
+        ws.suspend();
+        let feed = WsFeed::subscribe(
+            ws.clone(),
+            15,
+            vec![
+                ("binance".to_string(), vec!["BTC".to_string()]),
+                ("coingecko".to_string(), vec!["bitcoin".to_string()]),
+            ],
+        );
+        assert!(feed.streams("binance") && !feed.streams("coingecko"));
+        assert_eq!(ws.owned_symbols(OWNER).into_keys().collect::<Vec<_>>(), vec!["binance"]);
+
+        let tick = |symbol: &str, price: f64| WsTickerUpdate {
+            symbol: symbol.to_string(),
+            provider_id: "binance".to_string(),
+            data: AssetDataBuilder::new(symbol, "binance").price(price).build(),
+        };
+        feed.observe(tick("BTC", 1.0));
+        feed.observe(tick("BTC", 2.0));
+        // 其他 owner 要求的 symbol 不屬於 polling
+        feed.observe(tick("ETH", 3.0));
+        let latest = feed.take("binance");
+        assert_eq!(latest.iter().map(|d| d.price).collect::<Vec<_>>(), vec![2.0]);
+        assert!(feed.take("binance").is_empty());
+
+        // 下一輪不再需要的 provider 取消訂閱
+        let feed = WsFeed::subscribe(ws.clone(), 15, Vec::new());
+        assert!(!feed.streams("binance"));
+        assert!(ws.owned_symbols(OWNER).is_empty());
+    }
+}
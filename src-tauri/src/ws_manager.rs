@@ -0,0 +1,702 @@
+//! WebSocket 連線管理 — 所有 provider 的 WS 連線集中於 [`WsManager`]。
+//!
+//! 每個 provider 最多一條連線，訂閱集合為所有 owner（桌面前端、`/api/ws` 的各個 client、REST API）
+//! 要求的聯集；集合變動時在既有連線上送出增減的 subscribe / unsubscribe，不重建 socket。
+//! provider（[`WebSocketProvider`]）只描述 URL、訊息格式與推送解析，連線、心跳與重連都在這裡處理。
+//!
+//! 連線狀態（connecting / connected / reconnecting / failed / suspended / stopped）變化時送出
+//! `AppEvent::WsStatus`（前端事件 `ws-status`）。斷線後以指數退避重連，連續失敗
+//! [`MAX_RECONNECT_ATTEMPTS`] 次後為 failed，直到訂閱集合變動才再嘗試。
+//! 省電模式下暫停所有連線（suspended），保留訂閱集合供恢復時重建。
+//!
+//! 連線套用 provider 的 proxy 設定（未設定則為全域 proxy），經由 [`ws_proxy`](crate::ws_proxy) 建立通道。
+//!
+//! 收到的 ticker 先彙整為 1 分鐘 K 線（`candles::observe`），再廣播給 [`WsManager::subscribe`] 的接收端。
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures::{SinkExt, StreamExt};
+use serde::Serialize;
+use tokio::sync::{broadcast, mpsc};
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::db::DbPool;
+use crate::events::AppEvent;
+use crate::providers::{create_ws_provider, HttpOptions, WebSocketProvider, WsStreamSymbols, WsTickerUpdate};
+use crate::ws_proxy::{self, WsStream};
+
+/// 連續重連失敗幾次後視為 failed
+pub const MAX_RECONNECT_ATTEMPTS: u32 = 10;
+const INITIAL_RECONNECT_DELAY_MS: u64 = 1000;
+
+type WsWrite = futures::stream::SplitSink<WsStream, Message>;
+type WsRead = futures::stream::SplitStream<WsStream>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WsConnectionState {
+    /// 首次連線中
+    Connecting,
+    Connected,
+    /// 斷線後等待或正在重連
+    Reconnecting,
+    /// 重連次數用盡，等待訂閱集合變動
+    Failed,
+    /// 省電模式暫停
+    Suspended,
+    /// 已無任何訂閱，連線關閉
+    Stopped,
+}
+
+/// 單一 provider 的 WS 連線狀態（`ws-status` 事件、`get_ws_streams`）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WsStatus {
+    pub provider_id: String,
+    pub state: WsConnectionState,
+    /// 所有 owner 要求的 symbol 聯集（已排序）
+    pub symbols: Vec<String>,
+    /// 目前連續重連失敗次數
+    pub attempt: u32,
+    /// 最近一次斷線或連線失敗的原因
+    pub error: Option<String>,
+    /// 進入目前狀態的時間（ms）
+    pub since: i64,
+    /// 目前連線建立的時間（ms）；未連線時為 None
+    pub connected_at: Option<i64>,
+    /// 目前這段未連線期間的開始時間（ms）；重連失敗不重設，已連線時為 None
+    pub disconnected_at: Option<i64>,
+}
+
+/// 連線 task 與 manager 共用的狀態，每次變更都送出 `WsStatus` 事件
+#[derive(Clone)]
+struct StatusHandle {
+    status: Arc<Mutex<WsStatus>>,
+    event_bus: broadcast::Sender<AppEvent>,
+}
+
+impl StatusHandle {
+    fn new(provider_id: &str, event_bus: broadcast::Sender<AppEvent>) -> Self {
+        Self {
+            status: Arc::new(Mutex::new(WsStatus {
+                provider_id: provider_id.to_string(),
+                state: WsConnectionState::Connecting,
+                symbols: Vec::new(),
+                attempt: 0,
+                error: None,
+                since: chrono::Utc::now().timestamp_millis(),
+                connected_at: None,
+                disconnected_at: Some(chrono::Utc::now().timestamp_millis()),
+            })),
+            event_bus,
+        }
+    }
+
+    fn get(&self) -> WsStatus {
+        self.status.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    fn update(&self, f: impl FnOnce(&mut WsStatus)) {
+        let snapshot = {
+            let mut status = self.status.lock().unwrap_or_else(|e| e.into_inner());
+            let before = status.state;
+            f(&mut status);
+            let now = chrono::Utc::now().timestamp_millis();
+            if status.state != before {
+                status.since = now;
+            }
+            match status.state {
+                WsConnectionState::Connected => {
+                    status.connected_at.get_or_insert(now);
+                    status.disconnected_at = None;
+                }
+                _ => {
+                    status.connected_at = None;
+                    status.disconnected_at.get_or_insert(now);
+                }
+            }
+            status.clone()
+        };
+        let _ = self.event_bus.send(AppEvent::WsStatus(snapshot));
+    }
+
+    fn set_state(&self, state: WsConnectionState, attempt: u32, error: Option<String>) {
+        self.update(|s| {
+            s.state = state;
+            s.attempt = attempt;
+            s.error = error;
+        });
+    }
+}
+
+/// 單一 provider 的連線
+struct Connection {
+    provider: Arc<dyn WebSocketProvider>,
+    /// owner → 要求的 symbol
+    owners: HashMap<String, BTreeSet<String>>,
+    /// 送出新的訂閱集合給連線 task；暫停時為 None
+    control: Option<mpsc::UnboundedSender<Vec<String>>>,
+    task: Option<JoinHandle<()>>,
+    status: StatusHandle,
+}
+
+impl Connection {
+    fn symbols(&self) -> Vec<String> {
+        let all: BTreeSet<&String> = self.owners.values().flatten().collect();
+        all.into_iter().cloned().collect()
+    }
+
+    fn stop_task(&mut self) {
+        self.control = None;
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+    }
+}
+
+/// 所有 WS 連線的擁有者
+pub struct WsManager {
+    sender: broadcast::Sender<WsTickerUpdate>,
+    event_bus: broadcast::Sender<AppEvent>,
+    connections: Mutex<HashMap<String, Connection>>,
+    suspended: Mutex<bool>,
+    /// 讀取 provider 的連線設定（proxy）；None = 只使用全域 proxy
+    db: Option<Arc<DbPool>>,
+}
+
+impl WsManager {
+    pub fn new(event_bus: broadcast::Sender<AppEvent>) -> Self {
+        Self {
+            sender: broadcast::channel(256).0,
+            event_bus,
+            connections: Mutex::new(HashMap::new()),
+            suspended: Mutex::new(false),
+            db: None,
+        }
+    }
+
+    pub fn with_db(mut self, db: Arc<DbPool>) -> Self {
+        self.db = Some(db);
+        self
+    }
+
+    /// provider 目前的連線設定（每次建立連線時讀取，設定變更在重連後生效）
+    fn http_options(&self, provider_id: &str) -> HttpOptions {
+        self.db
+            .as_ref()
+            .and_then(|db| db.get_provider_settings(provider_id).ok().flatten())
+            .map(|settings| HttpOptions::from(&settings))
+            .unwrap_or_default()
+    }
+
+    /// 接收所有串流的 ticker
+    pub fn subscribe(&self) -> broadcast::Receiver<WsTickerUpdate> {
+        self.sender.subscribe()
+    }
+
+    /// 設定 `owner` 對 provider 要求的 symbol（空集合為取消）；聯集有變動時增減連線上的訂閱。
+    /// 聯集超過 provider 的 stream 上限時拒絕，不變更既有訂閱
+    pub fn set_symbols(&self, owner: &str, provider_id: &str, symbols: Vec<String>) -> Result<(), String> {
+        if symbols.is_empty() {
+            return self.update_owner(owner, provider_id, symbols, None);
+        }
+        let provider = create_ws_provider(provider_id)
+            .ok_or_else(|| format!("{} does not support WebSocket", provider_id))?;
+        self.update_owner(owner, provider_id, symbols, Some(provider))
+    }
+
+    /// 移除 `owner` 在所有 provider 的要求（例如 `/api/ws` client 斷線）
+    pub fn remove_owner(&self, owner: &str) {
+        let provider_ids: Vec<String> = self.lock().keys().cloned().collect();
+        for provider_id in provider_ids {
+            let _ = self.update_owner(owner, &provider_id, Vec::new(), None);
+        }
+    }
+
+    /// `owner` 目前要求的 provider 與 symbol
+    pub fn owned_symbols(&self, owner: &str) -> HashMap<String, Vec<String>> {
+        self.lock()
+            .iter()
+            .filter_map(|(provider_id, conn)| {
+                let symbols = conn.owners.get(owner)?;
+                Some((provider_id.clone(), symbols.iter().cloned().collect()))
+            })
+            .collect()
+    }
+
+    /// 單一 provider 的連線狀態；沒有任何訂閱時為 None
+    pub fn status(&self, provider_id: &str) -> Option<WsStatus> {
+        self.lock().get(provider_id).map(|c| c.status.get())
+    }
+
+    /// 所有連線的狀態，依 provider 排序
+    pub fn statuses(&self) -> Vec<WsStatus> {
+        let mut list: Vec<WsStatus> = self.lock().values().map(|c| c.status.get()).collect();
+        list.sort_by(|a, b| a.provider_id.cmp(&b.provider_id));
+        list
+    }
+
+    /// 進入省電模式：中斷所有連線（訂閱集合保留，供恢復時重建）
+    pub fn suspend(&self) {
+        *self.suspended.lock().unwrap_or_else(|e| e.into_inner()) = true;
+        for conn in self.lock().values_mut() {
+            conn.stop_task();
+            conn.status.set_state(WsConnectionState::Suspended, 0, None);
+        }
+    }
+
+    /// 離開省電模式或從休眠喚醒：重建所有連線（休眠期間 socket 可能已無聲斷線）
+    pub fn resume(&self) {
+        *self.suspended.lock().unwrap_or_else(|e| e.into_inner()) = false;
+        for conn in self.lock().values_mut() {
+            conn.stop_task();
+            self.spawn(conn);
+        }
+    }
+
+    fn is_suspended(&self) -> bool {
+        *self.suspended.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Connection>> {
+        self.connections.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn update_owner(
+        &self,
+        owner: &str,
+        provider_id: &str,
+        symbols: Vec<String>,
+        provider: Option<Arc<dyn WebSocketProvider>>,
+    ) -> Result<(), String> {
+        let mut connections = self.lock();
+        if !symbols.is_empty() {
+            let existing = connections.get(provider_id);
+            if let Some(provider) = existing.map(|c| &c.provider).or(provider.as_ref()) {
+                check_stream_limit(provider.as_ref(), existing, owner, &symbols)?;
+            }
+        }
+        let conn = match (connections.get_mut(provider_id), provider) {
+            (Some(conn), _) => conn,
+            (None, Some(provider)) => connections.entry(provider_id.to_string()).or_insert_with(|| Connection {
+                provider,
+                owners: HashMap::new(),
+                control: None,
+                task: None,
+                status: StatusHandle::new(provider_id, self.event_bus.clone()),
+            }),
+            (None, None) => return Ok(()),
+        };
+        let before = conn.symbols();
+        if symbols.is_empty() {
+            conn.owners.remove(owner);
+        } else {
+            conn.owners.insert(owner.to_string(), symbols.into_iter().collect());
+        }
+        let after = conn.symbols();
+
+        if after.is_empty() {
+            if let Some(mut conn) = connections.remove(provider_id) {
+                conn.stop_task();
+                conn.status.update(|s| {
+                    s.state = WsConnectionState::Stopped;
+                    s.symbols.clear();
+                });
+            }
+            return Ok(());
+        }
+        if after == before && conn.task.as_ref().is_some_and(|t| !t.is_finished()) {
+            return Ok(());
+        }
+        conn.status.update(|s| s.symbols = after.clone());
+        if self.is_suspended() {
+            conn.status.set_state(WsConnectionState::Suspended, 0, None);
+            return Ok(());
+        }
+        let sent = conn.control.as_ref().is_some_and(|tx| tx.send(after).is_ok());
+        if !sent || conn.task.as_ref().is_some_and(|t| t.is_finished()) {
+            conn.stop_task();
+            self.spawn(conn);
+        }
+        Ok(())
+    }
+
+    fn spawn(&self, conn: &mut Connection) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        conn.control = Some(tx);
+        conn.task = Some(tokio::spawn(run_connection(
+            conn.provider.clone(),
+            self.http_options(conn.provider.provider_id()),
+            conn.symbols(),
+            rx,
+            self.sender.clone(),
+            conn.status.clone(),
+        )));
+    }
+}
+
+/// 檢查 `owner` 改為要求 `symbols` 後，連線的 stream 數是否超過 provider 上限
+fn check_stream_limit(
+    provider: &dyn WebSocketProvider,
+    conn: Option<&Connection>,
+    owner: &str,
+    symbols: &[String],
+) -> Result<(), String> {
+    let Some(limit) = provider.max_streams() else {
+        return Ok(());
+    };
+    let mut all: BTreeSet<String> = conn
+        .map(|c| {
+            c.owners
+                .iter()
+                .filter(|(o, _)| o.as_str() != owner)
+                .flat_map(|(_, s)| s.iter().cloned())
+                .collect()
+        })
+        .unwrap_or_default();
+    all.extend(symbols.iter().cloned());
+    let streams = provider.stream_symbols(&all.into_iter().collect::<Vec<_>>()).len();
+    if streams > limit {
+        return Err(format!(
+            "{} WebSocket supports at most {} streams per connection (requested {})",
+            provider.provider_id(),
+            limit,
+            streams
+        ));
+    }
+    Ok(())
+}
+
+/// 訂閱集合變動時需新增與移除的 stream id（各自排序）
+fn diff_streams(before: &WsStreamSymbols, after: &WsStreamSymbols) -> (Vec<String>, Vec<String>) {
+    let mut added: Vec<String> = after.keys().filter(|k| !before.contains_key(*k)).cloned().collect();
+    let mut removed: Vec<String> = before.keys().filter(|k| !after.contains_key(*k)).cloned().collect();
+    added.sort();
+    removed.sort();
+    (added, removed)
+}
+
+/// 連線 task：連線、轉發推送、套用訂閱集合變動，斷線時指數退避重連
+async fn run_connection(
+    provider: Arc<dyn WebSocketProvider>,
+    http: HttpOptions,
+    mut symbols: Vec<String>,
+    mut control: mpsc::UnboundedReceiver<Vec<String>>,
+    sender: broadcast::Sender<WsTickerUpdate>,
+    status: StatusHandle,
+) {
+    let provider_id = provider.provider_id();
+    let mut attempt = 0u32;
+    let mut last_error = None;
+    loop {
+        let state = if attempt == 0 {
+            WsConnectionState::Connecting
+        } else {
+            WsConnectionState::Reconnecting
+        };
+        status.set_state(state, attempt, last_error.clone());
+
+        let error = match connect(provider.as_ref(), &http, &symbols).await {
+            Ok((write, read)) => {
+                if attempt > 0 {
+                    tracing::info!("[WS] {} reconnected", provider_id);
+                }
+                attempt = 0;
+                status.set_state(WsConnectionState::Connected, 0, None);
+                match stream(provider.as_ref(), &mut symbols, &mut control, &sender, write, read).await {
+                    Some(e) => e,
+                    // manager 已移除此連線
+                    None => return,
+                }
+            }
+            Err(e) => e,
+        };
+        attempt += 1;
+        tracing::warn!("[WS] {} disconnected (attempt {}): {}", provider_id, attempt, error);
+        last_error = Some(error);
+
+        if attempt > MAX_RECONNECT_ATTEMPTS {
+            tracing::warn!("[WS] {} reconnect attempts exhausted ({})", provider_id, MAX_RECONNECT_ATTEMPTS);
+            status.set_state(WsConnectionState::Failed, attempt - 1, last_error.clone());
+            // 訂閱集合變動時重新嘗試
+            match control.recv().await {
+                Some(next) => {
+                    symbols = next;
+                    attempt = 0;
+                    continue;
+                }
+                None => return,
+            }
+        }
+
+        status.set_state(WsConnectionState::Reconnecting, attempt, last_error.clone());
+        let delay = INITIAL_RECONNECT_DELAY_MS * 2u64.pow((attempt - 1).min(6));
+        let backoff = tokio::time::sleep(Duration::from_millis(delay));
+        tokio::pin!(backoff);
+        loop {
+            tokio::select! {
+                _ = &mut backoff => break,
+                next = control.recv() => match next {
+                    Some(next) => symbols = next,
+                    None => return,
+                },
+            }
+        }
+    }
+}
+
+/// 建立連線（經由設定的 proxy）並送出初始訊息與訂閱
+async fn connect(
+    provider: &dyn WebSocketProvider,
+    http: &HttpOptions,
+    symbols: &[String],
+) -> Result<(WsWrite, WsRead), String> {
+    let ws_stream = ws_proxy::connect(&provider.url(), http.effective_proxy().as_deref())
+        .await
+        .map_err(|e| format!("connection failed: {}", e))?;
+    let (mut write, read) = ws_stream.split();
+    let mut stream_ids: Vec<String> = provider.stream_symbols(symbols).into_keys().collect();
+    stream_ids.sort();
+    for msg in provider.connect_messages().into_iter().chain(provider.subscribe_messages(&stream_ids)) {
+        write
+            .send(Message::Text(msg.into()))
+            .await
+            .map_err(|e| format!("subscribe failed: {}", e))?;
+    }
+    Ok((write, read))
+}
+
+/// 處理一條已連線的 socket，直到斷線（回傳原因）或 manager 關閉控制通道（回傳 None）
+async fn stream(
+    provider: &dyn WebSocketProvider,
+    symbols: &mut Vec<String>,
+    control: &mut mpsc::UnboundedReceiver<Vec<String>>,
+    sender: &broadcast::Sender<WsTickerUpdate>,
+    mut write: WsWrite,
+    mut read: WsRead,
+) -> Option<String> {
+    let mut streams = provider.stream_symbols(symbols);
+    let (ping_message, ping_secs) = provider.ping().unwrap_or(("", u64::MAX));
+    let mut ping = tokio::time::interval(Duration::from_secs(ping_secs.min(86_400)));
+    // interval 的第一個 tick 立即完成
+    ping.tick().await;
+    loop {
+        tokio::select! {
+            _ = ping.tick(), if !ping_message.is_empty() => {
+                if let Err(e) = write.send(Message::Text(ping_message.into())).await {
+                    return Some(format!("ping send failed: {}", e));
+                }
+            }
+            next = control.recv() => {
+                let Some(next) = next else {
+                    let _ = write.send(Message::Close(None)).await;
+                    return None;
+                };
+                let next_streams = provider.stream_symbols(&next);
+                let (added, removed) = diff_streams(&streams, &next_streams);
+                let mut messages = Vec::new();
+                if !removed.is_empty() {
+                    messages.extend(provider.unsubscribe_messages(&removed));
+                }
+                if !added.is_empty() {
+                    messages.extend(provider.subscribe_messages(&added));
+                }
+                for msg in messages {
+                    if let Err(e) = write.send(Message::Text(msg.into())).await {
+                        return Some(format!("subscribe failed: {}", e));
+                    }
+                }
+                streams = next_streams;
+                *symbols = next;
+            }
+            msg = read.next() => match msg {
+                Some(Ok(Message::Text(text))) => {
+                    let Ok(data) = serde_json::from_str::<serde_json::Value>(text.as_ref()) else {
+                        continue;
+                    };
+                    if let Some(error) = provider.error_message(&data) {
+                        tracing::warn!("[WS] {} error message: {}", provider.provider_id(), error);
+                    }
+                    for update in provider.parse_message(&streams, &data) {
+                        crate::candles::observe(&update);
+                        let _ = sender.send(update);
+                    }
+                }
+                Some(Ok(Message::Ping(payload))) => {
+                    if let Err(e) = write.send(Message::Pong(payload)).await {
+                        return Some(format!("pong send failed: {}", e));
+                    }
+                }
+                Some(Ok(Message::Close(_))) => return Some("connection closed".to_string()),
+                Some(Err(e)) => return Some(e.to_string()),
+                None => return Some("stream ended".to_string()),
+                _ => {}
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::AssetDataBuilder;
+    use tokio::net::TcpListener;
+
+    /// 以本機 WS server 測試的 provider：訊息為 `{"op": "subscribe" | "unsubscribe", "args": [...]}`，
+    /// 推送為 `{"s": symbol, "p": price}`
+    struct TestProvider(String);
+
+    impl WebSocketProvider for TestProvider {
+        fn provider_id(&self) -> &'static str {
+            "test"
+        }
+        fn url(&self) -> String {
+            self.0.clone()
+        }
+        fn stream_id(&self, symbol: &str) -> String {
+            symbol.to_uppercase()
+        }
+        fn subscribe_messages(&self, stream_ids: &[String]) -> Vec<String> {
+            vec![serde_json::json!({ "op": "subscribe", "args": stream_ids }).to_string()]
+        }
+        fn unsubscribe_messages(&self, stream_ids: &[String]) -> Vec<String> {
+            vec![serde_json::json!({ "op": "unsubscribe", "args": stream_ids }).to_string()]
+        }
+        fn parse_message(&self, streams: &WsStreamSymbols, msg: &serde_json::Value) -> Vec<WsTickerUpdate> {
+            let Some(symbols) = msg["s"].as_str().and_then(|s| streams.get(s)) else {
+                return Vec::new();
+            };
+            symbols
+                .iter()
+                .map(|symbol| WsTickerUpdate {
+                    symbol: symbol.clone(),
+                    provider_id: "test".to_string(),
+                    data: AssetDataBuilder::new(symbol, "test").price(msg["p"].as_f64().unwrap_or(0.0)).build(),
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn stream_diff_only_touches_changed_ids() {
+        let provider = TestProvider(String::new());
+        let before = provider.stream_symbols(&["btc", "BTC", "eth"].map(String::from));
+        assert_eq!(before["BTC"], vec!["btc", "BTC"]);
+        let after = provider.stream_symbols(&["BTC", "sol"].map(String::from));
+        assert_eq!(diff_streams(&before, &after), (vec!["SOL".to_string()], vec!["ETH".to_string()]));
+    }
+
+    fn manager() -> (WsManager, broadcast::Receiver<AppEvent>) {
+        let (event_bus, events) = broadcast::channel(64);
+        (WsManager::new(event_bus), events)
+    }
+
+    impl WsManager {
+        fn set_test_symbols(&self, owner: &str, url: &str, symbols: &[&str]) {
+            let symbols = symbols.iter().map(|s| s.to_string()).collect();
+            let provider: Arc<dyn WebSocketProvider> = Arc::new(TestProvider(url.to_string()));
+            self.update_owner(owner, "test", symbols, Some(provider)).unwrap();
+        }
+    }
+
+    async fn next_state(events: &mut broadcast::Receiver<AppEvent>, state: WsConnectionState) -> WsStatus {
+        loop {
+            if let AppEvent::WsStatus(status) = events.recv().await.unwrap() {
+                if status.state == state {
+                    return status;
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn incremental_subscriptions_share_one_socket() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let (received_tx, mut received) = mpsc::unbounded_channel::<serde_json::Value>();
+        let server = tokio::spawn(async move {
+            let (tcp, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(tcp).await.unwrap();
+            while let Some(Ok(Message::Text(text))) = ws.next().await {
+                let msg: serde_json::Value = serde_json::from_str(text.as_ref()).unwrap();
+                if msg["op"] == "subscribe" {
+                    for id in msg["args"].as_array().unwrap() {
+                        let push = serde_json::json!({ "s": id, "p": 1.5 }).to_string();
+                        ws.send(Message::Text(push.into())).await.unwrap();
+                    }
+                }
+                let _ = received_tx.send(msg);
+            }
+            // 只接受一條連線：之後若重建 socket 會連線失敗
+        });
+
+        let (manager, mut events) = manager();
+        let mut ticks = manager.subscribe();
+        manager.set_test_symbols("app", &url, &["btc"]);
+        next_state(&mut events, WsConnectionState::Connected).await;
+        assert_eq!(received.recv().await.unwrap(), serde_json::json!({ "op": "subscribe", "args": ["BTC"] }));
+        let tick = ticks.recv().await.unwrap();
+        assert_eq!((tick.symbol.as_str(), tick.data.price), ("btc", 1.5));
+
+        // 其他 owner 加入：只訂閱新增的 stream
+        manager.set_test_symbols("client-1", &url, &["btc", "eth"]);
+        assert_eq!(received.recv().await.unwrap(), serde_json::json!({ "op": "subscribe", "args": ["ETH"] }));
+        assert_eq!(ticks.recv().await.unwrap().symbol, "eth");
+        assert_eq!(manager.statuses()[0].symbols, vec!["btc", "eth"]);
+
+        // owner 離開：只取消不再需要的 stream
+        manager.remove_owner("client-1");
+        assert_eq!(received.recv().await.unwrap(), serde_json::json!({ "op": "unsubscribe", "args": ["ETH"] }));
+        let status = &manager.statuses()[0];
+        assert_eq!((status.state, status.symbols.clone()), (WsConnectionState::Connected, vec!["btc".to_string()]));
+
+        manager.set_symbols("app", "test", Vec::new()).unwrap();
+        assert!(manager.statuses().is_empty());
+        assert_eq!(next_state(&mut events, WsConnectionState::Stopped).await.symbols, Vec::<String>::new());
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn subscriptions_over_the_provider_stream_limit_are_rejected() {
+        let (manager, _events) = manager();
+        let symbols: Vec<String> = (0..=crate::providers::ws_binance::MAX_STREAMS)
+            .map(|i| format!("C{}USDT", i))
+            .collect();
+        let err = manager.set_symbols("app", "binance", symbols).unwrap_err();
+        assert!(err.contains("1024"), "{}", err);
+        assert!(manager.statuses().is_empty());
+    }
+
+    #[tokio::test]
+    async fn connection_failures_report_reconnecting_and_suspend_keeps_symbols() {
+        // 沒有 server 的位址
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        let (manager, mut events) = manager();
+        manager.set_test_symbols("app", &url, &["btc"]);
+        let status = next_state(&mut events, WsConnectionState::Reconnecting).await;
+        assert_eq!(status.attempt, 1);
+        let down_since = status.disconnected_at.unwrap();
+        // 重連失敗時未連線期間的起點不變
+        let status = loop {
+            let status = next_state(&mut events, WsConnectionState::Reconnecting).await;
+            if status.attempt == 2 {
+                break status;
+            }
+        };
+        assert_eq!(status.disconnected_at, Some(down_since));
+        assert!(status.error.unwrap().contains("connection failed"));
+
+        manager.suspend();
+        let status = next_state(&mut events, WsConnectionState::Suspended).await;
+        assert_eq!(status.symbols, vec!["btc"]);
+        // 暫停時仍記錄訂閱集合
+        manager.set_test_symbols("client-1", &url, &["eth"]);
+        assert_eq!(manager.statuses()[0].symbols, vec!["btc", "eth"]);
+        assert_eq!(manager.owned_symbols("client-1")["test"], vec!["eth"]);
+    }
+}
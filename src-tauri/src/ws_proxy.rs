@@ -0,0 +1,230 @@
+//! WebSocket 連線的 proxy 通道 — `connect_async` 不支援 proxy，有設定時先經由
+//! HTTP CONNECT 或 SOCKS5 與目標主機建立 TCP 通道，再於其上完成 TLS 與 WebSocket handshake。
+//!
+//! proxy 與 HTTP 請求相同：provider 的 `proxy_url` 優先，否則為全域 `http_proxy`
+//! （[`HttpOptions::effective_proxy`](crate::providers::HttpOptions::effective_proxy)）。
+
+use base64::Engine;
+use reqwest::Url;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+
+pub type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// CONNECT 回應標頭的長度上限
+const MAX_CONNECT_RESPONSE: usize = 8 * 1024;
+const DEFAULT_SOCKS_PORT: u16 = 1080;
+
+/// 連線到 `url`；`proxy` 為 None 時直接連線
+pub async fn connect(url: &str, proxy: Option<&str>) -> Result<WsStream, String> {
+    let Some(proxy) = proxy else {
+        return connect_async(url).await.map(|(ws, _)| ws).map_err(|e| e.to_string());
+    };
+    let target = Url::parse(url).map_err(|e| format!("invalid WebSocket URL: {}", e))?;
+    let host = target.host_str().ok_or("WebSocket URL has no host")?;
+    let port = target.port_or_known_default().ok_or("WebSocket URL has no port")?;
+    let proxy = Url::parse(proxy).map_err(|e| format!("invalid proxy URL: {}", e))?;
+    let proxy_host = proxy.host_str().ok_or("proxy URL has no host")?;
+
+    let tcp = match proxy.scheme() {
+        "http" => {
+            let mut tcp = open(proxy_host, proxy.port_or_known_default().unwrap_or(80)).await?;
+            http_connect(&mut tcp, host, port, &proxy).await?;
+            tcp
+        }
+        "socks5" | "socks5h" => {
+            let mut tcp = open(proxy_host, proxy.port().unwrap_or(DEFAULT_SOCKS_PORT)).await?;
+            socks5_connect(&mut tcp, host, port, &proxy).await?;
+            tcp
+        }
+        other => return Err(format!("proxy scheme '{}' is not supported for WebSocket", other)),
+    };
+    tcp.set_nodelay(true).ok();
+    tokio_tungstenite::client_async_tls(url, tcp)
+        .await
+        .map(|(ws, _)| ws)
+        .map_err(|e| e.to_string())
+}
+
+async fn open(host: &str, port: u16) -> Result<TcpStream, String> {
+    TcpStream::connect((host, port))
+        .await
+        .map_err(|e| format!("proxy connection failed: {}", e))
+}
+
+/// HTTP CONNECT 通道；proxy URL 帶帳密時以 Basic 認證
+async fn http_connect(tcp: &mut TcpStream, host: &str, port: u16, proxy: &Url) -> Result<(), String> {
+    let mut request = format!("CONNECT {host}:{port} HTTP/1.1\r\nHost: {host}:{port}\r\n");
+    if !proxy.username().is_empty() {
+        let credentials = format!("{}:{}", proxy.username(), proxy.password().unwrap_or(""));
+        let encoded = base64::engine::general_purpose::STANDARD.encode(credentials);
+        request.push_str(&format!("Proxy-Authorization: Basic {}\r\n", encoded));
+    }
+    request.push_str("\r\n");
+    tcp.write_all(request.as_bytes())
+        .await
+        .map_err(|e| format!("proxy CONNECT failed: {}", e))?;
+
+    // 逐位元組讀到標頭結尾，避免吃掉通道上的後續資料
+    let mut response = Vec::new();
+    while !response.ends_with(b"\r\n\r\n") {
+        if response.len() >= MAX_CONNECT_RESPONSE {
+            return Err("proxy CONNECT response too long".to_string());
+        }
+        let byte = tcp
+            .read_u8()
+            .await
+            .map_err(|e| format!("proxy CONNECT failed: {}", e))?;
+        response.push(byte);
+    }
+    let status_line = String::from_utf8_lossy(&response);
+    let status_line = status_line.lines().next().unwrap_or_default();
+    match status_line.split_whitespace().nth(1) {
+        Some("200") => Ok(()),
+        _ => Err(format!("proxy CONNECT rejected: {}", status_line)),
+    }
+}
+
+/// SOCKS5 通道（RFC 1928）；目標以網域名稱交給 proxy 解析，帳密認證依 RFC 1929
+async fn socks5_connect(tcp: &mut TcpStream, host: &str, port: u16, proxy: &Url) -> Result<(), String> {
+    let err = |e: std::io::Error| format!("SOCKS5 handshake failed: {}", e);
+    let with_auth = !proxy.username().is_empty();
+    let greeting: &[u8] = if with_auth { &[5, 2, 0, 2] } else { &[5, 1, 0] };
+    tcp.write_all(greeting).await.map_err(err)?;
+    let mut reply = [0u8; 2];
+    tcp.read_exact(&mut reply).await.map_err(err)?;
+    match reply {
+        [5, 0] => {}
+        [5, 2] if with_auth => {
+            let (user, pass) = (proxy.username(), proxy.password().unwrap_or(""));
+            if user.len() > 255 || pass.len() > 255 {
+                return Err("SOCKS5 credentials too long".to_string());
+            }
+            let mut auth = vec![1, user.len() as u8];
+            auth.extend_from_slice(user.as_bytes());
+            auth.push(pass.len() as u8);
+            auth.extend_from_slice(pass.as_bytes());
+            tcp.write_all(&auth).await.map_err(err)?;
+            tcp.read_exact(&mut reply).await.map_err(err)?;
+            if reply[1] != 0 {
+                return Err("SOCKS5 authentication rejected".to_string());
+            }
+        }
+        _ => return Err("SOCKS5 proxy rejected the authentication method".to_string()),
+    }
+
+    if host.len() > 255 {
+        return Err("SOCKS5 target host too long".to_string());
+    }
+    let mut request = vec![5, 1, 0, 3, host.len() as u8];
+    request.extend_from_slice(host.as_bytes());
+    request.extend_from_slice(&port.to_be_bytes());
+    tcp.write_all(&request).await.map_err(err)?;
+
+    let mut head = [0u8; 4];
+    tcp.read_exact(&mut head).await.map_err(err)?;
+    if head[1] != 0 {
+        return Err(format!("SOCKS5 connect rejected (code {})", head[1]));
+    }
+    // 略過 proxy 回報的綁定位址與 port
+    let addr_len = match head[3] {
+        1 => 4,
+        4 => 16,
+        3 => tcp.read_u8().await.map_err(err)? as usize,
+        other => return Err(format!("SOCKS5 reply has unknown address type {}", other)),
+    };
+    let mut bound = vec![0u8; addr_len + 2];
+    tcp.read_exact(&mut bound).await.map_err(err)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{SinkExt, StreamExt};
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::tungstenite::Message;
+
+    /// 回覆 echo 一則訊息的 WebSocket server，跑在已建立的通道上
+    async fn echo_once(tcp: TcpStream) {
+        let mut ws = tokio_tungstenite::accept_async(tcp).await.unwrap();
+        if let Some(Ok(msg)) = ws.next().await {
+            ws.send(msg).await.unwrap();
+        }
+    }
+
+    async fn round_trip(proxy: &str) -> String {
+        let mut ws = connect("ws://stream.example.test:9443/ws", Some(proxy)).await.unwrap();
+        ws.send(Message::Text("ping".into())).await.unwrap();
+        match ws.next().await.unwrap().unwrap() {
+            Message::Text(text) => text.to_string(),
+            other => panic!("unexpected message {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn tunnels_through_http_connect_with_basic_auth() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let proxy = tokio::spawn(async move {
+            let (mut tcp, _) = listener.accept().await.unwrap();
+            let mut head = Vec::new();
+            while !head.ends_with(b"\r\n\r\n") {
+                head.push(tcp.read_u8().await.unwrap());
+            }
+            tcp.write_all(b"HTTP/1.1 200 Connection established\r\n\r\n").await.unwrap();
+            echo_once(tcp).await;
+            String::from_utf8(head).unwrap()
+        });
+
+        assert_eq!(round_trip(&format!("http://user:pass@{}", addr)).await, "ping");
+        let head = proxy.await.unwrap();
+        assert!(head.starts_with("CONNECT stream.example.test:9443 HTTP/1.1\r\n"), "{}", head);
+        assert!(head.contains("Proxy-Authorization: Basic dXNlcjpwYXNz\r\n"), "{}", head);
+    }
+
+    #[tokio::test]
+    async fn tunnels_through_socks5_by_domain_name() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let proxy = tokio::spawn(async move {
+            let (mut tcp, _) = listener.accept().await.unwrap();
+            let mut greeting = [0u8; 3];
+            tcp.read_exact(&mut greeting).await.unwrap();
+            tcp.write_all(&[5, 0]).await.unwrap();
+            let mut head = [0u8; 5];
+            tcp.read_exact(&mut head).await.unwrap();
+            let mut target = vec![0u8; head[4] as usize + 2];
+            tcp.read_exact(&mut target).await.unwrap();
+            tcp.write_all(&[5, 0, 0, 1, 127, 0, 0, 1, 0, 0]).await.unwrap();
+            echo_once(tcp).await;
+            (greeting, target)
+        });
+
+        assert_eq!(round_trip(&format!("socks5://{}", addr)).await, "ping");
+        let (greeting, target) = proxy.await.unwrap();
+        assert_eq!(greeting, [5, 1, 0]);
+        assert_eq!(&target[..target.len() - 2], b"stream.example.test");
+        assert_eq!(u16::from_be_bytes([target[target.len() - 2], target[target.len() - 1]]), 9443);
+    }
+
+    #[tokio::test]
+    async fn rejected_connect_reports_the_status_line() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut tcp, _) = listener.accept().await.unwrap();
+            let mut head = Vec::new();
+            while !head.ends_with(b"\r\n\r\n") {
+                head.push(tcp.read_u8().await.unwrap());
+            }
+            tcp.write_all(b"HTTP/1.1 407 Proxy Authentication Required\r\n\r\n").await.unwrap();
+        });
+
+        let err = connect("wss://stream.example.test/ws", Some(&format!("http://{}", addr)))
+            .await
+            .unwrap_err();
+        assert!(err.contains("407"), "{}", err);
+    }
+}
@@ -0,0 +1,106 @@
+//! Kiosk (read-only) mode over the HTTP API.
+//!
+//! While kiosk mode is on, or when a request carries the restricted kiosk token,
+//! writes are rejected with 403 while reads and live-data calls keep working.
+//! Unlocking requires the PIN and is never possible with the restricted token.
+//! Read-only clients never see stored secrets: provider keys are redacted and
+//! secret-bearing routes are rejected.
+
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::Router;
+use http::{Request, StatusCode};
+use http_body_util::BodyExt;
+use tower::ServiceExt;
+
+async fn send(app: &Router, method: &str, uri: &str, token: Option<&str>, body: Option<serde_json::Value>) -> (StatusCode, serde_json::Value) {
+    let mut req = Request::builder().method(method).uri(uri);
+    if let Some(token) = token {
+        req = req.header("Authorization", format!("Bearer {}", token));
+    }
+    let req = match body {
+        Some(body) => req
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap(),
+        None => req.body(Body::empty()).unwrap(),
+    };
+    let response = app.clone().oneshot(req).await.unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null))
+}
+
+#[tokio::test]
+async fn kiosk_mode_blocks_writes_but_not_reads() {
+    // passphrase 模式：不碰開發機的 OS keychain
+    std::env::set_var("SB_SECRETS_PASSPHRASE", "kiosk-test-passphrase");
+    let tmp = tempfile::TempDir::new().unwrap();
+    let state = stockenboard_lib::core_state::CoreState::new(tmp.path()).unwrap();
+    let app = stockenboard_lib::api::build_router(Arc::new(state));
+    let token = "wall-display-0123456789";
+
+    let (status, _) = send(
+        &app,
+        "PUT",
+        "/api/system/kiosk",
+        None,
+        Some(serde_json::json!({ "enabled": false, "pin": "2468", "token": token })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    // 受限 token：寫入被拒，讀取與報價查詢照常
+    let (status, json) = send(&app, "POST", "/api/views", Some(token), Some(serde_json::json!({ "name": "Wall", "type": "asset" }))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(json["error"]["key"], "kiosk_locked");
+    let (status, _) = send(&app, "GET", "/api/subscriptions", Some(token), None).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send(
+        &app,
+        "PUT",
+        "/api/system/kiosk",
+        Some(token),
+        Some(serde_json::json!({ "enabled": false, "current_pin": "2468" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // 唯讀用戶端讀不到 provider key
+    let key = serde_json::json!({ "api_key": "sk-live-kiosk", "api_secret": "kiosk-secret", "connection_type": "websocket" });
+    let (status, _) = send(&app, "PUT", "/api/provider-settings/binance", None, Some(key)).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, json) = send(&app, "GET", "/api/provider-settings", Some(token), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["data"][0]["connection_type"], "websocket");
+    assert_eq!(json["data"][0]["api_key"], serde_json::Value::Null);
+    assert_eq!(json["data"][0]["api_secret"], serde_json::Value::Null);
+    for uri in ["/api/data/config?include_secrets=true", "/api/notifications/channels", "/api/system/lan-listener"] {
+        let (status, _) = send(&app, "GET", uri, Some(token), None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN, "{}", uri);
+    }
+    let (_, json) = send(&app, "GET", "/api/provider-settings", None, None).await;
+    assert_eq!(json["data"][0]["api_key"], "sk-live-kiosk");
+
+    // 其他用戶端不受影響，直到開啟本機鎖定
+    let (status, _) = send(&app, "POST", "/api/views", None, Some(serde_json::json!({ "name": "Desk", "type": "asset" }))).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let (status, _) = send(&app, "PUT", "/api/system/kiosk", None, Some(serde_json::json!({ "enabled": true, "token": token }))).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send(&app, "DELETE", "/api/history", None, None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (_, json) = send(&app, "GET", "/api/system/kiosk", None, None).await;
+    assert_eq!(json["data"]["enabled"], true);
+    assert_eq!(json["data"]["has_pin"], true);
+
+    // 解除需正確 PIN
+    let wrong = serde_json::json!({ "enabled": false, "token": token, "current_pin": "1357" });
+    let (status, _) = send(&app, "PUT", "/api/system/kiosk", None, Some(wrong)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let right = serde_json::json!({ "enabled": false, "token": token, "current_pin": "2468" });
+    let (status, _) = send(&app, "PUT", "/api/system/kiosk", None, Some(right)).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send(&app, "POST", "/api/views", None, Some(serde_json::json!({ "name": "Desk 2", "type": "asset" }))).await;
+    assert_eq!(status, StatusCode::CREATED);
+}
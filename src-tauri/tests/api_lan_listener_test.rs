@@ -0,0 +1,119 @@
+//! LAN listener over the HTTP API.
+//!
+//! The LAN router serves the same routes as the loopback router, but every request needs the
+//! LAN token and the `read_only` scope rejects writes. The loopback router stays unauthenticated.
+//! The token is only accepted from the `Authorization` header, and no scope returns stored secrets
+//! or anything that may carry them (provider debug captures, recent logs).
+
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::Router;
+use http::{Request, StatusCode};
+use http_body_util::BodyExt;
+use stockenboard_lib::lan_listener::{set_lan_listener_config, LanListenerConfig, ListenerScope};
+use tower::ServiceExt;
+
+async fn send(app: &Router, method: &str, uri: &str, token: Option<&str>, body: Option<serde_json::Value>) -> (StatusCode, serde_json::Value) {
+    let mut req = Request::builder().method(method).uri(uri);
+    if let Some(token) = token {
+        req = req.header("Authorization", format!("Bearer {}", token));
+    }
+    let req = match body {
+        Some(body) => req
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap(),
+        None => req.body(Body::empty()).unwrap(),
+    };
+    let response = app.clone().oneshot(req).await.unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null))
+}
+
+#[tokio::test]
+async fn lan_listener_requires_token_and_enforces_scope() {
+    // passphrase 模式：不碰開發機的 OS keychain
+    std::env::set_var("SB_SECRETS_PASSPHRASE", "lan-test-passphrase");
+    let tmp = tempfile::TempDir::new().unwrap();
+    let state = Arc::new(stockenboard_lib::core_state::CoreState::new(tmp.path()).unwrap());
+    let local = stockenboard_lib::api::build_router(state.clone());
+    let lan = stockenboard_lib::api::build_lan_router(state);
+    let token = "lan-token-0123456789";
+
+    // 啟用時必須設定 token
+    let (status, _) = send(&local, "PUT", "/api/system/lan-listener", None, Some(serde_json::json!({ "enabled": true }))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    set_lan_listener_config(LanListenerConfig { enabled: true, token: token.to_string(), ..Default::default() });
+
+    // 沒有 token 或 token 錯誤
+    let (status, json) = send(&lan, "GET", "/api/subscriptions", None, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(json["error"]["code"], "unauthorized");
+    let (status, _) = send(&lan, "GET", "/api/subscriptions", Some("wrong-token-0123456789"), None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // read_only：讀取與報價查詢可用，寫入被拒
+    let (status, _) = send(&lan, "GET", "/api/subscriptions", Some(token), None).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send(&lan, "GET", "/api/prices/cached", Some(token), None).await;
+    assert_eq!(status, StatusCode::OK);
+    // query string 的 token 不被接受
+    let (status, _) = send(&lan, "GET", &format!("/api/prices/cached?token={}", token), None, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let view = serde_json::json!({ "name": "LAN", "type": "asset" });
+    let (status, json) = send(&lan, "POST", "/api/views", Some(token), Some(view.clone())).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(json["error"]["code"], "forbidden");
+    let (status, _) = send(&lan, "PUT", "/api/system/lan-listener", Some(token), Some(serde_json::json!({}))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // 本機 listener 不需 token
+    let (status, _) = send(&local, "POST", "/api/views", None, Some(view.clone())).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    // full scope 與本機相同
+    set_lan_listener_config(LanListenerConfig {
+        enabled: true,
+        token: token.to_string(),
+        scope: ListenerScope::Full,
+        ..Default::default()
+    });
+    let (status, _) = send(&lan, "POST", "/api/views", Some(token), Some(serde_json::json!({ "name": "LAN 2", "type": "asset" }))).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    // 不論 scope 都讀不到明文密鑰
+    let key = serde_json::json!({ "api_key": "sk-live-lan", "api_secret": "lan-secret" });
+    let (status, _) = send(&local, "PUT", "/api/provider-settings/binance", None, Some(key)).await;
+    assert_eq!(status, StatusCode::OK);
+    for scope in [ListenerScope::ReadOnly, ListenerScope::Full] {
+        set_lan_listener_config(LanListenerConfig { enabled: true, token: token.to_string(), scope, ..Default::default() });
+        let (status, json) = send(&lan, "GET", "/api/provider-settings", Some(token), None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"][0]["provider_id"], "binance");
+        assert_eq!(json["data"][0]["api_key"], serde_json::Value::Null);
+        assert_eq!(json["data"][0]["api_secret"], serde_json::Value::Null);
+        for uri in [
+            "/api/data/config?include_secrets=true",
+            "/api/notifications/channels",
+            "/api/system/lan-listener",
+            "/api/providers/polygon/debug",
+            "/api/system/logs",
+        ] {
+            let (status, _) = send(&lan, "GET", uri, Some(token), None).await;
+            assert_eq!(status, StatusCode::FORBIDDEN, "{:?} {}", scope, uri);
+        }
+    }
+    let (_, json) = send(&local, "GET", "/api/provider-settings", None, None).await;
+    assert_eq!(json["data"][0]["api_key"], "sk-live-lan");
+    for uri in ["/api/providers/polygon/debug", "/api/system/logs"] {
+        let (status, _) = send(&local, "GET", uri, None, None).await;
+        assert_eq!(status, StatusCode::OK, "{}", uri);
+    }
+
+    let (_, json) = send(&local, "GET", "/api/system/lan-listener", None, None).await;
+    assert_eq!(json["data"]["scope"], "full");
+    assert_eq!(json["data"]["status"]["listening"], serde_json::Value::Null);
+}
@@ -1,4 +1,4 @@
-﻿import { useState, useCallback, useRef, lazy, Suspense } from 'react';
+﻿import { useState, useCallback, useRef, useEffect, lazy, Suspense } from 'react';
 import { useAssetData } from './hooks/useAssetData';
 import { useViews } from './hooks/useViews';
 import { useViewToolbar } from './hooks/useViewToolbar';
@@ -26,6 +26,7 @@ import { ConfirmDialog } from './components/ConfirmDialog/ConfirmDialog';
 import { DashboardToolbar } from './components/DashboardToolbar/DashboardToolbar';
 import { AlertSidebar } from './components/AlertSidebar/AlertSidebar';
 import { ToastContainer } from './components/Toast/Toast';
+import { getTransport } from './lib/transport';
 import { t } from './lib/i18n';
 import { useLocale } from './hooks/useLocale';
 import { getGridClass } from './lib/viewUtils';
@@ -99,6 +100,24 @@ function App() {
     return result;
   }, [addSubscriptionBatch, refreshViews]);
 
+  // stockenboard:// deep link：新增的訂閱更新 view 計數，開啟 view 時切換到對應分頁
+  useEffect(() => {
+    const fns = [
+      getTransport().listen('subscriptions-changed', () => { refreshViews(); }),
+      getTransport().listen('open-view', (payload) => {
+        const p = payload as { view_id: number; view_type: string };
+        if (p.view_type === 'dex') {
+          // DexPage 尚未掛載時由 useViews 讀取此 key
+          localStorage.setItem(STORAGE_KEYS.DEX_ACTIVE_VIEW_ID, String(p.view_id));
+          setActiveTab('dex');
+        } else {
+          setActiveTab('dashboard');
+        }
+      }),
+    ];
+    return () => { for (const fn of fns) fn(); };
+  }, [refreshViews, setActiveTab]);
+
   const subscriptionsRef = useRef(subscriptions);
   subscriptionsRef.current = subscriptions;
   const isCustomView = activeViewSubscriptionIds !== null;
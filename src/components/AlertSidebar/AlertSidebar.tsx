@@ -84,6 +84,12 @@ function FilterBar({ searchText, onSearchChange, conditionFilter, onConditionCha
         <option value="price_below">Price Below</option>
         <option value="change_pct_above">Change % Up</option>
         <option value="change_pct_below">Change % Down</option>
+        <option value="gas_above">Gas Above</option>
+        <option value="gas_below">Gas Below</option>
+        <option value="depeg_above">Depeg Above</option>
+        <option value="event_before">Event Before</option>
+        <option value="vwap_above">Above VWAP</option>
+        <option value="vwap_below">Below VWAP</option>
         <option value="ai">AI</option>
       </select>
       {isActive && (
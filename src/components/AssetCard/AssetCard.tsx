@@ -1,12 +1,13 @@
-import { useState, useEffect, memo, useCallback, type ReactElement } from 'react';
-import { getTransport } from '../../lib/transport';
-import { Subscription, ProviderInfo } from '../../types';
+import { useState, useEffect, memo, useCallback, type MouseEvent } from 'react';
+import { getTransport, isTauri } from '../../lib/transport';
+import { Subscription, ProviderInfo, CachedPrice, AssetData, SessionQuote } from '../../types';
 import { useAssetPrice } from '../../hooks/useAssetData';
 import { CountdownCircle } from './CountdownCircle';
 import { AssetIcon, getIconName, invalidateIcon } from './AssetIcon';
 import { AssetEditPanel } from './AssetEditPanel';
 import { formatPrice, formatNumber, summarizeError } from '../../lib/format';
 import { t } from '../../lib/i18n';
+import { silentLog } from '../../lib/errorLog';
 import './AssetCard.css';
 
 interface AssetCardProps {
@@ -59,41 +60,30 @@ const PREPOST_HIDDEN_KEYS = new Set([
   'market_session',
 ]);
 
-function PrePostRow({ extra, currency, className }: { extra: Record<string, unknown>; currency: string; className?: string }) {
-  const prePrice = extra.pre_market_price as number | undefined;
-  const postPrice = extra.post_market_price as number | undefined;
-  const prePct = extra.pre_market_change_pct as number | undefined;
-  const postPct = extra.post_market_change_pct as number | undefined;
-  const rows: ReactElement[] = [];
-  if (prePrice !== undefined) {
-    const isPos = (prePct ?? 0) >= 0;
-    rows.push(
-      <div key="pre" className={`prepost-row ${className || ''}`}>
-        <span className="market-session-badge pre">{t.asset.sessionPre}</span>
-        <span className="prepost-price">{formatPrice(prePrice, currency)}</span>
-        {prePct !== undefined && (
-          <span className={`prepost-change ${isPos ? 'positive' : 'negative'}`}>
-            {isPos ? '▲' : '▼'} {Math.abs(prePct).toFixed(2)}%
-          </span>
-        )}
-      </div>
-    );
-  }
-  if (postPrice !== undefined) {
-    const isPos = (postPct ?? 0) >= 0;
-    rows.push(
-      <div key="post" className={`prepost-row ${className || ''}`}>
-        <span className="market-session-badge post">{t.asset.sessionPost}</span>
-        <span className="prepost-price">{formatPrice(postPrice, currency)}</span>
-        {postPct !== undefined && (
-          <span className={`prepost-change ${isPos ? 'positive' : 'negative'}`}>
-            {isPos ? '▲' : '▼'} {Math.abs(postPct).toFixed(2)}%
-          </span>
-        )}
-      </div>
-    );
-  }
-  return rows.length > 0 ? <>{rows}</> : null;
+function SessionRow({ kind, quote, currency, scale, className }: { kind: 'pre' | 'post'; quote: SessionQuote; currency: string; scale?: number; className?: string }) {
+  const pct = quote.change_percent;
+  const isPos = (pct ?? 0) >= 0;
+  return (
+    <div className={`prepost-row ${className || ''}`}>
+      <span className={`market-session-badge ${kind}`}>{kind === 'pre' ? t.asset.sessionPre : t.asset.sessionPost}</span>
+      <span className="prepost-price">{formatPrice(quote.price, currency, scale)}</span>
+      {pct != null && (
+        <span className={`prepost-change ${isPos ? 'positive' : 'negative'}`}>
+          {isPos ? '▲' : '▼'} {Math.abs(pct).toFixed(2)}%
+        </span>
+      )}
+    </div>
+  );
+}
+
+function PrePostRow({ asset, className }: { asset: AssetData; className?: string }) {
+  if (!asset.pre_market && !asset.post_market) return null;
+  return (
+    <>
+      {asset.pre_market && <SessionRow kind="pre" quote={asset.pre_market} currency={asset.currency} scale={asset.price_scale} className={className} />}
+      {asset.post_market && <SessionRow kind="post" quote={asset.post_market} currency={asset.currency} scale={asset.price_scale} className={className} />}
+    </>
+  );
 }
 
 export const AssetCard = memo(function AssetCard({ subscription, providers, currentProviderId, assetType, refreshInterval, onRemove, onEdit, viewMode = 'grid', isCustomView = false, forceExpand = false, hidePrePost = false }: AssetCardProps) {
@@ -113,6 +103,26 @@ export const AssetCard = memo(function AssetCard({ subscription, providers, curr
     } catch { /* cancelled */ }
   }, [subscription.symbol, iconName]);
 
+  const handleIconFetch = useCallback(async (e: MouseEvent) => {
+    e.preventDefault();
+    try {
+      await getTransport().invoke('fetch_icon', { symbol: subscription.symbol, providerId: currentProviderId });
+      invalidateIcon(iconName);
+      setIconKey(v => v + 1);
+    } catch (err) { silentLog('AssetCard.fetchIcon', err); }
+  }, [subscription.symbol, currentProviderId, iconName]);
+
+  const copyPrice = useCallback(async () => {
+    if (!isTauri()) return;
+    try {
+      await getTransport().invoke('copy_price_to_clipboard', { providerId: currentProviderId, symbol: subscription.symbol });
+    } catch (err) { silentLog('AssetCard.copyPrice', err); }
+  }, [subscription.symbol, currentProviderId]);
+  // 啟動時由快照還原、尚未被新資料取代的價格
+  const stale = !!(asset as Partial<CachedPrice> | undefined)?.stale;
+  const copyTitle = stale ? t.asset.stalePrice : isTauri() ? t.asset.doubleClickCopy : undefined;
+  const staleCls = stale ? ' stale' : '';
+
   const changePercent = asset?.change_percent_24h ?? 0;
   const isPositive = changePercent >= 0;
   const currentProvider = providers.find(p => p.id === currentProviderId);
@@ -121,7 +131,7 @@ export const AssetCard = memo(function AssetCard({ subscription, providers, curr
   useEffect(() => { if (error) setErrorExpanded(false); }, [error]);
 
   const renderIcon = (className: string) => (
-    <AssetIcon key={iconKey} symbol={subscription.symbol} className={className} onClick={handleIconClick} />
+    <AssetIcon key={iconKey} symbol={subscription.symbol} className={className} onClick={handleIconClick} onContextMenu={handleIconFetch} />
   );
 
   const editPanel = editing && (
@@ -150,8 +160,8 @@ export const AssetCard = memo(function AssetCard({ subscription, providers, curr
           <button className="asset-card-edit-btn" onClick={openEdit} title={t.common.edit}>✎</button>
         </div>
         <div className="compact-bottom">
-          <span className="compact-price">
-            {error ? <span className="asset-error" title={summarizeError(error)}>{t.common.error}</span> : asset ? formatPrice(asset.price, asset.currency) : '-'}
+          <span className={`compact-price${staleCls}`} onDoubleClick={copyPrice} title={copyTitle}>
+            {error ? <span className="asset-error" title={summarizeError(error)}>{t.common.error}</span> : asset ? formatPrice(asset.price, asset.currency, asset.price_scale) : '-'}
           </span>
           {asset && !error && (
             <span className={`compact-change ${isPositive ? 'positive' : 'negative'}`}>
@@ -159,7 +169,7 @@ export const AssetCard = memo(function AssetCard({ subscription, providers, curr
             </span>
           )}
         </div>
-        {!hidePrePost && asset && !error && asset.extra && <PrePostRow extra={asset.extra as Record<string, unknown>} currency={asset.currency} className="compact-prepost" />}
+        {!hidePrePost && asset && !error && <PrePostRow asset={asset} className="compact-prepost" />}
         {editPanel}
       </div>
     );
@@ -173,13 +183,13 @@ export const AssetCard = memo(function AssetCard({ subscription, providers, curr
           <span className="symbol" title={subscription.symbol}>{subscription.symbol} <span className={`asset-type-tag ${assetType}`}>{assetType === 'crypto' ? t.subForm.cryptoShort : t.subForm.stockShort}</span>{sessionInfo && <> <span className={`market-session-badge ${sessionInfo.cls}`}>{sessionInfo.label}</span></>}</span>
           {subscription.display_name && <span className="name" title={subscription.display_name}>{subscription.display_name}</span>}
         </div>
-        <div className="asset-list-price">
-          {error ? <span className="asset-error" title={summarizeError(error)}>{t.common.error}</span> : asset ? formatPrice(asset.price, asset.currency) : t.common.loading}
+        <div className={`asset-list-price${staleCls}`} onDoubleClick={copyPrice} title={copyTitle}>
+          {error ? <span className="asset-error" title={summarizeError(error)}>{t.common.error}</span> : asset ? formatPrice(asset.price, asset.currency, asset.price_scale) : t.common.loading}
         </div>
         <div className={`asset-list-change ${isPositive ? 'positive' : 'negative'}`}>
           {asset && !error && <>{isPositive ? '▲' : '▼'} {Math.abs(changePercent).toFixed(2)}%</>}
         </div>
-        {!hidePrePost && asset && !error && asset.extra && <PrePostRow extra={asset.extra as Record<string, unknown>} currency={asset.currency} className="list-prepost" />}
+        {!hidePrePost && asset && !error && <PrePostRow asset={asset} className="list-prepost" />}
         <span className="asset-list-provider-label">{t.dex.dataSource(currentProvider?.name || currentProviderId)}</span>
         <button className="asset-card-edit-btn" onClick={openEdit} title={t.common.edit}>✎</button>
         {refreshInterval > 0 && <CountdownCircle providerId={currentProviderId} fallbackInterval={refreshInterval} size={22} />}
@@ -201,8 +211,8 @@ export const AssetCard = memo(function AssetCard({ subscription, providers, curr
       </div>
 
       <div className="asset-card-body">
-        <p className="asset-price">
-          {error ? <span className="asset-error">{t.dex.fetchFailed}</span> : asset ? formatPrice(asset.price, asset.currency) : t.common.loading}
+        <p className={`asset-price${staleCls}`} onDoubleClick={copyPrice} title={copyTitle}>
+          {error ? <span className="asset-error">{t.dex.fetchFailed}</span> : asset ? formatPrice(asset.price, asset.currency, asset.price_scale) : t.common.loading}
           {sessionInfo && asset && !error && <> <span className={`market-session-badge ${sessionInfo.cls}`}>{sessionInfo.label}</span></>}
         </p>
         {asset && !error && (
@@ -212,7 +222,7 @@ export const AssetCard = memo(function AssetCard({ subscription, providers, curr
         )}
       </div>
 
-      {!hidePrePost && asset && !error && asset.extra && <PrePostRow extra={asset.extra as Record<string, unknown>} currency={asset.currency} />}
+      {!hidePrePost && asset && !error && <PrePostRow asset={asset} />}
 
       {error && (
         <div className="asset-error-detail" onClick={() => setErrorExpanded(v => !v)} title={t.asset.clickExpandCollapse}>
@@ -224,10 +234,10 @@ export const AssetCard = memo(function AssetCard({ subscription, providers, curr
       {asset && !error && (
         <div className="asset-card-stats">
           {asset.high_24h !== undefined && (
-            <div className="asset-stat"><span className="asset-stat-label">{t.asset.high24h}</span><span className="asset-stat-value">{formatPrice(asset.high_24h, asset.currency)}</span></div>
+            <div className="asset-stat"><span className="asset-stat-label">{t.asset.high24h}</span><span className="asset-stat-value">{formatPrice(asset.high_24h, asset.currency, asset.price_scale)}</span></div>
           )}
           {asset.low_24h !== undefined && (
-            <div className="asset-stat"><span className="asset-stat-label">{t.asset.low24h}</span><span className="asset-stat-value">{formatPrice(asset.low_24h, asset.currency)}</span></div>
+            <div className="asset-stat"><span className="asset-stat-label">{t.asset.low24h}</span><span className="asset-stat-value">{formatPrice(asset.low_24h, asset.currency, asset.price_scale)}</span></div>
           )}
           {asset.volume !== undefined && (
             <div className="asset-stat"><span className="asset-stat-label">{t.asset.volume}</span><span className="asset-stat-value">{formatNumber(asset.volume)}</span></div>
@@ -250,7 +260,7 @@ export const AssetCard = memo(function AssetCard({ subscription, providers, curr
               <div className="asset-stat"><span className="asset-stat-label">{t.asset.marketCap}</span><span className="asset-stat-value">{formatNumber(asset.market_cap)}</span></div>
             )}
             {asset.change_24h !== undefined && (
-              <div className="asset-stat"><span className="asset-stat-label">{t.asset.change24h}</span><span className="asset-stat-value">{formatPrice(asset.change_24h, asset.currency)}</span></div>
+              <div className="asset-stat"><span className="asset-stat-label">{t.asset.change24h}</span><span className="asset-stat-value">{formatPrice(asset.change_24h, asset.currency, asset.price_scale)}</span></div>
             )}
             {asset.extra && Object.entries(asset.extra).filter(([key]) => !PREPOST_HIDDEN_KEYS.has(key)).map(([key, value]) => (
               <div className="asset-stat" key={key}><span className="asset-stat-label">{formatExtraKey(key)}</span><span className="asset-stat-value">{formatExtraValue(value)}</span></div>
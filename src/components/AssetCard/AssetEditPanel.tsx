@@ -27,9 +27,7 @@ export function AssetEditPanel({ subscription, providers, currentProviderId, ass
   const [saving, setSaving] = useState(false);
 
   const filteredProviders = useMemo(() => providers.filter(p =>
-    editAssetType === 'crypto'
-      ? (p.provider_type === 'crypto' || p.provider_type === 'both' || p.provider_type === 'dex')
-      : (p.provider_type === 'stock' || p.provider_type === 'both')
+    p.capabilities.asset_classes.includes(editAssetType)
   ), [providers, editAssetType]);
 
   const editProviderInfo = providers.find(p => p.id === editProvider);
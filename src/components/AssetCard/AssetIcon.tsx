@@ -1,6 +1,7 @@
-import { useState, useEffect, memo } from 'react';
+import { useState, useEffect, memo, type MouseEvent } from 'react';
 import { getTransport } from '../../lib/transport';
 import { t } from '../../lib/i18n';
+import { getIconName } from '../../lib/iconName';
 
 /**
  * 全域 icon data URL 快取 — 同一個 iconName 只讀取一次檔案，
@@ -36,8 +37,10 @@ async function loadIconDataUrl(iconName: string): Promise<string | null> {
     try {
       const dir = await getIconsDir();
       const sep = dir.endsWith('\\') || dir.endsWith('/') ? '' : '/';
-      const filePath = `${dir}${sep}${iconName}.png`;
-      const dataUrl = await getTransport().invoke<string>('read_local_file_base64', { path: filePath });
+      // 點陣圖一律轉成 .png；SVG 原樣保存為 .svg
+      const dataUrl = await getTransport()
+        .invoke<string>('read_local_file_base64', { path: `${dir}${sep}${iconName}.png` })
+        .catch(() => getTransport().invoke<string>('read_local_file_base64', { path: `${dir}${sep}${iconName}.svg` }));
       _dataUrlCache.set(iconName, dataUrl);
       return dataUrl;
     } catch {
@@ -74,13 +77,12 @@ interface AssetIconProps {
   symbol: string;
   className: string;
   onClick: () => void;
+  onContextMenu?: (e: MouseEvent) => void;
 }
 
-export function getIconName(symbol: string): string {
-  return symbol.toLowerCase();
-}
+export { getIconName } from '../../lib/iconName';
 
-export const AssetIcon = memo(function AssetIcon({ symbol, className, onClick }: AssetIconProps) {
+export const AssetIcon = memo(function AssetIcon({ symbol, className, onClick, onContextMenu }: AssetIconProps) {
   const iconName = getIconName(symbol);
   const fallbackText = iconName.slice(0, 3).toUpperCase();
 
@@ -121,7 +123,7 @@ export const AssetIcon = memo(function AssetIcon({ symbol, className, onClick }:
   }, [iconName]);
 
   return (
-    <div className={`${className} clickable`} onClick={onClick} title={t.asset.clickSetIcon}>
+    <div className={`${className} clickable`} onClick={onClick} onContextMenu={onContextMenu} title={t.asset.clickSetIcon}>
       {loaded && dataUrl && !failed ? (
         <img src={dataUrl} alt={symbol} />
       ) : (
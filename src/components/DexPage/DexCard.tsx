@@ -38,7 +38,7 @@ export const DexCard = memo(function DexCard({
   const extra = asset?.extra as Record<string, unknown> | undefined;
   const gasEstimate = extra?.gas_estimate as string | undefined;
   const routePath = extra?.route_path as string | undefined;
-  const poolTvl = extra?.pool_tvl as number | undefined;
+  const poolTvl = asset?.liquidity_usd ?? (extra?.pool_tvl as number | undefined);
   const amountOut = extra?.amount_out as number | undefined;
 
   const [fromIconSymbol, toIconSymbol] = parsePairFromName(subscription.display_name);
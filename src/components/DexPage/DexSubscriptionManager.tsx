@@ -1,13 +1,14 @@
-import { useState } from 'react';
+import { useEffect, useMemo, useState } from 'react';
 import { getTransport } from '../../lib/transport';
 import { t } from '../../lib/i18n';
+import { silentLog } from '../../lib/errorLog';
 import { useEscapeKey } from '../../hooks/useEscapeKey';
+import type { SubgraphProtocol } from '../../types';
 
-const PROTOCOLS = [
-  { id: 'uniswap_v3', name: 'Uniswap V3' },
-  { id: 'sushiswap', name: 'SushiSwap' },
-  { id: 'pancakeswap', name: 'PancakeSwap' },
-];
+const CHAIN_LABELS: Record<string, string> = {
+  ethereum: 'Ethereum', base: 'Base', arbitrum: 'Arbitrum', polygon: 'Polygon',
+  optimism: 'Optimism', bsc: 'BSC', celo: 'Celo', avalanche: 'Avalanche',
+};
 
 interface DexPoolInfo {
   token0_address: string;
@@ -41,6 +42,26 @@ export function DexSubscriptionManager({ onAdd, existingKeys, onToast, onClose }
   const [manualTokenFrom, setManualTokenFrom] = useState('');
   const [manualTokenTo, setManualTokenTo] = useState('');
 
+  // Subgraph protocol registry（內建 + 使用者自訂）
+  const [registry, setRegistry] = useState<SubgraphProtocol[]>([]);
+  useEffect(() => {
+    getTransport().invoke<SubgraphProtocol[]>('get_subgraph_protocols').then(setRegistry).catch(e => silentLog('getSubgraphProtocols', e));
+  }, []);
+  const protocols = useMemo(() => {
+    const seen = new Map<string, string>();
+    for (const p of registry) if (!seen.has(p.protocol)) seen.set(p.protocol, p.name);
+    return [...seen].map(([id, name]) => ({ id, name }));
+  }, [registry]);
+  const chains = useMemo(() => registry.filter(p => p.protocol === protocol).map(p => p.chain), [registry, protocol]);
+
+  const changeProtocol = (next: string) => {
+    setProtocol(next);
+    const available = registry.filter(p => p.protocol === next).map(p => p.chain);
+    if (available.length > 0 && !available.includes(chain)) setChain(available[0]);
+    setPoolInfo(null);
+    setManualMode(false);
+  };
+
   const isJupiter = provider === 'jupiter';
 
   const tokenFrom = manualMode
@@ -195,20 +216,14 @@ export function DexSubscriptionManager({ onAdd, existingKeys, onToast, onClose }
               <>
                 <div className="dex-form-row">
                   <label>{t.dex.protocol}</label>
-                  <select value={protocol} onChange={e => { setProtocol(e.target.value); setPoolInfo(null); setManualMode(false); }} disabled={busy}>
-                    {PROTOCOLS.map(p => <option key={p.id} value={p.id}>{p.name}</option>)}
+                  <select value={protocol} onChange={e => changeProtocol(e.target.value)} disabled={busy}>
+                    {protocols.map(p => <option key={p.id} value={p.id}>{p.name}</option>)}
                   </select>
                 </div>
                 <div className="dex-form-row">
                   <label>{t.dex.chain}</label>
                   <select value={chain} onChange={e => { setChain(e.target.value); setPoolInfo(null); }} disabled={busy}>
-                    <option value="ethereum">Ethereum</option>
-                    <option value="base">Base</option>
-                    <option value="arbitrum">Arbitrum</option>
-                    <option value="polygon">Polygon</option>
-                    <option value="optimism">Optimism</option>
-                    <option value="bsc">BSC</option>
-                    <option value="celo">Celo</option>
+                    {chains.map(c => <option key={c} value={c}>{CHAIN_LABELS[c] ?? c}</option>)}
                   </select>
                 </div>
               </>
@@ -2,11 +2,12 @@
  * 歷史頁面 — 精簡版，子元件已抽出到 HistorySidebar / HistoryChart / HistoryTable
  */
 import { useState, useEffect, useCallback, useRef, useMemo } from 'react';
-import { getTransport } from '../../lib/transport';
+import { getTransport, isTauri } from '../../lib/transport';
 import { STORAGE_KEYS } from '../../lib/storageKeys';
 import { t } from '../../lib/i18n';
 import { TZ_LABEL } from '../../lib/format';
 import { silentLog } from '../../lib/errorLog';
+import { isCancelled } from '../../lib/commandError';
 import { useConfirm } from '../../hooks/useConfirm';
 import { ConfirmDialog } from '../ConfirmDialog/ConfirmDialog';
 import { HistorySidebar } from './HistorySidebar';
@@ -154,27 +155,55 @@ export function HistoryPage({ onToast }: Props) {
   }, [onToast]);
 
   // ── 載入歷史 ──
+  const timeRange = useCallback((): [number, number] => {
+    const now = Math.floor(Date.now() / 1000);
+    if (range === 'custom' && customFrom && customTo) {
+      return [
+        Math.floor(new Date(customFrom).getTime() / 1000),
+        Math.floor(new Date(customTo + 'T23:59:59').getTime() / 1000),
+      ];
+    }
+    return [now - (RANGE_MAP[range as keyof typeof RANGE_MAP] || DAY), now];
+  }, [range, customFrom, customTo]);
+
   const loadHistory = useCallback(async () => {
     if (!selectedId) return;
     setLoading(true);
     try {
-      const now = Math.floor(Date.now() / 1000);
-      let fromTs: number, toTs: number;
-      if (range === 'custom' && customFrom && customTo) {
-        fromTs = Math.floor(new Date(customFrom).getTime() / 1000);
-        toTs = Math.floor(new Date(customTo + 'T23:59:59').getTime() / 1000);
-      } else {
-        fromTs = now - (RANGE_MAP[range as keyof typeof RANGE_MAP] || DAY);
-        toTs = now;
-      }
+      const [fromTs, toTs] = timeRange();
       setRecords(await getTransport().invoke<PriceHistoryRecord[]>('get_price_history', { subscriptionId: selectedId, fromTs, toTs, limit: 10000 }));
     } catch (e) {
       silentLog('HistoryPage.loadHistory', e);
       setRecords([]);
     } finally { setLoading(false); }
-  }, [selectedId, range, customFrom, customTo]);
+  }, [selectedId, timeRange]);
   useEffect(() => { loadHistory(); }, [loadHistory]);
 
+  // ── 匯出（目前訂閱 + 時間範圍）──
+  const exportHistory = useCallback(async (format: 'csv' | 'xlsx') => {
+    if (!sel) return;
+    const [fromTs, toTs] = timeRange();
+    try {
+      await getTransport().invoke('export_file', {
+        filename: `${sel.symbol.replace(/[^\w.-]+/g, '_')}_history.${format}`,
+        format,
+        dataset: { kind: 'history', subscription_ids: [sel.id], from_ts: fromTs, to_ts: toTs },
+      });
+      onToast.success(t.settings.exportSuccess, t.settings.exportSavedMsg);
+    } catch (e) {
+      if (!isCancelled(e)) onToast.error(String(e));
+    }
+  }, [sel, timeRange, onToast]);
+
+  const copyHistory = useCallback(async () => {
+    if (!sel) return;
+    const [from, to] = timeRange();
+    try {
+      const n = await getTransport().invoke<number>('copy_history_csv_to_clipboard', { subscriptionId: sel.id, from, to });
+      onToast.success(t.history.copiedRows(n));
+    } catch (e) { onToast.error(String(e)); }
+  }, [sel, timeRange, onToast]);
+
   // ── Session 數據 ──
   const hasPre = useMemo(() => records.some(r => r.pre_price != null), [records]);
   const hasPost = useMemo(() => records.some(r => r.post_price != null), [records]);
@@ -317,6 +346,9 @@ export function HistoryPage({ onToast }: Props) {
               <button className="history-icon-btn" onClick={() => setMenuOpen(v => !v)} title={t.nav.settings}>⚙️</button>
               {menuOpen && (
                 <div className="history-menu">
+                  {showBar && <button onClick={() => { exportHistory('csv'); setMenuOpen(false); }}>{t.history.exportCsv}</button>}
+                  {showBar && <button onClick={() => { exportHistory('xlsx'); setMenuOpen(false); }}>{t.history.exportXlsx}</button>}
+                  {showBar && isTauri() && <button onClick={() => { copyHistory(); setMenuOpen(false); }}>{t.history.copyCsv}</button>}
                   <button onClick={() => { cleanup90(); setMenuOpen(false); }}>{t.history.cleanup}</button>
                   <button className="danger" onClick={() => { purgeAll(); setMenuOpen(false); }}>{t.history.purgeAll}</button>
                   <button onClick={() => { openDir(); setMenuOpen(false); }}>{t.history.openDataDir}</button>
@@ -1,6 +1,7 @@
 import { useState, useEffect, useCallback } from 'react';
 import { getTransport } from '../../lib/transport';
 import { t } from '../../lib/i18n';
+import { errorMessage } from '../../lib/commandError';
 import { silentLog } from '../../lib/errorLog';
 
 interface AiProviderConfigResponse {
@@ -164,7 +165,7 @@ export function AiSettings() {
       setFeedback({ type: 'success', msg: t.notifications.saveSuccess });
       setApiKey('');
     } catch (e: unknown) {
-      setFeedback({ type: 'error', msg: typeof e === 'string' ? e : t.notifications.saveFailed });
+      setFeedback({ type: 'error', msg: errorMessage(e, t.notifications.saveFailed) });
     } finally {
       setSaving(false);
     }
@@ -192,10 +193,7 @@ export function AiSettings() {
         : (result as unknown as { message?: string })?.message || String(result);
       setTestResult({ type: 'success', msg });
     } catch (e: unknown) {
-      const msg = typeof e === 'string' ? e
-        : e instanceof Error ? e.message
-        : (e as { message?: string })?.message || t.notifications.testFailed;
-      setTestResult({ type: 'error', msg });
+      setTestResult({ type: 'error', msg: errorMessage(e, t.notifications.testFailed) });
     } finally {
       setTesting(false);
     }
@@ -1,6 +1,7 @@
 import { useState, useEffect, useCallback } from 'react';
 import { getTransport, isTauri } from '../../lib/transport';
 import { t } from '../../lib/i18n';
+import { errorMessage } from '../../lib/commandError';
 import { silentLog } from '../../lib/errorLog';
 import { useConfirm } from '../../hooks/useConfirm';
 import { ConfirmDialog } from '../ConfirmDialog/ConfirmDialog';
@@ -57,7 +58,7 @@ export function ChannelSettings() {
       setName(''); setBotToken(''); setChatId(''); setWebhookUrl('');
       await fetchChannels();
     } catch (e: unknown) {
-      setError(typeof e === 'string' ? e : t.notifications.saveFailed);
+      setError(errorMessage(e, t.notifications.saveFailed));
     } finally {
       setSaving(false);
     }
@@ -81,7 +82,7 @@ export function ChannelSettings() {
       await getTransport().invoke('test_notification_channel', { id });
       setTestResult({ id, success: true, msg: t.notifications.testOk });
     } catch (e: unknown) {
-      setTestResult({ id, success: false, msg: typeof e === 'string' ? e : t.notifications.testFailed });
+      setTestResult({ id, success: false, msg: errorMessage(e, t.notifications.testFailed) });
     } finally {
       setTesting(null);
     }
@@ -1,6 +1,7 @@
 import { useState, useEffect } from 'react';
 import { getTransport } from '../../lib/transport';
 import { t } from '../../lib/i18n';
+import { errorMessage } from '../../lib/commandError';
 import { silentLog } from '../../lib/errorLog';
 import { loadAllSubscriptions } from '../../lib/subscriptionApi';
 import { estimateTokens } from '../../lib/tokenEstimator';
@@ -28,6 +29,12 @@ const CONDITION_TYPES = [
   { value: 'price_below', labelKey: 'priceBelow' },
   { value: 'change_pct_above', labelKey: 'changePctAbove' },
   { value: 'change_pct_below', labelKey: 'changePctBelow' },
+  { value: 'gas_above', labelKey: 'gasAbove' },
+  { value: 'gas_below', labelKey: 'gasBelow' },
+  { value: 'depeg_above', labelKey: 'depegAbove' },
+  { value: 'event_before', labelKey: 'eventBefore' },
+  { value: 'vwap_above', labelKey: 'vwapAbove' },
+  { value: 'vwap_below', labelKey: 'vwapBelow' },
 ] as const;
 
 /** 分析間隔選項 — label 於 render 時由 t.notifications[labelKey] 解析（i18n） */
@@ -89,7 +96,7 @@ export function RuleForm({ onClose, onSaved, editRule }: RuleFormProps) {
   useEffect(() => {
     loadAllSubscriptions()
       .then(setSubscriptions)
-      .catch((e: unknown) => setError(typeof e === 'string' ? e : t.common.error));
+      .catch((e: unknown) => setError(errorMessage(e, t.common.error)));
     getTransport().invoke<ChannelRow[]>('list_notification_channels').then(setChannels).catch(e => silentLog('RuleForm.loadChannels', e));
     getTransport().invoke<{ base_url: string; model: string; has_api_key: boolean } | null>('get_ai_provider_config')
       .then(config => setAiProviderConfigured(config !== null))
@@ -168,7 +175,7 @@ export function RuleForm({ onClose, onSaved, editRule }: RuleFormProps) {
       onSaved();
       onClose();
     } catch (e: unknown) {
-      setError(typeof e === 'string' ? e : (isEditing ? t.notifications.updateRuleFailed : t.notifications.createRuleFailed));
+      setError(errorMessage(e, isEditing ? t.notifications.updateRuleFailed : t.notifications.createRuleFailed));
     } finally {
       setSaving(false);
     }
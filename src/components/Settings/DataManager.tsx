@@ -133,6 +133,17 @@ export function DataManager({ views, onRefresh, onToast }: DataManagerProps) {
     } catch { /* cancelled */ }
   };
 
+  const exportSubscriptions = async (format: 'csv' | 'xlsx') => {
+    try {
+      await getTransport().invoke('export_file', {
+        filename: `stockenboard_subscriptions_${new Date().toISOString().slice(0, 10)}.${format}`,
+        format,
+        dataset: { kind: 'subscriptions' },
+      });
+      onToast?.('success', t.settings.exportSuccess, t.settings.exportSavedMsg);
+    } catch { /* cancelled */ }
+  };
+
   const handleImport = async () => {
     let raw: string;
     try {
@@ -234,6 +245,12 @@ export function DataManager({ views, onRefresh, onToast }: DataManagerProps) {
         <button className="dm-btn export" onClick={openExportPicker}>
           {t.settings.export}
         </button>
+        <button className="dm-btn export" onClick={() => exportSubscriptions('csv')}>
+          {t.settings.exportCsv}
+        </button>
+        <button className="dm-btn export" onClick={() => exportSubscriptions('xlsx')}>
+          {t.settings.exportXlsx}
+        </button>
         <button className="dm-btn import" onClick={handleImport} disabled={importing}>
           {importing ? t.settings.importing : t.settings.import}
         </button>
@@ -1,5 +1,6 @@
 import { t, LOCALES, setLocale, type LocaleId } from '../../lib/i18n';
 import { useLocale } from '../../hooks/useLocale';
+import { getTransport } from '../../lib/transport';
 import './Settings.css';
 
 export function LanguagePicker() {
@@ -7,6 +8,8 @@ export function LanguagePicker() {
 
   const handleSelect = (id: LocaleId) => {
     setLocale(id);
+    // 後端錯誤/通知訊息跟隨介面語言
+    getTransport().invoke('set_language', { language: id }).catch(() => {});
   };
 
   return (
@@ -5,6 +5,7 @@ import { useState, useEffect } from 'react';
 import { getTransport, isTauri } from '../../lib/transport';
 import { t } from '../../lib/i18n';
 import { clearAllIcons } from '../AssetCard/AssetIcon';
+import { getIconName } from '../../lib/iconName';
 
 interface LogoDownloadResult {
   succeeded: number;
@@ -58,7 +59,7 @@ function SearchIconModal({ onClose, onToast }: { onClose: () => void; onToast?:
         dataUrl: selected.data,
       });
       clearAllIcons();
-      onToast?.('success', t.settings.logoManagement, `${saveAs.trim().toLowerCase()}.png ✓`);
+      onToast?.('success', t.settings.logoManagement, `${getIconName(saveAs)}.png ✓`);
       onClose();
     } catch (e) {
       onToast?.('error', t.settings.logoManagement, typeof e === 'string' ? e : String(e));
@@ -1,10 +1,14 @@
 /**
  * Provider 編輯 Modal — 從 ProviderSettings.tsx 抽出
  */
+import { useEffect, useState } from 'react';
 import type { ProviderInfo } from '../../types';
 import { TYPE_COLORS, getTypeLabels } from './providerConstants';
 import { t } from '../../lib/i18n';
 import { TZ_LABEL } from '../../lib/format';
+import { getTransport } from '../../lib/transport';
+import { silentLog } from '../../lib/errorLog';
+import { SubgraphProtocolsEditor } from './SubgraphProtocolsEditor';
 
 interface ProviderRow {
   id: string; name: string; provider_type: string;
@@ -34,6 +38,76 @@ interface ProviderModalProps {
   onClose: () => void;
 }
 
+/** Provider 專屬的開關設定（get_/set_ 成對 command），立即生效不需儲存 */
+function ProviderSettingToggle({ setting, label, hint }: { setting: string; label: string; hint: string }) {
+  const [enabled, setEnabled] = useState(false);
+  useEffect(() => {
+    getTransport().invoke<boolean>(`get_${setting}`).then(setEnabled).catch(e => silentLog(`get_${setting}`, e));
+  }, [setting]);
+  const toggle = async (next: boolean) => {
+    setEnabled(next);
+    try {
+      await getTransport().invoke(`set_${setting}`, { enabled: next });
+    } catch (e) { setEnabled(!next); silentLog(`set_${setting}`, e); }
+  };
+  return (
+    <div className="form-group">
+      <label>
+        <input type="checkbox" checked={enabled} onChange={e => toggle(e.target.checked)} /> {label}
+      </label>
+      <span className="form-hint">{hint}</span>
+    </div>
+  );
+}
+
+/** EODHD 專屬：未帶交易所後綴的 symbol 自動補上的預設交易所，離開欄位時儲存 */
+function EodhdExchangeSetting() {
+  const [exchange, setExchange] = useState('US');
+  const [error, setError] = useState('');
+  useEffect(() => {
+    getTransport().invoke<string>('get_eodhd_default_exchange').then(setExchange).catch(e => silentLog('getEodhdDefaultExchange', e));
+  }, []);
+  const save = async () => {
+    try {
+      await getTransport().invoke('set_eodhd_default_exchange', { exchange });
+      setExchange(exchange.trim().toUpperCase());
+      setError('');
+    } catch (e) { setError(String(e)); }
+  };
+  return (
+    <div className="form-group">
+      <label>{t.providers.eodhdDefaultExchange}</label>
+      <input type="text" value={exchange} onChange={e => setExchange(e.target.value)} onBlur={save} placeholder="US" />
+      <span className={error ? 'form-hint warning' : 'form-hint'}>{error || t.providers.eodhdDefaultExchangeHint}</span>
+    </div>
+  );
+}
+
+/** Alpha Vantage 專屬：單一 symbol 的更新間隔與今日剩餘額度，立即生效不需儲存 */
+function AlphaVantageQuotaSettings() {
+  const [hours, setHours] = useState(24);
+  const [left, setLeft] = useState<number | null>(null);
+  useEffect(() => {
+    const transport = getTransport();
+    transport.invoke<number>('get_alphavantage_refresh_hours').then(setHours).catch(e => silentLog('getAlphavantageRefreshHours', e));
+    transport.invoke<number>('get_alphavantage_quota').then(setLeft).catch(e => silentLog('getAlphavantageQuota', e));
+  }, []);
+  const save = async (next: number) => {
+    const value = Math.max(1, next || 1);
+    setHours(value);
+    try {
+      await getTransport().invoke('set_alphavantage_refresh_hours', { hours: value });
+    } catch (e) { silentLog('setAlphavantageRefreshHours', e); }
+  };
+  return (
+    <div className="form-group">
+      <label>{t.providers.alphavantageRefreshHours}</label>
+      <input type="number" value={hours} onChange={e => save(parseInt(e.target.value))} min={1} step={1} />
+      {left !== null && <span className="form-hint">{t.providers.alphavantageQuotaHint(left)}</span>}
+    </div>
+  );
+}
+
 export function ProviderModal({
   provider, info, formData, useKeyMode, getDesc,
   showModeToggle, canUseFree,
@@ -93,16 +167,23 @@ export function ProviderModal({
               <input type="password" value={formData.api_secret} onChange={e => set({ api_secret: e.target.value })} placeholder={t.apiKey.secretPlaceholder} />
             </div>
           )}
-          {info?.provider_type === 'dex' && (
-            <div className="form-group">
-              <label>{t.providers.apiUrl} <span className="optional-badge">{t.providers.apiUrlOptional}</span></label>
-              <input value={formData.api_url} onChange={e => set({ api_url: e.target.value })} placeholder={t.providers.apiUrlPlaceholder} />
-            </div>
-          )}
+          <div className="form-group">
+            <label>{t.providers.apiUrl} <span className="optional-badge">{t.providers.apiUrlOptional}</span></label>
+            <input value={formData.api_url} onChange={e => set({ api_url: e.target.value })} placeholder={t.providers.apiUrlPlaceholder} />
+          </div>
           <div className="form-group">
             <label>{t.providers.refreshInterval} {info && <span className="optional-badge">{t.providers.refreshHint((useKeyMode ? info.key_interval : info.free_interval) / 1000)}</span>}</label>
             <input type="number" value={formData.refresh_interval} onChange={e => set({ refresh_interval: parseInt(e.target.value) || 5000 })} min={5000} step={1000} />
           </div>
+          {provider.id === 'yahoo' && (
+            <ProviderSettingToggle setting="yahoo_quote_summary" label={t.providers.yahooQuoteSummary} hint={t.providers.yahooQuoteSummaryHint} />
+          )}
+          {provider.id === 'alphavantage' && <AlphaVantageQuotaSettings />}
+          {provider.id === 'eodhd' && <EodhdExchangeSetting />}
+          {provider.id === 'jupiter' && (
+            <ProviderSettingToggle setting="jupiter_token_list" label={t.providers.jupiterTokenList} hint={t.providers.jupiterTokenListHint} />
+          )}
+          {provider.id === 'subgraph' && <SubgraphProtocolsEditor />}
           {provider.supports_websocket === 1 && (
             <div className="form-group">
               <label>{t.providers.connectionMethod}</label>
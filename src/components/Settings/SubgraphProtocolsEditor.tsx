@@ -0,0 +1,72 @@
+/**
+ * Subgraph provider 專屬：編輯自訂的 protocol registry（例如 Aerodrome、Camelot），
+ * 內建項目唯讀顯示；自訂同 protocol + chain 會覆蓋內建的 subgraph ID
+ */
+import { useEffect, useState } from 'react';
+import type { SubgraphProtocol } from '../../types';
+import { t } from '../../lib/i18n';
+import { getTransport } from '../../lib/transport';
+import { silentLog } from '../../lib/errorLog';
+
+const emptyRow = (): SubgraphProtocol => ({ protocol: '', chain: '', name: '', subgraph_id: '', builtin: false });
+
+export function SubgraphProtocolsEditor() {
+  const [builtins, setBuiltins] = useState<SubgraphProtocol[]>([]);
+  const [custom, setCustom] = useState<SubgraphProtocol[]>([]);
+  const [status, setStatus] = useState<{ ok: boolean; msg: string } | null>(null);
+
+  useEffect(() => {
+    getTransport().invoke<SubgraphProtocol[]>('get_subgraph_protocols')
+      .then(all => {
+        setBuiltins(all.filter(p => p.builtin));
+        setCustom(all.filter(p => !p.builtin));
+      })
+      .catch(e => silentLog('getSubgraphProtocols', e));
+  }, []);
+
+  const update = (i: number, patch: Partial<SubgraphProtocol>) => {
+    setCustom(rows => rows.map((r, j) => (j === i ? { ...r, ...patch } : r)));
+    setStatus(null);
+  };
+
+  const save = async () => {
+    const rows = custom.map(r => ({
+      ...r,
+      protocol: r.protocol.trim().toLowerCase(),
+      chain: r.chain.trim().toLowerCase(),
+      name: r.name.trim() || r.protocol.trim(),
+      subgraph_id: r.subgraph_id.trim(),
+    }));
+    try {
+      await getTransport().invoke('set_subgraph_protocols', { protocols: rows });
+      setCustom(rows);
+      setStatus({ ok: true, msg: t.providers.settingsSaved });
+    } catch (e) {
+      setStatus({ ok: false, msg: e instanceof Error ? e.message : String(e) });
+    }
+  };
+
+  return (
+    <div className="form-group">
+      <label>{t.providers.subgraphProtocols}</label>
+      <span className="form-hint">
+        {t.providers.subgraphProtocolsBuiltin} {[...new Set(builtins.map(p => p.name))].join(', ')}
+      </span>
+      {custom.map((row, i) => (
+        <div key={i} className="subgraph-protocol-row">
+          <input value={row.protocol} onChange={e => update(i, { protocol: e.target.value })} placeholder="aerodrome" />
+          <input value={row.chain} onChange={e => update(i, { chain: e.target.value })} placeholder="base" />
+          <input value={row.name} onChange={e => update(i, { name: e.target.value })} placeholder="Aerodrome" />
+          <input className="mono" value={row.subgraph_id} onChange={e => update(i, { subgraph_id: e.target.value })} placeholder={t.providers.subgraphIdPlaceholder} />
+          <button type="button" className="btn-cancel" onClick={() => { setCustom(rows => rows.filter((_, j) => j !== i)); setStatus(null); }} aria-label={t.common.delete}>&#x2715;</button>
+        </div>
+      ))}
+      <div className="subgraph-protocol-actions">
+        <button type="button" className="btn-cancel" onClick={() => setCustom(rows => [...rows, emptyRow()])}>{t.providers.subgraphAddProtocol}</button>
+        <button type="button" className="btn-save-key" onClick={save}>{t.providers.subgraphSaveProtocols}</button>
+      </div>
+      {status && <span className={`form-hint ${status.ok ? 'priority' : 'warning'}`}>{status.msg}</span>}
+      <span className="form-hint">{t.providers.subgraphProtocolsHint}</span>
+    </div>
+  );
+}
@@ -36,9 +36,7 @@ export function SubscriptionManager({ onBatchAdd, onBatchAddMultiple, subscripti
   const [keySaving, setKeySaving] = useState(false);
 
   const filteredProviders = providerInfoList.filter(p =>
-    assetType === 'crypto'
-      ? (p.provider_type === 'crypto' || p.provider_type === 'both' || p.provider_type === 'dex')
-      : (p.provider_type === 'stock' || p.provider_type === 'both')
+    p.capabilities.asset_classes.includes(assetType)
   );
   useEffect(() => { setProvider(assetType === 'crypto' ? 'binance' : 'yahoo'); }, [assetType]);
 
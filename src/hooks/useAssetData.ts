@@ -1,5 +1,5 @@
 import { useState, useEffect, useCallback, useRef, useMemo } from 'react';
-import type { AssetData, Subscription, ProviderInfo, WsTickerUpdate } from '../types';
+import type { AssetData, CachedPrice, Subscription, ProviderInfo, WsTickerUpdate } from '../types';
 import { getTransport } from '../lib/transport';
 import { priceStore } from '../lib/priceStore';
 import * as api from '../lib/subscriptionApi';
@@ -65,20 +65,26 @@ export function useAssetData(subType: 'asset' | 'dex' = 'asset') {
       const fns = [
         getTransport().listen('price-update', (payload) => priceStore.updatePrices(payload as AssetData[])),
         getTransport().listen('price-error', (payload) => priceStore.updateErrors(payload as Record<string, string>)),
+        // 桌面版節流時 poll-tick 合併成陣列
         getTransport().listen('poll-tick', (payload) => {
-          const p = payload as { provider_id: string; fetched_at: number; interval_ms: number };
-          priceStore.updateTick(p.provider_id, p.fetched_at, p.interval_ms);
+          type Tick = { provider_id: string; fetched_at: number; interval_ms: number };
+          const ticks = Array.isArray(payload) ? (payload as Tick[]) : [payload as Tick];
+          for (const t of ticks) priceStore.updateTick(t.provider_id, t.fetched_at, t.interval_ms);
         }),
         getTransport().listen('ws-ticker-update', (payload) => {
           const p = payload as WsTickerUpdate;
           priceStore.updateWs(p.provider_id, p.symbol, p.data);
         }),
+        // stockenboard://add deep link 由後端新增訂閱
+        getTransport().listen('subscriptions-changed', (payload) => {
+          if ((payload as { sub_type: string }).sub_type === subType) loadSubs();
+        }),
       ];
       unlistenRefs.current = fns;
 
       // 載入快取
       try {
-        const cached = await getTransport().invoke<AssetData[]>('get_cached_prices');
+        const cached = await getTransport().invoke<CachedPrice[]>('get_cached_prices');
         if (cached.length > 0) priceStore.updatePrices(cached);
       } catch (e) { silentLog('getCachedPrices', e); }
       try {
@@ -115,6 +115,17 @@ export function useViews(viewType: 'asset' | 'dex' = 'asset') {
     })();
   }, [loadViews, storageKey, loadActiveViewSubs]);
 
+  // stockenboard://view deep link：切換到指定 view
+  useEffect(() => getTransport().listen('open-view', async (payload) => {
+    const p = payload as { view_id: number; view_type: string };
+    if (p.view_type !== viewType) return;
+    const loaded = await loadViews();
+    if (!loaded.some(v => v.id === p.view_id)) return;
+    setActiveViewId(p.view_id);
+    localStorage.setItem(storageKey, String(p.view_id));
+    await loadActiveViewSubs(p.view_id, loaded);
+  }), [viewType, storageKey, loadViews, loadActiveViewSubs]);
+
   return {
     views, activeViewId, activeViewSubscriptionIds, viewSubCounts, loading,
     setActiveView, createView, renameView, deleteView,
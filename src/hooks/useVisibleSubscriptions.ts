@@ -5,11 +5,13 @@ import { Subscription } from '../types';
 /**
  * 根據 view 過濾訂閱，並同步 visible subscription ids 到 Rust 後端。
  * 消除 App.tsx 和 DexPage.tsx 的重複邏輯。
+ * intervalMs 為此視窗要求的更新間隔（例如精簡浮動視窗放慢更新）；省略時沿用 provider 間隔。
  */
 export function useVisibleSubscriptions(
   subscriptions: Subscription[],
   activeViewSubscriptionIds: number[] | null,
   scope: 'asset' | 'dex',
+  intervalMs?: number,
 ) {
   const viewFilteredSubs = useMemo(() => {
     if (activeViewSubscriptionIds === null) return subscriptions;
@@ -20,11 +22,11 @@ export function useVisibleSubscriptions(
   const prevVisibleRef = useRef<string>('');
   useEffect(() => {
     const ids = viewFilteredSubs.map(s => s.id);
-    const key = ids.join(',');
+    const key = `${ids.join(',')}@${intervalMs ?? ''}`;
     if (key === prevVisibleRef.current) return;
     prevVisibleRef.current = key;
-    getTransport().invoke('set_visible_subscriptions', { ids, scope }).catch(() => {});
-  }, [viewFilteredSubs, scope]);
+    getTransport().invoke('set_visible_subscriptions', { ids, scope, intervalMs }).catch(() => {});
+  }, [viewFilteredSubs, scope, intervalMs]);
 
   return viewFilteredSubs;
 }
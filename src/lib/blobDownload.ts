@@ -2,7 +2,7 @@
  * Triggers a browser file download from in-memory content.
  * Creates a Blob, generates an object URL, clicks a hidden <a>, then revokes.
  */
-export function downloadBlob(filename: string, content: string, mimeType: string): void {
+export function downloadBlob(filename: string, content: BlobPart, mimeType: string): void {
   const blob = new Blob([content], { type: mimeType });
   const url = URL.createObjectURL(blob);
 
@@ -0,0 +1,27 @@
+import { describe, it, expect } from 'vitest';
+import { CommandError, errorMessage, isCancelled, toCommandError } from './commandError';
+
+describe('toCommandError', () => {
+  it('wraps structured backend errors', () => {
+    const err = toCommandError({ code: 'db_busy', message: 'Database is busy', key: 'db_busy' });
+    expect(err).toBeInstanceOf(CommandError);
+    expect(err).toMatchObject({ code: 'db_busy', message: 'Database is busy', key: 'db_busy' });
+    expect(String(err)).toBe('Database is busy');
+  });
+
+  it('keeps plain string errors', () => {
+    expect(toCommandError('Subscription not found')).toBe('Subscription not found');
+  });
+
+  it('detects cancelled file dialogs', () => {
+    expect(isCancelled(toCommandError({ code: 'cancelled', message: 'Cancelled' }))).toBe(true);
+    expect(isCancelled(new CommandError('bad_request', 'Cancelled'))).toBe(false);
+    expect(isCancelled('Cancelled')).toBe(false);
+  });
+
+  it('formats error messages with a fallback', () => {
+    expect(errorMessage(new CommandError('bad_request', 'Webhook URL must not be empty'), 'Save failed')).toBe('Webhook URL must not be empty');
+    expect(errorMessage('Subscription not found', 'Save failed')).toBe('Subscription not found');
+    expect(errorMessage({ unexpected: true }, 'Save failed')).toBe('Save failed');
+  });
+});
@@ -0,0 +1,45 @@
+/**
+ * 後端指令錯誤 — 對應 Rust 的 `CommandError`（`{ code, message, key? }`）與 REST 錯誤的
+ * `{ error: { code, message, key? } }`，兩種 transport 都以此拋出，呼叫端可依 `code` 分支。
+ *
+ * 常見 code：`bad_request`、`not_found`、`forbidden`、`cancelled`、`db_busy`、`internal_error`。
+ */
+export class CommandError extends Error {
+  readonly code: string;
+  /** 後端多語言訊息 key（僅已翻譯的訊息） */
+  readonly key?: string;
+
+  constructor(code: string, message: string, key?: string) {
+    super(message);
+    this.name = 'CommandError';
+    this.code = code;
+    this.key = key;
+  }
+
+  /** 只回傳訊息，沿用 `String(e)` 顯示錯誤的呼叫端不受影響 */
+  toString(): string {
+    return this.message;
+  }
+}
+
+/** Tauri invoke 的錯誤：結構化錯誤轉成 CommandError，仍回傳字串的指令原樣保留 */
+export function toCommandError(err: unknown): unknown {
+  if (err && typeof err === 'object') {
+    const { code, message, key } = err as { code?: unknown; message?: unknown; key?: unknown };
+    if (typeof code === 'string' && typeof message === 'string') {
+      return new CommandError(code, message, typeof key === 'string' ? key : undefined);
+    }
+  }
+  return err;
+}
+
+/** 使用者關閉檔案對話框 */
+export function isCancelled(err: unknown): boolean {
+  return err instanceof CommandError && err.code === 'cancelled';
+}
+
+/** 錯誤顯示文字：後端錯誤取其訊息，其他未知錯誤用 fallback */
+export function errorMessage(err: unknown, fallback: string): string {
+  if (err instanceof Error) return err.message || fallback;
+  return typeof err === 'string' && err ? err : fallback;
+}
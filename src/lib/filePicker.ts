@@ -3,6 +3,8 @@
  * Wraps <input type="file"> in a Promise for use in web mode file operations.
  */
 
+import { CommandError } from './commandError';
+
 interface FilePickerOptions {
   accept: string;
   multiple?: boolean;
@@ -52,7 +54,7 @@ export function pickFile(options: FilePickerOptions): Promise<File | null> {
 }
 
 /**
- * Shows a browser file picker. Rejects with Error('Cancelled') if the user
+ * Shows a browser file picker. Rejects with a `cancelled` CommandError if the user
  * cancels without selecting a file. Used by import_file to match desktop behavior.
  */
 export function pickFileOrThrow(options: FilePickerOptions): Promise<File> {
@@ -76,7 +78,7 @@ export function pickFileOrThrow(options: FilePickerOptions): Promise<File> {
       if (file) {
         resolve(file);
       } else {
-        reject(new Error('Cancelled'));
+        reject(new CommandError('cancelled', 'Cancelled'));
       }
     });
 
@@ -86,7 +88,7 @@ export function pickFileOrThrow(options: FilePickerOptions): Promise<File> {
       setTimeout(() => {
         if (input.parentNode) {
           cleanup();
-          reject(new Error('Cancelled'));
+          reject(new CommandError('cancelled', 'Cancelled'));
         }
       }, 300);
     };
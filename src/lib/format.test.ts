@@ -68,6 +68,11 @@ describe('formatPrice', () => {
     expect(formatPrice(1)).toBe('$1.00');
     expect(formatPrice(99.9)).toBe('$99.90');
   });
+
+  it('uses the provider price scale when given', () => {
+    expect(formatPrice(0.00002345, 'USDT', 8)).toBe('$0.00002345');
+    expect(formatPrice(1.5, 'USD', 0)).toBe('$2');
+  });
 });
 
 describe('truncateAddr', () => {
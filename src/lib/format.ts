@@ -12,9 +12,11 @@ export function formatNumber(num: number | undefined | null, decimals = 2): stri
   return num.toFixed(decimals);
 }
 
-export function formatPrice(price: number | undefined | null, currency: string = 'USD'): string {
+/** `scale` 為 provider 提供的小數位數（AssetData.price_scale）；未提供時 ≥ 1 取兩位，否則四位有效數字 */
+export function formatPrice(price: number | undefined | null, currency: string = 'USD', scale?: number | null): string {
   if (price === undefined || price === null || isNaN(price)) return '-';
   const sym = currency === 'USD' || currency === 'USDT' ? '$' : currency + ' ';
+  if (scale != null) return sym + price.toLocaleString(undefined, { minimumFractionDigits: scale, maximumFractionDigits: scale });
   if (price >= 1) return sym + price.toLocaleString(undefined, { minimumFractionDigits: 2, maximumFractionDigits: 2 });
   return sym + price.toPrecision(4);
 }
@@ -61,6 +63,12 @@ export function formatConditionLabel(conditionType: string, threshold: number):
     case 'price_below': return t.notifications.condPriceBelow(threshold.toLocaleString());
     case 'change_pct_above': return t.notifications.condChangeUp(String(threshold));
     case 'change_pct_below': return t.notifications.condChangeDown(String(threshold));
+    case 'gas_above': return t.notifications.condGasAbove(String(threshold));
+    case 'gas_below': return t.notifications.condGasBelow(String(threshold));
+    case 'depeg_above': return t.notifications.condDepegAbove(String(threshold));
+    case 'event_before': return t.notifications.condEventBefore(String(threshold));
+    case 'vwap_above': return t.notifications.condVwapAbove(String(threshold));
+    case 'vwap_below': return t.notifications.condVwapBelow(String(threshold));
     case 'ai': return t.notifications.aiRule;
     default: return conditionType;
   }
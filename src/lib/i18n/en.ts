@@ -102,7 +102,7 @@ const en: Locale = {
     multiSymbolHint: 'Multiple symbols supported, separated by comma or semicolon',
     example: (syms: string) => `e.g. ${syms}`,
     jupiterHint: 'Supports common symbols (SOL, JUP, BONK, WIF) or Solana mint address',
-    okxDexHint: 'Supports common symbols (ETH, BNB, SOL) or "chain:address" format, e.g. eth:0x..., sol:mint_address, arb:0x...',
+    okxDexHint: 'Supports common symbols (ETH, BNB, SOL) or "chain:address" format, e.g. eth:0x..., sol:mint_address, base:0x...; append :quote=USDT or :amount=1000 to change the quote token or size',
     importing: 'Importing...',
     add: 'Add',
     alreadyExists: 'Already exists',
@@ -208,8 +208,10 @@ const en: Locale = {
     updatedAt: 'Updated',
     collapse: '▲ Collapse',
     expand: '▼ Show more',
+    doubleClickCopy: 'Double-click to copy quote',
+    stalePrice: 'Last saved price — waiting for update',
     clickExpandCollapse: 'Click to expand/collapse error',
-    clickSetIcon: 'Click to set icon',
+    clickSetIcon: 'Click to set icon · Right-click to fetch automatically',
     sessionPre: 'Pre-Mkt',
     sessionPost: 'After-Hrs',
     sessionRegular: 'Live',
@@ -247,6 +249,20 @@ const en: Locale = {
     websocket: 'WebSocket',
     apiUrl: 'API URL',
     apiUrlOptional: 'optional',
+    yahooQuoteSummary: 'Fetch fundamentals for stocks (market cap, P/E, dividend yield)',
+    yahooQuoteSummaryHint: 'One extra request per stock, cached for an hour',
+    jupiterTokenList: 'Load Jupiter verified token list',
+    jupiterTokenListHint: 'Resolves symbols and decimals of any verified Solana mint; refreshed daily',
+    alphavantageRefreshHours: 'Refresh each symbol at most every (hours)',
+    alphavantageQuotaHint: (left: number) => `${left} of 25 daily calls left; calls are spread across the rest of the day and cached quotes are reused`,
+    eodhdDefaultExchange: 'Default exchange',
+    eodhdDefaultExchangeHint: 'Appended to symbols without a suffix, e.g. AAPL → AAPL.US',
+    subgraphProtocols: 'Subgraph protocols',
+    subgraphProtocolsBuiltin: 'Built-in:',
+    subgraphAddProtocol: 'Add protocol',
+    subgraphSaveProtocols: 'Save protocols',
+    subgraphIdPlaceholder: 'Subgraph ID',
+    subgraphProtocolsHint: 'Custom entries (protocol, chain, name, subgraph ID) add DEXes such as Aerodrome or Camelot; the same protocol + chain overrides a built-in subgraph',
     apiUrlPlaceholder: 'Leave empty for default',
     boostRate: 'Boost rate',
     settingsSaved: 'Settings saved',
@@ -264,6 +280,8 @@ const en: Locale = {
     importing: 'Importing...',
     exportSuccess: 'Export successful',
     exportSavedMsg: 'Data saved',
+    exportCsv: '📊 Export CSV',
+    exportXlsx: '📊 Export Excel',
     factoryReset: '⚠️ Factory Reset',
     factoryResetConfirm: 'WARNING: This will clear all your custom subscriptions, views, and API keys, restoring the app to its factory defaults. Are you sure you want to continue?',
     factoryResetSuccess: 'Factory reset successful',
@@ -362,6 +380,8 @@ const en: Locale = {
     avg_price: 'Avg Price',
     name: 'Name',
     pe_ratio: 'P/E Ratio',
+    forward_pe: 'Forward P/E',
+    dividend_yield: 'Dividend Yield %',
     eps: 'EPS',
     circulating_supply: 'Circulating Supply',
     cmc_rank: 'CMC Rank',
@@ -425,6 +445,10 @@ const en: Locale = {
     purgeAllConfirm: 'Are you sure you want to delete all history records? This cannot be undone.',
     purgeAllDone: (n: number) => `Purged ${n} records`,
     openDataDir: 'Open Folder',
+    exportCsv: 'Export CSV',
+    exportXlsx: 'Export Excel (.xlsx)',
+    copyCsv: 'Copy CSV to clipboard',
+    copiedRows: (n: number) => `Copied ${n} records to clipboard`,
     spot: 'Spot',
     dex: 'DEX',
     prePrice: 'Pre-Market',
@@ -549,11 +573,23 @@ const en: Locale = {
     priceBelow: 'Price below',
     changePctAbove: '24h gain exceeds',
     changePctBelow: '24h drop exceeds',
+    gasAbove: 'Gas above (chain of subscription)',
+    gasBelow: 'Gas below (chain of subscription)',
+    depegAbove: 'Stablecoin depeg above % (coin of subscription)',
+    eventBefore: 'Economic event within minutes (calendar alert keywords)',
+    vwapAbove: 'Above session VWAP by (%)',
+    vwapBelow: 'Below session VWAP by (%)',
     // Condition summaries (RuleList formatCondition, with value interpolation)
     condPriceAbove: (v: string) => `Price > $${v}`,
     condPriceBelow: (v: string) => `Price < $${v}`,
     condChangeUp: (v: string) => `Gain > ${v}%`,
     condChangeDown: (v: string) => `Drop < ${v}%`,
+    condGasAbove: (v: string) => `Gas > ${v}`,
+    condGasBelow: (v: string) => `Gas < ${v}`,
+    condDepegAbove: (v: string) => `Depeg > ${v}%`,
+    condEventBefore: (v: string) => `Event in ≤ ${v} min`,
+    condVwapAbove: (v: string) => `≥ ${v}% above VWAP`,
+    condVwapBelow: (v: string) => `≥ ${v}% below VWAP`,
     // Analysis intervals (RuleForm)
     interval30s: '30 seconds',
     interval1m: '1 minute',
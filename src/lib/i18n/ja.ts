@@ -102,7 +102,7 @@ const ja: Locale = {
     multiSymbolHint: '複数シンボル対応、カンマまたはセミコロンで区切り',
     example: (syms: string) => `例: ${syms}`,
     jupiterHint: '一般的なシンボル (SOL, JUP, BONK, WIF) または Solana mint address に対応',
-    okxDexHint: '一般的なシンボル (ETH, BNB, SOL) または「チェーン:アドレス」形式に対応',
+    okxDexHint: '一般的なシンボル (ETH, BNB, SOL) または「チェーン:アドレス」形式に対応（:quote=USDT / :amount=1000 で見積り通貨・数量を指定可）',
     importing: 'インポート中...',
     add: '追加',
     alreadyExists: '既に存在',
@@ -208,8 +208,10 @@ const ja: Locale = {
     updatedAt: '更新時刻',
     collapse: '▲ 折りたたむ',
     expand: '▼ もっと見る',
+    doubleClickCopy: 'ダブルクリックで価格をコピー',
+    stalePrice: '前回保存した価格 — 更新待ち',
     clickExpandCollapse: 'クリックでエラー詳細を展開/折りたたみ',
-    clickSetIcon: 'クリックでアイコンを設定',
+    clickSetIcon: 'クリックでアイコンを設定・右クリックで自動取得',
     sessionPre: 'プレマーケット',
     sessionPost: 'アフターアワーズ',
     sessionRegular: 'リアルタイム',
@@ -247,6 +249,20 @@ const ja: Locale = {
     websocket: 'WebSocket',
     apiUrl: 'API URL',
     apiUrlOptional: '任意',
+    yahooQuoteSummary: '株式の基本データを取得（時価総額・PER・配当利回り）',
+    yahooQuoteSummaryHint: '銘柄ごとに追加リクエスト 1 回、1 時間キャッシュ',
+    jupiterTokenList: 'Jupiter 検証済みトークンリストを読み込む',
+    jupiterTokenListHint: '検証済み Solana mint のシンボルと decimals を解決、毎日更新',
+    alphavantageRefreshHours: '各シンボルの最短更新間隔（時間）',
+    alphavantageQuotaHint: (left: number) => `本日の残り呼び出し ${left} / 25 回。残りの時間に均等配分し、キャッシュ済みの価格を再利用します`,
+    eodhdDefaultExchange: 'デフォルト取引所',
+    eodhdDefaultExchangeHint: 'サフィックスのないシンボルに付加、例: AAPL → AAPL.US',
+    subgraphProtocols: 'Subgraph プロトコル',
+    subgraphProtocolsBuiltin: '内蔵:',
+    subgraphAddProtocol: 'プロトコルを追加',
+    subgraphSaveProtocols: 'プロトコルを保存',
+    subgraphIdPlaceholder: 'Subgraph ID',
+    subgraphProtocolsHint: 'カスタム項目（protocol・chain・名前・subgraph ID）で Aerodrome や Camelot などの DEX を追加できます。同じ protocol + chain は内蔵の subgraph を上書きします',
     apiUrlPlaceholder: '空欄でデフォルト使用',
     boostRate: 'レート向上',
     settingsSaved: '設定を保存しました',
@@ -264,6 +280,8 @@ const ja: Locale = {
     importing: 'インポート中...',
     exportSuccess: 'エクスポート成功',
     exportSavedMsg: 'データを保存しました',
+    exportCsv: '📊 CSV エクスポート',
+    exportXlsx: '📊 Excel エクスポート',
     factoryReset: '⚠️ 初期化',
     factoryResetConfirm: '警告：この操作により、カスタムのサブスクリプション、ビュー、およびAPIキーがすべて消去され、アプリが初期状態に戻ります。続行してもよろしいですか？',
     factoryResetSuccess: '初期化に成功しました',
@@ -362,6 +380,8 @@ const ja: Locale = {
     avg_price: '平均価格',
     name: '名前',
     pe_ratio: 'PER',
+    forward_pe: '予想PER',
+    dividend_yield: '配当利回り %',
     eps: 'EPS',
     circulating_supply: '流通量',
     cmc_rank: 'CMC ランク',
@@ -425,6 +445,10 @@ const ja: Locale = {
     purgeAllConfirm: 'すべての履歴を削除しますか？この操作は元に戻せません。',
     purgeAllDone: (n: number) => `${n} 件の記録を削除しました`,
     openDataDir: 'フォルダを開く',
+    exportCsv: 'CSV エクスポート',
+    exportXlsx: 'Excel エクスポート (.xlsx)',
+    copyCsv: 'CSV をクリップボードにコピー',
+    copiedRows: (n: number) => `${n} 件をクリップボードにコピーしました`,
     spot: '現物',
     dex: 'DEX',
     prePrice: 'プレマーケット',
@@ -549,11 +573,23 @@ const ja: Locale = {
     priceBelow: '価格が以下',
     changePctAbove: '24h 上昇率が超過',
     changePctBelow: '24h 下落率が超過',
+    gasAbove: 'ガス代が上回る（購読のチェーン）',
+    gasBelow: 'ガス代が下回る（購読のチェーン）',
+    depegAbove: 'ステーブルコインの乖離が上回る %（購読の通貨）',
+    eventBefore: '経済指標発表まで（分、カレンダーの通知キーワード）',
+    vwapAbove: '当日VWAPより上（%）',
+    vwapBelow: '当日VWAPより下（%）',
     // 条件サマリー（RuleList formatCondition、数値補間あり）
     condPriceAbove: (v: string) => `価格 > $${v}`,
     condPriceBelow: (v: string) => `価格 < $${v}`,
     condChangeUp: (v: string) => `上昇率 > ${v}%`,
     condChangeDown: (v: string) => `下落率 < ${v}%`,
+    condGasAbove: (v: string) => `ガス > ${v}`,
+    condGasBelow: (v: string) => `ガス < ${v}`,
+    condDepegAbove: (v: string) => `乖離 > ${v}%`,
+    condEventBefore: (v: string) => `指標発表 ${v} 分前`,
+    condVwapAbove: (v: string) => `VWAP より ${v}% 以上高い`,
+    condVwapBelow: (v: string) => `VWAP より ${v}% 以上安い`,
     // 分析間隔（RuleForm）
     interval30s: '30 秒',
     interval1m: '1 分',
@@ -102,7 +102,7 @@ const ko: Locale = {
     multiSymbolHint: '여러 심볼 지원, 쉼표 또는 세미콜론으로 구분',
     example: (syms: string) => `예: ${syms}`,
     jupiterHint: '일반 심볼 (SOL, JUP, BONK, WIF) 또는 Solana mint address 지원',
-    okxDexHint: '일반 심볼 (ETH, BNB, SOL) 또는 "체인:주소" 형식 지원',
+    okxDexHint: '일반 심볼 (ETH, BNB, SOL) 또는 "체인:주소" 형식 지원 (:quote=USDT / :amount=1000 으로 견적 토큰·수량 지정 가능)',
     importing: '가져오는 중...',
     add: '추가',
     alreadyExists: '이미 존재',
@@ -208,8 +208,10 @@ const ko: Locale = {
     updatedAt: '업데이트',
     collapse: '▲ 접기',
     expand: '▼ 더 보기',
+    doubleClickCopy: '더블클릭하여 시세 복사',
+    stalePrice: '마지막으로 저장된 시세 — 업데이트 대기 중',
     clickExpandCollapse: '클릭하여 오류 상세 펼치기/접기',
-    clickSetIcon: '클릭하여 아이콘 설정',
+    clickSetIcon: '클릭하여 아이콘 설정 · 우클릭으로 자동 가져오기',
     sessionPre: '프리마켓',
     sessionPost: '애프터아워',
     sessionRegular: '실시간',
@@ -247,6 +249,20 @@ const ko: Locale = {
     websocket: 'WebSocket',
     apiUrl: 'API URL',
     apiUrlOptional: '선택',
+    yahooQuoteSummary: '주식 기본 지표 조회 (시가총액, PER, 배당수익률)',
+    yahooQuoteSummaryHint: '종목당 추가 요청 1회, 1시간 캐시',
+    jupiterTokenList: 'Jupiter 검증 토큰 목록 불러오기',
+    jupiterTokenListHint: '검증된 Solana mint의 심볼과 decimals 확인, 매일 갱신',
+    alphavantageRefreshHours: '심볼별 최소 갱신 간격 (시간)',
+    alphavantageQuotaHint: (left: number) => `오늘 남은 호출 ${left} / 25회. 남은 시간에 고르게 분배하고 캐시된 시세를 재사용합니다`,
+    eodhdDefaultExchange: '기본 거래소',
+    eodhdDefaultExchangeHint: '접미사가 없는 심볼에 추가, 예: AAPL → AAPL.US',
+    subgraphProtocols: 'Subgraph 프로토콜',
+    subgraphProtocolsBuiltin: '내장:',
+    subgraphAddProtocol: '프로토콜 추가',
+    subgraphSaveProtocols: '프로토콜 저장',
+    subgraphIdPlaceholder: 'Subgraph ID',
+    subgraphProtocolsHint: '사용자 항목(protocol, chain, 이름, subgraph ID)으로 Aerodrome, Camelot 등 DEX를 추가할 수 있습니다. 같은 protocol + chain은 내장 subgraph를 덮어씁니다',
     apiUrlPlaceholder: '비워두면 기본값 사용',
     boostRate: '속도 향상',
     settingsSaved: '설정 저장됨',
@@ -264,6 +280,8 @@ const ko: Locale = {
     importing: '가져오는 중...',
     exportSuccess: '내보내기 성공',
     exportSavedMsg: '저장완료',
+    exportCsv: '📊 CSV 내보내기',
+    exportXlsx: '📊 Excel 내보내기',
     factoryReset: '⚠️ 초기화',
     factoryResetConfirm: '경고: 이 작업은 모든 맞춤 구독, 뷰 및 API 키를 지우고 앱을 초기 상태로 복원합니다. 계속하시겠습니까?',
     factoryResetSuccess: '성공적으로 초기화되었습니다',
@@ -362,6 +380,8 @@ const ko: Locale = {
     avg_price: '평균가',
     name: '이름',
     pe_ratio: 'PER',
+    forward_pe: '예상 PER',
+    dividend_yield: '배당수익률 %',
     eps: 'EPS',
     circulating_supply: '유통량',
     cmc_rank: 'CMC 순위',
@@ -425,6 +445,10 @@ const ko: Locale = {
     purgeAllConfirm: '모든 기록을 삭제하시겠습니까? 이 작업은 되돌릴 수 없습니다.',
     purgeAllDone: (n: number) => `${n}개의 기록을 삭제했습니다`,
     openDataDir: '폴더 열기',
+    exportCsv: 'CSV 내보내기',
+    exportXlsx: 'Excel 내보내기 (.xlsx)',
+    copyCsv: 'CSV를 클립보드에 복사',
+    copiedRows: (n: number) => `${n}개 기록을 클립보드에 복사했습니다`,
     spot: '현물',
     dex: 'DEX',
     prePrice: '프리마켓',
@@ -549,11 +573,23 @@ const ko: Locale = {
     priceBelow: '가격이 이하',
     changePctAbove: '24h 상승률 초과',
     changePctBelow: '24h 하락률 초과',
+    gasAbove: '가스비 초과 (구독 체인)',
+    gasBelow: '가스비 미만 (구독 체인)',
+    depegAbove: '스테이블코인 디페그 초과 % (구독 코인)',
+    eventBefore: '경제 이벤트까지 (분, 캘린더 알림 키워드)',
+    vwapAbove: '당일 VWAP 대비 상회 (%)',
+    vwapBelow: '당일 VWAP 대비 하회 (%)',
     // 조건 요약（RuleList formatCondition, 숫자 보간 포함）
     condPriceAbove: (v: string) => `가격 > $${v}`,
     condPriceBelow: (v: string) => `가격 < $${v}`,
     condChangeUp: (v: string) => `상승률 > ${v}%`,
     condChangeDown: (v: string) => `하락률 < ${v}%`,
+    condGasAbove: (v: string) => `가스 > ${v}`,
+    condGasBelow: (v: string) => `가스 < ${v}`,
+    condDepegAbove: (v: string) => `디페그 > ${v}%`,
+    condEventBefore: (v: string) => `이벤트 ${v}분 전`,
+    condVwapAbove: (v: string) => `VWAP 대비 ${v}% 이상 상회`,
+    condVwapBelow: (v: string) => `VWAP 대비 ${v}% 이상 하회`,
     // 분석 간격（RuleForm）
     interval30s: '30 초',
     interval1m: '1 분',
@@ -102,7 +102,7 @@ const zh_CN: Locale = {
     multiSymbolHint: '支持多个代号，用逗号或分号分隔',
     example: (syms: string) => `例如: ${syms}`,
     jupiterHint: '支持常见代号 (SOL, JUP, BONK, WIF) 或 Solana mint address',
-    okxDexHint: '支持常见代号 (ETH, BNB, SOL) 或「链:合约地址」格式，如 eth:0x..., sol:mint_address, arb:0x...',
+    okxDexHint: '支持常见代号 (ETH, BNB, SOL) 或「链:合约地址」格式，如 eth:0x..., sol:mint_address, base:0x...；可加 :quote=USDT 或 :amount=1000 指定报价币与询价数量',
     importing: '导入中...',
     add: '新增',
     alreadyExists: '已存在',
@@ -208,8 +208,10 @@ const zh_CN: Locale = {
     updatedAt: '更新时间',
     collapse: '▲ 收起',
     expand: '▼ 显示更多',
+    doubleClickCopy: '双击复制报价',
+    stalePrice: '上次保存的价格 — 等待更新',
     clickExpandCollapse: '点击展开/收起完整错误',
-    clickSetIcon: '点击设置图标',
+    clickSetIcon: '点击设置图标 · 右键自动获取',
     sessionPre: '盘前',
     sessionPost: '盘后',
     sessionRegular: '实时',
@@ -247,6 +249,20 @@ const zh_CN: Locale = {
     websocket: 'WebSocket',
     apiUrl: 'API URL',
     apiUrlOptional: '可选',
+    yahooQuoteSummary: '为股票获取基本面数据（市值、市盈率、股息率）',
+    yahooQuoteSummaryHint: '每只股票额外一次请求，缓存一小时',
+    jupiterTokenList: '载入 Jupiter 已验证代币列表',
+    jupiterTokenListHint: '解析已验证 Solana mint 的代号与 decimals，每天更新',
+    alphavantageRefreshHours: '每个代号最短更新间隔（小时）',
+    alphavantageQuotaHint: (left: number) => `今日剩余 ${left} / 25 次调用；平均分配到当天剩余时间，并沿用已缓存的报价`,
+    eodhdDefaultExchange: '默认交易所',
+    eodhdDefaultExchangeHint: '未带后缀的代号会自动补上，例如 AAPL → AAPL.US',
+    subgraphProtocols: 'Subgraph 协议',
+    subgraphProtocolsBuiltin: '内置：',
+    subgraphAddProtocol: '新增协议',
+    subgraphSaveProtocols: '保存协议',
+    subgraphIdPlaceholder: 'Subgraph ID',
+    subgraphProtocolsHint: '自定义项目（protocol、chain、名称、subgraph ID）可加入 Aerodrome、Camelot 等 DEX；相同 protocol + chain 会覆盖内置的 subgraph',
     apiUrlPlaceholder: '留空使用默认',
     boostRate: '提高速率',
     settingsSaved: '设置已保存',
@@ -264,6 +280,8 @@ const zh_CN: Locale = {
     importing: '导入中...',
     exportSuccess: '导出成功',
     exportSavedMsg: '资料已保存',
+    exportCsv: '📊 导出 CSV',
+    exportXlsx: '📊 导出 Excel',
     factoryReset: '⚠️ 恢复出厂设置',
     factoryResetConfirm: '警告：此操作将清除所有自定义的订阅项目、页面与 API 密钥设置，并还原为出厂默认。确定要继续吗？',
     factoryResetSuccess: '已成功恢复出厂设置',
@@ -362,6 +380,8 @@ const zh_CN: Locale = {
     avg_price: '均价',
     name: '名称',
     pe_ratio: '市盈率',
+    forward_pe: '预期市盈率',
+    dividend_yield: '股息率 %',
     eps: '每股收益',
     circulating_supply: '流通量',
     cmc_rank: 'CMC 排名',
@@ -425,6 +445,10 @@ const zh_CN: Locale = {
     purgeAllConfirm: '确定要清除所有历史记录吗？此操作无法恢复。',
     purgeAllDone: (n: number) => `已清除 ${n} 笔记录`,
     openDataDir: '打开文件夹',
+    exportCsv: '导出 CSV',
+    exportXlsx: '导出 Excel (.xlsx)',
+    copyCsv: '复制 CSV 到剪贴板',
+    copiedRows: (n: number) => `已复制 ${n} 条记录到剪贴板`,
     spot: '现货',
     dex: 'DEX',
     prePrice: '盘前',
@@ -549,11 +573,23 @@ const zh_CN: Locale = {
     priceBelow: '价格低于',
     changePctAbove: '24h涨幅超过',
     changePctBelow: '24h跌幅超过',
+    gasAbove: 'Gas 高于（订阅所在链）',
+    gasBelow: 'Gas 低于（订阅所在链）',
+    depegAbove: '稳定币脱钩偏离高于 %（订阅对应币种）',
+    eventBefore: '经济事件开始前（分钟，日历提醒关键字）',
+    vwapAbove: '高于当日 VWAP（%）',
+    vwapBelow: '低于当日 VWAP（%）',
     // 条件摘要（RuleList formatCondition，含数值插值）
     condPriceAbove: (v: string) => `价格 > $${v}`,
     condPriceBelow: (v: string) => `价格 < $${v}`,
     condChangeUp: (v: string) => `涨幅 > ${v}%`,
     condChangeDown: (v: string) => `跌幅 < ${v}%`,
+    condGasAbove: (v: string) => `Gas > ${v}`,
+    condGasBelow: (v: string) => `Gas < ${v}`,
+    condDepegAbove: (v: string) => `脱钩 > ${v}%`,
+    condEventBefore: (v: string) => `事件前 ${v} 分钟`,
+    condVwapAbove: (v: string) => `高于 VWAP ${v}%`,
+    condVwapBelow: (v: string) => `低于 VWAP ${v}%`,
     // 分析间隔（RuleForm）
     interval30s: '30 秒',
     interval1m: '1 分钟',
@@ -104,7 +104,7 @@ const zh_TW = {
     multiSymbolHint: '支援多個代號，用逗號或分號分隔',
     example: (syms: string) => `例如: ${syms}`,
     jupiterHint: '支援常見代號 (SOL, JUP, BONK, WIF) 或 Solana mint address',
-    okxDexHint: '支援常見代號 (ETH, BNB, SOL) 或「鏈:合約地址」格式，如 eth:0x..., sol:mint_address, arb:0x...',
+    okxDexHint: '支援常見代號 (ETH, BNB, SOL) 或「鏈:合約地址」格式，如 eth:0x..., sol:mint_address, base:0x...；可加 :quote=USDT 或 :amount=1000 指定報價幣與詢價數量',
     importing: '匯入中...',
     add: '新增',
     alreadyExists: '已存在',
@@ -211,8 +211,10 @@ const zh_TW = {
     updatedAt: '更新時間',
     collapse: '▲ 收起',
     expand: '▼ 顯示更多',
+    doubleClickCopy: '雙擊複製報價',
+    stalePrice: '上次保存的價格 — 等待更新',
     clickExpandCollapse: '點擊展開/收起完整錯誤',
-    clickSetIcon: '點擊設定圖示',
+    clickSetIcon: '點擊設定圖示 · 右鍵自動取得',
     sessionPre: '盤前',
     sessionPost: '盤後',
     sessionRegular: '即時',
@@ -250,6 +252,20 @@ const zh_TW = {
     websocket: 'WebSocket',
     apiUrl: 'API URL',
     apiUrlOptional: '可選',
+    yahooQuoteSummary: '為股票取得基本面資料（市值、本益比、殖利率）',
+    yahooQuoteSummaryHint: '每檔股票額外一次請求，快取一小時',
+    jupiterTokenList: '載入 Jupiter 已驗證代幣清單',
+    jupiterTokenListHint: '解析已驗證 Solana mint 的代號與 decimals，每天更新',
+    alphavantageRefreshHours: '每個代號最短更新間隔（小時）',
+    alphavantageQuotaHint: (left: number) => `今日剩餘 ${left} / 25 次呼叫；平均分配到當天剩餘時間，並沿用已快取的報價`,
+    eodhdDefaultExchange: '預設交易所',
+    eodhdDefaultExchangeHint: '未帶後綴的代號會自動補上，例如 AAPL → AAPL.US',
+    subgraphProtocols: 'Subgraph 協議',
+    subgraphProtocolsBuiltin: '內建：',
+    subgraphAddProtocol: '新增協議',
+    subgraphSaveProtocols: '儲存協議',
+    subgraphIdPlaceholder: 'Subgraph ID',
+    subgraphProtocolsHint: '自訂項目（protocol、chain、名稱、subgraph ID）可加入 Aerodrome、Camelot 等 DEX；相同 protocol + chain 會覆蓋內建的 subgraph',
     apiUrlPlaceholder: '留空使用預設',
     boostRate: '提高速率',
     settingsSaved: '設定已儲存',
@@ -267,6 +283,8 @@ const zh_TW = {
     importing: '匯入中...',
     exportSuccess: '匯出成功',
     exportSavedMsg: '資料已儲存',
+    exportCsv: '📊 匯出 CSV',
+    exportXlsx: '📊 匯出 Excel',
     factoryReset: '⚠️ 恢復出廠設定',
     factoryResetConfirm: '警告：此操作將清除所有自訂的訂閱項目、頁面與 API 金鑰設定，並還原為出廠預設。確定要繼續嗎？',
     factoryResetSuccess: '已成功恢復出廠設定',
@@ -365,6 +383,8 @@ const zh_TW = {
     avg_price: '均價',
     name: '名稱',
     pe_ratio: '本益比',
+    forward_pe: '預估本益比',
+    dividend_yield: '殖利率 %',
     eps: '每股盈餘',
     circulating_supply: '流通量',
     cmc_rank: 'CMC 排名',
@@ -428,6 +448,10 @@ const zh_TW = {
     purgeAllConfirm: '確定要清除所有歷史紀錄嗎？此操作無法復原。',
     purgeAllDone: (n: number) => `已清除 ${n} 筆紀錄`,
     openDataDir: '開啟資料夾',
+    exportCsv: '匯出 CSV',
+    exportXlsx: '匯出 Excel (.xlsx)',
+    copyCsv: '複製 CSV 到剪貼簿',
+    copiedRows: (n: number) => `已複製 ${n} 筆紀錄到剪貼簿`,
     spot: '現貨',
     dex: 'DEX',
     prePrice: '盤前',
@@ -554,11 +578,23 @@ const zh_TW = {
     priceBelow: '價格低於',
     changePctAbove: '24h漲幅超過',
     changePctBelow: '24h跌幅超過',
+    gasAbove: 'Gas 高於（訂閱所在鏈）',
+    gasBelow: 'Gas 低於（訂閱所在鏈）',
+    depegAbove: '穩定幣脫鉤偏離高於 %（訂閱對應幣種）',
+    eventBefore: '經濟事件開始前（分鐘，日曆提醒關鍵字）',
+    vwapAbove: '高於當日 VWAP（%）',
+    vwapBelow: '低於當日 VWAP（%）',
     // 條件摘要（RuleList formatCondition，含數值插值）
     condPriceAbove: (v: string) => `價格 > $${v}`,
     condPriceBelow: (v: string) => `價格 < $${v}`,
     condChangeUp: (v: string) => `漲幅 > ${v}%`,
     condChangeDown: (v: string) => `跌幅 < ${v}%`,
+    condGasAbove: (v: string) => `Gas > ${v}`,
+    condGasBelow: (v: string) => `Gas < ${v}`,
+    condDepegAbove: (v: string) => `脫鉤 > ${v}%`,
+    condEventBefore: (v: string) => `事件前 ${v} 分鐘`,
+    condVwapAbove: (v: string) => `高於 VWAP ${v}%`,
+    condVwapBelow: (v: string) => `低於 VWAP ${v}%`,
     // 分析間隔（RuleForm）
     interval30s: '30 秒',
     interval1m: '1 分鐘',
@@ -0,0 +1,17 @@
+import { describe, it, expect } from 'vitest';
+import { getIconName } from './iconName';
+
+// 測試向量與 src-tauri/src/icons.rs 的 icon_names_are_safe_and_distinct 相同
+describe('getIconName', () => {
+  it('keeps plain symbols lowercase', () => {
+    expect(getIconName('BTC')).toBe('btc');
+    expect(getIconName(' brk.b ')).toBe('brk.b');
+  });
+
+  it('hashes symbols that are not safe file names', () => {
+    expect(getIconName('pool:So11:EPjF')).toBe('pool_so11_epjf-bbefba9a');
+    expect(getIconName('CON')).toBe('con-f08dd14b');
+    expect(getIconName('BTC/USDT')).not.toBe(getIconName('btc_usdt'));
+    expect(getIconName('🚀')).toMatch(/^[a-z0-9._-]+$/);
+  });
+});
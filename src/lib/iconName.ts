@@ -0,0 +1,40 @@
+/**
+ * Icon 檔名規則 — 必須與 Rust 端 `icons::to_icon_name` 完全一致。
+ *
+ * 一般 symbol 直接使用小寫（`BTC` → `btc`）；含 `:` `/`、非 ASCII、Windows 保留名稱
+ * 或過長的 symbol 改為 `{sanitized}-{fnv1a hash}`，避免不同資產撞名或產生非法檔名。
+ */
+
+const ICON_NAME_MAX_LEN = 48;
+
+const WINDOWS_RESERVED_NAMES = new Set([
+  'con', 'prn', 'aux', 'nul',
+  'com1', 'com2', 'com3', 'com4', 'com5', 'com6', 'com7', 'com8', 'com9',
+  'lpt1', 'lpt2', 'lpt3', 'lpt4', 'lpt5', 'lpt6', 'lpt7', 'lpt8', 'lpt9',
+]);
+
+/** 32-bit FNV-1a（UTF-8 bytes） */
+function fnv1a(text: string): number {
+  let hash = 0x811c9dc5;
+  for (const byte of new TextEncoder().encode(text)) {
+    hash ^= byte;
+    hash = Math.imul(hash, 0x01000193) >>> 0;
+  }
+  return hash >>> 0;
+}
+
+export function getIconName(symbol: string): string {
+  const lower = symbol.trim().toLowerCase();
+  // 以 code point 逐字處理，與 Rust 的 chars() 一致
+  const sanitized = Array.from(lower, c => (/^[a-z0-9._-]$/.test(c) ? c : '_')).join('');
+  const deviceName = lower.split('.')[0];
+  const plain = lower.length > 0
+    && sanitized === lower
+    && lower.length <= ICON_NAME_MAX_LEN
+    && !lower.startsWith('.')
+    && !lower.endsWith('.')
+    && !WINDOWS_RESERVED_NAMES.has(deviceName);
+  if (plain) return lower;
+  const prefix = sanitized.replace(/^\.+|\.+$/g, '').slice(0, ICON_NAME_MAX_LEN);
+  return `${prefix}-${fnv1a(lower).toString(16).padStart(8, '0')}`;
+}
@@ -8,6 +8,8 @@ export const STORAGE_KEYS = {
     EXPAND_ALL: 'sb_expand_all',
     HIDE_PREPOST: 'sb_hide_prepost',
     THEME: 'sb_theme',
+    /** 看板網址 `?kiosk=` 帶入的受限 token */
+    KIOSK_TOKEN: 'sb_kiosk_token',
 
     // Views — Asset
     ACTIVE_VIEW_ID: 'sb_active_view_id',
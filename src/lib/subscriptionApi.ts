@@ -75,17 +75,18 @@ export async function addAssetSubscriptionBatch(
     else groups.set(pid, [prepared]);
   }
 
-  // 2. 每個 provider 用一次批量 API 驗證所有 symbol
+  // 2. 一次 IPC 並行向所有 provider 驗證（各 provider 的結果 / 錯誤分組回傳）
   const allValid: PreparedItem[] = [];
   const failed: string[] = [];
-  let done = 0;
-
-  for (const [pid, group] of groups) {
-    const symbols = group.map(g => g.storedSymbol);
-    try {
-      const results = await getTransport().invoke<{ symbol: string }[]>('fetch_multiple_prices', { providerId: pid, symbols });
-      const validSymbols = new Set(results.map(r => r.symbol.toUpperCase()));
 
+  const entries = [...groups];
+  try {
+    const results = await getTransport().invoke<{ provider_id: string; data: { symbol: string }[]; error: string | null }[]>(
+      'fetch_prices_multi',
+      { requests: entries.map(([pid, group]) => ({ provider_id: pid, symbols: group.map(g => g.storedSymbol) })) },
+    );
+    entries.forEach(([, group], i) => {
+      const validSymbols = new Set((results[i]?.data ?? []).map(r => r.symbol.toUpperCase()));
       for (const g of group) {
         if (validSymbols.has(g.storedSymbol.toUpperCase())) {
           allValid.push(g);
@@ -93,12 +94,12 @@ export async function addAssetSubscriptionBatch(
           failed.push(g.symbol);
         }
       }
-    } catch {
-      for (const g of group) failed.push(g.symbol);
-    }
-    done += group.length;
-    onProgress?.(done, total);
+    });
+  } catch (e) {
+    silentLog('fetchPricesMulti', e);
+    for (const [, group] of entries) for (const g of group) failed.push(g.symbol);
   }
+  onProgress?.(total, total);
 
   // 3. 批量寫入 DB（走 Rust IPC，一次搞定）
   const succeeded: string[] = [];
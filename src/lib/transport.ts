@@ -9,6 +9,7 @@
  */
 
 import { HttpTransport } from './transportWs';
+import { toCommandError } from './commandError';
 
 /**
  * Unified transport interface for communicating with the backend.
@@ -45,7 +46,11 @@ export function isTauri(): boolean {
 class TauriTransport implements Transport {
   async invoke<T>(command: string, args?: Record<string, unknown>): Promise<T> {
     const { invoke } = await import('@tauri-apps/api/core');
-    return invoke<T>(command, args);
+    try {
+      return await invoke<T>(command, args);
+    } catch (e) {
+      throw toCommandError(e);
+    }
   }
 
   listen(event: string, handler: (payload: unknown) => void): () => void {
@@ -9,4 +9,14 @@ export const dexRoutes: Record<string, RouteMapper> = {
     method: 'GET',
     path: `/dex/pool/${encodeURIComponent(String(a.providerId ?? a.provider))}/${encodeURIComponent(String(a.poolAddress ?? a.address))}`,
   }),
+  compare_dex_quotes: (a) => ({
+    method: 'POST',
+    path: '/dex/quotes',
+    body: JSON.stringify({
+      chain: a.chain,
+      token_in: a.tokenIn,
+      token_out: a.tokenOut,
+      amount: a.amount,
+    }),
+  }),
 };
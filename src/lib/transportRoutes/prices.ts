@@ -15,8 +15,29 @@ export const priceRoutes: Record<string, RouteMapper> = {
     path: '/prices/fetch-multiple',
     body: JSON.stringify({ provider_id: a.providerId, symbols: a.symbols }),
   }),
+  fetch_prices_multi: (a) => ({
+    method: 'POST',
+    path: '/prices/fetch-multi',
+    body: JSON.stringify({ requests: a.requests }),
+  }),
   get_cached_prices: () => ({ method: 'GET', path: '/prices/cached' }),
+  render_snapshot: (a) => ({
+    method: 'GET',
+    path: `/prices/snapshot?subscription_ids=${encodeURIComponent(((a.subscriptionIds as number[] | undefined) ?? []).join(','))}`,
+  }),
   get_poll_ticks: () => ({ method: 'GET', path: '/prices/poll-ticks' }),
+  get_gas_prices: () => ({ method: 'GET', path: '/prices/gas' }),
+  get_depeg_status: () => ({ method: 'GET', path: '/depeg' }),
+  get_economic_calendar: (a) => ({
+    method: 'GET',
+    path: `/calendar?${new URLSearchParams({
+      ...(a.from != null ? { from: String(a.from) } : {}),
+      ...(a.to != null ? { to: String(a.to) } : {}),
+      ...(a.country ? { country: String(a.country) } : {}),
+      ...(a.minImpact ? { impact: String(a.minImpact) } : {}),
+    }).toString()}`,
+  }),
+  refresh_economic_calendar: () => ({ method: 'POST', path: '/calendar/refresh' }),
 
   // --- History ---
   get_price_history: (a) => ({
@@ -25,12 +46,52 @@ export const priceRoutes: Record<string, RouteMapper> = {
       ...(a.fromTs != null ? { from: String(a.fromTs) } : {}),
       ...(a.toTs != null ? { to: String(a.toTs) } : {}),
       ...(a.limit != null ? { limit: String(a.limit) } : {}),
+      // 與 desktop IPC 一致：DEX 訂閱一併回傳流動性欄位，衍生品訂閱回傳持倉欄位
+      fields: 'liquidity,derivatives',
     }).toString()}`,
   }),
   get_history_stats: (a) => ({
     method: 'GET',
     path: `/history/stats${(a.subscriptionIds as number[] | undefined)?.length ? `?subscription_ids=${encodeURIComponent((a.subscriptionIds as number[]).join(','))}` : ''}`,
   }),
+  get_subscription_events: (a) => ({
+    method: 'GET',
+    path: `/history/${encodeURIComponent(String(a.subscriptionId))}/events?${new URLSearchParams({
+      ...(a.fromTs != null ? { from: String(a.fromTs) } : {}),
+      ...(a.toTs != null ? { to: String(a.toTs) } : {}),
+    }).toString()}`,
+  }),
+  get_streaks: (a) => ({
+    method: 'GET',
+    path: `/history/${encodeURIComponent(String(a.subscriptionId))}/streaks`,
+  }),
+  get_vwap: (a) => ({
+    method: 'GET',
+    path: `/history/${encodeURIComponent(String(a.subscriptionId))}/vwap${a.anchor != null ? `?anchor=${encodeURIComponent(String(a.anchor))}` : ''}`,
+  }),
+  get_sparkline: (a) => {
+    const params = new URLSearchParams({ subscription_id: String(a.subscriptionId) });
+    if (a.points != null) params.set('points', String(a.points));
+    if (a.window != null) params.set('window', String(a.window));
+    return { method: 'GET', path: `/sparkline?${params}` };
+  },
+  get_twap: (a) => {
+    const params = new URLSearchParams({ subscription_id: String(a.subscriptionId) });
+    if (a.window != null) params.set('window', String(a.window));
+    return { method: 'GET', path: `/twap?${params}` };
+  },
+  get_candles: (a) => {
+    const params = new URLSearchParams({ provider_id: String(a.providerId), symbol: String(a.symbol) });
+    if (a.from != null) params.set('from', String(a.from));
+    if (a.to != null) params.set('to', String(a.to));
+    if (a.limit != null) params.set('limit', String(a.limit));
+    return { method: 'GET', path: `/candles?${params}` };
+  },
+  import_history_csv: (a) => ({
+    method: 'POST',
+    path: `/history/${encodeURIComponent(String(a.subscriptionId))}/import-csv`,
+    body: JSON.stringify({ content: a.content, dry_run: a.dryRun ?? false }),
+  }),
   cleanup_history: (a) => ({
     method: 'POST',
     path: '/history/cleanup',
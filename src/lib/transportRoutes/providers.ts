@@ -11,6 +11,11 @@ export const providerRoutes: Record<string, RouteMapper> = {
     path: `/providers/${encodeURIComponent(String(a.id))}/enable`,
     body: JSON.stringify(a),
   }),
+  clear_provider_cache: () => ({ method: 'DELETE', path: '/providers/cache' }),
+  resolve_best_provider: (a) => ({
+    method: 'GET',
+    path: `/providers/resolve?symbol=${encodeURIComponent(String(a.symbol))}`,
+  }),
   list_provider_settings: () => ({ method: 'GET', path: '/provider-settings' }),
   upsert_provider_settings: (a) => ({
     method: 'PUT',
@@ -23,6 +28,9 @@ export const providerRoutes: Record<string, RouteMapper> = {
       connection_type: a.connectionType,
       record_from_hour: a.recordFromHour,
       record_to_hour: a.recordToHour,
+      proxy_url: a.proxyUrl,
+      timeout_ms: a.timeoutMs,
+      extra_headers: a.extraHeaders,
     }),
   }),
   has_api_key: (a) => ({
@@ -34,4 +42,21 @@ export const providerRoutes: Record<string, RouteMapper> = {
     path: `/provider-settings/${encodeURIComponent(String(a.provider_id ?? a.providerId))}/record-hours`,
     body: JSON.stringify({ from_hour: a.from_hour ?? a.fromHour, to_hour: a.to_hour ?? a.toHour }),
   }),
+  get_record_hours: (a) => (a.subscriptionId != null
+    ? { method: 'GET', path: `/subscriptions/${encodeURIComponent(String(a.subscriptionId))}/record-hours` }
+    : { method: 'GET', path: `/provider-settings/${encodeURIComponent(String(a.providerId))}/record-hours` }),
+  clear_record_hours: (a) => (a.subscriptionId != null
+    ? { method: 'DELETE', path: `/subscriptions/${encodeURIComponent(String(a.subscriptionId))}/record-hours` }
+    : { method: 'DELETE', path: `/provider-settings/${encodeURIComponent(String(a.providerId))}/record-hours` }),
+  get_price_anomaly_metrics: () => ({ method: 'GET', path: '/providers/anomalies' }),
+  get_provider_latency: () => ({ method: 'GET', path: '/providers/latency' }),
+  get_provider_debug: (a) => ({
+    method: 'GET',
+    path: `/providers/${encodeURIComponent(String(a.providerId))}/debug`,
+  }),
+  set_provider_debug: (a) => ({
+    method: 'PUT',
+    path: `/providers/${encodeURIComponent(String(a.providerId))}/debug`,
+    body: JSON.stringify({ enabled: a.enabled }),
+  }),
 };
@@ -45,6 +45,26 @@ export const subscriptionRoutes: Record<string, RouteMapper> = {
       }))
     ),
   }),
+  import_subscriptions_csv: (a) => ({
+    method: 'POST',
+    path: '/subscriptions/import-csv',
+    body: JSON.stringify({ content: a.content, validate: a.validate ?? true }),
+  }),
+  create_basket: (a) => ({
+    method: 'POST',
+    path: '/subscriptions/basket',
+    body: JSON.stringify({
+      symbol: a.symbol,
+      display_name: a.displayName,
+      components: a.components,
+    }),
+  }),
+  find_duplicate_subscriptions: () => ({ method: 'GET', path: '/subscriptions/duplicates' }),
+  merge_subscriptions: (a) => ({
+    method: 'POST',
+    path: '/subscriptions/merge',
+    body: JSON.stringify({ keep_id: a.keepId, merge_ids: a.mergeIds }),
+  }),
   update_subscription: (a) => ({
     method: 'PUT',
     path: `/subscriptions/${encodeURIComponent(String(a.id))}`,
@@ -55,6 +75,11 @@ export const subscriptionRoutes: Record<string, RouteMapper> = {
       asset_type: a.assetType,
     }),
   }),
+  set_provider_params: (a) => ({
+    method: 'PUT',
+    path: `/subscriptions/${encodeURIComponent(String(a.id))}/provider-params`,
+    body: JSON.stringify({ params: a.params ?? null }),
+  }),
   remove_subscription: (a) => ({
     method: 'DELETE',
     path: `/subscriptions/${encodeURIComponent(String(a.id))}`,
@@ -77,4 +102,9 @@ export const subscriptionRoutes: Record<string, RouteMapper> = {
     path: `/subscriptions/${encodeURIComponent(String(a.subscriptionId))}/record-hours`,
     body: JSON.stringify({ from_hour: a.fromHour, to_hour: a.toHour }),
   }),
+  set_record_dedup: (a) => ({
+    method: 'PUT',
+    path: `/subscriptions/${encodeURIComponent(String(a.subscriptionId))}/record-dedup`,
+    body: JSON.stringify({ dedup_secs: a.dedupSecs ?? null }),
+  }),
 };
@@ -12,6 +12,15 @@ export const systemRoutes: Record<string, RouteMapper> = {
     path: '/data/import',
     body: JSON.stringify(a.data ?? a),
   }),
+  export_app_config: (a) => ({
+    method: 'GET',
+    path: `/data/config${a.includeSecrets ? '?include_secrets=true' : ''}`,
+  }),
+  import_app_config: (a) => ({
+    method: 'POST',
+    path: `/data/config?mode=${encodeURIComponent(String(a.mode ?? 'merge'))}`,
+    body: JSON.stringify(a.config),
+  }),
 
   // --- System ---
   get_api_port: () => ({ method: 'GET', path: '/system/config', extractField: 'api_port' }),
@@ -26,6 +35,166 @@ export const systemRoutes: Record<string, RouteMapper> = {
     path: '/system/config',
     body: JSON.stringify({ unattended_polling: a.enabled }),
   }),
+  get_recording_paused: () => ({ method: 'GET', path: '/system/config', extractField: 'recording_paused' }),
+  set_recording_paused: (a) => ({
+    method: 'PUT',
+    path: '/system/config',
+    body: JSON.stringify({ recording_paused: a.paused }),
+  }),
+  get_recording_schedule: () => ({ method: 'GET', path: '/system/recording-schedule' }),
+  set_recording_schedule: (a) => ({
+    method: 'PUT',
+    path: '/system/recording-schedule',
+    body: JSON.stringify(a.schedule),
+  }),
+  get_power_mode: () => ({ method: 'GET', path: '/system/power-mode' }),
+  set_power_mode: (a) => ({
+    method: 'PUT',
+    path: '/system/power-mode',
+    body: JSON.stringify(a.config),
+  }),
+  get_gas_config: () => ({ method: 'GET', path: '/system/gas-tracker' }),
+  set_gas_config: (a) => ({
+    method: 'PUT',
+    path: '/system/gas-tracker',
+    body: JSON.stringify(a.config),
+  }),
+  get_calendar_config: () => ({ method: 'GET', path: '/system/economic-calendar' }),
+  set_calendar_config: (a) => ({
+    method: 'PUT',
+    path: '/system/economic-calendar',
+    body: JSON.stringify(a.config),
+  }),
+  get_price_sanity_config: () => ({ method: 'GET', path: '/system/price-sanity' }),
+  set_price_sanity_config: (a) => ({
+    method: 'PUT',
+    path: '/system/price-sanity',
+    body: JSON.stringify(a.config),
+  }),
+  get_cache_limit: () => ({ method: 'GET', path: '/system/cache-limit' }),
+  set_cache_limit: (a) => ({
+    method: 'PUT',
+    path: '/system/cache-limit',
+    body: JSON.stringify(a.config),
+  }),
+  get_emit_config: () => ({ method: 'GET', path: '/system/emit-throttle' }),
+  set_emit_config: (a) => ({
+    method: 'PUT',
+    path: '/system/emit-throttle',
+    body: JSON.stringify(a.config),
+  }),
+  get_record_stall_config: () => ({ method: 'GET', path: '/system/record-stall' }),
+  set_record_stall_config: (a) => ({
+    method: 'PUT',
+    path: '/system/record-stall',
+    body: JSON.stringify(a.config),
+  }),
+  get_latency_tune_config: () => ({ method: 'GET', path: '/system/latency-tune' }),
+  set_latency_tune_config: (a) => ({
+    method: 'PUT',
+    path: '/system/latency-tune',
+    body: JSON.stringify(a.config),
+  }),
+  get_ws_first_config: () => ({ method: 'GET', path: '/system/ws-first' }),
+  set_ws_first_config: (a) => ({
+    method: 'PUT',
+    path: '/system/ws-first',
+    body: JSON.stringify(a.config),
+  }),
+  get_candle_config: () => ({ method: 'GET', path: '/system/candles' }),
+  set_candle_config: (a) => ({
+    method: 'PUT',
+    path: '/system/candles',
+    body: JSON.stringify(a.config),
+  }),
+  get_app_status: () => ({ method: 'GET', path: '/status' }),
+  get_otlp_config: () => ({ method: 'GET', path: '/system/telemetry' }),
+  set_otlp_config: (a) => ({
+    method: 'PUT',
+    path: '/system/telemetry',
+    body: JSON.stringify(a.config),
+  }),
+  get_cloud_backup_config: () => ({ method: 'GET', path: '/system/cloud-backup' }),
+  set_cloud_backup_config: (a) => ({
+    method: 'PUT',
+    path: '/system/cloud-backup',
+    body: JSON.stringify(a.config),
+  }),
+  run_cloud_backup: () => ({ method: 'POST', path: '/system/cloud-backup/run' }),
+  list_cloud_backups: () => ({ method: 'GET', path: '/system/cloud-backups' }),
+  restore_from_cloud: (a) => ({
+    method: 'POST',
+    path: '/system/cloud-backups/restore',
+    body: JSON.stringify({ key: a.key }),
+  }),
+  get_sync_config: () => ({ method: 'GET', path: '/system/settings-sync' }),
+  set_sync_config: (a) => ({
+    method: 'PUT',
+    path: '/system/settings-sync',
+    body: JSON.stringify(a.config),
+  }),
+  sync_settings_now: () => ({ method: 'POST', path: '/system/settings-sync/run' }),
+  get_kiosk_config: () => ({ method: 'GET', path: '/system/kiosk' }),
+  set_kiosk_config: (a) => ({
+    method: 'PUT',
+    path: '/system/kiosk',
+    body: JSON.stringify({ ...(a.config as object), current_pin: a.currentPin ?? '' }),
+  }),
+  get_lan_listener_config: () => ({ method: 'GET', path: '/system/lan-listener' }),
+  set_lan_listener_config: (a) => ({
+    method: 'PUT',
+    path: '/system/lan-listener',
+    body: JSON.stringify(a.config),
+  }),
+  get_depeg_config: () => ({ method: 'GET', path: '/system/depeg-monitor' }),
+  set_depeg_config: (a) => ({
+    method: 'PUT',
+    path: '/system/depeg-monitor',
+    body: JSON.stringify(a.config),
+  }),
+  open_deep_link: (a) => ({
+    method: 'POST',
+    path: '/system/deep-link',
+    body: JSON.stringify({ url: a.url }),
+  }),
+  get_http_proxy: () => ({ method: 'GET', path: '/system/config', extractField: 'http_proxy' }),
+  set_http_proxy: (a) => ({
+    method: 'PUT',
+    path: '/system/config',
+    body: JSON.stringify({ http_proxy: a.proxy ?? '' }),
+  }),
+  get_yahoo_quote_summary: () => ({ method: 'GET', path: '/system/config', extractField: 'yahoo_quote_summary' }),
+  set_yahoo_quote_summary: (a) => ({
+    method: 'PUT',
+    path: '/system/config',
+    body: JSON.stringify({ yahoo_quote_summary: a.enabled }),
+  }),
+  get_jupiter_token_list: () => ({ method: 'GET', path: '/system/config', extractField: 'jupiter_token_list' }),
+  set_jupiter_token_list: (a) => ({
+    method: 'PUT',
+    path: '/system/config',
+    body: JSON.stringify({ jupiter_token_list: a.enabled }),
+  }),
+  get_alphavantage_refresh_hours: () => ({ method: 'GET', path: '/system/config', extractField: 'alphavantage_refresh_hours' }),
+  set_alphavantage_refresh_hours: (a) => ({
+    method: 'PUT',
+    path: '/system/config',
+    body: JSON.stringify({ alphavantage_refresh_hours: a.hours }),
+  }),
+  get_alphavantage_quota: () => ({ method: 'GET', path: '/system/config', extractField: 'alphavantage_quota_remaining' }),
+  get_eodhd_default_exchange: () => ({ method: 'GET', path: '/system/config', extractField: 'eodhd_default_exchange' }),
+  set_eodhd_default_exchange: (a) => ({
+    method: 'PUT',
+    path: '/system/config',
+    body: JSON.stringify({ eodhd_default_exchange: a.exchange }),
+  }),
+  get_provider_metadata_source: () => ({ method: 'GET', path: '/system/config', extractField: 'provider_metadata' }),
+  get_provider_metadata_status: () => ({ method: 'GET', path: '/system/config', extractField: 'provider_metadata_status' }),
+  set_provider_metadata_source: (a) => ({
+    method: 'PUT',
+    path: '/system/config',
+    body: JSON.stringify({ provider_metadata: a.source }),
+  }),
   reload_polling: () => ({
     method: 'POST',
     path: '/system/reload-polling',
@@ -36,17 +205,117 @@ export const systemRoutes: Record<string, RouteMapper> = {
   }),
   get_data_dir: () => ({ method: 'GET', path: '/system/data-dir' }),
 
+  // --- Demo Mode ---
+  get_demo_mode: () => ({ method: 'GET', path: '/system/demo-mode' }),
+  set_demo_mode: (a) => ({
+    method: 'PUT',
+    path: '/system/demo-mode',
+    body: JSON.stringify(a.config),
+  }),
+
+  // --- History Replay ---
+  get_replay_config: () => ({ method: 'GET', path: '/system/replay' }),
+  set_replay_config: (a) => ({
+    method: 'PUT',
+    path: '/system/replay',
+    body: JSON.stringify(a.config),
+  }),
+
+  // --- Subgraph Protocols ---
+  get_subgraph_protocols: () => ({ method: 'GET', path: '/system/subgraph-protocols' }),
+  set_subgraph_protocols: (a) => ({
+    method: 'PUT',
+    path: '/system/subgraph-protocols',
+    body: JSON.stringify(a.protocols),
+  }),
+
+  // --- System Tray / Autostart (Desktop Only) ---
+  get_tray_config: () => ({
+    method: 'POST',
+    path: '/system/desktop-only',
+    body: JSON.stringify({ command: 'get_tray_config', error: 'System tray is not available in web mode' }),
+  }),
+  set_tray_config: () => ({
+    method: 'POST',
+    path: '/system/desktop-only',
+    body: JSON.stringify({ command: 'set_tray_config', error: 'System tray is not available in web mode' }),
+  }),
+  get_close_to_tray: () => ({
+    method: 'POST',
+    path: '/system/desktop-only',
+    body: JSON.stringify({ command: 'get_close_to_tray', error: 'System tray is not available in web mode' }),
+  }),
+  set_close_to_tray: () => ({
+    method: 'POST',
+    path: '/system/desktop-only',
+    body: JSON.stringify({ command: 'set_close_to_tray', error: 'System tray is not available in web mode' }),
+  }),
+  get_autostart: () => ({
+    method: 'POST',
+    path: '/system/desktop-only',
+    body: JSON.stringify({ command: 'get_autostart', error: 'Autostart is not available in web mode' }),
+  }),
+  set_autostart: () => ({
+    method: 'POST',
+    path: '/system/desktop-only',
+    body: JSON.stringify({ command: 'set_autostart', error: 'Autostart is not available in web mode' }),
+  }),
+
+  // --- Clipboard (Desktop Only) ---
+  copy_price_to_clipboard: () => ({
+    method: 'POST',
+    path: '/system/desktop-only',
+    body: JSON.stringify({ command: 'copy_price_to_clipboard', error: 'Clipboard commands are not available in web mode' }),
+  }),
+  copy_history_csv_to_clipboard: () => ({
+    method: 'POST',
+    path: '/system/desktop-only',
+    body: JSON.stringify({ command: 'copy_history_csv_to_clipboard', error: 'Clipboard commands are not available in web mode' }),
+  }),
+
+  // --- Logging ---
+  get_recent_logs: (a) => {
+    const params = new URLSearchParams();
+    if (a.level) params.set('level', String(a.level));
+    if (a.limit != null) params.set('limit', String(a.limit));
+    const qs = params.toString();
+    return { method: 'GET', path: `/system/logs${qs ? `?${qs}` : ''}` };
+  },
+  get_log_level: () => ({ method: 'GET', path: '/system/config', extractField: 'log_level' }),
+  set_log_level: (a) => ({
+    method: 'PUT',
+    path: '/system/config',
+    body: JSON.stringify({ log_level: a.level }),
+  }),
+  get_language: () => ({ method: 'GET', path: '/system/config', extractField: 'language' }),
+  set_language: (a) => ({
+    method: 'PUT',
+    path: '/system/config',
+    body: JSON.stringify({ language: a.language }),
+  }),
+  open_log_dir: () => ({
+    method: 'POST',
+    path: '/system/desktop-only',
+    body: JSON.stringify({ command: 'open_log_dir', error: 'Opening folders is not available in web mode' }),
+  }),
+
   // --- Icons ---
   set_icon: (a) => ({
     method: 'POST',
     path: `/icons/${encodeURIComponent(String(a.symbol))}`,
     body: JSON.stringify(a),
   }),
+  set_icon_from_path: (a) => ({
+    method: 'POST',
+    path: `/icons/${encodeURIComponent(String(a.symbol))}/from-path`,
+    body: JSON.stringify({ path: a.path, data: a.data }),
+  }),
   remove_icon: (a) => ({
     method: 'DELETE',
     path: `/icons/${encodeURIComponent(String(a.symbol))}`,
   }),
   get_icons_dir: () => ({ method: 'GET', path: '/icons/dir' }),
+  list_icon_mappings: () => ({ method: 'GET', path: '/icons/mappings' }),
   download_logos: (a) => ({
     method: 'POST',
     path: '/icons/download-logos',
@@ -58,6 +327,11 @@ export const systemRoutes: Record<string, RouteMapper> = {
     path: `/icons/${encodeURIComponent(String(a.saveAs ?? a.save_as))}/download`,
     body: JSON.stringify({ symbol: a.symbol }),
   }),
+  fetch_icon: (a) => ({
+    method: 'POST',
+    path: `/icons/${encodeURIComponent(String(a.symbol))}/fetch`,
+    body: JSON.stringify({ provider_id: a.providerId ?? a.provider_id }),
+  }),
   search_icons: (a) => ({
     method: 'GET',
     path: `/icons/search?symbol=${encodeURIComponent(String(a.symbol))}`,
@@ -72,8 +346,9 @@ export const systemRoutes: Record<string, RouteMapper> = {
   set_visible_subscriptions: (a) => ({
     method: 'PUT',
     path: '/system/visible-subscriptions',
-    body: JSON.stringify(a),
+    body: JSON.stringify({ ids: a.ids, scope: a.scope, interval_ms: a.intervalMs ?? a.interval_ms }),
   }),
+  get_polling_rates: () => ({ method: 'GET', path: '/system/polling-rates' }),
 
   // --- Theme Background ---
   save_theme_bg: () => ({
@@ -81,6 +356,11 @@ export const systemRoutes: Record<string, RouteMapper> = {
     path: '/system/desktop-only',
     body: JSON.stringify({ command: 'save_theme_bg', error: 'File dialog not available in web mode' }),
   }),
+  save_theme_bg_from_path: (a) => ({
+    method: 'PUT',
+    path: `/system/theme-bg/${encodeURIComponent(String(a.themeId ?? a.theme_id))}`,
+    body: JSON.stringify({ path: a.path, data: a.data }),
+  }),
   remove_theme_bg: (a) => ({
     method: 'DELETE',
     path: `/system/theme-bg/${encodeURIComponent(String(a.theme_id))}`,
@@ -125,4 +405,5 @@ export const systemRoutes: Record<string, RouteMapper> = {
     path: '/ws/stream/stop',
     body: JSON.stringify({ provider_id: a.providerId }),
   }),
+  get_ws_streams: () => ({ method: 'GET', path: '/ws/streams' }),
 };
@@ -6,6 +6,16 @@
 import { mapCommandToHttp } from './transportRoutes';
 import { webModeHandlers } from './webFileOps';
 import type { Transport } from './transport';
+import { STORAGE_KEYS } from './storageKeys';
+import { CommandError } from './commandError';
+
+/** 看板網址帶 `?kiosk=<token>` 時記住受限 token，之後的 REST 請求都以唯讀身分送出 */
+function kioskToken(): string | null {
+  if (typeof window === 'undefined' || typeof localStorage === 'undefined') return null;
+  const fromUrl = new URLSearchParams(window.location.search).get('kiosk');
+  if (fromUrl) localStorage.setItem(STORAGE_KEYS.KIOSK_TOKEN, fromUrl);
+  return localStorage.getItem(STORAGE_KEYS.KIOSK_TOKEN);
+}
 
 /**
  * HttpTransport — routes calls through HTTP fetch to the server REST API
@@ -31,6 +41,10 @@ export class HttpTransport implements Transport {
     if (body) {
       headers['Content-Type'] = 'application/json';
     }
+    const token = kioskToken();
+    if (token) {
+      headers['Authorization'] = `Bearer ${token}`;
+    }
 
     const response = await fetch(`/api${path}`, {
       method,
@@ -43,26 +57,31 @@ export class HttpTransport implements Transport {
       return undefined as unknown as T;
     }
 
-    // Non-OK responses that aren't 204 — throw with the response text
+    // Non-OK responses that aren't 204 — throw with the error envelope, or the raw response text
     if (!response.ok) {
       const text = await response.text();
+      let code = 'internal_error';
       let message = text;
+      let key: string | undefined;
       try {
         const parsed = JSON.parse(text);
         if (parsed?.error?.message) {
+          code = parsed.error.code || code;
           message = parsed.error.message;
+          key = parsed.error.key;
         }
       } catch {
         // Use raw text as-is
       }
-      throw new Error(message);
+      throw new CommandError(code, message, key);
     }
 
     // Unwrap the response envelope: { data: T } or { error: { code, message } }
     const envelope = await response.json();
 
     if (envelope.error) {
-      throw new Error(envelope.error.message || envelope.error.code || 'Unknown error');
+      const { code, message, key } = envelope.error;
+      throw new CommandError(code || 'internal_error', message || code || 'Unknown error', key);
     }
 
     const data = envelope.data as T;
@@ -86,5 +86,22 @@ describe('webFileOps', () => {
         { numRuns: 100 }
       );
     });
+
+    it('webExportFile renders datasets through POST /api/data/export-file', async () => {
+      const { webExportFile } = await import('./webFileOps');
+      fetchSpy.mockResolvedValue(new Response('symbol,price\nBTC,1\n', {
+        status: 200,
+        headers: { 'Content-Type': 'text/csv; charset=utf-8' },
+      }));
+
+      const dataset = { kind: 'history', subscription_ids: [1] };
+      await webExportFile({ filename: 'btc.csv', format: 'csv', dataset });
+
+      expect(fetchSpy).toHaveBeenCalledTimes(1);
+      const [url, init] = fetchSpy.mock.calls[0];
+      expect(url).toBe('/api/data/export-file');
+      expect(JSON.parse(init.body)).toEqual({ dataset, format: 'csv', filename: 'btc.csv' });
+      expect(URL.createObjectURL).toHaveBeenCalled();
+    });
   });
 });
@@ -39,11 +39,31 @@ async function webSetIcon(args?: Record<string, unknown>): Promise<unknown> {
 }
 
 /**
- * Creates a Blob from content and triggers a browser download with the
- * specified filename. No server round-trip needed.
+ * Triggers a browser download with the specified filename.
+ * Plain `content` (JSON) is saved directly with no server round-trip; a
+ * `dataset` is rendered by POST /api/data/export-file in the requested
+ * `format` (json / csv / xlsx) and the returned bytes are downloaded.
  */
 export async function webExportFile(args?: Record<string, unknown>): Promise<void> {
   const filename = String(args?.filename ?? 'export.json');
+
+  if (args?.dataset) {
+    const response = await fetch('/api/data/export-file', {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ dataset: args.dataset, format: args.format ?? 'json', filename }),
+    });
+
+    if (!response.ok) {
+      const text = await response.text();
+      throw new Error(text);
+    }
+
+    const blob = await response.blob();
+    downloadBlob(filename, blob, response.headers.get('Content-Type') ?? 'application/octet-stream');
+    return;
+  }
+
   const content = String(args?.content ?? '');
   downloadBlob(filename, content, 'application/json');
 }
@@ -51,7 +71,7 @@ export async function webExportFile(args?: Record<string, unknown>): Promise<voi
 /**
  * Opens a browser file input for .json files, reads the selected file as
  * UTF-8 text, and resolves with the content string.
- * Rejects with Error('Cancelled') if the user cancels.
+ * Rejects with a `cancelled` CommandError if the user cancels.
  */
 export async function webImportFile(): Promise<string> {
   const file = await pickFileOrThrow({ accept: '.json' });
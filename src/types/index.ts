@@ -11,6 +11,270 @@ export interface AssetData {
   last_updated: number;
   provider_id: string;
   extra?: Record<string, unknown>;
+  pre_market?: SessionQuote;
+  post_market?: SessionQuote;
+  /** 價格顯示小數位數（provider 已知 tick size 時才有） */
+  price_scale?: number;
+  /** DEX pool 流動性（TVL，USD） */
+  liquidity_usd?: number;
+  /** 完全稀釋市值（USD） */
+  fdv?: number;
+}
+
+/** 盤前 / 盤後時段報價 */
+export interface SessionQuote {
+  price: number;
+  change: number | null;
+  change_percent: number | null;
+}
+
+/** `get_cached_prices` 的回傳：AssetData 加上由 poll tick 推算的新鮮度 */
+export interface CachedPrice extends AssetData {
+  /** 產生此價格的 poll tick 時間（ms） */
+  fetched_at: number | null;
+  interval_ms: number | null;
+  stale: boolean;
+  /** 由價格紀錄推算的多時間框漲跌幅（%）；沒有紀錄時省略 */
+  changes?: {
+    change_1h: number | null;
+    change_7d: number | null;
+    change_30d: number | null;
+    change_ytd: number | null;
+  };
+}
+
+/** gas tracker 的單鏈報價（ethereum: gwei，solana: micro-lamports/CU） */
+export interface GasPrice {
+  chain: 'ethereum' | 'solana';
+  unit: string;
+  safe?: number;
+  /** 通知規則比較的值 */
+  standard: number;
+  fast?: number;
+  base_fee?: number;
+  source: 'etherscan' | 'rpc';
+  /** Unix ms */
+  updated_at: number;
+}
+
+/** 穩定幣脫鉤監控的單一來源報價 */
+export interface DepegQuote {
+  provider_id: string;
+  symbol: string;
+  price: number;
+  /** 與 $1.00 的偏離（%） */
+  deviation_pct: number;
+}
+
+/** 穩定幣脫鉤狀態；price 為各來源中位數 */
+export interface DepegStatus {
+  coin: 'USDT' | 'USDC' | 'DAI' | 'FDUSD';
+  price: number;
+  deviation_pct: number;
+  depegged: boolean;
+  quotes: DepegQuote[];
+  /** Unix ms */
+  updated_at: number;
+}
+
+/** 被價格合理性檢查拒絕的報價（`price-anomaly` 事件） */
+export interface PriceAnomaly {
+  provider_id: string;
+  symbol: string;
+  price: number;
+  /** 比較用的近期中位數；樣本不足時為 null */
+  median: number | null;
+  /** Unix ms */
+  detected_at: number;
+}
+
+/** provider 健康監控探測成功、恢復輪詢（`provider-recovered` 事件） */
+export interface ProviderRecovered {
+  provider_id: string;
+  /** 恢復前的連續失敗次數 */
+  consecutive_failures: number;
+  /** Unix ms */
+  recovered_at: number;
+}
+
+/** 歷史紀錄寫入失敗或停滯（`record-error` 事件） */
+export interface RecordError {
+  subscription_id: number;
+  provider_id: string;
+  symbol: string;
+  kind: 'write_failed' | 'stalled';
+  error: string;
+  /** Unix 秒 */
+  occurred_at: number;
+}
+
+/** WS 串流彙整的 1 分鐘 K 線（`candle-close` 事件、get_candles） */
+export interface Candle {
+  provider_id: string;
+  symbol: string;
+  /** 分鐘起點（Unix 秒） */
+  start: number;
+  open: number;
+  high: number;
+  low: number;
+  close: number;
+  /** 由 24h 滾動成交量推算；無法推算時為 null */
+  volume: number | null;
+  ticks: number;
+}
+
+/** get_price_anomaly_metrics：單一 provider 的拒絕統計 */
+export interface AnomalyMetrics {
+  provider_id: string;
+  rejected: number;
+  last: PriceAnomaly | null;
+}
+
+/** get_provider_latency：單一 provider 最近的取價延遲與實際輪詢間隔 */
+export interface ProviderLatency {
+  provider_id: string;
+  samples: number;
+  last_ms: number;
+  p50_ms: number;
+  p95_ms: number;
+  configured_interval_ms: number;
+  effective_interval_ms: number;
+  adjustments: number;
+}
+
+/** get_app_status（`/api/status`）：執行狀態與價格快取用量 */
+export interface AppStatus {
+  version: string;
+  unattended: boolean;
+  recording_paused: boolean;
+  cache: {
+    entries: number;
+    max_entries: number;
+    restored: number;
+    ticks: number;
+    /** 啟動以來因超過上限被淘汰的項目數 */
+    evicted: number;
+    /** 估算的記憶體用量（bytes） */
+    approx_bytes: number;
+  };
+}
+
+/** 雲端備份設定（get_cloud_backup_config）；secret_access_key / passphrase 讀取時為空字串，更新時留空沿用原值 */
+export interface CloudBackupConfig {
+  enabled: boolean;
+  endpoint: string;
+  region: string;
+  bucket: string;
+  prefix: string;
+  access_key_id: string;
+  secret_access_key: string;
+  passphrase: string;
+  interval_hours: number;
+  retain: number;
+}
+
+/** list_cloud_backups 的一筆（新到舊） */
+export interface CloudBackup {
+  key: string;
+  size: number;
+  /** ISO 8601 */
+  last_modified: string;
+}
+
+/** 設定同步（get_sync_config）；location 為 WebDAV URL 或本機資料夾，password 讀取時為空字串 */
+export interface SyncConfig {
+  enabled: boolean;
+  location: string;
+  username: string;
+  password: string;
+  interval_minutes: number;
+}
+
+/** 最近一次設定同步的結果 */
+export interface SyncStatus {
+  /** Unix ms */
+  last_synced_at: number | null;
+  last_action: 'pushed' | 'pulled' | 'up_to_date' | null;
+  last_error: string | null;
+  remote_device_id: string | null;
+}
+
+/** 各視窗的輪詢間隔（get_polling_rates） */
+export interface WindowRates {
+  window_id: string;
+  /** 視窗要求的間隔（ms）；null 表示沿用 provider 間隔 */
+  requested_interval_ms: number | null;
+  /** subscription id → 實際輪詢間隔（ms）；同一 symbol 以最快的視窗為準 */
+  intervals: Record<string, number>;
+}
+
+/** Kiosk（唯讀）模式（get_kiosk_config）；PIN 不回傳，只回報是否已設定 */
+export interface KioskInfo {
+  enabled: boolean;
+  has_pin: boolean;
+  /** 受限 token；看板網址帶 `?kiosk=<token>` 時該瀏覽器一律唯讀 */
+  token: string;
+}
+
+/** 區網 API listener（get_lan_listener_config）；每個請求都需帶 token，read_only 只允許讀取與即時資料 */
+export interface LanListenerConfig {
+  enabled: boolean;
+  bind: string;
+  port: number;
+  token: string;
+  scope: 'read_only' | 'full';
+}
+
+export interface LanListenerInfo extends LanListenerConfig {
+  status: {
+    /** 正在監聽的位址 */
+    listening: string | null;
+    /** 最近一次啟動失敗的原因 */
+    error: string | null;
+  };
+}
+
+/** set_kiosk_config 的設定；pin 留空表示沿用已設定的 PIN */
+export interface KioskConfig {
+  enabled: boolean;
+  pin: string;
+  token: string;
+}
+
+/** 經濟日曆事件（get_economic_calendar） */
+export interface EconomicEvent {
+  id: number;
+  source: 'fmp' | 'finnhub';
+  /** Unix 秒（UTC） */
+  event_time: number;
+  country: string;
+  event: string;
+  impact: 'low' | 'medium' | 'high';
+  unit?: string;
+  previous: number | null;
+  forecast: number | null;
+  actual: number | null;
+}
+
+/** 單一 DEX 聚合器的詢價（compare_dex_quotes） */
+export interface DexQuote {
+  provider_id: string;
+  amount_out: number;
+  price: number;
+  /** 價格影響（%） */
+  price_impact: number | null;
+  gas_estimate: string | null;
+  route: string | null;
+}
+
+/** compare_dex_quotes 結果；quotes 依 amount_out 由多到少排序 */
+export interface DexQuoteComparison {
+  chain: string;
+  token_in: string;
+  token_out: string;
+  amount: number;
+  quotes: DexQuote[];
+  errors: { provider_id: string; error: string }[];
 }
 
 export interface ProviderInfo {
@@ -26,6 +290,14 @@ export interface ProviderInfo {
   supported_fields: string[];
   free_interval: number;
   key_interval: number;
+  capabilities: ProviderCapabilities;
+}
+
+export interface ProviderCapabilities {
+  supports_batch: boolean;
+  max_batch_size: number | null;
+  websocket_symbol_limit: number | null;
+  asset_classes: string[];
 }
 
 export interface ProviderSettings {
@@ -53,6 +325,25 @@ export interface Subscription {
   record_enabled: number;
   record_from_hour?: number | null;
   record_to_hour?: number | null;
+  /** provider 專屬參數（JSON 物件字串），例如 Polygon `{"adjusted": false}` */
+  provider_params?: string | null;
+  /** 兩筆價格紀錄的最短間隔（秒）；null 為預設 5 秒，0 為每次輪詢都紀錄 */
+  record_dedup_secs?: number | null;
+}
+
+/** 籃子訂閱的成分（`create_basket`）；weight 為相對權重 */
+export interface BasketWeight {
+  subscription_id: number;
+  weight: number;
+}
+
+/** Subgraph protocol registry 的一筆（內建項目 builtin = true） */
+export interface SubgraphProtocol {
+  protocol: string;
+  chain: string;
+  name: string;
+  subgraph_id: string;
+  builtin: boolean;
 }
 
 export interface View {
@@ -68,6 +359,25 @@ export interface WsTickerUpdate {
   data: AssetData;
 }
 
+export type WsConnectionState = 'connecting' | 'connected' | 'reconnecting' | 'failed' | 'suspended' | 'stopped';
+
+/** 單一 provider 的 WS 連線狀態（`ws-status` 事件、get_ws_streams） */
+export interface WsStatus {
+  provider_id: string;
+  state: WsConnectionState;
+  /** 所有 owner 要求的 symbol 聯集 */
+  symbols: string[];
+  /** 連續重連失敗次數 */
+  attempt: number;
+  error: string | null;
+  /** 進入目前狀態的時間（ms） */
+  since: number;
+  /** 目前連線建立的時間（ms） */
+  connected_at: number | null;
+  /** 目前這段未連線期間的開始時間（ms） */
+  disconnected_at: number | null;
+}
+
 export type ViewMode = 'grid' | 'list' | 'compact';
 
 export interface PriceHistoryRecord {
@@ -80,6 +390,215 @@ export interface PriceHistoryRecord {
   pre_price: number | null;
   post_price: number | null;
   recorded_at: number;
+  /** 僅 DEX 訂閱且以 `fields=liquidity` 查詢時提供 */
+  liquidity_usd?: number;
+  fdv?: number;
+  /** 僅帶 derivatives 參數的訂閱且以 `fields=derivatives` 查詢時提供 */
+  open_interest?: number;
+  long_short_ratio?: number;
+  /** 報價幣別（如 USDT、KRW）；較舊的紀錄為 null */
+  currency: string | null;
+}
+
+/** 連續漲跌區段（Unix 秒） */
+export interface Streak {
+  days: number;
+  start: number;
+  end: number;
+}
+
+/** 以日收盤價計算的連漲 / 連跌與最大回撤（get_streaks） */
+export interface StreakStats {
+  subscription_id: number;
+  days: number;
+  /** 正值為連漲天數、負值為連跌天數 */
+  current_streak: number;
+  longest_up: Streak | null;
+  longest_down: Streak | null;
+  max_drawdown: {
+    percent: number;
+    peak_price: number;
+    peak_at: number;
+    trough_price: number;
+    trough_at: number;
+    recovered_at: number | null;
+    recovery_days: number | null;
+  } | null;
+}
+
+/** 以紀錄計算的 VWAP；volume_weighted 為 false 時為等權平均 */
+export interface Vwap {
+  from: number;
+  to: number;
+  vwap: number;
+  last_price: number;
+  deviation_pct: number;
+  samples: number;
+  volume_weighted: boolean;
+}
+
+/** get_vwap：當日 Session VWAP 與（指定 anchor 時）Anchored VWAP */
+export interface VwapReport {
+  subscription_id: number;
+  session: Vwap | null;
+  anchored: Vwap | null;
+}
+
+/** get_sparkline：時間窗內的紀錄縮成固定點數（舊到新），供卡片走勢線使用 */
+export interface Sparkline {
+  subscription_id: number;
+  window_secs: number;
+  from: number | null;
+  to: number | null;
+  points: number[];
+  min: number | null;
+  max: number | null;
+  change_pct: number | null;
+}
+
+/** 以紀錄計算的 TWAP；每筆價格持續到下一筆紀錄（最後一筆到現在） */
+export interface Twap {
+  from: number;
+  to: number;
+  twap: number;
+  simple_average: number;
+  last_price: number;
+  samples: number;
+  max_gap_secs: number;
+}
+
+/** get_twap：最近時間窗的時間加權平均價 */
+export interface TwapReport {
+  subscription_id: number;
+  window_secs: number;
+  twap: Twap | null;
+}
+
+/** import_history_csv：通過驗證的一筆紀錄 */
+export interface HistoryCsvRow {
+  line: number;
+  recorded_at: number;
+  price: number;
+  volume: number | null;
+}
+
+/** import_history_csv 結果；dry_run 時 inserted 為 0，preview 為依時間排序的前 20 筆新紀錄 */
+export interface HistoryImportReport {
+  subscription_id: number;
+  dry_run: boolean;
+  total_rows: number;
+  new_rows: number;
+  duplicates: number;
+  inserted: number;
+  from: number | null;
+  to: number | null;
+  preview: HistoryCsvRow[];
+  errors: { line: number; error: string }[];
+}
+
+/** resolve_best_provider 的 symbol 分類 */
+export type SymbolKind = 'crypto_pair' | 'us_equity' | 'ticker' | 'mint_address' | 'pool_address' | 'condition_id' | 'unknown';
+
+/** 建議的 provider；available 為 false 時 reason 說明原因（缺 API key、退避中、額度用盡） */
+export interface ProviderSuggestion {
+  provider_id: string;
+  provider_name: string;
+  asset_type: string;
+  symbol: string;
+  available: boolean;
+  reason: string | null;
+  has_api_key: boolean;
+  interval_ms: number;
+  score: number;
+}
+
+/** resolve_best_provider：依排序由佳到差的建議清單 */
+export interface ProviderResolution {
+  symbol: string;
+  kind: SymbolKind;
+  suggestions: ProviderSuggestion[];
+}
+
+export interface DuplicateMember {
+  id: number;
+  symbol: string;
+  provider_id: string;
+  display_name?: string | null;
+  history_rows: number;
+}
+
+/** find_duplicate_subscriptions：追蹤同一資產的一組訂閱 */
+export interface DuplicateGroup {
+  /** 統一後的識別，例如 `crypto:BTC`、`stock:BRK.B` */
+  key: string;
+  asset_type: string;
+  members: DuplicateMember[];
+  /** 建議保留的訂閱 ID（紀錄最多者） */
+  suggested_keep: number;
+}
+
+/** merge_subscriptions 結果 */
+export interface MergeResult {
+  kept: number;
+  removed: number[];
+  moved_history: number;
+  dropped_history: number;
+}
+
+/** get_subscription_events：訂閱的 provider 切換 / symbol 變更，供圖表標註 */
+export interface SubscriptionEvent {
+  id: number;
+  subscription_id: number;
+  event_type: 'provider_change' | 'symbol_rename';
+  old_value: string;
+  new_value: string;
+  /** Unix 秒 */
+  occurred_at: number;
+}
+
+/** get_record_hours：訂閱 / provider 的紀錄時段設定，與依「訂閱 > provider > 全天」解析後的生效時段（本地時間） */
+export interface RecordHours {
+  provider_id: string;
+  /** 查詢 provider 時為 null */
+  subscription_id: number | null;
+  record_enabled: boolean | null;
+  subscription_from_hour: number | null;
+  subscription_to_hour: number | null;
+  provider_from_hour: number | null;
+  provider_to_hour: number | null;
+  /** 0..24 為全天，起點大於終點表示跨午夜 */
+  effective_from_hour: number;
+  effective_to_hour: number;
+  source: 'subscription' | 'provider' | 'all_day';
+}
+
+/** 桌面版 price-update / poll-tick 事件節流；max_per_sec = 0 不節流 */
+export interface EmitConfig {
+  max_per_sec: number;
+}
+
+/** 開啟紀錄的訂閱超過 stall_minutes 分鐘沒有寫入時提醒；0 = 不提醒 */
+export interface RecordStallConfig {
+  stall_minutes: number;
+}
+
+/** 依 p95 延遲自動放慢／加快 provider 輪詢間隔，範圍為 min_interval_ms～max_interval_ms */
+export interface LatencyTuneConfig {
+  enabled: boolean;
+  min_interval_ms: number;
+  max_interval_ms: number;
+}
+
+/** 支援 WebSocket 的 provider 改由串流取價；斷線超過 fallback_secs 秒改回 HTTP 輪詢 */
+export interface WsFirstConfig {
+  enabled: boolean;
+  fallback_secs: number;
+}
+
+/** WS 串流 1 分鐘 K 線：persist 時收盤的 K 線寫入資料庫，保存 retention_days 天 */
+export interface CandleConfig {
+  persist: boolean;
+  retention_days: number;
 }
 
 /** 共用 Toast 操作介面 — 消除各 hook 重複定義的 ToastLike */
@@ -128,7 +647,7 @@ export interface NotificationTriggeredEvent {
   symbol: string;
   provider: string;
   price: number;
-  condition_type: string;   // 'price_above' | 'price_below' | 'change_pct_above' | 'change_pct_below' | 'ai'
+  condition_type: string;   // 'price_above' | 'price_below' | 'change_pct_above' | 'change_pct_below' | 'gas_above' | 'gas_below' | 'depeg_above' | 'event_before' | 'vwap_above' | 'vwap_below' | 'ai'
   threshold: number;
   triggered_at: number;     // Unix 秒
   is_ai: boolean;